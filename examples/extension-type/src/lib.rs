@@ -0,0 +1,149 @@
+//! Support for two types of this crate in SQLx, through its public API only.
+
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+
+use sqlx::decode::Decode;
+use sqlx::encode::{Encode, IsNull};
+use sqlx::error::BoxDynError;
+use sqlx::mysql::MySql;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use sqlx::types::Type;
+
+/// An email address, stored as text; checked when it is created and when it is read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email(String);
+
+impl Email {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<String> for Email {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for Email {
+    type Error = String;
+
+    fn try_from(address: String) -> Result<Self, String> {
+        match address.find('@') {
+            Some(at) if at > 0 && address[at + 1..].contains('.') => Ok(Email(address)),
+            _ => Err(format!("not an email address: {:?}", address)),
+        }
+    }
+}
+
+// `Type`, `Encode` and `Decode` through those of `String`
+sqlx::declare_type!(Postgres, Email as String);
+sqlx::declare_type!(MySql, Email as String);
+
+/// A point on a plane, the `point` type of PostgreSQL.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
+
+impl Type<Postgres> for Point {
+    fn type_info() -> PgTypeInfo {
+        // the OID is looked up by the connection the first time it is needed; a type of an
+        // extension, whose OID differs between databases, is declared the same way
+        PgTypeInfo::with_name("point")
+    }
+}
+
+impl Encode<'_, Postgres> for Point {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        buf.extend_from_slice(&self.x.to_be_bytes());
+        buf.extend_from_slice(&self.y.to_be_bytes());
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        16
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Point {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => decode_binary(value.as_bytes()?),
+            PgValueFormat::Text => parse(value.as_str()?),
+        }
+    }
+}
+
+// two big-endian `float8`
+fn decode_binary(bytes: &[u8]) -> Result<Point, BoxDynError> {
+    if bytes.len() != 16 {
+        return Err(format!("expected 16 bytes for a point, got {}", bytes.len()).into());
+    }
+
+    let (x, y) = bytes.split_at(8);
+
+    Ok(Point {
+        x: f64::from_be_bytes(<[u8; 8]>::try_from(x)?),
+        y: f64::from_be_bytes(<[u8; 8]>::try_from(y)?),
+    })
+}
+
+// `(x,y)`
+fn parse(s: &str) -> Result<Point, BoxDynError> {
+    let invalid = || format!("not a point: {:?}", s);
+
+    let inner = s
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or_else(invalid)?;
+
+    let comma = inner.find(',').ok_or_else(invalid)?;
+    let (x, y) = (&inner[..comma], &inner[comma + 1..]);
+
+    Ok(Point {
+        x: x.trim().parse()?,
+        y: y.trim().parse()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_checks_email_addresses() {
+        assert!(Email::try_from("jane@example.com".to_owned()).is_ok());
+        assert!(Email::try_from("jane".to_owned()).is_err());
+        assert!(Email::try_from("@example.com".to_owned()).is_err());
+    }
+
+    #[test]
+    fn it_encodes_points_as_two_floats() {
+        let mut buf = PgArgumentBuffer::default();
+        let point = Point { x: 1.5, y: -2.0 };
+
+        Encode::<Postgres>::encode_by_ref(&point, &mut buf).unwrap();
+
+        assert_eq!(decode_binary(&buf).unwrap(), point);
+    }
+
+    #[test]
+    fn it_parses_points() {
+        assert_eq!(parse("(1.5,-2)").unwrap(), Point { x: 1.5, y: -2.0 });
+        assert_eq!(
+            parse(&Point { x: 3.0, y: 0.25 }.to_string()).unwrap(),
+            Point { x: 3.0, y: 0.25 }
+        );
+        assert!(parse("1.5,-2").is_err());
+    }
+}
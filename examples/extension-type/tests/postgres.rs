@@ -0,0 +1,54 @@
+use std::convert::TryFrom;
+use std::env;
+
+use sqlx::postgres::PgPool;
+use sqlx_example_extension_type::{Email, Point};
+
+async fn pool() -> anyhow::Result<PgPool> {
+    Ok(PgPool::connect(&env::var("DATABASE_URL")?).await?)
+}
+
+#[async_std::test]
+async fn it_round_trips_an_email_address() -> anyhow::Result<()> {
+    let pool = pool().await?;
+    let email = Email::try_from("jane@example.com".to_owned()).unwrap();
+
+    let decoded: Email = sqlx::query_scalar("SELECT $1")
+        .bind(&email)
+        .fetch_one(&pool)
+        .await?;
+
+    assert_eq!(decoded, email);
+
+    // checked when read, too
+    let res = sqlx::query_scalar::<_, Email>("SELECT 'jane'::text")
+        .fetch_one(&pool)
+        .await;
+
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[async_std::test]
+async fn it_round_trips_a_point() -> anyhow::Result<()> {
+    let pool = pool().await?;
+    let point = Point { x: 1.5, y: -2.0 };
+
+    let (decoded, text): (Point, String) = sqlx::query_as("SELECT $1, $1::text")
+        .bind(point)
+        .fetch_one(&pool)
+        .await?;
+
+    assert_eq!(decoded, point);
+    assert_eq!(text, "(1.5,-2)");
+
+    // made by the server
+    let decoded: Point = sqlx::query_scalar("SELECT point(3, 0.25)")
+        .fetch_one(&pool)
+        .await?;
+
+    assert_eq!(decoded, Point { x: 3.0, y: 0.25 });
+
+    Ok(())
+}
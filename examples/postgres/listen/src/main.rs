@@ -2,7 +2,7 @@ use async_std::stream;
 use futures::StreamExt;
 use futures::TryStreamExt;
 use sqlx::postgres::PgListener;
-use sqlx::{Executor, PgPool};
+use sqlx::PgPool;
 use std::sync::atomic::{AtomicI64, Ordering};
 use std::time::Duration;
 
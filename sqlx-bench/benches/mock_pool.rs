@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, Bencher, Criterion};
+use sqlx_core::bench_support::mock::{MockConnectOptions, MockPoolOptions};
+
+fn bench_mock_pool_acquire(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bench_mock_pool_acquire");
+
+    for &concurrent in [0u32, 5, 10, 50, 100, 500].iter() {
+        for &fair in [false, true].iter() {
+            let fairness = if fair { "(fair)" } else { "(unfair)" };
+
+            group.bench_with_input(
+                format!("{} concurrent {}", concurrent, fairness),
+                &(concurrent, fair),
+                |b, &(concurrent, fair)| do_bench_acquire(b, concurrent, fair),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn do_bench_acquire(b: &mut Bencher, concurrent: u32, fair: bool) {
+    let pool = sqlx_rt::block_on(
+        MockPoolOptions::new()
+            .connect_timeout(Duration::from_secs(3600))
+            .min_connections(50)
+            .max_connections(50)
+            .__fair(fair)
+            .connect_with(MockConnectOptions),
+    )
+    .expect("failed to open MockPool");
+
+    for _ in 0..concurrent {
+        let pool = pool.clone();
+        sqlx_rt::enter_runtime(|| {
+            sqlx_rt::spawn(async move {
+                while !pool.is_closed() {
+                    let conn = match pool.acquire().await {
+                        Ok(conn) => conn,
+                        Err(sqlx::Error::PoolClosed) => break,
+                        Err(e) => panic!("failed to acquire concurrent connection: {}", e),
+                    };
+
+                    // give the benchmarked task a chance to contend for the connection
+                    sqlx_rt::yield_now().await;
+                    drop(criterion::black_box(conn));
+                }
+            })
+        });
+    }
+
+    b.iter_custom(|iters| {
+        sqlx_rt::block_on(async {
+            let start = Instant::now();
+            for _ in 0..iters {
+                criterion::black_box(
+                    pool.acquire()
+                        .await
+                        .expect("failed to acquire connection for benchmark"),
+                );
+            }
+            start.elapsed()
+        })
+    });
+
+    sqlx_rt::block_on(pool.close());
+}
+
+criterion_group!(mock_pool, bench_mock_pool_acquire);
+criterion_main!(mock_pool);
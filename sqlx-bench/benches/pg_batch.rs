@@ -0,0 +1,83 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use sqlx::postgres::{PgConnection, PgQueryResult};
+use sqlx::{Connection, Executor};
+
+// statements per transaction, of three kinds in turn, as a sync job would apply them
+const STATEMENTS: i32 = 1000;
+
+const INSERT: &str = "INSERT INTO bench_batch (id, count) VALUES ($1, 1)";
+const UPDATE: &str = "UPDATE bench_batch SET count = count + 1 WHERE id = $1";
+const DELETE: &str = "DELETE FROM bench_batch WHERE id = $1";
+
+fn bench_pg_batch(c: &mut Criterion) {
+    let mut conn = sqlx_rt::block_on(PgConnection::connect(
+        &dotenv::var("DATABASE_URL").expect("DATABASE_URL must be set to run benchmarks"),
+    ))
+    .expect("failed to connect");
+
+    sqlx_rt::block_on(
+        conn.execute(
+            "CREATE TEMPORARY TABLE bench_batch (id INT4 PRIMARY KEY, count INT4 NOT NULL)",
+        ),
+    )
+    .expect("failed to create table");
+
+    let mut group = c.benchmark_group("bench_pg_batch");
+    group.sample_size(10);
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| sqlx_rt::block_on(sequential(&mut conn)));
+    });
+
+    group.bench_function("batch", |b| {
+        b.iter(|| sqlx_rt::block_on(batch(&mut conn)));
+    });
+
+    group.finish();
+}
+
+fn statement(i: i32) -> &'static str {
+    match i % 3 {
+        0 => INSERT,
+        1 => UPDATE,
+        _ => DELETE,
+    }
+}
+
+// one round trip per statement
+async fn sequential(conn: &mut PgConnection) -> Vec<PgQueryResult> {
+    let mut tx = conn.begin().await.expect("failed to begin");
+    let mut results = Vec::new();
+
+    for i in 0..STATEMENTS {
+        let result = sqlx::query(statement(i))
+            .bind(i / 3)
+            .execute(&mut tx)
+            .await
+            .expect("failed to execute");
+
+        results.push(result);
+    }
+
+    tx.rollback().await.expect("failed to roll back");
+
+    results
+}
+
+async fn batch(conn: &mut PgConnection) -> Vec<PgQueryResult> {
+    let mut tx = conn.begin().await.expect("failed to begin");
+    let mut statements = tx.batch();
+
+    for i in 0..STATEMENTS {
+        statements = statements.add_query(sqlx::query(statement(i)).bind(i / 3));
+    }
+
+    let results = statements.execute().await.expect("failed to execute batch");
+
+    tx.rollback().await.expect("failed to roll back");
+
+    results
+}
+
+criterion_group!(pg_batch, bench_pg_batch);
+criterion_main!(pg_batch);
@@ -0,0 +1,61 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+// a million rows of numbers, as an analytical query would return them
+const QUERY: &str =
+    "SELECT n::int8, n::float8 / 7, (n % 3)::int4 FROM generate_series(1, 1000000) n";
+
+fn bench_pg_columnar(c: &mut Criterion) {
+    let pool = sqlx_rt::block_on(PgPoolOptions::new().max_connections(1).connect(
+        &dotenv::var("DATABASE_URL").expect("DATABASE_URL must be set to run benchmarks"),
+    ))
+    .expect("failed to open PgPool");
+
+    let mut group = c.benchmark_group("bench_pg_columnar");
+    group.sample_size(10);
+
+    group.bench_function("rows", |b| {
+        b.iter(|| sqlx_rt::block_on(fetch_rows(&pool)));
+    });
+
+    group.bench_function("columnar", |b| {
+        b.iter(|| sqlx_rt::block_on(fetch_columnar(&pool)));
+    });
+
+    group.finish();
+
+    sqlx_rt::block_on(pool.close());
+}
+
+// the row-based path, turned into columns afterwards
+async fn fetch_rows(pool: &PgPool) -> (Vec<i64>, Vec<f64>, Vec<i32>) {
+    let rows: Vec<(i64, f64, i32)> = sqlx::query_as(QUERY)
+        .fetch_all(pool)
+        .await
+        .expect("failed to fetch rows");
+
+    let mut columns = (
+        Vec::with_capacity(rows.len()),
+        Vec::with_capacity(rows.len()),
+        Vec::with_capacity(rows.len()),
+    );
+
+    for (a, b, c) in rows {
+        columns.0.push(a);
+        columns.1.push(b);
+        columns.2.push(c);
+    }
+
+    columns
+}
+
+async fn fetch_columnar(pool: &PgPool) -> (Vec<i64>, Vec<f64>, Vec<i32>) {
+    sqlx::query(QUERY)
+        .fetch_columnar::<(i64, f64, i32), _>(pool)
+        .await
+        .expect("failed to fetch columns")
+}
+
+criterion_group!(pg_columnar, bench_pg_columnar);
+criterion_main!(pg_columnar);
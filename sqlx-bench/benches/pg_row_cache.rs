@@ -0,0 +1,70 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use sqlx::postgres::{PgPoolOptions, PgRow};
+use sqlx::types::JsonValue;
+use sqlx::{PgPool, Row};
+
+// rows of a JSON document of a few kilobytes each, and a number
+const QUERY: &str = "SELECT n::int8, jsonb_build_object('id', n, 'tags', \
+     (SELECT jsonb_agg(md5(m::text)) FROM generate_series(1, 64) m)) \
+     FROM generate_series(1, 1000) n";
+
+fn bench_pg_row_cache(c: &mut Criterion) {
+    let pool = sqlx_rt::block_on(PgPoolOptions::new().max_connections(1).connect(
+        &dotenv::var("DATABASE_URL").expect("DATABASE_URL must be set to run benchmarks"),
+    ))
+    .expect("failed to open PgPool");
+
+    let rows = sqlx_rt::block_on(fetch(&pool));
+
+    let mut group = c.benchmark_group("bench_pg_row_cache");
+
+    // every variant clones the rows, as a cached row owns the row it wraps
+
+    // every column read once: the overhead of the cache
+    group.bench_function("single_access", |b| {
+        b.iter(|| {
+            for row in rows.iter().cloned() {
+                let _: (i64, JsonValue) = (row.get(0), row.get(1));
+            }
+        })
+    });
+
+    group.bench_function("single_access_cached", |b| {
+        b.iter(|| {
+            for row in rows.iter().cloned().map(Row::cached) {
+                let _: (i64, JsonValue) = (row.get(0), row.get(1));
+            }
+        })
+    });
+
+    // the document read twice, e.g. by a `FromRow` implementation and then by hand
+    group.bench_function("double_access", |b| {
+        b.iter(|| {
+            for row in rows.iter().cloned() {
+                let _: (JsonValue, JsonValue) = (row.get(1), row.get(1));
+            }
+        })
+    });
+
+    group.bench_function("double_access_cached", |b| {
+        b.iter(|| {
+            for row in rows.iter().cloned().map(Row::cached) {
+                let _: (JsonValue, JsonValue) = (row.get(1), row.get(1));
+            }
+        })
+    });
+
+    group.finish();
+
+    sqlx_rt::block_on(pool.close());
+}
+
+async fn fetch(pool: &PgPool) -> Vec<PgRow> {
+    sqlx::query(QUERY)
+        .fetch_all(pool)
+        .await
+        .expect("failed to fetch rows")
+}
+
+criterion_group!(pg_row_cache, bench_pg_row_cache);
+criterion_main!(pg_row_cache);
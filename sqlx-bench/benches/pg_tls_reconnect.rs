@@ -0,0 +1,70 @@
+use criterion::{criterion_group, criterion_main, Bencher, Criterion};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions, PgSslMode};
+use std::time::{Duration, Instant};
+
+const CONNECTIONS: u32 = 500;
+
+fn bench_pgpool_reconnect_tls(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bench_pgpool_reconnect_tls");
+
+    // every iteration opens hundreds of connections
+    group.sample_size(10);
+
+    for &cache in [false, true].iter() {
+        let name = if cache {
+            "session cache"
+        } else {
+            "no session cache"
+        };
+
+        group.bench_with_input(
+            format!("{} connections ({})", CONNECTIONS, name),
+            &cache,
+            |b, &cache| do_bench_reconnect(b, cache),
+        );
+    }
+
+    group.finish();
+}
+
+fn do_bench_reconnect(b: &mut Bencher, cache: bool) {
+    let options: PgConnectOptions = dotenv::var("DATABASE_URL")
+        .expect("DATABASE_URL must be set to run benchmarks")
+        .parse()
+        .expect("failed to parse DATABASE_URL");
+
+    let pool = sqlx_rt::enter_runtime(|| {
+        PgPoolOptions::new()
+            .max_connections(1)
+            // every connection is expired by the time it is acquired again, so each `acquire()`
+            // closes the previous connection and opens a new one
+            .max_lifetime(Duration::from_nanos(1))
+            .test_before_acquire(false)
+            .connect_lazy_with(
+                options
+                    .ssl_mode(PgSslMode::Require)
+                    .tls_session_cache(cache),
+            )
+    });
+
+    b.iter_custom(|iters| {
+        sqlx_rt::block_on(async {
+            let start = Instant::now();
+            for _ in 0..iters {
+                for _ in 0..CONNECTIONS {
+                    criterion::black_box(
+                        pool.acquire()
+                            .await
+                            .expect("failed to acquire connection for benchmark"),
+                    );
+                }
+            }
+            start.elapsed()
+        })
+    });
+
+    sqlx_rt::block_on(pool.close());
+}
+
+criterion_group!(pg_tls_reconnect, bench_pgpool_reconnect_tls);
+criterion_main!(pg_tls_reconnect);
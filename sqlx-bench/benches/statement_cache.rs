@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use sqlx_core::bench_support::StatementCache;
+
+// what the cache is keyed by: the SQL of each query, which is usually a few hundred bytes
+fn statements(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| {
+            format!(
+                "SELECT id, name, email, created_at, updated_at FROM users_{} \
+                 WHERE id = $1 AND deleted_at IS NULL ORDER BY created_at DESC LIMIT $2",
+                i
+            )
+        })
+        .collect()
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let statements = statements(100);
+    let mut cache = StatementCache::new(100);
+
+    for (i, sql) in statements.iter().enumerate() {
+        cache.insert(sql, i, 0).unwrap();
+    }
+
+    let mut group = c.benchmark_group("statement_cache_100");
+
+    // every query of an application that runs the same statements over and over
+    group.bench_function("hit", |b| {
+        b.iter(|| {
+            for sql in &statements {
+                black_box(cache.get_mut(sql));
+            }
+        })
+    });
+
+    // twice as many statements as the cache holds, so every insert evicts another
+    let churn = self::statements(200);
+
+    group.bench_function("miss_and_evict", |b| {
+        b.iter(|| {
+            for (i, sql) in churn.iter().enumerate() {
+                if cache.get_mut(sql).is_none() {
+                    black_box(cache.insert(sql, i, 0));
+                }
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(statement_cache, bench_lookup);
+criterion_main!(statement_cache);
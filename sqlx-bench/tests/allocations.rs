@@ -0,0 +1,93 @@
+//! Counts the allocations on the path from a received row to its values.
+//!
+//! The names of the columns and their types are shared by every row of a statement, so none of
+//! the counts below should grow with the number of columns.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use sqlx::{Column, Row};
+use sqlx_core::bench_support::postgres::{PgDescriptionFixture, PgRowFixture};
+use sqlx_core::bench_support::StatementCache;
+use sqlx_core::types::UStr;
+
+struct CountingAllocator;
+
+thread_local! {
+    // per thread, so that other tests running at the same time are not counted
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// the number of allocations `f` made on this thread
+fn allocations<R>(f: impl FnOnce() -> R) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let after = ALLOCATIONS.with(Cell::get);
+
+    drop(result);
+
+    after - before
+}
+
+#[test]
+fn it_decodes_and_clones_rows_without_copying_names() {
+    let fixture = PgRowFixture::new();
+    let row = fixture.decode().unwrap();
+
+    // the offsets of the values
+    assert_eq!(allocations(|| fixture.decode().unwrap()), 1);
+    assert_eq!(allocations(|| row.clone()), 1);
+
+    assert_eq!(allocations(|| row.columns()[4].clone()), 0);
+    assert_eq!(allocations(|| row.columns()[4].name().len()), 0);
+    assert_eq!(
+        allocations(|| row.try_get::<i64, _>("column_6").unwrap()),
+        0
+    );
+    assert_eq!(
+        allocations(|| row.try_get::<&str, _>("column_19").unwrap()),
+        0
+    );
+}
+
+#[test]
+fn it_decodes_descriptions_into_the_arena_of_the_connection() {
+    let mut fixture = PgDescriptionFixture::new();
+
+    // the arena grows to fit the first description, and is reused by the next
+    assert!(allocations(|| fixture.decode().unwrap()) > 0);
+    assert_eq!(allocations(|| fixture.decode().unwrap()), 0);
+}
+
+#[test]
+fn it_shares_names_and_statement_keys() {
+    let name = UStr::new("column_name");
+
+    assert_eq!(allocations(|| UStr::new("column_name")), 1);
+    assert_eq!(allocations(|| name.clone()), 0);
+    assert_eq!(allocations(|| UStr::from("a literal")), 0);
+
+    let mut cache = StatementCache::new(10);
+    cache.insert("SELECT 1", 1, 0).unwrap();
+
+    assert_eq!(allocations(|| cache.get_mut("SELECT 1").copied()), 0);
+}
@@ -12,7 +12,7 @@ pub async fn create(uri: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub async fn drop(uri: &str, confirm: bool) -> anyhow::Result<()> {
+pub async fn drop(uri: &str, confirm: bool, force: bool) -> anyhow::Result<()> {
     if confirm
         && !Confirm::new()
             .with_prompt(format!(
@@ -27,14 +27,23 @@ pub async fn drop(uri: &str, confirm: bool) -> anyhow::Result<()> {
     }
 
     if Any::database_exists(uri).await? {
-        Any::drop_database(uri).await?;
+        if force {
+            Any::force_drop_database(uri).await?;
+        } else {
+            Any::drop_database(uri).await?;
+        }
     }
 
     Ok(())
 }
 
-pub async fn reset(migration_source: &str, uri: &str, confirm: bool) -> anyhow::Result<()> {
-    drop(uri, confirm).await?;
+pub async fn reset(
+    migration_source: &str,
+    uri: &str,
+    confirm: bool,
+    force: bool,
+) -> anyhow::Result<()> {
+    drop(uri, confirm, force).await?;
     setup(migration_source, uri).await
 }
 
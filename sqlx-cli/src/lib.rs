@@ -38,9 +38,11 @@ pub async fn run(opt: Opt) -> anyhow::Result<()> {
 
         Command::Database(database) => match database.command {
             DatabaseCommand::Create => database::create(&database_url).await?,
-            DatabaseCommand::Drop { yes } => database::drop(&database_url, !yes).await?,
-            DatabaseCommand::Reset { yes, source } => {
-                database::reset(&source, &database_url, yes).await?
+            DatabaseCommand::Drop { yes, force } => {
+                database::drop(&database_url, !yes, force).await?
+            }
+            DatabaseCommand::Reset { yes, force, source } => {
+                database::reset(&source, &database_url, !yes, force).await?
             }
             DatabaseCommand::Setup { source } => database::setup(&source, &database_url).await?,
         },
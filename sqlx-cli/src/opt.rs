@@ -60,6 +60,11 @@ pub enum DatabaseCommand {
         /// your database.
         #[clap(short)]
         yes: bool,
+
+        /// Disconnect the other sessions connected to the database before dropping it, instead
+        /// of failing or waiting for them to finish.
+        #[clap(long)]
+        force: bool,
     },
 
     /// Drops the database specified in your DATABASE_URL, re-creates it, and runs any pending migrations.
@@ -69,6 +74,11 @@ pub enum DatabaseCommand {
         #[clap(short)]
         yes: bool,
 
+        /// Disconnect the other sessions connected to the database before dropping it, instead
+        /// of failing or waiting for them to finish.
+        #[clap(long)]
+        force: bool,
+
         /// Path to folder containing migrations. Defaults to 'migrations'
         #[clap(long, default_value = "migrations")]
         source: String,
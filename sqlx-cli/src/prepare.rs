@@ -3,6 +3,7 @@ use console::style;
 use remove_dir_all::remove_dir_all;
 use serde::Deserialize;
 use sqlx::any::{AnyConnectOptions, AnyKind};
+use sqlx::offline::FORMAT_VERSION;
 use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufReader, BufWriter};
@@ -18,6 +19,7 @@ type JsonObject = serde_json::Map<String, serde_json::Value>;
 pub fn run(url: &str, merge: bool, cargo_args: Vec<String>) -> anyhow::Result<()> {
     #[derive(serde::Serialize)]
     struct DataFile {
+        version: u32,
         db: &'static str,
         #[serde(flatten)]
         data: QueryData,
@@ -37,7 +39,11 @@ pub fn run(url: &str, merge: bool, cargo_args: Vec<String>) -> anyhow::Result<()
         BufWriter::new(
             File::create("sqlx-data.json").context("failed to create/open `sqlx-data.json`")?,
         ),
-        &DataFile { db: db_kind, data },
+        &DataFile {
+            version: FORMAT_VERSION,
+            db: db_kind,
+            data,
+        },
     )
     .context("failed to write to `sqlx-data.json`")?;
 
@@ -59,6 +65,22 @@ pub fn check(url: &str, merge: bool, cargo_args: Vec<String>) -> anyhow::Result<
 
     let mut saved_data: QueryData = serde_json::from_reader(BufReader::new(data_file))?;
 
+    // files written before the format was versioned are of version 1
+    let version = match saved_data.remove("version") {
+        Some(version) => version
+            .as_u64()
+            .context("expected key `version` to be a number")?,
+        None => 1,
+    };
+
+    if version > u64::from(FORMAT_VERSION) {
+        bail!(
+            "`sqlx-data.json` has format version {}, which is newer than this version of \
+             sqlx-cli supports",
+            version
+        )
+    }
+
     let expected_db = saved_data
         .remove("db")
         .context("expected key `db` in data file")?;
@@ -201,5 +223,8 @@ fn get_db_kind(url: &str) -> anyhow::Result<&'static str> {
 
         #[cfg(feature = "mssql")]
         AnyKind::Mssql => Ok("MSSQL"),
+
+        // the macros only support the built-in drivers
+        AnyKind::Registered(name) => bail!("queries cannot be prepared for {}", name),
     }
 }
@@ -33,6 +33,24 @@ impl<'a, DB: Database> Acquire<'a> for &'_ Pool<DB> {
     }
 }
 
+// for every database, so a helper written against `Acquire` accepts `&mut conn` for a pooled
+// connection as it does for one that was opened directly, as often as it is called
+impl<'c, DB: Database> Acquire<'c> for &'c mut PoolConnection<DB> {
+    type Database = DB;
+
+    type Connection = &'c mut DB::Connection;
+
+    #[inline]
+    fn acquire(self) -> BoxFuture<'c, Result<Self::Connection, Error>> {
+        Box::pin(futures_util::future::ok(&mut **self))
+    }
+
+    #[inline]
+    fn begin(self) -> BoxFuture<'c, Result<Transaction<'c, DB>, Error>> {
+        Transaction::begin(MaybePoolConnection::Connection(&mut **self))
+    }
+}
+
 #[allow(unused_macros)]
 macro_rules! impl_acquire {
     ($DB:ident, $C:ident) => {
@@ -60,30 +78,6 @@ macro_rules! impl_acquire {
             }
         }
 
-        impl<'c> crate::acquire::Acquire<'c> for &'c mut crate::pool::PoolConnection<$DB> {
-            type Database = $DB;
-
-            type Connection = &'c mut <$DB as crate::database::Database>::Connection;
-
-            #[inline]
-            fn acquire(
-                self,
-            ) -> futures_core::future::BoxFuture<'c, Result<Self::Connection, crate::error::Error>>
-            {
-                Box::pin(futures_util::future::ok(&mut **self))
-            }
-
-            #[inline]
-            fn begin(
-                self,
-            ) -> futures_core::future::BoxFuture<
-                'c,
-                Result<crate::transaction::Transaction<'c, $DB>, crate::error::Error>,
-            > {
-                crate::transaction::Transaction::begin(&mut **self)
-            }
-        }
-
         impl<'c, 't> crate::acquire::Acquire<'t>
             for &'t mut crate::transaction::Transaction<'c, $DB>
         {
@@ -1,6 +1,7 @@
 use crate::any::Any;
 use crate::arguments::Arguments;
-use crate::encode::Encode;
+use crate::encode::{DynEncode, Encode, IsNull};
+use crate::erased::{ErasedArguments, ErasedValue};
 use crate::types::Type;
 
 #[derive(Default)]
@@ -21,6 +22,14 @@ impl<'q> Arguments<'q> for AnyArguments<'q> {
     {
         self.values.push(Box::new(value));
     }
+
+    fn add_dyn(&mut self, value: Box<dyn DynEncode<'q, Self::Database> + 'q>) {
+        self.values.push(Box::new(value));
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
 }
 
 pub struct AnyArgumentBuffer<'q>(pub(crate) AnyArgumentBufferKind<'q>);
@@ -46,6 +55,14 @@ pub(crate) enum AnyArgumentBufferKind<'q> {
         crate::mssql::MssqlArguments,
         std::marker::PhantomData<&'q ()>,
     ),
+
+    #[cfg(feature = "memory")]
+    Memory(
+        crate::memory::MemoryArguments,
+        std::marker::PhantomData<&'q ()>,
+    ),
+
+    Erased(ErasedArguments, std::marker::PhantomData<&'q ()>),
 }
 
 // control flow inferred type bounds would be fun
@@ -131,3 +148,50 @@ impl<'q> From<AnyArguments<'q>> for crate::postgres::PgArguments {
         }
     }
 }
+
+#[cfg(feature = "memory")]
+#[allow(irrefutable_let_patterns)]
+impl<'q> From<AnyArguments<'q>> for crate::memory::MemoryArguments {
+    fn from(args: AnyArguments<'q>) -> Self {
+        let mut buf = AnyArgumentBuffer(AnyArgumentBufferKind::Memory(
+            Default::default(),
+            std::marker::PhantomData,
+        ));
+
+        for value in args.values {
+            let _ = value.encode_by_ref(&mut buf);
+        }
+
+        if let AnyArgumentBufferKind::Memory(args, _) = buf.0 {
+            args
+        } else {
+            unreachable!()
+        }
+    }
+}
+
+// for the connections of registered drivers
+#[allow(irrefutable_let_patterns)]
+impl<'q> From<AnyArguments<'q>> for ErasedArguments {
+    fn from(args: AnyArguments<'q>) -> Self {
+        let mut buf = AnyArgumentBuffer(AnyArgumentBufferKind::Erased(
+            Default::default(),
+            std::marker::PhantomData,
+        ));
+
+        for value in args.values {
+            // a `None` is not written to the buffer, only reported to be NULL
+            if let Ok(IsNull::Yes) = value.encode_by_ref(&mut buf) {
+                if let AnyArgumentBufferKind::Erased(args, _) = &mut buf.0 {
+                    args.add(ErasedValue::Null);
+                }
+            }
+        }
+
+        if let AnyArgumentBufferKind::Erased(args, _) = buf.0 {
+            args
+        } else {
+            unreachable!()
+        }
+    }
+}
@@ -13,6 +13,9 @@ use crate::sqlite::{SqliteColumn, SqliteRow, SqliteStatement};
 #[cfg(feature = "mssql")]
 use crate::mssql::{MssqlColumn, MssqlRow, MssqlStatement};
 
+#[cfg(feature = "memory")]
+use crate::memory::MemoryColumn;
+
 #[derive(Debug, Clone)]
 pub struct AnyColumn {
     pub(crate) kind: AnyColumnKind,
@@ -34,6 +37,14 @@ pub(crate) enum AnyColumnKind {
 
     #[cfg(feature = "mssql")]
     Mssql(MssqlColumn),
+
+    #[cfg(feature = "memory")]
+    Memory(MemoryColumn),
+
+    Erased {
+        ordinal: usize,
+        name: String,
+    },
 }
 
 impl Column for AnyColumn {
@@ -52,6 +63,11 @@ impl Column for AnyColumn {
 
             #[cfg(feature = "mssql")]
             AnyColumnKind::Mssql(row) => row.ordinal(),
+
+            #[cfg(feature = "memory")]
+            AnyColumnKind::Memory(row) => row.ordinal(),
+
+            AnyColumnKind::Erased { ordinal, .. } => *ordinal,
         }
     }
 
@@ -68,6 +84,11 @@ impl Column for AnyColumn {
 
             #[cfg(feature = "mssql")]
             AnyColumnKind::Mssql(row) => row.name(),
+
+            #[cfg(feature = "memory")]
+            AnyColumnKind::Memory(row) => row.name(),
+
+            AnyColumnKind::Erased { name, .. } => name,
         }
     }
 
@@ -441,3 +462,35 @@ impl<I: ?Sized> AnyColumnIndex for I where
     I: ColumnIndex<SqliteRow> + for<'q> ColumnIndex<SqliteStatement<'q>>
 {
 }
+
+// only the in-memory driver; alongside any other driver it is left out of the bounds, as the
+// implementations for the built-in types name its own directly
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "memory"
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<crate::memory::MemoryRow> + for<'q> ColumnIndex<crate::memory::MemoryStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "memory"
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<crate::memory::MemoryRow>
+        + for<'q> ColumnIndex<crate::memory::MemoryStatement<'q>>
+{
+}
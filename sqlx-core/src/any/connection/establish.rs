@@ -34,6 +34,17 @@ impl AnyConnection {
                     .await
                     .map(AnyConnectionKind::Mssql)
             }
+
+            #[cfg(feature = "memory")]
+            AnyConnectOptionsKind::Memory(options) => {
+                crate::memory::MemoryConnection::connect_with(options)
+                    .await
+                    .map(AnyConnectionKind::Memory)
+            }
+
+            AnyConnectOptionsKind::Registered(o) => {
+                (o.driver.connect)(options).await.map(|conn| conn.0)
+            }
         }
         .map(AnyConnection)
     }
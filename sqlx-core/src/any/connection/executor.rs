@@ -11,6 +11,10 @@ use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_util::{StreamExt, TryStreamExt};
 
+impl AnyConnection {
+    impl_executor_methods!(Any, &mut self => self);
+}
+
 impl<'c> Executor<'c> for &'c mut AnyConnection {
     type Database = Any;
 
@@ -49,6 +53,17 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
                 .fetch_many((query, arguments.map(Into::into)))
                 .map_ok(|v| v.map_right(Into::into).map_left(Into::into))
                 .boxed(),
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(conn) => conn
+                .fetch_many((query, arguments.map(Into::into)))
+                .map_ok(|v| v.map_right(Into::into).map_left(Into::into))
+                .boxed(),
+
+            AnyConnectionKind::Registered(conn) => conn
+                .fetch_many_erased(query, arguments.map(Into::into).unwrap_or_default())
+                .map_ok(|v| v.map_right(Into::into).map_left(Into::into))
+                .boxed(),
         }
     }
 
@@ -88,6 +103,17 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
                     .fetch_optional((query, arguments.map(Into::into)))
                     .await?
                     .map(Into::into),
+
+                #[cfg(feature = "memory")]
+                AnyConnectionKind::Memory(conn) => conn
+                    .fetch_optional((query, arguments.map(Into::into)))
+                    .await?
+                    .map(Into::into),
+
+                AnyConnectionKind::Registered(conn) => conn
+                    .fetch_optional_erased(query, arguments.map(Into::into).unwrap_or_default())
+                    .await?
+                    .map(Into::into),
             })
         })
     }
@@ -114,6 +140,11 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
 
                 #[cfg(feature = "mssql")]
                 AnyConnectionKind::Mssql(conn) => conn.prepare(sql).await.map(Into::into)?,
+
+                #[cfg(feature = "memory")]
+                AnyConnectionKind::Memory(conn) => conn.prepare(sql).await.map(Into::into)?,
+
+                AnyConnectionKind::Registered(_) => return Err(not_prepared()),
             })
         })
     }
@@ -138,11 +169,21 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
 
                 #[cfg(feature = "mssql")]
                 AnyConnectionKind::Mssql(conn) => conn.describe(sql).await.map(map_describe)?,
+
+                #[cfg(feature = "memory")]
+                AnyConnectionKind::Memory(conn) => conn.describe(sql).await.map(map_describe)?,
+
+                AnyConnectionKind::Registered(_) => return Err(not_prepared()),
             })
         })
     }
 }
 
+// registered drivers run statements through `ErasedExecutor`, which only runs them
+fn not_prepared() -> Error {
+    Error::Configuration("registered drivers cannot prepare or describe statements".into())
+}
+
 fn map_describe<DB: Database>(info: Describe<DB>) -> Describe<Any>
 where
     AnyTypeInfo: From<DB::TypeInfo>,
@@ -1,8 +1,10 @@
 use futures_core::future::BoxFuture;
 
-use crate::any::{Any, AnyConnectOptions};
-use crate::connection::Connection;
+use crate::any::{Any, AnyConnectOptions, AnyDriverConnection};
+use crate::connection::{BufferSizes, Connection, ConnectionStats, SessionState};
+use crate::database::{Capabilities, Database};
 use crate::error::Error;
+use crate::events::ConnectionEvents;
 
 #[cfg(feature = "postgres")]
 use crate::postgres;
@@ -15,6 +17,9 @@ use crate::mssql;
 
 #[cfg(feature = "mysql")]
 use crate::mysql;
+
+#[cfg(feature = "memory")]
+use crate::memory;
 use crate::transaction::Transaction;
 
 mod establish;
@@ -45,22 +50,80 @@ pub(crate) enum AnyConnectionKind {
 
     #[cfg(feature = "sqlite")]
     Sqlite(sqlite::SqliteConnection),
+
+    #[cfg(feature = "memory")]
+    Memory(memory::MemoryConnection),
+
+    Registered(Box<dyn AnyDriverConnection>),
+}
+
+// the methods of `Connection` for the connections of registered drivers, most of which are
+// left out of `AnyDriverConnection` as only the built-in drivers implement them
+trait RegisteredConnection {
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    fn stats(&self) -> ConnectionStats;
+
+    fn shrink_buffers(&mut self) {}
+
+    fn buffer_sizes(&self) -> BufferSizes {
+        BufferSizes::default()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::empty()
+    }
+
+    fn session_state(&mut self) -> BoxFuture<'_, Result<SessionState, Error>> {
+        Box::pin(futures_util::future::ok(SessionState::default()))
+    }
+
+    fn take_events(&mut self) -> ConnectionEvents {
+        ConnectionEvents::default()
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(futures_util::future::ok(()))
+    }
+
+    fn should_flush(&self) -> bool {
+        false
+    }
+
+    fn saw_server_shutdown(&self) -> bool {
+        false
+    }
+}
+
+impl RegisteredConnection for Box<dyn AnyDriverConnection> {
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        AnyDriverConnection::ping(&mut **self)
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        AnyDriverConnection::stats(&**self)
+    }
 }
 
 macro_rules! delegate_to {
     ($self:ident.$method:ident($($arg:ident),*)) => {
         match &$self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.$method($($arg),*),
+            AnyConnectionKind::Postgres(conn) => Connection::$method(conn, $($arg),*),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.$method($($arg),*),
+            AnyConnectionKind::MySql(conn) => Connection::$method(conn, $($arg),*),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.$method($($arg),*),
+            AnyConnectionKind::Sqlite(conn) => Connection::$method(conn, $($arg),*),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(conn) => conn.$method($($arg),*),
+            AnyConnectionKind::Mssql(conn) => Connection::$method(conn, $($arg),*),
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(conn) => Connection::$method(conn, $($arg),*),
+
+            AnyConnectionKind::Registered(conn) => RegisteredConnection::$method(conn, $($arg),*),
         }
     };
 }
@@ -69,20 +132,76 @@ macro_rules! delegate_to_mut {
     ($self:ident.$method:ident($($arg:ident),*)) => {
         match &mut $self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.$method($($arg),*),
+            AnyConnectionKind::Postgres(conn) => Connection::$method(conn, $($arg),*),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => Connection::$method(conn, $($arg),*),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => Connection::$method(conn, $($arg),*),
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(conn) => Connection::$method(conn, $($arg),*),
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(conn) => Connection::$method(conn, $($arg),*),
+
+            AnyConnectionKind::Registered(conn) => RegisteredConnection::$method(conn, $($arg),*),
+        }
+    };
+}
+
+// the value of a `Database` constant for the driver of this connection
+macro_rules! database_const {
+    ($self:ident.$name:ident) => {
+        match &$self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(_) => <postgres::Postgres as Database>::$name,
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.$method($($arg),*),
+            AnyConnectionKind::MySql(_) => <mysql::MySql as Database>::$name,
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.$method($($arg),*),
+            AnyConnectionKind::Sqlite(_) => <sqlite::Sqlite as Database>::$name,
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(conn) => conn.$method($($arg),*),
+            AnyConnectionKind::Mssql(_) => <mssql::Mssql as Database>::$name,
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(_) => <memory::Memory as Database>::$name,
+
+            // no more than the `Any` driver promises
+            AnyConnectionKind::Registered(_) => <Any as Database>::$name,
         }
     };
 }
 
+impl AnyConnection {
+    /// A connection of a driver registered with
+    /// [`register_driver`](crate::any::register_driver), for its
+    /// [`connect`](crate::any::AnyDriverVtable::connect) to return.
+    pub fn from_driver<C>(conn: C) -> Self
+    where
+        C: AnyDriverConnection + 'static,
+    {
+        AnyConnection(AnyConnectionKind::Registered(Box::new(conn)))
+    }
+
+    /// The most arguments that can be bound to a single query on this connection.
+    ///
+    /// This is [`Database::MAX_BIND_PARAMETERS`] of the driver in use, which may be more than
+    /// that of [`Any`].
+    pub fn max_bind_parameters(&self) -> usize {
+        database_const!(self.MAX_BIND_PARAMETERS)
+    }
+
+    /// The longest an identifier can be on this connection, in bytes, or `None` if there is
+    /// no limit.
+    pub fn max_identifier_length(&self) -> Option<usize> {
+        database_const!(self.MAX_IDENTIFIER_LENGTH)
+    }
+}
+
 impl Connection for AnyConnection {
     type Database = Any;
 
@@ -101,6 +220,11 @@ impl Connection for AnyConnection {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => conn.close(),
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(conn) => conn.close(),
+
+            AnyConnectionKind::Registered(conn) => conn.close(),
         }
     }
 
@@ -129,6 +253,11 @@ impl Connection for AnyConnection {
             // no cache
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_) => 0,
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(_) => 0,
+
+            AnyConnectionKind::Registered(_) => 0,
         }
     }
 
@@ -146,9 +275,67 @@ impl Connection for AnyConnection {
             // no cache
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_) => Box::pin(futures_util::future::ok(())),
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(_) => Box::pin(futures_util::future::ok(())),
+
+            AnyConnectionKind::Registered(_) => Box::pin(futures_util::future::ok(())),
         }
     }
 
+    fn prepare_pinned<'e>(&'e mut self, sql: &'e str) -> BoxFuture<'e, Result<(), Error>> {
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.prepare_pinned(sql),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.prepare_pinned(sql),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.prepare_pinned(sql),
+
+            // no cache
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(_) => Box::pin(futures_util::future::err(
+                Error::Configuration("MSSQL has no statement cache".into()),
+            )),
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(_) => Box::pin(futures_util::future::err(
+                Error::Configuration("the memory driver has no statement cache".into()),
+            )),
+
+            AnyConnectionKind::Registered(_) => Box::pin(futures_util::future::err(
+                Error::Configuration("registered drivers have no statement cache".into()),
+            )),
+        }
+    }
+
+    fn shrink_buffers(&mut self) {
+        delegate_to_mut!(self.shrink_buffers())
+    }
+
+    fn buffer_sizes(&self) -> BufferSizes {
+        delegate_to!(self.buffer_sizes())
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        delegate_to!(self.stats())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        delegate_to!(self.capabilities())
+    }
+
+    fn session_state(&mut self) -> BoxFuture<'_, Result<SessionState, Error>> {
+        delegate_to_mut!(self.session_state())
+    }
+
+    #[doc(hidden)]
+    fn take_events(&mut self) -> ConnectionEvents {
+        delegate_to_mut!(self.take_events())
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         delegate_to_mut!(self.flush())
@@ -158,4 +345,9 @@ impl Connection for AnyConnection {
     fn should_flush(&self) -> bool {
         delegate_to!(self.should_flush())
     }
+
+    #[doc(hidden)]
+    fn saw_server_shutdown(&self) -> bool {
+        delegate_to!(self.saw_server_shutdown())
+    }
 }
@@ -2,7 +2,44 @@ use crate::any::{
     AnyArgumentBuffer, AnyArguments, AnyColumn, AnyConnection, AnyQueryResult, AnyRow,
     AnyStatement, AnyTransactionManager, AnyTypeInfo, AnyValue, AnyValueRef,
 };
-use crate::database::{Database, HasArguments, HasStatement, HasStatementCache, HasValueRef};
+use crate::database::{
+    Capabilities, Database, HasArguments, HasStatement, HasStatementCache, HasValueRef,
+};
+
+// folds the constant `$name` of each enabled driver into `$init` using `$f`
+macro_rules! common {
+    ($init:expr, $name:ident, $f:ident) => {{
+        #[allow(unused_mut)]
+        let mut value = $init;
+
+        #[cfg(feature = "postgres")]
+        {
+            value = $f(value, <crate::postgres::Postgres as Database>::$name);
+        }
+
+        #[cfg(feature = "mysql")]
+        {
+            value = $f(value, <crate::mysql::MySql as Database>::$name);
+        }
+
+        #[cfg(feature = "sqlite")]
+        {
+            value = $f(value, <crate::sqlite::Sqlite as Database>::$name);
+        }
+
+        #[cfg(feature = "mssql")]
+        {
+            value = $f(value, <crate::mssql::Mssql as Database>::$name);
+        }
+
+        #[cfg(feature = "memory")]
+        {
+            value = $f(value, <crate::memory::Memory as Database>::$name);
+        }
+
+        value
+    }};
+}
 
 /// Opaque database driver. Capable of being used in place of any SQLx database driver. The actual
 /// driver used will be selected at runtime, from the connection uri.
@@ -23,6 +60,34 @@ impl Database for Any {
     type TypeInfo = AnyTypeInfo;
 
     type Value = AnyValue;
+
+    // what every enabled driver supports; `AnyConnection` knows which one it is using
+    const CAPABILITIES: Capabilities = common!(Capabilities::all(), CAPABILITIES, intersection);
+
+    const MAX_BIND_PARAMETERS: usize = common!(usize::MAX, MAX_BIND_PARAMETERS, min);
+
+    const MAX_IDENTIFIER_LENGTH: Option<usize> =
+        common!(None, MAX_IDENTIFIER_LENGTH, min_identifier_length);
+}
+
+const fn intersection(a: Capabilities, b: Capabilities) -> Capabilities {
+    Capabilities::from_bits_truncate(a.bits() & b.bits())
+}
+
+const fn min(a: usize, b: usize) -> usize {
+    if a < b {
+        a
+    } else {
+        b
+    }
+}
+
+const fn min_identifier_length(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(min(a, b)),
+        (Some(a), None) => Some(a),
+        (None, b) => b,
+    }
 }
 
 impl<'r> HasValueRef<'r> for Any {
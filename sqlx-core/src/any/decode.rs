@@ -44,6 +44,15 @@ macro_rules! impl_any_decode {
                     crate::any::value::AnyValueRefKind::Postgres(value) => {
                         <$ty as crate::decode::Decode<'r, crate::postgres::Postgres>>::decode(value)
                     }
+
+                    #[cfg(feature = "memory")]
+                    crate::any::value::AnyValueRefKind::Memory(value) => {
+                        <$ty as crate::decode::Decode<'r, crate::memory::Memory>>::decode(value)
+                    }
+
+                    crate::any::value::AnyValueRefKind::Erased(value) => {
+                        <$ty as crate::any::erased::DecodeErased<'r>>::decode_erased(value)
+                    }
                 }
             }
         }
@@ -361,3 +370,31 @@ pub trait AnyDecode<'r>: Decode<'r, Sqlite> + Type<Sqlite> {}
     feature = "sqlite"
 ))]
 impl<'r, T> AnyDecode<'r> for T where T: Decode<'r, Sqlite> + Type<Sqlite> {}
+
+// only the in-memory driver; alongside any other driver it is left out of the bounds, as the
+// implementations for the built-in types name its own directly
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "memory"
+))]
+pub trait AnyDecode<'r>: Decode<'r, crate::memory::Memory> + Type<crate::memory::Memory> {}
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "memory"
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, crate::memory::Memory> + Type<crate::memory::Memory>
+{
+}
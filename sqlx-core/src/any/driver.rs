@@ -0,0 +1,324 @@
+use std::fmt::{self, Debug, Formatter};
+
+use futures_core::future::BoxFuture;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+use crate::any::kind::{self, AnyKind};
+use crate::any::{AnyConnectOptions, AnyConnection};
+use crate::connection::{Connection, ConnectionStats};
+use crate::database::Database;
+use crate::erased::ErasedExecutor;
+use crate::error::Error;
+use crate::transaction::TransactionManager;
+use crate::HashMap;
+
+/// How the [`Any`](crate::any::Any) driver parses the URLs of a database and connects to it;
+/// see [`register_driver`].
+///
+/// The built-in drivers are registered with one of these each, for the schemes they have
+/// always been selected by.
+pub struct AnyDriverVtable {
+    /// What [`AnyConnectOptions::kind`] returns for the options made by `parse`:
+    /// [`AnyKind::Registered`] with the name of the database, for a driver outside of SQLx.
+    pub kind: AnyKind,
+
+    /// Parses a URL with one of the schemes the driver is registered for, usually into
+    /// [`AnyConnectOptions::from_driver`].
+    pub parse: fn(url: &str) -> Result<AnyConnectOptions, Error>,
+
+    /// Connects with options made by `parse`, usually returning
+    /// [`AnyConnection::from_driver`].
+    pub connect: fn(options: &AnyConnectOptions) -> BoxFuture<'_, Result<AnyConnection, Error>>,
+}
+
+impl Debug for AnyDriverVtable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyDriverVtable")
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+/// A connection of a driver registered with [`register_driver`], as used by an
+/// [`AnyConnection`]: the [`ErasedExecutor`] queries are run through, and what else the
+/// `AnyConnection` needs of it.
+///
+/// Implemented for every [`Connection`] that is an [`ErasedExecutor`].
+pub trait AnyDriverConnection: ErasedExecutor {
+    fn close(self: Box<Self>) -> BoxFuture<'static, Result<(), Error>>;
+
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    fn stats(&self) -> ConnectionStats;
+
+    /// See [`TransactionManager::begin`].
+    fn begin(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// See [`TransactionManager::commit`].
+    fn commit(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// See [`TransactionManager::rollback`].
+    fn rollback(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// See [`TransactionManager::start_rollback`].
+    fn start_rollback(&mut self);
+}
+
+impl<C, DB> AnyDriverConnection for C
+where
+    C: Connection<Database = DB> + ErasedExecutor,
+    DB: Database<Connection = C>,
+{
+    fn close(self: Box<Self>) -> BoxFuture<'static, Result<(), Error>> {
+        <C as Connection>::close(*self)
+    }
+
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        <C as Connection>::ping(self)
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        <C as Connection>::stats(self)
+    }
+
+    fn begin(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        <DB::TransactionManager as TransactionManager>::begin(self)
+    }
+
+    fn commit(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        <DB::TransactionManager as TransactionManager>::commit(self)
+    }
+
+    fn rollback(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        <DB::TransactionManager as TransactionManager>::rollback(self)
+    }
+
+    fn start_rollback(&mut self) {
+        <DB::TransactionManager as TransactionManager>::start_rollback(self)
+    }
+}
+
+// the driver of each scheme, starting with the built-in drivers
+static DRIVERS: Lazy<RwLock<HashMap<&'static str, &'static AnyDriverVtable>>> =
+    Lazy::new(|| RwLock::new(built_in()));
+
+/// Registers the driver the [`Any`](crate::any::Any) driver uses for database URLs with the
+/// scheme `scheme` (without the `:`), so that a driver outside of SQLx can be used through
+/// [`AnyConnection`] and [`AnyPool`](crate::any::AnyPool).
+///
+/// Fails if a driver is already registered for the scheme, which includes the schemes of the
+/// built-in drivers that are enabled; a registration cannot be undone.
+///
+/// Queries are run through the [`AnyDriverConnection`] of the driver, so only the types of
+/// [`ErasedValue`](crate::erased::ErasedValue) can be bound and fetched, and statements cannot
+/// be prepared or described ahead of running them. The logging, middleware and connection
+/// event settings of [`ConnectOptions`](crate::connection::ConnectOptions) are not applied to
+/// these connections.
+///
+/// # Thread safety
+///
+/// The drivers are kept behind a lock, so a driver may be registered from any thread at any
+/// time, including while other threads parse URLs and connect. A URL with the scheme is
+/// recognized from the moment `register_driver` returns; one parsed before is an error, as
+/// if the driver did not exist.
+pub fn register_driver(
+    scheme: &'static str,
+    driver: &'static AnyDriverVtable,
+) -> Result<(), Error> {
+    insert(&mut DRIVERS.write(), scheme, driver)
+}
+
+fn insert(
+    drivers: &mut HashMap<&'static str, &'static AnyDriverVtable>,
+    scheme: &'static str,
+    driver: &'static AnyDriverVtable,
+) -> Result<(), Error> {
+    // as in RFC 3986
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+
+    if !valid {
+        return Err(Error::config(format!(
+            "{:?} is not a valid database URL scheme",
+            scheme
+        )));
+    }
+
+    if drivers.contains_key(scheme) {
+        return Err(Error::config(format!(
+            "a driver is already registered for the database URL scheme {:?}",
+            scheme
+        )));
+    }
+
+    drivers.insert(scheme, driver);
+
+    Ok(())
+}
+
+/// The driver registered for the scheme of `url`.
+pub(crate) fn driver_for(url: &str) -> Result<&'static AnyDriverVtable, Error> {
+    let driver = match url.find(':') {
+        Some(end) => DRIVERS.read().get(&url[..end]).copied(),
+        None => None,
+    };
+
+    driver.ok_or_else(|| kind::unrecognized(url))
+}
+
+fn built_in() -> HashMap<&'static str, &'static AnyDriverVtable> {
+    #[allow(unused_mut)]
+    let mut drivers = HashMap::new();
+
+    #[allow(unused_macros)]
+    macro_rules! register {
+        ($driver:expr, $($scheme:literal),*) => {
+            $(insert(&mut drivers, $scheme, $driver).unwrap();)*
+        };
+    }
+
+    #[cfg(feature = "postgres")]
+    register!(&POSTGRES, "postgres", "postgresql");
+
+    #[cfg(feature = "mysql")]
+    register!(&MYSQL, "mysql", "mariadb");
+
+    #[cfg(feature = "sqlite")]
+    register!(&SQLITE, "sqlite");
+
+    #[cfg(feature = "mssql")]
+    register!(&MSSQL, "mssql", "sqlserver");
+
+    #[cfg(feature = "memory")]
+    register!(&MEMORY, "memory");
+
+    drivers
+}
+
+// the built-in drivers connect through the variants of `AnyConnectionKind` made for them
+#[allow(dead_code)]
+fn establish(options: &AnyConnectOptions) -> BoxFuture<'_, Result<AnyConnection, Error>> {
+    Box::pin(AnyConnection::establish(options))
+}
+
+#[cfg(feature = "postgres")]
+static POSTGRES: AnyDriverVtable = AnyDriverVtable {
+    kind: AnyKind::Postgres,
+    parse: |url| Ok(url.parse::<crate::postgres::PgConnectOptions>()?.into()),
+    connect: establish,
+};
+
+#[cfg(feature = "mysql")]
+static MYSQL: AnyDriverVtable = AnyDriverVtable {
+    kind: AnyKind::MySql,
+    parse: |url| Ok(url.parse::<crate::mysql::MySqlConnectOptions>()?.into()),
+    connect: establish,
+};
+
+#[cfg(feature = "sqlite")]
+static SQLITE: AnyDriverVtable = AnyDriverVtable {
+    kind: AnyKind::Sqlite,
+    parse: |url| Ok(url.parse::<crate::sqlite::SqliteConnectOptions>()?.into()),
+    connect: establish,
+};
+
+#[cfg(feature = "mssql")]
+static MSSQL: AnyDriverVtable = AnyDriverVtable {
+    kind: AnyKind::Mssql,
+    parse: |url| Ok(url.parse::<crate::mssql::MssqlConnectOptions>()?.into()),
+    connect: establish,
+};
+
+#[cfg(feature = "memory")]
+static MEMORY: AnyDriverVtable = AnyDriverVtable {
+    kind: AnyKind::Memory,
+    parse: |url| Ok(url.parse::<crate::memory::MemoryConnectOptions>()?.into()),
+    connect: establish,
+};
+
+#[cfg(test)]
+static TEST: AnyDriverVtable = AnyDriverVtable {
+    kind: AnyKind::Registered("test"),
+    parse: |url| Ok(AnyConnectOptions::from_driver(&TEST, url.to_owned())),
+    connect: |_| Box::pin(async { Err(Error::PoolClosed) }),
+};
+
+#[test]
+fn it_rejects_a_scheme_registered_twice() {
+    register_driver("test-twice", &TEST).unwrap();
+
+    let err = register_driver("test-twice", &TEST).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "error with configuration: a driver is already registered for the database URL scheme \"test-twice\""
+    );
+
+    #[cfg(feature = "postgres")]
+    assert!(register_driver("postgres", &TEST).is_err());
+
+    assert!(register_driver("test:twice", &TEST).is_err());
+    assert!(register_driver("", &TEST).is_err());
+}
+
+#[test]
+fn it_registers_drivers_while_other_threads_parse_urls() {
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    const THREADS: usize = 8;
+
+    let schemes: Vec<&'static str> = (0..THREADS)
+        .map(|i| &*Box::leak(format!("test-concurrent-{}", i).into_boxed_str()))
+        .collect();
+
+    let barrier = Arc::new(Barrier::new(THREADS * 2));
+
+    let registering = schemes.iter().map(|&scheme| {
+        let barrier = Arc::clone(&barrier);
+
+        thread::spawn(move || {
+            barrier.wait();
+
+            // every thread races to register the first scheme, and alone registers its own
+            let first = register_driver("test-concurrent", &TEST).is_ok();
+            register_driver(scheme, &TEST).unwrap();
+
+            first
+        })
+    });
+
+    let parsing = schemes.iter().map(|&scheme| {
+        let barrier = Arc::clone(&barrier);
+
+        thread::spawn(move || {
+            barrier.wait();
+
+            // until it is registered a scheme is unrecognized, never half registered
+            loop {
+                match format!("{}://localhost", scheme).parse::<AnyConnectOptions>() {
+                    Ok(options) => break options.kind(),
+                    Err(Error::Configuration(_)) => thread::yield_now(),
+                    Err(err) => panic!("{}", err),
+                }
+            }
+        })
+    });
+
+    let registering: Vec<_> = registering.collect();
+    let parsing: Vec<_> = parsing.collect();
+
+    let registered_first = registering
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .filter(|&first| first)
+        .count();
+
+    assert_eq!(registered_first, 1);
+
+    for thread in parsing {
+        assert_eq!(thread.join().unwrap(), AnyKind::Registered("test"));
+    }
+}
@@ -24,7 +24,7 @@ macro_rules! impl_any_encode {
             fn encode_by_ref(
                 &self,
                 buf: &mut crate::any::AnyArgumentBuffer<'q>,
-            ) -> crate::encode::IsNull {
+            ) -> Result<crate::encode::IsNull, crate::encode::EncodeError> {
                 match &mut buf.0 {
                     #[cfg(feature = "postgres")]
                     crate::any::arguments::AnyArgumentBufferKind::Postgres(args, _) => {
@@ -39,10 +39,17 @@ macro_rules! impl_any_encode {
 
                     #[cfg(feature = "sqlite")]
                     crate::any::arguments::AnyArgumentBufferKind::Sqlite(args) => args.add(self),
+
+                    #[cfg(feature = "memory")]
+                    crate::any::arguments::AnyArgumentBufferKind::Memory(args, _) => args.add(self),
+
+                    crate::any::arguments::AnyArgumentBufferKind::Erased(args, _) => {
+                        args.add(<$ty as Clone>::clone(self))
+                    }
                 }
 
                 // unused
-                crate::encode::IsNull::No
+                Ok(crate::encode::IsNull::No)
             }
         }
     };
@@ -359,3 +366,31 @@ pub trait AnyEncode<'q>: Encode<'q, Sqlite> + Type<Sqlite> {}
     feature = "sqlite"
 ))]
 impl<'q, T> AnyEncode<'q> for T where T: Encode<'q, Sqlite> + Type<Sqlite> {}
+
+// only the in-memory driver; alongside any other driver it is left out of the bounds, as the
+// implementations for the built-in types name its own directly
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "memory"
+))]
+pub trait AnyEncode<'q>: Encode<'q, crate::memory::Memory> + Type<crate::memory::Memory> {}
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "memory"
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, crate::memory::Memory> + Type<crate::memory::Memory>
+{
+}
@@ -0,0 +1,215 @@
+use std::convert::TryFrom;
+
+use crate::any::arguments::AnyArgumentBufferKind;
+use crate::any::column::AnyColumnKind;
+use crate::any::row::AnyRowKind;
+use crate::any::type_info::AnyTypeInfoKind;
+use crate::any::{
+    Any, AnyArgumentBuffer, AnyArguments, AnyColumn, AnyQueryResult, AnyRow, AnyTypeInfo,
+};
+use crate::arguments::Arguments;
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::erased::{ErasedDatabase, ErasedQueryResult, ErasedRow, ErasedValue};
+use crate::error::{BoxDynError, Error, UnexpectedNullError};
+use crate::types::Type;
+
+#[cfg(feature = "postgres")]
+use crate::postgres::Postgres;
+
+#[cfg(feature = "mysql")]
+use crate::mysql::MySql;
+
+#[cfg(feature = "sqlite")]
+use crate::sqlite::Sqlite;
+
+#[cfg(feature = "mssql")]
+use crate::mssql::Mssql;
+
+#[cfg(feature = "memory")]
+use crate::memory::Memory;
+
+impl ErasedDatabase for Any {
+    fn add_erased(arguments: &mut AnyArguments<'_>, value: ErasedValue) -> Result<(), Error> {
+        match value {
+            ErasedValue::Null => arguments.add(ErasedNull),
+            ErasedValue::Bool(v) => arguments.add(v),
+            ErasedValue::Int(v) => arguments.add(v),
+            ErasedValue::Float(v) => arguments.add(v),
+            ErasedValue::Text(v) => arguments.add(v),
+
+            // like the driver itself, only the types every database supports are allowed
+            ErasedValue::Bytes(_) => return Err(value.unsupported("the Any driver")),
+        }
+
+        Ok(())
+    }
+
+    fn get_erased(row: &AnyRow, index: usize) -> Result<ErasedValue, Error> {
+        match &row.kind {
+            #[cfg(feature = "postgres")]
+            AnyRowKind::Postgres(row) => Postgres::get_erased(row, index),
+
+            #[cfg(feature = "mysql")]
+            AnyRowKind::MySql(row) => MySql::get_erased(row, index),
+
+            #[cfg(feature = "sqlite")]
+            AnyRowKind::Sqlite(row) => Sqlite::get_erased(row, index),
+
+            #[cfg(feature = "mssql")]
+            AnyRowKind::Mssql(row) => Mssql::get_erased(row, index),
+
+            #[cfg(feature = "memory")]
+            AnyRowKind::Memory(row) => Memory::get_erased(row, index),
+
+            AnyRowKind::Erased(row) => Ok(row.values()[index].clone()),
+        }
+    }
+
+    fn erase_query_result(result: AnyQueryResult) -> ErasedQueryResult {
+        ErasedQueryResult {
+            rows_affected: result.rows_affected,
+            last_insert_id: result.last_insert_id,
+        }
+    }
+}
+
+// NULL is bound the way the driver of the connection binds an erased NULL, as the typed NULL
+// of an `Option` would be declared with that type in PostgreSQL
+struct ErasedNull;
+
+impl Type<Any> for ErasedNull {
+    fn type_info() -> AnyTypeInfo {
+        // the drivers do not ask for it, as NULL is bound through `add_erased`; this is the type
+        // of a NULL from a registered driver
+        AnyTypeInfo(AnyTypeInfoKind::Erased(ErasedValue::Null.kind()))
+    }
+
+    fn compatible(_ty: &AnyTypeInfo) -> bool {
+        false
+    }
+}
+
+impl<'q> Encode<'q, Any> for ErasedNull {
+    fn encode_by_ref(&self, buf: &mut AnyArgumentBuffer<'q>) -> Result<IsNull, EncodeError> {
+        // binding NULL cannot fail in any driver
+        let _ = match &mut buf.0 {
+            #[cfg(feature = "postgres")]
+            AnyArgumentBufferKind::Postgres(args, _) => {
+                Postgres::add_erased(args, ErasedValue::Null)
+            }
+
+            #[cfg(feature = "mysql")]
+            AnyArgumentBufferKind::MySql(args, _) => MySql::add_erased(args, ErasedValue::Null),
+
+            #[cfg(feature = "sqlite")]
+            AnyArgumentBufferKind::Sqlite(args) => Sqlite::add_erased(args, ErasedValue::Null),
+
+            #[cfg(feature = "mssql")]
+            AnyArgumentBufferKind::Mssql(args, _) => Mssql::add_erased(args, ErasedValue::Null),
+
+            #[cfg(feature = "memory")]
+            AnyArgumentBufferKind::Memory(args, _) => Memory::add_erased(args, ErasedValue::Null),
+
+            AnyArgumentBufferKind::Erased(args, _) => {
+                args.add(ErasedValue::Null);
+                Ok(())
+            }
+        };
+
+        // unused
+        Ok(IsNull::No)
+    }
+}
+
+// the rows and results of the connections of registered drivers
+
+impl From<ErasedRow> for AnyRow {
+    fn from(row: ErasedRow) -> Self {
+        AnyRow {
+            columns: row
+                .columns()
+                .iter()
+                .zip(row.values())
+                .enumerate()
+                .map(|(ordinal, (name, value))| AnyColumn {
+                    kind: AnyColumnKind::Erased {
+                        ordinal,
+                        name: name.clone(),
+                    },
+                    type_info: AnyTypeInfo(AnyTypeInfoKind::Erased(value.kind())),
+                })
+                .collect(),
+
+            kind: AnyRowKind::Erased(row),
+        }
+    }
+}
+
+impl From<ErasedQueryResult> for AnyQueryResult {
+    fn from(result: ErasedQueryResult) -> Self {
+        AnyQueryResult {
+            rows_affected: result.rows_affected,
+            last_insert_id: result.last_insert_id,
+        }
+    }
+}
+
+// decodes the values of the rows of registered drivers, for `impl_any_decode!`
+pub(crate) trait DecodeErased<'r>: Sized {
+    fn decode_erased(value: &'r ErasedValue) -> Result<Self, BoxDynError>;
+}
+
+fn unexpected(value: &ErasedValue, expected: &str) -> BoxDynError {
+    if value.is_null() {
+        return UnexpectedNullError.into();
+    }
+
+    format!(
+        "expected a value of type {}, found {}",
+        expected,
+        value.kind()
+    )
+    .into()
+}
+
+impl<'r> DecodeErased<'r> for bool {
+    fn decode_erased(value: &'r ErasedValue) -> Result<Self, BoxDynError> {
+        value.as_bool().ok_or_else(|| unexpected(value, "boolean"))
+    }
+}
+
+impl<'r> DecodeErased<'r> for i64 {
+    fn decode_erased(value: &'r ErasedValue) -> Result<Self, BoxDynError> {
+        value.as_i64().ok_or_else(|| unexpected(value, "integer"))
+    }
+}
+
+impl<'r> DecodeErased<'r> for i32 {
+    fn decode_erased(value: &'r ErasedValue) -> Result<Self, BoxDynError> {
+        Ok(i32::try_from(i64::decode_erased(value)?)?)
+    }
+}
+
+impl<'r> DecodeErased<'r> for f64 {
+    fn decode_erased(value: &'r ErasedValue) -> Result<Self, BoxDynError> {
+        value.as_f64().ok_or_else(|| unexpected(value, "float"))
+    }
+}
+
+impl<'r> DecodeErased<'r> for f32 {
+    fn decode_erased(value: &'r ErasedValue) -> Result<Self, BoxDynError> {
+        f64::decode_erased(value).map(|v| v as f32)
+    }
+}
+
+impl<'r> DecodeErased<'r> for &'r str {
+    fn decode_erased(value: &'r ErasedValue) -> Result<Self, BoxDynError> {
+        value.as_str().ok_or_else(|| unexpected(value, "text"))
+    }
+}
+
+impl<'r> DecodeErased<'r> for String {
+    fn decode_erased(value: &'r ErasedValue) -> Result<Self, BoxDynError> {
+        <&str>::decode_erased(value).map(ToOwned::to_owned)
+    }
+}
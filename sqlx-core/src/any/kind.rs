@@ -1,7 +1,8 @@
+use crate::any::driver::driver_for;
 use crate::error::Error;
 use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AnyKind {
     #[cfg(feature = "postgres")]
     Postgres,
@@ -14,54 +15,51 @@ pub enum AnyKind {
 
     #[cfg(feature = "mssql")]
     Mssql,
+
+    #[cfg(feature = "memory")]
+    Memory,
+
+    /// A driver registered with [`register_driver`](crate::any::register_driver), by the name
+    /// of its database.
+    Registered(&'static str),
 }
 
 impl FromStr for AnyKind {
     type Err = Error;
 
     fn from_str(uri: &str) -> Result<Self, Self::Err> {
-        match uri {
-            #[cfg(feature = "postgres")]
-            _ if uri.starts_with("postgres:") || uri.starts_with("postgresql:") => {
-                Ok(AnyKind::Postgres)
-            }
-
-            #[cfg(not(feature = "postgres"))]
-            _ if uri.starts_with("postgres:") || uri.starts_with("postgresql:") => {
-                Err(Error::Configuration("database URL has the scheme of a PostgreSQL database but the `postgres` feature is not enabled".into()))
-            }
-
-            #[cfg(feature = "mysql")]
-            _ if uri.starts_with("mysql:") || uri.starts_with("mariadb:") => {
-                Ok(AnyKind::MySql)
-            }
-
-            #[cfg(not(feature = "mysql"))]
-            _ if uri.starts_with("mysql:") || uri.starts_with("mariadb:") => {
-                Err(Error::Configuration("database URL has the scheme of a MySQL database but the `mysql` feature is not enabled".into()))
-            }
+        driver_for(uri).map(|driver| driver.kind)
+    }
+}
 
-            #[cfg(feature = "sqlite")]
-            _ if uri.starts_with("sqlite:") => {
-                Ok(AnyKind::Sqlite)
-            }
+// the error for a URL with a scheme no driver is registered for
+pub(crate) fn unrecognized(uri: &str) -> Error {
+    match uri {
+        #[cfg(not(feature = "postgres"))]
+        _ if uri.starts_with("postgres:") || uri.starts_with("postgresql:") => {
+            Error::Configuration("database URL has the scheme of a PostgreSQL database but the `postgres` feature is not enabled".into())
+        }
 
-            #[cfg(not(feature = "sqlite"))]
-            _ if uri.starts_with("sqlite:") => {
-                Err(Error::Configuration("database URL has the scheme of a SQLite database but the `sqlite` feature is not enabled".into()))
-            }
+        #[cfg(not(feature = "mysql"))]
+        _ if uri.starts_with("mysql:") || uri.starts_with("mariadb:") => {
+            Error::Configuration("database URL has the scheme of a MySQL database but the `mysql` feature is not enabled".into())
+        }
 
-            #[cfg(feature = "mssql")]
-            _ if uri.starts_with("mssql:") || uri.starts_with("sqlserver:") => {
-                Ok(AnyKind::Mssql)
-            }
+        #[cfg(not(feature = "sqlite"))]
+        _ if uri.starts_with("sqlite:") => {
+            Error::Configuration("database URL has the scheme of a SQLite database but the `sqlite` feature is not enabled".into())
+        }
 
-            #[cfg(not(feature = "mssql"))]
-            _ if uri.starts_with("mssql:") || uri.starts_with("sqlserver:") => {
-                Err(Error::Configuration("database URL has the scheme of a MSSQL database but the `mssql` feature is not enabled".into()))
-            }
+        #[cfg(not(feature = "mssql"))]
+        _ if uri.starts_with("mssql:") || uri.starts_with("sqlserver:") => {
+            Error::Configuration("database URL has the scheme of a MSSQL database but the `mssql` feature is not enabled".into())
+        }
 
-            _ => Err(Error::Configuration(format!("unrecognized database url: {:?}", uri).into()))
+        #[cfg(not(feature = "memory"))]
+        _ if uri.starts_with("memory:") => {
+            Error::Configuration("database URL has the scheme of the in-memory driver but the `memory` feature is not enabled".into())
         }
+
+        _ => Error::Configuration(format!("unrecognized database url: {:?}", uri).into()),
     }
 }
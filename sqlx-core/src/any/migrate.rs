@@ -4,9 +4,15 @@ use crate::any::{Any, AnyConnection};
 use crate::error::Error;
 use crate::migrate::{Migrate, MigrateDatabase, MigrateError, Migration};
 use futures_core::future::BoxFuture;
+use futures_util::future;
 use std::str::FromStr;
 use std::time::Duration;
 
+// the error of the drivers that cannot run migrations
+fn unsupported(drivers: &str) -> Error {
+    Error::Configuration(format!("migrations are not supported by {}", drivers).into())
+}
+
 impl MigrateDatabase for Any {
     fn create_database(uri: &str) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
@@ -22,6 +28,11 @@ impl MigrateDatabase for Any {
 
                 #[cfg(feature = "mssql")]
                 AnyKind::Mssql => unimplemented!(),
+
+                #[cfg(feature = "memory")]
+                AnyKind::Memory => Err(unsupported("the memory driver")),
+
+                AnyKind::Registered(_) => Err(unsupported("registered drivers")),
             }
         })
     }
@@ -40,6 +51,11 @@ impl MigrateDatabase for Any {
 
                 #[cfg(feature = "mssql")]
                 AnyKind::Mssql => unimplemented!(),
+
+                #[cfg(feature = "memory")]
+                AnyKind::Memory => Err(unsupported("the memory driver")),
+
+                AnyKind::Registered(_) => Err(unsupported("registered drivers")),
             }
         })
     }
@@ -58,6 +74,34 @@ impl MigrateDatabase for Any {
 
                 #[cfg(feature = "mssql")]
                 AnyKind::Mssql => unimplemented!(),
+
+                #[cfg(feature = "memory")]
+                AnyKind::Memory => Err(unsupported("the memory driver")),
+
+                AnyKind::Registered(_) => Err(unsupported("registered drivers")),
+            }
+        })
+    }
+
+    fn force_drop_database(uri: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            match AnyKind::from_str(uri)? {
+                #[cfg(feature = "postgres")]
+                AnyKind::Postgres => crate::postgres::Postgres::force_drop_database(uri).await,
+
+                #[cfg(feature = "sqlite")]
+                AnyKind::Sqlite => crate::sqlite::Sqlite::force_drop_database(uri).await,
+
+                #[cfg(feature = "mysql")]
+                AnyKind::MySql => crate::mysql::MySql::force_drop_database(uri).await,
+
+                #[cfg(feature = "mssql")]
+                AnyKind::Mssql => unimplemented!(),
+
+                #[cfg(feature = "memory")]
+                AnyKind::Memory => Err(unsupported("the memory driver")),
+
+                AnyKind::Registered(_) => Err(unsupported("registered drivers")),
             }
         })
     }
@@ -77,6 +121,15 @@ impl Migrate for AnyConnection {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(_conn) => {
+                Box::pin(future::err(unsupported("the memory driver").into()))
+            }
+
+            AnyConnectionKind::Registered(_conn) => {
+                Box::pin(future::err(unsupported("registered drivers").into()))
+            }
         }
     }
 
@@ -93,6 +146,15 @@ impl Migrate for AnyConnection {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(_conn) => {
+                Box::pin(future::err(unsupported("the memory driver").into()))
+            }
+
+            AnyConnectionKind::Registered(_conn) => {
+                Box::pin(future::err(unsupported("registered drivers").into()))
+            }
         }
     }
 
@@ -109,6 +171,15 @@ impl Migrate for AnyConnection {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(_conn) => {
+                Box::pin(future::err(unsupported("the memory driver").into()))
+            }
+
+            AnyConnectionKind::Registered(_conn) => {
+                Box::pin(future::err(unsupported("registered drivers").into()))
+            }
         }
     }
 
@@ -125,6 +196,15 @@ impl Migrate for AnyConnection {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(_conn) => {
+                Box::pin(future::err(unsupported("the memory driver").into()))
+            }
+
+            AnyConnectionKind::Registered(_conn) => {
+                Box::pin(future::err(unsupported("registered drivers").into()))
+            }
         }
     }
 
@@ -147,6 +227,17 @@ impl Migrate for AnyConnection {
                 let _ = migration;
                 unimplemented!()
             }
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(_conn) => {
+                let _ = migration;
+                Box::pin(future::err(unsupported("the memory driver").into()))
+            }
+
+            AnyConnectionKind::Registered(_conn) => {
+                let _ = migration;
+                Box::pin(future::err(unsupported("registered drivers").into()))
+            }
         }
     }
 
@@ -169,6 +260,17 @@ impl Migrate for AnyConnection {
                 let _ = migration;
                 unimplemented!()
             }
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(_conn) => {
+                let _ = migration;
+                Box::pin(future::err(unsupported("the memory driver").into()))
+            }
+
+            AnyConnectionKind::Registered(_conn) => {
+                let _ = migration;
+                Box::pin(future::err(unsupported("registered drivers").into()))
+            }
         }
     }
 
@@ -188,6 +290,15 @@ impl Migrate for AnyConnection {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(_conn) => {
+                Box::pin(future::err(unsupported("the memory driver").into()))
+            }
+
+            AnyConnectionKind::Registered(_conn) => {
+                Box::pin(future::err(unsupported("registered drivers").into()))
+            }
         }
     }
 }
@@ -13,6 +13,8 @@ mod arguments;
 pub(crate) mod column;
 mod connection;
 mod database;
+mod driver;
+mod erased;
 mod kind;
 mod options;
 mod query_result;
@@ -31,6 +33,7 @@ pub use column::{AnyColumn, AnyColumnIndex};
 pub use connection::AnyConnection;
 pub use database::Any;
 pub use decode::AnyDecode;
+pub use driver::{register_driver, AnyDriverConnection, AnyDriverVtable};
 pub use encode::AnyEncode;
 pub use kind::AnyKind;
 pub use options::AnyConnectOptions;
@@ -56,3 +59,6 @@ impl_into_maybe_pool!(Any, AnyConnection);
 
 // required because some databases have a different handling of NULL
 impl_encode_for_option!(Any);
+
+#[cfg(test)]
+assert_driver_futures_are_send!(Any, AnyConnection);
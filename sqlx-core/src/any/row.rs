@@ -1,6 +1,8 @@
+use crate::any::value::{AnyValueRef, AnyValueRefKind};
 use crate::any::{Any, AnyColumn, AnyColumnIndex};
-use crate::column::ColumnIndex;
+use crate::column::{ColumnIndex, ColumnNameMatching};
 use crate::database::HasValueRef;
+use crate::erased::{ErasedColumnIndex, ErasedRow};
 use crate::error::Error;
 use crate::row::Row;
 
@@ -16,12 +18,36 @@ use crate::sqlite::SqliteRow;
 #[cfg(feature = "mssql")]
 use crate::mssql::MssqlRow;
 
+#[cfg(feature = "memory")]
+use crate::memory::MemoryRow;
+
 pub struct AnyRow {
     pub(crate) kind: AnyRowKind,
     pub(crate) columns: Vec<AnyColumn>,
 }
 
-impl crate::row::private_row::Sealed for AnyRow {}
+impl crate::row::private_row::Sealed for AnyRow {
+    fn column_name_matching(&self) -> ColumnNameMatching {
+        match &self.kind {
+            #[cfg(feature = "postgres")]
+            AnyRowKind::Postgres(row) => row.column_name_matching(),
+
+            #[cfg(feature = "mysql")]
+            AnyRowKind::MySql(row) => row.column_name_matching(),
+
+            #[cfg(feature = "sqlite")]
+            AnyRowKind::Sqlite(row) => row.column_name_matching(),
+
+            #[cfg(feature = "mssql")]
+            AnyRowKind::Mssql(row) => row.column_name_matching(),
+
+            #[cfg(feature = "memory")]
+            AnyRowKind::Memory(row) => row.column_name_matching(),
+
+            AnyRowKind::Erased(_) => ColumnNameMatching::Exact,
+        }
+    }
+}
 
 pub(crate) enum AnyRowKind {
     #[cfg(feature = "postgres")]
@@ -35,6 +61,11 @@ pub(crate) enum AnyRowKind {
 
     #[cfg(feature = "mssql")]
     Mssql(MssqlRow),
+
+    #[cfg(feature = "memory")]
+    Memory(MemoryRow),
+
+    Erased(ErasedRow),
 }
 
 impl Row for AnyRow {
@@ -65,6 +96,14 @@ impl Row for AnyRow {
 
             #[cfg(feature = "mssql")]
             AnyRowKind::Mssql(row) => row.try_get_raw(index).map(Into::into),
+
+            #[cfg(feature = "memory")]
+            AnyRowKind::Memory(row) => row.try_get_raw(index).map(Into::into),
+
+            AnyRowKind::Erased(row) => Ok(AnyValueRef {
+                kind: AnyValueRefKind::Erased(&row.values()[index]),
+                type_info: self.columns[index].type_info.clone(),
+            }),
         }
     }
 }
@@ -76,16 +115,21 @@ where
     fn index(&self, row: &AnyRow) -> Result<usize, Error> {
         match &row.kind {
             #[cfg(feature = "postgres")]
-            AnyRowKind::Postgres(row) => self.index(row),
+            AnyRowKind::Postgres(row) => ColumnIndex::index(self, row),
 
             #[cfg(feature = "mysql")]
-            AnyRowKind::MySql(row) => self.index(row),
+            AnyRowKind::MySql(row) => ColumnIndex::index(self, row),
 
             #[cfg(feature = "sqlite")]
-            AnyRowKind::Sqlite(row) => self.index(row),
+            AnyRowKind::Sqlite(row) => ColumnIndex::index(self, row),
 
             #[cfg(feature = "mssql")]
-            AnyRowKind::Mssql(row) => self.index(row),
+            AnyRowKind::Mssql(row) => ColumnIndex::index(self, row),
+
+            #[cfg(feature = "memory")]
+            AnyRowKind::Memory(row) => ColumnIndex::index(self, row),
+
+            AnyRowKind::Erased(row) => ErasedColumnIndex::index(self, row),
         }
     }
 }
@@ -32,6 +32,13 @@ impl TransactionManager for AnyTransactionManager {
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::begin(conn)
             }
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(conn) => {
+                <crate::memory::Memory as Database>::TransactionManager::begin(conn)
+            }
+
+            AnyConnectionKind::Registered(conn) => conn.begin(),
         }
     }
 
@@ -56,6 +63,13 @@ impl TransactionManager for AnyTransactionManager {
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::commit(conn)
             }
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(conn) => {
+                <crate::memory::Memory as Database>::TransactionManager::commit(conn)
+            }
+
+            AnyConnectionKind::Registered(conn) => conn.commit(),
         }
     }
 
@@ -80,6 +94,13 @@ impl TransactionManager for AnyTransactionManager {
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::rollback(conn)
             }
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(conn) => {
+                <crate::memory::Memory as Database>::TransactionManager::rollback(conn)
+            }
+
+            AnyConnectionKind::Registered(conn) => conn.rollback(),
         }
     }
 
@@ -104,6 +125,13 @@ impl TransactionManager for AnyTransactionManager {
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::start_rollback(conn)
             }
+
+            #[cfg(feature = "memory")]
+            AnyConnectionKind::Memory(conn) => {
+                <crate::memory::Memory as Database>::TransactionManager::start_rollback(conn)
+            }
+
+            AnyConnectionKind::Registered(conn) => conn.start_rollback(),
         }
     }
 }
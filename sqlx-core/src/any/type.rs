@@ -33,6 +33,14 @@ macro_rules! impl_any_type {
                     crate::any::type_info::AnyTypeInfoKind::Mssql(ty) => {
                         <$ty as crate::types::Type<crate::mssql::Mssql>>::compatible(&ty)
                     }
+
+                    #[cfg(feature = "memory")]
+                    crate::any::type_info::AnyTypeInfoKind::Memory(ty) => {
+                        <$ty as crate::types::Type<crate::memory::Memory>>::compatible(&ty)
+                    }
+
+                    // the kind of the value is checked when it is decoded
+                    crate::any::type_info::AnyTypeInfoKind::Erased(_) => true,
                 }
             }
         }
@@ -1,5 +1,6 @@
 use std::fmt::{self, Display, Formatter};
 
+use crate::erased::ErasedValue;
 use crate::type_info::TypeInfo;
 
 #[cfg(feature = "postgres")]
@@ -14,6 +15,9 @@ use crate::sqlite::SqliteTypeInfo;
 #[cfg(feature = "mssql")]
 use crate::mssql::MssqlTypeInfo;
 
+#[cfg(feature = "memory")]
+use crate::memory::MemoryTypeInfo;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct AnyTypeInfo(pub(crate) AnyTypeInfoKind);
 
@@ -30,6 +34,12 @@ pub(crate) enum AnyTypeInfoKind {
 
     #[cfg(feature = "mssql")]
     Mssql(MssqlTypeInfo),
+
+    #[cfg(feature = "memory")]
+    Memory(MemoryTypeInfo),
+
+    // the `ErasedValue::kind` of the values of registered drivers
+    Erased(&'static str),
 }
 
 impl TypeInfo for AnyTypeInfo {
@@ -46,6 +56,11 @@ impl TypeInfo for AnyTypeInfo {
 
             #[cfg(feature = "mssql")]
             AnyTypeInfoKind::Mssql(ty) => ty.is_null(),
+
+            #[cfg(feature = "memory")]
+            AnyTypeInfoKind::Memory(ty) => ty.is_null(),
+
+            AnyTypeInfoKind::Erased(kind) => *kind == ErasedValue::Null.kind(),
         }
     }
 
@@ -62,6 +77,11 @@ impl TypeInfo for AnyTypeInfo {
 
             #[cfg(feature = "mssql")]
             AnyTypeInfoKind::Mssql(ty) => ty.name(),
+
+            #[cfg(feature = "memory")]
+            AnyTypeInfoKind::Memory(ty) => ty.name(),
+
+            AnyTypeInfoKind::Erased(kind) => kind,
         }
     }
 }
@@ -80,6 +100,11 @@ impl Display for AnyTypeInfo {
 
             #[cfg(feature = "mssql")]
             AnyTypeInfoKind::Mssql(ty) => ty.fmt(f),
+
+            #[cfg(feature = "memory")]
+            AnyTypeInfoKind::Memory(ty) => ty.fmt(f),
+
+            AnyTypeInfoKind::Erased(kind) => f.write_str(kind),
         }
     }
 }
@@ -1,5 +1,6 @@
 use crate::any::{Any, AnyTypeInfo};
 use crate::database::HasValueRef;
+use crate::erased::ErasedValue;
 use crate::value::{Value, ValueRef};
 use std::borrow::Cow;
 
@@ -15,6 +16,9 @@ use crate::sqlite::{SqliteValue, SqliteValueRef};
 #[cfg(feature = "mssql")]
 use crate::mssql::{MssqlValue, MssqlValueRef};
 
+#[cfg(feature = "memory")]
+use crate::memory::{MemoryValue, MemoryValueRef};
+
 pub struct AnyValue {
     pub(crate) kind: AnyValueKind,
     pub(crate) type_info: AnyTypeInfo,
@@ -32,6 +36,11 @@ pub(crate) enum AnyValueKind {
 
     #[cfg(feature = "mssql")]
     Mssql(MssqlValue),
+
+    #[cfg(feature = "memory")]
+    Memory(MemoryValue),
+
+    Erased(ErasedValue),
 }
 
 pub struct AnyValueRef<'r> {
@@ -51,6 +60,11 @@ pub(crate) enum AnyValueRefKind<'r> {
 
     #[cfg(feature = "mssql")]
     Mssql(MssqlValueRef<'r>),
+
+    #[cfg(feature = "memory")]
+    Memory(MemoryValueRef<'r>),
+
+    Erased(&'r ErasedValue),
 }
 
 impl Value for AnyValue {
@@ -69,6 +83,14 @@ impl Value for AnyValue {
 
             #[cfg(feature = "mssql")]
             AnyValueKind::Mssql(value) => value.as_ref().into(),
+
+            #[cfg(feature = "memory")]
+            AnyValueKind::Memory(value) => value.as_ref().into(),
+
+            AnyValueKind::Erased(value) => AnyValueRef {
+                kind: AnyValueRefKind::Erased(value),
+                type_info: self.type_info.clone(),
+            },
         }
     }
 
@@ -89,6 +111,11 @@ impl Value for AnyValue {
 
             #[cfg(feature = "mssql")]
             AnyValueKind::Mssql(value) => value.is_null(),
+
+            #[cfg(feature = "memory")]
+            AnyValueKind::Memory(value) => value.is_null(),
+
+            AnyValueKind::Erased(value) => value.is_null(),
         }
     }
 }
@@ -109,6 +136,14 @@ impl<'r> ValueRef<'r> for AnyValueRef<'r> {
 
             #[cfg(feature = "mssql")]
             AnyValueRefKind::Mssql(value) => ValueRef::to_owned(value).into(),
+
+            #[cfg(feature = "memory")]
+            AnyValueRefKind::Memory(value) => ValueRef::to_owned(value).into(),
+
+            AnyValueRefKind::Erased(value) => AnyValue {
+                kind: AnyValueKind::Erased((*value).clone()),
+                type_info: self.type_info.clone(),
+            },
         }
     }
 
@@ -129,6 +164,11 @@ impl<'r> ValueRef<'r> for AnyValueRef<'r> {
 
             #[cfg(feature = "mssql")]
             AnyValueRefKind::Mssql(value) => value.is_null(),
+
+            #[cfg(feature = "memory")]
+            AnyValueRefKind::Memory(value) => value.is_null(),
+
+            AnyValueRefKind::Erased(value) => value.is_null(),
         }
     }
 }
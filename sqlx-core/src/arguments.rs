@@ -1,7 +1,7 @@
 //! Types and traits for passing arguments to SQL queries.
 
 use crate::database::{Database, HasArguments};
-use crate::encode::Encode;
+use crate::encode::{DynEncode, Encode};
 use crate::types::Type;
 
 /// A tuple of arguments to be sent to the database.
@@ -16,6 +16,25 @@ pub trait Arguments<'q>: Send + Sized + Default {
     fn add<T>(&mut self, value: T)
     where
         T: 'q + Send + Encode<'q, Self::Database> + Type<Self::Database>;
+
+    /// Add a value whose type is only known at runtime to the end of the arguments.
+    fn add_dyn(&mut self, value: Box<dyn DynEncode<'q, Self::Database> + 'q>);
+
+    /// The number of values added, including any that could not be encoded.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the encoded values and their types to `key`, for the query results cache of a
+    /// pool. Returns `false` if the arguments cannot tell queries apart this way, e.g. because
+    /// a value could not be encoded; the results of the query are then not cached.
+    #[doc(hidden)]
+    fn write_cache_key(&self, key: &mut Vec<u8>) -> bool {
+        let _ = key;
+        false
+    }
 }
 
 pub trait IntoArguments<'q, DB: HasArguments<'q>>: Sized + Send {
@@ -49,4 +68,216 @@ impl<'q, DB: HasArguments<'q>> IntoArguments<'q, DB> for ImmutableArguments<'q,
 }
 
 // TODO: Impl `IntoArguments` for &[&dyn Encode]
-// TODO: Impl `IntoArguments` for (impl Encode, ...) x16
+
+// a tuple of values is bound in order, so `(a, b)` is the same as `.bind(a).bind(b)`;
+// up to tuples of 16 values
+
+macro_rules! impl_into_arguments_for_tuple {
+    ($( ($idx:tt) -> $T:ident; )*) => {
+        impl<'q, DB, $($T,)*> IntoArguments<'q, DB> for ($($T,)*)
+        where
+            DB: Database,
+            $($T: 'q + Send + Encode<'q, DB> + Type<DB>,)*
+        {
+            #[allow(unused_mut, unused_variables)]
+            fn into_arguments(self) -> <DB as HasArguments<'q>>::Arguments {
+                let mut arguments = <DB as HasArguments<'q>>::Arguments::default();
+
+                arguments.reserve(
+                    <[&str]>::len(&[$(stringify!($idx)),*]),
+                    0 $(+ self.$idx.size_hint())*,
+                );
+
+                $(arguments.add(self.$idx);)*
+
+                arguments
+            }
+        }
+    };
+}
+
+impl_into_arguments_for_tuple!();
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+    (13) -> T14;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+    (13) -> T14;
+    (14) -> T15;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+    (13) -> T14;
+    (14) -> T15;
+    (15) -> T16;
+);
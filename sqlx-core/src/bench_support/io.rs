@@ -0,0 +1,57 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use sqlx_rt::{AsyncRead, AsyncWrite};
+
+use crate::net::{PollReadBuf, PollReadOut};
+
+/// A socket that discards everything written to it and is always at EOF when read.
+///
+/// Counts the writes and flushes it receives so benchmarks can check how well
+/// messages are being coalesced.
+#[derive(Debug, Default)]
+pub struct NullSocket {
+    pub writes: usize,
+    pub flushes: usize,
+    pub bytes: usize,
+}
+
+impl AsyncRead for NullSocket {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &mut PollReadBuf<'_>,
+    ) -> Poll<io::Result<PollReadOut>> {
+        Poll::Ready(Ok(Default::default()))
+    }
+}
+
+impl AsyncWrite for NullSocket {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.writes += 1;
+        self.bytes += buf.len();
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.flushes += 1;
+
+        Poll::Ready(Ok(()))
+    }
+
+    #[cfg(any(feature = "_rt-actix", feature = "_rt-tokio"))]
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
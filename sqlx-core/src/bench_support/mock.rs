@@ -0,0 +1,356 @@
+//! A database driver that never touches the network.
+//!
+//! Connecting, pinging and closing complete immediately and every other operation is a no-op,
+//! which makes it possible to measure the overhead of [`Pool`](crate::pool::Pool) on its own.
+
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use either::Either;
+use futures_core::future::BoxFuture;
+use log::LevelFilter;
+
+use crate::arguments::Arguments;
+use crate::column::{Column, ColumnIndex};
+use crate::connection::{BindLogging, ConnectOptions, Connection, ConnectionStats, StatsCollector};
+use crate::database::{Capabilities, Database, HasArguments, HasStatement, HasValueRef};
+use crate::encode::{DynEncode, Encode};
+use crate::error::Error;
+use crate::events::ConnectionEventListener;
+use crate::middleware::QueryMiddleware;
+use crate::row::Row;
+use crate::statement::Statement;
+use crate::transaction::{Transaction, TransactionManager};
+use crate::type_info::TypeInfo;
+use crate::types::Type;
+use crate::value::{Value, ValueRef};
+
+/// The mock database driver.
+#[derive(Debug)]
+pub struct Mock;
+
+impl Database for Mock {
+    type Connection = MockConnection;
+
+    type TransactionManager = MockTransactionManager;
+
+    type Row = MockRow;
+
+    type QueryResult = MockQueryResult;
+
+    type Column = MockColumn;
+
+    type TypeInfo = MockTypeInfo;
+
+    type Value = MockValue;
+
+    const CAPABILITIES: Capabilities = Capabilities::empty();
+
+    const MAX_BIND_PARAMETERS: usize = usize::MAX;
+
+    const MAX_IDENTIFIER_LENGTH: Option<usize> = None;
+}
+
+impl<'r> HasValueRef<'r> for Mock {
+    type Database = Mock;
+
+    type ValueRef = MockValue;
+}
+
+impl HasArguments<'_> for Mock {
+    type Database = Mock;
+
+    type Arguments = MockArguments;
+
+    type ArgumentBuffer = ();
+}
+
+impl<'q> HasStatement<'q> for Mock {
+    type Database = Mock;
+
+    type Statement = MockStatement;
+}
+
+/// Options for a [`MockConnection`]; parsed from any string.
+#[derive(Debug, Clone, Default)]
+pub struct MockConnectOptions;
+
+impl FromStr for MockConnectOptions {
+    type Err = Error;
+
+    fn from_str(_: &str) -> Result<Self, Error> {
+        Ok(MockConnectOptions)
+    }
+}
+
+impl ConnectOptions for MockConnectOptions {
+    type Connection = MockConnection;
+
+    fn connect(&self) -> BoxFuture<'_, Result<MockConnection, Error>> {
+        Box::pin(async {
+            Ok(MockConnection {
+                transaction_depth: 0,
+                stats: StatsCollector::new(),
+            })
+        })
+    }
+
+    fn log_statements(&mut self, _: LevelFilter) -> &mut Self {
+        self
+    }
+
+    fn log_slow_statements(&mut self, _: LevelFilter, _: Duration) -> &mut Self {
+        self
+    }
+
+    fn log_bind_values(&mut self, _: BindLogging) -> &mut Self {
+        self
+    }
+
+    fn query_middleware(&mut self, _: Arc<dyn QueryMiddleware>) -> &mut Self {
+        self
+    }
+
+    fn connection_events(&mut self, _: Arc<dyn ConnectionEventListener>) -> &mut Self {
+        self
+    }
+}
+
+/// A connection to nothing.
+#[derive(Debug)]
+pub struct MockConnection {
+    transaction_depth: usize,
+    stats: StatsCollector,
+}
+
+impl Connection for MockConnection {
+    type Database = Mock;
+
+    type Options = MockConnectOptions;
+
+    fn close(self) -> BoxFuture<'static, Result<(), Error>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn begin(&mut self) -> BoxFuture<'_, Result<Transaction<'_, Mock>, Error>> {
+        Transaction::begin(self)
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        self.stats.snapshot(0, 0)
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn should_flush(&self) -> bool {
+        false
+    }
+}
+
+pub struct MockTransactionManager;
+
+impl TransactionManager for MockTransactionManager {
+    type Database = Mock;
+
+    fn begin(conn: &mut MockConnection) -> BoxFuture<'_, Result<(), Error>> {
+        conn.transaction_depth += 1;
+        Box::pin(async { Ok(()) })
+    }
+
+    fn commit(conn: &mut MockConnection) -> BoxFuture<'_, Result<(), Error>> {
+        conn.transaction_depth -= 1;
+        Box::pin(async { Ok(()) })
+    }
+
+    fn rollback(conn: &mut MockConnection) -> BoxFuture<'_, Result<(), Error>> {
+        conn.transaction_depth -= 1;
+        Box::pin(async { Ok(()) })
+    }
+
+    fn start_rollback(conn: &mut MockConnection) {
+        conn.transaction_depth = conn.transaction_depth.saturating_sub(1);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MockQueryResult;
+
+impl Extend<MockQueryResult> for MockQueryResult {
+    fn extend<T: IntoIterator<Item = MockQueryResult>>(&mut self, _: T) {}
+}
+
+/// The only type known to the mock driver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockTypeInfo;
+
+impl Display for MockTypeInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("MOCK")
+    }
+}
+
+impl TypeInfo for MockTypeInfo {
+    fn is_null(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> &str {
+        "MOCK"
+    }
+}
+
+#[derive(Debug)]
+pub struct MockColumn;
+
+impl crate::column::private_column::Sealed for MockColumn {}
+
+impl Column for MockColumn {
+    type Database = Mock;
+
+    fn ordinal(&self) -> usize {
+        0
+    }
+
+    fn name(&self) -> &str {
+        ""
+    }
+
+    fn type_info(&self) -> &MockTypeInfo {
+        &MockTypeInfo
+    }
+}
+
+/// A row without any columns.
+pub struct MockRow;
+
+impl crate::row::private_row::Sealed for MockRow {}
+
+impl Row for MockRow {
+    type Database = Mock;
+
+    fn columns(&self) -> &[MockColumn] {
+        &[]
+    }
+
+    fn try_get_raw<I>(&self, index: I) -> Result<MockValue, Error>
+    where
+        I: ColumnIndex<Self>,
+    {
+        index.index(self)?;
+
+        Ok(MockValue)
+    }
+}
+
+#[derive(Clone)]
+pub struct MockValue;
+
+impl Value for MockValue {
+    type Database = Mock;
+
+    fn as_ref(&self) -> MockValue {
+        MockValue
+    }
+
+    fn type_info(&self) -> Cow<'_, MockTypeInfo> {
+        Cow::Owned(MockTypeInfo)
+    }
+
+    fn is_null(&self) -> bool {
+        true
+    }
+}
+
+impl ValueRef<'_> for MockValue {
+    type Database = Mock;
+
+    fn to_owned(&self) -> MockValue {
+        MockValue
+    }
+
+    fn type_info(&self) -> Cow<'_, MockTypeInfo> {
+        Cow::Owned(MockTypeInfo)
+    }
+
+    fn is_null(&self) -> bool {
+        true
+    }
+}
+
+/// Counts bound values and discards them.
+#[derive(Default)]
+pub struct MockArguments {
+    pub len: usize,
+}
+
+impl<'q> Arguments<'q> for MockArguments {
+    type Database = Mock;
+
+    fn reserve(&mut self, _: usize, _: usize) {}
+
+    fn add<T>(&mut self, _: T)
+    where
+        T: Encode<'q, Mock> + Type<Mock>,
+    {
+        self.len += 1;
+    }
+
+    fn add_dyn(&mut self, _: Box<dyn DynEncode<'q, Mock> + 'q>) {
+        self.len += 1;
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+pub struct MockStatement;
+
+impl<'q> Statement<'q> for MockStatement {
+    type Database = Mock;
+
+    fn to_owned(&self) -> MockStatement {
+        MockStatement
+    }
+
+    fn sql(&self) -> &str {
+        ""
+    }
+
+    fn parameters(&self) -> Option<Either<&[MockTypeInfo], usize>> {
+        None
+    }
+
+    fn columns(&self) -> &[MockColumn] {
+        &[]
+    }
+
+    impl_statement_query!(MockArguments);
+}
+
+impl_column_index_for_row!(MockRow);
+impl_into_maybe_pool!(Mock, MockConnection);
+
+impl ColumnIndex<MockStatement> for usize {
+    fn index(&self, _: &MockStatement) -> Result<usize, Error> {
+        Err(Error::ColumnIndexOutOfBounds {
+            index: *self,
+            len: 0,
+        })
+    }
+}
+
+/// An alias for [`Pool`][crate::pool::Pool], specialized for the mock driver.
+pub type MockPool = crate::pool::Pool<Mock>;
+
+/// An alias for [`PoolOptions`][crate::pool::PoolOptions], specialized for the mock driver.
+pub type MockPoolOptions = crate::pool::PoolOptions<Mock>;
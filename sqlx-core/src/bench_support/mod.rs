@@ -0,0 +1,14 @@
+//! Fixtures for benchmarking SQLx without a database server.
+//!
+//! Enabled by the `bench-support` feature. Nothing in here is covered by semver.
+
+mod io;
+pub mod mock;
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
+
+pub use io::NullSocket;
+
+/// The cache of prepared statements kept by each connection.
+pub use crate::common::StatementCache;
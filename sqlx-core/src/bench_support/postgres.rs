@@ -0,0 +1,200 @@
+//! Captured PostgreSQL protocol data.
+
+use std::sync::Arc;
+
+use bytes::{BufMut, Bytes};
+
+use crate::bench_support::NullSocket;
+use crate::error::Error;
+use crate::io::{BufStream, Decode};
+use crate::postgres::message::{self, Bind, DataRow, MessageArena, RowDescription};
+use crate::postgres::statement::PgStatementMetadata;
+use crate::postgres::{PgArguments, PgColumn, PgRow, PgTypeInfo, PgValueFormat};
+
+/// The body of a `DataRow` for a 20-column row in the binary format.
+///
+/// The columns repeat `INT4, INT8, FLOAT8, BOOL, TEXT`, as described by [`PgRowFixture`].
+pub const DATA_ROW_20: &[u8] = b"\x00\x14\
+    \x00\x00\x00\x04\x00\x00\x00\x00\
+    \x00\x00\x00\x08\x00\x00\x00\xe8\xd4\xa5\x10\x01\
+    \x00\x00\x00\x08@\x04\x00\x00\x00\x00\x00\x00\
+    \x00\x00\x00\x01\x01\
+    \x00\x00\x00\x11value of column 4\
+    \x00\x00\x00\x04\x00\x00\x13\x88\
+    \x00\x00\x00\x08\x00\x00\x00\xe8\xd4\xa5\x10\x06\
+    \x00\x00\x00\x08@\x1e\x00\x00\x00\x00\x00\x00\
+    \x00\x00\x00\x01\x00\
+    \x00\x00\x00\x11value of column 9\
+    \x00\x00\x00\x04\x00\x00'\x10\
+    \x00\x00\x00\x08\x00\x00\x00\xe8\xd4\xa5\x10\x0b\
+    \x00\x00\x00\x08@)\x00\x00\x00\x00\x00\x00\
+    \x00\x00\x00\x01\x01\
+    \x00\x00\x00\x12value of column 14\
+    \x00\x00\x00\x04\x00\x00:\x98\
+    \x00\x00\x00\x08\x00\x00\x00\xe8\xd4\xa5\x10\x10\
+    \x00\x00\x00\x08@1\x80\x00\x00\x00\x00\x00\
+    \x00\x00\x00\x01\x00\
+    \x00\x00\x00\x12value of column 19";
+
+/// Decodes [`DATA_ROW_20`] the same way a [`PgConnection`](crate::postgres::PgConnection)
+/// would after receiving it.
+pub struct PgRowFixture {
+    metadata: Arc<PgStatementMetadata>,
+}
+
+impl PgRowFixture {
+    pub fn new() -> Self {
+        let types = [
+            PgTypeInfo::INT4,
+            PgTypeInfo::INT8,
+            PgTypeInfo::FLOAT8,
+            PgTypeInfo::BOOL,
+            PgTypeInfo::TEXT,
+        ];
+
+        let mut metadata = PgStatementMetadata::default();
+
+        for ordinal in 0..20 {
+            let name = format!("column_{}", ordinal);
+
+            metadata.column_names.insert(name.clone().into(), ordinal);
+            metadata.columns.push(PgColumn {
+                ordinal,
+                name: name.into(),
+                type_info: types[ordinal % types.len()].clone(),
+                relation_id: None,
+                relation_attribute_no: None,
+            });
+        }
+
+        Self {
+            metadata: Arc::new(metadata),
+        }
+    }
+
+    pub fn decode(&self) -> Result<PgRow, Error> {
+        Ok(PgRow {
+            data: DataRow::decode(Bytes::from_static(DATA_ROW_20))?,
+            format: PgValueFormat::Binary.into(),
+            metadata: Arc::clone(&self.metadata),
+            column_name_matching: Default::default(),
+        })
+    }
+}
+
+impl Default for PgRowFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decodes the `RowDescription` of the columns of [`DATA_ROW_20`] into the arena of a
+/// connection, as it does for every query whose statement was not prepared before.
+pub struct PgDescriptionFixture {
+    message: Bytes,
+    arena: MessageArena,
+}
+
+impl PgDescriptionFixture {
+    pub fn new() -> Self {
+        // INT4, INT8, FLOAT8, BOOL and TEXT, with their sizes
+        let types: [(u32, i16); 5] = [(23, 4), (20, 8), (701, 8), (16, 1), (25, -1)];
+        let mut message = Vec::new();
+
+        message.put_u16(20);
+
+        for ordinal in 0..20 {
+            let (oid, size) = types[ordinal % types.len()];
+
+            message.put_slice(format!("column_{}\0", ordinal).as_bytes());
+            message.put_i32(0);
+            message.put_i16(0);
+            message.put_u32(oid);
+            message.put_i16(size);
+            message.put_i32(-1);
+            message.put_i16(1);
+        }
+
+        Self {
+            message: message.into(),
+            arena: MessageArena::default(),
+        }
+    }
+
+    /// Returns the number of fields decoded.
+    pub fn decode(&mut self) -> Result<usize, Error> {
+        let desc = RowDescription::decode_with(self.message.clone(), &mut self.arena)?;
+
+        Ok(desc.fields.len())
+    }
+}
+
+impl Default for PgDescriptionFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes the messages for executing a prepared statement into a [`NullSocket`].
+pub struct PgWriteFixture {
+    stream: BufStream<NullSocket>,
+}
+
+impl PgWriteFixture {
+    pub fn new() -> Self {
+        Self {
+            stream: BufStream::new(NullSocket::default()),
+        }
+    }
+
+    /// Buffers `Bind`, `Execute` and `Sync` and sends them with a single flush,
+    /// as [`PgConnection`](crate::postgres::PgConnection) does.
+    pub async fn write_coalesced(&mut self, arguments: &PgArguments) -> Result<(), Error> {
+        self.write_bind(arguments);
+        self.stream.write(message::Execute {
+            portal: None,
+            limit: 0,
+        });
+        self.stream.write(message::Sync);
+
+        self.stream.flush().await
+    }
+
+    /// Sends the same messages as [`write_coalesced`](Self::write_coalesced),
+    /// flushing after each one.
+    pub async fn write_each(&mut self, arguments: &PgArguments) -> Result<(), Error> {
+        self.write_bind(arguments);
+        self.stream.flush().await?;
+
+        self.stream.write(message::Execute {
+            portal: None,
+            limit: 0,
+        });
+        self.stream.flush().await?;
+
+        self.stream.write(message::Sync);
+        self.stream.flush().await
+    }
+
+    fn write_bind(&mut self, arguments: &PgArguments) {
+        self.stream.write(Bind {
+            portal: None,
+            statement: 1,
+            formats: &[PgValueFormat::Binary],
+            num_params: arguments.types.len(),
+            params: &*arguments.buffer,
+            result_formats: &[PgValueFormat::Binary],
+        });
+    }
+
+    /// The socket, for inspecting how many writes and flushes were issued.
+    pub fn socket(&self) -> &NullSocket {
+        &self.stream
+    }
+}
+
+impl Default for PgWriteFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
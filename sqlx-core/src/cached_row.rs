@@ -0,0 +1,142 @@
+//! Decoding each column of a row at most once per type; see [`CachedRow`].
+
+use std::any::{Any, TypeId};
+use std::ops::Deref;
+
+use parking_lot::Mutex;
+
+use crate::column::ColumnIndex;
+use crate::decode::Decode;
+use crate::error::Error;
+use crate::row::Row;
+use crate::types::Type;
+
+/// A row that keeps the values decoded from it, so that getting a column again as the same
+/// type clones the value instead of decoding it again.
+///
+/// This helps when a value that is costly to decode, e.g. a JSON document, is read more than
+/// once, as by a [`FromRow`](crate::from_row::FromRow) implementation and by the code that
+/// inspects the row after it. Types for which [`Decode::CHEAP_TO_DECODE`] is set, such as
+/// integers, are decoded each time, as are types that borrow from the row.
+///
+/// Caching is opt-in, with [`Row::cached`] or [`CachedRow::new`]: the first get of a cached
+/// type clones the value it returns into the row, which costs about as much as the decode
+/// itself for a string, and more than it saves when every column is read once.
+///
+/// ```rust,ignore
+/// let row = sqlx::query("SELECT id, document FROM reports")
+///     .fetch_one(&mut conn)
+///     .await?
+///     .cached();
+///
+/// let document: serde_json::Value = row.try_get("document")?;
+/// // a clone of the value above, without parsing the JSON again
+/// let report = Report::from_document(row.try_get("document")?);
+/// ```
+///
+/// Every method of the row itself is available through [`Deref`]; its
+/// [`try_get`](Row::try_get) decodes without the cache.
+pub struct CachedRow<R: Row> {
+    row: R,
+    // a slot per column and type decoded, which is seldom more than a few
+    values: Mutex<Vec<Slot>>,
+}
+
+struct Slot {
+    index: usize,
+    type_id: TypeId,
+    value: Box<dyn Any + Send + Sync>,
+}
+
+impl<R: Row> CachedRow<R>
+where
+    usize: ColumnIndex<R>,
+{
+    /// Wraps `row`, with nothing decoded from it yet.
+    pub fn new(row: R) -> Self {
+        CachedRow {
+            row,
+            values: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the row, dropping the values decoded from it.
+    pub fn into_inner(self) -> R {
+        self.row
+    }
+
+    /// Index into the row and decode a single value, or clone the value decoded from the same
+    /// column as the same type before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the column does not exist or its value cannot be decoded into the requested
+    /// type. See [`try_get`](Self::try_get) for a non-panicking version.
+    pub fn get<T, I>(&self, index: I) -> T
+    where
+        I: ColumnIndex<R>,
+        T: for<'r> Decode<'r, R::Database> + Type<R::Database> + Clone + Send + Sync + 'static,
+    {
+        self.try_get(index).unwrap()
+    }
+
+    /// Index into the row and decode a single value, or clone the value decoded from the same
+    /// column as the same type before.
+    ///
+    /// Fails as [`Row::try_get`] does; a value that fails to decode is not kept, so the next
+    /// get decodes it again.
+    pub fn try_get<T, I>(&self, index: I) -> Result<T, Error>
+    where
+        I: ColumnIndex<R>,
+        T: for<'r> Decode<'r, R::Database> + Type<R::Database> + Clone + Send + Sync + 'static,
+    {
+        if T::CHEAP_TO_DECODE {
+            return self.row.try_get(index);
+        }
+
+        let ordinal = index.index(&self.row)?;
+        let type_id = TypeId::of::<T>();
+
+        if let Some(value) = self.lookup::<T>(ordinal, type_id) {
+            return Ok(value);
+        }
+
+        // not under the lock; should two threads decode the same value at once, both are
+        // kept and the first is found
+        let value: T = self.row.try_get(ordinal)?;
+
+        self.values.lock().push(Slot {
+            index: ordinal,
+            type_id,
+            value: Box::new(value.clone()),
+        });
+
+        Ok(value)
+    }
+
+    fn lookup<T: Clone + 'static>(&self, index: usize, type_id: TypeId) -> Option<T> {
+        self.values
+            .lock()
+            .iter()
+            .find(|slot| slot.index == index && slot.type_id == type_id)
+            .and_then(|slot| slot.value.downcast_ref::<T>())
+            .cloned()
+    }
+}
+
+impl<R: Row> Deref for CachedRow<R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.row
+    }
+}
+
+impl<R: Row> From<R> for CachedRow<R>
+where
+    usize: ColumnIndex<R>,
+{
+    fn from(row: R) -> Self {
+        CachedRow::new(row)
+    }
+}
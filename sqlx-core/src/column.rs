@@ -32,6 +32,11 @@ pub(crate) mod private_column {
 /// This trait is implemented for strings which are used to look up a column by name, and for
 /// `usize` which is used as a positional index into the row.
 ///
+/// Names are looked up in a map built once per statement, so looking up a column by name costs
+/// about the same for every row. If several columns have the same name, the name refers to the
+/// first of them. A name no column has exactly falls back to the looser comparisons of the
+/// [`ColumnNameMatching`] of the connection.
+///
 /// This trait is sealed and cannot be implemented for types outside of SQLx.
 ///
 /// [`Row`]: crate::row::Row
@@ -48,6 +53,109 @@ pub trait ColumnIndex<T: ?Sized>: private_column_index::Sealed + Debug {
     fn index(&self, container: &T) -> Result<usize, Error>;
 }
 
+/// How a name given to [`Row::try_get`] is matched against the names of the columns of a row,
+/// e.g. the name of a field read by `#[derive(FromRow)]`.
+///
+/// Postgres folds unquoted identifiers to lower case, while MySQL and SQLite keep them as they
+/// are written, so the same query has a column `userid` on one and `userId` on the other. A
+/// column with exactly the given name is always taken first; each of the variants after
+/// [`Exact`](Self::Exact) adds a looser comparison, tried only if the ones before it found
+/// nothing.
+///
+/// If the name matches more than one column at the first comparison that finds any, e.g.
+/// `userId` when a row has both `userid` and `USERID`, the lookup fails with
+/// [`Error::ColumnAmbiguous`] naming them, rather than picking one.
+///
+/// Set for each connection with the `column_name_matching` option of the connect options of
+/// Postgres, MySQL and SQLite; the default is [`Exact`](Self::Exact), so the looser
+/// comparisons are only tried where they are asked for. Rows of other databases only match
+/// exactly.
+///
+/// [`Row::try_get`]: crate::row::Row::try_get
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ColumnNameMatching {
+    /// Only a column with exactly the given name.
+    Exact,
+
+    /// Then a column whose name differs only in ASCII case, e.g. `userId` finds `userid`.
+    CaseInsensitive,
+
+    /// Then a column whose name differs only in ASCII case and underscores, e.g. `userId`
+    /// finds `user_id` or `USER_ID`.
+    SnakeCaseInsensitive,
+}
+
+impl Default for ColumnNameMatching {
+    fn default() -> Self {
+        ColumnNameMatching::Exact
+    }
+}
+
+// the looser comparisons of `ColumnNameMatching`, in the order they are tried: two names
+// match if they fold to the same string
+const FOLDS: [fn(&str) -> String; 2] = [fold_case, fold_case_and_underscores];
+
+fn fold_case(name: &str) -> String {
+    name.to_ascii_lowercase()
+}
+
+fn fold_case_and_underscores(name: &str) -> String {
+    name.replace('_', "").to_ascii_lowercase()
+}
+
+impl ColumnNameMatching {
+    // the position of the column `name` refers to, given the position of the first column with
+    // exactly that name, if any, and the names of all the columns in order
+    pub(crate) fn find<'c>(
+        self,
+        name: &str,
+        exact: Option<usize>,
+        columns: impl Iterator<Item = &'c str>,
+    ) -> Result<usize, Error> {
+        if let Some(index) = exact {
+            return Ok(index);
+        }
+
+        let columns: Vec<&str> = columns.collect();
+
+        let looser = match self {
+            ColumnNameMatching::Exact => 0,
+            ColumnNameMatching::CaseInsensitive => 1,
+            ColumnNameMatching::SnakeCaseInsensitive => 2,
+        };
+
+        for fold in &FOLDS[..looser] {
+            let folded = fold(name);
+
+            // columns with the same name are one candidate, which refers to the first of them
+            let mut candidates: Vec<(usize, &str)> = Vec::new();
+
+            for (index, column) in columns.iter().enumerate() {
+                if fold(column) == folded && candidates.iter().all(|(_, seen)| seen != column) {
+                    candidates.push((index, *column));
+                }
+            }
+
+            match &*candidates {
+                [] => continue,
+                [(index, _)] => return Ok(*index),
+                _ => {
+                    return Err(Error::ColumnAmbiguous {
+                        name: name.to_owned(),
+                        candidates: candidates
+                            .iter()
+                            .map(|(_, column)| (*column).to_owned())
+                            .collect(),
+                    })
+                }
+            }
+        }
+
+        Err(Error::ColumnNotFound(name.to_owned()))
+    }
+}
+
 impl<T: ?Sized, I: ColumnIndex<T> + ?Sized> ColumnIndex<T> for &'_ I {
     #[inline]
     fn index(&self, row: &T) -> Result<usize, Error> {
@@ -95,3 +203,44 @@ mod private_column_index {
     impl Sealed for str {}
     impl<T> Sealed for &'_ T where T: Sealed + ?Sized {}
 }
+
+#[test]
+fn it_falls_back_to_looser_column_names() {
+    let find = |matching: ColumnNameMatching, name: &str, columns: &[&str]| {
+        let exact = columns.iter().position(|column| *column == name);
+
+        matching.find(name, exact, columns.iter().copied())
+    };
+
+    let columns = ["id", "userid", "created_at", "userid"];
+    let matching = ColumnNameMatching::SnakeCaseInsensitive;
+
+    assert_eq!(find(matching, "userid", &columns).unwrap(), 1);
+    assert_eq!(find(matching, "userId", &columns).unwrap(), 1);
+    assert_eq!(find(matching, "createdAt", &columns).unwrap(), 2);
+    assert_eq!(find(matching, "CREATED_AT", &columns).unwrap(), 2);
+
+    assert!(matches!(
+        find(ColumnNameMatching::CaseInsensitive, "createdAt", &columns),
+        Err(Error::ColumnNotFound(name)) if name == "createdAt"
+    ));
+    assert!(matches!(
+        find(ColumnNameMatching::Exact, "userId", &columns),
+        Err(Error::ColumnNotFound(_))
+    ));
+
+    // an exact match wins over looser ones, and a case-insensitive one over one ignoring
+    // underscores
+    let columns = ["user_id", "userId", "USERID"];
+    assert_eq!(find(matching, "userId", &columns).unwrap(), 1);
+    assert_eq!(find(matching, "USER_ID", &columns).unwrap(), 0);
+
+    match find(matching, "UserId", &columns) {
+        Err(Error::ColumnAmbiguous { name, candidates }) => {
+            assert_eq!(name, "UserId");
+            assert_eq!(candidates, ["userId", "USERID"]);
+        }
+
+        other => panic!("expected an ambiguous column, got {:?}", other),
+    }
+}
@@ -0,0 +1,224 @@
+//! Reading a result into one vector per column, for analytical processing.
+//!
+//! See [`Query::fetch_columnar`](crate::query::Query::fetch_columnar).
+
+use std::cmp;
+use std::mem;
+
+use futures_core::stream::BoxStream;
+use futures_util::TryStreamExt;
+
+use crate::decode::Decode;
+use crate::error::Error;
+use crate::from_row::{validate_columns, ExpectedColumn};
+use crate::row::Row;
+use crate::types::Type;
+
+/// A tuple of the types of the columns of a result, each of which is collected into a [`Vec`]
+/// by [`Query::fetch_columnar`](crate::query::Query::fetch_columnar).
+///
+/// Implemented for tuples of up to 12 types that can be decoded without borrowing from the
+/// row, e.g. `(i64, f64, Option<String>)`, whose columns are `(Vec<i64>, Vec<f64>,
+/// Vec<Option<String>>)`.
+pub trait Columnar<R: Row> {
+    /// The vectors the values of each column are collected into.
+    type Columns: Default + Send;
+
+    /// The columns of the result, by position, and the type each is read into.
+    fn expected_columns() -> Vec<ExpectedColumn<R::Database>>;
+
+    /// Appends the values of `row` to `columns`, once the columns of the result have been
+    /// checked against [`expected_columns`](Self::expected_columns).
+    fn push(row: &R, columns: &mut Self::Columns) -> Result<(), Error>;
+}
+
+/// Collects `rows` into chunks of at most `chunk_rows` rows, checking the columns of the first
+/// row before anything is decoded.
+pub(crate) fn fetch_chunks<'e, R, T>(
+    rows: BoxStream<'e, Result<R, Error>>,
+    chunk_rows: usize,
+) -> BoxStream<'e, Result<T::Columns, Error>>
+where
+    R: Row,
+    T: 'e + Columnar<R>,
+    T::Columns: 'e,
+{
+    let chunk_rows = cmp::max(chunk_rows, 1);
+
+    Box::pin(try_stream! {
+        let expected = T::expected_columns();
+        let mut rows = rows;
+        let mut columns = T::Columns::default();
+        let mut len = 0;
+
+        while let Some(row) = rows.try_next().await? {
+            if len == 0 {
+                validate(&row, &expected)?;
+            }
+
+            T::push(&row, &mut columns)?;
+            len += 1;
+
+            if len % chunk_rows == 0 {
+                r#yield!(mem::take(&mut columns));
+            }
+        }
+
+        if len % chunk_rows != 0 {
+            r#yield!(columns);
+        }
+
+        Ok(())
+    })
+}
+
+fn validate<R: Row>(row: &R, expected: &[ExpectedColumn<R::Database>]) -> Result<(), Error> {
+    // missing columns are reported with the mismatched types
+    if row.len() > expected.len() {
+        return Err(Error::decode(format!(
+            "the result has {} columns, but {} are read",
+            row.len(),
+            expected.len()
+        )));
+    }
+
+    validate_columns(row, expected)
+}
+
+macro_rules! impl_columnar_for_tuple {
+    ($( ($idx:tt) -> $T:ident );+;) => {
+        impl<R, $($T,)+> Columnar<R> for ($($T,)+)
+        where
+            R: Row,
+            usize: crate::column::ColumnIndex<R>,
+            $($T: for<'r> Decode<'r, R::Database> + Type<R::Database> + Send,)+
+        {
+            type Columns = ($(Vec<$T>,)+);
+
+            fn expected_columns() -> Vec<ExpectedColumn<R::Database>> {
+                vec![$(ExpectedColumn::indexed::<$T>($idx),)+]
+            }
+
+            #[inline]
+            fn push(row: &R, columns: &mut Self::Columns) -> Result<(), Error> {
+                // the types were checked against the first row of the result
+                $(columns.$idx.push(row.try_get_unchecked($idx as usize)?);)+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+);
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+);
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+);
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+);
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+);
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+);
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+);
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+);
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+);
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+);
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+);
+
+impl_columnar_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+);
@@ -0,0 +1,71 @@
+use std::borrow::Cow;
+use std::cmp;
+
+use once_cell::sync::Lazy;
+
+// the file name of the running executable (without extension), looked up once per process
+static DEFAULT_APPLICATION_NAME: Lazy<Option<String>> = Lazy::new(|| {
+    let exe = std::env::current_exe().ok()?;
+    let name = exe.file_stem()?.to_string_lossy();
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.into_owned())
+    }
+});
+
+/// The name reported to the server when the application has not set one: the name of the
+/// binary that is running, e.g. `myservice` for `target/release/myservice`.
+pub(crate) fn default_application_name() -> Option<&'static str> {
+    DEFAULT_APPLICATION_NAME.as_deref()
+}
+
+/// Makes `name` safe to send during the handshake.
+///
+/// Control characters (and every non-ASCII character if `ascii_only` is set) are replaced
+/// with `?`, and the result is truncated to at most `max_len` bytes on a character boundary.
+pub(crate) fn sanitize_application_name(
+    name: &str,
+    max_len: usize,
+    ascii_only: bool,
+) -> Cow<'_, str> {
+    let is_allowed = |c: char| !c.is_control() && (!ascii_only || c.is_ascii());
+
+    if name.len() <= max_len && name.chars().all(is_allowed) {
+        return Cow::Borrowed(name);
+    }
+
+    let mut sanitized = String::with_capacity(cmp::min(max_len, name.len()));
+
+    for c in name.chars() {
+        let c = if is_allowed(c) { c } else { '?' };
+
+        if sanitized.len() + c.len_utf8() > max_len {
+            break;
+        }
+
+        sanitized.push(c);
+    }
+
+    Cow::Owned(sanitized)
+}
+
+#[test]
+fn it_leaves_valid_names_alone() {
+    assert!(matches!(
+        sanitize_application_name("myservice-pool-3", 63, true),
+        Cow::Borrowed("myservice-pool-3")
+    ));
+}
+
+#[test]
+fn it_sanitizes_application_names() {
+    assert_eq!(sanitize_application_name("a\0b\nc", 63, true), "a?b?c");
+    assert_eq!(sanitize_application_name("café", 63, true), "caf?");
+    assert_eq!(sanitize_application_name("café", 63, false), "café");
+    assert_eq!(sanitize_application_name("abcdef", 4, true), "abcd");
+
+    // never split a multi-byte character
+    assert_eq!(sanitize_application_name("aéé", 4, false), "aé");
+}
@@ -0,0 +1,115 @@
+// shared pieces of the `serde` implementations for connect and pool options
+
+use std::time::Duration;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::Serializer;
+
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+use {
+    crate::error::Error,
+    crate::net::CertificateInput,
+    serde::de::{value::MapAccessDeserializer, MapAccess, Visitor},
+    std::fmt,
+    std::str::FromStr,
+};
+
+/// Deserializes connect options from either a URL, which is parsed with `FromStr`, or a map,
+/// which is deserialized as `M` and converted with `from_map`.
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) fn deserialize_url_or_map<'de, D, T, M>(
+    deserializer: D,
+    from_map: fn(M) -> T,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = Error>,
+    M: Deserialize<'de>,
+{
+    struct UrlOrMap<T, M>(fn(M) -> T);
+
+    impl<'de, T, M> Visitor<'de> for UrlOrMap<T, M>
+    where
+        T: FromStr<Err = Error>,
+        M: Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a connection URL or a map of connect options")
+        }
+
+        fn visit_str<E: de::Error>(self, url: &str) -> Result<T, E> {
+            url.parse().map_err(E::custom)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<T, A::Error> {
+            M::deserialize(MapAccessDeserializer::new(map)).map(self.0)
+        }
+    }
+
+    deserializer.deserialize_any(UrlOrMap(from_map))
+}
+
+/// Deserializes a value with `FromStr`, e.g. an SSL mode.
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = Error>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| s.parse().map_err(de::Error::custom))
+        .transpose()
+}
+
+/// Durations are written the way `humantime` formats them, e.g. `"30s"` or `"1h 30m"`.
+pub(crate) mod duration {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        duration: &Duration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&humantime::format_duration(*duration))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| humantime::parse_duration(&s).map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+/// Like [`duration`], for settings which `null` turns off; a missing setting keeps its default.
+pub(crate) mod optional_duration {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => super::duration::serialize(duration, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Option<Duration>>, D::Error> {
+        super::duration::deserialize(deserializer).map(Some)
+    }
+}
+
+/// Certificates are written as the path of their file, or as the PEM itself, which is how
+/// `CertificateInput::from` tells them apart again.
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) fn certificate_to_string(certificate: &CertificateInput) -> String {
+    match certificate {
+        CertificateInput::Inline(pem) => String::from_utf8_lossy(pem).into_owned(),
+        CertificateInput::File(path) => path.display().to_string(),
+    }
+}
@@ -0,0 +1,279 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_core::future::BoxFuture;
+use futures_core::stream::{BoxStream, Stream};
+use parking_lot::Mutex;
+
+use crate::error::Error;
+
+// Tracks the operation in progress on a connection, so that another is not started while its
+// messages are still being exchanged.
+//
+// An operation borrows its connection mutably, so one can only be started while another is in
+// progress if the other was dropped or leaked part way through, or is used through an alias
+// created with unsafe code. A dropped operation is over: the connection resynchronizes before
+// its next command. One that was leaked or is aliased is not, and the two would interleave.
+//
+// Operations started while another is being polled are a part of it, e.g. looking up a type
+// while preparing a statement.
+//
+// A panic that unwinds through the poll of an operation may leave a response half read, so the
+// connection is broken from then on: every operation fails, and the pool closes it.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct InFlight {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    // the operation in progress, if any
+    operation: Option<&'static str>,
+
+    // whether that operation is being polled
+    polling: bool,
+
+    // whether a panic unwound through the poll of an operation
+    poisoned: bool,
+}
+
+impl InFlight {
+    // whether an operation is in progress, which can only be the case outside of it if it was
+    // leaked or is aliased
+    pub(crate) fn is_busy(&self) -> bool {
+        self.state.lock().operation.is_some()
+    }
+
+    // whether a panic unwound through an operation, which leaves the connection unusable
+    pub(crate) fn is_poisoned(&self) -> bool {
+        self.state.lock().poisoned
+    }
+
+    pub(crate) fn watch<'a, T: 'a>(
+        &self,
+        operation: &'static str,
+        future: BoxFuture<'a, Result<T, Error>>,
+    ) -> BoxFuture<'a, Result<T, Error>> {
+        Box::pin(Watched::new(self, operation, future))
+    }
+
+    pub(crate) fn watch_stream<'a, T: 'a>(
+        &self,
+        operation: &'static str,
+        stream: BoxStream<'a, Result<T, Error>>,
+    ) -> BoxStream<'a, Result<T, Error>> {
+        Box::pin(Watched::new(self, operation, stream))
+    }
+}
+
+struct Watched<T> {
+    inner: T,
+    state: Arc<Mutex<State>>,
+    operation: &'static str,
+    role: Role,
+}
+
+#[derive(PartialEq)]
+enum Role {
+    NotStarted,
+
+    // started the operation in progress, and ends it when done or dropped
+    Owner,
+
+    // part of another operation
+    Nested,
+
+    Done,
+}
+
+impl<T> Watched<T> {
+    fn new(in_flight: &InFlight, operation: &'static str, inner: T) -> Self {
+        Self {
+            inner,
+            state: Arc::clone(&in_flight.state),
+            operation,
+            role: Role::NotStarted,
+        }
+    }
+
+    // polls the inner future or stream, with the operation marked as being polled; fails if
+    // the operation cannot be started because another is in progress
+    fn poll_inner<R>(&mut self, poll: impl FnOnce(&mut T) -> R) -> Result<R, Error> {
+        let was_polling = {
+            let mut state = self.state.lock();
+
+            if state.poisoned {
+                self.role = Role::Done;
+                return Err(err_protocol!(
+                    "connection is broken: a panic unwound through one of its operations"
+                ));
+            }
+
+            if self.role == Role::NotStarted {
+                self.role = match state.operation {
+                    None => {
+                        state.operation = Some(self.operation);
+                        Role::Owner
+                    }
+
+                    Some(_) if state.polling => Role::Nested,
+
+                    Some(operation) => {
+                        self.role = Role::Done;
+                        return Err(Error::ConnectionBusy { operation });
+                    }
+                };
+            }
+
+            std::mem::replace(&mut state.polling, true)
+        };
+
+        // restored even if the poll panics
+        let _restore = RestorePolling {
+            state: &self.state,
+            was_polling,
+        };
+
+        Ok(poll(&mut self.inner))
+    }
+
+    fn end(&mut self) {
+        if self.role == Role::Owner {
+            self.state.lock().operation = None;
+        }
+
+        self.role = Role::Done;
+    }
+}
+
+struct RestorePolling<'a> {
+    state: &'a Mutex<State>,
+    was_polling: bool,
+}
+
+impl Drop for RestorePolling<'_> {
+    fn drop(&mut self) {
+        let mut state = self.state.lock();
+
+        state.polling = self.was_polling;
+        state.poisoned |= std::thread::panicking();
+    }
+}
+
+impl<T> Drop for Watched<T> {
+    fn drop(&mut self) {
+        // dropping an operation part way through ends it; the connection catches up with the
+        // server before the next command
+        self.end();
+    }
+}
+
+impl<F, T> Future for Watched<F>
+where
+    F: Future<Output = Result<T, Error>> + Unpin,
+{
+    type Output = Result<T, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let poll = match self.poll_inner(|inner| Pin::new(inner).poll(cx)) {
+            Ok(poll) => poll,
+            Err(error) => return Poll::Ready(Err(error)),
+        };
+
+        if poll.is_ready() {
+            self.end();
+        }
+
+        poll
+    }
+}
+
+impl<S, T> Stream for Watched<S>
+where
+    S: Stream<Item = Result<T, Error>> + Unpin,
+{
+    type Item = Result<T, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.role == Role::Done {
+            return Poll::Ready(None);
+        }
+
+        let poll = match self.poll_inner(|inner| Pin::new(inner).poll_next(cx)) {
+            Ok(poll) => poll,
+            Err(error) => return Poll::Ready(Some(Err(error))),
+        };
+
+        if let Poll::Ready(None) = poll {
+            self.end();
+        }
+
+        poll
+    }
+}
+
+#[cfg(test)]
+use futures_util::future::{self, FutureExt};
+
+#[test]
+fn it_rejects_an_operation_started_while_another_is_leaked() {
+    let in_flight = InFlight::default();
+
+    let mut leaked = in_flight.watch("fetch_many", future::pending::<Result<(), Error>>().boxed());
+    assert!((&mut leaked).now_or_never().is_none());
+
+    let res = in_flight
+        .watch("ping", future::ok(()).boxed())
+        .now_or_never();
+    assert!(matches!(
+        res,
+        Some(Err(Error::ConnectionBusy {
+            operation: "fetch_many"
+        }))
+    ));
+
+    // dropping the operation ends it
+    drop(leaked);
+
+    let res = in_flight
+        .watch("ping", future::ok(()).boxed())
+        .now_or_never();
+    assert!(matches!(res, Some(Ok(()))));
+}
+
+#[test]
+fn it_accepts_operations_started_while_another_is_polled() {
+    let in_flight = InFlight::default();
+    let nested = in_flight.clone();
+
+    let prepare = async move { nested.watch("fetch_optional", future::ok(1).boxed()).await };
+    let res = in_flight.watch("prepare", prepare.boxed()).now_or_never();
+    assert!(matches!(res, Some(Ok(1))));
+
+    let res = in_flight
+        .watch("ping", future::ok(()).boxed())
+        .now_or_never();
+    assert!(matches!(res, Some(Ok(()))));
+}
+
+#[test]
+fn it_breaks_the_connection_when_a_poll_panics() {
+    let in_flight = InFlight::default();
+
+    let panicking = in_flight.watch(
+        "fetch_many",
+        future::poll_fn(|_| -> Poll<Result<(), Error>> { panic!("in the middle of a response") })
+            .boxed(),
+    );
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| panicking.now_or_never()));
+    assert!(res.is_err());
+    assert!(in_flight.is_poisoned());
+
+    let res = in_flight
+        .watch("ping", future::ok(()).boxed())
+        .now_or_never();
+    assert!(matches!(res, Some(Err(Error::Protocol(_)))));
+}
@@ -1,3 +1,31 @@
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+mod application_name;
+#[cfg(feature = "options-serde")]
+pub(crate) mod config;
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "mssql"))]
+mod in_flight;
+#[cfg(any(
+    feature = "postgres",
+    feature = "mysql",
+    feature = "sqlite",
+    feature = "mssql"
+))]
+mod quote;
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+mod read_only;
 mod statement_cache;
 
-pub(crate) use statement_cache::StatementCache;
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) use application_name::{default_application_name, sanitize_application_name};
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "mssql"))]
+pub(crate) use in_flight::InFlight;
+#[cfg(any(
+    feature = "postgres",
+    feature = "mysql",
+    feature = "sqlite",
+    feature = "mssql"
+))]
+pub(crate) use quote::quote_identifier;
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) use read_only::{check_read_only, leading_keyword, placeholders, SqlDialect};
+pub use statement_cache::StatementCache;
@@ -0,0 +1,45 @@
+use crate::error::Error;
+
+/// Quotes `name` as a delimited identifier between `open` and `close`, doubling every `close`
+/// inside it.
+///
+/// Empty names and names containing a NUL byte are rejected, as no database accepts them.
+pub(crate) fn quote_identifier(name: &str, open: char, close: char) -> Result<String, Error> {
+    if name.is_empty() {
+        return Err(Error::encode("identifier must not be empty"));
+    }
+
+    if name.contains('\0') {
+        return Err(Error::encode(format!(
+            "identifier must not contain a NUL byte: {:?}",
+            name
+        )));
+    }
+
+    let mut quoted = String::with_capacity(name.len() + 2);
+
+    quoted.push(open);
+
+    for c in name.chars() {
+        if c == close {
+            quoted.push(close);
+        }
+
+        quoted.push(c);
+    }
+
+    quoted.push(close);
+
+    Ok(quoted)
+}
+
+#[test]
+fn it_quotes_identifiers() {
+    assert_eq!(quote_identifier("users", '"', '"').unwrap(), r#""users""#);
+    assert_eq!(quote_identifier(r#"a"b"#, '"', '"').unwrap(), r#""a""b""#);
+    assert_eq!(quote_identifier("a]b", '[', ']').unwrap(), "[a]]b]");
+    assert_eq!(quote_identifier("a[b", '[', ']').unwrap(), "[a[b]");
+
+    assert!(quote_identifier("", '"', '"').is_err());
+    assert!(quote_identifier("a\0b", '"', '"').is_err());
+}
@@ -0,0 +1,617 @@
+use std::ops::Range;
+
+use crate::error::Error;
+
+/// The lexical rules a statement is scanned with; they differ in comments and quoting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SqlDialect {
+    #[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+    Postgres,
+    #[cfg_attr(not(feature = "mysql"), allow(dead_code))]
+    MySql,
+}
+
+// statements that cannot write on their own; anything else is rejected
+const READ_ONLY_KEYWORDS: &[&str] = &[
+    "SELECT",
+    "WITH",
+    "VALUES",
+    "TABLE",
+    "SHOW",
+    "EXPLAIN",
+    "DESCRIBE",
+    "DESC",
+    "BEGIN",
+    "START",
+    "COMMIT",
+    "END",
+    "ROLLBACK",
+    "SAVEPOINT",
+    "RELEASE",
+    "LISTEN",
+    "UNLISTEN",
+];
+
+// the options that may come between `EXPLAIN` and the statement being explained
+const EXPLAIN_OPTIONS: &[&str] = &[
+    "ANALYZE",
+    "ANALYSE",
+    "VERBOSE",
+    "COSTS",
+    "SETTINGS",
+    "BUFFERS",
+    "WAL",
+    "TIMING",
+    "SUMMARY",
+    "FORMAT",
+    "TEXT",
+    "XML",
+    "JSON",
+    "YAML",
+    "TREE",
+    "TRADITIONAL",
+    "EXTENDED",
+    "PARTITIONS",
+    "TRUE",
+    "FALSE",
+    "ON",
+    "OFF",
+];
+
+// how much of the offending statement is quoted in the error
+const STATEMENT_PREFIX_LEN: usize = 48;
+
+/// Rejects `sql` unless it is a single statement that obviously does not write.
+///
+/// Only the leading keywords of the statement are looked at, so this is a guard against
+/// mistakes rather than a security boundary; statements it lets through (a data-modifying
+/// `WITH`, a function with side effects) are left to the read-only session on the server.
+pub(crate) fn check_read_only(sql: &str, dialect: SqlDialect) -> Result<(), Error> {
+    let mut tokens = Tokens::new(sql, dialect);
+
+    // leading empty statements are harmless
+    let first = match tokens.find(|token| token.kind != TokenKind::Semicolon) {
+        Some(token) => token,
+
+        // only whitespace and comments
+        None => return Ok(()),
+    };
+
+    if !is_read_only_statement(sql, first, tokens.clone()) {
+        return Err(violation(sql, first.start));
+    }
+
+    // the statement may end in `;`, but nothing may follow it
+    while let Some(token) = tokens.next() {
+        match token.kind {
+            TokenKind::ExecutableComment => return Err(violation(sql, token.start)),
+
+            TokenKind::Semicolon => {
+                if let Some(token) = tokens.find(|token| token.kind != TokenKind::Semicolon) {
+                    return Err(violation(sql, token.start));
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// The first keyword of `sql`, past any comments and empty statements.
+#[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+pub(crate) fn leading_keyword(sql: &str, dialect: SqlDialect) -> Option<&str> {
+    Tokens::new(sql, dialect)
+        .find(|token| token.kind != TokenKind::Semicolon)
+        .and_then(|token| token.word(sql))
+}
+
+/// The byte ranges of the bind parameter placeholders in `sql`, in order: `$1`, `$2`, ... in
+/// PostgreSQL and `?` in MySQL, outside of strings, quoted identifiers and comments.
+///
+/// `backslash_escapes` is whether a backslash escapes the next character in every `'...'`
+/// string: in PostgreSQL with `standard_conforming_strings` off, and in MySQL unless the
+/// `NO_BACKSLASH_ESCAPES` SQL mode is set.
+#[cfg_attr(not(any(feature = "postgres", feature = "mysql")), allow(dead_code))]
+pub(crate) fn placeholders(
+    sql: &str,
+    dialect: SqlDialect,
+    backslash_escapes: bool,
+) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut tokens = Tokens::new(sql, dialect);
+    tokens.backslash_escapes = backslash_escapes;
+
+    tokens
+        .filter(move |token| {
+            let text = &sql[token.start..token.end];
+
+            match dialect {
+                SqlDialect::Postgres => {
+                    token.kind == TokenKind::Word
+                        && text.len() > 1
+                        && text.starts_with('$')
+                        && text[1..].bytes().all(|b| b.is_ascii_digit())
+                }
+
+                SqlDialect::MySql => token.kind == TokenKind::Other && text == "?",
+            }
+        })
+        .map(|token| token.start..token.end)
+}
+
+fn is_read_only_statement(sql: &str, first: Token, tokens: Tokens<'_>) -> bool {
+    let keyword = match first.word(sql) {
+        Some(keyword) => keyword,
+        None => return false,
+    };
+
+    if !is_one_of(keyword, READ_ONLY_KEYWORDS) {
+        return false;
+    }
+
+    match &*keyword.to_ascii_uppercase() {
+        // `EXPLAIN ANALYZE` runs the statement it explains
+        "EXPLAIN" | "DESCRIBE" | "DESC" => {
+            let mut analyze = false;
+
+            for token in tokens {
+                match token.word(sql) {
+                    Some(word) if is_one_of(word, EXPLAIN_OPTIONS) => {
+                        analyze |= is_one_of(word, &["ANALYZE", "ANALYSE"]);
+                    }
+
+                    Some(word) => {
+                        return !analyze || is_one_of(word, &["SELECT", "VALUES", "TABLE"]);
+                    }
+
+                    None if token.kind == TokenKind::Other => {}
+
+                    None => break,
+                }
+            }
+
+            !analyze
+        }
+
+        // a transaction may be opened, but not as `READ WRITE`
+        "BEGIN" | "START" => tokens
+            .take_while(|token| token.kind != TokenKind::Semicolon)
+            .all(|token| {
+                !token
+                    .word(sql)
+                    .map_or(false, |word| word.eq_ignore_ascii_case("WRITE"))
+            }),
+
+        _ => true,
+    }
+}
+
+fn is_one_of(word: &str, keywords: &[&str]) -> bool {
+    keywords
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(word))
+}
+
+fn violation(sql: &str, start: usize) -> Error {
+    let statement = &sql[start..];
+
+    let prefix = match statement.char_indices().nth(STATEMENT_PREFIX_LEN) {
+        Some((end, _)) => format!("{}...", &statement[..end]),
+        None => statement.to_owned(),
+    };
+
+    Error::ReadOnlyViolation(prefix)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Word,
+    Semicolon,
+
+    // a MySQL `/*! ... */` comment, whose contents are executed
+    ExecutableComment,
+
+    // punctuation, numbers, quoted strings and identifiers
+    Other,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    fn word<'a>(&self, sql: &'a str) -> Option<&'a str> {
+        if self.kind == TokenKind::Word {
+            Some(&sql[self.start..self.end])
+        } else {
+            None
+        }
+    }
+}
+
+// splits a statement into tokens, skipping whitespace and comments
+#[derive(Clone)]
+struct Tokens<'a> {
+    sql: &'a [u8],
+    dialect: SqlDialect,
+    pos: usize,
+
+    // whether backslashes escape in every '...' string, rather than only in PostgreSQL `E'...'`
+    backslash_escapes: bool,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(sql: &'a str, dialect: SqlDialect) -> Self {
+        Self {
+            sql: sql.as_bytes(),
+            dialect,
+            pos: 0,
+            backslash_escapes: dialect == SqlDialect::MySql,
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<u8> {
+        self.sql.get(self.pos + offset).copied()
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(b) = self.peek(0) {
+            self.pos += 1;
+
+            if b == b'\n' {
+                break;
+            }
+        }
+    }
+
+    fn skip_block_comment(&mut self) {
+        // PostgreSQL allows block comments to nest, MySQL does not
+        let mut depth = 0;
+
+        while self.pos < self.sql.len() {
+            match (self.peek(0), self.peek(1)) {
+                (Some(b'/'), Some(b'*')) if depth == 0 || self.dialect == SqlDialect::Postgres => {
+                    depth += 1;
+                    self.pos += 2;
+                }
+
+                (Some(b'*'), Some(b'/')) => {
+                    depth -= 1;
+                    self.pos += 2;
+
+                    if depth == 0 {
+                        return;
+                    }
+                }
+
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn skip_quoted(&mut self, quote: u8, backslash_escapes: bool) {
+        self.pos += 1;
+
+        while let Some(b) = self.peek(0) {
+            self.pos += 1;
+
+            if b == b'\\' && backslash_escapes {
+                self.pos += 1;
+            } else if b == quote {
+                // a doubled quote is an escaped quote
+                if self.peek(0) == Some(quote) {
+                    self.pos += 1;
+                } else {
+                    return;
+                }
+            }
+        }
+    }
+
+    // a PostgreSQL `$tag$ ... $tag$` string; returns `false` if this is not one
+    fn skip_dollar_quoted(&mut self) -> bool {
+        let rest = &self.sql[self.pos + 1..];
+        let tag_len = rest
+            .iter()
+            .take_while(|b| is_word_byte(**b) && **b != b'$')
+            .count();
+
+        if rest.get(tag_len) != Some(&b'$') || rest.first().map_or(false, u8::is_ascii_digit) {
+            return false;
+        }
+
+        let delimiter = &self.sql[self.pos..self.pos + tag_len + 2];
+        let body = self.pos + delimiter.len();
+
+        self.pos = match self.sql[body..]
+            .windows(delimiter.len())
+            .position(|window| window == delimiter)
+        {
+            Some(end) => body + end + delimiter.len(),
+            None => self.sql.len(),
+        };
+
+        true
+    }
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        loop {
+            let start = self.pos;
+            let b = self.peek(0)?;
+
+            let kind = match (b, self.peek(1)) {
+                _ if b.is_ascii_whitespace() => {
+                    self.pos += 1;
+                    continue;
+                }
+
+                // in MySQL, `--` only starts a comment if followed by whitespace
+                (b'-', Some(b'-'))
+                    if self.dialect == SqlDialect::Postgres
+                        || self.peek(2).map_or(true, |b| b.is_ascii_whitespace()) =>
+                {
+                    self.skip_line();
+                    continue;
+                }
+
+                (b'#', _) if self.dialect == SqlDialect::MySql => {
+                    self.skip_line();
+                    continue;
+                }
+
+                (b'/', Some(b'*')) => {
+                    let executable =
+                        self.dialect == SqlDialect::MySql && self.peek(2) == Some(b'!');
+
+                    self.skip_block_comment();
+
+                    if !executable {
+                        continue;
+                    }
+
+                    TokenKind::ExecutableComment
+                }
+
+                (b';', _) => {
+                    self.pos += 1;
+                    TokenKind::Semicolon
+                }
+
+                (b'\'', _) => {
+                    // backslashes escape in MySQL strings and PostgreSQL `E'...'` strings
+                    let escape_string = start > 0
+                        && self.sql[start - 1].eq_ignore_ascii_case(&b'e')
+                        && (start < 2 || !is_word_byte(self.sql[start - 2]));
+
+                    self.skip_quoted(b'\'', self.backslash_escapes || escape_string);
+                    TokenKind::Other
+                }
+
+                (b'"', _) => {
+                    self.skip_quoted(
+                        b'"',
+                        self.dialect == SqlDialect::MySql && self.backslash_escapes,
+                    );
+                    TokenKind::Other
+                }
+
+                (b'`', _) if self.dialect == SqlDialect::MySql => {
+                    self.skip_quoted(b'`', false);
+                    TokenKind::Other
+                }
+
+                (b'$', _) if self.dialect == SqlDialect::Postgres && self.skip_dollar_quoted() => {
+                    TokenKind::Other
+                }
+
+                _ if is_word_byte(b) && !b.is_ascii_digit() => {
+                    while self.peek(0).map_or(false, is_word_byte) {
+                        self.pos += 1;
+                    }
+
+                    // `E'...'` is a single string
+                    if self.pos - start == 1 && self.peek(0) == Some(b'\'') {
+                        continue;
+                    }
+
+                    TokenKind::Word
+                }
+
+                _ => {
+                    self.pos += 1;
+
+                    // a number, including any letters directly after it (`1e5`, `0x1f`)
+                    if b.is_ascii_digit() {
+                        while self.peek(0).map_or(false, is_word_byte) {
+                            self.pos += 1;
+                        }
+                    }
+
+                    TokenKind::Other
+                }
+            };
+
+            return Some(Token {
+                kind,
+                start,
+                end: self.pos,
+            });
+        }
+    }
+}
+
+fn is_word_byte(b: u8) -> bool {
+    // bytes of non-ASCII characters are treated as part of a word
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80
+}
+
+#[cfg(test)]
+fn is_allowed(sql: &str, dialect: SqlDialect) -> bool {
+    check_read_only(sql, dialect).is_ok()
+}
+
+#[test]
+fn it_allows_reading_statements() {
+    for dialect in [SqlDialect::Postgres, SqlDialect::MySql].iter().copied() {
+        assert!(is_allowed("SELECT 1", dialect));
+        assert!(is_allowed(
+            "  select update_time, delete_flag from t;  ",
+            dialect
+        ));
+        assert!(is_allowed(
+            "-- leading comment\n/* another */ SELECT 1",
+            dialect
+        ));
+        assert!(is_allowed("SELECT 'a; DELETE FROM t'", dialect));
+        assert!(is_allowed("SELECT 1 -- ; DELETE FROM t", dialect));
+        assert!(is_allowed("SELECT 1;;", dialect));
+        assert!(is_allowed("EXPLAIN DELETE FROM t", dialect));
+        assert!(is_allowed("EXPLAIN ANALYZE SELECT * FROM t", dialect));
+        assert!(is_allowed("BEGIN", dialect));
+        assert!(is_allowed("START TRANSACTION READ ONLY", dialect));
+        assert!(is_allowed("", dialect));
+        assert!(is_allowed("; SELECT 1", dialect));
+    }
+
+    assert!(is_allowed(
+        "WITH x AS (SELECT 1) SELECT * FROM x",
+        SqlDialect::Postgres
+    ));
+    assert!(is_allowed(
+        "SELECT $$;$$, $tag$ ; $$ $tag$",
+        SqlDialect::Postgres
+    ));
+    assert!(is_allowed("SELECT E'\\' ; '", SqlDialect::Postgres));
+    assert!(is_allowed("SELECT $1; ", SqlDialect::Postgres));
+    assert!(is_allowed(
+        "EXPLAIN (ANALYZE, FORMAT JSON) SELECT 1",
+        SqlDialect::Postgres
+    ));
+    assert!(is_allowed("SELECT '\\'; '", SqlDialect::MySql));
+    assert!(is_allowed(
+        "SELECT `a;b` FROM t # ; DELETE",
+        SqlDialect::MySql
+    ));
+    assert!(is_allowed("DESCRIBE t", SqlDialect::MySql));
+    assert!(is_allowed(
+        "SELECT /*+ NO_ICP(t) */ * FROM t",
+        SqlDialect::MySql
+    ));
+}
+
+#[test]
+fn it_rejects_writing_statements() {
+    for dialect in [SqlDialect::Postgres, SqlDialect::MySql].iter().copied() {
+        assert!(!is_allowed("DELETE FROM t", dialect));
+        assert!(!is_allowed(" /* SELECT */ update t set a = 1", dialect));
+        assert!(!is_allowed(
+            "SET default_transaction_read_only = off",
+            dialect
+        ));
+        assert!(!is_allowed("SELECT 1; DELETE FROM t", dialect));
+        assert!(!is_allowed("SELECT 1; SELECT 2", dialect));
+        assert!(!is_allowed("EXPLAIN ANALYZE DELETE FROM t", dialect));
+        assert!(!is_allowed("START TRANSACTION READ WRITE", dialect));
+        assert!(!is_allowed("BEGIN; DELETE FROM t", dialect));
+        assert!(!is_allowed("; DELETE FROM t", dialect));
+        assert!(!is_allowed("(SELECT 1)", dialect));
+    }
+
+    assert!(!is_allowed(
+        "EXPLAIN (ANALYZE) DELETE FROM t",
+        SqlDialect::Postgres
+    ));
+    assert!(!is_allowed(
+        "SELECT $$ $$; DROP TABLE t",
+        SqlDialect::Postgres
+    ));
+    assert!(!is_allowed(
+        "SELECT '\\'; DROP TABLE t",
+        SqlDialect::Postgres
+    ));
+    assert!(!is_allowed("SELECT 1--1; DROP TABLE t", SqlDialect::MySql));
+    assert!(!is_allowed("/*!50000 DROP TABLE t */", SqlDialect::MySql));
+    assert!(!is_allowed(
+        "SELECT 1 /*! ; DROP TABLE t */",
+        SqlDialect::MySql
+    ));
+}
+
+#[test]
+fn it_names_the_offending_statement() {
+    let err = check_read_only(
+        "SELECT 1; \n  DELETE FROM a_table_with_a_rather_long_name WHERE id = 1",
+        SqlDialect::Postgres,
+    )
+    .unwrap_err();
+
+    match err {
+        Error::ReadOnlyViolation(statement) => assert_eq!(
+            statement,
+            "DELETE FROM a_table_with_a_rather_long_name WHER..."
+        ),
+        _ => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[test]
+fn it_finds_the_leading_keyword() {
+    let dialect = SqlDialect::Postgres;
+
+    assert_eq!(leading_keyword("SELECT 1", dialect), Some("SELECT"));
+    assert_eq!(
+        leading_keyword("; -- comment\n/* another */ with t as (select 1)", dialect),
+        Some("with")
+    );
+    assert_eq!(leading_keyword("(SELECT 1)", dialect), None);
+    assert_eq!(leading_keyword("  ", dialect), None);
+}
+
+#[test]
+fn it_finds_placeholders() {
+    let found = |sql: &'static str, dialect, backslash_escapes| -> Vec<&'static str> {
+        placeholders(sql, dialect, backslash_escapes)
+            .map(|range| &sql[range])
+            .collect()
+    };
+
+    assert_eq!(
+        found(
+            "SELECT $1, a$2, '$3', \"$4\", $$ $5 $$, $tag$ $6 $tag$ -- $7\n+ $8 /* $9 */",
+            SqlDialect::Postgres,
+            false
+        ),
+        ["$1", "$8"]
+    );
+
+    // a backslash only escapes in `E'...'`, unless standard conforming strings are off
+    assert_eq!(
+        found(r"SELECT '\', $1, E'\', $2'", SqlDialect::Postgres, false),
+        ["$1"]
+    );
+    assert_eq!(
+        found(r"SELECT '\', $1'", SqlDialect::Postgres, true),
+        Vec::<&str>::new()
+    );
+
+    assert_eq!(
+        found(
+            r#"SELECT ?, '?', '\'?', `?`, "?" # ?"#,
+            SqlDialect::MySql,
+            true
+        ),
+        ["?"]
+    );
+    assert_eq!(
+        found(r"SELECT '\', ?, '?'", SqlDialect::MySql, false),
+        ["?"]
+    );
+}
@@ -1,67 +1,244 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::mem;
+
 use hashlink::lru_cache::LruCache;
 
+use crate::connection::StatementCacheStats;
+use crate::ext::ustr::UStr;
+use crate::HashMap;
+
+// the number of evicted statements remembered, to count those prepared again
+const EVICTED_HISTORY: usize = 256;
+
 /// A cache for prepared statements. When full, the least recently used
-/// statement gets removed.
+/// statement gets removed, unless it is pinned.
 #[derive(Debug)]
 pub struct StatementCache<T> {
-    inner: LruCache<String, T>,
+    inner: LruCache<UStr, Entry<T>>,
+    pinned: HashMap<UStr, Entry<T>>,
+    limit: Limit,
+
+    // the cost of all the statements in the cache, and of the pinned ones, in the unit of
+    // `limit`
+    used: usize,
+    pinned_used: usize,
+
+    // hashes of the SQL of the statements evicted lately
+    evicted: LruCache<u64, ()>,
+    stats: StatementCacheStats,
+}
+
+#[derive(Debug)]
+struct Entry<T> {
+    value: T,
+    cost: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Limit {
+    Statements(usize),
+
+    // the estimated size of the statements: their SQL and metadata
+    Bytes(usize),
 }
 
 impl<T> StatementCache<T> {
     /// Create a new cache with the given capacity.
     pub fn new(capacity: usize) -> Self {
+        Self::with_limit(Limit::Statements(capacity))
+    }
+
+    /// Create a new cache that holds as many statements as fit in `max_bytes`, by the
+    /// estimated size of their SQL and metadata.
+    pub fn with_max_bytes(max_bytes: usize) -> Self {
+        Self::with_limit(Limit::Bytes(max_bytes))
+    }
+
+    fn with_limit(limit: Limit) -> Self {
         Self {
-            inner: LruCache::new(capacity),
+            inner: LruCache::new_unbounded(),
+            pinned: HashMap::new(),
+            limit,
+            used: 0,
+            pinned_used: 0,
+            evicted: LruCache::new(EVICTED_HISTORY),
+            stats: StatementCacheStats::default(),
         }
     }
 
     /// Returns a mutable reference to the value corresponding to the given key
-    /// in the cache, if any.
+    /// in the cache, if any, counting a hit or a miss.
     pub fn get_mut(&mut self, k: &str) -> Option<&mut T> {
-        self.inner.get_mut(k)
+        let entry = match self.pinned.get_mut(k) {
+            Some(entry) => Some(entry),
+            None => self.inner.get_mut(k),
+        };
+
+        match entry {
+            Some(entry) => {
+                self.stats.hits += 1;
+                Some(&mut entry.value)
+            }
+
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Like [`get_mut`](Self::get_mut), but without counting a lookup or marking the
+    /// statement as recently used.
+    #[allow(dead_code)] // Only used for some `cfg`s
+    pub fn peek_mut(&mut self, k: &str) -> Option<&mut T> {
+        match self.pinned.get_mut(k) {
+            Some(entry) => Some(&mut entry.value),
+            None => self.inner.peek_mut(k).map(|entry| &mut entry.value),
+        }
     }
 
-    /// Inserts a new statement to the cache, returning the least recently used
-    /// statement id if the cache is full, or if inserting with an existing key,
-    /// the replaced existing statement.
-    pub fn insert(&mut self, k: &str, v: T) -> Option<T> {
-        let mut lru_item = None;
+    /// Inserts a new statement to the cache, returning the statements it replaces: the least
+    /// recently used statements that are evicted to make room, and the existing statement if
+    /// inserting with an existing key.
+    ///
+    /// `size` is the estimated size of the statement besides its SQL, which counts when the
+    /// capacity is in bytes. Pinned statements are never evicted: if the statement does not
+    /// fit next to them, it is returned as `Err` and the refusal counted.
+    pub fn insert(&mut self, k: &str, v: T, size: usize) -> Result<Vec<T>, T> {
+        let cost = self.cost(k, size);
+        let mut replaced = Vec::new();
 
-        if self.capacity() == self.len() && !self.contains_key(k) {
-            lru_item = self.remove_lru();
-        } else if self.contains_key(k) {
-            lru_item = self.inner.remove(k);
+        if let Some(entry) = self.pinned.get_mut(k) {
+            // a pinned statement stays pinned when it is prepared again
+            let old = mem::replace(entry, Entry { value: v, cost });
+
+            self.used = self.used - old.cost + cost;
+            self.pinned_used = self.pinned_used - old.cost + cost;
+            replaced.push(old.value);
+
+            self.evict_until_room_for(0, &mut replaced);
+
+            return Ok(replaced);
+        }
+
+        if self.pinned_used + cost > self.capacity() {
+            self.stats.refusals += 1;
+            return Err(v);
+        }
+
+        if let Some(old) = self.inner.remove(k) {
+            self.used -= old.cost;
+            replaced.push(old.value);
+        }
+
+        self.evict_until_room_for(cost, &mut replaced);
+
+        if self.evicted.remove(&hash(k)).is_some() {
+            self.stats.reprepares += 1;
+        }
+
+        self.inner.insert(UStr::new(k), Entry { value: v, cost });
+        self.used += cost;
+
+        Ok(replaced)
+    }
+
+    // evicts the least recently used statements that are not pinned until `cost` more fits;
+    // the pinned statements alone must leave room for it
+    fn evict_until_room_for(&mut self, cost: usize, evicted: &mut Vec<T>) {
+        while self.used + cost > self.capacity() {
+            let (k, entry) = match self.inner.remove_lru() {
+                Some(lru) => lru,
+                None => break,
+            };
+
+            self.used -= entry.cost;
+            self.stats.evictions += 1;
+            self.evicted.insert(hash(&k), ());
+
+            evicted.push(entry.value);
+        }
+    }
+
+    /// Pins the statement cached for the given key, so that it is never evicted; returns
+    /// `false` if there is none.
+    pub fn pin(&mut self, k: &str) -> bool {
+        if self.pinned.contains_key(k) {
+            return true;
         }
 
-        self.inner.insert(k.into(), v);
+        match self.inner.remove_entry(k) {
+            Some((k, entry)) => {
+                self.pinned_used += entry.cost;
+                self.pinned.insert(k, entry);
 
-        lru_item
+                true
+            }
+
+            None => false,
+        }
     }
 
     /// The number of statements in the cache.
     pub fn len(&self) -> usize {
-        self.inner.len()
+        self.inner.len() + self.pinned.len()
     }
 
-    /// Removes the least recently used item from the cache.
+    /// True if the cache holds no statements.
+    #[allow(dead_code)] // Only used by `bench-support`
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes the least recently used item from the cache, if it is not pinned.
+    #[allow(dead_code)] // Only used by `bench-support`
     pub fn remove_lru(&mut self) -> Option<T> {
-        self.inner.remove_lru().map(|(_, v)| v)
+        let (_, entry) = self.inner.remove_lru()?;
+        self.used -= entry.cost;
+
+        Some(entry.value)
+    }
+
+    /// Removes every statement from the cache, pinned or not, returning them.
+    #[allow(dead_code)] // Only used for some `cfg`s
+    pub fn drain(&mut self) -> Vec<T> {
+        let mut statements = Vec::with_capacity(self.len());
+
+        while let Some((_, entry)) = self.inner.remove_lru() {
+            statements.push(entry.value);
+        }
+
+        statements.extend(self.pinned.drain().map(|(_, entry)| entry.value));
+
+        self.used = 0;
+        self.pinned_used = 0;
+
+        statements
     }
 
-    /// Clear all cached statements from the cache.
+    /// Clear all cached statements from the cache, pinned or not.
     #[cfg(feature = "sqlite")]
     pub fn clear(&mut self) {
         self.inner.clear();
+        self.pinned.clear();
+
+        self.used = 0;
+        self.pinned_used = 0;
     }
 
     /// True if cache has a value for the given key.
+    #[allow(dead_code)] // Only used for some `cfg`s
     pub fn contains_key(&mut self, k: &str) -> bool {
-        self.inner.contains_key(k)
+        self.pinned.contains_key(k) || self.inner.contains_key(k)
     }
 
-    /// Returns the maximum number of statements the cache can hold.
+    /// Returns the maximum number of statements the cache can hold, or the maximum of their
+    /// estimated size in bytes.
     pub fn capacity(&self) -> usize {
-        self.inner.capacity()
+        match self.limit {
+            Limit::Statements(capacity) | Limit::Bytes(capacity) => capacity,
+        }
     }
 
     /// Returns true if the cache capacity is more than 0.
@@ -69,4 +246,95 @@ impl<T> StatementCache<T> {
     pub fn is_enabled(&self) -> bool {
         self.capacity() > 0
     }
+
+    /// The hits, misses, evictions and refusals of the cache so far.
+    #[allow(dead_code)] // Only used for some `cfg`s
+    pub fn stats(&self) -> StatementCacheStats {
+        self.stats
+    }
+
+    fn cost(&self, k: &str, size: usize) -> usize {
+        match self.limit {
+            Limit::Statements(_) => 1,
+            Limit::Bytes(_) => k.len() + size + mem::size_of::<(UStr, Entry<T>)>(),
+        }
+    }
+}
+
+fn hash(k: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    k.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+#[test]
+fn it_evicts_the_least_recently_used_statement() {
+    let mut cache = StatementCache::new(2);
+
+    assert_eq!(cache.insert("a", 1, 0).unwrap(), Vec::<i32>::new());
+    assert_eq!(cache.insert("b", 2, 0).unwrap(), Vec::<i32>::new());
+    assert_eq!(cache.get_mut("a"), Some(&mut 1));
+
+    assert_eq!(cache.insert("c", 3, 0).unwrap(), [2]);
+    assert_eq!(cache.insert("c", 4, 0).unwrap(), [3]);
+    assert_eq!(cache.get_mut("b"), None);
+
+    // prepared again after being evicted
+    assert_eq!(cache.insert("b", 5, 0).unwrap(), [1]);
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses), (1, 1));
+    assert_eq!(
+        (stats.evictions, stats.reprepares, stats.refusals),
+        (2, 1, 0)
+    );
+}
+
+#[test]
+fn it_never_evicts_pinned_statements() {
+    let mut cache = StatementCache::new(2);
+
+    assert!(!cache.pin("a"));
+
+    cache.insert("a", 1, 0).unwrap();
+    assert!(cache.pin("a"));
+
+    cache.insert("b", 2, 0).unwrap();
+    assert_eq!(cache.insert("c", 3, 0).unwrap(), [2]);
+    assert!(cache.pin("c"));
+
+    // no room is left next to the pinned statements
+    assert_eq!(cache.insert("d", 4, 0), Err(4));
+    assert_eq!(cache.stats().refusals, 1);
+
+    // pinned statements may be prepared again, and stay pinned
+    assert_eq!(cache.insert("a", 5, 0).unwrap(), [1]);
+    assert_eq!(cache.remove_lru(), None);
+    assert_eq!(cache.len(), 2);
+
+    let mut drained = cache.drain();
+    drained.sort_unstable();
+
+    assert_eq!(drained, [3, 5]);
+    assert_eq!(cache.insert("d", 4, 0).unwrap(), Vec::<i32>::new());
+}
+
+#[test]
+fn it_bounds_statements_by_their_size() {
+    let overhead = mem::size_of::<(UStr, Entry<i32>)>();
+    let mut cache = StatementCache::with_max_bytes(3 * overhead + 300);
+
+    cache.insert("a", 1, 100).unwrap();
+    cache.insert("b", 2, 100).unwrap();
+    cache.insert("c", 3, 10).unwrap();
+    assert!(cache.pin("a"));
+
+    // makes room by evicting both of the others
+    assert_eq!(cache.insert("d", 4, 200).unwrap(), [2, 3]);
+    assert_eq!(cache.len(), 2);
+
+    // more than the pinned statement leaves room for
+    assert_eq!(cache.insert("e", 5, 2 * overhead + 200), Err(5));
+    assert_eq!(cache.len(), 2);
 }
@@ -1,11 +1,14 @@
-use crate::database::{Database, HasStatementCache};
-use crate::error::Error;
+use crate::database::{Capabilities, Database, HasStatementCache};
+use crate::error::{ConfigError, Error};
+use crate::events::{ConnectionEventListener, ConnectionEvents};
+use crate::middleware::QueryMiddleware;
 use crate::transaction::Transaction;
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Formatter};
 use std::str::FromStr;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Represents a single database connection.
 pub trait Connection: Send {
@@ -40,7 +43,6 @@ pub trait Connection: Send {
     /// ```rust
     /// use sqlx_core::connection::Connection;
     /// use sqlx_core::error::Error;
-    /// use sqlx_core::executor::Executor;
     /// use sqlx_core::postgres::{PgConnection, PgRow};
     /// use sqlx_core::query::query;
     ///
@@ -96,12 +98,81 @@ pub trait Connection: Send {
         Box::pin(async move { Ok(()) })
     }
 
+    /// Prepares `sql` for the statement cache, unless it is cached already, and pins it there:
+    /// a pinned statement is never evicted to make room for others, and is only removed by
+    /// [`clear_cached_statements`](Self::clear_cached_statements).
+    ///
+    /// Pinned statements count towards the capacity of the cache; when they leave no room for
+    /// a statement, it is not cached rather than evicting one of them. Fails if the statement
+    /// cache is disabled or already full of pinned statements.
+    fn prepare_pinned<'e>(&'e mut self, sql: &'e str) -> BoxFuture<'e, Result<(), Error>>
+    where
+        Self::Database: HasStatementCache,
+    {
+        let _ = sql;
+
+        Box::pin(async move {
+            Err(Error::Configuration(
+                "this driver does not support pinning statements".into(),
+            ))
+        })
+    }
+
+    /// Releases capacity the connection's read and write buffers have grown to beyond their
+    /// default size, e.g. after fetching a very large result set.
+    ///
+    /// Does nothing for drivers that do not buffer I/O themselves.
+    fn shrink_buffers(&mut self) {}
+
+    /// The current capacity of the connection's read and write buffers.
+    ///
+    /// Intended for metrics and tests of memory usage; drivers that do not buffer I/O
+    /// themselves report zero.
+    fn buffer_sizes(&self) -> BufferSizes {
+        BufferSizes::default()
+    }
+
+    /// Counters and timings for this connection since it was established, for monitoring and
+    /// capacity planning.
+    fn stats(&self) -> ConnectionStats;
+
+    /// The features the server of this connection supports: those of
+    /// [`Database::CAPABILITIES`], and any that depend on the version of the server.
+    fn capabilities(&self) -> Capabilities {
+        <Self::Database as Database>::CAPABILITIES
+    }
+
+    /// A snapshot of the state of the session that outlives a query, e.g. to find out what a
+    /// connection was returned to a pool with; see
+    /// [`PoolOptions::track_session_state`](crate::pool::PoolOptions::track_session_state).
+    ///
+    /// This may take a round trip to the server. Drivers that track none of the state return
+    /// [`SessionState::default`].
+    fn session_state(&mut self) -> BoxFuture<'_, Result<SessionState, Error>> {
+        Box::pin(async move { Ok(SessionState::default()) })
+    }
+
+    /// Takes the listener of connection events off the connection, with what it is told about
+    /// the connection, so that the connection no longer reports being closed; the pool reports
+    /// why it closes a connection itself.
+    #[doc(hidden)]
+    fn take_events(&mut self) -> ConnectionEvents {
+        ConnectionEvents::default()
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>>;
 
     #[doc(hidden)]
     fn should_flush(&self) -> bool;
 
+    /// Whether the server told this connection that it is shutting down, or ended it with a
+    /// fatal error; the other connections to the server were likely ended as well.
+    #[doc(hidden)]
+    fn saw_server_shutdown(&self) -> bool {
+        false
+    }
+
     /// Establish a new database connection.
     ///
     /// A value of [`Options`][Self::Options] is parsed from the provided connection string. This parsing
@@ -125,11 +196,278 @@ pub trait Connection: Send {
     }
 }
 
+/// The capacity, in bytes, of a connection's internal buffers; see
+/// [`Connection::buffer_sizes`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferSizes {
+    pub read: usize,
+    pub write: usize,
+}
+
+impl BufferSizes {
+    /// The combined capacity of both buffers.
+    pub fn total(&self) -> usize {
+        self.read + self.write
+    }
+}
+
+/// The state of a session that a query can leave behind for the next one, which should not
+/// outlive a logical user of a pooled connection; see [`Connection::session_state`].
+///
+/// A field is `None` where the driver does not track it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionState {
+    /// Whether a transaction is open, including one begun with a plain `BEGIN`.
+    pub in_transaction: Option<bool>,
+
+    /// The number of statements prepared with the SQL `PREPARE` command. These are never closed
+    /// by SQLx, unlike the statements it prepares and caches itself, which are not counted.
+    pub prepared_statements: Option<u64>,
+
+    /// The `search_path` of the session.
+    pub search_path: Option<String>,
+
+    /// The number of temporary tables of the session.
+    pub temp_tables: Option<u64>,
+}
+
+impl SessionState {
+    /// Describes each field that differs from `before`, e.g. `search_path: "public" -> "other"`.
+    ///
+    /// Fields that either snapshot does not track are skipped.
+    pub fn changes_since(&self, before: &SessionState) -> Vec<String> {
+        fn change<T: PartialEq + Debug>(
+            changes: &mut Vec<String>,
+            name: &str,
+            before: &Option<T>,
+            after: &Option<T>,
+        ) {
+            if let (Some(before), Some(after)) = (before, after) {
+                if before != after {
+                    changes.push(format!("{}: {:?} -> {:?}", name, before, after));
+                }
+            }
+        }
+
+        let mut changes = Vec::new();
+
+        change(
+            &mut changes,
+            "in_transaction",
+            &before.in_transaction,
+            &self.in_transaction,
+        );
+
+        change(
+            &mut changes,
+            "prepared_statements",
+            &before.prepared_statements,
+            &self.prepared_statements,
+        );
+
+        change(
+            &mut changes,
+            "search_path",
+            &before.search_path,
+            &self.search_path,
+        );
+
+        change(
+            &mut changes,
+            "temp_tables",
+            &before.temp_tables,
+            &self.temp_tables,
+        );
+
+        changes
+    }
+}
+
+/// Telemetry of a single connection; see [`Connection::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionStats {
+    /// When the connection was established.
+    pub connected_at: Instant,
+
+    /// How long the last [`ping`](Connection::ping) took, if there has been one.
+    pub last_ping: Option<Duration>,
+
+    /// A moving average of the time from sending a query to receiving the first response,
+    /// weighted like TCP's smoothed round-trip time (each new sample counts for 1/8).
+    pub average_latency: Option<Duration>,
+
+    /// The sum of the latencies of all queries, for an exact mean over a period.
+    pub total_latency: Duration,
+
+    /// The number of queries sent, including pings and the queries SQLx sends itself, e.g. to
+    /// begin a transaction. Pings are not counted for SQLite, where they do nothing.
+    pub queries: u64,
+
+    /// The number of bytes of protocol messages sent to the server, before any encryption.
+    /// Always zero for SQLite.
+    pub bytes_sent: u64,
+
+    /// The number of bytes of protocol messages received from the server, after any
+    /// decryption. Always zero for SQLite.
+    pub bytes_received: u64,
+
+    /// The number of notices that were neither logged nor handled, as their query had already
+    /// sent as many as `PgConnectOptions::max_notices_per_query`. Always zero except for
+    /// Postgres.
+    pub notices_suppressed: u64,
+
+    /// The number of notifications a `PgListener` on this connection dropped from its full
+    /// buffer; see `PgListener::set_max_buffered_notifications`. Always zero except for
+    /// Postgres.
+    pub notifications_dropped: u64,
+
+    /// How the cache of prepared statements has been used. Always zero for drivers without
+    /// one.
+    pub statement_cache: StatementCacheStats,
+}
+
+/// Counters of the prepared statement cache of a connection; see
+/// [`ConnectionStats::statement_cache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StatementCacheStats {
+    /// The number of times a statement was found in the cache.
+    pub hits: u64,
+
+    /// The number of times a statement was looked for in the cache and not found, so it was
+    /// prepared.
+    pub misses: u64,
+
+    /// The number of statements evicted to make room for others.
+    pub evictions: u64,
+
+    /// The number of statements prepared again after they were evicted lately; many of these
+    /// mean the cache is too small for the statements the application runs over and over.
+    pub reprepares: u64,
+
+    /// The number of statements that were not cached because the
+    /// [pinned](Connection::prepare_pinned) statements left no room for them.
+    pub refusals: u64,
+}
+
+impl ConnectionStats {
+    /// How long ago the connection was established.
+    pub fn age(&self) -> Duration {
+        self.connected_at.elapsed()
+    }
+}
+
+// the part of `ConnectionStats` kept by the connection itself; the byte counts come from
+// its stream
+#[derive(Debug, Clone)]
+pub(crate) struct StatsCollector {
+    connected_at: Instant,
+    last_ping: Option<Duration>,
+    average_latency: Option<Duration>,
+    total_latency: Duration,
+    queries: u64,
+}
+
+impl StatsCollector {
+    pub(crate) fn new() -> Self {
+        Self {
+            connected_at: Instant::now(),
+            last_ping: None,
+            average_latency: None,
+            total_latency: Duration::from_secs(0),
+            queries: 0,
+        }
+    }
+
+    pub(crate) fn record_query(&mut self, latency: Duration) {
+        self.queries += 1;
+        self.total_latency += latency;
+
+        self.average_latency = Some(match self.average_latency {
+            Some(average) => (average * 7 + latency) / 8,
+            None => latency,
+        });
+    }
+
+    pub(crate) fn record_ping(&mut self, latency: Duration) {
+        self.last_ping = Some(latency);
+    }
+
+    pub(crate) fn snapshot(&self, bytes_sent: u64, bytes_received: u64) -> ConnectionStats {
+        ConnectionStats {
+            connected_at: self.connected_at,
+            last_ping: self.last_ping,
+            average_latency: self.average_latency,
+            total_latency: self.total_latency,
+            queries: self.queries,
+            bytes_sent,
+            bytes_received,
+            notices_suppressed: 0,
+            notifications_dropped: 0,
+            statement_cache: StatementCacheStats::default(),
+        }
+    }
+}
+
+/// Which bind parameters are included when statements are logged;
+/// see [`ConnectOptions::log_bind_values`].
+///
+/// Positions are the zero-based index of the value in bind order, so `$1` or the first `?`
+/// is position `0`.
+#[derive(Clone)]
+pub enum BindLogging {
+    /// Bind values are never logged. This is the default.
+    Never,
+
+    /// Every bind value is logged.
+    All,
+
+    /// Every bind value is logged, except that the values at these positions are replaced
+    /// with `<redacted>`.
+    Redacted(Vec<usize>),
+
+    /// Every bind value is logged, except that values at positions for which the function
+    /// returns `true` are replaced with `<redacted>`.
+    RedactWith(Arc<dyn Fn(usize) -> bool + Send + Sync>),
+}
+
+impl BindLogging {
+    pub(crate) fn is_enabled(&self) -> bool {
+        !matches!(self, BindLogging::Never)
+    }
+
+    pub(crate) fn is_redacted(&self, position: usize) -> bool {
+        match self {
+            BindLogging::Never => true,
+            BindLogging::All => false,
+            BindLogging::Redacted(positions) => positions.contains(&position),
+            BindLogging::RedactWith(redact) => redact(position),
+        }
+    }
+}
+
+impl Default for BindLogging {
+    fn default() -> Self {
+        BindLogging::Never
+    }
+}
+
+impl Debug for BindLogging {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BindLogging::Never => f.write_str("Never"),
+            BindLogging::All => f.write_str("All"),
+            BindLogging::Redacted(positions) => f.debug_tuple("Redacted").field(positions).finish(),
+            BindLogging::RedactWith(_) => f.debug_tuple("RedactWith").field(&"<function>").finish(),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct LogSettings {
     pub(crate) statements_level: LevelFilter,
     pub(crate) slow_statements_level: LevelFilter,
     pub(crate) slow_statements_duration: Duration,
+    pub(crate) bind_values: BindLogging,
 }
 
 impl Default for LogSettings {
@@ -138,6 +476,7 @@ impl Default for LogSettings {
             statements_level: LevelFilter::Info,
             slow_statements_level: LevelFilter::Warn,
             slow_statements_duration: Duration::from_secs(1),
+            bind_values: BindLogging::Never,
         }
     }
 }
@@ -150,9 +489,84 @@ impl LogSettings {
         self.slow_statements_level = level;
         self.slow_statements_duration = duration;
     }
+    pub(crate) fn log_bind_values(&mut self, logging: BindLogging) {
+        self.bind_values = logging;
+    }
+}
+
+/// How far the server may weaken the security of a connection while it is being established.
+///
+/// A violation aborts the handshake with [`Error::PolicyViolation`] before any credentials are
+/// sent under the weakened terms.
+///
+/// Unless a policy is set explicitly, drivers use [`strict`](Self::strict) when the TLS
+/// settings ask for TLS (an SSL mode that requires it, or a root certificate) and
+/// [`permissive`](Self::permissive) otherwise, which is how connections behaved before
+/// policies existed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtocolPolicy {
+    /// Continue without TLS if the server refuses a TLS request made because the SSL mode
+    /// prefers TLS.
+    pub allow_plaintext_fallback: bool,
+
+    /// Authenticate with a weaker method than the strongest one the driver supports when the
+    /// server asks for it, e.g. a cleartext or MD5 password instead of SCRAM in PostgreSQL, or
+    /// `mysql_native_password` after MySQL offered `caching_sha2_password`.
+    pub allow_auth_method_downgrade: bool,
+
+    /// The oldest version of TLS the server may negotiate.
+    pub minimum_tls_version: Option<TlsVersion>,
+}
+
+impl ProtocolPolicy {
+    /// Allows every downgrade.
+    pub const fn permissive() -> Self {
+        Self {
+            allow_plaintext_fallback: true,
+            allow_auth_method_downgrade: true,
+            minimum_tls_version: None,
+        }
+    }
+
+    /// Allows no downgrades, and requires at least TLS 1.2.
+    pub const fn strict() -> Self {
+        Self {
+            allow_plaintext_fallback: false,
+            allow_auth_method_downgrade: false,
+            minimum_tls_version: Some(TlsVersion::V1_2),
+        }
+    }
+
+    pub(crate) fn default_for(tls_configured: bool) -> Self {
+        if tls_configured {
+            Self::strict()
+        } else {
+            Self::permissive()
+        }
+    }
 }
 
-pub trait ConnectOptions: 'static + Send + Sync + FromStr<Err = Error> + Debug {
+/// A version of the TLS protocol; see [`ProtocolPolicy::minimum_tls_version`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TlsVersion {
+    V1_0,
+    V1_1,
+    V1_2,
+    V1_3,
+}
+
+impl fmt::Display for TlsVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TlsVersion::V1_0 => "TLS 1.0",
+            TlsVersion::V1_1 => "TLS 1.1",
+            TlsVersion::V1_2 => "TLS 1.2",
+            TlsVersion::V1_3 => "TLS 1.3",
+        })
+    }
+}
+
+pub trait ConnectOptions: 'static + Send + Sync + FromStr<Err = Error> + Debug + Clone {
     type Connection: Connection + ?Sized;
 
     /// Establish a new database connection with the options specified by `self`.
@@ -167,9 +581,126 @@ pub trait ConnectOptions: 'static + Send + Sync + FromStr<Err = Error> + Debug {
     /// at the specified `level`.
     fn log_slow_statements(&mut self, level: LevelFilter, duration: Duration) -> &mut Self;
 
+    /// Include bind parameters in the statements that are logged.
+    ///
+    /// Values are rendered from their encoded form, so the bound types do not need to
+    /// implement `Debug`. Types without a readable rendering are shown as their raw bytes in
+    /// hex, and long values are truncated. Redacted values are never rendered at all.
+    ///
+    /// Defaults to [`BindLogging::Never`], as bind values often contain personal data or
+    /// credentials. Not supported by MSSQL, which ignores this setting.
+    fn log_bind_values(&mut self, logging: BindLogging) -> &mut Self;
+
+    /// Sets a middleware to be called with the SQL of every query before it is executed, which
+    /// may rewrite the SQL or reject the query; see [`middleware`](crate::middleware).
+    fn query_middleware(&mut self, middleware: Arc<dyn QueryMiddleware>) -> &mut Self;
+
+    /// Sets a listener to be told when a connection made with these options is established,
+    /// fails to be, or is closed; see [`events`](crate::events).
+    fn connection_events(&mut self, listener: Arc<dyn ConnectionEventListener>) -> &mut Self;
+
     /// Entirely disables statement logging (both slow and regular).
     fn disable_statement_logging(&mut self) -> &mut Self {
         self.log_statements(LevelFilter::Off)
             .log_slow_statements(LevelFilter::Off, Duration::default())
     }
+
+    /// Replaces state that clones of these options share, such as the TLS session cache, with
+    /// fresh state, so that a pool built from the options shares it with nothing else.
+    #[doc(hidden)]
+    fn isolate(&mut self) {}
+
+    /// Establish a new database connection, appending `suffix` to the application name that
+    /// it reports to the server.
+    ///
+    /// Used by the pool to implement
+    /// [`PoolOptions::connection_name_suffix`](crate::pool::PoolOptions::connection_name_suffix).
+    /// Drivers that cannot name their connections ignore the suffix.
+    fn connect_with_name_suffix(
+        &self,
+        suffix: String,
+    ) -> BoxFuture<'_, Result<Self::Connection, Error>>
+    where
+        Self::Connection: Sized,
+    {
+        let _ = suffix;
+        self.connect()
+    }
+
+    /// Establish a new database connection, authenticating with `password` in place of any set
+    /// in these options, and appending `suffix` to the application name if one is given.
+    ///
+    /// Used by the pool to implement
+    /// [`PoolOptions::password_provider`](crate::pool::PoolOptions::password_provider).
+    /// Drivers that do not authenticate with a password ignore it.
+    fn connect_with_password(
+        &self,
+        password: String,
+        suffix: Option<String>,
+    ) -> BoxFuture<'_, Result<Self::Connection, Error>>
+    where
+        Self::Connection: Sized,
+    {
+        let _ = password;
+
+        match suffix {
+            Some(suffix) => self.connect_with_name_suffix(suffix),
+            None => self.connect(),
+        }
+    }
+
+    /// Checks these options for values that could never result in a working connection,
+    /// such as port `0` or an empty username, without contacting the server.
+    ///
+    /// Every problem found is returned, not just the first. This is not called
+    /// automatically by [`connect`](Self::connect).
+    fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        Ok(())
+    }
+}
+
+#[test]
+fn it_averages_latency_like_tcp() {
+    let mut stats = StatsCollector::new();
+
+    stats.record_query(Duration::from_millis(8));
+    assert_eq!(
+        stats.snapshot(0, 0).average_latency,
+        Some(Duration::from_millis(8))
+    );
+
+    stats.record_query(Duration::from_millis(16));
+    let snapshot = stats.snapshot(1, 2);
+
+    assert_eq!(snapshot.average_latency, Some(Duration::from_millis(9)));
+    assert_eq!(snapshot.total_latency, Duration::from_millis(24));
+    assert_eq!(snapshot.queries, 2);
+    assert_eq!((snapshot.bytes_sent, snapshot.bytes_received), (1, 2));
+}
+
+#[test]
+fn it_describes_changes_of_session_state() {
+    let before = SessionState {
+        in_transaction: Some(false),
+        prepared_statements: Some(0),
+        search_path: Some("public".into()),
+        temp_tables: None,
+    };
+
+    assert!(before.changes_since(&before).is_empty());
+
+    let after = SessionState {
+        in_transaction: Some(true),
+        search_path: Some("other".into()),
+        temp_tables: Some(1),
+        ..before.clone()
+    };
+
+    assert_eq!(
+        after.changes_since(&before),
+        [
+            "in_transaction: false -> true",
+            r#"search_path: "public" -> "other""#
+        ]
+    );
 }
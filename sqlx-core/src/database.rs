@@ -98,6 +98,47 @@ pub trait Database:
     /// The concrete type used to hold an owned copy of the not-yet-decoded value that was
     /// received from the database.
     type Value: Value<Database = Self> + 'static;
+
+    /// The features every server this driver connects to supports.
+    ///
+    /// Some depend on the server version; [`Connection::capabilities`] also includes those
+    /// detected when the connection was established.
+    const CAPABILITIES: Capabilities;
+
+    /// The most arguments that can be bound to a single query.
+    const MAX_BIND_PARAMETERS: usize;
+
+    /// The longest an identifier, such as a table or column name, can be, in bytes, or `None`
+    /// if there is no limit.
+    const MAX_IDENTIFIER_LENGTH: Option<usize>;
+}
+
+bitflags::bitflags! {
+    /// Features that a database may or may not support, for code that is generic over the
+    /// [`Database`] or uses the [`Any`](crate::any) driver.
+    pub struct Capabilities: u32 {
+        /// `INSERT`, `UPDATE` and `DELETE` can return rows with a `RETURNING` clause.
+        const RETURNING = 1 << 0;
+
+        /// Transactions can be nested, using savepoints.
+        const SAVEPOINTS = 1 << 1;
+
+        /// A query string can hold several statements, each returning its own result.
+        const MULTIPLE_RESULT_SETS = 1 << 2;
+
+        /// Values are sent and received in a binary format, rather than as text.
+        const BINARY_PROTOCOL = 1 << 3;
+
+        /// Notifications can be sent to other connections with `NOTIFY` and received with
+        /// `LISTEN`.
+        const LISTEN_NOTIFY = 1 << 4;
+
+        /// Parameters are written as `$1`, `$2`, ... in the query string.
+        const DOLLAR_PLACEHOLDERS = 1 << 5;
+
+        /// Parameters are written as `?` in the query string.
+        const QUESTION_MARK_PLACEHOLDERS = 1 << 6;
+    }
 }
 
 /// Associate [`Database`] with a [`ValueRef`](crate::value::ValueRef) of a generic lifetime.
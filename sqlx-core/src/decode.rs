@@ -60,6 +60,12 @@ use crate::value::ValueRef;
 pub trait Decode<'r, DB: Database>: Sized {
     /// Decode a new value of this type using a raw value from the database.
     fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError>;
+
+    /// Whether decoding a value of this type costs about as little as cloning one, so that a
+    /// [`CachedRow`](crate::cached_row::CachedRow) decodes it each time instead of keeping it.
+    ///
+    /// `false` unless overridden, as for the integer, float and `bool` types of the drivers.
+    const CHEAP_TO_DECODE: bool = false;
 }
 
 // implement `Decode` for Option<T> for all SQL types
@@ -68,6 +74,8 @@ where
     DB: Database,
     T: Decode<'r, DB>,
 {
+    const CHEAP_TO_DECODE: bool = T::CHEAP_TO_DECODE;
+
     fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
         if value.is_null() {
             Ok(None)
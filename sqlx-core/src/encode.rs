@@ -3,6 +3,15 @@
 use std::mem;
 
 use crate::database::{Database, HasArguments};
+use crate::error::BoxDynError;
+use crate::types::Type;
+
+/// The error returned by [`Encode`] when a value cannot be written in the format the database
+/// expects, e.g. a date outside the range of the database type.
+///
+/// It is reported as [`Error::ArgumentEncode`](crate::error::Error::ArgumentEncode) with the
+/// index of the argument, when the query is executed.
+pub type EncodeError = BoxDynError;
 
 /// The return type of [Encode::encode].
 pub enum IsNull {
@@ -18,8 +27,12 @@ pub enum IsNull {
 /// Encode a single value to be sent to the database.
 pub trait Encode<'q, DB: Database> {
     /// Writes the value of `self` into `buf` in the expected format for the database.
-    #[must_use]
-    fn encode(self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull
+    ///
+    /// On error, any data written to `buf` is discarded by the caller.
+    fn encode(
+        self,
+        buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer,
+    ) -> Result<IsNull, EncodeError>
     where
         Self: Sized,
     {
@@ -30,8 +43,10 @@ pub trait Encode<'q, DB: Database> {
     ///
     /// Where possible, make use of `encode` instead as it can take advantage of re-using
     /// memory.
-    #[must_use]
-    fn encode_by_ref(&self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull;
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer,
+    ) -> Result<IsNull, EncodeError>;
 
     fn produces(&self) -> Option<DB::TypeInfo> {
         // `produces` is inherently a hook to allow database drivers to produce value-dependent
@@ -50,12 +65,18 @@ where
     T: Encode<'q, DB>,
 {
     #[inline]
-    fn encode(self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+    fn encode(
+        self,
+        buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer,
+    ) -> Result<IsNull, EncodeError> {
         <T as Encode<DB>>::encode_by_ref(self, buf)
     }
 
     #[inline]
-    fn encode_by_ref(&self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer,
+    ) -> Result<IsNull, EncodeError> {
         <&T as Encode<DB>>::encode(self, buf)
     }
 
@@ -70,6 +91,130 @@ where
     }
 }
 
+/// A value to be encoded whose type is only known at runtime.
+///
+/// This is the object-safe part of [`Encode`] and [`Type`], so that values of different types
+/// can be bound from a single list, e.g. the values of a set of filters chosen by a user. It is
+/// implemented for every `T: Encode + Type + Send`; bind a boxed value with
+/// [`Query::bind_dyn`](crate::query::Query::bind_dyn), or see [`DynValue`] for the common
+/// scalars.
+pub trait DynEncode<'q, DB: Database>: Send {
+    /// The type of the value, as returned by [`Encode::produces`] or else [`Type::type_info`].
+    fn type_info(&self) -> DB::TypeInfo;
+
+    /// Writes the value into `buf`; see [`Encode::encode_by_ref`].
+    fn encode_dyn(
+        &self,
+        buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer,
+    ) -> Result<IsNull, EncodeError>;
+
+    /// See [`Encode::size_hint`].
+    fn size_hint(&self) -> usize;
+}
+
+impl<'q, DB: Database, T> DynEncode<'q, DB> for T
+where
+    T: Encode<'q, DB> + Type<DB> + Send,
+{
+    #[inline]
+    fn type_info(&self) -> DB::TypeInfo {
+        self.produces().unwrap_or_else(<T as Type<DB>>::type_info)
+    }
+
+    #[inline]
+    fn encode_dyn(
+        &self,
+        buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer,
+    ) -> Result<IsNull, EncodeError> {
+        self.encode_by_ref(buf)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        Encode::size_hint(self)
+    }
+}
+
+// the type of a boxed value is only known from the value, so it is always `produces`
+impl<'q, DB: Database> Encode<'q, DB> for Box<dyn DynEncode<'q, DB> + 'q> {
+    #[inline]
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer,
+    ) -> Result<IsNull, EncodeError> {
+        (**self).encode_dyn(buf)
+    }
+
+    #[inline]
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        Some((**self).type_info())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        (**self).size_hint()
+    }
+}
+
+/// One of the common scalar types, for binding values whose types are only known at runtime
+/// without boxing each of them by hand.
+///
+/// A `DynValue` converts into a `Box<dyn DynEncode<DB>>` for every database that can encode all
+/// of its variants, and so can be passed to [`Query::bind_dyn`](crate::query::Query::bind_dyn)
+/// as is. Integers are bound as `i64` and floats as `f64`, whatever their width. There is no
+/// variant for `NULL`, as it would have no type; bind a boxed `None::<T>` instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynValue {
+    Bool(bool),
+    I64(i64),
+    F64(f64),
+    String(String),
+}
+
+impl<'q, DB: Database> From<DynValue> for Box<dyn DynEncode<'q, DB> + 'q>
+where
+    bool: Encode<'q, DB> + Type<DB>,
+    i64: Encode<'q, DB> + Type<DB>,
+    f64: Encode<'q, DB> + Type<DB>,
+    String: Encode<'q, DB> + Type<DB>,
+{
+    fn from(value: DynValue) -> Self {
+        match value {
+            DynValue::Bool(v) => Box::new(v),
+            DynValue::I64(v) => Box::new(v),
+            DynValue::F64(v) => Box::new(v),
+            DynValue::String(v) => Box::new(v),
+        }
+    }
+}
+
+macro_rules! impl_from_for_dyn_value {
+    ($($ty:ty => $variant:ident,)*) => {
+        $(
+            impl From<$ty> for DynValue {
+                fn from(value: $ty) -> Self {
+                    DynValue::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_dyn_value!(
+    bool => Bool,
+    i8 => I64,
+    i16 => I64,
+    i32 => I64,
+    i64 => I64,
+    u8 => I64,
+    u16 => I64,
+    u32 => I64,
+    f32 => F64,
+    f64 => F64,
+    String => String,
+    &'_ str => String,
+);
+
 #[allow(unused_macros)]
 macro_rules! impl_encode_for_option {
     ($DB:ident) => {
@@ -90,11 +235,11 @@ macro_rules! impl_encode_for_option {
             fn encode(
                 self,
                 buf: &mut <$DB as crate::database::HasArguments<'q>>::ArgumentBuffer,
-            ) -> crate::encode::IsNull {
+            ) -> Result<crate::encode::IsNull, crate::encode::EncodeError> {
                 if let Some(v) = self {
                     v.encode(buf)
                 } else {
-                    crate::encode::IsNull::Yes
+                    Ok(crate::encode::IsNull::Yes)
                 }
             }
 
@@ -102,11 +247,11 @@ macro_rules! impl_encode_for_option {
             fn encode_by_ref(
                 &self,
                 buf: &mut <$DB as crate::database::HasArguments<'q>>::ArgumentBuffer,
-            ) -> crate::encode::IsNull {
+            ) -> Result<crate::encode::IsNull, crate::encode::EncodeError> {
                 if let Some(v) = self {
                     v.encode_by_ref(buf)
                 } else {
-                    crate::encode::IsNull::Yes
+                    Ok(crate::encode::IsNull::Yes)
                 }
             }
 
@@ -0,0 +1,633 @@
+//! An object-safe layer over [`Executor`] for code that cannot be generic over the database.
+//!
+//! Plugins, scripting hosts and other extension points usually need to hand a connection to
+//! code that was compiled without knowing which database it will talk to, and that may only
+//! hold it as a trait object. [`ErasedExecutor`] is object safe and is implemented for every
+//! connection, [`PoolConnection`](crate::pool::PoolConnection), [`Transaction`] and
+//! [`Pool`] whose database implements [`ErasedDatabase`], which the built-in drivers do.
+//!
+//! Arguments and column values are passed as [`ErasedValue`], which covers the scalars every
+//! database has. Binding a value the database cannot take, or fetching a column of any other
+//! type (e.g. dates or `NUMERIC`), is an error at runtime; cast such columns to text in the
+//! query instead.
+//!
+//! ```rust,no_run
+//! # async fn f(executor: &mut dyn sqlx_core::erased::ErasedExecutor) -> Result<(), sqlx_core::error::Error> {
+//! use sqlx_core::erased::ErasedArguments;
+//!
+//! let mut arguments = ErasedArguments::new();
+//! arguments.add(42_i64);
+//!
+//! let rows = executor
+//!     .fetch_all_erased("SELECT name FROM users WHERE id = $1", arguments)
+//!     .await?;
+//!
+//! for row in rows {
+//!     println!("{:?}", row.try_get("name")?.as_str());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Transaction`]: crate::transaction::Transaction
+
+use std::fmt::{self, Debug, Formatter};
+use std::iter::FromIterator;
+use std::sync::Arc;
+
+use either::Either;
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use futures_util::{future, stream, FutureExt, StreamExt, TryStreamExt};
+
+use crate::column::Column;
+use crate::database::{Database, HasArguments, HasStatement};
+use crate::error::Error;
+use crate::executor::{Execute, Executor};
+use crate::pool::Pool;
+use crate::row::Row;
+
+/// A value passed to or returned from an [`ErasedExecutor`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErasedValue {
+    /// SQL `NULL`.
+    Null,
+
+    Bool(bool),
+
+    /// Any integer type; unsigned values that do not fit are an error when fetched.
+    Int(i64),
+
+    /// Any floating point type.
+    Float(f64),
+
+    /// Any text type.
+    Text(String),
+
+    /// Any binary type.
+    Bytes(Vec<u8>),
+}
+
+impl ErasedValue {
+    /// The name of this kind of value, as used in error messages.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ErasedValue::Null => "NULL",
+            ErasedValue::Bool(_) => "boolean",
+            ErasedValue::Int(_) => "integer",
+            ErasedValue::Float(_) => "float",
+            ErasedValue::Text(_) => "text",
+            ErasedValue::Bytes(_) => "binary",
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, ErasedValue::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            ErasedValue::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            ErasedValue::Int(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns floats, and integers converted to floats.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            ErasedValue::Float(v) => Some(v),
+            ErasedValue::Int(v) => Some(v as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ErasedValue::Text(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns binary values, and the bytes of text values.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            ErasedValue::Bytes(v) => Some(v),
+            ErasedValue::Text(v) => Some(v.as_bytes()),
+            _ => None,
+        }
+    }
+
+    // the error for a value a driver cannot bind
+    #[cfg(any(feature = "mssql", feature = "any"))]
+    pub(crate) fn unsupported(&self, database: &str) -> Error {
+        Error::encode(format!(
+            "{} values cannot be bound in {}",
+            self.kind(),
+            database
+        ))
+    }
+}
+
+macro_rules! impl_from_for_erased_value {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$ty> for ErasedValue {
+                fn from(value: $ty) -> Self {
+                    ErasedValue::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_erased_value!(
+    bool => Bool,
+    i8 => Int,
+    i16 => Int,
+    i32 => Int,
+    i64 => Int,
+    u8 => Int,
+    u16 => Int,
+    u32 => Int,
+    f32 => Float,
+    f64 => Float,
+    String => Text,
+    &'_ str => Text,
+    Vec<u8> => Bytes,
+    &'_ [u8] => Bytes,
+);
+
+impl<T: Into<ErasedValue>> From<Option<T>> for ErasedValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(ErasedValue::Null, Into::into)
+    }
+}
+
+/// The bind parameters of an erased query, in order.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ErasedArguments {
+    values: Vec<ErasedValue>,
+}
+
+impl ErasedArguments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the value of the next bind parameter.
+    pub fn add(&mut self, value: impl Into<ErasedValue>) {
+        self.values.push(value.into());
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn values(&self) -> &[ErasedValue] {
+        &self.values
+    }
+}
+
+impl<T: Into<ErasedValue>> FromIterator<T> for ErasedArguments {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            values: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<T: Into<ErasedValue>> Extend<T> for ErasedArguments {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.values.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+/// The result of [`ErasedExecutor::execute_erased`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ErasedQueryResult {
+    pub(crate) rows_affected: u64,
+    pub(crate) last_insert_id: Option<i64>,
+}
+
+impl ErasedQueryResult {
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    /// The id of the last inserted row, for the databases that report one (MySQL and SQLite).
+    pub fn last_insert_id(&self) -> Option<i64> {
+        self.last_insert_id
+    }
+}
+
+/// A row returned by [`ErasedExecutor::fetch_erased`].
+#[derive(Clone, PartialEq)]
+pub struct ErasedRow {
+    columns: Arc<[String]>,
+    values: Vec<ErasedValue>,
+}
+
+impl ErasedRow {
+    /// The names of the columns, in order.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The value of a column, by position or by name.
+    ///
+    /// Like [`Row::try_get`], a name shared by several columns refers to the first of them.
+    pub fn try_get<I: ErasedColumnIndex>(&self, index: I) -> Result<&ErasedValue, Error> {
+        let index = index.index(self)?;
+
+        Ok(&self.values[index])
+    }
+
+    pub fn values(&self) -> &[ErasedValue] {
+        &self.values
+    }
+
+    pub fn into_values(self) -> Vec<ErasedValue> {
+        self.values
+    }
+}
+
+impl Debug for ErasedRow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("ErasedRow");
+
+        for (name, value) in self.columns.iter().zip(&self.values) {
+            s.field(name, value);
+        }
+
+        s.finish()
+    }
+}
+
+/// A column of an [`ErasedRow`]: its position as a `usize`, or its name as a `&str`.
+pub trait ErasedColumnIndex: private::Sealed {
+    fn index(&self, row: &ErasedRow) -> Result<usize, Error>;
+}
+
+impl ErasedColumnIndex for usize {
+    fn index(&self, row: &ErasedRow) -> Result<usize, Error> {
+        if *self >= row.len() {
+            return Err(Error::ColumnIndexOutOfBounds {
+                len: row.len(),
+                index: *self,
+            });
+        }
+
+        Ok(*self)
+    }
+}
+
+impl ErasedColumnIndex for &'_ str {
+    fn index(&self, row: &ErasedRow) -> Result<usize, Error> {
+        row.columns
+            .iter()
+            .position(|name| name == self)
+            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for usize {}
+    impl Sealed for &'_ str {}
+}
+
+/// A [`Database`] whose queries can be run through an [`ErasedExecutor`].
+///
+/// Implemented by each of the built-in drivers.
+pub trait ErasedDatabase: Database {
+    /// Binds `value` as the next argument, or fails if the database cannot take it.
+    fn add_erased(
+        arguments: &mut <Self as HasArguments<'_>>::Arguments,
+        value: ErasedValue,
+    ) -> Result<(), Error>;
+
+    /// Decodes the column at `index` of `row`, or fails if its type is not one of the kinds of
+    /// [`ErasedValue`].
+    fn get_erased(row: &Self::Row, index: usize) -> Result<ErasedValue, Error>;
+
+    fn erase_query_result(result: Self::QueryResult) -> ErasedQueryResult;
+}
+
+// the error for a column `ErasedDatabase::get_erased` cannot decode
+pub(crate) fn unsupported_column<R: Row>(row: &R, index: usize) -> Error {
+    use crate::type_info::TypeInfo;
+
+    let column = &row.columns()[index];
+
+    Error::column_decode(
+        column.name(),
+        format!(
+            "columns of type {} cannot be fetched through an erased executor",
+            column.type_info().name()
+        ),
+    )
+}
+
+/// An object-safe [`Executor`], running SQL with [`ErasedArguments`] and returning
+/// [`ErasedRow`]s.
+///
+/// Every statement is prepared and cached like a [`query()`](crate::query::query), with a bind
+/// parameter for each argument, written as `$1` in PostgreSQL, `@p1` in MSSQL and `?` in MySQL
+/// and SQLite.
+pub trait ErasedExecutor: Send + Debug {
+    /// Executes the statement and returns the number of rows affected.
+    fn execute_erased<'e>(
+        &'e mut self,
+        sql: &'e str,
+        arguments: ErasedArguments,
+    ) -> BoxFuture<'e, Result<ErasedQueryResult, Error>>;
+
+    /// Executes the statement and returns its rows as a stream.
+    fn fetch_erased<'e>(
+        &'e mut self,
+        sql: &'e str,
+        arguments: ErasedArguments,
+    ) -> BoxStream<'e, Result<ErasedRow, Error>>;
+
+    /// Executes the statements and returns the rows of each, followed by its result, as a
+    /// stream.
+    fn fetch_many_erased<'e>(
+        &'e mut self,
+        sql: &'e str,
+        arguments: ErasedArguments,
+    ) -> BoxStream<'e, Result<Either<ErasedQueryResult, ErasedRow>, Error>>;
+
+    /// Executes the statement and returns all of its rows.
+    fn fetch_all_erased<'e>(
+        &'e mut self,
+        sql: &'e str,
+        arguments: ErasedArguments,
+    ) -> BoxFuture<'e, Result<Vec<ErasedRow>, Error>> {
+        self.fetch_erased(sql, arguments).try_collect().boxed()
+    }
+
+    /// Executes the statement and returns its first row, if any.
+    fn fetch_optional_erased<'e>(
+        &'e mut self,
+        sql: &'e str,
+        arguments: ErasedArguments,
+    ) -> BoxFuture<'e, Result<Option<ErasedRow>, Error>> {
+        let mut rows = self.fetch_erased(sql, arguments);
+
+        Box::pin(async move { rows.try_next().await })
+    }
+}
+
+impl<T, DB> ErasedExecutor for T
+where
+    T: Send + Debug,
+    DB: ErasedDatabase,
+    for<'c> &'c mut T: Executor<'c, Database = DB>,
+{
+    fn execute_erased<'e>(
+        &'e mut self,
+        sql: &'e str,
+        arguments: ErasedArguments,
+    ) -> BoxFuture<'e, Result<ErasedQueryResult, Error>> {
+        execute(self, sql, arguments)
+    }
+
+    fn fetch_erased<'e>(
+        &'e mut self,
+        sql: &'e str,
+        arguments: ErasedArguments,
+    ) -> BoxStream<'e, Result<ErasedRow, Error>> {
+        fetch(self, sql, arguments)
+    }
+
+    fn fetch_many_erased<'e>(
+        &'e mut self,
+        sql: &'e str,
+        arguments: ErasedArguments,
+    ) -> BoxStream<'e, Result<Either<ErasedQueryResult, ErasedRow>, Error>> {
+        fetch_many(self, sql, arguments)
+    }
+}
+
+impl<DB> ErasedExecutor for Pool<DB>
+where
+    DB: ErasedDatabase,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    fn execute_erased<'e>(
+        &'e mut self,
+        sql: &'e str,
+        arguments: ErasedArguments,
+    ) -> BoxFuture<'e, Result<ErasedQueryResult, Error>> {
+        execute(&*self, sql, arguments)
+    }
+
+    fn fetch_erased<'e>(
+        &'e mut self,
+        sql: &'e str,
+        arguments: ErasedArguments,
+    ) -> BoxStream<'e, Result<ErasedRow, Error>> {
+        fetch(&*self, sql, arguments)
+    }
+
+    fn fetch_many_erased<'e>(
+        &'e mut self,
+        sql: &'e str,
+        arguments: ErasedArguments,
+    ) -> BoxStream<'e, Result<Either<ErasedQueryResult, ErasedRow>, Error>> {
+        fetch_many(&*self, sql, arguments)
+    }
+}
+
+fn execute<'e, E, DB>(
+    executor: E,
+    sql: &'e str,
+    arguments: ErasedArguments,
+) -> BoxFuture<'e, Result<ErasedQueryResult, Error>>
+where
+    E: 'e + Executor<'e, Database = DB>,
+    DB: ErasedDatabase,
+{
+    match ErasedQuery::new(sql, arguments) {
+        Ok(query) => executor
+            .execute(query)
+            .map(|result| result.map(DB::erase_query_result))
+            .boxed(),
+
+        Err(error) => future::ready(Err(error)).boxed(),
+    }
+}
+
+fn fetch<'e, E, DB>(
+    executor: E,
+    sql: &'e str,
+    arguments: ErasedArguments,
+) -> BoxStream<'e, Result<ErasedRow, Error>>
+where
+    E: 'e + Executor<'e, Database = DB>,
+    DB: ErasedDatabase,
+{
+    let query = match ErasedQuery::new(sql, arguments) {
+        Ok(query) => query,
+        Err(error) => return stream::once(future::ready(Err(error))).boxed(),
+    };
+
+    // the column names are shared by consecutive rows with the same columns
+    let mut columns: Option<Arc<[String]>> = None;
+
+    executor
+        .fetch(query)
+        .map(move |row| erase_row::<DB>(&row?, &mut columns))
+        .boxed()
+}
+
+fn fetch_many<'e, E, DB>(
+    executor: E,
+    sql: &'e str,
+    arguments: ErasedArguments,
+) -> BoxStream<'e, Result<Either<ErasedQueryResult, ErasedRow>, Error>>
+where
+    E: 'e + Executor<'e, Database = DB>,
+    DB: ErasedDatabase,
+{
+    let query = match ErasedQuery::new(sql, arguments) {
+        Ok(query) => query,
+        Err(error) => return stream::once(future::ready(Err(error))).boxed(),
+    };
+
+    let mut columns: Option<Arc<[String]>> = None;
+
+    executor
+        .fetch_many(query)
+        .map(move |step| match step? {
+            Either::Left(result) => Ok(Either::Left(DB::erase_query_result(result))),
+            Either::Right(row) => erase_row::<DB>(&row, &mut columns).map(Either::Right),
+        })
+        .boxed()
+}
+
+fn erase_row<DB: ErasedDatabase>(
+    row: &DB::Row,
+    columns: &mut Option<Arc<[String]>>,
+) -> Result<ErasedRow, Error> {
+    let row_columns = row.columns();
+
+    let same_columns = match columns {
+        Some(columns) => {
+            columns.len() == row_columns.len()
+                && columns
+                    .iter()
+                    .zip(row_columns)
+                    .all(|(name, column)| name == column.name())
+        }
+
+        None => false,
+    };
+
+    if !same_columns {
+        *columns = Some(row_columns.iter().map(|c| c.name().to_owned()).collect());
+    }
+
+    let values = (0..row_columns.len())
+        .map(|index| DB::get_erased(row, index))
+        .collect::<Result<_, _>>()?;
+
+    Ok(ErasedRow {
+        columns: columns.clone().unwrap(),
+        values,
+    })
+}
+
+// a prepared query with the arguments already bound for its database
+struct ErasedQuery<'q, DB: Database> {
+    sql: &'q str,
+    arguments: Option<<DB as HasArguments<'q>>::Arguments>,
+}
+
+impl<'q, DB: ErasedDatabase> ErasedQuery<'q, DB> {
+    fn new(sql: &'q str, arguments: ErasedArguments) -> Result<Self, Error> {
+        let mut bound = <DB as HasArguments<'q>>::Arguments::default();
+
+        for value in arguments.values {
+            DB::add_erased(&mut bound, value)?;
+        }
+
+        Ok(Self {
+            sql,
+            arguments: Some(bound),
+        })
+    }
+}
+
+impl<'q, DB: Database> Execute<'q, DB> for ErasedQuery<'q, DB> {
+    #[inline]
+    fn sql(&self) -> &'q str {
+        self.sql
+    }
+
+    #[inline]
+    fn statement(&self) -> Option<&<DB as HasStatement<'q>>::Statement> {
+        None
+    }
+
+    #[inline]
+    fn take_arguments(&mut self) -> Option<<DB as HasArguments<'q>>::Arguments> {
+        self.arguments.take()
+    }
+
+    #[inline]
+    fn persistent(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn it_finds_erased_columns_by_name_and_position() {
+    let row = ErasedRow {
+        columns: vec!["id".to_owned(), "name".to_owned(), "id".to_owned()].into(),
+        values: vec![ErasedValue::Int(1), "ferris".into(), ErasedValue::Int(2)],
+    };
+
+    assert_eq!(row.try_get("id").unwrap(), &ErasedValue::Int(1));
+    assert_eq!(row.try_get(1).unwrap().as_str(), Some("ferris"));
+    assert_eq!(row.try_get(2).unwrap().as_i64(), Some(2));
+
+    assert!(matches!(
+        row.try_get(3),
+        Err(Error::ColumnIndexOutOfBounds { index: 3, len: 3 })
+    ));
+    assert!(matches!(
+        row.try_get("email"),
+        Err(Error::ColumnNotFound(_))
+    ));
+}
+
+#[test]
+fn it_converts_options_to_erased_nulls() {
+    let arguments: ErasedArguments = vec![Some(1_i32), None].into_iter().collect();
+
+    assert_eq!(
+        arguments.values(),
+        &[ErasedValue::Int(1), ErasedValue::Null]
+    );
+}
@@ -1,10 +1,11 @@
 //! Types for working with errors produced by SQLx.
 
-use std::any::type_name;
+use std::any::{type_name, Any};
 use std::borrow::Cow;
 use std::error::Error as StdError;
-use std::fmt::Display;
+use std::fmt::{self, Debug, Display, Formatter};
 use std::io;
+use std::panic::{self, AssertUnwindSafe};
 use std::result::Result as StdResult;
 
 use crate::database::Database;
@@ -26,6 +27,190 @@ pub type BoxDynError = Box<dyn StdError + 'static + Send + Sync>;
 #[error("unexpected null; try decoding as an `Option`")]
 pub struct UnexpectedNullError;
 
+/// A problem with a single configuration value.
+///
+/// Returned, together with any other problems found, from
+/// [`ConnectOptions::validate`](crate::connection::ConnectOptions::validate) and
+/// [`PoolOptions::validate`](crate::pool::PoolOptions::validate).
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid `{option}`: {message}")]
+pub struct ConfigError {
+    option: &'static str,
+    message: Cow<'static, str>,
+}
+
+impl ConfigError {
+    #[allow(dead_code)]
+    pub(crate) fn new(option: &'static str, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            option,
+            message: message.into(),
+        }
+    }
+
+    /// The name of the offending option, e.g. `port`.
+    pub fn option(&self) -> &str {
+        self.option
+    }
+
+    /// A description of what is wrong with the option.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// A result column that does not match the field of a [`FromRow`](crate::from_row::FromRow)
+/// type it is read into.
+///
+/// Returned, together with any other mismatches in the same result, in
+/// [`Error::TypeValidation`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{}", self.describe())]
+pub struct ColumnMismatch {
+    column: String,
+    rust_type: &'static str,
+    expected: String,
+    found: Option<String>,
+}
+
+impl ColumnMismatch {
+    pub(crate) fn new(
+        column: String,
+        rust_type: &'static str,
+        expected: String,
+        found: Option<String>,
+    ) -> Self {
+        Self {
+            column,
+            rust_type,
+            expected,
+            found,
+        }
+    }
+
+    /// The name of the column, or its position if it is read by position.
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    /// The name of the Rust type the column is read into.
+    pub fn rust_type(&self) -> &str {
+        self.rust_type
+    }
+
+    /// The name of the SQL type of the Rust type.
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    /// The name of the SQL type of the column, or `None` if the result has no such column.
+    pub fn found(&self) -> Option<&str> {
+        self.found.as_deref()
+    }
+
+    fn describe(&self) -> String {
+        match &self.found {
+            Some(found) => format!(
+                "column `{}`: Rust type `{}` (as SQL type `{}`) is not compatible with SQL type `{}`",
+                self.column, self.rust_type, self.expected, found
+            ),
+
+            None => format!(
+                "column `{}`: not found, but read into Rust type `{}`",
+                self.column, self.rust_type
+            ),
+        }
+    }
+}
+
+/// The errors of an operation made up of several, such as opening each of the connections of a
+/// pool, each labeled with the part it came from, e.g. `connection 2` or `migration 3`.
+///
+/// Returned in [`Error::Aggregate`] when more than one part failed; a single failure is returned
+/// as it is. [`source`](StdError::source) is the first error, so walking the chain of sources
+/// finds what went wrong first.
+#[derive(Debug)]
+pub struct AggregateError {
+    what: &'static str,
+    attempted: usize,
+    errors: Vec<(String, Error)>,
+}
+
+impl AggregateError {
+    // `what` names the parts in the plural, e.g. `connections`
+    pub(crate) fn new(what: &'static str, attempted: usize) -> Self {
+        Self {
+            what,
+            attempted,
+            errors: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, label: impl Into<String>, error: Error) {
+        self.errors.push((label.into(), error));
+    }
+
+    // `Ok` if nothing failed, the error itself if one part did
+    pub(crate) fn into_result(mut self) -> Result<()> {
+        match self.errors.len() {
+            0 => Ok(()),
+            1 => Err(self.errors.pop().unwrap().1),
+            _ => Err(Error::Aggregate(self)),
+        }
+    }
+
+    /// The number of parts that were attempted, including those that succeeded.
+    pub fn attempted(&self) -> usize {
+        self.attempted
+    }
+
+    /// The label and the error of each part that failed, in the order the parts were attempted.
+    pub fn errors(&self) -> &[(String, Error)] {
+        &self.errors
+    }
+
+    /// Returns the label and the error of each part that failed.
+    pub fn into_errors(self) -> Vec<(String, Error)> {
+        self.errors
+    }
+}
+
+impl Display for AggregateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} {} failed:",
+            self.errors.len(),
+            self.attempted,
+            self.what
+        )?;
+
+        for (label, error) in &self.errors {
+            write!(f, "\n  {}: ", label)?;
+
+            // errors of more than one line, e.g. nested aggregates, stay under their label
+            let message = error.to_string();
+            let mut lines = message.lines();
+
+            f.write_str(lines.next().unwrap_or_default())?;
+
+            for line in lines {
+                write!(f, "\n    {}", line)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl StdError for AggregateError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.errors
+            .first()
+            .map(|(_, error)| error as &(dyn StdError + 'static))
+    }
+}
+
 /// Represents all the ways a method can fail within SQLx.
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
@@ -53,10 +238,32 @@ pub enum Error {
     #[error("encountered unexpected or invalid data: {0}")]
     Protocol(String),
 
+    /// The server asked to authenticate with a method the driver does not implement.
+    #[error("server requested unsupported authentication method {method}: {hint}")]
+    UnsupportedAuthMethod {
+        /// The method the server asked for, e.g. `GSSAPI`.
+        method: &'static str,
+
+        /// What the driver does support.
+        hint: &'static str,
+    },
+
+    /// The server tried to weaken the security of the connection further than the
+    /// [`ProtocolPolicy`](crate::connection::ProtocolPolicy) of the connect options allows.
+    ///
+    /// Describes what the server attempted.
+    #[error("connection aborted by protocol policy: {0}")]
+    PolicyViolation(String),
+
     /// No rows returned by a query that expected to return at least one row.
     #[error("no rows returned by a query that expected to return at least one row")]
     RowNotFound,
 
+    /// An insert that was expected to report the id of the row it inserted did not, e.g.
+    /// because the table has no `AUTO_INCREMENT` column or the statement was not an insert.
+    #[error("statement did not report the id of an inserted row")]
+    InsertIdNotFound,
+
     /// Type in query doesn't exist. Likely due to typo or missing user type.
     #[error("type named {type_name} not found")]
     TypeNotFound { type_name: String },
@@ -69,6 +276,17 @@ pub enum Error {
     #[error("no column found for name: {0}")]
     ColumnNotFound(String),
 
+    /// No column has exactly the given name, and more than one matches it when case, or case
+    /// and underscores, are ignored; see [`ColumnNameMatching`](crate::column::ColumnNameMatching).
+    #[error(
+        "column name {name} is ambiguous: it matches each of {}",
+        .candidates.join(", ")
+    )]
+    ColumnAmbiguous {
+        name: String,
+        candidates: Vec<String>,
+    },
+
     /// Error occurred while decoding a value from a specific column.
     #[error("error occurred while decoding column {index}: {source}")]
     ColumnDecode {
@@ -78,10 +296,91 @@ pub enum Error {
         source: BoxDynError,
     },
 
+    /// The columns of a result do not match the [`FromRow`](crate::from_row::FromRow) type it
+    /// is read into.
+    ///
+    /// Returned from [`QueryAs::fetch_checked`](crate::query_as::QueryAs::fetch_checked) before
+    /// any row is decoded; lists every column that is missing or has an incompatible type.
+    #[error(
+        "result does not match the type it is read into: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    TypeValidation(Vec<ColumnMismatch>),
+
     /// Error occurred while decoding a value.
     #[error("error occurred while decoding: {0}")]
     Decode(#[source] BoxDynError),
 
+    /// A value could not be bound to a query, e.g. because of its type.
+    ///
+    /// This is checked before anything is sent so the connection remains usable.
+    #[error("error occurred while encoding an argument: {0}")]
+    Encode(#[source] BoxDynError),
+
+    /// A value bound to a query could not be encoded, e.g. because it is out of the range of the
+    /// database type.
+    ///
+    /// `index` is the position of the argument, starting at 0. This is checked before anything
+    /// is sent so the connection remains usable.
+    #[error("error occurred while encoding argument {index}: {source}")]
+    ArgumentEncode {
+        index: usize,
+
+        #[source]
+        source: BoxDynError,
+    },
+
+    /// More values were bound to a query than the database protocol can carry.
+    ///
+    /// This is checked before anything is sent so the connection remains usable.
+    #[error("too many arguments bound to query: given {given}, but the limit is {limit}")]
+    TooManyArguments { given: usize, limit: usize },
+
+    /// A statement was rejected by a connection opened with `read_only(true)` because it may
+    /// write, or because it is followed by another statement.
+    ///
+    /// Contains the beginning of the offending statement. This is checked before anything is
+    /// sent so the connection remains usable.
+    #[error("statement rejected by read-only connection: {0:?}")]
+    ReadOnlyViolation(String),
+
+    /// A query was aborted by the [`QueryMiddleware`](crate::middleware::QueryMiddleware) of the
+    /// connection, with the error it returned.
+    ///
+    /// This is checked before anything is sent so the connection remains usable.
+    #[error("query rejected by middleware: {0}")]
+    QueryRejected(#[source] BoxDynError),
+
+    /// A value of the result of a query was larger than its
+    /// [`max_value_size`](crate::query::Query::max_value_size), or a row larger than its
+    /// [`max_row_size`](crate::query::Query::max_row_size).
+    ///
+    /// `column` is the index of the value, or `None` for a row as a whole; `size` and `limit`
+    /// are in bytes. The rest of the result is discarded without being decoded, so the
+    /// connection remains usable.
+    #[error(
+        "{} of {size} bytes is larger than the limit of {limit} bytes",
+        match column {
+            Some(column) => format!("value of column {}", column),
+            None => "row".to_owned(),
+        }
+    )]
+    ValueTooLarge {
+        column: Option<usize>,
+        size: usize,
+        limit: usize,
+    },
+
+    /// An operation was started on a connection while another was still in progress.
+    ///
+    /// Contains the name of the operation in progress. An operation borrows its connection
+    /// mutably until it is done, so the future or stream of that operation was leaked part way
+    /// through (e.g. with [`std::mem::forget`]), or is being polled through an alias created
+    /// with unsafe code; the connection cannot be used again. Dropping a future or stream before
+    /// it is done is fine: the connection catches up with the server before its next command.
+    #[error("connection is busy with `{operation}`, which was started and not finished")]
+    ConnectionBusy { operation: &'static str },
+
     /// A [`Pool::acquire`] timed out due to connections not becoming available or
     /// because another task encountered too many errors while trying to open a new connection.
     ///
@@ -89,6 +388,17 @@ pub enum Error {
     #[error("pool timed out while waiting for an open connection")]
     PoolTimedOut,
 
+    /// A [`Pool::acquire`] was rejected by the pool's
+    /// [`admission_policy`](crate::pool::PoolOptions::admission_policy) instead of waiting for
+    /// a connection, as the pool is overloaded.
+    ///
+    /// Not [retryable](Self::is_retryable): trying again right away adds to the load that
+    /// is being shed.
+    ///
+    /// [`Pool::acquire`]: crate::pool::Pool::acquire
+    #[error("pool is overloaded and rejected the request for a connection")]
+    PoolOverloaded,
+
     /// [`Pool::close`] was called while we were waiting in [`Pool::acquire`].
     ///
     /// [`Pool::acquire`]: crate::pool::Pool::acquire
@@ -100,6 +410,50 @@ pub enum Error {
     #[error("attempted to communicate with a crashed background worker")]
     WorkerCrashed,
 
+    /// A database could not be created because a database of that name already exists.
+    ///
+    /// Returned from [`MigrateDatabase::create_database`](crate::migrate::MigrateDatabase).
+    #[error("database {0:?} already exists")]
+    DatabaseExists(String),
+
+    /// The user is not permitted to create or drop the database.
+    ///
+    /// Returned from [`MigrateDatabase`](crate::migrate::MigrateDatabase), with the error the
+    /// database returned.
+    #[error("not permitted to create or drop database: {0}")]
+    DatabasePermissionDenied(Box<dyn DatabaseError>),
+
+    /// Code called by SQLx on behalf of the application, such as a [`Decode`] or
+    /// [`FromRow`](crate::from_row::FromRow) implementation or a pool hook, panicked.
+    ///
+    /// Contains the message of the panic. The panic is caught where the connection is not in
+    /// the middle of a response, so the connection can still be used.
+    ///
+    /// [`Decode`]: crate::decode::Decode
+    #[error("panicked: {0}")]
+    Panic(String),
+
+    /// A statement of a batch failed, so the statements after it were not executed.
+    ///
+    /// `index` is the position of the statement in the batch, starting at 0, and `source` its
+    /// error. Is a [connection error](Self::is_connection_error) or
+    /// [retryable](Self::is_retryable) if `source` is.
+    #[error("statement {index} of batch failed: {source}")]
+    Batch {
+        index: usize,
+
+        #[source]
+        source: Box<Error>,
+    },
+
+    /// More than one part of an operation made up of several failed, e.g. more than one of the
+    /// connections [`PoolOptions::connect`](crate::pool::PoolOptions::connect) opens, or more
+    /// than one of the migrations a [`Migrator`](crate::migrate::Migrator) checks.
+    ///
+    /// Contains the error of each part, labeled; see [`AggregateError`].
+    #[error(transparent)]
+    Aggregate(AggregateError),
+
     #[cfg(feature = "migrate")]
     #[error("{0}")]
     Migrate(#[source] Box<crate::migrate::MigrateError>),
@@ -120,17 +474,169 @@ impl Error {
         }
     }
 
+    /// Returns `true` if the connection this error came from is broken and should be replaced,
+    /// e.g. because the network connection was reset, the server is shutting down, or the
+    /// protocol is out of sync.
+    pub fn is_connection_error(&self) -> bool {
+        match self {
+            Error::Io(err) => is_connection_io_error(err),
+            Error::Tls(_)
+            | Error::Protocol(_)
+            | Error::WorkerCrashed
+            | Error::ConnectionBusy { .. } => true,
+            Error::Database(err) => err.is_connection_error(),
+            Error::Batch { source, .. } => source.is_connection_error(),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if trying the same operation again, on a new connection if
+    /// [`is_connection_error`](Self::is_connection_error) is also `true`, may succeed.
+    ///
+    /// This includes connection errors (other than TLS failures, which are usually caused by
+    /// configuration), serialization failures and deadlocks, and a pool that timed out.
+    /// Errors caused by the statement itself, such as syntax errors or constraint violations,
+    /// are not retryable. An [`Error::Aggregate`] is retryable if each of its errors is.
+    ///
+    /// Note that a statement may have been executed when a connection error is returned;
+    /// only retry statements that are safe to run twice.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Io(err) => is_connection_io_error(err),
+            Error::Protocol(_)
+            | Error::WorkerCrashed
+            | Error::ConnectionBusy { .. }
+            | Error::PoolTimedOut => true,
+            Error::Database(err) => err.is_retryable(),
+            Error::Aggregate(err) => err.errors.iter().all(|(_, err)| err.is_retryable()),
+            Error::Batch { source, .. } => source.is_retryable(),
+            _ => false,
+        }
+    }
+
+    // the constructors below are what drivers build these errors with, rather than the
+    // variants themselves
+
+    /// With the `error-location` feature, the message ends with the location in SQLx that
+    /// raised the error, as these are most often bugs in a driver.
     #[allow(dead_code)]
-    #[inline]
+    #[track_caller]
     pub(crate) fn protocol(err: impl Display) -> Self {
-        Error::Protocol(err.to_string())
+        #[cfg(feature = "error-location")]
+        {
+            Error::Protocol(format!("{} (at {})", err, panic::Location::caller()))
+        }
+
+        #[cfg(not(feature = "error-location"))]
+        {
+            Error::Protocol(err.to_string())
+        }
     }
 
     #[allow(dead_code)]
     #[inline]
-    pub(crate) fn config(err: impl StdError + Send + Sync + 'static) -> Self {
+    pub(crate) fn config(err: impl Into<BoxDynError>) -> Self {
         Error::Configuration(err.into())
     }
+
+    #[allow(dead_code)]
+    #[inline]
+    pub(crate) fn tls(err: impl Into<BoxDynError>) -> Self {
+        Error::Tls(err.into())
+    }
+
+    #[allow(dead_code)]
+    #[inline]
+    pub(crate) fn decode(err: impl Into<BoxDynError>) -> Self {
+        Error::Decode(err.into())
+    }
+
+    #[allow(dead_code)]
+    #[inline]
+    pub(crate) fn encode(err: impl Into<BoxDynError>) -> Self {
+        Error::Encode(err.into())
+    }
+
+    // `index` is the name or the position of the column, written as a literal
+    #[allow(dead_code)]
+    #[inline]
+    pub(crate) fn column_decode(index: impl Debug, err: impl Into<BoxDynError>) -> Self {
+        Error::ColumnDecode {
+            index: format!("{:?}", index),
+            source: err.into(),
+        }
+    }
+}
+
+/// Calls `f`, turning a panic into [`Error::Panic`] with the message of the panic.
+pub(crate) fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| Error::Panic(panic_message(payload)))
+}
+
+/// The message of a panic, from the payload [`catch_unwind`](panic::catch_unwind) returned.
+pub(crate) fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => (*message).to_owned(),
+            Err(_) => "Box<dyn Any>".to_owned(),
+        },
+    }
+}
+
+fn is_connection_io_error(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::UnexpectedEof
+    )
+}
+
+/// How an error is treated by [`Error::is_connection_error`] and [`Error::is_retryable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct ErrorClass {
+    pub(crate) connection: bool,
+    pub(crate) retryable: bool,
+}
+
+// SQLSTATE codes, or two-character classes, that are not plain statement errors;
+// the first match wins and anything else is neither a connection error nor retryable
+const SQLSTATE_CLASSES: &[(&str, ErrorClass)] = &[
+    // connection_exception
+    ("08", ErrorClass::CONNECTION),
+    // serialization_failure
+    ("40001", ErrorClass::RETRYABLE),
+    // deadlock_detected
+    ("40P01", ErrorClass::RETRYABLE),
+    // admin_shutdown, crash_shutdown, cannot_connect_now
+    ("57P01", ErrorClass::CONNECTION),
+    ("57P02", ErrorClass::CONNECTION),
+    ("57P03", ErrorClass::CONNECTION),
+];
+
+impl ErrorClass {
+    pub(crate) const CONNECTION: Self = ErrorClass {
+        connection: true,
+        retryable: true,
+    };
+
+    pub(crate) const RETRYABLE: Self = ErrorClass {
+        connection: false,
+        retryable: true,
+    };
+
+    pub(crate) fn from_sqlstate(code: &str) -> Self {
+        SQLSTATE_CLASSES
+            .iter()
+            .find(|(prefix, _)| code.starts_with(prefix))
+            .map(|(_, class)| *class)
+            .unwrap_or_default()
+    }
 }
 
 pub(crate) fn mismatched_types<DB: Database, T: Type<DB>>(ty: &DB::TypeInfo) -> BoxDynError {
@@ -171,6 +677,27 @@ pub trait DatabaseError: 'static + Send + Sync + StdError {
     fn constraint(&self) -> Option<&str> {
         None
     }
+
+    /// Returns `true` if this error means the connection can no longer be used;
+    /// see [`Error::is_connection_error`].
+    ///
+    /// The default implementation classifies the SQLSTATE [`code`](Self::code), e.g. any
+    /// `08xxx` code or `57P01` (the server is shutting down). Drivers whose errors carry more
+    /// specific information override it.
+    fn is_connection_error(&self) -> bool {
+        self.code()
+            .map_or(false, |code| ErrorClass::from_sqlstate(&code).connection)
+    }
+
+    /// Returns `true` if the statement may succeed if it is tried again;
+    /// see [`Error::is_retryable`].
+    ///
+    /// The default implementation classifies the SQLSTATE [`code`](Self::code), e.g.
+    /// `40001` (serialization failure) or `40P01` (deadlock).
+    fn is_retryable(&self) -> bool {
+        self.code()
+            .map_or(false, |code| ErrorClass::from_sqlstate(&code).retryable)
+    }
 }
 
 impl dyn DatabaseError {
@@ -262,10 +789,398 @@ impl From<webpki::InvalidDNSNameError> for Error {
 // Format an error message as a `Protocol` error
 macro_rules! err_protocol {
     ($expr:expr) => {
-        $crate::error::Error::Protocol($expr.into())
+        $crate::error::Error::protocol($expr)
     };
 
     ($fmt:expr, $($arg:tt)*) => {
-        $crate::error::Error::Protocol(format!($fmt, $($arg)*))
+        $crate::error::Error::protocol(format_args!($fmt, $($arg)*))
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("{0}")]
+    struct SqlStateError(&'static str);
+
+    impl DatabaseError for SqlStateError {
+        fn message(&self) -> &str {
+            self.0
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(self.0.into())
+        }
+
+        fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+            self
+        }
+    }
+
+    fn io(kind: io::ErrorKind) -> Error {
+        io::Error::new(kind, "test").into()
+    }
+
+    fn aggregate(errors: Vec<Error>) -> Error {
+        let mut aggregate = AggregateError::new("connections", errors.len());
+
+        for (index, error) in errors.into_iter().enumerate() {
+            aggregate.push(format!("connection {}", index + 1), error);
+        }
+
+        Error::Aggregate(aggregate)
+    }
+
+    #[test]
+    fn it_classifies_errors() {
+        // (error, is_connection_error, is_retryable)
+        let errors = vec![
+            (io(io::ErrorKind::BrokenPipe), true, true),
+            (io(io::ErrorKind::ConnectionReset), true, true),
+            (io(io::ErrorKind::ConnectionRefused), true, true),
+            (io(io::ErrorKind::TimedOut), true, true),
+            (io(io::ErrorKind::UnexpectedEof), true, true),
+            (io(io::ErrorKind::PermissionDenied), false, false),
+            (err_protocol!("unexpected message"), true, true),
+            (Error::Tls("bad certificate".into()), true, false),
+            (Error::PoolTimedOut, false, true),
+            (Error::PoolOverloaded, false, false),
+            (Error::PoolClosed, false, false),
+            (Error::RowNotFound, false, false),
+            (Error::Decode("bad value".into()), false, false),
+            (Error::Panic("in decode".into()), false, false),
+            (
+                aggregate(vec![
+                    io(io::ErrorKind::ConnectionRefused),
+                    Error::PoolTimedOut,
+                ]),
+                false,
+                true,
+            ),
+            (
+                aggregate(vec![
+                    io(io::ErrorKind::ConnectionRefused),
+                    Error::PoolClosed,
+                ]),
+                false,
+                false,
+            ),
+            (
+                Error::Batch {
+                    index: 3,
+                    source: Box::new(SqlStateError("40001").into()),
+                },
+                false,
+                true,
+            ),
+            (
+                Error::Batch {
+                    index: 3,
+                    source: Box::new(SqlStateError("23505").into()),
+                },
+                false,
+                false,
+            ),
+            (SqlStateError("08006").into(), true, true),
+            (SqlStateError("08P01").into(), true, true),
+            (SqlStateError("40001").into(), false, true),
+            (SqlStateError("40P01").into(), false, true),
+            (SqlStateError("57P01").into(), true, true),
+            (SqlStateError("57014").into(), false, false),
+            (SqlStateError("23505").into(), false, false),
+            (SqlStateError("23503").into(), false, false),
+            (SqlStateError("42601").into(), false, false),
+            (SqlStateError("22012").into(), false, false),
+        ];
+
+        for (error, connection, retryable) in errors {
+            assert_eq!(error.is_connection_error(), connection, "{:?}", error);
+            assert_eq!(error.is_retryable(), retryable, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn it_labels_each_error_of_an_aggregate() {
+        let mut errors = AggregateError::new("connections", 3);
+        errors.push("connection 1", io(io::ErrorKind::ConnectionRefused));
+        errors.push(
+            "connection 3",
+            Error::Protocol("first line\nsecond line".into()),
+        );
+
+        let error = errors.into_result().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "2 of 3 connections failed:\n  \
+             connection 1: error communicating with the server: test\n  \
+             connection 3: encountered unexpected or invalid data: first line\n    \
+             second line"
+        );
+
+        // the first error, for code that walks the chain of sources
+        let source = error.source().unwrap().downcast_ref::<Error>().unwrap();
+        assert!(matches!(source, Error::Io(_)));
+
+        match error {
+            Error::Aggregate(errors) => {
+                assert_eq!(errors.attempted(), 3);
+
+                let labels: Vec<&str> = errors.errors().iter().map(|(l, _)| &**l).collect();
+                assert_eq!(labels, ["connection 1", "connection 3"]);
+            }
+
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        // one failure is returned as it is
+        let mut errors = AggregateError::new("migrations", 2);
+        errors.push("migration 2", Error::PoolClosed);
+        assert!(matches!(errors.into_result(), Err(Error::PoolClosed)));
+
+        assert!(AggregateError::new("migrations", 2).into_result().is_ok());
+    }
+
+    // applications match on these messages, e.g. in log alerts, so a change to one is a
+    // breaking change, to be made on purpose
+    #[test]
+    fn it_keeps_the_display_of_each_variant() {
+        let mut aggregate = AggregateError::new("connections", 2);
+        aggregate.push("connection 1", Error::PoolClosed);
+        aggregate.push("connection 2", Error::RowNotFound);
+
+        let errors =
+            vec![
+            (
+                Error::Configuration("no port".into()),
+                "error with configuration: no port",
+            ),
+            (
+                SqlStateError("23505").into(),
+                "error returned from database: 23505",
+            ),
+            (io(io::ErrorKind::BrokenPipe), "error communicating with the server: test"),
+            (
+                Error::Tls("bad certificate".into()),
+                "error occurred while attempting to establish a TLS connection: bad certificate",
+            ),
+            (
+                Error::Protocol("unexpected message".into()),
+                "encountered unexpected or invalid data: unexpected message",
+            ),
+            (
+                Error::UnsupportedAuthMethod {
+                    method: "GSSAPI",
+                    hint: "use a password",
+                },
+                "server requested unsupported authentication method GSSAPI: use a password",
+            ),
+            (
+                Error::PolicyViolation("TLS was refused".into()),
+                "connection aborted by protocol policy: TLS was refused",
+            ),
+            (
+                Error::RowNotFound,
+                "no rows returned by a query that expected to return at least one row",
+            ),
+            (
+                Error::InsertIdNotFound,
+                "statement did not report the id of an inserted row",
+            ),
+            (
+                Error::TypeNotFound {
+                    type_name: "mood".into(),
+                },
+                "type named mood not found",
+            ),
+            (
+                Error::ColumnIndexOutOfBounds { index: 3, len: 2 },
+                "column index out of bounds: the len is 2, but the index is 3",
+            ),
+            (
+                Error::ColumnNotFound("name".into()),
+                "no column found for name: name",
+            ),
+            (
+                Error::ColumnAmbiguous {
+                    name: "userId".into(),
+                    candidates: vec!["userid".into(), "USERID".into()],
+                },
+                "column name userId is ambiguous: it matches each of userid, USERID",
+            ),
+            (
+                Error::column_decode("name", UnexpectedNullError),
+                "error occurred while decoding column \"name\": \
+                 unexpected null; try decoding as an `Option`",
+            ),
+            (
+                Error::TypeValidation(vec![
+                    ColumnMismatch::new("id".into(), "i32", "INT4".into(), Some("TEXT".into())),
+                    ColumnMismatch::new("name".into(), "String", "TEXT".into(), None),
+                ]),
+                "result does not match the type it is read into: \
+                 column `id`: Rust type `i32` (as SQL type `INT4`) is not compatible with SQL \
+                 type `TEXT`; column `name`: not found, but read into Rust type `String`",
+            ),
+            (
+                Error::decode("bad value"),
+                "error occurred while decoding: bad value",
+            ),
+            (
+                Error::encode("bad value"),
+                "error occurred while encoding an argument: bad value",
+            ),
+            (
+                Error::ArgumentEncode {
+                    index: 1,
+                    source: "out of range".into(),
+                },
+                "error occurred while encoding argument 1: out of range",
+            ),
+            (
+                Error::TooManyArguments {
+                    given: 70000,
+                    limit: 65535,
+                },
+                "too many arguments bound to query: given 70000, but the limit is 65535",
+            ),
+            (
+                Error::ReadOnlyViolation("DELETE FROM users".into()),
+                "statement rejected by read-only connection: \"DELETE FROM users\"",
+            ),
+            (
+                Error::QueryRejected("not allowed".into()),
+                "query rejected by middleware: not allowed",
+            ),
+            (
+                Error::ValueTooLarge {
+                    column: Some(2),
+                    size: 2048,
+                    limit: 1024,
+                },
+                "value of column 2 of 2048 bytes is larger than the limit of 1024 bytes",
+            ),
+            (
+                Error::ValueTooLarge {
+                    column: None,
+                    size: 2048,
+                    limit: 1024,
+                },
+                "row of 2048 bytes is larger than the limit of 1024 bytes",
+            ),
+            (
+                Error::ConnectionBusy {
+                    operation: "fetch_many",
+                },
+                "connection is busy with `fetch_many`, which was started and not finished",
+            ),
+            (
+                Error::PoolTimedOut,
+                "pool timed out while waiting for an open connection",
+            ),
+            (
+                Error::PoolOverloaded,
+                "pool is overloaded and rejected the request for a connection",
+            ),
+            (
+                Error::PoolClosed,
+                "attempted to acquire a connection on a closed pool",
+            ),
+            (
+                Error::WorkerCrashed,
+                "attempted to communicate with a crashed background worker",
+            ),
+            (
+                Error::DatabaseExists("app".into()),
+                "database \"app\" already exists",
+            ),
+            (
+                Error::DatabasePermissionDenied(Box::new(SqlStateError("42501"))),
+                "not permitted to create or drop database: 42501",
+            ),
+            (Error::Panic("in decode".into()), "panicked: in decode"),
+            (
+                Error::Batch {
+                    index: 3,
+                    source: Box::new(SqlStateError("23505").into()),
+                },
+                "statement 3 of batch failed: error returned from database: 23505",
+            ),
+            (
+                Error::Aggregate(aggregate),
+                "2 of 2 connections failed:\n  \
+                 connection 1: attempted to acquire a connection on a closed pool\n  \
+                 connection 2: no rows returned by a query that expected to return at least \
+                 one row",
+            ),
+        ];
+
+        for (error, display) in errors {
+            assert_eq!(error.to_string(), display, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn it_chains_the_sources_of_wrapped_errors() {
+        let errors = vec![
+            Error::Configuration("no port".into()),
+            io(io::ErrorKind::BrokenPipe),
+            Error::Tls("bad certificate".into()),
+            Error::column_decode(0, "bad value"),
+            Error::decode("bad value"),
+            Error::encode("bad value"),
+            Error::QueryRejected("not allowed".into()),
+            Error::Batch {
+                index: 0,
+                source: Box::new(Error::RowNotFound),
+            },
+        ];
+
+        for error in errors {
+            assert!(error.source().is_some(), "{:?}", error);
+        }
+
+        assert!(Error::RowNotFound.source().is_none());
+    }
+
+    #[cfg(feature = "error-location")]
+    #[test]
+    fn it_locates_protocol_errors() {
+        let line = line!() + 1;
+        let error = err_protocol!("unexpected message: {}", 'X');
+
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "encountered unexpected or invalid data: unexpected message: X (at {}:{}:21)",
+                file!(),
+                line
+            )
+        );
+    }
+
+    #[test]
+    fn it_catches_panics_with_their_message() {
+        assert_eq!(catch_panic(|| 1).unwrap(), 1);
+
+        match catch_panic(|| panic!("bad value: {}", 42)) {
+            Err(Error::Panic(message)) => assert_eq!(message, "bad value: 42"),
+            other => panic!("unexpected result: {:?}", other.map(|_: ()| ())),
+        }
+
+        match catch_panic(|| panic!("a literal")) {
+            Err(Error::Panic(message)) => assert_eq!(message, "a literal"),
+            other => panic!("unexpected result: {:?}", other.map(|_: ()| ())),
+        }
+    }
+}
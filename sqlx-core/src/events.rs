@@ -0,0 +1,244 @@
+//! Notifications of connections being opened, failing and closed.
+//!
+//! A [`ConnectionEventListener`] set with
+//! [`ConnectOptions::connection_events`](crate::connection::ConnectOptions::connection_events)
+//! or [`PoolOptions::connection_events`](crate::pool::PoolOptions::connection_events) is told
+//! about every connection the options open, e.g. for a supervisor to update the weights of
+//! service discovery or to emit events of its own. A pool sets its listener on every connection
+//! it opens, and reports why it closes each of them.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use crate::error::{catch_panic, Error};
+
+/// Called when a connection is opened, fails, or is closed.
+///
+/// The listener is called synchronously where the event happens, e.g. on the task that opens
+/// the connection or in the pool's reaper, so it must be fast and must not block; hand the
+/// event over to another task for anything slower. A panic in the listener is caught and
+/// logged, and does not affect the connection.
+///
+/// Every method does nothing by default.
+pub trait ConnectionEventListener: Send + Sync {
+    /// A connection was established.
+    fn on_connect(&self, info: &ConnectionInfo) {
+        let _ = info;
+    }
+
+    /// A connection could not be established, or the pool found an open connection to be
+    /// broken. In the latter case `on_close` follows with [`CloseReason::Broken`].
+    fn on_error(&self, info: &ConnectionInfo, error: &Error) {
+        let _ = (info, error);
+    }
+
+    /// A connection that was reported to [`on_connect`](Self::on_connect) was closed.
+    fn on_close(&self, info: &ConnectionInfo, reason: CloseReason) {
+        let _ = (info, reason);
+    }
+}
+
+/// What is known about the connection an event is about.
+///
+/// Fields that the driver does not know, or that were not given in the options, are `None`.
+/// For a connection that could not be established, only what the options say is known.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    pub(crate) database: &'static str,
+    pub(crate) peer_addr: Option<String>,
+    pub(crate) database_name: Option<String>,
+    pub(crate) application_name: Option<String>,
+    pub(crate) connection_id: Option<u64>,
+}
+
+impl ConnectionInfo {
+    pub(crate) fn new(database: &'static str) -> Self {
+        Self {
+            database,
+            ..Self::default()
+        }
+    }
+
+    /// The name of the database of the connection, e.g. `PostgreSQL`.
+    pub fn database(&self) -> &'static str {
+        self.database
+    }
+
+    /// The address of the server, as `host:port` or the path of a Unix socket; for SQLite, the
+    /// path of the database file.
+    pub fn peer_addr(&self) -> Option<&str> {
+        self.peer_addr.as_deref()
+    }
+
+    /// The database on the server the connection was opened to.
+    pub fn database_name(&self) -> Option<&str> {
+        self.database_name.as_deref()
+    }
+
+    /// The application name the connection reports to the server.
+    pub fn application_name(&self) -> Option<&str> {
+        self.application_name.as_deref()
+    }
+
+    /// The ID the server assigned to the connection, once it is established: the process ID
+    /// of the backend in PostgreSQL, the connection ID in MySQL.
+    pub fn connection_id(&self) -> Option<u64> {
+        self.connection_id
+    }
+}
+
+/// Why a connection was closed; see [`ConnectionEventListener::on_close`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CloseReason {
+    /// The connection was closed on purpose: by [`Connection::close`], by closing its pool, or
+    /// by a partition of a `PartitionedPool` to hand its slot over to another.
+    ///
+    /// [`Connection::close`]: crate::connection::Connection::close
+    Explicit,
+
+    /// The pool could not make the connection ready for its next user: `after_connect` failed,
+    /// or `after_release` or `before_acquire` rejected it, or one of them panicked.
+    ResetFailed,
+
+    /// The connection was open for longer than
+    /// [`max_lifetime`](crate::pool::PoolOptions::max_lifetime).
+    MaxLifetime,
+
+    /// The connection was idle for longer than
+    /// [`idle_timeout`](crate::pool::PoolOptions::idle_timeout), or was closed by the pool of
+    /// another partition of a `PartitionedPool` that needed its slot.
+    IdleReaped,
+
+    /// The connection failed a ping or could not be flushed when it was returned to the pool,
+    /// in which case the error was reported to [`on_error`](ConnectionEventListener::on_error)
+    /// first, or it saw the server shut down.
+    Broken,
+
+    /// The password the connection was opened with expired, and the connection was replaced
+    /// when it was returned to the pool; see
+    /// [`PasswordProvider`](crate::pool::PasswordProvider).
+    CredentialExpired,
+}
+
+// `ConnectOptions` are `Debug` and `Clone`, which a bare `Arc<dyn ConnectionEventListener>`
+// is not
+#[derive(Clone, Default)]
+pub(crate) struct EventListener(pub(crate) Option<Arc<dyn ConnectionEventListener>>);
+
+impl Debug for EventListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("Some(<listener>)"),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+/// The listener of a connection, with what it is told about the connection.
+///
+/// Kept by the connection, which reports an explicit close; the pool takes it off the
+/// connections it closes itself, to report why. Not part of the public API.
+#[doc(hidden)]
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionEvents {
+    listener: EventListener,
+    info: ConnectionInfo,
+}
+
+// the drivers report connecting, the pool and the drivers closing
+#[cfg_attr(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "mssql",
+        feature = "memory"
+    )),
+    allow(dead_code)
+)]
+impl ConnectionEvents {
+    pub(crate) fn new(listener: &EventListener, info: ConnectionInfo) -> Self {
+        Self {
+            listener: listener.clone(),
+            info,
+        }
+    }
+
+    pub(crate) fn set_connection_id(&mut self, id: u64) {
+        self.info.connection_id = Some(id);
+    }
+
+    pub(crate) fn connected(&self) {
+        self.notify("on_connect", |listener| listener.on_connect(&self.info));
+    }
+
+    pub(crate) fn error(&self, error: &Error) {
+        self.notify("on_error", |listener| listener.on_error(&self.info, error));
+    }
+
+    pub(crate) fn closed(self, reason: CloseReason) {
+        self.notify("on_close", |listener| listener.on_close(&self.info, reason));
+    }
+
+    fn notify(&self, event: &str, f: impl FnOnce(&dyn ConnectionEventListener)) {
+        if let Some(listener) = &self.listener.0 {
+            if let Err(error) = catch_panic(|| f(&**listener)) {
+                log::warn!("in `ConnectionEventListener::{}`: {}", event, error);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl ConnectionEventListener for Recorder {
+        fn on_connect(&self, info: &ConnectionInfo) {
+            self.0
+                .lock()
+                .push(format!("connect {:?}", info.connection_id()));
+        }
+
+        fn on_error(&self, _: &ConnectionInfo, error: &Error) {
+            self.0.lock().push(format!("error {}", error));
+        }
+
+        fn on_close(&self, _: &ConnectionInfo, reason: CloseReason) {
+            self.0.lock().push(format!("close {:?}", reason));
+
+            panic!("listeners may panic");
+        }
+    }
+
+    #[test]
+    fn it_reports_events_and_catches_panics() {
+        let recorder = Arc::new(Recorder::default());
+        let listener = EventListener(Some(recorder.clone()));
+
+        let mut events = ConnectionEvents::new(&listener, ConnectionInfo::new("PostgreSQL"));
+        events.set_connection_id(42);
+
+        events.connected();
+        events.error(&Error::PoolTimedOut);
+        events.closed(CloseReason::MaxLifetime);
+
+        assert_eq!(
+            *recorder.0.lock(),
+            [
+                "connect Some(42)",
+                "error pool timed out while waiting for an open connection",
+                "close MaxLifetime"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_does_nothing_without_a_listener() {
+        ConnectionEvents::default().closed(CloseReason::Explicit);
+    }
+}
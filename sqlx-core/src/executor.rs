@@ -6,6 +6,7 @@ use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_util::{future, FutureExt, StreamExt, TryFutureExt, TryStreamExt};
 use std::fmt::Debug;
+use std::time::Duration;
 
 /// A type that contains or can provide a database
 /// connection to use for executing queries against the database.
@@ -22,6 +23,17 @@ use std::fmt::Debug;
 ///  * [`&mut PoolConnection`](super::pool::PoolConnection)
 ///  * [`&mut Connection`](super::connection::Connection)
 ///
+/// Pools, pool connections, transactions and the connections of each database also have
+/// [`execute`](Self::execute), [`fetch_one`](Self::fetch_one),
+/// [`fetch_optional`](Self::fetch_optional) and [`fetch_all`](Self::fetch_all) as inherent
+/// methods, so the trait only needs importing to write code generic over the executor.
+///
+/// # `Send`
+///
+/// The futures and streams returned by executors, connections, pools and transactions are
+/// `Send` with every runtime, `actix` included, so they can be held across an `.await` in a
+/// spawned task. The few methods whose futures hold a generic argument, such as the iterator
+/// given to `Transaction::set_locals`, are `Send` as long as that argument is.
 pub trait Executor<'c>: Send + Debug + Sized {
     type Database: Database;
 
@@ -198,6 +210,90 @@ pub trait Execute<'q, DB: Database>: Send + Sized {
 
     /// Returns `true` if the statement should be cached.
     fn persistent(&self) -> bool;
+
+    /// Returns the limits on the size of the values and rows of the result.
+    ///
+    /// Unlimited by default; see [`Query::max_value_size`](crate::query::Query::max_value_size).
+    #[inline]
+    fn fetch_limits(&self) -> FetchLimits {
+        FetchLimits::default()
+    }
+
+    /// Returns how long the rows of the result may be served from the query results cache of
+    /// a pool, if at all.
+    ///
+    /// Not cached by default; see [`Query::cached`](crate::query::Query::cached).
+    #[inline]
+    fn cache_ttl(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Limits on the sizes of the values and rows returned by a query, in bytes.
+///
+/// Set with [`max_value_size`](crate::query::Query::max_value_size) and
+/// [`max_row_size`](crate::query::Query::max_row_size) on a query. A row that breaks a limit
+/// fails with [`Error::ValueTooLarge`] instead of being decoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FetchLimits {
+    /// The most bytes of a single value; a `NULL` has none.
+    pub max_value_size: Option<usize>,
+
+    /// The most bytes of all the values of a row together.
+    pub max_row_size: Option<usize>,
+}
+
+impl FetchLimits {
+    /// Returns `true` if neither limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_value_size.is_none() && self.max_row_size.is_none()
+    }
+
+    // checks a row whose values have these sizes, `None` for a `NULL`; the row as a whole
+    // first, as some drivers know its size before they know the size of each value
+    pub(crate) fn check<I>(&self, sizes: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = Option<usize>>,
+        I::IntoIter: Clone,
+    {
+        let sizes = sizes.into_iter();
+
+        if self.max_row_size.is_some() {
+            self.check_row(sizes.clone().flatten().sum())?;
+        }
+
+        for (column, size) in sizes.enumerate() {
+            if let Some(size) = size {
+                self.check_value(column, size)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn check_row(&self, size: usize) -> Result<(), Error> {
+        match self.max_row_size {
+            Some(limit) if size > limit => Err(Error::ValueTooLarge {
+                column: None,
+                size,
+                limit,
+            }),
+
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_value(&self, column: usize, size: usize) -> Result<(), Error> {
+        match self.max_value_size {
+            Some(limit) if size > limit => Err(Error::ValueTooLarge {
+                column: Some(column),
+                size,
+                limit,
+            }),
+
+            _ => Ok(()),
+        }
+    }
 }
 
 // NOTE: `Execute` is explicitly not implemented for String and &String to make it slightly more
@@ -245,3 +341,121 @@ impl<'q, DB: Database> Execute<'q, DB> for (&'q str, Option<<DB as HasArguments<
         true
     }
 }
+
+// `execute`, `fetch_one`, `fetch_optional` and `fetch_all` as inherent methods of an executor,
+// so that they can be called without importing `Executor`; they delegate to `$executor`, an
+// expression of `$this` (which is `self`, passed in to be in scope)
+#[allow(unused_macros)]
+macro_rules! impl_executor_methods {
+    ($DB:ty, &$this:ident => $executor:expr) => {
+        impl_executor_methods!(@methods $DB, [&'e $this], $executor);
+    };
+
+    ($DB:ty, &mut $this:ident => $executor:expr) => {
+        impl_executor_methods!(@methods $DB, [&'e mut $this], $executor);
+    };
+
+    (@methods $DB:ty, [$($receiver:tt)*], $executor:expr) => {
+        /// Execute the query and return the total number of rows affected.
+        ///
+        /// The same as [`Executor::execute`](crate::executor::Executor::execute), without
+        /// importing the trait.
+        #[inline]
+        pub fn execute<'e, 'q: 'e, E>(
+            $($receiver)*,
+            query: E,
+        ) -> futures_core::future::BoxFuture<
+            'e,
+            Result<<$DB as crate::database::Database>::QueryResult, crate::error::Error>,
+        >
+        where
+            E: 'q + crate::executor::Execute<'q, $DB>,
+        {
+            crate::executor::Executor::execute($executor, query)
+        }
+
+        /// Execute the query and return all the generated results, collected into a [`Vec`].
+        ///
+        /// The same as [`Executor::fetch_all`](crate::executor::Executor::fetch_all), without
+        /// importing the trait.
+        #[inline]
+        pub fn fetch_all<'e, 'q: 'e, E>(
+            $($receiver)*,
+            query: E,
+        ) -> futures_core::future::BoxFuture<
+            'e,
+            Result<Vec<<$DB as crate::database::Database>::Row>, crate::error::Error>,
+        >
+        where
+            E: 'q + crate::executor::Execute<'q, $DB>,
+        {
+            crate::executor::Executor::fetch_all($executor, query)
+        }
+
+        /// Execute the query and returns exactly one row.
+        ///
+        /// The same as [`Executor::fetch_one`](crate::executor::Executor::fetch_one), without
+        /// importing the trait.
+        #[inline]
+        pub fn fetch_one<'e, 'q: 'e, E>(
+            $($receiver)*,
+            query: E,
+        ) -> futures_core::future::BoxFuture<
+            'e,
+            Result<<$DB as crate::database::Database>::Row, crate::error::Error>,
+        >
+        where
+            E: 'q + crate::executor::Execute<'q, $DB>,
+        {
+            crate::executor::Executor::fetch_one($executor, query)
+        }
+
+        /// Execute the query and returns at most one row.
+        ///
+        /// The same as [`Executor::fetch_optional`](crate::executor::Executor::fetch_optional),
+        /// without importing the trait.
+        #[inline]
+        pub fn fetch_optional<'e, 'q: 'e, E>(
+            $($receiver)*,
+            query: E,
+        ) -> futures_core::future::BoxFuture<
+            'e,
+            Result<Option<<$DB as crate::database::Database>::Row>, crate::error::Error>,
+        >
+        where
+            E: 'q + crate::executor::Execute<'q, $DB>,
+        {
+            crate::executor::Executor::fetch_optional($executor, query)
+        }
+    };
+}
+
+#[test]
+fn it_checks_rows_against_fetch_limits() {
+    let limits = FetchLimits {
+        max_value_size: Some(10),
+        max_row_size: Some(25),
+    };
+
+    assert!(limits.check(vec![Some(10), None, Some(10)]).is_ok());
+    assert!(FetchLimits::default().check(vec![Some(usize::MAX)]).is_ok());
+
+    assert!(matches!(
+        limits.check(vec![Some(4), Some(11)]),
+        Err(Error::ValueTooLarge {
+            column: Some(1),
+            size: 11,
+            limit: 10
+        })
+    ));
+
+    // the row as a whole is checked first
+    assert!(matches!(
+        limits.check(vec![Some(10), Some(10), Some(11)]),
+        Err(Error::ValueTooLarge {
+            column: None,
+            size: 31,
+            limit: 25
+        })
+    ));
+}
@@ -1,4 +1,5 @@
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
+use std::cmp::Ordering;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::ops::Deref;
@@ -7,6 +8,14 @@ use std::sync::Arc;
 // U meaning micro
 // a micro-string is either a reference-counted string or a static string
 // this guarantees these are cheap to clone everywhere
+
+/// An immutable string that is cheap to clone: either a `&'static str` or an `Arc<str>`.
+///
+/// Column names, type names and the keys of the statement cache are kept as `UStr`, so that
+/// every row and column of a statement shares them instead of copying them. Names given as
+/// string literals are never copied at all.
+///
+/// It dereferences to `str`, and compares, orders and hashes as the string it holds.
 #[derive(Clone, Eq)]
 pub enum UStr {
     Static(&'static str),
@@ -14,9 +23,15 @@ pub enum UStr {
 }
 
 impl UStr {
-    #[allow(dead_code)]
-    pub(crate) fn new(s: &str) -> Self {
-        UStr::Shared(Arc::from(s.to_owned()))
+    /// Copies `s` into a new shared string.
+    pub fn new(s: &str) -> Self {
+        UStr::Shared(Arc::from(s))
+    }
+
+    /// The string itself.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self
     }
 }
 
@@ -48,12 +63,43 @@ impl Borrow<str> for UStr {
     }
 }
 
+impl AsRef<str> for UStr {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
 impl PartialEq<UStr> for UStr {
     fn eq(&self, other: &UStr) -> bool {
         (**self).eq(&**other)
     }
 }
 
+impl PartialEq<str> for UStr {
+    fn eq(&self, other: &str) -> bool {
+        (**self).eq(other)
+    }
+}
+
+impl PartialEq<&'_ str> for UStr {
+    fn eq(&self, other: &&str) -> bool {
+        (**self).eq(*other)
+    }
+}
+
+impl PartialOrd for UStr {
+    fn partial_cmp(&self, other: &UStr) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for UStr {
+    fn cmp(&self, other: &UStr) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
 impl From<&'static str> for UStr {
     #[inline]
     fn from(s: &'static str) -> Self {
@@ -68,6 +114,30 @@ impl From<String> for UStr {
     }
 }
 
+impl From<Arc<str>> for UStr {
+    #[inline]
+    fn from(s: Arc<str>) -> Self {
+        UStr::Shared(s)
+    }
+}
+
+impl From<Cow<'static, str>> for UStr {
+    #[inline]
+    fn from(s: Cow<'static, str>) -> Self {
+        match s {
+            Cow::Borrowed(s) => UStr::Static(s),
+            Cow::Owned(s) => s.into(),
+        }
+    }
+}
+
+impl From<&'_ UStr> for UStr {
+    #[inline]
+    fn from(s: &UStr) -> Self {
+        s.clone()
+    }
+}
+
 impl Debug for UStr {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -106,3 +176,23 @@ impl serde::Serialize for UStr {
         serializer.serialize_str(&self)
     }
 }
+
+#[test]
+fn it_compares_ustr_by_contents() {
+    use std::collections::HashMap;
+
+    let shared = UStr::new("name");
+
+    assert_eq!(shared, UStr::Static("name"));
+    assert_eq!(shared, "name");
+    assert!(UStr::Static("a") < UStr::new("b"));
+    assert!(matches!(
+        UStr::from(Cow::Borrowed("name")),
+        UStr::Static("name")
+    ));
+
+    let mut map = HashMap::new();
+    map.insert(shared, 1);
+
+    assert_eq!(map.get("name"), Some(&1));
+}
@@ -1,5 +1,12 @@
-use crate::error::Error;
+use std::any::type_name;
+use std::fmt::{self, Debug, Formatter};
+
+use crate::column::Column;
+use crate::database::Database;
+use crate::error::{ColumnMismatch, Error};
 use crate::row::Row;
+use crate::type_info::TypeInfo;
+use crate::types::Type;
 
 /// A record that can be built from a row returned by the database.
 ///
@@ -66,6 +73,11 @@ use crate::row::Row;
 /// reason), `lowercase`, `UPPERCASE`, `camelCase`, `PascalCase`, `SCREAMING_SNAKE_CASE` and `kebab-case`.
 /// The styling of each option is intended to be an example of its behavior.
 ///
+/// A name that no column has exactly may still find one that differs in case, or in case and
+/// underscores, as set by the [`ColumnNameMatching`](crate::column::ColumnNameMatching) of the
+/// connection; by default a field `user_id` reads a column `userId` as well. Two columns that
+/// both match, and neither exactly, are an error rather than either of them.
+///
 /// #### `default`
 ///
 /// When your struct contains a field that is not present in your query,
@@ -92,8 +104,216 @@ use crate::row::Row;
 /// will set the value of the field `location` to the default value of `Option<String>`,
 /// which is `None`.
 ///
+/// #### `try_from`
+///
+/// When the type of a field cannot be decoded itself but can be converted from one that can,
+/// the `try_from` attribute names the type to decode the column as, which is then converted
+/// with [`TryFrom`](std::convert::TryFrom):
+///
+/// ```rust,ignore
+/// #[derive(sqlx::FromRow)]
+/// struct Account {
+///     id: i32,
+///     // a SMALLINT column that is never negative
+///     #[sqlx(try_from = "i16")]
+///     login_count: u16,
+/// }
+/// ```
+///
+/// A value that fails to convert is reported as [`Error::ColumnDecode`] for the column.
+///
+/// #### `decode_with`
+///
+/// For anything else, the `decode_with` attribute names a function that decodes the field from
+/// the raw value of its column, returning an error that converts to a
+/// [`BoxDynError`](crate::error::BoxDynError), which is then reported as
+/// [`Error::ColumnDecode`] for the column:
+///
+/// ```rust,ignore
+/// fn decode_url(value: PgValueRef<'_>) -> Result<Url, BoxDynError> {
+///     Ok(<&str as Decode<Postgres>>::decode(value)?.parse()?)
+/// }
+///
+/// #[derive(sqlx::FromRow)]
+/// #[sqlx(database = "Postgres")]
+/// struct Link {
+///     id: i32,
+///     #[sqlx(decode_with = "decode_url")]
+///     url: Url,
+/// }
+/// ```
+///
+/// By default the implementation is for rows of any database, which only a function generic
+/// over the value of every database could decode. When the function is for one database,
+/// as above, `database` placed at the struct level makes the implementation for the rows of
+/// that database alone.
+///
+/// #### `skip`
+///
+/// A field marked `skip` is not read from the row at all, and is set to its [`Default`]
+/// value:
+///
+/// ```rust,ignore
+/// #[derive(sqlx::FromRow)]
+/// struct User {
+///     id: i32,
+///     name: String,
+///     #[sqlx(skip)]
+///     sessions: Vec<Session>,
+/// }
+/// ```
+///
+/// In a tuple struct the columns are then those of the other fields, in order.
+///
+/// `try_from` and `decode_with` cannot be combined, and `skip` cannot be combined with any
+/// other attribute of the field.
+///
+/// ### Checked queries
+///
+/// The derived implementation also lists the columns it reads in
+/// [`expected_columns`](FromRow::expected_columns), so that
+/// [`QueryAs::fetch_checked`](crate::query_as::QueryAs::fetch_checked) can check every column
+/// of a result against the type of its field before the first row is decoded. Columns of
+/// fields marked `default` may be missing, those of fields marked `try_from` are checked
+/// against the type they are converted from, and those of fields marked `decode_with` are not
+/// checked.
+///
 pub trait FromRow<'r, R: Row>: Sized {
     fn from_row(row: &'r R) -> Result<Self, Error>;
+
+    /// The columns read by [`from_row`](FromRow::from_row) and the types they are read into,
+    /// or `None` if they are not known, in which case results are not checked.
+    fn expected_columns() -> Option<Vec<ExpectedColumn<R::Database>>> {
+        None
+    }
+}
+
+/// A column read by a [`FromRow`] implementation, and the Rust type it is read into.
+pub struct ExpectedColumn<DB: Database> {
+    column: ColumnRef,
+    rust_type: &'static str,
+    type_info: DB::TypeInfo,
+    compatible: fn(&DB::TypeInfo) -> bool,
+    optional: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ColumnRef {
+    Name(&'static str),
+    Index(usize),
+}
+
+impl<DB: Database> ExpectedColumn<DB> {
+    /// The column named `name`, read into `T`.
+    pub fn named<T: Type<DB>>(name: &'static str) -> Self {
+        Self::new::<T>(ColumnRef::Name(name))
+    }
+
+    /// The column at position `index`, read into `T`.
+    pub fn indexed<T: Type<DB>>(index: usize) -> Self {
+        Self::new::<T>(ColumnRef::Index(index))
+    }
+
+    fn new<T: Type<DB>>(column: ColumnRef) -> Self {
+        Self {
+            column,
+            rust_type: type_name::<T>(),
+            type_info: T::type_info(),
+            compatible: T::compatible,
+            optional: false,
+        }
+    }
+
+    /// Marks the column as one that may be missing from the result.
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
+    // the column of `row` this refers to, and its name or position for reporting; a name is
+    // matched like `Row::try_get` matches it, failing if it is ambiguous
+    fn find<'r, R>(&self, row: &'r R) -> Result<(Option<&'r DB::Column>, String), Error>
+    where
+        R: Row<Database = DB>,
+    {
+        match self.column {
+            ColumnRef::Name(name) => {
+                let columns = row.columns();
+                let exact = columns.iter().position(|column| column.name() == name);
+
+                let found = match row.column_name_matching().find(
+                    name,
+                    exact,
+                    columns.iter().map(Column::name),
+                ) {
+                    Ok(index) => Some(&columns[index]),
+                    Err(Error::ColumnNotFound(_)) => None,
+                    Err(error) => return Err(error),
+                };
+
+                Ok((found, name.to_owned()))
+            }
+
+            ColumnRef::Index(index) => Ok((row.columns().get(index), index.to_string())),
+        }
+    }
+}
+
+impl<DB: Database> Debug for ExpectedColumn<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExpectedColumn")
+            .field("column", &self.column)
+            .field("rust_type", &self.rust_type)
+            .field("type_info", &self.type_info)
+            .field("optional", &self.optional)
+            .finish()
+    }
+}
+
+/// Checks the columns of `row` against `expected`, returning all the mismatches at once.
+///
+/// Columns whose type is not known, such as untyped expressions in SQLite, are not checked.
+pub(crate) fn validate_columns<R: Row>(
+    row: &R,
+    expected: &[ExpectedColumn<R::Database>],
+) -> Result<(), Error> {
+    let mut mismatches = Vec::new();
+
+    for column in expected {
+        let (found, name) = column.find(row)?;
+
+        let found = match found {
+            Some(found) => found.type_info(),
+
+            None if column.optional => continue,
+
+            None => {
+                mismatches.push(ColumnMismatch::new(
+                    name,
+                    column.rust_type,
+                    column.type_info.name().to_owned(),
+                    None,
+                ));
+
+                continue;
+            }
+        };
+
+        if !found.is_null() && !(column.compatible)(found) {
+            mismatches.push(ColumnMismatch::new(
+                name,
+                column.rust_type,
+                column.type_info.name().to_owned(),
+                Some(found.name().to_owned()),
+            ));
+        }
+    }
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::TypeValidation(mismatches))
+    }
 }
 
 // implement FromRow for tuples of types that implement Decode
@@ -111,6 +331,10 @@ macro_rules! impl_from_row_for_tuple {
             fn from_row(row: &'r R) -> Result<Self, Error> {
                 Ok(($(row.try_get($idx as usize)?,)+))
             }
+
+            fn expected_columns() -> Option<Vec<ExpectedColumn<R::Database>>> {
+                Some(vec![$(ExpectedColumn::indexed::<$T>($idx),)+])
+            }
         }
     };
 }
@@ -0,0 +1,73 @@
+//! Entry points for fuzzing the protocol decoders.
+//!
+//! Enabled by the `fuzzing` feature and driven by the targets in `fuzz/` at the root of the
+//! repository. Each function decodes arbitrary bytes as every message the server can send and
+//! reads back everything that was decoded; it may return an error for any input, but must never
+//! panic. Nothing in here is covered by semver.
+
+#[cfg(feature = "mysql")]
+mod mysql;
+
+#[cfg(feature = "postgres")]
+mod postgres;
+
+#[cfg(feature = "mysql")]
+pub use mysql::mysql_packet;
+
+#[cfg(feature = "postgres")]
+pub use postgres::postgres_message;
+
+// a small, seeded generator so the tests mutate messages the same way on every run
+#[cfg(test)]
+struct XorShift(u64);
+
+#[cfg(test)]
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+// calls `decode` with every truncation of each of `messages`, and then with many copies in which
+// a few bytes were overwritten, favouring values that make interesting lengths
+#[cfg(test)]
+fn mutate_all(messages: &[&[u8]], mut decode: impl FnMut(&[u8])) {
+    const INTERESTING: &[u8] = &[0x00, 0x01, 0x7f, 0x80, 0xfb, 0xfc, 0xfd, 0xfe, 0xff];
+
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+
+    for message in messages {
+        for len in 0..=message.len() {
+            decode(&message[..len]);
+        }
+
+        if message.is_empty() {
+            continue;
+        }
+
+        for _ in 0..2000 {
+            let mut mutated = message.to_vec();
+
+            for _ in 0..=rng.below(4) {
+                let i = rng.below(mutated.len());
+
+                mutated[i] = if rng.below(2) == 0 {
+                    INTERESTING[rng.below(INTERESTING.len())]
+                } else {
+                    rng.next() as u8
+                };
+            }
+
+            let len = mutated.len() - rng.below(2) * rng.below(mutated.len());
+
+            decode(&mutated[..len]);
+        }
+    }
+}
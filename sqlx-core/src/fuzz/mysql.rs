@@ -0,0 +1,154 @@
+use std::cmp;
+
+use bytes::Bytes;
+
+use crate::error::Error;
+use crate::ext::ustr::UStr;
+use crate::io::Decode;
+use crate::mysql::protocol::connect::{AuthSwitchRequest, Handshake};
+use crate::mysql::protocol::response::{EofPacket, ErrPacket, OkPacket};
+use crate::mysql::protocol::statement::{BinaryRow, PrepareOk};
+use crate::mysql::protocol::text::{ColumnDefinition, ColumnFlags, ColumnType, TextRow};
+use crate::mysql::protocol::{Capabilities, Row};
+use crate::mysql::{MySqlColumn, MySqlTypeInfo};
+
+/// Decodes a packet payload from the server.
+///
+/// The first byte picks the packet to decode the rest as. For a row, the byte after it holds
+/// the number of columns, which is followed by the type of each; the row begins after those.
+pub fn mysql_packet(data: &[u8]) -> Result<(), Error> {
+    let (&kind, payload) = match data.split_first() {
+        Some(split) => split,
+        None => return Ok(()),
+    };
+
+    let capabilities = Capabilities::PROTOCOL_41 | Capabilities::DEPRECATE_EOF;
+
+    match kind % 9 {
+        0 => read(Handshake::decode(Bytes::copy_from_slice(payload))?),
+        1 => read(AuthSwitchRequest::decode(Bytes::copy_from_slice(payload))?),
+        2 => read(OkPacket::decode_with(
+            Bytes::copy_from_slice(payload),
+            capabilities | Capabilities::SESSION_TRACK,
+        )?),
+        3 => read(EofPacket::decode_with(
+            Bytes::copy_from_slice(payload),
+            capabilities,
+        )?),
+        4 => read(ErrPacket::decode_with(
+            Bytes::copy_from_slice(payload),
+            capabilities,
+        )?),
+        5 => read(PrepareOk::decode_with(
+            Bytes::copy_from_slice(payload),
+            capabilities,
+        )?),
+
+        6 => {
+            let def = ColumnDefinition::decode_with(Bytes::copy_from_slice(payload), capabilities)?;
+
+            def.name()?;
+            def.alias()?;
+            read(MySqlTypeInfo::from_column(&def));
+        }
+
+        binary => {
+            let (columns, row) = columns(payload);
+            let row = Bytes::copy_from_slice(row);
+
+            let row = if binary == 7 {
+                BinaryRow::decode_with(row, &columns)?.0
+            } else {
+                TextRow::decode_with(row, &columns)?.0
+            };
+
+            read_row(&row, columns.len());
+        }
+    }
+
+    Ok(())
+}
+
+// the columns a row is decoded against, and the rest of the payload
+fn columns(payload: &[u8]) -> (Vec<MySqlColumn>, &[u8]) {
+    let (&count, rest) = match payload.split_first() {
+        Some(split) => split,
+        None => return (Vec::new(), payload),
+    };
+
+    let count = cmp::min(usize::from(count), rest.len());
+    let (types, row) = rest.split_at(count);
+
+    let columns = types
+        .iter()
+        .filter_map(|&ty| ColumnType::try_from_u16(ty).ok())
+        .enumerate()
+        .map(|(ordinal, ty)| MySqlColumn {
+            ordinal,
+            name: UStr::new(""),
+            type_info: MySqlTypeInfo {
+                r#type: ty,
+                flags: ColumnFlags::empty(),
+                char_set: 63,
+                max_size: None,
+                extended: None,
+            },
+            flags: None,
+            table: None,
+        })
+        .collect();
+
+    (columns, row)
+}
+
+fn read_row(row: &Row, columns: usize) {
+    for index in 0..columns {
+        row.get(index);
+    }
+}
+
+// formats every field of a decoded packet
+fn read<T: std::fmt::Debug>(packet: T) {
+    let _ = format!("{:?}", packet);
+}
+
+#[test]
+fn it_decodes_mutated_packets_without_panicking() {
+    super::mutate_all(
+        &[
+            b"\x00\n8.0.18\x00\x19\x00\x00\x00\x114aB0c\x06g\x00\xff\xff\xff\x02\x00\xff\xc7\x15\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00tL\x03s\x0f[4\rl4. \x00caching_sha2_password\x00",
+            b"\x01\xfemysql_native_password\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10\x11\x12\x13\x14\x00",
+            b"\x02\x00\x01\x02\x02@\x00\x00",
+            b"\x03\xfe\x00\x00\x02\x00",
+            b"\x04\xff\x19\x04#42000Unknown database 'unknown'",
+            b"\x05\x00\x01\x00\x00\x00\x02\x00\x01\x00\x00\x00\x00",
+            b"\x06\x03def\x04test\x01t\x01t\x02id\x02id\x0c?\x00\x0b\x00\x00\x00\x03\x03B\x00\x00\x00",
+            b"\x07\x04\x03\x0f\x0c\x01\x00\x00\x2a\x00\x00\x00\x02hi\x07\xe4\x07\x01\x02\x03\x04\x05\x07",
+            b"\x08\x03\x03\x0f\x0f\x0242\xfb\x05hello",
+        ],
+        |data| {
+            let _ = mysql_packet(data);
+        },
+    );
+}
+
+#[test]
+fn it_rejects_lengths_outside_the_packet() {
+    // a length-encoded integer cut short
+    assert!(mysql_packet(b"\x02\x00\xfc\x01").is_err());
+
+    // a text value longer than the row
+    assert!(mysql_packet(b"\x08\x01\x0f\xfd\xff\xff\xffabc").is_err());
+
+    // a binary value longer than the row
+    assert!(mysql_packet(b"\x07\x01\x0f\x00\x00\x10abc").is_err());
+
+    // a binary row too short for its NULL bitmap
+    assert!(mysql_packet(
+        b"\x07\x10\x03\x03\x03\x03\x03\x03\x03\x03\x03\x03\x03\x03\x03\x03\x03\x03\x00"
+    )
+    .is_err());
+
+    // a handshake that ends after the server version
+    assert!(mysql_packet(b"\x00\n8.0.18\x00").is_err());
+}
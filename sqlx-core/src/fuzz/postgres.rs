@@ -0,0 +1,166 @@
+use bytes::Bytes;
+
+use crate::error::Error;
+use crate::io::Decode;
+use crate::postgres::message::{
+    Authentication, BackendKeyData, CommandComplete, DataRow, MessageArena, MessageFormat, Notice,
+    Notification, ParameterDescription, ParameterStatus, ReadyForQuery, RowDescription,
+};
+use crate::postgres::replication::decode_copy_data;
+use crate::postgres::{PgDatabaseError, PgFlavor};
+
+/// Decodes a message from the server: the type byte followed by the contents, without the
+/// length.
+pub fn postgres_message(data: &[u8]) -> Result<(), Error> {
+    let (&tag, contents) = match data.split_first() {
+        Some(split) => split,
+        None => return Ok(()),
+    };
+
+    let contents = Bytes::copy_from_slice(contents);
+
+    match MessageFormat::try_from_u8(tag)? {
+        MessageFormat::Authentication => {
+            if let Authentication::Sasl(sasl) = read(Authentication::decode(contents)?) {
+                sasl.mechanisms().for_each(drop);
+            }
+        }
+
+        MessageFormat::BackendKeyData => {
+            read(BackendKeyData::decode(contents)?);
+        }
+
+        MessageFormat::CommandComplete => {
+            let cc = CommandComplete::decode(contents)?;
+
+            cc.tag()?;
+            cc.rows_affected();
+        }
+
+        MessageFormat::DataRow => {
+            let row = DataRow::decode(contents)?;
+
+            for index in 0..row.values.len() {
+                row.get(index);
+            }
+        }
+
+        MessageFormat::ErrorResponse | MessageFormat::NoticeResponse => {
+            let notice = Notice::decode(contents)?;
+
+            notice.severity();
+            notice.code();
+            notice.message();
+
+            for field in 0..=u8::MAX {
+                notice.get(field);
+            }
+
+            let error = PgDatabaseError::new(notice, true, PgFlavor::Postgres);
+
+            error.position();
+            error.line();
+            error.to_string();
+        }
+
+        MessageFormat::NotificationResponse => {
+            read(Notification::decode(contents)?);
+        }
+
+        MessageFormat::ParameterDescription => {
+            read(ParameterDescription::decode_with(
+                contents,
+                &mut MessageArena::default(),
+            )?);
+        }
+
+        MessageFormat::ParameterStatus => {
+            read(ParameterStatus::decode(contents)?);
+        }
+
+        MessageFormat::ReadyForQuery => {
+            read(ReadyForQuery::decode(contents)?);
+        }
+
+        MessageFormat::RowDescription => {
+            let mut arena = MessageArena::default();
+            let desc = read(RowDescription::decode_with(contents, &mut arena)?);
+
+            for field in desc.fields {
+                desc.name(field);
+            }
+        }
+
+        // only looked into on a replication stream
+        MessageFormat::CopyData => {
+            read(decode_copy_data(contents)?);
+        }
+
+        // these have no contents
+        MessageFormat::BindComplete
+        | MessageFormat::CloseComplete
+        | MessageFormat::CopyBothResponse
+        | MessageFormat::CopyOutResponse
+        | MessageFormat::CopyDone
+        | MessageFormat::EmptyQueryResponse
+        | MessageFormat::NoData
+        | MessageFormat::ParseComplete
+        | MessageFormat::PortalSuspended => {}
+    }
+
+    Ok(())
+}
+
+// formats every field of a decoded message
+fn read<T: std::fmt::Debug>(message: T) -> T {
+    let _ = format!("{:?}", message);
+
+    message
+}
+
+#[test]
+fn it_decodes_mutated_messages_without_panicking() {
+    super::mutate_all(
+        &[
+            b"R\x00\x00\x00\x00",
+            b"R\x00\x00\x00\x05\x01\x02\x03\x04",
+            b"R\x00\x00\x00\x0aSCRAM-SHA-256\x00SCRAM-SHA-256-PLUS\x00\x00",
+            b"R\x00\x00\x00\x0br=abc,s=c2FsdA==,i=4096",
+            b"R\x00\x00\x00\x0cv=c2lnbmF0dXJl",
+            b"K\x00\x00\x30\x39\x01\x02\x03\x04",
+            b"CINSERT 0 1\x00",
+            b"D\x00\x03\x00\x00\x00\x02hi\xff\xff\xff\xff\x00\x00\x00\x00",
+            b"ESERROR\x00VERROR\x00C42P01\x00Mrelation \"t\" does not exist\x00P15\x00L1180\x00\x00",
+            b"NSNOTICE\x00C00000\x00Mhello\x00\x00",
+            b"A\x00\x00\x04\xd2chan\x00payload\x00",
+            b"t\x00\x02\x00\x00\x00\x17\x00\x00\x00\x19",
+            b"SDateStyle\x00ISO, MDY\x00",
+            b"ZI",
+            b"T\x00\x02id\x00\x00\x00\x40\x00\x00\x01\x00\x00\x00\x17\x00\x04\xff\xff\xff\xff\x00\x01\
+              name\x00\x00\x00\x40\x00\x00\x02\x00\x00\x00\x19\xff\xff\xff\xff\xff\xff\x00\x00",
+            b"dw\x00\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00\x00\x01\x00\x00{}",
+            b"dk\x00\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x01",
+        ],
+        |data| {
+            let _ = postgres_message(data);
+        },
+    );
+}
+
+#[test]
+fn it_rejects_lengths_outside_the_message() {
+    // a column claiming to be longer than the row
+    assert!(postgres_message(b"D\x00\x01\x7f\xff\xff\xff").is_err());
+
+    // a negative length other than -1 (NULL)
+    assert!(postgres_message(b"D\x00\x01\xff\xff\xff\xfe").is_err());
+
+    // more parameters than the message holds
+    assert!(postgres_message(b"t\xff\xff\x00\x00\x00\x17").is_err());
+
+    // more fields than the message holds
+    assert!(postgres_message(b"T\x00\x02id\x00\x00\x00").is_err());
+
+    // a message cut short
+    assert!(postgres_message(b"K\x00\x00\x30").is_err());
+}
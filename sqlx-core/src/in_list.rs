@@ -0,0 +1,338 @@
+//! Binding a list of values to the `IN {in}` of a query; see
+//! [`Query::bind_expanded`](crate::query::Query::bind_expanded).
+
+// only PostgreSQL, MySQL and SQLite expand lists
+#![cfg_attr(
+    not(any(feature = "postgres", feature = "mysql", feature = "sqlite")),
+    allow(dead_code)
+)]
+
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use crate::arguments::Arguments;
+use crate::database::{Database, HasArguments};
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::types::Type;
+
+/// The marker in the SQL of a query that a list bound with
+/// [`bind_expanded`](crate::query::Query::bind_expanded) takes the place of.
+pub const MARKER: &str = "{in}";
+
+const NO_MARKER: &str = "the query has no `IN {in}` left to bind a list to";
+
+const PREPARED: &str = "a list cannot be bound to a prepared statement";
+
+const EMPTY: &str = "cannot bind an empty list to `IN {in}`; \
+     use `bind_expanded_allow_empty` for a list that may be empty";
+
+/// A list of values that can be bound to the `IN {in}` of a query of `DB`.
+///
+/// Implemented for every [`IntoIterator`] of values that can be bound to a query of PostgreSQL,
+/// MySQL or SQLite, with [`Clone`] values for the latter two, which may bind the last value
+/// more than once.
+pub trait InList<'q, DB: Database> {
+    /// Adds the values to `arguments` and returns the SQL with the first `{in}` of `sql`
+    /// expanded; or, if they cannot be bound, adds a value that fails to encode with the reason,
+    /// and returns `None`. `sql` is `None` for a prepared statement, which cannot be expanded.
+    #[doc(hidden)]
+    fn expand(
+        self,
+        sql: Option<&str>,
+        arguments: &mut <DB as HasArguments<'q>>::Arguments,
+        allow_empty: bool,
+    ) -> Option<&'static str>;
+}
+
+/// Adds a value that fails to encode with `reason`, so that the query fails with
+/// [`Error::ArgumentEncode`](crate::error::Error::ArgumentEncode) when it is executed.
+fn fail<'q, DB, T>(arguments: &mut <DB as HasArguments<'q>>::Arguments, reason: &str)
+where
+    DB: Database,
+    T: 'q + Type<DB>,
+{
+    arguments.add(Unbindable::<T>(reason.to_owned(), PhantomData));
+}
+
+struct Unbindable<T>(String, PhantomData<fn() -> T>);
+
+impl<T: Type<DB>, DB: Database> Type<DB> for Unbindable<T> {
+    fn type_info() -> DB::TypeInfo {
+        T::type_info()
+    }
+}
+
+impl<'q, T, DB: Database> Encode<'q, DB> for Unbindable<T> {
+    fn encode_by_ref(
+        &self,
+        _: &mut <DB as HasArguments<'q>>::ArgumentBuffer,
+    ) -> Result<IsNull, EncodeError> {
+        Err(self.0.clone().into())
+    }
+}
+
+// the SQL before and after the first marker, or why there is none
+fn split(sql: Option<&str>) -> Result<(&str, &str), &'static str> {
+    let sql = sql.ok_or(PREPARED)?;
+    let start = sql.find(MARKER).ok_or(NO_MARKER)?;
+
+    Ok((&sql[..start], &sql[start + MARKER.len()..]))
+}
+
+// `sql` without the `keyword` it ends with, ignoring case and trailing whitespace
+#[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+fn strip_keyword<'s>(sql: &'s str, keyword: &str) -> Option<&'s str> {
+    let sql = sql.trim_end();
+    let start = sql.len().checked_sub(keyword.len())?;
+
+    if !sql.is_char_boundary(start) || !sql[start..].eq_ignore_ascii_case(keyword) {
+        return None;
+    }
+
+    let rest = &sql[..start];
+
+    if rest.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+
+    Some(rest)
+}
+
+// The expanded SQL must live as long as the query, which only borrows its SQL, so every
+// distinct expansion is kept for the life of the process. Rounding the number of
+// placeholders up keeps them, and the statements prepared for them, few.
+fn intern(sql: String) -> &'static str {
+    static INTERNED: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+    let mut interned = INTERNED.lock();
+
+    if let Some(&sql) = interned.get(&*sql) {
+        return sql;
+    }
+
+    let sql = Box::leak(sql.into_boxed_str());
+    interned.insert(sql);
+
+    sql
+}
+
+/// The number of placeholders a list of `len` values is expanded to: the next power of two,
+/// so that lists of 5 to 8 values share a statement.
+fn padded_len(len: usize) -> usize {
+    len.next_power_of_two()
+}
+
+/// Expands `{in}` into a placeholder for each value, padded with the last value, for
+/// databases without arrays. `empty` is the list that matches no rows.
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+fn expand_placeholders<'q, DB, T>(
+    values: impl IntoIterator<Item = T>,
+    sql: Option<&str>,
+    arguments: &mut <DB as HasArguments<'q>>::Arguments,
+    allow_empty: bool,
+    empty: &str,
+) -> Option<&'static str>
+where
+    DB: Database,
+    T: 'q + Send + Clone + Encode<'q, DB> + Type<DB>,
+{
+    let (before, after) = match split(sql) {
+        Ok(parts) => parts,
+        Err(reason) => {
+            fail::<DB, T>(arguments, reason);
+            return None;
+        }
+    };
+
+    let mut values = values.into_iter();
+
+    let list = match values.next() {
+        None if allow_empty => empty.to_owned(),
+
+        None => {
+            fail::<DB, T>(arguments, EMPTY);
+            return None;
+        }
+
+        Some(first) => {
+            let mut last = first;
+            let mut len = 1;
+
+            for value in values {
+                arguments.add(last);
+                last = value;
+                len += 1;
+            }
+
+            for _ in len..padded_len(len) {
+                arguments.add(last.clone());
+            }
+
+            arguments.add(last);
+
+            let mut list = String::with_capacity(padded_len(len) * 3 + 1);
+            list.push('(');
+
+            for i in 0..padded_len(len) {
+                if i > 0 {
+                    list.push_str(", ");
+                }
+
+                list.push('?');
+            }
+
+            list.push(')');
+            list
+        }
+    };
+
+    Some(intern(format!("{}{}{}", before, list, after)))
+}
+
+#[cfg(feature = "mysql")]
+impl<'q, I, T> InList<'q, crate::mysql::MySql> for I
+where
+    I: IntoIterator<Item = T>,
+    T: 'q + Send + Clone + Encode<'q, crate::mysql::MySql> + Type<crate::mysql::MySql>,
+{
+    fn expand(
+        self,
+        sql: Option<&str>,
+        arguments: &mut crate::mysql::MySqlArguments,
+        allow_empty: bool,
+    ) -> Option<&'static str> {
+        // MySQL takes neither `IN ()` nor `IN (NULL)`, which is not false under `NOT`
+        expand_placeholders::<crate::mysql::MySql, T>(
+            self,
+            sql,
+            arguments,
+            allow_empty,
+            "(SELECT NULL FROM DUAL WHERE FALSE)",
+        )
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'q, I, T> InList<'q, crate::sqlite::Sqlite> for I
+where
+    I: IntoIterator<Item = T>,
+    T: 'q + Send + Clone + Encode<'q, crate::sqlite::Sqlite> + Type<crate::sqlite::Sqlite>,
+{
+    fn expand(
+        self,
+        sql: Option<&str>,
+        arguments: &mut crate::sqlite::SqliteArguments<'q>,
+        allow_empty: bool,
+    ) -> Option<&'static str> {
+        expand_placeholders::<crate::sqlite::Sqlite, T>(self, sql, arguments, allow_empty, "()")
+    }
+}
+
+/// Rewrites `IN {in}` to `= ANY($n)`, and `NOT IN {in}` to `<> ALL($n)`, where `$n` is the
+/// parameter of the list, bound as an array; the statement is the same for any number of
+/// values.
+#[cfg(feature = "postgres")]
+impl<'q, I, T> InList<'q, crate::postgres::Postgres> for I
+where
+    I: IntoIterator<Item = T>,
+    Vec<T>: 'q + Send + Encode<'q, crate::postgres::Postgres> + Type<crate::postgres::Postgres>,
+{
+    fn expand(
+        self,
+        sql: Option<&str>,
+        arguments: &mut crate::postgres::PgArguments,
+        allow_empty: bool,
+    ) -> Option<&'static str> {
+        use crate::postgres::Postgres;
+
+        let (before, after) = match split(sql) {
+            Ok(parts) => parts,
+            Err(reason) => {
+                fail::<Postgres, Vec<T>>(arguments, reason);
+                return None;
+            }
+        };
+
+        let (before, operator) = match strip_keyword(before, "IN") {
+            Some(before) => match strip_keyword(before, "NOT") {
+                Some(before) => (before, "<> ALL"),
+                None => (before, "= ANY"),
+            },
+
+            None => {
+                fail::<Postgres, Vec<T>>(arguments, "`{in}` must follow `IN` or `NOT IN`");
+                return None;
+            }
+        };
+
+        let values: Vec<T> = self.into_iter().collect();
+
+        if values.is_empty() && !allow_empty {
+            fail::<Postgres, Vec<T>>(arguments, EMPTY);
+            return None;
+        }
+
+        arguments.add(values);
+
+        Some(intern(format!(
+            "{} {}(${}){}",
+            before.trim_end(),
+            operator,
+            arguments.len(),
+            after
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_splits_at_the_first_marker() {
+        assert_eq!(
+            split(Some("a IN {in} AND b IN {in}")),
+            Ok(("a IN ", " AND b IN {in}"))
+        );
+
+        assert_eq!(split(Some("a IN (?)")), Err(NO_MARKER));
+        assert_eq!(split(None), Err(PREPARED));
+    }
+
+    #[test]
+    fn it_strips_keywords() {
+        assert_eq!(strip_keyword("WHERE id in  ", "IN"), Some("WHERE id "));
+        assert_eq!(
+            strip_keyword("WHERE id NOT IN", "IN"),
+            Some("WHERE id NOT ")
+        );
+        assert_eq!(strip_keyword("WHERE (id)IN", "IN"), Some("WHERE (id)"));
+
+        assert_eq!(strip_keyword("WHERE id JOIN", "IN"), None);
+        assert_eq!(strip_keyword("WHERE id =", "IN"), None);
+        assert_eq!(strip_keyword("N", "IN"), None);
+    }
+
+    #[test]
+    fn it_pads_to_a_power_of_two() {
+        let lens: Vec<_> = [1, 2, 3, 4, 5, 8, 9, 1000]
+            .iter()
+            .map(|len| padded_len(*len))
+            .collect();
+
+        assert_eq!(lens, [1, 2, 4, 4, 8, 8, 16, 1024]);
+    }
+
+    #[test]
+    fn it_interns_expansions() {
+        let sql = intern("SELECT * FROM users WHERE id IN (?, ?)".to_owned());
+
+        assert!(std::ptr::eq(
+            sql,
+            intern("SELECT * FROM users WHERE id IN (?, ?)".to_owned())
+        ));
+    }
+}
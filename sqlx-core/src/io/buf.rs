@@ -6,11 +6,15 @@ use memchr::memchr;
 use crate::error::Error;
 
 pub trait BufExt: Buf {
+    // Check that at least `len` bytes are left, so the fixed-size reads of `Buf` (which panic
+    // when the buffer is too short) can follow
+    fn ensure_remaining(&self, len: usize) -> Result<(), Error>;
+
     // Read a nul-terminated byte sequence
     fn get_bytes_nul(&mut self) -> Result<Bytes, Error>;
 
     // Read a byte sequence of the exact length
-    fn get_bytes(&mut self, len: usize) -> Bytes;
+    fn get_bytes(&mut self, len: usize) -> Result<Bytes, Error>;
 
     // Read a nul-terminated string
     fn get_str_nul(&mut self) -> Result<String, Error>;
@@ -20,6 +24,18 @@ pub trait BufExt: Buf {
 }
 
 impl BufExt for Bytes {
+    fn ensure_remaining(&self, len: usize) -> Result<(), Error> {
+        if self.len() < len {
+            return Err(err_protocol!(
+                "unexpected end of message: expected {} more bytes but found {}",
+                len,
+                self.len()
+            ));
+        }
+
+        Ok(())
+    }
+
     fn get_bytes_nul(&mut self) -> Result<Bytes, Error> {
         let nul =
             memchr(b'\0', &self).ok_or_else(|| err_protocol!("expected NUL in byte sequence"))?;
@@ -31,11 +47,10 @@ impl BufExt for Bytes {
         Ok(v)
     }
 
-    fn get_bytes(&mut self, len: usize) -> Bytes {
-        let v = self.slice(..len);
-        self.advance(len);
+    fn get_bytes(&mut self, len: usize) -> Result<Bytes, Error> {
+        self.ensure_remaining(len)?;
 
-        v
+        Ok(self.split_to(len))
     }
 
     fn get_str_nul(&mut self) -> Result<String, Error> {
@@ -47,6 +62,8 @@ impl BufExt for Bytes {
     }
 
     fn get_str(&mut self, len: usize) -> Result<String, Error> {
+        self.ensure_remaining(len)?;
+
         let v = from_utf8(&self[..len])
             .map_err(|err| err_protocol!("{}", err))
             .map(ToOwned::to_owned)?;
@@ -1,14 +1,17 @@
 #![allow(dead_code)]
 
+use std::cmp;
 use std::io;
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 
-use bytes::BytesMut;
+use bytes::{Buf, BytesMut};
 use sqlx_rt::{AsyncRead, AsyncReadExt, AsyncWrite};
 
 use crate::error::Error;
 use crate::io::write_and_flush::WriteAndFlush;
 use crate::io::{decode::Decode, encode::Encode};
+use crate::wire::{WireDirection, WireMessage, WireTracer};
 use std::io::Cursor;
 
 pub struct BufStream<S>
@@ -23,8 +26,67 @@ where
 
     // we read into the read buffer using 100% safe code
     rbuf: BytesMut,
+
+    // neither buffer is allowed to keep more than this much capacity around
+    max_capacity: Option<usize>,
+
+    // totals for connection stats
+    bytes_read: u64,
+    bytes_written: u64,
+
+    // traces the messages that go through the stream, split up by the protocol's framing
+    tracer: Option<(Arc<dyn WireTracer>, Framing)>,
+}
+
+/// How messages are delimited in the write buffer, so they can be traced one by one.
+#[derive(Debug, Clone, Copy)]
+pub enum Framing {
+    // a tag byte and a 4-byte big-endian length that counts itself; the startup messages have
+    // no tag, which shows as a leading zero byte
+    Postgres,
+
+    // a 3-byte little-endian payload length and a sequence id
+    MySql,
+}
+
+impl Framing {
+    // the tag, the size of the header and the size of the payload of the message `buf` starts
+    // with, or `None` if the buffer does not hold a whole message
+    fn split(self, buf: &[u8]) -> Option<(Option<u8>, usize, usize)> {
+        match self {
+            Framing::Postgres => {
+                let (tag, header) = match buf.first()? {
+                    0 => (None, 4),
+                    &tag => (Some(tag), 5),
+                };
+
+                let len = buf.get(header - 4..header)?;
+                let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+
+                // the length counts itself but not the tag
+                let payload = len.checked_sub(4)?;
+
+                Some((tag, header, payload)).filter(|_| buf.len() >= header + payload)
+            }
+
+            Framing::MySql => {
+                let len = buf.get(..4)?;
+                let payload = u32::from_le_bytes([len[0], len[1], len[2], 0]) as usize;
+
+                Some((buf.get(4).copied(), 4, payload)).filter(|_| buf.len() >= 4 + payload)
+            }
+        }
+    }
 }
 
+// the capacity buffers start out with, and go back to when shrunk
+const DEFAULT_WBUF_CAPACITY: usize = 512;
+const DEFAULT_RBUF_CAPACITY: usize = 4096;
+
+// the most bytes read at once when discarding, so that discarding a large message does not
+// buffer all of it
+const DISCARD_CHUNK_SIZE: usize = 64 * 1024;
+
 impl<S> BufStream<S>
 where
     S: AsyncRead + AsyncWrite + Unpin,
@@ -32,8 +94,82 @@ where
     pub fn new(stream: S) -> Self {
         Self {
             stream,
-            wbuf: Vec::with_capacity(512),
-            rbuf: BytesMut::with_capacity(4096),
+            wbuf: Vec::with_capacity(DEFAULT_WBUF_CAPACITY),
+            rbuf: BytesMut::with_capacity(DEFAULT_RBUF_CAPACITY),
+            max_capacity: None,
+            bytes_read: 0,
+            bytes_written: 0,
+            tracer: None,
+        }
+    }
+
+    /// Sets a tracer to be called with every message that is written to or read from the
+    /// stream. Written messages are split up according to `framing`; read messages are traced
+    /// with [`trace_received`](Self::trace_received), as only the caller knows where they end.
+    pub fn set_wire_tracer(&mut self, tracer: Option<Arc<dyn WireTracer>>, framing: Framing) {
+        self.tracer = tracer.map(|tracer| (tracer, framing));
+    }
+
+    /// Passes a message that was read from the stream to the tracer, if there is one.
+    #[inline]
+    pub fn trace_received(&self, tag: Option<u8>, payload: &[u8]) {
+        if let Some((tracer, _)) = &self.tracer {
+            tracer.trace(&WireMessage {
+                direction: WireDirection::Received,
+                tag,
+                payload,
+                include_payload: tracer.include_payload(),
+            });
+        }
+    }
+
+    fn trace_sent(&self, tracer: &dyn WireTracer, framing: Framing) {
+        let include_payload = tracer.include_payload();
+        let mut buf = &self.wbuf[..];
+
+        while let Some((tag, header, len)) = framing.split(buf) {
+            tracer.trace(&WireMessage {
+                direction: WireDirection::Sent,
+                tag,
+                payload: &buf[header..header + len],
+                include_payload,
+            });
+
+            buf = &buf[header + len..];
+        }
+    }
+
+    /// Caps how much capacity the buffers keep between messages.
+    ///
+    /// Messages larger than this are still read and written, but through a temporary buffer
+    /// that is freed once the message has been handled.
+    pub fn set_max_capacity(&mut self, max: Option<usize>) {
+        self.max_capacity = max;
+    }
+
+    /// The capacity of the read and write buffers, in that order.
+    pub fn capacity(&self) -> (usize, usize) {
+        (self.rbuf.capacity(), self.wbuf.capacity())
+    }
+
+    /// The number of bytes read from and written to the underlying stream, in that order.
+    pub fn bytes_transferred(&self) -> (u64, u64) {
+        (self.bytes_read, self.bytes_written)
+    }
+
+    /// Releases any capacity beyond the default, keeping data that has not been consumed yet.
+    pub fn shrink(&mut self) {
+        if self.rbuf.capacity() > DEFAULT_RBUF_CAPACITY {
+            let mut rbuf =
+                BytesMut::with_capacity(cmp::max(self.rbuf.len(), DEFAULT_RBUF_CAPACITY));
+            rbuf.extend_from_slice(&self.rbuf);
+            self.rbuf = rbuf;
+        }
+
+        if self.wbuf.capacity() > DEFAULT_WBUF_CAPACITY {
+            let mut wbuf = Vec::with_capacity(cmp::max(self.wbuf.len(), DEFAULT_WBUF_CAPACITY));
+            wbuf.extend_from_slice(&self.wbuf);
+            self.wbuf = wbuf;
         }
     }
 
@@ -52,9 +188,16 @@ where
     }
 
     pub fn flush(&mut self) -> WriteAndFlush<'_, S> {
+        if let Some((tracer, framing)) = &self.tracer {
+            self.trace_sent(&**tracer, *framing);
+        }
+
         WriteAndFlush {
             stream: &mut self.stream,
             buf: Cursor::new(&mut self.wbuf),
+            max_capacity: self.max_capacity,
+            bytes_written: &mut self.bytes_written,
+            done: false,
         }
     }
 
@@ -73,14 +216,76 @@ where
     }
 
     pub async fn read_raw(&mut self, cnt: usize) -> Result<BytesMut, Error> {
-        read_raw_into(&mut self.stream, &mut self.rbuf, cnt).await?;
-        let buf = self.rbuf.split_to(cnt);
+        self.fill(cnt).await?;
+
+        let oversized = matches!(self.max_capacity, Some(max) if self.rbuf.len() > max);
+        let raw = self.rbuf.split_to(cnt);
+
+        if oversized {
+            // let go of a read buffer that had to grow past the limit for this message; its
+            // memory is released when the caller is done with the message
+            let mut rbuf =
+                BytesMut::with_capacity(cmp::max(self.rbuf.len(), DEFAULT_RBUF_CAPACITY));
+            rbuf.extend_from_slice(&self.rbuf);
+            self.rbuf = rbuf;
+        }
+
+        Ok(raw)
+    }
+
+    /// Waits until at least `cnt` bytes have been read, and returns them, without consuming
+    /// them.
+    pub async fn peek(&mut self, cnt: usize) -> Result<&[u8], Error> {
+        self.fill(cnt).await?;
+
+        Ok(&self.rbuf[..cnt])
+    }
+
+    // reads until the read buffer holds at least `cnt` bytes
+    //
+    // the bytes are kept in the read buffer until they are consumed, so that if this is
+    // cancelled, the next read picks up where this one stopped
+    async fn fill(&mut self, cnt: usize) -> Result<(), Error> {
+        let missing = cnt.saturating_sub(self.rbuf.len());
+
+        if missing > 0 {
+            read_raw_into(
+                &mut self.stream,
+                &mut self.rbuf,
+                missing,
+                &mut self.bytes_read,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Consumes the next `remaining` bytes without keeping them, a bounded chunk at a time.
+    ///
+    /// `remaining` counts down as bytes are consumed, so that if this is cancelled, the caller
+    /// knows how many are still to be discarded.
+    pub async fn discard(&mut self, remaining: &mut usize) -> Result<(), Error> {
+        while *remaining > 0 {
+            if self.rbuf.is_empty() {
+                self.fill(cmp::min(*remaining, DISCARD_CHUNK_SIZE)).await?;
+            }
+
+            let buffered = cmp::min(*remaining, self.rbuf.len());
+
+            self.rbuf.advance(buffered);
+            *remaining -= buffered;
+        }
 
-        Ok(buf)
+        Ok(())
     }
 
     pub async fn read_raw_into(&mut self, buf: &mut BytesMut, cnt: usize) -> Result<(), Error> {
-        read_raw_into(&mut self.stream, buf, cnt).await
+        // what is left in the read buffer comes first
+        let buffered = cmp::min(cnt, self.rbuf.len());
+        buf.extend_from_slice(&self.rbuf.split_to(buffered));
+
+        read_raw_into(&mut self.stream, buf, cnt - buffered, &mut self.bytes_read).await
     }
 }
 
@@ -108,17 +313,20 @@ async fn read_raw_into<S: AsyncRead + Unpin>(
     stream: &mut S,
     buf: &mut BytesMut,
     cnt: usize,
+    bytes_read: &mut u64,
 ) -> Result<(), Error> {
     let offset = buf.len();
 
-    // zero-fills the space in the read buffer
+    // zero-fills the space in the read buffer; what was not read into is taken off again,
+    // even if this is cancelled
     buf.resize(offset + cnt, 0);
 
-    let mut read = offset;
-    while (offset + cnt) > read {
+    let mut filled = Filled { buf, len: offset };
+
+    while (offset + cnt) > filled.len {
         // read in bytes from the stream into the read buffer starting
         // from the offset we last read from
-        let n = stream.read(&mut buf[read..]).await?;
+        let n = stream.read(&mut filled.buf[filled.len..]).await?;
 
         if n == 0 {
             // a zero read when we had space in the read buffer
@@ -129,8 +337,36 @@ async fn read_raw_into<S: AsyncRead + Unpin>(
             return Err(io::Error::from(io::ErrorKind::ConnectionAborted).into());
         }
 
-        read += n;
+        filled.len += n;
+        *bytes_read += n as u64;
     }
 
     Ok(())
 }
+
+struct Filled<'a> {
+    buf: &'a mut BytesMut,
+    len: usize,
+}
+
+impl Drop for Filled<'_> {
+    fn drop(&mut self) {
+        self.buf.truncate(self.len);
+    }
+}
+
+#[test]
+fn it_splits_messages_by_framing() {
+    // a startup message without a tag, then a simple query
+    let pg = b"\0\0\0\x08\x04\xd2\x16\x2fQ\0\0\0\x06x\0";
+
+    assert_eq!(Framing::Postgres.split(pg), Some((None, 4, 4)));
+    assert_eq!(Framing::Postgres.split(&pg[8..]), Some((Some(b'Q'), 5, 2)));
+    assert_eq!(Framing::Postgres.split(&pg[8..14]), None);
+
+    // COM_PING, then the start of a packet that has not been written completely
+    let mysql = b"\x01\0\0\0\x0e\x05\0\0\0\x03";
+
+    assert_eq!(Framing::MySql.split(mysql), Some((Some(0x0e), 4, 1)));
+    assert_eq!(Framing::MySql.split(&mysql[5..]), None);
+}
@@ -7,6 +7,6 @@ mod write_and_flush;
 
 pub use buf::BufExt;
 pub use buf_mut::BufMutExt;
-pub use buf_stream::BufStream;
+pub use buf_stream::{BufStream, Framing};
 pub use decode::Decode;
 pub use encode::Encode;
@@ -8,9 +8,15 @@ use std::task::{Context, Poll};
 
 // Atomic operation that writes the full buffer to the stream, flushes the stream, and then
 // clears the buffer (even if either of the two previous operations failed).
+//
+// If it is dropped before it is done, only what was written is removed from the buffer, so
+// the next flush finishes sending the rest of a message that is already partly sent.
 pub struct WriteAndFlush<'a, S> {
     pub(super) stream: &'a mut S,
     pub(super) buf: Cursor<&'a mut Vec<u8>>,
+    pub(super) max_capacity: Option<usize>,
+    pub(super) bytes_written: &'a mut u64,
+    pub(super) done: bool,
 }
 
 impl<S: AsyncWrite + Unpin> Future for WriteAndFlush<'_, S> {
@@ -20,26 +26,54 @@ impl<S: AsyncWrite + Unpin> Future for WriteAndFlush<'_, S> {
         let Self {
             ref mut stream,
             ref mut buf,
+            ref mut bytes_written,
+            ref mut done,
+            ..
         } = *self;
 
-        loop {
-            let read = buf.fill_buf()?;
+        let poll = poll_write_and_flush(stream, buf, bytes_written, cx);
+        *done = poll.is_ready();
 
-            if !read.is_empty() {
-                let written = ready!(Pin::new(&mut *stream).poll_write(cx, read)?);
-                buf.consume(written);
-            } else {
-                break;
-            }
-        }
+        poll
+    }
+}
+
+fn poll_write_and_flush<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    buf: &mut Cursor<&mut Vec<u8>>,
+    bytes_written: &mut u64,
+    cx: &mut Context<'_>,
+) -> Poll<Result<(), Error>> {
+    loop {
+        let read = buf.fill_buf()?;
 
-        Pin::new(stream).poll_flush(cx).map_err(Error::Io)
+        if !read.is_empty() {
+            let written = ready!(Pin::new(&mut *stream).poll_write(cx, read)?);
+            buf.consume(written);
+            *bytes_written += written as u64;
+        } else {
+            break;
+        }
     }
+
+    Pin::new(stream).poll_flush(cx).map_err(Error::Io)
 }
 
 impl<'a, S> Drop for WriteAndFlush<'a, S> {
     fn drop(&mut self) {
-        // clear the buffer regardless of whether the flush succeeded or not
-        self.buf.get_mut().clear();
+        if self.done {
+            // clear the buffer regardless of whether the flush succeeded or not
+            self.buf.get_mut().clear();
+        } else {
+            let written = self.buf.position() as usize;
+            self.buf.get_mut().drain(..written);
+        }
+
+        // let go of a buffer that had to grow past the limit for this write
+        if let Some(max) = self.max_capacity {
+            if self.buf.get_ref().capacity() > max {
+                **self.buf.get_mut() = Vec::new();
+            }
+        }
     }
 }
@@ -28,6 +28,9 @@ pub mod error;
 #[macro_use]
 pub mod arguments;
 
+#[macro_use]
+pub mod executor;
+
 #[macro_use]
 pub mod pool;
 
@@ -57,29 +60,44 @@ pub mod column;
 #[macro_use]
 pub mod statement;
 
+pub mod cached_row;
+pub mod columnar;
 mod common;
 pub mod database;
 pub mod describe;
-pub mod executor;
+pub mod erased;
+pub mod events;
 pub mod from_row;
+pub mod in_list;
 mod io;
 mod logger;
+pub mod middleware;
 mod net;
 pub mod query_as;
 pub mod query_scalar;
+pub mod raw_sql;
 pub mod row;
+#[cfg(feature = "serde-row")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde-row")))]
+pub mod serde_row;
 pub mod type_info;
 pub mod value;
+pub mod wire;
 
 #[cfg(feature = "migrate")]
 pub mod migrate;
 
+#[cfg(feature = "offline")]
+#[cfg_attr(docsrs, doc(cfg(feature = "offline")))]
+pub mod offline;
+
 #[cfg(all(
     any(
         feature = "postgres",
         feature = "mysql",
         feature = "mssql",
-        feature = "sqlite"
+        feature = "sqlite",
+        feature = "memory"
     ),
     feature = "any"
 ))]
@@ -101,6 +119,25 @@ pub mod mysql;
 #[cfg_attr(docsrs, doc(cfg(feature = "mssql")))]
 pub mod mssql;
 
+#[cfg(feature = "memory")]
+#[cfg_attr(docsrs, doc(cfg(feature = "memory")))]
+pub mod memory;
+
+#[cfg(all(feature = "testing", any(feature = "_rt-tokio", feature = "_rt-actix")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+
+#[cfg(feature = "bench-support")]
+#[doc(hidden)]
+pub mod bench_support;
+
+#[cfg(all(
+    any(test, feature = "fuzzing"),
+    any(feature = "postgres", feature = "mysql")
+))]
+#[doc(hidden)]
+pub mod fuzz;
+
 /// sqlx uses ahash for increased performance, at the cost of reduced DoS resistance.
 use ahash::AHashMap as HashMap;
 //type HashMap<K, V> = std::collections::HashMap<K, V, ahash::RandomState>;
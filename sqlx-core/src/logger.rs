@@ -1,11 +1,18 @@
-use crate::connection::LogSettings;
-use std::time::Instant;
+use crate::connection::{BindLogging, LogSettings};
+use std::fmt::{self, Display, Formatter, Write};
+use std::time::{Duration, Instant};
+
+// rendered bind values longer than this many bytes are truncated
+const MAX_BIND_VALUE_LEN: usize = 100;
 
 pub(crate) struct QueryLogger<'q> {
     sql: &'q str,
     rows: usize,
     start: Instant,
     settings: LogSettings,
+    binds: Option<String>,
+    elapsed: Option<Duration>,
+    plan: Option<String>,
 }
 
 impl<'q> QueryLogger<'q> {
@@ -15,6 +22,33 @@ impl<'q> QueryLogger<'q> {
             rows: 0,
             start: Instant::now(),
             settings,
+            binds: None,
+            elapsed: None,
+            plan: None,
+        }
+    }
+
+    /// Records the bind values to include in the log line, if they are going to be logged.
+    ///
+    /// Values are only formatted if they are shown; redacted ones are skipped entirely.
+    pub(crate) fn bind_values<I>(&mut self, values: I)
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        let levels = [
+            self.settings.statements_level,
+            self.settings.slow_statements_level,
+        ];
+
+        let enabled = self.settings.bind_values.is_enabled()
+            && levels.iter().any(|lvl| {
+                lvl.to_level()
+                    .map_or(false, |lvl| log::log_enabled!(target: "sqlx::query", lvl))
+            });
+
+        if enabled {
+            self.binds = Some(format_bind_values(&self.settings.bind_values, values));
         }
     }
 
@@ -22,19 +56,46 @@ impl<'q> QueryLogger<'q> {
         self.rows += 1;
     }
 
-    pub(crate) fn finish(&self) {
-        let elapsed = self.start.elapsed();
+    /// Stops the clock, so that work done for the statement after it completed, such as
+    /// capturing its plan, is not counted in the time logged. Returns the time it took.
+    #[allow(dead_code)] // Only used by Postgres
+    pub(crate) fn stop(&mut self) -> Duration {
+        let start = self.start;
+
+        *self.elapsed.get_or_insert_with(|| start.elapsed())
+    }
+
+    /// True if finishing now logs a line for the statement.
+    #[allow(dead_code)] // Only used by Postgres
+    pub(crate) fn is_logged(&self) -> bool {
+        self.level().is_some()
+    }
+
+    /// Includes the plan of the statement in the log line.
+    #[allow(dead_code)] // Only used by Postgres
+    pub(crate) fn attach_plan(&mut self, plan: String) {
+        self.plan = Some(plan);
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.elapsed.unwrap_or_else(|| self.start.elapsed())
+    }
 
-        let lvl = if elapsed >= self.settings.slow_statements_duration {
+    fn level(&self) -> Option<log::Level> {
+        let lvl = if self.elapsed() >= self.settings.slow_statements_duration {
             self.settings.slow_statements_level
         } else {
             self.settings.statements_level
         };
 
-        if let Some(lvl) = lvl
-            .to_level()
+        lvl.to_level()
             .filter(|lvl| log::log_enabled!(target: "sqlx::query", *lvl))
-        {
+    }
+
+    pub(crate) fn finish(&self) {
+        let elapsed = self.elapsed();
+
+        if let Some(lvl) = self.level() {
             let mut summary = parse_query_summary(&self.sql);
 
             let sql = if summary != self.sql {
@@ -53,11 +114,21 @@ impl<'q> QueryLogger<'q> {
 
             let rows = self.rows;
 
+            let binds = match &self.binds {
+                Some(binds) => format!(", binds: {}", binds),
+                None => String::new(),
+            };
+
+            let plan = match &self.plan {
+                Some(plan) => format!("\n\nplan:\n{}\n", plan),
+                None => String::new(),
+            };
+
             log::logger().log(
                 &log::Record::builder()
                     .args(format_args!(
-                        "{}; rows: {}, elapsed: {:.3?}{}",
-                        summary, rows, elapsed, sql
+                        "{}; rows: {}, elapsed: {:.3?}{}{}{}",
+                        summary, rows, elapsed, binds, sql, plan
                     ))
                     .level(lvl)
                     .module_path_static(Some("sqlx::query"))
@@ -81,3 +152,98 @@ fn parse_query_summary(sql: &str) -> String {
         .collect::<Vec<&str>>()
         .join(" ")
 }
+
+/// Writes `s` as a quoted SQL string literal, for rendering bind values.
+#[allow(dead_code)]
+pub(crate) fn write_quoted(f: &mut Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('\'')?;
+
+    for c in s.chars() {
+        if c == '\'' {
+            f.write_str("''")?;
+        } else {
+            f.write_char(c)?;
+        }
+    }
+
+    f.write_char('\'')
+}
+
+/// Writes the digits of `bytes` in hex, for rendering bind values that have no readable form.
+#[allow(dead_code)]
+pub(crate) fn write_hex(f: &mut Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    for byte in bytes {
+        write!(f, "{:02x}", byte)?;
+    }
+
+    Ok(())
+}
+
+fn format_bind_values<I>(logging: &BindLogging, values: I) -> String
+where
+    I: IntoIterator,
+    I::Item: Display,
+{
+    let mut formatted = String::from("[");
+
+    for (position, value) in values.into_iter().enumerate() {
+        if position > 0 {
+            formatted.push_str(", ");
+        }
+
+        if logging.is_redacted(position) {
+            formatted.push_str("<redacted>");
+            continue;
+        }
+
+        let value = value.to_string();
+
+        if value.len() > MAX_BIND_VALUE_LEN {
+            let mut end = MAX_BIND_VALUE_LEN;
+            while !value.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            formatted.push_str(&value[..end]);
+            formatted.push_str(&format!("… ({} bytes)", value.len()));
+        } else {
+            formatted.push_str(&value);
+        }
+    }
+
+    formatted.push(']');
+    formatted
+}
+
+#[test]
+fn it_formats_bind_values() {
+    let values = ["1", "'secret'", "NULL"];
+
+    assert_eq!(
+        format_bind_values(&BindLogging::All, &values),
+        "[1, 'secret', NULL]"
+    );
+
+    assert_eq!(
+        format_bind_values(&BindLogging::Redacted(vec![1]), &values),
+        "[1, <redacted>, NULL]"
+    );
+
+    let redact_all = BindLogging::RedactWith(std::sync::Arc::new(|_| true));
+
+    assert_eq!(
+        format_bind_values(&redact_all, &values),
+        "[<redacted>, <redacted>, <redacted>]"
+    );
+
+    let long = "é".repeat(MAX_BIND_VALUE_LEN);
+
+    assert_eq!(
+        format_bind_values(&BindLogging::All, &[&long]),
+        format!(
+            "[{}… ({} bytes)]",
+            "é".repeat(MAX_BIND_VALUE_LEN / 2),
+            MAX_BIND_VALUE_LEN * 2
+        )
+    );
+}
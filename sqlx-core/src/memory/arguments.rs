@@ -0,0 +1,144 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::arguments::Arguments;
+use crate::encode::{DynEncode, Encode, EncodeError, IsNull};
+use crate::error::Error;
+use crate::logger::{write_hex, write_quoted};
+use crate::memory::value::Datum;
+use crate::memory::Memory;
+use crate::types::Type;
+
+/// The buffer that values are encoded into for the in-memory driver.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryArgumentBuffer(pub(crate) Vec<Datum>);
+
+/// Implementation of [`Arguments`] for the in-memory driver.
+#[derive(Debug, Default)]
+pub struct MemoryArguments {
+    pub(crate) values: MemoryArgumentBuffer,
+
+    // the first value that could not be encoded, with its index; reported before the statement
+    // is run
+    pub(crate) error: Option<(usize, EncodeError)>,
+}
+
+impl MemoryArguments {
+    pub(crate) fn add<'q, T>(&mut self, value: T)
+    where
+        T: Encode<'q, Memory>,
+    {
+        let index = self.values.0.len();
+
+        match value.encode(&mut self.values) {
+            Ok(IsNull::No) => {}
+
+            Ok(IsNull::Yes) => self.values.0.push(Datum::Null),
+
+            Err(error) => {
+                self.values.0.truncate(index);
+                self.values.0.push(Datum::Null);
+
+                if self.error.is_none() {
+                    self.error = Some((index, error));
+                }
+            }
+        }
+    }
+
+    pub(crate) fn check_encoded(&mut self) -> Result<(), Error> {
+        match self.error.take() {
+            Some((index, source)) => Err(Error::ArgumentEncode { index, source }),
+            None => Ok(()),
+        }
+    }
+}
+
+// the error is not `Clone`, so a copy of it keeps only its message
+impl Clone for MemoryArguments {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            error: self
+                .error
+                .as_ref()
+                .map(|(index, error)| (*index, error.to_string().into())),
+        }
+    }
+}
+
+impl<'q> Arguments<'q> for MemoryArguments {
+    type Database = Memory;
+
+    fn reserve(&mut self, additional: usize, _size: usize) {
+        self.values.0.reserve(additional);
+    }
+
+    fn add<T>(&mut self, value: T)
+    where
+        T: 'q + Send + Encode<'q, Self::Database> + Type<Self::Database>,
+    {
+        self.add(value)
+    }
+
+    fn add_dyn(&mut self, value: Box<dyn DynEncode<'q, Self::Database> + 'q>) {
+        self.add(value)
+    }
+
+    fn len(&self) -> usize {
+        self.values.0.len()
+    }
+
+    fn write_cache_key(&self, key: &mut Vec<u8>) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+
+        for value in &self.values.0 {
+            match value {
+                Datum::Null => key.push(0),
+                Datum::Bool(value) => key.extend_from_slice(&[1, *value as u8]),
+                Datum::Int(value) => {
+                    key.push(2);
+                    key.extend_from_slice(&value.to_le_bytes());
+                }
+                Datum::Float(value) => {
+                    key.push(3);
+                    key.extend_from_slice(&value.to_bits().to_le_bytes());
+                }
+                Datum::Text(value) => {
+                    key.push(4);
+                    key.extend_from_slice(&(value.len() as u64).to_le_bytes());
+                    key.extend_from_slice(value.as_bytes());
+                }
+                Datum::Blob(value) => {
+                    key.push(5);
+                    key.extend_from_slice(&(value.len() as u64).to_le_bytes());
+                    key.extend_from_slice(value);
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Renders a bind value for the statement log.
+pub(crate) struct MemoryBindValue<'a>(pub(crate) &'a Datum);
+
+impl Display for MemoryBindValue<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Datum::Null => f.write_str("NULL"),
+            Datum::Bool(v) => write!(f, "{}", v),
+            Datum::Int(v) => write!(f, "{}", v),
+            Datum::Float(v) => write!(f, "{}", v),
+            Datum::Text(v) => write_quoted(f, v),
+
+            Datum::Blob(bytes) => {
+                f.write_str("x'")?;
+                write_hex(f, bytes)?;
+                f.write_str("'")
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::column::Column;
+use crate::ext::ustr::UStr;
+use crate::memory::{Memory, MemoryTypeInfo};
+
+#[derive(Debug, Clone)]
+pub struct MemoryColumn {
+    pub(crate) name: UStr,
+    pub(crate) ordinal: usize,
+    pub(crate) type_info: MemoryTypeInfo,
+}
+
+impl crate::column::private_column::Sealed for MemoryColumn {}
+
+impl Column for MemoryColumn {
+    type Database = Memory;
+
+    fn ordinal(&self) -> usize {
+        self.ordinal
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn type_info(&self) -> &MemoryTypeInfo {
+        &self.type_info
+    }
+}
+
+#[cfg(feature = "any")]
+impl From<MemoryColumn> for crate::any::AnyColumn {
+    #[inline]
+    fn from(column: MemoryColumn) -> Self {
+        crate::any::AnyColumn {
+            type_info: column.type_info.clone().into(),
+            kind: crate::any::column::AnyColumnKind::Memory(column),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use either::Either;
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use futures_util::{future, TryStreamExt};
+
+use crate::connection::{Connection, ConnectionStats, LogSettings, StatsCollector};
+use crate::describe::Describe;
+use crate::error::Error;
+use crate::events::{CloseReason, ConnectionEvents};
+use crate::executor::{Execute, Executor};
+use crate::logger::QueryLogger;
+use crate::memory::arguments::MemoryBindValue;
+use crate::memory::sql::{self, Parsed};
+use crate::memory::store::{ResultColumn, Store};
+use crate::memory::value::Datum;
+use crate::memory::{
+    Memory, MemoryConnectOptions, MemoryError, MemoryQueryResult, MemoryRow, MemoryStatement,
+    MemoryTypeInfo,
+};
+use crate::middleware::Middleware;
+use crate::transaction::Transaction;
+use crate::HashMap;
+
+/// A connection to the in-memory driver.
+pub struct MemoryConnection {
+    store: Arc<Mutex<Store>>,
+
+    // a copy of the store from before each open transaction, innermost last
+    pub(crate) snapshots: Vec<Store>,
+
+    stats: StatsCollector,
+
+    log_settings: LogSettings,
+
+    middleware: Middleware,
+
+    // the listener of connection events, told when the connection is closed
+    events: ConnectionEvents,
+}
+
+impl MemoryConnection {
+    pub(crate) fn new(options: &MemoryConnectOptions, events: ConnectionEvents) -> Self {
+        Self {
+            store: Arc::clone(&options.store),
+            snapshots: Vec::new(),
+            stats: StatsCollector::new(),
+            log_settings: options.log_settings.clone(),
+            middleware: options.middleware.clone(),
+            events,
+        }
+    }
+
+    pub(crate) fn store(&self) -> MutexGuard<'_, Store> {
+        // the store is never left half-modified by a panic, so a poisoned lock is still valid
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // runs the statements of `sql`, stopping at the first that fails
+    fn run(
+        &mut self,
+        sql: &str,
+        arguments: &[Datum],
+    ) -> Vec<Result<Either<MemoryQueryResult, MemoryRow>, Error>> {
+        let started = Instant::now();
+        let mut results = Vec::new();
+
+        match parse(sql, arguments.len()) {
+            Ok(parsed) => {
+                let mut store = self.store();
+
+                for statement in &parsed.statements {
+                    match store.execute(statement, arguments) {
+                        Ok(rows) => results.extend(rows.into_iter().map(Ok)),
+
+                        Err(error) => {
+                            results.push(Err(error.into()));
+                            break;
+                        }
+                    }
+                }
+            }
+
+            Err(error) => results.push(Err(error)),
+        }
+
+        self.stats.record_query(started.elapsed());
+
+        results
+    }
+
+    // the number of parameters of `sql` and the columns of its first statement that
+    // returns any
+    fn describe_columns(&self, sql: &str) -> Result<(usize, Vec<ResultColumn>), Error> {
+        let parsed = sql::parse(sql)?;
+        let store = self.store();
+
+        for statement in &parsed.statements {
+            let columns = store.columns(statement)?;
+
+            if !columns.is_empty() {
+                return Ok((parsed.parameters, columns));
+            }
+        }
+
+        Ok((parsed.parameters, Vec::new()))
+    }
+}
+
+// parses `sql` and checks that it is given an argument for every parameter
+fn parse(sql: &str, arguments: usize) -> Result<Parsed, Error> {
+    let parsed = sql::parse(sql)?;
+
+    if parsed.parameters != arguments {
+        return Err(MemoryError::new(
+            "invalid_arguments",
+            format!(
+                "the query has {} parameters but {} arguments were bound",
+                parsed.parameters, arguments
+            ),
+        )
+        .into());
+    }
+
+    Ok(parsed)
+}
+
+impl Debug for MemoryConnection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryConnection")
+            .field("transaction_depth", &self.snapshots.len())
+            .finish()
+    }
+}
+
+impl Connection for MemoryConnection {
+    type Database = Memory;
+
+    type Options = MemoryConnectOptions;
+
+    fn close(self) -> BoxFuture<'static, Result<(), Error>> {
+        self.events.closed(CloseReason::Explicit);
+
+        Box::pin(future::ok(()))
+    }
+
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        self.stats.record_ping(Duration::from_secs(0));
+
+        Box::pin(future::ok(()))
+    }
+
+    fn begin(&mut self) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::begin(self)
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        // there is no stream to count bytes on
+        self.stats.snapshot(0, 0)
+    }
+
+    #[doc(hidden)]
+    fn take_events(&mut self) -> ConnectionEvents {
+        mem::take(&mut self.events)
+    }
+
+    #[doc(hidden)]
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(future::ok(()))
+    }
+
+    #[doc(hidden)]
+    fn should_flush(&self) -> bool {
+        false
+    }
+}
+
+impl MemoryConnection {
+    impl_executor_methods!(Memory, &mut self => self);
+}
+
+impl<'c> Executor<'c> for &'c mut MemoryConnection {
+    type Database = Memory;
+
+    fn fetch_many<'e, 'q, E>(
+        self,
+        mut query: E,
+    ) -> BoxStream<'e, Result<Either<MemoryQueryResult, MemoryRow>, Error>>
+    where
+        'c: 'e,
+        'q: 'e,
+        E: 'q + Execute<'q, Self::Database>,
+    {
+        let sql = query.sql();
+        let arguments = query.take_arguments();
+        let sql =
+            self.middleware
+                .before_execute(sql, "Memory", arguments.is_some(), query.persistent());
+        let mut arguments = arguments.unwrap_or_default();
+        let encoded = arguments.check_encoded();
+        let arguments = arguments.values.0;
+        let log_settings = self.log_settings.clone();
+
+        Box::pin(try_stream! {
+            let sql = sql?;
+            let mut logger = QueryLogger::new(&sql, log_settings);
+
+            logger.bind_values(arguments.iter().map(MemoryBindValue));
+
+            encoded?;
+
+            // the statements run to completion before the first row is returned, as the
+            // store cannot stay locked while the caller holds on to the stream
+            for result in self.run(&sql, &arguments) {
+                let result = result?;
+
+                if result.is_right() {
+                    logger.increment_rows();
+                }
+
+                r#yield!(result);
+            }
+
+            Ok(())
+        })
+    }
+
+    fn fetch_optional<'e, 'q, E>(self, query: E) -> BoxFuture<'e, Result<Option<MemoryRow>, Error>>
+    where
+        'c: 'e,
+        'q: 'e,
+        E: 'q + Execute<'q, Self::Database>,
+    {
+        let mut s = self.fetch_many(query);
+
+        Box::pin(async move {
+            while let Some(v) = s.try_next().await? {
+                if let Either::Right(r) = v {
+                    return Ok(Some(r));
+                }
+            }
+
+            Ok(None)
+        })
+    }
+
+    fn prepare_with<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+        _parameters: &[MemoryTypeInfo],
+    ) -> BoxFuture<'e, Result<MemoryStatement<'q>, Error>>
+    where
+        'c: 'e,
+    {
+        Box::pin(async move {
+            let (parameters, columns) = self.describe_columns(sql)?;
+
+            let mut column_names = HashMap::with_capacity(columns.len());
+
+            for (ordinal, column) in columns.iter().enumerate() {
+                column_names
+                    .entry(column.column.name.clone())
+                    .or_insert(ordinal);
+            }
+
+            Ok(MemoryStatement {
+                sql: Cow::Borrowed(sql),
+                parameters,
+                columns: Arc::new(columns.into_iter().map(|c| c.column).collect()),
+                column_names: Arc::new(column_names),
+            })
+        })
+    }
+
+    #[doc(hidden)]
+    fn describe<'e, 'q: 'e>(self, sql: &'q str) -> BoxFuture<'e, Result<Describe<Memory>, Error>>
+    where
+        'c: 'e,
+    {
+        Box::pin(async move {
+            let (parameters, columns) = self.describe_columns(sql)?;
+
+            Ok(Describe {
+                parameters: Some(Either::Right(parameters)),
+                nullable: columns.iter().map(|c| Some(!c.not_null)).collect(),
+                columns: columns.into_iter().map(|c| c.column).collect(),
+            })
+        })
+    }
+}
@@ -0,0 +1,57 @@
+use crate::database::{Capabilities, Database, HasArguments, HasStatement, HasValueRef};
+use crate::memory::{
+    MemoryArgumentBuffer, MemoryArguments, MemoryColumn, MemoryConnection, MemoryQueryResult,
+    MemoryRow, MemoryStatement, MemoryTransactionManager, MemoryTypeInfo, MemoryValue,
+    MemoryValueRef,
+};
+
+/// In-memory database driver, for tests.
+#[derive(Debug)]
+pub struct Memory;
+
+impl Database for Memory {
+    type Connection = MemoryConnection;
+
+    type TransactionManager = MemoryTransactionManager;
+
+    type Row = MemoryRow;
+
+    type QueryResult = MemoryQueryResult;
+
+    type Column = MemoryColumn;
+
+    type TypeInfo = MemoryTypeInfo;
+
+    type Value = MemoryValue;
+
+    const CAPABILITIES: Capabilities = Capabilities::from_bits_truncate(
+        Capabilities::SAVEPOINTS.bits()
+            | Capabilities::MULTIPLE_RESULT_SETS.bits()
+            | Capabilities::DOLLAR_PLACEHOLDERS.bits()
+            | Capabilities::QUESTION_MARK_PLACEHOLDERS.bits(),
+    );
+
+    const MAX_BIND_PARAMETERS: usize = usize::MAX;
+
+    const MAX_IDENTIFIER_LENGTH: Option<usize> = None;
+}
+
+impl<'r> HasValueRef<'r> for Memory {
+    type Database = Memory;
+
+    type ValueRef = MemoryValueRef<'r>;
+}
+
+impl<'q> HasArguments<'q> for Memory {
+    type Database = Memory;
+
+    type Arguments = MemoryArguments;
+
+    type ArgumentBuffer = MemoryArgumentBuffer;
+}
+
+impl<'q> HasStatement<'q> for Memory {
+    type Database = Memory;
+
+    type Statement = MemoryStatement<'q>;
+}
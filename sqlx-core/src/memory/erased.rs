@@ -0,0 +1,44 @@
+use crate::erased::{ErasedDatabase, ErasedQueryResult, ErasedValue};
+use crate::error::Error;
+use crate::memory::value::Datum;
+use crate::memory::{Memory, MemoryArguments, MemoryQueryResult, MemoryRow};
+
+impl ErasedDatabase for Memory {
+    fn add_erased(arguments: &mut MemoryArguments, value: ErasedValue) -> Result<(), Error> {
+        arguments.values.0.push(match value {
+            ErasedValue::Null => Datum::Null,
+            ErasedValue::Bool(v) => Datum::Bool(v),
+            ErasedValue::Int(v) => Datum::Int(v),
+            ErasedValue::Float(v) => Datum::Float(v),
+            ErasedValue::Text(v) => Datum::Text(v),
+            ErasedValue::Bytes(v) => Datum::Blob(v),
+        });
+
+        Ok(())
+    }
+
+    fn get_erased(row: &MemoryRow, index: usize) -> Result<ErasedValue, Error> {
+        if index >= row.values.len() {
+            return Err(Error::ColumnIndexOutOfBounds {
+                index,
+                len: row.values.len(),
+            });
+        }
+
+        Ok(match &row.values[index] {
+            Datum::Null => ErasedValue::Null,
+            Datum::Bool(v) => ErasedValue::Bool(*v),
+            Datum::Int(v) => ErasedValue::Int(*v),
+            Datum::Float(v) => ErasedValue::Float(*v),
+            Datum::Text(v) => ErasedValue::Text(v.clone()),
+            Datum::Blob(v) => ErasedValue::Bytes(v.clone()),
+        })
+    }
+
+    fn erase_query_result(result: MemoryQueryResult) -> ErasedQueryResult {
+        ErasedQueryResult {
+            rows_affected: result.rows_affected(),
+            last_insert_id: result.last_insert_id(),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+
+use crate::error::DatabaseError;
+
+/// An error returned by the in-memory driver, e.g. for unsupported SQL or a constraint violation.
+#[derive(Debug)]
+pub struct MemoryError {
+    code: &'static str,
+    message: String,
+}
+
+impl MemoryError {
+    pub(crate) fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn unsupported(clause: &str) -> Self {
+        Self::new(
+            "unsupported",
+            format!("memory driver does not support `{}`", clause),
+        )
+    }
+}
+
+impl Display for MemoryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(&self.message)
+    }
+}
+
+impl StdError for MemoryError {}
+
+impl DatabaseError for MemoryError {
+    /// A short name for the kind of error, e.g. `unsupported` or `unique_violation`.
+    #[inline]
+    fn code(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self.code))
+    }
+
+    #[inline]
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    #[doc(hidden)]
+    fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    #[doc(hidden)]
+    fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    #[doc(hidden)]
+    fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+        self
+    }
+
+    fn is_connection_error(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,77 @@
+//! In-memory database driver, for tests.
+//!
+//! This is not a real database. It keeps tables in the memory of the process and understands
+//! just enough SQL for the unit tests of code written against [`Any`](crate::any::Any) or a
+//! generic [`Executor`](crate::executor::Executor) to run without a database server:
+//!
+//! * `CREATE TABLE [IF NOT EXISTS]` with `BOOLEAN`, `INTEGER`, `REAL`, `TEXT` and `BLOB`
+//!   columns (and their common aliases) that may be `NOT NULL` or the `PRIMARY KEY`
+//! * `INSERT INTO ... [(columns)] VALUES (...), ...`
+//! * `SELECT * | columns [AS alias] FROM ... [WHERE ...]`
+//! * `DELETE FROM ... [WHERE ...]`
+//!
+//! where a `WHERE` clause is a list of `column = value` and `column IS [NOT] NULL` conditions
+//! joined by `AND`. Values are literals or parameters, written as `?` or `$1`. Anything else
+//! fails with a [`MemoryError`] that quotes the clause the driver does not support.
+//!
+//! Rows are returned in the order they were inserted. An `INTEGER PRIMARY KEY` column takes
+//! the row ID, which is assigned from 1 up when no value is given and reported as the last
+//! insert ID, as in SQLite.
+//!
+//! The URL `memory:` opens an empty database; see [`MemoryConnectOptions`] for how
+//! connections share one.
+
+mod arguments;
+mod column;
+mod connection;
+mod database;
+mod erased;
+mod error;
+mod options;
+mod query_result;
+mod row;
+mod sql;
+mod statement;
+mod store;
+mod transaction;
+mod type_info;
+pub mod types;
+mod value;
+
+pub use arguments::{MemoryArgumentBuffer, MemoryArguments};
+pub use column::MemoryColumn;
+pub use connection::MemoryConnection;
+pub use database::Memory;
+pub use error::MemoryError;
+pub use options::MemoryConnectOptions;
+pub use query_result::MemoryQueryResult;
+pub use row::MemoryRow;
+pub use statement::MemoryStatement;
+pub use transaction::MemoryTransactionManager;
+pub use type_info::MemoryTypeInfo;
+pub use value::{MemoryValue, MemoryValueRef};
+
+// to make rows in the tests of other modules
+#[cfg(test)]
+pub(crate) use value::Datum;
+
+/// An alias for [`Pool`][crate::pool::Pool], specialized for the in-memory driver.
+pub type MemoryPool = crate::pool::Pool<Memory>;
+
+/// An alias for [`PoolOptions`][crate::pool::PoolOptions], specialized for the in-memory driver.
+pub type MemoryPoolOptions = crate::pool::PoolOptions<Memory>;
+
+// NOTE: required due to the lack of lazy normalization
+impl_into_arguments_for_arguments!(MemoryArguments);
+impl_executor_for_pool_connection!(Memory, MemoryConnection, MemoryRow);
+impl_executor_for_transaction!(Memory, MemoryRow);
+impl_column_index_for_row!(MemoryRow);
+impl_column_index_for_statement!(MemoryStatement);
+impl_acquire!(Memory, MemoryConnection);
+impl_into_maybe_pool!(Memory, MemoryConnection);
+
+// required because some databases have a different handling of NULL
+impl_encode_for_option!(Memory);
+
+#[cfg(test)]
+assert_driver_futures_are_send!(Memory, MemoryConnection);
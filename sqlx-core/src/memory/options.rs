@@ -0,0 +1,144 @@
+use std::fmt::{self, Debug, Formatter};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+use log::LevelFilter;
+
+use crate::connection::{BindLogging, ConnectOptions, LogSettings};
+use crate::error::Error;
+use crate::events::{ConnectionEventListener, ConnectionEvents, ConnectionInfo, EventListener};
+use crate::memory::store::Store;
+use crate::memory::MemoryConnection;
+use crate::middleware::{Middleware, QueryMiddleware};
+
+/// Options for connecting to the in-memory driver.
+///
+/// Every call to [`new`](Self::new), and every parse of the URL `memory:`, creates an empty
+/// database. The connections opened with an instance of the options, its clones and a pool
+/// built from them all see the same tables, which live until the last of them is dropped.
+///
+/// ```rust,no_run
+/// # use sqlx_core::error::Error;
+/// # use sqlx_core::connection::{Connection, ConnectOptions};
+/// # use sqlx_core::memory::MemoryConnectOptions;
+/// # fn main() {
+/// # #[cfg(feature = "_rt-async-std")]
+/// # sqlx_rt::async_std::task::block_on::<_, Result<(), Error>>(async move {
+/// let options = MemoryConnectOptions::new();
+///
+/// let mut conn = options.connect().await?;
+/// conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)").await?;
+///
+/// // sees the table created above
+/// let mut other = options.connect().await?;
+/// other.execute("INSERT INTO users (name) VALUES ('alice')").await?;
+/// # Ok(())
+/// # }).unwrap();
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct MemoryConnectOptions {
+    pub(crate) store: Arc<Mutex<Store>>,
+    pub(crate) log_settings: LogSettings,
+    pub(crate) middleware: Middleware,
+    pub(crate) events: EventListener,
+}
+
+impl Default for MemoryConnectOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryConnectOptions {
+    pub fn new() -> Self {
+        Self {
+            store: Arc::default(),
+            log_settings: Default::default(),
+            middleware: Default::default(),
+            events: Default::default(),
+        }
+    }
+}
+
+impl Debug for MemoryConnectOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryConnectOptions")
+            .field("log_settings", &self.log_settings)
+            .field("middleware", &self.middleware)
+            .field("events", &self.events)
+            .finish()
+    }
+}
+
+impl FromStr for MemoryConnectOptions {
+    type Err = Error;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        match url {
+            "memory:" | "memory://" => Ok(Self::new()),
+
+            _ => Err(Error::Configuration(
+                format!(
+                    "the URL of the memory driver must be `memory:`, found {:?}",
+                    url
+                )
+                .into(),
+            )),
+        }
+    }
+}
+
+impl ConnectOptions for MemoryConnectOptions {
+    type Connection = MemoryConnection;
+
+    fn connect(&self) -> BoxFuture<'_, Result<Self::Connection, Error>>
+    where
+        Self::Connection: Sized,
+    {
+        Box::pin(async move {
+            let events = ConnectionEvents::new(&self.events, ConnectionInfo::new("Memory"));
+            events.connected();
+
+            Ok(MemoryConnection::new(self, events))
+        })
+    }
+
+    fn log_statements(&mut self, level: LevelFilter) -> &mut Self {
+        self.log_settings.log_statements(level);
+        self
+    }
+
+    fn log_slow_statements(&mut self, level: LevelFilter, duration: Duration) -> &mut Self {
+        self.log_settings.log_slow_statements(level, duration);
+        self
+    }
+
+    fn log_bind_values(&mut self, logging: BindLogging) -> &mut Self {
+        self.log_settings.log_bind_values(logging);
+        self
+    }
+
+    fn query_middleware(&mut self, middleware: Arc<dyn QueryMiddleware>) -> &mut Self {
+        self.middleware = Middleware(Some(middleware));
+        self
+    }
+
+    fn connection_events(&mut self, listener: Arc<dyn ConnectionEventListener>) -> &mut Self {
+        self.events = EventListener(Some(listener));
+        self
+    }
+}
+
+#[test]
+fn it_parses_the_memory_url() {
+    assert!("memory:".parse::<MemoryConnectOptions>().is_ok());
+    assert!("memory://".parse::<MemoryConnectOptions>().is_ok());
+
+    let err = "memory://tests"
+        .parse::<MemoryConnectOptions>()
+        .unwrap_err();
+    assert!(matches!(err, Error::Configuration(_)));
+}
@@ -0,0 +1,41 @@
+use std::iter::{Extend, IntoIterator};
+
+#[derive(Debug, Default)]
+pub struct MemoryQueryResult {
+    pub(super) rows_affected: u64,
+    pub(super) last_insert_id: Option<i64>,
+}
+
+impl MemoryQueryResult {
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    /// The row ID of the last row inserted by an `INSERT`, which is the value of its
+    /// `INTEGER PRIMARY KEY` column if the table has one.
+    pub fn last_insert_id(&self) -> Option<i64> {
+        self.last_insert_id
+    }
+}
+
+impl Extend<MemoryQueryResult> for MemoryQueryResult {
+    fn extend<T: IntoIterator<Item = MemoryQueryResult>>(&mut self, iter: T) {
+        for elem in iter {
+            self.rows_affected += elem.rows_affected;
+
+            if elem.last_insert_id.is_some() {
+                self.last_insert_id = elem.last_insert_id;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "any")]
+impl From<MemoryQueryResult> for crate::any::AnyQueryResult {
+    fn from(done: MemoryQueryResult) -> Self {
+        crate::any::AnyQueryResult {
+            rows_affected: done.rows_affected,
+            last_insert_id: done.last_insert_id,
+        }
+    }
+}
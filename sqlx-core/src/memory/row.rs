@@ -0,0 +1,76 @@
+#![allow(clippy::rc_buffer)]
+
+use std::sync::Arc;
+
+use crate::column::ColumnIndex;
+use crate::error::Error;
+use crate::ext::ustr::UStr;
+use crate::memory::value::Datum;
+use crate::memory::{Memory, MemoryColumn, MemoryValueRef};
+use crate::row::Row;
+use crate::HashMap;
+
+/// Implementation of [`Row`] for the in-memory driver.
+#[derive(Debug, Clone)]
+pub struct MemoryRow {
+    pub(crate) values: Vec<Datum>,
+    pub(crate) columns: Arc<Vec<MemoryColumn>>,
+    pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+}
+
+impl crate::row::private_row::Sealed for MemoryRow {}
+
+impl Row for MemoryRow {
+    type Database = Memory;
+
+    fn columns(&self) -> &[MemoryColumn] {
+        &self.columns
+    }
+
+    fn try_get_raw<I>(&self, index: I) -> Result<MemoryValueRef<'_>, Error>
+    where
+        I: ColumnIndex<Self>,
+    {
+        let index = index.index(self)?;
+
+        Ok(MemoryValueRef {
+            datum: &self.values[index],
+            type_info: &self.columns[index].type_info,
+        })
+    }
+
+    fn try_share(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+
+    fn data_len(&self) -> usize {
+        self.values
+            .iter()
+            .map(|value| match value {
+                Datum::Text(text) => text.len(),
+                Datum::Blob(blob) => blob.len(),
+                _ => 8,
+            })
+            .sum()
+    }
+}
+
+impl ColumnIndex<MemoryRow> for &'_ str {
+    fn index(&self, row: &MemoryRow) -> Result<usize, Error> {
+        row.column_names
+            .get(*self)
+            .copied()
+            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
+    }
+}
+
+#[cfg(feature = "any")]
+impl From<MemoryRow> for crate::any::AnyRow {
+    #[inline]
+    fn from(row: MemoryRow) -> Self {
+        crate::any::AnyRow {
+            columns: row.columns.iter().map(|col| col.clone().into()).collect(),
+            kind: crate::any::row::AnyRowKind::Memory(row),
+        }
+    }
+}
@@ -0,0 +1,712 @@
+// A parser for the small subset of SQL the in-memory driver understands:
+//
+//   CREATE TABLE [IF NOT EXISTS] t (column TYPE [PRIMARY KEY | NOT NULL | NULL]..., ...)
+//   INSERT INTO t [(column, ...)] VALUES (expr, ...), ...
+//   SELECT * | column [AS alias], ... FROM t [WHERE predicate [AND predicate]...]
+//   DELETE FROM t [WHERE predicate [AND predicate]...]
+//
+// where an expression is a literal or a parameter (`?` or `$N`) and a predicate is
+// `column = expr` or `column IS [NOT] NULL`. Statements are separated by `;`.
+
+use crate::memory::type_info::DataType;
+use crate::memory::value::Datum;
+use crate::memory::MemoryError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Statement {
+    CreateTable {
+        table: String,
+        if_not_exists: bool,
+        columns: Vec<ColumnDef>,
+    },
+
+    Insert {
+        table: String,
+        columns: Option<Vec<String>>,
+        rows: Vec<Vec<Expr>>,
+    },
+
+    Select {
+        table: String,
+        projection: Projection,
+        filter: Vec<Predicate>,
+    },
+
+    Delete {
+        table: String,
+        filter: Vec<Predicate>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ColumnDef {
+    pub(crate) name: String,
+    pub(crate) data_type: DataType,
+    pub(crate) not_null: bool,
+    pub(crate) primary_key: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Projection {
+    All,
+
+    // (column, name of the result column)
+    Columns(Vec<(String, String)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expr {
+    Literal(Datum),
+
+    // zero-based index into the arguments of the query
+    Param(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Predicate {
+    Eq(String, Expr),
+    IsNull { column: String, negated: bool },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Parsed {
+    pub(crate) statements: Vec<Statement>,
+
+    // the number of arguments the statements refer to
+    pub(crate) parameters: usize,
+}
+
+pub(crate) fn parse(sql: &str) -> Result<Parsed, MemoryError> {
+    let tokens = tokenize(sql)?;
+
+    let parameters = tokens
+        .iter()
+        .filter_map(|t| match t.token {
+            Token::Param(index) => Some(index + 1),
+            _ => None,
+        })
+        .fold(0, |max, n| if n > max { n } else { max });
+
+    let mut statements = Vec::new();
+    let mut rest = &tokens[..];
+
+    while !rest.is_empty() {
+        let len = rest
+            .iter()
+            .position(|t| t.token == Token::Punct(';'))
+            .unwrap_or(rest.len());
+
+        if len > 0 {
+            let mut parser = Parser {
+                sql,
+                tokens: &rest[..len],
+                end: rest.get(len).map_or(sql.len(), |t| t.start),
+                pos: 0,
+            };
+
+            statements.push(parser.statement()?);
+        }
+
+        rest = rest.get(len + 1..).unwrap_or_default();
+    }
+
+    Ok(Parsed {
+        statements,
+        parameters,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    // an unquoted identifier or keyword
+    Word(String),
+    Quoted(String),
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Param(usize),
+    Punct(char),
+}
+
+#[derive(Debug)]
+struct Spanned {
+    token: Token,
+
+    // byte offset of the token in the SQL
+    start: usize,
+}
+
+fn tokenize(sql: &str) -> Result<Vec<Spanned>, MemoryError> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.char_indices().peekable();
+    let mut next_param = 0;
+
+    while let Some(&(start, c)) = chars.peek() {
+        let token = match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+
+            '-' if sql[start..].starts_with("--") => {
+                for (_, c) in &mut chars {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+
+                continue;
+            }
+
+            '\'' | '"' | '`' => {
+                chars.next();
+
+                let mut value = String::new();
+
+                loop {
+                    match chars.next() {
+                        // a doubled quote is an escaped quote
+                        Some((_, q)) if q == c => match chars.peek() {
+                            Some(&(_, q)) if q == c => {
+                                chars.next();
+                                value.push(c);
+                            }
+
+                            _ => break,
+                        },
+
+                        Some((_, ch)) => value.push(ch),
+
+                        None => return Err(MemoryError::unsupported(&sql[start..])),
+                    }
+                }
+
+                if c == '\'' {
+                    Token::Str(value)
+                } else {
+                    Token::Quoted(value)
+                }
+            }
+
+            '?' => {
+                chars.next();
+                next_param += 1;
+
+                Token::Param(next_param - 1)
+            }
+
+            '$' => {
+                chars.next();
+
+                let end = scan(&mut chars, sql.len(), |c| c.is_ascii_digit());
+
+                match sql[start + 1..end].parse::<usize>() {
+                    Ok(n) if n > 0 => Token::Param(n - 1),
+                    _ => return Err(MemoryError::unsupported(&sql[start..])),
+                }
+            }
+
+            _ if c.is_ascii_digit() => {
+                let end = scan(&mut chars, sql.len(), |c| c.is_ascii_digit() || c == '.');
+                let number = &sql[start..end];
+
+                if number.contains('.') {
+                    match number.parse() {
+                        Ok(v) => Token::Float(v),
+                        Err(_) => return Err(MemoryError::unsupported(&sql[start..])),
+                    }
+                } else {
+                    match number.parse() {
+                        Ok(v) => Token::Int(v),
+                        Err(_) => return Err(MemoryError::unsupported(&sql[start..])),
+                    }
+                }
+            }
+
+            _ if c.is_alphabetic() || c == '_' => {
+                let end = scan(&mut chars, sql.len(), |c| c.is_alphanumeric() || c == '_');
+
+                Token::Word(sql[start..end].to_owned())
+            }
+
+            _ => {
+                chars.next();
+
+                Token::Punct(c)
+            }
+        };
+
+        tokens.push(Spanned { token, start });
+    }
+
+    Ok(tokens)
+}
+
+// advances past the characters matching `accept` and returns the offset after them
+fn scan(
+    chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>,
+    len: usize,
+    accept: impl Fn(char) -> bool,
+) -> usize {
+    while let Some(&(offset, c)) = chars.peek() {
+        if !accept(c) {
+            return offset;
+        }
+
+        chars.next();
+    }
+
+    len
+}
+
+struct Parser<'a> {
+    sql: &'a str,
+    tokens: &'a [Spanned],
+
+    // byte offset of the end of the statement
+    end: usize,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn statement(&mut self) -> Result<Statement, MemoryError> {
+        let statement = if self.keyword("CREATE") {
+            self.expect_keyword("TABLE")?;
+            self.create_table()?
+        } else if self.keyword("INSERT") {
+            self.expect_keyword("INTO")?;
+            self.insert()?
+        } else if self.keyword("SELECT") {
+            self.select()?
+        } else if self.keyword("DELETE") {
+            self.expect_keyword("FROM")?;
+
+            Statement::Delete {
+                table: self.identifier()?,
+                filter: self.filter()?,
+            }
+        } else {
+            return Err(self.unsupported());
+        };
+
+        if self.pos < self.tokens.len() {
+            return Err(self.unsupported());
+        }
+
+        Ok(statement)
+    }
+
+    fn create_table(&mut self) -> Result<Statement, MemoryError> {
+        let if_not_exists = if self.keyword("IF") {
+            self.expect_keyword("NOT")?;
+            self.expect_keyword("EXISTS")?;
+
+            true
+        } else {
+            false
+        };
+
+        let table = self.identifier()?;
+        let mut columns = Vec::new();
+
+        self.expect_punct('(')?;
+
+        loop {
+            columns.push(self.column_def()?);
+
+            if !self.punct(',') {
+                break;
+            }
+        }
+
+        self.expect_punct(')')?;
+
+        Ok(Statement::CreateTable {
+            table,
+            if_not_exists,
+            columns,
+        })
+    }
+
+    fn column_def(&mut self) -> Result<ColumnDef, MemoryError> {
+        let name = self.identifier()?;
+
+        let data_type = match self.peek() {
+            Some(Token::Word(ty)) => DataType::from_declared(ty),
+            _ => None,
+        }
+        .ok_or_else(|| self.unsupported())?;
+
+        self.pos += 1;
+
+        // DOUBLE PRECISION
+        if data_type == DataType::Float {
+            self.keyword("PRECISION");
+        }
+
+        // the length of VARCHAR(255) and the like is not enforced
+        if self.punct('(') {
+            while let Some(Token::Int(_)) | Some(Token::Punct(',')) = self.peek() {
+                self.pos += 1;
+            }
+
+            self.expect_punct(')')?;
+        }
+
+        let mut column = ColumnDef {
+            name,
+            data_type,
+            not_null: false,
+            primary_key: false,
+        };
+
+        loop {
+            if self.keyword("PRIMARY") {
+                self.expect_keyword("KEY")?;
+                column.primary_key = true;
+            } else if self.keyword("NOT") {
+                self.expect_keyword("NULL")?;
+                column.not_null = true;
+            } else if !self.keyword("NULL") {
+                break;
+            }
+        }
+
+        match self.peek() {
+            Some(Token::Punct(',')) | Some(Token::Punct(')')) => Ok(column),
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    fn insert(&mut self) -> Result<Statement, MemoryError> {
+        let table = self.identifier()?;
+
+        let columns = if self.punct('(') {
+            let columns = self.list(Self::identifier)?;
+            self.expect_punct(')')?;
+
+            Some(columns)
+        } else {
+            None
+        };
+
+        self.expect_keyword("VALUES")?;
+
+        let mut rows = Vec::new();
+
+        loop {
+            self.expect_punct('(')?;
+            rows.push(self.list(Self::expr)?);
+            self.expect_punct(')')?;
+
+            if !self.punct(',') {
+                break;
+            }
+        }
+
+        Ok(Statement::Insert {
+            table,
+            columns,
+            rows,
+        })
+    }
+
+    fn select(&mut self) -> Result<Statement, MemoryError> {
+        let projection = if self.punct('*') {
+            Projection::All
+        } else {
+            Projection::Columns(self.list(|p| {
+                let column = p.identifier()?;
+
+                let name = if p.keyword("AS") {
+                    p.identifier()?
+                } else {
+                    column.clone()
+                };
+
+                Ok((column, name))
+            })?)
+        };
+
+        self.expect_keyword("FROM")?;
+
+        Ok(Statement::Select {
+            table: self.identifier()?,
+            projection,
+            filter: self.filter()?,
+        })
+    }
+
+    fn filter(&mut self) -> Result<Vec<Predicate>, MemoryError> {
+        let mut predicates = Vec::new();
+
+        if !self.keyword("WHERE") {
+            return Ok(predicates);
+        }
+
+        loop {
+            let column = self.identifier()?;
+
+            predicates.push(if self.punct('=') {
+                Predicate::Eq(column, self.expr()?)
+            } else if self.keyword("IS") {
+                let negated = self.keyword("NOT");
+                self.expect_keyword("NULL")?;
+
+                Predicate::IsNull { column, negated }
+            } else {
+                return Err(self.unsupported());
+            });
+
+            if !self.keyword("AND") {
+                return Ok(predicates);
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, MemoryError> {
+        let negative = self.punct('-');
+
+        let expr = match (self.peek(), negative) {
+            (Some(Token::Int(v)), _) => Expr::Literal(Datum::Int(if negative { -v } else { *v })),
+            (Some(Token::Float(v)), _) => {
+                Expr::Literal(Datum::Float(if negative { -v } else { *v }))
+            }
+
+            (Some(Token::Str(v)), false) => Expr::Literal(Datum::Text(v.clone())),
+            (Some(Token::Param(index)), false) => Expr::Param(*index),
+
+            (Some(Token::Word(word)), false) if word.eq_ignore_ascii_case("NULL") => {
+                Expr::Literal(Datum::Null)
+            }
+
+            (Some(Token::Word(word)), false) if word.eq_ignore_ascii_case("TRUE") => {
+                Expr::Literal(Datum::Bool(true))
+            }
+
+            (Some(Token::Word(word)), false) if word.eq_ignore_ascii_case("FALSE") => {
+                Expr::Literal(Datum::Bool(false))
+            }
+
+            _ => return Err(self.unsupported()),
+        };
+
+        self.pos += 1;
+
+        Ok(expr)
+    }
+
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, MemoryError>,
+    ) -> Result<Vec<T>, MemoryError> {
+        let mut items = vec![item(self)?];
+
+        while self.punct(',') {
+            items.push(item(self)?);
+        }
+
+        Ok(items)
+    }
+
+    fn identifier(&mut self) -> Result<String, MemoryError> {
+        match self.peek() {
+            Some(Token::Word(name)) | Some(Token::Quoted(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+
+                Ok(name)
+            }
+
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+
+            _ => false,
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), MemoryError> {
+        if self.keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.unsupported())
+        }
+    }
+
+    fn punct(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<(), MemoryError> {
+        if self.punct(c) {
+            Ok(())
+        } else {
+            Err(self.unsupported())
+        }
+    }
+
+    // the error for the clause starting at the current token; at the end of the statement,
+    // the whole statement is reported instead
+    fn unsupported(&self) -> MemoryError {
+        let start = match self.tokens.get(self.pos) {
+            Some(token) => token.start,
+            None => self.tokens[0].start,
+        };
+
+        MemoryError::unsupported(self.sql[start..self.end].trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(sql: &str) -> Statement {
+        let mut parsed = parse(sql).unwrap();
+        assert_eq!(parsed.statements.len(), 1);
+
+        parsed.statements.remove(0)
+    }
+
+    fn unsupported(sql: &str) -> String {
+        parse(sql).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn it_parses_create_table() {
+        assert_eq!(
+            parse_one(
+                "CREATE TABLE IF NOT EXISTS users (id INTEGER PRIMARY KEY, name VARCHAR(255) NOT NULL, score DOUBLE PRECISION)"
+            ),
+            Statement::CreateTable {
+                table: "users".into(),
+                if_not_exists: true,
+                columns: vec![
+                    ColumnDef {
+                        name: "id".into(),
+                        data_type: DataType::Int,
+                        not_null: false,
+                        primary_key: true,
+                    },
+                    ColumnDef {
+                        name: "name".into(),
+                        data_type: DataType::Text,
+                        not_null: true,
+                        primary_key: false,
+                    },
+                    ColumnDef {
+                        name: "score".into(),
+                        data_type: DataType::Float,
+                        not_null: false,
+                        primary_key: false,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_insert_with_literals_and_parameters() {
+        let parsed = parse(
+            "INSERT INTO t (a, b) VALUES (?, 'it''s'), (-1.5, NULL); INSERT INTO t VALUES (?, $1)",
+        )
+        .unwrap();
+
+        assert_eq!(parsed.parameters, 2);
+        assert_eq!(
+            parsed.statements,
+            vec![
+                Statement::Insert {
+                    table: "t".into(),
+                    columns: Some(vec!["a".into(), "b".into()]),
+                    rows: vec![
+                        vec![Expr::Param(0), Expr::Literal(Datum::Text("it's".into()))],
+                        vec![
+                            Expr::Literal(Datum::Float(-1.5)),
+                            Expr::Literal(Datum::Null)
+                        ],
+                    ],
+                },
+                Statement::Insert {
+                    table: "t".into(),
+                    columns: None,
+                    rows: vec![vec![Expr::Param(1), Expr::Param(0)]],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_parses_select_and_delete() {
+        assert_eq!(
+            parse_one("select id, name as n from \"Users\" where id = $2 and name is not null"),
+            Statement::Select {
+                table: "Users".into(),
+                projection: Projection::Columns(vec![
+                    ("id".into(), "id".into()),
+                    ("name".into(), "n".into())
+                ]),
+                filter: vec![
+                    Predicate::Eq("id".into(), Expr::Param(1)),
+                    Predicate::IsNull {
+                        column: "name".into(),
+                        negated: true
+                    },
+                ],
+            }
+        );
+
+        assert_eq!(
+            parse_one("DELETE FROM users -- everything\n"),
+            Statement::Delete {
+                table: "users".into(),
+                filter: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn it_reports_the_unsupported_clause() {
+        assert_eq!(
+            unsupported("UPDATE users SET name = 'x'"),
+            "memory driver does not support `UPDATE users SET name = 'x'`"
+        );
+
+        assert_eq!(
+            unsupported("SELECT id FROM users WHERE id > 1; SELECT 1"),
+            "memory driver does not support `> 1`"
+        );
+
+        assert_eq!(
+            unsupported("SELECT * FROM users ORDER BY id"),
+            "memory driver does not support `ORDER BY id`"
+        );
+
+        assert_eq!(
+            unsupported("CREATE TABLE t (at TIMESTAMP)"),
+            "memory driver does not support `TIMESTAMP)`"
+        );
+
+        assert_eq!(
+            unsupported("INSERT INTO t VALUES (1"),
+            "memory driver does not support `INSERT INTO t VALUES (1`"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use either::Either;
+
+use crate::column::ColumnIndex;
+use crate::error::Error;
+use crate::ext::ustr::UStr;
+use crate::memory::{Memory, MemoryArguments, MemoryColumn, MemoryTypeInfo};
+use crate::statement::Statement;
+use crate::HashMap;
+
+#[derive(Debug, Clone)]
+#[allow(clippy::rc_buffer)]
+pub struct MemoryStatement<'q> {
+    pub(crate) sql: Cow<'q, str>,
+    pub(crate) parameters: usize,
+    pub(crate) columns: Arc<Vec<MemoryColumn>>,
+    pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+}
+
+impl<'q> Statement<'q> for MemoryStatement<'q> {
+    type Database = Memory;
+
+    fn to_owned(&self) -> MemoryStatement<'static> {
+        MemoryStatement::<'static> {
+            sql: Cow::Owned(self.sql.clone().into_owned()),
+            parameters: self.parameters,
+            columns: Arc::clone(&self.columns),
+            column_names: Arc::clone(&self.column_names),
+        }
+    }
+
+    fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    fn parameters(&self) -> Option<Either<&[MemoryTypeInfo], usize>> {
+        Some(Either::Right(self.parameters))
+    }
+
+    fn columns(&self) -> &[MemoryColumn] {
+        &self.columns
+    }
+
+    impl_statement_query!(MemoryArguments);
+}
+
+impl ColumnIndex<MemoryStatement<'_>> for &'_ str {
+    fn index(&self, statement: &MemoryStatement<'_>) -> Result<usize, Error> {
+        statement
+            .column_names
+            .get(*self)
+            .copied()
+            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
+    }
+}
+
+#[cfg(feature = "any")]
+impl<'q> From<MemoryStatement<'q>> for crate::any::AnyStatement<'q> {
+    #[inline]
+    fn from(statement: MemoryStatement<'q>) -> Self {
+        crate::any::AnyStatement::<'q> {
+            columns: statement
+                .columns
+                .iter()
+                .map(|col| col.clone().into())
+                .collect(),
+            column_names: statement.column_names,
+            parameters: Some(Either::Right(statement.parameters)),
+            sql: statement.sql,
+        }
+    }
+}
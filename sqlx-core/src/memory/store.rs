@@ -0,0 +1,618 @@
+use std::sync::Arc;
+
+use either::Either;
+
+use crate::ext::ustr::UStr;
+use crate::memory::sql::{ColumnDef, Expr, Predicate, Projection, Statement};
+use crate::memory::type_info::DataType;
+use crate::memory::value::Datum;
+use crate::memory::{MemoryColumn, MemoryError, MemoryQueryResult, MemoryRow, MemoryTypeInfo};
+use crate::HashMap;
+
+// the tables shared by the connections of one set of options
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Store {
+    tables: Vec<Table>,
+}
+
+#[derive(Debug, Clone)]
+struct Table {
+    name: String,
+    columns: Vec<ColumnDef>,
+
+    // (rowid, values) in insertion order
+    rows: Vec<(i64, Vec<Datum>)>,
+    next_rowid: i64,
+
+    // the INTEGER PRIMARY KEY column, which holds the rowid
+    rowid_column: Option<usize>,
+}
+
+// a result column of a SELECT
+pub(crate) struct ResultColumn {
+    pub(crate) column: MemoryColumn,
+    pub(crate) not_null: bool,
+
+    // index of the column in its table
+    index: usize,
+}
+
+impl Store {
+    pub(crate) fn execute(
+        &mut self,
+        statement: &Statement,
+        arguments: &[Datum],
+    ) -> Result<Vec<Either<MemoryQueryResult, MemoryRow>>, MemoryError> {
+        let done = match statement {
+            Statement::CreateTable {
+                table,
+                if_not_exists,
+                columns,
+            } => self.create_table(table, *if_not_exists, columns)?,
+
+            Statement::Insert {
+                table,
+                columns,
+                rows,
+            } => self
+                .table_mut(table)?
+                .insert(columns.as_deref(), rows, arguments)?,
+
+            Statement::Select { .. } => return self.select(statement, arguments),
+
+            Statement::Delete { table, filter } => {
+                let table = self.table_mut(table)?;
+                let filter = table.filter(filter, arguments)?;
+                let before = table.rows.len();
+
+                table.rows.retain(|(_, row)| !matches(&filter, row));
+
+                MemoryQueryResult {
+                    rows_affected: (before - table.rows.len()) as u64,
+                    last_insert_id: None,
+                }
+            }
+        };
+
+        Ok(vec![Either::Left(done)])
+    }
+
+    // the columns a statement returns
+    pub(crate) fn columns(&self, statement: &Statement) -> Result<Vec<ResultColumn>, MemoryError> {
+        let (table, projection) = match statement {
+            Statement::Select {
+                table, projection, ..
+            } => (self.table(table)?, projection),
+
+            _ => return Ok(Vec::new()),
+        };
+
+        let column = |ordinal, index: usize, name: &str| {
+            let def = &table.columns[index];
+
+            ResultColumn {
+                column: MemoryColumn {
+                    name: UStr::from(name.to_owned()),
+                    ordinal,
+                    type_info: MemoryTypeInfo(def.data_type),
+                },
+                not_null: def.not_null || def.primary_key,
+                index,
+            }
+        };
+
+        Ok(match projection {
+            Projection::All => table
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(index, def)| column(index, index, &def.name))
+                .collect(),
+
+            Projection::Columns(columns) => columns
+                .iter()
+                .enumerate()
+                .map(|(ordinal, (name, alias))| Ok(column(ordinal, table.column(name)?, alias)))
+                .collect::<Result<_, MemoryError>>()?,
+        })
+    }
+
+    fn select(
+        &self,
+        statement: &Statement,
+        arguments: &[Datum],
+    ) -> Result<Vec<Either<MemoryQueryResult, MemoryRow>>, MemoryError> {
+        let (table, filter) = match statement {
+            Statement::Select { table, filter, .. } => (self.table(table)?, filter),
+            _ => unreachable!(),
+        };
+
+        let result_columns = self.columns(statement)?;
+        let filter = table.filter(filter, arguments)?;
+
+        let mut column_names = HashMap::with_capacity(result_columns.len());
+
+        for (ordinal, column) in result_columns.iter().enumerate() {
+            // the first of several columns with the same name wins
+            column_names
+                .entry(column.column.name.clone())
+                .or_insert(ordinal);
+        }
+
+        let indexes: Vec<usize> = result_columns.iter().map(|c| c.index).collect();
+        let columns = Arc::new(result_columns.into_iter().map(|c| c.column).collect());
+        let column_names = Arc::new(column_names);
+
+        let mut results: Vec<_> = table
+            .rows
+            .iter()
+            .filter(|(_, row)| matches(&filter, row))
+            .map(|(_, row)| {
+                Either::Right(MemoryRow {
+                    values: indexes.iter().map(|&i| row[i].clone()).collect(),
+                    columns: Arc::clone(&columns),
+                    column_names: Arc::clone(&column_names),
+                })
+            })
+            .collect();
+
+        results.push(Either::Left(MemoryQueryResult::default()));
+
+        Ok(results)
+    }
+
+    fn create_table(
+        &mut self,
+        name: &str,
+        if_not_exists: bool,
+        columns: &[ColumnDef],
+    ) -> Result<MemoryQueryResult, MemoryError> {
+        if self.table(name).is_ok() {
+            return if if_not_exists {
+                Ok(MemoryQueryResult::default())
+            } else {
+                Err(MemoryError::new(
+                    "duplicate_table",
+                    format!("table `{}` already exists", name),
+                ))
+            };
+        }
+
+        for (i, column) in columns.iter().enumerate() {
+            if columns[..i]
+                .iter()
+                .any(|c| c.name.eq_ignore_ascii_case(&column.name))
+            {
+                return Err(MemoryError::new(
+                    "duplicate_column",
+                    format!("column `{}` is declared more than once", column.name),
+                ));
+            }
+        }
+
+        if columns.iter().filter(|c| c.primary_key).count() > 1 {
+            return Err(MemoryError::new(
+                "invalid_table_definition",
+                format!("table `{}` has more than one primary key", name),
+            ));
+        }
+
+        self.tables.push(Table {
+            name: name.to_owned(),
+            columns: columns.to_vec(),
+            rows: Vec::new(),
+            next_rowid: 1,
+            rowid_column: columns
+                .iter()
+                .position(|c| c.primary_key && c.data_type == DataType::Int),
+        });
+
+        Ok(MemoryQueryResult::default())
+    }
+
+    fn table(&self, name: &str) -> Result<&Table, MemoryError> {
+        self.tables
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| no_such_table(name))
+    }
+
+    fn table_mut(&mut self, name: &str) -> Result<&mut Table, MemoryError> {
+        self.tables
+            .iter_mut()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| no_such_table(name))
+    }
+}
+
+impl Table {
+    fn insert(
+        &mut self,
+        columns: Option<&[String]>,
+        rows: &[Vec<Expr>],
+        arguments: &[Datum],
+    ) -> Result<MemoryQueryResult, MemoryError> {
+        let targets = match columns {
+            Some(names) => names
+                .iter()
+                .map(|name| self.column(name))
+                .collect::<Result<Vec<_>, _>>()?,
+
+            None => (0..self.columns.len()).collect(),
+        };
+
+        // the rows are only added once all of them are known to be valid
+        let mut inserted = Vec::with_capacity(rows.len());
+        let mut next_rowid = self.next_rowid;
+
+        for exprs in rows {
+            if exprs.len() != targets.len() {
+                return Err(MemoryError::new(
+                    "syntax_error",
+                    format!(
+                        "INSERT has {} values for {} columns",
+                        exprs.len(),
+                        targets.len()
+                    ),
+                ));
+            }
+
+            let mut values = vec![Datum::Null; self.columns.len()];
+
+            for (&index, expr) in targets.iter().zip(exprs) {
+                values[index] = self.coerce(index, evaluate(expr, arguments)?)?;
+            }
+
+            let rowid = match self.rowid_column {
+                Some(index) => match values[index] {
+                    Datum::Int(id) => id,
+
+                    _ => {
+                        values[index] = Datum::Int(next_rowid);
+                        next_rowid
+                    }
+                },
+
+                None => next_rowid,
+            };
+
+            if rowid >= next_rowid {
+                next_rowid = rowid + 1;
+            }
+
+            self.check_constraints(&values, &inserted)?;
+
+            inserted.push((rowid, values));
+        }
+
+        let result = MemoryQueryResult {
+            rows_affected: inserted.len() as u64,
+            last_insert_id: inserted.last().map(|(rowid, _)| *rowid),
+        };
+
+        self.rows.extend(inserted);
+        self.next_rowid = next_rowid;
+
+        Ok(result)
+    }
+
+    fn check_constraints(
+        &self,
+        values: &[Datum],
+        inserted: &[(i64, Vec<Datum>)],
+    ) -> Result<(), MemoryError> {
+        for (index, column) in self.columns.iter().enumerate() {
+            if (column.not_null || column.primary_key) && values[index] == Datum::Null {
+                return Err(MemoryError::new(
+                    "not_null_violation",
+                    format!(
+                        "NULL value in column `{}` of table `{}` violates a not-null constraint",
+                        column.name, self.name
+                    ),
+                ));
+            }
+
+            if column.primary_key
+                && self
+                    .rows
+                    .iter()
+                    .chain(inserted)
+                    .any(|(_, row)| row[index] == values[index])
+            {
+                return Err(MemoryError::new(
+                    "unique_violation",
+                    format!(
+                        "duplicate value in column `{}` of table `{}` violates its primary key",
+                        column.name, self.name
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    // resolves the predicates into column indexes and values of the type of the column
+    fn filter(
+        &self,
+        predicates: &[Predicate],
+        arguments: &[Datum],
+    ) -> Result<Vec<(usize, Condition)>, MemoryError> {
+        predicates
+            .iter()
+            .map(|predicate| {
+                Ok(match predicate {
+                    Predicate::Eq(column, expr) => {
+                        let index = self.column(column)?;
+                        let value = self.coerce(index, evaluate(expr, arguments)?)?;
+
+                        (index, Condition::Eq(value))
+                    }
+
+                    Predicate::IsNull { column, negated } => {
+                        (self.column(column)?, Condition::IsNull(!negated))
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn column(&self, name: &str) -> Result<usize, MemoryError> {
+        self.columns
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                MemoryError::new(
+                    "undefined_column",
+                    format!("table `{}` has no column named `{}`", self.name, name),
+                )
+            })
+    }
+
+    // converts a value into the type of the column at `index`
+    fn coerce(&self, index: usize, value: Datum) -> Result<Datum, MemoryError> {
+        let column = &self.columns[index];
+
+        match (column.data_type, value) {
+            (_, Datum::Null) => Ok(Datum::Null),
+            (DataType::Float, Datum::Int(v)) => Ok(Datum::Float(v as f64)),
+            (ty, value) if value.data_type() == ty => Ok(value),
+
+            (_, value) => Err(MemoryError::new(
+                "datatype_mismatch",
+                format!(
+                    "a value of type {} cannot be used for column `{}` of type {}",
+                    MemoryTypeInfo(value.data_type()),
+                    column.name,
+                    MemoryTypeInfo(column.data_type)
+                ),
+            )),
+        }
+    }
+}
+
+enum Condition {
+    // `NULL` is equal to nothing, not even `NULL`
+    Eq(Datum),
+
+    // `true` for `IS NULL`, `false` for `IS NOT NULL`
+    IsNull(bool),
+}
+
+fn matches(filter: &[(usize, Condition)], row: &[Datum]) -> bool {
+    filter.iter().all(|(index, condition)| match condition {
+        Condition::Eq(Datum::Null) => false,
+        Condition::Eq(value) => row[*index] == *value,
+        Condition::IsNull(is_null) => (row[*index] == Datum::Null) == *is_null,
+    })
+}
+
+fn evaluate(expr: &Expr, arguments: &[Datum]) -> Result<Datum, MemoryError> {
+    match expr {
+        Expr::Literal(value) => Ok(value.clone()),
+
+        // the number of arguments is checked before any statement is executed
+        Expr::Param(index) => Ok(arguments[*index].clone()),
+    }
+}
+
+fn no_such_table(name: &str) -> MemoryError {
+    MemoryError::new(
+        "undefined_table",
+        format!("table `{}` does not exist", name),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::sql::parse;
+    use crate::row::Row;
+
+    fn run(store: &mut Store, sql: &str, arguments: &[Datum]) -> Result<Vec<Vec<Datum>>, String> {
+        let mut rows = Vec::new();
+
+        for statement in parse(sql).map_err(|e| e.to_string())?.statements {
+            for result in store
+                .execute(&statement, arguments)
+                .map_err(|e| e.to_string())?
+            {
+                if let Either::Right(row) = result {
+                    assert_eq!(row.columns().len(), row.values.len());
+                    rows.push(row.values);
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+
+    fn last_result(store: &mut Store, sql: &str) -> MemoryQueryResult {
+        let statement = parse(sql).unwrap().statements.remove(0);
+
+        match store.execute(&statement, &[]).unwrap().pop() {
+            Some(Either::Left(done)) => done,
+            _ => panic!("expected a query result"),
+        }
+    }
+
+    fn store() -> Store {
+        let mut store = Store::default();
+
+        run(
+            &mut store,
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, score REAL)",
+            &[],
+        )
+        .unwrap();
+
+        store
+    }
+
+    #[test]
+    fn it_assigns_row_ids_and_reports_the_last_one() {
+        let mut store = store();
+
+        let done = last_result(
+            &mut store,
+            "INSERT INTO users (name) VALUES ('alice'), ('bob')",
+        );
+
+        assert_eq!(done.rows_affected(), 2);
+        assert_eq!(done.last_insert_id(), Some(2));
+
+        let done = last_result(&mut store, "INSERT INTO users VALUES (10, 'carol', 1)");
+        assert_eq!(done.last_insert_id(), Some(10));
+
+        let done = last_result(&mut store, "INSERT INTO users VALUES (NULL, 'dave', NULL)");
+        assert_eq!(done.last_insert_id(), Some(11));
+
+        assert_eq!(
+            run(
+                &mut store,
+                "SELECT id, score FROM users WHERE name = 'carol'",
+                &[]
+            ),
+            Ok(vec![vec![Datum::Int(10), Datum::Float(1.0)]])
+        );
+    }
+
+    #[test]
+    fn it_filters_by_equality_and_null() {
+        let mut store = store();
+
+        run(
+            &mut store,
+            "INSERT INTO users (name, score) VALUES ('a', 1.5), ('b', NULL), ('c', 1.5)",
+            &[],
+        )
+        .unwrap();
+
+        let names = |store: &mut Store, sql: &str, arguments: &[Datum]| {
+            run(store, sql, arguments)
+                .unwrap()
+                .into_iter()
+                .map(|row| row[0].clone())
+                .collect::<Vec<_>>()
+        };
+
+        let text = |s: &str| Datum::Text(s.to_owned());
+
+        assert_eq!(
+            names(
+                &mut store,
+                "SELECT name FROM users WHERE score = ?",
+                &[Datum::Float(1.5)]
+            ),
+            vec![text("a"), text("c")]
+        );
+
+        assert_eq!(
+            names(
+                &mut store,
+                "SELECT name FROM users WHERE score = ?",
+                &[Datum::Null]
+            ),
+            Vec::<Datum>::new()
+        );
+
+        assert_eq!(
+            names(
+                &mut store,
+                "SELECT name FROM users WHERE score IS NULL",
+                &[]
+            ),
+            vec![text("b")]
+        );
+
+        assert_eq!(
+            names(
+                &mut store,
+                "SELECT name FROM users WHERE score IS NOT NULL AND name = $1",
+                &[text("c")]
+            ),
+            vec![text("c")]
+        );
+
+        let done = last_result(&mut store, "DELETE FROM users WHERE score = 1.5");
+        assert_eq!(done.rows_affected(), 2);
+        assert_eq!(done.last_insert_id(), None);
+
+        assert_eq!(
+            names(&mut store, "SELECT name FROM users", &[]),
+            vec![text("b")]
+        );
+    }
+
+    #[test]
+    fn it_enforces_constraints_and_types() {
+        let mut store = store();
+
+        run(&mut store, "INSERT INTO users VALUES (1, 'a', NULL)", &[]).unwrap();
+
+        assert!(run(
+            &mut store,
+            "INSERT INTO users (id, name) VALUES (1, 'b')",
+            &[]
+        )
+        .unwrap_err()
+        .contains("violates its primary key"));
+
+        assert!(run(&mut store, "INSERT INTO users (score) VALUES (1)", &[])
+            .unwrap_err()
+            .contains("column `name` of table `users` violates a not-null constraint"));
+
+        assert_eq!(
+            run(&mut store, "INSERT INTO users (name) VALUES (1)", &[]),
+            Err("a value of type INTEGER cannot be used for column `name` of type TEXT".into())
+        );
+
+        // a failing row leaves the other rows of the statement out as well
+        assert!(run(
+            &mut store,
+            "INSERT INTO users (name) VALUES ('b'), (NULL)",
+            &[]
+        )
+        .is_err());
+
+        assert_eq!(
+            run(&mut store, "SELECT * FROM users", &[]).unwrap().len(),
+            1
+        );
+
+        assert_eq!(
+            run(&mut store, "SELECT * FROM missing", &[]),
+            Err("table `missing` does not exist".into())
+        );
+
+        assert_eq!(
+            run(&mut store, "CREATE TABLE USERS (id INT)", &[]),
+            Err("table `USERS` already exists".into())
+        );
+
+        assert_eq!(
+            run(&mut store, "CREATE TABLE IF NOT EXISTS users (id INT)", &[]),
+            Ok(vec![])
+        );
+    }
+}
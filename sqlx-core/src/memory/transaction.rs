@@ -0,0 +1,42 @@
+use futures_core::future::BoxFuture;
+use futures_util::future;
+
+use crate::error::Error;
+use crate::memory::{Memory, MemoryConnection};
+use crate::transaction::TransactionManager;
+
+/// Implementation of [`TransactionManager`] for the in-memory driver.
+///
+/// Beginning a transaction takes a copy of the whole database, and rolling it back restores
+/// that copy. There is no isolation between connections: a rollback also undoes the changes
+/// that other connections made to the database while the transaction was open.
+pub struct MemoryTransactionManager;
+
+impl TransactionManager for MemoryTransactionManager {
+    type Database = Memory;
+
+    fn begin(conn: &mut MemoryConnection) -> BoxFuture<'_, Result<(), Error>> {
+        let snapshot = conn.store().clone();
+        conn.snapshots.push(snapshot);
+
+        Box::pin(future::ok(()))
+    }
+
+    fn commit(conn: &mut MemoryConnection) -> BoxFuture<'_, Result<(), Error>> {
+        conn.snapshots.pop();
+
+        Box::pin(future::ok(()))
+    }
+
+    fn rollback(conn: &mut MemoryConnection) -> BoxFuture<'_, Result<(), Error>> {
+        Self::start_rollback(conn);
+
+        Box::pin(future::ok(()))
+    }
+
+    fn start_rollback(conn: &mut MemoryConnection) {
+        if let Some(snapshot) = conn.snapshots.pop() {
+            *conn.store() = snapshot;
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::type_info::TypeInfo;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum DataType {
+    Null,
+    Bool,
+    Int,
+    Float,
+    Text,
+    Blob,
+}
+
+/// Type information for a column or value of the in-memory driver.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemoryTypeInfo(pub(crate) DataType);
+
+impl Display for MemoryTypeInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl TypeInfo for MemoryTypeInfo {
+    fn is_null(&self) -> bool {
+        matches!(self.0, DataType::Null)
+    }
+
+    fn name(&self) -> &str {
+        match self.0 {
+            DataType::Null => "NULL",
+            DataType::Bool => "BOOLEAN",
+            DataType::Int => "INTEGER",
+            DataType::Float => "REAL",
+            DataType::Text => "TEXT",
+            DataType::Blob => "BLOB",
+        }
+    }
+}
+
+impl DataType {
+    // the type of a column declared with `name` in `CREATE TABLE`
+    pub(crate) fn from_declared(name: &str) -> Option<Self> {
+        Some(match &*name.to_ascii_lowercase() {
+            "bool" | "boolean" => DataType::Bool,
+
+            "int" | "integer" | "smallint" | "bigint" | "int2" | "int4" | "int8" | "serial"
+            | "bigserial" => DataType::Int,
+
+            "real" | "float" | "double" | "float4" | "float8" => DataType::Float,
+
+            "text" | "varchar" | "char" | "character" | "string" => DataType::Text,
+
+            "blob" | "bytea" | "binary" | "varbinary" => DataType::Blob,
+
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(feature = "any")]
+impl From<MemoryTypeInfo> for crate::any::AnyTypeInfo {
+    #[inline]
+    fn from(ty: MemoryTypeInfo) -> Self {
+        crate::any::AnyTypeInfo(crate::any::type_info::AnyTypeInfoKind::Memory(ty))
+    }
+}
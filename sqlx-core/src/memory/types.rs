@@ -0,0 +1,151 @@
+//! Conversions between Rust and the types of the in-memory driver.
+//!
+//! # Types
+//!
+//! | Rust type                             | Column type(s)                                       |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `bool`                                | BOOLEAN, BOOL                                        |
+//! | `i8`, `i16`, `i32`, `i64`             | INTEGER, INT, SMALLINT, BIGINT                       |
+//! | `f32`, `f64`                          | REAL, FLOAT, DOUBLE                                  |
+//! | `&str`, [`String`]                    | TEXT, VARCHAR, CHAR                                  |
+//! | `&[u8]`, `Vec<u8>`                    | BLOB, BYTEA, BINARY, VARBINARY                       |
+//!
+//! Integers are stored as `i64` and floats as `f64`; decoding into a narrower type fails if the
+//! value does not fit.
+//!
+//! # Nullable
+//!
+//! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
+//! a potentially `NULL` value from SQL.
+//!
+
+use std::convert::TryFrom;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::error::BoxDynError;
+use crate::memory::type_info::DataType;
+use crate::memory::value::Datum;
+use crate::memory::{Memory, MemoryArgumentBuffer, MemoryTypeInfo, MemoryValueRef};
+use crate::types::Type;
+
+macro_rules! impl_memory_type {
+    ($ty:ty, $data_type:ident, $encode:expr, $decode:pat => $decoded:expr) => {
+        impl Type<Memory> for $ty {
+            fn type_info() -> MemoryTypeInfo {
+                MemoryTypeInfo(DataType::$data_type)
+            }
+
+            fn compatible(ty: &MemoryTypeInfo) -> bool {
+                ty.0 == DataType::$data_type
+            }
+        }
+
+        impl<'q> Encode<'q, Memory> for $ty {
+            fn encode_by_ref(&self, buf: &mut MemoryArgumentBuffer) -> Result<IsNull, EncodeError> {
+                #[allow(clippy::redundant_closure_call)]
+                buf.0.push(($encode)(self));
+
+                Ok(IsNull::No)
+            }
+        }
+
+        impl<'r> Decode<'r, Memory> for $ty {
+            // decoding clones the datum
+            const CHEAP_TO_DECODE: bool = true;
+
+            fn decode(value: MemoryValueRef<'r>) -> Result<Self, BoxDynError> {
+                match value.datum {
+                    $decode => Ok($decoded),
+
+                    datum => Err(format!(
+                        "expected a value of type {}, found {}",
+                        DataType::$data_type.name(),
+                        datum.data_type().name()
+                    )
+                    .into()),
+                }
+            }
+        }
+    };
+}
+
+impl DataType {
+    fn name(self) -> &'static str {
+        match self {
+            DataType::Null => "NULL",
+            DataType::Bool => "BOOLEAN",
+            DataType::Int => "INTEGER",
+            DataType::Float => "REAL",
+            DataType::Text => "TEXT",
+            DataType::Blob => "BLOB",
+        }
+    }
+}
+
+impl_memory_type!(bool, Bool, |v: &bool| Datum::Bool(*v), Datum::Bool(v) => *v);
+
+impl_memory_type!(i8, Int, |v: &i8| Datum::Int((*v).into()), Datum::Int(v) => i8::try_from(*v)?);
+impl_memory_type!(i16, Int, |v: &i16| Datum::Int((*v).into()), Datum::Int(v) => i16::try_from(*v)?);
+impl_memory_type!(i32, Int, |v: &i32| Datum::Int((*v).into()), Datum::Int(v) => i32::try_from(*v)?);
+impl_memory_type!(i64, Int, |v: &i64| Datum::Int(*v), Datum::Int(v) => *v);
+
+impl_memory_type!(f32, Float, |v: &f32| Datum::Float((*v).into()), Datum::Float(v) => *v as f32);
+impl_memory_type!(f64, Float, |v: &f64| Datum::Float(*v), Datum::Float(v) => *v);
+
+impl_memory_type!(String, Text, |v: &String| Datum::Text(v.clone()), Datum::Text(v) => v.clone());
+impl_memory_type!(Vec<u8>, Blob, |v: &Vec<u8>| Datum::Blob(v.clone()), Datum::Blob(v) => v.clone());
+
+impl Type<Memory> for str {
+    fn type_info() -> MemoryTypeInfo {
+        <String as Type<Memory>>::type_info()
+    }
+
+    fn compatible(ty: &MemoryTypeInfo) -> bool {
+        <String as Type<Memory>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, Memory> for &'q str {
+    fn encode_by_ref(&self, buf: &mut MemoryArgumentBuffer) -> Result<IsNull, EncodeError> {
+        buf.0.push(Datum::Text((*self).to_owned()));
+
+        Ok(IsNull::No)
+    }
+}
+
+impl<'r> Decode<'r, Memory> for &'r str {
+    fn decode(value: MemoryValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.datum {
+            Datum::Text(v) => Ok(v),
+            _ => <String as Decode<Memory>>::decode(value).map(|_| unreachable!()),
+        }
+    }
+}
+
+impl Type<Memory> for [u8] {
+    fn type_info() -> MemoryTypeInfo {
+        <Vec<u8> as Type<Memory>>::type_info()
+    }
+
+    fn compatible(ty: &MemoryTypeInfo) -> bool {
+        <Vec<u8> as Type<Memory>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, Memory> for &'q [u8] {
+    fn encode_by_ref(&self, buf: &mut MemoryArgumentBuffer) -> Result<IsNull, EncodeError> {
+        buf.0.push(Datum::Blob(self.to_vec()));
+
+        Ok(IsNull::No)
+    }
+}
+
+impl<'r> Decode<'r, Memory> for &'r [u8] {
+    fn decode(value: MemoryValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.datum {
+            Datum::Blob(v) => Ok(v),
+            _ => <Vec<u8> as Decode<Memory>>::decode(value).map(|_| unreachable!()),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::borrow::Cow;
+
+use crate::memory::type_info::DataType;
+use crate::memory::{Memory, MemoryTypeInfo};
+use crate::value::{Value, ValueRef};
+
+// a value as stored in a table, bound as an argument or returned in a row
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Datum {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl Datum {
+    pub(crate) fn data_type(&self) -> DataType {
+        match self {
+            Datum::Null => DataType::Null,
+            Datum::Bool(_) => DataType::Bool,
+            Datum::Int(_) => DataType::Int,
+            Datum::Float(_) => DataType::Float,
+            Datum::Text(_) => DataType::Text,
+            Datum::Blob(_) => DataType::Blob,
+        }
+    }
+
+    // the type of a `NULL` is that of the column it came from
+    fn type_info<'a>(&self, column: &'a MemoryTypeInfo) -> Cow<'a, MemoryTypeInfo> {
+        match self {
+            Datum::Null => Cow::Borrowed(column),
+            datum => Cow::Owned(MemoryTypeInfo(datum.data_type())),
+        }
+    }
+}
+
+/// Implementation of [`ValueRef`] for the in-memory driver.
+#[derive(Clone)]
+pub struct MemoryValueRef<'r> {
+    pub(crate) datum: &'r Datum,
+    pub(crate) type_info: &'r MemoryTypeInfo,
+}
+
+impl<'r> ValueRef<'r> for MemoryValueRef<'r> {
+    type Database = Memory;
+
+    fn to_owned(&self) -> MemoryValue {
+        MemoryValue {
+            datum: self.datum.clone(),
+            type_info: self.type_info.clone(),
+        }
+    }
+
+    fn type_info(&self) -> Cow<'_, MemoryTypeInfo> {
+        self.datum.type_info(self.type_info)
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self.datum, Datum::Null)
+    }
+}
+
+/// Implementation of [`Value`] for the in-memory driver.
+#[derive(Debug, Clone)]
+pub struct MemoryValue {
+    pub(crate) datum: Datum,
+    pub(crate) type_info: MemoryTypeInfo,
+}
+
+impl Value for MemoryValue {
+    type Database = Memory;
+
+    fn as_ref(&self) -> MemoryValueRef<'_> {
+        MemoryValueRef {
+            datum: &self.datum,
+            type_info: &self.type_info,
+        }
+    }
+
+    fn type_info(&self) -> Cow<'_, MemoryTypeInfo> {
+        self.datum.type_info(&self.type_info)
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self.datum, Datum::Null)
+    }
+}
+
+#[cfg(feature = "any")]
+impl<'r> From<MemoryValueRef<'r>> for crate::any::AnyValueRef<'r> {
+    #[inline]
+    fn from(value: MemoryValueRef<'r>) -> Self {
+        crate::any::AnyValueRef {
+            type_info: value.type_info().into_owned().into(),
+            kind: crate::any::value::AnyValueRefKind::Memory(value),
+        }
+    }
+}
+
+#[cfg(feature = "any")]
+impl From<MemoryValue> for crate::any::AnyValue {
+    #[inline]
+    fn from(value: MemoryValue) -> Self {
+        crate::any::AnyValue {
+            type_info: value.type_info().into_owned().into(),
+            kind: crate::any::value::AnyValueKind::Memory(value),
+        }
+    }
+}
@@ -0,0 +1,315 @@
+//! Rewriting or rejecting statements before they are executed.
+//!
+//! A [`QueryMiddleware`] set with
+//! [`ConnectOptions::query_middleware`](crate::connection::ConnectOptions::query_middleware) or
+//! [`PoolOptions::query_middleware`](crate::pool::PoolOptions::query_middleware) sees the SQL of
+//! every query the connection executes, and may return different SQL to execute instead, or
+//! an error to abort the query before anything is sent. [`SqlCommenter`] appends a comment of
+//! key-value pairs to every statement, e.g. to correlate the logs of the server with traces.
+
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter, Write as _};
+use std::sync::Arc;
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use crate::error::{BoxDynError, Error};
+
+/// Called with the SQL of every query a connection executes.
+///
+/// Bind parameters are left as they are, so a middleware that rewrites a query must keep its
+/// placeholders. Prepared statements are cached by the rewritten SQL, so a middleware that
+/// varies what it returns for the same query, e.g. by adding a trace ID, prevents the cache
+/// from being reused; this only matters for queries that are
+/// [prepared](QueryContext::prepared).
+///
+/// The middleware is called on the connection's task before the query is sent, so it should
+/// be quick; a shared middleware is called from all the connections it was set on. It is not
+/// called for statements SQLx executes itself, such as to begin a transaction, nor for
+/// [`prepare`](crate::executor::Executor::prepare) and
+/// [`describe`](crate::executor::Executor::describe).
+pub trait QueryMiddleware: Send + Sync {
+    /// Returns the SQL to execute instead of `sql`, which is usually `sql` itself, or an error
+    /// to abort the query with [`Error::QueryRejected`].
+    fn before_execute<'q>(
+        &self,
+        sql: &'q str,
+        context: &QueryContext,
+    ) -> Result<Cow<'q, str>, BoxDynError>;
+}
+
+/// What is known about a query passed to a [`QueryMiddleware`].
+#[derive(Debug, Clone)]
+pub struct QueryContext {
+    pub(crate) database: &'static str,
+    pub(crate) prepared: bool,
+    pub(crate) persistent: bool,
+}
+
+impl QueryContext {
+    /// The name of the database of the connection, e.g. `PostgreSQL`.
+    pub fn database(&self) -> &'static str {
+        self.database
+    }
+
+    /// Whether the query is executed as a prepared statement, as every query made with
+    /// [`query`](crate::query::query) is, with or without bind parameters, unlike SQL that is
+    /// executed as a string.
+    pub fn prepared(&self) -> bool {
+        self.prepared
+    }
+
+    /// Whether the prepared statement of the query is cached for reuse; see
+    /// [`Query::persistent`](crate::query::Query::persistent).
+    pub fn persistent(&self) -> bool {
+        self.persistent
+    }
+}
+
+// `ConnectOptions` are `Debug` and `Clone`, which a bare `Arc<dyn QueryMiddleware>` is not
+#[derive(Clone, Default)]
+pub(crate) struct Middleware(pub(crate) Option<Arc<dyn QueryMiddleware>>);
+
+impl Middleware {
+    // the SQL to execute for `sql`
+    #[cfg_attr(
+        not(any(
+            feature = "postgres",
+            feature = "mysql",
+            feature = "sqlite",
+            feature = "mssql",
+            feature = "memory"
+        )),
+        allow(dead_code)
+    )]
+    pub(crate) fn before_execute<'q>(
+        &self,
+        sql: &'q str,
+        database: &'static str,
+        prepared: bool,
+        persistent: bool,
+    ) -> Result<Cow<'q, str>, Error> {
+        let middleware = match &self.0 {
+            Some(middleware) => middleware,
+            None => return Ok(Cow::Borrowed(sql)),
+        };
+
+        let context = QueryContext {
+            database,
+            prepared,
+            persistent,
+        };
+
+        middleware
+            .before_execute(sql, &context)
+            .map_err(Error::QueryRejected)
+    }
+}
+
+impl Debug for Middleware {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("Some(<middleware>)"),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+/// A [`QueryMiddleware`] that appends a comment of key-value pairs to every statement, in the
+/// format of [sqlcommenter](https://google.github.io/sqlcommenter/spec/).
+///
+/// ```text
+/// SELECT * FROM users WHERE id = $1 /*application='api',trace_id='4bf92f3577b34da6'*/
+/// ```
+///
+/// The pairs are those set with [`tag`](Self::tag), which are the same for every statement, and
+/// those returned by the function set with [`tags_with`](Self::tags_with), which is called for
+/// each statement, e.g. to read the IDs of the current trace:
+///
+/// ```rust,ignore
+/// let commenter = SqlCommenter::new()
+///     .tag("application", "api")
+///     .tags_with(|_| {
+///         let span = tracing::Span::current();
+///
+///         match span.id() {
+///             Some(id) => vec![("span_id".to_owned(), id.into_u64().to_string())],
+///             None => Vec::new(),
+///         }
+///     });
+///
+/// let pool = PgPoolOptions::new()
+///     .query_middleware(Arc::new(commenter))
+///     .connect(&url)
+///     .await?;
+/// ```
+///
+/// As the specification requires, statements that already contain a comment are left as they
+/// are, as are statements for which there are no pairs. Keys and values are URL-encoded, and
+/// the pairs are sorted by key.
+///
+/// As the comment usually differs from one statement to the next, so that the prepared
+/// statement of a query cannot be reused, values that change with every statement should only
+/// be added to statements that are not [prepared](QueryContext::prepared), or the statement
+/// cache disabled; see [`QueryMiddleware`].
+pub struct SqlCommenter {
+    tags: Vec<(String, String)>,
+    tags_with: Option<TagsWith>,
+}
+
+type TagsWith = Box<dyn Fn(&QueryContext) -> Vec<(String, String)> + Send + Sync>;
+
+// what is escaped in keys and values, beyond the quotes the comment is made of: everything but
+// the unreserved characters of URLs
+const COMMENT_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+impl Default for SqlCommenter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SqlCommenter {
+    /// A commenter without any pairs, which leaves statements as they are.
+    pub fn new() -> Self {
+        Self {
+            tags: Vec::new(),
+            tags_with: None,
+        }
+    }
+
+    /// Adds a pair to the comment of every statement.
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets a function returning further pairs for the comment of each statement.
+    ///
+    /// It is called on the connection's task, so it sees the context of the task that executes
+    /// the query, such as the current tracing span.
+    pub fn tags_with<F>(mut self, tags: F) -> Self
+    where
+        F: Fn(&QueryContext) -> Vec<(String, String)> + Send + Sync + 'static,
+    {
+        self.tags_with = Some(Box::new(tags));
+        self
+    }
+
+    // the comment of `tags`, without the delimiters
+    fn comment(mut tags: Vec<(String, String)>) -> String {
+        tags.sort();
+
+        let mut comment = String::new();
+
+        for (key, value) in &tags {
+            if !comment.is_empty() {
+                comment.push(',');
+            }
+
+            let _ = write!(
+                comment,
+                "{}='{}'",
+                utf8_percent_encode(key, COMMENT_VALUE),
+                utf8_percent_encode(value, COMMENT_VALUE)
+            );
+        }
+
+        comment
+    }
+}
+
+impl QueryMiddleware for SqlCommenter {
+    fn before_execute<'q>(
+        &self,
+        sql: &'q str,
+        context: &QueryContext,
+    ) -> Result<Cow<'q, str>, BoxDynError> {
+        if sql.contains("--") || sql.contains("/*") {
+            return Ok(Cow::Borrowed(sql));
+        }
+
+        let mut tags = self.tags.clone();
+
+        if let Some(tags_with) = &self.tags_with {
+            tags.extend(tags_with(context));
+        }
+
+        if tags.is_empty() {
+            return Ok(Cow::Borrowed(sql));
+        }
+
+        // the comment goes before a final semicolon, so that it stays part of the statement
+        let trimmed = sql.trim_end();
+        let (statement, semicolon) = match trimmed.strip_suffix(';') {
+            Some(statement) => (statement.trim_end(), ";"),
+            None => (trimmed, ""),
+        };
+
+        Ok(Cow::Owned(format!(
+            "{} /*{}*/{}",
+            statement,
+            Self::comment(tags),
+            semicolon
+        )))
+    }
+}
+
+impl Debug for SqlCommenter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqlCommenter")
+            .field("tags", &self.tags)
+            .field("tags_with", &self.tags_with.as_ref().map(|_| "<function>"))
+            .finish()
+    }
+}
+
+#[test]
+fn it_appends_sqlcommenter_comments() {
+    let context = QueryContext {
+        database: "PostgreSQL",
+        prepared: false,
+        persistent: true,
+    };
+
+    let commenter = SqlCommenter::new()
+        .tag("route", "/users/{id}")
+        .tag("application", "api")
+        .tags_with(|context| vec![("db".to_owned(), context.database().to_owned())]);
+
+    let comment = |sql| commenter.before_execute(sql, &context).unwrap();
+
+    assert_eq!(
+        comment("SELECT 1"),
+        "SELECT 1 /*application='api',db='PostgreSQL',route='%2Fusers%2F%7Bid%7D'*/"
+    );
+
+    assert_eq!(
+        comment("SELECT 1 ;\n"),
+        "SELECT 1 /*application='api',db='PostgreSQL',route='%2Fusers%2F%7Bid%7D'*/;"
+    );
+
+    // statements with a comment of their own are left alone
+    assert!(matches!(
+        comment("SELECT 1 -- one"),
+        Cow::Borrowed("SELECT 1 -- one")
+    ));
+
+    assert!(matches!(
+        SqlCommenter::new().before_execute("SELECT 1", &context),
+        Ok(Cow::Borrowed("SELECT 1"))
+    ));
+
+    // quotes in values cannot end the value or the comment
+    assert_eq!(
+        SqlCommenter::new()
+            .tag("k", "it's */")
+            .before_execute("SELECT 1", &context)
+            .unwrap(),
+        "SELECT 1 /*k='it%27s%20%2A%2F'*/"
+    );
+}
@@ -6,15 +6,22 @@ use std::time::Duration;
 pub trait MigrateDatabase {
     // create database in uri
     // uses a maintenance database depending on driver
+    // fails with `Error::DatabaseExists` if it exists, and with `Error::DatabasePermissionDenied`
+    // if the user may not create databases
     fn create_database(uri: &str) -> BoxFuture<'_, Result<(), Error>>;
 
     // check if the database in uri exists
     // uses a maintenance database depending on driver
     fn database_exists(uri: &str) -> BoxFuture<'_, Result<bool, Error>>;
 
-    // drop database in uri
+    // drop database in uri, if it exists
     // uses a maintenance database depending on driver
+    // fails with `Error::DatabasePermissionDenied` if the user may not drop it
     fn drop_database(uri: &str) -> BoxFuture<'_, Result<(), Error>>;
+
+    // drop database in uri, if it exists, first disconnecting the other sessions connected to it
+    // uses a maintenance database depending on driver
+    fn force_drop_database(uri: &str) -> BoxFuture<'_, Result<(), Error>>;
 }
 
 // 'e = Executor
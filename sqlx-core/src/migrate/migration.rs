@@ -11,6 +11,9 @@ pub struct Migration {
     pub migration_type: MigrationType,
     pub sql: Cow<'static, str>,
     pub checksum: Cow<'static, [u8]>,
+    /// Set for scripts whose first line is `-- no-transaction`, which are run as they are
+    /// instead of inside a transaction; see [`Migration::is_no_transaction`].
+    pub no_tx: bool,
 }
 
 impl Migration {
@@ -21,6 +24,7 @@ impl Migration {
         sql: Cow<'static, str>,
     ) -> Self {
         let checksum = Cow::Owned(Vec::from(Sha384::digest(sql.as_bytes()).as_slice()));
+        let no_tx = Migration::is_no_transaction(&sql);
 
         Migration {
             version,
@@ -28,6 +32,28 @@ impl Migration {
             migration_type,
             sql,
             checksum,
+            no_tx,
         }
     }
+
+    /// Returns `true` if the script opts out of being run inside a transaction, for statements
+    /// such as `CREATE INDEX CONCURRENTLY` or `ALTER TYPE ... ADD VALUE` that refuse to run in
+    /// one. Such a script starts with the line `-- no-transaction`.
+    pub fn is_no_transaction(sql: &str) -> bool {
+        sql.lines()
+            .next()
+            .map_or(false, |line| line.trim() == "-- no-transaction")
+    }
+}
+
+#[test]
+fn it_detects_no_transaction_scripts() {
+    assert!(Migration::is_no_transaction(
+        "-- no-transaction\nCREATE INDEX CONCURRENTLY ..."
+    ));
+    assert!(Migration::is_no_transaction("-- no-transaction\r\n"));
+    assert!(!Migration::is_no_transaction(
+        "CREATE TABLE t (); -- no-transaction"
+    ));
+    assert!(!Migration::is_no_transaction("\n-- no-transaction"));
 }
@@ -1,5 +1,7 @@
 use crate::acquire::Acquire;
+use crate::error::{AggregateError, Error};
 use crate::migrate::{Migrate, MigrateError, Migration, MigrationSource};
+use futures_core::future::BoxFuture;
 use std::borrow::Cow;
 use std::ops::Deref;
 use std::slice;
@@ -44,6 +46,11 @@ impl Migrator {
     /// Run any pending migrations against the database; and, validate previously applied migrations
     /// against the current migration source to detect accidental changes in previously-applied migrations.
     ///
+    /// Every previously applied migration is validated before any is applied; if more than one
+    /// was modified, fails with [`Error::Aggregate`] inside [`MigrateError::Execute`], holding
+    /// the error of each. Pending migrations are applied in order, stopping at the first that
+    /// fails, as the ones after it may depend on it.
+    ///
     /// # Examples
     ///
     /// ```rust,no_run
@@ -58,38 +65,64 @@ impl Migrator {
     /// #     })
     /// # }
     /// ```
-    pub async fn run<'a, A>(&self, migrator: A) -> Result<(), MigrateError>
+    pub fn run<'a, 'm, A>(&'m self, migrator: A) -> BoxFuture<'m, Result<(), MigrateError>>
     where
-        A: Acquire<'a>,
+        'a: 'm,
+        A: Acquire<'a> + Send + 'm,
         <A::Connection as Deref>::Target: Migrate,
     {
-        let mut conn = migrator.acquire().await?;
+        // boxed here, where the connection is known to be `Send`; as an `async fn`, the future
+        // could not be shown to be `Send` when `A` is a `&mut` connection
+        Box::pin(async move {
+            let mut conn = migrator.acquire().await?;
+
+            // lock the database for exclusive access by the migrator
+            conn.lock().await?;
+
+            // creates [_migrations] table only if needed
+            // eventually this will likely migrate previous versions of the table
+            conn.ensure_migrations_table().await?;
+
+            let (version, dirty) = conn.version().await?.unwrap_or((0, false));
+
+            if dirty {
+                return Err(MigrateError::Dirty(version));
+            }
+
+            let (applied, pending): (Vec<_>, Vec<_>) = self
+                .iter()
+                .partition(|migration| migration.version <= version);
 
-        // lock the database for exclusive access by the migrator
-        conn.lock().await?;
+            let mut errors = AggregateError::new("migrations", applied.len());
 
-        // creates [_migrations] table only if needed
-        // eventually this will likely migrate previous versions of the table
-        conn.ensure_migrations_table().await?;
+            for migration in applied {
+                match conn.validate(migration).await {
+                    Ok(()) => {}
 
-        let (version, dirty) = conn.version().await?.unwrap_or((0, false));
+                    // anything else, e.g. a connection that broke, ends the run
+                    Err(error @ MigrateError::VersionMissing(_))
+                    | Err(error @ MigrateError::VersionMismatch(_)) => {
+                        errors.push(format!("migration {}", migration.version), error.into())
+                    }
 
-        if dirty {
-            return Err(MigrateError::Dirty(version));
-        }
+                    Err(error) => return Err(error),
+                }
+            }
 
-        for migration in self.iter() {
-            if migration.version > version {
+            errors.into_result().map_err(|error| match error {
+                Error::Migrate(error) => *error,
+                error => MigrateError::Execute(error),
+            })?;
+
+            for migration in pending {
                 conn.apply(migration).await?;
-            } else {
-                conn.validate(migration).await?;
             }
-        }
 
-        // unlock the migrator to allow other migrators to run
-        // but do nothing as we already migrated
-        conn.unlock().await?;
+            // unlock the migrator to allow other migrators to run
+            // but do nothing as we already migrated
+            conn.unlock().await?;
 
-        Ok(())
+            Ok(())
+        })
     }
 }
@@ -1,8 +1,10 @@
 use crate::arguments::Arguments;
-use crate::encode::Encode;
+use crate::encode::{DynEncode, Encode, EncodeError};
+use crate::error::Error;
 use crate::mssql::database::Mssql;
 use crate::mssql::io::MssqlBufMutExt;
 use crate::mssql::protocol::rpc::StatusFlags;
+use crate::mssql::MssqlTypeInfo;
 use crate::types::Type;
 
 #[derive(Default)]
@@ -13,6 +15,9 @@ pub struct MssqlArguments {
     name: String,
     pub(crate) data: Vec<u8>,
     pub(crate) declarations: String,
+    // the first value that could not be encoded, with its index; reported before the query is
+    // sent
+    pub(crate) error: Option<(usize, EncodeError)>,
 }
 
 impl MssqlArguments {
@@ -30,7 +35,8 @@ impl MssqlArguments {
         self.data.push(0); // [StatusFlags]
 
         ty.0.put(&mut self.data); // [TYPE_INFO]
-        ty.0.put_value(&mut self.data, value); // [ParamLenData]
+        let result = ty.0.put_value(&mut self.data, value); // [ParamLenData]
+        self.record_error(result);
     }
 
     pub(crate) fn add_unnamed<'q, T: Encode<'q, Mssql> + Type<Mssql>>(&mut self, value: T) {
@@ -51,12 +57,32 @@ impl MssqlArguments {
         self.data.push(StatusFlags::BY_REF_VALUE.bits()); // [StatusFlags]
 
         ty.0.put(&mut self.data); // [TYPE_INFO]
-        ty.0.put_value(&mut self.data, initial_value); // [ParamLenData]
+        let result = ty.0.put_value(&mut self.data, initial_value); // [ParamLenData]
+        self.record_error(result);
+    }
+
+    fn record_error(&mut self, result: Result<(), EncodeError>) {
+        if let Err(error) = result {
+            if self.error.is_none() {
+                self.error = Some((self.ordinal, error));
+            }
+        }
+    }
+
+    pub(crate) fn check_encoded(&mut self) -> Result<(), Error> {
+        match self.error.take() {
+            Some((index, source)) => Err(Error::ArgumentEncode { index, source }),
+            None => Ok(()),
+        }
     }
 
     pub(crate) fn append(&mut self, arguments: &mut MssqlArguments) {
         self.ordinal += arguments.ordinal;
         self.data.append(&mut arguments.data);
+
+        if self.error.is_none() {
+            self.error = arguments.error.take();
+        }
     }
 
     pub(crate) fn add<'q, T>(&mut self, value: T)
@@ -64,7 +90,13 @@ impl MssqlArguments {
         T: Encode<'q, Mssql> + Type<Mssql>,
     {
         let ty = value.produces().unwrap_or_else(T::type_info);
+        self.add_with_type(value, ty);
+    }
 
+    fn add_with_type<'q, T>(&mut self, value: T, ty: MssqlTypeInfo)
+    where
+        T: Encode<'q, Mssql>,
+    {
         // produce an ordinal parameter name
         //  @p1, @p2, ... @pN
 
@@ -98,7 +130,14 @@ impl MssqlArguments {
         data.push(0); // [StatusFlags]
 
         ty.0.put(data); // [TYPE_INFO]
-        ty.0.put_value(data, value); // [ParamLenData]
+        let result = ty.0.put_value(data, value); // [ParamLenData]
+
+        if let Err(error) = result {
+            if self.error.is_none() {
+                // the ordinal was already advanced past this value
+                self.error = Some((self.ordinal - 1, error));
+            }
+        }
     }
 }
 
@@ -115,4 +154,25 @@ impl<'q> Arguments<'q> for MssqlArguments {
     {
         self.add(value)
     }
+
+    fn add_dyn(&mut self, value: Box<dyn DynEncode<'q, Self::Database> + 'q>) {
+        let ty = value.type_info();
+        self.add_with_type(value, ty)
+    }
+
+    fn len(&self) -> usize {
+        self.ordinal
+    }
+
+    fn write_cache_key(&self, key: &mut Vec<u8>) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+
+        key.extend_from_slice(self.declarations.as_bytes());
+        key.push(0);
+        key.extend_from_slice(&self.data);
+
+        true
+    }
 }
@@ -1,5 +1,7 @@
-use crate::common::StatementCache;
+use crate::common::{InFlight, StatementCache};
+use crate::connection::StatsCollector;
 use crate::error::Error;
+use crate::events::ConnectionEvents;
 use crate::io::Decode;
 use crate::mssql::connection::stream::MssqlStream;
 use crate::mssql::protocol::login::Login7;
@@ -10,6 +12,25 @@ use crate::mssql::{MssqlConnectOptions, MssqlConnection};
 
 impl MssqlConnection {
     pub(crate) async fn establish(options: &MssqlConnectOptions) -> Result<Self, Error> {
+        let events = ConnectionEvents::new(&options.events, options.connection_info());
+
+        match Self::establish_session(options).await {
+            Ok(mut conn) => {
+                events.connected();
+                conn.events = events;
+
+                Ok(conn)
+            }
+
+            Err(error) => {
+                events.error(&error);
+
+                Err(error)
+            }
+        }
+    }
+
+    async fn establish_session(options: &MssqlConnectOptions) -> Result<Self, Error> {
         let mut stream: MssqlStream = MssqlStream::connect(options).await?;
 
         // Send PRELOGIN to set up the context for login. The server should immediately
@@ -82,7 +103,11 @@ impl MssqlConnection {
         Ok(Self {
             stream,
             cache_statement: StatementCache::new(1024),
+            in_flight: InFlight::default(),
+            stats: StatsCollector::new(),
             log_settings: options.log_settings.clone(),
+            middleware: options.middleware.clone(),
+            events: ConnectionEvents::default(),
         })
     }
 }
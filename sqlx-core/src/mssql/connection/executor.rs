@@ -19,9 +19,19 @@ use futures_core::stream::BoxStream;
 use futures_util::TryStreamExt;
 use std::borrow::Cow;
 use std::sync::Arc;
+use std::time::Instant;
 
 impl MssqlConnection {
-    async fn run(&mut self, query: &str, arguments: Option<MssqlArguments>) -> Result<(), Error> {
+    // returns when the query was sent
+    async fn run(
+        &mut self,
+        query: &str,
+        mut arguments: Option<MssqlArguments>,
+    ) -> Result<Instant, Error> {
+        if let Some(arguments) = &mut arguments {
+            arguments.check_encoded()?;
+        }
+
         self.stream.wait_until_ready().await?;
         self.stream.pending_done_count += 1;
 
@@ -60,12 +70,17 @@ impl MssqlConnection {
             );
         }
 
+        let sent_at = Instant::now();
         self.stream.flush().await?;
 
-        Ok(())
+        Ok(sent_at)
     }
 }
 
+impl MssqlConnection {
+    impl_executor_methods!(Mssql, &mut self => self);
+}
+
 impl<'c> Executor<'c> for &'c mut MssqlConnection {
     type Database = Mssql;
 
@@ -79,54 +94,68 @@ impl<'c> Executor<'c> for &'c mut MssqlConnection {
     {
         let sql = query.sql();
         let arguments = query.take_arguments();
-        let mut logger = QueryLogger::new(sql, self.log_settings.clone());
-
-        Box::pin(try_stream! {
-            self.run(sql, arguments).await?;
-
-            loop {
-                let message = self.stream.recv_message().await?;
+        let sql =
+            self.middleware
+                .before_execute(sql, "MSSQL", arguments.is_some(), query.persistent());
+        let log_settings = self.log_settings.clone();
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch_stream(
+            "fetch_many",
+            Box::pin(try_stream! {
+                let sql = sql?;
+                let mut logger = QueryLogger::new(&sql, log_settings);
+                let mut sent_at = Some(self.run(&sql, arguments).await?);
+
+                loop {
+                    let message = self.stream.recv_message().await;
+
+                    // the first response completes the round trip
+                    if let Some(sent_at) = sent_at.take() {
+                        self.stats.record_query(sent_at.elapsed());
+                    }
 
-                match message {
-                    Message::Row(row) => {
-                        let columns = Arc::clone(&self.stream.columns);
-                        let column_names = Arc::clone(&self.stream.column_names);
+                    let message = message?;
 
-                        logger.increment_rows();
+                    match message {
+                        Message::Row(row) => {
+                            let columns = Arc::clone(&self.stream.columns);
+                            let column_names = Arc::clone(&self.stream.column_names);
 
-                        r#yield!(Either::Right(MssqlRow { row, column_names, columns }));
-                    }
+                            logger.increment_rows();
 
-                    Message::Done(done) | Message::DoneProc(done) => {
-                        if !done.status.contains(Status::DONE_MORE) {
-                            self.stream.handle_done(&done);
+                            r#yield!(Either::Right(MssqlRow { row, column_names, columns }));
                         }
 
-                        if done.status.contains(Status::DONE_COUNT) {
-                            r#yield!(Either::Left(MssqlQueryResult {
-                                rows_affected: done.affected_rows,
-                            }));
-                        }
+                        Message::Done(done) | Message::DoneProc(done) => {
+                            if !done.status.contains(Status::DONE_MORE) {
+                                self.stream.handle_done(&done);
+                            }
+
+                            if done.status.contains(Status::DONE_COUNT) {
+                                r#yield!(Either::Left(MssqlQueryResult {
+                                    rows_affected: done.affected_rows,
+                                }));
+                            }
 
-                        if !done.status.contains(Status::DONE_MORE) {
-                            break;
+                            if !done.status.contains(Status::DONE_MORE) {
+                                break;
+                            }
                         }
-                    }
 
-                    Message::DoneInProc(done) => {
-                        if done.status.contains(Status::DONE_COUNT) {
+                        Message::DoneInProc(done) if done.status.contains(Status::DONE_COUNT) => {
                             r#yield!(Either::Left(MssqlQueryResult {
                                 rows_affected: done.affected_rows,
                             }));
                         }
-                    }
 
-                    _ => {}
+                        _ => {}
+                    }
                 }
-            }
 
-            Ok(())
-        })
+                Ok(())
+            }),
+        )
     }
 
     fn fetch_optional<'e, 'q: 'e, E: 'q>(
@@ -137,17 +166,21 @@ impl<'c> Executor<'c> for &'c mut MssqlConnection {
         'c: 'e,
         E: Execute<'q, Self::Database>,
     {
+        let in_flight = self.in_flight.clone();
         let mut s = self.fetch_many(query);
 
-        Box::pin(async move {
-            while let Some(v) = s.try_next().await? {
-                if let Either::Right(r) = v {
-                    return Ok(Some(r));
+        in_flight.watch(
+            "fetch_optional",
+            Box::pin(async move {
+                while let Some(v) = s.try_next().await? {
+                    if let Either::Right(r) = v {
+                        return Ok(Some(r));
+                    }
                 }
-            }
 
-            Ok(None)
-        })
+                Ok(None)
+            }),
+        )
     }
 
     fn prepare_with<'e, 'q: 'e>(
@@ -158,14 +191,19 @@ impl<'c> Executor<'c> for &'c mut MssqlConnection {
     where
         'c: 'e,
     {
-        Box::pin(async move {
-            let metadata = prepare(self, sql).await?;
-
-            Ok(MssqlStatement {
-                sql: Cow::Borrowed(sql),
-                metadata,
-            })
-        })
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch(
+            "prepare",
+            Box::pin(async move {
+                let metadata = prepare(self, sql).await?;
+
+                Ok(MssqlStatement {
+                    sql: Cow::Borrowed(sql),
+                    metadata,
+                })
+            }),
+        )
     }
 
     fn describe<'e, 'q: 'e>(
@@ -175,20 +213,25 @@ impl<'c> Executor<'c> for &'c mut MssqlConnection {
     where
         'c: 'e,
     {
-        Box::pin(async move {
-            let metadata = prepare(self, sql).await?;
+        let in_flight = self.in_flight.clone();
 
-            let mut nullable = Vec::with_capacity(metadata.columns.len());
+        in_flight.watch(
+            "describe",
+            Box::pin(async move {
+                let metadata = prepare(self, sql).await?;
 
-            for col in metadata.columns.iter() {
-                nullable.push(Some(col.flags.contains(Flags::NULLABLE)));
-            }
+                let mut nullable = Vec::with_capacity(metadata.columns.len());
+
+                for col in metadata.columns.iter() {
+                    nullable.push(Some(col.flags.contains(Flags::NULLABLE)));
+                }
 
-            Ok(Describe {
-                nullable,
-                columns: (metadata.columns).clone(),
-                parameters: None,
-            })
-        })
+                Ok(Describe {
+                    nullable,
+                    columns: (metadata.columns).clone(),
+                    parameters: None,
+                })
+            }),
+        )
     }
 }
@@ -1,15 +1,18 @@
-use crate::common::StatementCache;
-use crate::connection::{Connection, LogSettings};
+use crate::common::{InFlight, StatementCache};
+use crate::connection::{BufferSizes, Connection, ConnectionStats, LogSettings, StatsCollector};
 use crate::error::Error;
-use crate::executor::Executor;
+use crate::events::{CloseReason, ConnectionEvents};
+use crate::middleware::Middleware;
 use crate::mssql::connection::stream::MssqlStream;
 use crate::mssql::statement::MssqlStatementMetadata;
 use crate::mssql::{Mssql, MssqlConnectOptions};
 use crate::transaction::Transaction;
 use futures_core::future::BoxFuture;
-use futures_util::{FutureExt, TryFutureExt};
+use futures_util::FutureExt;
 use std::fmt::{self, Debug, Formatter};
+use std::mem;
 use std::sync::Arc;
+use std::time::Instant;
 
 mod establish;
 mod executor;
@@ -19,7 +22,14 @@ mod stream;
 pub struct MssqlConnection {
     pub(crate) stream: MssqlStream,
     pub(crate) cache_statement: StatementCache<Arc<MssqlStatementMetadata>>,
+    pub(crate) in_flight: InFlight,
+    stats: StatsCollector,
+
     log_settings: LogSettings,
+    middleware: Middleware,
+
+    // the listener of connection events, told when the connection is closed
+    events: ConnectionEvents,
 }
 
 impl Debug for MssqlConnection {
@@ -33,16 +43,21 @@ impl Connection for MssqlConnection {
 
     type Options = MssqlConnectOptions;
 
-    #[allow(unused_mut)]
     fn close(mut self) -> BoxFuture<'static, Result<(), Error>> {
         // NOTE: there does not seem to be a clean shutdown packet to send to MSSQL
 
+        let events = mem::take(&mut self.events);
+
         #[cfg(feature = "_rt-async-std")]
         {
             use std::future::ready;
             use std::net::Shutdown;
 
-            ready(self.stream.shutdown(Shutdown::Both).map_err(Into::into)).boxed()
+            let result: Result<(), Error> =
+                self.stream.shutdown(Shutdown::Both).map_err(Into::into);
+            events.closed(CloseReason::Explicit);
+
+            ready(result).boxed()
         }
 
         #[cfg(any(feature = "_rt-actix", feature = "_rt-tokio"))]
@@ -51,13 +66,26 @@ impl Connection for MssqlConnection {
 
             // FIXME: This is equivalent to Shutdown::Write, not Shutdown::Both like above
             // https://docs.rs/tokio/1.0.1/tokio/io/trait.AsyncWriteExt.html#method.shutdown
-            async move { self.stream.shutdown().await.map_err(Into::into) }.boxed()
+            async move {
+                let result: Result<(), Error> = self.stream.shutdown().await.map_err(Into::into);
+                events.closed(CloseReason::Explicit);
+
+                result
+            }
+            .boxed()
         }
     }
 
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
-        // NOTE: we do not use `SELECT 1` as that *could* interact with any ongoing transactions
-        self.execute("/* SQLx ping */").map_ok(|_| ()).boxed()
+        Box::pin(async move {
+            let start = Instant::now();
+
+            // NOTE: we do not use `SELECT 1` as that *could* interact with any ongoing transactions
+            self.execute("/* SQLx ping */").await?;
+            self.stats.record_ping(start.elapsed());
+
+            Ok(())
+        })
     }
 
     fn begin(&mut self) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
@@ -67,13 +95,38 @@ impl Connection for MssqlConnection {
         Transaction::begin(self)
     }
 
+    fn shrink_buffers(&mut self) {
+        self.stream.shrink();
+    }
+
+    fn buffer_sizes(&self) -> BufferSizes {
+        let (read, write) = self.stream.capacity();
+
+        BufferSizes { read, write }
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        let (bytes_received, bytes_sent) = self.stream.bytes_transferred();
+
+        self.stats.snapshot(bytes_sent, bytes_received)
+    }
+
+    #[doc(hidden)]
+    fn take_events(&mut self) -> ConnectionEvents {
+        mem::take(&mut self.events)
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
-        self.stream.wait_until_ready().boxed()
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch("flush", self.stream.wait_until_ready().boxed())
     }
 
     #[doc(hidden)]
     fn should_flush(&self) -> bool {
-        !self.stream.wbuf.is_empty()
+        // a connection with an operation in progress, or that a panic unwound through, fails to
+        // flush, and is closed by the pool
+        !self.stream.wbuf.is_empty() || self.in_flight.is_busy() || self.in_flight.is_poisoned()
     }
 }
@@ -136,7 +136,8 @@ pub(crate) async fn prepare(
         column_names: conn.stream.column_names.as_ref().clone(),
     });
 
-    conn.cache_statement.insert(sql, metadata.clone());
+    // only the metadata is cached, so there is nothing to close for evicted statements
+    let _ = conn.cache_statement.insert(sql, metadata.clone(), 0);
 
     Ok(metadata)
 }
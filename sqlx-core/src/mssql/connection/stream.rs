@@ -45,9 +45,10 @@ pub(crate) struct MssqlStream {
 
 impl MssqlStream {
     pub(super) async fn connect(options: &MssqlConnectOptions) -> Result<Self, Error> {
-        let inner = BufStream::new(MaybeTlsStream::Raw(
+        let mut inner = BufStream::new(MaybeTlsStream::Raw(
             TcpStream::connect((&*options.host, options.port)).await?,
         ));
+        inner.set_max_capacity(options.buffer_max);
 
         Ok(Self {
             inner,
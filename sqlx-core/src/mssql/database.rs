@@ -1,4 +1,4 @@
-use crate::database::{Database, HasArguments, HasStatement, HasValueRef};
+use crate::database::{Capabilities, Database, HasArguments, HasStatement, HasValueRef};
 use crate::mssql::{
     MssqlArguments, MssqlColumn, MssqlConnection, MssqlQueryResult, MssqlRow, MssqlStatement,
     MssqlTransactionManager, MssqlTypeInfo, MssqlValue, MssqlValueRef,
@@ -22,6 +22,16 @@ impl Database for Mssql {
     type TypeInfo = MssqlTypeInfo;
 
     type Value = MssqlValue;
+
+    const CAPABILITIES: Capabilities = Capabilities::from_bits_truncate(
+        Capabilities::SAVEPOINTS.bits()
+            | Capabilities::MULTIPLE_RESULT_SETS.bits()
+            | Capabilities::BINARY_PROTOCOL.bits(),
+    );
+
+    const MAX_BIND_PARAMETERS: usize = 2100;
+
+    const MAX_IDENTIFIER_LENGTH: Option<usize> = Some(128);
 }
 
 impl<'r> HasValueRef<'r> for Mssql {
@@ -0,0 +1,80 @@
+use crate::erased::{unsupported_column, ErasedDatabase, ErasedQueryResult, ErasedValue};
+use crate::error::Error;
+use crate::mssql::protocol::type_info::DataType;
+use crate::mssql::{Mssql, MssqlArguments, MssqlQueryResult, MssqlRow};
+use crate::row::Row;
+use crate::value::ValueRef;
+
+impl ErasedDatabase for Mssql {
+    fn add_erased(arguments: &mut MssqlArguments, value: ErasedValue) -> Result<(), Error> {
+        match value {
+            ErasedValue::Null => arguments.add(None::<String>),
+            ErasedValue::Bool(v) => arguments.add(v),
+            ErasedValue::Int(v) => arguments.add(v),
+            ErasedValue::Float(v) => arguments.add(v),
+            ErasedValue::Text(v) => arguments.add(v),
+
+            // the driver does not support binary types yet
+            ErasedValue::Bytes(_) => return Err(value.unsupported("MSSQL")),
+        }
+
+        Ok(())
+    }
+
+    fn get_erased(row: &MssqlRow, index: usize) -> Result<ErasedValue, Error> {
+        let value = row.try_get_raw(index)?;
+
+        if value.is_null() {
+            return Ok(ErasedValue::Null);
+        }
+
+        let ty = &value.type_info.0;
+
+        Ok(match (ty.ty, ty.size) {
+            (DataType::Bit, _) | (DataType::BitN, _) => {
+                ErasedValue::Bool(row.try_get_unchecked(index)?)
+            }
+
+            // TINYINT is unsigned
+            (DataType::TinyInt, _) | (DataType::IntN, 1) => {
+                ErasedValue::Int((row.try_get_unchecked::<i8, _>(index)? as u8).into())
+            }
+
+            (DataType::SmallInt, _) | (DataType::IntN, 2) => {
+                ErasedValue::Int(row.try_get_unchecked::<i16, _>(index)?.into())
+            }
+
+            (DataType::Int, _) | (DataType::IntN, 4) => {
+                ErasedValue::Int(row.try_get_unchecked::<i32, _>(index)?.into())
+            }
+
+            (DataType::BigInt, _) | (DataType::IntN, 8) => {
+                ErasedValue::Int(row.try_get_unchecked(index)?)
+            }
+
+            (DataType::Real, _) | (DataType::FloatN, 4) => {
+                ErasedValue::Float(row.try_get_unchecked::<f32, _>(index)?.into())
+            }
+
+            (DataType::Float, _) | (DataType::FloatN, 8) => {
+                ErasedValue::Float(row.try_get_unchecked(index)?)
+            }
+
+            (DataType::NVarChar, _)
+            | (DataType::NChar, _)
+            | (DataType::BigVarChar, _)
+            | (DataType::VarChar, _)
+            | (DataType::BigChar, _)
+            | (DataType::Char, _) => ErasedValue::Text(row.try_get_unchecked(index)?),
+
+            _ => return Err(unsupported_column(row, index)),
+        })
+    }
+
+    fn erase_query_result(result: MssqlQueryResult) -> ErasedQueryResult {
+        ErasedQueryResult {
+            rows_affected: result.rows_affected(),
+            last_insert_id: None,
+        }
+    }
+}
@@ -49,4 +49,16 @@ impl DatabaseError for MssqlDatabaseError {
     fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
         self
     }
+
+    // https://docs.microsoft.com/en-us/azure/azure-sql/database/troubleshoot-common-errors-issues
+
+    fn is_connection_error(&self) -> bool {
+        // the database is unavailable or being reconfigured
+        matches!(self.0.number, 40197 | 40613)
+    }
+
+    fn is_retryable(&self) -> bool {
+        // 1205: chosen as the deadlock victim; 40501: the service is busy
+        matches!(self.0.number, 1205 | 40501) || self.is_connection_error()
+    }
 }
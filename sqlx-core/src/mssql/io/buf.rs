@@ -10,7 +10,7 @@ pub trait MssqlBufExt: Buf {
 
     fn get_us_varchar(&mut self) -> Result<String, Error>;
 
-    fn get_b_varbyte(&mut self) -> Bytes;
+    fn get_b_varbyte(&mut self) -> Result<Bytes, Error>;
 }
 
 impl MssqlBufExt for Bytes {
@@ -36,7 +36,7 @@ impl MssqlBufExt for Bytes {
         self.get_utf16_str(size as usize)
     }
 
-    fn get_b_varbyte(&mut self) -> Bytes {
+    fn get_b_varbyte(&mut self) -> Result<Bytes, Error> {
         let size = self.get_u8();
         self.get_bytes(size as usize)
     }
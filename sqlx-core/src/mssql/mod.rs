@@ -4,11 +4,13 @@ mod arguments;
 mod column;
 mod connection;
 mod database;
+mod erased;
 mod error;
 mod io;
 mod options;
 mod protocol;
 mod query_result;
+mod quote;
 mod row;
 mod statement;
 mod transaction;
@@ -23,6 +25,7 @@ pub use database::Mssql;
 pub use error::MssqlDatabaseError;
 pub use options::MssqlConnectOptions;
 pub use query_result::MssqlQueryResult;
+pub use quote::{quote_identifier, quote_qualified};
 pub use row::MssqlRow;
 pub use statement::MssqlStatement;
 pub use transaction::MssqlTransactionManager;
@@ -40,3 +43,6 @@ impl_acquire!(Mssql, MssqlConnection);
 impl_column_index_for_row!(MssqlRow);
 impl_column_index_for_statement!(MssqlStatement);
 impl_into_maybe_pool!(Mssql, MssqlConnection);
+
+#[cfg(test)]
+assert_driver_futures_are_send!(Mssql, MssqlConnection);
@@ -1,8 +1,11 @@
-use crate::connection::ConnectOptions;
-use crate::error::Error;
+use crate::connection::{BindLogging, ConnectOptions};
+use crate::error::{ConfigError, Error};
+use crate::events::{ConnectionEventListener, EventListener};
+use crate::middleware::{Middleware, QueryMiddleware};
 use crate::mssql::{MssqlConnectOptions, MssqlConnection};
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
+use std::sync::Arc;
 use std::time::Duration;
 
 impl ConnectOptions for MssqlConnectOptions {
@@ -15,6 +18,20 @@ impl ConnectOptions for MssqlConnectOptions {
         Box::pin(MssqlConnection::establish(self))
     }
 
+    // MSSQL cannot name its connections, so the suffix is ignored
+    fn connect_with_password(
+        &self,
+        password: String,
+        _suffix: Option<String>,
+    ) -> BoxFuture<'_, Result<MssqlConnection, Error>> {
+        Box::pin(async move {
+            let mut options = self.clone();
+            options.password = Some(password);
+
+            MssqlConnection::establish(&options).await
+        })
+    }
+
     fn log_statements(&mut self, level: LevelFilter) -> &mut Self {
         self.log_settings.log_statements(level);
         self
@@ -24,4 +41,41 @@ impl ConnectOptions for MssqlConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn log_bind_values(&mut self, logging: BindLogging) -> &mut Self {
+        self.log_settings.log_bind_values(logging);
+        self
+    }
+
+    fn query_middleware(&mut self, middleware: Arc<dyn QueryMiddleware>) -> &mut Self {
+        self.middleware = Middleware(Some(middleware));
+        self
+    }
+
+    fn connection_events(&mut self, listener: Arc<dyn ConnectionEventListener>) -> &mut Self {
+        self.events = EventListener(Some(listener));
+        self
+    }
+
+    fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+
+        if self.port == 0 {
+            errors.push(ConfigError::new("port", "must be between 1 and 65535"));
+        }
+
+        if self.host.is_empty() {
+            errors.push(ConfigError::new("host", "must not be empty"));
+        }
+
+        if self.username.is_empty() {
+            errors.push(ConfigError::new("username", "must not be empty"));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
 }
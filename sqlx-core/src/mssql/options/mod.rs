@@ -1,4 +1,7 @@
 use crate::connection::LogSettings;
+use crate::events::{ConnectionInfo, EventListener};
+use crate::middleware::Middleware;
+use crate::net::unbracket;
 
 mod connect;
 mod parse;
@@ -11,6 +14,9 @@ pub struct MssqlConnectOptions {
     pub(crate) database: String,
     pub(crate) password: Option<String>,
     pub(crate) log_settings: LogSettings,
+    pub(crate) middleware: Middleware,
+    pub(crate) events: EventListener,
+    pub(crate) buffer_max: Option<usize>,
 }
 
 impl Default for MssqlConnectOptions {
@@ -28,11 +34,23 @@ impl MssqlConnectOptions {
             username: String::from("sa"),
             password: None,
             log_settings: Default::default(),
+            middleware: Default::default(),
+            events: Default::default(),
+            buffer_max: None,
+        }
+    }
+
+    // what a `ConnectionEventListener` is told about a connection made with these options
+    pub(crate) fn connection_info(&self) -> ConnectionInfo {
+        ConnectionInfo {
+            peer_addr: Some(format!("{}:{}", self.host, self.port)),
+            database_name: Some(self.database.clone()),
+            ..ConnectionInfo::new("MSSQL")
         }
     }
 
     pub fn host(mut self, host: &str) -> Self {
-        self.host = host.to_owned();
+        self.host = unbracket(host).to_owned();
         self
     }
 
@@ -55,4 +73,19 @@ impl MssqlConnectOptions {
         self.database = database.to_owned();
         self
     }
+
+    /// Caps the capacity, in bytes, that the connection's read and write buffers keep between
+    /// messages.
+    ///
+    /// Messages larger than this are still sent and received, but through a temporary buffer
+    /// that is freed as soon as the message has been handled, so one very large result set
+    /// does not leave the connection holding on to that much memory.
+    ///
+    /// By default the buffers are never shrunk; see also [`Connection::shrink_buffers`].
+    ///
+    /// [`Connection::shrink_buffers`]: crate::connection::Connection::shrink_buffers
+    pub fn buffer_max(mut self, max: usize) -> Self {
+        self.buffer_max = Some(max);
+        self
+    }
 }
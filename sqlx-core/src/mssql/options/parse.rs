@@ -1,17 +1,17 @@
 use crate::error::Error;
 use crate::mssql::MssqlConnectOptions;
+use crate::net::parse_url;
 use percent_encoding::percent_decode_str;
 use std::str::FromStr;
-use url::Url;
 
 impl FromStr for MssqlConnectOptions {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let url: Url = s.parse().map_err(Error::config)?;
+        let (url, host) = parse_url(s)?;
         let mut options = Self::new();
 
-        if let Some(host) = url.host_str() {
+        if let Some(host) = &host {
             options = options.host(host);
         }
 
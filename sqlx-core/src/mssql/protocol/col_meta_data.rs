@@ -100,7 +100,8 @@ impl ColMetaData {
         while count > 0 {
             let col = MssqlColumn::new(ColumnData::get(buf)?, ordinal);
 
-            column_names.insert(col.name.clone(), ordinal);
+            // a duplicated name refers to its first column
+            column_names.entry(col.name.clone()).or_insert(ordinal);
             columns.push(col);
 
             count -= 1;
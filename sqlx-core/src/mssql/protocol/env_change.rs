@@ -44,8 +44,8 @@ impl EnvChange {
             4 => EnvChange::PacketSize(data.get_b_varchar()?),
             5 => EnvChange::UnicodeDataSortingLocalId(data.get_b_varchar()?),
             6 => EnvChange::UnicodeDataSortingComparisonFlags(data.get_b_varchar()?),
-            7 => EnvChange::SqlCollation(data.get_b_varbyte()),
-            8 => EnvChange::BeginTransaction(data.get_b_varbyte().get_u64_le()),
+            7 => EnvChange::SqlCollation(data.get_b_varbyte()?),
+            8 => EnvChange::BeginTransaction(data.get_b_varbyte()?.get_u64_le()),
 
             9 => {
                 let _ = data.get_u8();
@@ -4,7 +4,7 @@ use crate::error::Error;
 use crate::io::BufExt;
 use crate::mssql::{MssqlColumn, MssqlTypeInfo};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Row {
     pub(crate) column_types: Vec<MssqlTypeInfo>,
     pub(crate) values: Vec<Option<Bytes>>,
@@ -20,7 +20,7 @@ impl Row {
         let mut column_types = Vec::with_capacity(columns.len());
 
         let nulls = if nullable {
-            buf.get_bytes((columns.len() + 7) / 8)
+            buf.get_bytes((columns.len() + 7) / 8)?
         } else {
             Bytes::from_static(b"")
         };
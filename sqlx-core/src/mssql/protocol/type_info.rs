@@ -2,7 +2,7 @@ use bitflags::bitflags;
 use bytes::{Buf, Bytes};
 use encoding_rs::Encoding;
 
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::Error;
 use crate::mssql::Mssql;
 
@@ -380,7 +380,11 @@ impl TypeInfo {
         }
     }
 
-    pub(crate) fn put_value<'q, T: Encode<'q, Mssql>>(&self, buf: &mut Vec<u8>, value: T) {
+    pub(crate) fn put_value<'q, T: Encode<'q, Mssql>>(
+        &self,
+        buf: &mut Vec<u8>,
+        value: T,
+    ) -> Result<(), EncodeError> {
         match self.ty {
             DataType::Null
             | DataType::TinyInt
@@ -393,9 +397,7 @@ impl TypeInfo {
             | DataType::DateTime
             | DataType::Float
             | DataType::SmallMoney
-            | DataType::BigInt => {
-                self.put_fixed_value(buf, value);
-            }
+            | DataType::BigInt => self.put_fixed_value(buf, value),
 
             DataType::Guid
             | DataType::IntN
@@ -414,9 +416,7 @@ impl TypeInfo {
             | DataType::Char
             | DataType::VarChar
             | DataType::Binary
-            | DataType::VarBinary => {
-                self.put_byte_len_value(buf, value);
-            }
+            | DataType::VarBinary => self.put_byte_len_value(buf, value),
 
             DataType::BigVarBinary
             | DataType::BigVarChar
@@ -425,61 +425,79 @@ impl TypeInfo {
             | DataType::NVarChar
             | DataType::NChar
             | DataType::Xml
-            | DataType::UserDefined => {
-                self.put_short_len_value(buf, value);
-            }
+            | DataType::UserDefined => self.put_short_len_value(buf, value),
 
             DataType::Text | DataType::Image | DataType::NText | DataType::Variant => {
-                self.put_long_len_value(buf, value);
+                self.put_long_len_value(buf, value)
             }
         }
     }
 
-    pub(crate) fn put_fixed_value<'q, T: Encode<'q, Mssql>>(&self, buf: &mut Vec<u8>, value: T) {
-        let _ = value.encode(buf);
+    pub(crate) fn put_fixed_value<'q, T: Encode<'q, Mssql>>(
+        &self,
+        buf: &mut Vec<u8>,
+        value: T,
+    ) -> Result<(), EncodeError> {
+        value.encode(buf)?;
+
+        Ok(())
     }
 
-    pub(crate) fn put_byte_len_value<'q, T: Encode<'q, Mssql>>(&self, buf: &mut Vec<u8>, value: T) {
+    pub(crate) fn put_byte_len_value<'q, T: Encode<'q, Mssql>>(
+        &self,
+        buf: &mut Vec<u8>,
+        value: T,
+    ) -> Result<(), EncodeError> {
         let offset = buf.len();
         buf.push(0);
 
-        let size = if let IsNull::Yes = value.encode(buf) {
+        let size = if let IsNull::Yes = value.encode(buf)? {
             0xFF
         } else {
             (buf.len() - offset - 1) as u8
         };
 
         buf[offset] = size;
+
+        Ok(())
     }
 
     pub(crate) fn put_short_len_value<'q, T: Encode<'q, Mssql>>(
         &self,
         buf: &mut Vec<u8>,
         value: T,
-    ) {
+    ) -> Result<(), EncodeError> {
         let offset = buf.len();
         buf.extend(&0_u16.to_le_bytes());
 
-        let size = if let IsNull::Yes = value.encode(buf) {
+        let size = if let IsNull::Yes = value.encode(buf)? {
             0xFFFF
         } else {
             (buf.len() - offset - 2) as u16
         };
 
         buf[offset..(offset + 2)].copy_from_slice(&size.to_le_bytes());
+
+        Ok(())
     }
 
-    pub(crate) fn put_long_len_value<'q, T: Encode<'q, Mssql>>(&self, buf: &mut Vec<u8>, value: T) {
+    pub(crate) fn put_long_len_value<'q, T: Encode<'q, Mssql>>(
+        &self,
+        buf: &mut Vec<u8>,
+        value: T,
+    ) -> Result<(), EncodeError> {
         let offset = buf.len();
         buf.extend(&0_u32.to_le_bytes());
 
-        let size = if let IsNull::Yes = value.encode(buf) {
+        let size = if let IsNull::Yes = value.encode(buf)? {
             0xFFFF_FFFF
         } else {
             (buf.len() - offset - 4) as u32
         };
 
         buf[offset..(offset + 4)].copy_from_slice(&size.to_le_bytes());
+
+        Ok(())
     }
 
     pub(crate) fn name(&self) -> &'static str {
@@ -7,6 +7,10 @@ use crate::row::Row;
 use crate::HashMap;
 use std::sync::Arc;
 
+/// Implementation of [`Row`] for MSSQL.
+///
+/// Cloning a row shares its data and columns.
+#[derive(Clone)]
 pub struct MssqlRow {
     pub(crate) row: ProtocolRow,
     pub(crate) columns: Arc<Vec<MssqlColumn>>,
@@ -34,6 +38,18 @@ impl Row for MssqlRow {
 
         Ok(value)
     }
+
+    fn try_share(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+
+    fn data_len(&self) -> usize {
+        self.row
+            .values
+            .iter()
+            .map(|value| value.as_ref().map_or(0, |value| value.len()))
+            .sum()
+    }
 }
 
 impl ColumnIndex<MssqlRow> for &'_ str {
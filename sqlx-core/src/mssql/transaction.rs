@@ -3,7 +3,6 @@ use std::borrow::Cow;
 use futures_core::future::BoxFuture;
 
 use crate::error::Error;
-use crate::executor::Executor;
 use crate::mssql::protocol::packet::PacketType;
 use crate::mssql::protocol::sql_batch::SqlBatch;
 use crate::mssql::{Mssql, MssqlConnection};
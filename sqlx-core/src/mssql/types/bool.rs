@@ -1,5 +1,5 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::mssql::protocol::type_info::{DataType, TypeInfo};
 use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
@@ -16,14 +16,16 @@ impl Type<Mssql> for bool {
 }
 
 impl Encode<'_, Mssql> for bool {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.push(if *self { 1 } else { 0 });
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Mssql> for bool {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(value.as_bytes()?[0] == 1)
     }
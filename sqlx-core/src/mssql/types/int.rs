@@ -1,7 +1,7 @@
 use byteorder::{ByteOrder, LittleEndian};
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::mssql::protocol::type_info::{DataType, TypeInfo};
 use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
@@ -18,14 +18,16 @@ impl Type<Mssql> for i8 {
 }
 
 impl Encode<'_, Mssql> for i8 {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_le_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Mssql> for i8 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(value.as_bytes()?[0] as i8)
     }
@@ -42,14 +44,16 @@ impl Type<Mssql> for i16 {
 }
 
 impl Encode<'_, Mssql> for i16 {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_le_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Mssql> for i16 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(LittleEndian::read_i16(value.as_bytes()?))
     }
@@ -66,14 +70,16 @@ impl Type<Mssql> for i32 {
 }
 
 impl Encode<'_, Mssql> for i32 {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_le_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Mssql> for i32 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(LittleEndian::read_i32(value.as_bytes()?))
     }
@@ -90,14 +96,16 @@ impl Type<Mssql> for i64 {
 }
 
 impl Encode<'_, Mssql> for i64 {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_le_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Mssql> for i64 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(LittleEndian::read_i64(value.as_bytes()?))
     }
@@ -1,4 +1,4 @@
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::mssql::protocol::type_info::{DataType, TypeInfo};
 use crate::mssql::{Mssql, MssqlTypeInfo};
 
@@ -8,19 +8,19 @@ mod int;
 mod str;
 
 impl<'q, T: 'q + Encode<'q, Mssql>> Encode<'q, Mssql> for Option<T> {
-    fn encode(self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode(self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         if let Some(v) = self {
             v.encode(buf)
         } else {
-            IsNull::Yes
+            Ok(IsNull::Yes)
         }
     }
 
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         if let Some(v) = self {
             v.encode_by_ref(buf)
         } else {
-            IsNull::Yes
+            Ok(IsNull::Yes)
         }
     }
 
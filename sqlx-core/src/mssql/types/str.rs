@@ -1,5 +1,5 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::mssql::io::MssqlBufMutExt;
 use crate::mssql::protocol::type_info::{Collation, CollationFlags, DataType, TypeInfo};
@@ -53,10 +53,10 @@ impl Encode<'_, Mssql> for &'_ str {
         }))
     }
 
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.put_utf16_str(self);
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
@@ -65,7 +65,7 @@ impl Encode<'_, Mssql> for String {
         <&str as Encode<Mssql>>::produces(&self.as_str())
     }
 
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         <&str as Encode<Mssql>>::encode_by_ref(&self.as_str(), buf)
     }
 }
@@ -1,5 +1,14 @@
+use std::cmp;
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+
+use byteorder::{ByteOrder, LittleEndian};
+
 use crate::arguments::Arguments;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{DynEncode, Encode, EncodeError, IsNull};
+use crate::error::Error;
+use crate::logger::{write_hex, write_quoted};
+use crate::mysql::protocol::text::{ColumnFlags, ColumnType};
 use crate::mysql::{MySql, MySqlTypeInfo};
 use crate::types::Type;
 
@@ -9,25 +18,239 @@ pub struct MySqlArguments {
     pub(crate) values: Vec<u8>,
     pub(crate) types: Vec<MySqlTypeInfo>,
     pub(crate) null_bitmap: Vec<u8>,
+
+    // the first value that could not be encoded, with its index; reported before the query is sent
+    pub(crate) error: Option<(usize, EncodeError)>,
 }
 
 impl MySqlArguments {
+    /// The maximum number of placeholders in a single prepared statement.
+    pub const MAX_ARGUMENTS: usize = u16::MAX as usize;
+
+    pub(crate) fn check_len(&self) -> Result<(), Error> {
+        if self.types.len() > Self::MAX_ARGUMENTS {
+            return Err(Error::TooManyArguments {
+                given: self.types.len(),
+                limit: Self::MAX_ARGUMENTS,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn check_encoded(&mut self) -> Result<(), Error> {
+        match self.error.take() {
+            Some((index, source)) => Err(Error::ArgumentEncode { index, source }),
+            None => Ok(()),
+        }
+    }
+
     pub(crate) fn add<'q, T>(&mut self, value: T)
     where
         T: Encode<'q, MySql> + Type<MySql>,
     {
         let ty = value.produces().unwrap_or_else(T::type_info);
+        self.add_with_type(value, ty);
+    }
+
+    fn add_with_type<'q, T>(&mut self, value: T, ty: MySqlTypeInfo)
+    where
+        T: Encode<'q, MySql>,
+    {
         let index = self.types.len();
 
         self.types.push(ty);
         self.null_bitmap.resize((index / 8) + 1, 0);
 
-        if let IsNull::Yes = value.encode(&mut self.values) {
-            self.null_bitmap[index / 8] |= (1 << (index % 8)) as u8;
+        let offset = self.values.len();
+
+        match value.encode(&mut self.values) {
+            Ok(IsNull::No) => {}
+
+            Ok(IsNull::Yes) => {
+                self.null_bitmap[index / 8] |= (1 << (index % 8)) as u8;
+            }
+
+            Err(error) => {
+                // sent as NULL if at all, which it is not as the error is reported first
+                self.values.truncate(offset);
+                self.null_bitmap[index / 8] |= (1 << (index % 8)) as u8;
+
+                if self.error.is_none() {
+                    self.error = Some((index, error));
+                }
+            }
+        }
+    }
+}
+
+impl MySqlArguments {
+    // The encoded values with their types, in bind order; `None` for NULL.
+    //
+    // Stops early if a value cannot be split off, which would mean a type encodes in a layout
+    // that is not known here.
+    pub(crate) fn values(&self) -> impl Iterator<Item = MySqlBindValue<'_>> {
+        self.types
+            .iter()
+            .enumerate()
+            .scan(&self.values[..], move |buf, (index, ty)| {
+                if self.null_bitmap[index / 8] & (1 << (index % 8)) != 0 {
+                    return Some(MySqlBindValue { ty, value: None });
+                }
+
+                let len = encoded_len(ty.r#type, buf)?;
+                let (value, rest) = buf.split_at(len);
+                *buf = rest;
+
+                Some(MySqlBindValue {
+                    ty,
+                    value: Some(value),
+                })
+            })
+    }
+}
+
+// the length of the (binary protocol) value at the start of `buf`, including any length prefix
+fn encoded_len(ty: ColumnType, buf: &[u8]) -> Option<usize> {
+    let len = match ty {
+        ColumnType::Tiny => 1,
+        ColumnType::Short | ColumnType::Year => 2,
+        ColumnType::Long | ColumnType::Int24 | ColumnType::Float => 4,
+        ColumnType::LongLong | ColumnType::Double => 8,
+
+        ColumnType::Date | ColumnType::Time | ColumnType::Datetime | ColumnType::Timestamp => {
+            1 + *buf.first()? as usize
+        }
+
+        // everything else is a length-encoded string
+        _ => match *buf.first()? {
+            0xfc => 3 + LittleEndian::read_u16(buf.get(1..3)?) as usize,
+            0xfd => 4 + LittleEndian::read_u24(buf.get(1..4)?) as usize,
+            0xfe => 9 + usize::try_from(LittleEndian::read_u64(buf.get(1..9)?)).ok()?,
+            len => 1 + len as usize,
+        },
+    };
+
+    if len <= buf.len() {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+/// Renders an encoded bind value for the statement log.
+pub(crate) struct MySqlBindValue<'a> {
+    pub(crate) ty: &'a MySqlTypeInfo,
+    pub(crate) value: Option<&'a [u8]>,
+}
+
+impl Display for MySqlBindValue<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let value = match self.value {
+            Some(value) => value,
+            None => return f.write_str("NULL"),
+        };
+
+        let unsigned = self.ty.flags.contains(ColumnFlags::UNSIGNED);
+
+        match self.ty.r#type {
+            ColumnType::Tiny if unsigned => write!(f, "{}", value[0]),
+            ColumnType::Tiny => write!(f, "{}", value[0] as i8),
+            ColumnType::Short | ColumnType::Year if unsigned => {
+                write!(f, "{}", LittleEndian::read_u16(value))
+            }
+            ColumnType::Short | ColumnType::Year => write!(f, "{}", LittleEndian::read_i16(value)),
+            ColumnType::Long | ColumnType::Int24 if unsigned => {
+                write!(f, "{}", LittleEndian::read_u32(value))
+            }
+            ColumnType::Long | ColumnType::Int24 => write!(f, "{}", LittleEndian::read_i32(value)),
+            ColumnType::LongLong if unsigned => write!(f, "{}", LittleEndian::read_u64(value)),
+            ColumnType::LongLong => write!(f, "{}", LittleEndian::read_i64(value)),
+            ColumnType::Float => write!(f, "{}", LittleEndian::read_f32(value)),
+            ColumnType::Double => write!(f, "{}", LittleEndian::read_f64(value)),
+
+            ColumnType::Date | ColumnType::Datetime | ColumnType::Timestamp => {
+                fmt_datetime(f, &value[1..])
+            }
+
+            ColumnType::Time => fmt_time(f, &value[1..]),
+
+            ty => {
+                // skip the length prefix
+                let prefix = match value[0] {
+                    0xfc => 3,
+                    0xfd => 4,
+                    0xfe => 9,
+                    _ => 1,
+                };
+
+                let value = &value[prefix..];
+
+                let is_blob = matches!(
+                    ty,
+                    ColumnType::TinyBlob
+                        | ColumnType::MediumBlob
+                        | ColumnType::LongBlob
+                        | ColumnType::Blob
+                );
+
+                // binary strings are shown as hex, whether or not they happen to be UTF-8
+                match std::str::from_utf8(value) {
+                    Ok(s) if !(is_blob && self.ty.char_set == 63) => write_quoted(f, s),
+
+                    _ => {
+                        f.write_str("x'")?;
+                        write_hex(f, value)?;
+                        f.write_str("'")
+                    }
+                }
+            }
         }
     }
 }
 
+// https://dev.mysql.com/doc/internals/en/binary-protocol-value.html
+fn fmt_datetime(f: &mut Formatter<'_>, value: &[u8]) -> fmt::Result {
+    let mut parts = [0_u8; 11];
+    let len = cmp::min(value.len(), parts.len());
+    parts[..len].copy_from_slice(&value[..len]);
+
+    write!(
+        f,
+        "'{:04}-{:02}-{:02}",
+        LittleEndian::read_u16(&parts),
+        parts[2],
+        parts[3]
+    )?;
+
+    if value.len() > 4 {
+        write!(f, " {:02}:{:02}:{:02}", parts[4], parts[5], parts[6])?;
+    }
+
+    if value.len() > 7 {
+        write!(f, ".{:06}", LittleEndian::read_u32(&parts[7..]))?;
+    }
+
+    f.write_str("'")
+}
+
+fn fmt_time(f: &mut Formatter<'_>, value: &[u8]) -> fmt::Result {
+    let mut parts = [0_u8; 12];
+    let len = cmp::min(value.len(), parts.len());
+    parts[..len].copy_from_slice(&value[..len]);
+
+    let hours = LittleEndian::read_u32(&parts[1..]) * 24 + u32::from(parts[5]);
+    let sign = if parts[0] == 1 { "-" } else { "" };
+
+    write!(f, "'{}{:02}:{:02}:{:02}", sign, hours, parts[6], parts[7])?;
+
+    if value.len() > 8 {
+        write!(f, ".{:06}", LittleEndian::read_u32(&parts[8..]))?;
+    }
+
+    f.write_str("'")
+}
+
 impl<'q> Arguments<'q> for MySqlArguments {
     type Database = MySql;
 
@@ -42,4 +265,110 @@ impl<'q> Arguments<'q> for MySqlArguments {
     {
         self.add(value)
     }
+
+    fn add_dyn(&mut self, value: Box<dyn DynEncode<'q, Self::Database> + 'q>) {
+        let ty = value.type_info();
+        self.add_with_type(value, ty)
+    }
+
+    fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    fn write_cache_key(&self, key: &mut Vec<u8>) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+
+        for ty in &self.types {
+            key.push(ty.r#type as u8);
+            key.extend_from_slice(&ty.flags.bits().to_le_bytes());
+            key.extend_from_slice(&ty.char_set.to_le_bytes());
+        }
+
+        key.extend_from_slice(&self.null_bitmap);
+        key.extend_from_slice(&self.values);
+
+        true
+    }
+}
+
+#[test]
+fn it_renders_bind_values() {
+    let mut arguments = MySqlArguments::default();
+    arguments.add(-5_i8);
+    arguments.add(u32::MAX);
+    arguments.add("it's");
+    arguments.add(None::<i64>);
+    arguments.add(&b"\x00\xff"[..]);
+    arguments.add(1.5_f64);
+
+    let rendered: Vec<String> = arguments.values().map(|v| v.to_string()).collect();
+
+    assert_eq!(
+        rendered,
+        ["-5", "4294967295", "'it''s'", "NULL", "x'00ff'", "1.5"]
+    );
+}
+
+#[test]
+fn it_records_the_first_value_that_cannot_be_encoded() {
+    struct Unencodable;
+
+    impl Type<MySql> for Unencodable {
+        fn type_info() -> MySqlTypeInfo {
+            <i32 as Type<MySql>>::type_info()
+        }
+    }
+
+    impl Encode<'_, MySql> for Unencodable {
+        fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
+            buf.push(0xff);
+
+            Err("cannot be encoded".into())
+        }
+    }
+
+    let mut arguments = MySqlArguments::default();
+    arguments.add(1_i32);
+    arguments.add(Unencodable);
+    arguments.add(Unencodable);
+    arguments.add(2_i32);
+
+    let rendered: Vec<String> = arguments.values().map(|v| v.to_string()).collect();
+    assert_eq!(rendered, ["1", "NULL", "NULL", "2"]);
+
+    assert!(matches!(
+        arguments.check_encoded(),
+        Err(Error::ArgumentEncode { index: 1, .. })
+    ));
+}
+
+#[test]
+fn it_renders_temporal_bind_values() {
+    let rendered = |ty, value: &[u8]| {
+        MySqlBindValue {
+            ty: &MySqlTypeInfo::binary(ty),
+            value: Some(value),
+        }
+        .to_string()
+    };
+
+    assert_eq!(
+        rendered(ColumnType::Date, &[4, 0xe5, 0x07, 3, 14]),
+        "'2021-03-14'"
+    );
+
+    assert_eq!(
+        rendered(
+            ColumnType::Datetime,
+            &[11, 0xe5, 0x07, 3, 14, 15, 9, 26, 0x40, 0xe2, 0x01, 0]
+        ),
+        "'2021-03-14 15:09:26.123456'"
+    );
+
+    assert_eq!(
+        rendered(ColumnType::Time, &[8, 1, 1, 0, 0, 0, 2, 30, 0]),
+        "'-26:30:00'"
+    );
 }
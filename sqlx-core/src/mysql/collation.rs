@@ -141,6 +141,43 @@ impl CharSet {
     }
 }
 
+impl CharSet {
+    /// The charset to use in place of this one with a server of `version`.
+    ///
+    /// `utf8mb4` was added in MySQL 5.5.3; an older server would not recognize its collation id
+    /// in the handshake (falling back to its own default charset) and would reject the
+    /// `SET NAMES` issued after connecting, so `utf8` is used with it instead.
+    pub(crate) fn supported_by(self, version: (u16, u16, u16)) -> CharSet {
+        match self {
+            CharSet::utf8mb4 if version < (5, 5, 3) => CharSet::utf8,
+            char_set => char_set,
+        }
+    }
+}
+
+/// Returns `true` if the collation identified by `id` encodes text as UTF-8
+/// (either `utf8` / `utf8mb3` or `utf8mb4`).
+///
+/// Unlike [`Collation`], this covers the ids above 255 that MySQL 8 reports in
+/// column definitions for the `utf8mb4_*_0900_*` family.
+pub(crate) fn is_utf8(id: u16) -> bool {
+    matches!(
+        id,
+        // utf8_general_ci, utf8_tolower_ci, utf8_bin
+        33 | 76 | 83
+        // utf8_unicode_ci ..= utf8_vietnamese_ci
+        | 192..=215
+        // utf8_general_mysql500_ci
+        | 223
+        // utf8mb4_general_ci, utf8mb4_bin
+        | 45 | 46
+        // utf8mb4_unicode_ci ..= utf8mb4_vietnamese_ci
+        | 224..=247
+        // utf8mb4_0900_ai_ci ..= utf8mb4_0900_bin
+        | 255..=309
+    )
+}
+
 impl FromStr for CharSet {
     type Err = Error;
 
@@ -898,3 +935,58 @@ impl FromStr for Collation {
         })
     }
 }
+
+#[test]
+fn it_recognizes_utf8_collations() {
+    for collation in &[
+        Collation::utf8_general_ci,
+        Collation::utf8_bin,
+        Collation::utf8_unicode_ci,
+        Collation::utf8_general_mysql500_ci,
+        Collation::utf8mb4_general_ci,
+        Collation::utf8mb4_bin,
+        Collation::utf8mb4_unicode_ci,
+        Collation::utf8mb4_vietnamese_ci,
+    ] {
+        assert!(is_utf8(*collation as u16), "{}", collation.as_str());
+    }
+
+    // utf8mb4_0900_ai_ci and utf8mb4_0900_bin, which MySQL 8 reports for columns
+    assert!(is_utf8(255));
+    assert!(is_utf8(309));
+}
+
+#[test]
+fn it_does_not_recognize_other_collations() {
+    for collation in &[
+        Collation::latin1_swedish_ci,
+        Collation::latin1_bin,
+        Collation::binary,
+        Collation::ascii_general_ci,
+        Collation::utf16_general_ci,
+        Collation::utf32_general_ci,
+    ] {
+        assert!(!is_utf8(*collation as u16), "{}", collation.as_str());
+    }
+
+    // ids the server may report that are not known to be UTF-8
+    assert!(!is_utf8(0));
+    assert!(!is_utf8(310));
+    assert!(!is_utf8(2048));
+}
+
+#[test]
+fn it_falls_back_to_utf8_on_servers_without_utf8mb4() {
+    assert_eq!(CharSet::utf8mb4.supported_by((5, 5, 2)).as_str(), "utf8");
+    assert_eq!(CharSet::utf8mb4.supported_by((5, 1, 73)).as_str(), "utf8");
+
+    assert_eq!(CharSet::utf8mb4.supported_by((5, 5, 3)).as_str(), "utf8mb4");
+    assert_eq!(
+        CharSet::utf8mb4.supported_by((8, 0, 23)).as_str(),
+        "utf8mb4"
+    );
+
+    // other charsets are left alone
+    assert_eq!(CharSet::latin1.supported_by((5, 0, 0)).as_str(), "latin1");
+    assert_eq!(CharSet::utf8.supported_by((5, 0, 0)).as_str(), "utf8");
+}
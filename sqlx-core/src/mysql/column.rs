@@ -12,6 +12,10 @@ pub struct MySqlColumn {
 
     #[cfg_attr(feature = "offline", serde(skip))]
     pub(crate) flags: Option<ColumnFlags>,
+
+    // the name (or alias) of the table the column comes from, if any
+    #[cfg_attr(feature = "offline", serde(skip))]
+    pub(crate) table: Option<UStr>,
 }
 
 impl crate::column::private_column::Sealed for MySqlColumn {}
@@ -39,12 +39,12 @@ impl AuthPlugin {
     ) -> Result<bool, Error> {
         match self {
             AuthPlugin::CachingSha2Password if packet[0] == 0x01 => {
-                match packet[1] {
+                match packet.get(1) {
                     // AUTH_OK
-                    0x03 => Ok(true),
+                    Some(0x03) => Ok(true),
 
                     // AUTH_CONTINUE
-                    0x04 => {
+                    Some(0x04) => {
                         let payload = encrypt_rsa(stream, 0x02, password, nonce).await?;
 
                         stream.write_packet(&*payload);
@@ -53,9 +53,11 @@ impl AuthPlugin {
                         Ok(false)
                     }
 
-                    v => {
+                    Some(v) => {
                         Err(err_protocol!("unexpected result from fast authentication 0x{:x} when expecting 0x03 (AUTH_OK) or 0x04 (AUTH_CONTINUE)", v))
                     }
+
+                    None => Err(err_protocol!("missing result from fast authentication")),
                 }
             }
 
@@ -131,8 +133,8 @@ async fn encrypt_rsa<'s>(
 ) -> Result<Vec<u8>, Error> {
     // https://mariadb.com/kb/en/caching_sha2_password-authentication-plugin/
 
-    if stream.is_tls() {
-        // If in a TLS stream, send the password directly in clear text
+    if stream.is_secure() {
+        // If in a TLS stream or over a Unix socket, send the password directly in clear text
         return Ok(to_asciz(password));
     }
 
@@ -0,0 +1,200 @@
+use std::cmp;
+use std::time::Instant;
+
+use crate::common::{check_read_only, SqlDialect};
+use crate::error::Error;
+use crate::logger::QueryLogger;
+use crate::mysql::connection::stream::Busy;
+use crate::mysql::protocol::response::Status;
+use crate::mysql::protocol::statement::BulkExecute;
+use crate::mysql::protocol::text::ColumnFlags;
+use crate::mysql::protocol::Capabilities;
+use crate::mysql::{MySqlArguments, MySqlConnection, MySqlFlavor, MySqlQueryResult};
+
+// the most bytes of bind values sent in one COM_STMT_BULK_EXECUTE, well below the `max_allowed_packet`
+// of a default server, which rejects a larger command outright
+const MAX_BULK_SIZE: usize = 1 << 20;
+
+impl MySqlConnection {
+    /// Executes `sql` once for each set of arguments in `rows`, and returns the rows affected
+    /// by all of them.
+    ///
+    /// On MariaDB, which can execute a prepared statement for many sets of arguments in a
+    /// single command (`COM_STMT_BULK_EXECUTE`), the statement is prepared once and every set
+    /// of arguments is sent together, in as few round trips as their size allows. Elsewhere,
+    /// or if the sets of arguments do not all have the same types, `sql` is executed for each
+    /// of them in turn, as [`execute`](crate::executor::Executor::execute) would.
+    ///
+    /// `sql` must not return rows, e.g. through `INSERT ... RETURNING`. Nothing is done in a
+    /// transaction of its own: if executing for one set of arguments fails, those before it
+    /// have been executed, unless the caller has opened a transaction.
+    ///
+    /// ```rust,ignore
+    /// let rows = users.iter().map(|user| {
+    ///     let mut arguments = MySqlArguments::default();
+    ///     arguments.add(&user.name);
+    ///     arguments.add(user.age);
+    ///     arguments
+    /// });
+    ///
+    /// conn.execute_bulk("INSERT INTO users (name, age) VALUES (?, ?)", rows).await?;
+    /// ```
+    pub async fn execute_bulk<I>(&mut self, sql: &str, rows: I) -> Result<MySqlQueryResult, Error>
+    where
+        I: IntoIterator<Item = MySqlArguments>,
+    {
+        let mut rows: Vec<MySqlArguments> = rows.into_iter().collect();
+
+        for arguments in &mut rows {
+            arguments.check_len()?;
+            arguments.check_encoded()?;
+        }
+
+        let in_flight = self.in_flight.clone();
+
+        in_flight
+            .watch(
+                "execute_bulk",
+                Box::pin(async move {
+                    if !self.can_execute_in_bulk(&rows) {
+                        let mut result = MySqlQueryResult::default();
+
+                        for arguments in rows {
+                            let done = self
+                                .execute(crate::query::query_with(sql, arguments))
+                                .await?;
+
+                            result.extend(Some(done));
+                        }
+
+                        return Ok(result);
+                    }
+
+                    self.run_bulk(sql, &rows).await
+                }),
+            )
+            .await
+    }
+
+    // whether the server can execute `rows` in bulk; they must all have the same types, and
+    // every value must be known to split off its arguments
+    fn can_execute_in_bulk(&self, rows: &[MySqlArguments]) -> bool {
+        if self.flavor != MySqlFlavor::MariaDb
+            || self.prefer_simple_protocol
+            || !self
+                .stream
+                .capabilities
+                .contains(Capabilities::MARIADB_CLIENT_STMT_BULK_OPERATIONS)
+        {
+            return false;
+        }
+
+        let first = match rows {
+            [first, _, ..] if !first.types.is_empty() => first,
+            _ => return false,
+        };
+
+        rows.iter().all(|row| {
+            row.types.len() == first.types.len()
+                && row.values().count() == row.types.len()
+                && row.types.iter().zip(&first.types).all(|(ty, first)| {
+                    ty.r#type == first.r#type
+                        && ty.flags.contains(ColumnFlags::UNSIGNED)
+                            == first.flags.contains(ColumnFlags::UNSIGNED)
+                })
+        })
+    }
+
+    async fn run_bulk(
+        &mut self,
+        sql: &str,
+        rows: &[MySqlArguments],
+    ) -> Result<MySqlQueryResult, Error> {
+        let sql = self.middleware.before_execute(sql, "MySQL", true, true)?;
+        let _logger = QueryLogger::new(&sql, self.log_settings.clone());
+
+        if self.read_only_check {
+            check_read_only(&sql, SqlDialect::MySql)?;
+        }
+
+        self.stream.wait_until_ready().await?;
+
+        let (id, _, cached) = self.get_or_prepare(&sql, true).await?;
+
+        let mut result = MySqlQueryResult::default();
+        let mut rest = rows;
+
+        let outcome = loop {
+            if rest.is_empty() {
+                break Ok(());
+            }
+
+            // at least one row, however large
+            let mut size = 0;
+            let len = cmp::max(
+                1,
+                rest.iter()
+                    .take_while(|row| {
+                        size += row.values.len();
+                        size <= MAX_BULK_SIZE
+                    })
+                    .count(),
+            );
+
+            let (chunk, next) = rest.split_at(len);
+            rest = next;
+
+            match self.send_bulk(id, chunk).await {
+                Ok(done) => result.extend(Some(done)),
+                Err(error) => break Err(error),
+            }
+        };
+
+        if !cached {
+            self.stream.close_statement_later(id);
+        }
+
+        outcome.map(|()| result)
+    }
+
+    async fn send_bulk(
+        &mut self,
+        statement: u32,
+        rows: &[MySqlArguments],
+    ) -> Result<MySqlQueryResult, Error> {
+        self.stream.busy = Busy::Result;
+
+        let sent_at = Instant::now();
+        self.stream
+            .send_packet(BulkExecute { statement, rows })
+            .await?;
+
+        let packet = self.stream.recv_packet().await?;
+        self.stats.record_query(sent_at.elapsed());
+
+        // `recv_packet` turns an ERR packet into an error, so anything but OK is a result set,
+        // which is read to the end and dropped
+        if packet[0] != 0x00 {
+            self.stream.busy = Busy::Row;
+            self.stream.wait_until_ready().await?;
+
+            return Err(Error::Configuration(
+                "statements executed in bulk must not return rows".into(),
+            ));
+        }
+
+        let ok = self.stream.decode_ok(packet)?;
+
+        if !ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
+            self.stream.busy = Busy::NotBusy;
+        }
+
+        Ok(MySqlQueryResult {
+            rows_affected: ok.affected_rows,
+            last_insert_id: ok.last_insert_id,
+            lossy_utf8_conversions: 0,
+            warnings: ok.warnings.into(),
+            info: ok.info,
+        })
+    }
+}
@@ -0,0 +1,114 @@
+use crate::error::Error;
+use crate::mysql::MySqlConnection;
+use crate::query_scalar::query_scalar;
+use serde_json::Value;
+
+impl MySqlConnection {
+    /// Find the tables of this statement on the nullable side of an outer join using EXPLAIN,
+    /// by the names (or aliases) its columns refer to them by.
+    ///
+    /// This only finds the tables which the server attaches a condition to that they may not
+    /// have a matching row for, and none if the statement cannot be explained.
+    pub(super) async fn outer_joined_tables(
+        &mut self,
+        sql: &str,
+        params_len: usize,
+    ) -> Result<Vec<String>, Error> {
+        let explain = format!("EXPLAIN FORMAT=JSON {}", sql);
+        let mut query = query_scalar::<_, String>(&explain);
+
+        // fill the arguments list with NULL, which should theoretically be valid
+        for _ in 0..params_len {
+            query = query.bind(None::<i32>);
+        }
+
+        let explain = match query.fetch_one(&mut *self).await {
+            Ok(explain) => explain,
+
+            // not every statement can be explained, nor by everyone who can run it
+            Err(Error::Database(error)) => {
+                log::debug!(
+                    "inferring nullability without EXPLAIN, which failed: {}",
+                    error
+                );
+
+                return Ok(Vec::new());
+            }
+
+            Err(error) => return Err(error),
+        };
+
+        let explain: Value = serde_json::from_str(&explain)
+            .map_err(|error| err_protocol!("EXPLAIN returned invalid JSON: {}", error))?;
+
+        let mut tables = Vec::new();
+        visit_explain(&explain, &mut tables);
+
+        Ok(tables)
+    }
+}
+
+fn visit_explain(value: &Value, tables: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::Object(table)) = object.get("table") {
+                if let (Some(Value::String(name)), Some(Value::String(condition))) =
+                    (table.get("table_name"), table.get("attached_condition"))
+                {
+                    // MySQL guards the condition of a table that may not have a matching row with
+                    // `is_not_null_compl`, and MariaDB with `trigcond`
+                    // N.B. this may produce false positives but those don't cause runtime errors
+                    if condition.contains("is_not_null_compl(") || condition.contains("trigcond(") {
+                        tables.push(name.clone());
+                    }
+                }
+            }
+
+            for value in object.values() {
+                visit_explain(value, tables);
+            }
+        }
+
+        Value::Array(values) => {
+            for value in values {
+                visit_explain(value, tables);
+            }
+        }
+
+        _ => {}
+    }
+}
+
+#[test]
+fn it_finds_outer_joined_tables() {
+    let explain: Value = serde_json::from_str(
+        r#"{
+  "query_block": {
+    "select_id": 1,
+    "nested_loop": [
+      {
+        "table": {
+          "table_name": "t1",
+          "access_type": "ALL",
+          "rows_examined_per_scan": 2
+        }
+      },
+      {
+        "table": {
+          "table_name": "t2",
+          "access_type": "ALL",
+          "rows_examined_per_scan": 2,
+          "attached_condition": "<if>(is_not_null_compl(t2), (`sqlx`.`t2`.`id` = `sqlx`.`t1`.`owner_id`), true)"
+        }
+      }
+    ]
+  }
+}"#,
+    )
+    .unwrap();
+
+    let mut tables = Vec::new();
+    visit_explain(&explain, &mut tables);
+
+    assert_eq!(tables, ["t2"]);
+}
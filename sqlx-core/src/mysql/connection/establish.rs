@@ -1,14 +1,20 @@
 use bytes::buf::Buf;
 use bytes::Bytes;
 
-use crate::common::StatementCache;
+use crate::common::{
+    default_application_name, sanitize_application_name, InFlight, StatementCache,
+};
+use crate::connection::StatsCollector;
+use crate::database::{self, Database};
 use crate::error::Error;
+use crate::events::ConnectionEvents;
 use crate::mysql::connection::{tls, MySqlStream, MAX_PACKET_SIZE};
+use crate::mysql::flavor::parse_server_version;
 use crate::mysql::protocol::connect::{
     AuthSwitchRequest, AuthSwitchResponse, Handshake, HandshakeResponse,
 };
 use crate::mysql::protocol::Capabilities;
-use crate::mysql::{MySqlConnectOptions, MySqlConnection, MySqlSslMode};
+use crate::mysql::{MySql, MySqlConnectOptions, MySqlConnection, MySqlFlavor, MySqlSslMode};
 
 impl MySqlConnection {
     pub(crate) async fn establish(options: &MySqlConnectOptions) -> Result<Self, Error> {
@@ -22,34 +28,28 @@ impl MySqlConnection {
         let mut plugin = handshake.auth_plugin;
         let mut nonce = handshake.auth_plugin_data;
 
-        // FIXME: server version parse is a bit ugly
-        // expecting MAJOR.MINOR.PATCH
+        let (flavor, server_version) =
+            parse_server_version(&handshake.server_version, handshake.server_capabilities);
 
-        let mut server_version = handshake.server_version.split('.');
+        let capabilities = server_capabilities(flavor, server_version);
 
-        let server_version_major: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
+        stream.server_version = server_version;
 
-        let server_version_minor: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
+        let charset = stream.charset.supported_by(server_version);
 
-        let server_version_patch: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
+        if charset.as_str() != stream.charset.as_str() {
+            log::warn!(
+                "MySQL server {}.{}.{} does not support the `{}` charset; falling back to `{}`",
+                server_version.0,
+                server_version.1,
+                server_version.2,
+                stream.charset.as_str(),
+                charset.as_str(),
+            );
 
-        stream.server_version = (
-            server_version_major,
-            server_version_minor,
-            server_version_patch,
-        );
+            stream.charset = charset;
+            stream.collation = charset.default_collation();
+        }
 
         stream.capabilities &= handshake.server_capabilities;
         stream.capabilities |= Capabilities::PROTOCOL_41;
@@ -68,6 +68,20 @@ impl MySqlConnection {
             None
         };
 
+        // the server truncates attribute values to 1024 bytes; do it here instead so a
+        // multi-byte character is never cut in half
+        let program_name = options
+            .application_name
+            .as_deref()
+            .or_else(|| default_application_name())
+            .map(|name| sanitize_application_name(name, 1024, false));
+
+        let connect_attrs: Vec<(&str, &str)> = program_name
+            .as_deref()
+            .map(|name| ("program_name", name))
+            .into_iter()
+            .collect();
+
         stream.write_packet(HandshakeResponse {
             collation: stream.collation as u8,
             max_packet_size: MAX_PACKET_SIZE,
@@ -75,6 +89,7 @@ impl MySqlConnection {
             database: options.database.as_deref(),
             auth_plugin: plugin,
             auth_response: auth_response.as_deref(),
+            connect_attrs: &connect_attrs,
         });
 
         stream.flush().await?;
@@ -83,7 +98,7 @@ impl MySqlConnection {
             let packet = stream.recv_packet().await?;
             match packet[0] {
                 0x00 => {
-                    let _ok = packet.ok()?;
+                    stream.decode_ok(packet)?;
 
                     break;
                 }
@@ -91,6 +106,21 @@ impl MySqlConnection {
                 0xfe => {
                     let switch: AuthSwitchRequest = packet.decode()?;
 
+                    if let Some(current) = plugin {
+                        if switch.plugin.is_weaker_than(current)
+                            && !options
+                                .effective_protocol_policy()
+                                .allow_auth_method_downgrade
+                        {
+                            return Err(Error::PolicyViolation(format!(
+                                "server asked to switch authentication from {} to {}, \
+                                 and auth method downgrades are not allowed",
+                                current.name(),
+                                switch.plugin.name()
+                            )));
+                        }
+                    }
+
                     plugin = Some(switch.plugin);
                     nonce = switch.data.chain(Bytes::new());
 
@@ -125,11 +155,63 @@ impl MySqlConnection {
             }
         }
 
+        let mut events = ConnectionEvents::new(&options.events, options.connection_info());
+        events.set_connection_id(handshake.connection_id.into());
+
         Ok(Self {
             stream,
             transaction_depth: 0,
-            cache_statement: StatementCache::new(options.statement_cache_capacity),
+            local_variables: Vec::new(),
+            xa_transaction: None,
+            cache_statement: match options.statement_cache_max_bytes {
+                Some(max_bytes) => StatementCache::with_max_bytes(max_bytes),
+                None => StatementCache::new(options.statement_cache_capacity),
+            },
+            in_flight: InFlight::default(),
+            // enabled once the session has been set up
+            read_only_check: false,
+            lossy_utf8: false,
+            prefer_simple_protocol: false,
+            column_name_matching: options.column_name_matching,
+            // told apart from MySQL once the session has been set up
+            flavor,
+            capabilities,
+            stats: StatsCollector::new(),
             log_settings: options.log_settings.clone(),
+            middleware: options.middleware.clone(),
+            events,
         })
     }
 }
+
+// the capabilities of the server, given the flavor and version it reported in the handshake
+fn server_capabilities(flavor: MySqlFlavor, version: (u16, u16, u16)) -> database::Capabilities {
+    let mut capabilities = MySql::CAPABILITIES;
+
+    // `INSERT ... RETURNING` and `DELETE ... RETURNING` were added in MariaDB 10.5
+    if flavor == MySqlFlavor::MariaDb && version >= (10, 5, 0) {
+        capabilities |= database::Capabilities::RETURNING;
+    }
+
+    capabilities
+}
+
+#[test]
+fn it_detects_server_capabilities() {
+    use database::Capabilities as Caps;
+
+    let of = |version| {
+        let (flavor, version) = parse_server_version(version, Capabilities::MYSQL);
+
+        server_capabilities(flavor, version)
+    };
+
+    let returning = |version| of(version).contains(Caps::RETURNING);
+
+    assert!(!returning("8.0.23"));
+    assert!(!returning("5.5.5-10.4.17-MariaDB"));
+    assert!(returning("5.5.5-10.5.8-MariaDB-1:10.5.8+maria~focal"));
+    assert!(returning("11.2.2-MariaDB"));
+
+    assert!(of("8.0.23").contains(Caps::SAVEPOINTS));
+}
@@ -1,38 +1,45 @@
 use super::MySqlStream;
+use crate::common::{check_read_only, SqlDialect};
 use crate::describe::Describe;
 use crate::error::Error;
-use crate::executor::{Execute, Executor};
+use crate::executor::{Execute, Executor, FetchLimits};
 use crate::ext::ustr::UStr;
 use crate::logger::QueryLogger;
 use crate::mysql::connection::stream::Busy;
+use crate::mysql::interpolate::interpolate;
 use crate::mysql::io::MySqlBufExt;
 use crate::mysql::protocol::response::Status;
 use crate::mysql::protocol::statement::{
-    BinaryRow, Execute as StatementExecute, Prepare, PrepareOk, StmtClose,
+    BinaryRow, Execute as StatementExecute, Prepare, PrepareOk,
 };
 use crate::mysql::protocol::text::{ColumnDefinition, ColumnFlags, Query, TextRow};
+use crate::mysql::row::replace_invalid_utf8;
 use crate::mysql::statement::{MySqlStatement, MySqlStatementMetadata};
 use crate::mysql::{
     MySql, MySqlArguments, MySqlColumn, MySqlConnection, MySqlQueryResult, MySqlRow, MySqlTypeInfo,
     MySqlValueFormat,
 };
 use crate::HashMap;
+use bytes::Bytes;
 use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_core::Stream;
 use futures_util::{pin_mut, TryStreamExt};
+use std::time::Instant;
 use std::{borrow::Cow, sync::Arc};
 
 impl MySqlConnection {
-    async fn get_or_prepare<'c>(
+    // the returned flag is `false` if the statement was not cached; the caller must then close
+    // it with `close_statement_later` once it is done with it, or it stays open on the server
+    pub(super) async fn get_or_prepare(
         &mut self,
         sql: &str,
         persistent: bool,
-    ) -> Result<(u32, MySqlStatementMetadata), Error> {
-        if let Some(statement) = self.cache_statement.get_mut(sql) {
+    ) -> Result<(u32, MySqlStatementMetadata, bool), Error> {
+        if let Some((id, metadata)) = self.cache_statement.get_mut(sql) {
             // <MySqlStatementMetadata> is internally reference-counted
-            return Ok((*statement).clone());
+            return Ok((*id, metadata.clone(), true));
         }
 
         // https://dev.mysql.com/doc/internals/en/com-stmt-prepare.html
@@ -73,26 +80,64 @@ impl MySqlConnection {
         };
 
         if persistent && self.cache_statement.is_enabled() {
-            // in case of the cache being full, close the least recently used statement
-            if let Some((id, _)) = self.cache_statement.insert(sql, (id, metadata.clone())) {
-                self.stream.send_packet(StmtClose { statement: id }).await?;
+            let size = metadata.estimated_size();
+
+            // in case of the cache being full, close the least recently used statements; if the
+            // pinned statements leave no room, this one is not cached
+            if let Ok(replaced) = self
+                .cache_statement
+                .insert(sql, (id, metadata.clone()), size)
+            {
+                for (id, _) in replaced {
+                    self.stream.close_statement_later(id);
+                }
+
+                return Ok((id, metadata, true));
             }
         }
 
-        Ok((id, metadata))
+        Ok((id, metadata, false))
     }
 
     #[allow(clippy::needless_lifetimes)]
     async fn run<'e, 'c: 'e, 'q: 'e>(
         &'c mut self,
         sql: &'q str,
-        arguments: Option<MySqlArguments>,
+        mut arguments: Option<MySqlArguments>,
         persistent: bool,
+        limits: FetchLimits,
     ) -> Result<impl Stream<Item = Result<Either<MySqlQueryResult, MySqlRow>, Error>> + 'e, Error>
     {
         let mut logger = QueryLogger::new(sql, self.log_settings.clone());
 
+        if let Some(arguments) = &mut arguments {
+            arguments.check_len()?;
+            arguments.check_encoded()?;
+        }
+
+        if self.read_only_check {
+            check_read_only(sql, SqlDialect::MySql)?;
+        }
+
         self.stream.wait_until_ready().await?;
+
+        // without prepared statements, the bind values are written into the SQL, which is then
+        // sent like an unprepared query
+        let mut interpolated = None;
+
+        if self.prefer_simple_protocol {
+            if let Some(arguments) = arguments.take() {
+                logger.bind_values(arguments.values());
+
+                let backslash_escapes = !self
+                    .stream
+                    .status
+                    .contains(Status::SERVER_STATUS_NO_BACKSLASH_ESCAPES);
+
+                interpolated = Some(interpolate(sql, &arguments, backslash_escapes)?);
+            }
+        }
+
         self.stream.busy = Busy::Result;
 
         Ok(Box::pin(try_stream! {
@@ -101,14 +146,19 @@ impl MySqlConnection {
             // to re-use this memory freely between result sets
             let mut columns = Arc::new(Vec::new());
 
+            let sent_at;
+
             let (mut column_names, format, mut needs_metadata) = if let Some(arguments) = arguments {
-                let (id, metadata) = self.get_or_prepare(
+                let (id, metadata, cached) = self.get_or_prepare(
                     sql,
                     persistent,
                 )
                 .await?;
 
+                logger.bind_values(arguments.values());
+
                 // https://dev.mysql.com/doc/internals/en/com-stmt-execute.html
+                sent_at = Instant::now();
                 self.stream
                     .send_packet(StatementExecute {
                         statement: id,
@@ -116,27 +166,47 @@ impl MySqlConnection {
                     })
                     .await?;
 
+                if !cached {
+                    // the server finishes sending the results before it reads the next command
+                    self.stream.close_statement_later(id);
+                }
+
                 (metadata.column_names, MySqlValueFormat::Binary, false)
             } else {
                 // https://dev.mysql.com/doc/internals/en/com-query.html
-                self.stream.send_packet(Query(sql)).await?;
+                sent_at = Instant::now();
+                self.stream
+                    .send_packet(Query(interpolated.as_deref().unwrap_or(sql)))
+                    .await?;
 
                 (Arc::default(), MySqlValueFormat::Text, true)
             };
 
+            let mut sent_at = Some(sent_at);
+
             loop {
                 // query response is a meta-packet which may be one of:
                 //  Ok, Err, ResultSet, or (unhandled) LocalInfileRequest
-                let mut packet = self.stream.recv_packet().await?;
+                let packet = self.stream.recv_packet().await;
+
+                // the first response completes the round trip
+                if let Some(sent_at) = sent_at.take() {
+                    self.stats.record_query(sent_at.elapsed());
+                }
+
+                let mut packet = packet?;
 
                 if packet[0] == 0x00 || packet[0] == 0xff {
                     // first packet in a query response is OK or ERR
                     // this indicates either a successful query with no rows at all or a failed query
-                    let ok = packet.ok()?;
+                    let ok = self.stream.decode_ok(packet)?;
 
                     let done = MySqlQueryResult {
                         rows_affected: ok.affected_rows,
                         last_insert_id: ok.last_insert_id,
+                        lossy_utf8_conversions: 0,
+                        warnings: ok.warnings.into(),
+                        info: ok.info,
                     };
 
                     r#yield!(Either::Left(done));
@@ -153,7 +223,7 @@ impl MySqlConnection {
                 // otherwise, this first packet is the start of the result-set metadata,
                 self.stream.busy = Busy::Row;
 
-                let num_columns = packet.get_uint_lenenc() as usize; // column count
+                let num_columns = column_count(&mut packet)?;
 
                 if needs_metadata {
                     column_names = Arc::new(recv_result_metadata(&mut self.stream, num_columns, Arc::make_mut(&mut columns)).await?);
@@ -165,16 +235,21 @@ impl MySqlConnection {
                     recv_result_columns(&mut self.stream, num_columns, Arc::make_mut(&mut columns)).await?;
                 }
 
+                let mut lossy_utf8_conversions = 0;
+
                 // finally, there will be none or many result-rows
                 loop {
                     let packet = self.stream.recv_packet().await?;
 
-                    if packet[0] == 0xfe && packet.len() < 9 {
-                        let eof = packet.eof(self.stream.capabilities)?;
+                    if self.stream.is_eof(&packet) {
+                        let eof = self.stream.decode_eof(packet)?;
 
                         r#yield!(Either::Left(MySqlQueryResult {
                             rows_affected: 0,
                             last_insert_id: 0,
+                            lossy_utf8_conversions,
+                            warnings: eof.warnings.into(),
+                            info: None,
                         }));
 
                         if eof.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
@@ -187,16 +262,26 @@ impl MySqlConnection {
                         return Ok(());
                     }
 
-                    let row = match format {
+                    let mut row = match format {
                         MySqlValueFormat::Binary => packet.decode_with::<BinaryRow, _>(&columns)?.0,
                         MySqlValueFormat::Text => packet.decode_with::<TextRow, _>(&columns)?.0,
                     };
 
+                    // the rest of the result is read to the end when the connection is next used
+                    limits.check(row.values.iter().map(|value| {
+                        value.as_ref().map(|range| range.end - range.start)
+                    }))?;
+
+                    if self.lossy_utf8 {
+                        lossy_utf8_conversions += replace_invalid_utf8(&mut row, &columns);
+                    }
+
                     let v = Either::Right(MySqlRow {
                         row,
                         format,
                         columns: Arc::clone(&columns),
                         column_names: Arc::clone(&column_names),
+                        column_name_matching: self.column_name_matching,
                     });
 
                     logger.increment_rows();
@@ -208,6 +293,10 @@ impl MySqlConnection {
     }
 }
 
+impl MySqlConnection {
+    impl_executor_methods!(MySql, &mut self => self);
+}
+
 impl<'c> Executor<'c> for &'c mut MySqlConnection {
     type Database = MySql;
 
@@ -222,17 +311,26 @@ impl<'c> Executor<'c> for &'c mut MySqlConnection {
         let sql = query.sql();
         let arguments = query.take_arguments();
         let persistent = query.persistent();
+        let limits = query.fetch_limits();
+        let sql = self
+            .middleware
+            .before_execute(sql, "MySQL", arguments.is_some(), persistent);
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch_stream(
+            "fetch_many",
+            Box::pin(try_stream! {
+                let sql = sql?;
+                let s = self.run(&sql, arguments, persistent, limits).await?;
+                pin_mut!(s);
+
+                while let Some(v) = s.try_next().await? {
+                    r#yield!(v);
+                }
 
-        Box::pin(try_stream! {
-            let s = self.run(sql, arguments, persistent).await?;
-            pin_mut!(s);
-
-            while let Some(v) = s.try_next().await? {
-                r#yield!(v);
-            }
-
-            Ok(())
-        })
+                Ok(())
+            }),
+        )
     }
 
     fn fetch_optional<'e, 'q: 'e, E: 'q>(
@@ -243,17 +341,21 @@ impl<'c> Executor<'c> for &'c mut MySqlConnection {
         'c: 'e,
         E: Execute<'q, Self::Database>,
     {
+        let in_flight = self.in_flight.clone();
         let mut s = self.fetch_many(query);
 
-        Box::pin(async move {
-            while let Some(v) = s.try_next().await? {
-                if let Either::Right(r) = v {
-                    return Ok(Some(r));
+        in_flight.watch(
+            "fetch_optional",
+            Box::pin(async move {
+                while let Some(v) = s.try_next().await? {
+                    if let Either::Right(r) = v {
+                        return Ok(Some(r));
+                    }
                 }
-            }
 
-            Ok(None)
-        })
+                Ok(None)
+            }),
+        )
     }
 
     fn prepare_with<'e, 'q: 'e>(
@@ -264,17 +366,28 @@ impl<'c> Executor<'c> for &'c mut MySqlConnection {
     where
         'c: 'e,
     {
-        Box::pin(async move {
-            self.stream.wait_until_ready().await?;
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch(
+            "prepare",
+            Box::pin(async move {
+                self.stream.wait_until_ready().await?;
 
-            let (_, metadata) = self.get_or_prepare(sql, true).await?;
+                // without prepared statements, only the metadata is kept
+                let persistent = !self.prefer_simple_protocol;
+                let (id, metadata, cached) = self.get_or_prepare(sql, persistent).await?;
 
-            Ok(MySqlStatement {
-                sql: Cow::Borrowed(sql),
-                // metadata has internal Arcs for expensive data structures
-                metadata: metadata.clone(),
-            })
-        })
+                if !cached {
+                    self.stream.close_statement_later(id);
+                }
+
+                Ok(MySqlStatement {
+                    sql: Cow::Borrowed(sql),
+                    // metadata has internal Arcs for expensive data structures
+                    metadata: metadata.clone(),
+                })
+            }),
+        )
     }
 
     #[doc(hidden)]
@@ -282,27 +395,47 @@ impl<'c> Executor<'c> for &'c mut MySqlConnection {
     where
         'c: 'e,
     {
-        Box::pin(async move {
-            self.stream.wait_until_ready().await?;
+        let in_flight = self.in_flight.clone();
 
-            let (_, metadata) = self.get_or_prepare(sql, false).await?;
+        in_flight.watch(
+            "describe",
+            Box::pin(async move {
+                self.stream.wait_until_ready().await?;
 
-            let columns = (&*metadata.columns).clone();
+                let (id, metadata, _) = self.get_or_prepare(sql, false).await?;
+                self.stream.close_statement_later(id);
 
-            let nullable = columns
-                .iter()
-                .map(|col| {
-                    col.flags
-                        .map(|flags| !flags.contains(ColumnFlags::NOT_NULL))
+                let columns = (*metadata.columns).clone();
+
+                let outer_joined = if columns.is_empty() {
+                    Vec::new()
+                } else {
+                    self.outer_joined_tables(sql, metadata.parameters).await?
+                };
+
+                let nullable = columns
+                    .iter()
+                    .map(|col| {
+                        // the flags don't always account for a table that may not have a
+                        // matching row in an outer join
+                        if let Some(table) = &col.table {
+                            if outer_joined.iter().any(|name| **name == **table) {
+                                return Some(true);
+                            }
+                        }
+
+                        col.flags
+                            .map(|flags| !flags.contains(ColumnFlags::NOT_NULL))
+                    })
+                    .collect();
+
+                Ok(Describe {
+                    parameters: Some(Either::Right(metadata.parameters)),
+                    columns,
+                    nullable,
                 })
-                .collect();
-
-            Ok(Describe {
-                parameters: Some(Either::Right(metadata.parameters)),
-                columns,
-                nullable,
-            })
-        })
+            }),
+        )
     }
 }
 
@@ -335,14 +468,35 @@ fn recv_next_result_column(def: &ColumnDefinition, ordinal: usize) -> Result<MyS
 
     let type_info = MySqlTypeInfo::from_column(&def);
 
+    let table = match def.table_alias()? {
+        "" => None,
+        table => Some(UStr::new(table)),
+    };
+
     Ok(MySqlColumn {
         name,
         type_info,
         ordinal,
         flags: Some(def.flags),
+        table,
     })
 }
 
+// the column count that starts a result set; the space for the columns is reserved up front, so
+// a count no server could send is rejected rather than allocated for
+fn column_count(packet: &mut Bytes) -> Result<usize, Error> {
+    let num_columns = packet.get_uint_lenenc()?;
+
+    if num_columns > u64::from(u16::MAX) {
+        return Err(err_protocol!(
+            "result set has an invalid column count: {}",
+            num_columns
+        ));
+    }
+
+    Ok(num_columns as usize)
+}
+
 async fn recv_result_metadata(
     stream: &mut MySqlStream,
     num_columns: usize,
@@ -361,7 +515,8 @@ async fn recv_result_metadata(
 
         let column = recv_next_result_column(&def, ordinal)?;
 
-        column_names.insert(column.name.clone(), ordinal);
+        // a duplicated name refers to its first column
+        column_names.entry(column.name.clone()).or_insert(ordinal);
         columns.push(column);
     }
 
@@ -1,16 +1,27 @@
-use crate::common::StatementCache;
-use crate::connection::{Connection, LogSettings};
+use crate::column::ColumnNameMatching;
+use crate::common::{InFlight, StatementCache};
+use crate::connection::{
+    BufferSizes, Connection, ConnectionStats, LogSettings, SessionState, StatsCollector,
+};
+use crate::database::Capabilities;
 use crate::error::Error;
-use crate::mysql::protocol::statement::StmtClose;
+use crate::events::{CloseReason, ConnectionEvents};
+use crate::middleware::Middleware;
+use crate::mysql::collation::CharSet;
+use crate::mysql::protocol::response::Status;
 use crate::mysql::protocol::text::{Ping, Quit};
 use crate::mysql::statement::MySqlStatementMetadata;
-use crate::mysql::{MySql, MySqlConnectOptions};
+use crate::mysql::{MySql, MySqlConnectOptions, MySqlFlavor};
 use crate::transaction::Transaction;
 use futures_core::future::BoxFuture;
 use futures_util::FutureExt;
 use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::time::Instant;
 
 mod auth;
+mod bulk;
+mod describe;
 mod establish;
 mod executor;
 mod stream;
@@ -30,15 +41,129 @@ pub struct MySqlConnection {
     // transaction status
     pub(crate) transaction_depth: usize,
 
+    // session variables set by `Transaction::set_local`, with the transaction depth they were
+    // set at; the previous value of the variable at index `i` is kept in `@_sqlx_local_{i}`
+    pub(crate) local_variables: Vec<(usize, String)>,
+
+    // the quoted id of the XA transaction started by `begin_two_phase`, which the transaction
+    // manager starts and ends instead of an ordinary transaction
+    pub(crate) xa_transaction: Option<String>,
+
     // cache by query string to the statement id and metadata
     cache_statement: StatementCache<(u32, MySqlStatementMetadata)>,
 
+    // the operation in progress, so that another is not started while it is
+    pub(crate) in_flight: InFlight,
+
+    // reject statements that may write before sending them
+    pub(crate) read_only_check: bool,
+
+    // replace invalid UTF-8 in text values instead of failing to decode them
+    pub(crate) lossy_utf8: bool,
+
+    // run every query as text, writing the bind values into the SQL
+    pub(crate) prefer_simple_protocol: bool,
+
+    // how the rows read on the connection match column names
+    pub(crate) column_name_matching: ColumnNameMatching,
+
+    // the kind of server, which decides e.g. whether rows can be executed in bulk
+    pub(crate) flavor: MySqlFlavor,
+
+    // what the server supports, including what depends on its version
+    capabilities: Capabilities,
+
+    stats: StatsCollector,
+
     log_settings: LogSettings,
+    middleware: Middleware,
+
+    // the listener of connection events, told when the connection is closed
+    pub(crate) events: ConnectionEvents,
+}
+
+impl MySqlConnection {
+    /// The default schema of the session, i.e. what `SELECT DATABASE()` returns.
+    ///
+    /// This starts as the database given in the connect options and follows changes made with
+    /// `USE` as long as the server reports them, which it does unless `session_track_schema`
+    /// is turned off.
+    pub fn schema(&self) -> Option<&str> {
+        self.stream.schema.as_deref()
+    }
+
+    /// The kind of server this is connected to.
+    ///
+    /// MariaDB is recognized by the version it reports when connecting, and Percona Server by
+    /// its `@@version_comment`, which is read while setting up the session; any other server
+    /// is taken for MySQL.
+    pub fn server_flavor(&self) -> MySqlFlavor {
+        self.flavor
+    }
+
+    /// The version of the server as `(major, minor, patch)`, e.g. `(10, 5, 8)` for MariaDB
+    /// 10.5.8, which reports itself as `5.5.5-10.5.8-MariaDB` before 11.0.
+    ///
+    /// A part that could not be parsed is `0`.
+    pub fn server_version(&self) -> (u16, u16, u16) {
+        self.stream.server_version
+    }
+
+    /// The value of a session system variable, as last reported by the server.
+    ///
+    /// The server only reports the variables listed in `session_track_system_variables`, by
+    /// default `autocommit`, `time_zone` and the `character_set_*` variables, and only when they
+    /// change; this returns `None` for a variable that has not changed since connecting.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.stream
+            .parameters
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Quotes `value` so it can be written into SQL as a string literal, where a bind parameter
+    /// is not allowed, e.g. the default of a column in DDL.
+    ///
+    /// Quotes, backslashes and the characters `mysql_real_escape_string` escapes are escaped
+    /// with a backslash, unless the `NO_BACKSLASH_ESCAPES` SQL mode is set, in which case only
+    /// single quotes are doubled. The mode is taken from the status of the session that the
+    /// server sends with every response, so a `SET sql_mode` applies once it has completed.
+    ///
+    /// Connections using `big5`, `cp932`, `gb18030`, `gbk` or `sjis` are refused, as the server
+    /// can read a backslash inserted as an escape as the second byte of a character there.
+    pub fn quote_literal(&self, value: &str) -> Result<String, Error> {
+        if matches!(
+            self.stream.charset,
+            CharSet::big5 | CharSet::cp932 | CharSet::gb18030 | CharSet::gbk | CharSet::sjis
+        ) {
+            return Err(Error::encode(format!(
+                "cannot quote literals for a connection using the {} character set",
+                self.stream.charset.as_str()
+            )));
+        }
+
+        Ok(crate::mysql::quote::quote_literal(
+            value,
+            self.stream
+                .status
+                .contains(Status::SERVER_STATUS_NO_BACKSLASH_ESCAPES),
+        ))
+    }
+
+    // whether the server has a transaction open, as of the last response; if a command is
+    // still in flight its effect is not known yet, so a transaction is assumed
+    pub(crate) fn in_server_transaction(&self) -> bool {
+        self.stream.busy != Busy::NotBusy
+            || !self.stream.wbuf.is_empty()
+            || self.stream.status.contains(Status::SERVER_STATUS_IN_TRANS)
+    }
 }
 
 impl Debug for MySqlConnection {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.debug_struct("MySqlConnection").finish()
+        f.debug_struct("MySqlConnection")
+            .field("schema", &self.stream.schema)
+            .finish()
     }
 }
 
@@ -48,27 +173,91 @@ impl Connection for MySqlConnection {
     type Options = MySqlConnectOptions;
 
     fn close(mut self) -> BoxFuture<'static, Result<(), Error>> {
+        let events = mem::take(&mut self.events);
+
         Box::pin(async move {
-            self.stream.send_packet(Quit).await?;
-            self.stream.shutdown().await?;
+            let result = async {
+                self.stream.send_packet(Quit).await?;
+                self.stream.shutdown().await?;
+
+                Ok::<_, Error>(())
+            }
+            .await;
 
-            Ok(())
+            events.closed(CloseReason::Explicit);
+
+            result
         })
     }
 
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
-        Box::pin(async move {
-            self.stream.wait_until_ready().await?;
-            self.stream.send_packet(Ping).await?;
-            self.stream.recv_ok().await?;
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch(
+            "ping",
+            Box::pin(async move {
+                self.stream.wait_until_ready().await?;
+
+                let start = Instant::now();
+                self.stream.send_packet(Ping).await?;
+                self.stream.recv_ok().await?;
 
-            Ok(())
+                // the other drivers ping with a query, so count it as one here too
+                let latency = start.elapsed();
+                self.stats.record_query(latency);
+                self.stats.record_ping(latency);
+
+                Ok(())
+            }),
+        )
+    }
+
+    fn shrink_buffers(&mut self) {
+        self.stream.shrink();
+    }
+
+    fn buffer_sizes(&self) -> BufferSizes {
+        let (read, write) = self.stream.capacity();
+
+        BufferSizes { read, write }
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        let (bytes_received, bytes_sent) = self.stream.bytes_transferred();
+
+        let mut stats = self.stats.snapshot(bytes_sent, bytes_received);
+        stats.statement_cache = self.cache_statement.stats();
+
+        stats
+    }
+
+    // the `search_path`, prepared statements and temporary tables are not tracked, as there
+    // is no cheap way to read them
+    fn session_state(&mut self) -> BoxFuture<'_, Result<SessionState, Error>> {
+        let in_transaction = self.stream.status.contains(Status::SERVER_STATUS_IN_TRANS);
+
+        Box::pin(async move {
+            Ok(SessionState {
+                in_transaction: Some(in_transaction),
+                ..SessionState::default()
+            })
         })
     }
 
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    #[doc(hidden)]
+    fn take_events(&mut self) -> ConnectionEvents {
+        mem::take(&mut self.events)
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
-        self.stream.wait_until_ready().boxed()
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch("flush", self.stream.wait_until_ready().boxed())
     }
 
     fn cached_statements_size(&self) -> usize {
@@ -76,22 +265,58 @@ impl Connection for MySqlConnection {
     }
 
     fn clear_cached_statements(&mut self) -> BoxFuture<'_, Result<(), Error>> {
-        Box::pin(async move {
-            while let Some((statement_id, _)) = self.cache_statement.remove_lru() {
-                self.stream
-                    .send_packet(StmtClose {
-                        statement: statement_id,
-                    })
-                    .await?;
-            }
+        let in_flight = self.in_flight.clone();
 
-            Ok(())
-        })
+        in_flight.watch(
+            "clear_cached_statements",
+            Box::pin(async move {
+                for (statement_id, _) in self.cache_statement.drain() {
+                    self.stream.close_statement_later(statement_id);
+                }
+
+                self.stream.wait_until_ready().await
+            }),
+        )
+    }
+
+    fn prepare_pinned<'e>(&'e mut self, sql: &'e str) -> BoxFuture<'e, Result<(), Error>> {
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch(
+            "prepare_pinned",
+            Box::pin(async move {
+                // nothing is cached without prepared statements
+                if self.prefer_simple_protocol || !self.cache_statement.is_enabled() {
+                    return Err(Error::Configuration(
+                        "cannot pin a statement: the statement cache is disabled".into(),
+                    ));
+                }
+
+                self.stream.wait_until_ready().await?;
+
+                let (id, _, cached) = self.get_or_prepare(sql, true).await?;
+
+                if !cached {
+                    self.stream.close_statement_later(id);
+                }
+
+                if self.cache_statement.pin(sql) {
+                    Ok(())
+                } else {
+                    Err(Error::Configuration(
+                        "cannot pin a statement: the statement cache is full of pinned statements"
+                            .into(),
+                    ))
+                }
+            }),
+        )
     }
 
     #[doc(hidden)]
     fn should_flush(&self) -> bool {
-        !self.stream.wbuf.is_empty()
+        // a connection with an operation in progress, or that a panic unwound through, fails to
+        // flush, and is closed by the pool
+        !self.stream.wbuf.is_empty() || self.in_flight.is_busy() || self.in_flight.is_poisoned()
     }
 
     fn begin(&mut self) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
@@ -1,16 +1,23 @@
+use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, Bytes, BytesMut};
 
 use crate::error::Error;
-use crate::io::{BufStream, Decode, Encode};
+use crate::io::{BufStream, Decode, Encode, Framing};
 use crate::mysql::collation::{CharSet, Collation};
 use crate::mysql::io::MySqlBufExt;
-use crate::mysql::protocol::response::{EofPacket, ErrPacket, OkPacket, Status};
+use crate::mysql::protocol::response::{
+    EofPacket, ErrPacket, OkPacket, SessionStateChange, Status,
+};
+use crate::mysql::protocol::statement::StmtClose;
 use crate::mysql::protocol::{Capabilities, Packet};
 use crate::mysql::{MySqlConnectOptions, MySqlDatabaseError};
 use crate::net::{MaybeTlsStream, Socket};
 
+// the most a single packet can carry of a payload
+const MAX_PART_SIZE: usize = 0xff_ff_ff;
+
 pub struct MySqlStream {
     stream: BufStream<MaybeTlsStream<Socket>>,
     pub(crate) server_version: (u16, u16, u16),
@@ -19,6 +26,14 @@ pub struct MySqlStream {
     pub(crate) busy: Busy,
     pub(crate) charset: CharSet,
     pub(crate) collation: Collation,
+    max_message_size: usize,
+
+    // the status flags of the last OK or EOF packet, which say whether a transaction is open
+    pub(crate) status: Status,
+
+    // the default schema and the system variables, as last reported by session state tracking
+    pub(crate) schema: Option<String>,
+    pub(crate) parameters: HashMap<String, String>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -42,15 +57,19 @@ impl MySqlStream {
             .transpose()?
             .unwrap_or_else(|| charset.default_collation());
 
-        let socket = match options.socket {
-            Some(ref path) => Socket::connect_uds(path).await?,
-            None => Socket::connect_tcp(&options.host, options.port).await?,
+        let socket = if !options.addresses.is_empty() {
+            Socket::connect_addresses(&options.addresses, &options.host).await?
+        } else {
+            match (&options.socket, options.fetch_socket_owner()) {
+                (Some(path), Some(ref owner)) => Socket::connect_uds_owned_by(path, owner).await?,
+                (Some(path), None) => Socket::connect_uds(path).await?,
+                (None, _) => Socket::connect_tcp(&options.host, options.port).await?,
+            }
         };
 
         let mut capabilities = Capabilities::PROTOCOL_41
             | Capabilities::IGNORE_SPACE
             | Capabilities::DEPRECATE_EOF
-            | Capabilities::FOUND_ROWS
             | Capabilities::TRANSACTIONS
             | Capabilities::SECURE_CONNECTION
             | Capabilities::PLUGIN_AUTH_LENENC_DATA
@@ -58,12 +77,31 @@ impl MySqlStream {
             | Capabilities::MULTI_RESULTS
             | Capabilities::PLUGIN_AUTH
             | Capabilities::PS_MULTI_RESULTS
-            | Capabilities::SSL;
+            | Capabilities::CONNECT_ATTRS
+            | Capabilities::SESSION_TRACK
+            | Capabilities::SSL
+            // only MariaDB offers these; against MySQL they go with the rest it does not offer
+            | Capabilities::MARIADB_CLIENT_STMT_BULK_OPERATIONS
+            | Capabilities::MARIADB_CLIENT_EXTENDED_TYPE_INFO;
 
         if options.database.is_some() {
             capabilities |= Capabilities::CONNECT_WITH_DB;
         }
 
+        if options.found_rows {
+            capabilities |= Capabilities::FOUND_ROWS;
+        }
+
+        let mut stream = BufStream::new(MaybeTlsStream::Raw(socket));
+        stream.set_max_capacity(options.buffer_max);
+        stream.set_wire_tracer(
+            options
+                .wire_tracing
+                .as_ref()
+                .map(|tracing| tracing.0.clone()),
+            Framing::MySql,
+        );
+
         Ok(Self {
             busy: Busy::NotBusy,
             capabilities,
@@ -71,10 +109,27 @@ impl MySqlStream {
             sequence_id: 0,
             collation,
             charset,
-            stream: BufStream::new(MaybeTlsStream::Raw(socket)),
+            stream,
+            max_message_size: options.max_message_size,
+            status: Status::SERVER_STATUS_AUTOCOMMIT,
+            schema: options.database.clone(),
+            parameters: HashMap::new(),
         })
     }
 
+    // whether a password may be sent as it is; the server trusts a Unix socket as it trusts
+    // TLS, and offers no public key over one either
+    pub(crate) fn is_secure(&self) -> bool {
+        match &*self.stream {
+            MaybeTlsStream::Tls(_) => true,
+
+            #[cfg(unix)]
+            MaybeTlsStream::Raw(Socket::Unix(_)) => true,
+
+            _ => false,
+        }
+    }
+
     pub(crate) async fn wait_until_ready(&mut self) -> Result<(), Error> {
         if !self.stream.wbuf.is_empty() {
             self.stream.flush().await?;
@@ -84,8 +139,8 @@ impl MySqlStream {
             while self.busy == Busy::Row {
                 let packet = self.recv_packet().await?;
 
-                if packet[0] == 0xfe && packet.len() < 9 {
-                    let eof = packet.eof(self.capabilities)?;
+                if self.is_eof(&packet) {
+                    let eof = self.decode_eof(packet)?;
 
                     self.busy = if eof.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
                         Busy::Result
@@ -99,7 +154,7 @@ impl MySqlStream {
                 let packet = self.recv_packet().await?;
 
                 if packet[0] == 0x00 || packet[0] == 0xff {
-                    let ok = packet.ok()?;
+                    let ok = self.decode_ok(packet)?;
 
                     if !ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
                         self.busy = Busy::NotBusy;
@@ -123,6 +178,13 @@ impl MySqlStream {
         self.flush().await
     }
 
+    // COM_STMT_CLOSE has no response, so instead of being flushed on its own it goes out
+    // together with the next command
+    pub(crate) fn close_statement_later(&mut self, statement: u32) {
+        self.sequence_id = 0;
+        self.write_packet(StmtClose { statement });
+    }
+
     pub(crate) fn write_packet<'en, T>(&mut self, payload: T)
     where
         T: Encode<'en, Capabilities>,
@@ -137,17 +199,39 @@ impl MySqlStream {
         // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_packets.html
         // https://mariadb.com/kb/en/library/0-packet/#standard-packet
 
-        let mut header: Bytes = self.stream.read(4).await?;
+        let mut payload = self.recv_packet_part().await?;
 
-        let packet_size = header.get_uint_le(3) as usize;
-        let sequence_id = header.get_u8();
+        // TODO: packet compression
 
-        self.sequence_id = sequence_id.wrapping_add(1);
+        // a payload of 16 MiB or more is split into packets of the largest size, followed by one
+        // that is shorter (and may be empty)
+        if payload.len() == MAX_PART_SIZE {
+            let mut joined = BytesMut::from(&payload[..]);
 
-        let payload: Bytes = self.stream.read(packet_size).await?;
+            loop {
+                let part = self.recv_packet_part().await?;
 
-        // TODO: packet compression
-        // TODO: packet joining
+                if joined.len() + part.len() > self.max_message_size {
+                    return Err(err_protocol!(
+                        "packet of more than {} bytes is larger than the maximum; \
+                         see `MySqlConnectOptions::max_message_size`",
+                        self.max_message_size
+                    ));
+                }
+
+                joined.extend_from_slice(&part);
+
+                if part.len() < MAX_PART_SIZE {
+                    break;
+                }
+            }
+
+            payload = joined.freeze();
+        }
+
+        if payload.is_empty() {
+            return Err(err_protocol!("received an empty packet"));
+        }
 
         if payload[0] == 0xff {
             self.busy = Busy::NotBusy;
@@ -162,6 +246,25 @@ impl MySqlStream {
         Ok(Packet(payload))
     }
 
+    async fn recv_packet_part(&mut self) -> Result<Bytes, Error> {
+        // the packet is only consumed once all of it has been read, so that a cancelled read
+        // leaves the stream at the start of the packet
+        let mut header = self.stream.peek(4).await?;
+
+        let packet_size = header.get_uint_le(3) as usize;
+        let sequence_id = header.get_u8();
+
+        let mut payload: Bytes = self.stream.read(4 + packet_size).await?;
+        payload.advance(4);
+
+        self.sequence_id = sequence_id.wrapping_add(1);
+
+        self.stream
+            .trace_received(payload.first().copied(), &payload);
+
+        Ok(payload)
+    }
+
     pub(crate) async fn recv<'de, T>(&mut self) -> Result<T, Error>
     where
         T: Decode<'de, Capabilities>,
@@ -170,7 +273,58 @@ impl MySqlStream {
     }
 
     pub(crate) async fn recv_ok(&mut self) -> Result<OkPacket, Error> {
-        self.recv_packet().await?.ok()
+        let packet = self.recv_packet().await?;
+
+        self.decode_ok(packet)
+    }
+
+    // decodes an OK packet and takes note of the changes to the session it reports
+    pub(crate) fn decode_ok(&mut self, packet: Packet<Bytes>) -> Result<OkPacket, Error> {
+        let ok = packet.ok(self.capabilities)?;
+
+        self.status = ok.status;
+
+        for change in &ok.session_state {
+            match change {
+                SessionStateChange::SystemVariable { name, value } => {
+                    self.parameters
+                        .insert(name.to_ascii_lowercase(), value.clone());
+                }
+
+                SessionStateChange::Schema(schema) => {
+                    self.schema = Some(schema.clone()).filter(|schema| !schema.is_empty());
+                }
+            }
+        }
+
+        Ok(ok)
+    }
+
+    // an EOF packet ends a result set; with `DEPRECATE_EOF` it is an OK packet with the header
+    // of an EOF packet, which can carry session state and so be longer than the EOF packet
+    pub(crate) fn is_eof(&self, packet: &[u8]) -> bool {
+        // a text row starting with 0xfe begins with a value of 16 MiB or more
+        packet[0] == 0xfe
+            && (packet.len() < 9
+                || (self.capabilities.contains(Capabilities::DEPRECATE_EOF)
+                    && packet.len() < MAX_PART_SIZE))
+    }
+
+    pub(crate) fn decode_eof(&mut self, packet: Packet<Bytes>) -> Result<EofPacket, Error> {
+        if self.capabilities.contains(Capabilities::DEPRECATE_EOF) {
+            let ok = self.decode_ok(packet)?;
+
+            Ok(EofPacket {
+                warnings: ok.warnings,
+                status: ok.status,
+            })
+        } else {
+            let eof: EofPacket = packet.decode_with(self.capabilities)?;
+
+            self.status = eof.status;
+
+            Ok(eof)
+        }
     }
 
     pub(crate) async fn maybe_recv_eof(&mut self) -> Result<Option<EofPacket>, Error> {
@@ -182,7 +336,7 @@ impl MySqlStream {
     }
 
     async fn skip_result_metadata(&mut self, mut packet: Packet<Bytes>) -> Result<(), Error> {
-        let num_columns: u64 = packet.get_uint_lenenc(); // column count
+        let num_columns: u64 = packet.get_uint_lenenc()?; // column count
 
         for _ in 0..num_columns {
             let _ = self.recv_packet().await?;
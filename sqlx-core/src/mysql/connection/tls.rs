@@ -13,14 +13,22 @@ pub(super) async fn maybe_upgrade(
         MySqlSslMode::Disabled => {}
 
         MySqlSslMode::Preferred => {
-            // try upgrade, but its okay if we fail
-            upgrade(stream, options).await?;
+            // try upgrade, but its okay if we fail, unless the policy forbids it
+            if !upgrade(stream, options).await?
+                && !options.effective_protocol_policy().allow_plaintext_fallback
+            {
+                return Err(Error::PolicyViolation(
+                    "server does not offer TLS under `ssl-mode=preferred`, \
+                     and plaintext fallback is not allowed"
+                        .into(),
+                ));
+            }
         }
 
         MySqlSslMode::Required | MySqlSslMode::VerifyIdentity | MySqlSslMode::VerifyCa => {
             if !upgrade(stream, options).await? {
                 // upgrade failed, die
-                return Err(Error::Tls("server does not support TLS".into()));
+                return Err(Error::tls("server does not support TLS"));
             }
         }
     }
@@ -47,12 +55,32 @@ async fn upgrade(stream: &mut MySqlStream, options: &MySqlConnectOptions) -> Res
     );
     let accept_invalid_host_names = !matches!(options.ssl_mode, MySqlSslMode::VerifyIdentity);
 
+    // the name to verify the certificate against, for a server whose certificate is issued to
+    // another name than the one it is reached by, e.g. that of a cloud instance
+    let server_name = options.ssl_server_name.as_deref().unwrap_or(&options.host);
+
+    let client_identity = match (&options.ssl_client_cert, &options.ssl_client_key) {
+        (Some(cert), Some(key)) => Some((cert, key)),
+        (None, None) => None,
+
+        _ => {
+            return Err(Error::Configuration(
+                "`ssl-cert` and `ssl-key` must be set together".into(),
+            ));
+        }
+    };
+
     stream
         .upgrade(
-            &options.host,
+            server_name,
             accept_invalid_certs,
             accept_invalid_host_names,
             options.ssl_ca.as_ref(),
+            options.tls_session_cache.as_ref(),
+            options.effective_protocol_policy().minimum_tls_version,
+            &[],
+            client_identity,
+            options.ssl_cipher.as_deref(),
         )
         .await?;
 
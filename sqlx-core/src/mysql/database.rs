@@ -1,4 +1,6 @@
-use crate::database::{Database, HasArguments, HasStatement, HasStatementCache, HasValueRef};
+use crate::database::{
+    Capabilities, Database, HasArguments, HasStatement, HasStatementCache, HasValueRef,
+};
 use crate::mysql::value::{MySqlValue, MySqlValueRef};
 use crate::mysql::{
     MySqlArguments, MySqlColumn, MySqlConnection, MySqlQueryResult, MySqlRow, MySqlStatement,
@@ -23,6 +25,17 @@ impl Database for MySql {
     type TypeInfo = MySqlTypeInfo;
 
     type Value = MySqlValue;
+
+    const CAPABILITIES: Capabilities = Capabilities::from_bits_truncate(
+        Capabilities::SAVEPOINTS.bits()
+            | Capabilities::MULTIPLE_RESULT_SETS.bits()
+            | Capabilities::BINARY_PROTOCOL.bits()
+            | Capabilities::QUESTION_MARK_PLACEHOLDERS.bits(),
+    );
+
+    const MAX_BIND_PARAMETERS: usize = MySqlArguments::MAX_ARGUMENTS;
+
+    const MAX_IDENTIFIER_LENGTH: Option<usize> = Some(64);
 }
 
 impl<'r> HasValueRef<'r> for MySql {
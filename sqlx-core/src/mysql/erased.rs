@@ -0,0 +1,74 @@
+use crate::erased::{unsupported_column, ErasedDatabase, ErasedQueryResult, ErasedValue};
+use crate::error::Error;
+use crate::mysql::protocol::text::ColumnType;
+use crate::mysql::{MySql, MySqlArguments, MySqlQueryResult, MySqlRow};
+use crate::row::Row;
+use crate::value::ValueRef;
+
+impl ErasedDatabase for MySql {
+    fn add_erased(arguments: &mut MySqlArguments, value: ErasedValue) -> Result<(), Error> {
+        match value {
+            ErasedValue::Null => arguments.add(None::<String>),
+            ErasedValue::Bool(v) => arguments.add(v),
+            ErasedValue::Int(v) => arguments.add(v),
+            ErasedValue::Float(v) => arguments.add(v),
+            ErasedValue::Text(v) => arguments.add(v),
+            ErasedValue::Bytes(v) => arguments.add(v),
+        }
+
+        Ok(())
+    }
+
+    fn get_erased(row: &MySqlRow, index: usize) -> Result<ErasedValue, Error> {
+        let value = row.try_get_raw(index)?;
+
+        if value.is_null() {
+            return Ok(ErasedValue::Null);
+        }
+
+        let ty = &value.type_info;
+
+        Ok(match ty.r#type {
+            // BOOLEAN is a TINYINT(1), so it comes back as an integer; unsigned integers that do
+            // not fit into an `i64` are a decode error
+            ColumnType::Tiny
+            | ColumnType::Short
+            | ColumnType::Long
+            | ColumnType::Int24
+            | ColumnType::LongLong
+            | ColumnType::Year
+            | ColumnType::Bit => ErasedValue::Int(row.try_get_unchecked(index)?),
+
+            ColumnType::Float => ErasedValue::Float(row.try_get_unchecked::<f32, _>(index)?.into()),
+            ColumnType::Double => ErasedValue::Float(row.try_get_unchecked(index)?),
+
+            // JSON has the `binary` character set but is always UTF-8
+            ColumnType::Json => ErasedValue::Text(row.try_get_unchecked(index)?),
+
+            ColumnType::VarChar
+            | ColumnType::VarString
+            | ColumnType::String
+            | ColumnType::TinyBlob
+            | ColumnType::Blob
+            | ColumnType::MediumBlob
+            | ColumnType::LongBlob
+            | ColumnType::Enum
+            | ColumnType::Set => {
+                if ty.is_binary_string() {
+                    ErasedValue::Bytes(row.try_get_unchecked(index)?)
+                } else {
+                    ErasedValue::Text(row.try_get_unchecked(index)?)
+                }
+            }
+
+            _ => return Err(unsupported_column(row, index)),
+        })
+    }
+
+    fn erase_query_result(result: MySqlQueryResult) -> ErasedQueryResult {
+        ErasedQueryResult {
+            rows_affected: result.rows_affected(),
+            last_insert_id: Some(result.last_insert_id() as i64),
+        }
+    }
+}
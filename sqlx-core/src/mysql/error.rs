@@ -1,7 +1,7 @@
 use std::error::Error;
 use std::fmt::{self, Debug, Display, Formatter};
 
-use crate::error::DatabaseError;
+use crate::error::{DatabaseError, ErrorClass};
 use crate::mysql::protocol::response::ErrPacket;
 use smallvec::alloc::borrow::Cow;
 
@@ -76,4 +76,45 @@ impl DatabaseError for MySqlDatabaseError {
     fn into_error(self: Box<Self>) -> Box<dyn Error + Send + Sync + 'static> {
         self
     }
+
+    fn is_connection_error(&self) -> bool {
+        // ER_SERVER_SHUTDOWN, ER_CONNECTION_KILLED
+        matches!(self.number(), 1053 | 1927)
+            || self
+                .code()
+                .map_or(false, |code| ErrorClass::from_sqlstate(code).connection)
+    }
+
+    fn is_retryable(&self) -> bool {
+        // ER_LOCK_WAIT_TIMEOUT, ER_LOCK_DEADLOCK
+        matches!(self.number(), 1205 | 1213)
+            || self.is_connection_error()
+            || self
+                .code()
+                .map_or(false, |code| ErrorClass::from_sqlstate(code).retryable)
+    }
+}
+
+#[test]
+fn it_classifies_errors_by_number() {
+    let error = |number, sql_state: &str| {
+        MySqlDatabaseError(ErrPacket {
+            error_code: number,
+            sql_state: Some(sql_state.to_owned()),
+            error_message: String::new(),
+        })
+    };
+
+    // ER_LOCK_DEADLOCK, ER_LOCK_WAIT_TIMEOUT
+    assert!(error(1213, "40001").is_retryable());
+    assert!(error(1205, "HY000").is_retryable());
+    assert!(!error(1205, "HY000").is_connection_error());
+
+    // ER_CONNECTION_KILLED
+    assert!(error(1927, "70100").is_connection_error());
+    assert!(error(1927, "70100").is_retryable());
+
+    // ER_DUP_ENTRY
+    assert!(!error(1062, "23000").is_retryable());
+    assert!(!error(1062, "23000").is_connection_error());
 }
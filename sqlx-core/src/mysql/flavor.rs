@@ -0,0 +1,97 @@
+use crate::mysql::protocol::Capabilities;
+
+/// The kind of server a [`MySqlConnection`](super::MySqlConnection) is connected to.
+///
+/// It is returned by the [`server_flavor`](super::MySqlConnection::server_flavor) method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MySqlFlavor {
+    /// Oracle MySQL, or a server that could not be told apart from it.
+    MySql,
+
+    /// MariaDB, which extends the protocol with capabilities of its own, e.g. executing a
+    /// prepared statement for many rows at once.
+    MariaDb,
+
+    /// Percona Server for MySQL, which speaks the protocol of the MySQL release it is based on.
+    Percona,
+}
+
+// the flavor and the version of the server, from the version it reported in the handshake;
+// a server that leaves out the `MYSQL` capability is MariaDB too, as only MariaDB does so, to
+// announce its extended capabilities
+//
+// Percona looks like MySQL here; it only tells itself apart in `@@version_comment`
+pub(crate) fn parse_server_version(
+    version: &str,
+    capabilities: Capabilities,
+) -> (MySqlFlavor, (u16, u16, u16)) {
+    let flavor = if version.contains("MariaDB") || !capabilities.contains(Capabilities::MYSQL) {
+        MySqlFlavor::MariaDb
+    } else {
+        MySqlFlavor::MySql
+    };
+
+    // before 11.0, MariaDB reports e.g. `5.5.5-10.5.8-MariaDB`, as some clients expect
+    // MySQL 5 or newer to start with a 5
+    let version = match flavor {
+        MySqlFlavor::MariaDb => version.strip_prefix("5.5.5-").unwrap_or(version),
+        _ => version,
+    };
+
+    // expecting MAJOR.MINOR.PATCH, followed by anything, e.g. `-log`
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    let mut next = || parts.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+
+    let major = next();
+    let minor = next();
+    let patch = next();
+
+    (flavor, (major, minor, patch))
+}
+
+// whether `@@version_comment` is that of a Percona server, e.g.
+// `Percona Server (GPL), Release 14, Revision 1f1b0e6`
+pub(crate) fn is_percona(version_comment: &str) -> bool {
+    version_comment.contains("Percona")
+}
+
+#[test]
+fn it_parses_server_versions() {
+    let mysql = Capabilities::MYSQL | Capabilities::PROTOCOL_41;
+
+    assert_eq!(
+        parse_server_version("8.0.23", mysql),
+        (MySqlFlavor::MySql, (8, 0, 23))
+    );
+
+    assert_eq!(
+        parse_server_version("5.7.33-36-log", mysql),
+        (MySqlFlavor::MySql, (5, 7, 33))
+    );
+
+    assert_eq!(
+        parse_server_version("5.5.5-10.4.7-MariaDB-1:10.4.7+maria~bionic", mysql),
+        (MySqlFlavor::MariaDb, (10, 4, 7))
+    );
+
+    assert_eq!(
+        parse_server_version("11.2.2-MariaDB", Capabilities::PROTOCOL_41),
+        (MySqlFlavor::MariaDb, (11, 2, 2))
+    );
+
+    // a MariaDB server with its name stripped from the version still announces itself
+    assert_eq!(
+        parse_server_version("5.5.5-10.6.12", Capabilities::PROTOCOL_41),
+        (MySqlFlavor::MariaDb, (10, 6, 12))
+    );
+
+    assert_eq!(
+        parse_server_version("", mysql),
+        (MySqlFlavor::MySql, (0, 0, 0))
+    );
+
+    assert!(is_percona(
+        "Percona Server (GPL), Release 14, Revision 1f1b0e6"
+    ));
+    assert!(!is_percona("MySQL Community Server - GPL"));
+}
@@ -0,0 +1,223 @@
+//! Writes bind values into the SQL of a query as literals, for connections that run every query
+//! as text; see
+//! [`MySqlConnectOptions::prefer_simple_protocol`](crate::mysql::MySqlConnectOptions::prefer_simple_protocol).
+
+use std::fmt::Write;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::common::{placeholders, SqlDialect};
+use crate::error::{BoxDynError, Error};
+use crate::mysql::arguments::MySqlBindValue;
+use crate::mysql::protocol::text::ColumnType;
+use crate::mysql::MySqlArguments;
+
+// the character set of binary strings
+const BINARY_CHAR_SET: u16 = 63;
+
+/// Replaces the placeholders of `sql` (`?`) with the values bound in `arguments`.
+///
+/// Strings are written as hex literals, e.g. `_utf8mb4 X'6869'`, which hold no quotes or
+/// backslashes to escape whatever the SQL mode. Placeholders inside strings, quoted identifiers
+/// and comments are left alone; `backslash_escapes` is whether a backslash escapes the next
+/// character in a string, i.e. the `NO_BACKSLASH_ESCAPES` SQL mode is not set.
+pub(crate) fn interpolate(
+    sql: &str,
+    arguments: &MySqlArguments,
+    backslash_escapes: bool,
+) -> Result<String, Error> {
+    let literals = arguments
+        .values()
+        .enumerate()
+        .map(|(index, value)| {
+            literal(&value).map_err(|source| Error::ArgumentEncode { index, source })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if literals.len() != arguments.types.len() {
+        return Err(Error::encode(format!(
+            "value {} cannot be written into SQL: its type encodes in an unknown layout",
+            literals.len()
+        )));
+    }
+
+    let mut interpolated =
+        String::with_capacity(sql.len() + literals.iter().map(String::len).sum::<usize>());
+
+    let mut literals = literals.iter();
+    let mut found = 0;
+    let mut written = 0;
+
+    for range in placeholders(sql, SqlDialect::MySql, backslash_escapes) {
+        found += 1;
+
+        if let Some(literal) = literals.next() {
+            interpolated.push_str(&sql[written..range.start]);
+            interpolated.push_str(literal);
+
+            written = range.end;
+        }
+    }
+
+    if found != arguments.types.len() {
+        return Err(Error::encode(format!(
+            "query has {} placeholders, but {} arguments were bound",
+            found,
+            arguments.types.len()
+        )));
+    }
+
+    interpolated.push_str(&sql[written..]);
+
+    Ok(interpolated)
+}
+
+// the value as a literal the server reads back as the same value
+fn literal(bind: &MySqlBindValue<'_>) -> Result<String, BoxDynError> {
+    let value = match bind.value {
+        Some(value) => value,
+        None => return Ok("NULL".to_owned()),
+    };
+
+    match bind.ty.r#type {
+        // integers, and dates and times as quoted strings of digits, are rendered as logged
+        ColumnType::Tiny
+        | ColumnType::Short
+        | ColumnType::Year
+        | ColumnType::Long
+        | ColumnType::Int24
+        | ColumnType::LongLong
+        | ColumnType::Date
+        | ColumnType::Datetime
+        | ColumnType::Timestamp
+        | ColumnType::Time => Ok(bind.to_string()),
+
+        // in exponent notation, to be read as a `DOUBLE` rather than a `DECIMAL`
+        ColumnType::Float => float(f64::from(LittleEndian::read_f32(value))),
+        ColumnType::Double => float(LittleEndian::read_f64(value)),
+
+        ty => {
+            // skip the length prefix
+            let prefix = match value[0] {
+                0xfc => 3,
+                0xfd => 4,
+                0xfe => 9,
+                _ => 1,
+            };
+
+            let value = &value[prefix..];
+
+            if matches!(ty, ColumnType::Decimal | ColumnType::NewDecimal) {
+                return decimal(value);
+            }
+
+            let mut literal = String::with_capacity(value.len() * 2 + 12);
+
+            if bind.ty.char_set != BINARY_CHAR_SET {
+                literal.push_str("_utf8mb4 ");
+            }
+
+            literal.push_str("X'");
+
+            for byte in value {
+                let _ = write!(literal, "{:02x}", byte);
+            }
+
+            literal.push('\'');
+
+            Ok(literal)
+        }
+    }
+}
+
+fn float(value: f64) -> Result<String, BoxDynError> {
+    if !value.is_finite() {
+        return Err(format!("{} cannot be stored in MySQL", value).into());
+    }
+
+    let mut literal = String::new();
+    let _ = write!(literal, "{:e}", value);
+
+    Ok(literal)
+}
+
+// written bare, so only a number may pass
+fn decimal(value: &[u8]) -> Result<String, BoxDynError> {
+    let is_number = !value.is_empty()
+        && value
+            .iter()
+            .all(|b| matches!(b, b'0'..=b'9' | b'+' | b'-' | b'.' | b'e' | b'E'));
+
+    match std::str::from_utf8(value) {
+        Ok(text) if is_number => Ok(text.to_owned()),
+        _ => Err(format!(
+            "{:?} is not a decimal number",
+            String::from_utf8_lossy(value)
+        )
+        .into()),
+    }
+}
+
+#[test]
+fn it_interpolates_bind_values() {
+    let mut arguments = MySqlArguments::default();
+    arguments.add(-5_i8);
+    arguments.add(u64::MAX);
+    arguments.add("it's ?");
+    arguments.add(None::<i32>);
+    arguments.add(&b"\x00\xff"[..]);
+    arguments.add(1.5_f64);
+
+    assert_eq!(
+        interpolate(
+            "SELECT ?, ?, '?', \"?\", `?` -- ?\n, ?, ?, /* ? */ ?, ?",
+            &arguments,
+            true
+        )
+        .unwrap(),
+        "SELECT -5, 18446744073709551615, '?', \"?\", `?` -- ?\n, _utf8mb4 X'69742773203f', \
+         NULL, /* ? */ X'00ff', 1.5e0"
+    );
+
+    // a backslash escapes the quote unless `NO_BACKSLASH_ESCAPES` is set
+    let mut arguments = MySqlArguments::default();
+    arguments.add(1_i32);
+
+    assert_eq!(
+        interpolate("SELECT 'a\\' ?', ?", &arguments, true).unwrap(),
+        "SELECT 'a\\' ?', 1"
+    );
+
+    assert_eq!(
+        interpolate("SELECT 'a\\', ?", &arguments, false).unwrap(),
+        "SELECT 'a\\', 1"
+    );
+}
+
+#[test]
+fn it_refuses_what_it_cannot_interpolate() {
+    let mut arguments = MySqlArguments::default();
+    arguments.add(1_i32);
+
+    assert!(matches!(
+        interpolate("SELECT ?, ?", &arguments, true),
+        Err(Error::Encode(_))
+    ));
+
+    assert!(matches!(
+        interpolate("SELECT 1", &arguments, true),
+        Err(Error::Encode(_))
+    ));
+
+    let mut arguments = MySqlArguments::default();
+    arguments.add(1_i32);
+    arguments.add(f64::NAN);
+
+    assert!(matches!(
+        interpolate("SELECT ?, ?", &arguments, true),
+        Err(Error::ArgumentEncode { index: 1, .. })
+    ));
+
+    assert!(decimal(b"1.25e-3").is_ok());
+    assert!(decimal(b"1; DROP TABLE tweet").is_err());
+}
@@ -8,33 +8,52 @@ pub trait MySqlBufExt: Buf {
     // NOTE: 0xfb or NULL is only returned for binary value encoding to indicate NULL.
     // NOTE: 0xff is only returned during a result set to indicate ERR.
     // <https://dev.mysql.com/doc/internals/en/integer.html#packet-Protocol::LengthEncodedInteger>
-    fn get_uint_lenenc(&mut self) -> u64;
+    fn get_uint_lenenc(&mut self) -> Result<u64, Error>;
 
     // Read a length-encoded string.
     fn get_str_lenenc(&mut self) -> Result<String, Error>;
 
     // Read a length-encoded byte sequence.
-    fn get_bytes_lenenc(&mut self) -> Bytes;
+    fn get_bytes_lenenc(&mut self) -> Result<Bytes, Error>;
 }
 
 impl MySqlBufExt for Bytes {
-    fn get_uint_lenenc(&mut self) -> u64 {
-        match self.get_u8() {
+    fn get_uint_lenenc(&mut self) -> Result<u64, Error> {
+        self.ensure_remaining(1)?;
+
+        let len = match self[0] {
+            0xfc => 2,
+            0xfd => 3,
+            0xfe => 8,
+
+            _ => 0,
+        };
+
+        self.ensure_remaining(1 + len)?;
+
+        Ok(match self.get_u8() {
             0xfc => u64::from(self.get_u16_le()),
             0xfd => self.get_uint_le(3),
             0xfe => self.get_u64_le(),
 
             v => u64::from(v),
-        }
+        })
     }
 
     fn get_str_lenenc(&mut self) -> Result<String, Error> {
-        let size = self.get_uint_lenenc();
-        self.get_str(size as usize)
+        let size = self.get_uint_lenenc()?;
+        self.get_str(lenenc_size(size)?)
     }
 
-    fn get_bytes_lenenc(&mut self) -> Bytes {
-        let size = self.get_uint_lenenc();
-        self.split_to(size as usize)
+    fn get_bytes_lenenc(&mut self) -> Result<Bytes, Error> {
+        let size = self.get_uint_lenenc()?;
+        self.get_bytes(lenenc_size(size)?)
     }
 }
+
+// a length read from the packet, which cannot be longer than the packet on any platform
+pub(crate) fn lenenc_size(size: u64) -> Result<usize, Error> {
+    use std::convert::TryFrom;
+
+    usize::try_from(size).map_err(|_| err_protocol!("length {} is out of range", size))
+}
@@ -1,5 +1,6 @@
 mod buf;
 mod buf_mut;
 
+pub(crate) use buf::lenenc_size;
 pub use buf::MySqlBufExt;
 pub use buf_mut::MySqlBufMutExt;
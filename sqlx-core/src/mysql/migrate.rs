@@ -1,10 +1,11 @@
 use crate::connection::ConnectOptions;
 use crate::error::Error;
-use crate::executor::Executor;
 use crate::migrate::MigrateError;
 use crate::migrate::Migration;
 use crate::migrate::{Migrate, MigrateDatabase};
-use crate::mysql::{MySql, MySqlConnectOptions, MySqlConnection};
+use crate::mysql::{
+    quote_identifier, MySql, MySqlConnectOptions, MySqlConnection, MySqlDatabaseError,
+};
 use crate::query::query;
 use crate::query_as::query_as;
 use crate::query_scalar::query_scalar;
@@ -31,6 +32,41 @@ fn parse_for_maintenance(uri: &str) -> Result<(MySqlConnectOptions, String), Err
     Ok((options, database))
 }
 
+// the errors of the statements that create and drop databases, and kill sessions
+const ER_DB_CREATE_EXISTS: u16 = 1007;
+const ER_DBACCESS_DENIED_ERROR: u16 = 1044;
+const ER_NO_SUCH_THREAD: u16 = 1094;
+const ER_KILL_DENIED_ERROR: u16 = 1095;
+const ER_SPECIFIC_ACCESS_DENIED_ERROR: u16 = 1227;
+
+// the number of a MySQL error, if it is one
+fn error_number(error: &Error) -> Option<u16> {
+    match error {
+        Error::Database(error) => error
+            .try_downcast_ref::<MySqlDatabaseError>()
+            .map(MySqlDatabaseError::number),
+
+        _ => None,
+    }
+}
+
+// tells the errors of `CREATE DATABASE` and `DROP DATABASE` that are about the database apart
+// from the rest
+fn admin_error(database: &str, error: Error) -> Error {
+    match (error_number(&error), error) {
+        (Some(ER_DB_CREATE_EXISTS), _) => Error::DatabaseExists(database.to_owned()),
+
+        (
+            Some(ER_DBACCESS_DENIED_ERROR)
+            | Some(ER_KILL_DENIED_ERROR)
+            | Some(ER_SPECIFIC_ACCESS_DENIED_ERROR),
+            Error::Database(error),
+        ) => Error::DatabasePermissionDenied(error),
+
+        (_, error) => error,
+    }
+}
+
 impl MigrateDatabase for MySql {
     fn create_database(uri: &str) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
@@ -38,8 +74,12 @@ impl MigrateDatabase for MySql {
             let mut conn = options.connect().await?;
 
             let _ = conn
-                .execute(&*format!("CREATE DATABASE `{}`", database))
-                .await?;
+                .execute(&*format!(
+                    "CREATE DATABASE {}",
+                    quote_identifier(&database)?
+                ))
+                .await
+                .map_err(|error| admin_error(&database, error))?;
 
             Ok(())
         })
@@ -67,8 +107,50 @@ impl MigrateDatabase for MySql {
             let mut conn = options.connect().await?;
 
             let _ = conn
-                .execute(&*format!("DROP DATABASE IF EXISTS `{}`", database,))
-                .await?;
+                .execute(&*format!(
+                    "DROP DATABASE IF EXISTS {}",
+                    quote_identifier(&database)?
+                ))
+                .await
+                .map_err(|error| admin_error(&database, error))?;
+
+            Ok(())
+        })
+    }
+
+    fn force_drop_database(uri: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let (options, database) = parse_for_maintenance(uri)?;
+            let mut conn = options.connect().await?;
+
+            // the sessions that would hold `DROP DATABASE` up, with their transactions open on
+            // its tables; only those of the current user are listed without `PROCESS`
+            let sessions: Vec<u64> = query_scalar(
+                "SELECT ID FROM INFORMATION_SCHEMA.PROCESSLIST \
+                 WHERE DB = ? AND ID <> CONNECTION_ID()",
+            )
+            .bind(&database)
+            .fetch_all(&mut conn)
+            .await?;
+
+            for id in sessions {
+                match conn.execute(&*format!("KILL {}", id)).await {
+                    // the session has ended in the meantime
+                    Err(error) if error_number(&error) == Some(ER_NO_SUCH_THREAD) => {}
+
+                    result => {
+                        let _ = result.map_err(|error| admin_error(&database, error))?;
+                    }
+                }
+            }
+
+            let _ = conn
+                .execute(&*format!(
+                    "DROP DATABASE IF EXISTS {}",
+                    quote_identifier(&database)?
+                ))
+                .await
+                .map_err(|error| admin_error(&database, error))?;
 
             Ok(())
         })
@@ -5,11 +5,15 @@ mod collation;
 mod column;
 mod connection;
 mod database;
+mod erased;
 mod error;
+mod flavor;
+mod interpolate;
 mod io;
 mod options;
-mod protocol;
+pub(crate) mod protocol;
 mod query_result;
+mod quote;
 mod row;
 mod statement;
 mod transaction;
@@ -25,8 +29,10 @@ pub use column::MySqlColumn;
 pub use connection::MySqlConnection;
 pub use database::MySql;
 pub use error::MySqlDatabaseError;
+pub use flavor::MySqlFlavor;
 pub use options::{MySqlConnectOptions, MySqlSslMode};
 pub use query_result::MySqlQueryResult;
+pub use quote::{quote_identifier, quote_qualified};
 pub use row::MySqlRow;
 pub use statement::MySqlStatement;
 pub use transaction::MySqlTransactionManager;
@@ -50,3 +56,28 @@ impl_into_maybe_pool!(MySql, MySqlConnection);
 
 // required because some databases have a different handling of NULL
 impl_encode_for_option!(MySql);
+
+#[cfg(test)]
+assert_driver_futures_are_send!(MySql, MySqlConnection);
+
+// the futures of what only MySQL has are `Send` as well
+#[test]
+#[allow(dead_code)]
+fn assert_mysql_futures_are_send() {
+    use crate::transaction::Transaction;
+
+    fn assert_send<T: Send>(_: T) {}
+
+    fn assert_connection(mut conn: MySqlConnection) {
+        assert_send(conn.begin_two_phase(""));
+        assert_send(conn.commit_prepared(""));
+        assert_send(conn.rollback_prepared(""));
+        assert_send(conn.list_prepared());
+    }
+
+    fn assert_transaction(mut tx: Transaction<'static, MySql>) {
+        assert_send(tx.set_local("", 1_i32));
+        assert_send(tx.set_locals(vec![("", "")]));
+        assert_send(tx.prepare_two_phase(""));
+    }
+}
@@ -0,0 +1,171 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::common::config::{certificate_to_string, deserialize_from_str, deserialize_url_or_map};
+use crate::mysql::{MySqlConnectOptions, MySqlSslMode};
+use crate::net::CertificateInput;
+
+// The map form of the options. Settings that are left out keep the defaults of
+// `MySqlConnectOptions::new()`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MySqlConfig {
+    host: Option<String>,
+    port: Option<u16>,
+    socket: Option<PathBuf>,
+    #[serde(alias = "user")]
+    username: Option<String>,
+    password: Option<String>,
+    database: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_from_str")]
+    ssl_mode: Option<MySqlSslMode>,
+    ssl_ca: Option<String>,
+    ssl_cert: Option<String>,
+    ssl_key: Option<String>,
+    ssl_cipher: Option<String>,
+    ssl_server_name: Option<String>,
+    statement_cache_capacity: Option<usize>,
+    charset: Option<String>,
+    collation: Option<String>,
+    application_name: Option<String>,
+}
+
+impl MySqlConfig {
+    fn into_options(self) -> MySqlConnectOptions {
+        let mut options = MySqlConnectOptions::new();
+
+        if let Some(host) = self.host {
+            options.host = host;
+        }
+
+        if let Some(port) = self.port {
+            options.port = port;
+        }
+
+        if let Some(username) = self.username {
+            options.username = username;
+        }
+
+        if let Some(ssl_mode) = self.ssl_mode {
+            options.ssl_mode = ssl_mode;
+        }
+
+        if let Some(capacity) = self.statement_cache_capacity {
+            options.statement_cache_capacity = capacity;
+        }
+
+        if let Some(charset) = self.charset {
+            options.charset = charset;
+        }
+
+        options.socket = self.socket.or(options.socket);
+        options.password = self.password.or(options.password);
+        options.database = self.database.or(options.database);
+        options.collation = self.collation.or(options.collation);
+        options.application_name = self.application_name.or(options.application_name);
+        options.ssl_ca = self.ssl_ca.map(CertificateInput::from).or(options.ssl_ca);
+        options.ssl_client_cert = self
+            .ssl_cert
+            .map(CertificateInput::from)
+            .or(options.ssl_client_cert);
+        options.ssl_client_key = self
+            .ssl_key
+            .map(CertificateInput::from)
+            .or(options.ssl_client_key);
+        options.ssl_cipher = self.ssl_cipher.or(options.ssl_cipher);
+        options.ssl_server_name = self.ssl_server_name.or(options.ssl_server_name);
+
+        options
+    }
+}
+
+#[derive(Serialize)]
+struct MySqlConfigRef<'a> {
+    host: &'a str,
+    port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    socket: Option<&'a Path>,
+    username: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    database: Option<&'a str>,
+    ssl_mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ssl_ca: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ssl_cert: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ssl_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ssl_cipher: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ssl_server_name: Option<&'a str>,
+    statement_cache_capacity: usize,
+    charset: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collation: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    application_name: Option<&'a str>,
+}
+
+impl MySqlConnectOptions {
+    /// Serializes these options including the password, which [`Serialize`] leaves out.
+    ///
+    /// Meant for `#[serde(serialize_with = "MySqlConnectOptions::serialize_with_password")]`
+    /// where the output needs to be deserialized into working options again.
+    pub fn serialize_with_password<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.config(true).serialize(serializer)
+    }
+
+    fn config(&self, with_password: bool) -> MySqlConfigRef<'_> {
+        MySqlConfigRef {
+            host: &self.host,
+            port: self.port,
+            socket: self.socket.as_deref(),
+            username: &self.username,
+            password: self.password.as_deref().filter(|_| with_password),
+            database: self.database.as_deref(),
+            ssl_mode: match self.ssl_mode {
+                MySqlSslMode::Disabled => "disabled",
+                MySqlSslMode::Preferred => "preferred",
+                MySqlSslMode::Required => "required",
+                MySqlSslMode::VerifyCa => "verify_ca",
+                MySqlSslMode::VerifyIdentity => "verify_identity",
+            },
+            ssl_ca: self.ssl_ca.as_ref().map(certificate_to_string),
+            ssl_cert: self.ssl_client_cert.as_ref().map(certificate_to_string),
+            // an inline key is as secret as the password; a path to one is not
+            ssl_key: self
+                .ssl_client_key
+                .as_ref()
+                .filter(|key| with_password || matches!(key, CertificateInput::File(_)))
+                .map(certificate_to_string),
+            ssl_cipher: self.ssl_cipher.as_deref(),
+            ssl_server_name: self.ssl_server_name.as_deref(),
+            statement_cache_capacity: self.statement_cache_capacity,
+            charset: &self.charset,
+            collation: self.collation.as_deref(),
+            application_name: self.application_name.as_deref(),
+        }
+    }
+}
+
+/// Accepts either a connection URL, as for [`FromStr`](std::str::FromStr), or a map of
+/// settings: `host`, `port`, `socket`, `username` (or `user`), `password`, `database`,
+/// `ssl_mode`, `ssl_ca`, `ssl_cert`, `ssl_key`, `ssl_cipher`, `ssl_server_name`,
+/// `statement_cache_capacity`, `charset`, `collation` and `application_name`.
+impl<'de> Deserialize<'de> for MySqlConnectOptions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_url_or_map(deserializer, MySqlConfig::into_options)
+    }
+}
+
+/// Serializes the map form of the options, without the password or an inline private key; see
+/// [`serialize_with_password`](MySqlConnectOptions::serialize_with_password).
+impl Serialize for MySqlConnectOptions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.config(false).serialize(serializer)
+    }
+}
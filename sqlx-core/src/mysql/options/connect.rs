@@ -1,9 +1,15 @@
-use crate::connection::ConnectOptions;
-use crate::error::Error;
-use crate::executor::Executor;
-use crate::mysql::{MySqlConnectOptions, MySqlConnection};
+use crate::connection::{BindLogging, ConnectOptions};
+use crate::error::{ConfigError, Error};
+use crate::events::{ConnectionEventListener, ConnectionEvents, EventListener};
+use crate::middleware::{Middleware, QueryMiddleware};
+use crate::mysql::collation::{CharSet, Collation};
+use crate::mysql::flavor::is_percona;
+use crate::mysql::{MySqlConnectOptions, MySqlConnection, MySqlFlavor};
+use crate::net::{CertificateInput, TlsSessionCache};
+use crate::row::Row;
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
+use std::sync::Arc;
 use std::time::Duration;
 
 impl ConnectOptions for MySqlConnectOptions {
@@ -14,45 +20,43 @@ impl ConnectOptions for MySqlConnectOptions {
         Self::Connection: Sized,
     {
         Box::pin(async move {
-            let mut conn = MySqlConnection::establish(self).await?;
+            match set_up(self).await {
+                Ok(conn) => {
+                    conn.events.connected();
 
-            // After the connection is established, we initialize by configuring a few
-            // connection parameters
+                    Ok(conn)
+                }
 
-            // https://mariadb.com/kb/en/sql-mode/
+                Err(error) => {
+                    ConnectionEvents::new(&self.events, self.connection_info()).error(&error);
 
-            // PIPES_AS_CONCAT - Allows using the pipe character (ASCII 124) as string concatenation operator.
-            //                   This means that "A" || "B" can be used in place of CONCAT("A", "B").
-
-            // NO_ENGINE_SUBSTITUTION - If not set, if the available storage engine specified by a CREATE TABLE is
-            //                          not available, a warning is given and the default storage
-            //                          engine is used instead.
-
-            // NO_ZERO_DATE - Don't allow '0000-00-00'. This is invalid in Rust.
-
-            // NO_ZERO_IN_DATE - Don't allow 'YYYY-00-00'. This is invalid in Rust.
-
-            // --
-
-            // Setting the time zone allows us to assume that the output
-            // from a TIMESTAMP field is UTC
-
-            // --
+                    Err(error)
+                }
+            }
+        })
+    }
 
-            // https://mathiasbynens.be/notes/mysql-utf8mb4
+    fn connect_with_name_suffix(
+        &self,
+        suffix: String,
+    ) -> BoxFuture<'_, Result<MySqlConnection, Error>> {
+        Box::pin(async move { self.with_name_suffix(&suffix).connect().await })
+    }
 
-            let mut options = String::new();
-            options.push_str(r#"SET sql_mode=(SELECT CONCAT(@@sql_mode, ',PIPES_AS_CONCAT,NO_ENGINE_SUBSTITUTION')),"#);
-            options.push_str(r#"time_zone='+00:00',"#);
-            options.push_str(&format!(
-                r#"NAMES {} COLLATE {};"#,
-                conn.stream.charset.as_str(),
-                conn.stream.collation.as_str()
-            ));
+    fn connect_with_password(
+        &self,
+        password: String,
+        suffix: Option<String>,
+    ) -> BoxFuture<'_, Result<MySqlConnection, Error>> {
+        Box::pin(async move {
+            let mut options = match suffix {
+                Some(suffix) => self.with_name_suffix(&suffix),
+                None => self.clone(),
+            };
 
-            conn.execute(&*options).await?;
+            options.password = Some(password);
 
-            Ok(conn)
+            options.connect().await
         })
     }
 
@@ -65,4 +69,204 @@ impl ConnectOptions for MySqlConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn log_bind_values(&mut self, logging: BindLogging) -> &mut Self {
+        self.log_settings.log_bind_values(logging);
+        self
+    }
+
+    fn query_middleware(&mut self, middleware: Arc<dyn QueryMiddleware>) -> &mut Self {
+        self.middleware = Middleware(Some(middleware));
+        self
+    }
+
+    fn connection_events(&mut self, listener: Arc<dyn ConnectionEventListener>) -> &mut Self {
+        self.events = EventListener(Some(listener));
+        self
+    }
+
+    fn isolate(&mut self) {
+        self.tls_session_cache = self.tls_session_cache.as_ref().map(TlsSessionCache::fresh);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+
+        if let Some(socket) = &self.socket {
+            // `localhost` is the default host and is ignored when connecting over a socket
+            if self.host != "localhost" {
+                errors.push(ConfigError::new(
+                    "socket",
+                    format!(
+                        "cannot be combined with `host` ({:?}); a socket at {} would be used and the host ignored",
+                        self.host,
+                        socket.display()
+                    ),
+                ));
+            }
+        } else {
+            if self.port == 0 {
+                errors.push(ConfigError::new("port", "must be between 1 and 65535"));
+            }
+
+            if self.host.is_empty() {
+                errors.push(ConfigError::new("host", "must not be empty"));
+            }
+        }
+
+        if self.username.is_empty() {
+            errors.push(ConfigError::new("username", "must not be empty"));
+        }
+
+        if matches!(&self.database, Some(database) if database.is_empty()) {
+            errors.push(ConfigError::new("database", "must not be empty if set"));
+        }
+
+        if self.verify_socket_peer && self.socket_owner.is_none() {
+            errors.push(ConfigError::new(
+                "verify_socket_peer",
+                "requires an expected_socket_owner to check the peer against",
+            ));
+        }
+
+        let files = [
+            ("ssl_ca", &self.ssl_ca),
+            ("ssl_cert", &self.ssl_client_cert),
+            ("ssl_key", &self.ssl_client_key),
+        ];
+
+        for &(field, input) in files.iter() {
+            if let Some(CertificateInput::File(path)) = input {
+                if !path.is_file() {
+                    errors.push(ConfigError::new(
+                        field,
+                        format!("file not found: {}", path.display()),
+                    ));
+                }
+            }
+        }
+
+        if self.ssl_client_cert.is_some() != self.ssl_client_key.is_some() {
+            errors.push(ConfigError::new(
+                "ssl_key",
+                "must be set together with `ssl_cert`",
+            ));
+        }
+
+        if self.charset.parse::<CharSet>().is_err() {
+            errors.push(ConfigError::new(
+                "charset",
+                format!("unknown character set: {:?}", self.charset),
+            ));
+        }
+
+        if let Some(collation) = &self.collation {
+            if collation.parse::<Collation>().is_err() {
+                errors.push(ConfigError::new(
+                    "collation",
+                    format!("unknown collation: {:?}", collation),
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+async fn set_up(options: &MySqlConnectOptions) -> Result<MySqlConnection, Error> {
+    let mut conn = MySqlConnection::establish(options).await?;
+
+    // After the connection is established, we initialize by configuring a few
+    // connection parameters
+
+    // https://mariadb.com/kb/en/sql-mode/
+
+    // PIPES_AS_CONCAT - Allows using the pipe character (ASCII 124) as string concatenation operator.
+    //                   This means that "A" || "B" can be used in place of CONCAT("A", "B").
+
+    // NO_ENGINE_SUBSTITUTION - If not set, if the available storage engine specified by a CREATE TABLE is
+    //                          not available, a warning is given and the default storage
+    //                          engine is used instead.
+
+    // NO_ZERO_DATE - Don't allow '0000-00-00'. This is invalid in Rust.
+
+    // NO_ZERO_IN_DATE - Don't allow 'YYYY-00-00'. This is invalid in Rust.
+
+    // --
+
+    // Setting the time zone allows us to assume that the output
+    // from a TIMESTAMP field is UTC
+
+    // --
+
+    // https://mathiasbynens.be/notes/mysql-utf8mb4
+
+    let mut sql = String::new();
+    sql.push_str(
+        r#"SET sql_mode=(SELECT CONCAT(@@sql_mode, ',PIPES_AS_CONCAT,NO_ENGINE_SUBSTITUTION')),"#,
+    );
+    sql.push_str(r#"time_zone='+00:00',"#);
+
+    for (name, value) in &options.session_variables {
+        sql.push_str(&format!("{}={},", name, value));
+    }
+
+    sql.push_str(&format!(
+        r#"NAMES {} COLLATE {};"#,
+        conn.stream.charset.as_str(),
+        conn.stream.collation.as_str()
+    ));
+
+    // a Percona server looks like MySQL until asked, which rides along with the above
+    let ask_version = conn.flavor == MySqlFlavor::MySql;
+
+    if ask_version {
+        sql.push_str("SELECT @@version_comment;");
+    }
+
+    // the rest of the setup goes in the same round trip, in the order it used to run in
+    if options.read_only {
+        sql.push_str("SET SESSION TRANSACTION READ ONLY;");
+    }
+
+    for command in &options.init_commands {
+        // on a line of its own, in case the command ends with a `--` comment
+        sql.push_str(command.trim_end().trim_end_matches(';'));
+        sql.push_str("\n;");
+    }
+
+    let rows = conn.fetch_all(&*sql).await?;
+
+    // the version comes first; any other rows are from the init commands
+    if let Some(row) = rows.first().filter(|_| ask_version) {
+        let comment: Option<String> = row.try_get(0)?;
+
+        if matches!(comment, Some(comment) if is_percona(&comment)) {
+            conn.flavor = MySqlFlavor::Percona;
+        }
+    }
+
+    conn.read_only_check = options.read_only && options.read_only_check;
+    conn.lossy_utf8 = options.lossy_utf8;
+    conn.prefer_simple_protocol = options.prefer_simple_protocol;
+
+    Ok(conn)
+}
+
+#[test]
+fn it_reports_every_invalid_option() {
+    let options = MySqlConnectOptions::new()
+        .host("db.example.com")
+        .socket("/tmp/mysql.sock")
+        .username("")
+        .charset("utf9");
+
+    let errors = options.validate().unwrap_err();
+    let invalid: Vec<_> = errors.iter().map(ConfigError::option).collect();
+
+    assert_eq!(invalid, ["socket", "username", "charset"]);
 }
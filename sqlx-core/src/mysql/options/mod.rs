@@ -1,12 +1,25 @@
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+#[cfg(feature = "options-serde")]
+mod config;
 mod connect;
 mod parse;
 mod ssl_mode;
 
-use crate::{connection::LogSettings, net::CertificateInput};
+use crate::column::ColumnNameMatching;
+use crate::connection::{LogSettings, ProtocolPolicy};
+use crate::events::{ConnectionInfo, EventListener};
+use crate::middleware::Middleware;
+use crate::net::{
+    unbracket, CertificateInput, SocketOwner, TlsSessionCache, DEFAULT_TLS_SESSION_CACHE_CAPACITY,
+};
+use crate::wire::{WireTracer, WireTracing};
 pub use ssl_mode::MySqlSslMode;
 
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
 /// Options and flags which can be used to configure a MySQL connection.
 ///
 /// A value of `MySqlConnectOptions` can be parsed from a connection URI,
@@ -24,8 +37,16 @@ pub use ssl_mode::MySqlSslMode;
 /// |---------|-------|-----------|
 /// | `ssl-mode` | `PREFERRED` | Determines whether or with what priority a secure SSL TCP/IP connection will be negotiated. See [`MySqlSslMode`]. |
 /// | `ssl-ca` | `None` | Sets the name of a file containing a list of trusted SSL Certificate Authorities. |
+/// | `ssl-cert` | `None` | Sets the name of a file containing the client certificate, for users created with `REQUIRE X509`. |
+/// | `ssl-key` | `None` | Sets the name of a file containing the private key of the client certificate. |
+/// | `ssl-cipher` | `None` | Restricts the cipher suites offered to the server to a colon-separated list. See [`MySqlConnectOptions::ssl_cipher`]. |
+/// | `ssl-server-name` | `None` | The name the certificate of the server is verified against, instead of the host. |
 /// | `statement-cache-capacity` | `100` | The maximum number of prepared statements stored in the cache. Set to `0` to disable. |
+/// | `statement-cache-max-bytes` | `None` | Bounds the cache by the estimated size of the statements in bytes instead. |
+/// | `prefer-simple-protocol` | `false` | Runs every query as text, writing its bind values into the SQL. See [`MySqlConnectOptions::prefer_simple_protocol`]. |
+/// | `found-rows` | `false` | Reports the rows matched by an `UPDATE` as affected, rather than those changed. See [`MySqlConnectOptions::found_rows`]. |
 /// | `socket` | `None` | Path to the unix domain socket, which will be used instead of TCP if set. |
+/// | `application-name` | name of the executable | Sent to the server as the `program_name` connection attribute. |
 ///
 /// # Example
 ///
@@ -55,16 +76,40 @@ pub use ssl_mode::MySqlSslMode;
 pub struct MySqlConnectOptions {
     pub(crate) host: String,
     pub(crate) port: u16,
+    pub(crate) addresses: Vec<SocketAddr>,
     pub(crate) socket: Option<PathBuf>,
+    pub(crate) socket_owner: Option<u32>,
+    pub(crate) verify_socket_peer: bool,
     pub(crate) username: String,
     pub(crate) password: Option<String>,
     pub(crate) database: Option<String>,
     pub(crate) ssl_mode: MySqlSslMode,
     pub(crate) ssl_ca: Option<CertificateInput>,
+    pub(crate) ssl_client_cert: Option<CertificateInput>,
+    pub(crate) ssl_client_key: Option<CertificateInput>,
+    pub(crate) ssl_cipher: Option<String>,
+    pub(crate) ssl_server_name: Option<String>,
+    pub(crate) tls_session_cache: Option<TlsSessionCache>,
+    pub(crate) protocol_policy: Option<ProtocolPolicy>,
     pub(crate) statement_cache_capacity: usize,
+    pub(crate) statement_cache_max_bytes: Option<usize>,
+    pub(crate) buffer_max: Option<usize>,
+    pub(crate) max_message_size: usize,
+    pub(crate) wire_tracing: Option<WireTracing>,
     pub(crate) charset: String,
     pub(crate) collation: Option<String>,
+    pub(crate) session_variables: Vec<(String, String)>,
+    pub(crate) init_commands: Vec<String>,
+    pub(crate) application_name: Option<String>,
+    pub(crate) read_only: bool,
+    pub(crate) read_only_check: bool,
+    pub(crate) lossy_utf8: bool,
+    pub(crate) prefer_simple_protocol: bool,
+    pub(crate) found_rows: bool,
+    pub(crate) column_name_matching: ColumnNameMatching,
     pub(crate) log_settings: LogSettings,
+    pub(crate) middleware: Middleware,
+    pub(crate) events: EventListener,
 }
 
 impl Default for MySqlConnectOptions {
@@ -79,16 +124,40 @@ impl MySqlConnectOptions {
         Self {
             port: 3306,
             host: String::from("localhost"),
+            addresses: Vec::new(),
             socket: None,
+            socket_owner: None,
+            verify_socket_peer: false,
             username: String::from("root"),
             password: None,
             database: None,
             charset: String::from("utf8mb4"),
             collation: None,
+            session_variables: Vec::new(),
+            init_commands: Vec::new(),
+            application_name: None,
             ssl_mode: MySqlSslMode::Preferred,
             ssl_ca: None,
+            ssl_client_cert: None,
+            ssl_client_key: None,
+            ssl_cipher: None,
+            ssl_server_name: None,
+            tls_session_cache: Some(TlsSessionCache::new(DEFAULT_TLS_SESSION_CACHE_CAPACITY)),
+            protocol_policy: None,
             statement_cache_capacity: 100,
+            statement_cache_max_bytes: None,
+            buffer_max: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            wire_tracing: None,
+            read_only: false,
+            read_only_check: true,
+            lossy_utf8: false,
+            prefer_simple_protocol: false,
+            found_rows: false,
+            column_name_matching: ColumnNameMatching::default(),
             log_settings: Default::default(),
+            middleware: Default::default(),
+            events: Default::default(),
         }
     }
 
@@ -97,7 +166,7 @@ impl MySqlConnectOptions {
     /// The default behavior when the host is not specified,
     /// is to connect to localhost.
     pub fn host(mut self, host: &str) -> Self {
-        self.host = host.to_owned();
+        self.host = unbracket(host).to_owned();
         self
     }
 
@@ -109,6 +178,33 @@ impl MySqlConnectOptions {
         self
     }
 
+    /// Sets the address to connect to, so the host name is not resolved.
+    ///
+    /// The [host](Self::host) is still used to verify the server's TLS certificate, and the
+    /// port of the address is used instead of the [port](Self::port). A Unix domain socket
+    /// is not used while an address is set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new()
+    ///     .host("db.example.com")
+    ///     .address(([10, 0, 0, 5], 3306).into());
+    /// ```
+    pub fn address(self, address: SocketAddr) -> Self {
+        self.addresses(vec![address])
+    }
+
+    /// Sets the addresses to connect to, so the host name is not resolved. They are tried in
+    /// order until one accepts the connection.
+    ///
+    /// See [`address`](Self::address).
+    pub fn addresses(mut self, addresses: Vec<SocketAddr>) -> Self {
+        self.addresses = addresses;
+        self
+    }
+
     /// Pass a path to a Unix socket. This changes the connection stream from
     /// TCP to UDS.
     ///
@@ -118,6 +214,38 @@ impl MySqlConnectOptions {
         self
     }
 
+    /// Sets the user ID the Unix socket file must be owned by, so that a socket put in place
+    /// by another user is not connected to. Connecting fails with
+    /// [`Error::PolicyViolation`](crate::error::Error::PolicyViolation) before anything is read
+    /// from the socket if it is owned by someone else.
+    ///
+    /// Has no effect on connections over TCP. By default set to `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new()
+    ///     .socket("/run/mysqld/mysqld.sock")
+    ///     .expected_socket_owner(27);
+    /// ```
+    pub fn expected_socket_owner(mut self, uid: u32) -> Self {
+        self.socket_owner = Some(uid);
+        self
+    }
+
+    /// Sets whether to also check, once connected, that the server process at the other end of
+    /// the Unix socket runs as the [expected owner](Self::expected_socket_owner) of the socket,
+    /// with `SO_PEERCRED` on Linux and `getpeereid` on macOS and the BSDs.
+    ///
+    /// Requires an expected owner, and fails to connect with the async-std runtime, which
+    /// cannot read the credentials of the peer. Has no effect on connections over TCP. Defaults
+    /// to `false`.
+    pub fn verify_socket_peer(mut self, verify: bool) -> Self {
+        self.verify_socket_peer = verify;
+        self
+    }
+
     /// Sets the username to connect as.
     pub fn username(mut self, username: &str) -> Self {
         self.username = username.to_owned();
@@ -136,6 +264,70 @@ impl MySqlConnectOptions {
         self
     }
 
+    /// Returns a copy of these options that connects to the database `database`, leaving these
+    /// options as they are.
+    ///
+    /// Everything else is shared with the copy, including the TLS session cache and the query
+    /// middleware.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let base = MySqlConnectOptions::new().host("db.example.com");
+    /// let tenant = base.with_database("tenant_42");
+    ///
+    /// assert_eq!(tenant.get_database(), Some("tenant_42"));
+    /// assert_eq!(base.get_database(), None);
+    /// ```
+    pub fn with_database(&self, database: &str) -> Self {
+        self.clone().database(database)
+    }
+
+    /// Returns a copy of these options that connects as `username` with `password`, leaving
+    /// these options as they are.
+    pub fn with_credentials(&self, username: &str, password: &str) -> Self {
+        self.clone().username(username).password(password)
+    }
+
+    /// Returns a copy of these options that connects over TCP/IP to `host` and `port`, leaving
+    /// these options as they are.
+    ///
+    /// Any [addresses](Self::addresses) and [socket](Self::socket) set on these options are not
+    /// copied, as they belong to the original host.
+    pub fn with_host_port(&self, host: &str, port: u16) -> Self {
+        let mut options = self.clone().host(host).port(port);
+
+        options.addresses = Vec::new();
+        options.socket = None;
+        options
+    }
+
+    /// The name of the host to connect to.
+    pub fn get_host(&self) -> &str {
+        &self.host
+    }
+
+    /// The port to connect to at the server host.
+    pub fn get_port(&self) -> u16 {
+        self.port
+    }
+
+    /// The path of the Unix domain socket to connect to, if one is set.
+    pub fn get_socket(&self) -> Option<&Path> {
+        self.socket.as_deref()
+    }
+
+    /// The username to connect as.
+    pub fn get_username(&self) -> &str {
+        &self.username
+    }
+
+    /// The name of the database to connect to, if one is set.
+    pub fn get_database(&self) -> Option<&str> {
+        self.database.as_deref()
+    }
+
     /// Sets whether or with what priority a secure SSL TCP/IP connection will be negotiated
     /// with the server.
     ///
@@ -184,6 +376,251 @@ impl MySqlConnectOptions {
         self
     }
 
+    /// Sets the name of a file containing the PEM encoded certificate presented to the server,
+    /// as a user created with `REQUIRE X509`, `REQUIRE SUBJECT` or `REQUIRE ISSUER` must.
+    ///
+    /// The private key is set with [`ssl_key`](Self::ssl_key); one is not used without the
+    /// other. Only supported with rustls.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::{MySqlSslMode, MySqlConnectOptions};
+    /// let options = MySqlConnectOptions::new()
+    ///     .ssl_mode(MySqlSslMode::Required)
+    ///     .ssl_cert("path/to/client.crt")
+    ///     .ssl_key("path/to/client.key");
+    /// ```
+    pub fn ssl_cert(mut self, file_name: impl AsRef<Path>) -> Self {
+        self.ssl_client_cert = Some(CertificateInput::File(file_name.as_ref().to_owned()));
+        self
+    }
+
+    /// Sets the PEM encoded certificate presented to the server; see
+    /// [`ssl_cert`](Self::ssl_cert).
+    pub fn ssl_cert_from_pem(mut self, pem_certificate: Vec<u8>) -> Self {
+        self.ssl_client_cert = Some(CertificateInput::Inline(pem_certificate));
+        self
+    }
+
+    /// Sets the name of a file containing the PEM encoded private key of the certificate set
+    /// with [`ssl_cert`](Self::ssl_cert), in PKCS #8 or PKCS #1 form.
+    pub fn ssl_key(mut self, file_name: impl AsRef<Path>) -> Self {
+        self.ssl_client_key = Some(CertificateInput::File(file_name.as_ref().to_owned()));
+        self
+    }
+
+    /// Sets the PEM encoded private key of the certificate set with
+    /// [`ssl_cert`](Self::ssl_cert).
+    pub fn ssl_key_from_pem(mut self, pem_key: Vec<u8>) -> Self {
+        self.ssl_client_key = Some(CertificateInput::Inline(pem_key));
+        self
+    }
+
+    /// Restricts the cipher suites offered to the server to those in a colon-separated list,
+    /// e.g. for a user created with `REQUIRE CIPHER`.
+    ///
+    /// Suites are named as by OpenSSL (`ECDHE-RSA-AES256-GCM-SHA384`) or IANA
+    /// (`TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384`); names that are not supported are skipped,
+    /// but at least one must be. As only the listed suites are offered, naming no TLS 1.3 suite
+    /// keeps the connection on TLS 1.2. Only supported with rustls.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::{MySqlSslMode, MySqlConnectOptions};
+    /// let options = MySqlConnectOptions::new()
+    ///     .ssl_mode(MySqlSslMode::Required)
+    ///     .ssl_cipher("ECDHE-RSA-AES256-GCM-SHA384:ECDHE-ECDSA-AES256-GCM-SHA384");
+    /// ```
+    pub fn ssl_cipher(mut self, list: &str) -> Self {
+        self.ssl_cipher = Some(list.to_owned());
+        self
+    }
+
+    /// Sets the name the certificate of the server is verified against under
+    /// [`VerifyIdentity`](MySqlSslMode::VerifyIdentity), and sent for server name indication,
+    /// instead of the host connected to.
+    ///
+    /// For a server reached by an address its certificate does not name, e.g. a managed
+    /// instance whose certificate is issued to its instance name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::{MySqlSslMode, MySqlConnectOptions};
+    /// let options = MySqlConnectOptions::new()
+    ///     .host("10.0.0.5")
+    ///     .ssl_mode(MySqlSslMode::VerifyIdentity)
+    ///     .ssl_ca("path/to/server-ca.pem")
+    ///     .ssl_server_name("project:region:instance");
+    /// ```
+    pub fn ssl_server_name(mut self, name: &str) -> Self {
+        self.ssl_server_name = Some(name.to_owned());
+        self
+    }
+
+    /// Sets how far the server may weaken the security of the connection while it is being
+    /// established.
+    ///
+    /// Without a policy, [`ProtocolPolicy::strict`] applies if the SSL mode is
+    /// [`Required`](MySqlSslMode::Required) or stronger or a CA certificate has been set, and
+    /// [`ProtocolPolicy::permissive`] otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::connection::ProtocolPolicy;
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new()
+    ///     .protocol_policy(ProtocolPolicy::strict());
+    /// ```
+    pub fn protocol_policy(mut self, policy: ProtocolPolicy) -> Self {
+        self.protocol_policy = Some(policy);
+        self
+    }
+
+    pub(crate) fn fetch_socket_owner(&self) -> Option<SocketOwner> {
+        self.socket_owner.map(|uid| SocketOwner {
+            uid,
+            check_peer: self.verify_socket_peer,
+        })
+    }
+
+    pub(crate) fn effective_protocol_policy(&self) -> ProtocolPolicy {
+        self.protocol_policy.unwrap_or_else(|| {
+            ProtocolPolicy::default_for(self.ssl_mode.requires_tls() || self.ssl_ca.is_some())
+        })
+    }
+
+    /// Sets the application name, sent as the `program_name` connection attribute and shown in
+    /// `performance_schema.session_connect_attrs`.
+    ///
+    /// Defaults to the name of the running executable. Control characters are replaced with `?`
+    /// and the name is truncated to 1024 bytes. Servers that do not support connection
+    /// attributes never see the name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new()
+    ///     .application_name("my-app");
+    /// ```
+    pub fn application_name(mut self, application_name: &str) -> Self {
+        self.application_name = Some(application_name.to_owned());
+        self
+    }
+
+    /// Sets whether TLS sessions are cached, so that reconnecting to the server can resume a
+    /// previous session instead of performing a full handshake.
+    ///
+    /// Enabled by default. A pool keeps a cache of its own, so sessions are never shared between
+    /// pools; connections opened directly share the cache of the options they are opened with.
+    ///
+    /// Only the `rustls` backend supports this; with `native-tls` it has no effect.
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new()
+    ///     .tls_session_cache(false);
+    /// ```
+    pub fn tls_session_cache(mut self, enabled: bool) -> Self {
+        if !enabled {
+            self.tls_session_cache = None;
+        } else if self.tls_session_cache.is_none() {
+            self.tls_session_cache = Some(TlsSessionCache::new(DEFAULT_TLS_SESSION_CACHE_CAPACITY));
+        }
+
+        self
+    }
+
+    /// Sets how many TLS sessions the session cache holds, enabling it if it was disabled.
+    ///
+    /// The default capacity is 32 sessions; as sessions are kept per server, this only needs
+    /// to be larger when connecting to several hosts.
+    pub fn tls_session_cache_capacity(mut self, capacity: usize) -> Self {
+        self.tls_session_cache = Some(TlsSessionCache::new(capacity));
+        self
+    }
+
+    /// A copy of these options whose application name (or the default name) ends in `suffix`.
+    // what a `ConnectionEventListener` is told about a connection made with these options
+    pub(crate) fn connection_info(&self) -> ConnectionInfo {
+        let peer_addr = match &self.socket {
+            Some(socket) => socket.display().to_string(),
+            None => format!("{}:{}", self.host, self.port),
+        };
+
+        ConnectionInfo {
+            peer_addr: Some(peer_addr),
+            database_name: self.database.clone(),
+            application_name: self
+                .application_name
+                .as_deref()
+                .or_else(|| crate::common::default_application_name())
+                .map(str::to_owned),
+            ..ConnectionInfo::new("MySQL")
+        }
+    }
+
+    pub(crate) fn with_name_suffix(&self, suffix: &str) -> Self {
+        let name = self
+            .application_name
+            .as_deref()
+            .or_else(|| crate::common::default_application_name())
+            .unwrap_or_default();
+
+        self.clone()
+            .application_name(&format!("{}{}", name, suffix))
+    }
+
+    /// Caps the capacity, in bytes, that the connection's read and write buffers keep between
+    /// messages.
+    ///
+    /// Messages larger than this are still sent and received, but through a temporary buffer
+    /// that is freed as soon as the message has been handled, so one very large result set
+    /// does not leave the connection holding on to that much memory.
+    ///
+    /// By default the buffers are never shrunk; see also [`Connection::shrink_buffers`].
+    ///
+    /// [`Connection::shrink_buffers`]: crate::connection::Connection::shrink_buffers
+    pub fn buffer_max(mut self, max: usize) -> Self {
+        self.buffer_max = Some(max);
+        self
+    }
+
+    /// Sets the largest message, in bytes, the connection accepts from the server.
+    ///
+    /// A packet holds at most 16 MiB, and larger payloads such as a long row are split over
+    /// several packets which are put back together before decoding. A payload that grows
+    /// longer than this fails the connection with a protocol error, instead of being buffered
+    /// for as long as the server keeps sending. Defaults to 64 MiB.
+    pub fn max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = max;
+        self
+    }
+
+    /// Sets a tracer to be called with every protocol message the connection sends or
+    /// receives, after TLS has been stripped; see [`wire`](crate::wire).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// # use sqlx_core::wire::FileWireTracer;
+    /// # fn main() -> std::io::Result<()> {
+    /// let options = MySqlConnectOptions::new()
+    ///     .wire_tracing(Arc::new(FileWireTracer::create("wire.log")?));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn wire_tracing(mut self, tracer: Arc<dyn WireTracer>) -> Self {
+        self.wire_tracing = Some(WireTracing(tracer));
+        self
+    }
+
     /// Sets the capacity of the connection's statement cache in a number of stored
     /// distinct statements. Caching is handled using LRU, meaning when the
     /// amount of queries hits the defined limit, the oldest statement will get
@@ -195,6 +632,18 @@ impl MySqlConnectOptions {
         self
     }
 
+    /// Bounds the connection's statement cache by the estimated memory of the statements,
+    /// their SQL and the metadata describing their parameters and columns, instead of by their
+    /// number. Statements that take much more room than others, such as those returning
+    /// many columns, then count for more.
+    ///
+    /// This replaces the bound set with
+    /// [`statement_cache_capacity`](Self::statement_cache_capacity).
+    pub fn statement_cache_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.statement_cache_max_bytes = Some(max_bytes);
+        self
+    }
+
     /// Sets the character set for the connection.
     ///
     /// The default character set is `utf8mb4`. This is supported from MySQL 5.5.3.
@@ -212,4 +661,193 @@ impl MySqlConnectOptions {
         self.collation = Some(collation.to_owned());
         self
     }
+
+    /// Sets a session variable right after connecting, as part of the same `SET` statement
+    /// that configures the connection.
+    ///
+    /// The `value` is inserted verbatim, so string values must be quoted.
+    /// Setting the same variable twice keeps the last value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new()
+    ///     .session_variable("wait_timeout", "600")
+    ///     .session_variable("transaction_isolation", "'READ-COMMITTED'");
+    /// ```
+    pub fn session_variable(mut self, name: &str, value: &str) -> Self {
+        if let Some(existing) = self.session_variables.iter_mut().find(|(n, _)| n == name) {
+            log::debug!(
+                "session variable {:?} set more than once; using {} instead of {}",
+                name,
+                value,
+                existing.1
+            );
+
+            existing.1 = value.to_owned();
+        } else {
+            self.session_variables
+                .push((name.to_owned(), value.to_owned()));
+        }
+
+        self
+    }
+
+    /// Adds a statement to execute right after connecting, once the session variables
+    /// are set.
+    ///
+    /// Statements run in the order they were added. They are sent in the same script as the
+    /// rest of the setup of the session, which takes a single round trip however many there
+    /// are; a statement failing fails the connection, and the ones after it do not run.
+    pub fn init_command(mut self, sql: &str) -> Self {
+        self.init_commands.push(sql.to_owned());
+        self
+    }
+
+    /// Sets whether the session is read-only.
+    ///
+    /// A read-only session runs `SET SESSION TRANSACTION READ ONLY` as part of the setup of
+    /// the session, so the server rejects writes. In addition, every statement is checked before it is sent:
+    /// anything that does not start with a reading keyword (`SELECT`, `WITH`, `VALUES`,
+    /// `TABLE`, `SHOW`, `DESCRIBE`, `EXPLAIN` without `ANALYZE`, or a transaction command), and
+    /// any string holding more than one statement, fails with [`Error::ReadOnlyViolation`]
+    /// without reaching the server. See
+    /// [`read_only_statement_check`](Self::read_only_statement_check) to turn the check off.
+    ///
+    /// The [init commands](Self::init_command) run before the check is enabled. Neither is a
+    /// substitute for a user without write privileges; writes to temporary tables, for
+    /// example, are still allowed by the server.
+    ///
+    /// [`Error::ReadOnlyViolation`]: crate::error::Error::ReadOnlyViolation
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new()
+    ///     .read_only(true);
+    /// ```
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Sets whether a [read-only](Self::read_only) connection checks statements before
+    /// sending them. Enabled by default; has no effect unless the connection is read-only.
+    pub fn read_only_statement_check(mut self, check: bool) -> Self {
+        self.read_only_check = check;
+        self
+    }
+
+    /// Sets how a column name given to [`Row::try_get`], or read by `#[derive(FromRow)]`, is
+    /// matched when no column has exactly that name. MySQL keeps the case of column names as
+    /// written in the query, so a field `userid` finds no column `userId` unless a looser
+    /// comparison is set here. By default, only a column with exactly the name is found.
+    ///
+    /// See [`ColumnNameMatching`] for the comparisons and how ambiguous names fail.
+    ///
+    /// [`Row::try_get`]: crate::row::Row::try_get
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::column::ColumnNameMatching;
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new()
+    ///     .column_name_matching(ColumnNameMatching::SnakeCaseInsensitive);
+    /// ```
+    pub fn column_name_matching(mut self, matching: ColumnNameMatching) -> Self {
+        self.column_name_matching = matching;
+        self
+    }
+
+    /// Sets whether text values that are not valid UTF-8 are read with the invalid sequences
+    /// replaced by U+FFFD, instead of failing to decode as `String` or `&str`.
+    ///
+    /// This is for databases that hold text in another encoding in columns that are declared
+    /// UTF-8. Values of binary columns (`BINARY`, `VARBINARY` and `BLOB`) are never changed.
+    /// [`MySqlQueryResult::lossy_utf8_conversions`] counts the values that were replaced.
+    /// Disabled by default.
+    ///
+    /// [`MySqlQueryResult::lossy_utf8_conversions`]: crate::mysql::MySqlQueryResult::lossy_utf8_conversions
+    pub fn lossy_utf8(mut self, lossy: bool) -> Self {
+        self.lossy_utf8 = lossy;
+        self
+    }
+
+    /// Runs every query as text (`COM_QUERY`), writing its bind values into the SQL, for
+    /// proxies that cannot handle prepared statements. Off by default.
+    ///
+    /// Strings and binary values are written as hex literals, e.g. `_utf8mb4 X'6869'`, so
+    /// they hold nothing to escape; numbers are written as digits, floats in exponent notation
+    /// (NaN and infinities are refused) and dates and times as quoted strings of digits, which
+    /// the server converts where a date or time is expected. The `?` placeholders are found
+    /// by the same rules the server reads the SQL with, following the `NO_BACKSLASH_ESCAPES`
+    /// SQL mode of the session: one inside a string, a quoted identifier or a comment is left
+    /// alone. So a value cannot change the statement it is written into, as long as the SQL of
+    /// the query itself is trusted.
+    ///
+    /// Results come back in the text format and nothing is cached.
+    /// [`prepare`](crate::executor::Executor::prepare) and
+    /// [`describe`](crate::executor::Executor::describe), used by the `query!` macros, still
+    /// prepare the statement for its metadata and close it again right after.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new().prefer_simple_protocol(true);
+    /// ```
+    pub fn prefer_simple_protocol(mut self, prefer: bool) -> Self {
+        self.prefer_simple_protocol = prefer;
+        self
+    }
+
+    /// Sets whether the client asks for `CLIENT_FOUND_ROWS`, so that
+    /// [`MySqlQueryResult::rows_affected`] of an `UPDATE` counts the rows it matched, rather
+    /// than only those it changed.
+    ///
+    /// An `UPDATE` that sets a row to the values it already has does not change it. Without
+    /// this flag, as with `libmysqlclient`, such a row is not counted; code that checks the
+    /// count for optimistic locking usually wants it counted. Off by default.
+    ///
+    /// [`MySqlQueryResult::rows_affected`]: crate::mysql::MySqlQueryResult::rows_affected
+    pub fn found_rows(mut self, found_rows: bool) -> Self {
+        self.found_rows = found_rows;
+        self
+    }
+}
+
+#[test]
+fn it_derives_options_without_changing_the_original() {
+    let base = MySqlConnectOptions::new()
+        .host("db.example.com")
+        .socket("/run/mysqld/mysqld.sock")
+        .username("app")
+        .password("secret")
+        .database("orders");
+
+    let tenant = base
+        .with_database("tenant_42")
+        .with_credentials("tenant", "hunter2")
+        .with_host_port("replica.example.com", 3307);
+
+    assert_eq!(tenant.get_database(), Some("tenant_42"));
+    assert_eq!(tenant.get_username(), "tenant");
+    assert_eq!(tenant.password.as_deref(), Some("hunter2"));
+    assert_eq!(
+        (tenant.get_host(), tenant.get_port()),
+        ("replica.example.com", 3307)
+    );
+    assert_eq!(tenant.get_socket(), None);
+
+    assert_eq!(base.get_database(), Some("orders"));
+    assert_eq!(base.get_username(), "app");
+    assert_eq!(base.password.as_deref(), Some("secret"));
+    assert_eq!((base.get_host(), base.get_port()), ("db.example.com", 3306));
+    assert_eq!(
+        base.get_socket(),
+        Some(Path::new("/run/mysqld/mysqld.sock"))
+    );
 }
@@ -1,17 +1,17 @@
 use crate::error::Error;
 use crate::mysql::MySqlConnectOptions;
+use crate::net::parse_url;
 use percent_encoding::percent_decode_str;
 use std::str::FromStr;
-use url::Url;
 
 impl FromStr for MySqlConnectOptions {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Error> {
-        let url: Url = s.parse().map_err(Error::config)?;
+        let (url, host) = parse_url(s)?;
         let mut options = Self::new();
 
-        if let Some(host) = url.host_str() {
+        if let Some(host) = &host {
             options = options.host(host);
         }
 
@@ -51,6 +51,22 @@ impl FromStr for MySqlConnectOptions {
                     options = options.ssl_ca(&*value);
                 }
 
+                "ssl-cert" => {
+                    options = options.ssl_cert(&*value);
+                }
+
+                "ssl-key" => {
+                    options = options.ssl_key(&*value);
+                }
+
+                "ssl-cipher" => {
+                    options = options.ssl_cipher(&value);
+                }
+
+                "ssl-server-name" => {
+                    options = options.ssl_server_name(&value);
+                }
+
                 "charset" => {
                     options = options.charset(&*value);
                 }
@@ -64,10 +80,27 @@ impl FromStr for MySqlConnectOptions {
                         options.statement_cache_capacity(value.parse().map_err(Error::config)?);
                 }
 
+                "statement-cache-max-bytes" => {
+                    options =
+                        options.statement_cache_max_bytes(value.parse().map_err(Error::config)?);
+                }
+
+                "prefer-simple-protocol" => {
+                    options = options.prefer_simple_protocol(value.parse().map_err(Error::config)?);
+                }
+
+                "found-rows" => {
+                    options = options.found_rows(value.parse().map_err(Error::config)?);
+                }
+
                 "socket" => {
                     options = options.socket(&*value);
                 }
 
+                "application-name" => {
+                    options = options.application_name(&*value);
+                }
+
                 _ => {}
             }
         }
@@ -91,3 +124,67 @@ fn it_parses_password_with_non_ascii_chars_correctly() {
 
     assert_eq!(Some("p@ssw0rd".into()), opts.password);
 }
+
+#[test]
+fn it_parses_ipv6_hosts() {
+    let opts = MySqlConnectOptions::from_str("mysql://root@[::1]:3307/db").unwrap();
+
+    assert_eq!("::1", &opts.host);
+    assert_eq!(3307, opts.port);
+
+    let opts = MySqlConnectOptions::from_str("mysql://root@[fe80::1%25eth0]/db").unwrap();
+
+    assert_eq!("fe80::1%eth0", &opts.host);
+}
+
+#[test]
+fn it_parses_application_name() {
+    let uri = "mysql://root@localhost/db?application-name=my%20app";
+    let opts = MySqlConnectOptions::from_str(uri).unwrap();
+
+    assert_eq!(Some("my app"), opts.application_name.as_deref());
+}
+
+#[test]
+fn it_parses_prefer_simple_protocol() {
+    let opts = MySqlConnectOptions::from_str("mysql://root@localhost/db").unwrap();
+    assert!(!opts.prefer_simple_protocol);
+
+    let opts =
+        MySqlConnectOptions::from_str("mysql://root@localhost/db?prefer-simple-protocol=true")
+            .unwrap();
+    assert!(opts.prefer_simple_protocol);
+}
+
+#[test]
+fn it_parses_found_rows() {
+    let opts = MySqlConnectOptions::from_str("mysql://root@localhost/db").unwrap();
+    assert!(!opts.found_rows);
+
+    let opts = MySqlConnectOptions::from_str("mysql://root@localhost/db?found-rows=true").unwrap();
+    assert!(opts.found_rows);
+}
+
+#[test]
+fn it_parses_client_certificate_and_cipher_options() {
+    use crate::net::CertificateInput;
+
+    let uri = "mysql://root@10.0.0.5/db?ssl-mode=verify_identity&ssl-cert=client.crt\
+               &ssl-key=client.key&ssl-cipher=ECDHE-RSA-AES256-GCM-SHA384\
+               &ssl-server-name=sqlx.rs";
+    let opts = MySqlConnectOptions::from_str(uri).unwrap();
+
+    assert!(matches!(
+        &opts.ssl_client_cert,
+        Some(CertificateInput::File(path)) if path.to_str() == Some("client.crt")
+    ));
+    assert!(matches!(
+        &opts.ssl_client_key,
+        Some(CertificateInput::File(path)) if path.to_str() == Some("client.key")
+    ));
+    assert_eq!(
+        Some("ECDHE-RSA-AES256-GCM-SHA384"),
+        opts.ssl_cipher.as_deref()
+    );
+    assert_eq!(Some("sqlx.rs"), opts.ssl_server_name.as_deref());
+}
@@ -30,6 +30,15 @@ pub enum MySqlSslMode {
     VerifyIdentity,
 }
 
+impl MySqlSslMode {
+    pub(crate) fn requires_tls(self) -> bool {
+        matches!(
+            self,
+            MySqlSslMode::Required | MySqlSslMode::VerifyCa | MySqlSslMode::VerifyIdentity
+        )
+    }
+}
+
 impl Default for MySqlSslMode {
     fn default() -> Self {
         MySqlSslMode::Preferred
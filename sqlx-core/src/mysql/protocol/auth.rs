@@ -17,6 +17,13 @@ impl AuthPlugin {
             AuthPlugin::Sha256Password => "sha256_password",
         }
     }
+
+    // `mysql_native_password` hashes with SHA-1 and can be brute-forced from a captured
+    // exchange; the SHA-256 plugins are considered equally strong
+    pub(crate) fn is_weaker_than(self, other: AuthPlugin) -> bool {
+        matches!(self, AuthPlugin::MySqlNativePassword)
+            && !matches!(other, AuthPlugin::MySqlNativePassword)
+    }
 }
 
 impl FromStr for AuthPlugin {
@@ -32,3 +39,14 @@ impl FromStr for AuthPlugin {
         }
     }
 }
+
+#[test]
+fn it_ranks_native_password_below_the_sha256_plugins() {
+    use AuthPlugin::*;
+
+    assert!(MySqlNativePassword.is_weaker_than(CachingSha2Password));
+    assert!(MySqlNativePassword.is_weaker_than(Sha256Password));
+    assert!(!MySqlNativePassword.is_weaker_than(MySqlNativePassword));
+    assert!(!Sha256Password.is_weaker_than(CachingSha2Password));
+    assert!(!CachingSha2Password.is_weaker_than(MySqlNativePassword));
+}
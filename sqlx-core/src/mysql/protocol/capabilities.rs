@@ -82,5 +82,23 @@ bitflags::bitflags! {
 
         // Don't reset the options after an unsuccessful connect
         const REMEMBER_OPTIONS = (1 << 31);
+
+        // [MariaDB] The extended capabilities, sent in the bytes MySQL leaves reserved, and only
+        // when the server leaves out `MYSQL`
+
+        // Client supports progress indicator
+        const MARIADB_CLIENT_PROGRESS = (1 << 32);
+
+        // Permit COM_MULTI protocol
+        const MARIADB_CLIENT_COM_MULTI = (1 << 33);
+
+        // Permit bulk insert
+        const MARIADB_CLIENT_STMT_BULK_OPERATIONS = (1 << 34);
+
+        // Add extended metadata information
+        const MARIADB_CLIENT_EXTENDED_TYPE_INFO = (1 << 35);
+
+        // Permit skipping metadata
+        const MARIADB_CLIENT_CACHE_METADATA = (1 << 36);
     }
 }
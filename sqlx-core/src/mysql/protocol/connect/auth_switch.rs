@@ -16,6 +16,8 @@ pub struct AuthSwitchRequest {
 
 impl Decode<'_> for AuthSwitchRequest {
     fn decode_with(mut buf: Bytes, _: ()) -> Result<Self, Error> {
+        buf.ensure_remaining(1)?;
+
         let header = buf.get_u8();
         if header != 0xfe {
             return Err(err_protocol!(
@@ -33,7 +35,7 @@ impl Decode<'_> for AuthSwitchRequest {
                 buf.len()
             ));
         }
-        let data = buf.get_bytes(20);
+        let data = buf.get_bytes(20)?;
         buf.advance(1); // NUL-terminator
 
         Ok(Self { plugin, data })
@@ -24,10 +24,16 @@ pub(crate) struct Handshake {
 
 impl Decode<'_> for Handshake {
     fn decode_with(mut buf: Bytes, _: ()) -> Result<Self, Error> {
+        buf.ensure_remaining(1)?;
+
         let protocol_version = buf.get_u8(); // int<1>
         let server_version = buf.get_str_nul()?; // string<NUL>
+
+        // everything up to the second part of the scramble has a fixed size
+        buf.ensure_remaining(31)?;
+
         let connection_id = buf.get_u32_le(); // int<4>
-        let auth_plugin_data_1 = buf.get_bytes(8); // string<8>
+        let auth_plugin_data_1 = buf.get_bytes(8)?; // string<8>
 
         buf.advance(1); // reserved: string<1>
 
@@ -58,7 +64,9 @@ impl Decode<'_> for Handshake {
 
         let auth_plugin_data_2 = if capabilities.contains(Capabilities::SECURE_CONNECTION) {
             let len = ((auth_plugin_data_len as isize) - 9).max(12) as usize;
-            let v = buf.get_bytes(len);
+            let v = buf.get_bytes(len)?;
+
+            buf.ensure_remaining(1)?;
             buf.advance(1); // NUL-terminator
 
             v
@@ -174,7 +182,10 @@ fn test_decode_handshake_mariadb_10_4_7() {
             | Capabilities::CAN_HANDLE_EXPIRED_PASSWORDS
             | Capabilities::SESSION_TRACK
             | Capabilities::DEPRECATE_EOF
-            | Capabilities::REMEMBER_OPTIONS,
+            | Capabilities::REMEMBER_OPTIONS
+            | Capabilities::MARIADB_CLIENT_PROGRESS
+            | Capabilities::MARIADB_CLIENT_COM_MULTI
+            | Capabilities::MARIADB_CLIENT_STMT_BULK_OPERATIONS,
     );
 
     assert!(p.server_capabilities.is_empty());
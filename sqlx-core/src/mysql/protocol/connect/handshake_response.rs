@@ -25,6 +25,9 @@ pub struct HandshakeResponse<'a> {
 
     /// Opaque authentication response
     pub auth_response: Option<&'a [u8]>,
+
+    /// Key-value pairs sent to the server if it supports connection attributes
+    pub connect_attrs: &'a [(&'a str, &'a str)],
 }
 
 impl Encode<'_, Capabilities> for HandshakeResponse<'_> {
@@ -69,5 +72,42 @@ impl Encode<'_, Capabilities> for HandshakeResponse<'_> {
                 buf.push(0);
             }
         }
+
+        if capabilities.contains(Capabilities::CONNECT_ATTRS) {
+            let mut attrs = Vec::new();
+
+            for (key, value) in self.connect_attrs {
+                attrs.put_str_lenenc(key);
+                attrs.put_str_lenenc(value);
+            }
+
+            buf.put_bytes_lenenc(&attrs);
+        }
     }
 }
+
+#[test]
+fn it_encodes_connect_attrs() {
+    let response = HandshakeResponse {
+        database: None,
+        max_packet_size: 1024,
+        collation: 45,
+        username: "root",
+        auth_plugin: None,
+        auth_response: None,
+        connect_attrs: &[("program_name", "myservice")],
+    };
+
+    let mut without = Vec::new();
+    response.encode_with(&mut without, Capabilities::PROTOCOL_41);
+
+    let mut with = Vec::new();
+    response.encode_with(
+        &mut with,
+        Capabilities::PROTOCOL_41 | Capabilities::CONNECT_ATTRS,
+    );
+
+    // everything but the capability flags is unchanged up to the attributes
+    assert_eq!(&with[4..without.len()], &without[4..]);
+    assert_eq!(&with[without.len()..], b"\x17\x0cprogram_name\x09myservice");
+}
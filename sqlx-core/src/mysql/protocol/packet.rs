@@ -4,7 +4,7 @@ use bytes::Bytes;
 
 use crate::error::Error;
 use crate::io::{Decode, Encode};
-use crate::mysql::protocol::response::{EofPacket, OkPacket};
+use crate::mysql::protocol::response::OkPacket;
 use crate::mysql::protocol::Capabilities;
 
 #[derive(Debug)]
@@ -56,21 +56,8 @@ impl Packet<Bytes> {
         T::decode_with(self.0, context)
     }
 
-    pub(crate) fn ok(self) -> Result<OkPacket, Error> {
-        self.decode()
-    }
-
-    pub(crate) fn eof(self, capabilities: Capabilities) -> Result<EofPacket, Error> {
-        if capabilities.contains(Capabilities::DEPRECATE_EOF) {
-            let ok = self.ok()?;
-
-            Ok(EofPacket {
-                warnings: ok.warnings,
-                status: ok.status,
-            })
-        } else {
-            self.decode_with(capabilities)
-        }
+    pub(crate) fn ok(self, capabilities: Capabilities) -> Result<OkPacket, Error> {
+        self.decode_with(capabilities)
     }
 }
 
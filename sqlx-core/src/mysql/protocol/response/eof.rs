@@ -1,7 +1,7 @@
 use bytes::{Buf, Bytes};
 
 use crate::error::Error;
-use crate::io::Decode;
+use crate::io::{BufExt, Decode};
 use crate::mysql::protocol::response::Status;
 use crate::mysql::protocol::Capabilities;
 
@@ -19,6 +19,8 @@ pub struct EofPacket {
 
 impl Decode<'_, Capabilities> for EofPacket {
     fn decode_with(mut buf: Bytes, _: Capabilities) -> Result<Self, Error> {
+        buf.ensure_remaining(5)?;
+
         let header = buf.get_u8();
         if header != 0xfe {
             return Err(err_protocol!(
@@ -17,6 +17,8 @@ pub struct ErrPacket {
 
 impl Decode<'_, Capabilities> for ErrPacket {
     fn decode_with(mut buf: Bytes, capabilities: Capabilities) -> Result<Self, Error> {
+        buf.ensure_remaining(3)?;
+
         let header = buf.get_u8();
         if header != 0xff {
             return Err(err_protocol!(
@@ -1,9 +1,14 @@
 use bytes::{Buf, Bytes};
 
 use crate::error::Error;
-use crate::io::Decode;
+use crate::io::{BufExt, Decode};
 use crate::mysql::io::MySqlBufExt;
 use crate::mysql::protocol::response::Status;
+use crate::mysql::protocol::Capabilities;
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/mysql__com_8h.html#a0b6ae5bc2a2d3f4ee8fe4ad2d6cee37e
+const SESSION_TRACK_SYSTEM_VARIABLES: u8 = 0;
+const SESSION_TRACK_SCHEMA: u8 = 1;
 
 /// Indicates successful completion of a previous command sent by the client.
 #[derive(Debug)]
@@ -12,10 +17,32 @@ pub struct OkPacket {
     pub last_insert_id: u64,
     pub status: Status,
     pub warnings: u16,
+
+    /// The human-readable information about the statement, e.g.
+    /// `Rows matched: 1  Changed: 0  Warnings: 0` after an `UPDATE`, if the server sent any.
+    pub info: Option<String>,
+
+    /// Changes to the session reported with `CLIENT_SESSION_TRACK`, in the order they happened.
+    pub session_state: Vec<SessionStateChange>,
+}
+
+/// A change to the session, from the session state information of an OK packet.
+///
+/// Only the trackers SQLx makes use of are decoded; the others (GTIDs, transaction state and
+/// characteristics, and any added after this was written) are skipped.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SessionStateChange {
+    /// A system variable listed in `session_track_system_variables` was set.
+    SystemVariable { name: String, value: String },
+
+    /// The default schema was changed, e.g. with `USE`.
+    Schema(String),
 }
 
-impl Decode<'_> for OkPacket {
-    fn decode_with(mut buf: Bytes, _: ()) -> Result<Self, Error> {
+impl Decode<'_, Capabilities> for OkPacket {
+    fn decode_with(mut buf: Bytes, capabilities: Capabilities) -> Result<Self, Error> {
+        buf.ensure_remaining(1)?;
+
         let header = buf.get_u8();
         if header != 0 && header != 0xfe {
             return Err(err_protocol!(
@@ -24,16 +51,66 @@ impl Decode<'_> for OkPacket {
             ));
         }
 
-        let affected_rows = buf.get_uint_lenenc();
-        let last_insert_id = buf.get_uint_lenenc();
+        let affected_rows = buf.get_uint_lenenc()?;
+        let last_insert_id = buf.get_uint_lenenc()?;
+
+        buf.ensure_remaining(4)?;
+
         let status = Status::from_bits_truncate(buf.get_u16_le());
         let warnings = buf.get_u16_le();
 
+        let mut session_state = Vec::new();
+
+        // without session tracking, the rest of the packet is the human-readable info; with it,
+        // the info is length-encoded (and may be left out entirely) and the changes follow
+        let info = if !capabilities.contains(Capabilities::SESSION_TRACK) {
+            buf.split_to(buf.len())
+        } else if buf.has_remaining() {
+            buf.get_bytes_lenenc()?
+        } else {
+            Bytes::new()
+        };
+
+        if capabilities.contains(Capabilities::SESSION_TRACK)
+            && status.contains(Status::SERVER_SESSION_STATE_CHANGED)
+            && buf.has_remaining()
+        {
+            let mut state = buf.get_bytes_lenenc()?;
+
+            while state.has_remaining() {
+                let tracker = state.get_u8();
+                let mut data = state.get_bytes_lenenc()?;
+
+                match tracker {
+                    SESSION_TRACK_SYSTEM_VARIABLES => {
+                        let name = data.get_str_lenenc()?;
+                        let value = data.get_str_lenenc()?;
+
+                        session_state.push(SessionStateChange::SystemVariable { name, value });
+                    }
+
+                    SESSION_TRACK_SCHEMA => {
+                        session_state.push(SessionStateChange::Schema(data.get_str_lenenc()?));
+                    }
+
+                    _ => {}
+                }
+            }
+        }
+
+        let info = if info.is_empty() {
+            None
+        } else {
+            Some(String::from_utf8_lossy(&info).into_owned())
+        };
+
         Ok(Self {
             affected_rows,
             last_insert_id,
             status,
             warnings,
+            info,
+            session_state,
         })
     }
 }
@@ -42,11 +119,69 @@ impl Decode<'_> for OkPacket {
 fn test_decode_ok_packet() {
     const DATA: &[u8] = b"\x00\x00\x00\x02@\x00\x00";
 
-    let p = OkPacket::decode(DATA.into()).unwrap();
+    let p = OkPacket::decode_with(DATA.into(), Capabilities::empty()).unwrap();
 
     assert_eq!(p.affected_rows, 0);
     assert_eq!(p.last_insert_id, 0);
     assert_eq!(p.warnings, 0);
     assert!(p.status.contains(Status::SERVER_STATUS_AUTOCOMMIT));
     assert!(p.status.contains(Status::SERVER_SESSION_STATE_CHANGED));
+    assert!(p.session_state.is_empty());
+    assert_eq!(p.info, None);
+}
+
+#[test]
+fn test_decode_ok_packet_with_info() {
+    const INFO: &[u8] = b"Rows matched: 1  Changed: 0  Warnings: 0";
+
+    let mut data = b"\x00\x00\x00\x02\x00\x00\x00".to_vec();
+    data.extend_from_slice(INFO);
+
+    let p = OkPacket::decode_with(data.into(), Capabilities::empty()).unwrap();
+    assert_eq!(p.info.as_deref().map(str::as_bytes), Some(INFO));
+
+    // length-encoded with session tracking
+    let mut data = b"\x00\x00\x00\x02\x00\x00\x00\x28".to_vec();
+    data.extend_from_slice(INFO);
+
+    let p = OkPacket::decode_with(data.into(), Capabilities::SESSION_TRACK).unwrap();
+    assert_eq!(p.info.as_deref().map(str::as_bytes), Some(INFO));
+}
+
+#[test]
+fn test_decode_ok_packet_with_session_state() {
+    // after `USE test; SET time_zone = '+00:00'`, with a GTID tracker block in between and an
+    // unknown tracker (0x7f) at the end
+    const DATA: &[u8] = b"\x00\x00\x00\x02\x40\x00\x00\x00\x23\
+        \x01\x05\x04test\
+        \x03\x03\x00\x01x\
+        \x00\x11\x09time_zone\x06+00:00\
+        \x7f\x02ab";
+
+    let p = OkPacket::decode_with(DATA.into(), Capabilities::SESSION_TRACK).unwrap();
+
+    assert_eq!(
+        p.session_state,
+        vec![
+            SessionStateChange::Schema("test".into()),
+            SessionStateChange::SystemVariable {
+                name: "time_zone".into(),
+                value: "+00:00".into()
+            },
+        ]
+    );
+
+    // the same packet but truncated inside the session state
+    assert!(OkPacket::decode_with(DATA[..20].into(), Capabilities::SESSION_TRACK).is_err());
+}
+
+#[test]
+fn test_decode_ok_packet_without_info() {
+    // MySQL leaves out the info string when there is neither info nor a session state change
+    const DATA: &[u8] = b"\x00\x01\x00\x02\x00\x00\x00";
+
+    let p = OkPacket::decode_with(DATA.into(), Capabilities::SESSION_TRACK).unwrap();
+
+    assert_eq!(p.affected_rows, 1);
+    assert!(p.session_state.is_empty());
 }
@@ -0,0 +1,80 @@
+use crate::io::Encode;
+use crate::mysql::protocol::text::ColumnFlags;
+use crate::mysql::protocol::Capabilities;
+use crate::mysql::MySqlArguments;
+
+// https://mariadb.com/kb/en/com_stmt_bulk_execute/
+
+// the types of the parameters follow the header
+const SEND_TYPES_TO_SERVER: u16 = 128;
+
+// the value of a parameter follows its indicator
+const INDICATOR_NONE: u8 = 0;
+const INDICATOR_NULL: u8 = 1;
+
+// [MariaDB] executes a statement once for each set of arguments, which must all have the
+// same types; the server answers with a single OK packet for all of them
+#[derive(Debug)]
+pub struct BulkExecute<'q> {
+    pub statement: u32,
+    pub rows: &'q [MySqlArguments],
+}
+
+impl<'q> Encode<'_, Capabilities> for BulkExecute<'q> {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: Capabilities) {
+        buf.push(0xfa); // COM_STMT_BULK_EXECUTE
+        buf.extend(&self.statement.to_le_bytes());
+        buf.extend(&SEND_TYPES_TO_SERVER.to_le_bytes());
+
+        if let Some(first) = self.rows.first() {
+            for ty in &first.types {
+                buf.push(ty.r#type as u8);
+
+                buf.push(if ty.flags.contains(ColumnFlags::UNSIGNED) {
+                    0x80
+                } else {
+                    0
+                });
+            }
+        }
+
+        for row in self.rows {
+            for bind in row.values() {
+                match bind.value {
+                    Some(value) => {
+                        buf.push(INDICATOR_NONE);
+                        buf.extend(value);
+                    }
+
+                    None => buf.push(INDICATOR_NULL),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn it_encodes_bulk_execute() {
+    let mut first = MySqlArguments::default();
+    first.add(1_i32);
+    first.add("a");
+
+    let mut second = MySqlArguments::default();
+    second.add(2_i32);
+    second.add(None::<&str>);
+
+    let mut buf = Vec::new();
+
+    BulkExecute {
+        statement: 7,
+        rows: &[first, second],
+    }
+    .encode_with(&mut buf, Capabilities::empty());
+
+    assert_eq!(
+        buf,
+        b"\xfa\x07\x00\x00\x00\x80\x00\x03\x00\xfd\x00\
+          \x00\x01\x00\x00\x00\x00\x01a\
+          \x00\x02\x00\x00\x00\x01"
+    );
+}
@@ -1,9 +1,11 @@
+mod bulk_execute;
 mod execute;
 mod prepare;
 mod prepare_ok;
 mod row;
 mod stmt_close;
 
+pub(crate) use bulk_execute::BulkExecute;
 pub(crate) use execute::Execute;
 pub(crate) use prepare::Prepare;
 pub(crate) use prepare_ok::PrepareOk;
@@ -1,7 +1,7 @@
 use bytes::{Buf, Bytes};
 
 use crate::error::Error;
-use crate::io::Decode;
+use crate::io::{BufExt, Decode};
 use crate::mysql::protocol::Capabilities;
 
 // https://dev.mysql.com/doc/internals/en/com-stmt-prepare-response.html#packet-COM_STMT_PREPARE_OK
@@ -16,6 +16,8 @@ pub(crate) struct PrepareOk {
 
 impl Decode<'_, Capabilities> for PrepareOk {
     fn decode_with(mut buf: Bytes, _: Capabilities) -> Result<Self, Error> {
+        buf.ensure_remaining(12)?;
+
         let status = buf.get_u8();
         if status != 0x00 {
             return Err(err_protocol!(
@@ -2,7 +2,7 @@ use bytes::{Buf, Bytes};
 
 use crate::error::Error;
 use crate::io::{BufExt, Decode};
-use crate::mysql::io::MySqlBufExt;
+use crate::mysql::io::{lenenc_size, MySqlBufExt};
 use crate::mysql::protocol::text::ColumnType;
 use crate::mysql::protocol::Row;
 use crate::mysql::MySqlColumn;
@@ -15,6 +15,8 @@ pub(crate) struct BinaryRow(pub(crate) Row);
 
 impl<'de> Decode<'de, &'de [MySqlColumn]> for BinaryRow {
     fn decode_with(mut buf: Bytes, columns: &'de [MySqlColumn]) -> Result<Self, Error> {
+        buf.ensure_remaining(1)?;
+
         let header = buf.get_u8();
         if header != 0 {
             return Err(err_protocol!(
@@ -27,7 +29,7 @@ impl<'de> Decode<'de, &'de [MySqlColumn]> for BinaryRow {
         let offset = buf.len();
 
         let null_bitmap_len = (columns.len() + 9) / 8;
-        let null_bitmap = buf.get_bytes(null_bitmap_len);
+        let null_bitmap = buf.get_bytes(null_bitmap_len)?;
 
         let mut values = Vec::with_capacity(columns.len());
 
@@ -59,7 +61,7 @@ impl<'de> Decode<'de, &'de [MySqlColumn]> for BinaryRow {
                 | ColumnType::Bit
                 | ColumnType::Decimal
                 | ColumnType::Json
-                | ColumnType::NewDecimal => buf.get_uint_lenenc() as usize,
+                | ColumnType::NewDecimal => lenenc_size(buf.get_uint_lenenc()?)?,
 
                 ColumnType::LongLong => 8,
                 ColumnType::Long | ColumnType::Int24 => 4,
@@ -73,13 +75,23 @@ impl<'de> Decode<'de, &'de [MySqlColumn]> for BinaryRow {
                 | ColumnType::Date
                 | ColumnType::Datetime => {
                     // The size of this type is important for decoding
-                    buf[0] as usize + 1
+                    match buf.first() {
+                        Some(&len) => usize::from(len) + 1,
+                        None => 1,
+                    }
                 }
 
                 // NOTE: MySQL will never generate NULL types for non-NULL values
-                ColumnType::Null => unreachable!(),
+                ColumnType::Null => {
+                    return Err(err_protocol!(
+                        "column {} of type NULL has a non-NULL value",
+                        column_idx
+                    ));
+                }
             };
 
+            buf.ensure_remaining(size)?;
+
             let offset = offset - buf.len();
 
             values.push(Some(offset..(offset + size)));
@@ -4,7 +4,8 @@ use bitflags::bitflags;
 use bytes::{Buf, Bytes};
 
 use crate::error::Error;
-use crate::io::Decode;
+use crate::ext::ustr::UStr;
+use crate::io::{BufExt, Decode};
 use crate::mysql::io::MySqlBufExt;
 use crate::mysql::protocol::Capabilities;
 
@@ -112,6 +113,10 @@ pub(crate) struct ColumnDefinition {
     pub(crate) r#type: ColumnType,
     pub(crate) flags: ColumnFlags,
     decimals: u8,
+
+    // [MariaDB] the name of a type the column type alone does not tell, e.g. `JSON` for a
+    // `LONGTEXT` that holds JSON, or `INET6`; only sent with `MARIADB_CLIENT_EXTENDED_TYPE_INFO`
+    pub(crate) extended_type: Option<UStr>,
 }
 
 impl ColumnDefinition {
@@ -125,17 +130,32 @@ impl ColumnDefinition {
     pub(crate) fn alias(&self) -> Result<&str, Error> {
         from_utf8(&self.alias).map_err(Error::protocol)
     }
+
+    pub(crate) fn table_alias(&self) -> Result<&str, Error> {
+        from_utf8(&self.table_alias).map_err(Error::protocol)
+    }
 }
 
 impl Decode<'_, Capabilities> for ColumnDefinition {
-    fn decode_with(mut buf: Bytes, _: Capabilities) -> Result<Self, Error> {
-        let catalog = buf.get_bytes_lenenc();
-        let schema = buf.get_bytes_lenenc();
-        let table_alias = buf.get_bytes_lenenc();
-        let table = buf.get_bytes_lenenc();
-        let alias = buf.get_bytes_lenenc();
-        let name = buf.get_bytes_lenenc();
-        let _next_len = buf.get_uint_lenenc(); // always 0x0c
+    fn decode_with(mut buf: Bytes, capabilities: Capabilities) -> Result<Self, Error> {
+        let catalog = buf.get_bytes_lenenc()?;
+        let schema = buf.get_bytes_lenenc()?;
+        let table_alias = buf.get_bytes_lenenc()?;
+        let table = buf.get_bytes_lenenc()?;
+        let alias = buf.get_bytes_lenenc()?;
+        let name = buf.get_bytes_lenenc()?;
+
+        let extended_type =
+            if capabilities.contains(Capabilities::MARIADB_CLIENT_EXTENDED_TYPE_INFO) {
+                decode_extended_type(buf.get_bytes_lenenc()?)?
+            } else {
+                None
+            };
+
+        let _next_len = buf.get_uint_lenenc()?; // always 0x0c
+
+        buf.ensure_remaining(10)?;
+
         let char_set = buf.get_u16_le();
         let max_size = buf.get_u32_le();
         let type_id = buf.get_u8();
@@ -154,10 +174,37 @@ impl Decode<'_, Capabilities> for ColumnDefinition {
             r#type: ColumnType::try_from_u16(type_id)?,
             flags: ColumnFlags::from_bits_truncate(flags),
             decimals,
+            extended_type,
         })
     }
 }
 
+// https://mariadb.com/kb/en/result-set-packets/#column-definition-packet
+//
+// the extended metadata is a list of (kind, value) pairs, the kind being 0 for the name of the
+// type (e.g. `point` or `uuid`) and 1 for the format of the value (e.g. `json`); the format is
+// preferred, as a JSON column has a plain string type
+fn decode_extended_type(mut buf: Bytes) -> Result<Option<UStr>, Error> {
+    let mut type_name = None;
+    let mut format = None;
+
+    while !buf.is_empty() {
+        let kind = buf.get_u8();
+        let value = buf.get_str_lenenc()?;
+
+        match kind {
+            0 => type_name = Some(value),
+            1 => format = Some(value),
+            _ => {}
+        }
+    }
+
+    Ok(format
+        .or(type_name)
+        .filter(|name| !name.is_empty())
+        .map(|name| UStr::from(name.to_ascii_uppercase())))
+}
+
 impl ColumnType {
     pub(crate) fn name(
         self,
@@ -168,6 +215,7 @@ impl ColumnType {
         let is_binary = char_set == 63;
         let is_unsigned = flags.contains(ColumnFlags::UNSIGNED);
         let is_enum = flags.contains(ColumnFlags::ENUM);
+        let is_set = flags.contains(ColumnFlags::SET);
 
         match self {
             ColumnType::Tiny if max_size == Some(1) => "BOOLEAN",
@@ -196,8 +244,10 @@ impl ColumnType {
             ColumnType::Geometry => "GEOMETRY",
             ColumnType::Json => "JSON",
 
-            ColumnType::String if is_binary => "BINARY",
+            // flagged before the character set, which is `binary` for these in some servers
             ColumnType::String if is_enum => "ENUM",
+            ColumnType::String if is_set => "SET",
+            ColumnType::String if is_binary => "BINARY",
             ColumnType::VarChar | ColumnType::VarString if is_binary => "VARBINARY",
 
             ColumnType::String => "CHAR",
@@ -257,3 +307,29 @@ impl ColumnType {
         })
     }
 }
+
+#[test]
+fn it_decodes_extended_type_info() {
+    // `doc LONGTEXT CHECK (json_valid(doc))`, i.e. a MariaDB JSON column
+    const DEF: &[u8] = b"\x03def\x01s\x01t\x01t\x03doc\x03doc\x0d\x01\x04json\x00\x05point\x0c\x2d\x00\xff\xff\xff\xff\xfc\x10\x00\x00\x00\x00";
+
+    let def = ColumnDefinition::decode_with(
+        Bytes::from_static(DEF),
+        Capabilities::MARIADB_CLIENT_EXTENDED_TYPE_INFO,
+    )
+    .unwrap();
+
+    assert_eq!(def.extended_type.as_deref(), Some("JSON"));
+    assert_eq!(def.r#type, ColumnType::Blob);
+    assert_eq!(def.char_set, 45);
+
+    // without the capability, the same column without the extended metadata
+    const PLAIN: &[u8] =
+        b"\x03def\x01s\x01t\x01t\x03doc\x03doc\x0c\x2d\x00\xff\xff\xff\xff\xfc\x10\x00\x00\x00\x00";
+
+    let def =
+        ColumnDefinition::decode_with(Bytes::from_static(PLAIN), Capabilities::empty()).unwrap();
+
+    assert_eq!(def.extended_type, None);
+    assert_eq!(def.r#type, ColumnType::Blob);
+}
@@ -1,8 +1,8 @@
 use bytes::{Buf, Bytes};
 
 use crate::error::Error;
-use crate::io::Decode;
-use crate::mysql::io::MySqlBufExt;
+use crate::io::{BufExt, Decode};
+use crate::mysql::io::{lenenc_size, MySqlBufExt};
 use crate::mysql::protocol::Row;
 use crate::mysql::MySqlColumn;
 
@@ -17,12 +17,14 @@ impl<'de> Decode<'de, &'de [MySqlColumn]> for TextRow {
         let mut values = Vec::with_capacity(columns.len());
 
         for _ in columns {
-            if buf[0] == 0xfb {
+            if buf.first() == Some(&0xfb) {
                 // NULL is sent as 0xfb
                 values.push(None);
                 buf.advance(1);
             } else {
-                let size = buf.get_uint_lenenc() as usize;
+                let size = lenenc_size(buf.get_uint_lenenc()?)?;
+                buf.ensure_remaining(size)?;
+
                 let offset = offset - buf.len();
 
                 values.push(Some(offset..(offset + size)));
@@ -4,6 +4,9 @@ use std::iter::{Extend, IntoIterator};
 pub struct MySqlQueryResult {
     pub(super) rows_affected: u64,
     pub(super) last_insert_id: u64,
+    pub(super) lossy_utf8_conversions: u64,
+    pub(super) warnings: u64,
+    pub(super) info: Option<String>,
 }
 
 impl MySqlQueryResult {
@@ -11,9 +14,56 @@ impl MySqlQueryResult {
         self.last_insert_id
     }
 
+    /// The number of rows the statement affected, as reported by the server.
+    ///
+    /// For an `UPDATE`, this counts the rows that were changed: a row set to the values it
+    /// already has is not counted, unless the connection was opened with
+    /// [`found_rows`](crate::mysql::MySqlConnectOptions::found_rows), in which case every row
+    /// matched by the `WHERE` clause is. Both counts are available from
+    /// [`matched_rows`](Self::matched_rows) and [`changed_rows`](Self::changed_rows) either way.
     pub fn rows_affected(&self) -> u64 {
         self.rows_affected
     }
+
+    /// The number of warnings the statement raised, which `SHOW WARNINGS` lists.
+    pub fn warnings(&self) -> u64 {
+        self.warnings
+    }
+
+    /// The human-readable information the server sent about the statement, e.g.
+    /// `Rows matched: 1  Changed: 0  Warnings: 0` after an `UPDATE`, or
+    /// `Records: 2  Duplicates: 0  Warnings: 0` after an `INSERT` of several rows.
+    pub fn info(&self) -> Option<&str> {
+        self.info.as_deref()
+    }
+
+    /// The number of rows an `UPDATE` matched, from [`info`](Self::info).
+    pub fn matched_rows(&self) -> Option<u64> {
+        self.info_count("Rows matched:")
+    }
+
+    /// The number of rows an `UPDATE` changed, from [`info`](Self::info).
+    pub fn changed_rows(&self) -> Option<u64> {
+        self.info_count("Changed:")
+    }
+
+    // the number after `label` in the info
+    fn info_count(&self, label: &str) -> Option<u64> {
+        let info = self.info.as_deref()?;
+        let rest = info[info.find(label)? + label.len()..].trim_start();
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+
+        rest[..end].parse().ok()
+    }
+
+    /// The number of values in the result that were not valid UTF-8 and had invalid sequences
+    /// replaced, on a connection with
+    /// [`lossy_utf8`](crate::mysql::MySqlConnectOptions::lossy_utf8) enabled.
+    pub fn lossy_utf8_conversions(&self) -> u64 {
+        self.lossy_utf8_conversions
+    }
 }
 
 impl Extend<MySqlQueryResult> for MySqlQueryResult {
@@ -21,6 +71,12 @@ impl Extend<MySqlQueryResult> for MySqlQueryResult {
         for elem in iter {
             self.rows_affected += elem.rows_affected;
             self.last_insert_id = elem.last_insert_id;
+            self.lossy_utf8_conversions += elem.lossy_utf8_conversions;
+            self.warnings += elem.warnings;
+
+            if elem.info.is_some() {
+                self.info = elem.info;
+            }
         }
     }
 }
@@ -34,3 +90,22 @@ impl From<MySqlQueryResult> for crate::any::AnyQueryResult {
         }
     }
 }
+
+#[test]
+fn it_reads_counts_from_the_info() {
+    let result = MySqlQueryResult {
+        info: Some("Rows matched: 3  Changed: 1  Warnings: 0".into()),
+        ..MySqlQueryResult::default()
+    };
+
+    assert_eq!(result.matched_rows(), Some(3));
+    assert_eq!(result.changed_rows(), Some(1));
+
+    let result = MySqlQueryResult {
+        info: Some("Records: 2  Duplicates: 0  Warnings: 0".into()),
+        ..MySqlQueryResult::default()
+    };
+
+    assert_eq!(result.matched_rows(), None);
+    assert_eq!(MySqlQueryResult::default().changed_rows(), None);
+}
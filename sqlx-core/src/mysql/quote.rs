@@ -0,0 +1,96 @@
+use crate::error::Error;
+
+/// Quotes `name` so it can be written into SQL as an identifier, e.g. a table name that comes
+/// from configuration.
+///
+/// The name is wrapped in backticks, with any backtick inside it doubled. This works
+/// regardless of the `ANSI_QUOTES` SQL mode. Empty names and names containing a NUL byte are
+/// rejected.
+///
+/// # Example
+///
+/// ```rust
+/// # use sqlx_core::mysql::quote_identifier;
+/// assert_eq!(quote_identifier("my table")?, "`my table`");
+/// assert_eq!(quote_identifier("a`b")?, "`a``b`");
+/// # Ok::<(), sqlx_core::error::Error>(())
+/// ```
+pub fn quote_identifier(name: &str) -> Result<String, Error> {
+    crate::common::quote_identifier(name, '`', '`')
+}
+
+/// Quotes `schema` and `name` as a database-qualified identifier, e.g. `` `tenant_1`.`users` ``.
+///
+/// See [`quote_identifier`] for how each part is quoted.
+pub fn quote_qualified(schema: &str, name: &str) -> Result<String, Error> {
+    Ok(format!(
+        "{}.{}",
+        quote_identifier(schema)?,
+        quote_identifier(name)?
+    ))
+}
+
+// quotes `value` as a string literal for a session with or without the `NO_BACKSLASH_ESCAPES`
+// SQL mode; see `MySqlConnection::quote_literal`
+pub(crate) fn quote_literal(value: &str, no_backslash_escapes: bool) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+
+    quoted.push('\'');
+
+    for c in value.chars() {
+        match c {
+            '\'' if no_backslash_escapes => quoted.push_str("''"),
+            c if no_backslash_escapes => quoted.push(c),
+
+            // what `mysql_real_escape_string` escapes
+            '\0' => quoted.push_str(r"\0"),
+            '\n' => quoted.push_str(r"\n"),
+            '\r' => quoted.push_str(r"\r"),
+            '\x1a' => quoted.push_str(r"\Z"),
+            '\\' | '\'' | '"' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('\'');
+
+    quoted
+}
+
+#[test]
+fn it_quotes_identifiers() -> Result<(), Error> {
+    assert_eq!(quote_identifier("users")?, "`users`");
+    assert_eq!(quote_identifier("my table")?, "`my table`");
+    assert_eq!(quote_identifier("a`b")?, "`a``b`");
+    assert_eq!(quote_identifier(r#"a"b'c"#)?, r#"`a"b'c`"#);
+    assert_eq!(quote_identifier("ünïcødé")?, "`ünïcødé`");
+
+    assert_eq!(quote_qualified("tenant 1", "x`y")?, "`tenant 1`.`x``y`");
+
+    assert!(quote_identifier("").is_err());
+    assert!(quote_identifier("a\0b").is_err());
+    assert!(quote_qualified("a", "\0").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn it_quotes_literals() {
+    assert_eq!(quote_literal("hello", false), "'hello'");
+    assert_eq!(quote_literal("", false), "''");
+    assert_eq!(
+        quote_literal(r#"it's "C:\dir""#, false),
+        r#"'it\'s \"C:\\dir\"'"#
+    );
+    assert_eq!(quote_literal("a\0b\nc\rd\x1a", false), r"'a\0b\nc\rd\Z'");
+
+    assert_eq!(
+        quote_literal(r#"it's "C:\dir""#, true),
+        r#"'it''s "C:\dir"'"#
+    );
+    assert_eq!(quote_literal("a\0b\n", true), "'a\0b\n'");
+}
@@ -1,21 +1,34 @@
-use crate::column::ColumnIndex;
+use crate::column::{ColumnIndex, ColumnNameMatching};
+use crate::decode::Decode;
 use crate::error::Error;
 use crate::ext::ustr::UStr;
+use crate::mysql::protocol::text::{ColumnFlags, ColumnType};
 use crate::mysql::{protocol, MySql, MySqlColumn, MySqlValueFormat, MySqlValueRef};
-use crate::row::Row;
+use crate::row::{debug_row, LooseValue, Row};
+use crate::value::ValueRef;
 use crate::HashMap;
+use bytes::BytesMut;
+use std::fmt::{self, Debug, Formatter};
+use std::str;
 use std::sync::Arc;
 
 /// Implementation of [`Row`] for MySQL.
-#[derive(Debug)]
+///
+/// Cloning a row shares its data and columns, only copying the offsets of its values.
+#[derive(Clone)]
 pub struct MySqlRow {
     pub(crate) row: protocol::Row,
     pub(crate) format: MySqlValueFormat,
     pub(crate) columns: Arc<Vec<MySqlColumn>>,
     pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+    pub(crate) column_name_matching: ColumnNameMatching,
 }
 
-impl crate::row::private_row::Sealed for MySqlRow {}
+impl crate::row::private_row::Sealed for MySqlRow {
+    fn column_name_matching(&self) -> ColumnNameMatching {
+        self.column_name_matching
+    }
+}
 
 impl Row for MySqlRow {
     type Database = MySql;
@@ -39,14 +52,182 @@ impl Row for MySqlRow {
             value,
         })
     }
+
+    fn try_share(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+
+    fn data_len(&self) -> usize {
+        self.row.storage.len()
+    }
+}
+
+impl MySqlRow {
+    fn loose_value(&self, index: usize) -> LooseValue<'_> {
+        let value = match self.try_get_raw(index) {
+            Ok(value) if !value.is_null() => value,
+            _ => return LooseValue::Null,
+        };
+
+        fn decode<'r, T: Decode<'r, MySql>>(value: &MySqlValueRef<'r>) -> Option<T> {
+            T::decode(value.clone()).ok()
+        }
+
+        let ty = &value.type_info;
+
+        // the `binary` character set marks BLOB and BINARY columns
+        let is_binary = ty.char_set == 63;
+
+        let loose = match ty.r#type {
+            ColumnType::Tiny
+            | ColumnType::Short
+            | ColumnType::Long
+            | ColumnType::Int24
+            | ColumnType::LongLong
+                if ty.flags.contains(ColumnFlags::UNSIGNED) =>
+            {
+                decode(&value).map(LooseValue::UInt)
+            }
+
+            ColumnType::Tiny
+            | ColumnType::Short
+            | ColumnType::Long
+            | ColumnType::Int24
+            | ColumnType::LongLong => decode(&value).map(LooseValue::Int),
+
+            ColumnType::Year | ColumnType::Bit => decode(&value).map(LooseValue::UInt),
+
+            ColumnType::Float => decode::<f32>(&value).map(|v| LooseValue::Float(v.into())),
+            ColumnType::Double => decode(&value).map(LooseValue::Float),
+
+            ColumnType::Json => value.as_str().ok().map(LooseValue::Json),
+
+            ColumnType::VarChar
+            | ColumnType::VarString
+            | ColumnType::String
+            | ColumnType::TinyBlob
+            | ColumnType::Blob
+            | ColumnType::MediumBlob
+            | ColumnType::LongBlob
+            | ColumnType::Geometry
+                if is_binary =>
+            {
+                None
+            }
+
+            // DECIMAL, ENUM and SET are sent as strings in both formats
+            ColumnType::Decimal
+            | ColumnType::NewDecimal
+            | ColumnType::Enum
+            | ColumnType::Set
+            | ColumnType::VarChar
+            | ColumnType::VarString
+            | ColumnType::String
+            | ColumnType::TinyBlob
+            | ColumnType::Blob
+            | ColumnType::MediumBlob
+            | ColumnType::LongBlob => value.as_str().ok().map(LooseValue::Text),
+
+            _ => match value.format {
+                MySqlValueFormat::Text => value.as_str().ok().map(LooseValue::Text),
+                MySqlValueFormat::Binary => None,
+            },
+        };
+
+        loose.unwrap_or_else(|| LooseValue::Binary(value.value.unwrap_or_default()))
+    }
+}
+
+impl Debug for MySqlRow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        debug_row(f, "MySqlRow", self, |index| self.loose_value(index))
+    }
+}
+
+#[cfg(feature = "json")]
+impl crate::row::RowExt for MySqlRow {
+    fn to_json(&self) -> serde_json::Map<String, serde_json::Value> {
+        use crate::column::Column;
+        use crate::type_info::TypeInfo;
+
+        self.columns()
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let value = self.loose_value(index).to_json(column.type_info().name());
+
+                (column.name().to_owned(), value)
+            })
+            .collect()
+    }
+}
+
+/// Replaces invalid UTF-8 in the text values of `row` with U+FFFD, returning the number of
+/// values that were changed. Values of binary columns are left as they are.
+pub(crate) fn replace_invalid_utf8(row: &mut protocol::Row, columns: &[MySqlColumn]) -> u64 {
+    let is_text = |column: &MySqlColumn| {
+        let ty = &column.type_info;
+
+        matches!(
+            ty.r#type,
+            ColumnType::VarChar
+                | ColumnType::VarString
+                | ColumnType::String
+                | ColumnType::TinyBlob
+                | ColumnType::Blob
+                | ColumnType::MediumBlob
+                | ColumnType::LongBlob
+                | ColumnType::Enum
+                | ColumnType::Set
+                | ColumnType::Json
+        ) && !ty.is_binary_string()
+    };
+
+    let invalid: Vec<bool> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            is_text(column)
+                && matches!(row.get(index), Some(value) if str::from_utf8(value).is_err())
+        })
+        .collect();
+
+    let replaced = invalid.iter().filter(|invalid| **invalid).count();
+
+    if replaced == 0 {
+        return 0;
+    }
+
+    // the row is only copied when it has to be changed
+    let mut storage = BytesMut::with_capacity(row.storage.len());
+
+    for (range, invalid) in row.values.iter_mut().zip(invalid) {
+        if let Some(range) = range {
+            let value = &row.storage[range.clone()];
+            let start = storage.len();
+
+            if invalid {
+                storage.extend_from_slice(String::from_utf8_lossy(value).as_bytes());
+            } else {
+                storage.extend_from_slice(value);
+            }
+
+            *range = start..storage.len();
+        }
+    }
+
+    row.storage = storage.freeze();
+
+    replaced as u64
 }
 
 impl ColumnIndex<MySqlRow> for &'_ str {
     fn index(&self, row: &MySqlRow) -> Result<usize, Error> {
-        row.column_names
-            .get(*self)
-            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
-            .map(|v| *v)
+        row.column_name_matching.find(
+            self,
+            row.column_names.get(*self).copied(),
+            row.columns.iter().map(|column| &*column.name),
+        )
     }
 }
 
@@ -61,3 +242,44 @@ impl From<MySqlRow> for crate::any::AnyRow {
         }
     }
 }
+
+#[test]
+fn it_replaces_invalid_utf8_in_text_columns() {
+    use crate::mysql::MySqlTypeInfo;
+    use bytes::Bytes;
+
+    let column = |ordinal: usize, type_info: MySqlTypeInfo| MySqlColumn {
+        ordinal,
+        name: UStr::Static(""),
+        type_info,
+        flags: None,
+        table: None,
+    };
+
+    let text = MySqlTypeInfo {
+        char_set: 224,
+        flags: ColumnFlags::empty(),
+        ..MySqlTypeInfo::binary(ColumnType::VarString)
+    };
+
+    let columns = [
+        column(0, text.clone()),
+        column(1, MySqlTypeInfo::binary(ColumnType::Blob)),
+        column(2, text.clone()),
+        column(3, text),
+    ];
+
+    let mut row = protocol::Row {
+        storage: Bytes::from_static(b"caf\xe9\xff\xfeok"),
+        values: vec![Some(0..4), Some(4..6), Some(6..8), None],
+    };
+
+    assert_eq!(replace_invalid_utf8(&mut row, &columns), 1);
+
+    assert_eq!(row.get(0), Some("caf\u{fffd}".as_bytes()));
+    assert_eq!(row.get(1), Some(&b"\xff\xfe"[..]));
+    assert_eq!(row.get(2), Some(&b"ok"[..]));
+    assert_eq!(row.get(3), None);
+
+    assert_eq!(replace_invalid_utf8(&mut row, &columns), 0);
+}
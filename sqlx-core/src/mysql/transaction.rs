@@ -1,14 +1,24 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+
 use futures_core::future::BoxFuture;
 
+use crate::encode::Encode;
 use crate::error::Error;
-use crate::executor::Executor;
 use crate::mysql::connection::Busy;
 use crate::mysql::protocol::text::Query;
-use crate::mysql::{MySql, MySqlConnection};
+use crate::mysql::{MySql, MySqlArguments, MySqlConnection};
+use crate::query::query_with;
+use crate::row::Row;
 use crate::transaction::{
-    begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
+    begin_ansi_transaction_sql, commit_ansi_transaction_sql, quote_transaction_id,
+    rollback_ansi_transaction_sql, validate_setting_name, PreparedTransaction, Transaction,
     TransactionManager,
 };
+use crate::types::Type;
+
+// the limit MySQL puts on the length of the `gtrid` part of an XA transaction id
+const MAX_GID_LEN: usize = 64;
 
 /// Implementation of [`TransactionManager`] for MySQL.
 pub struct MySqlTransactionManager;
@@ -20,7 +30,19 @@ impl TransactionManager for MySqlTransactionManager {
         Box::pin(async move {
             let depth = conn.transaction_depth;
 
-            conn.execute(&*begin_ansi_transaction_sql(depth)).await?;
+            let sql = match &conn.xa_transaction {
+                Some(xid) if depth == 0 => Cow::Owned(format!("XA START {}", xid)),
+                _ => begin_ansi_transaction_sql(depth),
+            };
+
+            if let Err(error) = conn.execute(&*sql).await {
+                if depth == 0 {
+                    conn.xa_transaction = None;
+                }
+
+                return Err(error);
+            }
+
             conn.transaction_depth = depth + 1;
 
             Ok(())
@@ -32,8 +54,20 @@ impl TransactionManager for MySqlTransactionManager {
             let depth = conn.transaction_depth;
 
             if depth > 0 {
-                conn.execute(&*commit_ansi_transaction_sql(depth)).await?;
+                let sql = match &conn.xa_transaction {
+                    Some(xid) if depth == 1 => {
+                        Cow::Owned(format!("XA END {0}; XA COMMIT {0} ONE PHASE", xid))
+                    }
+                    _ => commit_ansi_transaction_sql(depth),
+                };
+
+                conn.execute(&*sql).await?;
                 conn.transaction_depth = depth - 1;
+                end_xa_transaction(conn, depth);
+
+                if let Some(sql) = end_local_variables(conn, depth, true) {
+                    queue_query(conn, &sql);
+                }
             }
 
             Ok(())
@@ -45,8 +79,15 @@ impl TransactionManager for MySqlTransactionManager {
             let depth = conn.transaction_depth;
 
             if depth > 0 {
-                conn.execute(&*rollback_ansi_transaction_sql(depth)).await?;
+                let sql = rollback_sql(conn, depth);
+
+                conn.execute(&*sql).await?;
                 conn.transaction_depth = depth - 1;
+                end_xa_transaction(conn, depth);
+
+                if let Some(sql) = end_local_variables(conn, depth, false) {
+                    queue_query(conn, &sql);
+                }
             }
 
             Ok(())
@@ -57,12 +98,288 @@ impl TransactionManager for MySqlTransactionManager {
         let depth = conn.transaction_depth;
 
         if depth > 0 {
-            conn.stream.busy = Busy::Result;
-            conn.stream.sequence_id = 0;
-            conn.stream
-                .write_packet(Query(&*rollback_ansi_transaction_sql(depth)));
+            // nothing is left to roll back once the server has ended the transaction itself,
+            // e.g. with the implicit commit of a DDL statement; its savepoints are gone as well
+            let mut sql = if conn.xa_transaction.is_some() || conn.in_server_transaction() {
+                rollback_sql(conn, depth).into_owned()
+            } else {
+                String::new()
+            };
+
+            // the restore has to go out in the same packet, as only one result is awaited
+            if let Some(restore) = end_local_variables(conn, depth, false) {
+                if !sql.is_empty() {
+                    sql.push_str("; ");
+                }
+
+                sql.push_str(&restore);
+            }
+
+            if !sql.is_empty() {
+                queue_query(conn, &sql);
+            }
 
             conn.transaction_depth = depth - 1;
+            end_xa_transaction(conn, depth);
+        }
+    }
+}
+
+// an XA transaction is ended before it is rolled back
+fn rollback_sql(conn: &MySqlConnection, depth: usize) -> Cow<'static, str> {
+    match &conn.xa_transaction {
+        Some(xid) if depth == 1 => Cow::Owned(format!("XA END {0}; XA ROLLBACK {0}", xid)),
+        _ => rollback_ansi_transaction_sql(depth),
+    }
+}
+
+// the XA transaction, if any, is over once the transaction at depth 1 is
+fn end_xa_transaction(conn: &mut MySqlConnection, depth: usize) {
+    if depth == 1 {
+        conn.xa_transaction = None;
+    }
+}
+
+// writes a query to be sent with the next command, which waits for its result first
+fn queue_query(conn: &mut MySqlConnection, sql: &str) {
+    conn.stream.busy = Busy::Result;
+    conn.stream.sequence_id = 0;
+    conn.stream.write_packet(Query(sql));
+}
+
+// returns the statement restoring the session variables that go out of scope when the
+// transaction or savepoint at `depth` ends, if there are any
+//
+// like `SET LOCAL` in PostgreSQL, variables set within a savepoint that is released stay set
+// until the enclosing transaction ends
+fn end_local_variables(conn: &mut MySqlConnection, depth: usize, commit: bool) -> Option<String> {
+    if commit && depth > 1 {
+        for (variable_depth, _) in &mut conn.local_variables {
+            if *variable_depth == depth {
+                *variable_depth = depth - 1;
+            }
+        }
+
+        return None;
+    }
+
+    // variables are pushed in order of depth, so the ones ending here are at the end
+    let start = conn
+        .local_variables
+        .iter()
+        .position(|(variable_depth, _)| *variable_depth >= depth)?;
+
+    let mut restore = String::from("SET ");
+
+    // restore in reverse order, so a variable set more than once ends up with its first
+    // saved value
+    for (i, (_, name)) in conn.local_variables.iter().enumerate().skip(start).rev() {
+        let _ = write!(restore, "@@SESSION.{} = @_sqlx_local_{}, ", name, i);
+    }
+
+    for i in start..conn.local_variables.len() {
+        let _ = write!(restore, "@_sqlx_local_{} = NULL, ", i);
+    }
+
+    restore.truncate(restore.len() - 2);
+    conn.local_variables.truncate(start);
+
+    Some(restore)
+}
+
+impl<'c> Transaction<'c, MySql> {
+    /// Sets a session variable until the end of this transaction, like `SET LOCAL` in
+    /// PostgreSQL.
+    ///
+    /// MySQL has no transaction-scoped settings, so this saves the current value of
+    /// `@@SESSION.name` in a user variable and sets the new value, which is bound as a
+    /// parameter. The saved value is restored together with the next command after this
+    /// transaction commits or rolls back, or after rolling back a savepoint established
+    /// before the variable was set. The name must be one or more identifiers separated by `.`.
+    ///
+    /// ```rust,no_run
+    /// # async fn f(conn: &mut sqlx_core::mysql::MySqlConnection) -> Result<(), sqlx_core::error::Error> {
+    /// use sqlx_core::connection::Connection;
+    ///
+    /// let mut tx = conn.begin().await?;
+    /// tx.set_local("max_execution_time", 5000).await?;
+    /// tx.set_local("time_zone", "+00:00").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_local<'q, T>(&mut self, name: &str, value: T) -> Result<(), Error>
+    where
+        T: 'q + Send + Encode<'q, MySql> + Type<MySql>,
+    {
+        self.set_locals(Some((name, value))).await
+    }
+
+    /// Sets several session variables until the end of this transaction, in a single round
+    /// trip; see [`set_local`](Self::set_local).
+    pub async fn set_locals<'q, I, N, T>(&mut self, settings: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (N, T)>,
+        N: AsRef<str>,
+        T: 'q + Send + Encode<'q, MySql> + Type<MySql>,
+    {
+        let first = self.local_variables.len();
+        let mut names = Vec::new();
+        let mut sql = String::from("SET ");
+        let mut arguments = MySqlArguments::default();
+
+        for (name, value) in settings {
+            let name = name.as_ref();
+            validate_setting_name(name)?;
+
+            if !names.is_empty() {
+                sql.push_str(", ");
+            }
+
+            let _ = write!(
+                sql,
+                "@_sqlx_local_{0} = @@SESSION.{1}, @@SESSION.{1} = ?",
+                first + names.len(),
+                name
+            );
+
+            arguments.add(value);
+            names.push(name.to_owned());
+        }
+
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        // the statement differs with the number of variables already set, so it is not cached
+        query_with(&sql, arguments)
+            .persistent(false)
+            .execute(&mut **self)
+            .await?;
+
+        let depth = self.transaction_depth;
+
+        self.local_variables
+            .extend(names.into_iter().map(|name| (depth, name)));
+
+        Ok(())
+    }
+
+    /// Prepares this transaction for two-phase commit with `XA PREPARE`.
+    ///
+    /// MySQL can only prepare XA transactions, so the transaction must have been started with
+    /// [`MySqlConnection::begin_two_phase`] and the same id. Once prepared, it is finished with
+    /// [`MySqlConnection::commit_prepared`] or [`MySqlConnection::rollback_prepared`], from
+    /// this or any other connection.
+    pub async fn prepare_two_phase(mut self, gid: &str) -> Result<PreparedTransaction, Error> {
+        let xid = quote_transaction_id(gid, MAX_GID_LEN)?;
+
+        if self.transaction_depth != 1 {
+            return Err(Error::Configuration(
+                "a savepoint cannot be prepared for two-phase commit".into(),
+            ));
+        }
+
+        if self.xa_transaction.as_ref() != Some(&xid) {
+            return Err(Error::Configuration(
+                "MySQL can only prepare XA transactions; start the transaction with \
+                 `MySqlConnection::begin_two_phase` and the same id"
+                    .into(),
+            ));
+        }
+
+        self.execute(&*format!("XA END {0}; XA PREPARE {0}", xid))
+            .await?;
+
+        // the session is no longer in a transaction, and the prepared one must outlive `self`
+        self.transaction_depth = 0;
+        self.xa_transaction = None;
+
+        if let Some(sql) = end_local_variables(&mut self, 1, false) {
+            queue_query(&mut self, &sql);
+        }
+
+        self.finish();
+
+        Ok(PreparedTransaction::new(gid.to_owned()))
+    }
+}
+
+impl MySqlConnection {
+    /// Starts an XA transaction with `XA START`, which can be prepared for two-phase commit
+    /// with [`prepare_two_phase`](Transaction::<MySql>::prepare_two_phase).
+    ///
+    /// Until it is prepared, the transaction behaves like one started with
+    /// [`begin`](crate::connection::Connection::begin): committing it commits in one phase,
+    /// and dropping it rolls it back.
+    ///
+    /// ```rust,no_run
+    /// # async fn f(conn: &mut sqlx_core::mysql::MySqlConnection) -> Result<(), sqlx_core::error::Error> {
+    /// let tx = conn.begin_two_phase("order-1234").await?;
+    /// // ...
+    /// let prepared = tx.prepare_two_phase("order-1234").await?;
+    ///
+    /// // once every other participant has prepared too
+    /// conn.commit_prepared(prepared.gid()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn begin_two_phase(&mut self, gid: &str) -> Result<Transaction<'_, MySql>, Error> {
+        let xid = quote_transaction_id(gid, MAX_GID_LEN)?;
+
+        if self.transaction_depth > 0 {
+            return Err(Error::Configuration(
+                "an XA transaction cannot be started within another transaction".into(),
+            ));
+        }
+
+        self.xa_transaction = Some(xid);
+
+        Transaction::begin(self).await
+    }
+
+    /// Commits an XA transaction prepared with
+    /// [`prepare_two_phase`](Transaction::<MySql>::prepare_two_phase), as `XA COMMIT` does.
+    ///
+    /// This works from any connection, but not from within a transaction.
+    pub async fn commit_prepared(&mut self, gid: &str) -> Result<(), Error> {
+        let sql = format!("XA COMMIT {}", quote_transaction_id(gid, MAX_GID_LEN)?);
+        self.execute(&*sql).await?;
+
+        Ok(())
+    }
+
+    /// Rolls back an XA transaction prepared with
+    /// [`prepare_two_phase`](Transaction::<MySql>::prepare_two_phase), as `XA ROLLBACK` does;
+    /// see [`commit_prepared`](Self::commit_prepared).
+    pub async fn rollback_prepared(&mut self, gid: &str) -> Result<(), Error> {
+        let sql = format!("XA ROLLBACK {}", quote_transaction_id(gid, MAX_GID_LEN)?);
+        self.execute(&*sql).await?;
+
+        Ok(())
+    }
+
+    /// Lists the XA transactions prepared on the server and not yet committed or rolled back,
+    /// as `XA RECOVER` does, e.g. to recover after a coordinator crashed.
+    ///
+    /// Only transactions with an id of the form used by
+    /// [`begin_two_phase`](Self::begin_two_phase) are listed, i.e. those without a branch
+    /// qualifier or format id of their own.
+    pub async fn list_prepared(&mut self) -> Result<Vec<PreparedTransaction>, Error> {
+        let mut prepared = Vec::new();
+
+        for row in self.fetch_all("XA RECOVER").await? {
+            let format_id: i64 = row.try_get(0)?;
+            let bqual_length: i64 = row.try_get(2)?;
+
+            if format_id != 1 || bqual_length != 0 {
+                continue;
+            }
+
+            if let Ok(gid) = String::from_utf8(row.try_get(3)?) {
+                prepared.push(PreparedTransaction::new(gid));
+            }
         }
+
+        Ok(prepared)
     }
 }
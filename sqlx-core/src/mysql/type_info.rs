@@ -1,5 +1,6 @@
 use std::fmt::{self, Display, Formatter};
 
+use crate::ext::ustr::UStr;
 use crate::mysql::protocol::text::{ColumnDefinition, ColumnFlags, ColumnType};
 use crate::type_info::TypeInfo;
 
@@ -14,6 +15,10 @@ pub struct MySqlTypeInfo {
     // [max_size] for integer types, this is (M) in BIT(M) or TINYINT(M)
     #[cfg_attr(feature = "offline", serde(default))]
     pub(crate) max_size: Option<u32>,
+
+    // [MariaDB] the name of the type as the server described it, e.g. `JSON` or `INET6`
+    #[cfg_attr(feature = "offline", serde(default))]
+    pub(crate) extended: Option<UStr>,
 }
 
 impl MySqlTypeInfo {
@@ -23,6 +28,7 @@ impl MySqlTypeInfo {
             flags: ColumnFlags::BINARY,
             char_set: 63,
             max_size: None,
+            extended: None,
         }
     }
 
@@ -33,11 +39,16 @@ impl MySqlTypeInfo {
             flags: ColumnFlags::BINARY,
             char_set: 63,
             max_size: None,
+            extended: None,
         }
     }
 
     #[doc(hidden)]
     pub fn __type_feature_gate(&self) -> Option<&'static str> {
+        if self.extended.as_deref() == Some("JSON") {
+            return Some("json");
+        }
+
         match self.r#type {
             ColumnType::Date | ColumnType::Time | ColumnType::Timestamp | ColumnType::Datetime => {
                 Some("time")
@@ -50,12 +61,34 @@ impl MySqlTypeInfo {
         }
     }
 
+    /// Whether this is a string type with the `binary` character set, i.e. `BINARY`,
+    /// `VARBINARY` or one of the `BLOB` types.
+    pub(crate) fn is_binary_string(&self) -> bool {
+        matches!(
+            self.r#type,
+            ColumnType::VarChar
+                | ColumnType::VarString
+                | ColumnType::String
+                | ColumnType::TinyBlob
+                | ColumnType::Blob
+                | ColumnType::MediumBlob
+                | ColumnType::LongBlob
+        ) && self.char_set == 63
+            && !self.flags.intersects(ColumnFlags::ENUM | ColumnFlags::SET)
+    }
+
+    #[doc(hidden)]
+    pub fn __is_binary_string(&self) -> bool {
+        self.is_binary_string()
+    }
+
     pub(crate) fn from_column(column: &ColumnDefinition) -> Self {
         Self {
             r#type: column.r#type,
             flags: column.flags,
             char_set: column.char_set,
             max_size: Some(column.max_size),
+            extended: column.extended_type.clone(),
         }
     }
 }
@@ -72,7 +105,10 @@ impl TypeInfo for MySqlTypeInfo {
     }
 
     fn name(&self) -> &str {
-        self.r#type.name(self.char_set, self.flags, self.max_size)
+        match &self.extended {
+            Some(name) => name,
+            None => self.r#type.name(self.char_set, self.flags, self.max_size),
+        }
     }
 }
 
@@ -1,7 +1,7 @@
 use bigdecimal::BigDecimal;
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::mysql::io::MySqlBufMutExt;
 use crate::mysql::protocol::text::ColumnType;
@@ -15,10 +15,10 @@ impl Type<MySql> for BigDecimal {
 }
 
 impl Encode<'_, MySql> for BigDecimal {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.put_str_lenenc(&self.to_string());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
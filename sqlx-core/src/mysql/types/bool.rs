@@ -1,5 +1,5 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::mysql::{
     protocol::text::{ColumnFlags, ColumnType},
@@ -14,6 +14,7 @@ impl Type<MySql> for bool {
             flags: ColumnFlags::BINARY | ColumnFlags::UNSIGNED,
             char_set: 63,
             max_size: Some(1),
+            extended: None,
             r#type: ColumnType::Tiny,
         }
     }
@@ -32,12 +33,14 @@ impl Type<MySql> for bool {
 }
 
 impl Encode<'_, MySql> for bool {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         <i8 as Encode<MySql>>::encode(*self as i8, buf)
     }
 }
 
 impl Decode<'_, MySql> for bool {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(<i8 as Decode<MySql>>::decode(value)? != 0)
     }
@@ -1,5 +1,7 @@
+use std::convert::TryFrom;
+
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::mysql::io::MySqlBufMutExt;
 use crate::mysql::protocol::text::ColumnType;
@@ -27,10 +29,10 @@ impl Type<MySql> for [u8] {
 }
 
 impl Encode<'_, MySql> for &'_ [u8] {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.put_bytes_lenenc(self);
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
@@ -51,7 +53,7 @@ impl Type<MySql> for Vec<u8> {
 }
 
 impl Encode<'_, MySql> for Vec<u8> {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         <&[u8] as Encode<MySql>>::encode(&**self, buf)
     }
 }
@@ -61,3 +63,28 @@ impl Decode<'_, MySql> for Vec<u8> {
         <&[u8] as Decode<MySql>>::decode(value).map(ToOwned::to_owned)
     }
 }
+
+impl<const N: usize> Type<MySql> for [u8; N] {
+    fn type_info() -> MySqlTypeInfo {
+        <[u8] as Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        <&[u8] as Type<MySql>>::compatible(ty)
+    }
+}
+
+impl<const N: usize> Encode<'_, MySql> for [u8; N] {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
+        <&[u8] as Encode<MySql>>::encode(&self[..], buf)
+    }
+}
+
+impl<const N: usize> Decode<'_, MySql> for [u8; N] {
+    fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let bytes = <&[u8] as Decode<MySql>>::decode(value)?;
+
+        <[u8; N]>::try_from(bytes)
+            .map_err(|_| format!("expected {} bytes, got {}", N, bytes.len()).into())
+    }
+}
@@ -4,7 +4,7 @@ use bytes::Buf;
 use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::{BoxDynError, UnexpectedNullError};
 use crate::mysql::protocol::text::ColumnType;
 use crate::mysql::type_info::MySqlTypeInfo;
@@ -22,7 +22,7 @@ impl Type<MySql> for DateTime<Utc> {
 }
 
 impl Encode<'_, MySql> for DateTime<Utc> {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         Encode::<MySql>::encode(&self.naive_utc(), buf)
     }
 }
@@ -42,7 +42,7 @@ impl Type<MySql> for NaiveTime {
 }
 
 impl Encode<'_, MySql> for NaiveTime {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         let len = Encode::<MySql>::size_hint(self) - 1;
         buf.push(len as u8);
 
@@ -55,7 +55,7 @@ impl Encode<'_, MySql> for NaiveTime {
 
         encode_time(self, len > 9, buf);
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 
     fn size_hint(&self) -> usize {
@@ -111,12 +111,12 @@ impl Type<MySql> for NaiveDate {
 }
 
 impl Encode<'_, MySql> for NaiveDate {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.push(4);
 
-        encode_date(self, buf);
+        encode_date(self, buf)?;
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 
     fn size_hint(&self) -> usize {
@@ -146,17 +146,17 @@ impl Type<MySql> for NaiveDateTime {
 }
 
 impl Encode<'_, MySql> for NaiveDateTime {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         let len = Encode::<MySql>::size_hint(self) - 1;
         buf.push(len as u8);
 
-        encode_date(&self.date(), buf);
+        encode_date(&self.date(), buf)?;
 
         if len > 4 {
             encode_time(&self.time(), len > 8, buf);
         }
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 
     fn size_hint(&self) -> usize {
@@ -207,14 +207,16 @@ impl<'r> Decode<'r, MySql> for NaiveDateTime {
     }
 }
 
-fn encode_date(date: &NaiveDate, buf: &mut Vec<u8>) {
+fn encode_date(date: &NaiveDate, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
     // MySQL supports years from 1000 - 9999
     let year = u16::try_from(date.year())
-        .unwrap_or_else(|_| panic!("NaiveDateTime out of range for Mysql: {}", date));
+        .map_err(|_| format!("NaiveDateTime out of range for Mysql: {}", date))?;
 
     buf.extend_from_slice(&year.to_le_bytes());
     buf.push(date.month() as u8);
     buf.push(date.day() as u8);
+
+    Ok(())
 }
 
 fn decode_date(mut buf: &[u8]) -> Option<NaiveDate> {
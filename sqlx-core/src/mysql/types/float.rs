@@ -1,7 +1,7 @@
 use byteorder::{ByteOrder, LittleEndian};
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::mysql::protocol::text::ColumnType;
 use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueFormat, MySqlValueRef};
@@ -32,22 +32,24 @@ impl Type<MySql> for f64 {
 }
 
 impl Encode<'_, MySql> for f32 {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_le_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Encode<'_, MySql> for f64 {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_le_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, MySql> for f32 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(match value.format() {
             MySqlValueFormat::Binary => {
@@ -68,6 +70,8 @@ impl Decode<'_, MySql> for f32 {
 }
 
 impl Decode<'_, MySql> for f64 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(match value.format() {
             MySqlValueFormat::Binary => LittleEndian::read_f64(value.as_bytes()?),
@@ -1,23 +1,78 @@
-use std::convert::TryInto;
+use std::convert::TryFrom;
 
 use byteorder::{ByteOrder, LittleEndian};
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::mysql::protocol::text::{ColumnFlags, ColumnType};
 use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueFormat, MySqlValueRef};
 use crate::types::Type;
 
-fn int_compatible(ty: &MySqlTypeInfo) -> bool {
-    matches!(
-        ty.r#type,
-        ColumnType::Tiny
-            | ColumnType::Short
-            | ColumnType::Long
-            | ColumnType::Int24
-            | ColumnType::LongLong
-    ) && !ty.flags.contains(ColumnFlags::UNSIGNED)
+/// The column types that every Rust integer type can be decoded from.
+///
+/// Whether a value fits the Rust type is checked when it is decoded, reading it as signed or
+/// unsigned according to the column's `UNSIGNED` flag. A `BIGINT UNSIGNED` holding more than
+/// `i64::MAX` therefore decodes into `u64` but not into `i64`.
+const INT_TYPES: &[ColumnType] = &[
+    ColumnType::Tiny,
+    ColumnType::Short,
+    ColumnType::Long,
+    ColumnType::Int24,
+    ColumnType::LongLong,
+    ColumnType::Year,
+    ColumnType::Bit,
+];
+
+pub(super) fn int_compatible(ty: &MySqlTypeInfo) -> bool {
+    INT_TYPES.contains(&ty.r#type)
+}
+
+pub(super) fn int_decode<T>(value: MySqlValueRef<'_>) -> Result<T, BoxDynError>
+where
+    T: TryFrom<i128>,
+{
+    let int: i128 = if value.type_info.r#type == ColumnType::Bit {
+        // NOTE: Regardless of the value format, there is raw binary data here
+
+        let buf = value.as_bytes()?;
+        let mut value: u64 = 0;
+
+        for b in buf {
+            value = (*b as u64) | (value << 8);
+        }
+
+        value.into()
+    } else {
+        match value.format() {
+            MySqlValueFormat::Text => value.as_str()?.parse()?,
+
+            MySqlValueFormat::Binary => {
+                let buf = value.as_bytes()?;
+
+                if buf.is_empty() || buf.len() > 8 {
+                    return Err(
+                        format!("expected 1 to 8 bytes for an integer, got {}", buf.len()).into(),
+                    );
+                }
+
+                if value.type_info.flags.contains(ColumnFlags::UNSIGNED) {
+                    LittleEndian::read_uint(buf, buf.len()).into()
+                } else {
+                    LittleEndian::read_int(buf, buf.len()).into()
+                }
+            }
+        }
+    };
+
+    T::try_from(int).map_err(|_| {
+        format!(
+            "value {} is out of range for {}",
+            int,
+            std::any::type_name::<T>()
+        )
+        .into()
+    })
 }
 
 impl Type<MySql> for i8 {
@@ -61,67 +116,218 @@ impl Type<MySql> for i64 {
 }
 
 impl Encode<'_, MySql> for i8 {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_le_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Encode<'_, MySql> for i16 {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_le_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Encode<'_, MySql> for i32 {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_le_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Encode<'_, MySql> for i64 {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_le_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
-fn int_decode(value: MySqlValueRef<'_>) -> Result<i64, BoxDynError> {
-    Ok(match value.format() {
-        MySqlValueFormat::Text => value.as_str()?.parse()?,
-        MySqlValueFormat::Binary => {
-            let buf = value.as_bytes()?;
-            LittleEndian::read_int(buf, buf.len())
-        }
-    })
-}
-
 impl Decode<'_, MySql> for i8 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        int_decode(value)?.try_into().map_err(Into::into)
+        int_decode(value)
     }
 }
 
 impl Decode<'_, MySql> for i16 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        int_decode(value)?.try_into().map_err(Into::into)
+        int_decode(value)
     }
 }
 
 impl Decode<'_, MySql> for i32 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        int_decode(value)?.try_into().map_err(Into::into)
+        int_decode(value)
     }
 }
 
 impl Decode<'_, MySql> for i64 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
         int_decode(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::fmt::Debug;
+
+    use crate::decode::Decode;
+    use crate::mysql::protocol::text::{ColumnFlags, ColumnType};
+    use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueFormat, MySqlValueRef};
+    use crate::types::Type;
+
+    // column type, size in the binary protocol
+    const COLUMNS: &[(ColumnType, usize)] = &[
+        (ColumnType::Tiny, 1),
+        (ColumnType::Short, 2),
+        (ColumnType::Int24, 4),
+        (ColumnType::Long, 4),
+        (ColumnType::LongLong, 8),
+    ];
+
+    // the edges of every integer type plus a deterministic spread of values in between
+    fn samples() -> Vec<i128> {
+        let mut samples = vec![0, 1, -1];
+
+        for &(min, max) in &[
+            (i8::MIN as i128, u8::MAX as i128),
+            (i16::MIN as i128, u16::MAX as i128),
+            (i32::MIN as i128, u32::MAX as i128),
+            (i64::MIN as i128, u64::MAX as i128),
+        ] {
+            samples.extend(&[min - 1, min, min + 1, max - 1, max, max + 1]);
+            samples.extend(&[max / 2, max / 2 + 1]);
+        }
+
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..1000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            // shift by a varying amount so every magnitude is represented
+            samples.push((state >> (state % 64)) as i128);
+            samples.push(((state as i64) >> (state % 64)) as i128);
+        }
+
+        samples
+    }
+
+    fn type_info(ty: ColumnType, unsigned: bool) -> MySqlTypeInfo {
+        let mut info = MySqlTypeInfo::binary(ty);
+
+        if unsigned {
+            info.flags |= ColumnFlags::UNSIGNED;
+        }
+
+        info
+    }
+
+    fn decode<T>(type_info: &MySqlTypeInfo, format: MySqlValueFormat, buf: &[u8]) -> Option<T>
+    where
+        T: for<'r> Decode<'r, MySql>,
+    {
+        T::decode(MySqlValueRef {
+            value: Some(buf),
+            row: None,
+            type_info: type_info.clone(),
+            format,
+        })
+        .ok()
+    }
+
+    fn check<T>()
+    where
+        T: for<'r> Decode<'r, MySql> + Type<MySql> + TryFrom<i128> + PartialEq + Debug,
+    {
+        for &(ty, size) in COLUMNS {
+            for &unsigned in &[false, true] {
+                let info = type_info(ty, unsigned);
+                let bits = size as u32 * 8;
+                let (min, max) = if unsigned {
+                    (0, (1_i128 << bits) - 1)
+                } else {
+                    (-(1_i128 << (bits - 1)), (1_i128 << (bits - 1)) - 1)
+                };
+
+                assert!(T::compatible(&info));
+
+                // skip values the column itself could not hold
+                for value in samples().into_iter().filter(|v| (min..=max).contains(v)) {
+                    let expected = T::try_from(value).ok();
+                    let binary = &value.to_le_bytes()[..size];
+                    let text = value.to_string();
+
+                    assert_eq!(
+                        decode::<T>(&info, MySqlValueFormat::Binary, binary),
+                        expected,
+                        "{} in binary from {:?} (unsigned: {})",
+                        value,
+                        ty,
+                        unsigned
+                    );
+                    assert_eq!(
+                        decode::<T>(&info, MySqlValueFormat::Text, text.as_bytes()),
+                        expected,
+                        "{} in text from {:?} (unsigned: {})",
+                        value,
+                        ty,
+                        unsigned
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_checks_conversions_for_signed_integers() {
+        check::<i8>();
+        check::<i16>();
+        check::<i32>();
+        check::<i64>();
+    }
+
+    #[test]
+    fn it_checks_conversions_for_unsigned_integers() {
+        check::<u8>();
+        check::<u16>();
+        check::<u32>();
+        check::<u64>();
+    }
+
+    #[test]
+    fn it_decodes_unsigned_bigint_above_i64_max() {
+        let info = type_info(ColumnType::LongLong, true);
+        let buf = u64::MAX.to_le_bytes();
+
+        assert_eq!(
+            decode::<u64>(&info, MySqlValueFormat::Binary, &buf),
+            Some(u64::MAX)
+        );
+
+        let err = <i64 as Decode<MySql>>::decode(MySqlValueRef {
+            value: Some(&buf),
+            row: None,
+            type_info: info,
+            format: MySqlValueFormat::Binary,
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "value 18446744073709551615 is out of range for i64"
+        );
+    }
+}
@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::mysql::protocol::text::ColumnType;
+use crate::mysql::types::json_binary;
 use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueRef};
 use crate::types::{Json, Type};
 
@@ -26,9 +27,8 @@ impl<T> Encode<'_, MySql> for Json<T>
 where
     T: Serialize,
 {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
-        let json_string_value =
-            serde_json::to_string(&self.0).expect("serde_json failed to convert to string");
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
+        let json_string_value = serde_json::to_string(&self.0)?;
 
         <&str as Encode<MySql>>::encode(json_string_value.as_str(), buf)
     }
@@ -39,6 +39,15 @@ where
     T: 'r + Deserialize<'r>,
 {
     fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let bytes = value.as_bytes()?;
+
+        // some servers send the document in the binary format MySQL stores it in
+        if json_binary::is_binary(bytes) {
+            return T::deserialize(json_binary::decode(bytes)?)
+                .map(Json)
+                .map_err(Into::into);
+        }
+
         let string_value = <&str as Decode<MySql>>::decode(value)?;
 
         serde_json::from_str(&string_value)
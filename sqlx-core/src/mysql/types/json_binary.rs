@@ -0,0 +1,612 @@
+use std::convert::TryFrom;
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+use std::str;
+
+use serde::de::IgnoredAny;
+use serde_json::{Map, Number, Value};
+
+use crate::mysql::protocol::text::ColumnType;
+
+// The binary format MySQL stores JSON documents in, which some servers send to the client as
+// is; see `json_binary.h` in the sources of MySQL. A document is the type of its root value
+// followed by the value. Integers are little-endian, and offsets into an object or array count
+// from the start of its value.
+
+const SMALL_OBJECT: u8 = 0x00;
+const LARGE_OBJECT: u8 = 0x01;
+const SMALL_ARRAY: u8 = 0x02;
+const LARGE_ARRAY: u8 = 0x03;
+const LITERAL: u8 = 0x04;
+const INT16: u8 = 0x05;
+const UINT16: u8 = 0x06;
+const INT32: u8 = 0x07;
+const UINT32: u8 = 0x08;
+const INT64: u8 = 0x09;
+const UINT64: u8 = 0x0a;
+const DOUBLE: u8 = 0x0b;
+const STRING: u8 = 0x0c;
+const OPAQUE: u8 = 0x0f;
+
+const LITERAL_NULL: u8 = 0x00;
+const LITERAL_TRUE: u8 = 0x01;
+const LITERAL_FALSE: u8 = 0x02;
+
+// the greatest depth of a document that MySQL accepts
+const MAX_DEPTH: usize = 100;
+
+// the number of bytes holding 0 to 9 decimal digits in the binary format of DECIMAL
+const DIG2BYTES: [usize; 10] = [0, 1, 1, 2, 2, 3, 3, 4, 4, 4];
+
+/// Returns `true` if `bytes` is a JSON document in the binary format rather than text.
+///
+/// A document in the text format begins with a printable character or whitespace, while the
+/// first byte of the binary format is the type of the root value. Only the types of 64-bit
+/// integers, a tab and a line feed, are also whitespace; a document of either type is always 9
+/// bytes long, and is taken as binary unless it is also valid text.
+pub(crate) fn is_binary(bytes: &[u8]) -> bool {
+    match bytes.first() {
+        Some(&INT64) | Some(&UINT64) => {
+            bytes.len() == 9 && serde_json::from_slice::<IgnoredAny>(bytes).is_err()
+        }
+
+        Some(&ty) => ty <= STRING || ty == OPAQUE,
+        None => false,
+    }
+}
+
+/// Decodes a JSON document in the binary format.
+pub(crate) fn decode(bytes: &[u8]) -> Result<Value, Malformed> {
+    let document = Document { bytes };
+    let ty = document.u8(0)?;
+
+    document.value(ty, 1, 0)
+}
+
+/// A document in the binary format that could not be decoded.
+#[derive(Debug)]
+pub(crate) struct Malformed {
+    // the offset into the document of the value that is malformed
+    offset: usize,
+    reason: String,
+}
+
+impl Display for Malformed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "malformed binary JSON at byte {}: {}",
+            self.offset, self.reason
+        )
+    }
+}
+
+impl StdError for Malformed {}
+
+fn malformed<T>(offset: usize, reason: impl Into<String>) -> Result<T, Malformed> {
+    Err(Malformed {
+        offset,
+        reason: reason.into(),
+    })
+}
+
+struct Document<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Document<'a> {
+    fn slice(&self, offset: usize, len: usize) -> Result<&'a [u8], Malformed> {
+        match offset
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(offset..end))
+        {
+            Some(slice) => Ok(slice),
+            None => malformed(offset, "value runs past the end of the document"),
+        }
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8, Malformed> {
+        Ok(self.slice(offset, 1)?[0])
+    }
+
+    // an unsigned integer of `len` bytes
+    fn uint(&self, offset: usize, len: usize) -> Result<u64, Malformed> {
+        Ok(self
+            .slice(offset, len)?
+            .iter()
+            .rev()
+            .fold(0, |n, byte| (n << 8) | u64::from(*byte)))
+    }
+
+    fn usize(&self, offset: usize, len: usize) -> Result<usize, Malformed> {
+        let n = self.uint(offset, len)?;
+
+        usize::try_from(n).or_else(|_| malformed(offset, "size does not fit in memory"))
+    }
+
+    // the length of a string or opaque value, in 7 bits per byte from the lowest, each but the
+    // last with the high bit set; returns the length and the number of bytes it takes
+    fn data_length(&self, offset: usize) -> Result<(usize, usize), Malformed> {
+        let mut len = 0_usize;
+
+        for i in 0..5 {
+            let byte = self.u8(offset + i)?;
+
+            len |= usize::from(byte & 0x7f) << (7 * i);
+
+            if byte & 0x80 == 0 {
+                return Ok((len, i + 1));
+            }
+        }
+
+        malformed(offset, "data length is longer than 5 bytes")
+    }
+
+    fn str(&self, offset: usize, len: usize) -> Result<&'a str, Malformed> {
+        str::from_utf8(self.slice(offset, len)?).or_else(|e| malformed(offset, e.to_string()))
+    }
+
+    // the value of type `ty` at `offset`, in an object or array at `depth`
+    fn value(&self, ty: u8, offset: usize, depth: usize) -> Result<Value, Malformed> {
+        Ok(match ty {
+            SMALL_OBJECT => self.container(offset, false, true, depth)?,
+            LARGE_OBJECT => self.container(offset, true, true, depth)?,
+            SMALL_ARRAY => self.container(offset, false, false, depth)?,
+            LARGE_ARRAY => self.container(offset, true, false, depth)?,
+
+            LITERAL => match self.u8(offset)? {
+                LITERAL_NULL => Value::Null,
+                LITERAL_TRUE => Value::Bool(true),
+                LITERAL_FALSE => Value::Bool(false),
+                literal => return malformed(offset, format!("unknown literal {:#04x}", literal)),
+            },
+
+            INT16 => Value::from(self.uint(offset, 2)? as u16 as i16),
+            UINT16 => Value::from(self.uint(offset, 2)? as u16),
+            INT32 => Value::from(self.uint(offset, 4)? as u32 as i32),
+            UINT32 => Value::from(self.uint(offset, 4)? as u32),
+            INT64 => Value::from(self.uint(offset, 8)? as i64),
+            UINT64 => Value::from(self.uint(offset, 8)?),
+
+            DOUBLE => {
+                let double = f64::from_bits(self.uint(offset, 8)?);
+
+                match Number::from_f64(double) {
+                    Some(number) => Value::Number(number),
+                    None => return malformed(offset, format!("{} is not a JSON number", double)),
+                }
+            }
+
+            STRING => {
+                let (len, n) = self.data_length(offset)?;
+
+                Value::String(self.str(offset + n, len)?.to_owned())
+            }
+
+            OPAQUE => self.opaque(offset)?,
+
+            ty => return malformed(offset, format!("unknown type {:#04x}", ty)),
+        })
+    }
+
+    // an object or array: the number of elements and the size of the whole value, followed by
+    // the offset and length of each key of an object, then the type and the offset of each
+    // value, or the value itself if it fits in the space of the offset
+    fn container(
+        &self,
+        offset: usize,
+        large: bool,
+        object: bool,
+        depth: usize,
+    ) -> Result<Value, Malformed> {
+        if depth == MAX_DEPTH {
+            return malformed(offset, "document is nested too deeply");
+        }
+
+        let offset_size = if large { 4 } else { 2 };
+
+        let count = self.usize(offset, offset_size)?;
+        let size = self.usize(offset + offset_size, offset_size)?;
+
+        // the entries of the keys and values of each element must fit, and so must the value
+        let key_entry_size = if object { offset_size + 2 } else { 0 };
+        let value_entry_size = 1 + offset_size;
+
+        let header_size = count
+            .checked_mul(key_entry_size + value_entry_size)
+            .and_then(|entries| entries.checked_add(2 * offset_size));
+
+        match header_size {
+            Some(header_size) if header_size <= size => {}
+            _ => return malformed(offset, "elements do not fit in the size of the value"),
+        }
+
+        self.slice(offset, size)?;
+
+        let key_entries = offset + 2 * offset_size;
+        let value_entries = key_entries + count * key_entry_size;
+
+        let mut keys = Vec::with_capacity(if object { count } else { 0 });
+
+        if object {
+            for i in 0..count {
+                let entry = key_entries + i * key_entry_size;
+
+                let key_offset = self.usize(entry, offset_size)?;
+                let key_len = self.usize(entry + offset_size, 2)?;
+
+                if key_offset.saturating_add(key_len) > size {
+                    return malformed(entry, "key runs past the end of the object");
+                }
+
+                keys.push(self.str(offset + key_offset, key_len)?.to_owned());
+            }
+        }
+
+        let mut values = Vec::with_capacity(count);
+
+        for i in 0..count {
+            let entry = value_entries + i * value_entry_size;
+            let ty = self.u8(entry)?;
+
+            let inlined = match ty {
+                LITERAL | INT16 | UINT16 => true,
+                INT32 | UINT32 => large,
+                _ => false,
+            };
+
+            let value = if inlined {
+                self.value(ty, entry + 1, depth + 1)?
+            } else {
+                let value_offset = self.usize(entry + 1, offset_size)?;
+
+                if value_offset >= size {
+                    return malformed(entry, "value runs past the end of its container");
+                }
+
+                self.value(ty, offset + value_offset, depth + 1)?
+            };
+
+            values.push(value);
+        }
+
+        Ok(if object {
+            Value::Object(keys.into_iter().zip(values).collect::<Map<_, _>>())
+        } else {
+            Value::Array(values)
+        })
+    }
+
+    // a value of a MySQL type with no JSON counterpart: the type of the column it came from,
+    // and the data in the binary format of that type; rendered as MySQL renders it
+    fn opaque(&self, offset: usize) -> Result<Value, Malformed> {
+        let field_type = self.u8(offset)?;
+        let (len, n) = self.data_length(offset + 1)?;
+        let data_offset = offset + 1 + n;
+        let data = self.slice(data_offset, len)?;
+
+        if field_type == ColumnType::NewDecimal as u8 {
+            return decimal(data_offset, data);
+        }
+
+        let temporal = [
+            ColumnType::Date,
+            ColumnType::Time,
+            ColumnType::Datetime,
+            ColumnType::Timestamp,
+        ];
+
+        if temporal.iter().any(|ty| field_type == *ty as u8) {
+            if data.len() < 8 {
+                return malformed(data_offset, "temporal value is shorter than 8 bytes");
+            }
+
+            let packed = self.uint(data_offset, 8)? as i64;
+
+            return Ok(Value::String(temporal_to_string(field_type, packed)));
+        }
+
+        Ok(Value::String(format!(
+            "base64:type{}:{}",
+            field_type,
+            base64::encode(data)
+        )))
+    }
+}
+
+// a DECIMAL: its precision and scale, then its digits in groups of nine, each group in four
+// bytes and the leading and trailing partial groups in as few bytes as they need; the sign is
+// the inverted high bit, and the bytes of a negative value are inverted
+fn decimal(offset: usize, data: &[u8]) -> Result<Value, Malformed> {
+    if data.len() < 2 {
+        return malformed(offset, "DECIMAL is shorter than its precision and scale");
+    }
+
+    let precision = usize::from(data[0]);
+    let scale = usize::from(data[1]);
+
+    if scale > precision {
+        return malformed(offset, "DECIMAL has a scale greater than its precision");
+    }
+
+    let integral = precision - scale;
+
+    let size =
+        (integral / 9) * 4 + DIG2BYTES[integral % 9] + (scale / 9) * 4 + DIG2BYTES[scale % 9];
+
+    let mut bytes = match data.get(2..2 + size) {
+        Some(bytes) if size > 0 => bytes.to_vec(),
+        _ => return malformed(offset + 2, "DECIMAL is shorter than its precision"),
+    };
+
+    let negative = bytes[0] & 0x80 == 0;
+
+    bytes[0] ^= 0x80;
+
+    if negative {
+        for byte in &mut bytes {
+            *byte = !*byte;
+        }
+    }
+
+    let mut bytes = &bytes[..];
+
+    let mut read = |len: usize| {
+        let (group, rest) = bytes.split_at(len);
+        bytes = rest;
+
+        group
+            .iter()
+            .fold(0_u32, |n, byte| (n << 8) | u32::from(*byte))
+    };
+
+    let mut digits = String::new();
+
+    if integral % 9 > 0 {
+        digits.push_str(&read(DIG2BYTES[integral % 9]).to_string());
+    }
+
+    for _ in 0..integral / 9 {
+        digits.push_str(&format!("{:09}", read(4)));
+    }
+
+    let mut text = String::new();
+
+    if negative {
+        text.push('-');
+    }
+
+    match digits.trim_start_matches('0') {
+        "" => text.push('0'),
+        digits => text.push_str(digits),
+    }
+
+    if scale > 0 {
+        text.push('.');
+
+        for _ in 0..scale / 9 {
+            text.push_str(&format!("{:09}", read(4)));
+        }
+
+        if scale % 9 > 0 {
+            text.push_str(&format!(
+                "{:0width$}",
+                read(DIG2BYTES[scale % 9]),
+                width = scale % 9
+            ));
+        }
+    }
+
+    serde_json::from_str(&text).or_else(|e| malformed(offset, e.to_string()))
+}
+
+// a DATE, TIME, DATETIME or TIMESTAMP in the packed 64-bit format of MySQL: the fraction of a
+// second in microseconds in the low 24 bits, above which are the seconds, minutes and hours,
+// and for dates the day and the months since year 0
+fn temporal_to_string(field_type: u8, packed: i64) -> String {
+    let sign = if packed < 0 { "-" } else { "" };
+    let packed = packed.wrapping_abs() as u64;
+
+    let micros = packed % (1 << 24);
+    let hms = (packed >> 24) % (1 << 17);
+    let (second, minute) = (hms % (1 << 6), (hms >> 6) % (1 << 6));
+
+    if field_type == ColumnType::Time as u8 {
+        let hour = (packed >> 36) % (1 << 10);
+
+        return format!(
+            "{}{:02}:{:02}:{:02}.{:06}",
+            sign, hour, minute, second, micros
+        );
+    }
+
+    let hour = hms >> 12;
+    let ymd = packed >> 41;
+    let (day, month, year) = (ymd % (1 << 5), (ymd >> 5) % 13, (ymd >> 5) / 13);
+
+    if field_type == ColumnType::Date as u8 {
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    } else {
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:06}",
+            year, month, day, hour, minute, second, micros
+        )
+    }
+}
+
+#[cfg(test)]
+fn decode_ok(bytes: &[u8]) -> Value {
+    assert!(is_binary(bytes), "not taken as binary: {:?}", bytes);
+
+    decode(bytes).unwrap()
+}
+
+#[test]
+fn it_decodes_binary_json_scalars() {
+    use serde_json::json;
+
+    assert_eq!(decode_ok(&[0x04, 0x00]), Value::Null);
+    assert_eq!(decode_ok(&[0x04, 0x01]), json!(true));
+    assert_eq!(decode_ok(&[0x04, 0x02]), json!(false));
+    assert_eq!(decode_ok(&[0x05, 0xfe, 0xff]), json!(-2));
+    assert_eq!(decode_ok(&[0x06, 0xff, 0xff]), json!(65535));
+    assert_eq!(decode_ok(&[0x07, 0x60, 0x79, 0xfe, 0xff]), json!(-100000));
+    assert_eq!(
+        decode_ok(&[0x08, 0xff, 0xff, 0xff, 0xff]),
+        json!(4294967295_u32)
+    );
+    assert_eq!(
+        decode_ok(&[0x09, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        json!(-1)
+    );
+    assert_eq!(
+        decode_ok(&[0x0a, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        json!(u64::MAX)
+    );
+    assert_eq!(decode_ok(&[0x0b, 0, 0, 0, 0, 0, 0, 0xf8, 0x3f]), json!(1.5));
+    assert_eq!(decode_ok(&[0x0c, 0x02, b'h', b'i']), json!("hi"));
+
+    // a length of over 127 bytes takes two bytes
+    let mut long = vec![0x0c, 0xc8, 0x01];
+    long.extend(&[b'x'; 200]);
+
+    assert_eq!(decode_ok(&long), json!("x".repeat(200)));
+}
+
+#[test]
+fn it_decodes_binary_json_opaque_values() {
+    use serde_json::json;
+
+    // DECIMAL(5, 2)
+    assert_eq!(
+        decode_ok(&[0x0f, 0xf6, 0x05, 0x05, 0x02, 0x80, 0x7b, 0x2d]),
+        json!(123.45)
+    );
+    assert_eq!(
+        decode_ok(&[0x0f, 0xf6, 0x05, 0x05, 0x02, 0x7f, 0x84, 0xd2]),
+        json!(-123.45)
+    );
+
+    let datetime = [
+        0x0f, 0x0c, 0x08, 0x40, 0xe2, 0x01, 0x19, 0x76, 0x1f, 0x95, 0x19,
+    ];
+    assert_eq!(decode_ok(&datetime), json!("2015-01-15 23:24:25.123456"));
+
+    let date = [
+        0x0f, 0x0a, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1e, 0x95, 0x19,
+    ];
+    assert_eq!(decode_ok(&date), json!("2015-01-15"));
+
+    let time = [
+        0x0f, 0x0b, 0x08, 0xe0, 0x5e, 0xf8, 0x7c, 0xef, 0xff, 0xff, 0xff,
+    ];
+    assert_eq!(decode_ok(&time), json!("-01:02:03.500000"));
+
+    // a BLOB, as MySQL prints it
+    assert_eq!(
+        decode_ok(&[0x0f, 0xfc, 0x02, 0x61, 0x62]),
+        json!("base64:type252:YWI=")
+    );
+}
+
+#[test]
+fn it_decodes_binary_json_containers() {
+    use serde_json::json;
+
+    // {"a": 1, "b": [true, "x"]}
+    let document = [
+        0x00, // small object
+        0x02, 0x00, 0x20, 0x00, // 2 members in 32 bytes
+        0x12, 0x00, 0x01, 0x00, // "a" at 18
+        0x13, 0x00, 0x01, 0x00, // "b" at 19
+        0x05, 0x01, 0x00, // 1, inlined
+        0x02, 0x14, 0x00, // small array at 20
+        b'a', b'b', //
+        0x02, 0x00, 0x0c, 0x00, // 2 elements in 12 bytes
+        0x04, 0x01, 0x00, // true, inlined
+        0x0c, 0x0a, 0x00, // string at 10
+        0x01, b'x',
+    ];
+
+    assert_eq!(decode_ok(&document), json!({ "a": 1, "b": [true, "x"] }));
+
+    // a UINT32 is only inlined in the large format
+    let small = [
+        0x02, 0x01, 0x00, 0x0b, 0x00, 0x08, 0x07, 0x00, 0xff, 0xff, 0xff, 0xff,
+    ];
+    assert_eq!(decode_ok(&small), json!([4294967295_u32]));
+
+    // a large array of 1000 inlined INT32 elements
+    let mut large = vec![0x03];
+    large.extend(&1000_u32.to_le_bytes());
+    large.extend(&(8_u32 + 5000).to_le_bytes());
+
+    for i in 0..1000_i32 {
+        large.push(0x07);
+        large.extend(&(i - 500).to_le_bytes());
+    }
+
+    assert_eq!(decode_ok(&large), json!((-500..500).collect::<Vec<_>>()));
+}
+
+#[test]
+fn it_reports_the_offset_of_malformed_binary_json() {
+    let error = |bytes: &[u8]| decode(bytes).unwrap_err().to_string();
+
+    assert_eq!(
+        error(&[0x0c, 0x05, b'a']),
+        "malformed binary JSON at byte 2: value runs past the end of the document"
+    );
+
+    assert_eq!(
+        error(&[0x04, 0x03]),
+        "malformed binary JSON at byte 1: unknown literal 0x03"
+    );
+
+    // an array of one element whose value is past the end of the array
+    assert_eq!(
+        error(&[0x02, 0x01, 0x00, 0x07, 0x00, 0x0c, 0x07, 0x00]),
+        "malformed binary JSON at byte 5: value runs past the end of its container"
+    );
+
+    // more elements than fit in the size of the array
+    assert_eq!(
+        error(&[0x02, 0xff, 0x00, 0x07, 0x00, 0x04, 0x00, 0x00]),
+        "malformed binary JSON at byte 1: elements do not fit in the size of the value"
+    );
+
+    // arrays nested `levels` deep, each the only element of the one before
+    let nested = |levels: u16| {
+        let mut nested = vec![0x02];
+
+        for depth in 1..levels {
+            nested.extend(&[0x01, 0x00]);
+            nested.extend(&(7 * (levels - depth) + 4).to_le_bytes());
+            nested.extend(&[0x02, 0x07, 0x00]);
+        }
+
+        nested.extend(&[0x00, 0x00, 0x04, 0x00]);
+        nested
+    };
+
+    assert_eq!(
+        error(&nested(101)),
+        "malformed binary JSON at byte 701: document is nested too deeply"
+    );
+
+    assert_eq!(
+        decode(&nested(100)).unwrap().to_string(),
+        format!("{}{}", "[".repeat(100), "]".repeat(100))
+    );
+}
+
+#[test]
+fn it_tells_binary_json_from_text() {
+    assert!(!is_binary(br#"{"a": 1}"#));
+    assert!(!is_binary(b"\n[1, 2]"));
+    assert!(!is_binary(b"\t12345678"));
+    assert!(!is_binary(b""));
+    assert!(is_binary(&[
+        0x09, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff
+    ]));
+}
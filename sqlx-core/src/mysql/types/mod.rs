@@ -10,13 +10,54 @@
 //! | `i32`                                 | INT                                                  |
 //! | `i64`                                 | BIGINT                                               |
 //! | `u8`                                  | TINYINT UNSIGNED                                     |
-//! | `u16`                                 | SMALLINT UNSIGNED                                    |
+//! | `u16`                                 | SMALLINT UNSIGNED, YEAR                              |
 //! | `u32`                                 | INT UNSIGNED                                         |
 //! | `u64`                                 | BIGINT UNSIGNED                                      |
 //! | `f32`                                 | FLOAT                                                |
 //! | `f64`                                 | DOUBLE                                               |
-//! | `&str`, [`String`]                    | VARCHAR, CHAR, TEXT                                  |
-//! | `&[u8]`, `Vec<u8>`                    | VARBINARY, BINARY, BLOB                              |
+//! | `&str`, [`String`]                    | VARCHAR, CHAR, TEXT, ENUM, SET                       |
+//! | `Vec<String>`                         | SET                                                  |
+//! | `&[u8]`, `Vec<u8>`, `[u8; N]`         | VARBINARY, BINARY, BLOB                              |
+//! | `std::time::SystemTime`               | TIMESTAMP, DATETIME                                  |
+//! | [`UnixTimestamp`], [`UnixMillis`]     | BIGINT, INT, TIMESTAMP, DATETIME                     |
+//!
+//! A `YEAR` is decoded into any integer type; the zero year, `0000`, is `0`. A `SET` is decoded
+//! into a `String` of its members separated by commas, or a `Vec<String>` of them. A `DECIMAL`
+//! can be decoded into a `String` of its digits, as well as the decimal types below.
+//!
+//! Whether a column is text or binary is decided by its character set: with the `binary`
+//! character set (collation id 63), [`TypeInfo::name`](crate::type_info::TypeInfo::name)
+//! reports a `VARCHAR` as `VARBINARY`, a `CHAR` as `BINARY` and a `TEXT` as `BLOB`. Text columns can also be decoded into `Vec<u8>`, giving their bytes in the connection
+//! character set. Binary columns can be decoded into a `String` only if they are valid UTF-8;
+//! anything else is a decode error.
+//!
+//! `BINARY(n)` values are padded to `n` bytes with trailing NUL (`0x00`) bytes by MySQL when
+//! they are stored. The padding is returned exactly as MySQL sends it, and is part of the
+//! `String` decoded from such a column as well; it is never trimmed.
+//!
+//! Any of the integer types above can also be decoded from any other integer column, signed or
+//! unsigned, as long as the value fits; a value that does not fit is a decode error rather than
+//! being truncated.
+//!
+//! ### Timestamps without `chrono` or `time`
+//!
+//! `SystemTime` is sent as a TIMESTAMP, and read from a TIMESTAMP or DATETIME, in UTC: SQLx sets
+//! the `time_zone` of the session to UTC when connecting, which MySQL converts TIMESTAMP
+//! columns to and from. A DATETIME has no time zone, so it is taken to be in UTC as well.
+//! Precision below a microsecond is dropped, rounding towards the past also before 1970, and a
+//! column without fractional seconds rounds what is left to the second. A value outside the
+//! years 0 to 9999 is an encode error, and a zero date, or one with a zero month or day, a
+//! decode error, each with a [`TimestampOutOfRange`](crate::types::TimestampOutOfRange) as its
+//! source.
+//!
+//! [`UnixTimestamp`] and [`UnixMillis`] hold the seconds or milliseconds since 1970, and are
+//! decoded from an integer column as that number, or from a TIMESTAMP or DATETIME column as the
+//! instant it stands for, rounded down. They are always sent as a BIGINT: MySQL takes a number
+//! stored in a DATETIME column to be its digits, e.g. `20261014083000`, so convert it in the
+//! query with `FROM_UNIXTIME(?)`, or `FROM_UNIXTIME(? / 1000)` for milliseconds.
+//!
+//! [`UnixTimestamp`]: crate::types::UnixTimestamp
+//! [`UnixMillis`]: crate::types::UnixMillis
 //!
 //! ### [`chrono`](https://crates.io/crates/chrono)
 //!
@@ -72,6 +113,9 @@
 //! |---------------------------------------|------------------------------------------------------|
 //! | `json::JsonValue`             | JSON
 //!
+//! A JSON document is decoded from text, or from the binary format MySQL stores documents in,
+//! which some servers send as is.
+//!
 //! # Nullable
 //!
 //! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
@@ -83,6 +127,7 @@ mod bytes;
 mod float;
 mod int;
 mod str;
+mod system_time;
 mod uint;
 
 #[cfg(feature = "bigdecimal")]
@@ -102,3 +147,6 @@ mod uuid;
 
 #[cfg(feature = "json")]
 mod json;
+
+#[cfg(feature = "json")]
+mod json_binary;
@@ -1,16 +1,13 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
+use crate::mysql::collation;
 use crate::mysql::io::MySqlBufMutExt;
 use crate::mysql::protocol::text::{ColumnFlags, ColumnType};
 use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueRef};
 use crate::types::Type;
 
-const COLLATE_UTF8_GENERAL_CI: u16 = 33;
-const COLLATE_UTF8_UNICODE_CI: u16 = 192;
 const COLLATE_UTF8MB4_UNICODE_CI: u16 = 224;
-const COLLATE_UTF8MB4_BIN: u16 = 46;
-const COLLATE_UTF8MB4_GENERAL_CI: u16 = 45;
 
 impl Type<MySql> for str {
     fn type_info() -> MySqlTypeInfo {
@@ -19,11 +16,17 @@ impl Type<MySql> for str {
             char_set: COLLATE_UTF8MB4_UNICODE_CI, // utf8mb4_unicode_ci
             flags: ColumnFlags::empty(),
             max_size: None,
+            extended: None,
         }
     }
 
+    // binary strings are compatible too; decoding checks that they are valid UTF-8
     fn compatible(ty: &MySqlTypeInfo) -> bool {
-        // TODO: Support more collations being returned from SQL?
+        // sent as text by the binary protocol as well, whatever their character set
+        if is_text_in_binary_protocol(ty) {
+            return true;
+        }
+
         matches!(
             ty.r#type,
             ColumnType::VarChar
@@ -33,23 +36,15 @@ impl Type<MySql> for str {
                 | ColumnType::LongBlob
                 | ColumnType::String
                 | ColumnType::VarString
-                | ColumnType::Enum
-        ) && matches!(
-            ty.char_set,
-            COLLATE_UTF8MB4_UNICODE_CI
-                | COLLATE_UTF8_UNICODE_CI
-                | COLLATE_UTF8_GENERAL_CI
-                | COLLATE_UTF8MB4_BIN
-                | COLLATE_UTF8MB4_GENERAL_CI
-        )
+        ) && (collation::is_utf8(ty.char_set) || ty.is_binary_string())
     }
 }
 
 impl Encode<'_, MySql> for &'_ str {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.put_str_lenenc(self);
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
@@ -70,7 +65,7 @@ impl Type<MySql> for String {
 }
 
 impl Encode<'_, MySql> for String {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         <&str as Encode<MySql>>::encode(&**self, buf)
     }
 }
@@ -80,3 +75,159 @@ impl Decode<'_, MySql> for String {
         <&str as Decode<MySql>>::decode(value).map(ToOwned::to_owned)
     }
 }
+
+// a `DECIMAL`, `ENUM` or `SET`; an `ENUM` or `SET` column is usually described as a `CHAR`
+// with a flag, and has the `binary` character set in some servers
+fn is_text_in_binary_protocol(ty: &MySqlTypeInfo) -> bool {
+    matches!(
+        ty.r#type,
+        ColumnType::Decimal | ColumnType::NewDecimal | ColumnType::Enum | ColumnType::Set
+    ) || (ty.r#type == ColumnType::String
+        && ty.flags.intersects(ColumnFlags::ENUM | ColumnFlags::SET))
+}
+
+fn is_set(ty: &MySqlTypeInfo) -> bool {
+    ty.r#type == ColumnType::Set
+        || (ty.r#type == ColumnType::String && ty.flags.contains(ColumnFlags::SET))
+}
+
+/// The members of a `SET`, which MySQL sends separated by commas; a member cannot contain a
+/// comma. The empty set is an empty `Vec`.
+impl Type<MySql> for Vec<String> {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo {
+            r#type: ColumnType::String,
+            char_set: COLLATE_UTF8MB4_UNICODE_CI,
+            flags: ColumnFlags::SET,
+            max_size: None,
+            extended: None,
+        }
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        is_set(ty)
+    }
+}
+
+impl Encode<'_, MySql> for Vec<String> {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
+        if let Some(member) = self.iter().find(|member| member.contains(',')) {
+            return Err(format!("a member of a SET cannot contain a comma: {:?}", member).into());
+        }
+
+        buf.put_str_lenenc(&self.join(","));
+
+        Ok(IsNull::No)
+    }
+}
+
+impl Decode<'_, MySql> for Vec<String> {
+    fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let members = value.as_str()?;
+
+        if members.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(members.split(',').map(ToOwned::to_owned).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decode::Decode;
+    use crate::mysql::protocol::text::{ColumnFlags, ColumnType};
+    use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueFormat, MySqlValueRef};
+    use crate::type_info::TypeInfo;
+    use crate::types::Type;
+
+    fn string_type(ty: ColumnType, char_set: u16) -> MySqlTypeInfo {
+        MySqlTypeInfo {
+            r#type: ty,
+            flags: ColumnFlags::empty(),
+            char_set,
+            max_size: None,
+            extended: None,
+        }
+    }
+
+    fn decode(type_info: &MySqlTypeInfo, buf: &[u8]) -> Result<String, String> {
+        <String as Decode<MySql>>::decode(MySqlValueRef {
+            value: Some(buf),
+            row: None,
+            type_info: type_info.clone(),
+            format: MySqlValueFormat::Binary,
+        })
+        .map_err(|error| error.to_string())
+    }
+
+    #[test]
+    fn it_decodes_binary_strings_only_if_they_are_utf8() {
+        let varbinary = string_type(ColumnType::VarString, 63);
+        let binary = string_type(ColumnType::String, 63);
+
+        assert_eq!(varbinary.name(), "VARBINARY");
+        assert!(<String as Type<MySql>>::compatible(&varbinary));
+        assert!(<String as Type<MySql>>::compatible(&binary));
+
+        assert_eq!(decode(&varbinary, b"caf\xc3\xa9").unwrap(), "café");
+
+        let error = decode(&varbinary, b"\xff\xfe").unwrap_err();
+        assert!(error.contains("VARBINARY"), "{}", error);
+        assert!(error.contains("Vec<u8>"), "{}", error);
+
+        // the NUL padding of BINARY(n) is kept
+        assert_eq!(decode(&binary, b"ab\0\0").unwrap(), "ab\0\0");
+    }
+
+    #[test]
+    fn it_keeps_non_utf8_text_incompatible() {
+        // latin1_swedish_ci
+        let latin1 = string_type(ColumnType::VarString, 8);
+        assert_eq!(latin1.name(), "VARCHAR");
+        assert!(!<String as Type<MySql>>::compatible(&latin1));
+
+        // both kinds of strings can be decoded into bytes
+        assert!(<Vec<u8> as Type<MySql>>::compatible(&latin1));
+        assert!(<Vec<u8> as Type<MySql>>::compatible(&string_type(
+            ColumnType::Blob,
+            63
+        )));
+
+        // ENUM values have the `binary` character set in some servers but are text
+        let mut set = string_type(ColumnType::String, 63);
+        set.flags |= ColumnFlags::SET;
+        assert!(!set.__is_binary_string());
+        assert!(<String as Type<MySql>>::compatible(&set));
+    }
+
+    #[test]
+    fn it_decodes_decimal_enum_and_set_as_text() {
+        let decimal = MySqlTypeInfo::binary(ColumnType::NewDecimal);
+        assert_eq!(decimal.name(), "DECIMAL");
+        assert_eq!(decode(&decimal, b"-12.50").unwrap(), "-12.50");
+
+        let mut enum_ = string_type(ColumnType::String, 224);
+        enum_.flags |= ColumnFlags::ENUM;
+        assert_eq!(enum_.name(), "ENUM");
+        assert!(!<Vec<String> as Type<MySql>>::compatible(&enum_));
+
+        let mut set = string_type(ColumnType::String, 224);
+        set.flags |= ColumnFlags::SET;
+        assert_eq!(set.name(), "SET");
+        assert!(<Vec<String> as Type<MySql>>::compatible(&set));
+
+        let members = |buf: &[u8]| {
+            <Vec<String> as Decode<MySql>>::decode(MySqlValueRef {
+                value: Some(buf),
+                row: None,
+                type_info: set.clone(),
+                format: MySqlValueFormat::Binary,
+            })
+            .unwrap()
+        };
+
+        assert_eq!(members(b"a,c"), ["a", "c"]);
+        assert_eq!(members(b""), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,205 @@
+use crate::decode::Decode;
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::error::BoxDynError;
+use crate::mysql::protocol::text::ColumnType;
+use crate::mysql::types::int::int_compatible;
+use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueFormat, MySqlValueRef};
+use crate::types::unix_time::{try_system_time, unix_micros, DateTime, TimestampOutOfRange};
+use crate::types::{Type, UnixMillis, UnixTimestamp};
+use std::time::SystemTime;
+
+fn is_datetime(ty: &MySqlTypeInfo) -> bool {
+    matches!(ty.r#type, ColumnType::Datetime | ColumnType::Timestamp)
+}
+
+// the DATETIME of the instant `micros` from the unix epoch, in UTC, the time zone SQLx sets
+// for the session
+fn to_datetime(micros: i128) -> Result<DateTime, TimestampOutOfRange> {
+    let datetime = DateTime::from_unix_micros(micros);
+
+    // the protocol has room for any year up to 65535, but MySQL only knows four digits
+    if !(0..=9999).contains(&datetime.year) {
+        return Err(TimestampOutOfRange::new(datetime, "DATETIME"));
+    }
+
+    Ok(datetime)
+}
+
+fn encode_datetime(datetime: &DateTime, buf: &mut Vec<u8>) {
+    // to save space the time is left out at midnight, and the microseconds when they are zero
+    let len = if datetime.micros != 0 {
+        11
+    } else if (datetime.hour, datetime.minute, datetime.second) != (0, 0, 0) {
+        7
+    } else {
+        4
+    };
+
+    buf.push(len);
+    buf.extend_from_slice(&(datetime.year as u16).to_le_bytes());
+    buf.push(datetime.month as u8);
+    buf.push(datetime.day as u8);
+
+    if len > 4 {
+        buf.push(datetime.hour as u8);
+        buf.push(datetime.minute as u8);
+        buf.push(datetime.second as u8);
+    }
+
+    if len > 7 {
+        buf.extend_from_slice(&datetime.micros.to_le_bytes());
+    }
+}
+
+// the microseconds from the unix epoch of a DATETIME or TIMESTAMP, which is read in the UTC of
+// the session; `target` is the Rust type, named if the value is a zero date
+fn decode_datetime(value: MySqlValueRef<'_>, target: &'static str) -> Result<i128, BoxDynError> {
+    let datetime = match value.format() {
+        MySqlValueFormat::Binary => {
+            let buf = value.as_bytes()?;
+            let len = buf.first().map_or(0, |len| usize::from(*len));
+
+            if buf.len() < len + 1 || (len != 0 && len < 4) {
+                return Err(
+                    format!("expected {} bytes for a DATETIME, got {}", len, buf.len()).into(),
+                );
+            }
+
+            // the zero date, `0000-00-00 00:00:00`, is sent without any bytes
+            let byte = |at: usize| u32::from(if at <= len { buf[at] } else { 0 });
+
+            DateTime {
+                year: if len == 0 {
+                    0
+                } else {
+                    i64::from(u16::from_le_bytes([buf[1], buf[2]]))
+                },
+                month: byte(3),
+                day: byte(4),
+                hour: byte(5),
+                minute: byte(6),
+                second: byte(7),
+                micros: byte(8) | byte(9) << 8 | byte(10) << 16 | byte(11) << 24,
+            }
+        }
+
+        MySqlValueFormat::Text => {
+            let s = value.as_str()?;
+
+            if s.starts_with("0000-00-00") {
+                return Err(TimestampOutOfRange::new(s, target).into());
+            }
+
+            DateTime::parse(s)?
+        }
+    };
+
+    // a zero date, or one with a zero month or day, which MySQL allows unless `NO_ZERO_DATE` and
+    // `NO_ZERO_IN_DATE` are set, is no instant
+    if datetime.month == 0 || datetime.day == 0 {
+        return Err(TimestampOutOfRange::new(
+            format!(
+                "{:04}-{:02}-{:02}",
+                datetime.year, datetime.month, datetime.day
+            ),
+            target,
+        )
+        .into());
+    }
+
+    Ok(datetime.unix_micros())
+}
+
+impl Type<MySql> for SystemTime {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo::binary(ColumnType::Timestamp)
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        is_datetime(ty)
+    }
+}
+
+impl Encode<'_, MySql> for SystemTime {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
+        // sub-microsecond precision is rounded down, also before the unix epoch
+        encode_datetime(&to_datetime(unix_micros(*self))?, buf);
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        12
+    }
+}
+
+impl Decode<'_, MySql> for SystemTime {
+    fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        Ok(try_system_time(decode_datetime(value, "SystemTime")?)?)
+    }
+}
+
+// `UnixTimestamp` and `UnixMillis` are sent as a BIGINT; MySQL reads a number stored in a
+// DATETIME column as its digits, e.g. `20261014083000`, so it is converted with `FROM_UNIXTIME`
+macro_rules! impl_unix_time {
+    ($ty:ident, $per_second:expr) => {
+        impl Type<MySql> for $ty {
+            fn type_info() -> MySqlTypeInfo {
+                MySqlTypeInfo::binary(ColumnType::LongLong)
+            }
+
+            fn compatible(ty: &MySqlTypeInfo) -> bool {
+                int_compatible(ty) || is_datetime(ty)
+            }
+        }
+
+        impl Encode<'_, MySql> for $ty {
+            fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
+                Encode::<MySql>::encode(self.0, buf)
+            }
+        }
+
+        impl Decode<'_, MySql> for $ty {
+            fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+                if is_datetime(&value.type_info) {
+                    let micros = decode_datetime(value, stringify!($ty))?;
+
+                    // rounded down towards the past, like the conversion from `SystemTime`
+                    return Ok($ty(micros.div_euclid(1_000_000 / $per_second) as i64));
+                }
+
+                Decode::<MySql>::decode(value).map($ty)
+            }
+        }
+    };
+}
+
+impl_unix_time!(UnixTimestamp, 1);
+impl_unix_time!(UnixMillis, 1_000);
+
+#[test]
+fn it_encodes_datetimes_in_as_few_bytes_as_needed() {
+    let encode = |s: &str| {
+        let mut buf = Vec::new();
+        encode_datetime(&DateTime::parse(s).unwrap(), &mut buf);
+        buf
+    };
+
+    assert_eq!(encode("1969-07-20"), [4, 0xb1, 0x07, 7, 20]);
+    assert_eq!(
+        encode("1969-07-20 20:17:40"),
+        [7, 0xb1, 0x07, 7, 20, 20, 17, 40]
+    );
+    assert_eq!(
+        encode("1969-07-20 20:17:40.5"),
+        [11, 0xb1, 0x07, 7, 20, 20, 17, 40, 0x20, 0xa1, 0x07, 0]
+    );
+
+    let year = |s: &str| to_datetime(DateTime::parse(s).unwrap().unix_micros());
+    assert!(year("9999-12-31 23:59:59").is_ok());
+    assert_eq!(
+        year("10000-01-01 00:00:00").unwrap_err().target(),
+        "DATETIME"
+    );
+    assert!(year("-0001-12-31 23:59:59").is_err());
+}
@@ -6,7 +6,7 @@ use bytes::Buf;
 use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::{BoxDynError, UnexpectedNullError};
 use crate::mysql::protocol::text::ColumnType;
 use crate::mysql::type_info::MySqlTypeInfo;
@@ -24,7 +24,7 @@ impl Type<MySql> for OffsetDateTime {
 }
 
 impl Encode<'_, MySql> for OffsetDateTime {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         let utc_dt = self.to_offset(UtcOffset::UTC);
         let primitive_dt = PrimitiveDateTime::new(utc_dt.date(), utc_dt.time());
 
@@ -47,7 +47,7 @@ impl Type<MySql> for Time {
 }
 
 impl Encode<'_, MySql> for Time {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         let len = Encode::<MySql>::size_hint(self) - 1;
         buf.push(len as u8);
 
@@ -60,7 +60,7 @@ impl Encode<'_, MySql> for Time {
 
         encode_time(self, len > 9, buf);
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 
     fn size_hint(&self) -> usize {
@@ -127,12 +127,12 @@ impl Type<MySql> for Date {
 }
 
 impl Encode<'_, MySql> for Date {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.push(4);
 
-        encode_date(self, buf);
+        encode_date(self, buf)?;
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 
     fn size_hint(&self) -> usize {
@@ -161,17 +161,17 @@ impl Type<MySql> for PrimitiveDateTime {
 }
 
 impl Encode<'_, MySql> for PrimitiveDateTime {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         let len = Encode::<MySql>::size_hint(self) - 1;
         buf.push(len as u8);
 
-        encode_date(&self.date(), buf);
+        encode_date(&self.date(), buf)?;
 
         if len > 4 {
             encode_time(&self.time(), len > 8, buf);
         }
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 
     fn size_hint(&self) -> usize {
@@ -231,14 +231,16 @@ impl<'r> Decode<'r, MySql> for PrimitiveDateTime {
     }
 }
 
-fn encode_date(date: &Date, buf: &mut Vec<u8>) {
+fn encode_date(date: &Date, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
     // MySQL supports years from 1000 - 9999
-    let year = u16::try_from(date.year())
-        .unwrap_or_else(|_| panic!("Date out of range for Mysql: {}", date));
+    let year =
+        u16::try_from(date.year()).map_err(|_| format!("Date out of range for Mysql: {}", date))?;
 
     buf.extend_from_slice(&year.to_le_bytes());
     buf.push(date.month());
     buf.push(date.day());
+
+    Ok(())
 }
 
 fn decode_date(buf: &[u8]) -> Result<Option<Date>, BoxDynError> {
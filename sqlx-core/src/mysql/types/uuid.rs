@@ -1,7 +1,7 @@
 use uuid::{adapter::Hyphenated, Uuid};
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::mysql::io::MySqlBufMutExt;
 use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueRef};
@@ -18,10 +18,10 @@ impl Type<MySql> for Uuid {
 }
 
 impl Encode<'_, MySql> for Uuid {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.put_bytes_lenenc(self.as_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
@@ -46,10 +46,10 @@ impl Type<MySql> for Hyphenated {
 }
 
 impl Encode<'_, MySql> for Hyphenated {
-    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, EncodeError> {
         buf.put_str_lenenc(&self.to_string());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
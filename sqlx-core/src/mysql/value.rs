@@ -1,12 +1,13 @@
 use crate::error::{BoxDynError, UnexpectedNullError};
 use crate::mysql::protocol::text::ColumnType;
 use crate::mysql::{MySql, MySqlTypeInfo};
+use crate::type_info::TypeInfo;
 use crate::value::{Value, ValueRef};
 use bytes::Bytes;
 use std::borrow::Cow;
 use std::str::from_utf8;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 #[repr(u8)]
 pub enum MySqlValueFormat {
     Text,
@@ -31,19 +32,46 @@ pub struct MySqlValueRef<'r> {
 }
 
 impl<'r> MySqlValueRef<'r> {
-    pub(crate) fn format(&self) -> MySqlValueFormat {
+    /// Whether the value came from the text protocol (a plain query) or the binary protocol (a
+    /// prepared statement).
+    pub fn format(&self) -> MySqlValueFormat {
         self.format
     }
 
-    pub(crate) fn as_bytes(&self) -> Result<&'r [u8], BoxDynError> {
+    /// The raw bytes of the value, in its [`format`](Self::format).
+    ///
+    /// Returns an error if the value is `NULL`.
+    pub fn as_bytes(&self) -> Result<&'r [u8], BoxDynError> {
         match &self.value {
             Some(v) => Ok(v),
             None => Err(UnexpectedNullError.into()),
         }
     }
 
-    pub(crate) fn as_str(&self) -> Result<&'r str, BoxDynError> {
-        Ok(from_utf8(self.as_bytes()?)?)
+    /// The raw bytes of the value as a string.
+    ///
+    /// Returns an error if the value is `NULL` or not valid UTF-8.
+    pub fn as_str(&self) -> Result<&'r str, BoxDynError> {
+        from_utf8(self.as_bytes()?).map_err(|error| {
+            // the connection charset does not apply to binary strings
+            if self.type_info.is_binary_string() {
+                return format!(
+                    "value of SQL type {} is not valid UTF-8: {}; decode into `Vec<u8>`",
+                    self.type_info.name(),
+                    error
+                )
+                .into();
+            }
+
+            format!(
+                "value of SQL type {} is not valid UTF-8 (collation id {}): {}; \
+                 set the connection `charset` to `utf8mb4` or decode into `Vec<u8>`",
+                self.type_info.name(),
+                self.type_info.char_set,
+                error
+            )
+            .into()
+        })
     }
 }
 
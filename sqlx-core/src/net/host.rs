@@ -0,0 +1,175 @@
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use percent_encoding::percent_decode_str;
+use url::Url;
+
+use crate::error::Error;
+
+/// The host of a TCP connection, as stored in the connect options: a host name, or an IP
+/// address without brackets, where an IPv6 address may end in a `%` and a zone (scope) id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Host<'a> {
+    Hostname(&'a str),
+    Ipv4(Ipv4Addr),
+    Ipv6(Ipv6Addr, Option<&'a str>),
+}
+
+impl<'a> Host<'a> {
+    pub(crate) fn parse(host: &'a str) -> Self {
+        if let Ok(addr) = host.parse() {
+            return Host::Ipv4(addr);
+        }
+
+        let (addr, zone) = match host.find('%') {
+            Some(i) => (&host[..i], Some(&host[i + 1..])),
+            None => (host, None),
+        };
+
+        match addr.parse() {
+            Ok(addr) => Host::Ipv6(addr, zone),
+            Err(_) => Host::Hostname(host),
+        }
+    }
+
+    pub(crate) fn is_ip(&self) -> bool {
+        !matches!(self, Host::Hostname(_))
+    }
+
+    /// The name to verify the server's certificate against: the host name, or the address
+    /// without a zone id.
+    #[cfg_attr(not(feature = "_tls-native-tls"), allow(dead_code))]
+    pub(crate) fn tls_name(&self) -> Cow<'a, str> {
+        match *self {
+            Host::Hostname(name) => Cow::Borrowed(name),
+            Host::Ipv4(addr) => Cow::Owned(addr.to_string()),
+            Host::Ipv6(addr, _) => Cow::Owned(addr.to_string()),
+        }
+    }
+}
+
+// the form used in URLs and host:port pairs: IPv6 addresses are bracketed
+impl Display for Host<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Host::Hostname(name) => f.write_str(name),
+            Host::Ipv4(addr) => write!(f, "{}", addr),
+            Host::Ipv6(addr, None) => write!(f, "[{}]", addr),
+            Host::Ipv6(addr, Some(zone)) => write!(f, "[{}%{}]", addr, zone),
+        }
+    }
+}
+
+/// Removes the brackets around an IPv6 address, so `[::1]` and `::1` are stored the same way.
+pub(crate) fn unbracket(host: &str) -> &str {
+    match host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+    {
+        Some(addr) if Host::parse(addr).is_ip() => addr,
+        _ => host,
+    }
+}
+
+/// Parses a connection URL, returning it together with its host in the form it is stored in
+/// the connect options.
+///
+/// An IPv6 address in brackets may carry a percent-encoded zone id, as in
+/// `postgres://[fe80::1%25eth0]/db`; URLs do not allow them, so it is taken out before the rest
+/// is parsed.
+pub(crate) fn parse_url(s: &str) -> Result<(Url, Option<String>), Error> {
+    let (s, zone) = match take_zone(s) {
+        Some((s, zone)) => (Cow::Owned(s), Some(zone)),
+        None => (Cow::Borrowed(s), None),
+    };
+
+    let url: Url = s.parse().map_err(Error::config)?;
+
+    let host = match url.host() {
+        Some(url::Host::Domain(name)) => Some(name.to_owned()),
+        Some(url::Host::Ipv4(addr)) => Some(addr.to_string()),
+        Some(url::Host::Ipv6(addr)) => Some(match zone {
+            Some(zone) => format!("{}%{}", addr, zone),
+            None => addr.to_string(),
+        }),
+        None => None,
+    };
+
+    Ok((url, host))
+}
+
+// splits `%25<zone>` out of a bracketed host, returning the URL without it and the decoded zone
+fn take_zone(s: &str) -> Option<(String, String)> {
+    let authority_start = s.find("://")? + 3;
+    let authority_end = s[authority_start..]
+        .find(&['/', '?', '#'][..])
+        .map_or(s.len(), |i| authority_start + i);
+
+    let authority = &s[authority_start..authority_end];
+    let host_start = authority.rfind('@').map_or(0, |i| i + 1);
+
+    let host = authority[host_start..].strip_prefix('[')?;
+    let host_end = host.find(']')?;
+    let zone_start = host[..host_end].find("%25")?;
+
+    let zone = percent_decode_str(&host[zone_start + 3..host_end])
+        .decode_utf8_lossy()
+        .into_owned();
+
+    // `host` starts just after the `[`
+    let bracket = authority_start + host_start + 1;
+    let without = format!("{}{}", &s[..bracket + zone_start], &s[bracket + host_end..]);
+
+    Some((without, zone))
+}
+
+#[test]
+fn it_classifies_hosts() {
+    assert_eq!(Host::parse("localhost"), Host::Hostname("localhost"));
+    assert_eq!(
+        Host::parse("10.0.0.1"),
+        Host::Ipv4(Ipv4Addr::new(10, 0, 0, 1))
+    );
+    assert_eq!(Host::parse("::1"), Host::Ipv6(Ipv6Addr::LOCALHOST, None));
+    assert_eq!(
+        Host::parse("fe80::1%eth0"),
+        Host::Ipv6("fe80::1".parse().unwrap(), Some("eth0"))
+    );
+
+    assert_eq!(Host::parse("fe80::1%eth0").to_string(), "[fe80::1%eth0]");
+    assert_eq!(Host::parse("fe80::1%eth0").tls_name(), "fe80::1");
+    assert_eq!(Host::parse("10.0.0.1").to_string(), "10.0.0.1");
+
+    assert_eq!(unbracket("[::1]"), "::1");
+    assert_eq!(unbracket("::1"), "::1");
+    assert_eq!(unbracket("[example.com]"), "[example.com]");
+}
+
+#[test]
+fn it_parses_urls_with_any_host() {
+    let host = |url: &str| parse_url(url).unwrap().1;
+
+    assert_eq!(
+        host("postgres://example.com/db").as_deref(),
+        Some("example.com")
+    );
+    assert_eq!(
+        host("postgres://10.0.0.1:5432").as_deref(),
+        Some("10.0.0.1")
+    );
+    assert_eq!(host("postgres://[::1]:5432/db").as_deref(), Some("::1"));
+    assert_eq!(
+        host("postgres://u:p@[fe80::1%25eth0]:5432/db?sslmode=disable").as_deref(),
+        Some("fe80::1%eth0")
+    );
+    assert_eq!(host("postgres:///db"), None);
+
+    let (url, _) = parse_url("mysql://u:p@[fe80::1%25eth0]:3306/db").unwrap();
+
+    assert_eq!(url.username(), "u");
+    assert_eq!(url.port(), Some(3306));
+    assert_eq!(url.path(), "/db");
+
+    assert!(parse_url("postgres://[::1/db").is_err());
+}
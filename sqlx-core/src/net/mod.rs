@@ -1,17 +1,26 @@
+mod host;
 mod socket;
 mod tls;
 
+#[cfg_attr(
+    not(any(feature = "postgres", feature = "mysql", feature = "mssql")),
+    allow(unused_imports)
+)]
+pub(crate) use host::{parse_url, unbracket, Host};
 pub use socket::Socket;
-pub use tls::{CertificateInput, MaybeTlsStream};
+pub(crate) use socket::SocketOwner;
+pub use tls::{
+    CertificateInput, MaybeTlsStream, TlsSessionCache, DEFAULT_TLS_SESSION_CACHE_CAPACITY,
+};
 
 #[cfg(feature = "_rt-async-std")]
-type PollReadBuf<'a> = [u8];
+pub(crate) type PollReadBuf<'a> = [u8];
 
 #[cfg(any(feature = "_rt-actix", feature = "_rt-tokio"))]
-type PollReadBuf<'a> = sqlx_rt::ReadBuf<'a>;
+pub(crate) type PollReadBuf<'a> = sqlx_rt::ReadBuf<'a>;
 
 #[cfg(feature = "_rt-async-std")]
-type PollReadOut = usize;
+pub(crate) type PollReadOut = usize;
 
 #[cfg(any(feature = "_rt-actix", feature = "_rt-tokio"))]
-type PollReadOut = ();
+pub(crate) type PollReadOut = ();
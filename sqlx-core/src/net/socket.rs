@@ -1,12 +1,24 @@
 #![allow(dead_code)]
 
 use std::io;
+use std::net::SocketAddr;
 use std::path::Path;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use sqlx_rt::{AsyncRead, AsyncWrite, TcpStream};
 
+use crate::error::Error;
+
+/// The user a Unix socket of a server must belong to, from the `expected_socket_owner` of the
+/// connect options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SocketOwner {
+    pub uid: u32,
+    // whether to check the process at the other end as well as the file
+    pub check_peer: bool,
+}
+
 #[derive(Debug)]
 pub enum Socket {
     Tcp(TcpStream),
@@ -20,6 +32,28 @@ impl Socket {
         TcpStream::connect((host, port)).await.map(Socket::Tcp)
     }
 
+    /// Connects to the first of `addresses` that accepts, in order, without resolving `host`,
+    /// which only names the server in the error.
+    pub async fn connect_addresses(addresses: &[SocketAddr], host: &str) -> io::Result<Self> {
+        match TcpStream::connect(addresses).await {
+            Ok(stream) => Ok(Socket::Tcp(stream)),
+
+            Err(error) => {
+                let addresses: Vec<String> = addresses.iter().map(ToString::to_string).collect();
+
+                Err(io::Error::new(
+                    error.kind(),
+                    format!(
+                        "could not connect to {:?} at {}: {}",
+                        host,
+                        addresses.join(", "),
+                        error
+                    ),
+                ))
+            }
+        }
+    }
+
     #[cfg(unix)]
     pub async fn connect_uds(path: impl AsRef<Path>) -> io::Result<Self> {
         sqlx_rt::UnixStream::connect(path.as_ref())
@@ -35,6 +69,83 @@ impl Socket {
         ))
     }
 
+    /// Connects to the Unix socket at `path` as [`connect_uds`](Self::connect_uds) does, after
+    /// checking that the socket file is owned by `owner.uid`, and then, if asked to, that the
+    /// server process at the other end runs as that user.
+    #[cfg(unix)]
+    pub async fn connect_uds_owned_by(
+        path: impl AsRef<Path>,
+        owner: &SocketOwner,
+    ) -> Result<Self, Error> {
+        use std::os::unix::fs::MetadataExt;
+
+        let path = path.as_ref();
+        let file_uid = sqlx_rt::fs::metadata(path).await?.uid();
+
+        if file_uid != owner.uid {
+            return Err(Error::PolicyViolation(format!(
+                "socket {} is owned by uid {}, not by the expected uid {}",
+                path.display(),
+                file_uid,
+                owner.uid
+            )));
+        }
+
+        let socket = Self::connect_uds(path).await?;
+
+        if owner.check_peer {
+            let peer_uid = socket.peer_uid()?;
+
+            if peer_uid != owner.uid {
+                return Err(Error::PolicyViolation(format!(
+                    "server at socket {} runs as uid {}, not as the expected uid {}",
+                    path.display(),
+                    peer_uid,
+                    owner.uid
+                )));
+            }
+        }
+
+        Ok(socket)
+    }
+
+    #[cfg(not(unix))]
+    pub async fn connect_uds_owned_by(
+        path: impl AsRef<Path>,
+        _: &SocketOwner,
+    ) -> Result<Self, Error> {
+        Ok(Self::connect_uds(path).await?)
+    }
+
+    /// The user ID of the process at the other end of a Unix socket.
+    ///
+    /// Read through the runtime, so it is only known with Tokio or actix; async-std has no way
+    /// to read the credentials of the peer.
+    #[cfg(unix)]
+    pub fn peer_uid(&self) -> io::Result<u32> {
+        match self {
+            #[cfg(all(
+                any(feature = "_rt-tokio", feature = "_rt-actix"),
+                not(feature = "_rt-async-std"),
+            ))]
+            Socket::Unix(s) => s.peer_cred().map(|cred| cred.uid()),
+
+            #[cfg(all(
+                feature = "_rt-async-std",
+                not(any(feature = "_rt-actix", feature = "_rt-tokio")),
+            ))]
+            Socket::Unix(_) => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "the peer credentials of a Unix socket cannot be read with the async-std runtime",
+            )),
+
+            Socket::Tcp(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the peer credentials of a TCP socket are unknown",
+            )),
+        }
+    }
+
     pub async fn shutdown(&mut self) -> io::Result<()> {
         #[cfg(feature = "_rt-async-std")]
         {
@@ -120,3 +231,30 @@ impl AsyncWrite for Socket {
         }
     }
 }
+
+#[cfg(all(
+    unix,
+    any(feature = "_rt-tokio", feature = "_rt-actix"),
+    not(feature = "_rt-async-std"),
+))]
+#[test]
+fn it_reads_the_uid_of_the_peer() -> io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let runtime = sqlx_rt::tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()?;
+    let _entered = runtime.enter();
+
+    let (client, _server) = sqlx_rt::UnixStream::pair()?;
+
+    // the user running the test, who owns the files it creates
+    let path = std::env::temp_dir().join(format!("sqlx-peer-uid-{}", std::process::id()));
+    std::fs::write(&path, b"")?;
+    let uid = std::fs::metadata(&path)?.uid();
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(Socket::Unix(client).peer_uid()?, uid);
+
+    Ok(())
+}
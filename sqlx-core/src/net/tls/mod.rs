@@ -8,7 +8,9 @@ use std::task::{Context, Poll};
 
 use sqlx_rt::{AsyncRead, AsyncWrite, TlsStream};
 
+use crate::connection::TlsVersion;
 use crate::error::Error;
+use crate::net::Host;
 use std::mem::replace;
 
 /// X.509 Certificate input, either a file path or a PEM encoded inline certificate(s).
@@ -23,9 +25,11 @@ pub enum CertificateInput {
 impl From<String> for CertificateInput {
     fn from(value: String) -> Self {
         let trimmed = value.trim();
-        // Some heuristics according to https://tools.ietf.org/html/rfc7468
-        if trimmed.starts_with("-----BEGIN CERTIFICATE-----")
-            && trimmed.contains("-----END CERTIFICATE-----")
+        // Some heuristics according to https://tools.ietf.org/html/rfc7468; a private key is
+        // taken inline just as a certificate is
+        if (trimmed.starts_with("-----BEGIN CERTIFICATE-----")
+            && trimmed.contains("-----END CERTIFICATE-----"))
+            || (trimmed.starts_with("-----BEGIN ") && trimmed.contains(" PRIVATE KEY-----"))
         {
             CertificateInput::Inline(value.as_bytes().to_vec())
         } else {
@@ -53,6 +57,50 @@ impl std::fmt::Display for CertificateInput {
     }
 }
 
+pub const DEFAULT_TLS_SESSION_CACHE_CAPACITY: usize = 32;
+
+/// TLS sessions kept by the client so that reconnecting to a server can resume a previous
+/// session instead of performing a full handshake.
+///
+/// Clones share the same cache. Sessions are keyed by host name, and are only stored after a
+/// handshake has completed, so a handshake that fails certificate verification never leaves
+/// a session behind to be resumed.
+#[derive(Clone)]
+pub struct TlsSessionCache {
+    capacity: usize,
+
+    #[cfg(feature = "_tls-rustls")]
+    store: std::sync::Arc<::rustls::ClientSessionMemoryCache>,
+}
+
+impl TlsSessionCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+
+            #[cfg(feature = "_tls-rustls")]
+            store: ::rustls::ClientSessionMemoryCache::new(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// An empty cache of the same capacity, sharing nothing with this one.
+    pub fn fresh(&self) -> Self {
+        Self::new(self.capacity)
+    }
+}
+
+impl std::fmt::Debug for TlsSessionCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsSessionCache")
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
 #[cfg(feature = "_tls-rustls")]
 mod rustls;
 
@@ -74,17 +122,45 @@ where
         matches!(self, Self::Tls(_))
     }
 
+    /// The protocol the server picked among those offered with ALPN, if any.
+    pub fn alpn_protocol(&self) -> Option<&[u8]> {
+        match self {
+            #[cfg(feature = "_tls-rustls")]
+            MaybeTlsStream::Tls(stream) => {
+                use ::rustls::Session;
+
+                stream.get_ref().1.get_alpn_protocol()
+            }
+
+            _ => None,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn upgrade(
         &mut self,
         host: &str,
         accept_invalid_certs: bool,
         accept_invalid_hostnames: bool,
         root_cert_path: Option<&CertificateInput>,
+        session_cache: Option<&TlsSessionCache>,
+        minimum_version: Option<TlsVersion>,
+        alpn_protocols: &[&str],
+        client_identity: Option<(&CertificateInput, &CertificateInput)>,
+        cipher_suites: Option<&str>,
     ) -> Result<(), Error> {
+        let host = Host::parse(host);
+
         let connector = configure_tls_connector(
             accept_invalid_certs,
             accept_invalid_hostnames,
             root_cert_path,
+            session_cache,
+            minimum_version,
+            alpn_protocols,
+            client_identity,
+            cipher_suites,
+            host.is_ip(),
         )
         .await?;
 
@@ -103,27 +179,116 @@ where
             }
         };
 
+        // webpki can only check a certificate against a DNS name; with an IP address, any name
+        // does when the name is not checked, as it is not sent either
         #[cfg(feature = "_tls-rustls")]
-        let host = webpki::DNSNameRef::try_from_ascii_str(host)?;
+        let name = match host {
+            Host::Hostname(name) => webpki::DNSNameRef::try_from_ascii_str(name)?,
+
+            _ if accept_invalid_certs || accept_invalid_hostnames => {
+                webpki::DNSNameRef::try_from_ascii_str("ip-address.invalid")?
+            }
+
+            _ => {
+                return Err(Error::tls(format!(
+                    "cannot verify the certificate of {} for its IP address with rustls; \
+                         connect by host name or use native-tls",
+                    host
+                )));
+            }
+        };
+
+        #[cfg(feature = "_tls-native-tls")]
+        let name = host.tls_name();
+        #[cfg(feature = "_tls-native-tls")]
+        let name = &*name;
+
+        let stream = connector.connect(name, stream).await?;
+
+        // rustls supports nothing older than TLS 1.2 and cannot be asked to refuse it, so the
+        // version is checked once the handshake has agreed on one
+        #[cfg(feature = "_tls-rustls")]
+        if let Some(minimum) = minimum_version {
+            use ::rustls::{ProtocolVersion, Session};
+
+            let version = match stream.get_ref().1.get_protocol_version() {
+                Some(ProtocolVersion::TLSv1_3) => TlsVersion::V1_3,
+                _ => TlsVersion::V1_2,
+            };
+
+            if version < minimum {
+                return Err(Error::PolicyViolation(format!(
+                    "server negotiated {}, but the minimum TLS version is {}",
+                    version, minimum
+                )));
+            }
+        }
 
-        *self = MaybeTlsStream::Tls(connector.connect(host, stream).await?);
+        *self = MaybeTlsStream::Tls(stream);
 
         Ok(())
     }
 }
 
 #[cfg(feature = "_tls-native-tls")]
+#[allow(clippy::too_many_arguments)]
 async fn configure_tls_connector(
     accept_invalid_certs: bool,
     accept_invalid_hostnames: bool,
     root_cert_path: Option<&CertificateInput>,
+    // native-tls has no API for session resumption, whatever the platform library does on its
+    // own is all we get
+    _session_cache: Option<&TlsSessionCache>,
+    minimum_version: Option<TlsVersion>,
+    alpn_protocols: &[&str],
+    client_identity: Option<(&CertificateInput, &CertificateInput)>,
+    cipher_suites: Option<&str>,
+    ip_host: bool,
 ) -> Result<sqlx_rt::TlsConnector, Error> {
-    use sqlx_rt::native_tls::{Certificate, TlsConnector};
+    use sqlx_rt::native_tls::{Certificate, Protocol, TlsConnector};
 
+    // needs the `alpn` feature of native-tls, which is not enabled
+    if !alpn_protocols.is_empty() {
+        return Err(Error::Configuration(
+            "ALPN is not supported with native-tls; use rustls".into(),
+        ));
+    }
+
+    // native-tls only loads a client identity from a PKCS #12 archive, not from PEM files
+    if client_identity.is_some() {
+        return Err(Error::Configuration(
+            "a client certificate is not supported with native-tls; use rustls".into(),
+        ));
+    }
+
+    // the platform library picks the cipher suites, with no way to narrow them down
+    if cipher_suites.is_some() {
+        return Err(Error::Configuration(
+            "choosing cipher suites is not supported with native-tls; use rustls".into(),
+        ));
+    }
+
+    // server name indication is for host names only; the certificate is still checked against
+    // the address
     let mut builder = TlsConnector::builder();
     builder
         .danger_accept_invalid_certs(accept_invalid_certs)
-        .danger_accept_invalid_hostnames(accept_invalid_hostnames);
+        .danger_accept_invalid_hostnames(accept_invalid_hostnames)
+        .use_sni(!ip_host);
+
+    // the platform library refuses older versions during the handshake, which then fails
+    // with its own error
+    builder.min_protocol_version(match minimum_version {
+        None => None,
+        Some(TlsVersion::V1_0) => Some(Protocol::Tlsv10),
+        Some(TlsVersion::V1_1) => Some(Protocol::Tlsv11),
+        Some(TlsVersion::V1_2) => Some(Protocol::Tlsv12),
+        Some(TlsVersion::V1_3) => {
+            return Err(Error::Configuration(
+                "a minimum TLS version of 1.3 is not supported with native-tls".into(),
+            ));
+        }
+    });
 
     if !accept_invalid_certs {
         if let Some(ca) = root_cert_path {
@@ -256,3 +421,15 @@ where
         }
     }
 }
+
+#[cfg(feature = "_tls-rustls")]
+#[test]
+fn it_shares_sessions_between_clones_only() {
+    use std::sync::Arc;
+
+    let cache = TlsSessionCache::new(8);
+
+    assert!(Arc::ptr_eq(&cache.store, &cache.clone().store));
+    assert!(!Arc::ptr_eq(&cache.store, &cache.fresh().store));
+    assert_eq!(cache.fresh().capacity(), 8);
+}
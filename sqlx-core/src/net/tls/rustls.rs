@@ -1,7 +1,10 @@
-use crate::net::CertificateInput;
+use crate::connection::TlsVersion;
+use crate::net::{CertificateInput, TlsSessionCache};
+use rustls::ciphersuite::*;
+use rustls::internal::pemfile;
 use rustls::{
-    Certificate, ClientConfig, RootCertStore, ServerCertVerified, ServerCertVerifier, TLSError,
-    WebPKIVerifier,
+    Certificate, ClientConfig, RootCertStore, ServerCertVerified, ServerCertVerifier,
+    SupportedCipherSuite, TLSError, WebPKIVerifier,
 };
 use std::io::Cursor;
 use std::sync::Arc;
@@ -9,13 +12,106 @@ use webpki::DNSNameRef;
 
 use crate::error::Error;
 
+// the cipher suites of rustls, by their IANA and OpenSSL names
+static CIPHER_SUITES: [(&str, &str, &SupportedCipherSuite); 9] = [
+    (
+        "TLS_AES_128_GCM_SHA256",
+        "TLS_AES_128_GCM_SHA256",
+        &TLS13_AES_128_GCM_SHA256,
+    ),
+    (
+        "TLS_AES_256_GCM_SHA384",
+        "TLS_AES_256_GCM_SHA384",
+        &TLS13_AES_256_GCM_SHA384,
+    ),
+    (
+        "TLS_CHACHA20_POLY1305_SHA256",
+        "TLS_CHACHA20_POLY1305_SHA256",
+        &TLS13_CHACHA20_POLY1305_SHA256,
+    ),
+    (
+        "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+        "ECDHE-ECDSA-AES128-GCM-SHA256",
+        &TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
+    ),
+    (
+        "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384",
+        "ECDHE-ECDSA-AES256-GCM-SHA384",
+        &TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
+    ),
+    (
+        "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256",
+        "ECDHE-ECDSA-CHACHA20-POLY1305",
+        &TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+    ),
+    (
+        "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+        "ECDHE-RSA-AES128-GCM-SHA256",
+        &TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+    ),
+    (
+        "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+        "ECDHE-RSA-AES256-GCM-SHA384",
+        &TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+    ),
+    (
+        "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+        "ECDHE-RSA-CHACHA20-POLY1305",
+        &TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+    ),
+];
+
+#[allow(clippy::too_many_arguments)]
 pub async fn configure_tls_connector(
     accept_invalid_certs: bool,
     accept_invalid_hostnames: bool,
     root_cert_path: Option<&CertificateInput>,
+    session_cache: Option<&TlsSessionCache>,
+    // checked by the caller once the handshake is done
+    _minimum_version: Option<TlsVersion>,
+    alpn_protocols: &[&str],
+    client_identity: Option<(&CertificateInput, &CertificateInput)>,
+    cipher_suites: Option<&str>,
+    ip_host: bool,
 ) -> Result<sqlx_rt::TlsConnector, Error> {
     let mut config = ClientConfig::new();
 
+    if let Some(list) = cipher_suites {
+        config.ciphersuites = select_cipher_suites(list)?;
+    }
+
+    if let Some((cert, key)) = client_identity {
+        let certs = pemfile::certs(&mut Cursor::new(cert.data().await?))
+            .ok()
+            .filter(|certs| !certs.is_empty())
+            .ok_or_else(|| Error::tls(format!("Invalid certificate {}", cert)))?;
+
+        // unlike a certificate, an inline key is not written into the error
+        let key = private_key(&key.data().await?).ok_or_else(|| match key {
+            CertificateInput::File(path) => {
+                Error::tls(format!("Invalid private key file: {}", path.display()))
+            }
+            CertificateInput::Inline(_) => Error::tls("Invalid private key"),
+        })?;
+
+        config
+            .set_single_client_cert(certs, key)
+            .map_err(Error::tls)?;
+    }
+
+    config.alpn_protocols = alpn_protocols
+        .iter()
+        .map(|protocol| protocol.as_bytes().to_vec())
+        .collect();
+
+    // server name indication is for host names only, and sessions are keyed by the name, which
+    // is a placeholder for an IP address
+    config.enable_sni = !ip_host;
+
+    if let Some(cache) = session_cache.filter(|_| !ip_host) {
+        config.set_persistence(cache.store.clone());
+    }
+
     if accept_invalid_certs {
         config
             .dangerous()
@@ -31,7 +127,7 @@ pub async fn configure_tls_connector(
             config
                 .root_store
                 .add_pem_file(&mut cursor)
-                .map_err(|_| Error::Tls(format!("Invalid certificate {}", ca).into()))?;
+                .map_err(|_| Error::tls(format!("Invalid certificate {}", ca)))?;
         }
 
         if accept_invalid_hostnames {
@@ -44,6 +140,50 @@ pub async fn configure_tls_connector(
     Ok(Arc::new(config).into())
 }
 
+// the suites named in a colon-separated list, in the order of the list; as with OpenSSL, names
+// that are not known are skipped, but at least one must be
+fn select_cipher_suites(list: &str) -> Result<Vec<&'static SupportedCipherSuite>, Error> {
+    let mut selected: Vec<&'static SupportedCipherSuite> = Vec::new();
+
+    for name in list.split(':').map(str::trim) {
+        let suite = CIPHER_SUITES
+            .iter()
+            .find(|(iana, openssl, _)| name.eq_ignore_ascii_case(iana) || name == *openssl);
+
+        if let Some((_, _, suite)) = suite {
+            if !selected.iter().any(|s| std::ptr::eq(*s, *suite)) {
+                selected.push(*suite);
+            }
+        }
+    }
+
+    if selected.is_empty() {
+        return Err(Error::Configuration(
+            format!(
+                "none of the cipher suites {:?} is supported by rustls",
+                list
+            )
+            .into(),
+        ));
+    }
+
+    Ok(selected)
+}
+
+// a private key in PKCS #8 or PKCS #1 (RSA) form; the first one found is used
+fn private_key(pem: &[u8]) -> Option<rustls::PrivateKey> {
+    let pkcs8 = pemfile::pkcs8_private_keys(&mut Cursor::new(pem)).ok()?;
+
+    if let Some(key) = pkcs8.into_iter().next() {
+        return Some(key);
+    }
+
+    pemfile::rsa_private_keys(&mut Cursor::new(pem))
+        .ok()?
+        .into_iter()
+        .next()
+}
+
 struct DummyTlsVerifier;
 
 impl ServerCertVerifier for DummyTlsVerifier {
@@ -77,3 +217,23 @@ impl ServerCertVerifier for NoHostnameTlsVerifier {
         }
     }
 }
+
+#[test]
+fn it_selects_cipher_suites_by_name() {
+    let suites = select_cipher_suites(
+        "ECDHE-RSA-AES256-GCM-SHA384:DHE-RSA-AES128-SHA:tls_aes_128_gcm_sha256",
+    )
+    .unwrap();
+
+    assert_eq!(suites.len(), 2);
+    assert!(std::ptr::eq(
+        suites[0],
+        &TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384
+    ));
+    assert!(std::ptr::eq(suites[1], &TLS13_AES_128_GCM_SHA256));
+
+    assert!(matches!(
+        select_cipher_suites("DHE-RSA-AES128-SHA"),
+        Err(Error::Configuration(_))
+    ));
+}
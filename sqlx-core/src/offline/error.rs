@@ -0,0 +1,56 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::error::Error;
+
+/// An error of generating or verifying the query data of a workspace.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum OfflineError {
+    #[error("failed to read or write {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("failed to parse {}: {source}", path.display())]
+    Json {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// The file was written by a newer version of SQLx, in a format this one cannot read.
+    #[error(
+        "sqlx-data.json has format version {0}, but only versions up to {} are supported",
+        super::FORMAT_VERSION
+    )]
+    UnsupportedVersion(u32),
+
+    /// The URL is not of a database whose feature is enabled.
+    #[error("no enabled database driver for the URL {0:?}")]
+    UnsupportedDatabase(String),
+
+    /// The data is for another database than that of the URL.
+    #[error("sqlx-data.json is for {saved}, not {current}")]
+    DatabaseMismatch {
+        saved: String,
+        current: &'static str,
+    },
+
+    /// `DATABASE_URL` is not set, for [`verify`](super::verify).
+    #[error("DATABASE_URL must be set to verify the query data")]
+    MissingDatabaseUrl,
+
+    #[error("while connecting: {0}")]
+    Connect(#[source] Error),
+
+    /// A query could not be described, e.g. as it refers to a table that does not exist.
+    #[error("failed to describe {query:?}: {source}")]
+    Describe {
+        query: String,
+        #[source]
+        source: Error,
+    },
+}
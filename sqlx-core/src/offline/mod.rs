@@ -0,0 +1,565 @@
+//! Generating and verifying the query data the query macros build with offline, as a library
+//! call rather than with `cargo sqlx prepare`, e.g. from a build script or an `xtask`.
+//!
+//! [`generate`] finds the query macros in the source of a workspace, describes each of their
+//! queries against a live database and writes `sqlx-data.json` to the root of the workspace,
+//! where the macros look for it when `SQLX_OFFLINE` is set. [`verify`] describes the queries
+//! again and returns how the data in the file differs, e.g. in CI:
+//!
+//! ```rust,no_run
+//! # async fn example() -> Result<(), sqlx_core::offline::OfflineError> {
+//! let differences = sqlx_core::offline::verify(env!("CARGO_MANIFEST_DIR")).await?;
+//!
+//! for difference in &differences {
+//!     eprintln!("{}", difference);
+//! }
+//!
+//! assert!(differences.is_empty(), "sqlx-data.json is out of date");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The queries are found by reading the source, not by compiling it: every invocation of one of
+//! the query macros whose query is a string literal, or the path of a file for `query_file!`
+//! and its variants, is described, including ones in code that is not compiled, e.g. behind a
+//! feature that is not enabled. Comments are skipped.
+
+// only the databases that can be described offline have query data
+#![cfg_attr(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "mssql"
+    )),
+    allow(dead_code, unreachable_code)
+)]
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use either::Either;
+
+use crate::column::Column;
+use crate::connection::Connection;
+use crate::database::Database;
+use crate::describe::Describe;
+use crate::executor::Executor;
+use crate::type_info::TypeInfo;
+
+mod error;
+mod scan;
+
+pub use error::OfflineError;
+
+/// The version of the format of `sqlx-data.json` written by this version of SQLx.
+///
+/// Files without a version were written before it was added, in the format of version 1.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The name of the file of the query data, in the root of the workspace.
+pub const DATA_FILE: &str = "sqlx-data.json";
+
+/// The query data of a workspace, as written to `sqlx-data.json`.
+///
+/// The file is a JSON object of the `version` of its format, the `db` the data is for, and the
+/// data of each query, keyed by the SHA-256 hash of the query in hex.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SqlxData {
+    #[serde(default = "unversioned")]
+    version: u32,
+
+    db: String,
+
+    #[serde(flatten)]
+    queries: BTreeMap<String, QueryData>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct QueryData {
+    query: String,
+    describe: serde_json::Value,
+}
+
+fn unversioned() -> u32 {
+    1
+}
+
+impl SqlxData {
+    /// Reads the query data from `sqlx-data.json` in `workspace_root`.
+    pub fn read(workspace_root: impl AsRef<Path>) -> Result<Self, OfflineError> {
+        let path = workspace_root.as_ref().join(DATA_FILE);
+
+        let file = File::open(&path).map_err(|source| OfflineError::Io {
+            path: path.clone(),
+            source,
+        })?;
+
+        let data: Self = serde_json::from_reader(BufReader::new(file))
+            .map_err(|source| OfflineError::Json { path, source })?;
+
+        if data.version > FORMAT_VERSION {
+            return Err(OfflineError::UnsupportedVersion(data.version));
+        }
+
+        Ok(data)
+    }
+
+    /// Writes the query data to `sqlx-data.json` in `workspace_root`.
+    pub fn write(&self, workspace_root: impl AsRef<Path>) -> Result<(), OfflineError> {
+        let path = workspace_root.as_ref().join(DATA_FILE);
+
+        let file = File::create(&path).map_err(|source| OfflineError::Io {
+            path: path.clone(),
+            source,
+        })?;
+
+        serde_json::to_writer_pretty(BufWriter::new(file), self)
+            .map_err(|source| OfflineError::Json { path, source })
+    }
+
+    /// The version of the format the data was read or written in.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The name of the database the data is for, e.g. `PostgreSQL`.
+    pub fn database(&self) -> &str {
+        &self.db
+    }
+
+    /// The queries there is data for, ordered by their hash.
+    pub fn queries(&self) -> impl Iterator<Item = &str> + '_ {
+        self.queries.values().map(|data| &*data.query)
+    }
+}
+
+/// How the query data in `sqlx-data.json` differs from what the database describes now; see
+/// [`verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Difference {
+    /// A query of the source that there is no data for.
+    Missing { query: String },
+
+    /// A query there is data for, which is no longer in the source.
+    Removed { query: String },
+
+    /// The query returns other columns, or the same in another order.
+    Columns {
+        query: String,
+        saved: Vec<String>,
+        current: Vec<String>,
+    },
+
+    /// A column of the query has another type.
+    ColumnType {
+        query: String,
+        column: String,
+        saved: String,
+        current: String,
+    },
+
+    /// Whether a column of the query may be `NULL` changed, or is no longer known.
+    ColumnNullable {
+        query: String,
+        column: String,
+        saved: Option<bool>,
+        current: Option<bool>,
+    },
+
+    /// The query takes parameters of other types, or another number of them.
+    Parameters {
+        query: String,
+        saved: Vec<String>,
+        current: Vec<String>,
+    },
+}
+
+impl Difference {
+    /// The query that differs.
+    pub fn query(&self) -> &str {
+        match self {
+            Difference::Missing { query }
+            | Difference::Removed { query }
+            | Difference::Columns { query, .. }
+            | Difference::ColumnType { query, .. }
+            | Difference::ColumnNullable { query, .. }
+            | Difference::Parameters { query, .. } => query,
+        }
+    }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::Missing { query } => write!(f, "no data for {:?}", query),
+            Difference::Removed { query } => write!(f, "{:?} is no longer used", query),
+
+            Difference::Columns {
+                query,
+                saved,
+                current,
+            } => write!(
+                f,
+                "the columns of {:?} changed from {:?} to {:?}",
+                query, saved, current
+            ),
+
+            Difference::ColumnType {
+                query,
+                column,
+                saved,
+                current,
+            } => write!(
+                f,
+                "the type of column {:?} of {:?} changed from {} to {}",
+                column, query, saved, current
+            ),
+
+            Difference::ColumnNullable {
+                query,
+                column,
+                saved,
+                current,
+            } => write!(
+                f,
+                "whether column {:?} of {:?} is nullable changed from {:?} to {:?}",
+                column, query, saved, current
+            ),
+
+            Difference::Parameters {
+                query,
+                saved,
+                current,
+            } => write!(
+                f,
+                "the parameters of {:?} changed from {:?} to {:?}",
+                query, saved, current
+            ),
+        }
+    }
+}
+
+// calls `$f::<DB>(name, args...)` for the database of `url`, with the name of the database in
+// `sqlx-data.json`; the names match `DatabaseExt::NAME` in `sqlx-macros`
+macro_rules! for_database {
+    ($url:expr, $f:ident($($arg:expr),*)) => {{
+        let url: &str = $url;
+
+        if url.starts_with("postgres:") || url.starts_with("postgresql:") {
+            #[cfg(feature = "postgres")]
+            return $f::<crate::postgres::Postgres>("PostgreSQL", $($arg),*).await;
+        }
+
+        if url.starts_with("mysql:") || url.starts_with("mariadb:") {
+            #[cfg(feature = "mysql")]
+            return $f::<crate::mysql::MySql>("MySQL", $($arg),*).await;
+        }
+
+        if url.starts_with("sqlite:") {
+            #[cfg(feature = "sqlite")]
+            return $f::<crate::sqlite::Sqlite>("SQLite", $($arg),*).await;
+        }
+
+        if url.starts_with("mssql:") || url.starts_with("sqlserver:") {
+            #[cfg(feature = "mssql")]
+            return $f::<crate::mssql::Mssql>("MSSQL", $($arg),*).await;
+        }
+
+        Err(OfflineError::UnsupportedDatabase(
+            url.split(':').next().unwrap_or_default().to_owned(),
+        ))
+    }};
+}
+
+/// Describes every query of the query macros in the workspace at `workspace_root` against the
+/// database at `database_url`, and writes the data to `sqlx-data.json` in `workspace_root`.
+///
+/// This is what `cargo sqlx prepare` does, without compiling the workspace. Fails if any query
+/// cannot be described, leaving the file as it was.
+pub async fn generate(
+    database_url: &str,
+    workspace_root: impl AsRef<Path>,
+) -> Result<SqlxData, OfflineError> {
+    let workspace_root = workspace_root.as_ref();
+    let queries = scan::find_queries(workspace_root)?;
+
+    let data = describe_queries(database_url, &queries).await?;
+    data.write(workspace_root)?;
+
+    Ok(data)
+}
+
+async fn describe_queries(url: &str, queries: &[String]) -> Result<SqlxData, OfflineError> {
+    for_database!(url, generate_with(url, queries))
+}
+
+/// Describes every query of the query macros in the workspace at `workspace_root` against the
+/// database at `DATABASE_URL`, and returns how that differs from the data in `sqlx-data.json`;
+/// the data is up to date if there are no differences.
+///
+/// Fails if the file cannot be read, is for another database or in a newer format, or if a
+/// query cannot be described.
+pub async fn verify(workspace_root: impl AsRef<Path>) -> Result<Vec<Difference>, OfflineError> {
+    let database_url =
+        std::env::var("DATABASE_URL").map_err(|_| OfflineError::MissingDatabaseUrl)?;
+
+    let workspace_root = workspace_root.as_ref();
+    let saved = SqlxData::read(workspace_root)?;
+    let queries = scan::find_queries(workspace_root)?;
+
+    for_database!(&database_url, verify_with(&database_url, &queries, &saved))
+}
+
+// the hash the macros look up the data of a query by
+fn hash(query: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    hex::encode(Sha256::digest(query.as_bytes()))
+}
+
+async fn describe_all<DB>(
+    url: &str,
+    queries: &[String],
+) -> Result<BTreeMap<String, (String, Describe<DB>)>, OfflineError>
+where
+    DB: Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    let mut conn = DB::Connection::connect(url)
+        .await
+        .map_err(OfflineError::Connect)?;
+
+    let mut described = BTreeMap::new();
+
+    for query in queries {
+        let hash = hash(query);
+
+        if described.contains_key(&hash) {
+            continue;
+        }
+
+        let describe = conn
+            .describe(query)
+            .await
+            .map_err(|source| OfflineError::Describe {
+                query: query.clone(),
+                source,
+            })?;
+
+        described.insert(hash, (query.clone(), describe));
+    }
+
+    let _ = conn.close().await;
+
+    Ok(described)
+}
+
+async fn generate_with<DB>(
+    name: &'static str,
+    url: &str,
+    queries: &[String],
+) -> Result<SqlxData, OfflineError>
+where
+    DB: Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    Describe<DB>: serde::Serialize,
+{
+    let mut data = SqlxData {
+        version: FORMAT_VERSION,
+        db: name.to_owned(),
+        queries: BTreeMap::new(),
+    };
+
+    for (hash, (query, describe)) in describe_all::<DB>(url, queries).await? {
+        let describe = serde_json::to_value(&describe).map_err(|source| OfflineError::Json {
+            path: DATA_FILE.into(),
+            source,
+        })?;
+
+        data.queries.insert(hash, QueryData { query, describe });
+    }
+
+    Ok(data)
+}
+
+async fn verify_with<DB>(
+    name: &'static str,
+    url: &str,
+    queries: &[String],
+    saved: &SqlxData,
+) -> Result<Vec<Difference>, OfflineError>
+where
+    DB: Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    Describe<DB>: serde::de::DeserializeOwned,
+{
+    if saved.db != name {
+        return Err(OfflineError::DatabaseMismatch {
+            saved: saved.db.clone(),
+            current: name,
+        });
+    }
+
+    let current = describe_all::<DB>(url, queries).await?;
+    let mut differences = Vec::new();
+
+    for (hash, (query, describe)) in &current {
+        let data = match saved.queries.get(hash) {
+            Some(data) => data,
+
+            None => {
+                differences.push(Difference::Missing {
+                    query: query.clone(),
+                });
+
+                continue;
+            }
+        };
+
+        let saved_describe: Describe<DB> =
+            serde_json::from_value(data.describe.clone()).map_err(|source| OfflineError::Json {
+                path: DATA_FILE.into(),
+                source,
+            })?;
+
+        diff(query, &saved_describe, describe, &mut differences);
+    }
+
+    for (hash, data) in &saved.queries {
+        if !current.contains_key(hash) {
+            differences.push(Difference::Removed {
+                query: data.query.clone(),
+            });
+        }
+    }
+
+    Ok(differences)
+}
+
+fn diff<DB: Database>(
+    query: &str,
+    saved: &Describe<DB>,
+    current: &Describe<DB>,
+    differences: &mut Vec<Difference>,
+) {
+    let (saved_parameters, current_parameters) = (parameter_types(saved), parameter_types(current));
+
+    if saved_parameters != current_parameters {
+        differences.push(Difference::Parameters {
+            query: query.to_owned(),
+            saved: saved_parameters,
+            current: current_parameters,
+        });
+    }
+
+    let names = |describe: &Describe<DB>| -> Vec<String> {
+        describe
+            .columns()
+            .iter()
+            .map(|column| column.name().to_owned())
+            .collect()
+    };
+
+    let (saved_names, current_names) = (names(saved), names(current));
+
+    if saved_names != current_names {
+        differences.push(Difference::Columns {
+            query: query.to_owned(),
+            saved: saved_names,
+            current: current_names,
+        });
+
+        return;
+    }
+
+    for (i, (before, after)) in saved.columns().iter().zip(current.columns()).enumerate() {
+        let (saved_type, current_type) = (before.type_info().name(), after.type_info().name());
+
+        if saved_type != current_type {
+            differences.push(Difference::ColumnType {
+                query: query.to_owned(),
+                column: after.name().to_owned(),
+                saved: saved_type.to_owned(),
+                current: current_type.to_owned(),
+            });
+        }
+
+        if saved.nullable(i) != current.nullable(i) {
+            differences.push(Difference::ColumnNullable {
+                query: query.to_owned(),
+                column: after.name().to_owned(),
+                saved: saved.nullable(i),
+                current: current.nullable(i),
+            });
+        }
+    }
+}
+
+// the names of the types of the parameters, or `?` for each parameter of unknown type
+fn parameter_types<DB: Database>(describe: &Describe<DB>) -> Vec<String> {
+    match describe.parameters() {
+        Some(Either::Left(types)) => types.iter().map(|ty| ty.name().to_owned()).collect(),
+        Some(Either::Right(count)) => vec!["?".to_owned(); count],
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_unversioned_data() {
+        let data: SqlxData = serde_json::from_str(
+            r#"{
+                "db": "PostgreSQL",
+                "7b1b8e3b": { "query": "SELECT 1", "describe": { "columns": [] } }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(data.version(), 1);
+        assert_eq!(data.database(), "PostgreSQL");
+        assert_eq!(data.queries().collect::<Vec<_>>(), ["SELECT 1"]);
+    }
+
+    #[test]
+    fn it_writes_the_version_and_database_first() {
+        let mut data = SqlxData {
+            version: FORMAT_VERSION,
+            db: "MySQL".to_owned(),
+            queries: BTreeMap::new(),
+        };
+
+        data.queries.insert(
+            hash("SELECT 1"),
+            QueryData {
+                query: "SELECT 1".to_owned(),
+                describe: serde_json::json!({}),
+            },
+        );
+
+        // the macros expect `db` before the data of any query
+        let json = serde_json::to_string(&data).unwrap();
+
+        assert!(
+            json.starts_with(r#"{"version":1,"db":"MySQL","#),
+            "{}",
+            json
+        );
+        assert_eq!(serde_json::from_str::<SqlxData>(&json).unwrap(), data);
+    }
+
+    #[test]
+    fn it_hashes_like_the_macros() {
+        assert_eq!(
+            hash("SELECT 1"),
+            "e004ebd5b5532a4b85984a62f8ad48a81aa3460c1ca07701f386135d72cdecf5"
+        );
+    }
+}
@@ -0,0 +1,439 @@
+//! Finding the SQL of the query macros in the source of a workspace, without compiling it.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::offline::OfflineError;
+
+// the macros whose first argument is the type of the record, not the query
+const RECORD_MACROS: &[&str] = &[
+    "query_as",
+    "query_as_unchecked",
+    "query_file_as",
+    "query_file_as_unchecked",
+];
+
+const MACROS: &[&str] = &[
+    "query",
+    "query_unchecked",
+    "query_scalar",
+    "query_scalar_unchecked",
+    "query_file",
+    "query_file_unchecked",
+    "query_file_scalar",
+    "query_file_scalar_unchecked",
+];
+
+/// Returns the SQL of every query macro invoked in the `.rs` files under `root`, reading the
+/// files of `query_file!` and its variants, in the order they were found.
+///
+/// Directories named `target`, and hidden ones, are skipped. Only string literals are
+/// recognized as the query, as the macros accept nothing else; an invocation of one of them
+/// with anything else, e.g. in the expansion of another macro, is skipped.
+pub(super) fn find_queries(root: &Path) -> Result<Vec<String>, OfflineError> {
+    let mut files = Vec::new();
+    collect_files(root, &mut files).map_err(|source| OfflineError::Io {
+        path: root.to_path_buf(),
+        source,
+    })?;
+
+    let mut queries = Vec::new();
+
+    for path in files {
+        let source = fs::read_to_string(&path).map_err(|source| OfflineError::Io {
+            path: path.clone(),
+            source,
+        })?;
+
+        for invocation in find_invocations(&source) {
+            if !invocation.from_file {
+                queries.push(invocation.source);
+                continue;
+            }
+
+            // relative to the manifest of the crate, as for the macros
+            let file = match manifest_dir(&path) {
+                Some(dir) => dir.join(&invocation.source),
+                None => continue,
+            };
+
+            queries.push(
+                fs::read_to_string(&file)
+                    .map_err(|source| OfflineError::Io { path: file, source })?,
+            );
+        }
+    }
+
+    Ok(queries)
+}
+
+// every `.rs` file under `dir`, in a stable order
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    entries.sort();
+
+    for path in entries {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("");
+
+        if path.is_dir() {
+            if name != "target" && !name.starts_with('.') {
+                collect_files(&path, files)?;
+            }
+        } else if name.ends_with(".rs") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+// the directory of the nearest `Cargo.toml` above `file`
+fn manifest_dir(file: &Path) -> Option<&Path> {
+    file.ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Cargo.toml").is_file())
+}
+
+#[derive(Debug, PartialEq)]
+struct Invocation {
+    // the query, or the path of its file
+    source: String,
+    from_file: bool,
+}
+
+fn find_invocations(source: &str) -> Vec<Invocation> {
+    let mut cursor = Cursor { source, pos: 0 };
+    let mut invocations = Vec::new();
+
+    while let Some(c) = cursor.peek() {
+        if c.is_alphabetic() || c == '_' {
+            let ident = cursor.ident();
+
+            // a raw or byte string, not an identifier
+            if matches!(ident, "r" | "br") && matches!(cursor.peek(), Some('"') | Some('#')) {
+                let _ = cursor.raw_string();
+                continue;
+            }
+
+            if ident == "b" && cursor.peek() == Some('"') {
+                let _ = cursor.string_literal();
+                continue;
+            }
+
+            let takes_record = RECORD_MACROS.contains(&ident);
+
+            if takes_record || MACROS.contains(&ident) {
+                let from_file = ident.starts_with("query_file");
+
+                if let Some(source) = cursor.macro_source(takes_record) {
+                    invocations.push(Invocation { source, from_file });
+                }
+            }
+        } else if !cursor.skip_trivia() && !cursor.skip_literal() {
+            cursor.bump();
+        }
+    }
+
+    invocations
+}
+
+struct Cursor<'s> {
+    source: &'s str,
+    pos: usize,
+}
+
+impl<'s> Cursor<'s> {
+    fn rest(&self) -> &'s str {
+        &self.source[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+
+        Some(c)
+    }
+
+    fn ident(&mut self) -> &'s str {
+        let start = self.pos;
+
+        while matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_') {
+            self.bump();
+        }
+
+        &self.source[start..self.pos]
+    }
+
+    // skips whitespace or a comment, if there is one
+    fn skip_trivia(&mut self) -> bool {
+        let rest = self.rest();
+
+        if rest.starts_with("//") {
+            self.pos += rest.find('\n').unwrap_or_else(|| rest.len());
+        } else if rest.starts_with("/*") {
+            // block comments nest
+            let mut depth = 0;
+
+            while !self.rest().is_empty() {
+                if self.rest().starts_with("/*") {
+                    depth += 1;
+                    self.pos += 2;
+                } else if self.rest().starts_with("*/") {
+                    depth -= 1;
+                    self.pos += 2;
+
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    self.bump();
+                }
+            }
+        } else if rest.starts_with(char::is_whitespace) {
+            self.bump();
+        } else {
+            return false;
+        }
+
+        true
+    }
+
+    fn skip_all_trivia(&mut self) {
+        while self.skip_trivia() {}
+    }
+
+    // skips a string or character literal, or a lifetime, if there is one
+    fn skip_literal(&mut self) -> bool {
+        match self.peek() {
+            Some('"') => {
+                let _ = self.string_literal();
+                true
+            }
+
+            Some('\'') => {
+                self.bump();
+
+                // `'\n'`, or `'a'` as opposed to the lifetime `'a`
+                match self.bump() {
+                    Some('\\') => {
+                        self.bump();
+
+                        while !matches!(self.bump(), Some('\'') | None) {}
+                    }
+
+                    Some(_) if self.peek() == Some('\'') => {
+                        self.bump();
+                    }
+
+                    _ => {}
+                }
+
+                true
+            }
+
+            _ => false,
+        }
+    }
+
+    // reads a string literal, raw if it starts with `r`; `None` if there is none
+    fn string_literal(&mut self) -> Option<String> {
+        if self.peek() == Some('r') {
+            self.bump();
+            return self.raw_string();
+        }
+
+        if self.bump() != Some('"') {
+            return None;
+        }
+
+        let mut value = String::new();
+
+        loop {
+            match self.bump()? {
+                '"' => return Some(value),
+                // a malformed escape is left out
+                '\\' => {
+                    let _ = self.escape(&mut value);
+                }
+
+                c => value.push(c),
+            }
+        }
+    }
+
+    // from after `r`: `"..."` or `#"..."#`, with any number of `#`
+    fn raw_string(&mut self) -> Option<String> {
+        let mut hashes = 0;
+
+        while self.peek() == Some('#') {
+            self.bump();
+            hashes += 1;
+        }
+
+        if self.bump() != Some('"') {
+            return None;
+        }
+
+        let terminator = format!("\"{}", "#".repeat(hashes));
+        let end = self.rest().find(&terminator)?;
+        let value = self.rest()[..end].to_owned();
+
+        self.pos += end + terminator.len();
+
+        Some(value)
+    }
+
+    // the value of an escape, from after its `\`
+    fn escape(&mut self, value: &mut String) -> Option<()> {
+        match self.bump()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            '0' => value.push('\0'),
+            '\\' => value.push('\\'),
+            '\'' => value.push('\''),
+            '"' => value.push('"'),
+
+            'x' => {
+                let code = self.rest().get(..2)?;
+                self.pos += 2;
+
+                value.push(u8::from_str_radix(code, 16).ok()? as char);
+            }
+
+            'u' => {
+                let end = self.rest().find('}')?;
+                let code = self.rest().get(1..end)?.replace('_', "");
+                self.pos += end + 1;
+
+                value.push(std::char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+            }
+
+            // a line continuation skips the line break and the indentation after it
+            '\n' | '\r' => {
+                while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+                    self.bump();
+                }
+            }
+
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    // reads `!(...)` after the name of a query macro, returning its query, or the path of its
+    // file; the query may be several literals joined by `+`
+    fn macro_source(&mut self, takes_record: bool) -> Option<String> {
+        self.skip_all_trivia();
+
+        if self.peek() != Some('!') {
+            return None;
+        }
+
+        self.bump();
+        self.skip_all_trivia();
+
+        if !matches!(self.bump(), Some('(') | Some('[') | Some('{')) {
+            return None;
+        }
+
+        self.skip_all_trivia();
+
+        if takes_record {
+            self.skip_argument()?;
+        }
+
+        let mut source = String::new();
+
+        loop {
+            self.skip_all_trivia();
+            source.push_str(&self.string_literal()?);
+            self.skip_all_trivia();
+
+            if self.peek() != Some('+') {
+                return Some(source);
+            }
+
+            self.bump();
+        }
+    }
+
+    // skips the first argument of a macro, up to and including its comma
+    fn skip_argument(&mut self) -> Option<()> {
+        let mut depth = 0_usize;
+
+        loop {
+            if self.skip_trivia() || self.skip_literal() {
+                continue;
+            }
+
+            match self.bump()? {
+                '(' | '[' | '{' | '<' => depth += 1,
+                ')' | ']' | '}' | '>' => depth = depth.checked_sub(1)?,
+                ',' if depth == 0 => return Some(()),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queries(source: &str) -> Vec<(String, bool)> {
+        find_invocations(source)
+            .into_iter()
+            .map(|invocation| (invocation.source, invocation.from_file))
+            .collect()
+    }
+
+    #[test]
+    fn it_finds_query_macros() {
+        let source = r####"
+            // query!("SELECT 'commented out'")
+            /* query!("SELECT /* nested */ 1") */
+            let a = sqlx::query!("SELECT * FROM users WHERE id = $1", id);
+            let b = query_as!(Vec<User, ()>, r#"SELECT "name" FROM users"#);
+            let c = query_scalar! { "SELECT 1" + " + 1" };
+            let d = sqlx::query_file_as!(User, "queries/users.sql", id);
+            let e: &'static str = "query!(\"SELECT 'in a string'\")";
+            let f = query_unchecked!("SELECT 'caf\u{e9}', \
+                                     '\x41'");
+            let g = my_query!("SELECT 'not ours'");
+            let h = query!(SQL);
+        "####;
+
+        assert_eq!(
+            queries(source),
+            [
+                ("SELECT * FROM users WHERE id = $1".to_owned(), false),
+                (r#"SELECT "name" FROM users"#.to_owned(), false),
+                ("SELECT 1 + 1".to_owned(), false),
+                ("queries/users.sql".to_owned(), true),
+                ("SELECT 'café', 'A'".to_owned(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_tells_lifetimes_from_characters() {
+        let source = r#"
+            fn name<'a>(c: char) -> &'a str { if c == '"' { query!("SELECT 1") } }
+        "#;
+
+        assert_eq!(queries(source), [("SELECT 1".to_owned(), false)]);
+    }
+}
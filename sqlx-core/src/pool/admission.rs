@@ -0,0 +1,117 @@
+use super::histogram::RollingHistogram;
+use std::time::Duration;
+
+/// Decides whether a [`Pool::acquire`] that would have to wait for a connection may do so, or
+/// is rejected right away with [`Error::PoolOverloaded`]; see
+/// [`PoolOptions::admission_policy`].
+///
+/// The policy is asked on the task calling `acquire`, and only once the pool has neither an
+/// idle connection nor room to open one, so it is never in the way of a pool that keeps up.
+/// It should be quick and must not block; [`PoolLoad`] only reads counters the pool keeps
+/// anyway.
+///
+/// # Example
+///
+/// Shedding acquires made with [`Priority::Low`] long before the others:
+///
+/// ```
+/// use sqlx_core::pool::{AdmissionPolicy, PoolLoad, Priority};
+///
+/// struct ShedLowPriority;
+///
+/// impl AdmissionPolicy for ShedLowPriority {
+///     fn admit(&self, load: &PoolLoad<'_>, priority: Priority) -> bool {
+///         match priority {
+///             Priority::Low => load.waiters() < 10,
+///             _ => load.waiters() < 100,
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Pool::acquire`]: crate::pool::Pool::acquire
+/// [`Error::PoolOverloaded`]: crate::error::Error::PoolOverloaded
+/// [`PoolOptions::admission_policy`]: crate::pool::PoolOptions::admission_policy
+pub trait AdmissionPolicy: Send + Sync {
+    /// Returns `false` to reject an acquire of `priority`, given the load of the pool.
+    fn admit(&self, load: &PoolLoad<'_>, priority: Priority) -> bool;
+}
+
+/// Rejects acquires while more than this many tasks are already waiting for a connection.
+#[derive(Debug, Clone, Copy)]
+pub struct RejectWhenWaitersExceed(pub usize);
+
+impl AdmissionPolicy for RejectWhenWaitersExceed {
+    fn admit(&self, load: &PoolLoad<'_>, _priority: Priority) -> bool {
+        load.waiters() <= self.0
+    }
+}
+
+/// Rejects acquires while the [estimated wait](PoolLoad::estimated_wait) for a connection is
+/// longer than this.
+#[derive(Debug, Clone, Copy)]
+pub struct RejectWhenEstimatedWaitExceeds(pub Duration);
+
+impl AdmissionPolicy for RejectWhenEstimatedWaitExceeds {
+    fn admit(&self, load: &PoolLoad<'_>, _priority: Priority) -> bool {
+        load.estimated_wait().map_or(true, |wait| wait <= self.0)
+    }
+}
+
+/// How urgently a connection is needed, given with [`Pool::acquire_with_priority`]; a hint for
+/// the [`AdmissionPolicy`] of the pool.
+///
+/// The priority does not change the order tasks are given connections in, which is the order
+/// they started waiting.
+///
+/// [`Pool::acquire_with_priority`]: crate::pool::Pool::acquire_with_priority
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    /// The priority of [`Pool::acquire`](crate::pool::Pool::acquire), and of the queries run
+    /// on the pool directly.
+    Normal,
+    High,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::Normal
+    }
+}
+
+/// The load of a pool at the time an [`AdmissionPolicy`] is asked about an acquire.
+pub struct PoolLoad<'a> {
+    pub(super) waiters: usize,
+    pub(super) size: u32,
+    pub(super) max_connections: u32,
+    pub(super) waits: &'a RollingHistogram,
+}
+
+impl PoolLoad<'_> {
+    /// The number of tasks already waiting for a connection, not counting the one asking.
+    pub fn waiters(&self) -> usize {
+        self.waiters
+    }
+
+    /// The number of connections the pool has open, all of which are checked out.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// The [`max_connections`](crate::pool::PoolOptions::max_connections) of the pool.
+    pub fn max_connections(&self) -> u32 {
+        self.max_connections
+    }
+
+    /// The median of how long the acquires of the last one to two seconds that had to wait did
+    /// so, including those that timed out, rounded up to the buckets of [`DurationHistogram`];
+    /// `None` if none had to.
+    ///
+    /// Waits over ten seconds are counted as ten seconds.
+    ///
+    /// [`DurationHistogram`]: crate::pool::DurationHistogram
+    pub fn estimated_wait(&self) -> Option<Duration> {
+        self.waits.median()
+    }
+}
@@ -0,0 +1,107 @@
+use crate::error::Error;
+use crate::HashMap;
+use parking_lot::Mutex;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+/// Whether a pool has started closing, with the tasks waiting for it to.
+#[derive(Default)]
+pub(super) struct CloseSignal {
+    closed: AtomicBool,
+    next_key: AtomicU64,
+    wakers: Mutex<HashMap<u64, Waker>>,
+}
+
+impl CloseSignal {
+    /// Marks the pool as closing and wakes every [`CloseEvent`] waiting for it.
+    pub(super) fn notify(&self) {
+        self.closed.store(true, Ordering::Release);
+
+        let wakers: Vec<Waker> = self.wakers.lock().drain().map(|(_, waker)| waker).collect();
+
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// A future that resolves once its pool starts closing; see [`Pool::close_event`].
+///
+/// [`Pool::close_event`]: crate::pool::Pool::close_event
+pub struct CloseEvent {
+    signal: Arc<CloseSignal>,
+    key: u64,
+}
+
+impl CloseEvent {
+    pub(super) fn new(signal: Arc<CloseSignal>) -> Self {
+        let key = signal.next_key.fetch_add(1, Ordering::Relaxed);
+
+        Self { signal, key }
+    }
+
+    /// Runs `fut` to completion, unless the pool starts closing first, in which case `fut` is
+    /// dropped and [`Error::PoolClosed`] is returned.
+    ///
+    /// If the pool is already closing, `fut` is not polled at all. Dropping `fut` cancels it
+    /// at its last `.await`, as for any future; a transaction it had open is rolled back when
+    /// its connection goes back to the pool.
+    pub async fn do_until<Fut: Future>(&mut self, fut: Fut) -> Result<Fut::Output, Error> {
+        futures_util::pin_mut!(fut);
+
+        futures_util::future::poll_fn(|cx| {
+            // checked first, so a pool that is closing never runs the future
+            if Pin::new(&mut *self).poll(cx).is_ready() {
+                return Poll::Ready(Err(Error::PoolClosed));
+            }
+
+            fut.as_mut().poll(cx).map(Ok)
+        })
+        .await
+    }
+
+    fn is_closed(&self) -> bool {
+        self.signal.closed.load(Ordering::Acquire)
+    }
+}
+
+impl Future for CloseEvent {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_closed() {
+            return Poll::Ready(());
+        }
+
+        self.signal
+            .wakers
+            .lock()
+            .insert(self.key, cx.waker().clone());
+
+        // the pool may have started closing just before the waker was registered
+        if self.is_closed() {
+            self.signal.wakers.lock().remove(&self.key);
+            return Poll::Ready(());
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for CloseEvent {
+    fn drop(&mut self) {
+        self.signal.wakers.lock().remove(&self.key);
+    }
+}
+
+impl fmt::Debug for CloseEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CloseEvent")
+            .field("is_closed", &self.is_closed())
+            .finish()
+    }
+}
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::common::config::{duration, optional_duration};
+use crate::database::Database;
+use crate::pool::PoolOptions;
+
+// Settings that are left out keep the defaults of `PoolOptions::new()`; the ones that can be
+// turned off are turned off with `null`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PoolConfig {
+    max_connections: Option<u32>,
+    min_connections: Option<u32>,
+    #[serde(default, deserialize_with = "duration::deserialize")]
+    connect_timeout: Option<Duration>,
+    #[serde(default, deserialize_with = "optional_duration::deserialize")]
+    max_lifetime: Option<Option<Duration>>,
+    #[serde(default, deserialize_with = "optional_duration::deserialize")]
+    idle_timeout: Option<Option<Duration>>,
+    #[serde(default, deserialize_with = "optional_duration::deserialize")]
+    checkout_warning_after: Option<Option<Duration>>,
+    test_before_acquire: Option<bool>,
+}
+
+#[derive(Serialize)]
+struct PoolConfigRef {
+    max_connections: u32,
+    min_connections: u32,
+    #[serde(with = "duration")]
+    connect_timeout: Duration,
+    #[serde(with = "optional_duration")]
+    max_lifetime: Option<Duration>,
+    #[serde(with = "optional_duration")]
+    idle_timeout: Option<Duration>,
+    #[serde(with = "optional_duration")]
+    checkout_warning_after: Option<Duration>,
+    test_before_acquire: bool,
+}
+
+/// Accepts a map of `max_connections`, `min_connections`, `connect_timeout`, `max_lifetime`,
+/// `idle_timeout`, `checkout_warning_after` and `test_before_acquire`.
+///
+/// Durations are strings such as `"30s"` or `"1h 30m"`. Settings that are left out keep their
+/// defaults, and `max_lifetime`, `idle_timeout` and `checkout_warning_after` can be set to
+/// `null` to turn them off. Callbacks cannot be deserialized and are left unset.
+impl<'de, DB: Database> Deserialize<'de> for PoolOptions<DB> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = PoolConfig::deserialize(deserializer)?;
+        let mut options = PoolOptions::new();
+
+        if let Some(max) = config.max_connections {
+            options.max_connections = max;
+        }
+
+        if let Some(min) = config.min_connections {
+            options.min_connections = min;
+        }
+
+        if let Some(timeout) = config.connect_timeout {
+            options.connect_timeout = timeout;
+        }
+
+        if let Some(lifetime) = config.max_lifetime {
+            options.max_lifetime = lifetime;
+        }
+
+        if let Some(timeout) = config.idle_timeout {
+            options.idle_timeout = timeout;
+        }
+
+        if let Some(threshold) = config.checkout_warning_after {
+            options.checkout_warning_after = threshold;
+        }
+
+        if let Some(test) = config.test_before_acquire {
+            options.test_before_acquire = test;
+        }
+
+        Ok(options)
+    }
+}
+
+/// Serializes the same settings that [`Deserialize`] accepts.
+///
+/// Formats without `null`, such as TOML, leave out the settings that are turned off, which
+/// then come back with their defaults when deserialized.
+impl<DB: Database> Serialize for PoolOptions<DB> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PoolConfigRef {
+            max_connections: self.max_connections,
+            min_connections: self.min_connections,
+            connect_timeout: self.connect_timeout,
+            max_lifetime: self.max_lifetime,
+            idle_timeout: self.idle_timeout,
+            checkout_warning_after: self.checkout_warning_after,
+            test_before_acquire: self.test_before_acquire,
+        }
+        .serialize(serializer)
+    }
+}
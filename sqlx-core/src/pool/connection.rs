@@ -1,12 +1,16 @@
+use super::credentials::CredentialLease;
 use super::inner::{DecrementSizeGuard, SharedPool};
-use crate::connection::Connection;
+use super::{AcquireStats, PoolStats};
+use crate::connection::{Connection, SessionState};
 use crate::database::Database;
 use crate::error::Error;
-use sqlx_rt::spawn;
+use crate::events::CloseReason;
+use sqlx_rt::{spawn, Instant};
 use std::fmt::{self, Debug, Formatter};
 use std::ops::{Deref, DerefMut};
+use std::panic::Location;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::Duration;
 
 /// A connection managed by a [`Pool`][crate::pool::Pool].
 ///
@@ -14,11 +18,26 @@ use std::time::Instant;
 pub struct PoolConnection<DB: Database> {
     live: Option<Live<DB>>,
     pub(crate) pool: Arc<SharedPool<DB>>,
+    checked_out: Instant,
+    // the entry in the pool's checkout registry, if the reaper is watching this checkout
+    checkout_id: Option<u64>,
+    // where the time of the `acquire` that returned this connection went
+    acquire_stats: AcquireStats,
 }
 
 pub(super) struct Live<DB: Database> {
     pub(super) raw: DB::Connection,
     pub(super) created: Instant,
+    // how much of the connection's stats has been added to the pool's totals
+    pub(super) reported: PoolStats,
+    // the state of the session when it was last returned, if the pool tracks it
+    pub(super) session_state: Option<SessionState>,
+    // the credential from the pool's `password_provider` the connection was opened with, if it
+    // expires
+    pub(super) credential: Option<CredentialLease>,
+    // what the pool's `max_lifetime` and `idle_timeout` are multiplied by for this connection,
+    // drawn when it was opened from the pool's `lifetime_jitter`
+    pub(super) lifetime_factor: f64,
 }
 
 pub(super) struct Idle<DB: Database> {
@@ -37,7 +56,15 @@ const DEREF_ERR: &str = "(bug) connection already released to pool";
 impl<DB: Database> Debug for PoolConnection<DB> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         // TODO: Show the type name of the connection ?
-        f.debug_struct("PoolConnection").finish()
+        let mut f = f.debug_struct("PoolConnection");
+
+        if let Some(live) = &self.live {
+            f.field("age", &live.created.elapsed())
+                .field("max_lifetime", &self.max_lifetime())
+                .field("idle_timeout", &self.idle_timeout());
+        }
+
+        f.finish()
     }
 }
 
@@ -56,23 +83,97 @@ impl<DB: Database> DerefMut for PoolConnection<DB> {
 }
 
 impl<DB: Database> PoolConnection<DB> {
+    /// Where the time of the [`acquire`][crate::pool::Pool::acquire] that returned this
+    /// connection went: waiting in the queue, opening a connection, or pinging one.
+    ///
+    /// All zeros for a connection from [`try_acquire`][crate::pool::Pool::try_acquire], which
+    /// neither waits nor opens one.
+    pub fn acquire_stats(&self) -> AcquireStats {
+        self.acquire_stats
+    }
+
+    /// The [`max_lifetime`] of this connection: that of the pool, moved by up to its
+    /// [`lifetime_jitter`] when the connection was opened. `None` if the pool has none.
+    ///
+    /// The connection is closed once it is older than this; see also [`Connection::stats`].
+    ///
+    /// [`max_lifetime`]: crate::pool::PoolOptions::max_lifetime
+    /// [`lifetime_jitter`]: crate::pool::PoolOptions::lifetime_jitter
+    pub fn max_lifetime(&self) -> Option<Duration> {
+        let max_lifetime = self.pool.timeouts().max_lifetime?;
+
+        Some(max_lifetime.mul_f64(self.lifetime_factor()))
+    }
+
+    /// The [`idle_timeout`] of this connection: that of the pool, moved by up to its
+    /// [`lifetime_jitter`] when the connection was opened. `None` if the pool has none.
+    ///
+    /// [`idle_timeout`]: crate::pool::PoolOptions::idle_timeout
+    /// [`lifetime_jitter`]: crate::pool::PoolOptions::lifetime_jitter
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        let idle_timeout = self.pool.timeouts().idle_timeout?;
+
+        Some(idle_timeout.mul_f64(self.lifetime_factor()))
+    }
+
+    fn lifetime_factor(&self) -> f64 {
+        self.live.as_ref().expect(DEREF_ERR).lifetime_factor
+    }
+
+    /// Marks this connection as legitimately held for a long time (e.g. by a task that listens
+    /// for notifications for the lifetime of the application), so that it is not reported by
+    /// [`PoolOptions::checkout_warning_after`][crate::pool::PoolOptions::checkout_warning_after].
+    ///
+    /// The connection is still returned to the pool when dropped.
+    pub fn leaked_detach(&mut self) {
+        if let Some(id) = self.checkout_id.take() {
+            self.pool.forget_checkout(id);
+        }
+    }
+
+    /// Takes the connection out of the pool, to be owned by the caller; it is not returned to
+    /// the pool when dropped.
+    ///
+    /// The slot of the connection is given back at once: the pool counts one fewer connection
+    /// in [`size`][crate::pool::Pool::size], so that it may open another in its place without
+    /// going over [`max_connections`][crate::pool::PoolOptions::max_connections]. A pool with
+    /// [`min_connections`][crate::pool::PoolOptions::min_connections] replaces it the next time
+    /// the reaper runs, if it would otherwise have fewer.
+    pub fn detach(mut self) -> DB::Connection {
+        let mut live = self.live.take().expect("PoolConnection double-dropped");
+        self.pool.record_stats(&mut live);
+
+        live.float(&self.pool).detach()
+    }
+
     /// Explicitly release a connection from the pool
-    pub fn release(mut self) -> DB::Connection {
-        self.live
-            .take()
-            .expect("PoolConnection double-dropped")
-            .float(&self.pool)
-            .detach()
+    #[deprecated = "renamed to `.detach()` for clarity"]
+    pub fn release(self) -> DB::Connection {
+        self.detach()
     }
 }
 
 /// Returns the connection to the [`Pool`][crate::pool::Pool] it was checked-out from.
 impl<DB: Database> Drop for PoolConnection<DB> {
     fn drop(&mut self) {
+        self.pool
+            .end_checkout(self.checked_out, self.checkout_id.take());
+
         if let Some(mut live) = self.live.take() {
             let pool = self.pool.clone();
 
-            if live.raw.should_flush() {
+            if live.raw.saw_server_shutdown() {
+                // the server ended the connection, and most likely the idle ones with it
+                pool.server_shut_down();
+
+                spawn(async move {
+                    let _ = live
+                        .float(&pool)
+                        .into_idle()
+                        .close(CloseReason::Broken)
+                        .await;
+                });
+            } else if live.raw.should_flush() || pool.options.track_session_state {
                 spawn(async move {
                     // flush the connection (will immediately return if not needed) before
                     // we fully release to the pool
@@ -81,12 +182,33 @@ impl<DB: Database> Drop for PoolConnection<DB> {
 
                         // we now consider the connection to be broken
                         // close the connection and drop from the pool
-                        let _ = live.float(&pool).into_idle().close().await;
+                        let _ = live.float(&pool).into_idle().close_broken(&e).await;
+                    } else if pool.should_yield() {
+                        let _ = live
+                            .float(&pool)
+                            .into_idle()
+                            .close(CloseReason::Explicit)
+                            .await;
                     } else {
+                        if pool.options.track_session_state {
+                            pool.check_session_state(&mut live).await;
+                        }
+
                         // after we have flushed successfully, release to the pool
                         pool.release(live.float(&pool));
                     }
                 });
+            } else if pool.should_yield() {
+                // the pool is above a `max_connections` lowered by `resize`, or the partition
+                // has more than its share of a `PartitionedPool` while others wait; closing the
+                // connection hands its slot over to them
+                spawn(async move {
+                    let _ = live
+                        .float(&pool)
+                        .into_idle()
+                        .close(CloseReason::Explicit)
+                        .await;
+                });
             } else {
                 // nothing to flush, release immediately outside of a spawn
                 pool.release(live.float(&pool));
@@ -138,12 +260,21 @@ impl<'s, DB: Database> Floating<'s, Live<DB>> {
             inner: Live {
                 raw: conn,
                 created: Instant::now(),
+                reported: PoolStats::default(),
+                session_state: None,
+                credential: None,
+                lifetime_factor: 1.0,
             },
             guard,
         }
     }
 
-    pub fn attach(self, pool: &Arc<SharedPool<DB>>) -> PoolConnection<DB> {
+    pub fn attach(
+        self,
+        pool: &Arc<SharedPool<DB>>,
+        location: &'static Location<'static>,
+        acquire_stats: AcquireStats,
+    ) -> PoolConnection<DB> {
         let Floating { inner, guard } = self;
 
         debug_assert!(
@@ -155,6 +286,9 @@ impl<'s, DB: Database> Floating<'s, Live<DB>> {
         PoolConnection {
             live: Some(inner),
             pool: Arc::clone(pool),
+            checked_out: Instant::now(),
+            checkout_id: pool.begin_checkout(location),
+            acquire_stats,
         }
     }
 
@@ -162,6 +296,19 @@ impl<'s, DB: Database> Floating<'s, Live<DB>> {
         self.inner.raw
     }
 
+    /// Drops the connection without closing it, telling the listener why.
+    pub fn discard(mut self, reason: CloseReason) {
+        // `guard` is dropped as intended
+        self.inner.raw.take_events().closed(reason);
+    }
+
+    /// Drops a connection found to be broken without closing it, telling the listener why.
+    pub fn discard_broken(mut self, error: &Error) {
+        let events = self.inner.raw.take_events();
+        events.error(error);
+        events.closed(CloseReason::Broken);
+    }
+
     pub fn into_idle(self) -> Floating<'s, Idle<DB>> {
         Floating {
             inner: self.inner.into_idle(),
@@ -189,9 +336,27 @@ impl<'s, DB: Database> Floating<'s, Idle<DB>> {
         }
     }
 
-    pub async fn close(self) -> Result<(), Error> {
+    pub async fn close(self, reason: CloseReason) -> Result<(), Error> {
+        self.close_with(None, reason).await
+    }
+
+    /// Closes a connection found to be broken, reporting `error` first.
+    pub async fn close_broken(self, error: &Error) -> Result<(), Error> {
+        self.close_with(Some(error), CloseReason::Broken).await
+    }
+
+    async fn close_with(self, error: Option<&Error>, reason: CloseReason) -> Result<(), Error> {
         // `guard` is dropped as intended
-        self.inner.live.raw.close().await
+        let mut raw = self.inner.live.raw;
+        let events = raw.take_events();
+
+        if let Some(error) = error {
+            events.error(error);
+        }
+
+        let result = raw.close().await;
+        events.closed(reason);
+        result
     }
 }
 
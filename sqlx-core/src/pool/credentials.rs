@@ -0,0 +1,209 @@
+//! Passwords that expire, such as IAM authentication tokens; see [`PasswordProvider`].
+
+use std::cmp;
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use futures_core::future::BoxFuture;
+use parking_lot::Mutex;
+
+use crate::error::Error;
+use crate::HashMap;
+
+/// Provides the password of every connection a pool opens, for credentials that expire, such
+/// as IAM authentication tokens or database credentials leased from Vault.
+///
+/// Set with [`PoolOptions::password_provider`](super::PoolOptions::password_provider). The
+/// pool asks for a password each time it opens a connection, so a provider that fetches its
+/// credentials from elsewhere should cache them until shortly before they expire.
+///
+/// The server only checks the password when a connection is established, so a connection keeps
+/// working after its credential expired. The pool replaces such a connection the next time it
+/// is returned, rather than all of them at once: each connection is given a random point in
+/// the first quarter of the lifetime of its credential after the credential expired, and is
+/// kept until then. This spreads the connections that were opened together, and the requests
+/// for new credentials, over time. [`PoolStats::expired_credentials`] counts the connections
+/// whose credential has expired.
+///
+/// [`PoolStats::expired_credentials`]: super::PoolStats::expired_credentials
+pub trait PasswordProvider: Send + Sync {
+    /// Returns the password to open a connection with, and when it expires.
+    fn password(&self) -> BoxFuture<'_, Result<Credential, Error>>;
+}
+
+/// A password returned by a [`PasswordProvider`], and when it expires.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credential {
+    password: String,
+    expires_at: Option<SystemTime>,
+}
+
+impl Credential {
+    /// A password that does not expire, until [`expires_at`](Self::expires_at) or
+    /// [`expires_in`](Self::expires_in) is set.
+    pub fn new(password: impl Into<String>) -> Self {
+        Self {
+            password: password.into(),
+            expires_at: None,
+        }
+    }
+
+    /// Sets when the password expires.
+    pub fn expires_at(mut self, expires_at: SystemTime) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Sets the password to expire after `lifetime`, counted from now.
+    pub fn expires_in(self, lifetime: Duration) -> Self {
+        self.expires_at(SystemTime::now() + lifetime)
+    }
+
+    /// The password.
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    /// When the password expires, if it does.
+    pub fn expiry(&self) -> Option<SystemTime> {
+        self.expires_at
+    }
+}
+
+// the password is not to end up in logs
+impl Debug for Credential {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credential")
+            .field("password", &"<redacted>")
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+/// The expiry of the credential of every open connection of a pool, for
+/// [`PoolStats::expired_credentials`](super::PoolStats::expired_credentials).
+#[derive(Default)]
+pub(super) struct CredentialExpiries {
+    next_id: AtomicU64,
+    expiries: Mutex<HashMap<u64, SystemTime>>,
+}
+
+impl CredentialExpiries {
+    /// Records the credential of a newly opened connection, if it expires.
+    pub(super) fn lease(self: &Arc<Self>, credential: &Credential) -> Option<CredentialLease> {
+        let expires_at = credential.expires_at?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        self.expiries.lock().insert(id, expires_at);
+
+        Some(CredentialLease {
+            id,
+            recycle_at: recycle_at(id, expires_at),
+            expiries: Arc::clone(self),
+        })
+    }
+
+    /// The number of open connections whose credential has expired.
+    pub(super) fn expired(&self) -> u64 {
+        let now = SystemTime::now();
+
+        self.expiries
+            .lock()
+            .values()
+            .filter(|expires_at| **expires_at <= now)
+            .count() as u64
+    }
+}
+
+/// The credential a connection was opened with, which is forgotten when the connection is
+/// dropped.
+pub(super) struct CredentialLease {
+    id: u64,
+    recycle_at: SystemTime,
+    expiries: Arc<CredentialExpiries>,
+}
+
+impl CredentialLease {
+    /// Whether the connection is to be replaced when it is next returned to the pool.
+    pub(super) fn is_due(&self) -> bool {
+        SystemTime::now() >= self.recycle_at
+    }
+}
+
+impl Drop for CredentialLease {
+    fn drop(&mut self) {
+        self.expiries.expiries.lock().remove(&self.id);
+    }
+}
+
+// a random point in the first quarter of the remaining lifetime of the credential after it
+// expires, so that connections opened with the same credential are not all replaced at once
+fn recycle_at(id: u64, expires_at: SystemTime) -> SystemTime {
+    let spread = expires_at
+        .duration_since(SystemTime::now())
+        .unwrap_or_default()
+        / 4;
+
+    let spread_nanos = cmp::min(spread.as_nanos(), u128::from(u64::MAX)) as u64;
+
+    if spread_nanos == 0 {
+        return expires_at;
+    }
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(id);
+
+    expires_at + Duration::from_nanos(hasher.finish() % spread_nanos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_counts_expired_credentials_of_open_connections() {
+        let expiries = Arc::new(CredentialExpiries::default());
+
+        let expired = Credential::new("old").expires_at(SystemTime::now() - Duration::from_secs(1));
+        let fresh = Credential::new("new").expires_in(Duration::from_secs(3600));
+
+        assert!(expiries.lease(&Credential::new("forever")).is_none());
+
+        let first = expiries.lease(&expired).unwrap();
+        let second = expiries.lease(&fresh).unwrap();
+
+        assert_eq!(expiries.expired(), 1);
+        assert!(first.is_due());
+        assert!(!second.is_due());
+
+        drop(first);
+        assert_eq!(expiries.expired(), 0);
+    }
+
+    #[test]
+    fn it_spreads_recycling_over_a_quarter_of_the_lifetime() {
+        let now = SystemTime::now();
+        let expires_at = now + Duration::from_secs(400);
+
+        for id in 0..100 {
+            let at = recycle_at(id, expires_at);
+
+            assert!(at >= expires_at);
+            assert!(at < expires_at + Duration::from_secs(100));
+        }
+
+        let expired = now - Duration::from_secs(1);
+        assert_eq!(recycle_at(0, expired), expired);
+    }
+
+    #[test]
+    fn it_redacts_the_password() {
+        let credential = Credential::new("hunter2");
+
+        assert!(!format!("{:?}", credential).contains("hunter2"));
+    }
+}
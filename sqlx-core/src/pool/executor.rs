@@ -2,12 +2,15 @@ use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_util::TryStreamExt;
+use std::time::Duration;
 
-use crate::database::{Database, HasStatement};
+use crate::database::{Database, HasArguments, HasStatement};
 use crate::describe::Describe;
 use crate::error::Error;
-use crate::executor::{Execute, Executor};
-use crate::pool::Pool;
+use crate::executor::{Execute, Executor, FetchLimits};
+use crate::pool::query_cache::{Collector, QueryCache};
+use crate::pool::{Pool, PoolConnection};
+use crate::row::Row;
 
 impl<'p, DB: Database> Executor<'p> for &'_ Pool<DB>
 where
@@ -24,6 +27,10 @@ where
     {
         let pool = self.clone();
 
+        if let Some(ttl) = query.cache_ttl() {
+            return fetch_cached(pool, query, ttl);
+        }
+
         Box::pin(try_stream! {
             let mut conn = pool.acquire().await?;
             let mut s = conn.fetch_many(query);
@@ -45,6 +52,23 @@ where
     {
         let pool = self.clone();
 
+        if let Some(ttl) = query.cache_ttl() {
+            // the whole result is read, to be cached
+            let mut rows = fetch_cached(pool, query, ttl);
+
+            return Box::pin(async move {
+                let mut first = None;
+
+                while let Some(step) = rows.try_next().await? {
+                    if let Either::Right(row) = step {
+                        first = first.or(Some(row));
+                    }
+                }
+
+                Ok(first)
+            });
+        }
+
         Box::pin(async move { pool.acquire().await?.fetch_optional(query).await })
     }
 
@@ -69,6 +93,96 @@ where
     }
 }
 
+// the rows of a query marked with `cached`, from the cache of `pool` if they are there;
+// otherwise from the database, and then cached if they were all read
+fn fetch_cached<'e, 'q: 'e, DB, E>(
+    pool: Pool<DB>,
+    mut query: E,
+    ttl: Duration,
+) -> BoxStream<'e, Result<Either<DB::QueryResult, DB::Row>, Error>>
+where
+    DB: Database,
+    E: 'q + Execute<'q, DB>,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    let arguments = query.take_arguments();
+    let key = QueryCache::<DB>::key(query.sql(), arguments.as_ref());
+    let query = Keyed { query, arguments };
+
+    Box::pin(try_stream! {
+        let cache = &pool.0.query_cache;
+
+        if let Some(rows) = key.as_deref().and_then(|key| cache.get(key)) {
+            // rows are only cached if they could be shared
+            for row in rows.iter().filter_map(Row::try_share) {
+                r#yield!(Either::Right(row));
+            }
+
+            return Ok(());
+        }
+
+        let mut conn = pool.acquire().await?;
+        let mut collector = Collector::new(cache);
+        let mut s = conn.fetch_many(query);
+
+        while let Some(v) = s.try_next().await? {
+            if let Either::Right(row) = &v {
+                collector.push(row);
+            }
+
+            r#yield!(v);
+        }
+
+        if let Some(key) = key {
+            collector.finish(cache, key, ttl);
+        }
+
+        Ok(())
+    })
+}
+
+// a query with the arguments that were taken from it to make its key in the query cache
+struct Keyed<'q, DB: Database, E> {
+    query: E,
+    arguments: Option<<DB as HasArguments<'q>>::Arguments>,
+}
+
+impl<'q, DB: Database, E: Execute<'q, DB>> Execute<'q, DB> for Keyed<'q, DB, E> {
+    fn sql(&self) -> &'q str {
+        self.query.sql()
+    }
+
+    fn statement(&self) -> Option<&<DB as HasStatement<'q>>::Statement> {
+        self.query.statement()
+    }
+
+    fn take_arguments(&mut self) -> Option<<DB as HasArguments<'q>>::Arguments> {
+        self.arguments.take()
+    }
+
+    fn persistent(&self) -> bool {
+        self.query.persistent()
+    }
+
+    fn fetch_limits(&self) -> FetchLimits {
+        self.query.fetch_limits()
+    }
+}
+
+impl<DB: Database> Pool<DB>
+where
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    impl_executor_methods!(DB, &self => self);
+}
+
+impl<DB: Database> PoolConnection<DB>
+where
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    impl_executor_methods!(DB, &mut self => &mut **self);
+}
+
 // NOTE: required due to lack of lazy normalization
 #[allow(unused_macros)]
 macro_rules! impl_executor_for_pool_connection {
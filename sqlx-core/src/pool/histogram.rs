@@ -0,0 +1,230 @@
+use sqlx_rt::Instant;
+use std::cmp;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// the upper bounds of the buckets, in milliseconds; the last bucket holds everything above
+const BOUNDS_MILLIS: [u64; 13] = [
+    1, 2, 5, 10, 20, 50, 100, 200, 500, 1_000, 2_000, 5_000, 10_000,
+];
+
+const BUCKETS: usize = BOUNDS_MILLIS.len() + 1;
+
+/// A count of durations in fixed buckets, from under a millisecond to over ten seconds; see
+/// [`PoolStats::acquire_wait`](super::PoolStats::acquire_wait).
+///
+/// The counts start when the pool is created. The histogram of a window of time is the
+/// difference of two taken at its ends, with [`since`](Self::since).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DurationHistogram {
+    counts: [u64; BUCKETS],
+}
+
+impl DurationHistogram {
+    /// The number of durations counted.
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// The upper bound of each bucket, `None` for the last which has none, with the number of
+    /// durations counted in it.
+    pub fn buckets(&self) -> impl Iterator<Item = (Option<Duration>, u64)> + '_ {
+        BOUNDS_MILLIS
+            .iter()
+            .map(|millis| Some(Duration::from_millis(*millis)))
+            .chain(Some(None))
+            .zip(self.counts.iter().copied())
+    }
+
+    /// The upper bound of the bucket that holds the duration at quantile `q` (from 0 to 1),
+    /// e.g. `0.99` for the 99th percentile.
+    ///
+    /// Returns `None` if nothing was counted, or if the duration is in the last bucket, which
+    /// is over ten seconds.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        let count = self.count();
+
+        if count == 0 {
+            return None;
+        }
+
+        let rank = cmp::max((q.max(0.0).min(1.0) * count as f64).ceil() as u64, 1);
+        let mut seen = 0;
+
+        for (bound, n) in self.buckets() {
+            seen += n;
+
+            if seen >= rank {
+                return bound;
+            }
+        }
+
+        None
+    }
+
+    /// The durations counted after `earlier`, a histogram of the same pool taken before.
+    pub fn since(&self, earlier: &DurationHistogram) -> DurationHistogram {
+        let mut counts = self.counts;
+
+        for (count, earlier) in counts.iter_mut().zip(earlier.counts.iter()) {
+            *count = count.saturating_sub(*earlier);
+        }
+
+        DurationHistogram { counts }
+    }
+
+    // adds the counts of another pool, e.g. of one partition of a `PartitionedPool`
+    pub(super) fn add(&mut self, other: &DurationHistogram) {
+        for (count, other) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += other;
+        }
+    }
+}
+
+// the counts behind a `DurationHistogram`, updated by every acquire
+#[derive(Default)]
+pub(super) struct AtomicHistogram {
+    counts: [AtomicU64; BUCKETS],
+}
+
+impl AtomicHistogram {
+    pub(super) fn record(&self, duration: Duration) {
+        let millis = duration.as_nanos() as f64 / 1_000_000.0;
+
+        let bucket = BOUNDS_MILLIS
+            .iter()
+            .position(|bound| millis <= *bound as f64)
+            .unwrap_or(BUCKETS - 1);
+
+        self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn load(&self) -> DurationHistogram {
+        let mut counts = [0; BUCKETS];
+
+        for (count, atomic) in counts.iter_mut().zip(self.counts.iter()) {
+            *count = atomic.load(Ordering::Relaxed);
+        }
+
+        DurationHistogram { counts }
+    }
+
+    fn clear(&self) {
+        for count in &self.counts {
+            count.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+// the durations of the last one to two seconds, kept in two halves that take turns: a half is
+// cleared as it starts counting again, so what it counted two seconds before is forgotten
+pub(super) struct RollingHistogram {
+    start: Instant,
+    // the second each half is counting, since `start`
+    seconds: [AtomicU64; 2],
+    halves: [AtomicHistogram; 2],
+}
+
+impl Default for RollingHistogram {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            seconds: Default::default(),
+            halves: Default::default(),
+        }
+    }
+}
+
+impl RollingHistogram {
+    fn second(&self) -> u64 {
+        self.start.elapsed().as_secs()
+    }
+
+    // a count may be lost to a half being cleared at the same time, which is fine for an
+    // estimate
+    pub(super) fn record(&self, duration: Duration) {
+        let second = self.second();
+        let half = (second % 2) as usize;
+        let counting = self.seconds[half].load(Ordering::Acquire);
+
+        if counting < second {
+            if self.seconds[half]
+                .compare_exchange(counting, second, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                self.halves[half].clear();
+            }
+        } else if counting > second {
+            // another task moved on to the next turn of this half while we read the clock
+            return;
+        }
+
+        self.halves[half].record(duration);
+    }
+
+    pub(super) fn load(&self) -> DurationHistogram {
+        let second = self.second();
+        let mut window = DurationHistogram::default();
+
+        for (counting, half) in self.seconds.iter().zip(self.halves.iter()) {
+            if counting.load(Ordering::Acquire) + 1 >= second {
+                window.add(&half.load());
+            }
+        }
+
+        window
+    }
+
+    /// The median of the durations of the window, taking those over ten seconds to be ten
+    /// seconds; `None` if there were none.
+    pub(super) fn median(&self) -> Option<Duration> {
+        let window = self.load();
+
+        if window.count() == 0 {
+            return None;
+        }
+
+        let longest = BOUNDS_MILLIS[BOUNDS_MILLIS.len() - 1];
+
+        Some(
+            window
+                .quantile(0.5)
+                .unwrap_or_else(|| Duration::from_millis(longest)),
+        )
+    }
+}
+
+#[test]
+fn it_counts_durations_in_buckets() {
+    let histogram = AtomicHistogram::default();
+
+    for millis in &[0, 1, 3, 3, 40, 700, 60_000] {
+        histogram.record(Duration::from_millis(*millis));
+    }
+
+    let before = histogram.load();
+    assert_eq!(before.count(), 7);
+
+    let buckets: Vec<_> = before.buckets().filter(|(_, n)| *n > 0).collect();
+    assert_eq!(
+        buckets,
+        [
+            (Some(Duration::from_millis(1)), 2),
+            (Some(Duration::from_millis(5)), 2),
+            (Some(Duration::from_millis(50)), 1),
+            (Some(Duration::from_millis(1_000)), 1),
+            (None, 1),
+        ]
+    );
+
+    assert_eq!(before.quantile(0.5), Some(Duration::from_millis(5)));
+    assert_eq!(before.quantile(0.85), Some(Duration::from_millis(1_000)));
+    assert_eq!(before.quantile(1.0), None);
+    assert_eq!(DurationHistogram::default().quantile(0.5), None);
+
+    histogram.record(Duration::from_millis(15));
+
+    let window = histogram.load().since(&before);
+    assert_eq!(window.count(), 1);
+    assert_eq!(window.quantile(0.99), Some(Duration::from_millis(20)));
+}
@@ -1,30 +1,249 @@
+use super::admission::{PoolLoad, Priority};
+use super::close_event::{CloseEvent, CloseSignal};
 use super::connection::{Floating, Idle, Live};
+use super::credentials::{CredentialExpiries, CredentialLease};
+use super::histogram::{AtomicHistogram, RollingHistogram};
+use super::jitter;
+use super::query_cache::QueryCache;
 use crate::connection::ConnectOptions;
 use crate::connection::Connection;
 use crate::database::Database;
-use crate::error::Error;
-use crate::pool::{deadline_as_timeout, PoolOptions};
-use crossbeam_queue::{ArrayQueue, SegQueue};
-use futures_core::task::{Poll, Waker};
-use futures_util::future;
-use sqlx_rt::{sleep, spawn, timeout};
+use crate::error::{catch_panic, panic_message, ConfigError, Error};
+use crate::events::CloseReason;
+use crate::pool::{
+    deadline_as_timeout, AcquireStats, PoolOptions, PoolResize, PoolStats, PoolWarmUp,
+};
+use crate::HashMap;
+use crossbeam_queue::SegQueue;
+use futures_core::task::Poll;
+use futures_util::future::{self, FutureExt};
+use futures_util::task::AtomicWaker;
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
+use sqlx_rt::{sleep, spawn, timeout, Instant};
+use std::any::Any;
 use std::cmp;
+use std::future::Future;
+use std::iter;
 use std::mem;
+use std::panic::{AssertUnwindSafe, Location};
 use std::ptr;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{self, AtomicBool, AtomicU32, AtomicU64, AtomicU8, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
 use std::task::Context;
-use std::time::Instant;
+use std::time::Duration;
 
 pub(crate) struct SharedPool<DB: Database> {
     pub(super) connect_options: <DB::Connection as Connection>::Options,
-    pub(super) idle_conns: ArrayQueue<Idle<DB>>,
-    waiters: SegQueue<Weak<Waiter>>,
+    // unbounded, as `max_connections` may grow after the pool was created
+    pub(super) idle_conns: SegQueue<Idle<DB>>,
+    waiters: Waiters,
     pub(super) size: AtomicU32,
     is_closed: AtomicBool,
+    // wakes the tasks waiting in `Pool::close_event`
+    close_signal: Arc<CloseSignal>,
+    // the number of connections opened so far; passed to `connection_name_suffix`
+    connections_opened: AtomicU64,
+    stats: StatsCounters,
+    // where and when each checked-out connection was acquired, if `checkout_warning_after` is set
+    checkouts: Mutex<HashMap<u64, Checkout>>,
+    next_checkout_id: AtomicU64,
+    // whether the idle connections are being tested after a connection saw the server shut down
+    testing_idle: AtomicBool,
+    // the expiry of the credential of each open connection, if `password_provider` is set
+    credentials: Arc<CredentialExpiries>,
+    // the limit shared with the other partitions of a `PartitionedPool`
+    limit: Option<Limit>,
+    // the results of the queries marked with `cached`
+    pub(super) query_cache: QueryCache<DB>,
+    // the limits `Pool::resize` changes, which are read from here and not from `options`
+    max_connections: AtomicU32,
+    min_connections: AtomicU32,
+    timeouts: Mutex<Timeouts>,
+    // whether the reaper task is running; it stops when no timeout is left for it to watch
+    reaper_running: AtomicBool,
+    // see `Pool::driver_state`
+    pub(super) driver_state: OnceCell<Box<dyn Any + Send + Sync>>,
     pub(super) options: PoolOptions<DB>,
 }
 
+#[derive(Clone, Copy)]
+pub(super) struct Timeouts {
+    pub(super) connect_timeout: Duration,
+    pub(super) max_lifetime: Option<Duration>,
+    pub(super) idle_timeout: Option<Duration>,
+}
+
+/// The limit on the total size of the partitions of a `PartitionedPool`, which each hold one
+/// of its slots for every connection they have open.
+pub(super) struct SharedLimit {
+    max: u32,
+    size: AtomicU32,
+    // tasks waiting for a slot, which are woken as well as those of their partition
+    waiters: Waiters,
+    // the number of tasks waiting for a slot, and of idle connections being closed for them
+    waiting: AtomicU32,
+    evicting: AtomicU32,
+    // the number of partitions, which split the limit evenly between them while it is contended
+    partitions: AtomicU32,
+    // starts closing an idle connection of a partition other than the one given, to make room
+    evict_idle: Mutex<Option<EvictIdle>>,
+}
+
+pub(super) type EvictIdle = Box<dyn Fn(u64) -> bool + Send + Sync>;
+
+/// The share of a [`SharedLimit`] held by one partition.
+pub(super) struct Limit {
+    pub(super) shared: Arc<SharedLimit>,
+    pub(super) partition: u64,
+    // the number of tasks of this partition waiting for a slot
+    waiting: AtomicU32,
+}
+
+impl SharedLimit {
+    pub(super) fn new(max: u32) -> Self {
+        Self {
+            max,
+            size: AtomicU32::new(0),
+            waiters: Waiters::new(),
+            waiting: AtomicU32::new(0),
+            evicting: AtomicU32::new(0),
+            partitions: AtomicU32::new(0),
+            evict_idle: Mutex::new(None),
+        }
+    }
+
+    pub(super) fn size(&self) -> u32 {
+        self.size.load(Ordering::Acquire)
+    }
+
+    pub(super) fn set_evict_idle(&self, evict_idle: EvictIdle) {
+        *self.evict_idle.lock() = Some(evict_idle);
+    }
+
+    pub(super) fn add_partition(self: &Arc<Self>, partition: u64) -> Limit {
+        self.partitions.fetch_add(1, Ordering::AcqRel);
+
+        Limit {
+            shared: Arc::clone(self),
+            partition,
+            waiting: AtomicU32::new(0),
+        }
+    }
+
+    pub(super) fn remove_partition(&self) {
+        self.partitions.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    /// Counts an idle connection as being closed to make room for a waiting task, returning
+    /// `false` if there are already as many as there are tasks waiting.
+    pub(super) fn begin_eviction(&self) -> bool {
+        let mut evicting = self.evicting.load(Ordering::Acquire);
+
+        while evicting < self.waiting.load(Ordering::Acquire) {
+            match self.evicting.compare_exchange_weak(
+                evicting,
+                evicting + 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(current) => evicting = current,
+            }
+        }
+
+        false
+    }
+
+    pub(super) fn end_eviction(&self) {
+        self.evicting.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    fn try_increment(&self) -> bool {
+        let mut size = self.size();
+
+        while size < self.max {
+            match self.size.compare_exchange_weak(
+                size,
+                size + 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(current) => size = current,
+            }
+        }
+
+        false
+    }
+
+    fn decrement(&self) {
+        self.size.fetch_sub(1, Ordering::AcqRel);
+        self.waiters.wake_one();
+    }
+
+    // the number of connections a partition may keep while tasks of others wait for a slot
+    fn fair_share(&self) -> u32 {
+        let partitions = cmp::max(self.partitions.load(Ordering::Acquire), 1);
+
+        cmp::max(self.max / partitions, 1)
+    }
+}
+
+impl Limit {
+    /// Counts a task of this partition as waiting for a slot until the guard is dropped, and
+    /// makes room for it by closing an idle connection of another partition if there is one.
+    fn wait(&self) -> WaitingForLimit<'_> {
+        self.waiting.fetch_add(1, Ordering::AcqRel);
+        self.shared.waiting.fetch_add(1, Ordering::AcqRel);
+
+        if let Some(evict_idle) = &*self.shared.evict_idle.lock() {
+            evict_idle(self.partition);
+        }
+
+        WaitingForLimit(self)
+    }
+
+    // whether tasks of other partitions are waiting for a slot
+    fn others_waiting(&self) -> bool {
+        self.shared.waiting.load(Ordering::Acquire) > self.waiting.load(Ordering::Acquire)
+    }
+}
+
+struct WaitingForLimit<'a>(&'a Limit);
+
+impl Drop for WaitingForLimit<'_> {
+    fn drop(&mut self) {
+        self.0.waiting.fetch_sub(1, Ordering::AcqRel);
+        self.0.shared.waiting.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+struct Checkout {
+    location: &'static Location<'static>,
+    since: Instant,
+    warned: bool,
+}
+
+// the running totals behind `Pool::stats`
+#[derive(Default)]
+struct StatsCounters {
+    connections_opened: AtomicU64,
+    queries: AtomicU64,
+    total_latency_nanos: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    checked_out: AtomicU64,
+    longest_checkout_nanos: AtomicU64,
+    session_state_leaks: AtomicU64,
+    server_shutdowns: AtomicU64,
+    acquire_wait: AtomicHistogram,
+    connect_time: AtomicHistogram,
+    // the waits of the recent acquires that had to wait, for the `admission_policy`
+    recent_waits: RollingHistogram,
+    acquires_rejected: AtomicU64,
+}
+
 impl<DB: Database> SharedPool<DB> {
     pub(super) fn size(&self) -> u32 {
         self.size.load(Ordering::Acquire)
@@ -35,22 +254,286 @@ impl<DB: Database> SharedPool<DB> {
         self.idle_conns.len()
     }
 
+    pub(super) fn stats(&self) -> PoolStats {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+
+        let (query_cache_hits, query_cache_misses) = self.query_cache.hits_and_misses();
+
+        PoolStats {
+            connections_opened: load(&self.stats.connections_opened),
+            queries: load(&self.stats.queries),
+            total_latency: Duration::from_nanos(load(&self.stats.total_latency_nanos)),
+            bytes_sent: load(&self.stats.bytes_sent),
+            bytes_received: load(&self.stats.bytes_received),
+            checked_out: load(&self.stats.checked_out),
+            longest_checkout: Duration::from_nanos(load(&self.stats.longest_checkout_nanos)),
+            session_state_leaks: load(&self.stats.session_state_leaks),
+            query_cache_hits,
+            query_cache_misses,
+            server_shutdowns: load(&self.stats.server_shutdowns),
+            expired_credentials: self.credentials.expired(),
+            max_connections: self.max_connections(),
+            min_connections: self.min_connections(),
+            pending_shrink: self.size().saturating_sub(self.max_connections()),
+            acquires_rejected: load(&self.stats.acquires_rejected),
+            acquire_wait: self.stats.acquire_wait.load(),
+            connect_time: self.stats.connect_time.load(),
+        }
+    }
+
+    pub(super) fn max_connections(&self) -> u32 {
+        self.max_connections.load(Ordering::Acquire)
+    }
+
+    pub(super) fn min_connections(&self) -> u32 {
+        self.min_connections.load(Ordering::Acquire)
+    }
+
+    pub(super) fn timeouts(&self) -> Timeouts {
+        *self.timeouts.lock()
+    }
+
+    /// Applies the limits of `resize` that are set, if the configuration they make is valid.
+    pub(super) fn resize(self: &Arc<Self>, resize: PoolResize) -> Result<(), Vec<ConfigError>> {
+        let mut timeouts = self.timeouts.lock();
+
+        let mut options = self.options.clone();
+        options.max_connections = resize.max_connections.unwrap_or(self.max_connections());
+        options.min_connections = resize.min_connections.unwrap_or(self.min_connections());
+        options.connect_timeout = resize.connect_timeout.unwrap_or(timeouts.connect_timeout);
+        options.max_lifetime = resize.max_lifetime.unwrap_or(timeouts.max_lifetime);
+        options.idle_timeout = resize.idle_timeout.unwrap_or(timeouts.idle_timeout);
+
+        options.validate()?;
+
+        *timeouts = Timeouts {
+            connect_timeout: options.connect_timeout,
+            max_lifetime: options.max_lifetime,
+            idle_timeout: options.idle_timeout,
+        };
+
+        // under the lock, so that resizes made at once are applied in the same order to all
+        // of the limits
+        let old_max = self
+            .max_connections
+            .swap(options.max_connections, Ordering::AcqRel);
+        self.min_connections
+            .store(options.min_connections, Ordering::Release);
+
+        drop(timeouts);
+
+        // the tasks that were waiting for the slots the pool now has; each opens a connection,
+        // or waits again if another task took the slot first
+        for _ in old_max..options.max_connections {
+            self.waiters.wake_one();
+        }
+
+        if options.max_connections < old_max {
+            self.shrink_idle();
+        }
+
+        if self.size() < options.min_connections {
+            let pool = Arc::clone(self);
+
+            spawn(async move {
+                for error in pool.warm_up().await.errors {
+                    log::debug!("failed to open a connection of the resized pool: {}", error);
+                }
+            });
+        }
+
+        spawn_reaper(self);
+
+        Ok(())
+    }
+
+    /// Closes idle connections while the pool is above `max_connections`, after it shrank;
+    /// checked-out connections are closed as they are returned instead, by `should_yield`.
+    fn shrink_idle(self: &Arc<Self>) {
+        let mut excess = Vec::new();
+
+        while self.size().saturating_sub(excess.len() as u32) > self.max_connections() {
+            match self.idle_conns.pop() {
+                Some(idle) => excess.push(idle),
+                None => break,
+            }
+        }
+
+        if excess.is_empty() {
+            return;
+        }
+
+        let pool = Arc::clone(self);
+
+        spawn(async move {
+            for idle in excess {
+                let _ = Floating::from_idle(idle, &pool)
+                    .close(CloseReason::Explicit)
+                    .await;
+            }
+        });
+    }
+
+    /// Counts a connection as checked out, returning its id in the checkout registry if it is
+    /// to be watched by the reaper.
+    pub(super) fn begin_checkout(&self, location: &'static Location<'static>) -> Option<u64> {
+        self.stats.checked_out.fetch_add(1, Ordering::Relaxed);
+
+        if self.options.checkout_warning_after.is_none() {
+            return None;
+        }
+
+        let id = self.next_checkout_id.fetch_add(1, Ordering::Relaxed);
+
+        self.checkouts.lock().insert(
+            id,
+            Checkout {
+                location,
+                since: Instant::now(),
+                warned: false,
+            },
+        );
+
+        Some(id)
+    }
+
+    pub(super) fn end_checkout(&self, since: Instant, id: Option<u64>) {
+        self.stats.checked_out.fetch_sub(1, Ordering::Relaxed);
+        self.stats
+            .longest_checkout_nanos
+            .fetch_max(since.elapsed().as_nanos() as u64, Ordering::Relaxed);
+
+        if let Some(id) = id {
+            self.forget_checkout(id);
+        }
+    }
+
+    pub(super) fn forget_checkout(&self, id: u64) {
+        self.checkouts.lock().remove(&id);
+    }
+
+    /// Warns once about every connection checked out for longer than `checkout_warning_after`.
+    fn warn_long_checkouts(&self) {
+        let threshold = match self.options.checkout_warning_after {
+            Some(threshold) => threshold,
+            None => return,
+        };
+
+        for checkout in self.checkouts.lock().values_mut() {
+            let elapsed = checkout.since.elapsed();
+
+            if !checkout.warned && elapsed > threshold {
+                checkout.warned = true;
+
+                log::warn!(
+                    "connection acquired at {} has been checked out for {:?}, longer than \
+                     `checkout_warning_after` ({:?}); it may have been leaked",
+                    checkout.location,
+                    elapsed,
+                    threshold
+                );
+            }
+        }
+    }
+
+    /// Reads the session state of a connection being returned to the pool, warning about what
+    /// changed since it was checked out; the state it was returned with is the state it is
+    /// next checked out with.
+    pub(super) async fn check_session_state(&self, live: &mut Live<DB>) {
+        let state = match live.raw.session_state().await {
+            Ok(state) => state,
+
+            Err(error) => {
+                log::warn!(
+                    "failed to read the session state of a connection: {}",
+                    error
+                );
+                return;
+            }
+        };
+
+        if let Some(before) = &live.session_state {
+            let changes = state.changes_since(before);
+
+            if !changes.is_empty() {
+                self.stats
+                    .session_state_leaks
+                    .fetch_add(1, Ordering::Relaxed);
+
+                log::warn!(
+                    "connection was returned to the pool with session state left over: {}",
+                    changes.join(", ")
+                );
+            }
+        }
+
+        live.session_state = Some(state);
+    }
+
+    /// Adds what the connection did since it was last recorded to the pool's totals.
+    pub(super) fn record_stats(&self, live: &mut Live<DB>) {
+        let stats = live.raw.stats();
+        let reported = &mut live.reported;
+
+        let add = |counter: &AtomicU64, new: u64, old: &mut u64| {
+            counter.fetch_add(new.saturating_sub(*old), Ordering::Relaxed);
+            *old = new;
+        };
+
+        add(&self.stats.queries, stats.queries, &mut reported.queries);
+        add(
+            &self.stats.bytes_sent,
+            stats.bytes_sent,
+            &mut reported.bytes_sent,
+        );
+        add(
+            &self.stats.bytes_received,
+            stats.bytes_received,
+            &mut reported.bytes_received,
+        );
+
+        // the totals only ever grow
+        let latency = stats.total_latency - reported.total_latency;
+        self.stats
+            .total_latency_nanos
+            .fetch_add(latency.as_nanos() as u64, Ordering::Relaxed);
+        reported.total_latency = stats.total_latency;
+    }
+
     pub(super) fn is_closed(&self) -> bool {
         self.is_closed.load(Ordering::Acquire)
     }
 
+    /// Whether a connection being returned should be closed instead, as the pool is above a
+    /// `max_connections` lowered by `resize`, or this partition of a `PartitionedPool` holds
+    /// more than its share of the limit while others wait for it.
+    pub(super) fn should_yield(&self) -> bool {
+        if self.size() > self.max_connections() {
+            return true;
+        }
+
+        match &self.limit {
+            Some(limit) => limit.others_waiting() && self.size() > limit.shared.fair_share(),
+            None => false,
+        }
+    }
+
+    pub(super) fn close_event(&self) -> CloseEvent {
+        CloseEvent::new(Arc::clone(&self.close_signal))
+    }
+
     pub(super) async fn close(&self) {
         self.is_closed.store(true, Ordering::Release);
-        while let Some(waker) = self.waiters.pop() {
-            if let Some(waker) = waker.upgrade() {
-                waker.wake();
-            }
-        }
+        self.waiters.wake_all();
+        self.close_signal.notify();
 
         // ensure we wait until the pool is actually closed
         while self.size() > 0 {
             if let Some(idle) = self.idle_conns.pop() {
-                if let Err(e) = Floating::from_idle(idle, self).close().await {
+                if let Err(e) = Floating::from_idle(idle, self)
+                    .close(CloseReason::Explicit)
+                    .await
+                {
                     log::warn!("error occurred while closing the pool connection: {}", e);
                 }
             }
@@ -69,7 +552,7 @@ impl<DB: Database> SharedPool<DB> {
         Some(self.pop_idle()?.into_live())
     }
 
-    fn pop_idle(&self) -> Option<Floating<'_, Idle<DB>>> {
+    pub(super) fn pop_idle(&self) -> Option<Floating<'_, Idle<DB>>> {
         if self.is_closed.load(Ordering::Acquire) {
             return None;
         }
@@ -78,48 +561,167 @@ impl<DB: Database> SharedPool<DB> {
     }
 
     pub(super) fn release(&self, mut floating: Floating<'_, Live<DB>>) {
+        self.record_stats(&mut floating);
+
+        if floating
+            .credential
+            .as_ref()
+            .map_or(false, CredentialLease::is_due)
+        {
+            // the next user of the slot opens a connection with a fresh credential
+            return floating.discard(CloseReason::CredentialExpired);
+        }
+
         if let Some(test) = &self.options.after_release {
-            if !test(&mut floating.raw) {
+            match catch_panic(|| test(&mut floating.raw)) {
+                Ok(true) => {}
+
                 // drop the connection and do not return to the pool
-                return;
+                Ok(false) => return floating.discard(CloseReason::ResetFailed),
+
+                Err(error) => {
+                    log::warn!("in `after_release`: {}", error);
+                    return floating.discard(CloseReason::ResetFailed);
+                }
             }
         }
 
-        let is_ok = self
-            .idle_conns
-            .push(floating.into_idle().into_leakable())
-            .is_ok();
+        if let Some(threshold) = self.options.shrink_buffers_above {
+            if floating.raw.buffer_sizes().total() > threshold {
+                floating.raw.shrink_buffers();
+            }
+        }
+
+        self.idle_conns.push(floating.into_idle().into_leakable());
+        self.waiters.wake_one();
+    }
+
+    /// Pings every idle connection at once and closes those that fail, after a connection saw
+    /// the server shut down; the others were most likely ended with it, and would otherwise
+    /// fail one after the other as they are used.
+    pub(super) fn server_shut_down(self: &Arc<Self>) {
+        self.stats.server_shutdowns.fetch_add(1, Ordering::Relaxed);
 
-        if !is_ok {
-            panic!("BUG: connection queue overflow in release()");
+        if !self.options.test_idle_on_shutdown || self.testing_idle.swap(true, Ordering::AcqRel) {
+            return;
         }
 
-        while let Some(waker) = self.waiters.pop() {
-            if let Some(waker) = waker.upgrade() {
-                waker.wake();
-                break;
+        let pool = Arc::clone(self);
+
+        spawn(async move {
+            let idle: Vec<_> = iter::from_fn(|| pool.pop_idle()).collect();
+
+            future::join_all(idle.into_iter().map(|mut conn| {
+                let pool = &pool;
+
+                async move {
+                    match conn.ping().await {
+                        Ok(()) => {
+                            pool.idle_conns.push(conn.into_leakable());
+                            pool.waiters.wake_one();
+                        }
+
+                        Err(error) => {
+                            log::debug!("ping on idle connection returned error: {}", error);
+                            let _ = conn.close_broken(&error).await;
+                        }
+                    }
+                }
+            }))
+            .await;
+
+            pool.testing_idle.store(false, Ordering::Release);
+
+            // replace what was closed below `min_connections`
+            if pool.size() < pool.min_connections() {
+                for error in pool.warm_up().await.errors {
+                    log::debug!("failed to replace a connection of the pool: {}", error);
+                }
+            }
+        });
+    }
+
+    /// Adds a connection that was just opened to the idle queue.
+    pub(super) fn push_new(&self, conn: Floating<'_, Live<DB>>) {
+        self.idle_conns.push(conn.into_idle().into_leakable());
+        self.waiters.wake_one();
+    }
+
+    /// Opens connections until the pool has `min_connections`, trying once for each that is
+    /// missing.
+    pub(super) async fn warm_up(&self) -> PoolWarmUp {
+        let min_connections = self.min_connections();
+        let missing = min_connections.saturating_sub(self.size());
+        let mut warm_up = PoolWarmUp::default();
+
+        for _ in 0..missing {
+            // connections may have been opened by `acquire` or the reaper in the meantime, so the
+            // size is only incremented while it is below `min_connections`
+            let guard = match self.try_increment_size_below(min_connections) {
+                Some(guard) => guard,
+
+                None => {
+                    if self.is_closed() {
+                        warm_up.errors.push(Error::PoolClosed);
+                    }
+
+                    break;
+                }
+            };
+
+            let deadline = Instant::now() + self.timeouts().connect_timeout;
+
+            match self.open(deadline, guard).await {
+                Ok(conn) => {
+                    self.push_new(conn);
+                    warm_up.created += 1;
+                }
+
+                Err(error) => warm_up.errors.push(error),
             }
         }
+
+        warm_up
     }
 
     /// Try to atomically increment the pool size for a new connection.
     ///
     /// Returns `None` if we are at max_connections or if the pool is closed.
     pub(super) fn try_increment_size(&self) -> Option<DecrementSizeGuard<'_>> {
+        self.try_increment_size_below(self.max_connections())
+    }
+
+    /// Like `try_increment_size()`, but also returns `None` if the size is at `bound`.
+    pub(super) fn try_increment_size_below(&self, bound: u32) -> Option<DecrementSizeGuard<'_>> {
         if self.is_closed() {
             return None;
         }
 
+        let bound = cmp::min(bound, self.max_connections());
         let mut size = self.size();
 
-        while size < self.options.max_connections {
-            let new_size = self.size.compare_and_swap(size, size + 1, Ordering::AcqRel);
+        while size < bound {
+            match self
+                .size
+                .compare_exchange(size, size + 1, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    let mut guard = DecrementSizeGuard::new(self);
+
+                    if let Some(limit) = &self.limit {
+                        if !limit.shared.try_increment() {
+                            // only gives back the slot of this pool
+                            guard.limit = None;
+
+                            return None;
+                        }
+                    }
+
+                    return Some(guard);
+                }
 
-            if new_size == size {
-                return Some(DecrementSizeGuard::new(self));
+                Err(actual) => size = actual,
             }
-
-            size = new_size;
         }
 
         None
@@ -129,29 +731,42 @@ impl<DB: Database> SharedPool<DB> {
     /// open a new connection, or if an idle connection is returned to the pool.
     ///
     /// Returns an error if `deadline` elapses before we are woken.
+    ///
+    /// A partition of a `PartitionedPool` that is below `max_connections` is waiting for a slot
+    /// of the limit shared with the other partitions, so it is also woken when one is freed.
+    ///
+    /// If the future is dropped after the task was woken, but before it was polled again, the
+    /// wakeup is passed on to the next task waiting, so no connection is left idle while tasks
+    /// wait for one.
     async fn wait_for_conn(&self, deadline: Instant) -> Result<(), Error> {
         if self.is_closed() {
             return Err(Error::PoolClosed);
         }
 
-        let mut waiter = None;
+        let limit = self
+            .limit
+            .as_ref()
+            .filter(|_| self.size() < self.max_connections());
+
+        let _waiting = limit.map(Limit::wait);
+
+        let waiter = Waiting::new(&self.waiters, limit.map(|limit| &limit.shared.waiters));
+
+        // a connection may have been returned, or a slot freed, since `acquire` last checked
+        // and before we were in the queue to be woken for it; the task that has waited the
+        // longest is woken for it, which may be this one
+        if !self.idle_conns.is_empty()
+            || (self.size() < self.max_connections()
+                && limit
+                    .iter()
+                    .all(|limit| limit.shared.size() < limit.shared.max))
+        {
+            self.waiters.wake_one();
+        }
 
         timeout(
             deadline_as_timeout::<DB>(deadline)?,
-            // `poll_fn` gets us easy access to a `Waker` that we can push to our queue
-            future::poll_fn(|cx| -> Poll<()> {
-                let waiter = waiter.get_or_insert_with(|| {
-                    let waiter = Waiter::new(cx);
-                    self.waiters.push(Arc::downgrade(&waiter));
-                    waiter
-                });
-
-                if waiter.is_woken() {
-                    Poll::Ready(())
-                } else {
-                    Poll::Pending
-                }
-            }),
+            future::poll_fn(|cx| waiter.poll_woken(cx)),
         )
         .await
         .map_err(|_| Error::PoolTimedOut)
@@ -161,12 +776,53 @@ impl<DB: Database> SharedPool<DB> {
         options: PoolOptions<DB>,
         connect_options: <DB::Connection as Connection>::Options,
     ) -> Arc<Self> {
+        Self::new_arc_limited(options, connect_options, None)
+    }
+
+    /// Creates a pool whose connections also count towards `limit`, as a partition of a
+    /// `PartitionedPool`.
+    pub(super) fn new_arc_limited(
+        options: PoolOptions<DB>,
+        mut connect_options: <DB::Connection as Connection>::Options,
+        limit: Option<Limit>,
+    ) -> Arc<Self> {
+        connect_options.isolate();
+
+        if let Some(middleware) = &options.query_middleware {
+            connect_options.query_middleware(Arc::clone(middleware));
+        }
+
+        if let Some(listener) = &options.connection_events {
+            connect_options.connection_events(Arc::clone(listener));
+        }
+
         let pool = Self {
             connect_options,
-            idle_conns: ArrayQueue::new(options.max_connections as usize),
-            waiters: SegQueue::new(),
+            idle_conns: SegQueue::new(),
+            waiters: Waiters::new(),
             size: AtomicU32::new(0),
             is_closed: AtomicBool::new(false),
+            close_signal: Arc::default(),
+            connections_opened: AtomicU64::new(0),
+            stats: StatsCounters::default(),
+            checkouts: Mutex::new(HashMap::new()),
+            next_checkout_id: AtomicU64::new(0),
+            testing_idle: AtomicBool::new(false),
+            credentials: Arc::default(),
+            limit,
+            query_cache: QueryCache::new(
+                options.query_cache_max_entries,
+                options.query_cache_max_size,
+            ),
+            max_connections: AtomicU32::new(options.max_connections),
+            min_connections: AtomicU32::new(options.min_connections),
+            timeouts: Mutex::new(Timeouts {
+                connect_timeout: options.connect_timeout,
+                max_lifetime: options.max_lifetime,
+                idle_timeout: options.idle_timeout,
+            }),
+            reaper_running: AtomicBool::new(false),
+            driver_state: OnceCell::new(),
             options,
         };
 
@@ -178,11 +834,16 @@ impl<DB: Database> SharedPool<DB> {
     }
 
     #[allow(clippy::needless_lifetimes)]
-    pub(super) async fn acquire<'s>(&'s self) -> Result<Floating<'s, Live<DB>>, Error> {
+    pub(super) async fn acquire<'s>(
+        &'s self,
+        priority: Priority,
+    ) -> Result<(Floating<'s, Live<DB>>, AcquireStats), Error> {
         let start = Instant::now();
-        let deadline = start + self.options.connect_timeout;
+        let deadline = start + self.timeouts().connect_timeout;
         let mut waited = !self.options.fair;
+        let mut queued = false;
         let mut backoff = 0.01;
+        let mut stats = AcquireStats::default();
 
         // Unless the pool has been closed ...
         while !self.is_closed() {
@@ -191,16 +852,24 @@ impl<DB: Database> SharedPool<DB> {
                 // Attempt to immediately acquire a connection. This will return Some
                 // if there is an idle connection in our channel.
                 if let Some(conn) = self.pop_idle() {
-                    if let Some(live) = check_conn(conn, &self.options).await {
-                        return Ok(live);
+                    let timeouts = self.timeouts();
+                    let live = check_conn(conn, &self.options, &timeouts, &mut stats).await;
+
+                    if let Some(live) = live {
+                        return Ok(self.acquired(live, start, stats, queued));
                     }
                 }
             }
 
             if let Some(guard) = self.try_increment_size() {
                 // pool has slots available; open a new connection
+                let connecting = Instant::now();
+
                 match self.connection(deadline, guard).await {
-                    Ok(Some(conn)) => return Ok(conn),
+                    Ok(Some(conn)) => {
+                        add_elapsed(&mut stats.connect, connecting);
+                        return Ok(self.acquired(conn, start, stats, queued));
+                    }
                     // [size] is internally decremented on _retry_ and _error_
                     Ok(None) => {
                         // If the connection is refused wait in exponentially
@@ -208,15 +877,29 @@ impl<DB: Database> SharedPool<DB> {
                         // two seconds.
                         sqlx_rt::sleep(std::time::Duration::from_secs_f64(backoff)).await;
                         backoff = f64::min(backoff * 2.0, 2.0);
+                        add_elapsed(&mut stats.connect, connecting);
                         continue;
                     }
                     Err(e) => return Err(e),
                 }
             }
 
+            if !queued {
+                self.admit(priority)?;
+                queued = true;
+            }
+
             // Wait for a connection to become available (or we are allowed to open a new one)
             // Returns an error if `deadline` passes
-            self.wait_for_conn(deadline).await?;
+            let waiting = Instant::now();
+            let woken = self.wait_for_conn(deadline).await;
+            stats.queue_wait += waiting.elapsed();
+
+            // the wait of an acquire that timed out tells of the load as well
+            if let Err(error) = woken {
+                self.stats.recent_waits.record(stats.queue_wait);
+                return Err(error);
+            }
 
             waited = true;
         }
@@ -224,93 +907,231 @@ impl<DB: Database> SharedPool<DB> {
         Err(Error::PoolClosed)
     }
 
+    /// Asks the `admission_policy` whether an acquire of `priority` may wait for a connection.
+    fn admit(&self, priority: Priority) -> Result<(), Error> {
+        let policy = match &self.options.admission_policy {
+            Some(policy) => policy,
+            None => return Ok(()),
+        };
+
+        let load = PoolLoad {
+            waiters: self.waiters.len(),
+            size: self.size(),
+            max_connections: self.max_connections(),
+            waits: &self.stats.recent_waits,
+        };
+
+        if catch_panic(|| policy.admit(&load, priority))? {
+            return Ok(());
+        }
+
+        self.stats.acquires_rejected.fetch_add(1, Ordering::Relaxed);
+
+        Err(Error::PoolOverloaded)
+    }
+
+    // completes the stats of an `acquire` that returned a connection, and records them
+    fn acquired<'s>(
+        &'s self,
+        conn: Floating<'s, Live<DB>>,
+        start: Instant,
+        mut stats: AcquireStats,
+        queued: bool,
+    ) -> (Floating<'s, Live<DB>>, AcquireStats) {
+        stats.total = start.elapsed();
+
+        self.stats.acquire_wait.record(stats.queue_wait);
+
+        if queued {
+            self.stats.recent_waits.record(stats.queue_wait);
+        }
+
+        if let Some(connect) = stats.connect {
+            self.stats.connect_time.record(connect);
+        }
+
+        log::debug!(
+            target: "sqlx::pool::acquire",
+            "acquired connection in {:?}: queue_wait={:?} connect={:?} ping={:?}",
+            stats.total,
+            stats.queue_wait,
+            stats.connect,
+            stats.ping
+        );
+
+        (conn, stats)
+    }
+
     pub(super) async fn connection<'s>(
         &'s self,
         deadline: Instant,
         guard: DecrementSizeGuard<'s>,
     ) -> Result<Option<Floating<'s, Live<DB>>>, Error> {
+        match self.open(deadline, guard).await {
+            Ok(conn) => Ok(Some(conn)),
+
+            // an IO error while connecting is assumed to be the system starting up
+            Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => Ok(None),
+
+            // TODO: Handle other database "boot period"s
+
+            // [postgres] the database system is starting up
+            // TODO: Make this check actually check if this is postgres
+            Err(Error::Database(error)) if error.code().as_deref() == Some("57P03") => Ok(None),
+
+            // Any other error while connection should immediately
+            // terminate and bubble the error up
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Opens a connection, returning the error as it is if that fails, where [`connection`]
+    /// returns `None` for errors worth retrying.
+    ///
+    /// [`connection`]: Self::connection
+    pub(super) async fn open<'s>(
+        &'s self,
+        deadline: Instant,
+        guard: DecrementSizeGuard<'s>,
+    ) -> Result<Floating<'s, Live<DB>>, Error> {
         if self.is_closed() {
             return Err(Error::PoolClosed);
         }
 
         let timeout = super::deadline_as_timeout::<DB>(deadline)?;
 
+        let suffix = self.options.connection_name_suffix.as_ref().map(|suffix| {
+            let n = self.connections_opened.fetch_add(1, Ordering::AcqRel);
+            suffix(n)
+        });
+
+        // the credential is fetched within the connect timeout
+        let connect = self.establish(suffix);
+
         // result here is `Result<Result<C, Error>, TimeoutError>`
-        match sqlx_rt::timeout(timeout, self.connect_options.connect()).await {
+        match sqlx_rt::timeout(timeout, connect).await {
             // successfully established connection
-            Ok(Ok(mut raw)) => {
-                if let Some(callback) = &self.options.after_connect {
-                    callback(&mut raw).await?;
+            Ok(Ok((mut raw, credential))) => {
+                let callbacks = self
+                    .options
+                    .prepare_connection
+                    .iter()
+                    .chain(&self.options.after_connect);
+
+                for callback in callbacks {
+                    if let Err(error) = catch_hook_panic(async { callback(&mut raw).await }).await {
+                        raw.take_events().closed(CloseReason::ResetFailed);
+                        return Err(error);
+                    }
                 }
 
-                Ok(Some(Floating::new_live(raw, guard)))
-            }
+                self.stats
+                    .connections_opened
+                    .fetch_add(1, Ordering::Relaxed);
 
-            // an IO error while connecting is assumed to be the system starting up
-            Ok(Err(Error::Io(e))) if e.kind() == std::io::ErrorKind::ConnectionRefused => Ok(None),
+                let mut live = Floating::new_live(raw, guard);
+                live.credential = credential;
+                live.lifetime_factor = jitter::lifetime_factor(self.options.lifetime_jitter);
 
-            // TODO: Handle other database "boot period"s
+                // the state the first user of the connection is to return it with
+                if self.options.track_session_state {
+                    self.check_session_state(&mut live).await;
+                }
 
-            // [postgres] the database system is starting up
-            // TODO: Make this check actually check if this is postgres
-            Ok(Err(Error::Database(error))) if error.code().as_deref() == Some("57P03") => Ok(None),
+                Ok(live)
+            }
 
-            // Any other error while connection should immediately
-            // terminate and bubble the error up
             Ok(Err(e)) => Err(e),
 
             // timed out
             Err(_) => Err(Error::PoolTimedOut),
         }
     }
+
+    // opens a connection with a password from the `password_provider`, if there is one
+    async fn establish(
+        &self,
+        suffix: Option<String>,
+    ) -> Result<(DB::Connection, Option<CredentialLease>), Error> {
+        let provider = match &self.options.password_provider {
+            Some(provider) => provider,
+
+            None => {
+                let raw = match suffix {
+                    Some(suffix) => self.connect_options.connect_with_name_suffix(suffix),
+                    None => self.connect_options.connect(),
+                }
+                .await?;
+
+                return Ok((raw, None));
+            }
+        };
+
+        let credential = provider.password().await?;
+        let raw = self
+            .connect_options
+            .connect_with_password(credential.password().to_owned(), suffix)
+            .await?;
+
+        Ok((raw, self.credentials.lease(&credential)))
+    }
 }
 
 // NOTE: Function names here are bizzare. Helpful help would be appreciated.
 
-fn is_beyond_lifetime<DB: Database>(live: &Live<DB>, options: &PoolOptions<DB>) -> bool {
+fn is_beyond_lifetime<DB: Database>(live: &Live<DB>, timeouts: &Timeouts) -> bool {
     // check if connection was within max lifetime (or not set)
-    options
-        .max_lifetime
-        .map_or(false, |max| live.created.elapsed() > max)
+    timeouts.max_lifetime.map_or(false, |max| {
+        jitter::is_past(live.created.elapsed(), max, live.lifetime_factor)
+    })
 }
 
-fn is_beyond_idle<DB: Database>(idle: &Idle<DB>, options: &PoolOptions<DB>) -> bool {
+fn is_beyond_idle<DB: Database>(idle: &Idle<DB>, timeouts: &Timeouts) -> bool {
     // if connection wasn't idle too long (or not set)
-    options
-        .idle_timeout
-        .map_or(false, |timeout| idle.since.elapsed() > timeout)
+    timeouts.idle_timeout.map_or(false, |timeout| {
+        jitter::is_past(idle.since.elapsed(), timeout, idle.live.lifetime_factor)
+    })
 }
 
 async fn check_conn<'s: 'p, 'p, DB: Database>(
     mut conn: Floating<'s, Idle<DB>>,
     options: &'p PoolOptions<DB>,
+    timeouts: &Timeouts,
+    stats: &mut AcquireStats,
 ) -> Option<Floating<'s, Live<DB>>> {
     // If the connection we pulled has expired, close the connection and
     // immediately create a new connection
-    if is_beyond_lifetime(&conn, options) {
+    if is_beyond_lifetime(&conn, timeouts) {
         // we're closing the connection either way
         // close the connection but don't really care about the result
-        let _ = conn.close().await;
+        let _ = conn.close(CloseReason::MaxLifetime).await;
         return None;
     } else if options.test_before_acquire {
         // Check that the connection is still live
-        if let Err(e) = conn.ping().await {
+        let pinging = Instant::now();
+        let ping = conn.ping().await;
+        add_elapsed(&mut stats.ping, pinging);
+
+        if let Err(e) = ping {
             // an error here means the other end has hung up or we lost connectivity
             // either way we're fine to just discard the connection
             // the error itself here isn't necessarily unexpected so WARN is too strong
             log::info!("ping on idle connection returned error: {}", e);
             // connection is broken so don't try to close nicely
+            conn.into_live().discard_broken(&e);
             return None;
         }
     } else if let Some(test) = &options.before_acquire {
-        match test(&mut conn.live.raw).await {
+        match catch_hook_panic(async { test(&mut conn.live.raw).await }).await {
             Ok(false) => {
                 // connection was rejected by user-defined hook
+                conn.into_live().discard(CloseReason::ResetFailed);
                 return None;
             }
 
             Err(error) => {
                 log::info!("in `before_acquire`: {}", error);
+                conn.into_live().discard(CloseReason::ResetFailed);
                 return None;
             }
 
@@ -322,56 +1143,129 @@ async fn check_conn<'s: 'p, 'p, DB: Database>(
     Some(conn.into_live())
 }
 
-/// if `max_lifetime` or `idle_timeout` is set, spawn a task that reaps senescent connections
-fn spawn_reaper<DB: Database>(pool: &Arc<SharedPool<DB>>) {
-    let period = match (pool.options.max_lifetime, pool.options.idle_timeout) {
-        (Some(it), None) | (None, Some(it)) => it,
+// adds the time since `since` to one of the parts of `AcquireStats`
+fn add_elapsed(time: &mut Option<Duration>, since: Instant) {
+    *time = Some(time.unwrap_or_default() + since.elapsed());
+}
 
-        (Some(a), Some(b)) => cmp::min(a, b),
+// runs the future of a pool hook, turning a panic in it into `Error::Panic`; the connection the
+// hook was given may be in the middle of an operation, so it is not used again
+async fn catch_hook_panic<T>(hook: impl Future<Output = Result<T, Error>>) -> Result<T, Error> {
+    AssertUnwindSafe(hook)
+        .catch_unwind()
+        .await
+        .unwrap_or_else(|payload| Err(Error::Panic(panic_message(payload))))
+}
 
-        (None, None) => return,
-    };
+/// if `max_lifetime`, `idle_timeout` or `checkout_warning_after` is set, spawn a task that reaps
+/// senescent connections and warns about connections that have been checked out for too long,
+/// unless one is running already
+fn spawn_reaper<DB: Database>(pool: &Arc<SharedPool<DB>>) {
+    if reaper_period(pool).is_none() || pool.reaper_running.swap(true, Ordering::AcqRel) {
+        return;
+    }
 
     let pool = Arc::clone(&pool);
 
     spawn(async move {
-        while !pool.is_closed.load(Ordering::Acquire) {
+        loop {
+            // read again each time, as `resize` may have changed the timeouts
+            let period = match reaper_period(&pool) {
+                Some(period) => period,
+
+                None => {
+                    pool.reaper_running.store(false, Ordering::Release);
+
+                    // a timeout set by `resize` after it was read finds the reaper still
+                    // running, so it is this task that watches it
+                    if reaper_period(&pool).is_none()
+                        || pool.reaper_running.swap(true, Ordering::AcqRel)
+                    {
+                        break;
+                    }
+
+                    continue;
+                }
+            };
+
+            // nothing is due before the first period, and a lazy pool is not opened right away
+            sleep(period).await;
+
+            if pool.is_closed.load(Ordering::Acquire) {
+                break;
+            }
+
+            let timeouts = pool.timeouts();
+
             // reap at most the current size minus the minimum idle
-            let max_reaped = pool.size().saturating_sub(pool.options.min_connections);
+            let max_reaped = pool.size().saturating_sub(pool.min_connections());
 
             // collect connections to reap
             let (reap, keep) = (0..max_reaped)
                 // only connections waiting in the queue
                 .filter_map(|_| pool.pop_idle())
                 .partition::<Vec<_>, _>(|conn| {
-                    is_beyond_idle(conn, &pool.options) || is_beyond_lifetime(conn, &pool.options)
+                    is_beyond_idle(conn, &timeouts) || is_beyond_lifetime(conn, &timeouts)
                 });
 
             for conn in keep {
                 // return these connections to the pool first
-                let is_ok = pool.idle_conns.push(conn.into_leakable()).is_ok();
-
-                if !is_ok {
-                    panic!("BUG: connection queue overflow in spawn_reaper");
-                }
+                pool.idle_conns.push(conn.into_leakable());
             }
 
             for conn in reap {
-                let _ = conn.close().await;
+                let reason = if is_beyond_lifetime(&conn, &timeouts) {
+                    CloseReason::MaxLifetime
+                } else {
+                    CloseReason::IdleReaped
+                };
+
+                let _ = conn.close(reason).await;
             }
 
-            sleep(period).await;
+            // close what is still above a `max_connections` lowered by `resize`
+            pool.shrink_idle();
+
+            // replace what was reaped, detached or lost below `min_connections`
+            if pool.size() < pool.min_connections() {
+                for error in pool.warm_up().await.errors {
+                    log::debug!("failed to replace a connection of the pool: {}", error);
+                }
+            }
+
+            pool.warn_long_checkouts();
         }
     });
 }
 
+// the shortest of `max_lifetime`, `idle_timeout` and `checkout_warning_after`, the first two
+// shortened to a fraction of the band `lifetime_jitter` spreads them over
+fn reaper_period<DB: Database>(pool: &SharedPool<DB>) -> Option<Duration> {
+    let timeouts = pool.timeouts();
+    let jitter = |timeout: Option<Duration>| {
+        timeout.map(|timeout| jitter::reaper_period(timeout, pool.options.lifetime_jitter))
+    };
+
+    [
+        jitter(timeouts.max_lifetime),
+        jitter(timeouts.idle_timeout),
+        pool.options.checkout_warning_after,
+    ]
+    .iter()
+    .flatten()
+    .min()
+    .copied()
+}
+
 /// RAII guard returned by `Pool::try_increment_size()` and others.
 ///
 /// Will decrement the pool size if dropped, to avoid semantically "leaking" connections
 /// (where the pool thinks it has more connections than it does).
 pub(in crate::pool) struct DecrementSizeGuard<'a> {
     size: &'a AtomicU32,
-    waiters: &'a SegQueue<Weak<Waiter>>,
+    waiters: &'a Waiters,
+    // the limit the connection also holds a slot of, if the pool is a partition
+    limit: Option<&'a SharedLimit>,
     dropped: bool,
 }
 
@@ -380,6 +1274,7 @@ impl<'a> DecrementSizeGuard<'a> {
         Self {
             size: &pool.size,
             waiters: &pool.waiters,
+            limit: pool.limit.as_ref().map(|limit| &*limit.shared),
             dropped: false,
         }
     }
@@ -399,33 +1294,162 @@ impl Drop for DecrementSizeGuard<'_> {
         assert!(!self.dropped, "double-dropped!");
         self.dropped = true;
         self.size.fetch_sub(1, Ordering::SeqCst);
-        if let Some(waker) = self.waiters.pop() {
-            if let Some(waker) = waker.upgrade() {
-                waker.wake();
+        self.waiters.wake_one();
+
+        if let Some(limit) = self.limit {
+            limit.decrement();
+        }
+    }
+}
+
+/// The tasks waiting for a connection of a pool, or for a slot of a [`SharedLimit`].
+struct Waiters {
+    // also holds the tasks that stopped waiting, until they are popped
+    queue: SegQueue<Weak<Waiter>>,
+    // the number of tasks still waiting
+    len: AtomicUsize,
+}
+
+impl Waiters {
+    fn new() -> Self {
+        Self {
+            queue: SegQueue::new(),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    /// Wakes the task that has waited the longest, skipping those that stopped waiting.
+    fn wake_one(&self) {
+        // pairs with the fence in `Waiting::new`, so either the task sees what was freed before
+        // it waits, or it is in the queue by now
+        atomic::fence(Ordering::SeqCst);
+
+        while let Some(waiter) = self.queue.pop() {
+            if let Some(waiter) = waiter.upgrade() {
+                if waiter.claim() {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn wake_all(&self) {
+        while let Some(waiter) = self.queue.pop() {
+            if let Some(waiter) = waiter.upgrade() {
+                waiter.claim();
             }
         }
     }
 }
 
+const WAITING: u8 = 0;
+// woken, but not yet polled
+const CLAIMED: u8 = 1;
+const CONSUMED: u8 = 2;
+const CANCELLED: u8 = 3;
+
 struct Waiter {
-    woken: AtomicBool,
-    waker: Waker,
+    state: AtomicU8,
+    waker: AtomicWaker,
 }
 
 impl Waiter {
-    fn new(cx: &mut Context<'_>) -> Arc<Self> {
-        Arc::new(Self {
-            woken: AtomicBool::new(false),
-            waker: cx.waker().clone(),
-        })
+    /// Wakes the task, returning `false` if it already stopped waiting or was woken.
+    fn claim(&self) -> bool {
+        let claimed = self
+            .state
+            .compare_exchange(WAITING, CLAIMED, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok();
+
+        if claimed {
+            self.waker.wake();
+        }
+
+        claimed
     }
+}
 
-    fn wake(&self) {
-        self.woken.store(true, Ordering::Release);
-        self.waker.wake_by_ref();
+/// A task in the queues of a pool, and of its [`SharedLimit`] if it is waiting for a slot of
+/// one; it is woken at most once, by whichever queue gets to it first.
+struct Waiting<'a> {
+    waiter: Arc<Waiter>,
+    pool: &'a Waiters,
+    limit: Option<&'a Waiters>,
+}
+
+impl<'a> Waiting<'a> {
+    fn new(pool: &'a Waiters, limit: Option<&'a Waiters>) -> Self {
+        let waiter = Arc::new(Waiter {
+            state: AtomicU8::new(WAITING),
+            waker: AtomicWaker::new(),
+        });
+
+        for waiters in pool_and_limit(pool, limit) {
+            waiters.len.fetch_add(1, Ordering::AcqRel);
+            waiters.queue.push(Arc::downgrade(&waiter));
+        }
+
+        atomic::fence(Ordering::SeqCst);
+
+        Self {
+            waiter,
+            pool,
+            limit,
+        }
     }
 
-    fn is_woken(&self) -> bool {
-        self.woken.load(Ordering::Acquire)
+    fn poll_woken(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.waiter.waker.register(cx.waker());
+
+        match self.waiter.state.compare_exchange(
+            CLAIMED,
+            CONSUMED,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => Poll::Ready(()),
+            Err(_) => Poll::Pending,
+        }
     }
 }
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        for waiters in pool_and_limit(self.pool, self.limit) {
+            waiters.len.fetch_sub(1, Ordering::AcqRel);
+        }
+
+        let state = match self.waiter.state.compare_exchange(
+            WAITING,
+            CANCELLED,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => return,
+            Err(state) => state,
+        };
+
+        // woken for a connection or a slot that we will not take, which are given to the next
+        // task instead; as we don't know which queue woke us, both are told
+        if state == CLAIMED {
+            for waiters in pool_and_limit(self.pool, self.limit) {
+                waiters.wake_one();
+            }
+        }
+    }
+}
+
+fn pool_and_limit<'a>(
+    pool: &'a Waiters,
+    limit: Option<&'a Waiters>,
+) -> impl Iterator<Item = &'a Waiters> {
+    std::iter::once(pool).chain(limit)
+}
@@ -0,0 +1,123 @@
+use std::cmp;
+use std::time::Duration;
+
+use rand::Rng;
+
+/// The longest the first connections of a pool wait before opening; see [`stagger`].
+const MAX_STAGGER: Duration = Duration::from_millis(50);
+
+/// The factor the `max_lifetime` and `idle_timeout` of a connection are multiplied by, drawn
+/// when it is opened from `1 ± jitter`.
+pub(super) fn lifetime_factor(jitter: f64) -> f64 {
+    if jitter > 0.0 {
+        rand::thread_rng().gen_range(1.0 - jitter, 1.0 + jitter)
+    } else {
+        1.0
+    }
+}
+
+/// Whether a connection is past `timeout` after `elapsed`, with its `lifetime_factor`.
+pub(super) fn is_past(elapsed: Duration, timeout: Duration, factor: f64) -> bool {
+    elapsed > timeout.mul_f64(factor)
+}
+
+/// How often the reaper runs to watch `timeout`: once per `timeout` without jitter, and
+/// otherwise ten times over the band the timeouts of the connections are spread over, so that
+/// connections due at different points of it are not all closed by the same run.
+pub(super) fn reaper_period(timeout: Duration, jitter: f64) -> Duration {
+    if jitter > 0.0 {
+        cmp::max(timeout.mul_f64(jitter / 5.0), Duration::from_millis(1))
+    } else {
+        timeout
+    }
+}
+
+/// How long the `index`th of the first connections of a pool waits before opening, so that
+/// they are not all opened at the same moment: not at all for the first one, and a random
+/// part of 50 milliseconds for the others.
+pub(super) fn stagger(index: u64) -> Duration {
+    if index == 0 {
+        return Duration::from_secs(0);
+    }
+
+    MAX_STAGGER.mul_f64(rand::thread_rng().gen_range(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    // the number of connections closed on each run of the reaper, over three hours of a pool
+    // whose connections are all opened at once and replaced as soon as they are closed
+    fn simulate_recycling(jitter: f64) -> Vec<usize> {
+        let max_lifetime = 30 * MINUTE;
+        let period = reaper_period(max_lifetime, jitter);
+
+        // the time each connection was opened at, and its factor
+        let mut connections: Vec<(Duration, f64)> = (0..100)
+            .map(|_| (Duration::from_secs(0), lifetime_factor(jitter)))
+            .collect();
+
+        let mut closed = Vec::new();
+        let mut now = Duration::from_secs(0);
+
+        while now < 180 * MINUTE {
+            now += period;
+
+            let mut run = 0;
+
+            for (opened, factor) in &mut connections {
+                if is_past(now - *opened, max_lifetime, *factor) {
+                    *opened = now;
+                    *factor = lifetime_factor(jitter);
+
+                    run += 1;
+                }
+            }
+
+            closed.push(run);
+        }
+
+        closed
+    }
+
+    #[test]
+    fn it_spreads_recycling_over_the_band_of_the_jitter() {
+        // without jitter, every connection is closed by the same run, every time
+        let unjittered: Vec<usize> = simulate_recycling(0.0)
+            .into_iter()
+            .filter(|&run| run > 0)
+            .collect();
+
+        assert_eq!(unjittered, [100; 3]);
+
+        // with it, the first connections of the pool are closed over 27 to 33 minutes, and
+        // their replacements are spread further
+        let jittered = simulate_recycling(0.1);
+
+        assert!(jittered.iter().sum::<usize>() >= 500);
+        assert!(jittered.iter().all(|&run| run <= 35), "{:?}", jittered);
+        assert!(jittered.iter().filter(|&&run| run > 0).count() >= 40);
+    }
+
+    #[test]
+    fn it_draws_factors_within_the_band() {
+        let factors: Vec<f64> = (0..1000).map(|_| lifetime_factor(0.1)).collect();
+
+        for &factor in &factors {
+            assert!((0.9..1.1).contains(&factor), "{}", factor);
+        }
+
+        // connections opened at once still draw their own factors
+        assert!(factors.iter().any(|&factor| factor != factors[0]));
+
+        assert_eq!(lifetime_factor(0.0), 1.0);
+        assert_eq!(stagger(0), Duration::from_secs(0));
+
+        for index in 1..100 {
+            assert!(stagger(index) < MAX_STAGGER);
+        }
+    }
+}
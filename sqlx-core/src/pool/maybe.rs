@@ -2,6 +2,8 @@ use crate::database::Database;
 use crate::pool::PoolConnection;
 use std::ops::{Deref, DerefMut};
 
+// held only while a transaction is open, so its size matters little
+#[allow(clippy::large_enum_variant)]
 pub(crate) enum MaybePoolConnection<'c, DB: Database> {
     #[allow(dead_code)]
     Connection(&'c mut DB::Connection),
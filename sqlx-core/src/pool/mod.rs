@@ -50,19 +50,49 @@
 //! sqlx::query("DELETE FROM articles").execute(&pool).await?;
 //! ```
 //!
+//! Like connections and transactions, a pool also has `execute`, `fetch_one`, `fetch_optional`
+//! and `fetch_all` of its own, which need no trait in scope:
+//!
+//! ```rust,ignore
+//! let row = pool.fetch_one("SELECT count(*) FROM articles").await?;
+//! ```
+//!
 //! A connection or transaction may also be manually acquired with
 //! [`Pool::acquire`] or
 //! [`Pool::begin`].
+//!
+//! # Partitioning a pool
+//!
+//! An application that connects to many databases of the same kind, such as one per tenant,
+//! can keep a pool for each of them under a single limit on the total number of connections
+//! with [`PartitionedPool`].
+//!
+//! # Shedding load
+//!
+//! By default an acquire waits for a connection until the connect timeout, however long the
+//! queue. An [`AdmissionPolicy`] set with [`PoolOptions::admission_policy`] rejects acquires
+//! with [`Error::PoolOverloaded`] instead while the pool is overloaded, e.g. with
+//! [`RejectWhenWaitersExceed`], keeping the wait of those it lets in short.
+//!
+//! # Testing timeouts
+//!
+//! The pool measures time with the clock of the runtime. With Tokio, the timeouts of a pool can
+//! be tested without waiting for them by running the test on `sqlx::testing::VirtualTime`
+//! (behind the `testing` feature), which only moves the clock forward when asked to.
 
 use self::inner::SharedPool;
 use crate::connection::Connection;
 use crate::database::Database;
-use crate::error::Error;
+use crate::error::{ConfigError, Error};
 use crate::transaction::Transaction;
+use sqlx_rt::Instant;
+use std::any::Any;
+use std::cmp;
 use std::fmt;
 use std::future::Future;
+use std::panic::Location;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 #[macro_use]
 mod executor;
@@ -70,13 +100,31 @@ mod executor;
 #[macro_use]
 mod maybe;
 
+mod admission;
+mod close_event;
+#[cfg(feature = "options-serde")]
+mod config;
 mod connection;
+mod credentials;
+mod histogram;
 mod inner;
+mod jitter;
 mod options;
+mod partitioned;
+mod query_cache;
+mod resize;
 
+pub use self::admission::{
+    AdmissionPolicy, PoolLoad, Priority, RejectWhenEstimatedWaitExceeds, RejectWhenWaitersExceed,
+};
+pub use self::close_event::CloseEvent;
 pub use self::connection::PoolConnection;
+pub use self::credentials::{Credential, PasswordProvider};
+pub use self::histogram::DurationHistogram;
 pub(crate) use self::maybe::MaybePoolConnection;
 pub use self::options::PoolOptions;
+pub use self::partitioned::{PartitionedPool, PartitionedPoolOptions};
+pub use self::resize::PoolResize;
 
 /// An asynchronous pool of SQLx database connections.
 ///
@@ -218,6 +266,8 @@ pub struct Pool<DB: Database>(pub(crate) Arc<SharedPool<DB>>);
 impl<DB: Database> Pool<DB> {
     /// Creates a new connection pool with a default pool configuration and
     /// the given connection URI; and, immediately establishes one connection.
+    ///
+    /// Fails with the error of that connection; see [`PoolOptions::connect`].
     pub async fn connect(uri: &str) -> Result<Self, Error> {
         PoolOptions::<DB>::new().connect(uri).await
     }
@@ -231,14 +281,16 @@ impl<DB: Database> Pool<DB> {
     }
 
     /// Creates a new connection pool with a default pool configuration and
-    /// the given connection URI; and, will establish a connections as the pool
+    /// the given connection URI; and, will establish connections as the pool
     /// starts to be used.
+    ///
+    /// No connection is opened until then; see [`PoolOptions::connect_lazy`].
     pub fn connect_lazy(uri: &str) -> Result<Self, Error> {
         PoolOptions::<DB>::new().connect_lazy(uri)
     }
 
     /// Creates a new connection pool with a default pool configuration and
-    /// the given connection options; and, will establish a connections as the pool
+    /// the given connection options; and, will establish connections as the pool
     /// starts to be used.
     pub fn connect_lazy_with(options: <DB::Connection as Connection>::Options) -> Self {
         PoolOptions::<DB>::new().connect_lazy_with(options)
@@ -247,21 +299,60 @@ impl<DB: Database> Pool<DB> {
     /// Retrieves a connection from the pool.
     ///
     /// Waits for at most the configured connection timeout before returning an error.
+    ///
+    /// Where the time went is given by [`PoolConnection::acquire_stats`], and logged at the
+    /// `debug` level with the target `sqlx::pool::acquire`.
+    ///
+    /// The caller's location is recorded for
+    /// [`PoolOptions::checkout_warning_after`].
+    #[track_caller]
     pub fn acquire(&self) -> impl Future<Output = Result<PoolConnection<DB>, Error>> + 'static {
+        self.acquire_with_priority(Priority::Normal)
+    }
+
+    /// Retrieves a connection from the pool, like [`acquire`](Self::acquire), telling the
+    /// pool's [`admission_policy`](PoolOptions::admission_policy) how urgently it is needed.
+    ///
+    /// A policy may reject low priority acquires with [`Error::PoolOverloaded`] while the pool
+    /// is busy, but let others wait. Without a policy, the priority makes no difference.
+    #[track_caller]
+    pub fn acquire_with_priority(
+        &self,
+        priority: Priority,
+    ) -> impl Future<Output = Result<PoolConnection<DB>, Error>> + 'static {
         let shared = self.0.clone();
-        async move { shared.acquire().await.map(|conn| conn.attach(&shared)) }
+        let location = Location::caller();
+        async move {
+            shared
+                .acquire(priority)
+                .await
+                .map(|(conn, stats)| conn.attach(&shared, location, stats))
+        }
     }
 
     /// Attempts to retrieve a connection from the pool if there is one available.
     ///
-    /// Returns `None` immediately if there are no idle connections available in the pool.
+    /// Returns `None` immediately if there are no idle connections available in the pool, if the
+    /// pool is closed, or if tasks are already waiting in [`acquire`](Self::acquire), as they
+    /// are served first.
+    ///
+    /// This never opens a connection nor waits for one, so it can be called where blocking is
+    /// not an option, such as from a `Drop` impl. For the same reason the connection is not
+    /// tested first, as [`test_before_acquire`][PoolOptions::test_before_acquire] and
+    /// [`before_acquire`][PoolOptions::before_acquire] would.
+    #[track_caller]
     pub fn try_acquire(&self) -> Option<PoolConnection<DB>> {
-        self.0.try_acquire().map(|conn| conn.attach(&self.0))
+        let location = Location::caller();
+        self.0
+            .try_acquire()
+            .map(|conn| conn.attach(&self.0, location, AcquireStats::default()))
     }
 
     /// Retrieves a new connection and immediately begins a new transaction.
-    pub async fn begin(&self) -> Result<Transaction<'static, DB>, Error> {
-        Ok(Transaction::begin(MaybePoolConnection::PoolConnection(self.acquire().await?)).await?)
+    #[track_caller]
+    pub fn begin(&self) -> impl Future<Output = Result<Transaction<'static, DB>, Error>> + 'static {
+        let acquire = self.acquire();
+        async move { Transaction::begin(MaybePoolConnection::PoolConnection(acquire.await?)).await }
     }
 
     /// Attempts to retrieve a new connection and immediately begins a new transaction if there
@@ -289,6 +380,43 @@ impl<DB: Database> Pool<DB> {
         self.0.is_closed()
     }
 
+    /// Returns a future that resolves once [`.close()`][Pool::close] is called on the pool, or
+    /// right away if it already was.
+    ///
+    /// `close()` waits for every connection to be returned, so a background task holding one
+    /// should finish the work it is doing and stop, rather than be cut off mid-transaction
+    /// when the process exits. [`CloseEvent::do_until`] runs each unit of work until the pool
+    /// starts closing.
+    ///
+    /// # Example
+    ///
+    /// A worker that takes jobs from a queue until the pool is closed:
+    ///
+    /// ```rust,ignore
+    /// let mut close_event = pool.close_event();
+    /// let mut conn = pool.acquire().await?;
+    ///
+    /// loop {
+    ///     // waiting for the next job is cut short by `close()`
+    ///     let job = match close_event.do_until(next_job(&mut conn)).await {
+    ///         Ok(job) => job?,
+    ///         Err(sqlx::Error::PoolClosed) => break,
+    ///         Err(e) => return Err(e),
+    ///     };
+    ///
+    ///     // but a job that was started is finished
+    ///     let mut tx = conn.begin().await?;
+    ///     run_job(&mut tx, job).await?;
+    ///     tx.commit().await?;
+    /// }
+    ///
+    /// // back to the pool, which `close()` is waiting for
+    /// drop(conn);
+    /// ```
+    pub fn close_event(&self) -> CloseEvent {
+        self.0.close_event()
+    }
+
     /// Returns the number of connections currently active. This includes idle connections.
     pub fn size(&self) -> u32 {
         self.0.size()
@@ -302,6 +430,236 @@ impl<DB: Database> Pool<DB> {
     pub fn num_idle(&self) -> usize {
         self.0.num_idle()
     }
+
+    /// Opens connections until the pool has [`min_connections`], for a pool created with
+    /// [`connect_lazy`] or one that has lost connections.
+    ///
+    /// One attempt is made for each connection that is missing, and an attempt that fails does
+    /// not stop the others. Connections that could not be opened are left to be opened by
+    /// [`acquire`] as usual.
+    ///
+    /// [`min_connections`]: PoolOptions::min_connections
+    /// [`connect_lazy`]: Pool::connect_lazy
+    /// [`acquire`]: Pool::acquire
+    pub async fn warm_up(&self) -> PoolWarmUp {
+        self.0.warm_up().await
+    }
+
+    /// Changes the limits of the pool while it is in use, without closing the connections it
+    /// has open; settings not set in `resize` keep their current value.
+    ///
+    /// Growing takes effect at once: tasks waiting in [`acquire`] are woken to open the
+    /// connections the pool now has room for, and connections up to a larger
+    /// `min_connections` are opened in the background. Shrinking closes idle connections above
+    /// the new `max_connections` at once, and checked-out connections as they are returned;
+    /// those are never closed while in use, so the pool stays above its limit until then, as
+    /// counted by [`PoolStats::pending_shrink`]. The reaper uses the new timeouts from its next
+    /// run.
+    ///
+    /// Fails without changing anything if the limits it would leave the pool with are
+    /// invalid; see [`PoolOptions::validate`].
+    ///
+    /// [`acquire`]: Pool::acquire
+    pub fn resize(&self, resize: PoolResize) -> Result<(), Vec<ConfigError>> {
+        self.0.resize(resize)
+    }
+
+    /// Totals over every connection the pool has opened, as of the last time each was
+    /// returned to the pool.
+    ///
+    /// A connection that is still checked out is counted up to when it was last released,
+    /// except in [`checked_out`][PoolStats::checked_out].
+    pub fn stats(&self) -> PoolStats {
+        self.0.stats()
+    }
+
+    /// Drops every result in the pool's query cache, so the next execution of each query
+    /// marked with [`cached`] goes to the database; e.g. after writing to the tables they read.
+    ///
+    /// [`cached`]: crate::query::Query::cached
+    pub fn invalidate_query_cache(&self) {
+        self.0.query_cache.clear();
+    }
+
+    /// A pool of one connection, opened as those of this pool are but not counted towards its
+    /// `max_connections`, for a connection that a driver holds on to for as long as this pool
+    /// is open; e.g. the listening connection of a `PgSharedListener`.
+    ///
+    /// Its connection is opened lazily, with the connect timeout and password provider of this
+    /// pool, and is reported to its listener of connection events.
+    #[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+    pub(crate) fn dedicated(&self) -> Pool<DB> {
+        let mut options = PoolOptions::new()
+            .max_connections(1)
+            .max_lifetime(None)
+            .idle_timeout(None)
+            .connect_timeout(self.0.timeouts().connect_timeout);
+
+        options.password_provider = self.0.options.password_provider.clone();
+        options.connection_name_suffix = self.0.options.connection_name_suffix.clone();
+
+        options.connect_lazy_with(self.0.connect_options.clone())
+    }
+
+    /// The state of type `T` that a driver keeps for as long as the pool, made by `init` the
+    /// first time it is asked for; a pool keeps the state of one type only.
+    #[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+    pub(crate) fn driver_state<T: Any + Send + Sync>(&self, init: impl FnOnce() -> T) -> &T {
+        self.0
+            .driver_state
+            .get_or_init(|| Box::new(init()))
+            .downcast_ref()
+            .expect("(bug) driver state of two types kept by the same pool")
+    }
+
+    /// How long opening a connection may take, including the wait for a slot in the pool.
+    #[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+    pub(crate) fn connect_timeout(&self) -> Duration {
+        self.0.timeouts().connect_timeout
+    }
+}
+
+/// Telemetry of a [`Pool`], aggregated from the [`ConnectionStats`] of its connections;
+/// see [`Pool::stats`].
+///
+/// [`ConnectionStats`]: crate::connection::ConnectionStats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// The number of connections the pool has opened.
+    pub connections_opened: u64,
+
+    /// The number of queries sent on all connections.
+    pub queries: u64,
+
+    /// The sum of the latencies of all queries.
+    pub total_latency: Duration,
+
+    /// The number of bytes of protocol messages sent on all connections.
+    pub bytes_sent: u64,
+
+    /// The number of bytes of protocol messages received on all connections.
+    pub bytes_received: u64,
+
+    /// The number of connections currently checked out of the pool.
+    pub checked_out: u64,
+
+    /// The longest time a connection was checked out before it was returned to the pool.
+    pub longest_checkout: Duration,
+
+    /// The number of connections returned with session state left over; always zero unless
+    /// [`PoolOptions::track_session_state`] is enabled.
+    pub session_state_leaks: u64,
+
+    /// The number of queries marked with [`cached`] that were served from the pool's query
+    /// cache, without a round trip to the database.
+    ///
+    /// [`cached`]: crate::query::Query::cached
+    pub query_cache_hits: u64,
+
+    /// The number of queries marked with [`cached`] that were not found in the pool's query
+    /// cache, or had expired, and were sent to the database.
+    ///
+    /// [`cached`]: crate::query::Query::cached
+    pub query_cache_misses: u64,
+
+    /// The number of connections returned to the pool after seeing the server shut down; see
+    /// [`PoolOptions::test_idle_on_shutdown`].
+    pub server_shutdowns: u64,
+
+    /// The number of open connections, checked out or idle, whose credential from the
+    /// [`PasswordProvider`] has expired; see [`PoolOptions::password_provider`].
+    pub expired_credentials: u64,
+
+    /// The current [`max_connections`](PoolOptions::max_connections) of the pool, as last
+    /// changed by [`Pool::resize`]; for a [`PartitionedPool`], the sum over its partitions.
+    pub max_connections: u32,
+
+    /// The current [`min_connections`](PoolOptions::min_connections) of the pool, as last
+    /// changed by [`Pool::resize`].
+    pub min_connections: u32,
+
+    /// The number of connections the pool has open above `max_connections` after it was
+    /// shrunk by [`Pool::resize`], which it closes as they are returned.
+    pub pending_shrink: u32,
+
+    /// The number of acquires rejected with [`Error::PoolOverloaded`] by the
+    /// [`admission_policy`](PoolOptions::admission_policy) of the pool.
+    pub acquires_rejected: u64,
+
+    /// How long each [`Pool::acquire`] waited for another task to return a connection, or to
+    /// free a slot for a new one; see [`AcquireStats::queue_wait`].
+    pub acquire_wait: DurationHistogram,
+
+    /// How long each [`Pool::acquire`] that opened a connection took to do so; see
+    /// [`AcquireStats::connect`].
+    pub connect_time: DurationHistogram,
+}
+
+impl PoolStats {
+    /// The mean latency of all queries, if there have been any.
+    pub fn average_latency(&self) -> Option<Duration> {
+        if self.queries == 0 {
+            return None;
+        }
+
+        Some(Duration::from_nanos(
+            (self.total_latency.as_nanos() / u128::from(self.queries)) as u64,
+        ))
+    }
+
+    // adds the totals of another pool, e.g. of one partition of a `PartitionedPool`
+    fn add(&mut self, other: &PoolStats) {
+        self.connections_opened += other.connections_opened;
+        self.queries += other.queries;
+        self.total_latency += other.total_latency;
+        self.bytes_sent += other.bytes_sent;
+        self.bytes_received += other.bytes_received;
+        self.checked_out += other.checked_out;
+        self.longest_checkout = cmp::max(self.longest_checkout, other.longest_checkout);
+        self.session_state_leaks += other.session_state_leaks;
+        self.query_cache_hits += other.query_cache_hits;
+        self.query_cache_misses += other.query_cache_misses;
+        self.server_shutdowns += other.server_shutdowns;
+        self.expired_credentials += other.expired_credentials;
+        self.max_connections += other.max_connections;
+        self.min_connections += other.min_connections;
+        self.pending_shrink += other.pending_shrink;
+        self.acquires_rejected += other.acquires_rejected;
+        self.acquire_wait.add(&other.acquire_wait);
+        self.connect_time.add(&other.connect_time);
+    }
+}
+
+/// Where the time of one [`Pool::acquire`] went; see [`PoolConnection::acquire_stats`].
+///
+/// The parts add up to about the total; what is left is the time spent closing expired
+/// connections taken from the pool, and running [`before_acquire`][PoolOptions::before_acquire].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AcquireStats {
+    /// The time from the call to `acquire` to the connection being returned by it.
+    pub total: Duration,
+
+    /// The time spent waiting for another task to return a connection, or to free a slot for a
+    /// new one, while the pool was at [`max_connections`](PoolOptions::max_connections).
+    pub queue_wait: Duration,
+
+    /// The time spent opening new connections, if any were opened, including the attempts that
+    /// failed while the database was starting up and the pauses between them.
+    pub connect: Option<Duration>,
+
+    /// The time spent pinging idle connections, if any were tested with
+    /// [`test_before_acquire`](PoolOptions::test_before_acquire).
+    pub ping: Option<Duration>,
+}
+
+/// The outcome of [`Pool::warm_up`].
+#[derive(Debug, Default)]
+pub struct PoolWarmUp {
+    /// The number of connections that were opened.
+    pub created: u32,
+
+    /// The errors of the connections that could not be opened, in the order they occurred.
+    pub errors: Vec<Error>,
 }
 
 /// Returns a new [Pool] tied to the same shared connection pool.
@@ -1,24 +1,35 @@
 use crate::connection::Connection;
 use crate::database::Database;
-use crate::error::Error;
+use crate::error::{AggregateError, ConfigError, Error};
+use crate::events::ConnectionEventListener;
+use crate::middleware::QueryMiddleware;
 use crate::pool::inner::SharedPool;
-use crate::pool::Pool;
+use crate::pool::jitter;
+use crate::pool::{AdmissionPolicy, PasswordProvider, Pool};
 use futures_core::future::BoxFuture;
-use sqlx_rt::spawn;
+use sqlx_rt::Instant;
 use std::cmp;
 use std::fmt::{self, Debug, Formatter};
+use std::iter;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 pub struct PoolOptions<DB: Database> {
     pub(crate) test_before_acquire: bool,
+    pub(crate) test_idle_on_shutdown: bool,
     pub(crate) after_connect: Option<
-        Box<
+        Arc<
+            dyn Fn(&mut DB::Connection) -> BoxFuture<'_, Result<(), Error>> + 'static + Send + Sync,
+        >,
+    >,
+    // work a driver-specific option asks of each new connection, before `after_connect`
+    pub(crate) prepare_connection: Option<
+        Arc<
             dyn Fn(&mut DB::Connection) -> BoxFuture<'_, Result<(), Error>> + 'static + Send + Sync,
         >,
     >,
     pub(crate) before_acquire: Option<
-        Box<
+        Arc<
             dyn Fn(&mut DB::Connection) -> BoxFuture<'_, Result<bool, Error>>
                 + 'static
                 + Send
@@ -26,13 +37,24 @@ pub struct PoolOptions<DB: Database> {
         >,
     >,
     pub(crate) after_release:
-        Option<Box<dyn Fn(&mut DB::Connection) -> bool + 'static + Send + Sync>>,
+        Option<Arc<dyn Fn(&mut DB::Connection) -> bool + 'static + Send + Sync>>,
+    pub(crate) connection_name_suffix: Option<Arc<dyn Fn(u64) -> String + 'static + Send + Sync>>,
+    pub(crate) query_middleware: Option<Arc<dyn QueryMiddleware>>,
+    pub(crate) connection_events: Option<Arc<dyn ConnectionEventListener>>,
+    pub(crate) password_provider: Option<Arc<dyn PasswordProvider>>,
+    pub(crate) admission_policy: Option<Arc<dyn AdmissionPolicy>>,
+    pub(crate) shrink_buffers_above: Option<usize>,
     pub(crate) max_connections: u32,
     pub(crate) connect_timeout: Duration,
     pub(crate) min_connections: u32,
     pub(crate) max_lifetime: Option<Duration>,
     pub(crate) idle_timeout: Option<Duration>,
+    pub(crate) lifetime_jitter: f64,
+    pub(crate) checkout_warning_after: Option<Duration>,
     pub(crate) fair: bool,
+    pub(crate) track_session_state: bool,
+    pub(crate) query_cache_max_entries: usize,
+    pub(crate) query_cache_max_size: usize,
 }
 
 impl<DB: Database> Default for PoolOptions<DB> {
@@ -41,19 +63,63 @@ impl<DB: Database> Default for PoolOptions<DB> {
     }
 }
 
+// the callbacks are shared between clones, e.g. by the partitions of a `PartitionedPool`
+impl<DB: Database> Clone for PoolOptions<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            test_before_acquire: self.test_before_acquire,
+            test_idle_on_shutdown: self.test_idle_on_shutdown,
+            after_connect: self.after_connect.clone(),
+            prepare_connection: self.prepare_connection.clone(),
+            before_acquire: self.before_acquire.clone(),
+            after_release: self.after_release.clone(),
+            connection_name_suffix: self.connection_name_suffix.clone(),
+            query_middleware: self.query_middleware.clone(),
+            connection_events: self.connection_events.clone(),
+            password_provider: self.password_provider.clone(),
+            admission_policy: self.admission_policy.clone(),
+            shrink_buffers_above: self.shrink_buffers_above,
+            max_connections: self.max_connections,
+            connect_timeout: self.connect_timeout,
+            min_connections: self.min_connections,
+            max_lifetime: self.max_lifetime,
+            idle_timeout: self.idle_timeout,
+            lifetime_jitter: self.lifetime_jitter,
+            checkout_warning_after: self.checkout_warning_after,
+            fair: self.fair,
+            track_session_state: self.track_session_state,
+            query_cache_max_entries: self.query_cache_max_entries,
+            query_cache_max_size: self.query_cache_max_size,
+        }
+    }
+}
+
 impl<DB: Database> PoolOptions<DB> {
     pub fn new() -> Self {
         Self {
             after_connect: None,
+            prepare_connection: None,
             test_before_acquire: true,
+            test_idle_on_shutdown: true,
             before_acquire: None,
             after_release: None,
+            connection_name_suffix: None,
+            query_middleware: None,
+            connection_events: None,
+            password_provider: None,
+            admission_policy: None,
+            shrink_buffers_above: None,
             max_connections: 10,
             min_connections: 0,
             connect_timeout: Duration::from_secs(30),
             idle_timeout: Some(Duration::from_secs(10 * 60)),
             max_lifetime: Some(Duration::from_secs(30 * 60)),
+            lifetime_jitter: 0.1,
+            checkout_warning_after: None,
             fair: true,
+            track_session_state: false,
+            query_cache_max_entries: 1024,
+            query_cache_max_size: 16 * 1024 * 1024,
         }
     }
 
@@ -77,9 +143,13 @@ impl<DB: Database> PoolOptions<DB> {
     ///
     /// If any connection is reaped by [`max_lifetime`] or [`idle_timeout`] and it brings
     /// the connection count below this amount, a new connection will be opened to replace it.
+    /// So are connections that were [detached][crate::pool::PoolConnection::detach] or closed,
+    /// the next time the reaper runs; there is no reaper if neither [`max_lifetime`],
+    /// [`idle_timeout`] nor [`checkout_warning_after`] is set.
     ///
     /// [`max_lifetime`]: Self::max_lifetime
     /// [`idle_timeout`]: Self::idle_timeout
+    /// [`checkout_warning_after`]: Self::checkout_warning_after
     pub fn min_connections(mut self, min: u32) -> Self {
         self.min_connections = min;
         self
@@ -114,6 +184,101 @@ impl<DB: Database> PoolOptions<DB> {
         self
     }
 
+    /// Set how far the [`max_lifetime`] and [`idle_timeout`] of each connection may be from
+    /// the configured ones, as a fraction of them; `0.1` by default, for ±10%.
+    ///
+    /// Each connection multiplies both by a factor drawn at random from `1 ± jitter` when it is
+    /// opened, so that connections opened together, e.g. as a service starts, are not all
+    /// closed and replaced at the same moment every `max_lifetime`. The reaper runs often
+    /// enough to close each connection close to its own deadline, and the first connections
+    /// of a pool are opened a few milliseconds apart. `0.0` turns the jitter off.
+    ///
+    /// The timeouts a connection ended up with are in the [`Debug`](std::fmt::Debug) output
+    /// of its [`PoolConnection`], and returned by [`PoolConnection::max_lifetime`] and
+    /// [`PoolConnection::idle_timeout`].
+    ///
+    /// [`max_lifetime`]: Self::max_lifetime
+    /// [`idle_timeout`]: Self::idle_timeout
+    /// [`PoolConnection`]: crate::pool::PoolConnection
+    /// [`PoolConnection::max_lifetime`]: crate::pool::PoolConnection::max_lifetime
+    /// [`PoolConnection::idle_timeout`]: crate::pool::PoolConnection::idle_timeout
+    pub fn lifetime_jitter(mut self, jitter: f64) -> Self {
+        self.lifetime_jitter = jitter;
+        self
+    }
+
+    /// Log a warning about connections that have been checked out of the pool for longer than
+    /// `threshold`, to help find connections that are never returned.
+    ///
+    /// The warning names the location [`Pool::acquire`] (or [`Pool::try_acquire`] or
+    /// [`Pool::begin`]) was called from and is logged once per checkout, by the same background
+    /// task that reaps idle connections: it runs at least once every `threshold`, so the warning
+    /// can come up to twice `threshold` after the connection was acquired. Connections that are
+    /// meant to be held for a long time can opt out with [`PoolConnection::leaked_detach`].
+    ///
+    /// Disabled by default.
+    ///
+    /// [`PoolConnection::leaked_detach`]: crate::pool::PoolConnection::leaked_detach
+    pub fn checkout_warning_after(mut self, threshold: impl Into<Option<Duration>>) -> Self {
+        self.checkout_warning_after = threshold.into();
+        self
+    }
+
+    /// Log a warning about connections returned to the pool with session state that the next
+    /// user of the connection would inherit: an open transaction, statements prepared with SQL
+    /// `PREPARE`, a changed `search_path` or temporary tables; see [`SessionState`].
+    ///
+    /// This is a diagnostic for queries that work on their own connection but not behind the
+    /// pool. The state of a connection is read when it is opened and each time it is returned,
+    /// after the pool has rolled back any transaction that was dropped, and compared with the
+    /// state it was checked out with. Every returned connection then costs a round trip; for
+    /// MySQL only the transaction is tracked, which needs none. Leaks are also counted in
+    /// [`PoolStats::session_state_leaks`].
+    ///
+    /// Disabled by default.
+    ///
+    /// [`SessionState`]: crate::connection::SessionState
+    /// [`PoolStats::session_state_leaks`]: crate::pool::PoolStats::session_state_leaks
+    pub fn track_session_state(mut self, track: bool) -> Self {
+        self.track_session_state = track;
+        self
+    }
+
+    /// Set the maximum number of results in the pool's cache of queries marked with
+    /// [`cached`]; the least recently used are dropped to make room for new ones.
+    ///
+    /// Defaults to 1024. With zero nothing is cached.
+    ///
+    /// [`cached`]: crate::query::Query::cached
+    pub fn query_cache_max_entries(mut self, max: usize) -> Self {
+        self.query_cache_max_entries = max;
+        self
+    }
+
+    /// Set the maximum number of bytes of the results in the pool's cache of queries marked
+    /// with [`cached`], counting the data of their rows, their SQL and their bind values; the
+    /// least recently used are dropped to make room for new ones. A result larger than this on
+    /// its own is not cached.
+    ///
+    /// Defaults to 16 MiB.
+    ///
+    /// [`cached`]: crate::query::Query::cached
+    pub fn query_cache_max_size(mut self, bytes: usize) -> Self {
+        self.query_cache_max_size = bytes;
+        self
+    }
+
+    /// Shrink the buffers of connections returned to the pool whose buffers have grown past
+    /// `threshold` bytes in total.
+    ///
+    /// A connection that has fetched one very large result set otherwise keeps buffers of that
+    /// size for as long as it lives; with many connections in the pool that adds up.
+    /// See [`Connection::shrink_buffers`].
+    pub fn shrink_buffers_above(mut self, threshold: impl Into<Option<usize>>) -> Self {
+        self.shrink_buffers_above = threshold.into();
+        self
+    }
+
     /// If true, the health of a connection will be verified by a call to [`Connection::ping`]
     /// before returning the connection.
     ///
@@ -123,6 +288,18 @@ impl<DB: Database> PoolOptions<DB> {
         self
     }
 
+    /// If true, when a connection returned to the pool has seen the server shut down, e.g.
+    /// PostgreSQL reporting `57P01` during a failover, every idle connection is pinged right
+    /// away and those that fail are closed, instead of each failing once it is next used.
+    ///
+    /// Defaults to `true`. Each such event is counted in [`PoolStats::server_shutdowns`].
+    ///
+    /// [`PoolStats::server_shutdowns`]: crate::pool::PoolStats::server_shutdowns
+    pub fn test_idle_on_shutdown(mut self, test: bool) -> Self {
+        self.test_idle_on_shutdown = test;
+        self
+    }
+
     /// If set to `true`, calls to `acquire()` are fair and connections  are issued
     /// in first-come-first-serve order. If `false`, "drive-by" tasks may steal idle connections
     /// ahead of tasks that have been waiting.
@@ -147,7 +324,6 @@ impl<DB: Database> PoolOptions<DB> {
     ///
     /// ```no_run
     /// # async fn f() -> Result<(), Box<dyn std::error::Error>> {
-    /// use sqlx_core::executor::Executor;
     /// use sqlx_core::postgres::PgPoolOptions;
     /// // PostgreSQL
     /// let pool = PgPoolOptions::new()
@@ -166,7 +342,17 @@ impl<DB: Database> PoolOptions<DB> {
         for<'c> F:
             Fn(&'c mut DB::Connection) -> BoxFuture<'c, Result<(), Error>> + 'static + Send + Sync,
     {
-        self.after_connect = Some(Box::new(callback));
+        self.after_connect = Some(Arc::new(callback));
+        self
+    }
+
+    // sets the work of a driver-specific option, e.g. `PgPoolOptions::warm_types`
+    pub(crate) fn prepare_connection<F>(mut self, callback: F) -> Self
+    where
+        for<'c> F:
+            Fn(&'c mut DB::Connection) -> BoxFuture<'c, Result<(), Error>> + 'static + Send + Sync,
+    {
+        self.prepare_connection = Some(Arc::new(callback));
         self
     }
 
@@ -177,7 +363,7 @@ impl<DB: Database> PoolOptions<DB> {
             + Send
             + Sync,
     {
-        self.before_acquire = Some(Box::new(callback));
+        self.before_acquire = Some(Arc::new(callback));
         self
     }
 
@@ -185,16 +371,234 @@ impl<DB: Database> PoolOptions<DB> {
     where
         F: Fn(&mut DB::Connection) -> bool + 'static + Send + Sync,
     {
-        self.after_release = Some(Box::new(callback));
+        self.after_release = Some(Arc::new(callback));
+        self
+    }
+
+    /// Append a suffix to the application name of each connection the pool opens, so
+    /// individual connections can be told apart in `pg_stat_activity` or
+    /// `performance_schema.session_connect_attrs`.
+    ///
+    /// The callback receives the number of connections this pool has opened before (starting
+    /// at `0`) and returns the suffix. The suffix is appended to the configured application name,
+    /// or the name of the executable if none is set. Drivers that cannot name their connections
+    /// ignore it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sqlx_core::postgres::{PgConnectOptions, PgPoolOptions};
+    /// // connections are named `myservice-pool-0`, `myservice-pool-1`, ...
+    /// let pool = PgPoolOptions::new()
+    ///     .connection_name_suffix(|n| format!("-pool-{}", n))
+    ///     .connect_with(PgConnectOptions::new().application_name("myservice"))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connection_name_suffix<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(u64) -> String + 'static + Send + Sync,
+    {
+        self.connection_name_suffix = Some(Arc::new(callback));
         self
     }
 
-    /// Creates a new pool from this configuration and immediately establishes one connection.
+    /// Set a middleware to be called with the SQL of every query executed on the connections
+    /// of the pool, in place of any set on the connect options; see
+    /// [`ConnectOptions::query_middleware`](crate::connection::ConnectOptions::query_middleware).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::sync::Arc;
+    /// use sqlx_core::middleware::SqlCommenter;
+    /// use sqlx_core::postgres::PgPoolOptions;
+    /// // statements end with `/*application='myservice'*/`
+    /// let pool = PgPoolOptions::new()
+    ///     .query_middleware(Arc::new(SqlCommenter::new().tag("application", "myservice")))
+    ///     .connect("postgres://localhost/mydb")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_middleware(mut self, middleware: Arc<dyn QueryMiddleware>) -> Self {
+        self.query_middleware = Some(middleware);
+        self
+    }
+
+    /// Set a listener to be told about every connection the pool opens, fails to open or
+    /// closes, and why it closed it, in place of any set on the connect options; see
+    /// [`ConnectionEventListener`].
+    ///
+    /// The listener is called on the tasks of the pool, so it must return quickly.
+    pub fn connection_events(mut self, listener: Arc<dyn ConnectionEventListener>) -> Self {
+        self.connection_events = Some(listener);
+        self
+    }
+
+    /// Set a provider of the password of each connection the pool opens, in place of any set on
+    /// the connect options, for credentials that expire such as IAM authentication tokens.
+    ///
+    /// A connection whose credential expired is replaced the next time it is returned to the
+    /// pool, at a random point after the expiry so that connections opened together are not
+    /// all replaced at once; see [`PasswordProvider`]. Drivers that do not authenticate with a
+    /// password never ask the provider.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use futures_core::future::BoxFuture;
+    /// use sqlx_core::error::Error;
+    /// use sqlx_core::pool::{Credential, PasswordProvider};
+    /// use sqlx_core::postgres::PgPoolOptions;
+    ///
+    /// struct IamToken;
+    ///
+    /// impl PasswordProvider for IamToken {
+    ///     fn password(&self) -> BoxFuture<'_, Result<Credential, Error>> {
+    ///         Box::pin(async move {
+    ///             // e.g. generate an RDS authentication token, which is valid for 15 minutes
+    ///             let token = String::from("…");
+    ///
+    ///             Ok(Credential::new(token).expires_in(Duration::from_secs(15 * 60)))
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let pool = PgPoolOptions::new()
+    ///     .password_provider(Arc::new(IamToken))
+    ///     .connect("postgres://iam_user@mydb.rds.amazonaws.com/mydb")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn password_provider(mut self, provider: Arc<dyn PasswordProvider>) -> Self {
+        self.password_provider = Some(provider);
+        self
+    }
+
+    /// Set a policy that rejects acquires with [`Error::PoolOverloaded`] instead of letting them
+    /// wait for a connection, to shed load early while the pool cannot keep up rather than let
+    /// every acquire wait until it times out.
+    ///
+    /// The policy is only asked once an acquire would have to wait; see [`AdmissionPolicy`] and
+    /// the policies it is implemented by. Rejected acquires are counted in
+    /// [`PoolStats::acquires_rejected`]. Each partition of a [`PartitionedPool`] applies the
+    /// policy to its own load.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use sqlx_core::pool::RejectWhenEstimatedWaitExceeds;
+    /// use sqlx_core::postgres::PgPoolOptions;
+    /// // fail fast while acquires wait for longer than 100ms
+    /// let pool = PgPoolOptions::new()
+    ///     .max_connections(20)
+    ///     .admission_policy(Arc::new(RejectWhenEstimatedWaitExceeds(
+    ///         Duration::from_millis(100),
+    ///     )))
+    ///     .connect("postgres://localhost/mydb")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`PoolStats::acquires_rejected`]: crate::pool::PoolStats::acquires_rejected
+    /// [`PartitionedPool`]: crate::pool::PartitionedPool
+    pub fn admission_policy(mut self, policy: Arc<dyn AdmissionPolicy>) -> Self {
+        self.admission_policy = Some(policy);
+        self
+    }
+
+    /// Checks this configuration for values that would produce a pool that can never hand out
+    /// a connection, such as `max_connections(0)` or a zero `connect_timeout`.
+    ///
+    /// Every problem found is returned, not just the first. Combine with
+    /// [`ConnectOptions::validate`](crate::connection::ConnectOptions::validate) to check
+    /// the connection options as well.
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+
+        if self.max_connections == 0 {
+            errors.push(ConfigError::new("max_connections", "must be at least 1"));
+        }
+
+        if self.min_connections > self.max_connections {
+            errors.push(ConfigError::new(
+                "min_connections",
+                format!(
+                    "must not be greater than `max_connections` ({} > {})",
+                    self.min_connections, self.max_connections
+                ),
+            ));
+        }
+
+        if self.connect_timeout == Duration::from_secs(0) {
+            errors.push(ConfigError::new("connect_timeout", "must be non-zero"));
+        }
+
+        if self.max_lifetime == Some(Duration::from_secs(0)) {
+            errors.push(ConfigError::new(
+                "max_lifetime",
+                "must be non-zero; use `None` to disable",
+            ));
+        }
+
+        if self.idle_timeout == Some(Duration::from_secs(0)) {
+            errors.push(ConfigError::new(
+                "idle_timeout",
+                "must be non-zero; use `None` to disable",
+            ));
+        }
+
+        if !(0.0..1.0).contains(&self.lifetime_jitter) {
+            errors.push(ConfigError::new(
+                "lifetime_jitter",
+                format!(
+                    "must be at least 0 and less than 1, not {}",
+                    self.lifetime_jitter
+                ),
+            ));
+        }
+
+        if self.checkout_warning_after == Some(Duration::from_secs(0)) {
+            errors.push(ConfigError::new(
+                "checkout_warning_after",
+                "must be non-zero; use `None` to disable",
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Creates a new pool from this configuration and immediately establishes
+    /// [`min_connections`][Self::min_connections] connections, or one if that is zero.
+    ///
+    /// The connections are opened concurrently. If any cannot be established, fails with its
+    /// error, or with [`Error::Aggregate`] holding the error of each if more than one could
+    /// not, even if the database is refusing connections because it is still starting up; use
+    /// [`connect_lazy`][Self::connect_lazy] to start without a reachable database.
     pub async fn connect(self, uri: &str) -> Result<Pool<DB>, Error> {
         self.connect_with(uri.parse()?).await
     }
 
-    /// Creates a new pool from this configuration and immediately establishes one connection.
+    /// Creates a new pool from this configuration and immediately establishes
+    /// [`min_connections`][Self::min_connections] connections, or one if that is zero.
+    ///
+    /// See [`connect`][Self::connect].
     pub async fn connect_with(
         self,
         options: <DB::Connection as Connection>::Options,
@@ -206,50 +610,62 @@ impl<DB: Database> PoolOptions<DB> {
         Ok(Pool(shared))
     }
 
-    /// Creates a new pool from this configuration and will establish a connections as the pool
-    /// starts to be used.
+    /// Creates a new pool from this configuration without opening any connections; they are
+    /// established as the pool starts to be used.
+    ///
+    /// Only the URI is checked here, so this succeeds while the database is unreachable. Use
+    /// [`Pool::warm_up`] to open `min_connections` ahead of the first query; otherwise they are
+    /// also opened the first time the reaper runs, if there is one.
     pub fn connect_lazy(self, uri: &str) -> Result<Pool<DB>, Error> {
         Ok(self.connect_lazy_with(uri.parse()?))
     }
 
-    /// Creates a new pool from this configuration and will establish a connections as the pool
-    /// starts to be used.
+    /// Creates a new pool from this configuration without opening any connections; they are
+    /// established as the pool starts to be used.
+    ///
+    /// See [`connect_lazy`][Self::connect_lazy].
     pub fn connect_lazy_with(self, options: <DB::Connection as Connection>::Options) -> Pool<DB> {
-        let shared = SharedPool::new_arc(self, options);
-
-        let _ = spawn({
-            let shared = Arc::clone(&shared);
-            async move {
-                let _ = init_min_connections(&shared).await;
-            }
-        });
-
-        Pool(shared)
+        Pool(SharedPool::new_arc(self, options))
     }
 }
 
 async fn init_min_connections<DB: Database>(pool: &SharedPool<DB>) -> Result<(), Error> {
-    for _ in 0..cmp::max(pool.options.min_connections, 1) {
-        let deadline = Instant::now() + pool.options.connect_timeout;
-
-        // this guard will prevent us from exceeding `max_size`
-        if let Some(guard) = pool.try_increment_size() {
-            // [connect] will raise an error when past deadline
-            // [connect] returns None if its okay to retry
-            if let Some(conn) = pool.connection(deadline, guard).await? {
-                let is_ok = pool
-                    .idle_conns
-                    .push(conn.into_idle().into_leakable())
-                    .is_ok();
-
-                if !is_ok {
-                    panic!("BUG: connection queue overflow in init_min_connections");
-                }
+    let min_connections = cmp::max(pool.options.min_connections, 1);
+
+    let deadline = Instant::now() + pool.options.connect_timeout;
+
+    // these guards will prevent us from exceeding `max_size`, or opening connections the
+    // reaper already opened for `min_connections`
+    let guards: Vec<_> = iter::from_fn(|| pool.try_increment_size_below(min_connections))
+        .take(min_connections as usize)
+        .collect();
+
+    // unlike `acquire`, this does not wait out a database that is starting up
+    let jitter = pool.options.lifetime_jitter;
+
+    // with jittered lifetimes, the connections are also opened a few milliseconds apart, so
+    // that they do not hit the database all at once every time the pool starts
+    let opened = futures_util::future::join_all(guards.into_iter().zip(0..).map(
+        |(guard, index)| async move {
+            if jitter > 0.0 {
+                sqlx_rt::sleep(jitter::stagger(index)).await;
             }
+
+            pool.open(deadline, guard).await
+        },
+    ))
+    .await;
+
+    let mut errors = AggregateError::new("connections", opened.len());
+
+    for (index, result) in opened.into_iter().enumerate() {
+        match result {
+            Ok(conn) => pool.push_new(conn),
+            Err(error) => errors.push(format!("connection {}", index + 1), error),
         }
     }
 
-    Ok(())
+    errors.into_result()
 }
 
 impl<DB: Database> Debug for PoolOptions<DB> {
@@ -260,7 +676,14 @@ impl<DB: Database> Debug for PoolOptions<DB> {
             .field("connect_timeout", &self.connect_timeout)
             .field("max_lifetime", &self.max_lifetime)
             .field("idle_timeout", &self.idle_timeout)
+            .field("lifetime_jitter", &self.lifetime_jitter)
+            .field("checkout_warning_after", &self.checkout_warning_after)
             .field("test_before_acquire", &self.test_before_acquire)
+            .field("test_idle_on_shutdown", &self.test_idle_on_shutdown)
+            .field("shrink_buffers_above", &self.shrink_buffers_above)
+            .field("track_session_state", &self.track_session_state)
+            .field("query_cache_max_entries", &self.query_cache_max_entries)
+            .field("query_cache_max_size", &self.query_cache_max_size)
             .finish()
     }
 }
@@ -0,0 +1,535 @@
+use super::inner::{SharedLimit, SharedPool};
+use super::{AcquireStats, Pool, PoolConnection, PoolOptions, PoolStats, Priority};
+use crate::connection::Connection;
+use crate::database::Database;
+use crate::error::{ConfigError, Error};
+use crate::events::CloseReason;
+use crate::transaction::Transaction;
+use crate::HashMap;
+use parking_lot::Mutex;
+use sqlx_rt::{sleep, spawn, Instant};
+use std::cmp;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::hash::Hash;
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use super::MaybePoolConnection;
+
+/// A set of connection pools, one for each key, such as one database per tenant, that share a
+/// limit on the total number of connections.
+///
+/// Each partition is a [`Pool`] created from the [`PoolOptions`] of
+/// [`PartitionedPoolOptions::partition_options`] and the connection options returned by the
+/// factory for its key, the first time a connection is acquired for that key. Its
+/// `max_connections` bounds the connections of a single partition, and
+/// [`PartitionedPoolOptions::max_connections`] those of all of them together.
+///
+/// ```rust,ignore
+/// let pool = PartitionedPoolOptions::<Postgres>::new()
+///     .max_connections(50)
+///     .partition_options(PgPoolOptions::new().max_connections(10))
+///     .connect_lazy_with(|tenant: &String| {
+///         Ok(PgConnectOptions::new().database(&format!("tenant_{}", tenant)))
+///     });
+///
+/// let mut conn = pool.acquire(&tenant).await?;
+/// ```
+///
+/// ### Fairness
+///
+/// When the total limit is reached, a task that waits for a connection of a partition with
+/// fewer than its own `max_connections` has an idle connection of another partition closed for
+/// it, starting with the partition that was least recently used. While tasks wait this way, a
+/// partition that holds more than an even share of the limit closes its connections as they
+/// are returned instead of keeping them, so that a busy partition gives way to the others
+/// rather than keeping the connections it opened first.
+///
+/// ### Eviction
+///
+/// A partition that has no connections checked out and was last used longer ago than
+/// [`PartitionedPoolOptions::partition_idle_timeout`] is closed and removed; it is created anew
+/// if it is used again.
+pub struct PartitionedPool<DB: Database, K>(Arc<SharedPartitions<DB, K>>);
+
+/// Configuration for a [`PartitionedPool`].
+pub struct PartitionedPoolOptions<DB: Database> {
+    partition: PoolOptions<DB>,
+    max_connections: u32,
+    partition_idle_timeout: Option<Duration>,
+}
+
+type Factory<DB, K> = Box<
+    dyn Fn(&K) -> Result<<<DB as Database>::Connection as Connection>::Options, Error>
+        + Send
+        + Sync
+        + 'static,
+>;
+
+struct SharedPartitions<DB: Database, K> {
+    options: PartitionedPoolOptions<DB>,
+    factory: Factory<DB, K>,
+    limit: Arc<SharedLimit>,
+    partitions: Mutex<HashMap<K, Partition<DB>>>,
+    next_partition: AtomicU64,
+    // the totals of the partitions that have been evicted, which still count towards
+    // `PartitionedPool::stats`
+    evicted: Mutex<PoolStats>,
+    is_closed: AtomicBool,
+}
+
+struct Partition<DB: Database> {
+    pool: Pool<DB>,
+    id: u64,
+    last_used: Instant,
+}
+
+impl<DB: Database> Default for PartitionedPoolOptions<DB> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<DB: Database> PartitionedPoolOptions<DB> {
+    pub fn new() -> Self {
+        Self {
+            partition: PoolOptions::new(),
+            max_connections: 100,
+            partition_idle_timeout: Some(Duration::from_secs(30 * 60)),
+        }
+    }
+
+    /// Set the maximum number of connections of all partitions together.
+    ///
+    /// The default is `100`.
+    pub fn max_connections(mut self, max: u32) -> Self {
+        self.max_connections = max;
+        self
+    }
+
+    /// Set the options each partition is created with, such as how many connections it keeps
+    /// open at least and at most, and for how long they may be idle.
+    ///
+    /// The default is [`PoolOptions::new`].
+    pub fn partition_options(mut self, options: PoolOptions<DB>) -> Self {
+        self.partition = options;
+        self
+    }
+
+    /// Set how long a partition may go without a connection being acquired from it before it
+    /// is closed and removed, as long as none of its connections are checked out.
+    ///
+    /// When set to `None`, partitions are only removed by [`PartitionedPool::close`]. The
+    /// default is 30 minutes.
+    pub fn partition_idle_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.partition_idle_timeout = timeout.into();
+        self
+    }
+
+    /// Checks this configuration, and the options of the partitions, for values that would
+    /// produce a pool that can never hand out a connection.
+    ///
+    /// See [`PoolOptions::validate`].
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = match self.partition.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors,
+        };
+
+        if self.max_connections == 0 {
+            errors.push(ConfigError::new("max_connections", "must be at least 1"));
+        }
+
+        if self.partition_idle_timeout == Some(Duration::from_secs(0)) {
+            errors.push(ConfigError::new(
+                "partition_idle_timeout",
+                "must be non-zero; use `None` to disable",
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Creates a partitioned pool from this configuration, with no partitions.
+    ///
+    /// `factory` returns the options to connect with for a key, and is called the first time a
+    /// connection is acquired for it, or for the first time since its partition was evicted.
+    /// It is called with the partitions locked, so it should not block. An error it returns is
+    /// returned by [`acquire`](PartitionedPool::acquire).
+    pub fn connect_lazy_with<K, F>(self, factory: F) -> PartitionedPool<DB, K>
+    where
+        K: Eq + Hash + Clone + Send + Sync + 'static,
+        F: Fn(&K) -> Result<<DB::Connection as Connection>::Options, Error> + Send + Sync + 'static,
+    {
+        let shared = Arc::new(SharedPartitions {
+            limit: Arc::new(SharedLimit::new(self.max_connections)),
+            options: self,
+            factory: Box::new(factory),
+            partitions: Mutex::new(HashMap::new()),
+            next_partition: AtomicU64::new(0),
+            evicted: Mutex::new(PoolStats::default()),
+            is_closed: AtomicBool::new(false),
+        });
+
+        let weak = Arc::downgrade(&shared);
+
+        shared
+            .limit
+            .set_evict_idle(Box::new(move |partition| match weak.upgrade() {
+                Some(shared) => shared.evict_idle(partition),
+                None => false,
+            }));
+
+        spawn_evictor(&shared);
+
+        PartitionedPool(shared)
+    }
+}
+
+impl<DB: Database, K> PartitionedPool<DB, K>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+{
+    /// Retrieves a connection from the partition of `key`, creating the partition if there is
+    /// none.
+    ///
+    /// Within the partition this is the same as [`Pool::acquire`], also waiting for a slot of
+    /// the total limit if it is reached; see [the type documentation](Self) for how those are
+    /// shared between partitions.
+    #[track_caller]
+    pub fn acquire(
+        &self,
+        key: &K,
+    ) -> impl Future<Output = Result<PoolConnection<DB>, Error>> + 'static {
+        let partition = self.0.partition(key);
+        let location = Location::caller();
+
+        async move {
+            let shared = partition?;
+
+            shared
+                .acquire(Priority::Normal)
+                .await
+                .map(|(conn, stats)| conn.attach(&shared, location, stats))
+        }
+    }
+
+    /// Attempts to retrieve an idle connection from the partition of `key`; see
+    /// [`Pool::try_acquire`].
+    ///
+    /// Returns `None` if there is no partition of `key`, which is not created, as well as if the
+    /// partition has no idle connection.
+    #[track_caller]
+    pub fn try_acquire(&self, key: &K) -> Option<PoolConnection<DB>> {
+        if self.0.is_closed.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let location = Location::caller();
+        let shared = self.0.partitions.lock().get_mut(key).map(|partition| {
+            partition.last_used = Instant::now();
+            Arc::clone(&partition.pool.0)
+        })?;
+
+        let conn = shared.try_acquire()?;
+
+        Some(conn.attach(&shared, location, AcquireStats::default()))
+    }
+
+    /// Retrieves a connection from the partition of `key` and immediately begins a new
+    /// transaction.
+    #[track_caller]
+    pub fn begin(
+        &self,
+        key: &K,
+    ) -> impl Future<Output = Result<Transaction<'static, DB>, Error>> + 'static {
+        let acquire = self.acquire(key);
+        async move { Transaction::begin(MaybePoolConnection::PoolConnection(acquire.await?)).await }
+    }
+
+    /// Closes every partition and prevents new ones from being created.
+    ///
+    /// Does not resolve until all connections are closed; see [`Pool::close`].
+    pub async fn close(&self) {
+        self.0.is_closed.store(true, Ordering::Release);
+
+        let partitions: Vec<_> = self.0.partitions.lock().drain().collect();
+
+        for (_, partition) in partitions {
+            partition.pool.close().await;
+            self.0.retire(&partition);
+        }
+    }
+
+    /// Returns `true` if [`.close()`][Self::close] has been called on the pool.
+    pub fn is_closed(&self) -> bool {
+        self.0.is_closed.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of connections currently open in all partitions, including idle
+    /// connections.
+    pub fn size(&self) -> u32 {
+        self.0.limit.size()
+    }
+
+    /// Returns the number of idle connections in all partitions.
+    pub fn num_idle(&self) -> usize {
+        self.0
+            .partitions
+            .lock()
+            .values()
+            .map(|partition| partition.pool.num_idle())
+            .sum()
+    }
+
+    /// Returns the number of partitions.
+    pub fn num_partitions(&self) -> usize {
+        self.0.partitions.lock().len()
+    }
+
+    /// Returns the keys of the partitions.
+    pub fn keys(&self) -> Vec<K> {
+        self.0.partitions.lock().keys().cloned().collect()
+    }
+
+    /// Drops every result in the query cache of every partition; see
+    /// [`Pool::invalidate_query_cache`].
+    pub fn invalidate_query_cache(&self) {
+        for partition in self.0.partitions.lock().values() {
+            partition.pool.invalidate_query_cache();
+        }
+    }
+
+    /// Totals over every connection of every partition, including those that have been
+    /// evicted; see [`Pool::stats`].
+    pub fn stats(&self) -> PoolStats {
+        let mut stats = *self.0.evicted.lock();
+
+        for partition in self.0.partitions.lock().values() {
+            stats.add(&partition.pool.stats());
+        }
+
+        stats
+    }
+
+    /// Totals over every connection of the partition of `key`, or `None` if there is no
+    /// partition for it; see [`Pool::stats`].
+    ///
+    /// The totals start over if the partition is evicted and created again.
+    pub fn partition_stats(&self, key: &K) -> Option<PoolStats> {
+        self.0
+            .partitions
+            .lock()
+            .get(key)
+            .map(|partition| partition.pool.stats())
+    }
+
+    /// Totals over every connection of each partition, for reporting them all at once.
+    pub fn stats_by_partition(&self) -> Vec<(K, PoolStats)> {
+        self.0
+            .partitions
+            .lock()
+            .iter()
+            .map(|(key, partition)| (key.clone(), partition.pool.stats()))
+            .collect()
+    }
+}
+
+impl<DB: Database, K> SharedPartitions<DB, K>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+{
+    // the pool of the partition of `key`, which is created if there is none
+    fn partition(&self, key: &K) -> Result<Arc<SharedPool<DB>>, Error> {
+        if self.is_closed.load(Ordering::Acquire) {
+            return Err(Error::PoolClosed);
+        }
+
+        let mut partitions = self.partitions.lock();
+
+        if let Some(partition) = partitions.get_mut(key) {
+            partition.last_used = Instant::now();
+
+            return Ok(Arc::clone(&partition.pool.0));
+        }
+
+        let connect_options = (self.factory)(key)?;
+        let id = self.next_partition.fetch_add(1, Ordering::Relaxed);
+
+        let pool = Pool(SharedPool::new_arc_limited(
+            self.options.partition.clone(),
+            connect_options,
+            Some(self.limit.add_partition(id)),
+        ));
+
+        if pool.0.options.min_connections > 0 {
+            let pool = pool.clone();
+
+            spawn(async move {
+                for error in pool.warm_up().await.errors {
+                    log::debug!("failed to open a connection of a new partition: {}", error);
+                }
+            });
+        }
+
+        let shared = Arc::clone(&pool.0);
+
+        partitions.insert(
+            key.clone(),
+            Partition {
+                pool,
+                id,
+                last_used: Instant::now(),
+            },
+        );
+
+        Ok(shared)
+    }
+
+    // starts closing an idle connection of the least recently used partition that has one, other
+    // than `except`, returning whether there was one
+    fn evict_idle(&self, except: u64) -> bool {
+        let partitions = self.partitions.lock();
+
+        let mut candidates: Vec<&Partition<DB>> = partitions
+            .values()
+            .filter(|partition| partition.id != except && partition.pool.num_idle() > 0)
+            .collect();
+
+        candidates.sort_by_key(|partition| partition.last_used);
+
+        let pool = match candidates.first() {
+            Some(partition) => Arc::clone(&partition.pool.0),
+            None => return false,
+        };
+
+        if !self.limit.begin_eviction() {
+            return false;
+        }
+
+        let limit = Arc::clone(&self.limit);
+
+        spawn(async move {
+            if let Some(idle) = pool.pop_idle() {
+                let _ = idle.close(CloseReason::IdleReaped).await;
+            }
+
+            limit.end_eviction();
+        });
+
+        true
+    }
+
+    // adds the totals of a partition that is removed to those of the evicted partitions
+    fn retire(&self, partition: &Partition<DB>) {
+        let mut stats = partition.pool.stats();
+        stats.checked_out = 0;
+        stats.expired_credentials = 0;
+        stats.max_connections = 0;
+        stats.min_connections = 0;
+        stats.pending_shrink = 0;
+
+        self.evicted.lock().add(&stats);
+        self.limit.remove_partition();
+    }
+
+    // removes the partitions that have been unused for longer than `partition_idle_timeout`
+    async fn evict_partitions(&self, timeout: Duration) {
+        let evicted: Vec<(K, Partition<DB>)> = {
+            let mut partitions = self.partitions.lock();
+
+            let keys: Vec<K> = partitions
+                .iter()
+                .filter(|(_, partition)| {
+                    partition.last_used.elapsed() > timeout
+                        && partition.pool.stats().checked_out == 0
+                })
+                .map(|(key, _)| key.clone())
+                .collect();
+
+            keys.into_iter()
+                .filter_map(|key| partitions.remove(&key).map(|partition| (key, partition)))
+                .collect()
+        };
+
+        for (_, partition) in evicted {
+            partition.pool.close().await;
+            self.retire(&partition);
+        }
+    }
+}
+
+/// if `partition_idle_timeout` is set, spawn a task that evicts partitions that are unused
+fn spawn_evictor<DB: Database, K>(shared: &Arc<SharedPartitions<DB, K>>)
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+{
+    let timeout = match shared.options.partition_idle_timeout {
+        Some(timeout) => timeout,
+        None => return,
+    };
+
+    // check at least once a minute, so that a long timeout is not overshot by as much again
+    let period = cmp::min(timeout, Duration::from_secs(60));
+    let weak: Weak<SharedPartitions<DB, K>> = Arc::downgrade(shared);
+
+    spawn(async move {
+        loop {
+            sleep(period).await;
+
+            let shared = match weak.upgrade() {
+                Some(shared) if !shared.is_closed.load(Ordering::Acquire) => shared,
+                _ => break,
+            };
+
+            shared.evict_partitions(timeout).await;
+        }
+    });
+}
+
+/// Returns a new [PartitionedPool] tied to the same partitions.
+impl<DB: Database, K> Clone for PartitionedPool<DB, K> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<DB: Database, K> Debug for PartitionedPool<DB, K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartitionedPool")
+            .field("size", &self.0.limit.size())
+            .field("num_partitions", &self.0.partitions.lock().len())
+            .field("is_closed", &self.0.is_closed.load(Ordering::Acquire))
+            .field("options", &self.0.options)
+            .finish()
+    }
+}
+
+impl<DB: Database> Debug for PartitionedPoolOptions<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartitionedPoolOptions")
+            .field("max_connections", &self.max_connections)
+            .field("partition_idle_timeout", &self.partition_idle_timeout)
+            .field("partition", &self.partition)
+            .finish()
+    }
+}
+
+#[test]
+#[allow(dead_code)]
+fn assert_partitioned_pool_traits() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    fn assert_clone<T: Clone>() {}
+
+    fn assert_partitioned_pool<DB: Database>() {
+        assert_send_sync::<PartitionedPool<DB, String>>();
+        assert_clone::<PartitionedPool<DB, String>>();
+    }
+}
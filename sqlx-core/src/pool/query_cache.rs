@@ -0,0 +1,274 @@
+use crate::arguments::Arguments;
+use crate::database::{Database, HasArguments};
+use crate::row::Row;
+use hashlink::lru_cache::LruCache;
+use parking_lot::Mutex;
+use sqlx_rt::Instant;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The rows of the queries of a pool that were marked with
+/// [`cached`](crate::query::Query::cached), under their SQL and bind values.
+pub(super) struct QueryCache<DB: Database> {
+    entries: Mutex<Entries<DB>>,
+    max_entries: usize,
+    max_size: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+struct Entries<DB: Database> {
+    // the least recently used entries are evicted first
+    lru: LruCache<Vec<u8>, Entry<DB>>,
+    // the bytes of all the entries, with their keys
+    size: usize,
+}
+
+struct Entry<DB: Database> {
+    rows: Arc<[DB::Row]>,
+    size: usize,
+    expires: Instant,
+}
+
+impl<DB: Database> QueryCache<DB> {
+    pub(super) fn new(max_entries: usize, max_size: usize) -> Self {
+        Self {
+            entries: Mutex::new(Entries {
+                lru: LruCache::new_unbounded(),
+                size: 0,
+            }),
+            max_entries,
+            max_size,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The key of the results of a query, or `None` if its arguments cannot make one.
+    pub(super) fn key<'q>(
+        sql: &str,
+        arguments: Option<&<DB as HasArguments<'q>>::Arguments>,
+    ) -> Option<Vec<u8>> {
+        let mut key = sql.as_bytes().to_vec();
+
+        // the SQL is never empty, and cannot hold a NUL, so the arguments are told apart
+        match arguments {
+            Some(arguments) => {
+                key.push(0);
+
+                if !arguments.write_cache_key(&mut key) {
+                    return None;
+                }
+            }
+
+            None => key.push(1),
+        }
+
+        Some(key)
+    }
+
+    /// The rows cached under `key`, counting a hit or a miss.
+    pub(super) fn get(&self, key: &[u8]) -> Option<Arc<[DB::Row]>> {
+        let mut entries = self.entries.lock();
+
+        let rows = match entries.lru.get(key) {
+            Some(entry) if entry.expires > Instant::now() => Some(Arc::clone(&entry.rows)),
+
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+
+            None => None,
+        };
+
+        let counter = if rows.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        rows
+    }
+
+    /// Caches `rows`, which hold `size` bytes, under `key` for `ttl`, evicting the least
+    /// recently used entries to stay within the bounds of the cache.
+    pub(super) fn insert(&self, key: Vec<u8>, rows: Vec<DB::Row>, size: usize, ttl: Duration) {
+        let size = size + key.len();
+
+        if self.max_entries == 0 || size > self.max_size {
+            return;
+        }
+
+        let mut entries = self.entries.lock();
+
+        entries.remove(&key);
+
+        while entries.lru.len() >= self.max_entries || entries.size + size > self.max_size {
+            match entries.lru.remove_lru() {
+                Some((_, evicted)) => entries.size -= evicted.size,
+                None => break,
+            }
+        }
+
+        entries.size += size;
+        entries.lru.insert(
+            key,
+            Entry {
+                rows: rows.into(),
+                size,
+                expires: Instant::now() + ttl,
+            },
+        );
+    }
+
+    pub(super) fn clear(&self) {
+        let mut entries = self.entries.lock();
+
+        entries.lru.clear();
+        entries.size = 0;
+    }
+
+    /// The number of lookups that were served from the cache, and of those that were not.
+    pub(super) fn hits_and_misses(&self) -> (u64, u64) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+}
+
+impl<DB: Database> Entries<DB> {
+    fn remove(&mut self, key: &[u8]) {
+        if let Some(entry) = self.lru.remove(key) {
+            self.size -= entry.size;
+        }
+    }
+}
+
+/// Collects the rows of a result as they are read, to cache them once it has been read to its
+/// end; gives up once they are too large for the cache, or cannot be kept.
+pub(super) struct Collector<DB: Database> {
+    rows: Option<Vec<DB::Row>>,
+    size: usize,
+    max_size: usize,
+}
+
+impl<DB: Database> Collector<DB> {
+    pub(super) fn new(cache: &QueryCache<DB>) -> Self {
+        Self {
+            rows: Some(Vec::new()),
+            size: 0,
+            max_size: cache.max_size,
+        }
+    }
+
+    pub(super) fn push(&mut self, row: &DB::Row) {
+        if let Some(rows) = &mut self.rows {
+            self.size += row.data_len();
+
+            match row.try_share() {
+                Some(row) if self.size <= self.max_size => rows.push(row),
+                _ => self.rows = None,
+            }
+        }
+    }
+
+    pub(super) fn finish(self, cache: &QueryCache<DB>, key: Vec<u8>, ttl: Duration) {
+        if let Some(rows) = self.rows {
+            cache.insert(key, rows, self.size, ttl);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "memory"))]
+mod tests {
+    use super::*;
+    use crate::memory::{Datum, Memory, MemoryArguments, MemoryRow};
+    use crate::HashMap;
+
+    fn row(text: &str) -> MemoryRow {
+        MemoryRow {
+            values: vec![Datum::Text(text.to_owned())],
+            columns: Arc::default(),
+            column_names: Arc::new(HashMap::new()),
+        }
+    }
+
+    fn key(sql: &str, value: i64) -> Vec<u8> {
+        let mut arguments = MemoryArguments::default();
+        arguments.add(value);
+
+        QueryCache::<Memory>::key(sql, Some(&arguments)).unwrap()
+    }
+
+    #[test]
+    fn it_keys_queries_by_sql_and_bind_values() {
+        assert_ne!(key("SELECT ?", 1), key("SELECT ?", 2));
+        assert_ne!(key("SELECT ?", 1), key("SELECT ? ", 1));
+        assert_eq!(key("SELECT ?", 1), key("SELECT ?", 1));
+
+        // without arguments, as for the simple protocol, versus with none bound
+        assert_ne!(
+            QueryCache::<Memory>::key("SELECT 1", None),
+            QueryCache::<Memory>::key("SELECT 1", Some(&MemoryArguments::default()))
+        );
+    }
+
+    #[test]
+    fn it_evicts_the_least_recently_used_entries() {
+        let cache = QueryCache::<Memory>::new(2, 1 << 20);
+        let ttl = Duration::from_secs(60);
+
+        cache.insert(key("a", 0), vec![row("a")], 1, ttl);
+        cache.insert(key("b", 0), vec![row("b")], 1, ttl);
+
+        assert!(cache.get(&key("a", 0)).is_some());
+
+        cache.insert(key("c", 0), vec![row("c")], 1, ttl);
+
+        assert!(cache.get(&key("a", 0)).is_some());
+        assert!(cache.get(&key("b", 0)).is_none());
+        assert!(cache.get(&key("c", 0)).is_some());
+        assert_eq!(cache.hits_and_misses(), (3, 1));
+
+        // larger than the cache as a whole
+        let cache = QueryCache::<Memory>::new(10, 100);
+
+        cache.insert(key("a", 0), vec![row("a")], 90, ttl);
+        assert!(cache.get(&key("a", 0)).is_none());
+
+        // evicts to make room
+        cache.insert(key("a", 0), vec![row("a")], 40, ttl);
+        cache.insert(key("b", 0), vec![row("b")], 40, ttl);
+        assert!(cache.get(&key("a", 0)).is_none());
+        assert_eq!(cache.get(&key("b", 0)).unwrap().len(), 1);
+
+        cache.clear();
+        assert!(cache.get(&key("b", 0)).is_none());
+        assert_eq!(cache.entries.lock().size, 0);
+    }
+
+    #[test]
+    fn it_gives_up_on_results_too_large_to_cache() {
+        let cache = QueryCache::<Memory>::new(10, 100);
+        let ttl = Duration::from_secs(60);
+
+        let mut collector = Collector::new(&cache);
+        collector.push(&row(&"x".repeat(50)));
+        collector.push(&row(&"x".repeat(50)));
+        collector.push(&row("x"));
+        collector.finish(&cache, key("big", 0), ttl);
+
+        assert!(cache.get(&key("big", 0)).is_none());
+
+        let mut collector = Collector::new(&cache);
+        collector.push(&row("x"));
+        collector.finish(&cache, key("small", 0), ttl);
+
+        assert!(cache.get(&key("small", 0)).is_some());
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+/// Changes to the limits of a pool that is in use, applied with
+/// [`Pool::resize`](crate::pool::Pool::resize).
+///
+/// Each setting is that of [`PoolOptions`](crate::pool::PoolOptions) of the same name; those
+/// that are not set keep their current value.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // scale up for the day, and keep a few more connections open while at it
+/// pool.resize(PoolResize::new().max_connections(50).min_connections(10))?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PoolResize {
+    pub(crate) max_connections: Option<u32>,
+    pub(crate) min_connections: Option<u32>,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) max_lifetime: Option<Option<Duration>>,
+    pub(crate) idle_timeout: Option<Option<Duration>>,
+}
+
+impl PoolResize {
+    /// Creates a resize that changes nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of connections that the pool should maintain.
+    pub fn max_connections(mut self, max: u32) -> Self {
+        self.max_connections = Some(max);
+        self
+    }
+
+    /// Set the minimum number of connections to maintain at all times.
+    pub fn min_connections(mut self, min: u32) -> Self {
+        self.min_connections = Some(min);
+        self
+    }
+
+    /// Set the amount of time [`Pool::acquire`](crate::pool::Pool::acquire) waits for a
+    /// connection; an `acquire` that is already waiting keeps the timeout it started with.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum lifetime of individual connections, or `None` for no limit.
+    pub fn max_lifetime(mut self, lifetime: impl Into<Option<Duration>>) -> Self {
+        self.max_lifetime = Some(lifetime.into());
+        self
+    }
+
+    /// Set a maximum idle duration for individual connections, or `None` for no limit.
+    pub fn idle_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.idle_timeout = Some(timeout.into());
+        self
+    }
+}
@@ -0,0 +1,221 @@
+use std::ops::{Deref, DerefMut};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::Error;
+use crate::postgres::connection::PendingOp;
+use crate::postgres::PgConnection;
+use crate::query_scalar::query_scalar;
+
+/// A session-level [advisory lock] in PostgreSQL, e.g. for electing a leader among several
+/// instances of a service.
+///
+/// Acquiring the lock returns a [`PgAdvisoryLockGuard`] that borrows the connection holding the
+/// lock, so the lock cannot outlive the session it belongs to. The lock is released when the
+/// guard is dropped.
+///
+/// ```rust,no_run
+/// # async fn f(conn: &mut sqlx_core::postgres::PgConnection) -> Result<(), sqlx_core::error::Error> {
+/// use sqlx_core::postgres::PgAdvisoryLock;
+///
+/// let lock = PgAdvisoryLock::new("my-service-leader");
+///
+/// if let Some(mut guard) = lock.try_acquire(conn).await? {
+///     // we are the leader until `guard` is dropped; it can be used as the connection
+///     guard.release_now().await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [advisory lock]: https://www.postgresql.org/docs/current/explicit-locking.html#ADVISORY-LOCKS
+#[derive(Debug, Clone)]
+pub struct PgAdvisoryLock {
+    key: PgAdvisoryLockKey,
+}
+
+/// The key of a [`PgAdvisoryLock`].
+///
+/// PostgreSQL keeps the two forms apart: a lock on `BigInt(k)` never conflicts with a lock on
+/// any `IntPair`, even if the bits are the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PgAdvisoryLockKey {
+    /// The single `bigint` form, as in `pg_advisory_lock(bigint)`.
+    BigInt(i64),
+
+    /// The two `integer` form, as in `pg_advisory_lock(integer, integer)`.
+    IntPair(i32, i32),
+}
+
+impl PgAdvisoryLock {
+    /// An advisory lock identified by a string.
+    ///
+    /// The string is hashed with SHA-256 and the first 8 bytes of the hash are used as the
+    /// [`IntPair`][PgAdvisoryLockKey::IntPair] key, so every process using the same string
+    /// gets the same lock.
+    pub fn new(key: impl AsRef<str>) -> Self {
+        let hash = Sha256::digest(key.as_ref().as_bytes());
+
+        let mut first = [0; 4];
+        let mut second = [0; 4];
+        first.copy_from_slice(&hash[..4]);
+        second.copy_from_slice(&hash[4..8]);
+
+        Self::with_key(PgAdvisoryLockKey::IntPair(
+            i32::from_le_bytes(first),
+            i32::from_le_bytes(second),
+        ))
+    }
+
+    /// An advisory lock with an explicit key.
+    pub fn with_key(key: PgAdvisoryLockKey) -> Self {
+        Self { key }
+    }
+
+    /// The key of this lock.
+    pub fn key(&self) -> &PgAdvisoryLockKey {
+        &self.key
+    }
+
+    /// Acquires the lock, waiting until it is available.
+    ///
+    /// Session-level advisory locks are re-entrant: acquiring a lock the session already holds
+    /// succeeds immediately, and the lock is released once every guard has been.
+    pub async fn acquire<'c>(
+        &self,
+        conn: &'c mut PgConnection,
+    ) -> Result<PgAdvisoryLockGuard<'_, 'c>, Error> {
+        conn.execute(&*self.sql("pg_advisory_lock")).await?;
+
+        Ok(PgAdvisoryLockGuard::new(self, conn))
+    }
+
+    /// Acquires the lock if it is available, without waiting.
+    ///
+    /// Returns `None` if another session holds the lock.
+    pub async fn try_acquire<'c>(
+        &self,
+        conn: &'c mut PgConnection,
+    ) -> Result<Option<PgAdvisoryLockGuard<'_, 'c>>, Error> {
+        let locked: bool = query_scalar(&self.sql("pg_try_advisory_lock"))
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(if locked {
+            Some(PgAdvisoryLockGuard::new(self, conn))
+        } else {
+            None
+        })
+    }
+
+    // the keys are integers, so they are written into the SQL directly; this also lets the
+    // unlock on drop be queued as a simple query
+    fn sql(&self, function: &str) -> String {
+        match self.key {
+            PgAdvisoryLockKey::BigInt(key) => format!("SELECT {}({})", function, key),
+            PgAdvisoryLockKey::IntPair(k1, k2) => format!("SELECT {}({}, {})", function, k1, k2),
+        }
+    }
+}
+
+/// A held [`PgAdvisoryLock`], which releases the lock when dropped.
+///
+/// Derefs to the [`PgConnection`] holding the lock, so the connection can be used as normal in
+/// the meantime.
+///
+/// Dropping the guard queues the unlock to run before the next command on the connection
+/// (including when the connection is returned to a pool), the same way a dropped
+/// [`Transaction`][crate::transaction::Transaction] is rolled back. Use
+/// [`release_now`][Self::release_now] to release the lock immediately and check the result.
+pub struct PgAdvisoryLockGuard<'lock, 'c> {
+    lock: &'lock PgAdvisoryLock,
+    conn: Option<&'c mut PgConnection>,
+}
+
+impl<'lock, 'c> PgAdvisoryLockGuard<'lock, 'c> {
+    fn new(lock: &'lock PgAdvisoryLock, conn: &'c mut PgConnection) -> Self {
+        Self {
+            lock,
+            conn: Some(conn),
+        }
+    }
+
+    /// Releases the lock now, returning the connection.
+    ///
+    /// If the session turns out not to hold the lock any more (e.g. it was released with SQL
+    /// behind the guard's back), a warning is logged.
+    pub async fn release_now(mut self) -> Result<&'c mut PgConnection, Error> {
+        let conn = self.conn.take().expect("BUG: guard released twice");
+
+        let released: bool = query_scalar(&self.lock.sql("pg_advisory_unlock"))
+            .fetch_one(&mut *conn)
+            .await?;
+
+        if !released {
+            log::warn!(
+                "advisory lock {:?} was not held by this session when it was released",
+                self.lock.key
+            );
+        }
+
+        Ok(conn)
+    }
+
+    /// Returns the connection without releasing the lock, which is then held until it is
+    /// released with SQL or the session ends.
+    pub fn leak(mut self) -> &'c mut PgConnection {
+        self.conn.take().expect("BUG: guard released twice")
+    }
+}
+
+impl Deref for PgAdvisoryLockGuard<'_, '_> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        self.conn.as_deref().expect("BUG: guard released twice")
+    }
+}
+
+impl DerefMut for PgAdvisoryLockGuard<'_, '_> {
+    fn deref_mut(&mut self) -> &mut PgConnection {
+        self.conn.as_deref_mut().expect("BUG: guard released twice")
+    }
+}
+
+impl Drop for PgAdvisoryLockGuard<'_, '_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            // there is no one to hand the result of a queued query to, so the check that the
+            // lock was still held happens on the server, which warns through a notice otherwise
+            let sql = match self.lock.key {
+                PgAdvisoryLockKey::BigInt(key) => format!(
+                    "DO $$ BEGIN IF NOT pg_advisory_unlock({0}) THEN \
+                     RAISE WARNING 'advisory lock ({0}) was not held by this session \
+                     when its guard was dropped'; END IF; END $$",
+                    key
+                ),
+                PgAdvisoryLockKey::IntPair(k1, k2) => format!(
+                    "DO $$ BEGIN IF NOT pg_advisory_unlock({0}, {1}) THEN \
+                     RAISE WARNING 'advisory lock ({0}, {1}) was not held by this session \
+                     when its guard was dropped'; END IF; END $$",
+                    k1, k2
+                ),
+            };
+
+            conn.queue_op(PendingOp::Query(sql.into()));
+        }
+    }
+}
+
+#[test]
+fn it_hashes_string_keys_consistently() {
+    let lock = PgAdvisoryLock::new("my-service-leader");
+
+    assert_eq!(
+        lock.key(),
+        PgAdvisoryLock::new("my-service-leader").key(),
+        "the same string must always give the same lock"
+    );
+    assert_ne!(lock.key(), PgAdvisoryLock::new("my-service-worker").key());
+    assert!(matches!(lock.key(), PgAdvisoryLockKey::IntPair(..)));
+}
@@ -1,10 +1,15 @@
+use std::fmt::{self, Display, Formatter};
 use std::ops::{Deref, DerefMut};
 
+use byteorder::{BigEndian, ByteOrder};
+
 use crate::arguments::Arguments;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{DynEncode, Encode, EncodeError, IsNull};
 use crate::error::Error;
 use crate::ext::ustr::UStr;
-use crate::postgres::{PgConnection, PgTypeInfo, Postgres};
+use crate::logger::{write_hex, write_quoted};
+use crate::postgres::type_info::PgType;
+use crate::postgres::{PgConnection, PgResultFormat, PgTypeInfo, Postgres};
 use crate::types::Type;
 
 // TODO: buf.patch(|| ...) is a poor name, can we think of a better name? Maybe `buf.lazy(||)` ?
@@ -54,9 +59,38 @@ pub struct PgArguments {
 
     // Buffer of encoded bind parameters
     pub(crate) buffer: PgArgumentBuffer,
+
+    // The first value that could not be encoded, with its index; reported before the query is sent
+    pub(crate) error: Option<(usize, EncodeError)>,
+
+    // The formats to ask for the values of the rows in; binary unless set
+    pub(crate) result_format: PgResultFormat,
 }
 
 impl PgArguments {
+    /// The maximum number of bind parameters in a single statement.
+    ///
+    /// The parameter count is sent as a 16-bit integer in the `Parse` and `Bind` messages.
+    pub const MAX_ARGUMENTS: usize = u16::MAX as usize;
+
+    pub(crate) fn check_len(len: usize) -> Result<(), Error> {
+        if len > Self::MAX_ARGUMENTS {
+            return Err(Error::TooManyArguments {
+                given: len,
+                limit: Self::MAX_ARGUMENTS,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn check_encoded(&mut self) -> Result<(), Error> {
+        match self.error.take() {
+            Some((index, source)) => Err(Error::ArgumentEncode { index, source }),
+            None => Ok(()),
+        }
+    }
+
     pub(crate) fn add<'q, T>(&mut self, value: T)
     where
         T: Encode<'q, Postgres> + Type<Postgres>,
@@ -66,7 +100,37 @@ impl PgArguments {
             .push(value.produces().unwrap_or_else(T::type_info));
 
         // encode the value into our buffer
-        self.buffer.encode(value);
+        let result = self.buffer.encode(value);
+        self.finish_add(result);
+    }
+
+    /// Set the formats to ask for the values of the rows in; see
+    /// [`Query::result_format`](crate::query::Query::result_format).
+    pub fn set_result_format(&mut self, format: PgResultFormat) {
+        self.result_format = format;
+    }
+
+    /// Add a value, declaring it to Postgres as `ty` instead of the type implied by `T`.
+    ///
+    /// The declared type is sent with the statement when it is prepared, overriding what
+    /// Postgres would otherwise infer for the parameter. `value` must be encoded in the binary
+    /// format of `ty`; if it is not, Postgres will reject the query with an error.
+    pub fn add_with_type<'q, T>(&mut self, value: T, ty: PgTypeInfo)
+    where
+        T: Encode<'q, Postgres>,
+    {
+        self.types.push(ty);
+
+        let result = self.buffer.encode(value);
+        self.finish_add(result);
+    }
+
+    fn finish_add(&mut self, result: Result<(), EncodeError>) {
+        if let Err(error) = result {
+            if self.error.is_none() {
+                self.error = Some((self.buffer.count, error));
+            }
+        }
 
         // increment the number of arguments we are tracking
         self.buffer.count += 1;
@@ -98,6 +162,12 @@ impl PgArguments {
             buffer[*offset..(*offset + 4)].copy_from_slice(&oid.to_be_bytes());
         }
 
+        if !type_holes.is_empty() {
+            // a lookup stops reading at its row, so wait for the rest of its responses
+            // before the query is sent
+            conn.wait_until_ready().await?;
+        }
+
         Ok(())
     }
 }
@@ -116,10 +186,35 @@ impl<'q> Arguments<'q> for PgArguments {
     {
         self.add(value)
     }
+
+    fn add_dyn(&mut self, value: Box<dyn DynEncode<'q, Self::Database> + 'q>) {
+        let ty = value.type_info();
+        self.add_with_type(value, ty)
+    }
+
+    fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    fn write_cache_key(&self, key: &mut Vec<u8>) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+
+        // each value carries its length, so only the names of the types need a terminator
+        for ty in &self.types {
+            key.extend_from_slice(ty.name().as_bytes());
+            key.push(0);
+        }
+
+        key.extend_from_slice(&self.buffer);
+
+        true
+    }
 }
 
 impl PgArgumentBuffer {
-    pub(crate) fn encode<'q, T>(&mut self, value: T)
+    pub(crate) fn encode<'q, T>(&mut self, value: T) -> Result<(), EncodeError>
     where
         T: Encode<'q, Postgres>,
     {
@@ -128,17 +223,55 @@ impl PgArgumentBuffer {
         self.extend(&[0; 4]);
 
         // encode the value into our buffer
-        let len = if let IsNull::No = value.encode(self) {
-            (self.len() - offset - 4) as i32
-        } else {
-            // Write a -1 to indicate NULL
-            // NOTE: It is illegal for [encode] to write any data
-            debug_assert_eq!(self.len(), offset + 4);
-            -1_i32
+        let len = match value.encode(self) {
+            Ok(IsNull::No) => (self.len() - offset - 4) as i32,
+
+            Ok(IsNull::Yes) => {
+                // Write a -1 to indicate NULL
+                // NOTE: It is illegal for [encode] to write any data
+                debug_assert_eq!(self.len(), offset + 4);
+                -1_i32
+            }
+
+            Err(error) => {
+                // drop whatever was written, leaving a NULL in its place so the buffer stays
+                // well-formed
+                self.buffer.truncate(offset + 4);
+                self.patches.retain(|(patch, ..)| *patch < offset);
+                self.type_holes.retain(|(hole, _)| *hole < offset);
+                self[offset..(offset + 4)].copy_from_slice(&(-1_i32).to_be_bytes());
+
+                return Err(error);
+            }
         };
 
         // write the len to the beginning of the value
         self[offset..(offset + 4)].copy_from_slice(&len.to_be_bytes());
+
+        Ok(())
+    }
+
+    // The encoded values, in bind order; `None` for NULL
+    pub(crate) fn values(&self) -> impl Iterator<Item = Option<&[u8]>> {
+        let mut buf = &self.buffer[..];
+
+        std::iter::from_fn(move || {
+            if buf.len() < 4 {
+                return None;
+            }
+
+            let len = BigEndian::read_i32(buf);
+            buf = &buf[4..];
+
+            if len < 0 {
+                return Some(None);
+            }
+
+            let (value, rest) = buf.split_at(std::cmp::min(len as usize, buf.len()));
+            buf = rest;
+
+            Some(Some(value))
+        })
     }
 
     // Adds a callback to be invoked later when we know the parameter type
@@ -163,6 +296,78 @@ impl PgArgumentBuffer {
     }
 }
 
+/// Renders an encoded bind value for the statement log.
+pub(crate) struct PgBindValue<'a> {
+    pub(crate) ty: &'a PgTypeInfo,
+    pub(crate) value: Option<&'a [u8]>,
+}
+
+impl Display for PgBindValue<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let value = match self.value {
+            Some(value) => value,
+            None => return f.write_str("NULL"),
+        };
+
+        match (&self.ty.0, value.len()) {
+            (PgType::Bool, 1) => write!(f, "{}", value[0] != 0),
+            (PgType::Int2, 2) => write!(f, "{}", BigEndian::read_i16(value)),
+            (PgType::Int4, 4) => write!(f, "{}", BigEndian::read_i32(value)),
+            (PgType::Int8, 8) => write!(f, "{}", BigEndian::read_i64(value)),
+            (PgType::Oid, 4) => write!(f, "{}", BigEndian::read_u32(value)),
+            (PgType::Float4, 4) => write!(f, "{}", BigEndian::read_f32(value)),
+            (PgType::Float8, 8) => write!(f, "{}", BigEndian::read_f64(value)),
+
+            (PgType::Uuid, 16) => {
+                for (i, byte) in value.iter().enumerate() {
+                    if matches!(i, 4 | 6 | 8 | 10) {
+                        f.write_str("-")?;
+                    }
+
+                    write!(f, "{:02x}", byte)?;
+                }
+
+                Ok(())
+            }
+
+            (PgType::Text, _)
+            | (PgType::Varchar, _)
+            | (PgType::Bpchar, _)
+            | (PgType::Name, _)
+            | (PgType::Unknown, _)
+            | (PgType::Json, _) => match std::str::from_utf8(value) {
+                Ok(s) => write_quoted(f, s),
+                Err(_) => self.fmt_raw(f, value),
+            },
+
+            // JSONB is prefixed with a version byte
+            (PgType::Jsonb, _) if value.first() == Some(&1) => {
+                match std::str::from_utf8(&value[1..]) {
+                    Ok(s) => write_quoted(f, s),
+                    Err(_) => self.fmt_raw(f, value),
+                }
+            }
+
+            (PgType::Bytea, _) => {
+                f.write_str("'\\x")?;
+                write_hex(f, value)?;
+                f.write_str("'")
+            }
+
+            _ => self.fmt_raw(f, value),
+        }
+    }
+}
+
+impl PgBindValue<'_> {
+    // the binary encoding, tagged with the type it is for
+    fn fmt_raw(&self, f: &mut Formatter<'_>, value: &[u8]) -> fmt::Result {
+        f.write_str("'\\x")?;
+        write_hex(f, value)?;
+        write!(f, "'::{}", self.ty)
+    }
+}
+
 impl Deref for PgArgumentBuffer {
     type Target = Vec<u8>;
 
@@ -178,3 +383,73 @@ impl DerefMut for PgArgumentBuffer {
         &mut self.buffer
     }
 }
+
+#[test]
+fn it_renders_bind_values() {
+    let mut arguments = PgArguments::default();
+    arguments.add(42_i32);
+    arguments.add("it's");
+    arguments.add(None::<i64>);
+    arguments.add(&b"\x00\xff"[..]);
+    arguments.add(true);
+    arguments.add_with_type(7_i64, PgTypeInfo::INTERVAL);
+
+    let rendered: Vec<String> = arguments
+        .types
+        .iter()
+        .zip(arguments.buffer.values())
+        .map(|(ty, value)| PgBindValue { ty, value }.to_string())
+        .collect();
+
+    assert_eq!(
+        rendered,
+        [
+            "42",
+            "'it''s'",
+            "NULL",
+            "'\\x00ff'",
+            "true",
+            "'\\x0000000000000007'::INTERVAL"
+        ]
+    );
+}
+
+#[test]
+fn it_records_the_first_value_that_cannot_be_encoded() {
+    struct Unencodable;
+
+    impl Type<Postgres> for Unencodable {
+        fn type_info() -> PgTypeInfo {
+            PgTypeInfo::INT4
+        }
+    }
+
+    impl Encode<'_, Postgres> for Unencodable {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+            buf.extend(&[0xff; 3]);
+            buf.patch(|buf, _| buf[0] = 0);
+
+            Err("cannot be encoded".into())
+        }
+    }
+
+    let mut arguments = PgArguments::default();
+    arguments.add(1_i32);
+    arguments.add(Unencodable);
+    arguments.add(Unencodable);
+    arguments.add(2_i32);
+
+    assert_eq!(arguments.buffer.count, 4);
+    assert!(arguments.buffer.patches.is_empty());
+
+    let values: Vec<_> = arguments.buffer.values().collect();
+    assert_eq!(
+        values,
+        [Some(&[0, 0, 0, 1][..]), None, None, Some(&[0, 0, 0, 2][..])]
+    );
+
+    assert!(matches!(
+        arguments.check_encoded(),
+        Err(Error::ArgumentEncode { index: 1, .. })
+    ));
+}
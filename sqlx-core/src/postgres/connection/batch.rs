@@ -0,0 +1,358 @@
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::common::{check_read_only, SqlDialect};
+use crate::error::Error;
+use crate::executor::Execute;
+use crate::logger::QueryLogger;
+use crate::postgres::arguments::PgBindValue;
+use crate::postgres::connection::PendingOp;
+use crate::postgres::message::{self, Bind, CommandComplete, MessageFormat, Parse};
+use crate::postgres::statement::PgStatementMetadata;
+use crate::postgres::{PgArguments, PgConnection, PgQueryResult, PgValueFormat};
+use crate::HashMap;
+
+// the most statements written before their responses are read; the server stops reading once
+// it cannot send, so an unbounded pipeline could leave both ends waiting on each other
+const MAX_UNREAD: usize = 256;
+
+/// Statements of a transaction sent to the server together, rather than one round trip each;
+/// see [`Transaction::batch`].
+///
+/// [`Transaction::batch`]: crate::transaction::Transaction::<crate::postgres::Postgres>::batch
+#[must_use = "a batch does nothing until it is executed"]
+pub struct PgBatch<'t, 'q> {
+    conn: &'t mut PgConnection,
+    statements: Vec<BatchStatement<'q>>,
+}
+
+struct BatchStatement<'q> {
+    sql: &'q str,
+    arguments: PgArguments,
+    persistent: bool,
+    metadata: Option<Arc<PgStatementMetadata>>,
+}
+
+// a statement of the batch, ready to be written
+struct Prepared<'q> {
+    sql: Cow<'q, str>,
+    arguments: PgArguments,
+    statement: u32,
+    metadata: Arc<PgStatementMetadata>,
+}
+
+impl<'t, 'q> PgBatch<'t, 'q> {
+    pub(crate) fn new(conn: &'t mut PgConnection) -> Self {
+        Self {
+            conn,
+            statements: Vec::new(),
+        }
+    }
+
+    /// Adds a statement and the values bound to it.
+    pub fn add(mut self, sql: &'q str, arguments: PgArguments) -> Self {
+        self.statements.push(BatchStatement {
+            sql,
+            arguments,
+            persistent: true,
+            metadata: None,
+        });
+
+        self
+    }
+
+    /// Adds a query built with [`query`](crate::query::query), with the values bound to it.
+    pub fn add_query<E>(mut self, mut query: E) -> Self
+    where
+        E: Execute<'q, crate::postgres::Postgres>,
+    {
+        self.statements.push(BatchStatement {
+            sql: query.sql(),
+            metadata: query.statement().map(|s| Arc::clone(&s.metadata)),
+            persistent: query.persistent(),
+            arguments: query.take_arguments().unwrap_or_default(),
+        });
+
+        self
+    }
+
+    /// The number of statements added.
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    /// Returns `true` if no statement was added.
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// Executes every statement in the order they were added, and returns the result of each.
+    ///
+    /// Each distinct statement is prepared once, before any is executed; the executions are
+    /// then pipelined, a few hundred to a round trip. The rows a statement returns, e.g. with
+    /// `RETURNING`, are counted in its result and discarded.
+    ///
+    /// Stops at the first statement that fails, with [`Error::Batch`] holding its index and
+    /// its error; the statements after it are not executed. The transaction is aborted by then,
+    /// and can only be rolled back.
+    pub async fn execute(self) -> Result<Vec<PgQueryResult>, Error> {
+        let conn = self.conn;
+        let in_flight = conn.in_flight.clone();
+
+        in_flight
+            .watch("batch", Box::pin(run_batch(conn, self.statements)))
+            .await
+    }
+}
+
+async fn run_batch(
+    conn: &mut PgConnection,
+    statements: Vec<BatchStatement<'_>>,
+) -> Result<Vec<PgQueryResult>, Error> {
+    conn.wait_until_ready().await?;
+
+    let mut to_close = Vec::new();
+    let prepared = prepare_batch(conn, statements, &mut to_close).await;
+
+    if prepared.is_ok() {
+        // closes the statements the cache evicted, before the pipeline starts
+        conn.wait_until_ready().await?;
+    }
+
+    // statements that did not fit in the cache are closed before the next command, after the
+    // batch has run
+    for statement in to_close {
+        conn.queue_op(PendingOp::CloseStatement(statement));
+    }
+
+    let prepared = prepared?;
+
+    let mut results = Vec::with_capacity(prepared.len());
+
+    for window in prepared.chunks(MAX_UNREAD) {
+        let offset = results.len();
+        let last = offset + window.len() == prepared.len();
+
+        let mut loggers = Vec::with_capacity(window.len());
+
+        for statement in window {
+            write_statement(conn, statement);
+
+            let mut logger = QueryLogger::new(&statement.sql, conn.log_settings.clone());
+
+            logger.bind_values(
+                statement
+                    .metadata
+                    .parameters
+                    .iter()
+                    .zip(statement.arguments.buffer.values())
+                    .map(|(ty, value)| PgBindValue { ty, value }),
+            );
+
+            loggers.push(logger);
+        }
+
+        // the server holds back its responses until it is told to send them, and only
+        // resynchronizes after an error once it reads `Sync`
+        if last {
+            conn.write_sync();
+        } else {
+            conn.stream.write(message::Flush);
+        }
+
+        let sent_at = Instant::now();
+        conn.stream.flush().await?;
+
+        for (index, mut logger) in loggers.into_iter().enumerate() {
+            match recv_result(conn, &mut logger).await {
+                Ok(result) => results.push(result),
+
+                Err(error) => {
+                    if !last {
+                        conn.write_sync();
+                        conn.stream.flush().await?;
+                    }
+
+                    return Err(failed_at(offset + index)(error));
+                }
+            }
+
+            if index == 0 {
+                conn.stats.record_query(sent_at.elapsed());
+            }
+        }
+    }
+
+    if !prepared.is_empty() {
+        conn.recv_ready_for_query().await?;
+    }
+
+    Ok(results)
+}
+
+// checks and prepares every statement of the batch before anything is executed, so that the
+// round trips this takes do not hold up the pipeline
+async fn prepare_batch<'q>(
+    conn: &mut PgConnection,
+    statements: Vec<BatchStatement<'q>>,
+    to_close: &mut Vec<u32>,
+) -> Result<Vec<Prepared<'q>>, Error> {
+    // each distinct statement is prepared once, unless it is bound with other types
+    let mut known: HashMap<String, (u32, Arc<PgStatementMetadata>)> = HashMap::default();
+    let mut prepared = Vec::with_capacity(statements.len());
+
+    for (index, statement) in statements.into_iter().enumerate() {
+        let BatchStatement {
+            sql,
+            mut arguments,
+            persistent,
+            metadata,
+        } = statement;
+
+        let sql = conn
+            .middleware
+            .before_execute(sql, "PostgreSQL", true, persistent)
+            .map_err(failed_at(index))?;
+
+        PgArguments::check_len(arguments.types.len()).map_err(failed_at(index))?;
+        arguments.check_encoded().map_err(failed_at(index))?;
+
+        if conn.read_only_check {
+            check_read_only(&sql, SqlDialect::Postgres).map_err(failed_at(index))?;
+        }
+
+        let reuse = known
+            .get(&*sql)
+            .filter(|(_, metadata)| conn.parameters_match(&metadata.parameters, &arguments.types))
+            .cloned();
+
+        let (statement, metadata) = match reuse {
+            Some(statement) => statement,
+
+            None => {
+                let (statement, metadata, close) = conn
+                    .get_or_prepare(&sql, &arguments.types, persistent, metadata)
+                    .await
+                    .map_err(failed_at(index))?;
+
+                if close {
+                    to_close.push(statement);
+                }
+
+                known.insert(sql.to_string(), (statement, Arc::clone(&metadata)));
+
+                (statement, metadata)
+            }
+        };
+
+        arguments
+            .apply_patches(conn, &metadata.parameters)
+            .await
+            .map_err(failed_at(index))?;
+
+        prepared.push(Prepared {
+            sql,
+            arguments,
+            statement,
+            metadata,
+        });
+    }
+
+    // statements the cache evicted to make room for later ones are parsed again, unnamed, in
+    // the pipeline
+    for statement in &mut prepared {
+        if statement.statement != 0 && !to_close.contains(&statement.statement) {
+            let cached = conn.cache_statement.peek_mut(&statement.sql);
+
+            if !matches!(cached, Some((id, _)) if *id == statement.statement) {
+                statement.statement = 0;
+            }
+        }
+    }
+
+    Ok(prepared)
+}
+
+// wraps the error of the statement at `index` of the batch
+fn failed_at(index: usize) -> impl FnOnce(Error) -> Error {
+    move |error| Error::Batch {
+        index,
+        source: Box::new(error),
+    }
+}
+
+fn write_statement(conn: &mut PgConnection, statement: &Prepared<'_>) {
+    if statement.statement == 0 {
+        // the unnamed statement is replaced by every statement parsed into it
+        let param_types: Vec<u32> = statement
+            .metadata
+            .parameters
+            .iter()
+            .map(|ty| ty.0.oid())
+            .collect();
+
+        conn.stream.write(Parse {
+            param_types: &param_types,
+            query: &statement.sql,
+            statement: 0,
+        });
+    }
+
+    conn.stream.write(Bind {
+        portal: None,
+        statement: statement.statement,
+        formats: &[PgValueFormat::Binary],
+        num_params: statement.arguments.types.len(),
+        params: &statement.arguments.buffer,
+        result_formats: statement.arguments.result_format.codes(),
+    });
+
+    conn.stream.write(message::Execute {
+        portal: None,
+        limit: 0,
+    });
+}
+
+// reads the responses to one statement of the batch, up to its `CommandComplete`
+async fn recv_result(
+    conn: &mut PgConnection,
+    logger: &mut QueryLogger<'_>,
+) -> Result<PgQueryResult, Error> {
+    loop {
+        let message = conn.stream.recv().await?;
+
+        match message.format {
+            MessageFormat::ParseComplete | MessageFormat::BindComplete => {}
+
+            MessageFormat::DataRow => {
+                logger.increment_rows();
+            }
+
+            MessageFormat::CommandComplete => {
+                let cc: CommandComplete = message.decode()?;
+
+                return Ok(PgQueryResult {
+                    rows_affected: cc.rows_affected(),
+                });
+            }
+
+            MessageFormat::EmptyQueryResponse => {
+                return Ok(PgQueryResult { rows_affected: 0 });
+            }
+
+            format => {
+                return Err(err_protocol!("batch: unexpected message: {:?}", format));
+            }
+        }
+    }
+}
+
+impl Debug for PgBatch<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgBatch")
+            .field("statements", &self.statements.len())
+            .finish()
+    }
+}
@@ -0,0 +1,58 @@
+use crate::error::Error;
+use crate::postgres::connection::tls;
+use crate::postgres::message::CancelRequest;
+use crate::postgres::{PgConnectOptions, PgConnection};
+
+/// A handle to cancel the query running on a [`PgConnection`], from another task; see
+/// [`PgConnection::cancel_token`].
+#[derive(Debug, Clone)]
+pub struct PgCancelToken {
+    process_id: u32,
+    secret_key: u32,
+}
+
+impl PgCancelToken {
+    /// Asks the server to cancel the query the connection is running, over a connection of
+    /// its own made with `options`, which should be those the connection was made with.
+    ///
+    /// The server sends no answer. If the query was cancelled, it fails with the error
+    /// `57014` (`query_canceled`); if it completed before the request arrived, the request
+    /// may cancel the next query on the connection instead.
+    pub async fn cancel(&self, options: &PgConnectOptions) -> Result<(), Error> {
+        let mut stream = tls::connect(options, options.ssl_mode).await?;
+
+        stream
+            .send(CancelRequest {
+                process_id: self.process_id,
+                secret_key: self.secret_key,
+            })
+            .await?;
+
+        // the server closes the connection once it has read the request
+        let _ = stream.peek(1).await;
+
+        Ok(())
+    }
+}
+
+impl PgConnection {
+    /// Returns a token to cancel the query running on this connection from elsewhere, e.g.
+    /// another task, while this one waits for it.
+    ///
+    /// Fails if the server sent no key to cancel with while connecting, as some servers that
+    /// are not PostgreSQL but speak its protocol do not; see
+    /// [`PgConnectOptions::strict_protocol`].
+    pub fn cancel_token(&self) -> Result<PgCancelToken, Error> {
+        match self.secret_key {
+            Some(secret_key) => Ok(PgCancelToken {
+                process_id: self.process_id,
+                secret_key,
+            }),
+
+            None => Err(err_protocol!(
+                "the server sent no BackendKeyData while connecting, \
+                 so queries on this connection cannot be cancelled"
+            )),
+        }
+    }
+}
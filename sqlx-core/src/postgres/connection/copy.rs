@@ -0,0 +1,263 @@
+use std::time::Instant;
+
+use sqlx_rt::{AsyncWrite, AsyncWriteExt};
+
+use crate::common::{check_read_only, SqlDialect};
+use crate::error::Error;
+use crate::logger::QueryLogger;
+use crate::postgres::message::{CommandComplete, MessageFormat, Query};
+use crate::postgres::PgConnection;
+
+/// Options for the CSV written by [`PgConnection::copy_out_csv`].
+///
+/// These are rendered into the options of the `COPY` command, with the strings quoted as
+/// literals; the server rejects combinations that would make the output ambiguous, such as a
+/// NULL string containing the delimiter.
+#[derive(Debug, Clone)]
+pub struct PgCopyCsvOptions {
+    header: bool,
+    delimiter: char,
+    null: String,
+}
+
+impl Default for PgCopyCsvOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PgCopyCsvOptions {
+    /// Comma-separated values without a header row, with NULL written as an empty unquoted
+    /// string, as the server does by default.
+    pub fn new() -> Self {
+        Self {
+            header: false,
+            delimiter: ',',
+            null: String::new(),
+        }
+    }
+
+    /// Sets whether the first line holds the names of the columns.
+    ///
+    /// The default is `false`.
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the character separating the values of each row, which must be a single-byte
+    /// character (i.e. ASCII).
+    ///
+    /// The default is `,`.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets the string written for NULL values.
+    ///
+    /// The default is an empty unquoted string, while an empty string value is written as
+    /// `""`.
+    pub fn null(mut self, null: &str) -> Self {
+        self.null = null.to_owned();
+        self
+    }
+
+    // the options clause of the `COPY` command
+    fn to_sql(&self) -> Result<String, Error> {
+        if !self.delimiter.is_ascii() {
+            return Err(Error::encode(format!(
+                "COPY delimiter must be a single-byte character: {:?}",
+                self.delimiter
+            )));
+        }
+
+        Ok(format!(
+            "(FORMAT csv, HEADER {}, DELIMITER {}, NULL {})",
+            self.header,
+            quote_literal(&self.delimiter.to_string())?,
+            quote_literal(&self.null)?
+        ))
+    }
+}
+
+// quotes `value` as an escape string literal, which is read the same whatever the setting of
+// `standard_conforming_strings`
+fn quote_literal(value: &str) -> Result<String, Error> {
+    if value.contains('\0') {
+        return Err(Error::encode(format!(
+            "COPY option must not contain a NUL byte: {:?}",
+            value
+        )));
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 3);
+
+    quoted.push_str("E'");
+
+    for c in value.chars() {
+        if c == '\'' || c == '\\' {
+            quoted.push(c);
+        }
+
+        quoted.push(c);
+    }
+
+    quoted.push('\'');
+
+    Ok(quoted)
+}
+
+impl PgConnection {
+    /// Writes the rows of `source` to `writer` as CSV with `COPY ... TO STDOUT`, returning the
+    /// number of rows written.
+    ///
+    /// `source` is written into the command as is: the name of a table, optionally
+    /// schema-qualified and followed by a list of columns, or a query in parentheses. Names
+    /// that do not come from the program itself should be quoted with
+    /// [`quote_identifier`](crate::postgres::quote_identifier).
+    ///
+    /// ```rust,ignore
+    /// let options = PgCopyCsvOptions::new().header(true);
+    ///
+    /// let mut file = File::create("tweets.csv").await?;
+    /// let rows = conn.copy_out_csv("tweet (id, text)", &mut file, &options).await?;
+    ///
+    /// let mut file = File::create("owners.csv").await?;
+    /// let rows = conn
+    ///     .copy_out_csv("(SELECT DISTINCT owner_id FROM tweet)", &mut file, &options)
+    ///     .await?;
+    /// ```
+    ///
+    /// Each row is written before the next is read from the server, so a slow writer slows
+    /// down the server rather than the output piling up in memory. The writer receives one
+    /// small write per row; it should be buffered, e.g. with a `BufWriter`, if that is costly.
+    /// It is flushed at the end.
+    ///
+    /// If the server or the writer fails part way through, the rest of the output is read
+    /// and discarded so that the connection can be used again; what was written up to then
+    /// is left to the writer.
+    pub async fn copy_out_csv<W>(
+        &mut self,
+        source: &str,
+        writer: &mut W,
+        options: &PgCopyCsvOptions,
+    ) -> Result<u64, Error>
+    where
+        W: AsyncWrite + Unpin + Send + ?Sized,
+    {
+        let in_flight = self.in_flight.clone();
+
+        in_flight
+            .watch(
+                "copy_out_csv",
+                Box::pin(self.run_copy_out(source, writer, options)),
+            )
+            .await
+    }
+
+    async fn run_copy_out<W>(
+        &mut self,
+        source: &str,
+        writer: &mut W,
+        options: &PgCopyCsvOptions,
+    ) -> Result<u64, Error>
+    where
+        W: AsyncWrite + Unpin + Send + ?Sized,
+    {
+        if self.read_only_check {
+            // a table is only read from, but a query must be read-only itself
+            let source = source.trim();
+
+            if let Some(query) = source.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+                check_read_only(query, SqlDialect::Postgres)?;
+            }
+        }
+
+        let sql = format!("COPY {} TO STDOUT {}", source, options.to_sql()?);
+        let mut logger = QueryLogger::new(&sql, self.log_settings.clone());
+
+        self.wait_until_ready().await?;
+
+        self.stream.write(Query(&sql));
+        self.pending_ready_for_query_count += 1;
+
+        let sent_at = Instant::now();
+        self.stream.flush().await?;
+
+        // an error from the server is followed by `ReadyForQuery`, which the next command waits
+        // for along with anything else that was left unread
+        let message = self.stream.recv().await;
+        self.stats.record_query(sent_at.elapsed());
+
+        let message = message?;
+
+        if message.format != MessageFormat::CopyOutResponse {
+            return Err(err_protocol!(
+                "expecting CopyOutResponse but received {:?}",
+                message.format
+            ));
+        }
+
+        let rows = loop {
+            let message = self.stream.recv().await?;
+
+            match message.format {
+                MessageFormat::CopyData => {
+                    logger.increment_rows();
+
+                    if let Err(error) = writer.write_all(&message.contents).await {
+                        // the server goes on until it's done; should this fail, so will the
+                        // next command on the connection
+                        let _ = self.wait_until_ready().await;
+
+                        return Err(error.into());
+                    }
+                }
+
+                MessageFormat::CopyDone => {}
+
+                MessageFormat::CommandComplete => {
+                    let cc: CommandComplete = message.decode()?;
+
+                    break cc.rows_affected();
+                }
+
+                format => {
+                    return Err(err_protocol!(
+                        "expecting CopyData, CopyDone or CommandComplete but received {:?}",
+                        format
+                    ));
+                }
+            }
+        };
+
+        self.wait_until_ready().await?;
+
+        writer.flush().await?;
+
+        Ok(rows)
+    }
+}
+
+#[test]
+fn it_renders_copy_csv_options() -> Result<(), Error> {
+    assert_eq!(
+        PgCopyCsvOptions::new().to_sql()?,
+        "(FORMAT csv, HEADER false, DELIMITER E',', NULL E'')"
+    );
+
+    assert_eq!(
+        PgCopyCsvOptions::new()
+            .header(true)
+            .delimiter('\'')
+            .null(r"\N")
+            .to_sql()?,
+        r"(FORMAT csv, HEADER true, DELIMITER E'''', NULL E'\\N')"
+    );
+
+    assert!(PgCopyCsvOptions::new().delimiter('§').to_sql().is_err());
+    assert!(PgCopyCsvOptions::new().null("a\0").to_sql().is_err());
+
+    Ok(())
+}
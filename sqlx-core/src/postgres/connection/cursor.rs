@@ -0,0 +1,290 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::common::{check_read_only, SqlDialect};
+use crate::error::Error;
+use crate::executor::Execute;
+use crate::postgres::connection::PendingOp;
+use crate::postgres::message::{self, Bind, DataRow, MessageFormat, Parse, TransactionStatus};
+use crate::postgres::row::PgRowFormat;
+use crate::postgres::statement::PgStatementMetadata;
+use crate::postgres::{PgArguments, PgConnection, PgRow, PgValueFormat, Postgres};
+
+/// A query run a batch of rows at a time, on a portal of its own; see
+/// [`PgConnection::cursor`].
+///
+/// Dropping the cursor closes its portal before the next command on the connection.
+pub struct PgCursor {
+    portal: u32,
+    // the statement the portal was bound from, if it did not fit in the statement cache and has
+    // to be closed along with the portal
+    statement: Option<u32>,
+    // the backend and transaction the portal lives in
+    process_id: u32,
+    transaction: u64,
+    metadata: Arc<PgStatementMetadata>,
+    format: PgRowFormat,
+    done: bool,
+    // where the portal is queued to be closed once the cursor is dropped
+    dropped: Arc<Mutex<Vec<PendingOp>>>,
+}
+
+impl PgConnection {
+    /// Binds `query` to a named portal of the current transaction, and returns a cursor to
+    /// fetch its rows in batches with [`PgCursor::fetch_next`].
+    ///
+    /// Unlike [`fetch`](crate::executor::Executor::fetch), the connection is free for other
+    /// commands between batches, including other cursors; e.g. two sorted result sets can be
+    /// merged while they are read, without holding either in memory.
+    ///
+    /// ```rust,ignore
+    /// let mut tx = conn.begin().await?;
+    ///
+    /// let mut orders = tx.cursor(sqlx::query("SELECT * FROM orders ORDER BY id")).await?;
+    /// let mut refunds = tx.cursor(sqlx::query("SELECT * FROM refunds ORDER BY order_id")).await?;
+    ///
+    /// let orders = orders.fetch_next(&mut tx, 1000).await?;
+    /// let refunds = refunds.fetch_next(&mut tx, 1000).await?;
+    /// ```
+    ///
+    /// The server closes every portal when the transaction ends, so a cursor can only be
+    /// opened in a transaction, and fails to fetch once it has been committed or rolled back.
+    pub async fn cursor<'q, E>(&mut self, query: E) -> Result<PgCursor, Error>
+    where
+        E: Execute<'q, Postgres>,
+    {
+        let in_flight = self.in_flight.clone();
+
+        in_flight
+            .watch("cursor", Box::pin(self.open_cursor(query)))
+            .await
+    }
+
+    async fn open_cursor<'q, E>(&mut self, mut query: E) -> Result<PgCursor, Error>
+    where
+        E: Execute<'q, Postgres>,
+    {
+        let sql = query.sql();
+        let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
+        let persistent = query.persistent();
+        let mut arguments = query.take_arguments().unwrap_or_default();
+
+        PgArguments::check_len(arguments.types.len())?;
+        arguments.check_encoded()?;
+
+        if self.read_only_check {
+            check_read_only(sql, SqlDialect::Postgres)?;
+        }
+
+        self.wait_until_ready().await?;
+
+        if matches!(self.transaction_status, TransactionStatus::Idle) {
+            return Err(Error::Configuration(
+                "a cursor can only be opened in a transaction, which its portal is closed with"
+                    .into(),
+            ));
+        }
+
+        let (statement, metadata, close) = self
+            .get_or_prepare(sql, &arguments.types, persistent, metadata)
+            .await?;
+
+        let statement_to_close = if close { Some(statement) } else { None };
+
+        if let Err(error) = arguments.apply_patches(self, &metadata.parameters).await {
+            self.close_cursor(None, statement_to_close);
+            return Err(error);
+        }
+
+        if statement == 0 {
+            // as in `run`, the unnamed statement is parsed again in the same round trip
+            let param_types: Vec<u32> = metadata.parameters.iter().map(|ty| ty.0.oid()).collect();
+
+            self.stream.write(Parse {
+                param_types: &param_types,
+                query: sql,
+                statement,
+            });
+        }
+
+        let portal = self.next_portal_id;
+        self.next_portal_id = self.next_portal_id.wrapping_add(1);
+
+        self.stream.write(Bind {
+            portal: Some(portal),
+            statement,
+            formats: &[PgValueFormat::Binary],
+            num_params: arguments.types.len(),
+            params: &arguments.buffer,
+            result_formats: arguments.result_format.codes(),
+        });
+
+        // in a transaction, `Sync` leaves the portal open
+        self.write_sync();
+        self.stream.flush().await?;
+
+        if let Err(error) = self.recv_bind_complete().await {
+            self.close_cursor(Some(portal), statement_to_close);
+            return Err(error);
+        }
+
+        Ok(PgCursor {
+            portal,
+            statement: statement_to_close,
+            process_id: self.process_id,
+            transaction: self.transaction_generation,
+            metadata,
+            format: PgRowFormat::from_codes(arguments.result_format.codes()),
+            done: false,
+            dropped: Arc::clone(&self.dropped_cursors),
+        })
+    }
+
+    async fn recv_bind_complete(&mut self) -> Result<(), Error> {
+        loop {
+            let message = self.stream.recv().await?;
+
+            match message.format {
+                MessageFormat::ParseComplete | MessageFormat::BindComplete => {}
+
+                MessageFormat::ReadyForQuery => {
+                    self.handle_ready_for_query(message)?;
+                    return Ok(());
+                }
+
+                format => {
+                    return Err(err_protocol!("cursor: unexpected message: {:?}", format));
+                }
+            }
+        }
+    }
+
+    // queues the portal and statement of a cursor to be closed before the next command
+    fn close_cursor(&mut self, portal: Option<u32>, statement: Option<u32>) {
+        if let Some(portal) = portal {
+            self.queue_op(PendingOp::ClosePortal(portal));
+        }
+
+        if let Some(statement) = statement {
+            self.queue_op(PendingOp::CloseStatement(statement));
+        }
+    }
+}
+
+impl PgCursor {
+    /// Fetches up to `n` more rows from `conn`, the connection the cursor was opened on; `0`
+    /// fetches every row that is left.
+    ///
+    /// Returns fewer than `n` rows once the query has none left, and no rows after that.
+    ///
+    /// Fails if `conn` is another connection, or if the transaction the cursor was opened in
+    /// has ended since.
+    pub async fn fetch_next(
+        &mut self,
+        conn: &mut PgConnection,
+        n: u32,
+    ) -> Result<Vec<PgRow>, Error> {
+        let in_flight = conn.in_flight.clone();
+
+        in_flight
+            .watch("fetch_next", Box::pin(self.run_fetch_next(conn, n)))
+            .await
+    }
+
+    async fn run_fetch_next(
+        &mut self,
+        conn: &mut PgConnection,
+        n: u32,
+    ) -> Result<Vec<PgRow>, Error> {
+        if self.process_id != conn.process_id {
+            return Err(Error::Configuration(
+                "a cursor can only fetch from the connection it was opened on".into(),
+            ));
+        }
+
+        // a rollback queued by a dropped transaction ends it here
+        conn.wait_until_ready().await?;
+
+        if self.transaction != conn.transaction_generation {
+            return Err(Error::Configuration(
+                "the transaction the cursor was opened in has ended, closing its portal".into(),
+            ));
+        }
+
+        if self.done {
+            return Ok(Vec::new());
+        }
+
+        conn.stream.write(message::Execute {
+            portal: Some(self.portal),
+            limit: n,
+        });
+
+        conn.write_sync();
+        conn.stream.flush().await?;
+
+        let mut rows = Vec::new();
+
+        loop {
+            let message = conn.stream.recv().await?;
+
+            match message.format {
+                MessageFormat::DataRow => {
+                    let data: DataRow = message.decode()?;
+
+                    rows.push(PgRow::new(
+                        data,
+                        self.format.clone(),
+                        Arc::clone(&self.metadata),
+                        conn.column_name_matching,
+                    )?);
+                }
+
+                // the portal has more rows
+                MessageFormat::PortalSuspended => {}
+
+                MessageFormat::CommandComplete => {
+                    self.done = true;
+                }
+
+                MessageFormat::ReadyForQuery => {
+                    conn.handle_ready_for_query(message)?;
+                    break;
+                }
+
+                format => {
+                    return Err(err_protocol!(
+                        "fetch_next: unexpected message: {:?}",
+                        format
+                    ));
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+}
+
+impl Drop for PgCursor {
+    fn drop(&mut self) {
+        let mut dropped = self.dropped.lock();
+
+        // closing a portal the server already closed with its transaction does no harm
+        dropped.push(PendingOp::ClosePortal(self.portal));
+
+        if let Some(statement) = self.statement {
+            dropped.push(PendingOp::CloseStatement(statement));
+        }
+    }
+}
+
+impl Debug for PgCursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgCursor")
+            .field("portal", &self.portal)
+            .field("done", &self.done)
+            .finish()
+    }
+}
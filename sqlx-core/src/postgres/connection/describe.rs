@@ -1,76 +1,88 @@
 use crate::error::Error;
 use crate::ext::ustr::UStr;
-use crate::postgres::message::{ParameterDescription, RowDescription};
+use crate::postgres::message::{ParameterDescription, RowDescription, TransactionStatus};
 use crate::postgres::statement::PgStatementMetadata;
 use crate::postgres::type_info::{PgCustomType, PgType, PgTypeKind};
 use crate::postgres::{PgArguments, PgColumn, PgConnection, PgTypeInfo};
+use crate::query::query;
 use crate::query_as::query_as;
 use crate::query_scalar::{query_scalar, query_scalar_with};
 use crate::types::Json;
 use crate::HashMap;
 use futures_core::future::BoxFuture;
+use std::borrow::Cow;
 use std::fmt::Write;
 use std::sync::Arc;
 
-impl PgConnection {
-    pub(super) async fn handle_row_description(
-        &mut self,
-        desc: Option<RowDescription>,
-        should_fetch: bool,
-    ) -> Result<(Vec<PgColumn>, HashMap<UStr, usize>), Error> {
-        let mut columns = Vec::new();
-        let mut column_names = HashMap::new();
-
-        let desc = if let Some(desc) = desc {
-            desc
-        } else {
-            // no rows
-            return Ok((columns, column_names));
-        };
-
-        columns.reserve(desc.fields.len());
-        column_names.reserve(desc.fields.len());
-
-        for (index, field) in desc.fields.into_iter().enumerate() {
-            let name = UStr::from(field.name);
-
-            let type_info = self
-                .maybe_fetch_type_info_by_oid(field.data_type_id, should_fetch)
-                .await?;
+// the columns of a description, copied out of it, as it only lives until the next message is
+// received; a type that is not known without a query is left as a placeholder, for
+// `fetch_unknown_type` to replace
+pub(super) fn describe_columns(
+    desc: &RowDescription<'_>,
+    cache_type_info: &HashMap<u32, PgTypeInfo>,
+) -> (Vec<PgColumn>, HashMap<UStr, usize>) {
+    let mut columns = Vec::with_capacity(desc.fields.len());
+    let mut column_names = HashMap::with_capacity(desc.fields.len());
+
+    for (index, field) in desc.fields.iter().enumerate() {
+        let name = UStr::new(desc.name(field));
+
+        columns.push(PgColumn {
+            ordinal: index,
+            name: name.clone(),
+            type_info: known_type_info(field.data_type_id, cache_type_info),
+            relation_id: field.relation_id,
+            relation_attribute_no: field.relation_attribute_no,
+        });
+
+        // a duplicated name refers to its first column
+        column_names.entry(name).or_insert(index);
+    }
 
-            let column = PgColumn {
-                ordinal: index,
-                name: name.clone(),
-                type_info,
-                relation_id: field.relation_id,
-                relation_attribute_no: field.relation_attribute_no,
-            };
+    (columns, column_names)
+}
 
-            columns.push(column);
-            column_names.insert(name, index);
-        }
+// the types of the parameters of a description, as for `describe_columns`
+pub(super) fn describe_parameters(
+    desc: &ParameterDescription<'_>,
+    cache_type_info: &HashMap<u32, PgTypeInfo>,
+) -> Vec<PgTypeInfo> {
+    desc.types
+        .iter()
+        .map(|&oid| known_type_info(oid, cache_type_info))
+        .collect()
+}
 
-        Ok((columns, column_names))
-    }
+fn known_type_info(oid: u32, cache_type_info: &HashMap<u32, PgTypeInfo>) -> PgTypeInfo {
+    // first we check if this is a built-in type, and then the types looked up before
+    PgTypeInfo::try_from_oid(oid)
+        .or_else(|| cache_type_info.get(&oid).cloned())
+        .unwrap_or(PgTypeInfo(PgType::DeclareWithOid(oid)))
+}
 
-    pub(super) async fn handle_parameter_description(
-        &mut self,
-        desc: ParameterDescription,
-    ) -> Result<Vec<PgTypeInfo>, Error> {
-        let mut params = Vec::with_capacity(desc.types.len());
+// the OID of each name, qualified or along the `search_path` as in SQL; failing that, that of
+// a type of the name in any schema, e.g. of an extension installed into a schema off the path
+// language=SQL
+const RESOLVE_TYPE_NAMES: &str = "
+SELECT name, COALESCE(
+    to_regtype(name)::oid,
+    (SELECT MIN(oid) FROM pg_catalog.pg_type WHERE lower(typname) = lower(name))
+)
+FROM UNNEST($1::text[]) AS name
+";
 
-        for ty in desc.types {
-            params.push(self.maybe_fetch_type_info_by_oid(ty, true).await?);
+impl PgConnection {
+    // replaces a placeholder left by `describe_columns` or `describe_parameters` with the type
+    // it stands for, asking the database for it
+    pub(super) async fn fetch_unknown_type(&mut self, info: &mut PgTypeInfo) -> Result<(), Error> {
+        if let PgType::DeclareWithOid(oid) = info.0 {
+            *info = self.maybe_fetch_type_info_by_oid(oid).await?;
         }
 
-        Ok(params)
+        Ok(())
     }
 
-    async fn maybe_fetch_type_info_by_oid(
-        &mut self,
-        oid: u32,
-        should_fetch: bool,
-    ) -> Result<PgTypeInfo, Error> {
+    async fn maybe_fetch_type_info_by_oid(&mut self, oid: u32) -> Result<PgTypeInfo, Error> {
         // first we check if this is a built-in type
         // in the average application, the vast majority of checks should flow through this
         if let Some(info) = PgTypeInfo::try_from_oid(oid) {
@@ -83,36 +95,44 @@ impl PgConnection {
         }
 
         // fallback to asking the database directly for a type name
-        if should_fetch {
-            let info = self.fetch_type_by_oid(oid).await?;
+        let info = self.fetch_type_by_oid(oid).await?;
+
+        // cache the type name <-> oid relationship in a paired hashmap
+        // so we don't come down this road again
+        self.cache_type_info.insert(oid, info.clone());
 
-            // cache the type name <-> oid relationship in a paired hashmap
-            // so we don't come down this road again
-            self.cache_type_info.insert(oid, info.clone());
+        // a domain is cached as its base type, which is not named after it
+        if info.0.try_oid() == Some(oid) {
             self.cache_type_oid
                 .insert(info.0.name().to_string().into(), oid);
-
-            Ok(info)
-        } else {
-            // we are not in a place that *can* run a query
-            // this generally means we are in the middle of another query
-            // this _should_ only happen for complex types sent through the TEXT protocol
-            // we're open to ideas to correct this.. but it'd probably be more efficient to figure
-            // out a way to "prime" the type cache for connections rather than make this
-            // fallback work correctly for complex user-defined types for the TEXT protocol
-            Ok(PgTypeInfo(PgType::DeclareWithOid(oid)))
         }
+
+        Ok(info)
     }
 
     fn fetch_type_by_oid(&mut self, oid: u32) -> BoxFuture<'_, Result<PgTypeInfo, Error>> {
         Box::pin(async move {
-            let (name, category, relation_id, element): (String, i8, u32, u32) = query_as(
-                "SELECT typname, typcategory, typrelid, typelem FROM pg_catalog.pg_type WHERE oid = $1",
+            let (name, typ_type, category, relation_id, element, base_type): (
+                String,
+                i8,
+                i8,
+                u32,
+                u32,
+                u32,
+            ) = query_as(
+                "SELECT typname, typtype, typcategory, typrelid, typelem, typbasetype \
+                 FROM pg_catalog.pg_type WHERE oid = $1",
             )
             .bind(oid)
             .fetch_one(&mut *self)
             .await?;
 
+            // values of a domain are sent in the format of its base type, so a domain column
+            // is read as if it had the base type
+            if typ_type as u8 == b'd' {
+                return self.maybe_fetch_type_info_by_oid(base_type).await;
+            }
+
             match category as u8 {
                 b'A' => Ok(PgTypeInfo(PgType::Custom(Arc::new(PgCustomType {
                     kind: PgTypeKind::Array(self.fetch_type_by_oid(element).await?),
@@ -185,9 +205,9 @@ ORDER BY attnum
             let mut fields = Vec::new();
 
             for (field_name, field_oid) in raw_fields.into_iter() {
-                let field_type = self.maybe_fetch_type_info_by_oid(field_oid, true).await?;
+                let field_type = self.maybe_fetch_type_info_by_oid(field_oid).await?;
 
-                fields.push((field_name, field_type));
+                fields.push((UStr::from(field_name), field_type));
             }
 
             Ok(PgTypeInfo(PgType::Custom(Arc::new(PgCustomType {
@@ -215,7 +235,7 @@ WHERE rngtypid = $1
             .fetch_one(&mut *self)
             .await?;
 
-            let element = self.maybe_fetch_type_info_by_oid(element_oid, true).await?;
+            let element = self.maybe_fetch_type_info_by_oid(element_oid).await?;
 
             Ok(PgTypeInfo(PgType::Custom(Arc::new(PgCustomType {
                 kind: PgTypeKind::Range(element),
@@ -225,21 +245,76 @@ WHERE rngtypid = $1
         })
     }
 
+    /// Looks up the type named `name` if this connection has not before, and returns it.
+    ///
+    /// The first query to use a user-defined type, e.g. an enum, otherwise looks it up along the
+    /// way, which costs it a round trip or more to the server. See
+    /// [`PgPoolOptions::warm_types`](crate::pool::PoolOptions::warm_types) to do this for every
+    /// connection of a pool.
+    ///
+    /// The name may be qualified with a schema, as in `billing.status`; types of the same name
+    /// in different schemas are told apart. An unqualified name is looked up along the
+    /// `search_path` of the session at the time, and then in every schema, as for the types of
+    /// an extension installed into a schema that is not on the path. The type a name was
+    /// resolved to is kept for the life of the connection, also after the `search_path`
+    /// changes.
+    ///
+    /// Fails with [`Error::TypeNotFound`] if there is no such type.
+    pub async fn resolve_type(&mut self, name: &str) -> Result<PgTypeInfo, Error> {
+        let oid = self.fetch_type_id_by_name(name).await?;
+
+        self.maybe_fetch_type_info_by_oid(oid).await
+    }
+
+    /// Looks up every type of `names` as [`resolve_type`](Self::resolve_type) does, resolving
+    /// the names that are not yet known in a single query.
+    pub async fn resolve_types(&mut self, names: &[&str]) -> Result<(), Error> {
+        let unknown: Vec<&str> = names
+            .iter()
+            .copied()
+            .filter(|name| !self.cache_type_oid.contains_key(*name))
+            .collect();
+
+        if !unknown.is_empty() {
+            let resolved: Vec<(String, Option<u32>)> = query_as(RESOLVE_TYPE_NAMES)
+                .bind(unknown)
+                .fetch_all(&mut *self)
+                .await?;
+
+            for (name, oid) in resolved {
+                let oid = oid.ok_or_else(|| Error::TypeNotFound {
+                    type_name: name.clone(),
+                })?;
+
+                self.cache_type_oid.insert(name.into(), oid);
+            }
+        }
+
+        for name in names {
+            self.resolve_type(name).await?;
+        }
+
+        Ok(())
+    }
+
+    /// The user-defined types this connection has looked up, in no particular order.
+    ///
+    /// Built-in types are known without a lookup, and are not included.
+    pub fn cached_types(&self) -> impl Iterator<Item = &PgTypeInfo> + '_ {
+        self.cache_type_info.values()
+    }
+
     pub(crate) async fn fetch_type_id_by_name(&mut self, name: &str) -> Result<u32, Error> {
         if let Some(oid) = self.cache_type_oid.get(name) {
             return Ok(*oid);
         }
 
-        // language=SQL
-        let (oid,): (u32,) = query_as(
-            "
-SELECT oid FROM pg_catalog.pg_type WHERE typname ILIKE $1
-                ",
-        )
-        .bind(name)
-        .fetch_optional(&mut *self)
-        .await?
-        .ok_or_else(|| Error::TypeNotFound {
+        let (_, oid): (String, Option<u32>) = query_as(RESOLVE_TYPE_NAMES)
+            .bind(&[name][..])
+            .fetch_one(&mut *self)
+            .await?;
+
+        let oid = oid.ok_or_else(|| Error::TypeNotFound {
             type_name: String::from(name),
         })?;
 
@@ -290,13 +365,71 @@ SELECT oid FROM pg_catalog.pg_type WHERE typname ILIKE $1
             .fetch_all(&mut *self)
             .await?;
 
+        // `EXPLAIN EXECUTE` needs a named statement, which there is none of without prepared
+        // statements
+        if stmt_id == 0 {
+            return Ok(nullables);
+        }
+
         // patch up our null inference with data from EXPLAIN
+        //
+        // EXPLAIN can fail where the inference above does not, e.g. if we're not allowed to
+        // read from the tables involved, in which case we go without; in a transaction, it must
+        // not take the transaction down with it
+        let in_transaction = matches!(self.transaction_status, TransactionStatus::Transaction);
+
+        if in_transaction {
+            self.execute("SAVEPOINT _sqlx_explain").await?;
+        }
+
+        // with NULL for each parameter, a custom plan can fold away the parts of the query that
+        // depend on them, e.g. `WHERE id = $1` leaves nothing to scan; a generic plan keeps them
+        // (`plan_cache_mode` is new in Postgres 12)
+        let plan_cache_mode: Option<String> =
+            query_scalar("SELECT current_setting('plan_cache_mode', true)")
+                .fetch_one(&mut *self)
+                .await?;
+
+        if plan_cache_mode.is_some() {
+            self.execute("SET plan_cache_mode = force_generic_plan")
+                .await?;
+        }
+
         let nullable_patch = self
             .nullables_from_explain(stmt_id, meta.parameters.len())
+            .await;
+
+        if in_transaction {
+            self.execute(if nullable_patch.is_ok() {
+                "RELEASE SAVEPOINT _sqlx_explain"
+            } else {
+                "ROLLBACK TO SAVEPOINT _sqlx_explain"
+            })
             .await?;
+        }
+
+        if let Some(plan_cache_mode) = plan_cache_mode {
+            query("SELECT set_config('plan_cache_mode', $1, false)")
+                .bind(plan_cache_mode)
+                .execute(&mut *self)
+                .await?;
+        }
 
-        for (nullable, patch) in nullables.iter_mut().zip(nullable_patch) {
-            *nullable = patch.or(*nullable);
+        match nullable_patch {
+            Ok(nullable_patch) => {
+                for (nullable, patch) in nullables.iter_mut().zip(nullable_patch) {
+                    *nullable = patch.or(*nullable);
+                }
+            }
+
+            Err(Error::Database(error)) => {
+                log::debug!(
+                    "inferring nullability without EXPLAIN, which failed: {}",
+                    error
+                );
+            }
+
+            Err(error) => return Err(error),
         }
 
         Ok(nullables)
@@ -304,8 +437,9 @@ SELECT oid FROM pg_catalog.pg_type WHERE typname ILIKE $1
 
     /// Infer nullability for columns of this statement using EXPLAIN VERBOSE.
     ///
-    /// This currently only marks columns that are on the inner half of an outer join
-    /// and returns `None` for all others.
+    /// This marks the columns that can be NULL even where the columns they are computed from
+    /// cannot: those on the nullable side of an outer join, the results of sub-selects, and
+    /// expressions of either. It returns `None` for all others.
     async fn nullables_from_explain(
         &mut self,
         stmt_id: u32,
@@ -332,56 +466,267 @@ SELECT oid FROM pg_catalog.pg_type WHERE typname ILIKE $1
 
         let (Json([explain]),): (Json<[Explain; 1]>,) = query_as(&explain).fetch_one(self).await?;
 
-        let mut nullables = Vec::new();
+        let plan = match &explain {
+            Explain::Plan { plan } => plan,
+            Explain::Utility(_) => return Ok(Vec::new()),
+        };
 
-        if let Some(outputs) = &explain.plan.output {
-            nullables.resize(outputs.len(), None);
-            visit_plan(&explain.plan, outputs, &mut nullables);
-        }
+        let outputs = match &plan.output {
+            Some(outputs) => outputs,
+            None => return Ok(Vec::new()),
+        };
 
-        Ok(nullables)
+        let mut ctes = HashMap::new();
+        collect_ctes(plan, &mut ctes);
+
+        let nullable = nullable_outputs(plan, &ctes);
+
+        Ok(outputs
+            .iter()
+            .map(|output| {
+                // N.B. this may produce false positives but those don't cause runtime errors
+                if nullable.contains(&output.as_str()) {
+                    Some(true)
+                } else {
+                    None
+                }
+            })
+            .collect())
     }
 }
 
-fn visit_plan(plan: &Plan, outputs: &[String], nullables: &mut Vec<Option<bool>>) {
-    if let Some(plan_outputs) = &plan.output {
-        // all outputs of a Full Join must be marked nullable
-        // otherwise, all outputs of the inner half of an outer join must be marked nullable
-        if let Some("Full") | Some("Inner") = plan
-            .join_type
-            .as_deref()
-            .or(plan.parent_relation.as_deref())
-        {
-            for output in plan_outputs {
-                if let Some(i) = outputs.iter().position(|o| o == output) {
-                    // N.B. this may produce false positives but those don't cause runtime errors
-                    nullables[i] = Some(true);
+// the plans of the common table expressions in `plan`, by name
+fn collect_ctes<'a>(plan: &'a Plan, ctes: &mut HashMap<&'a str, &'a Plan>) {
+    if let Some(name) = plan
+        .subplan_name
+        .as_deref()
+        .and_then(|name| name.strip_prefix("CTE "))
+    {
+        ctes.insert(name, plan);
+    }
+
+    for plan in plan.plans.iter().flatten() {
+        collect_ctes(plan, ctes);
+    }
+}
+
+// the outputs of `plan` that can be NULL even where the columns they come from cannot
+fn nullable_outputs<'a>(plan: &'a Plan, ctes: &HashMap<&str, &'a Plan>) -> Vec<&'a str> {
+    let outputs = match &plan.output {
+        Some(outputs) => outputs,
+        None => return Vec::new(),
+    };
+
+    // the expressions that can be NULL, as the outputs of this plan would refer to them
+    let mut sources: Vec<Cow<'a, str>> = Vec::new();
+
+    // the sub-select or common table expression scanned by this plan, whose outputs are named
+    // differently in ours
+    let mut scanned = match (plan.node_type.as_str(), &plan.cte_name) {
+        ("CTE Scan", Some(name)) => ctes.get(name.as_str()).copied(),
+        _ => None,
+    };
+
+    for child in plan.plans.iter().flatten() {
+        match child.parent_relation.as_deref() {
+            Some("Subquery") => {
+                scanned = Some(child);
+                continue;
+            }
+
+            // a sub-select, which results in NULL if it returns no rows; common table
+            // expressions are picked up where they are scanned instead
+            Some("InitPlan") | Some("SubPlan") => {
+                if let Some(name) = &child.subplan_name {
+                    sources.extend(subplan_references(name).map(Cow::Owned));
                 }
+
+                continue;
             }
+
+            _ => {}
+        }
+
+        // all outputs of a Full Join can be NULL; otherwise, all outputs of the side of a
+        // Left or Right Join that may not have a matching row can be
+        let nullable_side = matches!(
+            (plan.join_type.as_deref(), child.parent_relation.as_deref()),
+            (Some("Full"), _) | (Some("Left"), Some("Inner")) | (Some("Right"), Some("Outer"))
+        );
+
+        if nullable_side {
+            sources.extend(child.output.iter().flatten().map(|o| Cow::from(o.as_str())));
+        } else {
+            sources.extend(nullable_outputs(child, ctes).into_iter().map(Cow::from));
         }
     }
 
-    if let Some(plans) = &plan.plans {
-        if let Some("Left") | Some("Right") = plan.join_type.as_deref() {
-            for plan in plans {
-                visit_plan(plan, outputs, nullables);
+    let scanned = scanned.map(|scanned| {
+        let columns = scanned.output.as_deref().unwrap_or_default();
+        let nullable = nullable_outputs(scanned, ctes);
+
+        (columns, nullable)
+    });
+
+    outputs
+        .iter()
+        .filter(|output| {
+            let from_scanned = match &scanned {
+                Some((columns, nullable)) => {
+                    refers_to_nullable_column(output, plan.alias.as_deref(), columns, nullable)
+                }
+
+                None => false,
+            };
+
+            from_scanned || sources.iter().any(|source| refers_to(output, source))
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+// how the outputs of a plan refer to the result of a sub-select, given its name, e.g.
+// `InitPlan 1 (returns $0,$1)` or `SubPlan 2`
+fn subplan_references(name: &str) -> impl Iterator<Item = String> + '_ {
+    let (name, returns) = match name.find(" (returns ") {
+        Some(i) => (&name[..i], name[i + 10..].trim_end_matches(')')),
+        None => (name, ""),
+    };
+
+    returns
+        .split(',')
+        .filter(|param| !param.is_empty())
+        .map(str::to_owned)
+        .chain(Some(format!("({})", name)))
+}
+
+// whether an output of a plan scanning a sub-select or common table expression, under `alias`,
+// refers to a column of it that can be NULL
+fn refers_to_nullable_column(
+    output: &str,
+    alias: Option<&str>,
+    columns: &[String],
+    nullable: &[&str],
+) -> bool {
+    if nullable.is_empty() {
+        return false;
+    }
+
+    let references = alias.map_or_else(Vec::new, |alias| column_references(output, alias));
+
+    // without a reference we can follow, we can only assume the worst
+    references.is_empty()
+        || references.into_iter().any(|name| {
+            let mut matching = columns
+                .iter()
+                .filter(|column| column_name(column) == Some(name))
+                .peekable();
+
+            // a column that was renamed in the sub-select cannot be told apart from the others
+            matching.peek().is_none() || matching.any(|column| nullable.contains(&&**column))
+        })
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+// whether a name starting at `i` in `expr` is not the end of another
+fn starts_name(expr: &str, i: usize) -> bool {
+    !matches!(
+        expr[..i].chars().next_back(),
+        Some(c) if is_identifier_char(c) || c == '.' || c == '"'
+    )
+}
+
+// whether `expr` refers to `source`, and not to something it is a part of
+fn refers_to(expr: &str, source: &str) -> bool {
+    expr.match_indices(source).any(|(i, _)| {
+        let after = expr[i + source.len()..].chars().next();
+
+        starts_name(expr, i) && !matches!(after, Some(c) if is_identifier_char(c))
+    })
+}
+
+// the names of the columns of `relation` which `expr` refers to
+fn column_references<'a>(expr: &'a str, relation: &str) -> Vec<&'a str> {
+    let quoted = format!("\"{}\"", relation.replace('"', "\"\""));
+    let mut names = Vec::new();
+
+    for prefix in [format!("{}.", relation), format!("{}.", quoted)].iter() {
+        for (i, _) in expr.match_indices(&**prefix) {
+            if !starts_name(expr, i) {
+                continue;
             }
+
+            let rest = &expr[i + prefix.len()..];
+
+            let len = if rest.starts_with('"') {
+                // a quoted name ends at the first quote that isn't doubled
+                let mut chars = rest.char_indices().skip(1).peekable();
+                let mut len = rest.len();
+
+                while let Some((i, c)) = chars.next() {
+                    if c == '"' {
+                        if let Some((_, '"')) = chars.peek() {
+                            chars.next();
+                        } else {
+                            len = i + 1;
+                            break;
+                        }
+                    }
+                }
+
+                len
+            } else {
+                rest.find(|c| !is_identifier_char(c)).unwrap_or(rest.len())
+            };
+
+            names.push(&rest[..len]);
         }
     }
+
+    names
+}
+
+// the name of the column an output refers to, if it is a plain column reference
+fn column_name(output: &str) -> Option<&str> {
+    if output
+        .chars()
+        .all(|c| is_identifier_char(c) || c == '.' || c == '"')
+    {
+        output.rsplit('.').next()
+    } else {
+        None
+    }
 }
 
 #[derive(serde::Deserialize)]
-struct Explain {
-    #[serde(rename = "Plan")]
-    plan: Plan,
+#[serde(untagged)]
+enum Explain {
+    Plan {
+        #[serde(rename = "Plan")]
+        plan: Plan,
+    },
+
+    // utility statements, e.g. `SHOW`, have no plan to speak of
+    Utility(serde::de::IgnoredAny),
 }
 
 #[derive(serde::Deserialize)]
 struct Plan {
+    #[serde(rename = "Node Type")]
+    node_type: String,
     #[serde(rename = "Join Type")]
     join_type: Option<String>,
     #[serde(rename = "Parent Relationship")]
     parent_relation: Option<String>,
+    #[serde(rename = "Subplan Name")]
+    subplan_name: Option<String>,
+    #[serde(rename = "CTE Name")]
+    cte_name: Option<String>,
+    #[serde(rename = "Alias")]
+    alias: Option<String>,
     #[serde(rename = "Output")]
     output: Option<Vec<String>>,
     #[serde(rename = "Plans")]
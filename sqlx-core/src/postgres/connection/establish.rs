@@ -1,23 +1,77 @@
 use crate::HashMap;
 
-use crate::common::StatementCache;
+use crate::common::{
+    default_application_name, sanitize_application_name, InFlight, StatementCache,
+};
+use crate::connection::StatsCollector;
 use crate::error::Error;
+use crate::events::ConnectionEvents;
 use crate::io::Decode;
-use crate::postgres::connection::{sasl, stream::PgStream, tls};
+use crate::postgres::connection::stream::PgStream;
+use crate::postgres::connection::{sasl, tls};
 use crate::postgres::message::{
     Authentication, BackendKeyData, MessageFormat, Password, ReadyForQuery, Startup,
 };
-use crate::postgres::{PgConnectOptions, PgConnection};
+use crate::postgres::{quote_identifier, PgConnectOptions, PgConnection, PgSslMode};
 
 // https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.3
 // https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.11
 
+// the parameters every PostgreSQL server reports while connecting that the driver relies on,
+// with the value assumed for a server that does not
+const EXPECTED_PARAMETERS: &[(&str, &str)] = &[
+    ("standard_conforming_strings", "on"),
+    ("integer_datetimes", "on"),
+    ("client_encoding", "UTF8"),
+    ("DateStyle", "ISO, MDY"),
+    ("IntervalStyle", "postgres"),
+];
+
 impl PgConnection {
     pub(crate) async fn establish(options: &PgConnectOptions) -> Result<Self, Error> {
-        let mut stream = PgStream::connect(options).await?;
+        let mut events = ConnectionEvents::new(&options.events, options.connection_info());
+
+        match Self::establish_with_fallback(options).await {
+            Ok(mut conn) => {
+                events.set_connection_id(conn.process_id.into());
+                events.connected();
+                conn.events = events;
+
+                Ok(conn)
+            }
+
+            Err(error) => {
+                events.error(&error);
+
+                Err(error)
+            }
+        }
+    }
+
+    async fn establish_with_fallback(options: &PgConnectOptions) -> Result<Self, Error> {
+        match options.ssl_mode {
+            // like libpq, try again with TLS if the server rejects the connection without it;
+            // it does so with the same error as for any other missing `pg_hba.conf` entry
+            PgSslMode::Allow => match Self::establish_with(options, PgSslMode::Disable).await {
+                Err(Error::Database(err)) if err.code().as_deref() == Some("28000") => {
+                    Self::establish_with(options, PgSslMode::Require).await
+                }
+
+                result => result,
+            },
+
+            ssl_mode => Self::establish_with(options, ssl_mode).await,
+        }
+    }
 
+    async fn establish_with(
+        options: &PgConnectOptions,
+        ssl_mode: PgSslMode,
+    ) -> Result<Self, Error> {
         // Upgrade to TLS if we were asked to and the server supports it
-        tls::maybe_upgrade(&mut stream, options).await?;
+        let mut stream = tls::connect(options, ssl_mode).await?;
+
+        let policy = options.effective_protocol_policy();
 
         // To begin a session, a frontend opens a connection to the server
         // and sends a startup message.
@@ -26,6 +80,8 @@ impl PgConnection {
             // Sets the display format for date and time values,
             // as well as the rules for interpreting ambiguous date input values.
             ("DateStyle", "ISO, MDY"),
+            // Sets the display format for intervals, which are parsed in this style only
+            ("IntervalStyle", "postgres"),
             // Sets the client-side encoding (character set).
             // <https://www.postgresql.org/docs/devel/multibyte.html#MULTIBYTE-CHARSET-SUPPORTED>
             ("client_encoding", "UTF8"),
@@ -36,10 +92,61 @@ impl PgConnection {
             ("extra_float_digits", "3"),
         ];
 
-        if let Some(ref application_name) = options.application_name {
+        // the server truncates the name to `NAMEDATALEN - 1` bytes and replaces anything that
+        // is not printable ASCII with `?`; do the same up front so the name is predictable
+        let application_name = options
+            .application_name
+            .as_deref()
+            .or_else(|| default_application_name())
+            .map(|name| sanitize_application_name(name, 63, true));
+
+        if let Some(application_name) = &application_name {
             params.push(("application_name", application_name));
         }
 
+        if options.replication {
+            params.push(("replication", "database"));
+        }
+
+        if options.read_only {
+            params.push(("default_transaction_read_only", "on"));
+        }
+
+        let search_path = match &options.search_path {
+            Some(schemas) => Some(
+                schemas
+                    .iter()
+                    .map(|schema| quote_identifier(schema))
+                    .collect::<Result<Vec<_>, Error>>()?
+                    .join(", "),
+            ),
+
+            None => None,
+        };
+
+        if let Some(search_path) = &search_path {
+            params.push(("search_path", search_path));
+        }
+
+        // user-provided parameters take precedence over the ones above
+        for (name, value) in &options.options {
+            if let Some(param) = params
+                .iter_mut()
+                .find(|(param, _)| param.eq_ignore_ascii_case(name))
+            {
+                log::debug!(
+                    "startup parameter {:?} overridden by connect options: {:?} -> {:?}",
+                    name,
+                    param.1,
+                    value
+                );
+
+                param.1 = value;
+            } else {
+                params.push((name, value));
+            }
+        }
+
         stream
             .send(Startup {
                 username: Some(&options.username),
@@ -53,8 +160,7 @@ impl PgConnection {
         // provisionally acceptable, and what additional
         // authentication is required (if any).
 
-        let mut process_id = 0;
-        let mut secret_key = 0;
+        let mut key_data = None;
         let transaction_status;
 
         loop {
@@ -67,6 +173,10 @@ impl PgConnection {
                     }
 
                     Authentication::CleartextPassword => {
+                        if !policy.allow_auth_method_downgrade {
+                            return Err(auth_method_downgrade("a cleartext password"));
+                        }
+
                         // The frontend must now send a [PasswordMessage] containing the
                         // password in clear-text form.
 
@@ -78,6 +188,10 @@ impl PgConnection {
                     }
 
                     Authentication::Md5Password(body) => {
+                        if !policy.allow_auth_method_downgrade {
+                            return Err(auth_method_downgrade("an MD5 password"));
+                        }
+
                         // The frontend must now send a [PasswordMessage] containing the
                         // password (with user name) encrypted via MD5, then encrypted again
                         // using the 4-byte random salt specified in the
@@ -96,6 +210,18 @@ impl PgConnection {
                         sasl::authenticate(&mut stream, options, body).await?;
                     }
 
+                    Authentication::KerberosV5 => {
+                        return Err(unsupported_auth_method("Kerberos V5"));
+                    }
+
+                    Authentication::Gss | Authentication::GssContinue => {
+                        return Err(unsupported_auth_method("GSSAPI"));
+                    }
+
+                    Authentication::Sspi => {
+                        return Err(unsupported_auth_method("SSPI"));
+                    }
+
                     method => {
                         return Err(err_protocol!(
                             "unsupported authentication method: {:?}",
@@ -110,8 +236,7 @@ impl PgConnection {
 
                     let data: BackendKeyData = message.decode()?;
 
-                    process_id = data.process_id;
-                    secret_key = data.secret_key;
+                    key_data = Some(data);
                 }
 
                 MessageFormat::ReadyForQuery => {
@@ -122,27 +247,111 @@ impl PgConnection {
                     break;
                 }
 
-                _ => {
+                _ if options.strict_protocol => {
                     return Err(err_protocol!(
                         "establish: unexpected message: {:?}",
                         message.format
                     ))
                 }
+
+                _ => {
+                    log::debug!(
+                        "establish: skipping unexpected message: {:?}",
+                        message.format
+                    );
+                }
             }
         }
 
+        // a server that sends no key cannot be asked to cancel a query
+        let (process_id, secret_key) = match key_data {
+            Some(data) => (data.process_id, Some(data.secret_key)),
+
+            None if options.strict_protocol => {
+                return Err(err_protocol!(
+                    "establish: the server sent no BackendKeyData"
+                ));
+            }
+
+            None => {
+                log::debug!("the server sent no BackendKeyData; queries cannot be cancelled");
+
+                (0, None)
+            }
+        };
+
+        check_reported_parameters(&mut stream, options.strict_protocol)?;
+
         Ok(PgConnection {
             stream,
             process_id,
             secret_key,
             transaction_status,
             transaction_depth: 0,
+            transaction_generation: 0,
             pending_ready_for_query_count: 0,
+            pending_ops: Vec::new(),
+            dropped_cursors: Default::default(),
+            broken: false,
+            in_flight: InFlight::default(),
             next_statement_id: 1,
-            cache_statement: StatementCache::new(options.statement_cache_capacity),
+            next_portal_id: 1,
+            cache_statement: match options.statement_cache_max_bytes {
+                Some(max_bytes) => StatementCache::with_max_bytes(max_bytes),
+                None => StatementCache::new(options.statement_cache_capacity),
+            },
             cache_type_oid: HashMap::new(),
             cache_type_info: HashMap::new(),
+            read_only_check: options.read_only && options.read_only_check,
+            column_name_matching: options.column_name_matching,
+            prefer_simple_protocol: options.prefer_simple_protocol,
+            stats: StatsCollector::new(),
             log_settings: options.log_settings.clone(),
+            explain: options.explain.clone(),
+            middleware: options.middleware.clone(),
+            events: ConnectionEvents::default(),
         })
     }
 }
+
+// fails, if strict, or logs the expected parameters the server did not report
+fn check_reported_parameters(stream: &mut PgStream, strict: bool) -> Result<(), Error> {
+    let reported = stream.reported_parameters.take().unwrap_or_default();
+
+    for (name, assumed) in EXPECTED_PARAMETERS {
+        if reported.iter().any(|reported| reported == *name) {
+            continue;
+        }
+
+        if strict {
+            return Err(err_protocol!(
+                "establish: the server did not report the parameter {:?}",
+                name
+            ));
+        }
+
+        log::debug!(
+            "the server did not report the parameter {:?}; assuming {:?}",
+            name,
+            assumed
+        );
+    }
+
+    Ok(())
+}
+
+fn unsupported_auth_method(method: &'static str) -> Error {
+    Error::UnsupportedAuthMethod {
+        method,
+        hint: "SQLx supports password, md5 and SCRAM-SHA-256 authentication; \
+               the server's pg_hba.conf decides which method is used for this user and host",
+    }
+}
+
+fn auth_method_downgrade(method: &str) -> Error {
+    Error::PolicyViolation(format!(
+        "server asked for {} instead of SCRAM-SHA-256 authentication, \
+         and auth method downgrades are not allowed",
+        method
+    ))
+}
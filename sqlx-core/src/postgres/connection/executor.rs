@@ -1,22 +1,33 @@
+use crate::common::{check_read_only, SqlDialect};
 use crate::describe::Describe;
 use crate::error::Error;
-use crate::executor::{Execute, Executor};
+use crate::executor::{Execute, Executor, FetchLimits};
+use crate::ext::ustr::UStr;
+use crate::io::Decode;
 use crate::logger::QueryLogger;
+use crate::postgres::arguments::PgBindValue;
+use crate::postgres::connection::describe::{describe_columns, describe_parameters};
+use crate::postgres::connection::explain::ExplainBinds;
+use crate::postgres::connection::PendingOp;
+use crate::postgres::interpolate::interpolate;
 use crate::postgres::message::{
-    self, Bind, Close, CommandComplete, DataRow, MessageFormat, ParameterDescription, Parse, Query,
+    self, Bind, CommandComplete, DataRow, MessageFormat, ParameterDescription, Parse, Query,
     RowDescription,
 };
+use crate::postgres::row::PgRowFormat;
 use crate::postgres::statement::PgStatementMetadata;
 use crate::postgres::type_info::PgType;
 use crate::postgres::{
-    statement::PgStatement, PgArguments, PgConnection, PgQueryResult, PgRow, PgTypeInfo,
-    PgValueFormat, Postgres,
+    statement::PgStatement, PgArguments, PgColumn, PgConnection, PgQueryResult, PgRow,
+    PgStatementCacheMode, PgTypeInfo, PgValueFormat, Postgres,
 };
+use crate::HashMap;
 use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_core::Stream;
 use futures_util::{pin_mut, TryStreamExt};
+use std::time::Instant;
 use std::{borrow::Cow, sync::Arc};
 
 async fn prepare(
@@ -24,9 +35,21 @@ async fn prepare(
     sql: &str,
     parameters: &[PgTypeInfo],
     metadata: Option<Arc<PgStatementMetadata>>,
+    // prepare a named statement, rather than the unnamed one (ID 0)
+    named: bool,
 ) -> Result<(u32, Arc<PgStatementMetadata>), Error> {
-    let id = conn.next_statement_id;
-    conn.next_statement_id = conn.next_statement_id.wrapping_add(1);
+    PgArguments::check_len(parameters.len())?;
+
+    let id = if named {
+        let id = conn.next_statement_id;
+
+        // ID 0 is the unnamed statement, so it is skipped when the sequence wraps
+        conn.next_statement_id = conn.next_statement_id.checked_add(1).unwrap_or(1);
+
+        id
+    } else {
+        0
+    };
 
     // build a list of type OIDs to send to the database in the PARSE command
     // we have not yet started the query sequence, so we are *safe* to cleanly make
@@ -62,7 +85,7 @@ async fn prepare(
     conn.stream.flush().await?;
 
     // indicates that the SQL query string is now successfully parsed and has semantic validity
-    let _ = conn
+    let _: () = conn
         .stream
         .recv_expect(MessageFormat::ParseComplete)
         .await?;
@@ -74,16 +97,20 @@ async fn prepare(
         // we already have metadata
         metadata
     } else {
-        let parameters = recv_desc_params(conn).await?;
+        let mut parameters = recv_desc_params(conn).await?;
 
-        let rows = recv_desc_rows(conn).await?;
+        let (mut columns, column_names) = recv_desc_rows(conn).await?;
 
         // each SYNC produces one READY FOR QUERY
         conn.recv_ready_for_query().await?;
 
-        let parameters = conn.handle_parameter_description(parameters).await?;
+        for parameter in &mut parameters {
+            conn.fetch_unknown_type(parameter).await?;
+        }
 
-        let (columns, column_names) = conn.handle_row_description(rows, true).await?;
+        for column in &mut columns {
+            conn.fetch_unknown_type(&mut column.type_info).await?;
+        }
 
         // ensure that if we did fetch custom data, we wait until we are fully ready before
         // continuing
@@ -99,19 +126,55 @@ async fn prepare(
     Ok((id, metadata))
 }
 
-async fn recv_desc_params(conn: &mut PgConnection) -> Result<ParameterDescription, Error> {
-    conn.stream
-        .recv_expect(MessageFormat::ParameterDescription)
-        .await
+// the formats of the rows a `RowDescription` announces
+fn row_format(desc: &RowDescription<'_>) -> PgRowFormat {
+    let formats: Vec<PgValueFormat> = desc
+        .fields
+        .iter()
+        .map(|field| match field.format {
+            1 => PgValueFormat::Binary,
+            _ => PgValueFormat::Text,
+        })
+        .collect();
+
+    match formats.split_first() {
+        Some((first, rest)) if rest.iter().any(|format| format != first) => {
+            PgRowFormat::PerColumn(formats.into())
+        }
+
+        Some((first, _)) => PgRowFormat::All(*first),
+        None => PgValueFormat::Text.into(),
+    }
 }
 
-async fn recv_desc_rows(conn: &mut PgConnection) -> Result<Option<RowDescription>, Error> {
-    let rows: Option<RowDescription> = match conn.stream.recv().await? {
+async fn recv_desc_params(conn: &mut PgConnection) -> Result<Vec<PgTypeInfo>, Error> {
+    let message = conn.stream.recv().await?;
+
+    if message.format != MessageFormat::ParameterDescription {
+        return Err(err_protocol!(
+            "expecting ParameterDescription but received {:?}",
+            message.format
+        ));
+    }
+
+    let desc = ParameterDescription::decode_with(message.contents, &mut conn.stream.arena)?;
+
+    Ok(describe_parameters(&desc, &conn.cache_type_info))
+}
+
+async fn recv_desc_rows(
+    conn: &mut PgConnection,
+) -> Result<(Vec<PgColumn>, HashMap<UStr, usize>), Error> {
+    let rows = match conn.stream.recv().await? {
         // describes the rows that will be returned when the statement is eventually executed
-        message if message.format == MessageFormat::RowDescription => Some(message.decode()?),
+        message if message.format == MessageFormat::RowDescription => {
+            let desc = RowDescription::decode_with(message.contents, &mut conn.stream.arena)?;
+
+            describe_columns(&desc, &conn.cache_type_info)
+        }
 
         // no data would be returned if this statement was executed
-        message if message.format == MessageFormat::NoData => None,
+        message if message.format == MessageFormat::NoData => Default::default(),
 
         message => {
             return Err(err_protocol!(
@@ -160,7 +223,37 @@ impl PgConnection {
         self.pending_ready_for_query_count += 1;
     }
 
-    async fn get_or_prepare<'a>(
+    pub(super) fn parameters_match(
+        &self,
+        prepared: &[PgTypeInfo],
+        declared: &[PgTypeInfo],
+    ) -> bool {
+        if prepared.len() != declared.len() {
+            // let Postgres report the wrong number of parameters
+            return true;
+        }
+
+        prepared.iter().zip(declared).all(|(prepared, declared)| {
+            let declared = match &declared.0 {
+                PgType::DeclareWithName(name) => match self.cache_type_oid.get(name) {
+                    Some(oid) => Some(*oid),
+                    // never resolved, so the statement cannot have been prepared with it
+                    None => return false,
+                },
+
+                ty => ty.try_oid(),
+            };
+
+            match (prepared.0.try_oid(), declared) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+        })
+    }
+
+    // the returned flag is `true` if the statement is named and was not cached, as there was
+    // no room for it; the caller must then close it once it is done with it
+    pub(super) async fn get_or_prepare(
         &mut self,
         sql: &str,
         parameters: &[PgTypeInfo],
@@ -169,47 +262,112 @@ impl PgConnection {
         // optional metadata that was provided by the user, this means they are reusing
         // a statement object
         metadata: Option<Arc<PgStatementMetadata>>,
-    ) -> Result<(u32, Arc<PgStatementMetadata>), Error> {
-        if let Some(statement) = self.cache_statement.get_mut(sql) {
-            return Ok((*statement).clone());
+    ) -> Result<(u32, Arc<PgStatementMetadata>, bool), Error> {
+        let mode = self.stream.statement_cache_mode;
+
+        if mode != PgStatementCacheMode::Session {
+            if let Some(metadata) = metadata {
+                // the unnamed statement is parsed again by `run`, which only needs the types
+                return Ok((0, metadata, false));
+            }
         }
 
-        let statement = prepare(self, sql, parameters, metadata).await?;
+        if let Some(statement) = self.cache_statement.get_mut(sql) {
+            let (id, metadata) = (*statement).clone();
 
-        if store_to_cache && self.cache_statement.is_enabled() {
-            if let Some((id, _)) = self.cache_statement.insert(sql, statement.clone()) {
-                self.stream.write(Close::Statement(id));
-                self.write_sync();
+            // `bind_with_type` can declare different types for the same SQL; a statement
+            // prepared with other parameter types has to be prepared again
+            if self.parameters_match(&metadata.parameters, parameters) {
+                return Ok((id, metadata, false));
+            }
+        }
 
-                self.stream.flush().await?;
+        let named = mode == PgStatementCacheMode::Session;
+        let (id, metadata) = prepare(self, sql, parameters, metadata, named).await?;
+
+        if store_to_cache
+            && mode != PgStatementCacheMode::Transaction
+            && self.cache_statement.is_enabled()
+        {
+            let size = metadata.estimated_size();
+
+            match self
+                .cache_statement
+                .insert(sql, (id, metadata.clone()), size)
+            {
+                Ok(replaced) => {
+                    // closed before the next command, saving a round trip now
+                    for (id, _) in replaced {
+                        if id != 0 {
+                            self.queue_op(PendingOp::CloseStatement(id));
+                        }
+                    }
+                }
 
-                self.wait_for_close_complete(1).await?;
-                self.recv_ready_for_query().await?;
+                // the pinned statements leave no room for it
+                Err(_) => return Ok((id, metadata, id != 0)),
             }
         }
 
-        Ok(statement)
+        Ok((id, metadata, false))
     }
 
     async fn run<'e, 'c: 'e, 'q: 'e>(
         &'c mut self,
         query: &'q str,
-        arguments: Option<PgArguments>,
+        mut arguments: Option<PgArguments>,
         limit: u8,
         persistent: bool,
         metadata_opt: Option<Arc<PgStatementMetadata>>,
+        limits: FetchLimits,
     ) -> Result<impl Stream<Item = Result<Either<PgQueryResult, PgRow>, Error>> + 'e, Error> {
         let mut logger = QueryLogger::new(query, self.log_settings.clone());
 
+        if let Some(arguments) = &mut arguments {
+            PgArguments::check_len(arguments.types.len())?;
+            arguments.check_encoded()?;
+        }
+
+        if self.read_only_check {
+            check_read_only(query, SqlDialect::Postgres)?;
+        }
+
         // before we continue, wait until we are "ready" to accept more queries
         self.wait_until_ready().await?;
 
+        // without prepared statements, the bind values are written into the SQL, which is then
+        // sent like an unprepared query
+        let mut interpolated = None;
+
+        if self.prefer_simple_protocol {
+            if let Some(mut arguments) = arguments.take() {
+                let types = arguments.types.clone();
+                arguments.apply_patches(self, &types).await?;
+
+                logger.bind_values(
+                    types
+                        .iter()
+                        .zip(arguments.buffer.values())
+                        .map(|(ty, value)| PgBindValue { ty, value }),
+                );
+
+                interpolated = Some(interpolate(
+                    query,
+                    &arguments,
+                    self.stream.standard_conforming_strings,
+                )?);
+            }
+        }
+
         let mut metadata: Arc<PgStatementMetadata>;
 
-        let format = if let Some(mut arguments) = arguments {
+        // kept to explain the statement with if it turns out to be slow
+        let mut explain_binds = None;
+
+        let mut format = if let Some(mut arguments) = arguments {
             // prepare the statement if this our first time executing it
             // always return the statement ID here
-            let (statement, metadata_) = self
+            let (statement, metadata_, close) = self
                 .get_or_prepare(query, &arguments.types, persistent, metadata_opt)
                 .await?;
 
@@ -218,14 +376,43 @@ impl PgConnection {
             // patch holes created during encoding
             arguments.apply_patches(self, &metadata.parameters).await?;
 
+            if statement == 0 {
+                // the unnamed statement does not outlive a transaction, so it is parsed again
+                // in the same round trip as the execution
+                let param_types: Vec<u32> =
+                    metadata.parameters.iter().map(|ty| ty.0.oid()).collect();
+
+                self.stream.write(Parse {
+                    param_types: &param_types,
+                    query,
+                    statement,
+                });
+            }
+
+            if self.explain.is_some() {
+                explain_binds = Some(ExplainBinds {
+                    param_types: metadata.parameters.iter().map(|ty| ty.0.oid()).collect(),
+                    count: arguments.types.len(),
+                    params: arguments.buffer.to_vec(),
+                });
+            }
+
+            logger.bind_values(
+                metadata
+                    .parameters
+                    .iter()
+                    .zip(arguments.buffer.values())
+                    .map(|(ty, value)| PgBindValue { ty, value }),
+            );
+
             // bind to attach the arguments to the statement and create a portal
             self.stream.write(Bind {
                 portal: None,
                 statement,
                 formats: &[PgValueFormat::Binary],
-                num_params: arguments.types.len() as i16,
+                num_params: arguments.types.len(),
                 params: &*arguments.buffer,
-                result_formats: &[PgValueFormat::Binary],
+                result_formats: arguments.result_format.codes(),
             });
 
             // executes the portal up to the passed limit
@@ -236,31 +423,61 @@ impl PgConnection {
             });
 
             // finally, [Sync] asks postgres to process the messages that we sent and respond with
-            // a [ReadyForQuery] message when it's completely done. Theoretically, we could send
-            // dozens of queries before a [Sync] and postgres can handle that. Execution on the server
-            // is still serial but it would reduce round-trips. Some kind of builder pattern that is
-            // termed batching might suit this.
+            // a [ReadyForQuery] message when it's completely done; a batch sends many statements
+            // before a single [Sync] instead
             self.write_sync();
 
-            // prepared statements are binary
-            PgValueFormat::Binary
+            if close {
+                // closed only after it has been executed
+                self.queue_op(PendingOp::CloseStatement(statement));
+            }
+
+            // prepared statements are binary, unless asked otherwise
+            PgRowFormat::from_codes(arguments.result_format.codes())
         } else {
             // Query will trigger a ReadyForQuery
-            self.stream.write(Query(query));
+            self.stream
+                .write(Query(interpolated.as_deref().unwrap_or(query)));
             self.pending_ready_for_query_count += 1;
 
+            // the plan of the SQL as written cannot be captured without its bind values
+            if self.explain.is_some() && interpolated.is_none() {
+                explain_binds = Some(ExplainBinds {
+                    param_types: Vec::new(),
+                    count: 0,
+                    params: Vec::new(),
+                });
+            }
+
             // metadata starts out as "nothing"
             metadata = Arc::new(PgStatementMetadata::default());
 
-            // and unprepared statements are text
-            PgValueFormat::Text
+            // and unprepared statements are text, unless e.g. fetching from a binary cursor,
+            // which the row description tells
+            PgValueFormat::Text.into()
         };
 
+        let mut sent_at = Some(Instant::now());
         self.stream.flush().await?;
 
         Ok(try_stream! {
             loop {
-                let message = self.stream.recv().await?;
+                // a row too large for the limits is not read at all
+                let message = if limits.is_unlimited() {
+                    self.stream.recv().await
+                } else {
+                    match self.stream.check_data_row(&limits).await {
+                        Ok(()) => self.stream.recv().await,
+                        Err(error) => Err(error),
+                    }
+                };
+
+                // the first response completes the round trip
+                if let Some(sent_at) = sent_at.take() {
+                    self.stats.record_query(sent_at.elapsed());
+                }
+
+                let message = message?;
 
                 match message.format {
                     MessageFormat::BindComplete
@@ -285,9 +502,15 @@ impl PgConnection {
 
                     MessageFormat::RowDescription => {
                         // indicates that a *new* set of rows are about to be returned
-                        let (columns, column_names) = self
-                            .handle_row_description(Some(message.decode()?), false)
-                            .await?;
+                        let desc =
+                            RowDescription::decode_with(message.contents, &mut self.stream.arena)?;
+
+                        format = row_format(&desc);
+
+                        // no query can be run in the middle of this one, so a user-defined
+                        // type that was not looked up before is only known by its OID
+                        let (columns, column_names) =
+                            describe_columns(&desc, &self.cache_type_info);
 
                         metadata = Arc::new(PgStatementMetadata {
                             column_names,
@@ -301,11 +524,12 @@ impl PgConnection {
 
                         // one of the set of rows returned by a SELECT, FETCH, etc query
                         let data: DataRow = message.decode()?;
-                        let row = PgRow {
+                        let row = PgRow::new(
                             data,
-                            format,
-                            metadata: Arc::clone(&metadata),
-                        };
+                            format.clone(),
+                            Arc::clone(&metadata),
+                            self.column_name_matching,
+                        )?;
 
                         r#yield!(Either::Right(row));
                     }
@@ -325,11 +549,19 @@ impl PgConnection {
                 }
             }
 
+            if let Some(binds) = explain_binds {
+                self.explain_if_slow(&mut logger, query, binds).await?;
+            }
+
             Ok(())
         })
     }
 }
 
+impl PgConnection {
+    impl_executor_methods!(Postgres, &mut self => self);
+}
+
 impl<'c> Executor<'c> for &'c mut PgConnection {
     type Database = Postgres;
 
@@ -345,17 +577,26 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
         let arguments = query.take_arguments();
         let persistent = query.persistent();
+        let limits = query.fetch_limits();
+        let sql =
+            self.middleware
+                .before_execute(sql, "PostgreSQL", arguments.is_some(), persistent);
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch_stream(
+            "fetch_many",
+            Box::pin(try_stream! {
+                let sql = sql?;
+                let s = self.run(&sql, arguments, 0, persistent, metadata, limits).await?;
+                pin_mut!(s);
+
+                while let Some(v) = s.try_next().await? {
+                    r#yield!(v);
+                }
 
-        Box::pin(try_stream! {
-            let s = self.run(sql, arguments, 0, persistent, metadata).await?;
-            pin_mut!(s);
-
-            while let Some(v) = s.try_next().await? {
-                r#yield!(v);
-            }
-
-            Ok(())
-        })
+                Ok(())
+            }),
+        )
     }
 
     fn fetch_optional<'e, 'q: 'e, E: 'q>(
@@ -370,19 +611,30 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
         let arguments = query.take_arguments();
         let persistent = query.persistent();
-
-        Box::pin(async move {
-            let s = self.run(sql, arguments, 1, persistent, metadata).await?;
-            pin_mut!(s);
-
-            while let Some(s) = s.try_next().await? {
-                if let Either::Right(r) = s {
-                    return Ok(Some(r));
+        let limits = query.fetch_limits();
+        let sql =
+            self.middleware
+                .before_execute(sql, "PostgreSQL", arguments.is_some(), persistent);
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch(
+            "fetch_optional",
+            Box::pin(async move {
+                let sql = sql?;
+                let s = self
+                    .run(&sql, arguments, 1, persistent, metadata, limits)
+                    .await?;
+                pin_mut!(s);
+
+                while let Some(s) = s.try_next().await? {
+                    if let Either::Right(r) = s {
+                        return Ok(Some(r));
+                    }
                 }
-            }
 
-            Ok(None)
-        })
+                Ok(None)
+            }),
+        )
     }
 
     fn prepare_with<'e, 'q: 'e>(
@@ -393,16 +645,32 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
     where
         'c: 'e,
     {
-        Box::pin(async move {
-            self.wait_until_ready().await?;
+        let in_flight = self.in_flight.clone();
 
-            let (_, metadata) = self.get_or_prepare(sql, parameters, true, None).await?;
+        in_flight.watch(
+            "prepare",
+            Box::pin(async move {
+                self.wait_until_ready().await?;
 
-            Ok(PgStatement {
-                sql: Cow::Borrowed(sql),
-                metadata,
-            })
-        })
+                let (id, metadata, close) = if self.prefer_simple_protocol {
+                    // parsed as the unnamed statement, only for its metadata
+                    let (id, metadata) = prepare(self, sql, parameters, None, false).await?;
+
+                    (id, metadata, false)
+                } else {
+                    self.get_or_prepare(sql, parameters, true, None).await?
+                };
+
+                if close {
+                    self.queue_op(PendingOp::CloseStatement(id));
+                }
+
+                Ok(PgStatement {
+                    sql: Cow::Borrowed(sql),
+                    metadata,
+                })
+            }),
+        )
     }
 
     fn describe<'e, 'q: 'e>(
@@ -412,18 +680,43 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
     where
         'c: 'e,
     {
-        Box::pin(async move {
-            self.wait_until_ready().await?;
+        let in_flight = self.in_flight.clone();
 
-            let (stmt_id, metadata) = self.get_or_prepare(sql, &[], true, None).await?;
+        in_flight.watch(
+            "describe",
+            Box::pin(async move {
+                self.wait_until_ready().await?;
 
-            let nullable = self.get_nullable_for_columns(stmt_id, &metadata).await?;
+                let session = self.stream.statement_cache_mode == PgStatementCacheMode::Session;
 
-            Ok(Describe {
-                columns: metadata.columns.clone(),
-                nullable,
-                parameters: Some(Either::Left(metadata.parameters.clone())),
-            })
-        })
+                let (stmt_id, metadata, close) = if self.prefer_simple_protocol {
+                    // parsed as the unnamed statement, which leaves nullability to the tables
+                    let (id, metadata) = prepare(self, sql, &[], None, false).await?;
+
+                    (id, metadata, false)
+                } else if session {
+                    self.get_or_prepare(sql, &[], true, None).await?
+                } else {
+                    // `EXPLAIN EXECUTE` needs a named statement, which is closed again right after
+                    let (id, metadata) = prepare(self, sql, &[], None, true).await?;
+
+                    (id, metadata, true)
+                };
+
+                let nullable = self.get_nullable_for_columns(stmt_id, &metadata).await;
+
+                if close {
+                    self.queue_op(PendingOp::CloseStatement(stmt_id));
+                }
+
+                let nullable = nullable?;
+
+                Ok(Describe {
+                    columns: metadata.columns.clone(),
+                    nullable,
+                    parameters: Some(Either::Left(metadata.parameters.clone())),
+                })
+            }),
+        )
     }
 }
@@ -0,0 +1,182 @@
+use crate::common::{check_read_only, leading_keyword, SqlDialect};
+use crate::error::Error;
+use crate::logger::QueryLogger;
+use crate::postgres::message::{
+    self, Bind, DataRow, MessageFormat, Parse, Query, TransactionStatus,
+};
+use crate::postgres::{PgConnection, PgValueFormat};
+
+// the statements `EXPLAIN` accepts
+const EXPLAINABLE_KEYWORDS: &[&str] = &[
+    "SELECT", "WITH", "VALUES", "TABLE", "INSERT", "UPDATE", "DELETE", "MERGE", "EXECUTE",
+    "DECLARE",
+];
+
+// the statements that are analyzed when asked to, as they do not write
+const ANALYZABLE_KEYWORDS: &[&str] = &["SELECT", "VALUES", "TABLE"];
+
+/// The bind values of a statement, kept to explain it with once it has completed.
+pub(super) struct ExplainBinds {
+    pub(super) param_types: Vec<u32>,
+    pub(super) count: usize,
+    pub(super) params: Vec<u8>,
+}
+
+impl PgConnection {
+    /// Captures the plan of the statement just completed and attaches it to its log line, if it
+    /// was slow enough and its plan was not captured lately.
+    ///
+    /// The `EXPLAIN` is sent directly rather than through the executor, so it cannot be logged
+    /// or explained itself.
+    pub(super) async fn explain_if_slow(
+        &mut self,
+        logger: &mut QueryLogger<'_>,
+        sql: &str,
+        binds: ExplainBinds,
+    ) -> Result<(), Error> {
+        let settings = match &self.explain {
+            Some(settings) => settings,
+            None => return Ok(()),
+        };
+
+        let elapsed = logger.stop();
+
+        if elapsed < settings.threshold
+            || !logger.is_logged()
+            || matches!(self.transaction_status, TransactionStatus::Error)
+        {
+            return Ok(());
+        }
+
+        let keyword = match leading_keyword(sql, SqlDialect::Postgres) {
+            Some(keyword) if is_one_of(keyword, EXPLAINABLE_KEYWORDS) => keyword,
+            _ => return Ok(()),
+        };
+
+        if !settings
+            .throttle
+            .try_capture(sql, settings.options.min_interval)
+        {
+            return Ok(());
+        }
+
+        let analyze = settings.options.analyze
+            && is_one_of(keyword, ANALYZABLE_KEYWORDS)
+            && check_read_only(sql, SqlDialect::Postgres).is_ok();
+
+        match self.explain(sql, analyze, &binds).await {
+            Ok(plan) => logger.attach_plan(plan),
+
+            Err(Error::Database(error)) => {
+                log::debug!("could not capture the plan of a slow statement: {}", error);
+            }
+
+            Err(error) => return Err(error),
+        }
+
+        Ok(())
+    }
+
+    async fn explain(
+        &mut self,
+        sql: &str,
+        analyze: bool,
+        binds: &ExplainBinds,
+    ) -> Result<String, Error> {
+        // an error, or the writes of `ANALYZE`, must not outlast the `EXPLAIN`
+        let (before, after) = if matches!(self.transaction_status, TransactionStatus::Transaction) {
+            (
+                Some("SAVEPOINT _sqlx_explain"),
+                Some("ROLLBACK TO SAVEPOINT _sqlx_explain; RELEASE SAVEPOINT _sqlx_explain"),
+            )
+        } else if analyze {
+            (Some("BEGIN"), Some("ROLLBACK"))
+        } else {
+            (None, None)
+        };
+
+        let explain = format!(
+            "EXPLAIN (FORMAT JSON{}) {}",
+            if analyze { ", ANALYZE" } else { "" },
+            sql
+        );
+
+        if let Some(before) = before {
+            self.stream.write(Query(before));
+            self.pending_ready_for_query_count += 1;
+        }
+
+        self.stream.write(Parse {
+            param_types: &binds.param_types,
+            query: &explain,
+            statement: 0,
+        });
+
+        self.stream.write(Bind {
+            portal: None,
+            statement: 0,
+            formats: &[PgValueFormat::Binary],
+            num_params: binds.count,
+            params: &binds.params,
+            result_formats: &[PgValueFormat::Text],
+        });
+
+        self.stream.write(message::Execute {
+            portal: None,
+            limit: 0,
+        });
+
+        self.write_sync();
+
+        if let Some(after) = after {
+            self.stream.write(Query(after));
+            self.pending_ready_for_query_count += 1;
+        }
+
+        self.stream.flush().await?;
+
+        let mut plan = String::new();
+        let mut result = Ok(());
+
+        // the server answers every query, so all of them are read even after an error
+        while self.pending_ready_for_query_count > 0 {
+            let message = match self.stream.recv().await {
+                Ok(message) => message,
+
+                Err(error @ Error::Database(_)) => {
+                    if result.is_ok() {
+                        result = Err(error);
+                    }
+
+                    continue;
+                }
+
+                Err(error) => return Err(error),
+            };
+
+            match message.format {
+                MessageFormat::DataRow => {
+                    let row: DataRow = message.decode()?;
+
+                    if let Some(value) = row.get(0) {
+                        plan.push_str(&String::from_utf8_lossy(value));
+                    }
+                }
+
+                MessageFormat::ReadyForQuery => {
+                    self.handle_ready_for_query(message)?;
+                }
+
+                _ => {}
+            }
+        }
+
+        result.map(|()| plan)
+    }
+}
+
+fn is_one_of(word: &str, keywords: &[&str]) -> bool {
+    keywords
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(word))
+}
@@ -1,31 +1,59 @@
 use std::fmt::{self, Debug, Formatter};
+use std::mem;
 use std::sync::Arc;
+use std::time::Instant;
 
+use crate::middleware::Middleware;
 use crate::HashMap;
 use futures_core::future::BoxFuture;
-use futures_util::{FutureExt, TryFutureExt};
+use futures_util::FutureExt;
+use parking_lot::Mutex;
 
-use crate::common::StatementCache;
-use crate::connection::{Connection, LogSettings};
+use crate::column::ColumnNameMatching;
+use crate::common::{InFlight, StatementCache};
+use crate::connection::{
+    BufferSizes, Connection, ConnectionStats, LogSettings, SessionState, StatsCollector,
+};
+use crate::database::Capabilities;
 use crate::error::Error;
-use crate::executor::Executor;
+use crate::events::{CloseReason, ConnectionEvents};
 use crate::ext::ustr::UStr;
 use crate::io::Decode;
+use crate::postgres::connection::pending::write_pending_ops;
+pub(crate) use crate::postgres::connection::pending::PendingOp;
 use crate::postgres::connection::stream::PgStream;
 use crate::postgres::message::{
     Close, Message, MessageFormat, ReadyForQuery, Terminate, TransactionStatus,
 };
+use crate::postgres::options::ExplainSettings;
 use crate::postgres::statement::PgStatementMetadata;
-use crate::postgres::{PgConnectOptions, PgTypeInfo, Postgres};
+use crate::postgres::{
+    PgConnectOptions, PgDatabaseError, PgFlavor, PgSeverity, PgStatementCacheMode, PgTypeInfo,
+    Postgres,
+};
+use crate::row::Row;
 use crate::transaction::Transaction;
 
+mod batch;
+mod cancel;
+mod copy;
+mod cursor;
 pub(crate) mod describe;
 mod establish;
 mod executor;
+mod explain;
+mod pending;
 mod sasl;
+mod script;
 mod stream;
 mod tls;
 
+pub use batch::PgBatch;
+pub use cancel::PgCancelToken;
+pub use copy::PgCopyCsvOptions;
+pub use cursor::PgCursor;
+pub use script::PgScriptOutcome;
+
 /// A connection to a PostgreSQL database.
 pub struct PgConnection {
     // underlying TCP or UDS stream,
@@ -35,18 +63,19 @@ pub struct PgConnection {
 
     // process id of this backend
     // used to send cancel requests
-    #[allow(dead_code)]
     process_id: u32,
 
     // secret key of this backend
-    // used to send cancel requests
-    #[allow(dead_code)]
-    secret_key: u32,
+    // used to send cancel requests; `None` if the server sent none
+    secret_key: Option<u32>,
 
     // sequence of statement IDs for use in preparing statements
     // in PostgreSQL, the statement is prepared to a user-supplied identifier
     next_statement_id: u32,
 
+    // sequence of portal IDs for cursors
+    next_portal_id: u32,
+
     // cache statement by query string to the id and columns
     cache_statement: StatementCache<(u32, Arc<PgStatementMetadata>)>,
 
@@ -57,22 +86,147 @@ pub struct PgConnection {
     // number of ReadyForQuery messages that we are currently expecting
     pub(crate) pending_ready_for_query_count: usize,
 
+    // work queued by drop guards, written before the next command
+    pending_ops: Vec<PendingOp>,
+
+    // the portals of dropped cursors, which cannot reach `pending_ops`
+    dropped_cursors: Arc<Mutex<Vec<PendingOp>>>,
+
+    // set when the queued work could not be completed; the state of the session is unknown
+    broken: bool,
+
+    // the operation in progress, so that another is not started while it is
+    pub(crate) in_flight: InFlight,
+
     // current transaction status
     transaction_status: TransactionStatus,
     pub(crate) transaction_depth: usize,
 
+    // counts the transactions that ended, and with them the portals of their cursors
+    transaction_generation: u64,
+
+    // reject statements that may write before sending them
+    read_only_check: bool,
+
+    // how the rows read on the connection match column names
+    column_name_matching: ColumnNameMatching,
+
+    // run every query with the simple query protocol, writing the bind values into the SQL
+    prefer_simple_protocol: bool,
+
+    stats: StatsCollector,
+
     log_settings: LogSettings,
+    explain: Option<ExplainSettings>,
+    middleware: Middleware,
+
+    // the listener of connection events, told when the connection is closed
+    events: ConnectionEvents,
 }
 
 impl PgConnection {
+    /// The kind of server this is connected to.
+    ///
+    /// CockroachDB is recognized by the `crdb_version` parameter it reports when connecting,
+    /// and YugabyteDB by the `-YB-` in its `server_version`; any other server is taken for
+    /// PostgreSQL.
+    pub fn server_flavor(&self) -> PgFlavor {
+        self.stream.flavor
+    }
+
+    /// Whether backslashes are ordinary characters in string literals written as `'...'`, which
+    /// is the `standard_conforming_strings` setting of the session as last reported by the
+    /// server.
+    ///
+    /// This is on by default since PostgreSQL 9.1. The server reports every change of it, so
+    /// the value is current as of the last response received.
+    pub fn standard_conforming_strings(&self) -> bool {
+        self.stream.standard_conforming_strings
+    }
+
+    /// Quotes `value` so it can be written into SQL as a string literal, where a bind parameter
+    /// is not allowed, e.g. the options of `COPY` or the default of a column in DDL.
+    ///
+    /// Single quotes are doubled. If [`standard_conforming_strings`] is off and the value has a
+    /// backslash, it is written as an escape string, `E'...'`, with every backslash doubled as
+    /// well. Values containing a NUL byte are rejected, as no `TEXT` can hold one.
+    ///
+    /// [`standard_conforming_strings`]: Self::standard_conforming_strings
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx_core::postgres::PgConnection) -> Result<(), sqlx_core::error::Error> {
+    /// let status = conn.quote_literal("it's new")?;
+    ///
+    /// conn.execute(&*format!("ALTER TABLE users ALTER COLUMN status SET DEFAULT {}", status))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn quote_literal(&self, value: &str) -> Result<String, Error> {
+        crate::postgres::quote::quote_literal(value, self.stream.standard_conforming_strings)
+    }
+
     // will return when the connection is ready for another query
-    async fn wait_until_ready(&mut self) -> Result<(), Error> {
+    pub(crate) async fn wait_until_ready(&mut self) -> Result<(), Error> {
+        if self.broken {
+            return Err(err_protocol!(
+                "connection is broken: work queued when a transaction or guard was dropped \
+                 could not be completed"
+            ));
+        }
+
+        // the OIDs looked up before may no longer name the same types
+        if self.stream.type_oids_stale {
+            self.stream.type_oids_stale = false;
+            self.cache_type_info.clear();
+            self.cache_type_oid.clear();
+        }
+
+        self.pending_ops.append(&mut self.dropped_cursors.lock());
+
+        let has_pending_ops = !self.pending_ops.is_empty();
+
+        self.pending_ready_for_query_count +=
+            write_pending_ops(&mut self.pending_ops, &mut self.stream.wbuf);
+
+        let result = self.recv_pending_ready_for_query().await;
+
+        // if queued work failed (e.g. a rollback), the state of the session is unknown
+        if has_pending_ops && result.is_err() {
+            self.broken = true;
+        }
+
+        result
+    }
+
+    async fn recv_pending_ready_for_query(&mut self) -> Result<(), Error> {
         if !self.stream.wbuf.is_empty() {
             self.stream.flush().await?;
         }
 
         while self.pending_ready_for_query_count > 0 {
-            let message = self.stream.recv().await?;
+            // the rows of an abandoned result are skipped over rather than read
+            let message = match self.stream.skip_data_rows().await {
+                Ok(()) => self.stream.recv().await,
+                Err(error) => Err(error),
+            };
+
+            let message = match message {
+                Ok(message) => message,
+
+                // an error here belongs to a query whose results were abandoned before it was
+                // reached (e.g. by `fetch_one`), not to whatever query is about to be sent
+                Err(Error::Database(error))
+                    if error.downcast_ref::<PgDatabaseError>().severity() == PgSeverity::Error =>
+                {
+                    log::debug!("discarding error from an abandoned query: {}", error);
+                    continue;
+                }
+
+                Err(error) => return Err(error),
+            };
 
             if let MessageFormat::ReadyForQuery = message.format {
                 self.handle_ready_for_query(message)?;
@@ -82,6 +236,11 @@ impl PgConnection {
         Ok(())
     }
 
+    /// Queues work to be done before the next command, for drop guards that cannot wait.
+    pub(crate) fn queue_op(&mut self, op: PendingOp) {
+        self.pending_ops.push(op);
+    }
+
     async fn recv_ready_for_query(&mut self) -> Result<(), Error> {
         let r: ReadyForQuery = self
             .stream
@@ -89,17 +248,28 @@ impl PgConnection {
             .await?;
 
         self.pending_ready_for_query_count -= 1;
-        self.transaction_status = r.transaction_status;
+        self.set_transaction_status(r.transaction_status);
 
         Ok(())
     }
 
     fn handle_ready_for_query(&mut self, message: Message) -> Result<(), Error> {
         self.pending_ready_for_query_count -= 1;
-        self.transaction_status = ReadyForQuery::decode(message.contents)?.transaction_status;
+        self.set_transaction_status(ReadyForQuery::decode(message.contents)?.transaction_status);
 
         Ok(())
     }
+
+    fn set_transaction_status(&mut self, status: TransactionStatus) {
+        // the server closes the portals of cursors with their transaction
+        if matches!(status, TransactionStatus::Idle)
+            && !matches!(self.transaction_status, TransactionStatus::Idle)
+        {
+            self.transaction_generation += 1;
+        }
+
+        self.transaction_status = status;
+    }
 }
 
 impl Debug for PgConnection {
@@ -120,17 +290,33 @@ impl Connection for PgConnection {
         // On receipt of this message, the backend closes the
         // connection and terminates.
 
+        let events = mem::take(&mut self.events);
+
         Box::pin(async move {
-            self.stream.send(Terminate).await?;
-            self.stream.shutdown().await?;
+            let result = async {
+                self.stream.send(Terminate).await?;
+                self.stream.shutdown().await?;
 
-            Ok(())
+                Ok::<_, Error>(())
+            }
+            .await;
+
+            events.closed(CloseReason::Explicit);
+
+            result
         })
     }
 
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
-        // By sending a comment we avoid an error if the connection was in the middle of a rowset
-        self.execute("/* SQLx ping */").map_ok(|_| ()).boxed()
+        Box::pin(async move {
+            let start = Instant::now();
+
+            // By sending a comment we avoid an error if the connection was in the middle of a rowset
+            self.execute("/* SQLx ping */").await?;
+            self.stats.record_ping(start.elapsed());
+
+            Ok(())
+        })
     }
 
     fn begin(&mut self) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
@@ -145,35 +331,155 @@ impl Connection for PgConnection {
     }
 
     fn clear_cached_statements(&mut self) -> BoxFuture<'_, Result<(), Error>> {
-        Box::pin(async move {
-            let mut cleared = 0_usize;
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch(
+            "clear_cached_statements",
+            Box::pin(async move {
+                let mut cleared = 0_usize;
+
+                self.wait_until_ready().await?;
+
+                for (id, _) in self.cache_statement.drain() {
+                    // only the types of the unnamed statement are cached, there is nothing to close
+                    if id != 0 {
+                        self.stream.write(Close::Statement(id));
+                        cleared += 1;
+                    }
+                }
+
+                if cleared > 0 {
+                    self.write_sync();
+                    self.stream.flush().await?;
+
+                    self.wait_for_close_complete(cleared).await?;
+                    self.recv_ready_for_query().await?;
+                }
+
+                Ok(())
+            }),
+        )
+    }
 
-            self.wait_until_ready().await?;
+    fn prepare_pinned<'e>(&'e mut self, sql: &'e str) -> BoxFuture<'e, Result<(), Error>> {
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch(
+            "prepare_pinned",
+            Box::pin(async move {
+                // nothing is cached in `Transaction` mode, or without prepared statements
+                if self.stream.statement_cache_mode == PgStatementCacheMode::Transaction
+                    || self.prefer_simple_protocol
+                    || !self.cache_statement.is_enabled()
+                {
+                    return Err(Error::Configuration(
+                        "cannot pin a statement: the statement cache is disabled".into(),
+                    ));
+                }
+
+                self.wait_until_ready().await?;
+
+                let (id, _, close) = self.get_or_prepare(sql, &[], true, None).await?;
+
+                if close {
+                    self.queue_op(PendingOp::CloseStatement(id));
+                }
+
+                if self.cache_statement.pin(sql) {
+                    Ok(())
+                } else {
+                    Err(Error::Configuration(
+                        "cannot pin a statement: the statement cache is full of pinned statements"
+                            .into(),
+                    ))
+                }
+            }),
+        )
+    }
 
-            while let Some((id, _)) = self.cache_statement.remove_lru() {
-                self.stream.write(Close::Statement(id));
-                cleared += 1;
-            }
+    fn shrink_buffers(&mut self) {
+        self.stream.shrink();
+    }
 
-            if cleared > 0 {
-                self.write_sync();
-                self.stream.flush().await?;
+    fn buffer_sizes(&self) -> BufferSizes {
+        let (read, write) = self.stream.capacity();
 
-                self.wait_for_close_complete(cleared).await?;
-                self.recv_ready_for_query().await?;
+        BufferSizes { read, write }
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        let (bytes_received, bytes_sent) = self.stream.bytes_transferred();
+
+        let mut stats = self.stats.snapshot(bytes_sent, bytes_received);
+
+        stats.notices_suppressed = self.stream.notices_suppressed;
+        stats.statement_cache = self.cache_statement.stats();
+        stats.notifications_dropped = match &self.stream.notifications {
+            Some(buffer) => buffer.dropped(),
+            None => 0,
+        };
+
+        stats
+    }
+
+    fn session_state(&mut self) -> BoxFuture<'_, Result<SessionState, Error>> {
+        Box::pin(async move {
+            // nothing can be queried in a failed transaction until it is rolled back
+            if matches!(self.transaction_status, TransactionStatus::Error) {
+                return Ok(SessionState {
+                    in_transaction: Some(true),
+                    ..SessionState::default()
+                });
             }
 
-            Ok(())
+            // one round trip, with the simple query protocol so that no statement is cached
+            let row = self
+                .fetch_one(
+                    "SELECT current_setting('search_path'), \
+                     (SELECT count(*) FROM pg_prepared_statements WHERE from_sql), \
+                     (SELECT count(*) FROM pg_class \
+                      WHERE relnamespace = pg_my_temp_schema() AND relkind IN ('r', 'p'))",
+                )
+                .await?;
+
+            Ok(SessionState {
+                in_transaction: Some(!matches!(self.transaction_status, TransactionStatus::Idle)),
+                prepared_statements: Some(row.try_get::<i64, _>(1)? as u64),
+                search_path: Some(row.try_get(0)?),
+                temp_tables: Some(row.try_get::<i64, _>(2)? as u64),
+            })
         })
     }
 
+    fn capabilities(&self) -> Capabilities {
+        self.stream.flavor.capabilities()
+    }
+
+    #[doc(hidden)]
+    fn take_events(&mut self) -> ConnectionEvents {
+        mem::take(&mut self.events)
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
-        self.wait_until_ready().boxed()
+        let in_flight = self.in_flight.clone();
+
+        in_flight.watch("flush", self.wait_until_ready().boxed())
     }
 
     #[doc(hidden)]
     fn should_flush(&self) -> bool {
+        // a connection with an operation in progress, or that a panic unwound through, fails to
+        // flush, and is closed by the pool
         !self.stream.wbuf.is_empty()
+            || !self.pending_ops.is_empty()
+            || !self.dropped_cursors.lock().is_empty()
+            || self.in_flight.is_busy()
+            || self.in_flight.is_poisoned()
+    }
+
+    #[doc(hidden)]
+    fn saw_server_shutdown(&self) -> bool {
+        self.stream.saw_server_shutdown
     }
 }
@@ -0,0 +1,136 @@
+use std::borrow::Cow;
+use std::mem;
+
+use crate::io::Encode;
+use crate::postgres::message::{Close, Query, Sync};
+
+/// Protocol work queued by a drop guard, which cannot wait for it to complete.
+///
+/// Queued operations are written before the next command on the connection, which includes
+/// [`ping`](crate::connection::Connection::ping) and the flush when the connection is
+/// returned to a pool. They stay queued if the task flushing them is cancelled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PendingOp {
+    /// `ROLLBACK` or `ROLLBACK TO SAVEPOINT` for a dropped transaction.
+    Rollback(Cow<'static, str>),
+
+    /// Any other simple query, e.g. to release an advisory lock.
+    Query(Cow<'static, str>),
+
+    /// Close a prepared statement evicted from the statement cache.
+    CloseStatement(u32),
+
+    /// Close the portal of a dropped cursor.
+    ClosePortal(u32),
+}
+
+impl PendingOp {
+    // rollbacks go first: until the transaction is rolled back, the server would reject
+    // queries queued behind it if the transaction failed
+    fn is_rollback(&self) -> bool {
+        matches!(self, PendingOp::Rollback(_))
+    }
+}
+
+/// Writes and clears the queued operations, returning the number of `ReadyForQuery` messages
+/// they will produce.
+///
+/// Rollbacks are written before everything else; otherwise, operations are written in the
+/// order they were queued.
+pub(super) fn write_pending_ops(ops: &mut Vec<PendingOp>, buf: &mut Vec<u8>) -> usize {
+    let mut ops = mem::take(ops);
+    let mut ready_for_query = 0;
+
+    // a stable sort keeps the queued order among rollbacks and among everything else
+    ops.sort_by_key(|op| !op.is_rollback());
+
+    let mut closing = false;
+
+    for op in ops {
+        match op {
+            PendingOp::Rollback(sql) | PendingOp::Query(sql) => {
+                if closing {
+                    Sync.encode(buf);
+                    ready_for_query += 1;
+                    closing = false;
+                }
+
+                Query(&sql).encode(buf);
+                ready_for_query += 1;
+            }
+
+            PendingOp::CloseStatement(id) => {
+                Close::Statement(id).encode(buf);
+                closing = true;
+            }
+
+            PendingOp::ClosePortal(id) => {
+                Close::Portal(id).encode(buf);
+                closing = true;
+            }
+        }
+    }
+
+    if closing {
+        Sync.encode(buf);
+        ready_for_query += 1;
+    }
+
+    ready_for_query
+}
+
+#[test]
+fn it_writes_rollbacks_first() {
+    let mut ops = vec![
+        PendingOp::CloseStatement(1),
+        PendingOp::Query("SELECT pg_advisory_unlock(1)".into()),
+        PendingOp::Rollback("ROLLBACK TO SAVEPOINT _sqlx_savepoint_1".into()),
+        PendingOp::CloseStatement(2),
+        PendingOp::Rollback("ROLLBACK".into()),
+    ];
+
+    let mut buf = Vec::new();
+    assert_eq!(write_pending_ops(&mut ops, &mut buf), 5);
+    assert!(ops.is_empty(), "the queue must be cleared");
+
+    let mut expected = Vec::new();
+    Query("ROLLBACK TO SAVEPOINT _sqlx_savepoint_1").encode(&mut expected);
+    Query("ROLLBACK").encode(&mut expected);
+    Close::Statement(1).encode(&mut expected);
+    Sync.encode(&mut expected);
+    Query("SELECT pg_advisory_unlock(1)").encode(&mut expected);
+    Close::Statement(2).encode(&mut expected);
+    Sync.encode(&mut expected);
+
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn it_groups_closes_under_one_sync() {
+    let mut ops = vec![
+        PendingOp::CloseStatement(1),
+        PendingOp::ClosePortal(1),
+        PendingOp::CloseStatement(2),
+        PendingOp::CloseStatement(3),
+    ];
+
+    let mut buf = Vec::new();
+    assert_eq!(write_pending_ops(&mut ops, &mut buf), 1);
+
+    let mut expected = Vec::new();
+    Close::Statement(1).encode(&mut expected);
+    Close::Portal(1).encode(&mut expected);
+    Close::Statement(2).encode(&mut expected);
+    Close::Statement(3).encode(&mut expected);
+    Sync.encode(&mut expected);
+
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn it_writes_nothing_for_an_empty_queue() {
+    let mut buf = Vec::new();
+
+    assert_eq!(write_pending_ops(&mut Vec::new(), &mut buf), 0);
+    assert!(buf.is_empty());
+}
@@ -0,0 +1,156 @@
+use std::mem;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::common::{check_read_only, SqlDialect};
+use crate::error::Error;
+use crate::io::Decode;
+use crate::logger::QueryLogger;
+use crate::postgres::connection::describe::describe_columns;
+use crate::postgres::message::{CommandComplete, DataRow, MessageFormat, Query, RowDescription};
+use crate::postgres::statement::PgStatementMetadata;
+use crate::postgres::{PgConnection, PgRow, PgValueFormat};
+
+/// The outcome of one statement of a script run with
+/// [`PgConnection::execute_raw_script`].
+#[derive(Debug)]
+pub struct PgScriptOutcome {
+    tag: String,
+    rows_affected: u64,
+    rows: Vec<PgRow>,
+}
+
+impl PgScriptOutcome {
+    /// The command tag the server completed the statement with, e.g. `INSERT 0 1` or
+    /// `CREATE FUNCTION`.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// The number of rows inserted, updated, deleted or returned by the statement.
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    /// The rows returned by the statement, if any.
+    ///
+    /// Values in these rows are in the text format.
+    pub fn rows(&self) -> &[PgRow] {
+        &self.rows
+    }
+
+    /// Takes the rows returned by the statement.
+    pub fn into_rows(self) -> Vec<PgRow> {
+        self.rows
+    }
+}
+
+impl PgConnection {
+    /// Runs a script of semicolon-separated statements with the simple query protocol,
+    /// returning the outcome of each statement in order.
+    ///
+    /// The extended protocol used for everything else can only run a single statement at a
+    /// time, while the server splits the script itself here, so semicolons inside strings,
+    /// comments and dollar-quoted function bodies are fine. The statements cannot have bind
+    /// parameters.
+    ///
+    /// Unless the script manages transactions itself with `BEGIN` and `COMMIT`, the server
+    /// runs all of it in one implicit transaction: an error in any statement rolls back the
+    /// ones before it, and commands that refuse to run inside a transaction block (such as
+    /// `CREATE INDEX CONCURRENTLY`) fail.
+    ///
+    /// A script that is empty or only holds comments returns no outcomes.
+    pub async fn execute_raw_script(&mut self, sql: &str) -> Result<Vec<PgScriptOutcome>, Error> {
+        let in_flight = self.in_flight.clone();
+
+        in_flight
+            .watch("execute_raw_script", Box::pin(self.run_script(sql)))
+            .await
+    }
+
+    async fn run_script(&mut self, sql: &str) -> Result<Vec<PgScriptOutcome>, Error> {
+        let mut logger = QueryLogger::new(sql, self.log_settings.clone());
+
+        if self.read_only_check {
+            check_read_only(sql, SqlDialect::Postgres)?;
+        }
+
+        self.wait_until_ready().await?;
+
+        self.stream.write(Query(sql));
+        self.pending_ready_for_query_count += 1;
+
+        let mut sent_at = Some(Instant::now());
+        self.stream.flush().await?;
+
+        let mut outcomes = Vec::new();
+        let mut metadata = Arc::new(PgStatementMetadata::default());
+        let mut rows = Vec::new();
+
+        loop {
+            // notices sent in between statements are handled by the stream
+            let message = self.stream.recv().await;
+
+            if let Some(sent_at) = sent_at.take() {
+                self.stats.record_query(sent_at.elapsed());
+            }
+
+            let message = message?;
+
+            match message.format {
+                MessageFormat::CommandComplete => {
+                    let cc: CommandComplete = message.decode()?;
+
+                    outcomes.push(PgScriptOutcome {
+                        tag: cc.tag()?.to_owned(),
+                        rows_affected: cc.rows_affected(),
+                        rows: mem::take(&mut rows),
+                    });
+                }
+
+                MessageFormat::EmptyQueryResponse => {
+                    // the script was empty
+                }
+
+                MessageFormat::RowDescription => {
+                    let desc =
+                        RowDescription::decode_with(message.contents, &mut self.stream.arena)?;
+                    let (columns, column_names) = describe_columns(&desc, &self.cache_type_info);
+
+                    metadata = Arc::new(PgStatementMetadata {
+                        column_names,
+                        columns,
+                        parameters: Vec::default(),
+                    });
+                }
+
+                MessageFormat::DataRow => {
+                    logger.increment_rows();
+
+                    let data: DataRow = message.decode()?;
+
+                    rows.push(PgRow::new(
+                        data,
+                        PgValueFormat::Text.into(),
+                        Arc::clone(&metadata),
+                        self.column_name_matching,
+                    )?);
+                }
+
+                MessageFormat::ReadyForQuery => {
+                    self.handle_ready_for_query(message)?;
+                    break;
+                }
+
+                _ => {
+                    return Err(err_protocol!(
+                        "execute_raw_script: unexpected message: {:?}",
+                        message.format
+                    ));
+                }
+            }
+        }
+
+        Ok(outcomes)
+    }
+}
@@ -1,15 +1,20 @@
 use std::ops::{Deref, DerefMut};
 
 use bytes::{Buf, Bytes};
-use futures_channel::mpsc::UnboundedSender;
-use futures_util::SinkExt;
 use log::Level;
 
-use crate::error::Error;
-use crate::io::{BufStream, Decode, Encode};
+use crate::error::{catch_panic, Error};
+use crate::executor::FetchLimits;
+use crate::io::{BufStream, Decode, Encode, Framing};
 use crate::net::{MaybeTlsStream, Socket};
-use crate::postgres::message::{Message, MessageFormat, Notice, Notification};
-use crate::postgres::{PgConnectOptions, PgDatabaseError, PgSeverity};
+use crate::postgres::listener::NotificationBuffer;
+use crate::postgres::message::{
+    Message, MessageArena, MessageFormat, Notice, Notification, ParameterStatus,
+};
+use crate::postgres::notice::PgNoticeHandler;
+use crate::postgres::{
+    PgConnectOptions, PgDatabaseError, PgFlavor, PgNotice, PgSeverity, PgStatementCacheMode,
+};
 
 // the stream is a separate type from the connection to uphold the invariant where an instantiated
 // [PgConnection] is a **valid** connection to postgres
@@ -26,21 +31,96 @@ pub struct PgStream {
     // buffer of unreceived notification messages from `PUBLISH`
     // this is set when creating a PgListener and only written to if that listener is
     // re-used for query execution in-between receiving messages
-    pub(crate) notifications: Option<UnboundedSender<Notification>>,
+    pub(crate) notifications: Option<NotificationBuffer>,
+
+    notice_handler: Option<PgNoticeHandler>,
+
+    // notices past `max_notices` in one query are only counted, until the connection is ready
+    // for the next query
+    max_notices: Option<usize>,
+    notices: usize,
+    pub(crate) notices_suppressed: u64,
+
+    // set once a replication stream has been stopped; the server can still send keepalive
+    // messages after the copy has ended, which are of no use to anyone
+    pub(crate) discard_copy_data: bool,
+
+    pub(crate) statement_cache_mode: PgStatementCacheMode,
+
+    // whether backslashes are ordinary characters in '...' literals, as last reported by the
+    // server; the default since PostgreSQL 9.1
+    pub(crate) standard_conforming_strings: bool,
+
+    max_message_size: usize,
+
+    // the bytes left of a message that is being skipped over rather than read, e.g. a data
+    // row larger than the limits of its query
+    discarding: usize,
+
+    // set once the server reports that it is shutting down, or ends the session
+    pub(crate) saw_server_shutdown: bool,
+
+    // the kind of server, as told by the parameters it reports while connecting
+    pub(crate) flavor: PgFlavor,
+
+    // set when a server whose type OIDs may change reports a type that does not exist; the
+    // connection then forgets the user-defined types it looked up
+    pub(crate) type_oids_stale: bool,
+
+    // fail on messages of unknown types rather than skip them
+    strict_protocol: bool,
+
+    // the names of the parameters reported while connecting, until the connection is
+    // established
+    pub(super) reported_parameters: Option<Vec<String>>,
+
+    // what the descriptions in a response are decoded into; reset as the server reports it is
+    // ready for the next query
+    pub(crate) arena: MessageArena,
 }
 
 impl PgStream {
     pub(super) async fn connect(options: &PgConnectOptions) -> Result<Self, Error> {
-        let socket = match options.fetch_socket() {
-            Some(ref path) => Socket::connect_uds(path).await?,
-            None => Socket::connect_tcp(&options.host, options.port).await?,
+        let socket = if !options.addresses.is_empty() {
+            Socket::connect_addresses(&options.addresses, &options.host).await?
+        } else {
+            match (options.fetch_socket(), options.fetch_socket_owner()) {
+                (Some(ref path), Some(ref owner)) => {
+                    Socket::connect_uds_owned_by(path, owner).await?
+                }
+                (Some(ref path), None) => Socket::connect_uds(path).await?,
+                (None, _) => Socket::connect_tcp(&options.host, options.port).await?,
+            }
         };
 
-        let inner = BufStream::new(MaybeTlsStream::Raw(socket));
+        let mut inner = BufStream::new(MaybeTlsStream::Raw(socket));
+        inner.set_max_capacity(options.buffer_max);
+        inner.set_wire_tracer(
+            options
+                .wire_tracing
+                .as_ref()
+                .map(|tracing| tracing.0.clone()),
+            Framing::Postgres,
+        );
 
         Ok(Self {
             inner,
             notifications: None,
+            notice_handler: options.notice_handler.clone(),
+            max_notices: options.max_notices,
+            notices: 0,
+            notices_suppressed: 0,
+            discard_copy_data: false,
+            statement_cache_mode: options.statement_cache_mode,
+            standard_conforming_strings: true,
+            max_message_size: options.max_message_size,
+            discarding: 0,
+            saw_server_shutdown: false,
+            flavor: PgFlavor::Postgres,
+            type_oids_stale: false,
+            strict_protocol: options.strict_protocol,
+            reported_parameters: Some(Vec::new()),
+            arena: MessageArena::default(),
         })
     }
 
@@ -70,17 +150,158 @@ impl PgStream {
         message.decode()
     }
 
+    // checks the next message, if it is a data row, against `limits`, reading no more of it than
+    // needed to tell; a row that breaks them is skipped over, without being buffered
+    pub(crate) async fn check_data_row(&mut self, limits: &FetchLimits) -> Result<(), Error> {
+        let size = match self.peek_data_row().await? {
+            Some(size) => size,
+            None => return Ok(()),
+        };
+
+        let result = self.check_data_row_values(size, limits).await;
+
+        if let Err(Error::ValueTooLarge { .. }) = result {
+            self.discarding = 5 + size;
+        }
+
+        result
+    }
+
+    // skips over the data rows that come next, which are of no use to whoever is only waiting
+    // for the end of a query
+    pub(crate) async fn skip_data_rows(&mut self) -> Result<(), Error> {
+        while let Some(size) = self.peek_data_row().await? {
+            self.discarding = 5 + size;
+            self.inner.discard(&mut self.discarding).await?;
+        }
+
+        Ok(())
+    }
+
+    // the size of the next message, if it is a data row
+    async fn peek_data_row(&mut self) -> Result<Option<usize>, Error> {
+        if self.discarding > 0 {
+            self.inner.discard(&mut self.discarding).await?;
+        }
+
+        let mut header = self.inner.peek(5).await?;
+
+        if header.get_u8() != b'D' {
+            return Ok(None);
+        }
+
+        Ok(Some((header.get_u32() as usize).saturating_sub(4)))
+    }
+
+    async fn check_data_row_values(
+        &mut self,
+        size: usize,
+        limits: &FetchLimits,
+    ) -> Result<(), Error> {
+        // an Int16 count of the values, then for each, an Int32 length (-1 for NULL) and as
+        // many bytes, much as `DataRow` decodes them
+        let columns = (&self.inner.peek(7).await?[5..]).get_u16() as usize;
+
+        // the lengths take up what the values do not
+        let values = size.saturating_sub(2 + 4 * columns);
+
+        limits.check_row(values)?;
+
+        if !matches!(limits.max_value_size, Some(limit) if values > limit) {
+            // no value can be larger than all of them together
+            return Ok(());
+        }
+
+        let mut offset = 7;
+
+        for column in 0..columns {
+            let len = (&self.inner.peek(offset + 4).await?[offset..]).get_i32();
+            offset += 4;
+
+            if len < 0 {
+                continue;
+            }
+
+            let len = len as usize;
+
+            if offset + len > 5 + size {
+                return Err(err_protocol!(
+                    "value {} of a data row of {} bytes is {} bytes long",
+                    column,
+                    size,
+                    len
+                ));
+            }
+
+            limits.check_value(column, len)?;
+            offset += len;
+        }
+
+        Ok(())
+    }
+
     pub(crate) async fn recv_unchecked(&mut self) -> Result<Message, Error> {
-        // all packets in postgres start with a 5-byte header
-        // this header contains the message type and the total length of the message
-        let mut header: Bytes = self.inner.read(5).await?;
+        loop {
+            if self.discarding > 0 {
+                self.inner.discard(&mut self.discarding).await?;
+            }
+
+            // all packets in postgres start with a 5-byte header
+            // this header contains the message type and the total length of the message
+            //
+            // the message is only consumed once all of it has been read, so that a cancelled
+            // read leaves the stream at the start of the message
+            let mut header = self.inner.peek(5).await?;
+
+            let tag = header.get_u8();
+
+            // the length includes itself
+            let size = match header.get_u32().checked_sub(4) {
+                Some(size) => size as usize,
+                None => {
+                    return Err(err_protocol!(
+                        "invalid length for message {:?}",
+                        tag as char
+                    ))
+                }
+            };
+
+            let format = match MessageFormat::try_from_u8(tag) {
+                Ok(format) => format,
 
-        let format = MessageFormat::try_from_u8(header.get_u8())?;
-        let size = (header.get_u32() - 4) as usize;
+                Err(error) if self.strict_protocol => return Err(error),
 
-        let contents = self.inner.read(size).await?;
+                // sent by a server that is not quite PostgreSQL, or by a later version; the
+                // length tells where the next message starts
+                Err(_) => {
+                    log::debug!(
+                        "skipping a message of unknown type {:?} ({} bytes)",
+                        tag as char,
+                        size
+                    );
 
-        Ok(Message { format, contents })
+                    self.discarding = 5 + size;
+                    continue;
+                }
+            };
+
+            if size > self.max_message_size {
+                return Err(err_protocol!(
+                    "message {:?} of {} bytes is larger than the maximum of {} bytes; \
+                     see `PgConnectOptions::max_message_size`",
+                    tag as char,
+                    size,
+                    self.max_message_size
+                ));
+            }
+
+            let mut contents: Bytes = self.inner.read(5 + size).await?;
+            contents.advance(5);
+
+            self.inner.trace_received(Some(tag), &contents);
+
+            return Ok(Message { format, contents });
+        }
     }
 
     // Get the next message from the server
@@ -92,29 +313,104 @@ impl PgStream {
             match message.format {
                 MessageFormat::ErrorResponse => {
                     // An error returned from the database server.
-                    return Err(PgDatabaseError(message.decode()?).into());
+                    let error = PgDatabaseError::new(
+                        message.decode()?,
+                        self.statement_cache_mode == PgStatementCacheMode::Session,
+                        self.flavor,
+                    );
+
+                    // also sent unprompted, e.g. when the backend is terminated, and read with
+                    // the response to the next query
+                    if error.is_server_shutdown() {
+                        self.saw_server_shutdown = true;
+                    }
+
+                    // `undefined_object`, e.g. a type recreated under another OID
+                    if !self.flavor.has_stable_type_oids() && error.code() == "42704" {
+                        self.type_oids_stale = true;
+                    }
+
+                    return Err(error.into());
                 }
 
                 MessageFormat::NotificationResponse => {
                     if let Some(buffer) = &mut self.notifications {
                         let notification: Notification = message.decode()?;
-                        let _ = buffer.send(notification).await;
+                        buffer.push(notification);
 
                         continue;
                     }
                 }
 
+                MessageFormat::CopyData if self.discard_copy_data => {
+                    log::trace!("discarding CopyData received outside of a copy");
+                    continue;
+                }
+
                 MessageFormat::ParameterStatus => {
                     // informs the frontend about the current (initial)
                     // setting of backend parameters
 
-                    // we currently have no use for that data so we promptly ignore this message
+                    // we care about `DateStyle` and `IntervalStyle`, as dates, times and
+                    // intervals in the text format (from the simple query protocol) are only
+                    // parsed in the styles connections start with, and about
+                    // `standard_conforming_strings` for quoting literals, and about those
+                    // that tell what kind of server this is
+                    let status: ParameterStatus = message.decode()?;
+
+                    if let Some(reported) = &mut self.reported_parameters {
+                        reported.push(status.name.clone());
+                    }
+
+                    if let Some(flavor) = PgFlavor::from_parameter(&status.name, &status.value) {
+                        self.flavor = flavor;
+                    }
+
+                    if status.name == "standard_conforming_strings" {
+                        self.standard_conforming_strings = status.value == "on";
+                    }
+
+                    if status.name == "IntervalStyle" && status.value != "postgres" {
+                        log::warn!(
+                            "IntervalStyle was changed to {:?}; intervals returned in the text \
+                             format can no longer be decoded",
+                            status.value
+                        );
+                    }
+
+                    if status.name == "DateStyle" && !status.value.starts_with("ISO") {
+                        log::warn!(
+                            "DateStyle was changed to {:?}; dates and times returned in the \
+                             text format can no longer be decoded",
+                            status.value
+                        );
+                    }
+
                     continue;
                 }
 
                 MessageFormat::NoticeResponse => {
-                    // do we need this to be more configurable?
-                    // if you are reading this comment and think so, open an issue
+                    // notices are always logged; a handler set through
+                    // `PgConnectOptions::notice_handler` gets a copy as well
+                    // unless the query already sent as many as `max_notices`
+
+                    self.notices += 1;
+
+                    if let Some(max) = self.max_notices {
+                        if self.notices > max {
+                            if self.notices == max + 1 {
+                                log::warn!(
+                                    "more than {} notices for one query; further notices are \
+                                     counted but neither logged nor handled",
+                                    max
+                                );
+                            }
+
+                            self.notices_suppressed += 1;
+
+                            continue;
+                        }
+                    }
 
                     let notice: Notice = message.decode()?;
 
@@ -139,9 +435,24 @@ impl PgStream {
                         );
                     }
 
+                    if let Some(handler) = &self.notice_handler {
+                        let handler = &handler.0;
+
+                        // the handler is user code; a panic must not unwind through the
+                        // connection while it is in the middle of reading a response
+                        if let Err(error) = catch_panic(|| handler(PgNotice(notice))) {
+                            log::error!("notice handler {}; the notice was dropped", error);
+                        }
+                    }
+
                     continue;
                 }
 
+                MessageFormat::ReadyForQuery => {
+                    self.notices = 0;
+                    self.arena.reset();
+                }
+
                 _ => {}
             }
 
@@ -3,26 +3,78 @@ use bytes::Bytes;
 use crate::error::Error;
 use crate::postgres::connection::stream::PgStream;
 use crate::postgres::message::SslRequest;
-use crate::postgres::{PgConnectOptions, PgSslMode};
+use crate::postgres::{PgConnectOptions, PgSslMode, PgSslNegotiation};
 
-pub(super) async fn maybe_upgrade(
+// the protocol a server taking TLS directly requires from ALPN
+const ALPN_POSTGRESQL: &str = "postgresql";
+
+// opens a connection, and sets up TLS on it as `ssl_mode` and `ssl_negotiation` say
+pub(super) async fn connect(
+    options: &PgConnectOptions,
+    ssl_mode: PgSslMode,
+) -> Result<PgStream, Error> {
+    let mut stream = PgStream::connect(options).await?;
+
+    let direct = options.ssl_negotiation == PgSslNegotiation::Direct
+        && !matches!(ssl_mode, PgSslMode::Disable | PgSslMode::Allow);
+
+    if !direct {
+        maybe_upgrade(&mut stream, options, ssl_mode).await?;
+
+        return Ok(stream);
+    }
+
+    match upgrade_directly(&mut stream, options, ssl_mode).await {
+        Ok(()) => Ok(stream),
+
+        // a server older than Postgres 17 reads the handshake as a malformed startup packet
+        // and closes the connection; `prefer` may then try an `SSLRequest` on a new one
+        Err(error @ Error::Io(_)) | Err(error @ Error::Tls(_))
+            if matches!(ssl_mode, PgSslMode::Prefer) =>
+        {
+            log::debug!(
+                "direct TLS negotiation failed, falling back to an SSLRequest: {}",
+                error
+            );
+
+            let mut stream = PgStream::connect(options).await?;
+            maybe_upgrade(&mut stream, options, ssl_mode).await?;
+
+            Ok(stream)
+        }
+
+        Err(error) => Err(error),
+    }
+}
+
+async fn maybe_upgrade(
     stream: &mut PgStream,
     options: &PgConnectOptions,
+    ssl_mode: PgSslMode,
 ) -> Result<(), Error> {
     // https://www.postgresql.org/docs/12/libpq-ssl.html#LIBPQ-SSL-SSLMODE-STATEMENTS
-    match options.ssl_mode {
-        // FIXME: Implement ALLOW
+    match ssl_mode {
+        // `Allow` starts without TLS; `establish` retries with TLS if the server refuses that
         PgSslMode::Allow | PgSslMode::Disable => {}
 
         PgSslMode::Prefer => {
-            // try upgrade, but its okay if we fail
-            upgrade(stream, options).await?;
+            // try upgrade, but its okay if we fail, unless that is exactly what a proxy
+            // stripping the TLS request would make us do and the policy forbids it
+            if !upgrade(stream, options, ssl_mode).await?
+                && !options.effective_protocol_policy().allow_plaintext_fallback
+            {
+                return Err(Error::PolicyViolation(
+                    "server refused to upgrade to TLS under `sslmode=prefer`, \
+                     and plaintext fallback is not allowed"
+                        .into(),
+                ));
+            }
         }
 
         PgSslMode::Require | PgSslMode::VerifyFull | PgSslMode::VerifyCa => {
-            if !upgrade(stream, options).await? {
+            if !upgrade(stream, options, ssl_mode).await? {
                 // upgrade failed, die
-                return Err(Error::Tls("server does not support TLS".into()));
+                return Err(Error::tls("server does not support TLS"));
             }
         }
     }
@@ -30,7 +82,11 @@ pub(super) async fn maybe_upgrade(
     Ok(())
 }
 
-async fn upgrade(stream: &mut PgStream, options: &PgConnectOptions) -> Result<bool, Error> {
+async fn upgrade(
+    stream: &mut PgStream,
+    options: &PgConnectOptions,
+    ssl_mode: PgSslMode,
+) -> Result<bool, Error> {
     // https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.11
 
     // To initiate an SSL-encrypted connection, the frontend initially sends an
@@ -59,11 +115,8 @@ async fn upgrade(stream: &mut PgStream, options: &PgConnectOptions) -> Result<bo
         }
     }
 
-    let accept_invalid_certs = !matches!(
-        options.ssl_mode,
-        PgSslMode::VerifyCa | PgSslMode::VerifyFull
-    );
-    let accept_invalid_hostnames = !matches!(options.ssl_mode, PgSslMode::VerifyFull);
+    let accept_invalid_certs = !matches!(ssl_mode, PgSslMode::VerifyCa | PgSslMode::VerifyFull);
+    let accept_invalid_hostnames = !matches!(ssl_mode, PgSslMode::VerifyFull);
 
     stream
         .upgrade(
@@ -71,8 +124,48 @@ async fn upgrade(stream: &mut PgStream, options: &PgConnectOptions) -> Result<bo
             accept_invalid_certs,
             accept_invalid_hostnames,
             options.ssl_root_cert.as_ref(),
+            options.tls_session_cache.as_ref(),
+            options.effective_protocol_policy().minimum_tls_version,
+            &[],
+            None,
+            None,
         )
         .await?;
 
     Ok(true)
 }
+
+async fn upgrade_directly(
+    stream: &mut PgStream,
+    options: &PgConnectOptions,
+    ssl_mode: PgSslMode,
+) -> Result<(), Error> {
+    // https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-FLOW-SSL
+
+    // the handshake starts right away, with nothing sent before it
+    let accept_invalid_certs = !matches!(ssl_mode, PgSslMode::VerifyCa | PgSslMode::VerifyFull);
+    let accept_invalid_hostnames = !matches!(ssl_mode, PgSslMode::VerifyFull);
+
+    stream
+        .upgrade(
+            &options.host,
+            accept_invalid_certs,
+            accept_invalid_hostnames,
+            options.ssl_root_cert.as_ref(),
+            options.tls_session_cache.as_ref(),
+            options.effective_protocol_policy().minimum_tls_version,
+            &[ALPN_POSTGRESQL],
+            None,
+            None,
+        )
+        .await?;
+
+    // like libpq, refuse a server that did not agree to speak Postgres over this connection
+    if stream.alpn_protocol() != Some(ALPN_POSTGRESQL.as_bytes()) {
+        return Err(Error::tls(
+            "server did not accept the `postgresql` ALPN protocol of a direct TLS connection",
+        ));
+    }
+
+    Ok(())
+}
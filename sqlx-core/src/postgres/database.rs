@@ -1,4 +1,6 @@
-use crate::database::{Database, HasArguments, HasStatement, HasStatementCache, HasValueRef};
+use crate::database::{
+    Capabilities, Database, HasArguments, HasStatement, HasStatementCache, HasValueRef,
+};
 use crate::postgres::arguments::PgArgumentBuffer;
 use crate::postgres::value::{PgValue, PgValueRef};
 use crate::postgres::{
@@ -24,6 +26,19 @@ impl Database for Postgres {
     type TypeInfo = PgTypeInfo;
 
     type Value = PgValue;
+
+    const CAPABILITIES: Capabilities = Capabilities::from_bits_truncate(
+        Capabilities::RETURNING.bits()
+            | Capabilities::SAVEPOINTS.bits()
+            | Capabilities::MULTIPLE_RESULT_SETS.bits()
+            | Capabilities::BINARY_PROTOCOL.bits()
+            | Capabilities::LISTEN_NOTIFY.bits()
+            | Capabilities::DOLLAR_PLACEHOLDERS.bits(),
+    );
+
+    const MAX_BIND_PARAMETERS: usize = PgArguments::MAX_ARGUMENTS;
+
+    const MAX_IDENTIFIER_LENGTH: Option<usize> = Some(63);
 }
 
 impl<'r> HasValueRef<'r> for Postgres {
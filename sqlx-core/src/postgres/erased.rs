@@ -0,0 +1,56 @@
+use crate::erased::{unsupported_column, ErasedDatabase, ErasedQueryResult, ErasedValue};
+use crate::error::Error;
+use crate::postgres::type_info::PgType;
+use crate::postgres::{PgArguments, PgQueryResult, PgRow, PgTypeInfo, Postgres};
+use crate::row::Row;
+use crate::value::ValueRef;
+
+impl ErasedDatabase for Postgres {
+    fn add_erased(arguments: &mut PgArguments, value: ErasedValue) -> Result<(), Error> {
+        match value {
+            // declared without a type, so Postgres infers it like for an untyped literal
+            ErasedValue::Null => arguments.add_with_type(None::<i32>, PgTypeInfo::with_oid(0)),
+
+            ErasedValue::Bool(v) => arguments.add(v),
+            ErasedValue::Int(v) => arguments.add(v),
+            ErasedValue::Float(v) => arguments.add(v),
+            ErasedValue::Text(v) => arguments.add(v),
+            ErasedValue::Bytes(v) => arguments.add(v),
+        }
+
+        Ok(())
+    }
+
+    fn get_erased(row: &PgRow, index: usize) -> Result<ErasedValue, Error> {
+        let value = row.try_get_raw(index)?;
+
+        if value.is_null() {
+            return Ok(ErasedValue::Null);
+        }
+
+        Ok(match value.type_info.0 {
+            PgType::Bool => ErasedValue::Bool(row.try_get_unchecked(index)?),
+            PgType::Int2 => ErasedValue::Int(row.try_get_unchecked::<i16, _>(index)?.into()),
+            PgType::Int4 => ErasedValue::Int(row.try_get_unchecked::<i32, _>(index)?.into()),
+            PgType::Int8 => ErasedValue::Int(row.try_get_unchecked(index)?),
+            PgType::Oid => ErasedValue::Int(row.try_get_unchecked::<u32, _>(index)?.into()),
+            PgType::Float4 => ErasedValue::Float(row.try_get_unchecked::<f32, _>(index)?.into()),
+            PgType::Float8 => ErasedValue::Float(row.try_get_unchecked(index)?),
+
+            PgType::Text | PgType::Varchar | PgType::Bpchar | PgType::Name | PgType::Unknown => {
+                ErasedValue::Text(row.try_get_unchecked(index)?)
+            }
+
+            PgType::Bytea => ErasedValue::Bytes(row.try_get_unchecked(index)?),
+
+            _ => return Err(unsupported_column(row, index)),
+        })
+    }
+
+    fn erase_query_result(result: PgQueryResult) -> ErasedQueryResult {
+        ErasedQueryResult {
+            rows_affected: result.rows_affected(),
+            last_insert_id: None,
+        }
+    }
+}
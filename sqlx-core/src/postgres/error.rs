@@ -6,9 +6,19 @@ use smallvec::alloc::borrow::Cow;
 
 use crate::error::DatabaseError;
 use crate::postgres::message::{Notice, PgSeverity};
+use crate::postgres::PgFlavor;
 
 /// An error returned from the PostgreSQL database.
-pub struct PgDatabaseError(pub(crate) Notice);
+pub struct PgDatabaseError {
+    pub(crate) notice: Notice,
+
+    // advice added by SQLx when it recognizes the cause of the error, see `hint`
+    pub(crate) client_hint: Option<&'static str>,
+
+    // the kind of server that returned the error, which some word their advice to retry
+    // differently
+    pub(crate) flavor: PgFlavor,
+}
 
 // Error message fields are documented:
 // https://www.postgresql.org/docs/current/protocol-error-fields.html
@@ -16,49 +26,54 @@ pub struct PgDatabaseError(pub(crate) Notice);
 impl PgDatabaseError {
     #[inline]
     pub fn severity(&self) -> PgSeverity {
-        self.0.severity()
+        self.notice.severity()
     }
 
     /// The [SQLSTATE](https://www.postgresql.org/docs/current/errcodes-appendix.html) code for
     /// this error.
     #[inline]
     pub fn code(&self) -> &str {
-        self.0.code()
+        self.notice.code()
     }
 
     /// The primary human-readable error message. This should be accurate but
     /// terse (typically one line).
     #[inline]
     pub fn message(&self) -> &str {
-        self.0.message()
+        self.notice.message()
     }
 
     /// An optional secondary error message carrying more detail about the problem.
     /// Might run to multiple lines.
     #[inline]
     pub fn detail(&self) -> Option<&str> {
-        self.0.get(b'D')
+        self.notice.get(b'D')
     }
 
     /// An optional suggestion what to do about the problem. This is intended to differ from
     /// `detail` in that it offers advice (potentially inappropriate) rather than hard facts.
     /// Might run to multiple lines.
+    ///
+    /// If the server sent no hint, this may be one added by SQLx, e.g. suggesting
+    /// [`PgStatementCacheMode::Transaction`](crate::postgres::PgStatementCacheMode) when a
+    /// prepared statement has disappeared behind a connection pooler. Such a hint is also
+    /// appended to the `Display` output.
     #[inline]
     pub fn hint(&self) -> Option<&str> {
-        self.0.get(b'H')
+        self.notice.get(b'H').or(self.client_hint)
     }
 
     /// Indicates an error cursor position as an index into the original query string; or,
     /// a position into an internally generated query.
     #[inline]
     pub fn position(&self) -> Option<PgErrorPosition<'_>> {
-        self.0
+        self.notice
             .get_raw(b'P')
             .and_then(atoi)
             .map(PgErrorPosition::Original)
             .or_else(|| {
-                let position = self.0.get_raw(b'p').and_then(atoi)?;
-                let query = self.0.get(b'q')?;
+                let position = self.notice.get_raw(b'p').and_then(atoi)?;
+                let query = self.notice.get(b'q')?;
 
                 Some(PgErrorPosition::Internal { position, query })
             })
@@ -68,50 +83,81 @@ impl PgDatabaseError {
     /// stack traceback of active procedural language functions and internally-generated queries.
     /// The trace is one entry per line, most recent first.
     pub fn r#where(&self) -> Option<&str> {
-        self.0.get(b'W')
+        self.notice.get(b'W')
     }
 
     /// If this error is with a specific database object, the
     /// name of the schema containing that object, if any.
     pub fn schema(&self) -> Option<&str> {
-        self.0.get(b's')
+        self.notice.get(b's')
     }
 
     /// If this error is with a specific table, the name of the table.
     pub fn table(&self) -> Option<&str> {
-        self.0.get(b't')
+        self.notice.get(b't')
     }
 
     /// If the error is with a specific table column, the name of the column.
     pub fn column(&self) -> Option<&str> {
-        self.0.get(b'c')
+        self.notice.get(b'c')
     }
 
     /// If the error is with a specific data type, the name of the data type.
     pub fn data_type(&self) -> Option<&str> {
-        self.0.get(b'd')
+        self.notice.get(b'd')
     }
 
     /// If the error is with a specific constraint, the name of the constraint.
     /// For this purpose, indexes are constraints, even if they weren't created
     /// with constraint syntax.
     pub fn constraint(&self) -> Option<&str> {
-        self.0.get(b'n')
+        self.notice.get(b'n')
     }
 
     /// The file name of the source-code location where this error was reported.
     pub fn file(&self) -> Option<&str> {
-        self.0.get(b'F')
+        self.notice.get(b'F')
     }
 
     /// The line number of the source-code location where this error was reported.
     pub fn line(&self) -> Option<usize> {
-        self.0.get_raw(b'L').and_then(atoi)
+        self.notice.get_raw(b'L').and_then(atoi)
     }
 
     /// The name of the source-code routine reporting this error.
     pub fn routine(&self) -> Option<&str> {
-        self.0.get(b'R')
+        self.notice.get(b'R')
+    }
+}
+
+impl PgDatabaseError {
+    pub(crate) fn new(notice: Notice, session_statements: bool, flavor: PgFlavor) -> Self {
+        // a statement prepared by this connection is gone: the connection was most likely
+        // moved to another server session by a pooler
+        let client_hint = if session_statements
+            && notice.code() == "26000"
+            && notice.message().starts_with("prepared statement \"sqlx_s_")
+        {
+            Some(
+                "if the connection goes through a pooler in transaction mode, such as \
+                 PgBouncer, set `PgConnectOptions::statement_cache_mode` to \
+                 `PgStatementCacheMode::Transaction`",
+            )
+        } else {
+            None
+        };
+
+        Self {
+            notice,
+            client_hint,
+            flavor,
+        }
+    }
+
+    // the server is shutting down (`57P01`, `57P02`, `57P03`), or has ended the session
+    pub(crate) fn is_server_shutdown(&self) -> bool {
+        matches!(self.severity(), PgSeverity::Fatal | PgSeverity::Panic)
+            || matches!(self.code(), "57P01" | "57P02" | "57P03")
     }
 }
 
@@ -155,7 +201,13 @@ impl Debug for PgDatabaseError {
 
 impl Display for PgDatabaseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str(self.message())
+        f.write_str(self.message())?;
+
+        if let Some(hint) = self.client_hint {
+            write!(f, " (hint: {})", hint)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -188,4 +240,11 @@ impl DatabaseError for PgDatabaseError {
     fn constraint(&self) -> Option<&str> {
         self.constraint()
     }
+
+    // CockroachDB and YugabyteDB may ask for a retry in the message of an error whose code
+    // does not say so
+    fn is_retryable(&self) -> bool {
+        crate::error::ErrorClass::from_sqlstate(self.code()).retryable
+            || self.flavor.asks_for_retry(self.message())
+    }
 }
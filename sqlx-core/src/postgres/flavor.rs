@@ -0,0 +1,100 @@
+use crate::database::{Capabilities, Database};
+use crate::postgres::Postgres;
+
+/// The kind of server a [`PgConnection`](super::PgConnection) is connected to.
+///
+/// It is returned by the [`server_flavor`](super::PgConnection::server_flavor) method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgFlavor {
+    /// PostgreSQL, or a server that could not be told apart from it.
+    Postgres,
+
+    /// CockroachDB, which speaks the protocol of PostgreSQL but has no `LISTEN` or `NOTIFY`,
+    /// asks for most failed transactions to be retried, and may give a user-defined type
+    /// another OID when it is recreated.
+    CockroachDb,
+
+    /// YugabyteDB, which runs the query layer of PostgreSQL 11 on a distributed storage
+    /// engine, and asks for transactions that conflict to be retried.
+    Yugabyte,
+}
+
+impl PgFlavor {
+    // the flavor of the server, from a parameter it reported while connecting: CockroachDB
+    // reports `crdb_version` next to a `server_version` that looks like PostgreSQL, e.g.
+    // `13.0.0`, and YugabyteDB adds its own version to `server_version`, e.g.
+    // `11.2-YB-2.8.0.0-b0`
+    pub(crate) fn from_parameter(name: &str, value: &str) -> Option<Self> {
+        match name {
+            "crdb_version" => Some(PgFlavor::CockroachDb),
+            "server_version" if value.contains("-YB-") => Some(PgFlavor::Yugabyte),
+
+            _ => None,
+        }
+    }
+
+    // the features the server supports
+    pub(crate) fn capabilities(self) -> Capabilities {
+        match self {
+            PgFlavor::CockroachDb => Postgres::CAPABILITIES - Capabilities::LISTEN_NOTIFY,
+            PgFlavor::Postgres | PgFlavor::Yugabyte => Postgres::CAPABILITIES,
+        }
+    }
+
+    // whether a user-defined type keeps its OID for the life of a connection, unless it is
+    // dropped; the distributed servers may assign another when their schema changes
+    pub(crate) fn has_stable_type_oids(self) -> bool {
+        self == PgFlavor::Postgres
+    }
+
+    // whether an error message asks for the transaction to be retried, for errors that are
+    // not reported as a serialization failure, e.g. CockroachDB's `restart transaction:
+    // TransactionRetryWithProtoRefreshError: ... RETRY_SERIALIZABLE` when it comes wrapped in
+    // the error of a failed `COMMIT`
+    pub(crate) fn asks_for_retry(self, message: &str) -> bool {
+        match self {
+            PgFlavor::CockroachDb => {
+                message.starts_with("restart transaction") || message.contains("RETRY_")
+            }
+
+            PgFlavor::Yugabyte => message.contains("Restart read required"),
+
+            PgFlavor::Postgres => false,
+        }
+    }
+}
+
+impl Default for PgFlavor {
+    fn default() -> Self {
+        PgFlavor::Postgres
+    }
+}
+
+#[test]
+fn it_detects_the_flavor_from_parameters() {
+    assert_eq!(
+        PgFlavor::from_parameter("crdb_version", "CockroachDB CCL v21.2.4"),
+        Some(PgFlavor::CockroachDb)
+    );
+
+    assert_eq!(
+        PgFlavor::from_parameter("server_version", "11.2-YB-2.8.0.0-b0"),
+        Some(PgFlavor::Yugabyte)
+    );
+
+    assert_eq!(PgFlavor::from_parameter("server_version", "13.3"), None);
+    assert_eq!(PgFlavor::from_parameter("TimeZone", "UTC"), None);
+
+    assert!(!PgFlavor::CockroachDb
+        .capabilities()
+        .contains(Capabilities::LISTEN_NOTIFY));
+    assert!(PgFlavor::Yugabyte
+        .capabilities()
+        .contains(Capabilities::LISTEN_NOTIFY));
+
+    assert!(PgFlavor::CockroachDb.asks_for_retry(
+        "restart transaction: TransactionRetryWithProtoRefreshError: \
+         TransactionRetryError: retry txn (RETRY_SERIALIZABLE)"
+    ));
+    assert!(!PgFlavor::Postgres.asks_for_retry("restart transaction"));
+}
@@ -0,0 +1,488 @@
+//! Writes bind values into the SQL of a query as literals, for connections that run every query
+//! with the simple query protocol; see
+//! [`PgConnectOptions::prefer_simple_protocol`](crate::postgres::PgConnectOptions::prefer_simple_protocol).
+
+use std::cmp;
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::common::{placeholders, SqlDialect};
+use crate::error::{BoxDynError, Error};
+use crate::postgres::quote::quote_literal;
+use crate::postgres::type_info::PgType;
+use crate::postgres::types::numeric::{PgNumeric, PgNumericSign};
+use crate::postgres::{PgArguments, PgTypeInfo, PgTypeKind};
+
+// days from 1970-01-01, the epoch of the civil calendar below, to 2000-01-01, that of Postgres
+const POSTGRES_EPOCH_DAYS: i64 = 10_957;
+
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// Replaces the placeholders of `sql` (`$1`, `$2`, ...) with the values bound in `arguments`.
+///
+/// Every value is written as a string literal, quoted for the `standard_conforming_strings` of
+/// the session, and cast to the type it was bound as, e.g. `('42'::pg_catalog."int4")`.
+/// Placeholders inside strings, quoted identifiers and comments are left alone.
+pub(crate) fn interpolate(
+    sql: &str,
+    arguments: &PgArguments,
+    standard_conforming_strings: bool,
+) -> Result<String, Error> {
+    let literals = arguments
+        .types
+        .iter()
+        .zip(arguments.buffer.values())
+        .enumerate()
+        .map(|(index, (ty, value))| {
+            literal(ty, value, standard_conforming_strings)
+                .map_err(|source| Error::ArgumentEncode { index, source })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut interpolated =
+        String::with_capacity(sql.len() + literals.iter().map(String::len).sum::<usize>());
+    let mut written = 0;
+
+    for range in placeholders(sql, SqlDialect::Postgres, !standard_conforming_strings) {
+        let literal = sql[range.start + 1..range.end]
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|index| literals.get(index))
+            .ok_or_else(|| {
+                Error::encode(format!(
+                    "query refers to {}, but {} arguments were bound",
+                    &sql[range.clone()],
+                    literals.len()
+                ))
+            })?;
+
+        interpolated.push_str(&sql[written..range.start]);
+
+        // in parentheses, so that e.g. `$1[1]` indexes the value rather than its type
+        interpolated.push('(');
+        interpolated.push_str(literal);
+        interpolated.push(')');
+
+        written = range.end;
+    }
+
+    interpolated.push_str(&sql[written..]);
+
+    Ok(interpolated)
+}
+
+// the value as a string literal, cast to its type
+fn literal(
+    ty: &PgTypeInfo,
+    value: Option<&[u8]>,
+    standard_conforming_strings: bool,
+) -> Result<String, BoxDynError> {
+    if matches!(
+        ty.0,
+        PgType::Custom(_) | PgType::DeclareWithName(_) | PgType::DeclareWithOid(_)
+    ) {
+        return Err(unsupported(ty));
+    }
+
+    let cast = if ty.0 == PgType::Unknown {
+        String::new()
+    } else {
+        format!("::pg_catalog.\"{}\"", ty.0.name())
+    };
+
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(format!("NULL{}", cast)),
+    };
+
+    if let PgTypeKind::Array(element) = ty.kind() {
+        return array(ty, element, value, standard_conforming_strings)
+            .map(|array| format!("{}{}", array, cast));
+    }
+
+    let text = text(ty, value)?;
+
+    Ok(format!(
+        "{}{}",
+        quote_literal(&text, standard_conforming_strings)?,
+        cast
+    ))
+}
+
+// a one-dimensional array as `ARRAY[...]`, or `'{}'` if it is empty
+fn array(
+    ty: &PgTypeInfo,
+    element: &PgTypeInfo,
+    mut value: &[u8],
+    standard_conforming_strings: bool,
+) -> Result<String, BoxDynError> {
+    let dimensions = read_i32(&mut value)?;
+
+    if dimensions == 0 {
+        return Ok("'{}'".to_owned());
+    }
+
+    // whether there are NULLs, then the type of the elements
+    let _flags = read_i32(&mut value)?;
+    let _element_oid = read_i32(&mut value)?;
+
+    let len = read_i32(&mut value)?;
+    let lower_bound = read_i32(&mut value)?;
+
+    if dimensions != 1 || lower_bound != 1 {
+        return Err(format!(
+            "{} with {} dimensions and a lower bound of {} is not supported with the simple \
+             query protocol",
+            ty, dimensions, lower_bound
+        )
+        .into());
+    }
+
+    if len == 0 {
+        return Ok("'{}'".to_owned());
+    }
+
+    let mut elements = Vec::with_capacity(cmp::max(len, 0) as usize);
+
+    for _ in 0..len {
+        let element_len = read_i32(&mut value)?;
+
+        let element_value = if element_len < 0 {
+            None
+        } else {
+            let (element_value, rest) = split_at(value, element_len as usize)?;
+            value = rest;
+
+            Some(element_value)
+        };
+
+        elements.push(literal(
+            element,
+            element_value,
+            standard_conforming_strings,
+        )?);
+    }
+
+    Ok(format!("ARRAY[{}]", elements.join(", ")))
+}
+
+// the value in the text format of its type
+fn text(ty: &PgTypeInfo, value: &[u8]) -> Result<String, BoxDynError> {
+    let text = match (&ty.0, value.len()) {
+        (PgType::Bool, 1) => (if value[0] != 0 { "t" } else { "f" }).to_owned(),
+        (PgType::Int2, 2) => BigEndian::read_i16(value).to_string(),
+        (PgType::Int4, 4) => BigEndian::read_i32(value).to_string(),
+        (PgType::Int8, 8) => BigEndian::read_i64(value).to_string(),
+        (PgType::Oid, 4) => BigEndian::read_u32(value).to_string(),
+        (PgType::Float4, 4) => float(f64::from(BigEndian::read_f32(value)), || {
+            BigEndian::read_f32(value).to_string()
+        }),
+        (PgType::Float8, 8) => float(BigEndian::read_f64(value), || {
+            BigEndian::read_f64(value).to_string()
+        }),
+        (PgType::Numeric, _) => numeric(PgNumeric::decode(value)?),
+
+        (PgType::Text, _)
+        | (PgType::Varchar, _)
+        | (PgType::Bpchar, _)
+        | (PgType::Name, _)
+        | (PgType::Unknown, _)
+        | (PgType::Json, _) => std::str::from_utf8(value)?.to_owned(),
+
+        // JSONB is prefixed with a version byte
+        (PgType::Jsonb, _) if value.first() == Some(&1) => {
+            std::str::from_utf8(&value[1..])?.to_owned()
+        }
+
+        (PgType::Bytea, _) => {
+            let mut text = String::with_capacity(2 + value.len() * 2);
+            text.push_str("\\x");
+
+            for byte in value {
+                let _ = write!(text, "{:02x}", byte);
+            }
+
+            text
+        }
+
+        (PgType::Uuid, 16) => {
+            let mut text = String::with_capacity(36);
+
+            for (i, byte) in value.iter().enumerate() {
+                if matches!(i, 4 | 6 | 8 | 10) {
+                    text.push('-');
+                }
+
+                let _ = write!(text, "{:02x}", byte);
+            }
+
+            text
+        }
+
+        (PgType::Date, 4) => match BigEndian::read_i32(value) {
+            i32::MAX => "infinity".to_owned(),
+            i32::MIN => "-infinity".to_owned(),
+            days => date(i64::from(days)),
+        },
+
+        (PgType::Time, 8) => time(BigEndian::read_i64(value)),
+
+        (PgType::Timestamp, 8) | (PgType::Timestamptz, 8) => match BigEndian::read_i64(value) {
+            i64::MAX => "infinity".to_owned(),
+            i64::MIN => "-infinity".to_owned(),
+            micros => timestamp(micros, ty.0 == PgType::Timestamptz),
+        },
+
+        (PgType::Interval, 16) => format!(
+            "{} mons {} days {} microseconds",
+            BigEndian::read_i32(&value[12..]),
+            BigEndian::read_i32(&value[8..]),
+            BigEndian::read_i64(value)
+        ),
+
+        _ => return Err(unsupported(ty)),
+    };
+
+    Ok(text)
+}
+
+fn float(value: f64, finite: impl FnOnce() -> String) -> String {
+    if value.is_nan() {
+        "NaN".to_owned()
+    } else if value.is_infinite() && value > 0.0 {
+        "Infinity".to_owned()
+    } else if value.is_infinite() {
+        "-Infinity".to_owned()
+    } else {
+        finite()
+    }
+}
+
+fn numeric(numeric: PgNumeric) -> String {
+    let (sign, digits, weight, scale) = match numeric {
+        PgNumeric::NotANumber => return "NaN".to_owned(),
+        PgNumeric::Infinity(PgNumericSign::Positive) => return "Infinity".to_owned(),
+        PgNumeric::Infinity(PgNumericSign::Negative) => return "-Infinity".to_owned(),
+
+        PgNumeric::Number {
+            sign,
+            digits,
+            weight,
+            scale,
+        } => (sign, digits, weight, scale),
+    };
+
+    // each digit is base 10000, the first one being multiplied by 10000 ^ weight
+    let digit = |i: i32| -> i16 {
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| digits.get(i))
+            .copied()
+            .unwrap_or(0)
+    };
+
+    let mut text = String::new();
+
+    if sign == PgNumericSign::Negative {
+        text.push('-');
+    }
+
+    if weight < 0 {
+        text.push('0');
+    } else {
+        let _ = write!(text, "{}", digit(0));
+
+        for i in 1..=i32::from(weight) {
+            let _ = write!(text, "{:04}", digit(i));
+        }
+    }
+
+    if scale > 0 {
+        let mut fraction = String::new();
+        let mut i = i32::from(weight) + 1;
+
+        while fraction.len() < scale as usize {
+            let _ = write!(fraction, "{:04}", digit(i));
+            i += 1;
+        }
+
+        fraction.truncate(scale as usize);
+
+        text.push('.');
+        text.push_str(&fraction);
+    }
+
+    text
+}
+
+// `days` after 2000-01-01
+fn date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days + POSTGRES_EPOCH_DAYS);
+
+    // there is no year 0: 1 BC comes right before 1 AD
+    if year > 0 {
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    } else {
+        format!("{:04}-{:02}-{:02} BC", 1 - year, month, day)
+    }
+}
+
+// `micros` after midnight
+fn time(micros: i64) -> String {
+    let seconds = micros / 1_000_000;
+
+    format!(
+        "{:02}:{:02}:{:02}.{:06}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        micros % 1_000_000
+    )
+}
+
+// `micros` after 2000-01-01 00:00:00, in UTC if `utc`
+fn timestamp(micros: i64, utc: bool) -> String {
+    let days = micros.div_euclid(MICROS_PER_DAY);
+    let date = date(days);
+    let time = time(micros.rem_euclid(MICROS_PER_DAY));
+    let zone = if utc { "+00" } else { "" };
+
+    match date.strip_suffix(" BC") {
+        Some(date) => format!("{} {}{} BC", date, time, zone),
+        None => format!("{} {}{}", date, time, zone),
+    }
+}
+
+// the year, month and day of the proleptic Gregorian calendar `days` after 1970-01-01
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+fn read_i32(buf: &mut &[u8]) -> Result<i32, BoxDynError> {
+    let (value, rest) = split_at(buf, 4)?;
+    *buf = rest;
+
+    Ok(BigEndian::read_i32(value))
+}
+
+fn split_at(buf: &[u8], len: usize) -> Result<(&[u8], &[u8]), BoxDynError> {
+    if buf.len() < len {
+        return Err("unexpected end of an encoded array".into());
+    }
+
+    Ok(buf.split_at(len))
+}
+
+fn unsupported(ty: &PgTypeInfo) -> BoxDynError {
+    format!(
+        "values of type {} cannot be written into SQL, which the simple query protocol \
+         requires; cast a supported type to it in the query instead",
+        ty
+    )
+    .into()
+}
+
+#[cfg(test)]
+fn interpolated(sql: &str, arguments: PgArguments) -> String {
+    interpolate(sql, &arguments, true).unwrap()
+}
+
+#[test]
+fn it_interpolates_bind_values() {
+    let mut arguments = PgArguments::default();
+    arguments.add(-42_i32);
+    arguments.add("it's $1");
+    arguments.add(None::<i64>);
+    arguments.add(&[1_u8, 0xab][..]);
+    arguments.add(vec![1.5_f64, f64::NAN]);
+
+    assert_eq!(
+        interpolated("SELECT $1, '$1', $2, $3 -- $4\n, $4, $5[1], $1", arguments),
+        "SELECT ('-42'::pg_catalog.\"int4\"), '$1', ('it''s $1'::pg_catalog.\"text\"), \
+         (NULL::pg_catalog.\"int8\") -- $4\n, ('\\x01ab'::pg_catalog.\"bytea\"), \
+         (ARRAY['1.5'::pg_catalog.\"float8\", 'NaN'::pg_catalog.\"float8\"]\
+         ::pg_catalog.\"_float8\")[1], ('-42'::pg_catalog.\"int4\")"
+    );
+
+    let mut arguments = PgArguments::default();
+    arguments.add(Vec::<i32>::new());
+
+    assert_eq!(
+        interpolated("SELECT $1", arguments),
+        "SELECT ('{}'::pg_catalog.\"_int4\")"
+    );
+
+    // a backslash needs an escape string without standard conforming strings
+    let mut arguments = PgArguments::default();
+    arguments.add("a\\b");
+
+    assert_eq!(
+        interpolate("SELECT $1", &arguments, false).unwrap(),
+        "SELECT (E'a\\\\b'::pg_catalog.\"text\")"
+    );
+}
+
+#[test]
+fn it_refuses_what_it_cannot_interpolate() {
+    let mut arguments = PgArguments::default();
+    arguments.add(1_i32);
+
+    assert!(matches!(
+        interpolate("SELECT $2", &arguments, true),
+        Err(Error::Encode(_))
+    ));
+
+    let mut arguments = PgArguments::default();
+    arguments.add(1_i32);
+    arguments.add_with_type("happy", PgTypeInfo::with_name("mood"));
+
+    assert!(matches!(
+        interpolate("SELECT $1, $2", &arguments, true),
+        Err(Error::ArgumentEncode { index: 1, .. })
+    ));
+}
+
+#[test]
+fn it_renders_dates_and_numbers_as_text() {
+    assert_eq!(date(0), "2000-01-01");
+    assert_eq!(date(-730_119), "0001-01-01");
+    assert_eq!(date(-730_120), "0001-12-31 BC");
+    assert_eq!(date(8_460), "2023-03-01");
+
+    assert_eq!(time(45_296_000_007), "12:34:56.000007");
+    assert_eq!(timestamp(-1, true), "1999-12-31 23:59:59.999999+00");
+
+    let number = |sign, digits: &[i16], weight, scale| {
+        numeric(PgNumeric::Number {
+            sign,
+            digits: digits.to_vec(),
+            weight,
+            scale,
+        })
+    };
+
+    assert_eq!(number(PgNumericSign::Positive, &[12, 3400], 1, 0), "123400");
+    assert_eq!(number(PgNumericSign::Negative, &[1, 2500], 0, 2), "-1.25");
+    assert_eq!(number(PgNumericSign::Positive, &[50], -1, 4), "0.0050");
+    assert_eq!(number(PgNumericSign::Positive, &[], 0, 0), "0");
+}
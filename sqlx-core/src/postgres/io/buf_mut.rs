@@ -27,13 +27,16 @@ impl PgBufMutExt for Vec<u8> {
         self[offset..(offset + 4)].copy_from_slice(&size.to_be_bytes());
     }
 
-    // writes a statement name by ID
+    // writes a statement name by ID, where 0 is the unnamed statement
     #[inline]
     fn put_statement_name(&mut self, id: u32) {
-        // N.B. if you change this don't forget to update it in ../describe.rs
-        self.extend(b"sqlx_s_");
+        if id != 0 {
+            // N.B. if you change this don't forget to update it in ../describe.rs
+            // and ../error.rs
+            self.extend(b"sqlx_s_");
 
-        itoa::write(&mut *self, id).unwrap();
+            itoa::write(&mut *self, id).unwrap();
+        }
 
         self.push(0);
     }
@@ -1,17 +1,31 @@
+use crate::connection::Connection;
+use crate::database::Capabilities;
 use crate::describe::Describe;
 use crate::error::Error;
+use crate::events::CloseReason;
 use crate::executor::{Execute, Executor};
 use crate::pool::PoolOptions;
 use crate::pool::{Pool, PoolConnection};
 use crate::postgres::message::{MessageFormat, Notification};
 use crate::postgres::{PgConnection, PgQueryResult, PgRow, PgStatement, PgTypeInfo, Postgres};
+use crate::HashMap;
+use bytes::Bytes;
 use either::Either;
-use futures_channel::mpsc;
 use futures_core::future::BoxFuture;
 use futures_core::stream::{BoxStream, Stream};
+use futures_util::future;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
 use std::fmt::{self, Debug};
+use std::future::Future;
 use std::io;
+use std::marker::PhantomData;
+use std::mem;
+use std::pin::Pin;
 use std::str::from_utf8;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
 
 /// A stream of asynchronous notifications from Postgres.
 ///
@@ -19,17 +33,164 @@ use std::str::from_utf8;
 /// connection being used ever dies, this listener will detect that event, create a
 /// new connection, will re-subscribe to all of the originally specified channels, and will resume
 /// operations as normal.
+///
+/// Notifications are read from the connection only as they are asked for, so while they are not
+/// received, e.g. because the consumer of [`into_stream`](Self::into_stream) has stalled, they
+/// wait in the queue of the server. Only those that arrive while the listener is used to
+/// run a query are buffered by the listener, which can be capped with
+/// [`set_max_buffered_notifications`](Self::set_max_buffered_notifications).
+///
+/// Notifications can also be fanned out to typed [subscriptions](Self::subscribe) of a channel.
 pub struct PgListener {
-    pool: Pool<Postgres>,
+    pub(super) pool: Pool<Postgres>,
     connection: Option<PoolConnection<Postgres>>,
-    buffer_rx: mpsc::UnboundedReceiver<Notification>,
-    buffer_tx: Option<mpsc::UnboundedSender<Notification>>,
+    // held by the connection while there is one
+    buffer: Option<NotificationBuffer>,
     channels: Vec<String>,
+    pub(super) subscriptions: Arc<Mutex<Subscriptions>>,
+}
+
+// notifications that arrived while a `PgListener` ran a query, to be returned by `try_recv`,
+// or the events of a subscription, until its stream is polled
+pub(crate) struct NotificationBuffer<T = Notification> {
+    queue: VecDeque<T>,
+    capacity: Option<usize>,
+    dropped: u64,
+}
+
+impl<T> NotificationBuffer<T> {
+    pub(super) fn new(capacity: Option<usize>) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            capacity,
+            dropped: 0,
+        }
+    }
+
+    // buffers `notification`, dropping the oldest one if the buffer is full
+    pub(crate) fn push(&mut self, notification: T) {
+        self.queue.push_back(notification);
+        self.truncate();
+    }
+
+    // caps the buffer at `capacity`, dropping the oldest notifications beyond it
+    pub(super) fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity;
+        self.truncate();
+    }
+
+    fn truncate(&mut self) {
+        let capacity = match self.capacity {
+            Some(capacity) => capacity,
+            None => return,
+        };
+
+        while self.queue.len() > capacity {
+            self.queue.pop_front();
+            self.dropped += 1;
+
+            // a flood would repeat the warning for every notification, so it is logged after
+            // 1, 2, 4, 8, .. notifications were dropped
+            if self.dropped.is_power_of_two() {
+                log::warn!(
+                    "PgListener buffer of {} notifications is full; {} dropped so far",
+                    capacity,
+                    self.dropped
+                );
+            }
+        }
+    }
+
+    pub(crate) fn dropped(&self) -> u64 {
+        self.dropped
+    }
 }
 
 /// An asynchronous notification from Postgres.
 pub struct PgNotification(Notification);
 
+// the subscriptions of a `PgListener`, shared with each `PgSubscription` so that dropping it
+// tells the listener to stop listening
+#[derive(Default)]
+pub(super) struct Subscriptions {
+    pub(super) channels: HashMap<String, Vec<Arc<Mutex<Subscriber>>>>,
+    // channels whose last subscription was dropped, to be unlistened by the listener
+    unsubscribed: Vec<String>,
+    // channels subscribed to through a `PgSharedListener`, to be listened to by the listener
+    pub(super) listen: Vec<String>,
+    pub(super) capacity: Option<usize>,
+    // the listener waiting for a notification, woken to listen or unlisten
+    pub(super) listener: Option<Waker>,
+    // set once the listener of a `PgSharedListener` stopped, or is to stop
+    pub(super) closed: bool,
+}
+
+pub(super) struct Subscriber {
+    pub(super) events: NotificationBuffer<SubscriptionEvent>,
+    pub(super) waker: Option<Waker>,
+    // set once the listener listens on the channel
+    pub(super) listening: bool,
+    // set once the listener is dropped, so that the stream ends
+    pub(super) closed: bool,
+}
+
+impl Subscriber {
+    fn push(&mut self, event: SubscriptionEvent) {
+        self.events.push(event);
+
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn close(&mut self) {
+        self.closed = true;
+
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn set_listening(&mut self) {
+        self.listening = true;
+
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+// an event waiting in a subscription; the payload is only parsed as the stream is polled
+#[derive(Clone)]
+pub(super) enum SubscriptionEvent {
+    Notification(Bytes),
+    Reconnected,
+}
+
+/// A stream of the notifications on one channel, with their payloads parsed from JSON; see
+/// [`PgListener::subscribe`].
+///
+/// The listener stops listening on the channel once every subscription to it is dropped,
+/// unless it was also [listened to](PgListener::listen). The stream ends when the listener is
+/// dropped.
+pub struct PgSubscription<T> {
+    pub(super) subscriptions: Arc<Mutex<Subscriptions>>,
+    pub(super) subscriber: Arc<Mutex<Subscriber>>,
+    pub(super) channel: String,
+    pub(super) ty: PhantomData<fn() -> T>,
+}
+
+/// An item of a [`PgSubscription`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgSubscriptionEvent<T> {
+    /// A notification on the channel, with its payload parsed from JSON.
+    Notification(T),
+
+    /// The listener lost its connection and reconnected; notifications sent while it was
+    /// disconnected were missed, so whatever they signal should be read again.
+    Reconnected,
+}
+
 impl PgListener {
     pub async fn connect(uri: &str) -> Result<Self, Error> {
         // Create a pool of 1 without timeouts (as they don't apply here)
@@ -48,19 +209,99 @@ impl PgListener {
         // Pull out an initial connection
         let mut connection = pool.acquire().await?;
 
+        // CockroachDB accepts neither `LISTEN` nor `UNLISTEN`
+        if !connection
+            .capabilities()
+            .contains(Capabilities::LISTEN_NOTIFY)
+        {
+            return Err(Error::Configuration(
+                format!(
+                    "cannot listen for notifications: {:?} does not support LISTEN",
+                    connection.server_flavor()
+                )
+                .into(),
+            ));
+        }
+
         // Setup a notification buffer
-        let (sender, receiver) = mpsc::unbounded();
-        connection.stream.notifications = Some(sender);
+        connection.stream.notifications = Some(NotificationBuffer::new(None));
 
         Ok(Self {
             pool: pool.clone(),
             connection: Some(connection),
-            buffer_rx: receiver,
-            buffer_tx: None,
+            buffer: None,
             channels: Vec::new(),
+            subscriptions: Arc::default(),
         })
     }
 
+    // a listener that connects once it is first driven, for a `PgSharedListener`
+    pub(super) fn lazy(pool: Pool<Postgres>, subscriptions: Arc<Mutex<Subscriptions>>) -> Self {
+        let buffer = NotificationBuffer::new(subscriptions.lock().capacity);
+
+        Self {
+            pool,
+            connection: None,
+            buffer: Some(buffer),
+            channels: Vec::new(),
+            subscriptions,
+        }
+    }
+
+    /// Caps the number of notifications buffered while the listener is used to run a query,
+    /// dropping the oldest ones beyond it. Unlimited by default, or with `None`.
+    ///
+    /// Notifications are otherwise only read as they are received, so only the notifications
+    /// that arrive while a query runs on the listener are buffered; they may be many, e.g. if
+    /// a trigger notifies for each row the query changes. A warning is logged as notifications
+    /// are dropped, and [`dropped_notifications`](Self::dropped_notifications) counts them.
+    ///
+    /// The cap also applies to the notifications waiting in each
+    /// [subscription](Self::subscribe), which fills up while its stream is not polled.
+    pub fn set_max_buffered_notifications(&mut self, max: Option<usize>) {
+        self.buffer().set_capacity(max);
+
+        let mut subscriptions = self.subscriptions.lock();
+        subscriptions.capacity = max;
+
+        for subscriber in subscriptions.channels.values().flatten() {
+            subscriber.lock().events.set_capacity(max);
+        }
+    }
+
+    /// The number of notifications buffered while the listener was used to run a query, which
+    /// are returned first by [`recv`](Self::recv) and [`try_recv`](Self::try_recv).
+    pub fn buffered_notifications(&self) -> usize {
+        self.buffer_ref().queue.len()
+    }
+
+    /// The number of notifications dropped from the full buffer; see
+    /// [`set_max_buffered_notifications`](Self::set_max_buffered_notifications).
+    ///
+    /// The count is kept across reconnections, and is also in the
+    /// [stats](crate::connection::Connection::stats) of the connection.
+    pub fn dropped_notifications(&self) -> u64 {
+        self.buffer_ref().dropped
+    }
+
+    fn buffer(&mut self) -> &mut NotificationBuffer {
+        let buffer = match &mut self.connection {
+            Some(connection) => connection.stream.notifications.as_mut(),
+            None => self.buffer.as_mut(),
+        };
+
+        buffer.expect("(bug) PgListener without a notification buffer")
+    }
+
+    fn buffer_ref(&self) -> &NotificationBuffer {
+        let buffer = match &self.connection {
+            Some(connection) => connection.stream.notifications.as_ref(),
+            None => self.buffer.as_ref(),
+        };
+
+        buffer.expect("(bug) PgListener without a notification buffer")
+    }
+
     /// Starts listening for notifications on a channel.
     /// The channel name is quoted here to ensure case sensitivity.
     pub async fn listen(&mut self, channel: &str) -> Result<(), Error> {
@@ -92,10 +333,14 @@ impl PgListener {
 
     /// Stops listening for notifications on a channel.
     /// The channel name is quoted here to ensure case sensitivity.
+    ///
+    /// The listener keeps listening on a channel that is still [subscribed](Self::subscribe) to.
     pub async fn unlisten(&mut self, channel: &str) -> Result<(), Error> {
-        self.connection()
-            .execute(&*format!(r#"UNLISTEN "{}""#, ident(channel)))
-            .await?;
+        if !self.subscriptions.lock().channels.contains_key(channel) {
+            self.connection()
+                .execute(&*format!(r#"UNLISTEN "{}""#, ident(channel)))
+                .await?;
+        }
 
         if let Some(pos) = self.channels.iter().position(|s| s == channel) {
             self.channels.remove(pos);
@@ -104,31 +349,237 @@ impl PgListener {
         Ok(())
     }
 
-    /// Stops listening for notifications on all channels.
+    /// Stops listening for notifications on all channels, except those still
+    /// [subscribed](Self::subscribe) to.
     pub async fn unlisten_all(&mut self) -> Result<(), Error> {
-        self.connection().execute("UNLISTEN *").await?;
+        let query = {
+            let subscriptions = self.subscriptions.lock();
+
+            if subscriptions.channels.is_empty() {
+                "UNLISTEN *".to_owned()
+            } else {
+                build_unlisten_all_query(
+                    self.channels
+                        .iter()
+                        .filter(|channel| !subscriptions.channels.contains_key(*channel)),
+                )
+            }
+        };
+
+        self.connection().execute(&*query).await?;
 
         self.channels.clear();
 
         Ok(())
     }
 
+    /// Subscribes to the notifications on a channel, listening on it if the listener does not
+    /// already, and returns a stream of them with their payloads parsed from JSON into `T`.
+    ///
+    /// Each subscription to a channel gets its own copy of every notification. A payload that
+    /// is not valid JSON for `T` is returned as an [`Error::Decode`] by the stream, which goes
+    /// on with the next notification. Once every subscription to the channel is dropped, the
+    /// listener stops listening on it, unless it was also [listened to](Self::listen).
+    ///
+    /// Notifications are handed out to the subscriptions while the listener is driven by
+    /// [`recv`](Self::recv), [`try_recv`](Self::try_recv) or [`into_stream`](Self::into_stream),
+    /// which return the notifications of channels that were listened to, but not those of
+    /// channels that are only subscribed to. If the listener reconnects, it listens on every
+    /// channel again, and each subscription gets a [`PgSubscriptionEvent::Reconnected`].
+    ///
+    /// A subscription buffers its notifications until its stream is polled, up to
+    /// [`set_max_buffered_notifications`](Self::set_max_buffered_notifications), dropping the
+    /// oldest ones beyond it.
+    ///
+    /// ```rust,ignore
+    /// #[derive(serde::Deserialize)]
+    /// struct OrderPlaced {
+    ///     id: i64,
+    /// }
+    ///
+    /// let mut orders = listener.subscribe::<OrderPlaced>("orders").await?;
+    ///
+    /// sqlx_rt::spawn(async move {
+    ///     while let Some(event) = orders.next().await {
+    ///         match event? {
+    ///             PgSubscriptionEvent::Notification(order) => ship(order.id).await,
+    ///             PgSubscriptionEvent::Reconnected => ship_unshipped_orders().await,
+    ///         }
+    ///     }
+    /// });
+    ///
+    /// // drive the listener
+    /// listener.recv().await?;
+    /// ```
+    pub async fn subscribe<T: DeserializeOwned>(
+        &mut self,
+        channel: &str,
+    ) -> Result<PgSubscription<T>, Error> {
+        self.connect_if_needed().await?;
+
+        let listening = self.channels.iter().any(|c| c == channel)
+            || self.subscriptions.lock().channels.contains_key(channel);
+
+        if !listening {
+            self.connection()
+                .execute(&*format!(r#"LISTEN "{}""#, ident(channel)))
+                .await?;
+        }
+
+        let mut subscriptions = self.subscriptions.lock();
+
+        let subscriber = Arc::new(Mutex::new(Subscriber {
+            events: NotificationBuffer::new(subscriptions.capacity),
+            waker: None,
+            listening: true,
+            closed: false,
+        }));
+
+        subscriptions
+            .channels
+            .entry(channel.to_owned())
+            .or_default()
+            .push(Arc::clone(&subscriber));
+
+        Ok(PgSubscription {
+            subscriptions: Arc::clone(&self.subscriptions),
+            subscriber,
+            channel: channel.to_owned(),
+            ty: PhantomData,
+        })
+    }
+
     #[inline]
     async fn connect_if_needed(&mut self) -> Result<(), Error> {
         if self.connection.is_none() {
             let mut connection = self.pool.acquire().await?;
-            connection.stream.notifications = self.buffer_tx.take();
+            connection.stream.notifications = self.buffer.take();
+
+            let channels: Vec<String> = {
+                let subscriptions = self.subscriptions.lock();
+
+                let subscribed = subscriptions
+                    .channels
+                    .keys()
+                    .filter(|channel| !self.channels.contains(channel));
+
+                self.channels.iter().chain(subscribed).cloned().collect()
+            };
 
             connection
-                .execute(&*build_listen_all_query(&self.channels))
+                .execute(&*build_listen_all_query(&channels))
                 .await?;
 
             self.connection = Some(connection);
+
+            let subscriptions = self.subscriptions.lock();
+
+            for subscriber in channels
+                .iter()
+                .filter_map(|channel| subscriptions.channels.get(channel))
+                .flatten()
+            {
+                let mut subscriber = subscriber.lock();
+
+                // notifications sent while the connection was lost are gone; a subscription
+                // of a `PgSharedListener` may only now be listened to, and missed none
+                if subscriber.listening {
+                    subscriber.push(SubscriptionEvent::Reconnected);
+                } else {
+                    subscriber.set_listening();
+                }
+            }
         }
 
         Ok(())
     }
 
+    // stops listening on the channels whose last subscription was dropped, and starts
+    // listening on those subscribed to through a `PgSharedListener`
+    async fn sync_subscriptions(&mut self) -> Result<(), Error> {
+        let (unlisten, listen): (Vec<String>, Vec<String>) = {
+            let mut subscriptions = self.subscriptions.lock();
+            let unsubscribed = mem::take(&mut subscriptions.unsubscribed);
+            let subscribed = mem::take(&mut subscriptions.listen);
+
+            // a channel may have been subscribed to again since, or unsubscribed from
+            let unlisten = unsubscribed
+                .into_iter()
+                .filter(|channel| {
+                    !subscriptions.channels.contains_key(channel)
+                        && !self.channels.contains(channel)
+                })
+                .collect();
+
+            let listen = subscribed
+                .into_iter()
+                .filter(|channel| subscriptions.channels.contains_key(channel))
+                .collect();
+
+            (unlisten, listen)
+        };
+
+        if unlisten.is_empty() && listen.is_empty() {
+            return Ok(());
+        }
+
+        let query = build_unlisten_all_query(&unlisten) + &build_listen_all_query(&listen);
+        self.connection().execute(&*query).await?;
+
+        let subscriptions = self.subscriptions.lock();
+
+        for subscriber in listen
+            .iter()
+            .filter_map(|channel| subscriptions.channels.get(channel))
+            .flatten()
+        {
+            subscriber.lock().set_listening();
+        }
+
+        Ok(())
+    }
+
+    // hands `notification` out to the subscriptions of its channel, and returns it unless
+    // its channel is only subscribed to
+    fn dispatch(&mut self, notification: Notification) -> Option<Notification> {
+        let channel = match from_utf8(&notification.channel) {
+            Ok(channel) => channel,
+            Err(_) => return Some(notification),
+        };
+
+        let subscriptions = self.subscriptions.lock();
+
+        if let Some(subscribers) = subscriptions.channels.get(channel) {
+            for subscriber in subscribers {
+                subscriber.lock().push(SubscriptionEvent::Notification(
+                    notification.payload.clone(),
+                ));
+            }
+
+            if !self.channels.iter().any(|c| c == channel) {
+                return None;
+            }
+        }
+
+        drop(subscriptions);
+
+        Some(notification)
+    }
+
+    // drops the connection, to reconnect on the next call to `try_recv`
+    pub(super) fn lose_connection(&mut self, error: &Error) {
+        if let Some(mut connection) = self.connection.take() {
+            self.buffer = connection.stream.notifications.take();
+
+            // detached rather than returned to the pool, so that the listener of connection
+            // events is told at once why it was lost
+            let events = connection.detach().take_events();
+
+            events.error(error);
+            events.closed(CloseReason::Broken);
+        }
+    }
+
     #[inline]
     fn connection(&mut self) -> &mut PgConnection {
         self.connection.as_mut().unwrap()
@@ -196,24 +647,61 @@ impl PgListener {
     /// # }).unwrap();
     /// ```
     pub async fn try_recv(&mut self) -> Result<Option<PgNotification>, Error> {
-        // Flush the buffer first, if anything
-        // This would only fill up if this listener is used as a connection
-        if let Ok(Some(notification)) = self.buffer_rx.try_next() {
-            return Ok(Some(PgNotification(notification)));
-        }
-
         loop {
+            // the pool of a `PgSharedListener` was closed or dropped
+            if self.subscriptions.lock().closed {
+                return Ok(None);
+            }
+
+            // Flush the buffer first, if anything
+            // This would only fill up if this listener is used as a connection
+            while let Some(notification) = self.buffer().queue.pop_front() {
+                if let Some(notification) = self.dispatch(notification) {
+                    return Ok(Some(PgNotification(notification)));
+                }
+            }
+
             // Ensure we have an active connection to work with.
             self.connect_if_needed().await?;
 
-            let message = match self.connection().stream.recv_unchecked().await {
+            match self.sync_subscriptions().await {
+                Err(Error::Io(err)) if err.kind() == io::ErrorKind::ConnectionAborted => {
+                    self.lose_connection(&Error::Io(err));
+
+                    return Ok(None);
+                }
+
+                Err(error) => return Err(error),
+
+                // notifications may have been buffered meanwhile
+                Ok(()) if self.buffered_notifications() > 0 => continue,
+
+                Ok(()) => {}
+            }
+
+            // reading a message can be cancelled, to stop listening on a channel as soon as
+            // its last subscription is dropped, or to start listening on one subscribed to
+            let subscriptions = Arc::clone(&self.subscriptions);
+
+            let message = {
+                let changed = wait_for_changes(&subscriptions);
+                let recv = self.connection().stream.recv_unchecked();
+
+                futures_util::pin_mut!(changed, recv);
+
+                match future::select(recv, changed).await {
+                    future::Either::Left((message, _)) => message,
+                    future::Either::Right(_) => continue,
+                }
+            };
+
+            let message = match message {
                 Ok(message) => message,
 
                 // The connection is dead, ensure that it is dropped,
                 // update self state, and loop to try again.
                 Err(Error::Io(err)) if err.kind() == io::ErrorKind::ConnectionAborted => {
-                    self.buffer_tx = self.connection().stream.notifications.take();
-                    self.connection = None;
+                    self.lose_connection(&Error::Io(err));
 
                     // lost connection
                     return Ok(None);
@@ -228,7 +716,9 @@ impl PgListener {
             match message.format {
                 // We've received an async notification, return it.
                 MessageFormat::NotificationResponse => {
-                    return Ok(Some(PgNotification(message.decode()?)));
+                    if let Some(notification) = self.dispatch(message.decode()?) {
+                        return Ok(Some(PgNotification(notification)));
+                    }
                 }
 
                 // Mark the connection as ready for another query
@@ -257,6 +747,117 @@ impl PgListener {
     }
 }
 
+impl Drop for PgListener {
+    fn drop(&mut self) {
+        // ends the stream of every subscription
+        for subscriber in self.subscriptions.lock().channels.values().flatten() {
+            subscriber.lock().close();
+        }
+    }
+}
+
+// resolves once the last subscription to a channel is dropped, a channel is subscribed to
+// through a `PgSharedListener`, or its pool is closed
+fn wait_for_changes(subscriptions: &Mutex<Subscriptions>) -> impl Future<Output = ()> + '_ {
+    future::poll_fn(move |cx| {
+        let mut subscriptions = subscriptions.lock();
+
+        if subscriptions.unsubscribed.is_empty()
+            && subscriptions.listen.is_empty()
+            && !subscriptions.closed
+        {
+            subscriptions.listener = Some(cx.waker().clone());
+
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    })
+}
+
+impl<T: DeserializeOwned> Stream for PgSubscription<T> {
+    type Item = Result<PgSubscriptionEvent<T>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut subscriber = self.subscriber.lock();
+
+        match subscriber.events.queue.pop_front() {
+            Some(SubscriptionEvent::Notification(payload)) => Poll::Ready(Some(
+                serde_json::from_slice(&payload)
+                    .map(PgSubscriptionEvent::Notification)
+                    .map_err(Error::decode),
+            )),
+
+            Some(SubscriptionEvent::Reconnected) => {
+                Poll::Ready(Some(Ok(PgSubscriptionEvent::Reconnected)))
+            }
+
+            None if subscriber.closed => Poll::Ready(None),
+
+            None => {
+                subscriber.waker = Some(cx.waker().clone());
+
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> PgSubscription<T> {
+    /// The channel subscribed to.
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// The number of notifications waiting in this subscription, until its stream is polled.
+    pub fn buffered_notifications(&self) -> usize {
+        self.subscriber.lock().events.queue.len()
+    }
+
+    /// The number of notifications dropped from this full subscription; see
+    /// [`PgListener::set_max_buffered_notifications`].
+    pub fn dropped_notifications(&self) -> u64 {
+        self.subscriber.lock().events.dropped
+    }
+}
+
+impl<T> Drop for PgSubscription<T> {
+    fn drop(&mut self) {
+        let mut subscriptions = self.subscriptions.lock();
+
+        let last = match subscriptions.channels.get_mut(&self.channel) {
+            Some(subscribers) => {
+                subscribers.retain(|subscriber| !Arc::ptr_eq(subscriber, &self.subscriber));
+                subscribers.is_empty()
+            }
+
+            None => false,
+        };
+
+        if last {
+            subscriptions.channels.remove(&self.channel);
+            subscriptions.unsubscribed.push(self.channel.clone());
+
+            if let Some(listener) = subscriptions.listener.take() {
+                listener.wake();
+            }
+        }
+    }
+}
+
+impl<T> Debug for PgSubscription<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgSubscription")
+            .field("channel", &self.channel)
+            .field("buffered_notifications", &self.buffered_notifications())
+            .finish()
+    }
+}
+
+impl PgListener {
+    impl_executor_methods!(Postgres, &mut self => self);
+}
+
 impl<'c> Executor<'c> for &'c mut PgListener {
     type Database = Postgres;
 
@@ -343,7 +944,7 @@ impl Debug for PgNotification {
     }
 }
 
-fn ident(mut name: &str) -> String {
+pub(super) fn ident(mut name: &str) -> String {
     // If the input string contains a NUL byte, we should truncate the
     // identifier.
     if let Some(index) = name.find('\0') {
@@ -355,8 +956,20 @@ fn ident(mut name: &str) -> String {
 }
 
 fn build_listen_all_query(channels: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    build_query_for_all("LISTEN", channels)
+}
+
+fn build_unlisten_all_query(channels: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    build_query_for_all("UNLISTEN", channels)
+}
+
+fn build_query_for_all(
+    command: &str,
+    channels: impl IntoIterator<Item = impl AsRef<str>>,
+) -> String {
     channels.into_iter().fold(String::new(), |mut acc, chan| {
-        acc.push_str(r#"LISTEN ""#);
+        acc.push_str(command);
+        acc.push_str(r#" ""#);
         acc.push_str(&ident(chan.as_ref()));
         acc.push_str(r#"";"#);
         acc
@@ -374,3 +987,12 @@ fn test_build_listen_all_query_with_multiple_channels() {
     let output = build_listen_all_query(&["channel.0", "channel.1"]);
     assert_eq!(output.as_str(), r#"LISTEN "channel.0";LISTEN "channel.1";"#);
 }
+
+#[test]
+fn test_build_unlisten_all_query() {
+    let output = build_unlisten_all_query(vec!["channel.0", "chan\"1"]);
+    assert_eq!(
+        output.as_str(),
+        r#"UNLISTEN "channel.0";UNLISTEN "chan""1";"#
+    );
+}
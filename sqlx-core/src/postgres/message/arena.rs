@@ -0,0 +1,49 @@
+use crate::postgres::message::row_description::Field;
+
+// past this many fields, the storage a response made the arena grow to is released once the
+// server is ready for the next query, rather than kept for the life of the connection
+const MAX_RETAINED_FIELDS: usize = 256;
+
+/// Storage that the descriptions in a response are decoded into, kept by the connection.
+///
+/// The fields of a [`RowDescription`](super::RowDescription) and the types of a
+/// [`ParameterDescription`](super::ParameterDescription) only live until their columns and
+/// types are copied out, which is before the next message is received. A description borrows
+/// the arena, so that at most one is alive at a time and each reuses the storage of the last.
+#[derive(Debug, Default)]
+pub struct MessageArena {
+    pub(super) fields: Vec<Field>,
+    pub(super) types: Vec<u32>,
+}
+
+impl MessageArena {
+    /// Clears the arena, once no description decoded from the response can be alive.
+    pub fn reset(&mut self) {
+        self.fields.clear();
+        self.types.clear();
+
+        if self.fields.capacity() > MAX_RETAINED_FIELDS {
+            self.fields = Vec::new();
+        }
+
+        if self.types.capacity() > MAX_RETAINED_FIELDS {
+            self.types = Vec::new();
+        }
+    }
+}
+
+#[test]
+fn test_reset_releases_large_storage() {
+    let mut arena = MessageArena::default();
+
+    arena.types.extend(0..10);
+    arena.reset();
+
+    assert!(arena.types.is_empty());
+    assert!(arena.types.capacity() >= 10);
+
+    arena.types.extend(0..1000);
+    arena.reset();
+
+    assert_eq!(arena.types.capacity(), 0);
+}
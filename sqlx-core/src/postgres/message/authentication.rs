@@ -4,7 +4,7 @@ use bytes::{Buf, Bytes};
 use memchr::memchr;
 
 use crate::error::Error;
-use crate::io::Decode;
+use crate::io::{BufExt, Decode};
 
 // On startup, the server sends an appropriate authentication request message,
 // to which the frontend must reply with an appropriate authentication
@@ -25,6 +25,10 @@ pub enum Authentication {
     /// The authentication exchange is successfully completed.
     Ok,
 
+    /// The frontend must now take part in a Kerberos V5 authentication dialog. Only servers
+    /// older than PostgreSQL 9.4 ask for this.
+    KerberosV5,
+
     /// The frontend must now send a [PasswordMessage] containing the
     /// password in clear-text form.
     CleartextPassword,
@@ -34,6 +38,16 @@ pub enum Authentication {
     /// again using the 4-byte random salt.
     Md5Password(AuthenticationMd5Password),
 
+    /// The frontend must now initiate a GSSAPI negotiation.
+    Gss,
+
+    /// This message contains the response data from the previous step of GSSAPI
+    /// or SSPI negotiation; GSSAPI is not implemented, so the data is not kept.
+    GssContinue,
+
+    /// The frontend must now initiate an SSPI negotiation.
+    Sspi,
+
     /// The frontend must now initiate a SASL negotiation,
     /// using one of the SASL mechanisms listed in the message.
     ///
@@ -60,18 +74,28 @@ pub enum Authentication {
 
 impl Decode<'_> for Authentication {
     fn decode_with(mut buf: Bytes, _: ()) -> Result<Self, Error> {
+        buf.ensure_remaining(4)?;
+
         Ok(match buf.get_u32() {
             0 => Authentication::Ok,
 
+            2 => Authentication::KerberosV5,
+
             3 => Authentication::CleartextPassword,
 
             5 => {
+                buf.ensure_remaining(4)?;
+
                 let mut salt = [0; 4];
                 buf.copy_to_slice(&mut salt);
 
                 Authentication::Md5Password(AuthenticationMd5Password { salt })
             }
 
+            7 => Authentication::Gss,
+            8 => Authentication::GssContinue,
+            9 => Authentication::Sspi,
+
             10 => Authentication::Sasl(AuthenticationSasl(buf)),
             11 => Authentication::SaslContinue(AuthenticationSaslContinue::decode(buf)?),
             12 => Authentication::SaslFinal(AuthenticationSaslFinal::decode(buf)?),
@@ -137,8 +161,10 @@ impl Decode<'_> for AuthenticationSaslContinue {
         // r=/z+giZiTxAH7r8sNAeHr7cvpqV3uo7G/bJBIJO3pjVM7t3ng,s=4UV68bIkC8f9/X8xH7aPhg==,i=4096
 
         for item in buf.split(|b| *b == b',') {
-            let key = item[0];
-            let value = &item[2..];
+            let (key, value) = match item {
+                [key, b'=', value @ ..] => (*key, value),
+                _ => return Err(err_protocol!("invalid SCRAM attribute: {:?}", item)),
+            };
 
             match key {
                 b'r' => {
@@ -176,8 +202,10 @@ impl Decode<'_> for AuthenticationSaslFinal {
         let mut verifier = Vec::new();
 
         for item in buf.split(|b| *b == b',') {
-            let key = item[0];
-            let value = &item[2..];
+            let (key, value) = match item {
+                [key, b'=', value @ ..] => (*key, value),
+                _ => return Err(err_protocol!("invalid SCRAM attribute: {:?}", item)),
+            };
 
             if let b'v' = key {
                 verifier = base64::decode(value).map_err(Error::protocol)?;
@@ -187,3 +215,18 @@ impl Decode<'_> for AuthenticationSaslFinal {
         Ok(Self { verifier })
     }
 }
+
+#[test]
+fn test_decode_authentication_gss() {
+    let m = Authentication::decode(Bytes::from_static(b"\0\0\0\x07")).unwrap();
+    assert!(matches!(m, Authentication::Gss));
+
+    let m = Authentication::decode(Bytes::from_static(b"\0\0\0\x08token")).unwrap();
+    assert!(matches!(m, Authentication::GssContinue));
+
+    let m = Authentication::decode(Bytes::from_static(b"\0\0\0\x09")).unwrap();
+    assert!(matches!(m, Authentication::Sspi));
+
+    let m = Authentication::decode(Bytes::from_static(b"\0\0\0\x02")).unwrap();
+    assert!(matches!(m, Authentication::KerberosV5));
+}
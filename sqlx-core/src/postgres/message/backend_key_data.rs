@@ -2,7 +2,7 @@ use byteorder::{BigEndian, ByteOrder};
 use bytes::Bytes;
 
 use crate::error::Error;
-use crate::io::Decode;
+use crate::io::{BufExt, Decode};
 
 /// Contains cancellation key data. The frontend must save these values if it
 /// wishes to be able to issue `CancelRequest` messages later.
@@ -17,6 +17,8 @@ pub struct BackendKeyData {
 
 impl Decode<'_> for BackendKeyData {
     fn decode_with(buf: Bytes, _: ()) -> Result<Self, Error> {
+        buf.ensure_remaining(8)?;
+
         let process_id = BigEndian::read_u32(&buf);
         let secret_key = BigEndian::read_u32(&buf[4..]);
 
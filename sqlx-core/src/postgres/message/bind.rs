@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+
 use crate::io::Encode;
 use crate::postgres::io::PgBufMutExt;
 use crate::postgres::PgValueFormat;
@@ -18,7 +20,7 @@ pub struct Bind<'a> {
     pub formats: &'a [PgValueFormat],
 
     /// The number of parameters.
-    pub num_params: i16,
+    pub num_params: usize,
 
     /// The value of each parameter, in the indicated format.
     pub params: &'a [u8],
@@ -47,7 +49,11 @@ impl Encode<'_> for Bind<'_> {
                 buf.extend(&(format as i16).to_be_bytes());
             }
 
-            buf.extend(&self.num_params.to_be_bytes());
+            // the count is read by the server as an unsigned 16-bit integer
+            let num_params = u16::try_from(self.num_params)
+                .expect("BUG: bind parameter count should have been checked against the limit");
+
+            buf.extend(&num_params.to_be_bytes());
 
             buf.extend(self.params);
 
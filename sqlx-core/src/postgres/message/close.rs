@@ -5,7 +5,6 @@ const CLOSE_PORTAL: u8 = b'P';
 const CLOSE_STATEMENT: u8 = b'S';
 
 #[derive(Debug)]
-#[allow(dead_code)]
 pub enum Close {
     Statement(u32),
     Portal(u32),
@@ -1,6 +1,8 @@
+use std::str::from_utf8;
+
 use atoi::atoi;
 use bytes::Bytes;
-use memchr::memrchr;
+use memchr::{memchr, memrchr};
 
 use crate::error::Error;
 use crate::io::Decode;
@@ -20,6 +22,14 @@ impl Decode<'_> for CommandComplete {
 }
 
 impl CommandComplete {
+    /// Returns the command tag, e.g. `INSERT 0 1` or `CREATE FUNCTION`.
+    pub fn tag(&self) -> Result<&str, Error> {
+        let end = memchr(b'\0', &self.tag).unwrap_or_else(|| self.tag.len());
+
+        from_utf8(&self.tag[..end])
+            .map_err(|err| err_protocol!("CommandComplete tag is not valid UTF-8: {}", err))
+    }
+
     /// Returns the number of rows affected.
     /// If the command does not return rows (e.g., "CREATE TABLE"), returns 0.
     pub fn rows_affected(&self) -> u64 {
@@ -47,6 +57,7 @@ fn test_decode_command_complete_for_begin() {
     let cc = CommandComplete::decode(Bytes::from_static(DATA)).unwrap();
 
     assert_eq!(cc.rows_affected(), 0);
+    assert_eq!(cc.tag().unwrap(), "BEGIN");
 }
 
 #[test]
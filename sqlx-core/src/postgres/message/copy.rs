@@ -0,0 +1,33 @@
+use crate::io::Encode;
+
+#[derive(Debug)]
+pub struct CopyData<'a>(pub &'a [u8]);
+
+impl Encode<'_> for CopyData<'_> {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: ()) {
+        buf.reserve(self.0.len() + 5);
+        buf.push(b'd');
+        buf.extend(&(self.0.len() as i32 + 4).to_be_bytes());
+        buf.extend_from_slice(self.0);
+    }
+}
+
+#[derive(Debug)]
+pub struct CopyDone;
+
+impl Encode<'_> for CopyDone {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: ()) {
+        buf.push(b'c');
+        buf.extend(&4_i32.to_be_bytes());
+    }
+}
+
+#[test]
+fn test_encode_copy_data() {
+    const EXPECTED: &[u8] = b"d\0\0\0\x07abc";
+
+    let mut buf = Vec::new();
+    CopyData(b"abc").encode(&mut buf);
+
+    assert_eq!(buf, EXPECTED);
+}
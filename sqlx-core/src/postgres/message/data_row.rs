@@ -4,10 +4,10 @@ use byteorder::{BigEndian, ByteOrder};
 use bytes::Bytes;
 
 use crate::error::Error;
-use crate::io::Decode;
+use crate::io::{BufExt, Decode};
 
 /// A row of data from the database.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DataRow {
     pub(crate) storage: Bytes,
 
@@ -28,6 +28,8 @@ impl DataRow {
 
 impl Decode<'_> for DataRow {
     fn decode_with(buf: Bytes, _: ()) -> Result<Self, Error> {
+        buf.ensure_remaining(2)?;
+
         let cnt = BigEndian::read_u16(&buf) as usize;
 
         let mut values = Vec::with_capacity(cnt);
@@ -37,11 +39,26 @@ impl Decode<'_> for DataRow {
             // Length of the column value, in bytes (this count does not include itself).
             // Can be zero. As a special case, -1 indicates a NULL column value.
             // No value bytes follow in the NULL case.
-            let length = BigEndian::read_i32(&buf[(offset as usize)..]);
+            let rest = &buf[(offset as usize)..];
+
+            if rest.len() < 4 {
+                return Err(err_protocol!(
+                    "DataRow ends before the length of column {}",
+                    values.len()
+                ));
+            }
+
+            let length = BigEndian::read_i32(rest);
             offset += 4;
 
-            if length < 0 {
+            if length == -1 {
                 values.push(None);
+            } else if length < 0 || length as usize > rest.len() - 4 {
+                return Err(err_protocol!(
+                    "DataRow column {} has an invalid length of {} bytes",
+                    values.len(),
+                    length
+                ));
             } else {
                 values.push(Some(offset..(offset + length as u32)));
                 offset += length as u32;
@@ -3,17 +3,21 @@ use bytes::Bytes;
 use crate::error::Error;
 use crate::io::Decode;
 
+mod arena;
 mod authentication;
 mod backend_key_data;
 mod bind;
+mod cancel_request;
 mod close;
 mod command_complete;
+mod copy;
 mod data_row;
 mod describe;
 mod execute;
 mod flush;
 mod notification;
 mod parameter_description;
+mod parameter_status;
 mod parse;
 mod password;
 mod query;
@@ -26,17 +30,21 @@ mod startup;
 mod sync;
 mod terminate;
 
+pub use arena::MessageArena;
 pub use authentication::{Authentication, AuthenticationSasl};
 pub use backend_key_data::BackendKeyData;
 pub use bind::Bind;
+pub use cancel_request::CancelRequest;
 pub use close::Close;
 pub use command_complete::CommandComplete;
+pub use copy::{CopyData, CopyDone};
 pub use data_row::DataRow;
 pub use describe::Describe;
 pub use execute::Execute;
 pub use flush::Flush;
 pub use notification::Notification;
 pub use parameter_description::ParameterDescription;
+pub use parameter_status::ParameterStatus;
 pub use parse::Parse;
 pub use password::Password;
 pub use query::Query;
@@ -57,6 +65,10 @@ pub enum MessageFormat {
     BindComplete,
     CloseComplete,
     CommandComplete,
+    CopyBothResponse,
+    CopyData,
+    CopyDone,
+    CopyOutResponse,
     DataRow,
     EmptyQueryResponse,
     ErrorResponse,
@@ -98,6 +110,7 @@ impl MessageFormat {
             b'C' => MessageFormat::CommandComplete,
             b'D' => MessageFormat::DataRow,
             b'E' => MessageFormat::ErrorResponse,
+            b'H' => MessageFormat::CopyOutResponse,
             b'I' => MessageFormat::EmptyQueryResponse,
             b'A' => MessageFormat::NotificationResponse,
             b'K' => MessageFormat::BackendKeyData,
@@ -105,7 +118,10 @@ impl MessageFormat {
             b'R' => MessageFormat::Authentication,
             b'S' => MessageFormat::ParameterStatus,
             b'T' => MessageFormat::RowDescription,
+            b'W' => MessageFormat::CopyBothResponse,
             b'Z' => MessageFormat::ReadyForQuery,
+            b'c' => MessageFormat::CopyDone,
+            b'd' => MessageFormat::CopyData,
             b'n' => MessageFormat::NoData,
             b's' => MessageFormat::PortalSuspended,
             b't' => MessageFormat::ParameterDescription,
@@ -13,6 +13,8 @@ pub struct Notification {
 impl Decode<'_> for Notification {
     #[inline]
     fn decode_with(mut buf: Bytes, _: ()) -> Result<Self, Error> {
+        buf.ensure_remaining(4)?;
+
         let process_id = buf.get_u32();
         let channel = buf.get_bytes_nul()?;
         let payload = buf.get_bytes_nul()?;
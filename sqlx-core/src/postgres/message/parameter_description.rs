@@ -1,24 +1,29 @@
 use bytes::{Buf, Bytes};
-use smallvec::SmallVec;
 
 use crate::error::Error;
-use crate::io::Decode;
+use crate::io::{BufExt, Decode};
+use crate::postgres::message::MessageArena;
 
+/// The types of the parameters of a statement, decoded into the [`MessageArena`] of the
+/// connection.
 #[derive(Debug)]
-pub struct ParameterDescription {
-    pub types: SmallVec<[u32; 6]>,
+pub struct ParameterDescription<'a> {
+    pub types: &'a [u32],
 }
 
-impl Decode<'_> for ParameterDescription {
-    fn decode_with(mut buf: Bytes, _: ()) -> Result<Self, Error> {
+impl<'a> Decode<'a, &'a mut MessageArena> for ParameterDescription<'a> {
+    fn decode_with(mut buf: Bytes, arena: &'a mut MessageArena) -> Result<Self, Error> {
+        buf.ensure_remaining(2)?;
+
         let cnt = buf.get_u16();
-        let mut types = SmallVec::with_capacity(cnt as usize);
+        buf.ensure_remaining(cnt as usize * 4)?;
 
-        for _ in 0..cnt {
-            types.push(buf.get_u32());
-        }
+        arena.types.clear();
+        arena.types.extend((0..cnt).map(|_| buf.get_u32()));
 
-        Ok(Self { types })
+        Ok(Self {
+            types: &arena.types,
+        })
     }
 }
 
@@ -26,7 +31,8 @@ impl Decode<'_> for ParameterDescription {
 fn test_decode_parameter_description() {
     const DATA: &[u8] = b"\x00\x02\x00\x00\x00\x00\x00\x00\x05\x00";
 
-    let m = ParameterDescription::decode(DATA.into()).unwrap();
+    let mut arena = MessageArena::default();
+    let m = ParameterDescription::decode_with(DATA.into(), &mut arena).unwrap();
 
     assert_eq!(m.types.len(), 2);
     assert_eq!(m.types[0], 0x0000_0000);
@@ -37,7 +43,8 @@ fn test_decode_parameter_description() {
 fn test_decode_empty_parameter_description() {
     const DATA: &[u8] = b"\x00\x00";
 
-    let m = ParameterDescription::decode(DATA.into()).unwrap();
+    let mut arena = MessageArena::default();
+    let m = ParameterDescription::decode_with(DATA.into(), &mut arena).unwrap();
 
     assert!(m.types.is_empty());
 }
@@ -47,7 +54,10 @@ fn test_decode_empty_parameter_description() {
 fn bench_decode_parameter_description(b: &mut test::Bencher) {
     const DATA: &[u8] = b"\x00\x02\x00\x00\x00\x00\x00\x00\x05\x00";
 
+    let mut arena = MessageArena::default();
+
     b.iter(|| {
-        ParameterDescription::decode(test::black_box(Bytes::from_static(DATA))).unwrap();
+        ParameterDescription::decode_with(test::black_box(Bytes::from_static(DATA)), &mut arena)
+            .unwrap();
     });
 }
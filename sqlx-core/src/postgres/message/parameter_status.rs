@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::error::Error;
+use crate::io::{BufExt, Decode};
+
+/// Reports the current value of a run-time parameter, both after startup and whenever a
+/// reported parameter (e.g. `DateStyle` or `TimeZone`) is changed in the session.
+#[derive(Debug)]
+pub struct ParameterStatus {
+    pub name: String,
+    pub value: String,
+}
+
+impl Decode<'_> for ParameterStatus {
+    fn decode_with(mut buf: Bytes, _: ()) -> Result<Self, Error> {
+        let name = buf.get_str_nul()?;
+        let value = buf.get_str_nul()?;
+
+        Ok(Self { name, value })
+    }
+}
+
+#[test]
+fn test_decode_parameter_status() {
+    const DATA: &[u8] = b"DateStyle\0ISO, MDY\0";
+
+    let m = ParameterStatus::decode(DATA.into()).unwrap();
+
+    assert_eq!(m.name, "DateStyle");
+    assert_eq!(m.value, "ISO, MDY");
+}
@@ -1,4 +1,4 @@
-use std::i16;
+use std::convert::TryFrom;
 
 use crate::io::{BufMutExt, Encode};
 use crate::postgres::io::PgBufMutExt;
@@ -26,10 +26,11 @@ impl Encode<'_> for Parse<'_> {
 
             buf.put_str_nul(self.query);
 
-            // TODO: Return an error here instead
-            assert!(self.param_types.len() <= (i16::MAX as usize));
+            // the count is read by the server as an unsigned 16-bit integer
+            let num_params = u16::try_from(self.param_types.len())
+                .expect("BUG: parameter type count should have been checked against the limit");
 
-            buf.extend(&(self.param_types.len() as i16).to_be_bytes());
+            buf.extend(&num_params.to_be_bytes());
 
             for &oid in self.param_types {
                 buf.extend(&oid.to_be_bytes());
@@ -53,3 +54,19 @@ fn test_encode_parse() {
 
     assert_eq!(buf, EXPECTED);
 }
+
+#[test]
+fn test_encode_parse_unnamed() {
+    const EXPECTED: &[u8] = b"P\0\0\0\x15\0SELECT $1\0\0\x01\0\0\0\x19";
+
+    let mut buf = Vec::new();
+    let m = Parse {
+        statement: 0,
+        query: "SELECT $1",
+        param_types: &[25],
+    };
+
+    m.encode(&mut buf);
+
+    assert_eq!(buf, EXPECTED);
+}
@@ -23,7 +23,7 @@ pub struct ReadyForQuery {
 
 impl Decode<'_> for ReadyForQuery {
     fn decode_with(buf: Bytes, _: ()) -> Result<Self, Error> {
-        let status = match buf[0] {
+        let status = match buf.first().copied().unwrap_or_default() {
             b'I' => TransactionStatus::Idle,
             b'T' => TransactionStatus::Transaction,
             b'E' => TransactionStatus::Error,
@@ -53,8 +53,8 @@ impl std::convert::TryFrom<&str> for PgSeverity {
 pub struct Notice {
     storage: Bytes,
     severity: PgSeverity,
-    message: (u16, u16),
-    code: (u16, u16),
+    message: (usize, usize),
+    code: (usize, usize),
 }
 
 impl Notice {
@@ -84,7 +84,7 @@ impl Notice {
     pub fn get_raw(&self, ty: u8) -> Option<&[u8]> {
         self.fields()
             .filter(|(field, _)| *field == ty)
-            .map(|(_, (start, end))| &self.storage[start as usize..end as usize])
+            .map(|(_, (start, end))| &self.storage[start..end])
             .next()
     }
 }
@@ -99,9 +99,9 @@ impl Notice {
     }
 
     #[inline]
-    fn get_cached_str(&self, cache: (u16, u16)) -> &str {
-        // unwrap: this cannot fail at this stage
-        from_utf8(&self.storage[cache.0 as usize..cache.1 as usize]).unwrap()
+    fn get_cached_str(&self, cache: (usize, usize)) -> &str {
+        // unwrap: checked when the notice was decoded
+        from_utf8(&self.storage[cache.0..cache.1]).unwrap()
     }
 }
 
@@ -135,18 +135,17 @@ impl Decode<'_> for Notice {
             match field {
                 b'S' => {
                     // Discard potential errors, because the message might be localized
-                    severity_s = from_utf8(&buf[v.0 as usize..v.1 as usize])
-                        .unwrap()
-                        .try_into()
-                        .ok();
+                    severity_s = from_utf8(&buf[v.0..v.1])
+                        .ok()
+                        .and_then(|s| s.try_into().ok());
                 }
 
                 b'V' => {
                     // Propagate errors here, because V is not localized and thus we are missing a possible
                     // variant.
                     severity_v = Some(
-                        from_utf8(&buf[v.0 as usize..v.1 as usize])
-                            .unwrap()
+                        from_utf8(&buf[v.0..v.1])
+                            .map_err(Error::protocol)?
                             .try_into()?,
                     );
                 }
@@ -163,6 +162,10 @@ impl Decode<'_> for Notice {
             }
         }
 
+        // `message` and `code` return these as `str`
+        from_utf8(&buf[message.0..message.1]).map_err(Error::protocol)?;
+        from_utf8(&buf[code.0..code.1]).map_err(Error::protocol)?;
+
         Ok(Self {
             severity: severity_v.or(severity_s).unwrap_or(DEFAULT_SEVERITY),
             message,
@@ -175,23 +178,23 @@ impl Decode<'_> for Notice {
 /// An iterator over each field in the Error (or Notice) response.
 struct Fields<'a> {
     storage: &'a [u8],
-    offset: u16,
+    offset: usize,
 }
 
 impl<'a> Iterator for Fields<'a> {
-    type Item = (u8, (u16, u16));
+    type Item = (u8, (usize, usize));
 
     fn next(&mut self) -> Option<Self::Item> {
         // The fields in the response body are sequentially stored as [tag][string],
         // ending in a final, additional [nul]
 
-        let ty = self.storage[self.offset as usize];
+        let ty = *self.storage.get(self.offset)?;
 
         if ty == 0 {
             return None;
         }
 
-        let nul = memchr(b'\0', &self.storage[(self.offset + 1) as usize..])? as u16;
+        let nul = memchr(b'\0', &self.storage[self.offset + 1..])?;
         let offset = self.offset;
 
         self.offset += nul + 2;
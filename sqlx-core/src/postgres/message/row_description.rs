@@ -1,17 +1,23 @@
+use std::str::from_utf8;
+
 use bytes::{Buf, Bytes};
+use memchr::memchr;
 
 use crate::error::Error;
 use crate::io::{BufExt, Decode};
+use crate::postgres::message::MessageArena;
 
+/// The fields of the rows of a query, decoded into the [`MessageArena`] of the connection.
 #[derive(Debug)]
-pub struct RowDescription {
-    pub fields: Vec<Field>,
+pub struct RowDescription<'a> {
+    storage: Bytes,
+    pub fields: &'a [Field],
 }
 
 #[derive(Debug)]
 pub struct Field {
-    /// The name of the field.
-    pub name: String,
+    /// The name of the field, as a range of the message; see [`RowDescription::name`].
+    name: (usize, usize),
 
     /// If the field can be identified as a column of a specific table, the
     /// object ID of the table; otherwise zero.
@@ -36,13 +42,36 @@ pub struct Field {
     pub format: i16,
 }
 
-impl Decode<'_> for RowDescription {
-    fn decode_with(mut buf: Bytes, _: ()) -> Result<Self, Error> {
+impl RowDescription<'_> {
+    #[inline]
+    pub fn name(&self, field: &Field) -> &str {
+        // unwrap: checked when the description was decoded
+        from_utf8(&self.storage[field.name.0..field.name.1]).unwrap()
+    }
+}
+
+impl<'a> Decode<'a, &'a mut MessageArena> for RowDescription<'a> {
+    fn decode_with(mut buf: Bytes, arena: &'a mut MessageArena) -> Result<Self, Error> {
+        let storage = buf.clone();
+
+        buf.ensure_remaining(2)?;
+
         let cnt = buf.get_u16();
-        let mut fields = Vec::with_capacity(cnt as usize);
+        let fields = &mut arena.fields;
+
+        fields.clear();
+        fields.reserve(cnt as usize);
 
         for _ in 0..cnt {
-            let name = buf.get_str_nul()?.to_owned();
+            let start = storage.len() - buf.len();
+            let len = memchr(b'\0', &buf)
+                .ok_or_else(|| err_protocol!("expected NUL in byte sequence"))?;
+
+            from_utf8(&buf[..len]).map_err(|err| err_protocol!("{}", err))?;
+            buf.advance(len + 1);
+
+            buf.ensure_remaining(18)?;
+
             let relation_id = buf.get_i32();
             let relation_attribute_no = buf.get_i16();
             let data_type_id = buf.get_u32();
@@ -51,7 +80,7 @@ impl Decode<'_> for RowDescription {
             let format = buf.get_i16();
 
             fields.push(Field {
-                name,
+                name: (start, start + len),
                 relation_id: if relation_id == 0 {
                     None
                 } else {
@@ -69,9 +98,68 @@ impl Decode<'_> for RowDescription {
             })
         }
 
-        Ok(Self { fields })
+        Ok(Self {
+            storage,
+            fields: &arena.fields,
+        })
     }
 }
 
-// TODO: Unit Test RowDescription
-// TODO: Benchmark RowDescription
+#[test]
+fn test_decode_row_description() {
+    const DATA: &[u8] = b"\x00\x02id\x00\x00\x00@\x0c\x00\x01\x00\x00\x00\x17\x00\x04\xff\xff\xff\xff\x00\x01\
+                          caf\xc3\xa9\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x19\xff\xff\xff\xff\xff\xff\x00\x00";
+
+    let mut arena = MessageArena::default();
+    let m = RowDescription::decode_with(DATA.into(), &mut arena).unwrap();
+
+    assert_eq!(m.fields.len(), 2);
+
+    assert_eq!(m.name(&m.fields[0]), "id");
+    assert_eq!(m.fields[0].relation_id, Some(0x400c));
+    assert_eq!(m.fields[0].relation_attribute_no, Some(1));
+    assert_eq!(m.fields[0].data_type_id, 23);
+    assert_eq!(m.fields[0].format, 1);
+
+    assert_eq!(m.name(&m.fields[1]), "café");
+    assert_eq!(m.fields[1].relation_id, None);
+    assert_eq!(m.fields[1].data_type_id, 25);
+    assert_eq!(m.fields[1].data_type_size, -1);
+    assert_eq!(m.fields[1].format, 0);
+}
+
+#[test]
+fn test_decode_row_description_reuses_the_arena() {
+    const DATA: &[u8] =
+        b"\x00\x01id\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x17\x00\x04\xff\xff\xff\xff\x00\x01";
+
+    let mut arena = MessageArena::default();
+    RowDescription::decode_with(DATA.into(), &mut arena).unwrap();
+
+    let capacity = arena.fields.capacity();
+    let m = RowDescription::decode_with(DATA.into(), &mut arena).unwrap();
+
+    assert_eq!(m.fields.len(), 1);
+    assert_eq!(arena.fields.capacity(), capacity);
+}
+
+#[test]
+fn test_decode_row_description_with_invalid_name() {
+    const DATA: &[u8] =
+        b"\x00\x01\xff\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x17\x00\x04\xff\xff\xff\xff\x00\x01";
+
+    assert!(RowDescription::decode_with(DATA.into(), &mut MessageArena::default()).is_err());
+}
+
+#[cfg(all(test, not(debug_assertions)))]
+#[bench]
+fn bench_decode_row_description(b: &mut test::Bencher) {
+    const DATA: &[u8] = b"\x00\x02id\x00\x00\x00@\x0c\x00\x01\x00\x00\x00\x17\x00\x04\xff\xff\xff\xff\x00\x01\
+                          caf\xc3\xa9\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x19\xff\xff\xff\xff\xff\xff\x00\x00";
+
+    let mut arena = MessageArena::default();
+
+    b.iter(|| {
+        RowDescription::decode_with(test::black_box(Bytes::from_static(DATA)), &mut arena).unwrap();
+    });
+}
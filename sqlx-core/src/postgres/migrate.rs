@@ -1,10 +1,9 @@
 use crate::connection::{ConnectOptions, Connection};
 use crate::error::Error;
-use crate::executor::Executor;
 use crate::migrate::MigrateError;
 use crate::migrate::Migration;
 use crate::migrate::{Migrate, MigrateDatabase};
-use crate::postgres::{PgConnectOptions, PgConnection, Postgres};
+use crate::postgres::{quote_identifier, PgConnectOptions, PgConnection, Postgres};
 use crate::query::query;
 use crate::query_as::query_as;
 use crate::query_scalar::query_scalar;
@@ -36,6 +35,24 @@ fn parse_for_maintenance(uri: &str) -> Result<(PgConnectOptions, String), Error>
     Ok((options, database))
 }
 
+// tells the errors of `CREATE DATABASE` and `DROP DATABASE` that are about the database apart
+// from the rest
+fn admin_error(database: &str, error: Error) -> Error {
+    match error {
+        Error::Database(error) => match error.code().as_deref() {
+            // duplicate_database
+            Some("42P04") => Error::DatabaseExists(database.to_owned()),
+
+            // insufficient_privilege
+            Some("42501") => Error::DatabasePermissionDenied(error),
+
+            _ => Error::Database(error),
+        },
+
+        error => error,
+    }
+}
+
 impl MigrateDatabase for Postgres {
     fn create_database(uri: &str) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
@@ -44,10 +61,11 @@ impl MigrateDatabase for Postgres {
 
             let _ = conn
                 .execute(&*format!(
-                    "CREATE DATABASE \"{}\"",
-                    database.replace('"', "\"\"")
+                    "CREATE DATABASE {}",
+                    quote_identifier(&database)?
                 ))
-                .await?;
+                .await
+                .map_err(|error| admin_error(&database, error))?;
 
             Ok(())
         })
@@ -75,11 +93,51 @@ impl MigrateDatabase for Postgres {
 
             let _ = conn
                 .execute(&*format!(
-                    "DROP DATABASE IF EXISTS \"{}\"",
-                    database.replace('"', "\"\"")
+                    "DROP DATABASE IF EXISTS {}",
+                    quote_identifier(&database)?
                 ))
+                .await
+                .map_err(|error| admin_error(&database, error))?;
+
+            Ok(())
+        })
+    }
+
+    fn force_drop_database(uri: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let (options, database) = parse_for_maintenance(uri)?;
+            let mut conn = options.connect().await?;
+
+            let version: i32 = query_scalar("SELECT current_setting('server_version_num')::int")
+                .fetch_one(&mut conn)
                 .await?;
 
+            // `WITH (FORCE)` is new in PostgreSQL 13; before, the other sessions are terminated
+            // first, and may reconnect in the meantime
+            let force = if version >= 130000 {
+                " WITH (FORCE)"
+            } else {
+                let _ = query(
+                    "SELECT pg_terminate_backend(pid) FROM pg_stat_activity \
+                     WHERE datname = $1 AND pid <> pg_backend_pid()",
+                )
+                .bind(&database)
+                .execute(&mut conn)
+                .await
+                .map_err(|error| admin_error(&database, error))?;
+
+                ""
+            };
+
+            let _ = conn
+                .execute(&*format!(
+                    "DROP DATABASE IF EXISTS {}{}",
+                    quote_identifier(&database)?,
+                    force
+                ))
+                .await
+                .map_err(|error| admin_error(&database, error))?;
+
             Ok(())
         })
     }
@@ -185,12 +243,15 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            if migration.no_tx {
+                let _ = self.execute_raw_script(&migration.sql).await?;
+            } else {
+                let mut tx = self.begin().await?;
+                let _ = tx.execute(&*migration.sql).await?;
+                tx.commit().await?;
+            }
 
             let elapsed = start.elapsed();
 
@@ -217,12 +278,15 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            if migration.no_tx {
+                let _ = self.execute_raw_script(&migration.sql).await?;
+            } else {
+                let mut tx = self.begin().await?;
+                let _ = tx.execute(&*migration.sql).await?;
+                tx.commit().await?;
+            }
 
             let elapsed = start.elapsed();
 
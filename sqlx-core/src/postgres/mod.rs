@@ -1,17 +1,27 @@
 //! **PostgreSQL** database driver.
 
+mod advisory_lock;
 mod arguments;
 mod column;
 mod connection;
 mod database;
+mod erased;
 mod error;
+mod flavor;
+mod interpolate;
 mod io;
 mod listener;
-mod message;
+pub(crate) mod message;
+mod notice;
 mod options;
+pub(crate) mod plan;
+mod pool;
 mod query_result;
+mod quote;
+pub(crate) mod replication;
 mod row;
-mod statement;
+mod shared_listener;
+pub(crate) mod statement;
 mod transaction;
 mod type_info;
 pub mod types;
@@ -20,20 +30,34 @@ mod value;
 #[cfg(feature = "migrate")]
 mod migrate;
 
+pub use advisory_lock::{PgAdvisoryLock, PgAdvisoryLockGuard, PgAdvisoryLockKey};
 pub use arguments::{PgArgumentBuffer, PgArguments};
 pub use column::PgColumn;
-pub use connection::PgConnection;
+pub use connection::{
+    PgBatch, PgCancelToken, PgConnection, PgCopyCsvOptions, PgCursor, PgScriptOutcome,
+};
 pub use database::Postgres;
 pub use error::{PgDatabaseError, PgErrorPosition};
-pub use listener::{PgListener, PgNotification};
+pub use flavor::PgFlavor;
+pub use listener::{PgListener, PgNotification, PgSubscription, PgSubscriptionEvent};
 pub use message::PgSeverity;
-pub use options::{PgConnectOptions, PgSslMode};
+pub use notice::PgNotice;
+pub use options::{
+    PgConnectOptions, PgExplainOptions, PgSslMode, PgSslNegotiation, PgStatementCacheMode,
+};
+pub use plan::{PgAnalyzeOptions, PgPlanBuffers, PgPlanNode, PgPlanTrigger, PgQueryPlan};
 pub use query_result::PgQueryResult;
+pub use quote::{quote_identifier, quote_qualified};
+pub use replication::{
+    PgLsn, PgReplication, PgReplicationMessage, PgReplicationSlot, PgReplicationStream,
+};
 pub use row::PgRow;
+pub use shared_listener::PgSharedListener;
 pub use statement::PgStatement;
 pub use transaction::PgTransactionManager;
 pub use type_info::{PgTypeInfo, PgTypeKind};
-pub use value::{PgValue, PgValueFormat, PgValueRef};
+pub use types::PgHasArrayType;
+pub use value::{PgResultFormat, PgValue, PgValueFormat, PgValueRef};
 
 /// An alias for [`Pool`][crate::pool::Pool], specialized for Postgres.
 pub type PgPool = crate::pool::Pool<Postgres>;
@@ -49,3 +73,75 @@ impl_column_index_for_row!(PgRow);
 impl_column_index_for_statement!(PgStatement);
 impl_into_maybe_pool!(Postgres, PgConnection);
 impl_encode_for_option!(Postgres);
+
+#[cfg(test)]
+assert_driver_futures_are_send!(Postgres, PgConnection);
+
+// the futures of what only Postgres has are `Send` as well
+#[test]
+#[allow(dead_code)]
+fn assert_postgres_futures_are_send() {
+    use crate::transaction::Transaction;
+
+    fn assert_send<T: Send>(_: T) {}
+
+    fn assert_connection(mut conn: PgConnection, lock: PgAdvisoryLock, mut writer: Vec<u8>) {
+        assert_send(conn.execute_raw_script(""));
+        assert_send(conn.copy_out_csv("", &mut writer, &PgCopyCsvOptions::new()));
+        assert_send(lock.acquire(&mut conn));
+        assert_send(lock.try_acquire(&mut conn));
+    }
+
+    fn assert_lock_guard(guard: PgAdvisoryLockGuard<'_, '_>) {
+        assert_send(guard.release_now());
+    }
+
+    fn assert_transaction(mut tx: Transaction<'static, Postgres>) {
+        assert_send(tx.set_local("", ""));
+        assert_send(tx.set_locals(vec![("", "")]));
+        assert_send(tx.list_prepared());
+        assert_send(tx.commit_prepared(""));
+        assert_send(tx.rollback_prepared(""));
+        assert_send(tx.prepare_two_phase(""));
+    }
+
+    fn assert_pool(pool: PgPool) {
+        assert_send(PgListener::connect_with(&pool));
+        assert_send(pool.listener().subscribe::<()>(""));
+    }
+
+    #[cfg(feature = "migrate")]
+    fn assert_migrator(migrator: crate::migrate::Migrator, pool: PgPool, mut conn: PgConnection) {
+        assert_send(migrator.run(&pool));
+        assert_send(migrator.run(&mut conn));
+    }
+
+    fn assert_listener(mut listener: PgListener) {
+        assert_send(listener.listen(""));
+        assert_send(listener.listen_all(vec![""]));
+        assert_send(listener.unlisten(""));
+        assert_send(listener.unlisten_all());
+        assert_send(listener.recv());
+        assert_send(listener.try_recv());
+        assert_send(listener.subscribe::<()>(""));
+        assert_send(listener.into_stream());
+    }
+
+    fn assert_replication(
+        mut replication: PgReplication,
+        other: PgReplication,
+        options: PgConnectOptions,
+    ) {
+        assert_send(PgReplication::connect_with(&options));
+        assert_send(replication.create_slot("", "", false));
+        assert_send(replication.drop_slot(""));
+        assert_send(replication.start_replication("", PgLsn(0), &[]));
+        assert_send(other.close());
+    }
+
+    fn assert_replication_stream(mut stream: PgReplicationStream) {
+        assert_send(stream.recv());
+        assert_send(stream.standby_status_update(PgLsn(0)));
+        assert_send(stream.finish());
+    }
+}
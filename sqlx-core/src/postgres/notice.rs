@@ -0,0 +1,69 @@
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::Arc;
+
+use crate::postgres::message::{Notice, PgSeverity};
+
+/// A notice or warning sent by the server, e.g. from `RAISE NOTICE`.
+///
+/// See [`PgConnectOptions::notice_handler`](crate::postgres::PgConnectOptions::notice_handler).
+pub struct PgNotice(pub(crate) Notice);
+
+impl PgNotice {
+    #[inline]
+    pub fn severity(&self) -> PgSeverity {
+        self.0.severity()
+    }
+
+    /// The [SQLSTATE](https://www.postgresql.org/docs/current/errcodes-appendix.html) code for
+    /// this notice.
+    #[inline]
+    pub fn code(&self) -> &str {
+        self.0.code()
+    }
+
+    /// The primary human-readable message.
+    #[inline]
+    pub fn message(&self) -> &str {
+        self.0.message()
+    }
+
+    /// An optional secondary message carrying more detail.
+    #[inline]
+    pub fn detail(&self) -> Option<&str> {
+        self.0.get(b'D')
+    }
+
+    /// An optional suggestion of what to do about the condition being reported.
+    #[inline]
+    pub fn hint(&self) -> Option<&str> {
+        self.0.get(b'H')
+    }
+}
+
+impl Debug for PgNotice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgNotice")
+            .field("severity", &self.severity())
+            .field("code", &self.code())
+            .field("message", &self.message())
+            .field("detail", &self.detail())
+            .field("hint", &self.hint())
+            .finish()
+    }
+}
+
+impl Display for PgNotice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+// `PgConnectOptions` is `Debug` and `Clone`, which a bare `Arc<dyn Fn>` is not
+#[derive(Clone)]
+pub(crate) struct PgNoticeHandler(pub(crate) Arc<dyn Fn(PgNotice) + Send + Sync>);
+
+impl Debug for PgNoticeHandler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("PgNoticeHandler")
+    }
+}
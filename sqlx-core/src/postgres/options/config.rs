@@ -0,0 +1,132 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::common::config::{certificate_to_string, deserialize_from_str, deserialize_url_or_map};
+use crate::net::CertificateInput;
+use crate::postgres::{PgConnectOptions, PgSslMode};
+
+// The map form of the options. Settings that are left out keep the defaults of
+// `PgConnectOptions::new()`, so the `PG*` environment variables still apply to them.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PgConfig {
+    host: Option<String>,
+    port: Option<u16>,
+    socket: Option<PathBuf>,
+    #[serde(alias = "user")]
+    username: Option<String>,
+    password: Option<String>,
+    #[serde(alias = "dbname")]
+    database: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_from_str")]
+    ssl_mode: Option<PgSslMode>,
+    ssl_root_cert: Option<String>,
+    statement_cache_capacity: Option<usize>,
+    application_name: Option<String>,
+}
+
+impl PgConfig {
+    fn into_options(self) -> PgConnectOptions {
+        let mut options = PgConnectOptions::new();
+
+        if let Some(host) = self.host {
+            options.host = host;
+        }
+
+        if let Some(port) = self.port {
+            options.port = port;
+        }
+
+        if let Some(username) = self.username {
+            options.username = username;
+        }
+
+        if let Some(ssl_mode) = self.ssl_mode {
+            options.ssl_mode = ssl_mode;
+        }
+
+        if let Some(capacity) = self.statement_cache_capacity {
+            options.statement_cache_capacity = capacity;
+        }
+
+        options.socket = self.socket.or(options.socket);
+        options.password = self.password.or(options.password);
+        options.database = self.database.or(options.database);
+        options.application_name = self.application_name.or(options.application_name);
+        options.ssl_root_cert = self
+            .ssl_root_cert
+            .map(CertificateInput::from)
+            .or(options.ssl_root_cert);
+
+        options
+    }
+}
+
+#[derive(Serialize)]
+struct PgConfigRef<'a> {
+    host: &'a str,
+    port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    socket: Option<&'a Path>,
+    username: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    database: Option<&'a str>,
+    ssl_mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ssl_root_cert: Option<String>,
+    statement_cache_capacity: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    application_name: Option<&'a str>,
+}
+
+impl PgConnectOptions {
+    /// Serializes these options including the password, which [`Serialize`] leaves out.
+    ///
+    /// Meant for `#[serde(serialize_with = "PgConnectOptions::serialize_with_password")]`
+    /// where the output needs to be deserialized into working options again.
+    pub fn serialize_with_password<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.config(true).serialize(serializer)
+    }
+
+    fn config(&self, with_password: bool) -> PgConfigRef<'_> {
+        PgConfigRef {
+            host: &self.host,
+            port: self.port,
+            socket: self.socket.as_deref(),
+            username: &self.username,
+            password: self.password.as_deref().filter(|_| with_password),
+            database: self.database.as_deref(),
+            ssl_mode: match self.ssl_mode {
+                PgSslMode::Disable => "disable",
+                PgSslMode::Allow => "allow",
+                PgSslMode::Prefer => "prefer",
+                PgSslMode::Require => "require",
+                PgSslMode::VerifyCa => "verify-ca",
+                PgSslMode::VerifyFull => "verify-full",
+            },
+            ssl_root_cert: self.ssl_root_cert.as_ref().map(certificate_to_string),
+            statement_cache_capacity: self.statement_cache_capacity,
+            application_name: self.application_name.as_deref(),
+        }
+    }
+}
+
+/// Accepts either a connection URL, as for [`FromStr`](std::str::FromStr), or a map of
+/// settings: `host`, `port`, `socket`, `username` (or `user`), `password`, `database` (or
+/// `dbname`), `ssl_mode`, `ssl_root_cert`, `statement_cache_capacity` and `application_name`.
+impl<'de> Deserialize<'de> for PgConnectOptions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_url_or_map(deserializer, PgConfig::into_options)
+    }
+}
+
+/// Serializes the map form of the options, without the password; see
+/// [`serialize_with_password`](PgConnectOptions::serialize_with_password).
+impl Serialize for PgConnectOptions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.config(false).serialize(serializer)
+    }
+}
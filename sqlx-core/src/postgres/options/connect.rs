@@ -1,8 +1,12 @@
-use crate::connection::ConnectOptions;
-use crate::error::Error;
+use crate::connection::{BindLogging, ConnectOptions};
+use crate::error::{ConfigError, Error};
+use crate::events::{ConnectionEventListener, EventListener};
+use crate::middleware::{Middleware, QueryMiddleware};
+use crate::net::{CertificateInput, TlsSessionCache};
 use crate::postgres::{PgConnectOptions, PgConnection};
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
+use std::sync::Arc;
 use std::time::Duration;
 
 impl ConnectOptions for PgConnectOptions {
@@ -15,6 +19,30 @@ impl ConnectOptions for PgConnectOptions {
         Box::pin(PgConnection::establish(self))
     }
 
+    fn connect_with_name_suffix(
+        &self,
+        suffix: String,
+    ) -> BoxFuture<'_, Result<PgConnection, Error>> {
+        Box::pin(async move { PgConnection::establish(&self.with_name_suffix(&suffix)).await })
+    }
+
+    fn connect_with_password(
+        &self,
+        password: String,
+        suffix: Option<String>,
+    ) -> BoxFuture<'_, Result<PgConnection, Error>> {
+        Box::pin(async move {
+            let mut options = match suffix {
+                Some(suffix) => self.with_name_suffix(&suffix),
+                None => self.clone(),
+            };
+
+            options.password = Some(password);
+
+            PgConnection::establish(&options).await
+        })
+    }
+
     fn log_statements(&mut self, level: LevelFilter) -> &mut Self {
         self.log_settings.log_statements(level);
         self
@@ -24,4 +52,92 @@ impl ConnectOptions for PgConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn log_bind_values(&mut self, logging: BindLogging) -> &mut Self {
+        self.log_settings.log_bind_values(logging);
+        self
+    }
+
+    fn query_middleware(&mut self, middleware: Arc<dyn QueryMiddleware>) -> &mut Self {
+        self.middleware = Middleware(Some(middleware));
+        self
+    }
+
+    fn connection_events(&mut self, listener: Arc<dyn ConnectionEventListener>) -> &mut Self {
+        self.events = EventListener(Some(listener));
+        self
+    }
+
+    fn isolate(&mut self) {
+        self.tls_session_cache = self.tls_session_cache.as_ref().map(TlsSessionCache::fresh);
+
+        if let Some(explain) = &mut self.explain {
+            explain.throttle = explain.throttle.fresh();
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+
+        if self.port == 0 {
+            errors.push(ConfigError::new("port", "must be between 1 and 65535"));
+        }
+
+        if self.host.is_empty() && self.socket.is_none() {
+            errors.push(ConfigError::new("host", "must not be empty"));
+        }
+
+        if self.username.is_empty() {
+            errors.push(ConfigError::new("username", "must not be empty"));
+        }
+
+        // the database is optional and defaults to the username, but it cannot be blank
+        if matches!(&self.database, Some(database) if database.is_empty()) {
+            errors.push(ConfigError::new("database", "must not be empty if set"));
+        }
+
+        if self.verify_socket_peer && self.socket_owner.is_none() {
+            errors.push(ConfigError::new(
+                "verify_socket_peer",
+                "requires an expected_socket_owner to check the peer against",
+            ));
+        }
+
+        if let Some(CertificateInput::File(path)) = &self.ssl_root_cert {
+            if !path.is_file() {
+                errors.push(ConfigError::new(
+                    "ssl_root_cert",
+                    format!("file not found: {}", path.display()),
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[test]
+fn it_reports_every_invalid_option() {
+    let options = PgConnectOptions::new()
+        .port(0)
+        .username("")
+        .verify_socket_peer(true)
+        .ssl_root_cert("/does/not/exist.pem");
+
+    let errors = options.validate().unwrap_err();
+    let invalid: Vec<_> = errors.iter().map(ConfigError::option).collect();
+
+    assert_eq!(
+        invalid,
+        ["port", "username", "verify_socket_peer", "ssl_root_cert"]
+    );
+}
+
+#[test]
+fn it_accepts_default_options() {
+    assert_eq!(PgConnectOptions::new().host("localhost").validate(), Ok(()));
 }
@@ -0,0 +1,126 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::HashMap;
+
+// past this many statements remembered, those captured longer than the interval ago are
+// forgotten; if none are, no other statement is captured until some are
+const THROTTLE_CAPACITY: usize = 1024;
+
+/// How the plans of slow statements are captured; see
+/// [`PgConnectOptions::explain_slow_statements`](super::PgConnectOptions::explain_slow_statements).
+#[derive(Debug, Clone)]
+pub struct PgExplainOptions {
+    pub(crate) analyze: bool,
+    pub(crate) min_interval: Duration,
+}
+
+impl PgExplainOptions {
+    /// Plans without `ANALYZE`, captured at most once a minute for each statement.
+    pub fn new() -> Self {
+        Self {
+            analyze: false,
+            min_interval: Duration::from_secs(60),
+        }
+    }
+
+    /// Runs the statement again under `EXPLAIN ANALYZE`, to include the actual row counts and
+    /// timings of its plan. Off by default.
+    ///
+    /// Only statements that start with `SELECT`, `VALUES` or `TABLE` are analyzed; others are
+    /// explained without `ANALYZE`. The statement runs in a transaction, or a savepoint, that
+    /// is rolled back, but functions it calls with effects outside the database (or on
+    /// sequences) are run again.
+    pub fn analyze(mut self, analyze: bool) -> Self {
+        self.analyze = analyze;
+        self
+    }
+
+    /// Sets the shortest time between two captures of the plan of the same SQL. The statements
+    /// are counted for all the connections made with the same options, such as those of a pool.
+    pub fn min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+}
+
+impl Default for PgExplainOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ExplainSettings {
+    pub(crate) threshold: Duration,
+    pub(crate) options: PgExplainOptions,
+    pub(crate) throttle: ExplainThrottle,
+}
+
+/// When the plan of each statement was last captured. Clones share the same record.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ExplainThrottle {
+    captured_at: Arc<Mutex<HashMap<u64, Instant>>>,
+}
+
+impl ExplainThrottle {
+    /// An empty record, sharing nothing with this one.
+    pub(crate) fn fresh(&self) -> Self {
+        Self::default()
+    }
+
+    /// Records a capture of the plan of `sql` now, unless the last one was less than `interval`
+    /// ago or too many statements were captured within it; returns `false` if so.
+    pub(crate) fn try_capture(&self, sql: &str, interval: Duration) -> bool {
+        let now = Instant::now();
+        let key = hash(sql);
+
+        let mut captured_at = match self.captured_at.lock() {
+            Ok(captured_at) => captured_at,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if let Some(at) = captured_at.get(&key) {
+            if now.duration_since(*at) < interval {
+                return false;
+            }
+        }
+
+        if captured_at.len() >= THROTTLE_CAPACITY {
+            captured_at.retain(|_, at| now.duration_since(*at) < interval);
+
+            if captured_at.len() >= THROTTLE_CAPACITY {
+                return false;
+            }
+        }
+
+        captured_at.insert(key, now);
+
+        true
+    }
+}
+
+fn hash(sql: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    sql.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+#[test]
+fn it_captures_each_statement_once_per_interval() {
+    let throttle = ExplainThrottle::default();
+    let interval = Duration::from_secs(60);
+
+    assert!(throttle.try_capture("SELECT 1", interval));
+    assert!(!throttle.try_capture("SELECT 1", interval));
+    assert!(throttle.try_capture("SELECT 2", interval));
+
+    // shared by clones, but not by fresh ones
+    assert!(!throttle.clone().try_capture("SELECT 1", interval));
+    assert!(throttle.fresh().try_capture("SELECT 1", interval));
+
+    assert!(throttle.try_capture("SELECT 1", Duration::from_secs(0)));
+}
@@ -1,11 +1,35 @@
 use std::env::var;
+use std::fmt::Display;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
+#[cfg(feature = "options-serde")]
+mod config;
 mod connect;
+mod explain;
 mod parse;
 mod ssl_mode;
-use crate::{connection::LogSettings, net::CertificateInput};
+mod ssl_negotiation;
+mod statement_cache_mode;
+use crate::column::ColumnNameMatching;
+use crate::connection::{LogSettings, ProtocolPolicy};
+use crate::events::{ConnectionInfo, EventListener};
+use crate::middleware::Middleware;
+use crate::net::{
+    unbracket, CertificateInput, SocketOwner, TlsSessionCache, DEFAULT_TLS_SESSION_CACHE_CAPACITY,
+};
+use crate::postgres::notice::PgNoticeHandler;
+use crate::postgres::PgNotice;
+use crate::wire::{WireTracer, WireTracing};
+pub(crate) use explain::ExplainSettings;
+pub use explain::PgExplainOptions;
 pub use ssl_mode::PgSslMode;
+pub use ssl_negotiation::PgSslNegotiation;
+pub use statement_cache_mode::PgStatementCacheMode;
+
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
 
 /// Options and flags which can be used to configure a PostgreSQL connection.
 ///
@@ -23,8 +47,11 @@ pub use ssl_mode::PgSslMode;
 /// |Parameter|Default|Description|
 /// |---------|-------|-----------|
 /// | `sslmode` | `prefer` | Determines whether or with what priority a secure SSL TCP/IP connection will be negotiated. See [`PgSslMode`]. |
+/// | `sslnegotiation` | `postgres` | Whether TLS is started with an `SSLRequest` or directly, `postgres` or `direct`. See [`PgSslNegotiation`]. |
 /// | `sslrootcert` | `None` | Sets the name of a file containing a list of trusted SSL Certificate Authorities. |
 /// | `statement-cache-capacity` | `100` | The maximum number of prepared statements stored in the cache. Set to `0` to disable. |
+/// | `statement-cache-max-bytes` | `None` | Bounds the cache by the estimated size of the statements in bytes instead. |
+/// | `statement-cache-mode` | `session` | How statements are prepared and cached: `session`, `transaction` or `describe-only`. See [`PgStatementCacheMode`]. |
 /// | `host` | `None` | Path to the directory containing a PostgreSQL unix domain socket, which will be used instead of TCP if set. |
 /// | `hostaddr` | `None` | Same as `host`, but only accepts IP addresses. |
 /// | `application-name` | `None` | The name will be displayed in the pg_stat_activity view and included in CSV log entries. |
@@ -32,6 +59,8 @@ pub use ssl_mode::PgSslMode;
 /// | `password` | `None` | Password to be used if the server demands password authentication. |
 /// | `port` | `5432` | Port number to connect to at the server host, or socket file name extension for Unix-domain connections. |
 /// | `dbname` | `None` | The database name. |
+/// | `strict-protocol` | `false` | Fails the connection on messages or omissions that PostgreSQL itself would not make. See [`PgConnectOptions::strict_protocol`]. |
+/// | `replication` | `None` | Set to `database` to open a logical replication connection. See [`PgConnectOptions::replication`]. |
 ///
 /// The URI scheme designator can be either `postgresql://` or `postgres://`.
 /// Each of the URI parts is optional.
@@ -75,15 +104,39 @@ pub use ssl_mode::PgSslMode;
 pub struct PgConnectOptions {
     pub(crate) host: String,
     pub(crate) port: u16,
+    pub(crate) addresses: Vec<SocketAddr>,
     pub(crate) socket: Option<PathBuf>,
+    pub(crate) socket_owner: Option<u32>,
+    pub(crate) verify_socket_peer: bool,
     pub(crate) username: String,
     pub(crate) password: Option<String>,
     pub(crate) database: Option<String>,
     pub(crate) ssl_mode: PgSslMode,
+    pub(crate) ssl_negotiation: PgSslNegotiation,
     pub(crate) ssl_root_cert: Option<CertificateInput>,
+    pub(crate) tls_session_cache: Option<TlsSessionCache>,
+    pub(crate) protocol_policy: Option<ProtocolPolicy>,
     pub(crate) statement_cache_capacity: usize,
+    pub(crate) statement_cache_max_bytes: Option<usize>,
+    pub(crate) statement_cache_mode: PgStatementCacheMode,
+    pub(crate) prefer_simple_protocol: bool,
+    pub(crate) strict_protocol: bool,
+    pub(crate) buffer_max: Option<usize>,
+    pub(crate) max_message_size: usize,
+    pub(crate) wire_tracing: Option<WireTracing>,
     pub(crate) application_name: Option<String>,
+    pub(crate) search_path: Option<Vec<String>>,
+    pub(crate) options: Vec<(String, String)>,
+    pub(crate) notice_handler: Option<PgNoticeHandler>,
+    pub(crate) max_notices: Option<usize>,
+    pub(crate) replication: bool,
+    pub(crate) read_only: bool,
+    pub(crate) read_only_check: bool,
+    pub(crate) column_name_matching: ColumnNameMatching,
     pub(crate) log_settings: LogSettings,
+    pub(crate) explain: Option<ExplainSettings>,
+    pub(crate) middleware: Middleware,
+    pub(crate) events: EventListener,
 }
 
 impl Default for PgConnectOptions {
@@ -124,18 +177,45 @@ impl PgConnectOptions {
         PgConnectOptions {
             port,
             host,
+            addresses: Vec::new(),
             socket: None,
+            socket_owner: None,
+            verify_socket_peer: false,
             username: var("PGUSER").ok().unwrap_or_else(whoami::username),
             password: var("PGPASSWORD").ok(),
             database: var("PGDATABASE").ok(),
             ssl_root_cert: var("PGSSLROOTCERT").ok().map(CertificateInput::from),
+            tls_session_cache: Some(TlsSessionCache::new(DEFAULT_TLS_SESSION_CACHE_CAPACITY)),
+            protocol_policy: None,
             ssl_mode: var("PGSSLMODE")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or_default(),
+            ssl_negotiation: var("PGSSLNEGOTIATION")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(PgSslNegotiation::Postgres),
             statement_cache_capacity: 100,
+            statement_cache_max_bytes: None,
+            statement_cache_mode: PgStatementCacheMode::Session,
+            prefer_simple_protocol: false,
+            strict_protocol: false,
+            buffer_max: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            wire_tracing: None,
             application_name: var("PGAPPNAME").ok(),
+            search_path: None,
+            options: Vec::new(),
+            notice_handler: None,
+            max_notices: None,
+            replication: false,
+            read_only: false,
+            read_only_check: true,
+            column_name_matching: ColumnNameMatching::default(),
             log_settings: Default::default(),
+            explain: None,
+            middleware: Default::default(),
+            events: Default::default(),
         }
     }
 
@@ -156,7 +236,7 @@ impl PgConnectOptions {
     ///     .host("localhost");
     /// ```
     pub fn host(mut self, host: &str) -> Self {
-        self.host = host.to_owned();
+        self.host = unbracket(host).to_owned();
         self
     }
 
@@ -176,6 +256,33 @@ impl PgConnectOptions {
         self
     }
 
+    /// Sets the address to connect to, so the host name is not resolved.
+    ///
+    /// The [host](Self::host) is still used to verify the server's TLS certificate, and the
+    /// port of the address is used instead of the [port](Self::port). A Unix domain socket
+    /// is not used while an address is set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .host("db.example.com")
+    ///     .address(([10, 0, 0, 5], 5432).into());
+    /// ```
+    pub fn address(self, address: SocketAddr) -> Self {
+        self.addresses(vec![address])
+    }
+
+    /// Sets the addresses to connect to, so the host name is not resolved. They are tried in
+    /// order until one accepts the connection.
+    ///
+    /// See [`address`](Self::address).
+    pub fn addresses(mut self, addresses: Vec<SocketAddr>) -> Self {
+        self.addresses = addresses;
+        self
+    }
+
     /// Sets a custom path to a directory containing a unix domain socket,
     /// switching the connection method from TCP to the corresponding socket.
     ///
@@ -185,6 +292,38 @@ impl PgConnectOptions {
         self
     }
 
+    /// Sets the user ID the Unix domain socket file must be owned by, so that a socket put in
+    /// place by another user is not connected to. Connecting fails with
+    /// [`Error::PolicyViolation`](crate::error::Error::PolicyViolation) before anything is sent
+    /// if it is owned by someone else.
+    ///
+    /// Has no effect on connections over TCP. By default set to `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .socket("/run/postgresql")
+    ///     .expected_socket_owner(26);
+    /// ```
+    pub fn expected_socket_owner(mut self, uid: u32) -> Self {
+        self.socket_owner = Some(uid);
+        self
+    }
+
+    /// Sets whether to also check, once connected, that the server process at the other end of
+    /// the Unix domain socket runs as the [expected owner](Self::expected_socket_owner) of the
+    /// socket, with `SO_PEERCRED` on Linux and `getpeereid` on macOS and the BSDs.
+    ///
+    /// Requires an expected owner, and fails to connect with the async-std runtime, which
+    /// cannot read the credentials of the peer. Has no effect on connections over TCP. Defaults
+    /// to `false`.
+    pub fn verify_socket_peer(mut self, verify: bool) -> Self {
+        self.verify_socket_peer = verify;
+        self
+    }
+
     /// Sets the username to connect as.
     ///
     /// Defaults to be the same as the operating system name of
@@ -231,6 +370,71 @@ impl PgConnectOptions {
         self
     }
 
+    /// Returns a copy of these options that connects to the database `database`, leaving these
+    /// options as they are.
+    ///
+    /// Everything else is shared with the copy, including the TLS session cache, the notice
+    /// handler and the query middleware.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let base = PgConnectOptions::new().host("db.example.com");
+    /// let tenant = base.with_database("tenant_42");
+    ///
+    /// assert_eq!(tenant.get_database(), Some("tenant_42"));
+    /// assert_eq!(tenant.get_host(), "db.example.com");
+    /// ```
+    pub fn with_database(&self, database: &str) -> Self {
+        self.clone().database(database)
+    }
+
+    /// Returns a copy of these options that connects as `username` with `password`, leaving
+    /// these options as they are.
+    pub fn with_credentials(&self, username: &str, password: &str) -> Self {
+        self.clone().username(username).password(password)
+    }
+
+    /// Returns a copy of these options that connects over TCP/IP to `host` and `port`, leaving
+    /// these options as they are.
+    ///
+    /// Any [addresses](Self::addresses) and [socket](Self::socket) set on these options are not
+    /// copied, as they belong to the original host.
+    pub fn with_host_port(&self, host: &str, port: u16) -> Self {
+        let mut options = self.clone().host(host).port(port);
+
+        options.addresses = Vec::new();
+        options.socket = None;
+        options
+    }
+
+    /// The name of the host to connect to.
+    pub fn get_host(&self) -> &str {
+        &self.host
+    }
+
+    /// The port to connect to at the server host.
+    pub fn get_port(&self) -> u16 {
+        self.port
+    }
+
+    /// The path of the directory holding the Unix domain socket to connect to, if one is set.
+    pub fn get_socket(&self) -> Option<&Path> {
+        self.socket.as_deref()
+    }
+
+    /// The username to connect as.
+    pub fn get_username(&self) -> &str {
+        &self.username
+    }
+
+    /// The name of the database to connect to, if one is set; the server defaults to the name
+    /// of the user.
+    pub fn get_database(&self) -> Option<&str> {
+        self.database.as_deref()
+    }
+
     /// Sets whether or with what priority a secure SSL TCP/IP connection will be negotiated
     /// with the server.
     ///
@@ -251,6 +455,26 @@ impl PgConnectOptions {
         self
     }
 
+    /// Sets whether TLS is started with an `SSLRequest`, or directly once connected, which
+    /// takes one round trip less but needs Postgres 17 or later. See [`PgSslNegotiation`] for
+    /// how each [`PgSslMode`] falls back when the server does not support it.
+    ///
+    /// By default, the negotiation is [`Postgres`](PgSslNegotiation::Postgres), with an
+    /// `SSLRequest`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::{PgSslMode, PgSslNegotiation, PgConnectOptions};
+    /// let options = PgConnectOptions::new()
+    ///     .ssl_mode(PgSslMode::Require)
+    ///     .ssl_negotiation(PgSslNegotiation::Direct);
+    /// ```
+    pub fn ssl_negotiation(mut self, negotiation: PgSslNegotiation) -> Self {
+        self.ssl_negotiation = negotiation;
+        self
+    }
+
     /// Sets the name of a file containing SSL certificate authority (CA) certificate(s).
     /// If the file exists, the server's certificate will be verified to be signed by
     /// one of these authorities.
@@ -285,6 +509,86 @@ impl PgConnectOptions {
         self
     }
 
+    /// Sets how far the server may weaken the security of the connection while it is being
+    /// established.
+    ///
+    /// Without a policy, [`ProtocolPolicy::strict`] applies if the SSL mode is
+    /// [`Require`](PgSslMode::Require) or stronger or a root certificate has been set, and
+    /// [`ProtocolPolicy::permissive`] otherwise. Note that a server which refuses TLS under
+    /// [`Prefer`](PgSslMode::Prefer) looks exactly like an attacker stripping the TLS request,
+    /// which the strict policy does not allow.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::connection::ProtocolPolicy;
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .protocol_policy(ProtocolPolicy {
+    ///         allow_plaintext_fallback: false,
+    ///         ..ProtocolPolicy::permissive()
+    ///     });
+    /// ```
+    pub fn protocol_policy(mut self, policy: ProtocolPolicy) -> Self {
+        self.protocol_policy = Some(policy);
+        self
+    }
+
+    pub(crate) fn effective_protocol_policy(&self) -> ProtocolPolicy {
+        self.protocol_policy.unwrap_or_else(|| {
+            ProtocolPolicy::default_for(
+                self.ssl_mode.requires_tls() || self.ssl_root_cert.is_some(),
+            )
+        })
+    }
+
+    /// Caps the capacity, in bytes, that the connection's read and write buffers keep between
+    /// messages.
+    ///
+    /// Messages larger than this are still sent and received, but through a temporary buffer
+    /// that is freed as soon as the message has been handled, so one very large result set
+    /// does not leave the connection holding on to that much memory.
+    ///
+    /// By default the buffers are never shrunk; see also [`Connection::shrink_buffers`].
+    ///
+    /// [`Connection::shrink_buffers`]: crate::connection::Connection::shrink_buffers
+    pub fn buffer_max(mut self, max: usize) -> Self {
+        self.buffer_max = Some(max);
+        self
+    }
+
+    /// Sets the largest message, in bytes, the connection accepts from the server.
+    ///
+    /// The length of each message is sent ahead of it and the whole message is read into memory
+    /// before it is decoded, so without a limit a broken server or proxy could have the client
+    /// attempt an allocation of up to 4 GiB. A message that is longer fails the connection
+    /// with a protocol error. Defaults to 64 MiB; a single row or notice can be no larger than
+    /// this.
+    pub fn max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = max;
+        self
+    }
+
+    /// Sets a tracer to be called with every protocol message the connection sends or
+    /// receives, after TLS has been stripped; see [`wire`](crate::wire).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// # use sqlx_core::wire::FileWireTracer;
+    /// # fn main() -> std::io::Result<()> {
+    /// let options = PgConnectOptions::new()
+    ///     .wire_tracing(Arc::new(FileWireTracer::create("wire.log")?));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn wire_tracing(mut self, tracer: Arc<dyn WireTracer>) -> Self {
+        self.wire_tracing = Some(WireTracing(tracer));
+        self
+    }
+
     /// Sets the capacity of the connection's statement cache in a number of stored
     /// distinct statements. Caching is handled using LRU, meaning when the
     /// amount of queries hits the defined limit, the oldest statement will get
@@ -296,7 +600,111 @@ impl PgConnectOptions {
         self
     }
 
-    /// Sets the application name. Defaults to None
+    /// Bounds the connection's statement cache by the estimated memory of the statements,
+    /// their SQL and the metadata describing their parameters and columns, instead of by their
+    /// number. Statements that take much more room than others, such as those returning
+    /// many columns, then count for more.
+    ///
+    /// This replaces the bound set with
+    /// [`statement_cache_capacity`](Self::statement_cache_capacity).
+    pub fn statement_cache_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.statement_cache_max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Sets how statements are prepared and how long they are assumed to persist on the
+    /// server.
+    ///
+    /// The default, [`Session`](PgStatementCacheMode::Session), keeps named statements for the
+    /// lifetime of the connection, which fails behind a pooler that moves the connection to
+    /// another server session between transactions, e.g. PgBouncer in transaction pooling
+    /// mode. Use [`Transaction`](PgStatementCacheMode::Transaction) or
+    /// [`DescribeOnly`](PgStatementCacheMode::DescribeOnly) there.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::{PgConnectOptions, PgStatementCacheMode};
+    /// let options = PgConnectOptions::new()
+    ///     .statement_cache_mode(PgStatementCacheMode::DescribeOnly);
+    /// ```
+    pub fn statement_cache_mode(mut self, mode: PgStatementCacheMode) -> Self {
+        self.statement_cache_mode = mode;
+        self
+    }
+
+    /// Runs every query with the simple query protocol, writing its bind values into the SQL,
+    /// for proxies that cannot handle prepared statements at all, e.g. PgBouncer in statement
+    /// pooling mode. Off by default.
+    ///
+    /// Each value is written as a string literal cast to the type it was bound as, e.g.
+    /// `('42'::pg_catalog."int4")`. Literals are quoted like
+    /// [`PgConnection::quote_literal`](crate::postgres::PgConnection::quote_literal) does, for
+    /// the `standard_conforming_strings` the server last reported, and the placeholders are
+    /// found by the same rules the server reads the SQL with: `$1` inside a string, a quoted
+    /// identifier, a dollar-quoted string or a comment is left alone. So a value cannot change
+    /// the statement it is written into, as long as the SQL of the query itself is trusted;
+    /// a value that cannot be written safely, such as a string with a NUL byte, fails the
+    /// query with [`Error::ArgumentEncode`] before anything is sent.
+    ///
+    /// Supported types are `BOOL`, `INT2`, `INT4`, `INT8`, `OID`, `FLOAT4`, `FLOAT8`,
+    /// `NUMERIC`, `TEXT`, `VARCHAR`, `CHAR`, `NAME`, `JSON`, `JSONB`, `BYTEA` (written in the
+    /// hex format), `UUID`, `DATE`, `TIME`, `TIMESTAMP`, `TIMESTAMPTZ` and `INTERVAL`, and
+    /// one-dimensional arrays of them. Other types, including enums, composites and domains,
+    /// fail with [`Error::ArgumentEncode`]; bind them as text and cast in the SQL instead.
+    ///
+    /// Results come back in the text format. [`prepare`](crate::executor::Executor::prepare)
+    /// and [`describe`](crate::executor::Executor::describe), used by the `query!` macros,
+    /// still need the server to parse the statement, which uses the unnamed statement: it is
+    /// replaced by the next one and never outlives the transaction. Nullability is then only
+    /// inferred from the columns of tables, not from the plan of the statement, and
+    /// [`explain_slow_statements`](Self::explain_slow_statements) captures no plans.
+    ///
+    /// [`Error::ArgumentEncode`]: crate::error::Error::ArgumentEncode
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new().prefer_simple_protocol(true);
+    /// ```
+    pub fn prefer_simple_protocol(mut self, prefer: bool) -> Self {
+        self.prefer_simple_protocol = prefer;
+        self
+    }
+
+    /// Fails the connection when the server departs from what PostgreSQL itself does, rather
+    /// than carrying on. Off by default.
+    ///
+    /// Servers that speak the protocol of PostgreSQL without being it, such as the admin
+    /// console of PgBouncer, Materialize or QuestDB, may leave out parts of it. Unless this is
+    /// set:
+    ///
+    ///  * a server that sends no `BackendKeyData` while connecting is accepted, and
+    ///    [`PgConnection::cancel_token`](crate::postgres::PgConnection::cancel_token) returns
+    ///    an error on its connections, which cannot be cancelled;
+    ///  * a parameter the driver relies on that the server does not report, such as
+    ///    `standard_conforming_strings`, is taken to have the value PostgreSQL reports by
+    ///    default, and logged at the `debug` level;
+    ///  * a message of a type the driver does not know is skipped, and logged at the `debug`
+    ///    level, instead of failing the query it arrives with.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new().strict_protocol(true);
+    /// ```
+    pub fn strict_protocol(mut self, strict: bool) -> Self {
+        self.strict_protocol = strict;
+        self
+    }
+
+    /// Sets the application name, shown in `pg_stat_activity` and the server log.
+    ///
+    /// Defaults to the value of `PGAPPNAME` if set, otherwise to the name of the running
+    /// executable. The name is truncated to 63 bytes and characters that are not printable
+    /// ASCII are replaced with `?`, as the server would do.
     ///
     /// # Example
     ///
@@ -310,6 +718,316 @@ impl PgConnectOptions {
         self
     }
 
+    /// Sets whether TLS sessions are cached, so that reconnecting to the server can resume a
+    /// previous session instead of performing a full handshake.
+    ///
+    /// Enabled by default. A pool keeps a cache of its own, so sessions are never shared between
+    /// pools; connections opened directly share the cache of the options they are opened with.
+    ///
+    /// Only the `rustls` backend supports this; with `native-tls` it has no effect.
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .tls_session_cache(false);
+    /// ```
+    pub fn tls_session_cache(mut self, enabled: bool) -> Self {
+        if !enabled {
+            self.tls_session_cache = None;
+        } else if self.tls_session_cache.is_none() {
+            self.tls_session_cache = Some(TlsSessionCache::new(DEFAULT_TLS_SESSION_CACHE_CAPACITY));
+        }
+
+        self
+    }
+
+    /// Sets how many TLS sessions the session cache holds, enabling it if it was disabled.
+    ///
+    /// The default capacity is 32 sessions; as sessions are kept per server, this only needs
+    /// to be larger when connecting to several hosts.
+    pub fn tls_session_cache_capacity(mut self, capacity: usize) -> Self {
+        self.tls_session_cache = Some(TlsSessionCache::new(capacity));
+        self
+    }
+
+    /// A copy of these options whose application name (or the default name) ends in `suffix`.
+    // what a `ConnectionEventListener` is told about a connection made with these options
+    pub(crate) fn connection_info(&self) -> ConnectionInfo {
+        let peer_addr = match &self.socket {
+            Some(socket) => socket.display().to_string(),
+            None => format!("{}:{}", self.host, self.port),
+        };
+
+        ConnectionInfo {
+            peer_addr: Some(peer_addr),
+            database_name: Some(self.database.as_ref().unwrap_or(&self.username).clone()),
+            application_name: self
+                .application_name
+                .as_deref()
+                .or_else(|| crate::common::default_application_name())
+                .map(str::to_owned),
+            ..ConnectionInfo::new("PostgreSQL")
+        }
+    }
+
+    pub(crate) fn with_name_suffix(&self, suffix: &str) -> Self {
+        let name = self
+            .application_name
+            .as_deref()
+            .or_else(|| crate::common::default_application_name())
+            .unwrap_or_default();
+
+        self.clone()
+            .application_name(&format!("{}{}", name, suffix))
+    }
+
+    /// Sets whether to open the connection in logical replication mode (`replication=database`).
+    ///
+    /// A replication connection accepts the
+    /// [replication commands](https://www.postgresql.org/docs/current/protocol-replication.html)
+    /// as well as simple queries, but not prepared statements, so queries with bind parameters
+    /// will fail. See [`PgReplication`](crate::postgres::PgReplication) for consuming a
+    /// replication slot.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .replication(true);
+    /// ```
+    pub fn replication(mut self, replication: bool) -> Self {
+        self.replication = replication;
+        self
+    }
+
+    /// Sets whether the session is read-only.
+    ///
+    /// A read-only session is opened with `default_transaction_read_only = on`, so the server
+    /// rejects writes. In addition, every statement is checked before it is sent: anything that
+    /// does not start with a reading keyword (`SELECT`, `WITH`, `VALUES`, `TABLE`, `SHOW`,
+    /// `EXPLAIN` without `ANALYZE`, or a transaction command), and any string holding more than
+    /// one statement, fails with [`Error::ReadOnlyViolation`] without reaching the server.
+    /// See [`read_only_statement_check`](Self::read_only_statement_check) to turn the check off.
+    ///
+    /// Neither is a substitute for a database role without write privileges: a session
+    /// setting can be changed by a function, for example.
+    ///
+    /// [`Error::ReadOnlyViolation`]: crate::error::Error::ReadOnlyViolation
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .read_only(true);
+    /// ```
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Sets whether a [read-only](Self::read_only) connection checks statements before
+    /// sending them. Enabled by default; has no effect unless the connection is read-only.
+    ///
+    /// Turn it off for a read-only [`PgListener`](crate::postgres::PgListener) that listens on
+    /// several channels at once, as those are subscribed to in one multi-statement query.
+    pub fn read_only_statement_check(mut self, check: bool) -> Self {
+        self.read_only_check = check;
+        self
+    }
+
+    /// Sets how a column name given to [`Row::try_get`], or read by `#[derive(FromRow)]`, is
+    /// matched when no column has exactly that name. Postgres folds unquoted identifiers to
+    /// lower case, so a field `userId` finds no column `userid` unless a looser comparison is
+    /// set here. By default, only a column with exactly the name is found.
+    ///
+    /// See [`ColumnNameMatching`] for the comparisons and how ambiguous names fail.
+    ///
+    /// [`Row::try_get`]: crate::row::Row::try_get
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::column::ColumnNameMatching;
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .column_name_matching(ColumnNameMatching::SnakeCaseInsensitive);
+    /// ```
+    pub fn column_name_matching(mut self, matching: ColumnNameMatching) -> Self {
+        self.column_name_matching = matching;
+        self
+    }
+
+    /// Sets the schemas to look up unqualified names in, in order.
+    ///
+    /// Each schema is quoted with [`quote_identifier`](crate::postgres::quote_identifier), so
+    /// names are taken literally (and case-sensitively), and sent as the `search_path` startup
+    /// parameter. A schema name containing a NUL byte makes connecting fail. A `search_path`
+    /// given to [`options`](Self::options) takes precedence.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .search_path(&["tenant_42", "public"]);
+    /// ```
+    pub fn search_path(mut self, schemas: &[&str]) -> Self {
+        self.search_path = Some(schemas.iter().map(|&schema| schema.to_owned()).collect());
+        self
+    }
+
+    /// Sets the time zone of the session, which is `UTC` unless set.
+    ///
+    /// Like the other settings of the session here, it is sent in the startup message rather
+    /// than with `SET` after connecting, which would take a round trip of its own. A `TimeZone`
+    /// given to [`options`](Self::options) takes precedence.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .timezone("Europe/Berlin");
+    /// ```
+    pub fn timezone(mut self, timezone: &str) -> Self {
+        self.set_option("TimeZone".to_owned(), timezone.to_owned());
+        self
+    }
+
+    /// Sets the `statement_timeout` of the session: the server cancels any statement running
+    /// for longer, failing it with SQLSTATE `57014`. Sent in the startup message, rounded down
+    /// to milliseconds; a timeout of zero turns it off.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// # use std::time::Duration;
+    /// let options = PgConnectOptions::new()
+    ///     .statement_timeout(Duration::from_secs(30));
+    /// ```
+    pub fn statement_timeout(mut self, timeout: Duration) -> Self {
+        self.set_option(
+            "statement_timeout".to_owned(),
+            format!("{}ms", timeout.as_millis()),
+        );
+        self
+    }
+
+    /// Sets run-time parameters to be sent to the server in the startup message.
+    ///
+    /// This is equivalent to `-c key=value` in the libpq `options` connection parameter,
+    /// which is also accepted in a connection URI (`?options=-c%20search_path%3Dmyschema`).
+    /// Setting the same parameter twice keeps the last value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .options([("search_path", "myschema"), ("geqo", "off")]);
+    /// ```
+    pub fn options<K, V, I>(mut self, options: I) -> Self
+    where
+        K: Display,
+        V: Display,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, value) in options {
+            self.set_option(key.to_string(), value.to_string());
+        }
+
+        self
+    }
+
+    fn set_option(&mut self, key: String, value: String) {
+        if let Some(existing) = self.options.iter_mut().find(|(k, _)| *k == key) {
+            log::debug!(
+                "startup parameter {:?} set more than once; using {:?} instead of {:?}",
+                key,
+                value,
+                existing.1
+            );
+
+            existing.1 = value;
+        } else {
+            self.options.push((key, value));
+        }
+    }
+
+    /// Sets a callback to be invoked for every notice or warning sent by the server.
+    ///
+    /// Notices are always logged under the `sqlx::postgres::notice` target at a level
+    /// matching their severity; the handler is called in addition to that. A panic
+    /// inside the handler is caught and logged so it cannot break the connection.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// # use std::sync::Arc;
+    /// let options = PgConnectOptions::new()
+    ///     .notice_handler(Arc::new(|notice| println!("{}", notice.message())));
+    /// ```
+    pub fn notice_handler(mut self, handler: Arc<dyn Fn(PgNotice) + Send + Sync>) -> Self {
+        self.notice_handler = Some(PgNoticeHandler(handler));
+        self
+    }
+
+    /// Caps the number of notices of one query that are logged and passed to the
+    /// [notice handler](Self::notice_handler).
+    ///
+    /// A trigger or function that raises notices in a loop can send a great many of them; past
+    /// the cap, further notices of the query are only counted, in
+    /// [`ConnectionStats::notices_suppressed`](crate::connection::ConnectionStats::notices_suppressed),
+    /// and a warning is logged once. The count starts over with each query. Unlimited by
+    /// default.
+    pub fn max_notices_per_query(mut self, max: usize) -> Self {
+        self.max_notices = Some(max);
+        self
+    }
+
+    /// Captures the plan of statements that take `threshold` or longer, and includes it in the
+    /// line logged for the statement. Off by default.
+    ///
+    /// Once such a statement has completed, it is sent again as `EXPLAIN (FORMAT JSON)`, with
+    /// the same bind values, on the same connection. Plans are only captured for statements
+    /// whose results are read to the end (not those of `fetch_one` or `fetch_optional`), whose
+    /// line is logged at a level that is enabled (see
+    /// [`log_slow_statements`](crate::connection::ConnectOptions::log_slow_statements)), and
+    /// that `EXPLAIN` accepts: `SELECT`, `WITH`, `VALUES`, `TABLE`, `INSERT`, `UPDATE`,
+    /// `DELETE`, `MERGE`, `EXECUTE` and `DECLARE`. The `EXPLAIN` itself is neither logged nor
+    /// explained.
+    ///
+    /// Inside a transaction, the `EXPLAIN` runs in a savepoint, so that an error in it leaves
+    /// the transaction usable; errors are logged at the `debug` level and otherwise ignored.
+    /// Nothing is captured in a failed transaction.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::{PgConnectOptions, PgExplainOptions};
+    /// # use std::time::Duration;
+    /// let options = PgConnectOptions::new().explain_slow_statements(
+    ///     Duration::from_secs(1),
+    ///     PgExplainOptions::new().min_interval(Duration::from_secs(300)),
+    /// );
+    /// ```
+    pub fn explain_slow_statements(
+        mut self,
+        threshold: Duration,
+        options: PgExplainOptions,
+    ) -> Self {
+        self.explain = Some(ExplainSettings {
+            threshold,
+            options,
+            throttle: Default::default(),
+        });
+        self
+    }
+
     /// We try using a socket if hostname starts with `/` or if socket parameter
     /// is specified.
     pub(crate) fn fetch_socket(&self) -> Option<String> {
@@ -325,6 +1043,13 @@ impl PgConnectOptions {
             _ => None,
         }
     }
+
+    pub(crate) fn fetch_socket_owner(&self) -> Option<SocketOwner> {
+        self.socket_owner.map(|uid| SocketOwner {
+            uid,
+            check_peer: self.verify_socket_peer,
+        })
+    }
 }
 
 fn default_host(port: u16) -> String {
@@ -345,3 +1070,41 @@ fn default_host(port: u16) -> String {
     // fallback to localhost if no socket was found
     "localhost".to_owned()
 }
+
+#[test]
+fn it_derives_options_without_changing_the_original() {
+    let base = PgConnectOptions::new()
+        .host("db.example.com")
+        .port(6432)
+        .socket("/run/postgresql")
+        .address(([10, 0, 0, 5], 6432).into())
+        .username("app")
+        .password("secret")
+        .database("orders")
+        .application_name("api");
+
+    let tenant = base.with_database("tenant_42");
+
+    assert_eq!(tenant.get_database(), Some("tenant_42"));
+    assert_eq!(tenant.application_name.as_deref(), Some("api"));
+    assert_eq!(base.get_database(), Some("orders"));
+
+    let other = base.with_credentials("tenant", "hunter2");
+
+    assert_eq!(other.get_username(), "tenant");
+    assert_eq!(other.password.as_deref(), Some("hunter2"));
+    assert_eq!(base.get_username(), "app");
+    assert_eq!(base.password.as_deref(), Some("secret"));
+
+    let moved = base.with_host_port("replica.example.com", 5433);
+
+    assert_eq!(
+        (moved.get_host(), moved.get_port()),
+        ("replica.example.com", 5433)
+    );
+    assert!(moved.addresses.is_empty());
+    assert_eq!(moved.get_socket(), None);
+    assert_eq!((base.get_host(), base.get_port()), ("db.example.com", 6432));
+    assert_eq!(base.addresses.len(), 1);
+    assert_eq!(base.get_socket(), Some(Path::new("/run/postgresql")));
+}
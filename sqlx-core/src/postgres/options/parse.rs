@@ -1,19 +1,19 @@
 use crate::error::Error;
+use crate::net::parse_url;
 use crate::postgres::PgConnectOptions;
 use percent_encoding::percent_decode_str;
 use std::net::IpAddr;
 use std::str::FromStr;
-use url::Url;
 
 impl FromStr for PgConnectOptions {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Error> {
-        let url: Url = s.parse().map_err(Error::config)?;
+        let (url, host) = parse_url(s)?;
 
         let mut options = Self::default();
 
-        if let Some(host) = url.host_str() {
+        if let Some(host) = &host {
             let host_decoded = percent_decode_str(host);
             options = match host_decoded.clone().next() {
                 Some(b'/') => options.socket(&*host_decoded.decode_utf8().map_err(Error::config)?),
@@ -53,6 +53,10 @@ impl FromStr for PgConnectOptions {
                     options = options.ssl_mode(value.parse().map_err(Error::config)?);
                 }
 
+                "sslnegotiation" | "ssl-negotiation" => {
+                    options = options.ssl_negotiation(value.parse()?);
+                }
+
                 "sslrootcert" | "ssl-root-cert" | "ssl-ca" => {
                     options = options.ssl_root_cert(&*value);
                 }
@@ -62,6 +66,23 @@ impl FromStr for PgConnectOptions {
                         options.statement_cache_capacity(value.parse().map_err(Error::config)?);
                 }
 
+                "statement-cache-max-bytes" => {
+                    options =
+                        options.statement_cache_max_bytes(value.parse().map_err(Error::config)?);
+                }
+
+                "statement-cache-mode" => {
+                    options = options.statement_cache_mode(value.parse()?);
+                }
+
+                "prefer-simple-protocol" => {
+                    options = options.prefer_simple_protocol(value.parse().map_err(Error::config)?);
+                }
+
+                "strict-protocol" => {
+                    options = options.strict_protocol(value.parse().map_err(Error::config)?);
+                }
+
                 "host" => {
                     if value.starts_with("/") {
                         options = options.socket(&*value);
@@ -85,6 +106,24 @@ impl FromStr for PgConnectOptions {
 
                 "application_name" => options = options.application_name(&*value),
 
+                "options" => options = options.options(parse_options(&value)?),
+
+                "replication" => {
+                    options = options.replication(match &*value {
+                        "database" => true,
+                        "false" | "off" | "no" | "0" => false,
+                        _ => {
+                            return Err(Error::Configuration(
+                                format!(
+                                    "unsupported replication mode {:?}; only `database` (logical replication) is supported",
+                                    value
+                                )
+                                .into(),
+                            ))
+                        }
+                    })
+                }
+
                 _ => log::warn!("ignoring unrecognized connect parameter: {}={}", key, value),
             }
         }
@@ -93,6 +132,106 @@ impl FromStr for PgConnectOptions {
     }
 }
 
+/// Splits a libpq `options` string (e.g. `-c geqo=off --search_path=myschema`) into
+/// key/value pairs.
+fn parse_options(s: &str) -> Result<Vec<(String, String)>, Error> {
+    // arguments are separated by whitespace; a backslash escapes the next character
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => arg.extend(chars.next()),
+
+            c if c.is_whitespace() => {
+                if !arg.is_empty() {
+                    args.push(std::mem::take(&mut arg));
+                }
+            }
+
+            c => arg.push(c),
+        }
+    }
+
+    if !arg.is_empty() {
+        args.push(arg);
+    }
+
+    let mut settings = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let setting = if arg == "-c" {
+            args.next().ok_or_else(|| {
+                Error::Configuration("expected a setting after `-c` in `options`".into())
+            })?
+        } else if let Some(setting) = arg.strip_prefix("-c") {
+            setting.to_owned()
+        } else if let Some(setting) = arg.strip_prefix("--") {
+            // `--name=value` is shorthand for `-c name=value`, with dashes in the name
+            // standing in for underscores
+            match setting.find('=') {
+                Some(i) => format!("{}{}", setting[..i].replace('-', "_"), &setting[i..]),
+                None => setting.to_owned(),
+            }
+        } else {
+            return Err(Error::Configuration(
+                format!("unsupported argument in `options`: {:?}", arg).into(),
+            ));
+        };
+
+        match setting.find('=') {
+            Some(i) => settings.push((setting[..i].to_owned(), setting[i + 1..].to_owned())),
+
+            None => {
+                return Err(Error::Configuration(
+                    format!("expected `name=value` in `options`, got {:?}", setting).into(),
+                ));
+            }
+        }
+    }
+
+    Ok(settings)
+}
+
+#[test]
+fn it_parses_options_correctly() {
+    let uri = "postgres:///?options=-c%20search_path%3Dmyschema%20-cgeqo%3Doff%20--statement-timeout%3D5s";
+    let opts = PgConnectOptions::from_str(uri).unwrap();
+
+    assert_eq!(
+        opts.options,
+        [
+            ("search_path".to_owned(), "myschema".to_owned()),
+            ("geqo".to_owned(), "off".to_owned()),
+            ("statement_timeout".to_owned(), "5s".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn it_parses_escaped_options_with_the_last_value_winning() {
+    let opts = PgConnectOptions::new().options(
+        parse_options(r"-c search_path=a -c application_name=my\ app -c search_path=c\,d").unwrap(),
+    );
+
+    assert_eq!(
+        opts.options,
+        [
+            ("search_path".to_owned(), "c,d".to_owned()),
+            ("application_name".to_owned(), "my app".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn it_rejects_malformed_options() {
+    assert!(parse_options("-c").is_err());
+    assert!(parse_options("-c geqo").is_err());
+    assert!(parse_options("geqo=off").is_err());
+}
+
 #[test]
 fn it_parses_socket_correctly_from_parameter() {
     let uri = "postgres:///?host=/var/run/postgres/";
@@ -119,6 +258,25 @@ fn it_parses_hostaddr_correctly_from_parameter() {
     assert_eq!("8.8.8.8", &opts.host);
 }
 
+#[test]
+fn it_parses_ipv6_hosts() {
+    let opts = PgConnectOptions::from_str("postgres://[::1]:5433/db").unwrap();
+
+    assert_eq!("::1", &opts.host);
+    assert_eq!(5433, opts.port);
+    assert_eq!(Some("db"), opts.database.as_deref());
+
+    let opts = PgConnectOptions::from_str("postgres://u@[fe80::1%25eth0]/db").unwrap();
+
+    assert_eq!("fe80::1%eth0", &opts.host);
+    assert_eq!("u", &opts.username);
+
+    let opts = PgConnectOptions::from_str("postgres:///db?host=[2001:db8::2]").unwrap();
+
+    assert_eq!("2001:db8::2", &opts.host);
+    assert_eq!("::1", &PgConnectOptions::new().host("[::1]").host);
+}
+
 #[test]
 fn it_parses_port_correctly_from_parameter() {
     let uri = "postgres:///?port=1234";
@@ -195,3 +353,93 @@ fn it_parses_socket_correctly_with_username_percent_encoded() {
     assert_eq!(Some("/var/lib/postgres/".into()), opts.socket);
     assert_eq!(Some("database"), opts.database.as_deref());
 }
+
+#[test]
+fn it_parses_statement_cache_mode() {
+    use super::PgStatementCacheMode;
+
+    let opts = PgConnectOptions::from_str("postgres:///mydb").unwrap();
+    assert_eq!(opts.statement_cache_mode, PgStatementCacheMode::Session);
+
+    let opts =
+        PgConnectOptions::from_str("postgres:///mydb?statement-cache-mode=transaction").unwrap();
+    assert_eq!(opts.statement_cache_mode, PgStatementCacheMode::Transaction);
+
+    let opts =
+        PgConnectOptions::from_str("postgres:///mydb?statement-cache-mode=describe-only").unwrap();
+    assert_eq!(
+        opts.statement_cache_mode,
+        PgStatementCacheMode::DescribeOnly
+    );
+
+    assert!(PgConnectOptions::from_str("postgres:///mydb?statement-cache-mode=always").is_err());
+}
+
+#[test]
+fn it_parses_prefer_simple_protocol() {
+    let opts = PgConnectOptions::from_str("postgres:///mydb").unwrap();
+    assert!(!opts.prefer_simple_protocol);
+
+    let opts = PgConnectOptions::from_str("postgres:///mydb?prefer-simple-protocol=true").unwrap();
+    assert!(opts.prefer_simple_protocol);
+
+    assert!(PgConnectOptions::from_str("postgres:///mydb?prefer-simple-protocol=yes").is_err());
+}
+
+#[test]
+fn it_parses_strict_protocol() {
+    let opts = PgConnectOptions::from_str("postgres:///mydb").unwrap();
+    assert!(!opts.strict_protocol);
+
+    let opts = PgConnectOptions::from_str("postgres:///mydb?strict-protocol=true").unwrap();
+    assert!(opts.strict_protocol);
+}
+
+#[test]
+fn it_parses_replication_mode() {
+    let opts = PgConnectOptions::from_str("postgres:///mydb?replication=database").unwrap();
+    assert!(opts.replication);
+
+    let opts = PgConnectOptions::from_str("postgres:///mydb?replication=off").unwrap();
+    assert!(!opts.replication);
+
+    // physical replication is not supported
+    assert!(PgConnectOptions::from_str("postgres:///mydb?replication=true").is_err());
+}
+
+#[test]
+fn it_picks_a_strict_protocol_policy_when_tls_is_required() {
+    use crate::connection::ProtocolPolicy;
+
+    let opts = PgConnectOptions::from_str("postgres:///mydb?sslmode=prefer").unwrap();
+    assert_eq!(
+        opts.effective_protocol_policy(),
+        ProtocolPolicy::permissive()
+    );
+
+    let opts = PgConnectOptions::from_str("postgres:///mydb?sslmode=require").unwrap();
+    assert_eq!(opts.effective_protocol_policy(), ProtocolPolicy::strict());
+
+    let opts = PgConnectOptions::from_str("postgres:///mydb?sslrootcert=ca.pem").unwrap();
+    assert_eq!(opts.effective_protocol_policy(), ProtocolPolicy::strict());
+
+    // an explicit policy always wins
+    let opts = opts.protocol_policy(ProtocolPolicy::permissive());
+    assert_eq!(
+        opts.effective_protocol_policy(),
+        ProtocolPolicy::permissive()
+    );
+}
+
+#[test]
+fn it_parses_ssl_negotiation() {
+    use super::PgSslNegotiation;
+
+    let opts = PgConnectOptions::from_str("postgres:///mydb").unwrap();
+    assert_eq!(opts.ssl_negotiation, PgSslNegotiation::Postgres);
+
+    let opts = PgConnectOptions::from_str("postgres:///mydb?sslnegotiation=direct").unwrap();
+    assert_eq!(opts.ssl_negotiation, PgSslNegotiation::Direct);
+
+    assert!(PgConnectOptions::from_str("postgres:///mydb?sslnegotiation=gss").is_err());
+}
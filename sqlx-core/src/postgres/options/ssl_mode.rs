@@ -28,6 +28,15 @@ pub enum PgSslMode {
     VerifyFull,
 }
 
+impl PgSslMode {
+    pub(crate) fn requires_tls(self) -> bool {
+        matches!(
+            self,
+            PgSslMode::Require | PgSslMode::VerifyCa | PgSslMode::VerifyFull
+        )
+    }
+}
+
 impl Default for PgSslMode {
     fn default() -> Self {
         PgSslMode::Prefer
@@ -0,0 +1,45 @@
+use crate::error::Error;
+use std::str::FromStr;
+
+/// How a connection starts TLS with the server.
+///
+/// It is used by the [`ssl_negotiation`](super::PgConnectOptions::ssl_negotiation) method.
+/// What is tried, in order, for each [`PgSslMode`](super::PgSslMode):
+///
+/// | `sslmode` | `Postgres` | `Direct` |
+/// |---|---|---|
+/// | `disable` | plaintext | plaintext |
+/// | `allow` | plaintext, then `SSLRequest` | plaintext, then direct TLS |
+/// | `prefer` | `SSLRequest`, plaintext if refused | direct TLS, then `SSLRequest`, plaintext if refused |
+/// | `require`, `verify-ca`, `verify-full` | `SSLRequest` | direct TLS |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgSslNegotiation {
+    /// Ask the server whether it accepts TLS with an `SSLRequest`, and start the handshake once
+    /// it has answered. Supported by every version of Postgres.
+    Postgres,
+
+    /// Start the TLS handshake as soon as the connection is open, saving the round trip of the
+    /// `SSLRequest`. Requires Postgres 17 or later, or a proxy that supports it, and rustls,
+    /// as the server requires the `postgresql` ALPN protocol.
+    ///
+    /// Older servers close the connection on the handshake, which fails the connection except
+    /// under `sslmode=prefer`, where a second connection negotiates TLS with an `SSLRequest`.
+    Direct,
+}
+
+impl FromStr for PgSslNegotiation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "postgres" => PgSslNegotiation::Postgres,
+            "direct" => PgSslNegotiation::Direct,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `ssl_negotiation`", s).into(),
+                ));
+            }
+        })
+    }
+}
@@ -0,0 +1,52 @@
+use crate::error::Error;
+use std::str::FromStr;
+
+/// How a connection prepares statements and how long it assumes they persist on the server.
+///
+/// It is used by the [`statement_cache_mode`](super::PgConnectOptions::statement_cache_mode)
+/// method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgStatementCacheMode {
+    /// Statements are prepared once, with a name, and kept for the lifetime of the connection,
+    /// up to the [statement cache capacity](super::PgConnectOptions::statement_cache_capacity).
+    Session,
+
+    /// Statements are prepared again, as the unnamed statement, every time they are run, and
+    /// nothing about them is cached. Each query takes an extra round trip to describe the
+    /// statement.
+    ///
+    /// Use this behind a pooler that can move the connection to another server session between
+    /// transactions, such as PgBouncer in transaction pooling mode.
+    Transaction,
+
+    /// Like `Transaction`, but the parameter and column types of each statement are cached
+    /// on the client, so a statement is only described the first time it is run.
+    ///
+    /// The cached types are not checked against the server again, so this should not be used
+    /// while the schema of a queried table may change.
+    DescribeOnly,
+}
+
+impl Default for PgStatementCacheMode {
+    fn default() -> Self {
+        PgStatementCacheMode::Session
+    }
+}
+
+impl FromStr for PgStatementCacheMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "session" => PgStatementCacheMode::Session,
+            "transaction" => PgStatementCacheMode::Transaction,
+            "describe-only" => PgStatementCacheMode::DescribeOnly,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `statement_cache_mode`", s).into(),
+                ));
+            }
+        })
+    }
+}
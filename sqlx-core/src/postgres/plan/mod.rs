@@ -0,0 +1,502 @@
+//! Running a query under `EXPLAIN ANALYZE` and reading its plan; see
+//! [`Query::analyze`](crate::query::Query::analyze).
+
+use std::time::Duration;
+
+use serde_json::{Map, Value as JsonValue};
+
+use crate::acquire::Acquire;
+use crate::error::Error;
+use crate::postgres::{PgArguments, Postgres};
+use crate::row::Row;
+
+/// What [`Query::analyze`](crate::query::Query::analyze) asks `EXPLAIN ANALYZE` to report,
+/// and whether the writes of the query are kept.
+#[derive(Debug, Clone)]
+pub struct PgAnalyzeOptions {
+    buffers: bool,
+    timing: bool,
+    verbose: bool,
+    settings: bool,
+    wal: bool,
+    allow_mutation: bool,
+}
+
+impl PgAnalyzeOptions {
+    /// Timings and buffer usage, in a transaction that is rolled back.
+    pub fn new() -> Self {
+        Self {
+            buffers: true,
+            timing: true,
+            verbose: false,
+            settings: false,
+            wal: false,
+            allow_mutation: false,
+        }
+    }
+
+    /// Reports the buffers each node hit, read, dirtied and wrote, as `EXPLAIN (BUFFERS)`.
+    /// On by default.
+    pub fn buffers(mut self, buffers: bool) -> Self {
+        self.buffers = buffers;
+        self
+    }
+
+    /// Reports the time spent in each node, as `EXPLAIN (TIMING)`. On by default; reading the
+    /// clock for every row can slow some queries down noticeably.
+    pub fn timing(mut self, timing: bool) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// Reports the output columns of each node and other details, as `EXPLAIN (VERBOSE)`,
+    /// in [`PgPlanNode::details`]. Off by default.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Reports the settings that affect planning and differ from their defaults, as
+    /// `EXPLAIN (SETTINGS)`, in [`PgQueryPlan::details`]. Off by default; PostgreSQL 12 or
+    /// later.
+    pub fn settings(mut self, settings: bool) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Reports the WAL records each node generated, as `EXPLAIN (WAL)`, in
+    /// [`PgPlanNode::details`]. Off by default; PostgreSQL 13 or later.
+    pub fn wal(mut self, wal: bool) -> Self {
+        self.wal = wal;
+        self
+    }
+
+    /// Keeps the writes of the query. Off by default.
+    ///
+    /// `EXPLAIN ANALYZE` runs the query, so an `INSERT`, `UPDATE` or `DELETE` that is analyzed
+    /// writes as it would otherwise. Unless this is set, it runs in a transaction, or a
+    /// savepoint, that is rolled back; changes that are not undone by a rollback, such as the
+    /// values taken from a sequence, are kept either way.
+    pub fn allow_mutation(mut self, allow: bool) -> Self {
+        self.allow_mutation = allow;
+        self
+    }
+
+    // `sql` under `EXPLAIN` with these options
+    fn explain(&self, sql: &str) -> String {
+        let mut explain = String::from("EXPLAIN (ANALYZE, FORMAT JSON");
+
+        if self.buffers {
+            explain.push_str(", BUFFERS");
+        }
+
+        if !self.timing {
+            explain.push_str(", TIMING FALSE");
+        }
+
+        if self.verbose {
+            explain.push_str(", VERBOSE");
+        }
+
+        if self.settings {
+            explain.push_str(", SETTINGS");
+        }
+
+        if self.wal {
+            explain.push_str(", WAL");
+        }
+
+        explain.push_str(") ");
+        explain.push_str(sql);
+        explain
+    }
+}
+
+impl Default for PgAnalyzeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The plan of a query, with what was measured while running it, as reported by
+/// `EXPLAIN (ANALYZE, FORMAT JSON)`.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct PgQueryPlan {
+    /// The node that returns the rows of the query, with every other node below it.
+    pub root: PgPlanNode,
+
+    /// The time spent planning the query.
+    pub planning_time: Option<Duration>,
+
+    /// The time spent running the query, including its triggers.
+    pub execution_time: Option<Duration>,
+
+    /// The triggers the query fired, and the time spent in each.
+    pub triggers: Vec<PgPlanTrigger>,
+
+    /// Everything else reported for the query, e.g. `"Settings"`, by the name PostgreSQL
+    /// gives it.
+    pub details: Map<String, JsonValue>,
+}
+
+/// A node of a [`PgQueryPlan`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct PgPlanNode {
+    /// What the node does, e.g. `Seq Scan`, `Hash Join` or `ModifyTable`.
+    pub node_type: String,
+
+    /// The table the node scans or modifies, if any.
+    pub relation_name: Option<String>,
+
+    /// The name the table is referred to by in the query, if the node scans a table.
+    pub alias: Option<String>,
+
+    /// The index the node scans, if any.
+    pub index_name: Option<String>,
+
+    /// The estimated cost before the first row is returned, in the units of the planner.
+    pub startup_cost: f64,
+
+    /// The estimated cost of returning every row, in the units of the planner.
+    pub total_cost: f64,
+
+    /// The estimated number of rows.
+    pub plan_rows: f64,
+
+    /// The estimated average size of a row, in bytes.
+    pub plan_width: u64,
+
+    /// The time before the first row was returned, averaged over the loops; `None` without
+    /// [`timing`](PgAnalyzeOptions::timing).
+    pub actual_startup_time: Option<Duration>,
+
+    /// The time spent returning every row, averaged over the loops; `None` without
+    /// [`timing`](PgAnalyzeOptions::timing).
+    pub actual_total_time: Option<Duration>,
+
+    /// The number of rows returned, averaged over the loops.
+    pub actual_rows: f64,
+
+    /// The number of times the node ran; `0` for a node that never did, e.g. the inner side
+    /// of a join whose outer side returned no rows.
+    pub actual_loops: u64,
+
+    /// The buffers the node, and the nodes below it, used; all `0` without
+    /// [`buffers`](PgAnalyzeOptions::buffers).
+    pub buffers: PgPlanBuffers,
+
+    /// The nodes whose rows this node takes.
+    pub children: Vec<PgPlanNode>,
+
+    /// Everything else reported for the node, e.g. `"Filter"` or `"Rows Removed by Filter"`,
+    /// by the name PostgreSQL gives it.
+    pub details: Map<String, JsonValue>,
+}
+
+/// The number of blocks of each kind a [`PgPlanNode`] used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PgPlanBuffers {
+    pub shared_hit: u64,
+    pub shared_read: u64,
+    pub shared_dirtied: u64,
+    pub shared_written: u64,
+    pub local_hit: u64,
+    pub local_read: u64,
+    pub local_dirtied: u64,
+    pub local_written: u64,
+    pub temp_read: u64,
+    pub temp_written: u64,
+}
+
+/// A trigger fired by the query of a [`PgQueryPlan`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct PgPlanTrigger {
+    /// The name of the trigger, or of the constraint that fired it.
+    pub name: String,
+
+    /// The table the trigger is on.
+    pub relation: Option<String>,
+
+    /// The time spent in the trigger, over every call.
+    pub time: Option<Duration>,
+
+    /// The number of times the trigger fired.
+    pub calls: u64,
+}
+
+impl PgQueryPlan {
+    /// Every node of the plan, from the root, each before the nodes below it.
+    pub fn nodes(&self) -> impl Iterator<Item = &PgPlanNode> {
+        let mut stack = vec![&self.root];
+
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+
+            Some(node)
+        })
+    }
+
+    // from the single plan `EXPLAIN (FORMAT JSON)` returns in an array
+    pub(crate) fn from_json(value: JsonValue) -> Result<Self, Error> {
+        let mut plan = match value {
+            JsonValue::Array(mut plans) if plans.len() == 1 => into_object(plans.remove(0))?,
+            _ => return Err(malformed("expected an array of one plan")),
+        };
+
+        let root = match plan.remove("Plan") {
+            Some(node) => PgPlanNode::from_json(into_object(node)?)?,
+            None => return Err(malformed("the plan has no \"Plan\"")),
+        };
+
+        let triggers = match plan.remove("Triggers") {
+            Some(JsonValue::Array(triggers)) => triggers
+                .into_iter()
+                .map(|trigger| PgPlanTrigger::from_json(into_object(trigger)?))
+                .collect::<Result<_, _>>()?,
+
+            _ => Vec::new(),
+        };
+
+        Ok(PgQueryPlan {
+            root,
+            planning_time: take_millis(&mut plan, "Planning Time"),
+            execution_time: take_millis(&mut plan, "Execution Time"),
+            triggers,
+            details: plan,
+        })
+    }
+}
+
+impl PgPlanNode {
+    fn from_json(mut node: Map<String, JsonValue>) -> Result<Self, Error> {
+        let node_type = take_string(&mut node, "Node Type")
+            .ok_or_else(|| malformed("a node of the plan has no \"Node Type\""))?;
+
+        let children = match node.remove("Plans") {
+            Some(JsonValue::Array(children)) => children
+                .into_iter()
+                .map(|child| PgPlanNode::from_json(into_object(child)?))
+                .collect::<Result<_, _>>()?,
+
+            _ => Vec::new(),
+        };
+
+        let buffers = PgPlanBuffers {
+            shared_hit: take_u64(&mut node, "Shared Hit Blocks"),
+            shared_read: take_u64(&mut node, "Shared Read Blocks"),
+            shared_dirtied: take_u64(&mut node, "Shared Dirtied Blocks"),
+            shared_written: take_u64(&mut node, "Shared Written Blocks"),
+            local_hit: take_u64(&mut node, "Local Hit Blocks"),
+            local_read: take_u64(&mut node, "Local Read Blocks"),
+            local_dirtied: take_u64(&mut node, "Local Dirtied Blocks"),
+            local_written: take_u64(&mut node, "Local Written Blocks"),
+            temp_read: take_u64(&mut node, "Temp Read Blocks"),
+            temp_written: take_u64(&mut node, "Temp Written Blocks"),
+        };
+
+        Ok(PgPlanNode {
+            node_type,
+            relation_name: take_string(&mut node, "Relation Name"),
+            alias: take_string(&mut node, "Alias"),
+            index_name: take_string(&mut node, "Index Name"),
+            startup_cost: take_f64(&mut node, "Startup Cost").unwrap_or_default(),
+            total_cost: take_f64(&mut node, "Total Cost").unwrap_or_default(),
+            plan_rows: take_f64(&mut node, "Plan Rows").unwrap_or_default(),
+            plan_width: take_u64(&mut node, "Plan Width"),
+            actual_startup_time: take_millis(&mut node, "Actual Startup Time"),
+            actual_total_time: take_millis(&mut node, "Actual Total Time"),
+            actual_rows: take_f64(&mut node, "Actual Rows").unwrap_or_default(),
+            actual_loops: take_u64(&mut node, "Actual Loops"),
+            buffers,
+            children,
+            details: node,
+        })
+    }
+}
+
+impl PgPlanTrigger {
+    fn from_json(mut trigger: Map<String, JsonValue>) -> Result<Self, Error> {
+        Ok(PgPlanTrigger {
+            name: take_string(&mut trigger, "Trigger Name")
+                .or_else(|| take_string(&mut trigger, "Constraint Name"))
+                .ok_or_else(|| malformed("a trigger of the plan has no \"Trigger Name\""))?,
+            relation: take_string(&mut trigger, "Relation"),
+            time: take_millis(&mut trigger, "Time"),
+            calls: take_u64(&mut trigger, "Calls"),
+        })
+    }
+}
+
+/// Runs `sql` under `EXPLAIN ANALYZE` with `arguments`, rolling it back unless the options
+/// allow it to write.
+pub(crate) async fn analyze<'c, C>(
+    sql: &str,
+    arguments: PgArguments,
+    conn: C,
+    options: &PgAnalyzeOptions,
+) -> Result<PgQueryPlan, Error>
+where
+    C: Acquire<'c, Database = Postgres>,
+{
+    let explain = options.explain(sql);
+    let query = crate::query::query_with::<Postgres, _>(&explain, arguments).persistent(false);
+
+    let row = if options.allow_mutation {
+        let mut conn = conn.acquire().await?;
+
+        query.fetch_one(&mut *conn).await?
+    } else {
+        let mut tx = conn.begin().await?;
+
+        // an error drops `tx`, which rolls it back
+        let row = query.fetch_one(&mut tx).await?;
+        tx.rollback().await?;
+
+        row
+    };
+
+    PgQueryPlan::from_json(row.try_get(0)?)
+}
+
+fn malformed(reason: &str) -> Error {
+    Error::decode(format!("malformed plan from EXPLAIN: {}", reason))
+}
+
+fn into_object(value: JsonValue) -> Result<Map<String, JsonValue>, Error> {
+    match value {
+        JsonValue::Object(object) => Ok(object),
+        _ => Err(malformed("expected an object")),
+    }
+}
+
+fn take_string(object: &mut Map<String, JsonValue>, key: &str) -> Option<String> {
+    match object.remove(key)? {
+        JsonValue::String(value) => Some(value),
+        _ => None,
+    }
+}
+
+fn take_f64(object: &mut Map<String, JsonValue>, key: &str) -> Option<f64> {
+    object.remove(key)?.as_f64()
+}
+
+// a count, which PostgreSQL 16 and later may report as a fraction when averaged
+fn take_u64(object: &mut Map<String, JsonValue>, key: &str) -> u64 {
+    take_f64(object, key).map_or(0, |value| value.max(0.0).round() as u64)
+}
+
+// a time, which is reported in fractional milliseconds
+fn take_millis(object: &mut Map<String, JsonValue>, key: &str) -> Option<Duration> {
+    take_f64(object, key).map(|millis| Duration::from_nanos((millis.max(0.0) * 1e6).round() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(fixture: &str) -> PgQueryPlan {
+        PgQueryPlan::from_json(serde_json::from_str(fixture).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn it_reads_the_plan_of_a_join() {
+        let plan = plan(include_str!("fixtures/join.json"));
+
+        assert_eq!(plan.planning_time, Some(Duration::from_micros(412)));
+        assert_eq!(plan.execution_time, Some(Duration::from_micros(1873)));
+        assert!(plan.triggers.is_empty());
+
+        let root = &plan.root;
+        assert_eq!(root.node_type, "Hash Join");
+        assert_eq!(root.total_cost, 40.55);
+        assert_eq!(root.actual_rows, 42.0);
+        assert_eq!(root.actual_loops, 1);
+        assert_eq!(root.buffers.shared_hit, 12);
+        assert_eq!(root.buffers.shared_read, 3);
+        assert_eq!(root.details["Hash Cond"], "(tweet.owner_id = account.id)");
+        assert!(!root.details.contains_key("Plans"));
+
+        let types: Vec<_> = plan.nodes().map(|node| node.node_type.as_str()).collect();
+        assert_eq!(types, ["Hash Join", "Seq Scan", "Hash", "Index Scan"]);
+
+        let scan = &root.children[1].children[0];
+        assert_eq!(scan.relation_name.as_deref(), Some("account"));
+        assert_eq!(scan.alias.as_deref(), Some("account"));
+        assert_eq!(scan.index_name.as_deref(), Some("account_pkey"));
+        assert_eq!(scan.actual_startup_time, Some(Duration::from_micros(11)));
+        assert_eq!(scan.details["Rows Removed by Filter"], 0);
+    }
+
+    #[test]
+    fn it_reads_the_plan_of_an_insert() {
+        let plan = plan(include_str!("fixtures/insert.json"));
+
+        assert_eq!(plan.root.node_type, "ModifyTable");
+        assert_eq!(plan.root.relation_name.as_deref(), Some("tweet"));
+        assert_eq!(plan.root.details["Operation"], "Insert");
+        assert_eq!(plan.root.buffers.shared_dirtied, 1);
+
+        assert_eq!(
+            plan.triggers,
+            [PgPlanTrigger {
+                name: "RI_ConstraintTrigger_c_16420".to_owned(),
+                relation: Some("tweet".to_owned()),
+                time: Some(Duration::from_micros(250)),
+                calls: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn it_reads_a_plan_without_timing_or_buffers() {
+        let plan = plan(include_str!("fixtures/no_timing.json"));
+
+        assert!(plan.nodes().all(|node| node.actual_total_time.is_none()));
+        assert!(plan
+            .nodes()
+            .all(|node| node.buffers == PgPlanBuffers::default()));
+
+        // the inner side of a join whose outer side returned nothing
+        let inner = &plan.root.children[1];
+        assert_eq!(inner.actual_loops, 0);
+        assert_eq!(inner.actual_rows, 0.0);
+
+        assert_eq!(plan.details["Settings"]["work_mem"], "64MB");
+    }
+
+    #[test]
+    fn it_rejects_malformed_plans() {
+        for json in &[r#"{"Plan": {}}"#, "[]", r#"[{"Plan": {"Plans": []}}]"#] {
+            let value = serde_json::from_str(json).unwrap();
+
+            assert!(matches!(
+                PgQueryPlan::from_json(value),
+                Err(Error::Decode(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn it_writes_the_options_into_the_explain() {
+        assert_eq!(
+            PgAnalyzeOptions::new().explain("SELECT 1"),
+            "EXPLAIN (ANALYZE, FORMAT JSON, BUFFERS) SELECT 1"
+        );
+
+        assert_eq!(
+            PgAnalyzeOptions::new()
+                .buffers(false)
+                .timing(false)
+                .verbose(true)
+                .settings(true)
+                .wal(true)
+                .explain("DELETE FROM tweet"),
+            "EXPLAIN (ANALYZE, FORMAT JSON, TIMING FALSE, VERBOSE, SETTINGS, WAL) DELETE FROM tweet"
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use crate::postgres::PgPoolOptions;
+
+impl PgPoolOptions {
+    /// Look up the types of `names` on every new connection, before it is used, so that the
+    /// first queries to use them are not slowed down by the lookup.
+    ///
+    /// Names are resolved as by [`PgConnection::resolve_type`], with the names not yet known
+    /// in a single query; a connection fails to open if one of the types does not exist.
+    /// Lookups run before any callback set with [`after_connect`](Self::after_connect), and
+    /// so with the `search_path` the connection started with.
+    ///
+    /// A later call replaces the names of an earlier one.
+    ///
+    /// [`PgConnection::resolve_type`]: crate::postgres::PgConnection::resolve_type
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), sqlx_core::error::Error> {
+    /// use sqlx_core::postgres::PgPoolOptions;
+    ///
+    /// let pool = PgPoolOptions::new()
+    ///     .warm_types(&["status", "billing.invoice_line"])
+    ///     .connect("postgres:// …")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn warm_types(self, names: &[&str]) -> Self {
+        let names: Arc<[String]> = names.iter().map(|name| name.to_string()).collect();
+
+        self.prepare_connection(move |conn| {
+            let names = Arc::clone(&names);
+
+            Box::pin(async move {
+                let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+                conn.resolve_types(&names).await
+            })
+        })
+    }
+}
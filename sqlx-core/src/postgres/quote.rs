@@ -0,0 +1,107 @@
+use crate::error::Error;
+
+/// Quotes `name` so it can be written into SQL as an identifier, e.g. a table name that comes
+/// from configuration.
+///
+/// The name is wrapped in double quotes, with any double quote inside it doubled. The result
+/// is case-sensitive, as quoted identifiers are in PostgreSQL. Empty names and names
+/// containing a NUL byte are rejected.
+///
+/// # Example
+///
+/// ```rust
+/// # use sqlx_core::postgres::quote_identifier;
+/// assert_eq!(quote_identifier("my table")?, r#""my table""#);
+/// assert_eq!(quote_identifier(r#"say "hi""#)?, r#""say ""hi""""#);
+/// # Ok::<(), sqlx_core::error::Error>(())
+/// ```
+pub fn quote_identifier(name: &str) -> Result<String, Error> {
+    crate::common::quote_identifier(name, '"', '"')
+}
+
+/// Quotes `schema` and `name` as a schema-qualified identifier, e.g. `"tenant_1"."users"`.
+///
+/// See [`quote_identifier`] for how each part is quoted.
+pub fn quote_qualified(schema: &str, name: &str) -> Result<String, Error> {
+    Ok(format!(
+        "{}.{}",
+        quote_identifier(schema)?,
+        quote_identifier(name)?
+    ))
+}
+
+// quotes `value` as a string literal for a session with the given `standard_conforming_strings`;
+// see `PgConnection::quote_literal`
+pub(crate) fn quote_literal(
+    value: &str,
+    standard_conforming_strings: bool,
+) -> Result<String, Error> {
+    if value.contains('\0') {
+        return Err(Error::encode(format!(
+            "string literal must not contain a NUL byte: {:?}",
+            value
+        )));
+    }
+
+    // without standard conforming strings, a backslash in '...' begins an escape, as it always
+    // does in E'...'; the latter is used so the server does not warn about it
+    let escape_backslashes = !standard_conforming_strings && value.contains('\\');
+
+    let mut quoted = String::with_capacity(value.len() + 3);
+
+    if escape_backslashes {
+        quoted.push('E');
+    }
+
+    quoted.push('\'');
+
+    for c in value.chars() {
+        match c {
+            '\'' => quoted.push_str("''"),
+            '\\' if escape_backslashes => quoted.push_str(r"\\"),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('\'');
+
+    Ok(quoted)
+}
+
+#[test]
+fn it_quotes_identifiers() -> Result<(), Error> {
+    assert_eq!(quote_identifier("users")?, r#""users""#);
+    assert_eq!(quote_identifier("Users")?, r#""Users""#);
+    assert_eq!(quote_identifier("my table")?, r#""my table""#);
+    assert_eq!(quote_identifier(r#"a"b"#)?, r#""a""b""#);
+    assert_eq!(quote_identifier("ünïcødé")?, r#""ünïcødé""#);
+    assert_eq!(quote_identifier("a`b")?, r#""a`b""#);
+
+    assert_eq!(
+        quote_qualified("tenant 1", r#"x"y"#)?,
+        r#""tenant 1"."x""y""#
+    );
+
+    assert!(quote_identifier("").is_err());
+    assert!(quote_identifier("a\0b").is_err());
+    assert!(quote_qualified("a\0", "b").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn it_quotes_literals() -> Result<(), Error> {
+    assert_eq!(quote_literal("hello", true)?, "'hello'");
+    assert_eq!(quote_literal("it's", true)?, "'it''s'");
+    assert_eq!(quote_literal(r"C:\dir", true)?, r"'C:\dir'");
+    assert_eq!(quote_literal("", true)?, "''");
+
+    assert_eq!(quote_literal("it's", false)?, "'it''s'");
+    assert_eq!(quote_literal(r"C:\dir", false)?, r"E'C:\\dir'");
+    assert_eq!(quote_literal(r"\'", false)?, r"E'\\'''");
+
+    assert!(quote_literal("a\0b", true).is_err());
+    assert!(quote_literal("a\0b", false).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,426 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::connection::Connection;
+use crate::error::Error;
+use crate::postgres::listener::ident;
+use crate::postgres::message::{CopyData, CopyDone, MessageFormat, Query};
+use crate::postgres::{PgConnectOptions, PgConnection};
+use crate::row::Row;
+
+/// A position in the write-ahead log, written as two hexadecimal numbers (`16/B374D848`).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgLsn(pub u64);
+
+impl Display for PgLsn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 as u32)
+    }
+}
+
+impl FromStr for PgLsn {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let (hi, lo) = s
+            .find('/')
+            .map(|i| (&s[..i], &s[i + 1..]))
+            .ok_or_else(|| err_protocol!("expected an LSN of the form `X/X`, got {:?}", s))?;
+
+        let hi = u32::from_str_radix(hi, 16).map_err(|e| err_protocol!("{}: {:?}", e, s))?;
+        let lo = u32::from_str_radix(lo, 16).map_err(|e| err_protocol!("{}: {:?}", e, s))?;
+
+        Ok(PgLsn((u64::from(hi) << 32) | u64::from(lo)))
+    }
+}
+
+/// A logical replication slot created by [`PgReplication::create_slot`].
+#[derive(Debug, Clone)]
+pub struct PgReplicationSlot {
+    pub name: String,
+
+    /// The position from which changes are available on the slot.
+    pub consistent_point: PgLsn,
+}
+
+/// A message received while streaming from a replication slot.
+#[derive(Debug, Clone)]
+pub enum PgReplicationMessage {
+    /// A chunk of WAL data, already run through the slot's output plugin.
+    XLogData {
+        wal_start: PgLsn,
+        wal_end: PgLsn,
+        server_time: SystemTime,
+        data: Bytes,
+    },
+
+    /// A heartbeat from the server.
+    ///
+    /// If `reply_requested` is set the server wants a
+    /// [`standby_status_update`](PgReplicationStream::standby_status_update) as soon as
+    /// possible, and will disconnect after `wal_sender_timeout` if it does not get one.
+    Keepalive {
+        wal_end: PgLsn,
+        server_time: SystemTime,
+        reply_requested: bool,
+    },
+}
+
+/// A connection in logical replication mode, for managing and consuming replication slots.
+///
+/// The connected role needs the `REPLICATION` attribute and the server needs
+/// `wal_level = logical`.
+///
+/// ```rust,no_run
+/// # use sqlx_core::postgres::{PgReplication, PgReplicationMessage};
+/// # async fn example() -> sqlx_core::error::Result<()> {
+/// let mut replication = PgReplication::connect("postgres://localhost/mydb").await?;
+/// let slot = replication.create_slot("my_slot", "test_decoding", false).await?;
+///
+/// let mut stream = replication
+///     .start_replication(&slot.name, slot.consistent_point, &[])
+///     .await?;
+///
+/// while let Some(message) = stream.recv().await? {
+///     match message {
+///         PgReplicationMessage::XLogData { wal_end, data, .. } => {
+///             println!("{}", String::from_utf8_lossy(&data));
+///             stream.standby_status_update(wal_end).await?;
+///         }
+///
+///         PgReplicationMessage::Keepalive { wal_end, reply_requested: true, .. } => {
+///             stream.standby_status_update(wal_end).await?;
+///         }
+///
+///         PgReplicationMessage::Keepalive { .. } => {}
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct PgReplication {
+    conn: PgConnection,
+}
+
+impl PgReplication {
+    pub async fn connect(url: &str) -> Result<Self, Error> {
+        Self::connect_with(&url.parse()?).await
+    }
+
+    /// Connect with the given options, enabling [`replication`](PgConnectOptions::replication).
+    pub async fn connect_with(options: &PgConnectOptions) -> Result<Self, Error> {
+        let options = options.clone().replication(true);
+
+        Ok(Self {
+            conn: PgConnection::connect_with(&options).await?,
+        })
+    }
+
+    /// Create a logical replication slot that decodes changes with `plugin`
+    /// (e.g. `pgoutput` or `wal2json`).
+    ///
+    /// A temporary slot is dropped when this connection is closed.
+    pub async fn create_slot(
+        &mut self,
+        name: &str,
+        plugin: &str,
+        temporary: bool,
+    ) -> Result<PgReplicationSlot, Error> {
+        let row = self
+            .conn
+            .fetch_one(&*format!(
+                r#"CREATE_REPLICATION_SLOT "{}" {}LOGICAL "{}" NOEXPORT_SNAPSHOT"#,
+                ident(name),
+                if temporary { "TEMPORARY " } else { "" },
+                ident(plugin)
+            ))
+            .await?;
+
+        Ok(PgReplicationSlot {
+            name: row.try_get(0)?,
+            consistent_point: row.try_get::<&str, _>(1)?.parse()?,
+        })
+    }
+
+    /// Drop a replication slot. The slot must not be in use.
+    pub async fn drop_slot(&mut self, name: &str) -> Result<(), Error> {
+        self.conn
+            .execute(&*format!(r#"DROP_REPLICATION_SLOT "{}""#, ident(name)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Start streaming changes from the slot named `slot`.
+    ///
+    /// Streaming starts at `start`, or where the slot last left off if that is later; pass
+    /// `PgLsn(0)` to always resume from the slot. `options` are passed to the output plugin.
+    pub async fn start_replication(
+        mut self,
+        slot: &str,
+        start: PgLsn,
+        options: &[(&str, &str)],
+    ) -> Result<PgReplicationStream, Error> {
+        let mut sql = format!(
+            r#"START_REPLICATION SLOT "{}" LOGICAL {}"#,
+            ident(slot),
+            start
+        );
+
+        if !options.is_empty() {
+            let options: Vec<_> = options
+                .iter()
+                .map(|(name, value)| {
+                    format!(r#""{}" '{}'"#, ident(name), value.replace('\'', "''"))
+                })
+                .collect();
+
+            sql.push_str(" (");
+            sql.push_str(&options.join(", "));
+            sql.push(')');
+        }
+
+        self.conn.wait_until_ready().await?;
+        self.conn.stream.discard_copy_data = false;
+        self.conn.stream.send(Query(&sql)).await?;
+        self.conn.pending_ready_for_query_count += 1;
+
+        let message = self.conn.stream.recv().await?;
+
+        if message.format != MessageFormat::CopyBothResponse {
+            return Err(err_protocol!(
+                "expecting CopyBothResponse but received {:?}",
+                message.format
+            ));
+        }
+
+        Ok(PgReplicationStream {
+            conn: self.conn,
+            server_done: false,
+        })
+    }
+
+    /// Explicitly close this connection.
+    pub async fn close(self) -> Result<(), Error> {
+        self.conn.close().await
+    }
+}
+
+/// A stream of changes from a replication slot, returned by
+/// [`PgReplication::start_replication`].
+///
+/// The server must be sent a [`standby_status_update`](Self::standby_status_update)
+/// regularly, at least once every `wal_sender_timeout`, or it will close the connection.
+#[derive(Debug)]
+pub struct PgReplicationStream {
+    conn: PgConnection,
+
+    // the server has sent `CopyDone` and will not send any more data
+    server_done: bool,
+}
+
+impl PgReplicationStream {
+    /// Receive the next message from the server.
+    ///
+    /// Returns `None` once the server has stopped streaming.
+    pub async fn recv(&mut self) -> Result<Option<PgReplicationMessage>, Error> {
+        if self.server_done {
+            return Ok(None);
+        }
+
+        let message = self.conn.stream.recv().await?;
+
+        match message.format {
+            MessageFormat::CopyData => decode_copy_data(message.contents).map(Some),
+
+            MessageFormat::CopyDone => {
+                self.server_done = true;
+                Ok(None)
+            }
+
+            format => Err(err_protocol!(
+                "expecting CopyData or CopyDone but received {:?}",
+                format
+            )),
+        }
+    }
+
+    /// Report that everything up to `applied` has been processed, allowing the server to
+    /// discard the WAL before it.
+    pub async fn standby_status_update(&mut self, applied: PgLsn) -> Result<(), Error> {
+        let mut buf = Vec::with_capacity(34);
+
+        buf.put_u8(b'r');
+        // written, flushed and applied
+        buf.put_u64(applied.0);
+        buf.put_u64(applied.0);
+        buf.put_u64(applied.0);
+        buf.put_i64(to_pg_epoch(SystemTime::now()));
+        // don't ask for a reply
+        buf.put_u8(0);
+
+        self.conn.stream.send(CopyData(&buf)).await
+    }
+
+    /// Stop streaming and return to a connection that accepts replication commands.
+    ///
+    /// Any data still in flight from the server is discarded.
+    pub async fn finish(mut self) -> Result<PgReplication, Error> {
+        self.conn.stream.send(CopyDone).await?;
+
+        while !self.server_done {
+            let message = self.conn.stream.recv().await?;
+
+            match message.format {
+                MessageFormat::CopyData => {}
+                MessageFormat::CopyDone => self.server_done = true,
+
+                format => {
+                    return Err(err_protocol!(
+                        "expecting CopyData or CopyDone but received {:?}",
+                        format
+                    ));
+                }
+            }
+        }
+
+        // the server may keep sending keepalives for a little while
+        self.conn.stream.discard_copy_data = true;
+
+        // the command completes after both sides have ended the copy
+        self.conn.wait_until_ready().await?;
+
+        Ok(PgReplication { conn: self.conn })
+    }
+}
+
+pub(crate) fn decode_copy_data(mut buf: Bytes) -> Result<PgReplicationMessage, Error> {
+    let (len, tag) = (buf.len(), buf.first().copied());
+
+    match tag {
+        Some(b'w') if len >= 25 => {
+            buf.advance(1);
+
+            Ok(PgReplicationMessage::XLogData {
+                wal_start: PgLsn(buf.get_u64()),
+                wal_end: PgLsn(buf.get_u64()),
+                server_time: from_pg_epoch(buf.get_i64())?,
+                data: buf,
+            })
+        }
+
+        Some(b'k') if len >= 18 => {
+            buf.advance(1);
+
+            Ok(PgReplicationMessage::Keepalive {
+                wal_end: PgLsn(buf.get_u64()),
+                server_time: from_pg_epoch(buf.get_i64())?,
+                reply_requested: buf.get_u8() != 0,
+            })
+        }
+
+        _ => Err(err_protocol!(
+            "unexpected replication message {:?} of {} bytes",
+            tag.map(char::from),
+            len
+        )),
+    }
+}
+
+// timestamps in the replication protocol are microseconds since 2000-01-01 UTC
+const PG_EPOCH: Duration = Duration::from_secs(946_684_800);
+
+// the range of `SystemTime` depends on the platform, and may not cover every timestamp
+fn from_pg_epoch(micros: i64) -> Result<SystemTime, Error> {
+    let epoch = UNIX_EPOCH + PG_EPOCH;
+
+    let time = if micros >= 0 {
+        epoch.checked_add(Duration::from_micros(micros as u64))
+    } else {
+        epoch.checked_sub(Duration::from_micros(micros.wrapping_neg() as u64))
+    };
+
+    time.ok_or_else(|| err_protocol!("replication timestamp {} is out of range", micros))
+}
+
+fn to_pg_epoch(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH + PG_EPOCH) {
+        Ok(since) => since.as_micros() as i64,
+        Err(e) => -(e.duration().as_micros() as i64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_copy_data, from_pg_epoch, to_pg_epoch, PgLsn, PgReplicationMessage};
+    use bytes::Bytes;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn it_parses_and_formats_lsns() {
+        let lsn: PgLsn = "16/B374D848".parse().unwrap();
+
+        assert_eq!(lsn, PgLsn(0x16_B374_D848));
+        assert_eq!(lsn.to_string(), "16/B374D848");
+        assert_eq!(PgLsn(0).to_string(), "0/0");
+
+        assert!("16B374D848".parse::<PgLsn>().is_err());
+        assert!("16/XYZ".parse::<PgLsn>().is_err());
+    }
+
+    #[test]
+    fn it_decodes_xlog_data() {
+        let mut buf = vec![b'w'];
+        buf.extend(&1_u64.to_be_bytes());
+        buf.extend(&2_u64.to_be_bytes());
+        buf.extend(&1_000_000_i64.to_be_bytes());
+        buf.extend(b"BEGIN 1");
+
+        match decode_copy_data(Bytes::from(buf)).unwrap() {
+            PgReplicationMessage::XLogData {
+                wal_start,
+                wal_end,
+                server_time,
+                data,
+            } => {
+                assert_eq!(wal_start, PgLsn(1));
+                assert_eq!(wal_end, PgLsn(2));
+                assert_eq!(server_time, UNIX_EPOCH + Duration::from_secs(946_684_801));
+                assert_eq!(&*data, b"BEGIN 1");
+            }
+
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_decodes_keepalives() {
+        let mut buf = vec![b'k'];
+        buf.extend(&7_u64.to_be_bytes());
+        buf.extend(&0_i64.to_be_bytes());
+        buf.push(1);
+
+        assert!(matches!(
+            decode_copy_data(Bytes::from(buf)).unwrap(),
+            PgReplicationMessage::Keepalive {
+                wal_end: PgLsn(7),
+                reply_requested: true,
+                ..
+            }
+        ));
+
+        assert!(decode_copy_data(Bytes::from_static(b"k\0")).is_err());
+        assert!(decode_copy_data(Bytes::new()).is_err());
+    }
+
+    #[test]
+    fn it_converts_timestamps() {
+        for &micros in &[0, 1, -1, 1_234_567_890_123] {
+            assert_eq!(to_pg_epoch(from_pg_epoch(micros).unwrap()), micros);
+        }
+    }
+}
@@ -1,20 +1,68 @@
-use crate::column::ColumnIndex;
+use crate::column::{ColumnIndex, ColumnNameMatching};
+use crate::decode::Decode;
 use crate::error::Error;
 use crate::postgres::message::DataRow;
 use crate::postgres::statement::PgStatementMetadata;
+use crate::postgres::type_info::PgType;
 use crate::postgres::value::PgValueFormat;
 use crate::postgres::{PgColumn, PgValueRef, Postgres};
-use crate::row::Row;
+use crate::row::{debug_row, LooseValue, Row};
+use crate::value::ValueRef;
+use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
 
 /// Implementation of [`Row`] for PostgreSQL.
+///
+/// Cloning a row shares its data and columns, only copying the offsets of its values.
+#[derive(Clone)]
 pub struct PgRow {
     pub(crate) data: DataRow,
-    pub(crate) format: PgValueFormat,
+    pub(crate) format: PgRowFormat,
     pub(crate) metadata: Arc<PgStatementMetadata>,
+    pub(crate) column_name_matching: ColumnNameMatching,
 }
 
-impl crate::row::private_row::Sealed for PgRow {}
+// the formats of the values of a row: those asked for in the `Bind` message of a prepared
+// query, or those in the `RowDescription` of a simple one
+#[derive(Debug, Clone)]
+pub(crate) enum PgRowFormat {
+    // every value in the same format
+    All(PgValueFormat),
+
+    // the format of each column in turn
+    PerColumn(Arc<[PgValueFormat]>),
+}
+
+impl PgRowFormat {
+    // the format codes of a `Bind` message: none for text, one for all of the columns, or one
+    // for each of them
+    pub(crate) fn from_codes(codes: &[PgValueFormat]) -> Self {
+        match codes {
+            [] => PgRowFormat::All(PgValueFormat::Text),
+            [format] => PgRowFormat::All(*format),
+            _ => PgRowFormat::PerColumn(codes.into()),
+        }
+    }
+
+    fn get(&self, index: usize) -> PgValueFormat {
+        match self {
+            PgRowFormat::All(format) => *format,
+            PgRowFormat::PerColumn(formats) => formats[index],
+        }
+    }
+}
+
+impl From<PgValueFormat> for PgRowFormat {
+    fn from(format: PgValueFormat) -> Self {
+        PgRowFormat::All(format)
+    }
+}
+
+impl crate::row::private_row::Sealed for PgRow {
+    fn column_name_matching(&self) -> ColumnNameMatching {
+        self.column_name_matching
+    }
+}
 
 impl Row for PgRow {
     type Database = Postgres;
@@ -32,21 +80,135 @@ impl Row for PgRow {
         let value = self.data.get(index);
 
         Ok(PgValueRef {
-            format: self.format,
+            format: self.format.get(index),
             row: Some(&self.data.storage),
             type_info: column.type_info.clone(),
             value,
         })
     }
+
+    fn try_share(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+
+    fn data_len(&self) -> usize {
+        self.data.storage.len()
+    }
+}
+
+impl PgRow {
+    // a row is always read against the description the server sent before it, which must have
+    // a column for every value
+    pub(crate) fn new(
+        data: DataRow,
+        format: PgRowFormat,
+        metadata: Arc<PgStatementMetadata>,
+        column_name_matching: ColumnNameMatching,
+    ) -> Result<Self, Error> {
+        if data.values.len() != metadata.columns.len() {
+            return Err(err_protocol!(
+                "DataRow has {} values for {} columns",
+                data.values.len(),
+                metadata.columns.len()
+            ));
+        }
+
+        if let PgRowFormat::PerColumn(formats) = &format {
+            if formats.len() != data.values.len() {
+                return Err(err_protocol!(
+                    "DataRow has {} values for {} result formats",
+                    data.values.len(),
+                    formats.len()
+                ));
+            }
+        }
+
+        Ok(Self {
+            data,
+            format,
+            metadata,
+            column_name_matching,
+        })
+    }
+
+    fn loose_value(&self, index: usize) -> LooseValue<'_> {
+        let value = match self.try_get_raw(index) {
+            Ok(value) if !value.is_null() => value,
+            _ => return LooseValue::Null,
+        };
+
+        fn decode<'r, T: Decode<'r, Postgres>>(value: &PgValueRef<'r>) -> Option<T> {
+            T::decode(value.clone()).ok()
+        }
+
+        let loose = match value.type_info.0 {
+            PgType::Bool => decode(&value).map(LooseValue::Bool),
+            PgType::Int2 => decode::<i16>(&value).map(|v| LooseValue::Int(v.into())),
+            PgType::Int4 => decode::<i32>(&value).map(|v| LooseValue::Int(v.into())),
+            PgType::Int8 => decode(&value).map(LooseValue::Int),
+            PgType::Oid => decode::<u32>(&value).map(|v| LooseValue::UInt(v.into())),
+            PgType::Float4 => decode::<f32>(&value).map(|v| LooseValue::Float(v.into())),
+            PgType::Float8 => decode(&value).map(LooseValue::Float),
+
+            PgType::Json => decode(&value).map(LooseValue::Json),
+
+            // binary JSONB is prefixed with a version byte
+            PgType::Jsonb => match value.format {
+                PgValueFormat::Binary => value
+                    .as_bytes()
+                    .ok()
+                    .filter(|buf| buf.first() == Some(&1))
+                    .and_then(|buf| std::str::from_utf8(&buf[1..]).ok())
+                    .map(LooseValue::Json),
+
+                PgValueFormat::Text => decode(&value).map(LooseValue::Json),
+            },
+
+            PgType::Text | PgType::Varchar | PgType::Bpchar | PgType::Name | PgType::Unknown => {
+                decode(&value).map(LooseValue::Text)
+            }
+
+            // everything sent in the text format is, well, text
+            _ if value.format == PgValueFormat::Text => decode(&value).map(LooseValue::Text),
+
+            _ => None,
+        };
+
+        loose.unwrap_or_else(|| LooseValue::Binary(value.value.unwrap_or_default()))
+    }
+}
+
+impl Debug for PgRow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        debug_row(f, "PgRow", self, |index| self.loose_value(index))
+    }
+}
+
+#[cfg(feature = "json")]
+impl crate::row::RowExt for PgRow {
+    fn to_json(&self) -> serde_json::Map<String, serde_json::Value> {
+        use crate::column::Column;
+        use crate::type_info::TypeInfo;
+
+        self.columns()
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let value = self.loose_value(index).to_json(column.type_info().name());
+
+                (column.name().to_owned(), value)
+            })
+            .collect()
+    }
 }
 
 impl ColumnIndex<PgRow> for &'_ str {
     fn index(&self, row: &PgRow) -> Result<usize, Error> {
-        row.metadata
-            .column_names
-            .get(*self)
-            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
-            .map(|v| *v)
+        row.column_name_matching.find(
+            self,
+            row.metadata.column_names.get(*self).copied(),
+            row.metadata.columns.iter().map(|column| &*column.name),
+        )
     }
 }
 
@@ -0,0 +1,278 @@
+use std::cmp;
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::Duration;
+
+use futures_util::future;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+
+use crate::error::Error;
+use crate::pool::{CloseEvent, Pool};
+use crate::postgres::listener::{NotificationBuffer, Subscriber, Subscriptions};
+use crate::postgres::{PgListener, PgPool, PgSubscription, Postgres};
+
+// how long the listener waits before trying again after an error, doubling up to the maximum
+const MIN_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// A handle to the listener shared by every user of a pool; see [`PgPool::listener`].
+///
+/// The listener has a connection of its own, which is opened with the options of the pool the
+/// first time a channel is subscribed to, but is not counted towards its `max_connections`. It
+/// listens on each channel once, however many subscriptions the channel has, and stops
+/// listening on it once the last of them is dropped.
+///
+/// The connection is driven by a task of its own, which hands notifications out to the
+/// subscriptions of their channel as they arrive. Each subscription buffers its notifications
+/// until its stream is polled, up to
+/// [`set_max_buffered_notifications`](Self::set_max_buffered_notifications), so that a
+/// subscriber that falls behind does not hold up the others.
+///
+/// If the connection is lost, it is reopened and listens on every channel again, and each
+/// subscription gets a [`PgSubscriptionEvent::Reconnected`]; the listener of
+/// [connection events](crate::pool::PoolOptions::connection_events) of the pool is told about
+/// every connection the listener opens, fails to open and loses. An error other than a lost
+/// connection is logged, and the connection is reopened after a delay.
+///
+/// Once the pool is closed or dropped, the connection is closed and the streams of the
+/// subscriptions end.
+///
+/// [`PgSubscriptionEvent::Reconnected`]: crate::postgres::PgSubscriptionEvent::Reconnected
+#[derive(Clone)]
+pub struct PgSharedListener {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    // the pool of the listening connection, which is apart from the pool it listens for
+    pool: Pool<Postgres>,
+    subscriptions: Arc<Mutex<Subscriptions>>,
+    // taken by the task that drives the listener, once the first channel is subscribed to
+    close_event: Mutex<Option<CloseEvent>>,
+    connect_timeout: Duration,
+}
+
+impl PgPool {
+    /// The listener shared by every user of this pool, which subscribes to channels on a
+    /// single connection; see [`PgSharedListener`].
+    ///
+    /// Every call returns a handle to the same listener, so that hundreds of consumers in a
+    /// process need one connection between them rather than one [`PgListener`] each.
+    ///
+    /// ```rust,ignore
+    /// #[derive(serde::Deserialize)]
+    /// struct OrderPlaced {
+    ///     id: i64,
+    /// }
+    ///
+    /// let mut orders = pool.listener().subscribe::<OrderPlaced>("orders").await?;
+    ///
+    /// while let Some(event) = orders.next().await {
+    ///     match event? {
+    ///         PgSubscriptionEvent::Notification(order) => ship(order.id).await,
+    ///         PgSubscriptionEvent::Reconnected => ship_unshipped_orders().await,
+    ///     }
+    /// }
+    /// ```
+    pub fn listener(&self) -> PgSharedListener {
+        self.driver_state(|| PgSharedListener {
+            shared: Arc::new(Shared {
+                pool: self.dedicated(),
+                subscriptions: Arc::default(),
+                close_event: Mutex::new(Some(self.close_event())),
+                connect_timeout: self.connect_timeout(),
+            }),
+        })
+        .clone()
+    }
+}
+
+impl PgSharedListener {
+    /// Subscribes to the notifications on a channel, and returns a stream of them with their
+    /// payloads parsed from JSON into `T`, as [`PgListener::subscribe`] does.
+    ///
+    /// Returns once the listener listens on the channel, so that every notification sent
+    /// after that is received; the first subscription to a channel waits for `LISTEN` to run,
+    /// and for the connection to be opened if it is not yet. Fails with
+    /// [`Error::PoolTimedOut`] if that takes longer than the
+    /// [`connect_timeout`](crate::pool::PoolOptions::connect_timeout) of the pool, or with
+    /// [`Error::PoolClosed`] if the pool is closed.
+    pub async fn subscribe<T: DeserializeOwned>(
+        &self,
+        channel: &str,
+    ) -> Result<PgSubscription<T>, Error> {
+        self.start();
+
+        let subscription = {
+            let mut subscriptions = self.shared.subscriptions.lock();
+
+            if subscriptions.closed {
+                return Err(Error::PoolClosed);
+            }
+
+            // the channel is listened to for its first subscription only
+            let listening = match subscriptions.channels.get(channel) {
+                Some(subscribers) => subscribers
+                    .iter()
+                    .any(|subscriber| subscriber.lock().listening),
+
+                None => {
+                    subscriptions.listen.push(channel.to_owned());
+
+                    if let Some(listener) = subscriptions.listener.take() {
+                        listener.wake();
+                    }
+
+                    false
+                }
+            };
+
+            let subscriber = Arc::new(Mutex::new(Subscriber {
+                events: NotificationBuffer::new(subscriptions.capacity),
+                waker: None,
+                listening,
+                closed: false,
+            }));
+
+            subscriptions
+                .channels
+                .entry(channel.to_owned())
+                .or_default()
+                .push(Arc::clone(&subscriber));
+
+            PgSubscription {
+                subscriptions: Arc::clone(&self.shared.subscriptions),
+                subscriber,
+                channel: channel.to_owned(),
+                ty: PhantomData,
+            }
+        };
+
+        let listening = future::poll_fn(|cx| {
+            let mut subscriber = subscription.subscriber.lock();
+
+            if subscriber.listening {
+                Poll::Ready(Ok(()))
+            } else if subscriber.closed {
+                Poll::Ready(Err(Error::PoolClosed))
+            } else {
+                subscriber.waker = Some(cx.waker().clone());
+
+                Poll::Pending
+            }
+        });
+
+        let listening = sqlx_rt::timeout(self.shared.connect_timeout, listening).await;
+
+        // on an error the subscription is dropped, unsubscribing again
+        match listening {
+            Ok(Ok(())) => Ok(subscription),
+            Ok(Err(error)) => Err(error),
+            Err(_) => Err(Error::PoolTimedOut),
+        }
+    }
+
+    /// Caps the number of notifications waiting in each subscription until its stream is
+    /// polled, dropping the oldest ones beyond it. Unlimited by default, or with `None`.
+    ///
+    /// Applies to the subscriptions made before as well as after; see
+    /// [`PgSubscription::dropped_notifications`].
+    pub fn set_max_buffered_notifications(&self, max: Option<usize>) {
+        let mut subscriptions = self.shared.subscriptions.lock();
+        subscriptions.capacity = max;
+
+        for subscriber in subscriptions.channels.values().flatten() {
+            subscriber.lock().events.set_capacity(max);
+        }
+    }
+
+    /// The channels subscribed to, in no particular order.
+    pub fn channels(&self) -> Vec<String> {
+        self.shared
+            .subscriptions
+            .lock()
+            .channels
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    // starts the task that drives the listener, unless it was already started
+    fn start(&self) {
+        let close_event = match self.shared.close_event.lock().take() {
+            Some(close_event) => close_event,
+            None => return,
+        };
+
+        let listener = PgListener::lazy(
+            self.shared.pool.clone(),
+            Arc::clone(&self.shared.subscriptions),
+        );
+
+        sqlx_rt::spawn(run(listener, close_event));
+    }
+}
+
+// drives `listener` until its pool is closed or dropped
+async fn run(mut listener: PgListener, mut close_event: CloseEvent) {
+    let mut backoff = MIN_BACKOFF;
+
+    while !listener.subscriptions.lock().closed {
+        match close_event.do_until(listener.try_recv()).await {
+            // the pool is closing
+            Err(_) => break,
+
+            // a late notification of a channel no longer subscribed to, or a lost connection,
+            // which is reopened by the next `try_recv`
+            Ok(Ok(_)) => backoff = MIN_BACKOFF,
+
+            Ok(Err(error)) => {
+                log::warn!(
+                    "shared PgListener failed, reconnecting in {:?}: {}",
+                    backoff,
+                    error
+                );
+
+                listener.lose_connection(&error);
+
+                if close_event.do_until(sqlx_rt::sleep(backoff)).await.is_err() {
+                    break;
+                }
+
+                backoff = cmp::min(backoff * 2, MAX_BACKOFF);
+            }
+        }
+    }
+
+    listener.subscriptions.lock().closed = true;
+
+    // dropping the listener ends the streams of the subscriptions, and returns the connection
+    // to its pool to be closed
+    let pool = listener.pool.clone();
+    drop(listener);
+
+    pool.close().await;
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        // the pool and every handle were dropped; the task only holds on to the subscriptions
+        let mut subscriptions = self.subscriptions.lock();
+        subscriptions.closed = true;
+
+        if let Some(listener) = subscriptions.listener.take() {
+            listener.wake();
+        }
+    }
+}
+
+impl Debug for PgSharedListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgSharedListener")
+            .field("channels", &self.channels())
+            .finish()
+    }
+}
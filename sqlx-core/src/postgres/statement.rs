@@ -7,6 +7,7 @@ use crate::statement::Statement;
 use crate::HashMap;
 use either::Either;
 use std::borrow::Cow;
+use std::mem;
 use std::sync::Arc;
 
 #[derive(Debug, Clone)]
@@ -22,6 +23,18 @@ pub(crate) struct PgStatementMetadata {
     pub(crate) parameters: Vec<PgTypeInfo>,
 }
 
+impl PgStatementMetadata {
+    // roughly the memory the metadata takes, for a statement cache bounded by size
+    pub(crate) fn estimated_size(&self) -> usize {
+        let names: usize = self.columns.iter().map(|column| column.name.len()).sum();
+
+        mem::size_of::<Self>()
+            + self.columns.len() * (mem::size_of::<PgColumn>() + mem::size_of::<(UStr, usize)>())
+            + self.parameters.len() * mem::size_of::<PgTypeInfo>()
+            + names
+    }
+}
+
 impl<'q> Statement<'q> for PgStatement<'q> {
     type Database = Postgres;
 
@@ -1,14 +1,21 @@
+use std::fmt::Write;
+
 use futures_core::future::BoxFuture;
 
 use crate::error::Error;
-use crate::executor::Executor;
-use crate::postgres::message::Query;
-use crate::postgres::{PgConnection, Postgres};
+use crate::postgres::connection::PendingOp;
+use crate::postgres::{PgArguments, PgBatch, PgConnection, Postgres};
+use crate::query::query_with;
+use crate::query_scalar::query_scalar;
 use crate::transaction::{
-    begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
+    begin_ansi_transaction_sql, commit_ansi_transaction_sql, quote_transaction_id,
+    rollback_ansi_transaction_sql, validate_setting_name, PreparedTransaction, Transaction,
     TransactionManager,
 };
 
+// the limit PostgreSQL puts on the length of a global transaction id
+const MAX_GID_LEN: usize = 199;
+
 /// Implementation of [`TransactionManager`] for PostgreSQL.
 pub struct PgTransactionManager;
 
@@ -54,8 +61,7 @@ impl TransactionManager for PgTransactionManager {
 
     fn start_rollback(conn: &mut PgConnection) {
         if conn.transaction_depth > 0 {
-            conn.pending_ready_for_query_count += 1;
-            conn.stream.write(Query(&rollback_ansi_transaction_sql(
+            conn.queue_op(PendingOp::Rollback(rollback_ansi_transaction_sql(
                 conn.transaction_depth,
             )));
 
@@ -63,3 +69,177 @@ impl TransactionManager for PgTransactionManager {
         }
     }
 }
+
+impl<'c> Transaction<'c, Postgres> {
+    /// Sets a configuration parameter until the end of this transaction, as `SET LOCAL` does.
+    ///
+    /// The value is bound as a parameter of `set_config(name, value, true)`, so it is never
+    /// written into the SQL and needs no quoting. The name must be one or more identifiers
+    /// separated by `.`. As with `SET LOCAL`, rolling back a savepoint also undoes the settings
+    /// made since it was established.
+    ///
+    /// ```rust,no_run
+    /// # async fn f(conn: &mut sqlx_core::postgres::PgConnection) -> Result<(), sqlx_core::error::Error> {
+    /// use sqlx_core::connection::Connection;
+    ///
+    /// let mut tx = conn.begin().await?;
+    /// tx.set_local("app.current_tenant", "42").await?;
+    /// tx.set_local("statement_timeout", "5s").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_local(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        self.set_locals(Some((name, value))).await
+    }
+
+    /// Sets several configuration parameters until the end of this transaction, in a single
+    /// round trip; see [`set_local`](Self::set_local).
+    pub async fn set_locals<I, N, V>(&mut self, settings: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (N, V)>,
+        N: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut sql = String::from("SELECT ");
+        let mut arguments = PgArguments::default();
+        let mut count = 0;
+
+        for (name, value) in settings {
+            validate_setting_name(name.as_ref())?;
+
+            if count > 0 {
+                sql.push_str(", ");
+            }
+
+            let _ = write!(
+                sql,
+                "set_config(${}, ${}, true)",
+                count * 2 + 1,
+                count * 2 + 2
+            );
+
+            arguments.add(name.as_ref());
+            arguments.add(value.as_ref());
+            count += 1;
+        }
+
+        if count > 0 {
+            query_with(&sql, arguments).execute(&mut **self).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Starts a batch of statements to execute in this transaction in as few round trips as
+    /// possible, rather than one each; see [`PgBatch::execute`].
+    ///
+    /// ```rust,no_run
+    /// # async fn f(conn: &mut sqlx_core::postgres::PgConnection) -> Result<(), sqlx_core::error::Error> {
+    /// use sqlx_core::connection::Connection;
+    /// use sqlx_core::query::query;
+    ///
+    /// let mut tx = conn.begin().await?;
+    ///
+    /// let results = tx
+    ///     .batch()
+    ///     .add_query(query("INSERT INTO orders (id) VALUES ($1)").bind(1_i64))
+    ///     .add_query(query("UPDATE stock SET count = count - 1 WHERE item = $1").bind(7_i64))
+    ///     .add_query(query("DELETE FROM carts WHERE order_id = $1").bind(1_i64))
+    ///     .execute()
+    ///     .await?;
+    ///
+    /// tx.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// A failed statement aborts the transaction. To recover from it without losing the work
+    /// done before the batch, run the batch in a savepoint: begin a transaction on this one,
+    /// and roll that back on failure.
+    pub fn batch<'q>(&mut self) -> PgBatch<'_, 'q> {
+        PgBatch::new(self)
+    }
+
+    /// Prepares this transaction for two-phase commit with `PREPARE TRANSACTION`.
+    ///
+    /// Once prepared, the transaction is finished with [`PgConnection::commit_prepared`] or
+    /// [`PgConnection::rollback_prepared`], from this or any other connection to the same
+    /// database. The server must allow prepared transactions by setting
+    /// `max_prepared_transactions` above zero. Savepoints cannot be prepared.
+    ///
+    /// ```rust,no_run
+    /// # async fn f(conn: &mut sqlx_core::postgres::PgConnection) -> Result<(), sqlx_core::error::Error> {
+    /// use sqlx_core::connection::Connection;
+    ///
+    /// let tx = conn.begin().await?;
+    /// // ...
+    /// let prepared = tx.prepare_two_phase("order-1234").await?;
+    ///
+    /// // once every other participant has prepared too
+    /// conn.commit_prepared(prepared.gid()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn prepare_two_phase(mut self, gid: &str) -> Result<PreparedTransaction, Error> {
+        if self.transaction_depth != 1 {
+            return Err(Error::Configuration(
+                "a savepoint cannot be prepared for two-phase commit".into(),
+            ));
+        }
+
+        let sql = format!(
+            "PREPARE TRANSACTION {}",
+            quote_transaction_id(gid, MAX_GID_LEN)?
+        );
+        self.execute(&*sql).await?;
+
+        // the session is no longer in a transaction, and the prepared one must outlive `self`
+        self.transaction_depth = 0;
+        self.finish();
+
+        Ok(PreparedTransaction::new(gid.to_owned()))
+    }
+}
+
+impl PgConnection {
+    /// Commits a transaction prepared with
+    /// [`prepare_two_phase`](Transaction::<Postgres>::prepare_two_phase), as
+    /// `COMMIT PREPARED` does.
+    ///
+    /// This works from any connection to the database the transaction was prepared in, but
+    /// not from within a transaction.
+    pub async fn commit_prepared(&mut self, gid: &str) -> Result<(), Error> {
+        let sql = format!(
+            "COMMIT PREPARED {}",
+            quote_transaction_id(gid, MAX_GID_LEN)?
+        );
+        self.execute(&*sql).await?;
+
+        Ok(())
+    }
+
+    /// Rolls back a transaction prepared with
+    /// [`prepare_two_phase`](Transaction::<Postgres>::prepare_two_phase), as
+    /// `ROLLBACK PREPARED` does; see [`commit_prepared`](Self::commit_prepared).
+    pub async fn rollback_prepared(&mut self, gid: &str) -> Result<(), Error> {
+        let sql = format!(
+            "ROLLBACK PREPARED {}",
+            quote_transaction_id(gid, MAX_GID_LEN)?
+        );
+        self.execute(&*sql).await?;
+
+        Ok(())
+    }
+
+    /// Lists the transactions prepared in the current database and not yet committed or rolled
+    /// back, oldest first, e.g. to recover after a coordinator crashed.
+    pub async fn list_prepared(&mut self) -> Result<Vec<PreparedTransaction>, Error> {
+        let gids: Vec<String> = query_scalar(
+            "SELECT gid FROM pg_prepared_xacts WHERE database = current_database() ORDER BY prepared",
+        )
+        .fetch_all(self)
+        .await?;
+
+        Ok(gids.into_iter().map(PreparedTransaction::new).collect())
+    }
+}
@@ -115,6 +115,10 @@ pub enum PgType {
     JsonpathArray,
     Money,
     MoneyArray,
+    Xml,
+    XmlArray,
+    TsVector,
+    TsVectorArray,
 
     // https://www.postgresql.org/docs/9.3/datatype-pseudo.html
     Void,
@@ -146,7 +150,7 @@ pub enum PgTypeKind {
     Simple,
     Pseudo,
     Domain(PgTypeInfo),
-    Composite(Arc<[(String, PgTypeInfo)]>),
+    Composite(Arc<[(UStr, PgTypeInfo)]>),
     Array(PgTypeInfo),
     Enum(Arc<[String]>),
     Range(PgTypeInfo),
@@ -154,7 +158,10 @@ pub enum PgTypeKind {
 
 impl PgTypeInfo {
     /// Returns the corresponding `PgTypeInfo` if the OID is a built-in type and recognized by SQLx.
-    pub(crate) fn try_from_oid(oid: u32) -> Option<Self> {
+    ///
+    /// The OIDs of built-in types are fixed, so unlike [`with_oid`](Self::with_oid) this is
+    /// safe to use with any database; e.g. `PgTypeInfo::try_from_oid(1043)` is `VARCHAR`.
+    pub fn try_from_oid(oid: u32) -> Option<Self> {
         PgType::try_from_oid(oid).map(Self)
     }
 
@@ -209,8 +216,13 @@ impl PgTypeInfo {
     ///
     /// The OID for the type will be fetched from Postgres on use of
     /// a value of this type. The fetched OID will be cached per-connection.
-    pub const fn with_name(name: &'static str) -> Self {
-        Self(PgType::DeclareWithName(UStr::Static(name)))
+    /// The name is resolved as by
+    /// [`PgConnection::resolve_type`](crate::postgres::PgConnection::resolve_type).
+    ///
+    /// A string literal is kept as it is; a name built at runtime, e.g. to qualify it with a
+    /// schema, can be given as a `String`.
+    pub fn with_name(name: impl Into<UStr>) -> Self {
+        Self(PgType::DeclareWithName(name.into()))
     }
 
     /// Create a `PgTypeInfo` from an OID.
@@ -243,6 +255,8 @@ impl PgType {
             25 => PgType::Text,
             26 => PgType::Oid,
             114 => PgType::Json,
+            142 => PgType::Xml,
+            143 => PgType::XmlArray,
             199 => PgType::JsonArray,
             600 => PgType::Point,
             601 => PgType::Lseg,
@@ -309,6 +323,8 @@ impl PgType {
             2287 => PgType::RecordArray,
             2950 => PgType::Uuid,
             2951 => PgType::UuidArray,
+            3614 => PgType::TsVector,
+            3643 => PgType::TsVectorArray,
             3802 => PgType::Jsonb,
             3807 => PgType::JsonbArray,
             3904 => PgType::Int4Range,
@@ -351,6 +367,8 @@ impl PgType {
             PgType::Text => 25,
             PgType::Oid => 26,
             PgType::Json => 114,
+            PgType::Xml => 142,
+            PgType::XmlArray => 143,
             PgType::JsonArray => 199,
             PgType::Point => 600,
             PgType::Lseg => 601,
@@ -417,6 +435,8 @@ impl PgType {
             PgType::RecordArray => 2287,
             PgType::Uuid => 2950,
             PgType::UuidArray => 2951,
+            PgType::TsVector => 3614,
+            PgType::TsVectorArray => 3643,
             PgType::Jsonb => 3802,
             PgType::JsonbArray => 3807,
             PgType::Int4Range => 3904,
@@ -535,6 +555,10 @@ impl PgType {
             PgType::JsonpathArray => "JSONPATH[]",
             PgType::Money => "MONEY",
             PgType::MoneyArray => "MONEY[]",
+            PgType::Xml => "XML",
+            PgType::XmlArray => "XML[]",
+            PgType::TsVector => "TSVECTOR",
+            PgType::TsVectorArray => "TSVECTOR[]",
             PgType::Void => "VOID",
             PgType::Custom(ty) => &*ty.name,
             PgType::DeclareWithOid(_) => "?",
@@ -635,6 +659,10 @@ impl PgType {
             PgType::JsonpathArray => "_jsonpath",
             PgType::Money => "money",
             PgType::MoneyArray => "_money",
+            PgType::Xml => "xml",
+            PgType::XmlArray => "_xml",
+            PgType::TsVector => "tsvector",
+            PgType::TsVectorArray => "_tsvector",
             PgType::Void => "void",
             PgType::Custom(ty) => &*ty.name,
             PgType::DeclareWithOid(_) => "?",
@@ -735,6 +763,10 @@ impl PgType {
             PgType::JsonpathArray => &PgTypeKind::Array(PgTypeInfo(PgType::Jsonpath)),
             PgType::Money => &PgTypeKind::Simple,
             PgType::MoneyArray => &PgTypeKind::Array(PgTypeInfo(PgType::Money)),
+            PgType::Xml => &PgTypeKind::Simple,
+            PgType::XmlArray => &PgTypeKind::Array(PgTypeInfo(PgType::Xml)),
+            PgType::TsVector => &PgTypeKind::Simple,
+            PgType::TsVectorArray => &PgTypeKind::Array(PgTypeInfo(PgType::TsVector)),
 
             PgType::Void => &PgTypeKind::Pseudo,
 
@@ -880,6 +912,14 @@ impl PgTypeInfo {
     pub(crate) const MONEY: Self = Self(PgType::Money);
     pub(crate) const MONEY_ARRAY: Self = Self(PgType::MoneyArray);
 
+    // xml documents and content
+    pub(crate) const XML: Self = Self(PgType::Xml);
+    pub(crate) const XML_ARRAY: Self = Self(PgType::XmlArray);
+
+    // full text search documents
+    pub(crate) const TSVECTOR: Self = Self(PgType::TsVector);
+    pub(crate) const TSVECTOR_ARRAY: Self = Self(PgType::TsVectorArray);
+
     //
     // date/time types
     // https://www.postgresql.org/docs/current/datatype-datetime.html
@@ -1,12 +1,67 @@
+use std::convert::TryFrom;
+
 use bytes::Buf;
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
-use crate::postgres::type_info::PgType;
+use crate::postgres::type_info::{PgType, PgTypeKind};
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
 
+use super::array_compatible;
+
+/// Provides the array type of a type that is only known to Postgres by name.
+///
+/// The OID of a user-defined type is assigned when the type is created, so it cannot be known
+/// ahead of time, and neither can the OID of its array type. `#[derive(sqlx::Type)]` implements
+/// this trait for enumerations, composite types and non-transparent newtypes, naming the array
+/// type after the convention of Postgres: the name of the element type with a leading
+/// underscore. With that, `Vec<T>` and `&[T]` can be bound and decoded as `T[]`, the OID being
+/// looked up by name on first use and cached on the connection.
+///
+/// ```rust,ignore
+/// #[derive(sqlx::Type)]
+/// #[sqlx(type_name = "mood", rename_all = "lowercase")]
+/// enum Mood { Sad, Ok, Happy }
+///
+/// let moods: Vec<Option<Mood>> = sqlx::query_scalar("SELECT '{happy,NULL,sad}'::mood[]")
+///     .fetch_one(&mut conn)
+///     .await?;
+/// ```
+///
+/// Implement this by hand for a type in a schema not on the `search_path`, or whose array type
+/// was given another name.
+pub trait PgHasArrayType: Type<Postgres> {
+    fn array_type_info() -> PgTypeInfo;
+}
+
+impl<T> Type<Postgres> for [T]
+where
+    T: PgHasArrayType,
+{
+    fn type_info() -> PgTypeInfo {
+        T::array_type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        array_compatible::<T>(ty)
+    }
+}
+
+impl<T> Type<Postgres> for Vec<T>
+where
+    T: PgHasArrayType,
+{
+    fn type_info() -> PgTypeInfo {
+        T::array_type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        array_compatible::<T>(ty)
+    }
+}
+
 impl<T> Type<Postgres> for [Option<T>]
 where
     [T]: Type<Postgres>,
@@ -33,6 +88,21 @@ where
     }
 }
 
+// the type of the slice, so that `[u8; N]` is BYTEA as `[u8]` is, and an array of any other
+// element type, including `Option<T>`, is a Postgres array of it
+impl<T, const N: usize> Type<Postgres> for [T; N]
+where
+    [T]: Type<Postgres>,
+{
+    fn type_info() -> PgTypeInfo {
+        <[T] as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <[T] as Type<Postgres>>::compatible(ty)
+    }
+}
+
 impl<'q, T> Encode<'q, Postgres> for Vec<T>
 where
     for<'a> &'a [T]: Encode<'q, Postgres>,
@@ -40,7 +110,19 @@ where
     Self: Type<Postgres>,
 {
     #[inline]
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        self.as_slice().encode_by_ref(buf)
+    }
+}
+
+impl<'q, T, const N: usize> Encode<'q, Postgres> for [T; N]
+where
+    for<'a> &'a [T]: Encode<'q, Postgres>,
+    T: Encode<'q, Postgres>,
+    Self: Type<Postgres>,
+{
+    #[inline]
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         self.as_slice().encode_by_ref(buf)
     }
 }
@@ -50,7 +132,7 @@ where
     T: Encode<'q, Postgres> + Type<Postgres>,
     Self: Type<Postgres>,
 {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.extend(&1_i32.to_be_bytes()); // number of dimensions
         buf.extend(&0_i32.to_be_bytes()); // flags
 
@@ -63,14 +145,17 @@ where
             }
         }
 
-        buf.extend(&(self.len() as i32).to_be_bytes()); // len
+        let len = i32::try_from(self.len())
+            .map_err(|_| format!("array of {} elements is too long for Postgres", self.len()))?;
+
+        buf.extend(&len.to_be_bytes()); // len
         buf.extend(&1_i32.to_be_bytes()); // lower bound
 
         for element in self.iter() {
-            buf.encode(element);
+            buf.encode(element)?;
         }
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
@@ -108,7 +193,9 @@ where
 
                 // the OID of the element
                 let element_type_oid = buf.get_u32();
-                element_type_info = PgTypeInfo::try_from_oid(element_type_oid)
+                element_type_info = custom_element_type(&value.type_info)
+                    .filter(|ty| ty.0.try_oid() == Some(element_type_oid))
+                    .or(PgTypeInfo::try_from_oid(element_type_oid))
                     .unwrap_or_else(|| PgTypeInfo(PgType::DeclareWithOid(element_type_oid)));
 
                 // length of the array axis
@@ -123,12 +210,11 @@ where
 
                 let mut elements = Vec::with_capacity(len as usize);
 
-                for _ in 0..len {
-                    elements.push(T::decode(PgValueRef::get(
-                        &mut buf,
-                        format,
-                        element_type_info.clone(),
-                    ))?)
+                for index in 0..len {
+                    elements.push(
+                        T::decode(PgValueRef::get(&mut buf, format, element_type_info.clone()))
+                            .map_err(|e| element_error(index as usize, e))?,
+                    )
                 }
 
                 Ok(elements)
@@ -136,7 +222,8 @@ where
 
             PgValueFormat::Text => {
                 // no type is provided from the database for the element
-                element_type_info = T::type_info();
+                element_type_info =
+                    custom_element_type(&value.type_info).unwrap_or_else(T::type_info);
 
                 let s = value.as_str()?;
 
@@ -160,6 +247,7 @@ where
                 let mut done = false;
                 let mut in_quotes = false;
                 let mut in_escape = false;
+                let mut quoted = false;
                 let mut value = String::with_capacity(10);
                 let mut chars = s.chars();
                 let mut elements = Vec::with_capacity(4);
@@ -175,6 +263,7 @@ where
 
                                 '"' => {
                                     in_quotes = !in_quotes;
+                                    quoted = true;
                                 }
 
                                 '\\' => {
@@ -197,20 +286,25 @@ where
                         }
                     }
 
-                    let value_opt = if value == "NULL" {
+                    // a quoted "NULL" is the text and not a NULL
+                    let value_opt = if value == "NULL" && !quoted {
                         None
                     } else {
                         Some(value.as_bytes())
                     };
 
-                    elements.push(T::decode(PgValueRef {
+                    let element = T::decode(PgValueRef {
                         value: value_opt,
                         row: None,
                         type_info: element_type_info.clone(),
                         format,
-                    })?);
+                    })
+                    .map_err(|e| element_error(elements.len(), e))?;
+
+                    elements.push(element);
 
                     value.clear();
+                    quoted = false;
                 }
 
                 Ok(elements)
@@ -218,3 +312,41 @@ where
         }
     }
 }
+
+// an array of a length other than `N` is an error rather than being truncated or padded
+impl<'r, T, const N: usize> Decode<'r, Postgres> for [T; N]
+where
+    T: for<'a> Decode<'a, Postgres> + Type<Postgres>,
+    Vec<T>: Decode<'r, Postgres>,
+    Self: Type<Postgres>,
+{
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let elements = Vec::<T>::decode(value)?;
+        let len = elements.len();
+
+        <[T; N]>::try_from(elements).map_err(|_| {
+            format!("expected an array of {} elements, got {} elements", N, len).into()
+        })
+    }
+}
+
+// the element type of an array of a user-defined type, as resolved with the columns; this is
+// needed to decode composite types, which are described by their fields
+fn custom_element_type(array: &PgTypeInfo) -> Option<PgTypeInfo> {
+    match &array.0 {
+        PgType::Custom(ty) => match &ty.kind {
+            PgTypeKind::Array(element) => Some(element.clone()),
+            _ => None,
+        },
+
+        _ => None,
+    }
+}
+
+fn element_error(index: usize, error: BoxDynError) -> BoxDynError {
+    format!(
+        "error decoding the array element at index {}: {}",
+        index, error
+    )
+    .into()
+}
@@ -5,7 +5,7 @@ use bigdecimal::BigDecimal;
 use num_bigint::{BigInt, Sign};
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::postgres::types::numeric::{PgNumeric, PgNumericSign};
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
@@ -44,6 +44,10 @@ impl TryFrom<PgNumeric> for BigDecimal {
             PgNumeric::NotANumber => {
                 return Err("BigDecimal does not support NaN values".into());
             }
+
+            PgNumeric::Infinity(_) => {
+                return Err("BigDecimal does not support infinite values".into());
+            }
         };
 
         if digits.is_empty() {
@@ -145,15 +149,12 @@ impl TryFrom<&'_ BigDecimal> for PgNumeric {
     }
 }
 
-/// ### Panics
-/// If this `BigDecimal` cannot be represented by `PgNumeric`.
+/// Returns an error if this `BigDecimal` cannot be represented by `PgNumeric`.
 impl Encode<'_, Postgres> for BigDecimal {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
-        PgNumeric::try_from(self)
-            .expect("BigDecimal magnitude too great for Postgres NUMERIC type")
-            .encode(buf);
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        PgNumeric::try_from(self)?.encode(buf)?;
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 
     fn size_hint(&self) -> usize {
@@ -1,6 +1,6 @@
 use crate::{
     decode::Decode,
-    encode::{Encode, IsNull},
+    encode::{Encode, EncodeError, IsNull},
     error::BoxDynError,
     postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres},
     types::Type,
@@ -40,11 +40,11 @@ impl Type<Postgres> for Vec<BitVec> {
 }
 
 impl Encode<'_, Postgres> for BitVec {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.extend(&(self.len() as i32).to_be_bytes());
         buf.extend(self.to_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 
     fn size_hint(&self) -> usize {
@@ -0,0 +1,205 @@
+use std::iter::FromIterator;
+use std::mem;
+
+use bytes::Buf;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::error::BoxDynError;
+use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use crate::types::Type;
+
+/// A string of bits, stored in the PostgreSQL [`BIT(n)` and `VARBIT`] types.
+///
+/// Bits are packed most significant first, as they are on the wire: the first bit of the string
+/// is the high bit of the first byte. Bits past the end of the string in the last byte are
+/// always zero.
+///
+/// Use this type when the `bit-vec` feature (for `bit_vec::BitVec`) is not wanted.
+///
+/// [`BIT(n)` and `VARBIT`]: https://www.postgresql.org/docs/current/datatype-bit.html
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PgBits {
+    len: usize,
+    bytes: Vec<u8>,
+}
+
+impl PgBits {
+    /// Creates an empty bit string.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a bit string of `len` bits from packed `bytes`.
+    ///
+    /// Returns `None` unless `bytes` holds exactly `len` bits, rounded up to a whole byte.
+    /// Unused trailing bits are cleared.
+    pub fn from_bytes(len: usize, mut bytes: Vec<u8>) -> Option<Self> {
+        if bytes.len() != (len + 7) / 8 {
+            return None;
+        }
+
+        if len % 8 != 0 {
+            if let Some(last) = bytes.last_mut() {
+                *last &= 0xff << (8 - len % 8);
+            }
+        }
+
+        Some(Self { len, bytes })
+    }
+
+    /// The number of bits in the string.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the string has no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The packed bits; see the [type-level documentation](Self) for the layout.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The bit at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index < self.len {
+            Some(self.bytes[index / 8] & (0x80 >> (index % 8)) != 0)
+        } else {
+            None
+        }
+    }
+
+    /// Appends a bit to the end of the string.
+    pub fn push(&mut self, bit: bool) {
+        if self.len % 8 == 0 {
+            self.bytes.push(0);
+        }
+
+        if bit {
+            self.bytes[self.len / 8] |= 0x80 >> (self.len % 8);
+        }
+
+        self.len += 1;
+    }
+
+    /// Iterates over the bits, first to last.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(move |index| self.bytes[index / 8] & (0x80 >> (index % 8)) != 0)
+    }
+}
+
+impl FromIterator<bool> for PgBits {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bits = PgBits::new();
+
+        for bit in iter {
+            bits.push(bit);
+        }
+
+        bits
+    }
+}
+
+impl Type<Postgres> for PgBits {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::VARBIT
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        *ty == PgTypeInfo::BIT || *ty == PgTypeInfo::VARBIT
+    }
+}
+
+impl Type<Postgres> for [PgBits] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::VARBIT_ARRAY
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        *ty == PgTypeInfo::BIT_ARRAY || *ty == PgTypeInfo::VARBIT_ARRAY
+    }
+}
+
+impl Type<Postgres> for Vec<PgBits> {
+    fn type_info() -> PgTypeInfo {
+        <[PgBits] as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <[PgBits] as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for PgBits {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        buf.extend(&(self.len as i32).to_be_bytes());
+        buf.extend(&self.bytes);
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        mem::size_of::<i32>() + self.bytes.len()
+    }
+}
+
+impl Decode<'_, Postgres> for PgBits {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => {
+                let mut bytes = value.as_bytes()?;
+
+                if bytes.len() < mem::size_of::<i32>() {
+                    return Err("VARBIT value is too short".into());
+                }
+
+                let len = bytes.get_i32();
+
+                if len < 0 {
+                    return Err(format!("negative VARBIT length: {}", len).into());
+                }
+
+                PgBits::from_bytes(len as usize, bytes.to_vec())
+                    .ok_or_else(|| "VARBIT length mismatch".into())
+            }
+
+            PgValueFormat::Text => value
+                .as_str()?
+                .chars()
+                .map(|c| match c {
+                    '0' => Ok(false),
+                    '1' => Ok(true),
+                    _ => Err(format!("invalid character in VARBIT value: {:?}", c).into()),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[test]
+fn it_packs_bits_most_significant_first() {
+    let bits: PgBits = [true, false, true, true, false, false, false, false, true]
+        .iter()
+        .copied()
+        .collect();
+
+    assert_eq!(bits.len(), 9);
+    assert_eq!(bits.as_bytes(), [0b1011_0000, 0b1000_0000]);
+    assert_eq!(bits.get(8), Some(true));
+    assert_eq!(bits.get(9), None);
+    assert_eq!(bits.iter().filter(|bit| *bit).count(), 4);
+}
+
+#[test]
+fn it_clears_unused_trailing_bits() {
+    let bits = PgBits::from_bytes(3, vec![0xff]).unwrap();
+
+    assert_eq!(bits.as_bytes(), [0b1110_0000]);
+    assert_eq!(bits, [true, true, true].iter().copied().collect());
+
+    assert!(PgBits::from_bytes(9, vec![0xff]).is_none());
+    assert!(PgBits::from_bytes(0, Vec::new()).unwrap().is_empty());
+}
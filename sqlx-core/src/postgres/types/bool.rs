@@ -1,5 +1,5 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
@@ -23,14 +23,16 @@ impl Type<Postgres> for Vec<bool> {
 }
 
 impl Encode<'_, Postgres> for bool {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.push(*self as u8);
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Postgres> for bool {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(match value.format() {
             PgValueFormat::Binary => value.as_bytes()?[0] != 0,
@@ -1,6 +1,7 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
+use crate::postgres::types::PgHasArrayType;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
 
@@ -41,15 +42,15 @@ impl Type<Postgres> for Vec<Vec<u8>> {
 }
 
 impl Encode<'_, Postgres> for &'_ [u8] {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.extend_from_slice(self);
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Encode<'_, Postgres> for Vec<u8> {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         <&[u8] as Encode<Postgres>>::encode(self, buf)
     }
 }
@@ -59,7 +60,8 @@ impl<'r> Decode<'r, Postgres> for &'r [u8] {
         match value.format() {
             PgValueFormat::Binary => value.as_bytes(),
             PgValueFormat::Text => {
-                Err("unsupported decode to `&[u8]` of BYTEA in a simple query; use a prepared query or decode to `Vec<u8>`".into())
+                // the text format is hex, which cannot be borrowed as the bytes it stands for
+                Err("unsupported decode to `&[u8]` of BYTEA in the text format (a simple query, or one asking for text results); use a prepared query with binary results or decode to `Vec<u8>`".into())
             }
         }
     }
@@ -76,3 +78,49 @@ impl Decode<'_, Postgres> for Vec<u8> {
         })
     }
 }
+
+// `[u8; N]` is BYTEA through the impl of `Type` for `[T; N]`, as `[u8]` is
+impl<const N: usize> PgHasArrayType for [u8; N] {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::BYTEA_ARRAY
+    }
+}
+
+impl<const N: usize> Encode<'_, Postgres> for [u8; N] {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        buf.extend_from_slice(self);
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        N
+    }
+}
+
+impl<const N: usize> Decode<'_, Postgres> for [u8; N] {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        let mut bytes = [0; N];
+
+        match value.format() {
+            PgValueFormat::Binary => copy_exactly(value.as_bytes()?, &mut bytes)?,
+
+            PgValueFormat::Text => {
+                // BYTEA is formatted as \x followed by hex characters
+                copy_exactly(&hex::decode(&value.as_str()?[2..])?, &mut bytes)?
+            }
+        }
+
+        Ok(bytes)
+    }
+}
+
+fn copy_exactly(src: &[u8], dst: &mut [u8]) -> Result<(), BoxDynError> {
+    if src.len() != dst.len() {
+        return Err(format!("expected {} bytes, got {}", dst.len(), src.len()).into());
+    }
+
+    dst.copy_from_slice(src);
+
+    Ok(())
+}
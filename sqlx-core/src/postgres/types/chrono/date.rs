@@ -1,6 +1,7 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
+use crate::postgres::types::date_style_error;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
 use chrono::{Duration, NaiveDate};
@@ -25,7 +26,7 @@ impl Type<Postgres> for Vec<NaiveDate> {
 }
 
 impl Encode<'_, Postgres> for NaiveDate {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         // DATE is encoded as the days since epoch
         let days = (*self - NaiveDate::from_ymd(2000, 1, 1)).num_days() as i32;
         Encode::<Postgres>::encode(&days, buf)
@@ -45,7 +46,11 @@ impl<'r> Decode<'r, Postgres> for NaiveDate {
                 NaiveDate::from_ymd(2000, 1, 1) + Duration::days(days.into())
             }
 
-            PgValueFormat::Text => NaiveDate::parse_from_str(value.as_str()?, "%Y-%m-%d")?,
+            PgValueFormat::Text => {
+                let s = value.as_str()?;
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .map_err(|error| date_style_error(s, error))?
+            }
         })
     }
 }
@@ -1,6 +1,7 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
+use crate::postgres::types::{date_style_error, split_utc_offset};
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
 use chrono::{
@@ -45,13 +46,12 @@ impl<Tz: TimeZone> Type<Postgres> for Vec<DateTime<Tz>> {
 }
 
 impl Encode<'_, Postgres> for NaiveDateTime {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
-        // FIXME: We should *really* be returning an error, Encode needs to be fallible
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         // TIMESTAMP is encoded as the microseconds since the epoch
         let epoch = NaiveDate::from_ymd(2000, 1, 1).and_hms(0, 0, 0);
         let us = (*self - epoch)
             .num_microseconds()
-            .unwrap_or_else(|| panic!("NaiveDateTime out of range for Postgres: {:?}", self));
+            .ok_or_else(|| format!("NaiveDateTime out of range for Postgres: {:?}", self))?;
 
         Encode::<Postgres>::encode(&us, buf)
     }
@@ -72,25 +72,20 @@ impl<'r> Decode<'r, Postgres> for NaiveDateTime {
             }
 
             PgValueFormat::Text => {
+                // `timestamptz` is printed in the session time zone, with its offset from UTC
                 let s = value.as_str()?;
-                NaiveDateTime::parse_from_str(
-                    s,
-                    if s.contains('+') {
-                        // Contains a time-zone specifier
-                        // This is given for timestamptz for some reason
-                        // Postgres already guarantees this to always be UTC
-                        "%Y-%m-%d %H:%M:%S%.f%#z"
-                    } else {
-                        "%Y-%m-%d %H:%M:%S%.f"
-                    },
-                )?
+                let (datetime, offset) = split_utc_offset(s)?;
+
+                NaiveDateTime::parse_from_str(datetime, "%Y-%m-%d %H:%M:%S%.f")
+                    .map_err(|error| date_style_error(s, error))?
+                    - Duration::seconds(offset.into())
             }
         })
     }
 }
 
 impl<Tz: TimeZone> Encode<'_, Postgres> for DateTime<Tz> {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         Encode::<Postgres>::encode(self.naive_utc(), buf)
     }
 
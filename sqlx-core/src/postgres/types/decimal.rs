@@ -6,7 +6,7 @@ use rust_decimal::{
 use std::convert::{TryFrom, TryInto};
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::postgres::types::numeric::{PgNumeric, PgNumericSign};
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
@@ -16,6 +16,14 @@ impl Type<Postgres> for Decimal {
     fn type_info() -> PgTypeInfo {
         PgTypeInfo::NUMERIC
     }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        *ty == PgTypeInfo::NUMERIC || <i64 as Type<Postgres>>::compatible(ty) || is_float(ty)
+    }
+}
+
+fn is_float(ty: &PgTypeInfo) -> bool {
+    *ty == PgTypeInfo::FLOAT4 || *ty == PgTypeInfo::FLOAT8
 }
 
 impl Type<Postgres> for [Decimal] {
@@ -45,6 +53,10 @@ impl TryFrom<PgNumeric> for Decimal {
             PgNumeric::NotANumber => {
                 return Err("Decimal does not support NaN values".into());
             }
+
+            PgNumeric::Infinity(_) => {
+                return Err("Decimal does not support infinite values".into());
+            }
         };
 
         if digits.is_empty() {
@@ -154,20 +166,39 @@ impl TryFrom<&'_ Decimal> for PgNumeric {
     }
 }
 
-/// ### Panics
-/// If this `Decimal` cannot be represented by `PgNumeric`.
+/// Returns an error if this `Decimal` cannot be represented by `PgNumeric`.
 impl Encode<'_, Postgres> for Decimal {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
-        PgNumeric::try_from(self)
-            .expect("Decimal magnitude too great for Postgres NUMERIC type")
-            .encode(buf);
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        PgNumeric::try_from(self)?.encode(buf)?;
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Postgres> for Decimal {
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        if <i64 as Type<Postgres>>::compatible(&value.type_info) {
+            return Ok(<i64 as Decode<Postgres>>::decode(value)?.into());
+        }
+
+        if is_float(&value.type_info) {
+            // the shortest text that reads back as the same float, so `0.1` is not
+            // `0.1000000000000000055511151231257827`
+            let (text, finite) = if value.type_info == PgTypeInfo::FLOAT4 {
+                let float = <f32 as Decode<Postgres>>::decode(value)?;
+                (float.to_string(), float.is_finite())
+            } else {
+                let float = <f64 as Decode<Postgres>>::decode(value)?;
+                (float.to_string(), float.is_finite())
+            };
+
+            if !finite {
+                return Err(format!("Decimal does not support {} values", text).into());
+            }
+
+            return Ok(text.parse::<Decimal>()?);
+        }
+
         match value.format() {
             PgValueFormat::Binary => PgNumeric::decode(value.as_bytes()?)?.try_into(),
             PgValueFormat::Text => Ok(value.as_str()?.parse::<Decimal>()?),
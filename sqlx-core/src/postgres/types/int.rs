@@ -1,11 +1,66 @@
+use std::convert::TryFrom;
+
 use byteorder::{BigEndian, ByteOrder};
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
 
+/// The column types that `i16`, `i32` and `i64` can be decoded from, with the size of their
+/// binary encoding.
+///
+/// Decoding into a wider type always succeeds. Decoding into a narrower type succeeds only if
+/// the value fits, so `int8` can be read as `i32` as long as it holds no more than `i32::MAX`.
+const INT_TYPES: &[(PgTypeInfo, usize)] = &[
+    (PgTypeInfo::INT2, 2),
+    (PgTypeInfo::INT4, 4),
+    (PgTypeInfo::INT8, 8),
+];
+
+fn int_compatible(ty: &PgTypeInfo) -> bool {
+    INT_TYPES.iter().any(|(int, _)| int == ty)
+}
+
+fn int_decode<T>(value: PgValueRef<'_>) -> Result<T, BoxDynError>
+where
+    T: TryFrom<i64>,
+{
+    let int: i64 = match value.format() {
+        PgValueFormat::Binary => {
+            let buf = value.as_bytes()?;
+            let size = INT_TYPES
+                .iter()
+                .find(|(int, _)| *int == value.type_info)
+                .map_or(buf.len(), |(_, size)| *size);
+
+            if buf.len() != size || size == 0 || size > 8 {
+                return Err(format!(
+                    "expected {} bytes for an integer of type {}, got {}",
+                    size,
+                    value.type_info,
+                    buf.len()
+                )
+                .into());
+            }
+
+            BigEndian::read_int(buf, size)
+        }
+
+        PgValueFormat::Text => value.as_str()?.parse()?,
+    };
+
+    T::try_from(int).map_err(|_| {
+        format!(
+            "value {} is out of range for {}",
+            int,
+            std::any::type_name::<T>()
+        )
+        .into()
+    })
+}
+
 impl Type<Postgres> for i8 {
     fn type_info() -> PgTypeInfo {
         PgTypeInfo::CHAR
@@ -25,14 +80,16 @@ impl Type<Postgres> for Vec<i8> {
 }
 
 impl Encode<'_, Postgres> for i8 {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_be_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Postgres> for i8 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         // note: in the TEXT encoding, a value of "0" here is encoded as an empty string
         Ok(value.as_bytes()?.get(0).copied().unwrap_or_default() as i8)
@@ -43,6 +100,9 @@ impl Type<Postgres> for i16 {
     fn type_info() -> PgTypeInfo {
         PgTypeInfo::INT2
     }
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        int_compatible(ty)
+    }
 }
 
 impl Type<Postgres> for [i16] {
@@ -58,19 +118,18 @@ impl Type<Postgres> for Vec<i16> {
 }
 
 impl Encode<'_, Postgres> for i16 {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_be_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Postgres> for i16 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
-        Ok(match value.format() {
-            PgValueFormat::Binary => BigEndian::read_i16(value.as_bytes()?),
-            PgValueFormat::Text => value.as_str()?.parse()?,
-        })
+        int_decode(value)
     }
 }
 
@@ -78,6 +137,9 @@ impl Type<Postgres> for u32 {
     fn type_info() -> PgTypeInfo {
         PgTypeInfo::OID
     }
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        *ty == PgTypeInfo::OID || int_compatible(ty)
+    }
 }
 
 impl Type<Postgres> for [u32] {
@@ -93,15 +155,21 @@ impl Type<Postgres> for Vec<u32> {
 }
 
 impl Encode<'_, Postgres> for u32 {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_be_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Postgres> for u32 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        if value.type_info != PgTypeInfo::OID {
+            return int_decode(value);
+        }
+
         Ok(match value.format() {
             PgValueFormat::Binary => BigEndian::read_u32(value.as_bytes()?),
             PgValueFormat::Text => value.as_str()?.parse()?,
@@ -113,6 +181,9 @@ impl Type<Postgres> for i32 {
     fn type_info() -> PgTypeInfo {
         PgTypeInfo::INT4
     }
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        int_compatible(ty)
+    }
 }
 
 impl Type<Postgres> for [i32] {
@@ -128,19 +199,18 @@ impl Type<Postgres> for Vec<i32> {
 }
 
 impl Encode<'_, Postgres> for i32 {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_be_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Postgres> for i32 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
-        Ok(match value.format() {
-            PgValueFormat::Binary => BigEndian::read_i32(value.as_bytes()?),
-            PgValueFormat::Text => value.as_str()?.parse()?,
-        })
+        int_decode(value)
     }
 }
 
@@ -148,6 +218,9 @@ impl Type<Postgres> for i64 {
     fn type_info() -> PgTypeInfo {
         PgTypeInfo::INT8
     }
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        int_compatible(ty)
+    }
 }
 
 impl Type<Postgres> for [i64] {
@@ -163,18 +236,300 @@ impl Type<Postgres> for Vec<i64> {
 }
 
 impl Encode<'_, Postgres> for i64 {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.extend(&self.to_be_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Decode<'_, Postgres> for i64 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
-        Ok(match value.format() {
-            PgValueFormat::Binary => BigEndian::read_i64(value.as_bytes()?),
-            PgValueFormat::Text => value.as_str()?.parse()?,
+        int_decode(value)
+    }
+}
+
+// Postgres has no unsigned integers; `u16` is sent as `int4`, which holds all of its values,
+// and `u64` as `int8` as long as it fits (see `PgNumericU64` for every `u64`). `u32` is the
+// type of `oid`, which is unsigned. `u8` is left out, as `Vec<u8>` and `&[u8]` are `bytea`.
+
+impl Type<Postgres> for u16 {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::INT4
+    }
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        int_compatible(ty)
+    }
+}
+
+impl Type<Postgres> for [u16] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::INT4_ARRAY
+    }
+}
+
+impl Type<Postgres> for Vec<u16> {
+    fn type_info() -> PgTypeInfo {
+        <[u16] as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for u16 {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        Encode::<Postgres>::encode(i32::from(*self), buf)
+    }
+}
+
+impl Decode<'_, Postgres> for u16 {
+    const CHEAP_TO_DECODE: bool = true;
+
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        int_decode(value)
+    }
+}
+
+impl Type<Postgres> for u64 {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::INT8
+    }
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        int_compatible(ty)
+    }
+}
+
+impl Type<Postgres> for [u64] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::INT8_ARRAY
+    }
+}
+
+impl Type<Postgres> for Vec<u64> {
+    fn type_info() -> PgTypeInfo {
+        <[u64] as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for u64 {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        let int = i64::try_from(*self).map_err(|_| {
+            format!(
+                "value {} is out of range for INT8; bind it as `PgNumericU64` to send a NUMERIC",
+                self
+            )
+        })?;
+
+        Encode::<Postgres>::encode(int, buf)
+    }
+}
+
+impl Decode<'_, Postgres> for u64 {
+    const CHEAP_TO_DECODE: bool = true;
+
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        int_decode(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::fmt::Debug;
+
+    use super::INT_TYPES;
+    use crate::decode::Decode;
+    use crate::encode::Encode;
+    use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+    use crate::types::Type;
+
+    // the edges of every integer type plus a deterministic spread of values in between
+    fn samples() -> Vec<i64> {
+        let mut samples = vec![0, 1, -1];
+
+        for &(min, max) in &[
+            (i16::MIN as i64, i16::MAX as i64),
+            (i32::MIN as i64, i32::MAX as i64),
+            (i64::MIN, i64::MAX),
+        ] {
+            samples.extend(&[min, min + 1, max - 1, max]);
+            samples.extend(&[min.saturating_sub(1), max.saturating_add(1)]);
+        }
+
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..1000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            // shift by a varying amount so every magnitude is represented
+            samples.push((state as i64) >> (state % 64));
+        }
+
+        samples
+    }
+
+    fn decode<T>(ty: &PgTypeInfo, format: PgValueFormat, buf: &[u8]) -> Option<T>
+    where
+        T: for<'r> Decode<'r, Postgres>,
+    {
+        T::decode(PgValueRef {
+            value: Some(buf),
+            row: None,
+            type_info: ty.clone(),
+            format,
         })
+        .ok()
+    }
+
+    fn check<T>()
+    where
+        T: for<'r> Decode<'r, Postgres> + Type<Postgres> + TryFrom<i64> + PartialEq + Debug,
+    {
+        for (ty, size) in INT_TYPES {
+            assert!(T::compatible(ty));
+
+            let shift = 64 - size * 8;
+
+            // skip values the column itself could not hold
+            for value in samples()
+                .into_iter()
+                .filter(|v| (v << shift) >> shift == *v)
+            {
+                let expected = T::try_from(value).ok();
+                let binary = &value.to_be_bytes()[8 - size..];
+                let text = value.to_string();
+
+                assert_eq!(
+                    decode::<T>(ty, PgValueFormat::Binary, binary),
+                    expected,
+                    "{} in binary from {}",
+                    value,
+                    ty
+                );
+                assert_eq!(
+                    decode::<T>(ty, PgValueFormat::Text, text.as_bytes()),
+                    expected,
+                    "{} in text from {}",
+                    value,
+                    ty
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn it_widens_and_checks_narrowing_for_i16() {
+        check::<i16>();
+    }
+
+    #[test]
+    fn it_widens_and_checks_narrowing_for_i32() {
+        check::<i32>();
+    }
+
+    #[test]
+    fn it_widens_and_checks_narrowing_for_i64() {
+        check::<i64>();
+    }
+
+    #[test]
+    fn it_checks_negatives_and_narrowing_for_u16() {
+        check::<u16>();
+    }
+
+    #[test]
+    fn it_checks_negatives_and_narrowing_for_u32() {
+        check::<u32>();
+    }
+
+    #[test]
+    fn it_checks_negatives_and_narrowing_for_u64() {
+        check::<u64>();
+    }
+
+    fn encode<T>(value: T) -> Option<Vec<u8>>
+    where
+        T: for<'q> Encode<'q, Postgres>,
+    {
+        let mut buf = PgArgumentBuffer::default();
+        value.encode(&mut buf).ok()?;
+
+        Some(buf.to_vec())
+    }
+
+    #[test]
+    fn it_widens_u16_to_int4() {
+        assert_eq!(<u16 as Type<Postgres>>::type_info(), PgTypeInfo::INT4);
+
+        for &value in &[0, 1, i16::MAX as u16, i16::MAX as u16 + 1, u16::MAX] {
+            assert_eq!(encode(value), Some(i32::from(value).to_be_bytes().to_vec()));
+        }
+    }
+
+    #[test]
+    fn it_refuses_to_encode_u64_over_int8() {
+        assert_eq!(<u64 as Type<Postgres>>::type_info(), PgTypeInfo::INT8);
+
+        for &value in &[
+            0,
+            1,
+            i32::MAX as u64 + 1,
+            i64::MAX as u64 - 1,
+            i64::MAX as u64,
+        ] {
+            assert_eq!(encode(value), Some((value as i64).to_be_bytes().to_vec()));
+        }
+
+        for &value in &[i64::MAX as u64 + 1, u64::MAX - 1, u64::MAX] {
+            assert_eq!(encode(value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn it_still_reads_u32_from_oid() {
+        let value = <u32 as Decode<Postgres>>::decode(PgValueRef {
+            value: Some(&u32::MAX.to_be_bytes()),
+            row: None,
+            type_info: PgTypeInfo::OID,
+            format: PgValueFormat::Binary,
+        })
+        .unwrap();
+
+        assert_eq!(value, u32::MAX);
+        assert_eq!(encode(u32::MAX), Some(u32::MAX.to_be_bytes().to_vec()));
+    }
+
+    #[test]
+    fn it_reports_negative_values_for_unsigned_types() {
+        let err = <u64 as Decode<Postgres>>::decode(PgValueRef {
+            value: Some(&(-1_i64).to_be_bytes()),
+            row: None,
+            type_info: PgTypeInfo::INT8,
+            format: PgValueFormat::Binary,
+        })
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "value -1 is out of range for u64");
+    }
+
+    #[test]
+    fn it_reports_the_out_of_range_value() {
+        let err = <i16 as Decode<Postgres>>::decode(PgValueRef {
+            value: Some(&70000_i32.to_be_bytes()),
+            row: None,
+            type_info: PgTypeInfo::INT4,
+            format: PgValueFormat::Binary,
+        })
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "value 70000 is out of range for i16");
+    }
+
+    #[test]
+    fn it_is_not_compatible_with_other_types() {
+        assert!(!<i32 as Type<Postgres>>::compatible(&PgTypeInfo::TEXT));
+        assert!(!<i64 as Type<Postgres>>::compatible(
+            &PgTypeInfo::INT8_ARRAY
+        ));
     }
 }
@@ -4,7 +4,7 @@ use std::mem;
 use byteorder::{NetworkEndian, ReadBytesExt};
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
@@ -46,21 +46,100 @@ impl<'de> Decode<'de, Postgres> for PgInterval {
                 })
             }
 
-            // TODO: Implement parsing of text mode
-            PgValueFormat::Text => {
-                Err("not implemented: decode `INTERVAL` in text mode (unprepared queries)".into())
+            PgValueFormat::Text => parse_interval(value.as_str()?),
+        }
+    }
+}
+
+// parses the text of an INTERVAL in the `postgres` IntervalStyle, which connections are started
+// with, e.g. `1 year 2 mons -3 days +04:05:06.789`
+fn parse_interval(s: &str) -> Result<PgInterval, BoxDynError> {
+    let invalid = || format!("invalid INTERVAL value: {:?}", s);
+
+    let mut interval = PgInterval {
+        months: 0,
+        days: 0,
+        microseconds: 0,
+    };
+
+    let mut parts = s.split_whitespace();
+
+    while let Some(part) = parts.next() {
+        if part.contains(':') {
+            interval.microseconds = parse_interval_time(part).ok_or_else(invalid)?;
+            continue;
+        }
+
+        let n: i32 = part.parse().map_err(|_| invalid())?;
+
+        match parts.next() {
+            Some("year") | Some("years") => {
+                interval.months = n
+                    .checked_mul(12)
+                    .and_then(|months| months.checked_add(interval.months))
+                    .ok_or_else(invalid)?
             }
+
+            Some("mon") | Some("mons") => {
+                interval.months = interval.months.checked_add(n).ok_or_else(invalid)?
+            }
+
+            Some("day") | Some("days") => interval.days = n,
+            _ => return Err(invalid().into()),
+        }
+    }
+
+    Ok(interval)
+}
+
+// `[+-]HH:MM:SS[.ffffff]` in microseconds, where the hours may exceed 24
+fn parse_interval_time(s: &str) -> Option<i64> {
+    let (negative, s) = match s.as_bytes().first()? {
+        b'-' => (true, &s[1..]),
+        b'+' => (false, &s[1..]),
+        _ => (false, s),
+    };
+
+    let mut parts = s.splitn(3, ':');
+
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let minutes: i64 = parts.next()?.parse().ok()?;
+
+    let (seconds, fraction) = match parts.next()? {
+        seconds if seconds.contains('.') => {
+            let mut seconds = seconds.splitn(2, '.');
+            (seconds.next()?, seconds.next()?)
         }
+
+        seconds => (seconds, ""),
+    };
+
+    let seconds: i64 = seconds.parse().ok()?;
+
+    if fraction.len() > 6 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
     }
+
+    let fraction: i64 = format!("{:0<6}", fraction).parse().ok()?;
+
+    let microseconds = hours
+        .checked_mul(3_600_000_000)?
+        .checked_add(minutes * 60_000_000 + seconds * 1_000_000 + fraction)?;
+
+    Some(if negative {
+        -microseconds
+    } else {
+        microseconds
+    })
 }
 
 impl Encode<'_, Postgres> for PgInterval {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.extend(&self.microseconds.to_be_bytes());
         buf.extend(&self.days.to_be_bytes());
         buf.extend(&self.months.to_be_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 
     fn size_hint(&self) -> usize {
@@ -84,10 +163,8 @@ impl Type<Postgres> for [std::time::Duration] {
 }
 
 impl Encode<'_, Postgres> for std::time::Duration {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
-        PgInterval::try_from(*self)
-            .expect("failed to encode `std::time::Duration`")
-            .encode_by_ref(buf)
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        PgInterval::try_from(*self)?.encode_by_ref(buf)
     }
 
     fn size_hint(&self) -> usize {
@@ -131,9 +208,8 @@ impl Type<Postgres> for [chrono::Duration] {
 
 #[cfg(feature = "chrono")]
 impl Encode<'_, Postgres> for chrono::Duration {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
-        let pg_interval = PgInterval::try_from(*self).expect("Failed to encode chrono::Duration");
-        pg_interval.encode_by_ref(buf)
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        PgInterval::try_from(*self)?.encode_by_ref(buf)
     }
 
     fn size_hint(&self) -> usize {
@@ -170,9 +246,8 @@ impl Type<Postgres> for [time::Duration] {
 
 #[cfg(feature = "time")]
 impl Encode<'_, Postgres> for time::Duration {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
-        let pg_interval = PgInterval::try_from(*self).expect("Failed to encode time::Duration");
-        pg_interval.encode_by_ref(buf)
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        PgInterval::try_from(*self)?.encode_by_ref(buf)
     }
 
     fn size_hint(&self) -> usize {
@@ -212,7 +287,7 @@ fn test_encode_interval() {
     };
     assert!(matches!(
         Encode::<Postgres>::encode(&interval, &mut buf),
-        IsNull::No
+        Ok(IsNull::No)
     ));
     assert_eq!(&**buf, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
     buf.clear();
@@ -224,7 +299,7 @@ fn test_encode_interval() {
     };
     assert!(matches!(
         Encode::<Postgres>::encode(&interval, &mut buf),
-        IsNull::No
+        Ok(IsNull::No)
     ));
     assert_eq!(&**buf, [0, 0, 0, 0, 0, 0, 3, 232, 0, 0, 0, 0, 0, 0, 0, 0]);
     buf.clear();
@@ -236,7 +311,7 @@ fn test_encode_interval() {
     };
     assert!(matches!(
         Encode::<Postgres>::encode(&interval, &mut buf),
-        IsNull::No
+        Ok(IsNull::No)
     ));
     assert_eq!(&**buf, [0, 0, 0, 0, 0, 15, 66, 64, 0, 0, 0, 0, 0, 0, 0, 0]);
     buf.clear();
@@ -248,7 +323,7 @@ fn test_encode_interval() {
     };
     assert!(matches!(
         Encode::<Postgres>::encode(&interval, &mut buf),
-        IsNull::No
+        Ok(IsNull::No)
     ));
     assert_eq!(
         &**buf,
@@ -263,7 +338,7 @@ fn test_encode_interval() {
     };
     assert!(matches!(
         Encode::<Postgres>::encode(&interval, &mut buf),
-        IsNull::No
+        Ok(IsNull::No)
     ));
     assert_eq!(&**buf, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0]);
     buf.clear();
@@ -275,12 +350,60 @@ fn test_encode_interval() {
     };
     assert!(matches!(
         Encode::<Postgres>::encode(&interval, &mut buf),
-        IsNull::No
+        Ok(IsNull::No)
     ));
     assert_eq!(&**buf, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
     buf.clear();
 }
 
+#[test]
+fn test_decode_interval_text() {
+    let parse = |s| parse_interval(s).unwrap();
+
+    assert_eq!(
+        parse("00:00:00"),
+        PgInterval {
+            months: 0,
+            days: 0,
+            microseconds: 0
+        }
+    );
+
+    assert_eq!(
+        parse("1 year 2 mons 3 days 04:05:06.789"),
+        PgInterval {
+            months: 14,
+            days: 3,
+            microseconds: 14_706_789_000
+        }
+    );
+
+    assert_eq!(
+        parse("-1 years -2 mons +3 days -04:05:06.000001"),
+        PgInterval {
+            months: -14,
+            days: 3,
+            microseconds: -14_706_000_001
+        }
+    );
+
+    assert_eq!(
+        parse("1 mon -1 days"),
+        PgInterval {
+            months: 1,
+            days: -1,
+            microseconds: 0
+        }
+    );
+
+    assert_eq!(parse("100:00:00.5").microseconds, 360_000_500_000);
+
+    assert!(parse_interval("1 fortnight").is_err());
+    assert!(parse_interval("1:2").is_err());
+    assert!(parse_interval("00:00:00.1234567").is_err());
+    assert!(parse_interval("P1Y2M").is_err());
+}
+
 #[test]
 fn test_pginterval_std() {
     let interval = PgInterval {
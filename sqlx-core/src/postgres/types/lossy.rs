@@ -0,0 +1,97 @@
+use crate::decode::Decode;
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::error::BoxDynError;
+use crate::postgres::types::numeric::PgNumeric;
+use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use crate::types::{Lossy, Type};
+
+fn is_int(ty: &PgTypeInfo) -> bool {
+    *ty == PgTypeInfo::INT2 || *ty == PgTypeInfo::INT4 || *ty == PgTypeInfo::INT8
+}
+
+impl Type<Postgres> for Lossy<f64> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::FLOAT8
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        *ty == PgTypeInfo::NUMERIC
+            || *ty == PgTypeInfo::FLOAT4
+            || *ty == PgTypeInfo::FLOAT8
+            || is_int(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for Lossy<f64> {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        Encode::<Postgres>::encode_by_ref(&self.0, buf)
+    }
+}
+
+impl Decode<'_, Postgres> for Lossy<f64> {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        if value.type_info == PgTypeInfo::FLOAT4 {
+            return Ok(Lossy(f64::from(<f32 as Decode<Postgres>>::decode(value)?)));
+        }
+
+        if is_int(&value.type_info) {
+            // past 2^53 this rounds to the nearest `f64`, like any other conversion here
+            return Ok(Lossy(<i64 as Decode<Postgres>>::decode(value)? as f64));
+        }
+
+        if value.type_info != PgTypeInfo::NUMERIC {
+            return <f64 as Decode<Postgres>>::decode(value).map(Lossy);
+        }
+
+        let float = match value.format() {
+            PgValueFormat::Binary => PgNumeric::decode(value.as_bytes()?)?.to_f64()?,
+
+            PgValueFormat::Text => {
+                let s = value.as_str()?;
+                let float: f64 = s.parse()?;
+
+                if float.is_infinite() && !s.ends_with("Infinity") {
+                    return Err("NUMERIC value is out of range for f64".into());
+                }
+
+                float
+            }
+        };
+
+        Ok(Lossy(float))
+    }
+}
+
+impl Type<Postgres> for Lossy<i64> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::INT8
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        *ty == PgTypeInfo::NUMERIC || is_int(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for Lossy<i64> {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        Encode::<Postgres>::encode_by_ref(&self.0, buf)
+    }
+}
+
+impl Decode<'_, Postgres> for Lossy<i64> {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        if value.type_info != PgTypeInfo::NUMERIC {
+            return <i64 as Decode<Postgres>>::decode(value).map(Lossy);
+        }
+
+        let int = match value.format() {
+            PgValueFormat::Binary => PgNumeric::decode(value.as_bytes()?)?.to_i64()?,
+
+            // the text of a NUMERIC with a scale of 0 has no decimal point, so anything else
+            // fails to parse
+            PgValueFormat::Text => value.as_str()?.parse()?,
+        };
+
+        Ok(Lossy(int))
+    }
+}
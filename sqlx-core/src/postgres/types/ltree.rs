@@ -0,0 +1,137 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::error::{BoxDynError, Error};
+use crate::postgres::types::PgHasArrayType;
+use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use crate::types::Type;
+
+/// A path of labels, e.g. `Top.Science.Astronomy`, stored in the [`LTREE`] type of the `ltree`
+/// extension.
+///
+/// As the type is created by an extension, its OID is looked up by name on first use, so the
+/// extension must be installed in a schema on the `search_path`.
+///
+/// [`FromStr`] checks that each label separated by `.` is made of letters, digits, `_` and `-`,
+/// and is at most 1000 characters long. Postgres itself is stricter before version 16, which
+/// refuses `-` and labels longer than 255 characters, and decides which letters are letters by
+/// the locale of the database.
+///
+/// [`LTREE`]: https://www.postgresql.org/docs/current/ltree.html
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PgLtree(pub String);
+
+// the version of the binary format, which precedes the text of the value
+const BINARY_VERSION: u8 = 1;
+
+const MAX_LABEL_LEN: usize = 1000;
+
+impl PgLtree {
+    /// The text of the value.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The labels of the path, from the root.
+    pub fn labels(&self) -> impl Iterator<Item = &str> + '_ {
+        self.0.split('.').filter(|label| !label.is_empty())
+    }
+}
+
+impl Display for PgLtree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for PgLtree {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        // the empty path has no labels
+        if s.is_empty() {
+            return Ok(PgLtree::default());
+        }
+
+        for label in s.split('.') {
+            if label.is_empty() {
+                return Err(err_protocol!("empty label in LTREE: {:?}", s));
+            }
+
+            if label.chars().count() > MAX_LABEL_LEN {
+                return Err(err_protocol!(
+                    "label of more than {} characters in LTREE: {:?}",
+                    MAX_LABEL_LEN,
+                    s
+                ));
+            }
+
+            if let Some(c) = label
+                .chars()
+                .find(|c| !(c.is_alphanumeric() || *c == '_' || *c == '-'))
+            {
+                return Err(err_protocol!("invalid character {:?} in LTREE: {:?}", c, s));
+            }
+        }
+
+        Ok(PgLtree(s.to_owned()))
+    }
+}
+
+impl Type<Postgres> for PgLtree {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("ltree")
+    }
+}
+
+impl PgHasArrayType for PgLtree {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_ltree")
+    }
+}
+
+impl Encode<'_, Postgres> for PgLtree {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        buf.push(BINARY_VERSION);
+        buf.extend(self.0.as_bytes());
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        1 + self.0.len()
+    }
+}
+
+impl Decode<'_, Postgres> for PgLtree {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => match value.as_bytes()? {
+                [BINARY_VERSION, text @ ..] => Ok(PgLtree(std::str::from_utf8(text)?.to_owned())),
+                [version, ..] => Err(format!("unsupported LTREE version: {}", version).into()),
+                [] => Err("LTREE value is too short".into()),
+            },
+
+            PgValueFormat::Text => Ok(PgLtree(value.as_str()?.to_owned())),
+        }
+    }
+}
+
+#[test]
+fn it_checks_ltree_labels() {
+    let path: PgLtree = "Top.Science_2.Astro-nomy".parse().unwrap();
+
+    assert_eq!(
+        path.labels().collect::<Vec<_>>(),
+        ["Top", "Science_2", "Astro-nomy"]
+    );
+
+    assert_eq!("".parse::<PgLtree>().unwrap().labels().count(), 0);
+
+    assert!("Top..Science".parse::<PgLtree>().is_err());
+    assert!("Top.".parse::<PgLtree>().is_err());
+    assert!("Top.Sci ence".parse::<PgLtree>().is_err());
+    assert!("a".repeat(1001).parse::<PgLtree>().is_err());
+}
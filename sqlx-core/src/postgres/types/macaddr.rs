@@ -0,0 +1,156 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::error::BoxDynError;
+use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use crate::types::Type;
+
+/// A MAC address in the EUI-48 format, stored in the PostgreSQL [`MACADDR`] type.
+///
+/// `[u8; 6]` on its own is sent as BYTEA, like a byte array of any other length.
+///
+/// [`MACADDR`]: https://www.postgresql.org/docs/current/datatype-net-types.html#DATATYPE-MACADDR
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PgMacAddress(pub [u8; 6]);
+
+/// A MAC address in the EUI-64 format, stored in the PostgreSQL [`MACADDR8`] type.
+///
+/// `[u8; 8]` on its own is sent as BYTEA, like a byte array of any other length.
+///
+/// [`MACADDR8`]: https://www.postgresql.org/docs/current/datatype-net-types.html#DATATYPE-MACADDR8
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PgMacAddress8(pub [u8; 8]);
+
+macro_rules! impl_mac_address {
+    ($name:ident, $len:literal, $ty:ident, $array_ty:ident) => {
+        impl From<[u8; $len]> for $name {
+            fn from(address: [u8; $len]) -> Self {
+                $name(address)
+            }
+        }
+
+        impl From<$name> for [u8; $len] {
+            fn from(address: $name) -> Self {
+                address.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                for (i, byte) in self.0.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(":")?;
+                    }
+
+                    write!(f, "{:02x}", byte)?;
+                }
+
+                Ok(())
+            }
+        }
+
+        impl Type<Postgres> for $name {
+            fn type_info() -> PgTypeInfo {
+                PgTypeInfo::$ty
+            }
+        }
+
+        impl Type<Postgres> for [$name] {
+            fn type_info() -> PgTypeInfo {
+                PgTypeInfo::$array_ty
+            }
+        }
+
+        impl Type<Postgres> for Vec<$name> {
+            fn type_info() -> PgTypeInfo {
+                <[$name] as Type<Postgres>>::type_info()
+            }
+        }
+
+        impl Encode<'_, Postgres> for $name {
+            fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+                buf.extend_from_slice(&self.0);
+
+                Ok(IsNull::No)
+            }
+
+            fn size_hint(&self) -> usize {
+                $len
+            }
+        }
+
+        impl Decode<'_, Postgres> for $name {
+            fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+                let mut address = [0; $len];
+
+                match value.format() {
+                    PgValueFormat::Binary => {
+                        let bytes = value.as_bytes()?;
+
+                        if bytes.len() != $len {
+                            return Err(
+                                format!("expected {} bytes, got {}", $len, bytes.len()).into()
+                            );
+                        }
+
+                        address.copy_from_slice(bytes);
+                    }
+
+                    PgValueFormat::Text => parse_mac_address(value.as_str()?, &mut address)?,
+                }
+
+                Ok($name(address))
+            }
+        }
+    };
+}
+
+impl_mac_address!(PgMacAddress, 6, MACADDR, MACADDR_ARRAY);
+impl_mac_address!(PgMacAddress8, 8, MACADDR8, MACADDR8_ARRAY);
+
+// Postgres prints MAC addresses as colon-separated pairs of hex digits, e.g. `08:00:2b:01:02:03`
+fn parse_mac_address(s: &str, address: &mut [u8]) -> Result<(), BoxDynError> {
+    let mut parts = s.split(':');
+
+    for byte in address.iter_mut() {
+        let part = parts
+            .next()
+            .filter(|part| part.len() == 2)
+            .ok_or_else(|| format!("invalid MAC address: {:?}", s))?;
+
+        *byte = u8::from_str_radix(part, 16)?;
+    }
+
+    if parts.next().is_some() {
+        return Err(format!("invalid MAC address: {:?}", s).into());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn it_parses_mac_addresses() {
+    let mut address = [0; 6];
+    parse_mac_address("08:00:2b:01:02:ff", &mut address).unwrap();
+    assert_eq!(address, [0x08, 0x00, 0x2b, 0x01, 0x02, 0xff]);
+
+    let mut address = [0; 8];
+    parse_mac_address("08:00:2b:01:02:03:04:05", &mut address).unwrap();
+    assert_eq!(address, [0x08, 0x00, 0x2b, 0x01, 0x02, 0x03, 0x04, 0x05]);
+
+    let mut address = [0; 6];
+    assert!(parse_mac_address("08:00:2b:01:02", &mut address).is_err());
+    assert!(parse_mac_address("08:00:2b:01:02:03:04", &mut address).is_err());
+    assert!(parse_mac_address("08:00:2b:01:02:0g", &mut address).is_err());
+}
+
+#[test]
+fn it_formats_mac_addresses() {
+    let address = PgMacAddress([0x08, 0x00, 0x2b, 0x01, 0x02, 0xff]);
+    assert_eq!(address.to_string(), "08:00:2b:01:02:ff");
+
+    let mut parsed = [0; 6];
+    parse_mac_address(&address.to_string(), &mut parsed).unwrap();
+    assert_eq!(PgMacAddress(parsed), address);
+}
@@ -9,13 +9,85 @@
 //! | `i16`                                 | SMALLINT, SMALLSERIAL, INT2                          |
 //! | `i32`                                 | INT, SERIAL, INT4                                    |
 //! | `i64`                                 | BIGINT, BIGSERIAL, INT8                              |
+//! | `u16`                                 | INT, INT4                                            |
+//! | `u32`                                 | OID                                                  |
+//! | `u64`                                 | BIGINT, INT8                                         |
+//! | [`PgNumericU64`]                      | NUMERIC                                              |
 //! | `f32`                                 | REAL, FLOAT4                                         |
 //! | `f64`                                 | DOUBLE PRECISION, FLOAT8                             |
 //! | `&str`, [`String`]                    | VARCHAR, CHAR(N), TEXT, NAME                         |
-//! | `&[u8]`, `Vec<u8>`                    | BYTEA                                                |
+//! | `&[u8]`, `Vec<u8>`, `[u8; N]`         | BYTEA                                                |
 //! | [`PgInterval`]                        | INTERVAL                                             |
 //! | [`PgRange<T>`](PgRange)               | INT8RANGE, INT4RANGE, TSRANGE, TSTZTRANGE, DATERANGE, NUMRANGE |
 //! | [`PgMoney`]                           | MONEY                                                |
+//! | [`PgBits`]                            | BIT, VARBIT                                          |
+//! | [`PgXml`]                             | XML                                                  |
+//! | [`PgTsVector`]                        | TSVECTOR                                             |
+//! | [`PgLtree`]                           | LTREE                                                |
+//! | [`PgMacAddress`]                      | MACADDR                                              |
+//! | [`PgMacAddress8`]                     | MACADDR8                                             |
+//! | `std::time::SystemTime`               | TIMESTAMPTZ, TIMESTAMP                               |
+//! | [`UnixTimestamp`], [`UnixMillis`]     | BIGINT, INT, SMALLINT, TIMESTAMPTZ, TIMESTAMP        |
+//!
+//! A value of a domain is read as a value of its base type, so e.g. a column of a domain over
+//! INT can be decoded as `i32`.
+//!
+//! `i16`, `i32` and `i64` can each be decoded from any of SMALLINT, INT and BIGINT. A value that
+//! does not fit the Rust type, e.g. a BIGINT over `i32::MAX` read as `i32`, is a decode error.
+//!
+//! Postgres has no unsigned integers. `u16`, `u32` and `u64` can also be decoded from SMALLINT,
+//! INT and BIGINT, where a negative value is a decode error. `u16` is sent as INT, which holds
+//! all of its values, and `u64` as BIGINT: encoding a `u64` over `i64::MAX` is an error, so bind
+//! a [`PgNumericU64`] to send the full range as a NUMERIC. `u32` is sent as an OID, which is
+//! unsigned; cast the parameter (`$1::int8`) to compare it against an integer column. There is
+//! no `u8`, as `&[u8]` and `Vec<u8>` are BYTEA.
+//!
+//! `[u8; N]` is sent as BYTEA, whatever its length; wrap six or eight bytes in a
+//! [`PgMacAddress`] or [`PgMacAddress8`] to send them as a MAC address. Decoding a value of any
+//! other length than `N` is an error.
+//!
+//! ### Timestamps without `chrono` or `time`
+//!
+//! `SystemTime` is sent as a TIMESTAMPTZ. Its precision below a microsecond is dropped, rounding
+//! towards the past also before 1970. A value outside the range of Postgres, 4714 BC to 294276
+//! AD, is an encode error, and `infinity` or `-infinity` a decode error, each with a
+//! [`TimestampOutOfRange`](crate::types::TimestampOutOfRange) as its source.
+//!
+//! [`UnixTimestamp`] and [`UnixMillis`] hold the seconds or milliseconds since 1970. They are
+//! sent without a type, which Postgres infers from where the parameter is used: compared with
+//! or stored in a BIGINT column it is that number, and in a TIMESTAMPTZ or TIMESTAMP column
+//! it is the instant the number stands for. Where Postgres cannot infer the type, e.g.
+//! `SELECT $1`, cast the parameter: `$1::int8` or `$1::timestamptz`. A value beyond the range
+//! of a TIMESTAMPTZ is sent as a BIGINT. Decoding a timestamp rounds down to the second or the
+//! millisecond.
+//!
+//! A TIMESTAMP has no time zone, so SQLx reads and writes it as UTC. That agrees with the
+//! session, whose `TimeZone` SQLx sets to UTC when connecting: a `SystemTime` stored in a
+//! TIMESTAMP column is read back as the same instant. If the session changes `TimeZone`,
+//! Postgres converts between TIMESTAMPTZ parameters and TIMESTAMP columns in that zone, while
+//! the TIMESTAMP read back is still taken to be UTC, so the instant is off by the offset of
+//! the zone; use TIMESTAMPTZ columns for instants.
+//!
+//! [`UnixTimestamp`]: crate::types::UnixTimestamp
+//! [`UnixMillis`]: crate::types::UnixMillis
+//!
+//! ### Lossy conversions
+//!
+//! [`Lossy<T>`](crate::types::Lossy) decodes a column into a type that cannot hold all of its
+//! values, which is otherwise refused.
+//!
+//! | Rust type                             | Postgres type(s)                                     |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `Lossy<f64>`                          | NUMERIC, REAL, DOUBLE PRECISION, SMALLINT, INT, BIGINT |
+//! | `Lossy<i64>`                          | NUMERIC, SMALLINT, INT, BIGINT                       |
+//!
+//! A NUMERIC read as `Lossy<f64>` is rounded to the nearest `f64`, with ties going to the even
+//! neighbour, exactly as if its decimal text were parsed; so `0.1` gives `0.1_f64` and
+//! `9007199254740993` gives `9007199254740992.0`. `NaN` and `±Infinity` carry over. A value
+//! beyond the range of `f64` is a decode error, while one too close to zero becomes `0.0`.
+//!
+//! A NUMERIC read as `Lossy<i64>` must have a scale of 0 and fit in `i64`, otherwise it is a
+//! decode error; a value is never rounded or truncated to an integer.
 //!
 //!
 //! ### [`bigdecimal`](https://crates.io/crates/bigdecimal)
@@ -25,12 +97,18 @@
 //! |---------------------------------------|------------------------------------------------------|
 //! | `bigdecimal::BigDecimal`              | NUMERIC                                              |
 //!
+//! A `NaN` or `±Infinity` NUMERIC cannot be decoded as `BigDecimal`.
+//!
 //! ### [`decimal`](https://crates.io/crates/rust_decimal)
 //! Requires the `decimal` Cargo feature flag.
 //!
 //! | Rust type                             | Postgres type(s)                                        |
 //! |---------------------------------------|------------------------------------------------------|
-//! | `rust_decimal::Decimal`               | NUMERIC                                              |
+//! | `rust_decimal::Decimal`               | NUMERIC, SMALLINT, INT, BIGINT, REAL, DOUBLE PRECISION |
+//!
+//! A REAL or DOUBLE PRECISION is decoded as the shortest decimal that reads back as the same
+//! float, so `0.1::float8` gives `0.1`; `NaN`, `±Infinity` and values out of the range of
+//! `Decimal` are a decode error.
 //!
 //! ### [`chrono`](https://crates.io/crates/chrono)
 //!
@@ -123,7 +201,12 @@
 //!
 //! # Arrays
 //!
-//! One-dimensional arrays are supported as `Vec<T>` or `&[T]` where `T` implements `Type`.
+//! One-dimensional arrays are supported as `Vec<T>` or `&[T]` where `T` implements `Type`, and
+//! as `[T; N]`, where decoding an array of any other length than `N` is an error.
+//!
+//! This includes arrays of user-defined enumerations and composite types; the derive for `Type`
+//! implements [`PgHasArrayType`], which names the array type so that its OID can be looked up.
+//! Arrays that contain `NULL` are decoded as `Vec<Option<T>>`.
 //!
 //! # [Enumerations](https://www.postgresql.org/docs/current/datatype-enum.html)
 //!
@@ -150,22 +233,30 @@
 //! ```
 //!
 
-use crate::postgres::type_info::PgTypeKind;
+use crate::error::BoxDynError;
+use crate::postgres::type_info::{PgType, PgTypeKind};
 use crate::postgres::{PgTypeInfo, Postgres};
 use crate::types::Type;
 
 mod array;
+mod bits;
 mod bool;
 mod bytes;
 mod float;
 mod int;
 mod interval;
+mod ltree;
+mod macaddr;
 mod money;
+mod numeric_u64;
 mod range;
 mod record;
 mod str;
+mod system_time;
+mod tsvector;
 mod tuple;
 mod void;
+mod xml;
 
 #[cfg(any(feature = "chrono", feature = "time"))]
 mod time_tz;
@@ -173,8 +264,8 @@ mod time_tz;
 #[cfg(feature = "bigdecimal")]
 mod bigdecimal;
 
-#[cfg(any(feature = "bigdecimal", feature = "decimal"))]
-mod numeric;
+mod lossy;
+pub(crate) mod numeric;
 
 #[cfg(feature = "decimal")]
 mod decimal;
@@ -197,9 +288,16 @@ mod ipnetwork;
 #[cfg(feature = "bit-vec")]
 mod bit_vec;
 
+pub use array::PgHasArrayType;
+pub use bits::PgBits;
 pub use interval::PgInterval;
+pub use ltree::PgLtree;
+pub use macaddr::{PgMacAddress, PgMacAddress8};
 pub use money::PgMoney;
+pub use numeric_u64::PgNumericU64;
 pub use range::PgRange;
+pub use tsvector::PgTsVector;
+pub use xml::PgXml;
 
 #[cfg(any(feature = "chrono", feature = "time"))]
 pub use time_tz::PgTimeTz;
@@ -211,6 +309,13 @@ pub use record::{PgRecordDecoder, PgRecordEncoder};
 
 // Type::compatible impl appropriate for arrays
 fn array_compatible<E: Type<Postgres>>(ty: &PgTypeInfo) -> bool {
+    // the TEXT protocol does not resolve custom types, so the kind of an array of a type only
+    // known by name, such as one of an extension, is unknown; opt out of type checking as
+    // `PgType::eq` does
+    if let PgType::DeclareWithOid(_) = ty.0 {
+        return true;
+    }
+
     // we require the declared type to be an _array_ with an
     // element type that is acceptable
     if let PgTypeKind::Array(element) = &ty.kind() {
@@ -219,3 +324,94 @@ fn array_compatible<E: Type<Postgres>>(ty: &PgTypeInfo) -> bool {
 
     false
 }
+
+// splits the UTC offset off a `timestamptz` in the text format, which is printed in the
+// session time zone, e.g. `2026-10-14 08:30:00-04` or `2026-10-14 18:00:00+05:30`
+fn split_utc_offset(s: &str) -> Result<(&str, i32), BoxDynError> {
+    let time = s.find(':').unwrap_or(s.len());
+
+    let sign = match s[time..].rfind(|c| c == '+' || c == '-') {
+        Some(sign) => time + sign,
+        None => return Ok((s, 0)),
+    };
+
+    let mut seconds = 0;
+    let mut unit = 3600;
+
+    for part in s[sign + 1..].split(':') {
+        if unit == 0 {
+            return Err(format!("invalid UTC offset in {:?}", s).into());
+        }
+
+        seconds += part.parse::<i32>()? * unit;
+        unit /= 60;
+    }
+
+    if s.as_bytes()[sign] == b'-' {
+        seconds = -seconds;
+    }
+
+    Ok((&s[..sign], seconds))
+}
+
+// SQLx asks for the ISO `DateStyle` when connecting, as that is the only one dates and times
+// in the text format are parsed in; explain a parse failure caused by a session that changed it
+fn date_style_error(s: &str, error: impl Into<BoxDynError>) -> BoxDynError {
+    let year = s.bytes().take_while(u8::is_ascii_digit).count();
+    let iso = year >= 4 && s.as_bytes().get(year) == Some(&b'-');
+
+    if iso || s.ends_with("infinity") {
+        error.into()
+    } else {
+        format!(
+            "cannot decode {:?}: non-ISO DateStyle not supported in text mode \
+             (the session's DateStyle was changed from ISO)",
+            s
+        )
+        .into()
+    }
+}
+
+#[test]
+fn it_splits_utc_offsets() {
+    assert_eq!(
+        split_utc_offset("2026-10-14 12:00:00.5+00").unwrap(),
+        ("2026-10-14 12:00:00.5", 0)
+    );
+    assert_eq!(
+        split_utc_offset("2026-10-14 08:00:00-04").unwrap(),
+        ("2026-10-14 08:00:00", -4 * 3600)
+    );
+    assert_eq!(
+        split_utc_offset("2026-10-14 17:30:00+05:30").unwrap().1,
+        5 * 3600 + 30 * 60
+    );
+    assert_eq!(
+        split_utc_offset("1850-01-01 00:00:00+00:53:28").unwrap().1,
+        53 * 60 + 28
+    );
+    assert_eq!(
+        split_utc_offset("2026-10-14 12:00:00").unwrap(),
+        ("2026-10-14 12:00:00", 0)
+    );
+}
+
+#[test]
+fn it_explains_non_iso_date_styles() {
+    let error = || "parse error";
+
+    assert_eq!(
+        date_style_error("2026-10-14", error()).to_string(),
+        "parse error"
+    );
+    assert_eq!(
+        date_style_error("infinity", error()).to_string(),
+        "parse error"
+    );
+
+    for s in &["14.10.2026", "10/14/2026", "Wed Oct 14 12:00:00 2026 UTC"] {
+        assert!(date_style_error(s, error())
+            .to_string()
+            .contains("non-ISO DateStyle"));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     decode::Decode,
-    encode::{Encode, IsNull},
+    encode::{Encode, EncodeError, IsNull},
     error::BoxDynError,
     postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres},
     types::Type,
@@ -18,7 +18,14 @@ use std::{
 /// Data is read and written as 64-bit signed integers, and conversion into a
 /// decimal should be done using the right precision.
 ///
-/// Reading `MONEY` value in text format is not supported and will cause an error.
+/// The text format (used by the simple query API) depends on `lc_monetary`, e.g. `$1,234.56`
+/// or `-1.234,56 €`, but always prints every fractional digit, so the value is decoded from
+/// its digits and sign alone, independent of the locale.
+///
+/// The [`From`] conversion into `rust_decimal::Decimal` assumes a scale of two fractional
+/// digits, which is the case for every `lc_monetary` that uses cents. Use
+/// [`to_decimal`](Self::to_decimal) with an explicit scale otherwise, and
+/// [`try_from_decimal`](Self::try_from_decimal) to convert back without rounding.
 ///
 /// [`MONEY`]: https://www.postgresql.org/docs/current/datatype-money.html
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -62,6 +69,31 @@ impl PgMoney {
         Self(i64::from_le_bytes(buf))
     }
 
+    /// Convert a [`Decimal`] value into money with an explicit scale, failing instead of
+    /// rounding if the value has more fractional digits than `scale` or does not fit.
+    ///
+    /// [`Decimal`]: crate::types::Decimal
+    #[cfg(feature = "decimal")]
+    pub fn try_from_decimal(
+        decimal: rust_decimal::Decimal,
+        scale: u32,
+    ) -> Result<Self, BoxDynError> {
+        use rust_decimal::prelude::{ToPrimitive, Zero};
+
+        let cents = decimal
+            .checked_mul(rust_decimal::Decimal::new(10i64.pow(scale), 0))
+            .filter(|cents| cents.fract().is_zero())
+            .and_then(|cents| cents.to_i64())
+            .ok_or_else(|| {
+                format!(
+                    "{} cannot be represented exactly as money with a scale of {}",
+                    decimal, scale
+                )
+            })?;
+
+        Ok(Self(cents))
+    }
+
     /// Convert a [`BigDecimal`](crate::types::BigDecimal) value into money using the correct precision
     /// defined in the PostgreSQL settings. The default precision is two.
     #[cfg(feature = "bigdecimal")]
@@ -114,11 +146,19 @@ where
     }
 }
 
+/// Converts assuming a scale of two fractional digits; never loses precision.
+#[cfg(feature = "decimal")]
+impl From<PgMoney> for rust_decimal::Decimal {
+    fn from(money: PgMoney) -> Self {
+        money.to_decimal(2)
+    }
+}
+
 impl Encode<'_, Postgres> for PgMoney {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.extend(&self.0.to_be_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
@@ -130,18 +170,37 @@ impl Decode<'_, Postgres> for PgMoney {
 
                 Ok(PgMoney(cents))
             }
-            PgValueFormat::Text => {
-                let error = io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "Reading a `MONEY` value in text format is not supported.",
-                );
-
-                Err(Box::new(error))
-            }
+            PgValueFormat::Text => parse_money(value.as_str()?).map(PgMoney),
         }
     }
 }
 
+// every fractional digit is always printed, so the digits are the amount in the smallest unit
+fn parse_money(s: &str) -> Result<i64, BoxDynError> {
+    let negative = s.contains('-') || s.contains('(');
+    let mut digits = s.bytes().filter(u8::is_ascii_digit).peekable();
+
+    if digits.peek().is_none() {
+        return Err(format!("invalid MONEY value: {:?}", s).into());
+    }
+
+    digits.try_fold(0_i64, |amount, digit| {
+        let digit = i64::from(digit - b'0');
+
+        // accumulate negative amounts downwards so `i64::MIN` does not overflow
+        amount
+            .checked_mul(10)
+            .and_then(|amount| {
+                if negative {
+                    amount.checked_sub(digit)
+                } else {
+                    amount.checked_add(digit)
+                }
+            })
+            .ok_or_else(|| format!("MONEY value is out of range: {:?}", s).into())
+    })
+}
+
 impl Add<PgMoney> for PgMoney {
     type Output = PgMoney;
 
@@ -202,6 +261,24 @@ impl SubAssign<PgMoney> for PgMoney {
 mod tests {
     use super::PgMoney;
 
+    #[test]
+    fn it_parses_money_in_any_locale() {
+        use super::parse_money;
+
+        assert_eq!(parse_money("$1,234.56").unwrap(), 123456);
+        assert_eq!(parse_money("-$1,234.56").unwrap(), -123456);
+        assert_eq!(parse_money("($0.05)").unwrap(), -5);
+        assert_eq!(parse_money("-1.234,56 €").unwrap(), -123456);
+        assert_eq!(parse_money("¥1,235").unwrap(), 1235);
+        assert_eq!(
+            parse_money("-$92,233,720,368,547,758.08").unwrap(),
+            i64::MIN
+        );
+
+        assert!(parse_money("$92,233,720,368,547,758.08").is_err());
+        assert!(parse_money("$").is_err());
+    }
+
     #[test]
     fn adding_works() {
         assert_eq!(PgMoney(3), PgMoney(1) + PgMoney(2))
@@ -282,6 +359,26 @@ mod tests {
         assert_eq!(PgMoney(12345), PgMoney::from_decimal(dec, 2));
     }
 
+    #[test]
+    #[cfg(feature = "decimal")]
+    fn conversion_from_decimal_is_lossless() {
+        let money = PgMoney(-12345);
+        let dec = rust_decimal::Decimal::from(money);
+
+        assert_eq!(dec, rust_decimal::Decimal::new(-12345, 2));
+        assert_eq!(PgMoney::try_from_decimal(dec, 2).unwrap(), money);
+
+        let too_precise = rust_decimal::Decimal::new(12345, 3);
+        assert!(PgMoney::try_from_decimal(too_precise, 2).is_err());
+        assert_eq!(
+            PgMoney::try_from_decimal(too_precise, 3).unwrap(),
+            PgMoney(12345)
+        );
+
+        let too_large = rust_decimal::Decimal::new(i64::MAX, 0);
+        assert!(PgMoney::try_from_decimal(too_large, 2).is_err());
+    }
+
     #[test]
     #[cfg(feature = "bigdecimal")]
     fn conversion_from_bigdecimal_works() {
@@ -11,6 +11,9 @@ pub(crate) enum PgNumeric {
     /// Equivalent to the `'NaN'` value in Postgres. The result of, e.g. `1 / 0`.
     NotANumber,
 
+    /// `'Infinity'` or `'-Infinity'`, which Postgres 14 and newer can store in a `NUMERIC`.
+    Infinity(PgNumericSign),
+
     /// A populated `NUMERIC` value.
     ///
     /// A description of these fields can be found here (although the type being described is the
@@ -54,6 +57,8 @@ pub(crate) enum PgNumeric {
 const SIGN_POS: u16 = 0x0000;
 const SIGN_NEG: u16 = 0x4000;
 const SIGN_NAN: u16 = 0xC000; // overflows i16 (C equivalent truncates from integer literal)
+const SIGN_PINF: u16 = 0xD000;
+const SIGN_NINF: u16 = 0xF000;
 
 /// Possible sign values for [PgNumeric].
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -78,14 +83,31 @@ impl PgNumericSign {
 
 impl PgNumeric {
     pub(crate) fn decode(mut buf: &[u8]) -> Result<Self, BoxDynError> {
+        if buf.len() < 8 {
+            return Err(format!("expected at least 8 bytes for NUMERIC, got {}", buf.len()).into());
+        }
+
         // https://github.com/postgres/postgres/blob/bcd1c3630095e48bc3b1eb0fc8e8c8a7c851eba1/src/backend/utils/adt/numeric.c#L874
         let num_digits = buf.get_u16();
         let weight = buf.get_i16();
         let sign = buf.get_u16();
         let scale = buf.get_i16();
 
+        if buf.len() != usize::from(num_digits) * 2 {
+            return Err(format!(
+                "expected {} digits for NUMERIC, got {} bytes",
+                num_digits,
+                buf.len()
+            )
+            .into());
+        }
+
         if sign == SIGN_NAN {
             Ok(PgNumeric::NotANumber)
+        } else if sign == SIGN_PINF {
+            Ok(PgNumeric::Infinity(PgNumericSign::Positive))
+        } else if sign == SIGN_NINF {
+            Ok(PgNumeric::Infinity(PgNumericSign::Negative))
         } else {
             let digits: Vec<_> = (0..num_digits).map(|_| buf.get_i16()).collect::<_>();
 
@@ -98,11 +120,16 @@ impl PgNumeric {
         }
     }
 
+    /// Returns an error if `digits.len()` overflows `i16`.
+    ///
     /// ### Panics
     ///
-    /// * If `digits.len()` overflows `i16`
-    /// * If any element in `digits` is greater than or equal to 10000
-    pub(crate) fn encode(&self, buf: &mut PgArgumentBuffer) {
+    /// In debug builds, if any element in `digits` is greater than or equal to 10000.
+    #[cfg_attr(
+        not(any(feature = "bigdecimal", feature = "decimal")),
+        allow(dead_code)
+    )]
+    pub(crate) fn encode(&self, buf: &mut PgArgumentBuffer) -> Result<(), BoxDynError> {
         match *self {
             PgNumeric::Number {
                 ref digits,
@@ -113,7 +140,7 @@ impl PgNumeric {
                 let digits_len: i16 = digits
                     .len()
                     .try_into()
-                    .expect("PgNumeric.digits.len() should not overflow i16");
+                    .map_err(|_| "PgNumeric has too many digits for Postgres NUMERIC type")?;
 
                 buf.extend(&digits_len.to_be_bytes());
                 buf.extend(&weight.to_be_bytes());
@@ -133,6 +160,371 @@ impl PgNumeric {
                 buf.extend(&SIGN_NAN.to_be_bytes());
                 buf.extend(&0_i16.to_be_bytes());
             }
+
+            PgNumeric::Infinity(sign) => {
+                let sign = match sign {
+                    PgNumericSign::Positive => SIGN_PINF,
+                    PgNumericSign::Negative => SIGN_NINF,
+                };
+
+                buf.extend(&0_i16.to_be_bytes());
+                buf.extend(&0_i16.to_be_bytes());
+                buf.extend(&sign.to_be_bytes());
+                buf.extend(&0_i16.to_be_bytes());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The nearest `f64`, as if the value were printed in decimal and that text parsed, so
+    /// ties are rounded to the even neighbour. Values beyond the range of `f64` are an error;
+    /// values too close to zero for it become zero.
+    pub(crate) fn to_f64(&self) -> Result<f64, BoxDynError> {
+        let (sign, digits, weight) = match self {
+            PgNumeric::NotANumber => return Ok(f64::NAN),
+            PgNumeric::Infinity(PgNumericSign::Positive) => return Ok(f64::INFINITY),
+            PgNumeric::Infinity(PgNumericSign::Negative) => return Ok(f64::NEG_INFINITY),
+
+            PgNumeric::Number {
+                sign,
+                digits,
+                weight,
+                ..
+            } => (sign, digits, weight),
+        };
+
+        // all the digits as one integer, followed by the power of ten it is to be multiplied by
+        let mut text = String::with_capacity(digits.len() * 4 + 8);
+
+        if *sign == PgNumericSign::Negative {
+            text.push('-');
+        }
+
+        text.push('0');
+
+        for digit in digits {
+            text.push_str(&format!("{:04}", digit));
+        }
+
+        let exponent = (i64::from(*weight) + 1 - digits.len() as i64) * 4;
+        text.push_str(&format!("e{}", exponent));
+
+        let float: f64 = text.parse()?;
+
+        if float.is_infinite() {
+            return Err("NUMERIC value is out of range for f64".into());
         }
+
+        Ok(float)
+    }
+
+    /// The value as an integer, if it has no digits after the decimal point, not even zeroes,
+    /// and fits in an `i64`.
+    pub(crate) fn to_i64(&self) -> Result<i64, BoxDynError> {
+        let (sign, digits, weight, scale) = match self {
+            PgNumeric::NotANumber => return Err("cannot represent NaN as an integer".into()),
+            PgNumeric::Infinity(_) => return Err("cannot represent infinity as an integer".into()),
+
+            PgNumeric::Number {
+                sign,
+                digits,
+                weight,
+                scale,
+            } => (sign, digits, *weight, *scale),
+        };
+
+        if scale != 0 {
+            return Err(format!(
+                "NUMERIC value has a scale of {}; only a scale of 0 can be read as an integer",
+                scale
+            )
+            .into());
+        }
+
+        let out_of_range = || "NUMERIC value is out of range for i64";
+
+        // accumulated as a negative number, which can reach `i64::MIN`
+        let mut int: i64 = 0;
+
+        for (i, &digit) in digits.iter().enumerate() {
+            // with a scale of 0 no digit falls after the decimal point
+            if weight < i as i16 {
+                break;
+            }
+
+            int = int
+                .checked_mul(10_000)
+                .and_then(|int| int.checked_sub(i64::from(digit)))
+                .ok_or_else(out_of_range)?;
+        }
+
+        // the digits end before the units, which are zeroes
+        for _ in (digits.len() as i64)..(i64::from(weight) + 1) {
+            int = int.checked_mul(10_000).ok_or_else(out_of_range)?;
+        }
+
+        match sign {
+            PgNumericSign::Negative => Ok(int),
+            PgNumericSign::Positive => Ok(int.checked_neg().ok_or_else(out_of_range)?),
+        }
+    }
+
+    /// The value as an unsigned integer, if it has no digits after the decimal point, not even
+    /// zeroes, and fits in a `u64`.
+    pub(crate) fn to_u64(&self) -> Result<u64, BoxDynError> {
+        let (sign, digits, weight, scale) = match self {
+            PgNumeric::NotANumber => return Err("cannot represent NaN as an integer".into()),
+            PgNumeric::Infinity(_) => return Err("cannot represent infinity as an integer".into()),
+
+            PgNumeric::Number {
+                sign,
+                digits,
+                weight,
+                scale,
+            } => (sign, digits, *weight, *scale),
+        };
+
+        if scale != 0 {
+            return Err(format!(
+                "NUMERIC value has a scale of {}; only a scale of 0 can be read as an integer",
+                scale
+            )
+            .into());
+        }
+
+        let out_of_range = || "NUMERIC value is out of range for u64";
+
+        let mut int: u64 = 0;
+
+        for (i, &digit) in digits.iter().enumerate() {
+            if weight < i as i16 {
+                break;
+            }
+
+            int = int
+                .checked_mul(10_000)
+                .and_then(|int| int.checked_add(digit as u64))
+                .ok_or_else(out_of_range)?;
+        }
+
+        for _ in (digits.len() as i64)..(i64::from(weight) + 1) {
+            int = int.checked_mul(10_000).ok_or_else(out_of_range)?;
+        }
+
+        // -0 is sent with a positive sign, but check anyway
+        if *sign == PgNumericSign::Negative && int != 0 {
+            return Err(out_of_range().into());
+        }
+
+        Ok(int)
+    }
+}
+
+impl From<u64> for PgNumeric {
+    fn from(mut int: u64) -> Self {
+        let mut digits = Vec::new();
+
+        while int != 0 {
+            digits.push((int % 10_000) as i16);
+            int /= 10_000;
+        }
+
+        // zero has no digits and a weight of zero
+        let weight = digits.len().saturating_sub(1) as i16;
+
+        digits.reverse();
+
+        // trailing zero digits are not sent
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        PgNumeric::Number {
+            sign: PgNumericSign::Positive,
+            digits,
+            weight,
+            scale: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PgNumeric, PgNumericSign};
+
+    fn number(sign: PgNumericSign, weight: i16, scale: i16, digits: &[i16]) -> PgNumeric {
+        PgNumeric::Number {
+            sign,
+            weight,
+            scale,
+            digits: digits.to_vec(),
+        }
+    }
+
+    #[test]
+    fn it_rounds_to_the_nearest_f64() {
+        let positive = PgNumericSign::Positive;
+
+        // 0.1
+        assert_eq!(number(positive, -1, 1, &[1000]).to_f64().unwrap(), 0.1);
+
+        // 123.456
+        assert_eq!(
+            number(positive, 0, 3, &[123, 4560]).to_f64().unwrap(),
+            123.456
+        );
+
+        // -0.000001
+        assert_eq!(
+            number(PgNumericSign::Negative, -2, 6, &[100])
+                .to_f64()
+                .unwrap(),
+            -0.000001
+        );
+
+        // 9007199254740993 lies halfway between two floats and rounds to the even one
+        assert_eq!(
+            number(positive, 3, 0, &[9007, 1992, 5474, 993])
+                .to_f64()
+                .unwrap(),
+            9007199254740992.0
+        );
+
+        // 9007199254740995 rounds up to its even neighbour
+        assert_eq!(
+            number(positive, 3, 0, &[9007, 1992, 5474, 995])
+                .to_f64()
+                .unwrap(),
+            9007199254740996.0
+        );
+
+        // 0
+        assert_eq!(number(positive, 0, 0, &[]).to_f64().unwrap(), 0.0);
+
+        // 1e-400 is too small for a float and becomes 0
+        assert_eq!(number(positive, -100, 400, &[1]).to_f64().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn it_converts_special_values_to_f64() {
+        assert!(PgNumeric::NotANumber.to_f64().unwrap().is_nan());
+
+        assert_eq!(
+            PgNumeric::Infinity(PgNumericSign::Positive)
+                .to_f64()
+                .unwrap(),
+            f64::INFINITY
+        );
+
+        assert_eq!(
+            PgNumeric::Infinity(PgNumericSign::Negative)
+                .to_f64()
+                .unwrap(),
+            f64::NEG_INFINITY
+        );
+
+        // 1e400
+        assert!(number(PgNumericSign::Positive, 100, 0, &[1])
+            .to_f64()
+            .is_err());
+    }
+
+    #[test]
+    fn it_converts_integers_to_i64() {
+        let positive = PgNumericSign::Positive;
+        let negative = PgNumericSign::Negative;
+
+        assert_eq!(number(positive, 0, 0, &[]).to_i64().unwrap(), 0);
+        assert_eq!(number(positive, 0, 0, &[42]).to_i64().unwrap(), 42);
+        assert_eq!(number(negative, 0, 0, &[42]).to_i64().unwrap(), -42);
+
+        // trailing zero digits are not sent
+        assert_eq!(number(positive, 2, 0, &[5]).to_i64().unwrap(), 5_0000_0000);
+
+        assert_eq!(
+            number(positive, 4, 0, &[922, 3372, 368, 5477, 5807])
+                .to_i64()
+                .unwrap(),
+            i64::MAX
+        );
+
+        assert_eq!(
+            number(negative, 4, 0, &[922, 3372, 368, 5477, 5808])
+                .to_i64()
+                .unwrap(),
+            i64::MIN
+        );
+    }
+
+    #[test]
+    fn it_converts_u64_both_ways() {
+        let positive = PgNumericSign::Positive;
+
+        assert_eq!(PgNumeric::from(0), number(positive, 0, 0, &[]));
+        assert_eq!(PgNumeric::from(42), number(positive, 0, 0, &[42]));
+        assert_eq!(PgNumeric::from(5_0000_0000), number(positive, 2, 0, &[5]));
+        assert_eq!(
+            PgNumeric::from(u64::MAX),
+            number(positive, 4, 0, &[1844, 6744, 737, 955, 1615])
+        );
+
+        for &int in &[
+            0,
+            1,
+            9_999,
+            10_000,
+            i64::MAX as u64,
+            i64::MAX as u64 + 1,
+            u64::MAX,
+        ] {
+            assert_eq!(PgNumeric::from(int).to_u64().unwrap(), int);
+        }
+
+        // u64::MAX + 1
+        assert!(number(positive, 4, 0, &[1844, 6744, 737, 955, 1616])
+            .to_u64()
+            .is_err());
+
+        assert!(number(PgNumericSign::Negative, 0, 0, &[1])
+            .to_u64()
+            .is_err());
+        assert!(number(positive, 0, 2, &[1]).to_u64().is_err());
+        assert!(PgNumeric::NotANumber.to_u64().is_err());
+    }
+
+    #[test]
+    fn it_refuses_to_convert_non_integers_to_i64() {
+        let positive = PgNumericSign::Positive;
+
+        // i64::MAX + 1
+        assert!(number(positive, 4, 0, &[922, 3372, 368, 5477, 5808])
+            .to_i64()
+            .is_err());
+
+        // 1e40
+        assert!(number(positive, 10, 0, &[1]).to_i64().is_err());
+
+        // 1.00 and 1.50 have a scale, even when the fraction is zero
+        assert!(number(positive, 0, 2, &[1]).to_i64().is_err());
+        assert!(number(positive, 0, 2, &[1, 5000]).to_i64().is_err());
+
+        assert!(PgNumeric::NotANumber.to_i64().is_err());
+        assert!(PgNumeric::Infinity(PgNumericSign::Positive)
+            .to_i64()
+            .is_err());
+    }
+
+    #[test]
+    fn it_rejects_truncated_numerics() {
+        assert!(PgNumeric::decode(&[0, 1, 0, 0]).is_err());
+
+        // one digit, but no bytes for it
+        assert!(PgNumeric::decode(&[0, 1, 0, 0, 0, 0, 0, 0]).is_err());
+
+        assert_eq!(
+            PgNumeric::decode(&[0, 0, 0, 0, 0xD0, 0, 0, 0]).unwrap(),
+            PgNumeric::Infinity(PgNumericSign::Positive)
+        );
     }
 }
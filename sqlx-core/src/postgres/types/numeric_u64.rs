@@ -0,0 +1,81 @@
+use crate::decode::Decode;
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::error::BoxDynError;
+use crate::postgres::types::numeric::PgNumeric;
+use crate::postgres::{
+    PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres,
+};
+use crate::types::Type;
+
+/// A `u64` sent to and read from Postgres as a `NUMERIC`, so that values above `i64::MAX`
+/// can be stored.
+///
+/// A plain `u64` is sent as an `INT8`, and encoding one that does not fit is an error. Bind a
+/// `PgNumericU64` instead for a column of type `NUMERIC(20)` or wider:
+///
+/// ```rust,ignore
+/// sqlx::query("INSERT INTO counters (id, hits) VALUES ($1, $2)")
+///     .bind(id)
+///     .bind(PgNumericU64(u64::MAX))
+///     .execute(&mut conn)
+///     .await?;
+/// ```
+///
+/// It can be decoded from a `NUMERIC` with a scale of 0, or from any integer column. A negative
+/// value, or one with digits after the decimal point, is a decode error.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub struct PgNumericU64(pub u64);
+
+impl From<u64> for PgNumericU64 {
+    fn from(value: u64) -> Self {
+        PgNumericU64(value)
+    }
+}
+
+impl From<PgNumericU64> for u64 {
+    fn from(value: PgNumericU64) -> Self {
+        value.0
+    }
+}
+
+impl Type<Postgres> for PgNumericU64 {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::NUMERIC
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        *ty == PgTypeInfo::NUMERIC || <u64 as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for PgNumericU64 {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::NUMERIC_ARRAY
+    }
+}
+
+impl Encode<'_, Postgres> for PgNumericU64 {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        PgNumeric::from(self.0).encode(buf)?;
+
+        Ok(IsNull::No)
+    }
+}
+
+impl Decode<'_, Postgres> for PgNumericU64 {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        if value.type_info != PgTypeInfo::NUMERIC {
+            return <u64 as Decode<Postgres>>::decode(value).map(PgNumericU64);
+        }
+
+        let int = match value.format() {
+            PgValueFormat::Binary => PgNumeric::decode(value.as_bytes()?)?.to_u64()?,
+
+            // the text of a NUMERIC with a scale of 0 has no decimal point, so anything else
+            // fails to parse
+            PgValueFormat::Text => value.as_str()?.parse()?,
+        };
+
+        Ok(PgNumericU64(int))
+    }
+}
@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::ops::{Bound, Range, RangeBounds, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive};
 
@@ -5,7 +6,7 @@ use bitflags::bitflags;
 use bytes::Buf;
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::postgres::type_info::PgTypeKind;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
@@ -25,74 +26,130 @@ bitflags! {
   }
 }
 
+/// A PostgreSQL range, such as an `INT4RANGE` or a `TSTZRANGE`.
+///
+/// PostgreSQL stores discrete ranges in canonical form, with an inclusive start and an exclusive
+/// end, so `[1,2]` is read back as `[1,3)`.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct PgRange<T> {
     pub start: Bound<T>,
     pub end: Bound<T>,
+
+    /// Whether this is the empty range, which contains no values. The bounds of an empty range
+    /// are not used, and are `Unbounded` when it is decoded or created with
+    /// [`empty`](Self::empty).
+    pub empty: bool,
+}
+
+impl<T> PgRange<T> {
+    /// Creates a range from its bounds.
+    pub fn new(start: Bound<T>, end: Bound<T>) -> Self {
+        Self {
+            start,
+            end,
+            empty: false,
+        }
+    }
+
+    /// Creates the empty range.
+    pub fn empty() -> Self {
+        Self {
+            start: Bound::Unbounded,
+            end: Bound::Unbounded,
+            empty: true,
+        }
+    }
+
+    /// Returns `true` if this is the empty range.
+    ///
+    /// A range whose bounds contain no values, such as `[1,1)`, is only empty once PostgreSQL
+    /// has normalized it.
+    pub fn is_empty(&self) -> bool {
+        self.empty
+    }
 }
 
 impl<T> From<[Bound<T>; 2]> for PgRange<T> {
     fn from(v: [Bound<T>; 2]) -> Self {
         let [start, end] = v;
-        Self { start, end }
+        Self::new(start, end)
     }
 }
 
 impl<T> From<(Bound<T>, Bound<T>)> for PgRange<T> {
     fn from(v: (Bound<T>, Bound<T>)) -> Self {
-        Self {
-            start: v.0,
-            end: v.1,
-        }
+        Self::new(v.0, v.1)
     }
 }
 
 impl<T> From<Range<T>> for PgRange<T> {
     fn from(v: Range<T>) -> Self {
-        Self {
-            start: Bound::Included(v.start),
-            end: Bound::Excluded(v.end),
-        }
+        Self::new(Bound::Included(v.start), Bound::Excluded(v.end))
     }
 }
 
 impl<T> From<RangeFrom<T>> for PgRange<T> {
     fn from(v: RangeFrom<T>) -> Self {
-        Self {
-            start: Bound::Included(v.start),
-            end: Bound::Unbounded,
-        }
+        Self::new(Bound::Included(v.start), Bound::Unbounded)
     }
 }
 
 impl<T> From<RangeInclusive<T>> for PgRange<T> {
     fn from(v: RangeInclusive<T>) -> Self {
         let (start, end) = v.into_inner();
-        Self {
-            start: Bound::Included(start),
-            end: Bound::Included(end),
-        }
+        Self::new(Bound::Included(start), Bound::Included(end))
     }
 }
 
 impl<T> From<RangeTo<T>> for PgRange<T> {
     fn from(v: RangeTo<T>) -> Self {
-        Self {
-            start: Bound::Unbounded,
-            end: Bound::Excluded(v.end),
-        }
+        Self::new(Bound::Unbounded, Bound::Excluded(v.end))
     }
 }
 
 impl<T> From<RangeToInclusive<T>> for PgRange<T> {
     fn from(v: RangeToInclusive<T>) -> Self {
-        Self {
-            start: Bound::Unbounded,
-            end: Bound::Included(v.end),
+        Self::new(Bound::Unbounded, Bound::Included(v.end))
+    }
+}
+
+/// Converts a range with an inclusive start and an exclusive end, the form PostgreSQL stores
+/// discrete ranges in, returning the range back if it has other bounds or is empty.
+impl<T> TryFrom<PgRange<T>> for Range<T> {
+    type Error = PgRange<T>;
+
+    fn try_from(range: PgRange<T>) -> Result<Self, Self::Error> {
+        match range {
+            PgRange {
+                start: Bound::Included(start),
+                end: Bound::Excluded(end),
+                empty: false,
+            } => Ok(start..end),
+
+            range => Err(range),
         }
     }
 }
 
+/// Converts a range with inclusive bounds, returning the range back if it has other bounds or
+/// is empty.
+impl<T> TryFrom<PgRange<T>> for RangeInclusive<T> {
+    type Error = PgRange<T>;
+
+    fn try_from(range: PgRange<T>) -> Result<Self, Self::Error> {
+        match range {
+            PgRange {
+                start: Bound::Included(start),
+                end: Bound::Included(end),
+                empty: false,
+            } => Ok(start..=end),
+
+            range => Err(range),
+        }
+    }
+}
+
+// the bounds of an empty range are `Unbounded`; check `is_empty` first
 impl<T> RangeBounds<T> for PgRange<T> {
     fn start_bound(&self) -> Bound<&T> {
         match self.start {
@@ -142,6 +199,17 @@ impl Type<Postgres> for PgRange<bigdecimal::BigDecimal> {
     }
 }
 
+#[cfg(feature = "decimal")]
+impl Type<Postgres> for PgRange<rust_decimal::Decimal> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::NUM_RANGE
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        range_compatible::<rust_decimal::Decimal>(ty)
+    }
+}
+
 #[cfg(feature = "chrono")]
 impl Type<Postgres> for PgRange<chrono::NaiveDate> {
     fn type_info() -> PgTypeInfo {
@@ -227,6 +295,13 @@ impl Type<Postgres> for [PgRange<bigdecimal::BigDecimal>] {
     }
 }
 
+#[cfg(feature = "decimal")]
+impl Type<Postgres> for [PgRange<rust_decimal::Decimal>] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::NUM_RANGE_ARRAY
+    }
+}
+
 #[cfg(feature = "chrono")]
 impl Type<Postgres> for [PgRange<chrono::NaiveDate>] {
     fn type_info() -> PgTypeInfo {
@@ -288,6 +363,13 @@ impl Type<Postgres> for Vec<PgRange<bigdecimal::BigDecimal>> {
     }
 }
 
+#[cfg(feature = "decimal")]
+impl Type<Postgres> for Vec<PgRange<rust_decimal::Decimal>> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::NUM_RANGE_ARRAY
+    }
+}
+
 #[cfg(feature = "chrono")]
 impl Type<Postgres> for Vec<PgRange<chrono::NaiveDate>> {
     fn type_info() -> PgTypeInfo {
@@ -334,9 +416,15 @@ impl<'q, T> Encode<'q, Postgres> for PgRange<T>
 where
     T: Encode<'q, Postgres>,
 {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         // https://github.com/postgres/postgres/blob/2f48ede080f42b97b594fb14102c82ca1001b80c/src/backend/utils/adt/rangetypes.c#L245
 
+        if self.empty {
+            buf.push(RangeFlags::EMPTY.bits());
+
+            return Ok(IsNull::No);
+        }
+
         let mut flags = RangeFlags::empty();
 
         flags |= match self.start {
@@ -354,15 +442,15 @@ where
         buf.push(flags.bits());
 
         if let Bound::Included(v) | Bound::Excluded(v) = &self.start {
-            buf.encode(v);
+            buf.encode(v)?;
         }
 
         if let Bound::Included(v) | Bound::Excluded(v) = &self.end {
-            buf.encode(v);
+            buf.encode(v)?;
         }
 
         // ranges are themselves never null
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
@@ -387,7 +475,7 @@ where
                 let flags = RangeFlags::from_bits_truncate(buf.get_u8());
 
                 if flags.contains(RangeFlags::EMPTY) {
-                    return Ok(PgRange { start, end });
+                    return Ok(PgRange::empty());
                 }
 
                 if !flags.contains(RangeFlags::LB_INF) {
@@ -412,7 +500,7 @@ where
                     };
                 }
 
-                Ok(PgRange { start, end })
+                Ok(PgRange::new(start, end))
             }
 
             PgValueFormat::Text => {
@@ -423,6 +511,10 @@ where
 
                 let s = value.as_str()?;
 
+                if s == "empty" {
+                    return Ok(PgRange::empty());
+                }
+
                 // remember the bounds
                 let sb = s.as_bytes();
                 let lower = sb[0] as char;
@@ -435,15 +527,17 @@ where
 
                 let mut element = String::new();
                 let mut done = false;
-                let mut quoted = false;
                 let mut in_quotes = false;
                 let mut in_escape = false;
-                let mut prev_ch = '\0';
                 let mut count = 0;
 
                 while !done {
                     element.clear();
 
+                    // both are tracked per element; `""` inside quotes is an escaped quote
+                    let mut quoted = false;
+                    let mut prev_ch = '\0';
+
                     loop {
                         match chars.next() {
                             Some(ch) => {
@@ -508,7 +602,7 @@ where
                 let start = parse_bound(lower, start)?;
                 let end = parse_bound(upper, end)?;
 
-                Ok(PgRange { start, end })
+                Ok(PgRange::new(start, end))
             }
         }
     }
@@ -538,6 +632,10 @@ where
     T: Display,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.empty {
+            return f.write_str("empty");
+        }
+
         match &self.start {
             Bound::Unbounded => f.write_str("(,")?,
             Bound::Excluded(v) => write!(f, "({},", v)?,
@@ -563,3 +661,25 @@ fn range_compatible<E: Type<Postgres>>(ty: &PgTypeInfo) -> bool {
 
     false
 }
+
+#[test]
+fn it_converts_to_std_ranges() {
+    assert_eq!(Range::try_from(PgRange::from(1..5)), Ok(1..5));
+    assert_eq!(RangeInclusive::try_from(PgRange::from(1..=5)), Ok(1..=5));
+
+    assert_eq!(
+        Range::try_from(PgRange::from(1..=5)),
+        Err(PgRange::from(1..=5))
+    );
+    assert_eq!(
+        RangeInclusive::try_from(PgRange::from(..5)),
+        Err(PgRange::from(..5))
+    );
+    assert_eq!(
+        Range::<i32>::try_from(PgRange::empty()),
+        Err(PgRange::empty())
+    );
+
+    assert_eq!(PgRange::from(1..5).to_string(), "[1,5)");
+    assert_eq!(PgRange::<i32>::empty().to_string(), "empty");
+}
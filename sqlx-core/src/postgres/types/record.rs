@@ -1,7 +1,7 @@
 use bytes::Buf;
 
 use crate::decode::Decode;
-use crate::encode::Encode;
+use crate::encode::{Encode, EncodeError};
 use crate::error::{mismatched_types, BoxDynError};
 use crate::postgres::type_info::{PgType, PgTypeKind};
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
@@ -33,7 +33,7 @@ impl<'a> PgRecordEncoder<'a> {
     }
 
     #[doc(hidden)]
-    pub fn encode<'q, T>(&mut self, value: T) -> &mut Self
+    pub fn encode<'q, T>(&mut self, value: T) -> Result<&mut Self, EncodeError>
     where
         'a: 'q,
         T: Encode<'q, Postgres> + Type<Postgres>,
@@ -49,10 +49,10 @@ impl<'a> PgRecordEncoder<'a> {
             self.buf.extend(&ty.0.oid().to_be_bytes());
         }
 
-        self.buf.encode(value);
+        self.buf.encode(value)?;
         self.num += 1;
 
-        self
+        Ok(self)
     }
 }
 
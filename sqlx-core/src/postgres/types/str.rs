@@ -1,5 +1,5 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::postgres::types::array_compatible;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef, Postgres};
@@ -43,15 +43,20 @@ impl Type<Postgres> for Vec<&'_ str> {
 }
 
 impl Encode<'_, Postgres> for &'_ str {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        // PostgreSQL does not allow NUL in text values
+        if self.contains('\0') {
+            return Err("string contains a NUL character, which PostgreSQL does not allow".into());
+        }
+
         buf.extend(self.as_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl Encode<'_, Postgres> for String {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         <&str as Encode<Postgres>>::encode(&**self, buf)
     }
 }
@@ -0,0 +1,200 @@
+use crate::decode::Decode;
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::error::BoxDynError;
+use crate::postgres::types::{date_style_error, split_utc_offset, PgHasArrayType};
+use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use crate::types::unix_time::{try_system_time, unix_micros, DateTime, TimestampOutOfRange};
+use crate::types::{Type, UnixMillis, UnixTimestamp};
+use std::mem;
+use std::time::SystemTime;
+
+// TIMESTAMP and TIMESTAMPTZ are the microseconds since 2000-01-01 00:00:00 UTC
+const POSTGRES_EPOCH: i128 = 946_684_800_000_000;
+
+// the range Postgres accepts, from 4714-11-24 BC up to 294277-01-01; the least and greatest
+// `i64` beyond it stand for `-infinity` and `infinity`
+const MIN_TIMESTAMP: i128 = -211_813_488_000_000_000;
+const END_TIMESTAMP: i128 = 9_223_371_331_200_000_000;
+
+fn is_timestamp(ty: &PgTypeInfo) -> bool {
+    *ty == PgTypeInfo::TIMESTAMP || *ty == PgTypeInfo::TIMESTAMPTZ
+}
+
+fn is_int(ty: &PgTypeInfo) -> bool {
+    *ty == PgTypeInfo::INT2 || *ty == PgTypeInfo::INT4 || *ty == PgTypeInfo::INT8
+}
+
+// the TIMESTAMPTZ of the instant `micros` from the unix epoch
+fn to_timestamp(micros: i128) -> Result<i64, TimestampOutOfRange> {
+    let timestamp = micros - POSTGRES_EPOCH;
+
+    if !(MIN_TIMESTAMP..END_TIMESTAMP).contains(&timestamp) {
+        return Err(TimestampOutOfRange::new(
+            DateTime::from_unix_micros(micros),
+            "TIMESTAMPTZ",
+        ));
+    }
+
+    Ok(timestamp as i64)
+}
+
+// the microseconds from the unix epoch of a TIMESTAMP or TIMESTAMPTZ, where a TIMESTAMP is
+// taken to be in UTC; `target` is the Rust type, named if the value is infinite
+fn decode_timestamp(value: PgValueRef<'_>, target: &'static str) -> Result<i128, BoxDynError> {
+    match value.format() {
+        PgValueFormat::Binary => {
+            let timestamp: i64 = Decode::<Postgres>::decode(value)?;
+
+            match timestamp {
+                i64::MAX => Err(TimestampOutOfRange::new("infinity", target).into()),
+                i64::MIN => Err(TimestampOutOfRange::new("-infinity", target).into()),
+                _ => Ok(i128::from(timestamp) + POSTGRES_EPOCH),
+            }
+        }
+
+        PgValueFormat::Text => {
+            let s = value.as_str()?;
+
+            if s.ends_with("infinity") {
+                return Err(TimestampOutOfRange::new(s, target).into());
+            }
+
+            // e.g. `0044-03-15 12:00:00+00 BC`
+            let (timestamp, bc) = match s.strip_suffix(" BC") {
+                Some(timestamp) => (timestamp, true),
+                None => (s, false),
+            };
+
+            // a TIMESTAMPTZ is printed in the session time zone, with its offset from UTC
+            let (timestamp, offset) = split_utc_offset(timestamp)?;
+            let mut datetime =
+                DateTime::parse(timestamp).map_err(|error| date_style_error(s, error))?;
+
+            if bc {
+                datetime.year = 1 - datetime.year;
+            }
+
+            Ok(datetime.unix_micros() - i128::from(offset) * 1_000_000)
+        }
+    }
+}
+
+impl Type<Postgres> for SystemTime {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::TIMESTAMPTZ
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        is_timestamp(ty)
+    }
+}
+
+impl PgHasArrayType for SystemTime {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::TIMESTAMPTZ_ARRAY
+    }
+}
+
+impl Encode<'_, Postgres> for SystemTime {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        // sub-microsecond precision is rounded down, also before the unix epoch
+        Encode::<Postgres>::encode(to_timestamp(unix_micros(*self))?, buf)
+    }
+
+    fn size_hint(&self) -> usize {
+        mem::size_of::<i64>()
+    }
+}
+
+impl Decode<'_, Postgres> for SystemTime {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        Ok(try_system_time(decode_timestamp(value, "SystemTime")?)?)
+    }
+}
+
+// `UnixTimestamp` and `UnixMillis` are sent without a type, as Postgres infers it from where the
+// parameter is used; the integer written for them is replaced by a TIMESTAMPTZ when it is
+// inferred to be one
+macro_rules! impl_unix_time {
+    ($ty:ident, $per_second:expr) => {
+        impl Type<Postgres> for $ty {
+            fn type_info() -> PgTypeInfo {
+                PgTypeInfo::with_oid(0)
+            }
+
+            fn compatible(ty: &PgTypeInfo) -> bool {
+                is_int(ty) || is_timestamp(ty)
+            }
+        }
+
+        impl Encode<'_, Postgres> for $ty {
+            fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+                if let Ok(timestamp) = to_timestamp(self.unix_micros()) {
+                    buf.patch(move |buf, ty| {
+                        if is_timestamp(ty) {
+                            buf[..8].copy_from_slice(&timestamp.to_be_bytes());
+                        }
+                    });
+                }
+
+                Encode::<Postgres>::encode(self.0, buf)
+            }
+
+            fn produces(&self) -> Option<PgTypeInfo> {
+                // beyond the range of a TIMESTAMPTZ, it can only be an integer
+                to_timestamp(self.unix_micros())
+                    .err()
+                    .map(|_| PgTypeInfo::INT8)
+            }
+
+            fn size_hint(&self) -> usize {
+                mem::size_of::<i64>()
+            }
+        }
+
+        impl Decode<'_, Postgres> for $ty {
+            fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+                if is_timestamp(&value.type_info) {
+                    let micros = decode_timestamp(value, stringify!($ty))?;
+
+                    // rounded down towards the past, like the conversion from `SystemTime`
+                    return Ok($ty(micros.div_euclid(1_000_000 / $per_second) as i64));
+                }
+
+                Decode::<Postgres>::decode(value).map($ty)
+            }
+        }
+    };
+}
+
+impl_unix_time!(UnixTimestamp, 1);
+impl_unix_time!(UnixMillis, 1_000);
+
+#[test]
+fn it_checks_the_range_of_timestamps() {
+    let micros = |s: &str| DateTime::parse(s).unwrap().unix_micros();
+
+    assert_eq!(to_timestamp(micros("2000-01-01 00:00:00")), Ok(0));
+    assert_eq!(
+        to_timestamp(micros("1969-12-31 23:59:59")),
+        Ok(-946_684_801_000_000)
+    );
+    assert_eq!(
+        to_timestamp(micros("294276-12-31 23:59:59.999999")),
+        Ok(END_TIMESTAMP as i64 - 1)
+    );
+
+    let error = to_timestamp(micros("294277-01-01 00:00:00")).unwrap_err();
+    assert_eq!(error.target(), "TIMESTAMPTZ");
+    assert_eq!(
+        error.to_string(),
+        "294277-01-01 00:00:00 UTC is out of range for TIMESTAMPTZ"
+    );
+
+    // 4714-11-24 BC is the year -4713
+    assert_eq!(
+        to_timestamp(micros("-4713-11-24 00:00:00")),
+        Ok(MIN_TIMESTAMP as i64)
+    );
+    assert!(to_timestamp(micros("-4713-11-23 23:59:59")).is_err());
+}
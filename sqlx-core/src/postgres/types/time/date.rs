@@ -1,6 +1,7 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
+use crate::postgres::types::date_style_error;
 use crate::postgres::types::time::PG_EPOCH;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
@@ -26,7 +27,7 @@ impl Type<Postgres> for Vec<Date> {
 }
 
 impl Encode<'_, Postgres> for Date {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         // DATE is encoded as the days since epoch
         let days = (*self - PG_EPOCH).whole_days() as i32;
         Encode::<Postgres>::encode(&days, buf)
@@ -46,7 +47,10 @@ impl<'r> Decode<'r, Postgres> for Date {
                 PG_EPOCH + Duration::days(days.into())
             }
 
-            PgValueFormat::Text => Date::parse(value.as_str()?, "%Y-%m-%d")?,
+            PgValueFormat::Text => {
+                let s = value.as_str()?;
+                Date::parse(s, "%Y-%m-%d").map_err(|error| date_style_error(s, error))?
+            }
         })
     }
 }
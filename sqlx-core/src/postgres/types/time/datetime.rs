@@ -1,7 +1,8 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::postgres::types::time::PG_EPOCH;
+use crate::postgres::types::{date_style_error, split_utc_offset};
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
 use std::borrow::Cow;
@@ -45,7 +46,7 @@ impl Type<Postgres> for Vec<OffsetDateTime> {
 }
 
 impl Encode<'_, Postgres> for PrimitiveDateTime {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         // TIMESTAMP is encoded as the microseconds since the epoch
         let us = (*self - PG_EPOCH.midnight()).whole_microseconds() as i64;
         Encode::<Postgres>::encode(&us, buf)
@@ -72,19 +73,11 @@ impl<'r> Decode<'r, Postgres> for PrimitiveDateTime {
                 // TODO: De-duplicate with MySQL
                 // TODO: Ask [time] to add a parse % for less-than-fixed-9 nanos
 
-                let s = value.as_str()?;
+                // `timestamptz` is printed in the session time zone, with its offset from UTC
+                let original = value.as_str()?;
+                let (s, offset) = split_utc_offset(original)?;
 
-                let s = if let Some(plus) = s.rfind('+') {
-                    let mut big = String::from(&s[..plus]);
-
-                    while big.len() < 31 {
-                        big.push('0');
-                    }
-
-                    big.push_str(&s[plus..]);
-
-                    Cow::Owned(big)
-                } else if s.len() < 31 {
+                let s = if s.len() < 31 {
                     if s.contains('.') {
                         Cow::Owned(format!("{:0<30}", s))
                     } else {
@@ -94,14 +87,16 @@ impl<'r> Decode<'r, Postgres> for PrimitiveDateTime {
                     Cow::Borrowed(s)
                 };
 
-                PrimitiveDateTime::parse(&*s, "%Y-%m-%d %H:%M:%S.%N")?
+                PrimitiveDateTime::parse(&*s, "%Y-%m-%d %H:%M:%S.%N")
+                    .map_err(|error| date_style_error(original, error))?
+                    - Duration::seconds(offset.into())
             }
         })
     }
 }
 
 impl Encode<'_, Postgres> for OffsetDateTime {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         let utc = self.to_offset(offset!(UTC));
         let primitive = PrimitiveDateTime::new(utc.date(), utc.time());
 
@@ -2,5 +2,7 @@ mod date;
 mod datetime;
 mod time;
 
+pub(super) use self::time::parse_time;
+
 #[rustfmt::skip]
 const PG_EPOCH: ::time::Date = ::time::date!(2000-1-1);
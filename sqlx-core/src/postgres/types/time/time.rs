@@ -1,5 +1,5 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
@@ -26,7 +26,7 @@ impl Type<Postgres> for Vec<Time> {
 }
 
 impl Encode<'_, Postgres> for Time {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         // TIME is encoded as the microseconds since midnight
         let us = (*self - Time::midnight()).whole_microseconds() as i64;
         Encode::<Postgres>::encode(&us, buf)
@@ -46,22 +46,23 @@ impl<'r> Decode<'r, Postgres> for Time {
                 Time::midnight() + Duration::microseconds(us)
             }
 
-            PgValueFormat::Text => {
-                // If there are less than 9 digits after the decimal point
-                // We need to zero-pad
+            PgValueFormat::Text => parse_time(value.as_str()?)?,
+        })
+    }
+}
 
-                // FIXME: Ask [time] to add a parse % for less-than-fixed-9 nanos
+// a `TIME` in the text format, e.g. `13:14:15` or `13:14:15.25`
+pub(crate) fn parse_time(s: &str) -> Result<Time, BoxDynError> {
+    // If there are less than 9 digits after the decimal point
+    // We need to zero-pad
 
-                let s = value.as_str()?;
+    // FIXME: Ask [time] to add a parse % for less-than-fixed-9 nanos
 
-                let s = if s.len() < 20 {
-                    Cow::Owned(format!("{:0<19}", s))
-                } else {
-                    Cow::Borrowed(s)
-                };
+    let s = match s.find('.') {
+        Some(dot) if s.len() < dot + 10 => Cow::Owned(format!("{:0<1$}", s, dot + 10)),
+        Some(_) => Cow::Borrowed(s),
+        None => Cow::Owned(format!("{}.000000000", s)),
+    };
 
-                Time::parse(&*s, "%H:%M:%S.%N")?
-            }
-        })
-    }
+    Ok(Time::parse(&*s, "%H:%M:%S.%N")?)
 }
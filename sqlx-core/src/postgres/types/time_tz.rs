@@ -1,5 +1,5 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
@@ -63,11 +63,11 @@ mod chrono {
     }
 
     impl Encode<'_, Postgres> for PgTimeTz<NaiveTime, FixedOffset> {
-        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
             let _ = <NaiveTime as Encode<'_, Postgres>>::encode(self.time, buf);
             let _ = <i32 as Encode<'_, Postgres>>::encode(self.offset.utc_minus_local(), buf);
 
-            IsNull::No
+            Ok(IsNull::No)
         }
 
         fn size_hint(&self) -> usize {
@@ -132,6 +132,8 @@ mod chrono {
 #[cfg(feature = "time")]
 mod time {
     use super::*;
+    use crate::postgres::types::split_utc_offset;
+    use crate::postgres::types::time::parse_time;
     use ::time::{Duration, Time, UtcOffset};
 
     impl Type<Postgres> for PgTimeTz<Time, UtcOffset> {
@@ -141,11 +143,11 @@ mod time {
     }
 
     impl Encode<'_, Postgres> for PgTimeTz<Time, UtcOffset> {
-        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
             let _ = <Time as Encode<'_, Postgres>>::encode(self.time, buf);
             let _ = <i32 as Encode<'_, Postgres>>::encode(-self.offset.as_seconds(), buf);
 
-            IsNull::No
+            Ok(IsNull::No)
         }
 
         fn size_hint(&self) -> usize {
@@ -173,9 +175,13 @@ mod time {
                 }
 
                 PgValueFormat::Text => {
-                    // the `time` crate has a limited ability to parse and can't parse the
-                    // timezone format
-                    Err("reading a `TIMETZ` value in text format is not supported.".into())
+                    // e.g. `13:14:15.25+02`; the `time` crate cannot parse the offset itself
+                    let (time, seconds) = split_utc_offset(value.as_str()?)?;
+
+                    Ok(PgTimeTz {
+                        time: parse_time(time)?,
+                        offset: UtcOffset::seconds(seconds),
+                    })
                 }
             }
         }
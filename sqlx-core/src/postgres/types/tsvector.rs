@@ -0,0 +1,355 @@
+use std::cmp;
+use std::fmt::{self, Display, Formatter, Write as _};
+use std::iter::Peekable;
+use std::mem;
+use std::str::{self, Chars, FromStr};
+
+use bytes::Buf;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::error::{BoxDynError, Error};
+use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use crate::types::Type;
+
+/// A full text search document, stored in the PostgreSQL [`TSVECTOR`] type.
+///
+/// The value is kept as its text, e.g. `'a':1,3A 'fat':2B`: a list of lexemes, each followed by
+/// the positions at which it occurs, with an optional weight of `A`, `B`, `C` or `D`. A value
+/// read from the database is in the form Postgres prints it in, with every lexeme quoted and the
+/// lexemes sorted.
+///
+/// [`FromStr`] checks the syntax of the text, as Postgres would; a value created directly is
+/// checked when it is encoded. As when Postgres reads a `TSVECTOR`, repeated lexemes and
+/// positions are merged, and positions past 16383 are limited to 16383.
+///
+/// The text is not normalized into lexemes; use `to_tsvector` in a query to turn a document into
+/// a `TSVECTOR`.
+///
+/// [`TSVECTOR`]: https://www.postgresql.org/docs/current/datatype-textsearch.html
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PgTsVector(pub String);
+
+// the greatest position, in the lower 14 bits of an entry, and the number of positions a lexeme
+// can have; both are limits of the on-disk format
+const MAX_POSITION: u16 = (1 << 14) - 1;
+const MAX_POSITIONS: usize = 256;
+
+// a lexeme and its positions, with the weight in the upper two bits of each: 3 for `A` down to
+// 0 for `D`
+#[derive(Debug, PartialEq)]
+struct Lexeme {
+    text: String,
+    positions: Vec<u16>,
+}
+
+impl PgTsVector {
+    /// The text of the value.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for PgTsVector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for PgTsVector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        parse(s).map_err(|e| err_protocol!("{}: {:?}", e, s))?;
+
+        Ok(PgTsVector(s.to_owned()))
+    }
+}
+
+// the lexemes of `s`, sorted and merged as Postgres stores them
+fn parse(s: &str) -> Result<Vec<Lexeme>, &'static str> {
+    let mut chars = s.chars().peekable();
+    let mut lexemes = Vec::new();
+
+    loop {
+        while matches!(chars.peek(), Some(c) if c.is_whitespace()) {
+            chars.next();
+        }
+
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let text = parse_lexeme(&mut chars)?;
+
+        let positions = if chars.peek() == Some(&':') {
+            chars.next();
+            parse_positions(&mut chars)?
+        } else {
+            Vec::new()
+        };
+
+        match chars.peek() {
+            Some(c) if !c.is_whitespace() => return Err("syntax error in TSVECTOR"),
+            _ => {}
+        }
+
+        lexemes.push(Lexeme { text, positions });
+    }
+
+    lexemes.sort_by(|a, b| a.text.as_bytes().cmp(b.text.as_bytes()));
+
+    let mut merged: Vec<Lexeme> = Vec::with_capacity(lexemes.len());
+
+    for lexeme in lexemes {
+        match merged.last_mut() {
+            Some(last) if last.text == lexeme.text => last.positions.extend(lexeme.positions),
+            _ => merged.push(lexeme),
+        }
+    }
+
+    for lexeme in &mut merged {
+        lexeme.positions = merge_positions(mem::take(&mut lexeme.positions));
+    }
+
+    Ok(merged)
+}
+
+fn parse_lexeme(chars: &mut Peekable<Chars<'_>>) -> Result<String, &'static str> {
+    let mut text = String::new();
+
+    if chars.peek() == Some(&'\'') {
+        chars.next();
+
+        loop {
+            match chars.next() {
+                Some('\\') => text.push(chars.next().ok_or("unterminated lexeme in TSVECTOR")?),
+
+                Some('\'') if chars.peek() == Some(&'\'') => {
+                    chars.next();
+                    text.push('\'');
+                }
+
+                Some('\'') => break,
+                Some(c) => text.push(c),
+                None => return Err("unterminated lexeme in TSVECTOR"),
+            }
+        }
+
+        if text.is_empty() {
+            return Err("empty lexeme in TSVECTOR");
+        }
+    } else {
+        while let Some(&c) = chars.peek() {
+            // a colon begins the positions, unless it begins the lexeme
+            if c.is_whitespace() || (c == ':' && !text.is_empty()) {
+                break;
+            }
+
+            chars.next();
+
+            if c == '\\' {
+                text.push(chars.next().ok_or("unterminated lexeme in TSVECTOR")?);
+            } else {
+                text.push(c);
+            }
+        }
+    }
+
+    Ok(text)
+}
+
+fn parse_positions(chars: &mut Peekable<Chars<'_>>) -> Result<Vec<u16>, &'static str> {
+    let mut positions = Vec::new();
+
+    loop {
+        let mut position: u32 = 0;
+        let mut digits = 0;
+
+        while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+            chars.next();
+
+            position = cmp::min(position * 10 + digit, u32::from(MAX_POSITION));
+            digits += 1;
+        }
+
+        if digits == 0 {
+            return Err("syntax error in TSVECTOR");
+        }
+
+        if position == 0 {
+            return Err("wrong position in TSVECTOR");
+        }
+
+        let weight = match chars.peek() {
+            Some('A') | Some('a') => Some(3),
+            Some('B') | Some('b') => Some(2),
+            Some('C') | Some('c') => Some(1),
+            Some('D') | Some('d') => Some(0),
+            _ => None,
+        };
+
+        if weight.is_some() {
+            chars.next();
+        }
+
+        positions.push((weight.unwrap_or(0) << 14) | position as u16);
+
+        if chars.peek() == Some(&',') {
+            chars.next();
+        } else {
+            break;
+        }
+    }
+
+    Ok(positions)
+}
+
+// sorts the positions of a lexeme, keeping the greatest weight of a repeated position
+fn merge_positions(mut positions: Vec<u16>) -> Vec<u16> {
+    positions.sort_by_key(|entry| (entry & MAX_POSITION, cmp::Reverse(entry >> 14)));
+    positions.dedup_by_key(|entry| *entry & MAX_POSITION);
+    positions.truncate(MAX_POSITIONS);
+
+    positions
+}
+
+// the text of `lexemes`, as Postgres prints it
+fn write_text(lexemes: &[Lexeme]) -> String {
+    let mut s = String::new();
+
+    for lexeme in lexemes {
+        if !s.is_empty() {
+            s.push(' ');
+        }
+
+        s.push('\'');
+
+        for c in lexeme.text.chars() {
+            match c {
+                '\'' => s.push_str("''"),
+                '\\' => s.push_str("\\\\"),
+                c => s.push(c),
+            }
+        }
+
+        s.push('\'');
+
+        for (i, entry) in lexeme.positions.iter().enumerate() {
+            s.push(if i == 0 { ':' } else { ',' });
+
+            let _ = write!(s, "{}", entry & MAX_POSITION);
+
+            match entry >> 14 {
+                3 => s.push('A'),
+                2 => s.push('B'),
+                1 => s.push('C'),
+                _ => {}
+            }
+        }
+    }
+
+    s
+}
+
+impl Type<Postgres> for PgTsVector {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::TSVECTOR
+    }
+}
+
+impl Type<Postgres> for [PgTsVector] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::TSVECTOR_ARRAY
+    }
+}
+
+impl Type<Postgres> for Vec<PgTsVector> {
+    fn type_info() -> PgTypeInfo {
+        <[PgTsVector] as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for PgTsVector {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        let lexemes = parse(&self.0).map_err(|e| format!("{}: {:?}", e, self.0))?;
+
+        buf.extend(&(lexemes.len() as i32).to_be_bytes());
+
+        for lexeme in &lexemes {
+            buf.extend(lexeme.text.as_bytes());
+            buf.push(0);
+            buf.extend(&(lexeme.positions.len() as u16).to_be_bytes());
+
+            for entry in &lexeme.positions {
+                buf.extend(&entry.to_be_bytes());
+            }
+        }
+
+        Ok(IsNull::No)
+    }
+}
+
+impl Decode<'_, Postgres> for PgTsVector {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => {
+                let mut bytes = value.as_bytes()?;
+
+                if bytes.len() < mem::size_of::<i32>() {
+                    return Err("TSVECTOR value is too short".into());
+                }
+
+                let count = bytes.get_i32();
+                let mut lexemes = Vec::new();
+
+                for _ in 0..count {
+                    let end = bytes
+                        .iter()
+                        .position(|b| *b == 0)
+                        .ok_or("unterminated lexeme in TSVECTOR value")?;
+
+                    let text = str::from_utf8(&bytes[..end])?.to_owned();
+                    bytes.advance(end + 1);
+
+                    if bytes.len() < mem::size_of::<u16>() {
+                        return Err("TSVECTOR value is too short".into());
+                    }
+
+                    let count = bytes.get_u16() as usize;
+
+                    if bytes.len() < count * mem::size_of::<u16>() {
+                        return Err("TSVECTOR value is too short".into());
+                    }
+
+                    let positions = (0..count).map(|_| bytes.get_u16()).collect();
+
+                    lexemes.push(Lexeme { text, positions });
+                }
+
+                Ok(PgTsVector(write_text(&lexemes)))
+            }
+
+            PgValueFormat::Text => Ok(PgTsVector(value.as_str()?.to_owned())),
+        }
+    }
+}
+
+#[test]
+fn it_parses_tsvector_text() {
+    let lexemes = parse(r"a:20000B,3,3A b\\c 'it''s' a:1 :1").unwrap();
+
+    assert_eq!(write_text(&lexemes), r"':1' 'a':1,3A,16383B 'b\\c' 'it''s'");
+
+    assert_eq!(parse("").unwrap(), Vec::new());
+    assert_eq!(
+        write_text(&parse(&write_text(&lexemes)).unwrap()),
+        write_text(&lexemes)
+    );
+
+    assert!(parse("a:0").is_err());
+    assert!(parse("a:1,").is_err());
+    assert!(parse("a: b").is_err());
+    assert!(parse("'' a").is_err());
+    assert!(parse("'a").is_err());
+}
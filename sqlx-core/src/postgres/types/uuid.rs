@@ -1,7 +1,7 @@
 use uuid::Uuid;
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
@@ -25,10 +25,10 @@ impl Type<Postgres> for Vec<Uuid> {
 }
 
 impl Encode<'_, Postgres> for Uuid {
-    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
         buf.extend_from_slice(self.as_bytes());
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
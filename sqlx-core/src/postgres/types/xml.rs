@@ -0,0 +1,90 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, EncodeError, IsNull};
+use crate::error::{BoxDynError, Error};
+use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef, Postgres};
+use crate::types::Type;
+
+/// An XML document or fragment, stored in the PostgreSQL [`XML`] type.
+///
+/// The value is read and written as its text, in both the text and binary formats; Postgres
+/// checks that it is well-formed when it is written. Only a NUL character, which no XML can
+/// contain, is refused by [`FromStr`].
+///
+/// [`XML`]: https://www.postgresql.org/docs/current/datatype-xml.html
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PgXml(pub String);
+
+impl PgXml {
+    /// The text of the value.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for PgXml {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for PgXml {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        if s.contains('\0') {
+            return Err(err_protocol!("XML value cannot contain NUL: {:?}", s));
+        }
+
+        Ok(PgXml(s.to_owned()))
+    }
+}
+
+impl Type<Postgres> for PgXml {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::XML
+    }
+}
+
+impl Type<Postgres> for [PgXml] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::XML_ARRAY
+    }
+}
+
+impl Type<Postgres> for Vec<PgXml> {
+    fn type_info() -> PgTypeInfo {
+        <[PgXml] as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for PgXml {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, EncodeError> {
+        buf.extend(self.0.as_bytes());
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl Decode<'_, Postgres> for PgXml {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        // the binary format is the text, in the encoding of the client
+        Ok(PgXml(value.as_str()?.to_owned()))
+    }
+}
+
+#[test]
+fn it_refuses_xml_with_nul() {
+    assert_eq!(
+        "<a>b</a>".parse::<PgXml>().unwrap(),
+        PgXml("<a>b</a>".to_owned())
+    );
+
+    assert!("<a>\0</a>".parse::<PgXml>().is_err());
+}
@@ -12,7 +12,82 @@ pub enum PgValueFormat {
     Binary = 1,
 }
 
+/// The formats to ask for the values of a query's rows in.
+///
+/// It is used by the [`result_format`](crate::query::Query::result_format) method.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PgResultFormat {
+    /// Every value in the text format, i.e. as the output function of its type writes it.
+    ///
+    /// This suits types that only have a text output function, as some extensions do.
+    Text,
+
+    /// Every value in the binary format.
+    ///
+    /// This is the default for a prepared query.
+    Binary,
+
+    /// The format of each column in turn; there must be one for every column the query
+    /// returns, or Postgres rejects the query.
+    PerColumn(Vec<PgValueFormat>),
+}
+
+impl PgResultFormat {
+    // the format codes of the `Bind` message
+    pub(crate) fn codes(&self) -> &[PgValueFormat] {
+        match self {
+            PgResultFormat::Text => &[PgValueFormat::Text],
+            PgResultFormat::Binary => &[PgValueFormat::Binary],
+            PgResultFormat::PerColumn(formats) => formats,
+        }
+    }
+}
+
+impl Default for PgResultFormat {
+    fn default() -> Self {
+        PgResultFormat::Binary
+    }
+}
+
 /// Implementation of [`ValueRef`] for PostgreSQL.
+///
+/// Along with [`type_info`](ValueRef::type_info), the wire format and raw bytes of the value are
+/// available, which is everything the built-in [`Decode`](crate::decode::Decode) impls use. The
+/// same is possible for types SQLx does not know about.
+///
+/// # Example
+///
+/// ```rust
+/// use sqlx_core::decode::Decode;
+/// use sqlx_core::error::BoxDynError;
+/// use sqlx_core::postgres::{PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+/// use sqlx_core::types::Type;
+///
+/// /// A label path from the `ltree` extension, e.g. `top.science.astronomy`.
+/// struct LabelPath(Vec<String>);
+///
+/// impl Type<Postgres> for LabelPath {
+///     fn type_info() -> PgTypeInfo {
+///         PgTypeInfo::with_name("ltree")
+///     }
+/// }
+///
+/// impl<'r> Decode<'r, Postgres> for LabelPath {
+///     fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+///         let path = match value.format() {
+///             PgValueFormat::Text => value.as_str()?,
+///
+///             // the binary format is a version number followed by the text format
+///             PgValueFormat::Binary => match value.as_bytes()? {
+///                 [1, path @ ..] => std::str::from_utf8(path)?,
+///                 _ => return Err("unsupported ltree version".into()),
+///             },
+///         };
+///
+///         Ok(LabelPath(path.split('.').map(String::from).collect()))
+///     }
+/// }
+/// ```
 #[derive(Clone)]
 pub struct PgValueRef<'r> {
     pub(crate) value: Option<&'r [u8]>,
@@ -50,18 +125,26 @@ impl<'r> PgValueRef<'r> {
         }
     }
 
-    pub(crate) fn format(&self) -> PgValueFormat {
+    /// Whether the value was sent in the text or the binary format of its type.
+    pub fn format(&self) -> PgValueFormat {
         self.format
     }
 
-    pub(crate) fn as_bytes(&self) -> Result<&'r [u8], BoxDynError> {
+    /// The raw bytes of the value, in its [`format`](Self::format).
+    ///
+    /// Returns an error if the value is `NULL`.
+    pub fn as_bytes(&self) -> Result<&'r [u8], BoxDynError> {
         match &self.value {
             Some(v) => Ok(v),
             None => Err(UnexpectedNullError.into()),
         }
     }
 
-    pub(crate) fn as_str(&self) -> Result<&'r str, BoxDynError> {
+    /// The raw bytes of the value as a string.
+    ///
+    /// Returns an error if the value is `NULL` or not valid UTF-8, which may be the
+    /// case for values in the binary format.
+    pub fn as_str(&self) -> Result<&'r str, BoxDynError> {
         Ok(from_utf8(self.as_bytes()?)?)
     }
 }
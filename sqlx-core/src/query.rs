@@ -1,14 +1,17 @@
 use std::marker::PhantomData;
+use std::time::Duration;
 
 use either::Either;
 use futures_core::stream::BoxStream;
 use futures_util::{future, StreamExt, TryFutureExt, TryStreamExt};
 
 use crate::arguments::{Arguments, IntoArguments};
+use crate::columnar::{self, Columnar};
 use crate::database::{Database, HasArguments, HasStatement, HasStatementCache};
-use crate::encode::Encode;
-use crate::error::Error;
-use crate::executor::{Execute, Executor};
+use crate::encode::{DynEncode, Encode};
+use crate::error::{catch_panic, Error};
+use crate::executor::{Execute, Executor, FetchLimits};
+use crate::in_list::InList;
 use crate::statement::Statement;
 use crate::types::Type;
 
@@ -19,6 +22,9 @@ pub struct Query<'q, DB: Database, A> {
     pub(crate) arguments: Option<A>,
     pub(crate) database: PhantomData<DB>,
     pub(crate) persistent: bool,
+    pub(crate) always_prepare: bool,
+    pub(crate) limits: FetchLimits,
+    pub(crate) cache_ttl: Option<Duration>,
 }
 
 /// SQL query that will map its results to owned Rust types.
@@ -58,13 +64,34 @@ where
 
     #[inline]
     fn take_arguments(&mut self) -> Option<<DB as HasArguments<'q>>::Arguments> {
-        self.arguments.take().map(IntoArguments::into_arguments)
+        let arguments = self.arguments.take()?.into_arguments();
+
+        // a query run once, without parameters, gains nothing from being prepared first
+        if arguments.is_empty()
+            && !self.persistent
+            && !self.always_prepare
+            && self.statement.is_left()
+        {
+            return None;
+        }
+
+        Some(arguments)
     }
 
     #[inline]
     fn persistent(&self) -> bool {
         self.persistent
     }
+
+    #[inline]
+    fn fetch_limits(&self) -> FetchLimits {
+        self.limits
+    }
+
+    #[inline]
+    fn cache_ttl(&self) -> Option<Duration> {
+        self.cache_ttl
+    }
 }
 
 impl<'q, DB: Database> Query<'q, DB, <DB as HasArguments<'q>>::Arguments> {
@@ -83,6 +110,245 @@ impl<'q, DB: Database> Query<'q, DB, <DB as HasArguments<'q>>::Arguments> {
 
         self
     }
+
+    /// Bind a value whose type is only known at runtime, e.g. one of a list of filters chosen
+    /// by a user.
+    ///
+    /// Accepts a `Box<dyn DynEncode<DB>>` of any value that could be passed to [`bind`], or a
+    /// [`DynValue`](crate::encode::DynValue) for the common scalars. The value is bound with
+    /// its own type, as [`bind`] would bind it; a value that cannot be encoded is reported as
+    /// [`Error::ArgumentEncode`] with its index when the query is executed.
+    ///
+    /// [`bind`]: Self::bind
+    pub fn bind_dyn(mut self, value: impl Into<Box<dyn DynEncode<'q, DB> + 'q>>) -> Self {
+        if let Some(arguments) = &mut self.arguments {
+            arguments.add_dyn(value.into());
+        }
+
+        self
+    }
+
+    /// Bind a list of values to the first `IN {in}` (or `NOT IN {in}`) of this SQL query.
+    ///
+    /// A single bind parameter cannot hold a list, so the marker is expanded into what the
+    /// database takes instead, in the SQL of this query:
+    ///
+    /// * for PostgreSQL, `IN {in}` becomes `= ANY($n)` and `NOT IN {in}` becomes `<> ALL($n)`,
+    ///   with the list bound as a single array; `$n` is the next parameter, so `{in}` must be
+    ///   bound in the order the parameters are numbered;
+    /// * for MySQL and SQLite, `{in}` becomes a placeholder for each value, e.g. `(?, ?, ?, ?)`.
+    ///   The number of placeholders is rounded up to a power of two, repeating the last
+    ///   value, so that lists of similar lengths share a prepared statement; each length
+    ///   is otherwise a statement of its own in the statement cache.
+    ///
+    /// An empty list cannot be bound this way, and the query fails with
+    /// [`Error::ArgumentEncode`] when it is executed; see
+    /// [`bind_expanded_allow_empty`](Self::bind_expanded_allow_empty). So does a query without an
+    /// `{in}` left to expand, or one made from a prepared statement, whose SQL cannot change.
+    ///
+    /// The SQL of every distinct expansion is kept for as long as the program runs, so the
+    /// marker is meant for the SQL of a query written in the program, not for SQL that is
+    /// built anew each time. Not supported by MSSQL, or through [`Any`](crate::any::Any).
+    ///
+    /// ```rust,ignore
+    /// let users: Vec<(i64, String)> =
+    ///     sqlx::query_as("SELECT id, name FROM users WHERE active = ? AND id IN {in}")
+    ///         .bind(true)
+    ///         .bind_expanded(vec![3_i64, 5, 8])
+    ///         .fetch_all(&mut conn)
+    ///         .await?;
+    /// ```
+    pub fn bind_expanded<L: InList<'q, DB>>(self, values: L) -> Self {
+        self.expand(values, false)
+    }
+
+    /// Bind a list of values that may be empty to the first `IN {in}` of this SQL query.
+    ///
+    /// See [`bind_expanded`](Self::bind_expanded). An empty list matches no rows, so
+    /// `IN {in}` is false for every row and `NOT IN {in}` is true; in MySQL, `{in}` then
+    /// becomes a subquery that returns no rows.
+    pub fn bind_expanded_allow_empty<L: InList<'q, DB>>(self, values: L) -> Self {
+        self.expand(values, true)
+    }
+
+    fn expand<L: InList<'q, DB>>(mut self, values: L, allow_empty: bool) -> Self {
+        if let Some(arguments) = &mut self.arguments {
+            let sql = self.statement.left();
+
+            if let Some(sql) = values.expand(sql, arguments, allow_empty) {
+                self.statement = Either::Left(sql);
+            }
+        }
+
+        self
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'q> Query<'q, crate::postgres::Postgres, crate::postgres::PgArguments> {
+    /// Bind a value for use with this SQL query, declaring its type to Postgres as `ty`.
+    ///
+    /// Postgres infers the type of every bind parameter when the query is prepared and may pick
+    /// a different type than the one being bound, e.g. for a parameter compared against a
+    /// `VARCHAR` column. The declared type overrides that inference. The value must be
+    /// encoded in the binary format of `ty`; if it is not, executing the query returns the
+    /// error raised by Postgres.
+    ///
+    /// Use [`PgTypeInfo::with_name`](crate::postgres::PgTypeInfo::with_name) to declare a type by
+    /// name; its OID is looked up on first use and then cached by the connection.
+    ///
+    /// A cached statement for the same SQL that was prepared with different parameter types is
+    /// prepared again with the declared ones.
+    pub fn bind_with_type<T: 'q + Send + Encode<'q, crate::postgres::Postgres>>(
+        mut self,
+        value: T,
+        ty: crate::postgres::PgTypeInfo,
+    ) -> Self {
+        if let Some(arguments) = &mut self.arguments {
+            arguments.add_with_type(value, ty);
+        }
+
+        self
+    }
+
+    /// Ask for the values of the rows in the text format, the binary format, or a format
+    /// chosen for each column.
+    ///
+    /// A prepared query returns its values in the binary format by default, which is the
+    /// fastest to decode but needs a binary output function, which some types from extensions
+    /// lack; ask for [`PgResultFormat::Text`] to fetch those. Every type SQLx decodes can be
+    /// decoded from the text format as well, except a `BYTEA` into a borrowed `&[u8]`.
+    ///
+    /// The query is prepared even without bind parameters, as only a prepared query can choose
+    /// its formats; on a connection with
+    /// [`prefer_simple_protocol`](crate::postgres::PgConnectOptions::prefer_simple_protocol),
+    /// the values are always in the text format.
+    ///
+    /// [`PgResultFormat::Text`]: crate::postgres::PgResultFormat::Text
+    pub fn result_format(mut self, format: crate::postgres::PgResultFormat) -> Self {
+        if let Some(arguments) = &mut self.arguments {
+            arguments.set_result_format(format);
+        }
+
+        self.always_prepare = true;
+        self
+    }
+
+    /// Run this query under `EXPLAIN ANALYZE`, with the same bind values, and return its plan
+    /// with the row counts, timings and buffer usage measured while it ran.
+    ///
+    /// The query runs in a transaction, or a savepoint, that is rolled back, unless
+    /// [`allow_mutation`](crate::postgres::PgAnalyzeOptions::allow_mutation) is set; its rows
+    /// are not returned.
+    ///
+    /// ```rust,ignore
+    /// let plan = sqlx::query("SELECT * FROM tweet WHERE owner_id = $1")
+    ///     .bind(owner_id)
+    ///     .analyze(&pool, PgAnalyzeOptions::new())
+    ///     .await?;
+    ///
+    /// for node in plan.nodes() {
+    ///     println!("{}: {} rows", node.node_type, node.actual_rows);
+    /// }
+    /// ```
+    pub async fn analyze<'c, C>(
+        self,
+        conn: C,
+        options: crate::postgres::PgAnalyzeOptions,
+    ) -> Result<crate::postgres::PgQueryPlan, Error>
+    where
+        C: crate::acquire::Acquire<'c, Database = crate::postgres::Postgres>,
+    {
+        let sql = match self.statement {
+            Either::Right(statement) => statement.sql(),
+            Either::Left(sql) => sql,
+        };
+
+        crate::postgres::plan::analyze(sql, self.arguments.unwrap_or_default(), conn, &options)
+            .await
+    }
+}
+
+#[cfg(feature = "mysql")]
+impl<'q, A> Query<'q, crate::mysql::MySql, A>
+where
+    A: 'q + IntoArguments<'q, crate::mysql::MySql> + Send,
+{
+    /// Execute an `INSERT` and return the `AUTO_INCREMENT` id it generated, as reported by
+    /// [`MySqlQueryResult::last_insert_id`](crate::mysql::MySqlQueryResult::last_insert_id).
+    ///
+    /// For an insert of several rows, this is the id of the _first_ row.
+    ///
+    /// Returns [`Error::InsertIdNotFound`] if no id was generated, i.e. the statement was not
+    /// an insert into a table with an `AUTO_INCREMENT` column.
+    pub async fn execute_returning_id<'e, 'c: 'e, E>(self, executor: E) -> Result<u64, Error>
+    where
+        'q: 'e,
+        A: 'e,
+        E: Executor<'c, Database = crate::mysql::MySql>,
+    {
+        match executor.execute(self).await?.last_insert_id() {
+            0 => Err(Error::InsertIdNotFound),
+            id => Ok(id),
+        }
+    }
+}
+
+#[cfg(feature = "any")]
+impl<'q, A> Query<'q, crate::any::Any, A>
+where
+    A: 'q + IntoArguments<'q, crate::any::Any> + Send,
+{
+    /// Execute an `INSERT` and return the id of the inserted row, using whichever way the
+    /// database reports it.
+    ///
+    /// If the statement returns rows, the id is the first column of the first row, as an
+    /// integer; for PostgreSQL and MSSQL the statement must ask for it with `RETURNING id` or
+    /// `OUTPUT INSERTED.id`. Otherwise, the id is the one MySQL and SQLite report with every
+    /// insert (the `AUTO_INCREMENT` id or the `rowid`).
+    /// Whether the connection takes `RETURNING` is reported by
+    /// [`Connection::capabilities`](crate::connection::Connection::capabilities), as
+    /// [`Capabilities::RETURNING`](crate::database::Capabilities::RETURNING).
+    ///
+    /// For an insert of several rows, the databases do not agree on which id this is: it is
+    /// the first row returned with `RETURNING` or `OUTPUT`, the _first_ row inserted in MySQL
+    /// and the _last_ row inserted in SQLite. Use `RETURNING` with
+    /// [`fetch_all`](Query::fetch_all) to get every id.
+    ///
+    /// Returns [`Error::InsertIdNotFound`] if there is no id. SQLite reports the id of the
+    /// connection's most recent insert even for statements that are not inserts, so this is
+    /// not an error there.
+    pub async fn insert_returning_id<'e, 'c: 'e, E>(self, executor: E) -> Result<i64, Error>
+    where
+        'q: 'e,
+        A: 'e,
+        E: Executor<'c, Database = crate::any::Any>,
+    {
+        use crate::row::Row;
+
+        let mut results = executor.fetch_many(self);
+        let mut id = None;
+
+        // the results are drained either way, so the statement runs to completion
+        while let Some(result) = results.try_next().await? {
+            match result {
+                Either::Right(row) if id.is_none() => {
+                    id = Some(
+                        row.try_get::<i64, _>(0)
+                            .or_else(|_| row.try_get::<i32, _>(0).map(i64::from))?,
+                    );
+                }
+
+                Either::Left(done) if id.is_none() => {
+                    id = done.last_insert_id().filter(|id| *id != 0);
+                }
+
+                _ => {}
+            }
+        }
+
+        id.ok_or(Error::InsertIdNotFound)
+    }
 }
 
 impl<'q, DB, A> Query<'q, DB, A>
@@ -96,11 +362,96 @@ where
     /// matching the one with the flag will use the cached statement until the
     /// cache is cleared.
     ///
+    /// A query that is not persistent and has no bind parameters is not prepared at all, but
+    /// sent as it is in a single round trip, as a `&str` would be; see [`always_prepare`].
+    ///
     /// Default: `true`.
+    ///
+    /// [`always_prepare`]: Self::always_prepare
     pub fn persistent(mut self, value: bool) -> Self {
         self.persistent = value;
         self
     }
+
+    /// If `true`, the query is prepared even if it has no bind parameters and is not
+    /// [`persistent`](Self::persistent).
+    ///
+    /// Such a query is otherwise sent as it is, with the simple query protocol of Postgres or
+    /// `COM_QUERY` in MySQL, which saves the round trip of preparing it. The values of its rows
+    /// are then sent in the text format, and the SQL may hold more than one statement.
+    ///
+    /// Default: `false`.
+    pub fn always_prepare(mut self, value: bool) -> Self {
+        self.always_prepare = value;
+        self
+    }
+}
+
+impl<'q, DB: Database, A> Query<'q, DB, A> {
+    /// Fail with [`Error::ValueTooLarge`] on a value of the result larger than `bytes`.
+    ///
+    /// The limit is enforced as the result is read, before anything is decoded: from Postgres,
+    /// a row holding such a value is skipped over on the wire without being buffered; MySQL
+    /// and SQLite check each row as it arrives, which the `max_message_size` of the connect
+    /// options still bounds for MySQL. Either way, the rest of the result is then discarded, and the connection can be
+    /// used again. Not enforced by MSSQL, or through [`Any`](crate::any::Any).
+    ///
+    /// The size of a value is that of its encoding on the wire, e.g. the bytes of a string in
+    /// the character set of the connection; a `NULL` has none.
+    ///
+    /// Default: unlimited.
+    ///
+    /// ```rust,ignore
+    /// let documents = sqlx::query("SELECT body FROM documents WHERE owner = $1")
+    ///     .bind(user_id)
+    ///     .max_value_size(1 << 20)
+    ///     .max_row_size(4 << 20)
+    ///     .fetch_all(&mut conn)
+    ///     .await?;
+    /// ```
+    pub fn max_value_size(mut self, bytes: usize) -> Self {
+        self.limits.max_value_size = Some(bytes);
+        self
+    }
+
+    /// Fail with [`Error::ValueTooLarge`] on a row of the result whose values together are
+    /// larger than `bytes`.
+    ///
+    /// Enforced as [`max_value_size`](Self::max_value_size) is.
+    ///
+    /// Default: unlimited.
+    pub fn max_row_size(mut self, bytes: usize) -> Self {
+        self.limits.max_row_size = Some(bytes);
+        self
+    }
+
+    /// Serve the rows of this query from the query results cache of the pool it is executed
+    /// on for up to `ttl`, instead of executing it again.
+    ///
+    /// Meant for small lookups that rarely change, such as a table of currencies. The rows are
+    /// cached under the SQL and the encoded bind values together, once the whole result has
+    /// been read; a result that is not read to its end, e.g. by
+    /// [`fetch_one`](Self::fetch_one), is read to its end first. Clear the cache with
+    /// [`Pool::invalidate_query_cache`](crate::pool::Pool::invalidate_query_cache) once the
+    /// rows may have changed.
+    ///
+    /// Only queries executed directly on a [`Pool`](crate::pool::Pool) are cached; on a
+    /// connection or a transaction, this does nothing. Nor are the results of SQLite, which
+    /// borrow from the statement they were read from, or of [`Any`](crate::any::Any). Only
+    /// rows are served from the cache, so do not cache a query that changes anything.
+    ///
+    /// Default: not cached.
+    ///
+    /// ```rust,ignore
+    /// let currencies: Vec<(String, i32)> = sqlx::query_as("SELECT code, digits FROM currencies")
+    ///     .cached(Duration::from_secs(300))
+    ///     .fetch_all(&pool)
+    ///     .await?;
+    /// ```
+    pub fn cached(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
 }
 
 impl<'q, DB, A: Send> Query<'q, DB, A>
@@ -225,6 +576,49 @@ where
     {
         executor.fetch_optional(self).await
     }
+
+    /// Execute the query and collect the values of each column into a [`Vec`], decoding the
+    /// columns into the types of the tuple `T`; see [`Columnar`].
+    ///
+    /// Before the first row is decoded, its columns are checked against `T` by position: if
+    /// the result has more or fewer columns, or a column has a type that is not compatible,
+    /// the query fails without decoding anything. The values of the rows are then decoded
+    /// without checking their types again. An empty result is not checked.
+    ///
+    /// ```rust,ignore
+    /// let (ids, prices, labels): (Vec<i64>, Vec<f64>, Vec<Option<String>>) =
+    ///     sqlx::query("SELECT id, price, label FROM trades")
+    ///         .fetch_columnar::<(i64, f64, Option<String>), _>(&pool)
+    ///         .await?;
+    /// ```
+    pub async fn fetch_columnar<'e, 'c: 'e, T, E>(self, executor: E) -> Result<T::Columns, Error>
+    where
+        'q: 'e,
+        A: 'e,
+        E: Executor<'c, Database = DB>,
+        T: 'e + Columnar<DB::Row>,
+    {
+        let mut chunks = self.fetch_columnar_chunks::<T, E>(executor, usize::MAX);
+
+        Ok(chunks.try_next().await?.unwrap_or_default())
+    }
+
+    /// Execute the query and return the values of each column in chunks of up to `rows` rows,
+    /// to bound the memory held for a large result; see
+    /// [`fetch_columnar`](Self::fetch_columnar).
+    pub fn fetch_columnar_chunks<'e, 'c: 'e, T, E>(
+        self,
+        executor: E,
+        rows: usize,
+    ) -> BoxStream<'e, Result<T::Columns, Error>>
+    where
+        'q: 'e,
+        A: 'e,
+        E: Executor<'c, Database = DB>,
+        T: 'e + Columnar<DB::Row>,
+    {
+        columnar::fetch_chunks::<DB::Row, T>(executor.fetch(self), rows)
+    }
 }
 
 impl<'q, DB, F: Send, A: Send> Execute<'q, DB> for Map<'q, DB, F, A>
@@ -251,6 +645,42 @@ where
     fn persistent(&self) -> bool {
         self.inner.arguments.is_some()
     }
+
+    #[inline]
+    fn fetch_limits(&self) -> FetchLimits {
+        self.inner.limits
+    }
+
+    #[inline]
+    fn cache_ttl(&self) -> Option<Duration> {
+        self.inner.cache_ttl
+    }
+}
+
+impl<'q, DB: Database, F, A> Map<'q, DB, F, A> {
+    /// Fail with [`Error::ValueTooLarge`] on a value of the result larger than `bytes`.
+    ///
+    /// See [`Query::max_value_size`].
+    pub fn max_value_size(mut self, bytes: usize) -> Self {
+        self.inner = self.inner.max_value_size(bytes);
+        self
+    }
+
+    /// Fail with [`Error::ValueTooLarge`] on a row of the result larger than `bytes`.
+    ///
+    /// See [`Query::max_row_size`].
+    pub fn max_row_size(mut self, bytes: usize) -> Self {
+        self.inner = self.inner.max_row_size(bytes);
+        self
+    }
+
+    /// Serve the rows of this query from the query results cache of its pool for up to `ttl`.
+    ///
+    /// See [`Query::cached`].
+    pub fn cached(mut self, ttl: Duration) -> Self {
+        self.inner = self.inner.cached(ttl);
+        self
+    }
 }
 
 impl<'q, DB, F, O, A> Map<'q, DB, F, A>
@@ -332,12 +762,13 @@ where
     {
         Box::pin(try_stream! {
             let mut s = executor.fetch_many(self.inner);
+            let mapper = &mut self.mapper;
 
             while let Some(v) = s.try_next().await? {
                 r#yield!(match v {
                     Either::Left(v) => Either::Left(v),
                     Either::Right(row) => {
-                        Either::Right((self.mapper)(row)?)
+                        Either::Right(catch_panic(|| mapper(row))??)
                     }
                 });
             }
@@ -385,9 +816,10 @@ where
         O: 'e,
     {
         let row = executor.fetch_optional(self.inner).await?;
+        let mapper = &mut self.mapper;
 
         if let Some(row) = row {
-            (self.mapper)(row).map(Some)
+            catch_panic(|| mapper(row))?.map(Some)
         } else {
             Ok(None)
         }
@@ -406,6 +838,9 @@ where
         arguments: Some(Default::default()),
         statement: Either::Right(statement),
         persistent: true,
+        always_prepare: false,
+        limits: FetchLimits::default(),
+        cache_ttl: None,
     }
 }
 
@@ -423,6 +858,9 @@ where
         arguments: Some(arguments),
         statement: Either::Right(statement),
         persistent: true,
+        always_prepare: false,
+        limits: FetchLimits::default(),
+        cache_ttl: None,
     }
 }
 
@@ -436,6 +874,9 @@ where
         arguments: Some(Default::default()),
         statement: Either::Left(sql),
         persistent: true,
+        always_prepare: false,
+        limits: FetchLimits::default(),
+        cache_ttl: None,
     }
 }
 
@@ -450,5 +891,66 @@ where
         arguments: Some(arguments),
         statement: Either::Left(sql),
         persistent: true,
+        always_prepare: false,
+        limits: FetchLimits::default(),
+        cache_ttl: None,
     }
 }
+
+// asserts that the futures and streams every driver returns are `Send`, so that they can be
+// held across an `.await` in a spawned task; each driver module invokes this with its types
+#[cfg(test)]
+macro_rules! assert_driver_futures_are_send {
+    ($DB:ident, $C:ident) => {
+        #[test]
+        #[allow(dead_code)]
+        fn assert_driver_futures_are_send() {
+            use crate::connection::{ConnectOptions, Connection};
+            use crate::pool::{Pool, PoolConnection, PoolOptions};
+            use crate::query::query;
+            use crate::transaction::Transaction;
+
+            fn assert_send<T: Send>(_: T) {}
+
+            fn assert_connection(mut conn: $C, options: <$C as Connection>::Options) {
+                assert_send(options.connect());
+                assert_send(Connection::ping(&mut conn));
+                assert_send(Connection::begin(&mut conn));
+                assert_send(query::<$DB>("").execute(&mut conn));
+                assert_send(query::<$DB>("").fetch(&mut conn));
+                assert_send(query::<$DB>("").fetch_one(&mut conn));
+                assert_send(query::<$DB>("").map(|_| ()).fetch_all(&mut conn));
+                assert_send(crate::raw_sql::raw_sql("").fetch_all(&mut conn));
+                assert_send(conn.close());
+            }
+
+            fn assert_transaction(
+                mut tx: Transaction<'static, $DB>,
+                other: Transaction<'static, $DB>,
+            ) {
+                assert_send(query::<$DB>("").fetch_optional(&mut tx));
+                assert_send(tx.commit());
+                assert_send(other.rollback());
+            }
+
+            fn assert_pool(
+                pool: Pool<$DB>,
+                mut conn: PoolConnection<$DB>,
+                options: <$C as Connection>::Options,
+            ) {
+                assert_send(PoolOptions::<$DB>::new().connect_with(options.clone()));
+                assert_send(Pool::<$DB>::connect_with(options));
+                assert_send(pool.acquire());
+                assert_send(pool.begin());
+                assert_send(pool.try_begin());
+                assert_send(pool.warm_up());
+                assert_send(query::<$DB>("").fetch_all(&pool));
+                assert_send(query::<$DB>("").fetch(&pool));
+                assert_send(crate::raw_sql::raw_sql("").execute(&pool));
+                assert_send(pool.close());
+                assert_send(Connection::ping(&mut *conn));
+                assert_send(query::<$DB>("").execute(&mut conn));
+            }
+        }
+    };
+}
@@ -1,4 +1,5 @@
 use std::marker::PhantomData;
+use std::time::Duration;
 
 use either::Either;
 use futures_core::stream::BoxStream;
@@ -6,10 +7,11 @@ use futures_util::{StreamExt, TryStreamExt};
 
 use crate::arguments::IntoArguments;
 use crate::database::{Database, HasArguments, HasStatement};
-use crate::encode::Encode;
-use crate::error::Error;
-use crate::executor::{Execute, Executor};
-use crate::from_row::FromRow;
+use crate::encode::{DynEncode, Encode};
+use crate::error::{catch_panic, Error};
+use crate::executor::{Execute, Executor, FetchLimits};
+use crate::from_row::{validate_columns, FromRow};
+use crate::in_list::InList;
 use crate::query::{query, query_statement, query_statement_with, query_with, Query};
 use crate::types::Type;
 
@@ -45,6 +47,16 @@ where
     fn persistent(&self) -> bool {
         self.inner.persistent()
     }
+
+    #[inline]
+    fn fetch_limits(&self) -> FetchLimits {
+        self.inner.fetch_limits()
+    }
+
+    #[inline]
+    fn cache_ttl(&self) -> Option<Duration> {
+        self.inner.cache_ttl()
+    }
 }
 
 impl<'q, DB: Database, O> QueryAs<'q, DB, O, <DB as HasArguments<'q>>::Arguments> {
@@ -55,10 +67,84 @@ impl<'q, DB: Database, O> QueryAs<'q, DB, O, <DB as HasArguments<'q>>::Arguments
         self.inner = self.inner.bind(value);
         self
     }
+
+    /// Bind a value whose type is only known at runtime.
+    ///
+    /// See [`Query::bind_dyn`](Query::bind_dyn).
+    pub fn bind_dyn(mut self, value: impl Into<Box<dyn DynEncode<'q, DB> + 'q>>) -> Self {
+        self.inner = self.inner.bind_dyn(value);
+        self
+    }
+
+    /// Bind a list of values to the first `IN {in}` of this SQL query.
+    ///
+    /// See [`Query::bind_expanded`](Query::bind_expanded).
+    pub fn bind_expanded<L: InList<'q, DB>>(mut self, values: L) -> Self {
+        self.inner = self.inner.bind_expanded(values);
+        self
+    }
+
+    /// Bind a list of values that may be empty to the first `IN {in}` of this SQL query.
+    ///
+    /// See [`Query::bind_expanded_allow_empty`](Query::bind_expanded_allow_empty).
+    pub fn bind_expanded_allow_empty<L: InList<'q, DB>>(mut self, values: L) -> Self {
+        self.inner = self.inner.bind_expanded_allow_empty(values);
+        self
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'q, O> QueryAs<'q, crate::postgres::Postgres, O, crate::postgres::PgArguments> {
+    /// Bind a value for use with this SQL query, declaring its type to Postgres as `ty`.
+    ///
+    /// See [`Query::bind_with_type`](Query::bind_with_type).
+    pub fn bind_with_type<T: 'q + Send + Encode<'q, crate::postgres::Postgres>>(
+        mut self,
+        value: T,
+        ty: crate::postgres::PgTypeInfo,
+    ) -> Self {
+        self.inner = self.inner.bind_with_type(value, ty);
+        self
+    }
+
+    /// Ask for the values of the rows in the text format, the binary format, or a format
+    /// chosen for each column.
+    ///
+    /// See [`Query::result_format`](Query::result_format).
+    pub fn result_format(mut self, format: crate::postgres::PgResultFormat) -> Self {
+        self.inner = self.inner.result_format(format);
+        self
+    }
 }
 
 // FIXME: This is very close, nearly 1:1 with `Map`
 // noinspection DuplicatedCode
+impl<'q, DB: Database, O, A> QueryAs<'q, DB, O, A> {
+    /// Fail with [`Error::ValueTooLarge`] on a value of the result larger than `bytes`.
+    ///
+    /// See [`Query::max_value_size`](Query::max_value_size).
+    pub fn max_value_size(mut self, bytes: usize) -> Self {
+        self.inner = self.inner.max_value_size(bytes);
+        self
+    }
+
+    /// Fail with [`Error::ValueTooLarge`] on a row of the result larger than `bytes`.
+    ///
+    /// See [`Query::max_row_size`](Query::max_row_size).
+    pub fn max_row_size(mut self, bytes: usize) -> Self {
+        self.inner = self.inner.max_row_size(bytes);
+        self
+    }
+
+    /// Serve the rows of this query from the query results cache of its pool for up to `ttl`.
+    ///
+    /// See [`Query::cached`](Query::cached).
+    pub fn cached(mut self, ttl: Duration) -> Self {
+        self.inner = self.inner.cached(ttl);
+        self
+    }
+}
+
 impl<'q, DB, O, A> QueryAs<'q, DB, O, A>
 where
     DB: Database,
@@ -79,6 +165,61 @@ where
             .boxed()
     }
 
+    /// Execute the query and return the generated results as a stream, first checking the
+    /// columns of the result against the fields of `O`.
+    ///
+    /// Before the first row of each result is decoded, its columns are checked against
+    /// [`FromRow::expected_columns`]; if any column is missing or has a type that is not
+    /// compatible with its field, [`Error::TypeValidation`] is returned listing all of them,
+    /// instead of an error for the first column that fails to decode. Types that do not list
+    /// their columns are not checked.
+    ///
+    /// ```rust,ignore
+    /// #[derive(sqlx::FromRow)]
+    /// struct User {
+    ///     id: i64,
+    ///     name: String,
+    /// }
+    ///
+    /// let users: Vec<User> = sqlx::query_as("SELECT id, name FROM users")
+    ///     .fetch_checked(&pool)
+    ///     .try_collect()
+    ///     .await?;
+    /// ```
+    pub fn fetch_checked<'e, 'c: 'e, E>(self, executor: E) -> BoxStream<'e, Result<O, Error>>
+    where
+        'q: 'e,
+        E: 'e + Executor<'c, Database = DB>,
+        DB: 'e,
+        O: 'e,
+        A: 'e,
+    {
+        Box::pin(try_stream! {
+            let expected = O::expected_columns();
+            let mut s = executor.fetch_many(self.inner);
+
+            // the columns are checked on the first row of each result
+            let mut checked = false;
+
+            while let Some(v) = s.try_next().await? {
+                match v {
+                    Either::Left(_) => checked = false,
+
+                    Either::Right(row) => {
+                        if let (Some(expected), false) = (&expected, checked) {
+                            validate_columns(&row, expected)?;
+                            checked = true;
+                        }
+
+                        r#yield!(catch_panic(|| O::from_row(&row))??);
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+
     /// Execute multiple queries and return the generated results as a stream
     /// from each query, in a stream.
     pub fn fetch_many<'e, 'c: 'e, E>(
@@ -98,7 +239,7 @@ where
             while let Some(v) = s.try_next().await? {
                 r#yield!(match v {
                     Either::Left(v) => Either::Left(v),
-                    Either::Right(row) => Either::Right(O::from_row(&row)?),
+                    Either::Right(row) => Either::Right(catch_panic(|| O::from_row(&row))??),
                 });
             }
 
@@ -144,7 +285,7 @@ where
     {
         let row = executor.fetch_optional(self.inner).await?;
         if let Some(row) = row {
-            O::from_row(&row).map(Some)
+            catch_panic(|| O::from_row(&row))?.map(Some)
         } else {
             Ok(None)
         }
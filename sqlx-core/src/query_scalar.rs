@@ -1,13 +1,16 @@
+use std::time::Duration;
+
 use either::Either;
 use futures_core::stream::BoxStream;
 use futures_util::{StreamExt, TryFutureExt, TryStreamExt};
 
 use crate::arguments::IntoArguments;
 use crate::database::{Database, HasArguments, HasStatement};
-use crate::encode::Encode;
+use crate::encode::{DynEncode, Encode};
 use crate::error::Error;
-use crate::executor::{Execute, Executor};
+use crate::executor::{Execute, Executor, FetchLimits};
 use crate::from_row::FromRow;
+use crate::in_list::InList;
 use crate::query_as::{
     query_as, query_as_with, query_statement_as, query_statement_as_with, QueryAs,
 };
@@ -42,6 +45,16 @@ where
     fn persistent(&self) -> bool {
         self.inner.persistent()
     }
+
+    #[inline]
+    fn fetch_limits(&self) -> FetchLimits {
+        self.inner.fetch_limits()
+    }
+
+    #[inline]
+    fn cache_ttl(&self) -> Option<Duration> {
+        self.inner.cache_ttl()
+    }
 }
 
 impl<'q, DB: Database, O> QueryScalar<'q, DB, O, <DB as HasArguments<'q>>::Arguments> {
@@ -52,10 +65,84 @@ impl<'q, DB: Database, O> QueryScalar<'q, DB, O, <DB as HasArguments<'q>>::Argum
         self.inner = self.inner.bind(value);
         self
     }
+
+    /// Bind a value whose type is only known at runtime.
+    ///
+    /// See [`Query::bind_dyn`](crate::query::Query::bind_dyn).
+    pub fn bind_dyn(mut self, value: impl Into<Box<dyn DynEncode<'q, DB> + 'q>>) -> Self {
+        self.inner = self.inner.bind_dyn(value);
+        self
+    }
+
+    /// Bind a list of values to the first `IN {in}` of this SQL query.
+    ///
+    /// See [`Query::bind_expanded`](crate::query::Query::bind_expanded).
+    pub fn bind_expanded<L: InList<'q, DB>>(mut self, values: L) -> Self {
+        self.inner = self.inner.bind_expanded(values);
+        self
+    }
+
+    /// Bind a list of values that may be empty to the first `IN {in}` of this SQL query.
+    ///
+    /// See [`Query::bind_expanded_allow_empty`](crate::query::Query::bind_expanded_allow_empty).
+    pub fn bind_expanded_allow_empty<L: InList<'q, DB>>(mut self, values: L) -> Self {
+        self.inner = self.inner.bind_expanded_allow_empty(values);
+        self
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'q, O> QueryScalar<'q, crate::postgres::Postgres, O, crate::postgres::PgArguments> {
+    /// Bind a value for use with this SQL query, declaring its type to Postgres as `ty`.
+    ///
+    /// See [`Query::bind_with_type`](crate::query::Query::bind_with_type).
+    pub fn bind_with_type<T: 'q + Send + Encode<'q, crate::postgres::Postgres>>(
+        mut self,
+        value: T,
+        ty: crate::postgres::PgTypeInfo,
+    ) -> Self {
+        self.inner = self.inner.bind_with_type(value, ty);
+        self
+    }
+
+    /// Ask for the values of the rows in the text format, the binary format, or a format
+    /// chosen for each column.
+    ///
+    /// See [`Query::result_format`](crate::query::Query::result_format).
+    pub fn result_format(mut self, format: crate::postgres::PgResultFormat) -> Self {
+        self.inner = self.inner.result_format(format);
+        self
+    }
 }
 
 // FIXME: This is very close, nearly 1:1 with `Map`
 // noinspection DuplicatedCode
+impl<'q, DB: Database, O, A> QueryScalar<'q, DB, O, A> {
+    /// Fail with [`Error::ValueTooLarge`] on a value of the result larger than `bytes`.
+    ///
+    /// See [`Query::max_value_size`](crate::query::Query::max_value_size).
+    pub fn max_value_size(mut self, bytes: usize) -> Self {
+        self.inner = self.inner.max_value_size(bytes);
+        self
+    }
+
+    /// Fail with [`Error::ValueTooLarge`] on a row of the result larger than `bytes`.
+    ///
+    /// See [`Query::max_row_size`](crate::query::Query::max_row_size).
+    pub fn max_row_size(mut self, bytes: usize) -> Self {
+        self.inner = self.inner.max_row_size(bytes);
+        self
+    }
+
+    /// Serve the rows of this query from the query results cache of its pool for up to `ttl`.
+    ///
+    /// See [`Query::cached`](crate::query::Query::cached).
+    pub fn cached(mut self, ttl: Duration) -> Self {
+        self.inner = self.inner.cached(ttl);
+        self
+    }
+}
+
 impl<'q, DB, O, A> QueryScalar<'q, DB, O, A>
 where
     DB: Database,
@@ -73,7 +160,11 @@ where
         A: 'e,
         O: 'e,
     {
-        self.inner.fetch(executor).map_ok(|it| it.0).boxed()
+        self.inner
+            .fetch(executor)
+            .map_ok(|it| it.0)
+            .map_err(no_columns)
+            .boxed()
     }
 
     /// Execute multiple queries and return the generated results as a stream
@@ -93,6 +184,7 @@ where
         self.inner
             .fetch_many(executor)
             .map_ok(|v| v.map_right(|it| it.0))
+            .map_err(no_columns)
             .boxed()
     }
 
@@ -109,6 +201,7 @@ where
         self.inner
             .fetch(executor)
             .map_ok(|it| it.0)
+            .map_err(no_columns)
             .try_collect()
             .await
     }
@@ -123,7 +216,11 @@ where
         O: 'e,
         A: 'e,
     {
-        self.inner.fetch_one(executor).map_ok(|it| it.0).await
+        self.inner
+            .fetch_one(executor)
+            .map_ok(|it| it.0)
+            .map_err(no_columns)
+            .await
     }
 
     /// Execute the query and returns at most one row.
@@ -136,12 +233,32 @@ where
         O: 'e,
         A: 'e,
     {
-        Ok(self.inner.fetch_optional(executor).await?.map(|it| it.0))
+        Ok(self
+            .inner
+            .fetch_optional(executor)
+            .await
+            .map_err(no_columns)?
+            .map(|it| it.0))
+    }
+}
+
+// decoding the first column of a row that has none is a mistake in the query, not in
+// the column index, so say that instead of reporting an out of bounds index
+fn no_columns(error: Error) -> Error {
+    match error {
+        Error::ColumnIndexOutOfBounds { index: 0, len: 0 } => Error::decode(
+            "query_scalar expects the query to return at least one column, but it returned none",
+        ),
+
+        error => error,
     }
 }
 
 /// Make a SQL query that is mapped to a single concrete type
 /// using [`FromRow`].
+///
+/// Only the first column of each row is decoded; any further columns are ignored, so they
+/// may be of types that cannot be decoded.
 #[inline]
 pub fn query_scalar<'q, DB, O>(
     sql: &'q str,
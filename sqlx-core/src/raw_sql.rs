@@ -0,0 +1,159 @@
+use either::Either;
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+
+use crate::database::{Database, HasArguments, HasStatement};
+use crate::error::Error;
+use crate::executor::{Execute, Executor};
+
+/// One or more SQL statements run without bind parameters or preparation. Returned by
+/// [`raw_sql()`].
+#[must_use = "query must be executed to affect database"]
+#[derive(Debug, Clone, Copy)]
+pub struct RawSql<'q>(&'q str);
+
+/// Run SQL as it is, for SQL that comes from the user of an application (e.g. in admin
+/// tooling) or scripts of several statements.
+///
+/// The SQL is sent over the simple query protocol in PostgreSQL and the text protocol in
+/// MySQL, so it may contain any number of semicolon-separated statements. It is never
+/// prepared and never touches the statement cache, and it cannot have bind parameters.
+///
+/// [`fetch_many`](RawSql::fetch_many) yields every result of every statement in order: for
+/// each statement, the rows it returned (each with its own columns) followed by its
+/// query result. An error in a statement is yielded in the stream and ends it: PostgreSQL
+/// runs the rest of the script in the same implicit transaction as the failed statement,
+/// so it rolls back the statements before the error (unless the script starts its own
+/// transaction), while MySQL keeps them but skips the statements after the error.
+///
+/// Values in rows returned by PostgreSQL and MySQL are in the text format.
+///
+/// # Note: Never Use This with Untrusted Input
+///
+/// There are no bind parameters, so values must never be written into the SQL. Use
+/// [`query()`](crate::query::query) to run a statement with values from untrusted input.
+pub fn raw_sql(sql: &str) -> RawSql<'_> {
+    RawSql(sql)
+}
+
+impl<'q, DB: Database> Execute<'q, DB> for RawSql<'q> {
+    #[inline]
+    fn sql(&self) -> &'q str {
+        self.0
+    }
+
+    #[inline]
+    fn statement(&self) -> Option<&<DB as HasStatement<'q>>::Statement> {
+        None
+    }
+
+    #[inline]
+    fn take_arguments(&mut self) -> Option<<DB as HasArguments<'q>>::Arguments> {
+        None
+    }
+
+    #[inline]
+    fn persistent(&self) -> bool {
+        false
+    }
+}
+
+impl<'q> RawSql<'q> {
+    /// Execute the SQL and return the total number of rows affected.
+    #[inline]
+    pub fn execute<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> BoxFuture<'e, Result<<E::Database as Database>::QueryResult, Error>>
+    where
+        'q: 'e,
+        E: Executor<'c>,
+    {
+        executor.execute(self)
+    }
+
+    /// Execute the SQL and return the query result of each statement, in a stream.
+    #[inline]
+    pub fn execute_many<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> BoxStream<'e, Result<<E::Database as Database>::QueryResult, Error>>
+    where
+        'q: 'e,
+        E: Executor<'c>,
+    {
+        executor.execute_many(self)
+    }
+
+    /// Execute the SQL and return the rows of every statement, in a stream.
+    #[inline]
+    pub fn fetch<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> BoxStream<'e, Result<<E::Database as Database>::Row, Error>>
+    where
+        'q: 'e,
+        E: Executor<'c>,
+    {
+        executor.fetch(self)
+    }
+
+    /// Execute the SQL and return the rows and query results of every statement, in the
+    /// order the database returns them, in a stream.
+    #[inline]
+    pub fn fetch_many<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> BoxStream<
+        'e,
+        Result<
+            Either<<E::Database as Database>::QueryResult, <E::Database as Database>::Row>,
+            Error,
+        >,
+    >
+    where
+        'q: 'e,
+        E: Executor<'c>,
+    {
+        executor.fetch_many(self)
+    }
+
+    /// Execute the SQL and return the rows of every statement, collected into a [`Vec`].
+    #[inline]
+    pub fn fetch_all<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> BoxFuture<'e, Result<Vec<<E::Database as Database>::Row>, Error>>
+    where
+        'q: 'e,
+        E: Executor<'c>,
+    {
+        executor.fetch_all(self)
+    }
+
+    /// Execute the SQL and return exactly one row.
+    #[inline]
+    pub fn fetch_one<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> BoxFuture<'e, Result<<E::Database as Database>::Row, Error>>
+    where
+        'q: 'e,
+        E: Executor<'c>,
+    {
+        executor.fetch_one(self)
+    }
+
+    /// Execute the SQL and return at most one row.
+    #[inline]
+    pub fn fetch_optional<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> BoxFuture<'e, Result<Option<<E::Database as Database>::Row>, Error>>
+    where
+        'q: 'e,
+        E: Executor<'c>,
+    {
+        executor.fetch_optional(self)
+    }
+}
@@ -1,11 +1,15 @@
+use crate::cached_row::CachedRow;
 use crate::column::ColumnIndex;
 use crate::database::{Database, HasValueRef};
 use crate::decode::Decode;
-use crate::error::{mismatched_types, Error};
+use crate::error::{catch_panic, mismatched_types, Error};
 use crate::type_info::TypeInfo;
 use crate::types::Type;
 use crate::value::ValueRef;
 
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+use std::fmt::{self, Debug, Formatter};
+
 /// Represents a single row from the database.
 ///
 /// This trait is sealed and cannot be implemented for types outside of SQLx.
@@ -101,11 +105,17 @@ pub trait Row: private_row::Sealed + Unpin + Send + Sync + 'static {
     /// # Errors
     ///
     ///  * [`ColumnNotFound`] if the column by the given name was not found.
+    ///  * [`ColumnAmbiguous`] if no column has exactly the given name, and several match it
+    ///    when case is ignored; see [`ColumnNameMatching`].
     ///  * [`ColumnIndexOutOfBounds`] if the `usize` index was greater than the number of columns in the row.
     ///  * [`ColumnDecode`] if the value could not be decoded into the requested type.
+    ///  * [`Panic`] if the [`Decode`] implementation of the type panicked.
     ///
     /// [`ColumnDecode`]: Error::ColumnDecode
+    /// [`Panic`]: Error::Panic
     /// [`ColumnNotFound`]: Error::ColumnNotFound
+    /// [`ColumnAmbiguous`]: Error::ColumnAmbiguous
+    /// [`ColumnNameMatching`]: crate::column::ColumnNameMatching
     /// [`ColumnIndexOutOfBounds`]: Error::ColumnIndexOutOfBounds
     ///
     fn try_get<'r, T, I>(&'r self, index: I) -> Result<T, Error>
@@ -119,17 +129,14 @@ pub trait Row: private_row::Sealed + Unpin + Send + Sync + 'static {
             let ty = value.type_info();
 
             if !ty.is_null() && !T::compatible(&ty) {
-                return Err(Error::ColumnDecode {
-                    index: format!("{:?}", index),
-                    source: mismatched_types::<Self::Database, T>(&ty),
-                });
+                return Err(Error::column_decode(
+                    &index,
+                    mismatched_types::<Self::Database, T>(&ty),
+                ));
             }
         }
 
-        T::decode(value).map_err(|source| Error::ColumnDecode {
-            index: format!("{:?}", index),
-            source,
-        })
+        catch_panic(|| T::decode(value))?.map_err(|source| Error::column_decode(&index, source))
     }
 
     /// Index into the database row and decode a single value.
@@ -143,8 +150,10 @@ pub trait Row: private_row::Sealed + Unpin + Send + Sync + 'static {
     ///  * [`ColumnNotFound`] if the column by the given name was not found.
     ///  * [`ColumnIndexOutOfBounds`] if the `usize` index was greater than the number of columns in the row.
     ///  * [`ColumnDecode`] if the value could not be decoded into the requested type.
+    ///  * [`Panic`] if the [`Decode`] implementation of the type panicked.
     ///
     /// [`ColumnDecode`]: Error::ColumnDecode
+    /// [`Panic`]: Error::Panic
     /// [`ColumnNotFound`]: Error::ColumnNotFound
     /// [`ColumnIndexOutOfBounds`]: Error::ColumnIndexOutOfBounds
     ///
@@ -156,10 +165,7 @@ pub trait Row: private_row::Sealed + Unpin + Send + Sync + 'static {
     {
         let value = self.try_get_raw(&index)?;
 
-        T::decode(value).map_err(|source| Error::ColumnDecode {
-            index: format!("{:?}", index),
-            source,
-        })
+        catch_panic(|| T::decode(value))?.map_err(|source| Error::column_decode(&index, source))
     }
 
     /// Index into the database row and decode a single value.
@@ -178,9 +184,141 @@ pub trait Row: private_row::Sealed + Unpin + Send + Sync + 'static {
     ) -> Result<<Self::Database as HasValueRef<'_>>::ValueRef, Error>
     where
         I: ColumnIndex<Self>;
+
+    /// Wraps this row in a [`CachedRow`], which decodes each of its columns at most once per
+    /// type.
+    #[inline]
+    fn cached(self) -> CachedRow<Self>
+    where
+        Self: Sized,
+        usize: ColumnIndex<Self>,
+    {
+        CachedRow::new(self)
+    }
+
+    /// A copy of this row that shares its data, for the query results cache of a pool, or
+    /// `None` if rows of this driver cannot be kept once the next row has been read.
+    #[doc(hidden)]
+    #[inline]
+    fn try_share(&self) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// The number of bytes this row holds, counted against the memory bound of the query
+    /// results cache of a pool.
+    #[doc(hidden)]
+    #[inline]
+    fn data_len(&self) -> usize {
+        0
+    }
+}
+
+/// Extension methods for inspecting a [`Row`] without knowing its shape ahead of time.
+///
+/// This is intended for ad-hoc tooling (scripts, REPLs, debugging output) and not as a
+/// replacement for [`FromRow`](crate::from_row::FromRow).
+#[cfg(feature = "json")]
+pub trait RowExt: Row {
+    /// Converts this row into a JSON object keyed by column name.
+    ///
+    /// The column type is used to pick between JSON `null`, booleans, numbers and strings.
+    /// JSON columns are embedded as-is. Any other value that cannot be represented as text
+    /// is encoded as an object of the form `{"type": "<SQL type>", "base64": "..."}`.
+    fn to_json(&self) -> serde_json::Map<String, serde_json::Value>;
+}
+
+/// A best-effort view of a single column value, shared by the row `Debug`
+/// implementations and [`RowExt::to_json`].
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) enum LooseValue<'r> {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Text(&'r str),
+    Json(&'r str),
+    Binary(&'r [u8]),
+}
+
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+impl LooseValue<'_> {
+    #[cfg(feature = "json")]
+    pub(crate) fn to_json(&self, type_name: &str) -> serde_json::Value {
+        use serde_json::Value;
+
+        match *self {
+            LooseValue::Null => Value::Null,
+            LooseValue::Bool(v) => Value::Bool(v),
+            LooseValue::Int(v) => Value::from(v),
+            LooseValue::UInt(v) => Value::from(v),
+
+            // NaN and infinity have no JSON representation
+            LooseValue::Float(v) => serde_json::Number::from_f64(v)
+                .map(Value::Number)
+                .unwrap_or_else(|| Value::String(v.to_string())),
+
+            LooseValue::Text(v) => Value::String(v.to_owned()),
+
+            LooseValue::Json(v) => {
+                serde_json::from_str(v).unwrap_or_else(|_| Value::String(v.to_owned()))
+            }
+
+            LooseValue::Binary(v) => serde_json::json!({
+                "type": type_name,
+                "base64": base64::encode(v),
+            }),
+        }
+    }
+}
+
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+impl Debug for LooseValue<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LooseValue::Null => f.write_str("NULL"),
+            LooseValue::Bool(v) => Debug::fmt(v, f),
+            LooseValue::Int(v) => Debug::fmt(v, f),
+            LooseValue::UInt(v) => Debug::fmt(v, f),
+            LooseValue::Float(v) => Debug::fmt(v, f),
+            LooseValue::Text(v) => Debug::fmt(v, f),
+            LooseValue::Json(v) => f.write_str(v),
+            LooseValue::Binary(v) => write!(f, "<binary, {} bytes>", v.len()),
+        }
+    }
+}
+
+/// Writes `row` as `Name { column: value, .. }` using `value` to render each column.
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) fn debug_row<'r, R: Row>(
+    f: &mut Formatter<'_>,
+    name: &str,
+    row: &'r R,
+    value: impl Fn(usize) -> LooseValue<'r>,
+) -> fmt::Result {
+    use crate::column::Column;
+
+    let mut s = f.debug_struct(name);
+
+    for (index, column) in row.columns().iter().enumerate() {
+        s.field(column.name(), &value(index));
+    }
+
+    s.finish()
 }
 
 // Prevent users from implementing the `Row` trait.
 pub(crate) mod private_row {
-    pub trait Sealed {}
+    use crate::column::ColumnNameMatching;
+
+    pub trait Sealed {
+        // how the names of the columns of the row are matched, set by the connection it was
+        // read on; rows of the databases without the option match exactly
+        fn column_name_matching(&self) -> ColumnNameMatching {
+            ColumnNameMatching::Exact
+        }
+    }
 }
@@ -0,0 +1,706 @@
+//! Reading rows into types that implement [`serde::Deserialize`], for those that already derive
+//! it and should not need [`FromRow`](crate::from_row::FromRow) too.
+//!
+//! ```rust,ignore
+//! #[derive(serde::Deserialize)]
+//! struct User {
+//!     id: i64,
+//!     name: String,
+//!     email: Option<String>,
+//! }
+//!
+//! let row = sqlx::query("SELECT id, name, email FROM users").fetch_one(&mut conn).await?;
+//! let user: User = sqlx::serde_row::from_row(&row)?;
+//! ```
+//!
+//! The columns of the row are the entries of a map, keyed by column name, so a struct is read
+//! field by field as with `FromRow`; a tuple or tuple struct is read from the columns in order.
+//! Each value is decoded as the Rust type serde asks for, with the rules of the driver for
+//! which SQL types that Rust type accepts. Integer types a driver does not support are decoded
+//! as a wider type and checked to be in range, e.g. `u16` as `i32` for Postgres. `NULL` is
+//! read as `None` into an `Option`, and is an error for any other type.
+//!
+//! This is less precise than `FromRow`:
+//!
+//!  * a nested struct, sequence or map cannot be read from a column; `#[serde(flatten)]` does
+//!    work, as the flattened fields are columns of the same row;
+//!  * an enum is read from a text column naming a unit variant, and cannot have data;
+//!  * types that deserialize from anything, like `serde_json::Value` or `#[serde(untagged)]`
+//!    enums, get the first of an integer, a float, a boolean, text and bytes that the column
+//!    can be decoded as;
+//!  * bytes are not supported by MSSQL or `Any`.
+
+use std::convert::TryFrom;
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+
+use serde::de::value::StrDeserializer;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use crate::column::{Column, ColumnIndex};
+use crate::decode::Decode;
+use crate::error::{Error, UnexpectedNullError};
+use crate::row::Row;
+use crate::types::Type;
+
+/// Deserializes `T` from the columns of `row`, ignoring the columns that are no field of `T`.
+pub fn from_row<T, R>(row: &R) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    R: SerdeRow,
+{
+    from_row_with(row, SerdeRowOptions::new())
+}
+
+/// Deserializes `T` from the columns of `row`, as `options` say.
+pub fn from_row_with<T, R>(row: &R, options: SerdeRowOptions) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    R: SerdeRow,
+{
+    T::deserialize(RowDeserializer { row, options }).map_err(DeError::into_error)
+}
+
+/// Options for [`from_row_with`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SerdeRowOptions {
+    deny_unknown_columns: bool,
+}
+
+impl SerdeRowOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fails if the row has a column that is no field of the struct it is read into, instead
+    /// of ignoring it. Does nothing for maps and tuples, which take every column.
+    pub fn deny_unknown_columns(mut self, deny: bool) -> Self {
+        self.deny_unknown_columns = deny;
+        self
+    }
+}
+
+/// A row that can be read with [`from_row`]; implemented for the row of every driver.
+pub trait SerdeRow: Row {
+    #[doc(hidden)]
+    fn decode_bool(&self, index: usize) -> Result<Option<bool>, Error>;
+
+    #[doc(hidden)]
+    fn decode_i8(&self, index: usize) -> Result<Option<i8>, Error>;
+
+    #[doc(hidden)]
+    fn decode_i16(&self, index: usize) -> Result<Option<i16>, Error>;
+
+    #[doc(hidden)]
+    fn decode_i32(&self, index: usize) -> Result<Option<i32>, Error>;
+
+    #[doc(hidden)]
+    fn decode_i64(&self, index: usize) -> Result<Option<i64>, Error>;
+
+    #[doc(hidden)]
+    fn decode_u8(&self, index: usize) -> Result<Option<u8>, Error>;
+
+    #[doc(hidden)]
+    fn decode_u16(&self, index: usize) -> Result<Option<u16>, Error>;
+
+    #[doc(hidden)]
+    fn decode_u32(&self, index: usize) -> Result<Option<u32>, Error>;
+
+    #[doc(hidden)]
+    fn decode_u64(&self, index: usize) -> Result<Option<u64>, Error>;
+
+    #[doc(hidden)]
+    fn decode_f32(&self, index: usize) -> Result<Option<f32>, Error>;
+
+    #[doc(hidden)]
+    fn decode_f64(&self, index: usize) -> Result<Option<f64>, Error>;
+
+    #[doc(hidden)]
+    fn decode_string(&self, index: usize) -> Result<Option<String>, Error>;
+
+    #[doc(hidden)]
+    fn decode_bytes(&self, index: usize) -> Result<Option<Vec<u8>>, Error> {
+        Err(Error::column_decode(
+            self.columns()[index].name(),
+            "bytes cannot be read from this driver's rows",
+        ))
+    }
+
+    #[doc(hidden)]
+    fn is_null(&self, index: usize) -> Result<bool, Error>;
+}
+
+// decodes the value as `S`, which the driver supports, and converts it to the `T` serde asks for
+#[allow(dead_code)] // Only used with a driver
+fn decode<R, S, T>(row: &R, index: usize) -> Result<Option<T>, Error>
+where
+    R: Row,
+    usize: ColumnIndex<R>,
+    S: for<'r> Decode<'r, R::Database> + Type<R::Database>,
+    T: TryFrom<S>,
+    <T as TryFrom<S>>::Error: StdError + Send + Sync + 'static,
+{
+    let name = || format!("{:?}", row.columns()[index].name());
+
+    let value = match row.try_get::<Option<S>, _>(index) {
+        Ok(value) => value,
+
+        // by name, which is what the struct knows the column by
+        Err(Error::ColumnDecode { source, .. }) => {
+            return Err(Error::ColumnDecode {
+                index: name(),
+                source,
+            });
+        }
+
+        Err(error) => return Err(error),
+    };
+
+    value
+        .map(T::try_from)
+        .transpose()
+        .map_err(|error| Error::ColumnDecode {
+            index: name(),
+            source: Box::new(error),
+        })
+}
+
+// `$method: $ty = $decoded` decodes `$ty` as the wider `$decoded`
+#[allow(unused_macros)] // Only used with a driver
+macro_rules! impl_serde_row {
+    ($row:ty { $($method:ident: $ty:ty $(= $decoded:ty)?),* $(,)? }) => {
+        impl SerdeRow for $row {
+            $(
+                fn $method(&self, index: usize) -> Result<Option<$ty>, Error> {
+                    decode::<Self, impl_serde_row!(@decoded $ty $(, $decoded)?), $ty>(self, index)
+                }
+            )*
+
+            fn is_null(&self, index: usize) -> Result<bool, Error> {
+                use crate::value::ValueRef;
+
+                Ok(self.try_get_raw(index)?.is_null())
+            }
+        }
+    };
+
+    (@decoded $ty:ty) => { $ty };
+    (@decoded $ty:ty, $decoded:ty) => { $decoded };
+}
+
+#[cfg(feature = "postgres")]
+impl_serde_row!(crate::postgres::PgRow {
+    decode_bool: bool,
+    decode_i8: i8 = i16,
+    decode_i16: i16,
+    decode_i32: i32,
+    decode_i64: i64,
+    decode_u8: u8 = i16,
+    decode_u16: u16 = i32,
+    decode_u32: u32 = i64,
+    decode_u64: u64 = i64,
+    decode_f32: f32,
+    decode_f64: f64,
+    decode_string: String,
+    decode_bytes: Vec<u8>,
+});
+
+#[cfg(feature = "mysql")]
+impl_serde_row!(crate::mysql::MySqlRow {
+    decode_bool: bool,
+    decode_i8: i8,
+    decode_i16: i16,
+    decode_i32: i32,
+    decode_i64: i64,
+    decode_u8: u8,
+    decode_u16: u16,
+    decode_u32: u32,
+    decode_u64: u64,
+    decode_f32: f32,
+    decode_f64: f64,
+    decode_string: String,
+    decode_bytes: Vec<u8>,
+});
+
+#[cfg(feature = "sqlite")]
+impl_serde_row!(crate::sqlite::SqliteRow {
+    decode_bool: bool,
+    decode_i8: i8,
+    decode_i16: i16,
+    decode_i32: i32,
+    decode_i64: i64,
+    decode_u8: u8,
+    decode_u16: u16,
+    decode_u32: u32,
+    decode_u64: u64 = i64,
+    decode_f32: f32,
+    decode_f64: f64,
+    decode_string: String,
+    decode_bytes: Vec<u8>,
+});
+
+#[cfg(feature = "mssql")]
+impl_serde_row!(crate::mssql::MssqlRow {
+    decode_bool: bool,
+    decode_i8: i8,
+    decode_i16: i16,
+    decode_i32: i32,
+    decode_i64: i64,
+    decode_u8: u8 = i16,
+    decode_u16: u16 = i32,
+    decode_u32: u32 = i64,
+    decode_u64: u64 = i64,
+    decode_f32: f32,
+    decode_f64: f64,
+    decode_string: String,
+});
+
+#[cfg(feature = "any")]
+impl_serde_row!(crate::any::AnyRow {
+    decode_bool: bool,
+    decode_i8: i8 = i32,
+    decode_i16: i16 = i32,
+    decode_i32: i32,
+    decode_i64: i64,
+    decode_u8: u8 = i32,
+    decode_u16: u16 = i32,
+    decode_u32: u32 = i64,
+    decode_u64: u64 = i64,
+    decode_f32: f32,
+    decode_f64: f64,
+    decode_string: String,
+});
+
+#[cfg(feature = "memory")]
+impl_serde_row!(crate::memory::MemoryRow {
+    decode_bool: bool,
+    decode_i8: i8,
+    decode_i16: i16,
+    decode_i32: i32,
+    decode_i64: i64,
+    decode_u8: u8 = i64,
+    decode_u16: u16 = i64,
+    decode_u32: u32 = i64,
+    decode_u64: u64 = i64,
+    decode_f32: f32,
+    decode_f64: f64,
+    decode_string: String,
+    decode_bytes: Vec<u8>,
+});
+
+// serde requires its own error type; it is turned back into an `Error` once deserializing ends
+#[derive(Debug)]
+enum DeError {
+    Sqlx(Error),
+
+    // from the `Deserialize` implementation, e.g. a value it did not expect
+    Custom(String),
+
+    MissingField(&'static str),
+}
+
+impl DeError {
+    fn into_error(self) -> Error {
+        match self {
+            DeError::Sqlx(error) => error,
+            DeError::Custom(message) => Error::decode(message),
+            DeError::MissingField(field) => Error::ColumnNotFound(field.to_owned()),
+        }
+    }
+
+    // errors about a value are attributed to its column
+    fn in_column(self, name: &str) -> Self {
+        match self {
+            DeError::Custom(message) => DeError::Sqlx(Error::column_decode(name, message)),
+
+            error => error,
+        }
+    }
+}
+
+impl Display for DeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DeError::Sqlx(error) => Display::fmt(error, f),
+            DeError::Custom(message) => f.write_str(message),
+            DeError::MissingField(field) => write!(f, "no column found for name: {}", field),
+        }
+    }
+}
+
+impl StdError for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: Display>(message: T) -> Self {
+        DeError::Custom(message.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        DeError::MissingField(field)
+    }
+}
+
+impl From<Error> for DeError {
+    fn from(error: Error) -> Self {
+        DeError::Sqlx(error)
+    }
+}
+
+struct RowDeserializer<'r, R> {
+    row: &'r R,
+    options: SerdeRowOptions,
+}
+
+impl<'de, R: SerdeRow> de::Deserializer<'de> for RowDeserializer<'_, R> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_map(Columns::all(self.row))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let columns = self.row.columns();
+
+        if self.options.deny_unknown_columns {
+            if let Some(column) = columns.iter().find(|c| !fields.contains(&c.name())) {
+                return Err(DeError::Sqlx(Error::decode(format!(
+                    "column {:?} is no field of {}",
+                    column.name(),
+                    name
+                ))));
+            }
+        }
+
+        let indices = (0..columns.len())
+            .filter(|&index| fields.contains(&columns[index].name()))
+            .collect();
+
+        visitor.visit_map(Columns {
+            row: self.row,
+            indices,
+            next: 0,
+        })
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_seq(Columns::all(self.row))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct map enum identifier ignored_any
+    }
+}
+
+// the columns of a row as the entries of a map, or the elements of a sequence
+struct Columns<'r, R> {
+    row: &'r R,
+    indices: Vec<usize>,
+    next: usize,
+}
+
+impl<'r, R: Row> Columns<'r, R> {
+    fn all(row: &'r R) -> Self {
+        Self {
+            row,
+            indices: (0..row.len()).collect(),
+            next: 0,
+        }
+    }
+
+    fn name(&self, index: usize) -> &'r str {
+        self.row.columns()[index].name()
+    }
+}
+
+impl<'de, R: SerdeRow> MapAccess<'de> for Columns<'_, R> {
+    type Error = DeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, DeError>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let index = match self.indices.get(self.next) {
+            Some(&index) => index,
+            None => return Ok(None),
+        };
+
+        let name: StrDeserializer<'_, DeError> = self.name(index).into_deserializer();
+
+        seed.deserialize(name).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, DeError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let index = self.indices[self.next];
+        self.next += 1;
+
+        seed.deserialize(ValueDeserializer {
+            row: self.row,
+            index,
+        })
+        .map_err(|error| error.in_column(self.name(index)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.indices.len() - self.next)
+    }
+}
+
+impl<'de, R: SerdeRow> SeqAccess<'de> for Columns<'_, R> {
+    type Error = DeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, DeError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let index = match self.indices.get(self.next) {
+            Some(&index) => index,
+            None => return Ok(None),
+        };
+
+        self.next += 1;
+
+        seed.deserialize(ValueDeserializer {
+            row: self.row,
+            index,
+        })
+        .map(Some)
+        .map_err(|error| error.in_column(self.name(index)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.indices.len() - self.next)
+    }
+}
+
+// a single column
+struct ValueDeserializer<'r, R> {
+    row: &'r R,
+    index: usize,
+}
+
+impl<R: SerdeRow> ValueDeserializer<'_, R> {
+    fn non_null<T>(&self, value: Option<T>) -> Result<T, DeError> {
+        value.ok_or_else(|| {
+            DeError::Sqlx(Error::column_decode(
+                self.row.columns()[self.index].name(),
+                UnexpectedNullError,
+            ))
+        })
+    }
+}
+
+macro_rules! deserialize_value {
+    ($($deserialize:ident => $decode:ident, $visit:ident;)*) => {
+        $(
+            fn $deserialize<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                let value = self.row.$decode(self.index)?;
+
+                visitor.$visit(self.non_null(value)?)
+            }
+        )*
+    };
+}
+
+impl<'de, R: SerdeRow> de::Deserializer<'de> for ValueDeserializer<'_, R> {
+    type Error = DeError;
+
+    // the first kind of value the column can be decoded as; the order puts integers before
+    // booleans, as SQLite and MySQL store booleans as integers
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        let (row, index) = (self.row, self.index);
+
+        if row.is_null(index)? {
+            return visitor.visit_unit();
+        }
+
+        if let Ok(Some(value)) = row.decode_i64(index) {
+            return visitor.visit_i64(value);
+        }
+
+        if let Ok(Some(value)) = row.decode_f64(index) {
+            return visitor.visit_f64(value);
+        }
+
+        if let Ok(Some(value)) = row.decode_bool(index) {
+            return visitor.visit_bool(value);
+        }
+
+        match row.decode_string(index) {
+            Ok(value) => visitor.visit_string(self.non_null(value)?),
+
+            Err(error) => match row.decode_bytes(index) {
+                Ok(value) => visitor.visit_byte_buf(self.non_null(value)?),
+                Err(_) => Err(error.into()),
+            },
+        }
+    }
+
+    deserialize_value! {
+        deserialize_bool => decode_bool, visit_bool;
+        deserialize_i8 => decode_i8, visit_i8;
+        deserialize_i16 => decode_i16, visit_i16;
+        deserialize_i32 => decode_i32, visit_i32;
+        deserialize_i64 => decode_i64, visit_i64;
+        deserialize_u8 => decode_u8, visit_u8;
+        deserialize_u16 => decode_u16, visit_u16;
+        deserialize_u32 => decode_u32, visit_u32;
+        deserialize_u64 => decode_u64, visit_u64;
+        deserialize_f32 => decode_f32, visit_f32;
+        deserialize_f64 => decode_f64, visit_f64;
+        deserialize_string => decode_string, visit_string;
+        deserialize_byte_buf => decode_bytes, visit_byte_buf;
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        if self.row.is_null(self.index)? {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    // the value is not looked at
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // a unit variant, by its name
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let value = self.row.decode_string(self.index)?;
+        let variant: de::value::StringDeserializer<DeError> =
+            self.non_null(value)?.into_deserializer();
+
+        visitor.visit_enum(variant)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, DeError> {
+        Err(nested("a sequence"))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, DeError> {
+        Err(nested("a tuple"))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, DeError> {
+        Err(nested("a tuple struct"))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, DeError> {
+        Err(nested("a map"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, DeError> {
+        Err(nested(name))
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        let value = self.row.decode_i64(self.index)?;
+
+        visitor.visit_i128(self.non_null(value)?.into())
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        let value = self.row.decode_u64(self.index)?;
+
+        visitor.visit_u128(self.non_null(value)?.into())
+    }
+}
+
+fn nested(what: &str) -> DeError {
+    DeError::Custom(format!(
+        "cannot read {} from a single column; only flat values are supported",
+        what
+    ))
+}
@@ -1,11 +1,13 @@
 use crate::arguments::Arguments;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{DynEncode, Encode, EncodeError, IsNull};
 use crate::error::Error;
+use crate::logger::{write_hex, write_quoted};
 use crate::sqlite::statement::StatementHandle;
 use crate::sqlite::Sqlite;
 use atoi::atoi;
 use libsqlite3_sys::SQLITE_OK;
 use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
 
 #[derive(Debug, Clone)]
 pub enum SqliteArgumentValue<'q> {
@@ -17,9 +19,13 @@ pub enum SqliteArgumentValue<'q> {
     Int64(i64),
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug)]
 pub struct SqliteArguments<'q> {
     pub(crate) values: Vec<SqliteArgumentValue<'q>>,
+
+    // the first value that could not be encoded, with its index; reported before the statement
+    // is run
+    pub(crate) error: Option<(usize, EncodeError)>,
 }
 
 impl<'q> SqliteArguments<'q> {
@@ -27,8 +33,62 @@ impl<'q> SqliteArguments<'q> {
     where
         T: Encode<'q, Sqlite>,
     {
-        if let IsNull::Yes = value.encode(&mut self.values) {
-            self.values.push(SqliteArgumentValue::Null);
+        let index = self.values.len();
+
+        match value.encode(&mut self.values) {
+            Ok(IsNull::No) => {}
+
+            Ok(IsNull::Yes) => self.values.push(SqliteArgumentValue::Null),
+
+            Err(error) => {
+                self.values.truncate(index);
+                self.values.push(SqliteArgumentValue::Null);
+
+                if self.error.is_none() {
+                    self.error = Some((index, error));
+                }
+            }
+        }
+    }
+
+    pub(crate) fn check_encoded(&mut self) -> Result<(), Error> {
+        match self.error.take() {
+            Some((index, source)) => Err(Error::ArgumentEncode { index, source }),
+            None => Ok(()),
+        }
+    }
+}
+
+// the error is not `Clone`, so a copy of it keeps only its message
+impl Clone for SqliteArguments<'_> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            error: self
+                .error
+                .as_ref()
+                .map(|(index, error)| (*index, error.to_string().into())),
+        }
+    }
+}
+
+/// Renders a bind value for the statement log.
+pub(crate) struct SqliteBindValue<'a, 'q>(pub(crate) &'a SqliteArgumentValue<'q>);
+
+impl Display for SqliteBindValue<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            SqliteArgumentValue::Null => f.write_str("NULL"),
+            SqliteArgumentValue::Text(s) => write_quoted(f, s),
+            SqliteArgumentValue::Double(v) => write!(f, "{}", v),
+            SqliteArgumentValue::Int(v) => write!(f, "{}", v),
+            SqliteArgumentValue::Int64(v) => write!(f, "{}", v),
+
+            SqliteArgumentValue::Blob(bytes) => {
+                f.write_str("x'")?;
+                write_hex(f, bytes)?;
+                f.write_str("'")
+            }
         }
     }
 }
@@ -46,6 +106,14 @@ impl<'q> Arguments<'q> for SqliteArguments<'q> {
     {
         self.add(value)
     }
+
+    fn add_dyn(&mut self, value: Box<dyn DynEncode<'q, Self::Database> + 'q>) {
+        self.add(value)
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
 }
 
 impl SqliteArguments<'_> {
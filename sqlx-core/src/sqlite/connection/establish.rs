@@ -1,4 +1,6 @@
+use crate::connection::StatsCollector;
 use crate::error::Error;
+use crate::events::ConnectionEvents;
 use crate::sqlite::connection::handle::ConnectionHandle;
 use crate::sqlite::statement::StatementWorker;
 use crate::{
@@ -111,9 +113,16 @@ pub(crate) async fn establish(options: &SqliteConnectOptions) -> Result<SqliteCo
     Ok(SqliteConnection {
         handle,
         worker: StatementWorker::new(),
-        statements: StatementCache::new(options.statement_cache_capacity),
+        statements: match options.statement_cache_max_bytes {
+            Some(max_bytes) => StatementCache::with_max_bytes(max_bytes),
+            None => StatementCache::new(options.statement_cache_capacity),
+        },
         statement: None,
         transaction_depth: 0,
+        column_name_matching: options.column_name_matching,
+        stats: StatsCollector::new(),
         log_settings: options.log_settings.clone(),
+        middleware: options.middleware.clone(),
+        events: ConnectionEvents::new(&options.events, options.connection_info()),
     })
 }
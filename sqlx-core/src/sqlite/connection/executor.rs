@@ -1,8 +1,9 @@
 use crate::common::StatementCache;
 use crate::describe::Describe;
 use crate::error::Error;
-use crate::executor::{Execute, Executor};
+use crate::executor::{Execute, Executor, FetchLimits};
 use crate::logger::QueryLogger;
+use crate::sqlite::arguments::SqliteBindValue;
 use crate::sqlite::connection::describe::describe;
 use crate::sqlite::statement::{StatementHandle, VirtualStatement};
 use crate::sqlite::{
@@ -12,11 +13,12 @@ use crate::sqlite::{
 use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
-use libsqlite3_sys::sqlite3_last_insert_rowid;
+use libsqlite3_sys::{sqlite3_last_insert_rowid, SQLITE_BLOB, SQLITE_NULL, SQLITE_TEXT};
 use std::borrow::Cow;
 use std::sync::Arc;
+use std::time::Instant;
 
-fn prepare<'a>(
+pub(super) fn prepare<'a>(
     statements: &'a mut StatementCache<VirtualStatement>,
     statement: &'a mut Option<VirtualStatement>,
     query: &str,
@@ -27,14 +29,22 @@ fn prepare<'a>(
         return Ok(statement.as_mut().unwrap());
     }
 
-    let exists = statements.contains_key(query);
+    // counts the lookup, and marks the statement as recently used
+    let exists = statements.get_mut(query).is_some();
 
     if !exists {
-        let statement = VirtualStatement::new(query, true)?;
-        statements.insert(query, statement);
+        let new = VirtualStatement::new(query, true)?;
+
+        // the compiled statements are owned by SQLite, so only their SQL counts towards a
+        // capacity in bytes
+        if let Err(new) = statements.insert(query, new, 0) {
+            // the pinned statements leave no room for it, so it is run like a one-off
+            *statement = Some(new);
+            return Ok(statement.as_mut().unwrap());
+        }
     }
 
-    let statement = statements.get_mut(query).unwrap();
+    let statement = statements.peek_mut(query).unwrap();
 
     if exists {
         // as this statement has been executed before, we reset before continuing
@@ -59,6 +69,28 @@ fn bind(
     Ok(n)
 }
 
+// checks the current row of `statement` against `limits`; numbers count as 8 bytes, as asking
+// for their size would convert them to text
+fn check_limits(statement: &StatementHandle, limits: &FetchLimits) -> Result<(), Error> {
+    if limits.is_unlimited() {
+        return Ok(());
+    }
+
+    let sizes: Vec<Option<usize>> = (0..statement.column_count())
+        .map(|index| match statement.column_type(index) {
+            SQLITE_NULL => None,
+            SQLITE_TEXT | SQLITE_BLOB => Some(statement.column_bytes(index)),
+            _ => Some(8),
+        })
+        .collect();
+
+    limits.check(sizes)
+}
+
+impl SqliteConnection {
+    impl_executor_methods!(Sqlite, &mut self => self);
+}
+
 impl<'c> Executor<'c> for &'c mut SqliteConnection {
     type Database = Sqlite;
 
@@ -71,21 +103,37 @@ impl<'c> Executor<'c> for &'c mut SqliteConnection {
         E: Execute<'q, Self::Database>,
     {
         let sql = query.sql();
-        let mut logger = QueryLogger::new(sql, self.log_settings.clone());
-        let arguments = query.take_arguments();
+        let mut arguments = query.take_arguments();
         let persistent = query.persistent() && arguments.is_some();
+        let limits = query.fetch_limits();
+        let sql = self
+            .middleware
+            .before_execute(sql, "SQLite", arguments.is_some(), persistent);
+        let log_settings = self.log_settings.clone();
 
         Box::pin(try_stream! {
+            let sql = sql?;
+            let mut logger = QueryLogger::new(&sql, log_settings);
+
+            if let Some(arguments) = &mut arguments {
+                logger.bind_values(arguments.values.iter().map(SqliteBindValue));
+                arguments.check_encoded()?;
+            }
+
             let SqliteConnection {
                 handle: ref mut conn,
                 ref mut statements,
                 ref mut statement,
                 ref mut worker,
+                ref mut stats,
+                column_name_matching,
                 ..
             } = self;
 
+            let mut started = Some(Instant::now());
+
             // prepare statement object (or checkout from cache)
-            let stmt = prepare(statements, statement, sql, persistent)?;
+            let stmt = prepare(statements, statement, &sql, persistent)?;
 
             // keep track of how many arguments we have bound
             let mut num_arguments = 0;
@@ -101,7 +149,14 @@ impl<'c> Executor<'c> for &'c mut SqliteConnection {
 
                     // invoke [sqlite3_step] on the dedicated worker thread
                     // this will move us forward one row or finish the statement
-                    let s = worker.step(*stmt).await?;
+                    let s = worker.step(*stmt).await;
+
+                    // the first step is the closest SQLite has to a round trip
+                    if let Some(started) = started.take() {
+                        stats.record_query(started.elapsed());
+                    }
+
+                    let s = s?;
 
                     match s {
                         Either::Left(changes) => {
@@ -120,10 +175,13 @@ impl<'c> Executor<'c> for &'c mut SqliteConnection {
                         }
 
                         Either::Right(()) => {
+                            check_limits(stmt, &limits)?;
+
                             let (row, weak_values_ref) = SqliteRow::current(
                                 *stmt,
                                 columns,
-                                column_names
+                                column_names,
+                                *column_name_matching,
                             );
 
                             let v = Either::Right(row);
@@ -150,21 +208,37 @@ impl<'c> Executor<'c> for &'c mut SqliteConnection {
         E: Execute<'q, Self::Database>,
     {
         let sql = query.sql();
-        let mut logger = QueryLogger::new(sql, self.log_settings.clone());
-        let arguments = query.take_arguments();
+        let mut arguments = query.take_arguments();
         let persistent = query.persistent() && arguments.is_some();
+        let limits = query.fetch_limits();
+        let sql = self
+            .middleware
+            .before_execute(sql, "SQLite", arguments.is_some(), persistent);
+        let log_settings = self.log_settings.clone();
 
         Box::pin(async move {
+            let sql = sql?;
+            let mut logger = QueryLogger::new(&sql, log_settings);
+
+            if let Some(arguments) = &mut arguments {
+                logger.bind_values(arguments.values.iter().map(SqliteBindValue));
+                arguments.check_encoded()?;
+            }
+
             let SqliteConnection {
                 handle: ref mut conn,
                 ref mut statements,
                 ref mut statement,
                 ref mut worker,
+                ref mut stats,
+                column_name_matching,
                 ..
             } = self;
 
+            let mut started = Some(Instant::now());
+
             // prepare statement object (or checkout from cache)
-            let virtual_stmt = prepare(statements, statement, sql, persistent)?;
+            let virtual_stmt = prepare(statements, statement, &sql, persistent)?;
 
             // keep track of how many arguments we have bound
             let mut num_arguments = 0;
@@ -181,12 +255,23 @@ impl<'c> Executor<'c> for &'c mut SqliteConnection {
 
                 // invoke [sqlite3_step] on the dedicated worker thread
                 // this will move us forward one row or finish the statement
-                match worker.step(*stmt).await? {
+                let s = worker.step(*stmt).await;
+
+                if let Some(started) = started.take() {
+                    stats.record_query(started.elapsed());
+                }
+
+                match s? {
                     Either::Left(_) => (),
 
                     Either::Right(()) => {
+                        if let Err(error) = check_limits(stmt, &limits) {
+                            virtual_stmt.reset();
+                            return Err(error);
+                        }
+
                         let (row, weak_values_ref) =
-                            SqliteRow::current(*stmt, columns, column_names);
+                            SqliteRow::current(*stmt, columns, column_names, *column_name_matching);
 
                         *last_row_values = Some(weak_values_ref);
 
@@ -1,14 +1,20 @@
+use crate::column::ColumnNameMatching;
 use crate::common::StatementCache;
-use crate::connection::{Connection, LogSettings};
+use crate::connection::{Connection, ConnectionStats, LogSettings, StatsCollector};
+use crate::database::{Capabilities, Database};
 use crate::error::Error;
+use crate::events::{CloseReason, ConnectionEvents};
+use crate::middleware::Middleware;
 use crate::sqlite::statement::{StatementWorker, VirtualStatement};
 use crate::sqlite::{Sqlite, SqliteConnectOptions};
 use crate::transaction::Transaction;
 use futures_core::future::BoxFuture;
 use futures_util::future;
-use libsqlite3_sys::sqlite3;
+use libsqlite3_sys::{sqlite3, sqlite3_libversion_number};
 use std::cmp::Ordering;
 use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::time::Duration;
 
 mod collation;
 mod describe;
@@ -33,7 +39,16 @@ pub struct SqliteConnection {
     // most recent non-persistent statement
     pub(crate) statement: Option<VirtualStatement>,
 
+    // how the rows read on the connection match column names
+    pub(crate) column_name_matching: ColumnNameMatching,
+
+    stats: StatsCollector,
+
     log_settings: LogSettings,
+    middleware: Middleware,
+
+    // the listener of connection events, told when the connection is closed
+    pub(crate) events: ConnectionEvents,
 }
 
 impl SqliteConnection {
@@ -62,13 +77,20 @@ impl Connection for SqliteConnection {
 
     type Options = SqliteConnectOptions;
 
-    fn close(self) -> BoxFuture<'static, Result<(), Error>> {
+    fn close(mut self) -> BoxFuture<'static, Result<(), Error>> {
+        let events = mem::take(&mut self.events);
+
         // nothing explicit to do; connection will close in drop
+        drop(self);
+        events.closed(CloseReason::Explicit);
+
         Box::pin(future::ok(()))
     }
 
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         // For SQLite connections, PING does effectively nothing
+        self.stats.record_ping(Duration::from_secs(0));
+
         Box::pin(future::ok(()))
     }
 
@@ -79,6 +101,25 @@ impl Connection for SqliteConnection {
         Transaction::begin(self)
     }
 
+    fn stats(&self) -> ConnectionStats {
+        // there is no stream to count bytes on
+        let mut stats = self.stats.snapshot(0, 0);
+        stats.statement_cache = self.statements.stats();
+
+        stats
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let mut capabilities = Sqlite::CAPABILITIES;
+
+        // `RETURNING` was added in SQLite 3.35.0
+        if unsafe { sqlite3_libversion_number() } >= 3_035_000 {
+            capabilities |= Capabilities::RETURNING;
+        }
+
+        capabilities
+    }
+
     fn cached_statements_size(&self) -> usize {
         self.statements.len()
     }
@@ -90,6 +131,41 @@ impl Connection for SqliteConnection {
         })
     }
 
+    fn prepare_pinned<'e>(&'e mut self, sql: &'e str) -> BoxFuture<'e, Result<(), Error>> {
+        Box::pin(async move {
+            if !self.statements.is_enabled() {
+                return Err(Error::Configuration(
+                    "cannot pin a statement: the statement cache is disabled".into(),
+                ));
+            }
+
+            let SqliteConnection {
+                handle: ref mut conn,
+                ref mut statements,
+                ref mut statement,
+                ..
+            } = self;
+
+            // compile each of the statements in `sql`, so that errors surface here
+            let virtual_stmt = executor::prepare(statements, statement, sql, true)?;
+            while virtual_stmt.prepare(conn)?.is_some() {}
+
+            if self.statements.pin(sql) {
+                Ok(())
+            } else {
+                Err(Error::Configuration(
+                    "cannot pin a statement: the statement cache is full of pinned statements"
+                        .into(),
+                ))
+            }
+        })
+    }
+
+    #[doc(hidden)]
+    fn take_events(&mut self) -> ConnectionEvents {
+        mem::take(&mut self.events)
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         // For SQLite, FLUSH does effectively nothing
@@ -1,4 +1,6 @@
-use crate::database::{Database, HasArguments, HasStatement, HasStatementCache, HasValueRef};
+use crate::database::{
+    Capabilities, Database, HasArguments, HasStatement, HasStatementCache, HasValueRef,
+};
 use crate::sqlite::{
     SqliteArgumentValue, SqliteArguments, SqliteColumn, SqliteConnection, SqliteQueryResult,
     SqliteRow, SqliteStatement, SqliteTransactionManager, SqliteTypeInfo, SqliteValue,
@@ -23,6 +25,17 @@ impl Database for Sqlite {
     type TypeInfo = SqliteTypeInfo;
 
     type Value = SqliteValue;
+
+    const CAPABILITIES: Capabilities = Capabilities::from_bits_truncate(
+        Capabilities::SAVEPOINTS.bits()
+            | Capabilities::MULTIPLE_RESULT_SETS.bits()
+            | Capabilities::DOLLAR_PLACEHOLDERS.bits()
+            | Capabilities::QUESTION_MARK_PLACEHOLDERS.bits(),
+    );
+
+    const MAX_BIND_PARAMETERS: usize = 32766;
+
+    const MAX_IDENTIFIER_LENGTH: Option<usize> = None;
 }
 
 impl<'r> HasValueRef<'r> for Sqlite {
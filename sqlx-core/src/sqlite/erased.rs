@@ -0,0 +1,57 @@
+use crate::column::Column;
+use crate::erased::{unsupported_column, ErasedDatabase, ErasedQueryResult, ErasedValue};
+use crate::error::Error;
+use crate::row::Row;
+use crate::sqlite::type_info::DataType;
+use crate::sqlite::{Sqlite, SqliteArguments, SqliteQueryResult, SqliteRow};
+use crate::value::ValueRef;
+
+impl ErasedDatabase for Sqlite {
+    fn add_erased(arguments: &mut SqliteArguments<'_>, value: ErasedValue) -> Result<(), Error> {
+        match value {
+            ErasedValue::Null => arguments.add(None::<i64>),
+            ErasedValue::Bool(v) => arguments.add(v),
+            ErasedValue::Int(v) => arguments.add(v),
+            ErasedValue::Float(v) => arguments.add(v),
+            ErasedValue::Text(v) => arguments.add(v),
+            ErasedValue::Bytes(v) => arguments.add(v),
+        }
+
+        Ok(())
+    }
+
+    fn get_erased(row: &SqliteRow, index: usize) -> Result<ErasedValue, Error> {
+        let value = row.try_get_raw(index)?;
+
+        if value.is_null() {
+            return Ok(ErasedValue::Null);
+        }
+
+        // the storage class of the value decides, as SQLite does not enforce the declared types;
+        // only an integer in a column declared as BOOLEAN is taken as a boolean
+        Ok(match value.type_info().0 {
+            DataType::Int | DataType::Int64
+                if matches!(row.columns()[index].type_info().0, DataType::Bool) =>
+            {
+                ErasedValue::Bool(row.try_get_unchecked(index)?)
+            }
+
+            DataType::Int | DataType::Int64 | DataType::Bool => {
+                ErasedValue::Int(row.try_get_unchecked(index)?)
+            }
+
+            DataType::Float => ErasedValue::Float(row.try_get_unchecked(index)?),
+            DataType::Text => ErasedValue::Text(row.try_get_unchecked(index)?),
+            DataType::Blob => ErasedValue::Bytes(row.try_get_unchecked(index)?),
+
+            _ => return Err(unsupported_column(row, index)),
+        })
+    }
+
+    fn erase_query_result(result: SqliteQueryResult) -> ErasedQueryResult {
+        ErasedQueryResult {
+            rows_affected: result.rows_affected(),
+            last_insert_id: Some(result.last_insert_rowid()),
+        }
+    }
+}
@@ -4,7 +4,9 @@ use std::fmt::{self, Display, Formatter};
 use std::os::raw::c_int;
 use std::{borrow::Cow, str::from_utf8_unchecked};
 
-use libsqlite3_sys::{sqlite3, sqlite3_errmsg, sqlite3_extended_errcode};
+use libsqlite3_sys::{
+    sqlite3, sqlite3_errmsg, sqlite3_extended_errcode, SQLITE_BUSY, SQLITE_LOCKED,
+};
 
 use crate::error::DatabaseError;
 
@@ -71,4 +73,30 @@ impl DatabaseError for SqliteError {
     fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
         self
     }
+
+    fn is_connection_error(&self) -> bool {
+        false
+    }
+
+    fn is_retryable(&self) -> bool {
+        // the primary result code is the low byte of the extended one
+        matches!(self.code & 0xff, SQLITE_BUSY | SQLITE_LOCKED)
+    }
+}
+
+#[test]
+fn it_retries_busy_and_locked_databases() {
+    let error = |code| SqliteError {
+        code,
+        message: String::new(),
+    };
+
+    // SQLITE_BUSY, SQLITE_BUSY_SNAPSHOT, SQLITE_LOCKED
+    assert!(error(5).is_retryable());
+    assert!(error(517).is_retryable());
+    assert!(error(6).is_retryable());
+
+    // SQLITE_CONSTRAINT_UNIQUE
+    assert!(!error(2067).is_retryable());
+    assert!(!error(5).is_connection_error());
 }
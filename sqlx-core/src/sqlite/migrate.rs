@@ -1,6 +1,5 @@
 use crate::connection::{ConnectOptions, Connection};
 use crate::error::Error;
-use crate::executor::Executor;
 use crate::migrate::MigrateError;
 use crate::migrate::Migration;
 use crate::migrate::{Migrate, MigrateDatabase};
@@ -50,6 +49,12 @@ impl MigrateDatabase for Sqlite {
             Ok(())
         })
     }
+
+    fn force_drop_database(uri: &str) -> BoxFuture<'_, Result<(), Error>> {
+        // other connections to the file keep it open until they close, as they do after
+        // `drop_database`
+        Self::drop_database(uri)
+    }
 }
 
 impl Migrate for SqliteConnection {
@@ -124,12 +129,15 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            if migration.no_tx {
+                let _ = self.execute(&*migration.sql).await?;
+            } else {
+                let mut tx = self.begin().await?;
+                let _ = tx.execute(&*migration.sql).await?;
+                tx.commit().await?;
+            }
 
             let elapsed = start.elapsed();
 
@@ -156,12 +164,15 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            if migration.no_tx {
+                let _ = self.execute(&*migration.sql).await?;
+            } else {
+                let mut tx = self.begin().await?;
+                let _ = tx.execute(&*migration.sql).await?;
+                tx.commit().await?;
+            }
 
             let elapsed = start.elapsed();
 
@@ -9,9 +9,11 @@ mod arguments;
 mod column;
 mod connection;
 mod database;
+mod erased;
 mod error;
 mod options;
 mod query_result;
+mod quote;
 mod row;
 mod statement;
 mod transaction;
@@ -29,6 +31,7 @@ pub use database::Sqlite;
 pub use error::SqliteError;
 pub use options::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
 pub use query_result::SqliteQueryResult;
+pub use quote::{quote_identifier, quote_qualified};
 pub use row::SqliteRow;
 pub use statement::SqliteStatement;
 pub use transaction::SqliteTransactionManager;
@@ -52,3 +55,6 @@ impl_into_maybe_pool!(Sqlite, SqliteConnection);
 
 // required because some databases have a different handling of NULL
 impl_encode_for_option!(Sqlite);
+
+#[cfg(test)]
+assert_driver_futures_are_send!(Sqlite, SqliteConnection);
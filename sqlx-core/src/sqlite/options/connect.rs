@@ -1,10 +1,12 @@
-use crate::connection::ConnectOptions;
+use crate::connection::{BindLogging, ConnectOptions};
 use crate::error::Error;
-use crate::executor::Executor;
+use crate::events::{ConnectionEventListener, ConnectionEvents, EventListener};
+use crate::middleware::{Middleware, QueryMiddleware};
 use crate::sqlite::connection::establish::establish;
 use crate::sqlite::{SqliteConnectOptions, SqliteConnection};
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
+use std::sync::Arc;
 use std::time::Duration;
 
 impl ConnectOptions for SqliteConnectOptions {
@@ -15,19 +17,19 @@ impl ConnectOptions for SqliteConnectOptions {
         Self::Connection: Sized,
     {
         Box::pin(async move {
-            let mut conn = establish(self).await?;
+            match set_up(self).await {
+                Ok(conn) => {
+                    conn.events.connected();
 
-            // send an initial sql statement comprised of options
-            let init = format!(
-                "PRAGMA journal_mode = {}; PRAGMA foreign_keys = {}; PRAGMA synchronous = {}",
-                self.journal_mode.as_str(),
-                if self.foreign_keys { "ON" } else { "OFF" },
-                self.synchronous.as_str(),
-            );
+                    Ok(conn)
+                }
 
-            conn.execute(&*init).await?;
+                Err(error) => {
+                    ConnectionEvents::new(&self.events, self.connection_info()).error(&error);
 
-            Ok(conn)
+                    Err(error)
+                }
+            }
         })
     }
 
@@ -40,4 +42,35 @@ impl ConnectOptions for SqliteConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn log_bind_values(&mut self, logging: BindLogging) -> &mut Self {
+        self.log_settings.log_bind_values(logging);
+        self
+    }
+
+    fn query_middleware(&mut self, middleware: Arc<dyn QueryMiddleware>) -> &mut Self {
+        self.middleware = Middleware(Some(middleware));
+        self
+    }
+
+    fn connection_events(&mut self, listener: Arc<dyn ConnectionEventListener>) -> &mut Self {
+        self.events = EventListener(Some(listener));
+        self
+    }
+}
+
+async fn set_up(options: &SqliteConnectOptions) -> Result<SqliteConnection, Error> {
+    let mut conn = establish(options).await?;
+
+    // send an initial sql statement comprised of options
+    let init = format!(
+        "PRAGMA journal_mode = {}; PRAGMA foreign_keys = {}; PRAGMA synchronous = {}",
+        options.journal_mode.as_str(),
+        if options.foreign_keys { "ON" } else { "OFF" },
+        options.synchronous.as_str(),
+    );
+
+    conn.execute(&*init).await?;
+
+    Ok(conn)
 }
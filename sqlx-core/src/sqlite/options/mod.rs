@@ -5,7 +5,10 @@ mod journal_mode;
 mod parse;
 mod synchronous;
 
+use crate::column::ColumnNameMatching;
 use crate::connection::LogSettings;
+use crate::events::{ConnectionInfo, EventListener};
+use crate::middleware::Middleware;
 pub use journal_mode::SqliteJournalMode;
 use std::{borrow::Cow, time::Duration};
 pub use synchronous::SqliteSynchronous;
@@ -53,8 +56,12 @@ pub struct SqliteConnectOptions {
     pub(crate) foreign_keys: bool,
     pub(crate) shared_cache: bool,
     pub(crate) statement_cache_capacity: usize,
+    pub(crate) statement_cache_max_bytes: Option<usize>,
     pub(crate) busy_timeout: Duration,
+    pub(crate) column_name_matching: ColumnNameMatching,
     pub(crate) log_settings: LogSettings,
+    pub(crate) middleware: Middleware,
+    pub(crate) events: EventListener,
     pub(crate) synchronous: SqliteSynchronous,
 }
 
@@ -74,13 +81,25 @@ impl SqliteConnectOptions {
             foreign_keys: true,
             shared_cache: false,
             statement_cache_capacity: 100,
+            statement_cache_max_bytes: None,
             journal_mode: SqliteJournalMode::Wal,
             busy_timeout: Duration::from_secs(5),
+            column_name_matching: ColumnNameMatching::default(),
             log_settings: Default::default(),
+            middleware: Default::default(),
+            events: Default::default(),
             synchronous: SqliteSynchronous::Full,
         }
     }
 
+    // what a `ConnectionEventListener` is told about a connection made with these options
+    pub(crate) fn connection_info(&self) -> ConnectionInfo {
+        ConnectionInfo {
+            peer_addr: Some(self.filename.display().to_string()),
+            ..ConnectionInfo::new("SQLite")
+        }
+    }
+
     /// Sets the name of the database file.
     pub fn filename(mut self, filename: impl AsRef<Path>) -> Self {
         self.filename = Cow::Owned(filename.as_ref().to_owned());
@@ -111,6 +130,16 @@ impl SqliteConnectOptions {
         self
     }
 
+    /// Sets how a column name given to [`Row::try_get`], or read by `#[derive(FromRow)]`, is
+    /// matched when no column has exactly that name. By default only a column with exactly the
+    /// name is found; see [`ColumnNameMatching`] for the looser comparisons.
+    ///
+    /// [`Row::try_get`]: crate::row::Row::try_get
+    pub fn column_name_matching(mut self, matching: ColumnNameMatching) -> Self {
+        self.column_name_matching = matching;
+        self
+    }
+
     /// Sets the [access mode](https://www.sqlite.org/c3ref/open.html) to create the database file
     /// if the file does not exist.
     ///
@@ -131,6 +160,18 @@ impl SqliteConnectOptions {
         self
     }
 
+    /// Bounds the connection's statement cache by the estimated memory of the statements,
+    /// their SQL and the metadata describing their parameters and columns, instead of by their
+    /// number. Statements that take much more room than others, such as those returning
+    /// many columns, then count for more.
+    ///
+    /// This replaces the bound set with
+    /// [`statement_cache_capacity`](Self::statement_cache_capacity).
+    pub fn statement_cache_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.statement_cache_max_bytes = Some(max_bytes);
+        self
+    }
+
     /// Sets a timeout value to wait when the database is locked, before
     /// returning a busy timeout error.
     ///
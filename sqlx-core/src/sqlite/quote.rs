@@ -0,0 +1,43 @@
+use crate::error::Error;
+
+/// Quotes `name` so it can be written into SQL as an identifier, e.g. a table name that comes
+/// from configuration.
+///
+/// The name is wrapped in double quotes, with any double quote inside it doubled. Empty names
+/// and names containing a NUL byte are rejected.
+///
+/// # Example
+///
+/// ```rust
+/// # use sqlx_core::sqlite::quote_identifier;
+/// assert_eq!(quote_identifier("my table")?, r#""my table""#);
+/// # Ok::<(), sqlx_core::error::Error>(())
+/// ```
+pub fn quote_identifier(name: &str) -> Result<String, Error> {
+    crate::common::quote_identifier(name, '"', '"')
+}
+
+/// Quotes `schema` and `name` as a schema-qualified identifier, e.g. `"aux"."users"` for a table
+/// in an attached database.
+///
+/// See [`quote_identifier`] for how each part is quoted.
+pub fn quote_qualified(schema: &str, name: &str) -> Result<String, Error> {
+    Ok(format!(
+        "{}.{}",
+        quote_identifier(schema)?,
+        quote_identifier(name)?
+    ))
+}
+
+#[test]
+fn it_quotes_identifiers() -> Result<(), Error> {
+    assert_eq!(quote_identifier("my table")?, r#""my table""#);
+    assert_eq!(quote_identifier(r#"a"b"#)?, r#""a""b""#);
+    assert_eq!(quote_identifier("ünïcødé")?, r#""ünïcødé""#);
+    assert_eq!(quote_qualified("main", "t")?, r#""main"."t""#);
+
+    assert!(quote_identifier("").is_err());
+    assert!(quote_identifier("a\0b").is_err());
+
+    Ok(())
+}
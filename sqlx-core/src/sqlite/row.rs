@@ -7,7 +7,7 @@ use std::sync::{Arc, Weak};
 
 use crate::HashMap;
 
-use crate::column::ColumnIndex;
+use crate::column::{ColumnIndex, ColumnNameMatching};
 use crate::error::Error;
 use crate::ext::ustr::UStr;
 use crate::row::Row;
@@ -30,9 +30,14 @@ pub struct SqliteRow {
 
     pub(crate) columns: Arc<Vec<SqliteColumn>>,
     pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+    pub(crate) column_name_matching: ColumnNameMatching,
 }
 
-impl crate::row::private_row::Sealed for SqliteRow {}
+impl crate::row::private_row::Sealed for SqliteRow {
+    fn column_name_matching(&self) -> ColumnNameMatching {
+        self.column_name_matching
+    }
+}
 
 // Accessing values from the statement object is
 // safe across threads as long as we don't call [sqlite3_step]
@@ -51,6 +56,7 @@ impl SqliteRow {
         statement: StatementHandle,
         columns: &Arc<Vec<SqliteColumn>>,
         column_names: &Arc<HashMap<UStr, usize>>,
+        column_name_matching: ColumnNameMatching,
     ) -> (Self, Weak<AtomicPtr<SqliteValue>>) {
         let values = Arc::new(AtomicPtr::new(null_mut()));
         let weak_values = Arc::downgrade(&values);
@@ -62,6 +68,7 @@ impl SqliteRow {
             num_values: size,
             columns: Arc::clone(columns),
             column_names: Arc::clone(column_names),
+            column_name_matching,
         };
 
         (row, weak_values)
@@ -149,10 +156,11 @@ impl Drop for SqliteRow {
 
 impl ColumnIndex<SqliteRow> for &'_ str {
     fn index(&self, row: &SqliteRow) -> Result<usize, Error> {
-        row.column_names
-            .get(*self)
-            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
-            .map(|v| *v)
+        row.column_name_matching.find(
+            self,
+            row.column_names.get(*self).copied(),
+            row.columns.iter().map(|column| &*column.name),
+        )
     }
 }
 
@@ -260,6 +260,12 @@ impl StatementHandle {
         unsafe { sqlite3_column_value(self.0.as_ptr(), index as c_int) }
     }
 
+    // the size in bytes of a TEXT or BLOB value; any other is converted to text first
+    #[inline]
+    pub(crate) fn column_bytes(&self, index: usize) -> usize {
+        unsafe { sqlite3_column_bytes(self.0.as_ptr(), index as c_int) as usize }
+    }
+
     pub(crate) fn column_blob(&self, index: usize) -> &[u8] {
         let index = index as c_int;
         let len = unsafe { sqlite3_column_bytes(self.0.as_ptr(), index) } as usize;
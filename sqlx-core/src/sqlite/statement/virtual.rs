@@ -156,7 +156,8 @@ impl VirtualStatement {
                         type_info,
                     });
 
-                    column_names.insert(name, i);
+                    // a duplicated name refers to its first column
+                    column_names.entry(name).or_insert(i);
                 }
 
                 self.handles.push(statement);
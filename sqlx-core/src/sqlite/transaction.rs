@@ -4,7 +4,6 @@ use futures_core::future::BoxFuture;
 use libsqlite3_sys::{sqlite3_exec, SQLITE_OK};
 
 use crate::error::Error;
-use crate::executor::Executor;
 use crate::sqlite::{Sqlite, SqliteConnection, SqliteError};
 use crate::transaction::{
     begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
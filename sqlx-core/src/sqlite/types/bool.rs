@@ -1,5 +1,5 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::sqlite::type_info::DataType;
 use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
@@ -16,14 +16,19 @@ impl Type<Sqlite> for bool {
 }
 
 impl<'q> Encode<'q, Sqlite> for bool {
-    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<SqliteArgumentValue<'q>>,
+    ) -> Result<IsNull, EncodeError> {
         args.push(SqliteArgumentValue::Int((*self).into()));
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl<'r> Decode<'r, Sqlite> for bool {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: SqliteValueRef<'r>) -> Result<bool, BoxDynError> {
         Ok(value.int() != 0)
     }
@@ -1,7 +1,8 @@
 use std::borrow::Cow;
+use std::convert::TryFrom;
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::sqlite::type_info::DataType;
 use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
@@ -18,10 +19,13 @@ impl Type<Sqlite> for [u8] {
 }
 
 impl<'q> Encode<'q, Sqlite> for &'q [u8] {
-    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<SqliteArgumentValue<'q>>,
+    ) -> Result<IsNull, EncodeError> {
         args.push(SqliteArgumentValue::Blob(Cow::Borrowed(self)));
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
@@ -42,16 +46,19 @@ impl Type<Sqlite> for Vec<u8> {
 }
 
 impl<'q> Encode<'q, Sqlite> for Vec<u8> {
-    fn encode(self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+    fn encode(self, args: &mut Vec<SqliteArgumentValue<'q>>) -> Result<IsNull, EncodeError> {
         args.push(SqliteArgumentValue::Blob(Cow::Owned(self)));
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 
-    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<SqliteArgumentValue<'q>>,
+    ) -> Result<IsNull, EncodeError> {
         args.push(SqliteArgumentValue::Blob(Cow::Owned(self.clone())));
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
@@ -60,3 +67,34 @@ impl<'r> Decode<'r, Sqlite> for Vec<u8> {
         Ok(value.blob().to_owned())
     }
 }
+
+impl<const N: usize> Type<Sqlite> for [u8; N] {
+    fn type_info() -> SqliteTypeInfo {
+        <&[u8] as Type<Sqlite>>::type_info()
+    }
+
+    fn compatible(ty: &SqliteTypeInfo) -> bool {
+        <&[u8] as Type<Sqlite>>::compatible(ty)
+    }
+}
+
+// a borrowed array is copied by the blanket impl for references; bind `&array[..]` to borrow it
+impl<'q, const N: usize> Encode<'q, Sqlite> for [u8; N] {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<SqliteArgumentValue<'q>>,
+    ) -> Result<IsNull, EncodeError> {
+        args.push(SqliteArgumentValue::Blob(Cow::Owned(self.to_vec())));
+
+        Ok(IsNull::No)
+    }
+}
+
+impl<'r, const N: usize> Decode<'r, Sqlite> for [u8; N] {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        let bytes = value.blob();
+
+        <[u8; N]>::try_from(bytes)
+            .map_err(|_| format!("expected {} bytes, got {}", N, bytes.len()).into())
+    }
+}
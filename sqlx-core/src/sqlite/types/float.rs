@@ -1,5 +1,5 @@
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::sqlite::type_info::DataType;
 use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
@@ -12,14 +12,19 @@ impl Type<Sqlite> for f32 {
 }
 
 impl<'q> Encode<'q, Sqlite> for f32 {
-    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<SqliteArgumentValue<'q>>,
+    ) -> Result<IsNull, EncodeError> {
         args.push(SqliteArgumentValue::Double((*self).into()));
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl<'r> Decode<'r, Sqlite> for f32 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: SqliteValueRef<'r>) -> Result<f32, BoxDynError> {
         Ok(value.double() as f32)
     }
@@ -32,14 +37,19 @@ impl Type<Sqlite> for f64 {
 }
 
 impl<'q> Encode<'q, Sqlite> for f64 {
-    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<SqliteArgumentValue<'q>>,
+    ) -> Result<IsNull, EncodeError> {
         args.push(SqliteArgumentValue::Double(*self));
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl<'r> Decode<'r, Sqlite> for f64 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: SqliteValueRef<'r>) -> Result<f64, BoxDynError> {
         Ok(value.double())
     }
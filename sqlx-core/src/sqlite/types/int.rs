@@ -1,7 +1,7 @@
 use std::convert::TryInto;
 
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::sqlite::type_info::DataType;
 use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
@@ -18,14 +18,19 @@ impl Type<Sqlite> for i8 {
 }
 
 impl<'q> Encode<'q, Sqlite> for i8 {
-    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<SqliteArgumentValue<'q>>,
+    ) -> Result<IsNull, EncodeError> {
         args.push(SqliteArgumentValue::Int(*self as i32));
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl<'r> Decode<'r, Sqlite> for i8 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
         Ok(value.int().try_into()?)
     }
@@ -42,14 +47,19 @@ impl Type<Sqlite> for i16 {
 }
 
 impl<'q> Encode<'q, Sqlite> for i16 {
-    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<SqliteArgumentValue<'q>>,
+    ) -> Result<IsNull, EncodeError> {
         args.push(SqliteArgumentValue::Int(*self as i32));
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl<'r> Decode<'r, Sqlite> for i16 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
         Ok(value.int().try_into()?)
     }
@@ -66,14 +76,19 @@ impl Type<Sqlite> for i32 {
 }
 
 impl<'q> Encode<'q, Sqlite> for i32 {
-    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<SqliteArgumentValue<'q>>,
+    ) -> Result<IsNull, EncodeError> {
         args.push(SqliteArgumentValue::Int(*self));
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl<'r> Decode<'r, Sqlite> for i32 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
         Ok(value.int())
     }
@@ -90,14 +105,19 @@ impl Type<Sqlite> for i64 {
 }
 
 impl<'q> Encode<'q, Sqlite> for i64 {
-    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<SqliteArgumentValue<'q>>,
+    ) -> Result<IsNull, EncodeError> {
         args.push(SqliteArgumentValue::Int64(*self));
 
-        IsNull::No
+        Ok(IsNull::No)
     }
 }
 
 impl<'r> Decode<'r, Sqlite> for i64 {
+    const CHEAP_TO_DECODE: bool = true;
+
     fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
         Ok(value.int64())
     }
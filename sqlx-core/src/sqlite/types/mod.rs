@@ -16,7 +16,7 @@
 //! | `f32`                                 | REAL                                                 |
 //! | `f64`                                 | REAL                                                 |
 //! | `&str`, [`String`]                    | TEXT                                                 |
-//! | `&[u8]`, `Vec<u8>`                    | BLOB                                                 |
+//! | `&[u8]`, `Vec<u8>`, `[u8; N]`         | BLOB                                                 |
 //!
 //! ### [`chrono`](https://crates.io/crates/chrono)
 //!
@@ -0,0 +1,93 @@
+//! Utilities for testing code built on SQLx.
+//!
+//! The pool takes the current time from the runtime, so its timeouts can be tested with
+//! [`VirtualTime`] without waiting for them to pass:
+//!
+//! ```rust,ignore
+//! use std::time::Duration;
+//! use sqlx::memory::MemoryPoolOptions;
+//! use sqlx::testing::VirtualTime;
+//!
+//! #[test]
+//! fn it_closes_idle_connections() {
+//!     VirtualTime::new().block_on(async {
+//!         let pool = MemoryPoolOptions::new()
+//!             .idle_timeout(Duration::from_secs(60))
+//!             .connect("memory:")
+//!             .await
+//!             .unwrap();
+//!
+//!         drop(pool.acquire().await.unwrap());
+//!
+//!         // an hour passes in no time at all
+//!         VirtualTime::advance(Duration::from_secs(60 * 60)).await;
+//!
+//!         assert_eq!(pool.size(), 0);
+//!     });
+//! }
+//! ```
+//!
+//! The same goes for retries and timeouts in your own code, as long as they wait with the
+//! timers of Tokio and measure time with `tokio::time::Instant` rather than `std::time::Instant`.
+//!
+//! The clock does not stand entirely still: whenever every task is waiting, it skips ahead to
+//! the next timer. Time then passes for any I/O too, so a timeout can expire while waiting for
+//! a reply from a database server. Use the `memory` driver, or another that answers without
+//! I/O, for anything that depends on a timeout not expiring.
+
+use std::future::Future;
+use std::time::Duration;
+
+use sqlx_rt::tokio::runtime::{self, Runtime};
+use sqlx_rt::tokio::time;
+
+/// A Tokio runtime whose clock only moves when told to, or when every task is waiting on a
+/// timer.
+///
+/// Futures are run on a single thread, as with `#[tokio::test]`.
+pub struct VirtualTime {
+    runtime: Runtime,
+}
+
+impl VirtualTime {
+    /// Creates the runtime, with its clock frozen at the current time.
+    ///
+    /// ### Panics
+    /// If the runtime cannot be created.
+    pub fn new() -> Self {
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to initialize Tokio runtime");
+
+        {
+            let _guard = runtime.enter();
+            time::pause();
+        }
+
+        Self { runtime }
+    }
+
+    /// Runs a future to completion on this runtime.
+    ///
+    /// ### Panics
+    /// If called from within another runtime.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Moves the clock forward by `duration`, running the timers that come due before then, and
+    /// the tasks they wake, in order.
+    ///
+    /// On any other runtime, this sleeps for `duration`.
+    pub async fn advance(duration: Duration) {
+        // with the clock frozen, sleeping skips from one timer to the next
+        time::sleep(duration).await;
+    }
+}
+
+impl Default for VirtualTime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -46,6 +46,24 @@ pub trait TransactionManager {
 /// executed after it was established to be rolled back, restoring the transaction state to
 /// what it was at the time of the savepoint.
 ///
+/// # Owned transactions
+///
+/// A transaction begun with [`Pool::begin`] owns the connection it was given by the pool, so it
+/// is a `Transaction<'static, DB>`: it is `Send` and can be moved into a spawned task, to be
+/// committed or rolled back there. Either returns the connection to the pool; so does dropping
+/// the transaction, after the rollback it starts has run on the connection. A transaction
+/// begun on a `&mut` connection borrows it for as long as it is open instead.
+///
+/// ```rust,ignore
+/// let mut tx = pool.begin().await?;
+/// sqlx::query("INSERT INTO orders (id) VALUES ($1)").bind(id).execute(&mut tx).await?;
+///
+/// tokio::spawn(async move {
+///     notify_warehouse(id).await?;
+///     tx.commit().await
+/// });
+/// ```
+///
 /// [`Connection::begin`]: crate::connection::Connection::begin()
 /// [`Pool::begin`]: crate::pool::Pool::begin()
 /// [`commit`]: Self::commit()
@@ -92,6 +110,37 @@ where
 
         Ok(())
     }
+
+    // for transactions the driver ended some other way, e.g. by preparing them for two-phase
+    // commit, which must not be rolled back on drop
+    #[allow(dead_code)]
+    pub(crate) fn finish(mut self) {
+        self.open = false;
+    }
+}
+
+/// A transaction prepared for two-phase commit, identified by its global transaction id.
+///
+/// Returned from `prepare_two_phase` on a PostgreSQL or MySQL transaction. A prepared
+/// transaction no longer belongs to the connection that prepared it: it survives that
+/// connection closing, and is finished with `commit_prepared` or `rollback_prepared` from any
+/// connection to the same database. Dropping this handle leaves the transaction prepared.
+#[must_use = "the transaction stays prepared until it is committed or rolled back by its id"]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PreparedTransaction {
+    gid: String,
+}
+
+impl PreparedTransaction {
+    #[allow(dead_code)]
+    pub(crate) fn new(gid: String) -> Self {
+        Self { gid }
+    }
+
+    /// The global transaction id the transaction was prepared with.
+    pub fn gid(&self) -> &str {
+        &self.gid
+    }
 }
 
 // NOTE: required due to lack of lazy normalization
@@ -165,6 +214,13 @@ macro_rules! impl_executor_for_transaction {
     };
 }
 
+impl<'c, DB: Database> Transaction<'c, DB>
+where
+    for<'t> &'t mut DB::Connection: crate::executor::Executor<'t, Database = DB>,
+{
+    impl_executor_methods!(DB, &mut self => &mut **self);
+}
+
 impl<'c, DB> Debug for Transaction<'c, DB>
 where
     DB: Database,
@@ -214,6 +270,17 @@ where
     }
 }
 
+#[test]
+#[allow(dead_code)]
+fn assert_owned_transaction_traits() {
+    fn assert_send_static<T: Send + 'static>(_: &T) {}
+
+    fn assert_transaction<DB: Database>(tx: Transaction<'static, DB>) {
+        assert_send_static(&tx);
+        assert_send_static(&tx.commit());
+    }
+}
+
 #[allow(dead_code)]
 pub(crate) fn begin_ansi_transaction_sql(depth: usize) -> Cow<'static, str> {
     if depth == 0 {
@@ -243,3 +310,84 @@ pub(crate) fn rollback_ansi_transaction_sql(depth: usize) -> Cow<'static, str> {
         ))
     }
 }
+
+/// Checks that `name` is a configuration parameter name: one or more identifiers separated by
+/// `.`, e.g. `search_path` or `app.current_tenant`. Names that pass can be written into SQL
+/// without quoting.
+#[allow(dead_code)]
+pub(crate) fn validate_setting_name(name: &str) -> Result<(), Error> {
+    let valid = name.split('.').all(|part| {
+        let mut chars = part.chars();
+
+        match chars.next() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+            }
+
+            _ => false,
+        }
+    });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::encode(format!(
+            "invalid configuration parameter name: {:?}",
+            name
+        )))
+    }
+}
+
+/// Quotes a global transaction id for two-phase commit as a string literal; it cannot be a bind
+/// parameter in the statements that take it.
+///
+/// Backslashes and control characters are rejected instead of escaped, as their meaning in a
+/// literal depends on the settings of the session.
+#[allow(dead_code)]
+pub(crate) fn quote_transaction_id(gid: &str, max_len: usize) -> Result<String, Error> {
+    if gid.is_empty() || gid.len() > max_len {
+        return Err(Error::encode(format!(
+            "transaction id must be between 1 and {} bytes long: {:?}",
+            max_len, gid
+        )));
+    }
+
+    if gid.chars().any(|c| c == '\\' || c.is_control()) {
+        return Err(Error::encode(format!(
+            "transaction id must not contain backslashes or control characters: {:?}",
+            gid
+        )));
+    }
+
+    Ok(format!("'{}'", gid.replace('\'', "''")))
+}
+
+#[test]
+fn it_validates_setting_names() {
+    for name in &["search_path", "app.current_tenant", "_x$1", "a.b.c"] {
+        assert!(validate_setting_name(name).is_ok(), "{}", name);
+    }
+
+    for name in &[
+        "",
+        "1abc",
+        "app.",
+        ".app",
+        "a b",
+        "x; DROP TABLE y",
+        "\"quoted\"",
+    ] {
+        assert!(validate_setting_name(name).is_err(), "{}", name);
+    }
+}
+
+#[test]
+fn it_quotes_transaction_ids() {
+    assert_eq!(quote_transaction_id("tx-1", 200).unwrap(), "'tx-1'");
+    assert_eq!(quote_transaction_id("it's", 200).unwrap(), "'it''s'");
+
+    assert!(quote_transaction_id("", 200).is_err());
+    assert!(quote_transaction_id(&"x".repeat(65), 64).is_err());
+    assert!(quote_transaction_id("a\\'b", 200).is_err());
+    assert!(quote_transaction_id("a\nb", 200).is_err());
+}
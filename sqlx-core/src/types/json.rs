@@ -6,7 +6,7 @@ use serde_json::Value as JsonValue;
 
 use crate::database::{Database, HasArguments, HasValueRef};
 use crate::decode::Decode;
-use crate::encode::{Encode, IsNull};
+use crate::encode::{Encode, EncodeError, IsNull};
 use crate::error::BoxDynError;
 use crate::types::Type;
 
@@ -90,7 +90,10 @@ where
     for<'a> Json<&'a Self>: Encode<'q, DB>,
     DB: Database,
 {
-    fn encode_by_ref(&self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer,
+    ) -> Result<IsNull, EncodeError> {
         <Json<&Self> as Encode<'q, DB>>::encode(Json(self), buf)
     }
 }
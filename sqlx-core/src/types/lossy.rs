@@ -0,0 +1,48 @@
+use std::ops::{Deref, DerefMut};
+
+/// Opts in to decoding a value into a Rust type that cannot always represent it exactly.
+///
+/// By default a column is only decoded into a type that can hold every value of the column, so
+/// e.g. a Postgres `NUMERIC` cannot be read as `f64`. With `Lossy<f64>` it can, and the value is
+/// rounded to the nearest `f64`. See the `types` module of each database for the conversions
+/// it supports and how each one rounds or fails.
+///
+/// ```rust,ignore
+/// let total: Lossy<f64> = row.try_get("total")?;
+/// let total: f64 = total.0;
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct Lossy<T>(pub T);
+
+impl<T> Lossy<T> {
+    /// Returns the decoded value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Lossy<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Lossy<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> AsRef<T> for Lossy<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> AsMut<T> for Lossy<T> {
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
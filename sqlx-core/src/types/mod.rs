@@ -71,6 +71,15 @@ pub mod ipnetwork {
 #[cfg(feature = "json")]
 pub use json::Json;
 
+mod lossy;
+// also holds the calendar the drivers read and write `SystemTime` with
+pub(crate) mod unix_time;
+
+pub use lossy::Lossy;
+pub use unix_time::{TimestampOutOfRange, UnixMillis, UnixTimestamp};
+
+pub use crate::ext::ustr::UStr;
+
 /// Indicates that a SQL type is supported for a database.
 ///
 /// ## Compile-time verification
@@ -149,6 +158,19 @@ pub use json::Json;
 /// }
 /// ```
 ///
+/// ## In other crates
+///
+/// A library that defines its own types can implement `Type`, [`Encode`](crate::encode::Encode)
+/// and [`Decode`](crate::decode::Decode) for them without the derives, which is a supported
+/// API: the drivers export what an implementation needs, such as
+/// [`PgArgumentBuffer`](crate::postgres::PgArgumentBuffer),
+/// [`PgValueRef`](crate::postgres::PgValueRef),
+/// [`PgTypeInfo::with_name`](crate::postgres::PgTypeInfo::with_name) for types whose OID is
+/// only known to the database, and [`MySqlValueRef`](crate::mysql::MySqlValueRef). A type that
+/// is stored as another can have all three implemented with [`declare_type!`](crate::declare_type).
+///
+/// The crate `examples/extension-type` in the repository does both, with tests.
+///
 pub trait Type<DB: Database> {
     /// Returns the canonical SQL type for this Rust type.
     ///
@@ -192,3 +214,158 @@ impl<T: Type<DB>, DB: Database> Type<DB> for Option<T> {
         <T as Type<DB>>::compatible(ty)
     }
 }
+
+/// Implements [`Type`], [`Encode`](crate::encode::Encode) and [`Decode`](crate::decode::Decode)
+/// for a type that is stored in the database as another, which SQLx supports already.
+///
+/// `declare_type!(DB, T as Inner)` encodes a `T` as the `Inner` that `T: AsRef<Inner>` returns,
+/// and decodes it from an `Inner` with `T: TryFrom<Inner>`, whose error becomes the error of
+/// the decode. The SQL type is that of `Inner`. This is what `#[derive(sqlx::Type)]` with
+/// `#[sqlx(transparent)]` does for a newtype, for code that does not use the derives or for
+/// types that are not newtypes.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "postgres")] {
+/// use std::convert::TryFrom;
+///
+/// /// An email address, which is checked when read from the database.
+/// pub struct Email(String);
+///
+/// impl AsRef<String> for Email {
+///     fn as_ref(&self) -> &String {
+///         &self.0
+///     }
+/// }
+///
+/// impl TryFrom<String> for Email {
+///     type Error = String;
+///
+///     fn try_from(address: String) -> Result<Self, String> {
+///         if address.contains('@') {
+///             Ok(Email(address))
+///         } else {
+///             Err(format!("not an email address: {:?}", address))
+///         }
+///     }
+/// }
+///
+/// sqlx_core::declare_type!(sqlx_core::postgres::Postgres, Email as String);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! declare_type {
+    ($db:ty, $ty:ty as $inner:ty $(,)?) => {
+        impl $crate::types::Type<$db> for $ty {
+            fn type_info() -> <$db as $crate::database::Database>::TypeInfo {
+                <$inner as $crate::types::Type<$db>>::type_info()
+            }
+
+            fn compatible(ty: &<$db as $crate::database::Database>::TypeInfo) -> bool {
+                <$inner as $crate::types::Type<$db>>::compatible(ty)
+            }
+        }
+
+        impl<'q> $crate::encode::Encode<'q, $db> for $ty {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <$db as $crate::database::HasArguments<'q>>::ArgumentBuffer,
+            ) -> ::std::result::Result<$crate::encode::IsNull, $crate::encode::EncodeError> {
+                <$inner as $crate::encode::Encode<'q, $db>>::encode_by_ref(
+                    ::std::convert::AsRef::<$inner>::as_ref(self),
+                    buf,
+                )
+            }
+
+            fn produces(
+                &self,
+            ) -> ::std::option::Option<<$db as $crate::database::Database>::TypeInfo> {
+                <$inner as $crate::encode::Encode<'q, $db>>::produces(
+                    ::std::convert::AsRef::<$inner>::as_ref(self),
+                )
+            }
+
+            fn size_hint(&self) -> usize {
+                <$inner as $crate::encode::Encode<'q, $db>>::size_hint(::std::convert::AsRef::<
+                    $inner,
+                >::as_ref(self))
+            }
+        }
+
+        impl<'r> $crate::decode::Decode<'r, $db> for $ty {
+            fn decode(
+                value: <$db as $crate::database::HasValueRef<'r>>::ValueRef,
+            ) -> ::std::result::Result<Self, $crate::error::BoxDynError> {
+                let inner = <$inner as $crate::decode::Decode<'r, $db>>::decode(value)?;
+
+                <$ty as ::std::convert::TryFrom<$inner>>::try_from(inner)
+                    .map_err(::std::convert::Into::into)
+            }
+        }
+    };
+}
+
+#[cfg(all(test, feature = "postgres"))]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::decode::Decode;
+    use crate::encode::Encode;
+    use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+    use crate::types::Type;
+
+    #[derive(Debug, PartialEq)]
+    struct Slug(String);
+
+    impl AsRef<String> for Slug {
+        fn as_ref(&self) -> &String {
+            &self.0
+        }
+    }
+
+    impl TryFrom<String> for Slug {
+        type Error = &'static str;
+
+        fn try_from(slug: String) -> Result<Self, Self::Error> {
+            if slug.chars().all(|c| c.is_ascii_lowercase() || c == '-') {
+                Ok(Slug(slug))
+            } else {
+                Err("not a slug")
+            }
+        }
+    }
+
+    declare_type!(Postgres, Slug as String);
+
+    fn text(value: &[u8]) -> PgValueRef<'_> {
+        PgValueRef {
+            value: Some(value),
+            row: None,
+            type_info: PgTypeInfo::TEXT,
+            format: PgValueFormat::Text,
+        }
+    }
+
+    #[test]
+    fn it_declares_a_type_stored_as_another() {
+        assert_eq!(<Slug as Type<Postgres>>::type_info(), PgTypeInfo::TEXT);
+        assert!(<Slug as Type<Postgres>>::compatible(&PgTypeInfo::VARCHAR));
+
+        let mut declared = PgArgumentBuffer::default();
+        let mut inner = PgArgumentBuffer::default();
+
+        Encode::<Postgres>::encode_by_ref(&Slug("hello-world".into()), &mut declared).unwrap();
+        Encode::<Postgres>::encode_by_ref(&String::from("hello-world"), &mut inner).unwrap();
+
+        assert_eq!(*declared, *inner);
+
+        assert_eq!(
+            <Slug as Decode<Postgres>>::decode(text(b"hello-world")).unwrap(),
+            Slug("hello-world".into())
+        );
+
+        let error = <Slug as Decode<Postgres>>::decode(text(b"Hello World")).unwrap_err();
+        assert_eq!(error.to_string(), "not a slug");
+    }
+}
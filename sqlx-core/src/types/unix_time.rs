@@ -0,0 +1,400 @@
+// only the drivers that support `SystemTime` use the calendar below
+#![cfg_attr(not(any(feature = "postgres", feature = "mysql")), allow(dead_code))]
+
+use crate::error::BoxDynError;
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A point in time as the whole seconds since the unix epoch, `1970-01-01 00:00:00 UTC`;
+/// negative before it.
+///
+/// It can be stored in an integer column as is, or in a timestamp column as the instant it
+/// stands for, and is decoded from either. See the `types` module of each database for the
+/// columns it supports, and how a parameter is sent to each.
+///
+/// ```rust,ignore
+/// let created: UnixTimestamp = sqlx::query_scalar("SELECT created_at FROM users")
+///     .fetch_one(&mut conn)
+///     .await?;
+///
+/// let seconds: i64 = created.0;
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnixTimestamp(pub i64);
+
+/// A point in time as the whole milliseconds since the unix epoch, `1970-01-01 00:00:00 UTC`;
+/// negative before it.
+///
+/// Stored and decoded like [`UnixTimestamp`], in milliseconds instead of seconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnixMillis(pub i64);
+
+impl UnixTimestamp {
+    /// The current time, rounded down to the second.
+    pub fn now() -> Self {
+        Self::from(SystemTime::now())
+    }
+}
+
+impl UnixMillis {
+    /// The current time, rounded down to the millisecond.
+    pub fn now() -> Self {
+        Self::from(SystemTime::now())
+    }
+}
+
+// a `SystemTime` is stored as a 64-bit count of seconds or less on every platform, so it
+// always fits in either, rounded down towards the past
+impl From<SystemTime> for UnixTimestamp {
+    fn from(time: SystemTime) -> Self {
+        Self(unix_micros(time).div_euclid(1_000_000) as i64)
+    }
+}
+
+impl From<SystemTime> for UnixMillis {
+    fn from(time: SystemTime) -> Self {
+        Self(unix_micros(time).div_euclid(1_000) as i64)
+    }
+}
+
+impl UnixTimestamp {
+    /// The `SystemTime` of the timestamp, or `None` if it is beyond what the platform can
+    /// represent.
+    pub fn to_system_time(self) -> Option<SystemTime> {
+        system_time(i128::from(self.0) * 1_000_000)
+    }
+
+    pub(crate) fn unix_micros(self) -> i128 {
+        i128::from(self.0) * 1_000_000
+    }
+}
+
+impl UnixMillis {
+    /// The `SystemTime` of the timestamp, or `None` if it is beyond what the platform can
+    /// represent.
+    pub fn to_system_time(self) -> Option<SystemTime> {
+        system_time(i128::from(self.0) * 1_000)
+    }
+
+    pub(crate) fn unix_micros(self) -> i128 {
+        i128::from(self.0) * 1_000
+    }
+}
+
+/// The error of encoding or decoding a point in time that does not fit the type it is
+/// converted to, e.g. a `SystemTime` past the year 294276 bound as a Postgres `TIMESTAMPTZ`,
+/// or a MySQL `DATETIME` read as a [`UnixTimestamp`] of a zero date.
+///
+/// It is the source of [`Error::ColumnDecode`] or [`Error::ArgumentEncode`], and can be told
+/// apart from other errors there by downcasting.
+///
+/// [`Error::ColumnDecode`]: crate::error::Error::ColumnDecode
+/// [`Error::ArgumentEncode`]: crate::error::Error::ArgumentEncode
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{value} is out of range for {target}")]
+pub struct TimestampOutOfRange {
+    value: String,
+    target: &'static str,
+}
+
+impl TimestampOutOfRange {
+    pub(crate) fn new(value: impl Display, target: &'static str) -> Self {
+        Self {
+            value: value.to_string(),
+            target,
+        }
+    }
+
+    /// The type the value did not fit, e.g. `TIMESTAMPTZ` or `SystemTime`.
+    pub fn target(&self) -> &'static str {
+        self.target
+    }
+}
+
+/// The microseconds from the unix epoch to `time`, rounded down towards the past.
+pub(crate) fn unix_micros(time: SystemTime) -> i128 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_micros() as i128,
+        Err(before) => -((before.duration().as_nanos() as i128 + 999) / 1_000),
+    }
+}
+
+/// The `SystemTime` at `micros` from the unix epoch, if the platform can represent it.
+pub(crate) fn system_time(micros: i128) -> Option<SystemTime> {
+    let magnitude = Duration::from_micros(u64::try_from(micros.abs()).ok()?);
+
+    if micros < 0 {
+        UNIX_EPOCH.checked_sub(magnitude)
+    } else {
+        UNIX_EPOCH.checked_add(magnitude)
+    }
+}
+
+/// Like [`system_time`], failing with [`TimestampOutOfRange`].
+pub(crate) fn try_system_time(micros: i128) -> Result<SystemTime, TimestampOutOfRange> {
+    system_time(micros)
+        .ok_or_else(|| TimestampOutOfRange::new(DateTime::from_unix_micros(micros), "SystemTime"))
+}
+
+/// A date and time of the proleptic Gregorian calendar in UTC, to write and read the timestamps
+/// of databases without going through `chrono` or `time`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DateTime {
+    // the astronomical year, where 1 BC is 0
+    pub(crate) year: i64,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+    pub(crate) micros: u32,
+}
+
+const MICROS_PER_DAY: i128 = 86_400_000_000;
+
+impl DateTime {
+    pub(crate) fn from_unix_micros(micros: i128) -> Self {
+        let (year, month, day) = civil_from_days(micros.div_euclid(MICROS_PER_DAY) as i64);
+        let time = micros.rem_euclid(MICROS_PER_DAY) as u64;
+        let seconds = (time / 1_000_000) as u32;
+
+        Self {
+            year,
+            month,
+            day,
+            hour: seconds / 3600,
+            minute: seconds / 60 % 60,
+            second: seconds % 60,
+            micros: (time % 1_000_000) as u32,
+        }
+    }
+
+    pub(crate) fn unix_micros(&self) -> i128 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let seconds = i128::from(self.hour * 3600 + self.minute * 60 + self.second);
+
+        i128::from(days) * MICROS_PER_DAY + seconds * 1_000_000 + i128::from(self.micros)
+    }
+
+    /// Parses `YYYY-MM-DD HH:MM:SS[.ffffff]`, as both Postgres and MySQL print a timestamp; the
+    /// time may be left out for midnight.
+    pub(crate) fn parse(s: &str) -> Result<Self, BoxDynError> {
+        let invalid = || format!("invalid timestamp {:?}", s);
+
+        let (date, time) = match s.find(|c| c == ' ' || c == 'T') {
+            Some(at) => (&s[..at], &s[at + 1..]),
+            None => (s, "00:00:00"),
+        };
+
+        // a negative year is not printed by either, but is not ambiguous either
+        let (negative, date) = match date.strip_prefix('-') {
+            Some(date) => (true, date),
+            None => (false, date),
+        };
+
+        let date: Vec<&str> = date.split('-').collect();
+        let time: Vec<&str> = time.split(':').collect();
+
+        if date.len() != 3 || time.len() != 3 {
+            return Err(invalid().into());
+        }
+
+        let number = |part: &str| part.parse::<u32>().map_err(|_| invalid());
+
+        let year: i64 = date[0].parse().map_err(|_| invalid())?;
+        let month = number(date[1])?;
+        let day = number(date[2])?;
+        let hour = number(time[0])?;
+        let minute = number(time[1])?;
+
+        let seconds = time[2];
+        let (second, fraction) = match seconds.find('.') {
+            Some(dot) => (&seconds[..dot], &seconds[dot + 1..]),
+            None => (seconds, ""),
+        };
+
+        // the digits past the microseconds are dropped; neither database prints them
+        let mut micros = 0;
+        let mut digits = 0;
+
+        for c in fraction.chars() {
+            let digit = c.to_digit(10).ok_or_else(invalid)?;
+
+            if digits < 6 {
+                micros = micros * 10 + digit;
+                digits += 1;
+            }
+        }
+
+        micros *= 10_u32.pow(6 - digits);
+
+        let datetime = Self {
+            year: if negative { -year } else { year },
+            month,
+            day,
+            hour,
+            minute,
+            second: number(second)?,
+            micros,
+        };
+
+        let valid = (1..=12).contains(&datetime.month)
+            && (1..=days_in_month(datetime.year, datetime.month)).contains(&datetime.day)
+            && datetime.hour < 24
+            && datetime.minute < 60
+            && datetime.second < 60;
+
+        if !valid {
+            return Err(invalid().into());
+        }
+
+        Ok(datetime)
+    }
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+
+        if self.micros != 0 {
+            write!(f, ".{:06}", self.micros)?;
+        }
+
+        f.write_str(" UTC")
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// the days from 1970-01-01 to a date; from <http://howardhinnant.github.io/date_algorithms.html>
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+
+    // the months counted from March, so that the leap day is the last day of the year
+    let month = i64::from((month + 9) % 12);
+    let day_of_year = (153 * month + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+// the inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+
+    let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+    let month = ((month + 2) % 12 + 1) as u32;
+    let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(s: &str) -> DateTime {
+        DateTime::parse(s).unwrap()
+    }
+
+    #[test]
+    fn it_converts_dates_across_the_epoch() {
+        let cases: &[(&str, i128)] = &[
+            ("1970-01-01 00:00:00", 0),
+            ("1970-01-01 00:00:01.5", 1_500_000),
+            ("1969-12-31 23:59:59", -1_000_000),
+            ("1969-12-31 23:59:59.999999", -1),
+            ("2000-02-29 12:00:00", 951_825_600_000_000),
+            ("1900-03-01 00:00:00", -2_203_891_200_000_000),
+            ("0001-01-01 00:00:00", -62_135_596_800_000_000),
+            ("9999-12-31 23:59:59.999999", 253_402_300_799_999_999),
+            ("294276-12-31 23:59:59", 9_224_318_015_999_000_000),
+        ];
+
+        for (s, micros) in cases {
+            assert_eq!(datetime(s).unix_micros(), *micros, "{}", s);
+            assert_eq!(DateTime::from_unix_micros(*micros), datetime(s), "{}", s);
+        }
+
+        // every day for a few centuries around the epoch
+        for days in -150_000..150_000 {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn it_parses_timestamps() {
+        assert_eq!(
+            datetime("2026-10-14T08:30:00.25"),
+            DateTime {
+                year: 2026,
+                month: 10,
+                day: 14,
+                hour: 8,
+                minute: 30,
+                second: 0,
+                micros: 250_000,
+            }
+        );
+        assert_eq!(datetime("2026-10-14"), datetime("2026-10-14 00:00:00"));
+        assert_eq!(datetime("-0043-03-15").year, -43);
+
+        for s in &[
+            "2026-13-01 00:00:00",
+            "2026-02-29 00:00:00",
+            "2026-10-14 24:00:00",
+            "2026-10-14 12:00",
+            "2026-10-14 12:00:00.5x",
+            "infinity",
+        ] {
+            assert!(DateTime::parse(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn it_rounds_system_times_towards_the_past() {
+        let before = UNIX_EPOCH - Duration::from_nanos(1_500);
+        assert_eq!(unix_micros(before), -2);
+        assert_eq!(UnixTimestamp::from(before), UnixTimestamp(-1));
+        assert_eq!(UnixMillis::from(before), UnixMillis(-1));
+
+        let after = UNIX_EPOCH + Duration::from_millis(1_999);
+        assert_eq!(UnixTimestamp::from(after), UnixTimestamp(1));
+        assert_eq!(UnixMillis::from(after), UnixMillis(1_999));
+
+        assert_eq!(
+            UnixTimestamp(-86_400).to_system_time(),
+            Some(UNIX_EPOCH - Duration::from_secs(86_400))
+        );
+        assert_eq!(
+            DateTime::from_unix_micros(unix_micros(before)).to_string(),
+            "1969-12-31 23:59:59.999998 UTC"
+        );
+    }
+}
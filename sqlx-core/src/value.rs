@@ -68,7 +68,7 @@ pub trait Value {
             let ty = self.type_info();
 
             if !ty.is_null() && !T::compatible(&ty) {
-                return Err(Error::Decode(mismatched_types::<Self::Database, T>(&ty)));
+                return Err(Error::decode(mismatched_types::<Self::Database, T>(&ty)));
             }
         }
 
@@ -0,0 +1,269 @@
+//! Tracing of the protocol messages exchanged with the database server.
+//!
+//! A [`WireTracer`] set with `PgConnectOptions::wire_tracing` or
+//! `MySqlConnectOptions::wire_tracing` is called for every message the connection sends or
+//! receives. Messages are traced below TLS, so this works where a packet capture does not.
+//! [`FileWireTracer`] writes them to a file.
+//!
+//! With the `wire-replay` feature, [`replay`] records them to a file that can be played back
+//! by a scripted server, for testing the driver against a captured exchange without a
+//! database.
+
+use std::fmt::{self, Debug, Formatter, Write as _};
+use std::fs::File;
+use std::io::{self, Write as _};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+#[cfg(feature = "wire-replay")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wire-replay")))]
+pub mod replay;
+
+/// Receives every protocol message a connection sends or receives.
+///
+/// The tracer is called on the connection's task while it is reading or writing, so it should
+/// be quick; a shared tracer is called from all the connections it was set on.
+pub trait WireTracer: Send + Sync {
+    /// Called for each message.
+    fn trace(&self, message: &WireMessage<'_>);
+
+    /// Whether [`WireMessage::payload`] should be filled in. Defaults to `false`, as payloads
+    /// hold the query text, bound values and result rows, and possibly passwords.
+    fn include_payload(&self) -> bool {
+        false
+    }
+}
+
+/// Whether a message was sent to or received from the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireDirection {
+    Sent,
+    Received,
+}
+
+/// A protocol message passed to a [`WireTracer`].
+#[derive(Debug)]
+pub struct WireMessage<'a> {
+    pub(crate) direction: WireDirection,
+    pub(crate) tag: Option<u8>,
+    pub(crate) payload: &'a [u8],
+    pub(crate) include_payload: bool,
+}
+
+impl<'a> WireMessage<'a> {
+    /// Whether the message was sent or received.
+    pub fn direction(&self) -> WireDirection {
+        self.direction
+    }
+
+    /// The byte identifying the type of message.
+    ///
+    /// For PostgreSQL this is the message type, e.g. `b'Q'` for a simple query; it is `None` for
+    /// the startup, SSL and cancel requests, which have none. For MySQL it is the first byte of
+    /// the packet payload: the command for a packet that is sent, or `0x00`, `0xfe` or `0xff`
+    /// for the OK, EOF and error packets that are received. It is `None` for an empty packet.
+    pub fn tag(&self) -> Option<u8> {
+        self.tag
+    }
+
+    /// The length of the message in bytes, without the header that carries the tag and the
+    /// length.
+    pub fn len(&self) -> usize {
+        self.payload.len()
+    }
+
+    /// Returns `true` if the message has no content past its header.
+    pub fn is_empty(&self) -> bool {
+        self.payload.is_empty()
+    }
+
+    /// The content of the message, if the tracer [asked for it](WireTracer::include_payload).
+    pub fn payload(&self) -> Option<&'a [u8]> {
+        if self.include_payload {
+            Some(self.payload)
+        } else {
+            None
+        }
+    }
+}
+
+// `ConnectOptions` are `Debug` and `Clone`, which a bare `Arc<dyn WireTracer>` is not
+#[derive(Clone)]
+#[cfg_attr(not(any(feature = "postgres", feature = "mysql")), allow(dead_code))]
+pub(crate) struct WireTracing(pub(crate) Arc<dyn WireTracer>);
+
+impl Debug for WireTracing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("WireTracing")
+    }
+}
+
+/// A [`WireTracer`] that writes one line per message to a file, optionally followed by a hex
+/// dump of the payload.
+///
+/// Each line holds the seconds since the tracer was created, `>>` for a message that was sent
+/// or `<<` for one that was received, the tag and the length:
+///
+/// ```text
+/// 0.004817 >> 'Q' 14 bytes
+///     0000  53 45 4c 45 43 54 20 31 20 2b 20 31 3b 00        |SELECT 1 + 1;.|
+/// 0.005203 << 'T' 29 bytes
+/// ```
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use std::sync::Arc;
+/// # use sqlx_core::wire::FileWireTracer;
+/// # use sqlx_core::postgres::PgConnectOptions;
+/// # fn main() -> std::io::Result<()> {
+/// let tracer = FileWireTracer::create("wire.log")?.hex_dump(true);
+///
+/// let options = PgConnectOptions::new().wire_tracing(Arc::new(tracer));
+/// # Ok(())
+/// # }
+/// ```
+pub struct FileWireTracer {
+    file: Mutex<File>,
+    started: Instant,
+    hex_dump: bool,
+}
+
+impl FileWireTracer {
+    /// Creates the file at `path`, truncating it if it exists.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            file: Mutex::new(File::create(path)?),
+            started: Instant::now(),
+            hex_dump: false,
+        })
+    }
+
+    /// Sets whether a hex dump of each message is written after its line. Off by default.
+    pub fn hex_dump(mut self, hex_dump: bool) -> Self {
+        self.hex_dump = hex_dump;
+        self
+    }
+}
+
+impl Debug for FileWireTracer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileWireTracer")
+            .field("hex_dump", &self.hex_dump)
+            .finish()
+    }
+}
+
+impl WireTracer for FileWireTracer {
+    fn trace(&self, message: &WireMessage<'_>) {
+        let elapsed = self.started.elapsed();
+        let mut line = format!("{}.{:06} ", elapsed.as_secs(), elapsed.subsec_micros());
+
+        format_message(&mut line, message);
+
+        // the connection must not fail because the log could not be written
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = file.write_all(line.as_bytes());
+    }
+
+    fn include_payload(&self) -> bool {
+        self.hex_dump
+    }
+}
+
+fn format_message(out: &mut String, message: &WireMessage<'_>) {
+    out.push_str(match message.direction {
+        WireDirection::Sent => ">> ",
+        WireDirection::Received => "<< ",
+    });
+
+    format_tag(out, message.tag);
+
+    let _ = writeln!(out, " {} bytes", message.len());
+
+    if let Some(payload) = message.payload() {
+        for (n, chunk) in payload.chunks(16).enumerate() {
+            let _ = write!(out, "    {:04x} ", n * 16);
+
+            for byte in chunk {
+                let _ = write!(out, " {:02x}", byte);
+            }
+
+            for _ in chunk.len()..16 {
+                out.push_str("   ");
+            }
+
+            out.push_str("  |");
+
+            for &byte in chunk {
+                out.push(if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                });
+            }
+
+            out.push_str("|\n");
+        }
+    }
+}
+
+// `'Q'` for a printable tag, `0xfe` for another and `-` for none
+fn format_tag(out: &mut String, tag: Option<u8>) {
+    match tag {
+        Some(tag) if tag.is_ascii_graphic() => {
+            let _ = write!(out, "'{}'", tag as char);
+        }
+
+        Some(tag) => {
+            let _ = write!(out, "0x{:02x}", tag);
+        }
+
+        None => out.push('-'),
+    }
+}
+
+#[test]
+fn it_formats_messages() {
+    let mut out = String::new();
+
+    format_message(
+        &mut out,
+        &WireMessage {
+            direction: WireDirection::Sent,
+            tag: Some(b'Q'),
+            payload: b"SELECT 1 + 1;\0",
+            include_payload: false,
+        },
+    );
+
+    format_message(
+        &mut out,
+        &WireMessage {
+            direction: WireDirection::Received,
+            tag: Some(0xfe),
+            payload: b"0123456789abcdef\x01",
+            include_payload: true,
+        },
+    );
+
+    format_message(
+        &mut out,
+        &WireMessage {
+            direction: WireDirection::Sent,
+            tag: None,
+            payload: &[],
+            include_payload: true,
+        },
+    );
+
+    assert_eq!(
+        out,
+        ">> 'Q' 14 bytes\n\
+         << 0xfe 17 bytes\n    \
+         0000  30 31 32 33 34 35 36 37 38 39 61 62 63 64 65 66  |0123456789abcdef|\n    \
+         0010  01                                               |.|\n\
+         >> - 0 bytes\n"
+    );
+}
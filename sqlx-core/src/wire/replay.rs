@@ -0,0 +1,752 @@
+//! Recording of protocol exchanges, and a scripted server that plays them back.
+//!
+//! A [`ReplayRecorder`] set with `wire_tracing` writes every message of a connection to a
+//! `.replay` file. [`Replay::load`] reads the file back, and [`Replay::serve`] starts a server
+//! on a local port that sends a client what the database sent, checking that the client sends
+//! what it sent when the exchange was recorded. A test can so run the driver through an
+//! exchange captured once from a real server, without one.
+//!
+//! The client has to be configured as it was when recording, as everything it sends is
+//! compared: the user, the database and the application name are all in the first message.
+//!
+//! # Format
+//!
+//! A `.replay` file is text, with a line naming the protocol and then a line per message:
+//!
+//! ```text
+//! # comments and blank lines are skipped
+//! protocol postgres
+//! 0.000000 >> - 00030000757365720070...
+//! 0.001830 << 'R' 00000000
+//! 0.002041 >> 'Q' 53454c45435420313b00
+//! ```
+//!
+//! Each line holds the seconds since recording started, `>>` for a message the client sent or
+//! `<<` for one it received, the tag as [`FileWireTracer`](super::FileWireTracer) writes it,
+//! and the payload in hex. The timestamps are for the reader; the replay does not wait for
+//! them.
+//!
+//! What the client sends differently every time, such as a nonce, a key or a process ID, can
+//! be masked by editing the file: `..` in place of a byte matches any byte, and `*` in place of
+//! the payload matches any payload.
+//!
+//! # Limitations
+//!
+//! The server does not speak TLS, so the exchange has to be recorded and played with TLS
+//! disabled. The single byte a PostgreSQL server answers an SSL request with is not a message
+//! and is not recorded; the replay declines with `N`. MySQL sequence IDs are not recorded
+//! either; the replay numbers its packets on from those of the client, as a server does.
+//! Authentication in which the client picks a nonce, like SCRAM, cannot be replayed.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # use std::sync::Arc;
+//! # use sqlx_core::connection::Connection;
+//! # use sqlx_core::postgres::{PgConnectOptions, PgConnection, PgSslMode};
+//! # use sqlx_core::wire::replay::{Replay, ReplayProtocol, ReplayRecorder};
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! // once, against a real server
+//! let recorder = ReplayRecorder::create("handshake.replay", ReplayProtocol::Postgres)?;
+//!
+//! let options = PgConnectOptions::new()
+//!     .ssl_mode(PgSslMode::Disable)
+//!     .wire_tracing(Arc::new(recorder));
+//!
+//! PgConnection::connect_with(&options).await?.close().await?;
+//!
+//! // then in a test
+//! let server = Replay::load("handshake.replay")?.serve()?;
+//!
+//! let options = PgConnectOptions::new()
+//!     .host("127.0.0.1")
+//!     .port(server.port())
+//!     .ssl_mode(PgSslMode::Disable);
+//!
+//! PgConnection::connect_with(&options).await?.close().await?;
+//!
+//! server.finish()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{format_tag, WireDirection, WireMessage, WireTracer};
+use std::fmt::{self, Debug, Formatter, Write as _};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Read, Write as _};
+use std::iter;
+use std::net::{TcpListener, TcpStream};
+use std::panic;
+use std::path::Path;
+use std::str;
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// how long the server waits for the client to connect, and then for each of its messages
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// the request code of a PostgreSQL `SSLRequest`
+const PG_SSL_REQUEST: [u8; 4] = [0x04, 0xd2, 0x16, 0x2f];
+
+/// The protocol of a replay, which says how its messages are framed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayProtocol {
+    Postgres,
+    MySql,
+}
+
+impl ReplayProtocol {
+    fn name(self) -> &'static str {
+        match self {
+            ReplayProtocol::Postgres => "postgres",
+            ReplayProtocol::MySql => "mysql",
+        }
+    }
+}
+
+/// A [`WireTracer`] that records the messages of a connection to a `.replay` file; see the
+/// [module documentation](self).
+///
+/// A recorder should be set on a single connection, as the messages of several would be
+/// interleaved.
+pub struct ReplayRecorder {
+    file: Mutex<File>,
+    started: Instant,
+}
+
+impl ReplayRecorder {
+    /// Creates the file at `path` for a connection speaking `protocol`, truncating it if it
+    /// exists.
+    pub fn create(path: impl AsRef<Path>, protocol: ReplayProtocol) -> io::Result<Self> {
+        let mut file = File::create(path)?;
+        writeln!(file, "protocol {}", protocol.name())?;
+
+        Ok(Self {
+            file: Mutex::new(file),
+            started: Instant::now(),
+        })
+    }
+}
+
+impl Debug for ReplayRecorder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReplayRecorder").finish()
+    }
+}
+
+impl WireTracer for ReplayRecorder {
+    fn trace(&self, message: &WireMessage<'_>) {
+        let elapsed = self.started.elapsed();
+        let mut line = format!("{}.{:06} ", elapsed.as_secs(), elapsed.subsec_micros());
+
+        format_message(
+            &mut line,
+            message.direction,
+            message.tag,
+            message.payload.iter().copied().map(Some),
+        );
+
+        line.push('\n');
+
+        // the connection must not fail because the replay could not be written
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = file.write_all(line.as_bytes());
+    }
+
+    fn include_payload(&self) -> bool {
+        true
+    }
+}
+
+// a message as it is written in a replay, without the timestamp; `None` is a masked byte
+fn format_message(
+    out: &mut String,
+    direction: WireDirection,
+    tag: Option<u8>,
+    payload: impl Iterator<Item = Option<u8>>,
+) {
+    out.push_str(match direction {
+        WireDirection::Sent => ">> ",
+        WireDirection::Received => "<< ",
+    });
+
+    format_tag(out, tag);
+
+    let mut payload = payload.peekable();
+
+    if payload.peek().is_some() {
+        out.push(' ');
+    }
+
+    for byte in payload {
+        match byte {
+            Some(byte) => {
+                let _ = write!(out, "{:02x}", byte);
+            }
+
+            None => out.push_str(".."),
+        }
+    }
+}
+
+/// An exchange recorded by a [`ReplayRecorder`]; see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct Replay {
+    protocol: ReplayProtocol,
+    messages: Vec<ReplayMessage>,
+}
+
+#[derive(Debug, Clone)]
+struct ReplayMessage {
+    // the line of the message in the file, for pointing out a mismatch
+    line: usize,
+    direction: WireDirection,
+    tag: Option<u8>,
+    // `None` if any payload matches; the masked bytes are `None`, which only a message the
+    // client sends may have
+    payload: Option<Vec<Option<u8>>>,
+}
+
+impl ReplayMessage {
+    fn matches(&self, tag: Option<u8>, payload: &[u8]) -> bool {
+        if self.tag != tag {
+            return false;
+        }
+
+        match &self.payload {
+            Some(expected) => {
+                expected.len() == payload.len()
+                    && expected
+                        .iter()
+                        .zip(payload)
+                        .all(|(expected, byte)| expected.is_none() || *expected == Some(*byte))
+            }
+
+            None => true,
+        }
+    }
+
+    // the payload of a message to send to the client, which is never masked
+    fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.payload.iter().flatten().flatten().copied()
+    }
+
+    fn describe(&self) -> String {
+        let mut out = String::new();
+
+        match &self.payload {
+            Some(payload) => {
+                format_message(&mut out, self.direction, self.tag, payload.iter().copied())
+            }
+
+            None => {
+                format_message(&mut out, self.direction, self.tag, iter::empty());
+                out.push_str(" *");
+            }
+        }
+
+        out
+    }
+}
+
+impl Replay {
+    /// Reads a replay from the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Reads a replay from the contents of a `.replay` file.
+    pub fn parse(replay: &str) -> io::Result<Self> {
+        let mut protocol = None;
+        let mut messages = Vec::new();
+
+        for (index, line) in replay.lines().enumerate() {
+            let line_number = index + 1;
+            let invalid =
+                |message: &str| invalid_data(format!("line {}: {}", line_number, message));
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            match fields.first() {
+                None => continue,
+                Some(field) if field.starts_with('#') => continue,
+
+                Some(&"protocol") => {
+                    protocol = Some(match fields.get(1) {
+                        Some(&"postgres") if fields.len() == 2 => ReplayProtocol::Postgres,
+                        Some(&"mysql") if fields.len() == 2 => ReplayProtocol::MySql,
+                        _ => {
+                            return Err(invalid("expected `protocol postgres` or `protocol mysql`"))
+                        }
+                    });
+
+                    continue;
+                }
+
+                Some(_) => {}
+            }
+
+            if fields.len() < 3 || fields.len() > 4 || fields[0].parse::<f64>().is_err() {
+                return Err(invalid(
+                    "expected a timestamp, a direction, a tag and a payload",
+                ));
+            }
+
+            let direction = match fields[1] {
+                ">>" => WireDirection::Sent,
+                "<<" => WireDirection::Received,
+                _ => return Err(invalid("expected `>>` or `<<` for the direction")),
+            };
+
+            let tag = parse_tag(fields[2])
+                .ok_or_else(|| invalid("expected a tag like `'Q'` or `0xfe`, or `-` for none"))?;
+
+            let payload = parse_payload(fields.get(3).copied().unwrap_or_default())
+                .ok_or_else(|| invalid("expected the payload in hex, or `*`"))?;
+
+            let masked = match &payload {
+                Some(payload) => payload.contains(&None),
+                None => true,
+            };
+
+            if direction == WireDirection::Received && masked {
+                return Err(invalid(
+                    "a message the client received is sent as it is, and cannot be masked",
+                ));
+            }
+
+            messages.push(ReplayMessage {
+                line: line_number,
+                direction,
+                tag,
+                payload,
+            });
+        }
+
+        let protocol = protocol.ok_or_else(|| {
+            invalid_data("expected a line naming the protocol, e.g. `protocol postgres`")
+        })?;
+
+        Ok(Self { protocol, messages })
+    }
+
+    /// The protocol the replay was recorded with.
+    pub fn protocol(&self) -> ReplayProtocol {
+        self.protocol
+    }
+
+    /// The number of times the client waits on the server in the replay: the runs of
+    /// messages it sends that the server answers. A test can so pin down how many round trips
+    /// an exchange takes, which the server holds the client to as it plays the replay.
+    pub fn round_trips(&self) -> usize {
+        self.messages
+            .windows(2)
+            .filter(|pair| {
+                pair[0].direction == WireDirection::Sent
+                    && pair[1].direction == WireDirection::Received
+            })
+            .count()
+    }
+
+    /// Starts a server on a free port of `127.0.0.1` that plays the replay to the first client
+    /// to connect, on a thread of its own.
+    ///
+    /// The server stops at the first message of the client that does not match the replay,
+    /// hanging up on it; [`ReplayServer::finish`] tells what did not match.
+    pub fn serve(self) -> io::Result<ReplayServer> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+
+        let handle = thread::spawn(move || self.play(&listener));
+
+        Ok(ReplayServer { port, handle })
+    }
+
+    fn play(&self, listener: &TcpListener) -> io::Result<()> {
+        let mut socket = accept(listener)?;
+        socket.set_read_timeout(Some(TIMEOUT))?;
+
+        // MySQL numbers the packets of a command, and the replies to it, from 0
+        let mut sequence_id = 0_u8;
+
+        for (index, message) in self.messages.iter().enumerate() {
+            if message.direction == WireDirection::Received {
+                socket.write_all(&self.frame(message, &mut sequence_id))?;
+
+                continue;
+            }
+
+            let mismatch = |actual: String| {
+                invalid_data(format!(
+                    "line {}: expected the client to send `{}`, but {}",
+                    message.line,
+                    message.describe(),
+                    actual
+                ))
+            };
+
+            let (tag, payload) = match self.read_message(&mut socket, &mut sequence_id) {
+                Ok(Some(received)) => received,
+                Ok(None) => return Err(mismatch("it hung up".into())),
+                Err(error) => return Err(mismatch(format!("reading failed: {}", error))),
+            };
+
+            if !message.matches(tag, &payload) {
+                let mut actual = String::new();
+
+                format_message(
+                    &mut actual,
+                    WireDirection::Sent,
+                    tag,
+                    payload.iter().copied().map(Some),
+                );
+
+                return Err(mismatch(format!("it sent `{}`", actual)));
+            }
+
+            // the answer to an SSL request is a single byte, which is not recorded
+            let answered = matches!(
+                self.messages.get(index + 1),
+                Some(next) if next.direction == WireDirection::Received && next.tag.is_none()
+            );
+
+            if self.protocol == ReplayProtocol::Postgres
+                && tag.is_none()
+                && payload == PG_SSL_REQUEST
+                && !answered
+            {
+                socket.write_all(b"N")?;
+            }
+        }
+
+        // the client may hang up or stay connected, but must not send anything more
+        let mut rest = Vec::new();
+        let _ = socket.read_to_end(&mut rest);
+
+        if !rest.is_empty() {
+            return Err(invalid_data(format!(
+                "the client sent {} bytes past the end of the replay",
+                rest.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    // the next message of the client, or `None` if it hung up
+    fn read_message(
+        &self,
+        socket: &mut TcpStream,
+        sequence_id: &mut u8,
+    ) -> io::Result<Option<(Option<u8>, Vec<u8>)>> {
+        let (tag, len) = match self.protocol {
+            ReplayProtocol::Postgres => {
+                let mut first = [0];
+
+                if !read_unless_hung_up(socket, &mut first)? {
+                    return Ok(None);
+                }
+
+                // the startup messages have no tag, and start with the length
+                let tag = Some(first[0]).filter(|tag| *tag != 0);
+                let mut len = [0; 4];
+
+                if tag.is_some() {
+                    socket.read_exact(&mut len)?;
+                } else {
+                    socket.read_exact(&mut len[1..])?;
+                }
+
+                let len = (u32::from_be_bytes(len) as usize)
+                    .checked_sub(4)
+                    .ok_or_else(|| invalid_data("the client sent an invalid message length"))?;
+
+                (tag, len)
+            }
+
+            ReplayProtocol::MySql => {
+                let mut header = [0; 4];
+
+                if !read_unless_hung_up(socket, &mut header)? {
+                    return Ok(None);
+                }
+
+                *sequence_id = header[3].wrapping_add(1);
+
+                (
+                    None,
+                    u32::from_le_bytes([header[0], header[1], header[2], 0]) as usize,
+                )
+            }
+        };
+
+        let mut payload = vec![0; len];
+        socket.read_exact(&mut payload)?;
+
+        // the tag of a MySQL packet is the first byte of its payload
+        let tag = match self.protocol {
+            ReplayProtocol::Postgres => tag,
+            ReplayProtocol::MySql => payload.first().copied(),
+        };
+
+        Ok(Some((tag, payload)))
+    }
+
+    // a message to the client with the header of the protocol
+    fn frame(&self, message: &ReplayMessage, sequence_id: &mut u8) -> Vec<u8> {
+        let payload: Vec<u8> = message.bytes().collect();
+        let mut frame = Vec::with_capacity(payload.len() + 5);
+
+        match self.protocol {
+            ReplayProtocol::Postgres => {
+                // a message without a tag is written as it is, e.g. the answer to an SSL
+                // request when the replay has one
+                if let Some(tag) = message.tag {
+                    frame.push(tag);
+                    frame.extend(&(payload.len() as u32 + 4).to_be_bytes());
+                }
+            }
+
+            ReplayProtocol::MySql => {
+                frame.extend(&(payload.len() as u32).to_le_bytes()[..3]);
+                frame.push(*sequence_id);
+
+                *sequence_id = sequence_id.wrapping_add(1);
+            }
+        }
+
+        frame.extend(payload);
+        frame
+    }
+}
+
+/// A server playing a [`Replay`]; see [`Replay::serve`].
+#[derive(Debug)]
+pub struct ReplayServer {
+    port: u16,
+    handle: JoinHandle<io::Result<()>>,
+}
+
+impl ReplayServer {
+    /// The port the server listens on, at `127.0.0.1`.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Waits for the replay to end, and returns an error if the client did not send what was
+    /// recorded, or sent more.
+    ///
+    /// After the last message the server waits for the client to hang up, for up to ten
+    /// seconds, so the connection should be closed first.
+    pub fn finish(self) -> io::Result<()> {
+        self.handle
+            .join()
+            .unwrap_or_else(|panic| panic::resume_unwind(panic))
+    }
+}
+
+fn parse_tag(tag: &str) -> Option<Option<u8>> {
+    match tag.as_bytes() {
+        b"-" => Some(None),
+        [b'\'', tag, b'\''] => Some(Some(*tag)),
+        [b'0', b'x', hex @ ..] => parse_hex(hex).map(Some),
+        _ => None,
+    }
+}
+
+fn parse_payload(payload: &str) -> Option<Option<Vec<Option<u8>>>> {
+    if payload == "*" {
+        return Some(None);
+    }
+
+    let bytes = payload.as_bytes().chunks_exact(2);
+
+    if !bytes.remainder().is_empty() {
+        return None;
+    }
+
+    bytes
+        .map(|byte| match byte {
+            b".." => Some(None),
+            hex => parse_hex(hex).map(Some),
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(Some)
+}
+
+// a byte in two hex digits, without the sign `from_str_radix` would take
+fn parse_hex(hex: &[u8]) -> Option<u8> {
+    if hex.len() != 2 || !hex.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+
+    u8::from_str_radix(str::from_utf8(hex).ok()?, 16).ok()
+}
+
+// waits for the client to connect, for up to `TIMEOUT`
+fn accept(listener: &TcpListener) -> io::Result<TcpStream> {
+    let started = Instant::now();
+
+    listener.set_nonblocking(true)?;
+
+    loop {
+        match listener.accept() {
+            Ok((socket, _)) => {
+                socket.set_nonblocking(false)?;
+
+                return Ok(socket);
+            }
+
+            Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                if started.elapsed() > TIMEOUT {
+                    return Err(io::Error::new(
+                        ErrorKind::TimedOut,
+                        "no client connected to the replay",
+                    ));
+                }
+
+                thread::sleep(Duration::from_millis(5));
+            }
+
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+// fills `buf`, or returns `false` if the client hung up before sending any of it
+fn read_unless_hung_up(socket: &mut TcpStream, buf: &mut [u8]) -> io::Result<bool> {
+    let read = loop {
+        match socket.read(buf) {
+            Ok(read) => break read,
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) if error.kind() == ErrorKind::ConnectionReset => return Ok(false),
+            Err(error) => return Err(error),
+        }
+    };
+
+    if read == 0 {
+        return Ok(false);
+    }
+
+    socket.read_exact(&mut buf[read..])?;
+
+    Ok(true)
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.into())
+}
+
+#[test]
+fn it_parses_what_it_records() -> io::Result<()> {
+    let mut line = String::new();
+
+    format_message(
+        &mut line,
+        WireDirection::Sent,
+        Some(b'Q'),
+        b"SELECT 1;\0".iter().copied().map(Some),
+    );
+
+    assert_eq!(line, ">> 'Q' 53454c45435420313b00");
+
+    let replay = Replay::parse(&format!(
+        "# a comment\n\
+         protocol postgres\n\
+         \n\
+         0.000100 {}\n\
+         0.000200 << 0xfe\n\
+         0.000300 >> - 00..ff\n\
+         0.000400 >> - *\n",
+        line
+    ))?;
+
+    assert_eq!(replay.protocol(), ReplayProtocol::Postgres);
+    assert_eq!(replay.messages.len(), 4);
+    assert_eq!(replay.round_trips(), 1);
+
+    let (query, empty, masked, any) = (
+        &replay.messages[0],
+        &replay.messages[1],
+        &replay.messages[2],
+        &replay.messages[3],
+    );
+
+    assert_eq!(query.line, 4);
+    assert!(query.matches(Some(b'Q'), b"SELECT 1;\0"));
+    assert!(!query.matches(Some(b'Q'), b"SELECT 2;\0"));
+
+    assert_eq!(empty.direction, WireDirection::Received);
+    assert_eq!(empty.tag, Some(0xfe));
+    assert_eq!(empty.bytes().count(), 0);
+
+    assert!(masked.matches(None, b"\x00\x42\xff"));
+    assert!(!masked.matches(None, b"\x00\x42\xfe"));
+    assert!(!masked.matches(None, b"\x00\x42"));
+    assert_eq!(masked.describe(), ">> - 00..ff");
+
+    assert!(any.matches(None, b"anything"));
+    assert!(!any.matches(Some(b'X'), b""));
+    assert_eq!(any.describe(), ">> - *");
+
+    let error = |replay: &str| Replay::parse(replay).unwrap_err().to_string();
+
+    assert_eq!(
+        error("0.1 >> 'Q' 00"),
+        "expected a line naming the protocol, e.g. `protocol postgres`"
+    );
+    assert!(error("protocol mysql\n0.1 << 0x00 00..").starts_with("line 2: "));
+    assert!(error("protocol mysql\n0.1 >> 0x03 0").starts_with("line 2: "));
+    assert!(error("protocol mysql\n0.1 >> 0x03 +3").starts_with("line 2: "));
+    assert!(error("protocol mysql\n0.1 >> 0x3 03").starts_with("line 2: "));
+    assert!(error("protocol mysql\n0.1 <> 0x03 03").starts_with("line 2: "));
+    assert!(error("protocol sqlite").starts_with("line 1: "));
+
+    Ok(())
+}
+
+#[test]
+fn it_plays_replays() -> io::Result<()> {
+    let replay = "protocol mysql\n\
+                  0.000100 << 0x0a 0a38\n\
+                  0.000200 >> 0x03 03..\n\
+                  0.000300 << 0x00 00000002000000\n";
+
+    // a client that reads the greeting, sends a command and reads the reply
+    let client = |command: &[u8]| -> io::Result<Vec<u8>> {
+        let server = Replay::parse(replay)?.serve()?;
+        let mut socket = TcpStream::connect(("127.0.0.1", server.port()))?;
+
+        let mut greeting = [0; 6];
+        socket.read_exact(&mut greeting)?;
+        assert_eq!(greeting, [2, 0, 0, 0, 0x0a, 0x38]);
+
+        socket.write_all(&[command.len() as u8, 0, 0, 1])?;
+        socket.write_all(command)?;
+
+        // the server hangs up after a mismatch, and otherwise waits for the client to
+        let mut reply = Vec::new();
+        let _ = (&mut socket).take(11).read_to_end(&mut reply);
+
+        drop(socket);
+        server.finish()?;
+
+        Ok(reply)
+    };
+
+    // the reply is numbered on from the command
+    assert_eq!(
+        client(b"\x03\x2a")?,
+        b"\x07\x00\x00\x02\x00\x00\x00\x02\x00\x00\x00"
+    );
+
+    let error = client(b"\x01").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        error.to_string(),
+        "line 3: expected the client to send `>> 0x03 03..`, but it sent `>> 0x01 01`"
+    );
+
+    Ok(())
+}
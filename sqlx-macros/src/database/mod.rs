@@ -36,6 +36,7 @@ macro_rules! impl_database_ext {
         },
         ParamChecking::$param_checking:ident,
         feature-types: $ty_info:ident => $get_gate:expr,
+        $(return-types: $ret_info:ident => $get_ret:expr,)?
         row = $row:path,
         name = $db_name:literal
     ) => {
@@ -60,6 +61,14 @@ macro_rules! impl_database_ext {
             }
 
             fn return_type_for_id(info: &Self::TypeInfo) -> Option<&'static str> {
+                $(
+                    let $ret_info = info;
+
+                    if let Some(ty) = $get_ret {
+                        return Some(ty);
+                    }
+                )?
+
                 match () {
                     $(
                         $(#[$meta])?
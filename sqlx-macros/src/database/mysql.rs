@@ -55,6 +55,8 @@ impl_database_ext! {
     },
     ParamChecking::Weak,
     feature-types: info => info.__type_feature_gate(),
+    // `String` is compatible with binary strings too, but they should stay `Vec<u8>`
+    return-types: info => if info.__is_binary_string() { Some("Vec<u8>") } else { None },
     row = sqlx::mysql::MySqlRow,
     name = "MySQL"
 }
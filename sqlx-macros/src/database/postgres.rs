@@ -16,6 +16,18 @@ impl_database_ext! {
 
         sqlx::postgres::types::PgInterval,
 
+        sqlx::postgres::types::PgMoney,
+
+        sqlx::postgres::types::PgBits,
+
+        sqlx::postgres::types::PgXml,
+
+        sqlx::postgres::types::PgTsVector,
+
+        sqlx::postgres::types::PgMacAddress,
+
+        sqlx::postgres::types::PgMacAddress8,
+
         #[cfg(feature = "uuid")]
         sqlx::types::Uuid,
 
@@ -121,6 +133,9 @@ impl_database_ext! {
         #[cfg(feature = "bigdecimal")]
         sqlx::postgres::types::PgRange<sqlx::types::BigDecimal>,
 
+        #[cfg(feature = "decimal")]
+        sqlx::postgres::types::PgRange<sqlx::types::Decimal>,
+
         #[cfg(feature = "chrono")]
         sqlx::postgres::types::PgRange<sqlx::types::chrono::NaiveDate>,
 
@@ -149,6 +164,10 @@ impl_database_ext! {
         Vec<sqlx::postgres::types::PgRange<sqlx::types::BigDecimal>> |
             &[sqlx::postgres::types::PgRange<sqlx::types::BigDecimal>],
 
+        #[cfg(feature = "decimal")]
+        Vec<sqlx::postgres::types::PgRange<sqlx::types::Decimal>> |
+            &[sqlx::postgres::types::PgRange<sqlx::types::Decimal>],
+
         #[cfg(feature = "chrono")]
         Vec<sqlx::postgres::types::PgRange<sqlx::types::chrono::NaiveDate>> |
             &[sqlx::postgres::types::PgRange<sqlx::types::chrono::NaiveDate>],
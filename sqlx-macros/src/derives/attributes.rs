@@ -3,7 +3,9 @@ use quote::{quote, quote_spanned};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Comma;
-use syn::{Attribute, DeriveInput, Field, Lit, Meta, MetaNameValue, NestedMeta, Variant};
+use syn::{
+    Attribute, DeriveInput, Field, Lit, Meta, MetaNameValue, NestedMeta, Path, Type, Variant,
+};
 
 macro_rules! assert_attribute {
     ($e:expr, $err:expr, $input:expr) => {
@@ -15,7 +17,7 @@ macro_rules! assert_attribute {
 
 macro_rules! fail {
     ($t:expr, $m:expr) => {
-        return Err(syn::Error::new_spanned($t, $m));
+        return Err(syn::Error::new_spanned($t, $m))
     };
 }
 
@@ -65,11 +67,15 @@ pub struct SqlxContainerAttributes {
     pub type_name: Option<TypeName>,
     pub rename_all: Option<RenameAll>,
     pub repr: Option<Ident>,
+    pub database: Option<Type>,
 }
 
 pub struct SqlxChildAttributes {
     pub rename: Option<String>,
     pub default: bool,
+    pub try_from: Option<Type>,
+    pub decode_with: Option<Path>,
+    pub skip: bool,
 }
 
 pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContainerAttributes> {
@@ -77,6 +83,7 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
     let mut repr = None;
     let mut type_name = None;
     let mut rename_all = None;
+    let mut database = None;
 
     for attr in input
         .iter()
@@ -145,6 +152,14 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
                                 )
                             }
 
+                            Meta::NameValue(MetaNameValue {
+                                path,
+                                lit: Lit::Str(val),
+                                ..
+                            }) if path.is_ident("database") => {
+                                try_set!(database, val.parse()?, value)
+                            }
+
                             u => fail!(u, "unexpected attribute"),
                         },
                         u => fail!(u, "unexpected attribute"),
@@ -171,12 +186,16 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
         repr,
         type_name,
         rename_all,
+        database,
     })
 }
 
 pub fn parse_child_attributes(input: &[Attribute]) -> syn::Result<SqlxChildAttributes> {
     let mut rename = None;
     let mut default = false;
+    let mut try_from = None;
+    let mut decode_with = None;
+    let mut skip = None;
 
     for attr in input.iter().filter(|a| a.path.is_ident("sqlx")) {
         let meta = attr
@@ -192,7 +211,22 @@ pub fn parse_child_attributes(input: &[Attribute]) -> syn::Result<SqlxChildAttri
                             lit: Lit::Str(val),
                             ..
                         }) if path.is_ident("rename") => try_set!(rename, val.value(), value),
+                        Meta::NameValue(MetaNameValue {
+                            path,
+                            lit: Lit::Str(val),
+                            ..
+                        }) if path.is_ident("try_from") => try_set!(try_from, val.parse()?, value),
+                        Meta::NameValue(MetaNameValue {
+                            path,
+                            lit: Lit::Str(val),
+                            ..
+                        }) if path.is_ident("decode_with") => {
+                            try_set!(decode_with, val.parse()?, value)
+                        }
                         Meta::Path(path) if path.is_ident("default") => default = true,
+                        Meta::Path(path) if path.is_ident("skip") => {
+                            try_set!(skip, path.clone(), value)
+                        }
                         u => fail!(u, "unexpected attribute"),
                     },
                     u => fail!(u, "unexpected attribute"),
@@ -201,7 +235,30 @@ pub fn parse_child_attributes(input: &[Attribute]) -> syn::Result<SqlxChildAttri
         }
     }
 
-    Ok(SqlxChildAttributes { rename, default })
+    if let (Some(_), Some(decode_with)) = (&try_from, &decode_with) {
+        fail!(
+            decode_with,
+            "#[sqlx(try_from = ..)] and #[sqlx(decode_with = ..)] cannot be combined"
+        );
+    }
+
+    // a skipped field is not read from the row, so nothing else can be said about reading it
+    if let Some(skip) = &skip {
+        if rename.is_some() || default || try_from.is_some() || decode_with.is_some() {
+            fail!(
+                skip,
+                "#[sqlx(skip)] cannot be combined with other attributes of the field"
+            );
+        }
+    }
+
+    Ok(SqlxChildAttributes {
+        rename,
+        default,
+        try_from,
+        decode_with,
+        skip: skip.is_some(),
+    })
 }
 
 pub fn check_transparent_attributes(
@@ -218,6 +275,12 @@ pub fn check_transparent_attributes(
 
     assert_attribute!(attributes.repr.is_none(), "unexpected #[repr(..)]", input);
 
+    assert_attribute!(
+        attributes.database.is_none(),
+        "unexpected #[sqlx(database = ..)]",
+        input
+    );
+
     let ch_attributes = parse_child_attributes(&field.attrs)?;
 
     assert_attribute!(
@@ -238,6 +301,12 @@ pub fn check_enum_attributes(input: &DeriveInput) -> syn::Result<SqlxContainerAt
         input
     );
 
+    assert_attribute!(
+        attributes.database.is_none(),
+        "unexpected #[sqlx(database = ..)]",
+        input
+    );
+
     Ok(attributes)
 }
 
@@ -299,6 +368,12 @@ pub fn check_struct_attributes<'a>(
 
     assert_attribute!(attributes.repr.is_none(), "unexpected #[repr(..)]", input);
 
+    assert_attribute!(
+        attributes.database.is_none(),
+        "unexpected #[sqlx(database = ..)]",
+        input
+    );
+
     for field in fields {
         let attributes = parse_child_attributes(&field.attrs)?;
 
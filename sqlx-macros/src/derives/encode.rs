@@ -84,7 +84,7 @@ fn expand_derive_encode_transparent(
             fn encode_by_ref(
                 &self,
                 buf: &mut <DB as ::sqlx::database::HasArguments<#lifetime>>::ArgumentBuffer,
-            ) -> ::sqlx::encode::IsNull {
+            ) -> ::std::result::Result<::sqlx::encode::IsNull, ::sqlx::encode::EncodeError> {
                 <#ty as ::sqlx::encode::Encode<#lifetime, DB>>::encode_by_ref(&self.0, buf)
             }
 
@@ -122,7 +122,7 @@ fn expand_derive_encode_weak_enum(
             fn encode_by_ref(
                 &self,
                 buf: &mut <DB as ::sqlx::database::HasArguments<'q>>::ArgumentBuffer,
-            ) -> ::sqlx::encode::IsNull {
+            ) -> ::std::result::Result<::sqlx::encode::IsNull, ::sqlx::encode::EncodeError> {
                 let value = match self {
                     #(#values)*
                 };
@@ -171,7 +171,7 @@ fn expand_derive_encode_strong_enum(
             fn encode_by_ref(
                 &self,
                 buf: &mut <DB as ::sqlx::database::HasArguments<'q>>::ArgumentBuffer,
-            ) -> ::sqlx::encode::IsNull {
+            ) -> ::std::result::Result<::sqlx::encode::IsNull, ::sqlx::encode::EncodeError> {
                 let val = match self {
                     #(#value_arms)*
                 };
@@ -225,7 +225,7 @@ fn expand_derive_encode_struct(
             let id = &field.ident;
 
             parse_quote!(
-                encoder.encode(&self. #id);
+                encoder.encode(&self. #id)?;
             )
         });
 
@@ -245,14 +245,14 @@ fn expand_derive_encode_struct(
                 fn encode_by_ref(
                     &self,
                     buf: &mut ::sqlx::postgres::PgArgumentBuffer,
-                ) -> ::sqlx::encode::IsNull {
+                ) -> ::std::result::Result<::sqlx::encode::IsNull, ::sqlx::encode::EncodeError> {
                     let mut encoder = ::sqlx::postgres::types::PgRecordEncoder::new(buf);
 
                     #(#writes)*
 
                     encoder.finish();
 
-                    ::sqlx::encode::IsNull::No
+                    ::std::result::Result::Ok(::sqlx::encode::IsNull::No)
                 }
 
                 fn size_hint(&self) -> ::std::primitive::usize {
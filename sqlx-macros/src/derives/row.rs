@@ -1,12 +1,12 @@
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{
-    parse_quote, punctuated::Punctuated, token::Comma, Data, DataStruct, DeriveInput, Field,
-    Fields, FieldsNamed, FieldsUnnamed, Lifetime, Stmt,
+    parse_quote, punctuated::Punctuated, token::Comma, Data, DataStruct, DeriveInput, Expr, Field,
+    Fields, FieldsNamed, FieldsUnnamed, Generics, Lifetime, Stmt, Type, WherePredicate,
 };
 
 use super::{
-    attributes::{parse_child_attributes, parse_container_attributes},
+    attributes::{parse_child_attributes, parse_container_attributes, SqlxChildAttributes},
     rename_all,
 };
 
@@ -36,85 +36,218 @@ pub fn expand_derive_from_row(input: &DeriveInput) -> syn::Result<TokenStream> {
     }
 }
 
-fn expand_derive_from_row_struct(
-    input: &DeriveInput,
-    fields: &Punctuated<Field, Comma>,
-) -> syn::Result<TokenStream> {
-    let ident = &input.ident;
-
-    let generics = &input.generics;
+// the generics of the implementation, and the row and database types it is for: any row,
+// unless the struct is for one database with `#[sqlx(database = ..)]`
+struct ImplGenerics {
+    lifetime: Lifetime,
+    generics: Generics,
+    row: Type,
+    database: Type,
+}
 
-    let (lifetime, provided) = generics
-        .lifetimes()
-        .next()
-        .map(|def| (def.lifetime.clone(), false))
-        .unwrap_or_else(|| (Lifetime::new("'a", Span::call_site()), true));
+impl ImplGenerics {
+    fn new(input: &DeriveInput, database: Option<Type>) -> Self {
+        let generics = &input.generics;
 
-    let (_, ty_generics, _) = generics.split_for_impl();
+        let (lifetime, provided) = generics
+            .lifetimes()
+            .next()
+            .map(|def| (def.lifetime.clone(), false))
+            .unwrap_or_else(|| (Lifetime::new("'a", Span::call_site()), true));
 
-    let mut generics = generics.clone();
-    generics.params.insert(0, parse_quote!(R: ::sqlx::Row));
+        let mut generics = generics.clone();
 
-    if provided {
-        generics.params.insert(0, parse_quote!(#lifetime));
-    }
+        let (row, database) = match database {
+            Some(database) => (parse_quote!(<#database as ::sqlx::Database>::Row), database),
 
-    let predicates = &mut generics.make_where_clause().predicates;
+            None => {
+                generics.params.insert(0, parse_quote!(R: ::sqlx::Row));
 
-    predicates.push(parse_quote!(&#lifetime ::std::primitive::str: ::sqlx::ColumnIndex<R>));
+                (parse_quote!(R), parse_quote!(R::Database))
+            }
+        };
 
-    for field in fields {
-        let ty = &field.ty;
+        if provided {
+            generics.params.insert(0, parse_quote!(#lifetime));
+        }
 
-        predicates.push(parse_quote!(#ty: ::sqlx::decode::Decode<#lifetime, R::Database>));
-        predicates.push(parse_quote!(#ty: ::sqlx::types::Type<R::Database>));
+        Self {
+            lifetime,
+            generics,
+            row,
+            database,
+        }
     }
 
-    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    fn predicates(&mut self) -> &mut Punctuated<WherePredicate, Comma> {
+        &mut self.generics.make_where_clause().predicates
+    }
 
-    let container_attributes = parse_container_attributes(&input.attrs)?;
+    // the expression reading `field` from the column at `index`, adding what that requires of
+    // the field's type to the predicates
+    fn read_field(
+        &mut self,
+        field: &Field,
+        attributes: &SqlxChildAttributes,
+        index: &TokenStream,
+    ) -> Expr {
+        let ty = &field.ty;
+        let lifetime = &self.lifetime;
+        let row = &self.row;
+        let database = &self.database;
+
+        let mut predicates: Vec<WherePredicate> = Vec::new();
+
+        let read: Expr = if let Some(source) = &attributes.try_from {
+            predicates.push(parse_quote!(
+                #source: ::sqlx::decode::Decode<#lifetime, #database>
+            ));
+            predicates.push(parse_quote!(#source: ::sqlx::types::Type<#database>));
+            predicates.push(parse_quote!(#ty: ::std::convert::TryFrom<#source>));
+            predicates.push(parse_quote!(
+                ::sqlx::error::BoxDynError: ::std::convert::From<
+                    <#ty as ::std::convert::TryFrom<#source>>::Error
+                >
+            ));
+
+            parse_quote!(
+                <#row as ::sqlx::Row>::try_get::<#source, _>(row, #index).and_then(|value| {
+                    <#ty as ::std::convert::TryFrom<#source>>::try_from(value).map_err(|e| {
+                        ::sqlx::Error::ColumnDecode {
+                            index: ::std::format!("{:?}", #index),
+                            source: ::sqlx::error::BoxDynError::from(e),
+                        }
+                    })
+                })
+            )
+        } else if let Some(decode_with) = &attributes.decode_with {
+            parse_quote!(
+                <#row as ::sqlx::Row>::try_get_raw(row, #index).and_then(|value| {
+                    #decode_with(value).map_err(|e| ::sqlx::Error::ColumnDecode {
+                        index: ::std::format!("{:?}", #index),
+                        source: ::std::convert::Into::into(e),
+                    })
+                })
+            )
+        } else {
+            predicates.push(parse_quote!(#ty: ::sqlx::decode::Decode<#lifetime, #database>));
+            predicates.push(parse_quote!(#ty: ::sqlx::types::Type<#database>));
 
-    let reads = fields.iter().filter_map(|field| -> Option<Stmt> {
-        let id = &field.ident.as_ref()?;
-        let attributes = parse_child_attributes(&field.attrs).unwrap();
-        let id_s = attributes
-            .rename
-            .or_else(|| Some(id.to_string().trim_start_matches("r#").to_owned()))
-            .map(|s| match container_attributes.rename_all {
-                Some(pattern) => rename_all(&s, pattern),
-                None => s,
-            })
-            .unwrap();
+            parse_quote!(<#row as ::sqlx::Row>::try_get(row, #index))
+        };
 
-        let ty = &field.ty;
+        self.generics
+            .make_where_clause()
+            .predicates
+            .extend(predicates);
 
         if attributes.default {
-            Some(
-                parse_quote!(let #id: #ty = row.try_get(#id_s).or_else(|e| match e {
+            parse_quote!(#read.or_else(|e| match e {
                 ::sqlx::Error::ColumnNotFound(_) => {
                     ::std::result::Result::Ok(Default::default())
                 },
                 e => ::std::result::Result::Err(e)
-            })?;),
-            )
+            })?)
         } else {
-            Some(parse_quote!(
-                let #id: #ty = row.try_get(#id_s)?;
-            ))
+            parse_quote!(#read?)
+        }
+    }
+}
+
+// the type checked against the column a field is read from, if any: the type of the field or
+// the one it is converted from; a field decoded by a function of its own is not checked
+fn checked_type<'a>(field: &'a Field, attributes: &'a SqlxChildAttributes) -> Option<&'a Type> {
+    if attributes.decode_with.is_some() {
+        None
+    } else {
+        Some(attributes.try_from.as_ref().unwrap_or(&field.ty))
+    }
+}
+
+fn expand_derive_from_row_struct(
+    input: &DeriveInput,
+    fields: &Punctuated<Field, Comma>,
+) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let container_attributes = parse_container_attributes(&input.attrs)?;
+
+    let mut generics = ImplGenerics::new(input, container_attributes.database);
+
+    let row = generics.row.clone();
+    let lifetime = generics.lifetime.clone();
+
+    generics
+        .predicates()
+        .push(parse_quote!(&#lifetime ::std::primitive::str: ::sqlx::ColumnIndex<#row>));
+
+    let mut expected = Vec::with_capacity(fields.len());
+    let mut reads: Vec<Stmt> = Vec::with_capacity(fields.len());
+
+    for field in fields {
+        let id = match &field.ident {
+            Some(id) => id,
+            None => continue,
+        };
+
+        let attributes = parse_child_attributes(&field.attrs)?;
+        let ty = &field.ty;
+
+        if attributes.skip {
+            reads.push(parse_quote!(
+                let #id: #ty = ::std::default::Default::default();
+            ));
+
+            continue;
         }
-    });
+
+        let id_s = attributes
+            .rename
+            .clone()
+            .unwrap_or_else(|| id.to_string().trim_start_matches("r#").to_owned());
+
+        let id_s = match container_attributes.rename_all {
+            Some(pattern) => rename_all(&id_s, pattern),
+            None => id_s,
+        };
+
+        if let Some(checked) = checked_type(field, &attributes) {
+            expected.push(if attributes.default {
+                quote!(::sqlx::from_row::ExpectedColumn::named::<#checked>(#id_s).optional())
+            } else {
+                quote!(::sqlx::from_row::ExpectedColumn::named::<#checked>(#id_s))
+            });
+        }
+
+        let read = generics.read_field(field, &attributes, &quote!(#id_s));
+
+        reads.push(parse_quote!(
+            let #id: #ty = #read;
+        ));
+    }
 
     let names = fields.iter().map(|field| &field.ident);
 
+    let database = &generics.database;
+    let (impl_generics, _, where_clause) = generics.generics.split_for_impl();
+
     Ok(quote!(
-        impl #impl_generics ::sqlx::FromRow<#lifetime, R> for #ident #ty_generics #where_clause {
-            fn from_row(row: &#lifetime R) -> ::sqlx::Result<Self> {
+        impl #impl_generics ::sqlx::FromRow<#lifetime, #row> for #ident #ty_generics #where_clause {
+            fn from_row(row: &#lifetime #row) -> ::sqlx::Result<Self> {
                 #(#reads)*
 
                 ::std::result::Result::Ok(#ident {
                     #(#names),*
                 })
             }
+
+            fn expected_columns() -> ::std::option::Option<
+                ::std::vec::Vec<::sqlx::from_row::ExpectedColumn<#database>>
+            > {
+                ::std::option::Option::Some(::std::vec![#(#expected),*])
+            }
         }
     ))
 }
@@ -125,50 +258,70 @@ fn expand_derive_from_row_struct_unnamed(
 ) -> syn::Result<TokenStream> {
     let ident = &input.ident;
 
-    let generics = &input.generics;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
 
-    let (lifetime, provided) = generics
-        .lifetimes()
-        .next()
-        .map(|def| (def.lifetime.clone(), false))
-        .unwrap_or_else(|| (Lifetime::new("'a", Span::call_site()), true));
+    let container_attributes = parse_container_attributes(&input.attrs)?;
 
-    let (_, ty_generics, _) = generics.split_for_impl();
+    let mut generics = ImplGenerics::new(input, container_attributes.database);
 
-    let mut generics = generics.clone();
-    generics.params.insert(0, parse_quote!(R: ::sqlx::Row));
+    let row = generics.row.clone();
+    let lifetime = generics.lifetime.clone();
 
-    if provided {
-        generics.params.insert(0, parse_quote!(#lifetime));
-    }
+    generics.predicates().push(parse_quote!(
+        ::std::primitive::usize: ::sqlx::ColumnIndex<#row>
+    ));
 
-    let predicates = &mut generics.make_where_clause().predicates;
+    let mut expected = Vec::with_capacity(fields.len());
+    let mut gets: Vec<Expr> = Vec::with_capacity(fields.len());
 
-    predicates.push(parse_quote!(
-        ::std::primitive::usize: ::sqlx::ColumnIndex<R>
-    ));
+    // skipped fields are not read, so the columns are those of the other fields in order
+    let mut idx = 0_usize;
 
     for field in fields {
-        let ty = &field.ty;
+        let attributes = parse_child_attributes(&field.attrs)?;
 
-        predicates.push(parse_quote!(#ty: ::sqlx::decode::Decode<#lifetime, R::Database>));
-        predicates.push(parse_quote!(#ty: ::sqlx::types::Type<R::Database>));
-    }
+        if attributes.rename.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "unexpected #[sqlx(rename = ..)] on a field of a tuple struct",
+            ));
+        }
 
-    let (impl_generics, _, where_clause) = generics.split_for_impl();
+        if attributes.skip {
+            gets.push(parse_quote!(::std::default::Default::default()));
 
-    let gets = fields
-        .iter()
-        .enumerate()
-        .map(|(idx, _)| quote!(row.try_get(#idx)?));
+            continue;
+        }
+
+        if let Some(checked) = checked_type(field, &attributes) {
+            expected.push(if attributes.default {
+                quote!(::sqlx::from_row::ExpectedColumn::indexed::<#checked>(#idx).optional())
+            } else {
+                quote!(::sqlx::from_row::ExpectedColumn::indexed::<#checked>(#idx))
+            });
+        }
+
+        gets.push(generics.read_field(field, &attributes, &quote!(#idx)));
+
+        idx += 1;
+    }
+
+    let database = &generics.database;
+    let (impl_generics, _, where_clause) = generics.generics.split_for_impl();
 
     Ok(quote!(
-        impl #impl_generics ::sqlx::FromRow<#lifetime, R> for #ident #ty_generics #where_clause {
-            fn from_row(row: &#lifetime R) -> ::sqlx::Result<Self> {
+        impl #impl_generics ::sqlx::FromRow<#lifetime, #row> for #ident #ty_generics #where_clause {
+            fn from_row(row: &#lifetime #row) -> ::sqlx::Result<Self> {
                 ::std::result::Result::Ok(#ident (
                     #(#gets),*
                 ))
             }
+
+            fn expected_columns() -> ::std::option::Option<
+                ::std::vec::Vec<::sqlx::from_row::ExpectedColumn<#database>>
+            > {
+                ::std::option::Option::Some(::std::vec![#(#expected),*])
+            }
         }
     ))
 }
@@ -88,12 +88,20 @@ fn expand_derive_has_sql_type_transparent(
     if cfg!(feature = "postgres") {
         let ty_name = type_name(ident, attr.type_name.as_ref());
 
+        let array_ty_name = array_type_name(ident, attr.type_name.as_ref());
+
         tts.extend(quote!(
             impl ::sqlx::Type<::sqlx::postgres::Postgres> for #ident #ty_generics {
                 fn type_info() -> ::sqlx::postgres::PgTypeInfo {
                     ::sqlx::postgres::PgTypeInfo::with_name(#ty_name)
                 }
             }
+
+            impl ::sqlx::postgres::PgHasArrayType for #ident #ty_generics {
+                fn array_type_info() -> ::sqlx::postgres::PgTypeInfo {
+                    ::sqlx::postgres::PgTypeInfo::with_name(#array_ty_name)
+                }
+            }
         ));
     }
 
@@ -147,12 +155,20 @@ fn expand_derive_has_sql_type_strong_enum(
     if cfg!(feature = "postgres") {
         let ty_name = type_name(ident, attributes.type_name.as_ref());
 
+        let array_ty_name = array_type_name(ident, attributes.type_name.as_ref());
+
         tts.extend(quote!(
             impl ::sqlx::Type<::sqlx::Postgres> for #ident {
                 fn type_info() -> ::sqlx::postgres::PgTypeInfo {
                     ::sqlx::postgres::PgTypeInfo::with_name(#ty_name)
                 }
             }
+
+            impl ::sqlx::postgres::PgHasArrayType for #ident {
+                fn array_type_info() -> ::sqlx::postgres::PgTypeInfo {
+                    ::sqlx::postgres::PgTypeInfo::with_name(#array_ty_name)
+                }
+            }
         ));
     }
 
@@ -185,12 +201,20 @@ fn expand_derive_has_sql_type_struct(
     if cfg!(feature = "postgres") {
         let ty_name = type_name(ident, attributes.type_name.as_ref());
 
+        let array_ty_name = array_type_name(ident, attributes.type_name.as_ref());
+
         tts.extend(quote!(
             impl ::sqlx::Type<::sqlx::Postgres> for #ident {
                 fn type_info() -> ::sqlx::postgres::PgTypeInfo {
                     ::sqlx::postgres::PgTypeInfo::with_name(#ty_name)
                 }
             }
+
+            impl ::sqlx::postgres::PgHasArrayType for #ident {
+                fn array_type_info() -> ::sqlx::postgres::PgTypeInfo {
+                    ::sqlx::postgres::PgTypeInfo::with_name(#array_ty_name)
+                }
+            }
         ));
     }
 
@@ -203,3 +227,12 @@ fn type_name(ident: &Ident, explicit_name: Option<&TypeName>) -> TokenStream {
         quote_spanned!(ident.span()=> { #s })
     })
 }
+
+// Postgres names the array type of `foo` `_foo`
+fn array_type_name(ident: &Ident, explicit_name: Option<&TypeName>) -> String {
+    let name = explicit_name
+        .map(|tn| tn.val.clone())
+        .unwrap_or_else(|| ident.to_string());
+
+    format!("_{}", name)
+}
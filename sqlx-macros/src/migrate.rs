@@ -1,7 +1,7 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens, TokenStreamExt};
 use sha2::{Digest, Sha384};
-use sqlx_core::migrate::MigrationType;
+use sqlx_core::migrate::{Migration, MigrationType};
 use std::fs;
 use syn::LitStr;
 
@@ -26,6 +26,7 @@ struct QuotedMigration {
     migration_type: QuotedMigrationType,
     sql: String,
     checksum: Vec<u8>,
+    no_tx: bool,
 }
 
 impl ToTokens for QuotedMigration {
@@ -36,6 +37,7 @@ impl ToTokens for QuotedMigration {
             migration_type,
             sql,
             checksum,
+            no_tx,
         } = &self;
 
         let ts = quote! {
@@ -47,6 +49,7 @@ impl ToTokens for QuotedMigration {
                 checksum: ::std::borrow::Cow::Borrowed(&[
                     #(#checksum),*
                 ]),
+                no_tx: #no_tx,
             }
         };
 
@@ -88,6 +91,7 @@ pub(crate) fn expand_migrator_from_dir(dir: LitStr) -> crate::Result<TokenStream
         let sql = fs::read_to_string(&entry.path())?;
 
         let checksum = Vec::from(Sha384::digest(sql.as_bytes()).as_slice());
+        let no_tx = Migration::is_no_transaction(&sql);
 
         migrations.push(QuotedMigration {
             version,
@@ -95,6 +99,7 @@ pub(crate) fn expand_migrator_from_dir(dir: LitStr) -> crate::Result<TokenStream
             migration_type: QuotedMigrationType(migration_type),
             sql,
             checksum,
+            no_tx,
         })
     }
 
@@ -155,7 +155,17 @@ pub mod offline {
                 })?;
 
                 // lazily deserialize the query data only
-                if key == "db" {
+                if key == "version" {
+                    let version = map.next_value::<u32>()?;
+
+                    if version > sqlx_core::offline::FORMAT_VERSION {
+                        return Err(serde::de::Error::custom(format_args!(
+                            "sqlx-data.json has format version {}, which is newer than this \
+                             version of SQLx supports",
+                            version
+                        )));
+                    }
+                } else if key == "db" {
                     db_name = Some(map.next_value::<String>()?);
                 } else if key == self.hash {
                     let db_name = db_name.ok_or_else(|| {
@@ -7,21 +7,40 @@
     feature = "runtime-tokio-rustls",
 )))]
 compile_error!(
-    "one of the features ['runtime-actix-native-tls', 'runtime-async-std-native-tls', \
-     'runtime-tokio-native-tls', 'runtime-actix-rustls', 'runtime-async-std-rustls', \
-     'runtime-tokio-rustls'] must be enabled"
+    "sqlx needs an async runtime: one of the features ['runtime-actix-native-tls', \
+     'runtime-async-std-native-tls', 'runtime-tokio-native-tls', 'runtime-actix-rustls', \
+     'runtime-async-std-rustls', 'runtime-tokio-rustls'] must be enabled (the database \
+     features such as 'postgres' do not enable one on their own)"
 );
 
-#[cfg(any(
-    all(feature = "_rt-actix", feature = "_rt-async-std"),
-    all(feature = "_rt-actix", feature = "_rt-tokio"),
-    all(feature = "_rt-async-std", feature = "_rt-tokio"),
-    all(feature = "_tls-native-tls", feature = "_tls-rustls"),
-))]
+// sqlx-rt re-exports the types of exactly one runtime, so there is no sensible way to pick one
+// when several are enabled; say which features clash instead of failing on ambiguous imports
+#[cfg(all(feature = "_rt-actix", feature = "_rt-async-std"))]
+compile_error!(
+    "the 'actix' and 'async-std' runtimes are both enabled, but sqlx supports only one runtime \
+     at a time; enable exactly one of the 'runtime-{rt}-{tls}' features (check for other crates \
+     in your dependency tree that enable sqlx features)"
+);
+
+#[cfg(all(feature = "_rt-actix", feature = "_rt-tokio"))]
 compile_error!(
-    "only one of ['runtime-actix-native-tls', 'runtime-async-std-native-tls', \
-     'runtime-tokio-native-tls', 'runtime-actix-rustls', 'runtime-async-std-rustls', \
-     'runtime-tokio-rustls'] can be enabled"
+    "the 'actix' and 'tokio' runtimes are both enabled, but sqlx supports only one runtime \
+     at a time; enable exactly one of the 'runtime-{rt}-{tls}' features (check for other crates \
+     in your dependency tree that enable sqlx features)"
+);
+
+#[cfg(all(feature = "_rt-async-std", feature = "_rt-tokio"))]
+compile_error!(
+    "the 'async-std' and 'tokio' runtimes are both enabled, but sqlx supports only one runtime \
+     at a time; enable exactly one of the 'runtime-{rt}-{tls}' features (check for other crates \
+     in your dependency tree that enable sqlx features)"
+);
+
+#[cfg(all(feature = "_tls-native-tls", feature = "_tls-rustls"))]
+compile_error!(
+    "both 'native-tls' and 'rustls' are enabled, but sqlx supports only one TLS backend \
+     at a time; enable exactly one of the 'runtime-{rt}-{tls}' features (check for other crates \
+     in your dependency tree that enable sqlx features)"
 );
 
 #[cfg(all(feature = "_tls-native-tls"))]
@@ -37,7 +56,7 @@ pub use native_tls;
 ))]
 pub use tokio::{
     self, fs, io::AsyncRead, io::AsyncReadExt, io::AsyncWrite, io::AsyncWriteExt, io::ReadBuf,
-    net::TcpStream, task::spawn, task::yield_now, time::sleep, time::timeout,
+    net::TcpStream, task::spawn, task::yield_now, time::sleep, time::timeout, time::Instant,
 };
 
 #[cfg(all(
@@ -75,7 +94,7 @@ mod tokio_runtime {
     where
         F: FnOnce() -> R,
     {
-        RUNTIME.enter();
+        let _rt = RUNTIME.enter();
         f()
     }
 }
@@ -144,6 +163,13 @@ pub use async_std::{
     net::TcpStream, task::sleep, task::spawn, task::yield_now,
 };
 
+// async-std has no clock of its own
+#[cfg(all(
+    feature = "_rt-async-std",
+    not(any(feature = "_rt-actix", feature = "_rt-tokio")),
+))]
+pub use std::time::Instant;
+
 #[cfg(all(
     feature = "_rt-async-std",
     not(any(feature = "_rt-actix", feature = "_rt-tokio")),
@@ -36,6 +36,31 @@ where
     Ok(pool)
 }
 
+// Strings made of the characters that string literals escape, for testing quoting; the same
+// strings every run
+pub fn strings_to_quote(count: usize) -> Vec<String> {
+    const CHARS: &[char] = &[
+        'a', 'E', ' ', '\'', '\\', '"', '`', '\n', '\r', '\t', '\x1a', '$', ';', '-', '%', '_',
+        'é', '€', '¿', '😀',
+    ];
+
+    // xorshift, so the strings need no dependency
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as usize
+    };
+
+    (0..count)
+        .map(|_| {
+            let len = next() % 16;
+            (0..len).map(|_| CHARS[next() % CHARS.len()]).collect()
+        })
+        .collect()
+}
+
 // Test type encoding and decoding
 #[macro_export]
 macro_rules! test_type {
@@ -13,23 +13,37 @@ compile_error!(
 
 pub use sqlx_core::acquire::Acquire;
 pub use sqlx_core::arguments::{Arguments, IntoArguments};
+pub use sqlx_core::cached_row::CachedRow;
 pub use sqlx_core::column::Column;
-pub use sqlx_core::column::ColumnIndex;
-pub use sqlx_core::connection::{ConnectOptions, Connection};
+pub use sqlx_core::column::{ColumnIndex, ColumnNameMatching};
+pub use sqlx_core::columnar::{self, Columnar};
+pub use sqlx_core::connection::{
+    BindLogging, BufferSizes, ConnectOptions, Connection, ConnectionStats, ProtocolPolicy,
+    SessionState, StatementCacheStats, TlsVersion,
+};
 pub use sqlx_core::database::{self, Database};
 pub use sqlx_core::describe::Describe;
+pub use sqlx_core::erased::{self, ErasedExecutor};
+pub use sqlx_core::events::{self, ConnectionEventListener};
 pub use sqlx_core::executor::{Execute, Executor};
-pub use sqlx_core::from_row::FromRow;
+pub use sqlx_core::from_row::{self, FromRow};
+pub use sqlx_core::in_list::{self, InList};
+pub use sqlx_core::middleware::{self, QueryMiddleware};
 pub use sqlx_core::pool::{self, Pool};
 pub use sqlx_core::query::{query, query_with};
 pub use sqlx_core::query_as::{query_as, query_as_with};
 pub use sqlx_core::query_scalar::{query_scalar, query_scalar_with};
+pub use sqlx_core::raw_sql::{raw_sql, RawSql};
 pub use sqlx_core::row::Row;
+
+#[cfg(feature = "json")]
+pub use sqlx_core::row::RowExt;
 pub use sqlx_core::statement::Statement;
-pub use sqlx_core::transaction::{Transaction, TransactionManager};
+pub use sqlx_core::transaction::{PreparedTransaction, Transaction, TransactionManager};
 pub use sqlx_core::type_info::TypeInfo;
 pub use sqlx_core::types::Type;
 pub use sqlx_core::value::{Value, ValueRef};
+pub use sqlx_core::wire::{self, WireTracer};
 
 #[doc(inline)]
 pub use sqlx_core::error::{self, Error, Result};
@@ -37,12 +51,21 @@ pub use sqlx_core::error::{self, Error, Result};
 #[cfg(feature = "migrate")]
 pub use sqlx_core::migrate;
 
+#[cfg(feature = "offline")]
+#[cfg_attr(docsrs, doc(cfg(feature = "offline")))]
+pub use sqlx_core::offline;
+
+#[cfg(feature = "serde-row")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde-row")))]
+pub use sqlx_core::serde_row;
+
 #[cfg(all(
     any(
         feature = "mysql",
         feature = "sqlite",
         feature = "postgres",
-        feature = "mssql"
+        feature = "mssql",
+        feature = "memory"
     ),
     feature = "any"
 ))]
@@ -64,6 +87,14 @@ pub use sqlx_core::postgres::{self, PgConnection, PgPool, Postgres};
 #[cfg_attr(docsrs, doc(cfg(feature = "sqlite")))]
 pub use sqlx_core::sqlite::{self, Sqlite, SqliteConnection, SqlitePool};
 
+#[cfg(feature = "memory")]
+#[cfg_attr(docsrs, doc(cfg(feature = "memory")))]
+pub use sqlx_core::memory::{self, Memory, MemoryConnection, MemoryPool};
+
+#[cfg(all(feature = "testing", any(feature = "_rt-tokio", feature = "_rt-actix")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub use sqlx_core::testing;
+
 #[cfg(feature = "macros")]
 #[doc(hidden)]
 pub extern crate sqlx_macros;
@@ -106,7 +137,7 @@ pub mod types {
 
 /// Provides [`Encode`](encode::Encode) for encoding values for the database.
 pub mod encode {
-    pub use sqlx_core::encode::{Encode, IsNull};
+    pub use sqlx_core::encode::{DynEncode, DynValue, Encode, EncodeError, IsNull};
 
     #[cfg(feature = "macros")]
     #[doc(hidden)]
@@ -126,6 +157,8 @@ pub mod decode {
 
 pub use self::decode::Decode;
 
+pub use sqlx_core::declare_type;
+
 /// Types and traits for the `query` family of functions and macros.
 pub mod query {
     pub use sqlx_core::query::{Map, Query};
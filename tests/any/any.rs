@@ -1,6 +1,8 @@
 use sqlx::any::AnyRow;
-use sqlx::{Any, Connection, Executor, Row};
+use sqlx::database::Capabilities;
+use sqlx::{Any, Connection, Database, Row};
 use sqlx_test::new;
+use std::env;
 
 #[sqlx_macros::test]
 async fn it_connects() -> anyhow::Result<()> {
@@ -18,6 +20,32 @@ async fn it_connects() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_reports_capabilities() -> anyhow::Result<()> {
+    let conn = new::<Any>().await?;
+    let url = env::var("DATABASE_URL")?;
+
+    let capabilities = conn.capabilities();
+
+    // the connection knows its driver, which has at least what all drivers have in common
+    assert!(capabilities.contains(Any::CAPABILITIES));
+    assert!(conn.max_bind_parameters() >= Any::MAX_BIND_PARAMETERS);
+
+    if url.starts_with("postgres") {
+        assert!(capabilities.contains(Capabilities::RETURNING | Capabilities::LISTEN_NOTIFY));
+        assert!(capabilities.contains(Capabilities::DOLLAR_PLACEHOLDERS));
+        assert_eq!(conn.max_identifier_length(), Some(63));
+    } else if url.starts_with("mysql") || url.starts_with("mariadb") {
+        assert!(capabilities.contains(Capabilities::QUESTION_MARK_PLACEHOLDERS));
+        assert!(!capabilities.contains(Capabilities::LISTEN_NOTIFY));
+    } else if url.starts_with("sqlite") {
+        assert!(capabilities.contains(Capabilities::SAVEPOINTS));
+        assert_eq!(conn.max_identifier_length(), None);
+    }
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_pings() -> anyhow::Result<()> {
     let mut conn = new::<Any>().await?;
@@ -97,3 +125,72 @@ async fn it_can_fail_and_recover_with_pool() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_returns_inserted_ids() -> anyhow::Result<()> {
+    // PostgreSQL reports the id through `RETURNING`, SQLite through `last_insert_rowid()`
+    let url = env::var("DATABASE_URL")?;
+    let returning = if url.starts_with("postgres") {
+        " RETURNING id"
+    } else if url.starts_with("sqlite") {
+        ""
+    } else {
+        return Ok(());
+    };
+
+    let mut conn = new::<Any>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE insert_ids (id INTEGER PRIMARY KEY, name TEXT)")
+        .await?;
+
+    let id = sqlx::query(&format!(
+        "INSERT INTO insert_ids (id, name) VALUES (7, 'seven'){}",
+        returning
+    ))
+    .insert_returning_id(&mut conn)
+    .await?;
+    assert_eq!(id, 7);
+
+    if url.starts_with("postgres") {
+        let error = sqlx::query("INSERT INTO insert_ids (id, name) VALUES (8, 'eight')")
+            .insert_returning_id(&mut conn)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, sqlx::Error::InsertIdNotFound));
+    }
+
+    conn.close().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_runs_queries_through_an_erased_executor() -> anyhow::Result<()> {
+    use sqlx::erased::{ErasedArguments, ErasedExecutor, ErasedValue};
+
+    let mut conn = new::<Any>().await?;
+    let executor: &mut dyn ErasedExecutor = &mut conn;
+
+    let row = executor
+        .fetch_optional_erased(
+            "SELECT 1 + 1 AS two, 'text' AS text",
+            ErasedArguments::new(),
+        )
+        .await?
+        .unwrap();
+
+    assert_eq!(row.try_get("two")?.as_i64(), Some(2));
+    assert_eq!(row.try_get("text")?.as_str(), Some("text"));
+
+    // binary values are not supported by every database
+    let error = executor
+        .execute_erased(
+            "SELECT 1",
+            vec![ErasedValue::Bytes(vec![0])].into_iter().collect(),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(error, sqlx::Error::Encode(_)), "{}", error);
+
+    Ok(())
+}
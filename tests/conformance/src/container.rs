@@ -0,0 +1,73 @@
+//! Starting the database of a dialect with `docker-compose`, if no URL is given.
+
+use std::collections::HashMap;
+use std::env;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+
+use anyhow::{bail, Context};
+use once_cell::sync::Lazy;
+
+use crate::Dialect;
+
+// the URL of each service, so that every test does not run `docker-compose` again
+static URLS: Lazy<Mutex<HashMap<&'static str, String>>> = Lazy::new(Default::default);
+
+/// The URL of the database of `D` to test against.
+pub(crate) fn database_url<D: Dialect>() -> anyhow::Result<String> {
+    if let Ok(url) = env::var(D::URL_VAR) {
+        return Ok(D::configure_url(url));
+    }
+
+    if let Ok(url) = env::var("DATABASE_URL") {
+        if url.starts_with(D::SCHEME) {
+            return Ok(D::configure_url(url));
+        }
+    }
+
+    let mut urls = URLS.lock().unwrap();
+
+    if let Some(url) = urls.get(D::SERVICE) {
+        return Ok(url.clone());
+    }
+
+    let url = D::configure_url(D::service_url(&start(D::SERVICE, D::PORT)?));
+    urls.insert(D::SERVICE, url.clone());
+
+    Ok(url)
+}
+
+// starts the service, if it is not running, and returns the `host:port` it listens on
+fn start(service: &str, port: u16) -> anyhow::Result<String> {
+    docker_compose(&["up", "-d", service])?;
+
+    let published = docker_compose(&["port", service, &port.to_string()])?;
+    let published = published.trim();
+
+    // the port is published on every interface
+    Ok(match published.strip_prefix("0.0.0.0:") {
+        Some(port) => format!("127.0.0.1:{}", port),
+        None => published.to_owned(),
+    })
+}
+
+fn docker_compose(args: &[&str]) -> anyhow::Result<String> {
+    let tests = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+
+    let output = Command::new("docker-compose")
+        .args(args)
+        .current_dir(&tests)
+        .output()
+        .context("failed to run `docker-compose`; set the URL of the database to test against")?;
+
+    if !output.status.success() {
+        bail!(
+            "`docker-compose {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
@@ -0,0 +1,86 @@
+//! The [`Dialect`] of each driver the suite runs against.
+
+#[allow(unused_imports)]
+use crate::Dialect;
+
+#[cfg(feature = "postgres")]
+impl Dialect for sqlx::Postgres {
+    const URL_VAR: &'static str = "POSTGRES_URL";
+    const SCHEME: &'static str = "postgres";
+    const SERVICE: &'static str = "postgres_13";
+    const PORT: u16 = 5432;
+
+    const UNIQUE_VIOLATION: &'static str = "23505";
+    const SYNTAX_ERROR: &'static str = "42601";
+    const UNDEFINED_TABLE: &'static str = "42P01";
+
+    fn service_url(host_port: &str) -> String {
+        format!("postgres://postgres:password@{}/sqlx", host_port)
+    }
+
+    fn param(n: usize) -> String {
+        format!("${}", n)
+    }
+
+    fn sleep(seconds: f64) -> String {
+        format!("SELECT pg_sleep({})", seconds)
+    }
+
+    fn rows_affected(result: &sqlx::postgres::PgQueryResult) -> u64 {
+        result.rows_affected()
+    }
+
+    fn unsupported(conn: &sqlx::PgConnection, test: &str) -> Option<&'static str> {
+        use sqlx::postgres::PgFlavor;
+
+        match (conn.server_flavor(), test) {
+            // the other connection waits for the transaction to end, on the same task
+            (
+                PgFlavor::CockroachDb,
+                "transactions::it_hides_uncommitted_writes_from_other_connections",
+            ) => Some("CockroachDB blocks reads of rows written by a transaction until it ends"),
+
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "mysql")]
+impl Dialect for sqlx::MySql {
+    const URL_VAR: &'static str = "MYSQL_URL";
+    const SCHEME: &'static str = "mysql";
+    const SERVICE: &'static str = "mysql_8";
+    const PORT: u16 = 3306;
+
+    // MySQL reports both unique and foreign key violations as 23000
+    const UNIQUE_VIOLATION: &'static str = "23000";
+    const SYNTAX_ERROR: &'static str = "42000";
+    const UNDEFINED_TABLE: &'static str = "42S02";
+
+    fn service_url(host_port: &str) -> String {
+        format!("mysql://root:password@{}/sqlx", host_port)
+    }
+
+    // the other databases count the rows an `UPDATE` matched, not those it changed
+    fn configure_url(url: String) -> String {
+        if url.contains("found-rows=") {
+            url
+        } else if url.contains('?') {
+            format!("{}&found-rows=true", url)
+        } else {
+            format!("{}?found-rows=true", url)
+        }
+    }
+
+    fn param(_: usize) -> String {
+        "?".to_owned()
+    }
+
+    fn sleep(seconds: f64) -> String {
+        format!("SELECT SLEEP({})", seconds)
+    }
+
+    fn rows_affected(result: &sqlx::mysql::MySqlQueryResult) -> u64 {
+        result.rows_affected()
+    }
+}
@@ -0,0 +1,198 @@
+//! A suite of behavioral tests that every driver is held to.
+//!
+//! The tests are written once, against the generic [`Database`], [`Executor`] and [`Row`]
+//! traits, and instantiated for a driver with [`conformance_suite!`]; what SQL cannot express
+//! the same way for every database, such as bind parameters or SQLSTATE codes, comes from the
+//! driver's [`Dialect`]. Each behavior is its own test, so a driver that diverges from the
+//! others fails a test that names the behavior, e.g. `errors_it_classifies_unique_violations`.
+//!
+//! The database to test against is taken from `POSTGRES_URL` or `MYSQL_URL`, or from
+//! `DATABASE_URL` if that is a URL of the database. Otherwise the service of
+//! `tests/docker-compose.yml` named by the dialect is started with `docker-compose`, and the
+//! tests wait for it to accept connections.
+//!
+//! The runtime is chosen with a feature, as for SQLx itself; none is enabled by default:
+//!
+//! ```text
+//! cargo test --manifest-path tests/conformance/Cargo.toml \
+//!     --no-default-features --features postgres,runtime-tokio
+//! ```
+//!
+//! SQLx has no blocking runtime, so the suite runs on Tokio, async-std and actix.
+//!
+//! Servers that speak the protocol of another database, such as CockroachDB, are tested with
+//! its dialect, given their URL; a test of something such a server is known not to support is
+//! skipped, with the reason printed, rather than failed.
+//!
+//! [`Executor`]: sqlx::Executor
+//! [`Row`]: sqlx::Row
+
+use std::time::Duration;
+
+use sqlx::database::HasStatementCache;
+use sqlx::{Connection, Database};
+
+mod container;
+mod dialect;
+pub mod suite;
+
+/// What the suite needs to know about a database that the generic traits do not tell.
+pub trait Dialect: Database + HasStatementCache {
+    /// The environment variable with the URL of the database to test against.
+    const URL_VAR: &'static str;
+
+    /// The scheme of the URLs of the database, to recognize one in `DATABASE_URL`.
+    const SCHEME: &'static str;
+
+    /// The service of `tests/docker-compose.yml` to start if no URL is given.
+    const SERVICE: &'static str;
+
+    /// The port the service listens on inside its container.
+    const PORT: u16;
+
+    /// The SQLSTATE of a violated unique constraint.
+    const UNIQUE_VIOLATION: &'static str;
+
+    /// The SQLSTATE of a syntax error.
+    const SYNTAX_ERROR: &'static str;
+
+    /// The SQLSTATE of a query of a table that does not exist.
+    const UNDEFINED_TABLE: &'static str;
+
+    /// The URL of the database of the service, listening on `host:port`.
+    fn service_url(host_port: &str) -> String;
+
+    /// Sets the options the suite expects on a URL of the database.
+    fn configure_url(url: String) -> String {
+        url
+    }
+
+    /// The `n`th bind parameter of a query, counting from 1.
+    fn param(n: usize) -> String;
+
+    /// A query that takes `seconds` to run.
+    fn sleep(seconds: f64) -> String;
+
+    /// The number of rows a statement inserted, updated or deleted.
+    fn rows_affected(result: &Self::QueryResult) -> u64;
+
+    /// Why the server of `conn` cannot pass `test`, named as `module::test`, if it is known
+    /// not to support what the test needs; the test is then skipped.
+    fn unsupported(conn: &Self::Connection, test: &str) -> Option<&'static str> {
+        let _ = (conn, test);
+        None
+    }
+}
+
+/// Connects to the database of `D`, starting its container first if needed.
+pub async fn connect<D: Dialect>() -> anyhow::Result<D::Connection> {
+    sqlx_test::setup_if_needed();
+
+    let url = container::database_url::<D>()?;
+
+    // the container may still be starting up
+    let mut attempts = 0;
+
+    loop {
+        match D::Connection::connect(&url).await {
+            Ok(conn) => return Ok(conn),
+
+            Err(error) if attempts < 120 && is_starting_up(&error) => {
+                attempts += 1;
+                sqlx_rt::sleep(Duration::from_millis(500)).await;
+            }
+
+            Err(error) => return Err(error.into()),
+        }
+    }
+}
+
+/// Whether `test` is to be skipped on the server of `D`, which is then logged.
+#[doc(hidden)]
+pub async fn skip<D: Dialect>(test: &str) -> anyhow::Result<bool> {
+    let conn = connect::<D>().await?;
+    let reason = D::unsupported(&conn, test);
+
+    conn.close().await?;
+
+    match reason {
+        Some(reason) => {
+            eprintln!("skipping {}: {}", test, reason);
+            Ok(true)
+        }
+
+        None => Ok(false),
+    }
+}
+
+fn is_starting_up(error: &sqlx::Error) -> bool {
+    matches!(error, sqlx::Error::Io(_)) || error.is_connection_error()
+}
+
+/// Instantiates the suite for a database, as one test per behavior, named after the module of
+/// the suite and the behavior.
+///
+/// ```rust,ignore
+/// sqlx_conformance::conformance_suite!(sqlx::Postgres);
+/// ```
+#[macro_export]
+macro_rules! conformance_suite {
+    ($db:ty) => {
+        $crate::__conformance_tests!($db;
+            types::it_round_trips_bool,
+            types::it_round_trips_i16,
+            types::it_round_trips_i32,
+            types::it_round_trips_i64,
+            types::it_round_trips_f64,
+            types::it_round_trips_strings,
+            types::it_round_trips_bytes,
+            types::it_round_trips_nulls,
+            types::it_refuses_to_decode_null_into_a_non_option,
+            types::it_refuses_to_decode_into_another_type,
+            results::it_counts_rows_affected,
+            results::it_counts_matched_rows_of_updates,
+            results::it_reports_a_missing_row,
+            results::it_reports_missing_columns,
+            results::it_fetches_rows_in_order,
+            transactions::it_commits,
+            transactions::it_rolls_back,
+            transactions::it_rolls_back_when_dropped,
+            transactions::it_rolls_back_to_a_savepoint,
+            transactions::it_hides_uncommitted_writes_from_other_connections,
+            errors::it_classifies_unique_violations,
+            errors::it_classifies_syntax_errors,
+            errors::it_classifies_missing_tables,
+            errors::it_keeps_the_connection_usable_after_an_error,
+            statement_cache::it_caches_prepared_statements,
+            statement_cache::it_does_not_cache_statements_that_are_not_persistent,
+            statement_cache::it_clears_cached_statements,
+            cancellation::it_recovers_from_a_dropped_stream,
+            cancellation::it_recovers_from_a_cancelled_query,
+            cancellation::it_recovers_from_a_cancelled_transaction,
+        );
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __conformance_tests {
+    ($db:ty; $($module:ident :: $test:ident),+ $(,)?) => {
+        $(
+            $crate::paste::item! {
+                #[sqlx_macros::test]
+                async fn [< $module _ $test >]() -> anyhow::Result<()> {
+                    let name = concat!(stringify!($module), "::", stringify!($test));
+
+                    if $crate::skip::<$db>(name).await? {
+                        return Ok(());
+                    }
+
+                    $crate::suite::$module::$test::<$db>().await
+                }
+            }
+        )+
+    };
+}
+
+#[doc(hidden)]
+pub use paste;
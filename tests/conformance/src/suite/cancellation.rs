@@ -0,0 +1,99 @@
+//! A query that is not run to completion, because its stream was dropped or its future was
+//! cancelled, leaves the connection usable for the next one.
+
+use std::time::Duration;
+
+use futures::TryStreamExt;
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Connection, Decode, Encode, Executor, IntoArguments, Row, Type};
+
+use super::{check_usable, count, create_table, insert};
+use crate::Dialect;
+
+// long enough to still be running when it is cancelled
+const QUERY_SECONDS: f64 = 1.0;
+const CANCEL_AFTER: Duration = Duration::from_millis(100);
+
+pub async fn it_recovers_from_a_dropped_stream<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i32: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+    i64: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+    for<'a> &'a str: Encode<'a, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_dropped_stream").await?;
+
+    for id in 1..=3 {
+        insert(&mut conn, "conformance_dropped_stream", id, &id.to_string()).await?;
+    }
+
+    {
+        let mut rows =
+            sqlx::query("SELECT id FROM conformance_dropped_stream ORDER BY id").fetch(&mut conn);
+
+        let row = rows.try_next().await?.expect("a row");
+        assert_eq!(row.try_get::<i32, _>(0)?, 1);
+    }
+
+    // the rest of the result is not mistaken for that of the next query
+    check_usable(&mut conn).await?;
+    assert_eq!(count(&mut conn, "conformance_dropped_stream").await?, 3);
+
+    Ok(())
+}
+
+pub async fn it_recovers_from_a_cancelled_query<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i64: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    let sql = D::sleep(QUERY_SECONDS);
+
+    let result = sqlx_rt::timeout(CANCEL_AFTER, sqlx::query(&sql).execute(&mut conn)).await;
+    assert!(result.is_err(), "the query was not cancelled");
+
+    check_usable(&mut conn).await?;
+    check_usable(&mut conn).await?;
+
+    Ok(())
+}
+
+pub async fn it_recovers_from_a_cancelled_transaction<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i32: for<'q> Encode<'q, D> + Type<D>,
+    i64: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+    for<'a> &'a str: Encode<'a, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_cancelled_transaction").await?;
+
+    {
+        let mut tx = conn.begin().await?;
+        insert(&mut *tx, "conformance_cancelled_transaction", 1, "one").await?;
+
+        let sql = D::sleep(QUERY_SECONDS);
+        let result = sqlx_rt::timeout(CANCEL_AFTER, sqlx::query(&sql).execute(&mut *tx)).await;
+        assert!(result.is_err(), "the query was not cancelled");
+    }
+
+    // the transaction was rolled back when it was dropped
+    check_usable(&mut conn).await?;
+    assert_eq!(
+        count(&mut conn, "conformance_cancelled_transaction").await?,
+        0
+    );
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+//! Errors of the database are reported with their SQLSTATE, are classified the same way by
+//! every driver, and leave the connection usable.
+
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Decode, Encode, Executor, IntoArguments, Type};
+
+use super::{check_usable, create_table, insert};
+use crate::Dialect;
+
+// checks that `error` is an error of the database with `code`, that is not to be retried
+fn assert_database_error(error: sqlx::Error, code: &str) {
+    let database_error = match &error {
+        sqlx::Error::Database(database_error) => database_error,
+        _ => panic!("expected an error of the database, got {:?}", error),
+    };
+
+    assert_eq!(database_error.code().as_deref(), Some(code), "{:?}", error);
+
+    // the statement fails the same way every time
+    assert!(!error.is_retryable(), "{:?}", error);
+    assert!(!error.is_connection_error(), "{:?}", error);
+}
+
+pub async fn it_classifies_unique_violations<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    i32: for<'q> Encode<'q, D> + Type<D>,
+    for<'a> &'a str: Encode<'a, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_unique_violation").await?;
+
+    insert(&mut conn, "conformance_unique_violation", 1, "one").await?;
+
+    // the primary key, then the unique name
+    let error = insert(&mut conn, "conformance_unique_violation", 1, "uno")
+        .await
+        .err()
+        .expect("inserted a duplicate key");
+
+    assert_database_error(error.downcast()?, D::UNIQUE_VIOLATION);
+
+    let error = insert(&mut conn, "conformance_unique_violation", 2, "one")
+        .await
+        .err()
+        .expect("inserted a duplicate name");
+
+    assert_database_error(error.downcast()?, D::UNIQUE_VIOLATION);
+
+    Ok(())
+}
+
+pub async fn it_classifies_syntax_errors<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+
+    // both as a prepared statement and as a simple query
+    let error = sqlx::query("SELEC 1")
+        .execute(&mut conn)
+        .await
+        .err()
+        .expect("executed invalid SQL");
+
+    assert_database_error(error, D::SYNTAX_ERROR);
+
+    let error = conn
+        .execute("SELEC 1")
+        .await
+        .err()
+        .expect("executed invalid SQL");
+    assert_database_error(error, D::SYNTAX_ERROR);
+
+    Ok(())
+}
+
+pub async fn it_classifies_missing_tables<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+
+    let error = sqlx::query("SELECT * FROM conformance_does_not_exist")
+        .fetch_all(&mut conn)
+        .await
+        .err()
+        .expect("queried a missing table");
+
+    assert_database_error(error, D::UNDEFINED_TABLE);
+
+    Ok(())
+}
+
+pub async fn it_keeps_the_connection_usable_after_an_error<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i64: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+
+    for _ in 0..3 {
+        assert!(sqlx::query("SELEC 1").execute(&mut conn).await.is_err());
+        check_usable(&mut conn).await?;
+
+        assert!(conn.execute("SELEC 1").await.is_err());
+        check_usable(&mut conn).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+//! The behaviors every driver is held to, one module per area.
+//!
+//! Every test is generic over the [`Dialect`](crate::Dialect) of the database, and is run for
+//! each driver by [`conformance_suite!`](crate::conformance_suite). Tests that need a table
+//! create their own, named after the test, so that they can run concurrently.
+//!
+//! The results and rows of a driver need not be `Debug`, so a statement that must fail is
+//! checked with `.err().expect(..)` rather than `.unwrap_err()`.
+
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Executor, IntoArguments, Row};
+
+use crate::Dialect;
+
+pub mod cancellation;
+pub mod errors;
+pub mod results;
+pub mod statement_cache;
+pub mod transactions;
+pub mod types;
+
+// (re)creates `table`, with an integer key and a unique name
+async fn create_table<D>(conn: &mut D::Connection, table: &str) -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+{
+    conn.execute(&*format!("DROP TABLE IF EXISTS {}", table))
+        .await?;
+
+    conn.execute(&*format!(
+        "CREATE TABLE {} (id INT PRIMARY KEY, name VARCHAR(255) NOT NULL UNIQUE)",
+        table
+    ))
+    .await?;
+
+    Ok(())
+}
+
+async fn insert<D>(
+    conn: &mut D::Connection,
+    table: &str,
+    id: i32,
+    name: &str,
+) -> anyhow::Result<D::QueryResult>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    i32: for<'q> sqlx::Encode<'q, D> + sqlx::Type<D>,
+    for<'a> &'a str: sqlx::Encode<'a, D> + sqlx::Type<D>,
+{
+    let sql = format!(
+        "INSERT INTO {} (id, name) VALUES ({}, {})",
+        table,
+        D::param(1),
+        D::param(2)
+    );
+
+    Ok(sqlx::query(&sql).bind(id).bind(name).execute(conn).await?)
+}
+
+async fn count<D>(conn: &mut D::Connection, table: &str) -> anyhow::Result<i64>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i64: for<'r> sqlx::Decode<'r, D> + sqlx::Type<D>,
+{
+    let sql = format!("SELECT COUNT(*) FROM {}", table);
+    let row = sqlx::query(&sql).fetch_one(conn).await?;
+
+    Ok(row.try_get(0)?)
+}
+
+// a query that must still return the right result on a connection that was just used
+async fn check_usable<D>(conn: &mut D::Connection) -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i64: for<'r> sqlx::Decode<'r, D> + for<'q> sqlx::Encode<'q, D> + sqlx::Type<D>,
+{
+    let sql = format!("SELECT {}", D::param(1));
+    let row = sqlx::query(&sql).bind(42_i64).fetch_one(conn).await?;
+
+    assert_eq!(row.try_get::<i64, _>(0)?, 42);
+
+    Ok(())
+}
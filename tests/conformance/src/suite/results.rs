@@ -0,0 +1,155 @@
+//! Results report what a statement did, and the rows of a query are read as they were returned.
+
+use futures::TryStreamExt;
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Decode, Encode, Executor, IntoArguments, Row, Type};
+
+use super::{create_table, insert};
+use crate::Dialect;
+
+pub async fn it_counts_rows_affected<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    i32: for<'q> Encode<'q, D> + Type<D>,
+    for<'a> &'a str: Encode<'a, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_rows_affected").await?;
+
+    for (id, name) in [(1, "one"), (2, "two"), (3, "three")].iter() {
+        let result = insert(&mut conn, "conformance_rows_affected", *id, name).await?;
+        assert_eq!(D::rows_affected(&result), 1);
+    }
+
+    let sql = format!(
+        "DELETE FROM conformance_rows_affected WHERE id > {}",
+        D::param(1)
+    );
+    let result = sqlx::query(&sql).bind(1_i32).execute(&mut conn).await?;
+    assert_eq!(D::rows_affected(&result), 2);
+
+    // nothing left to delete
+    let result = sqlx::query(&sql).bind(1_i32).execute(&mut conn).await?;
+    assert_eq!(D::rows_affected(&result), 0);
+
+    Ok(())
+}
+
+pub async fn it_counts_matched_rows_of_updates<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    i32: for<'q> Encode<'q, D> + Type<D>,
+    for<'a> &'a str: Encode<'a, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_matched_rows").await?;
+
+    insert(&mut conn, "conformance_matched_rows", 1, "one").await?;
+    insert(&mut conn, "conformance_matched_rows", 2, "two").await?;
+
+    // the rows are matched, though none of them changes
+    let result = conn
+        .execute("UPDATE conformance_matched_rows SET name = name")
+        .await?;
+
+    assert_eq!(D::rows_affected(&result), 2);
+
+    Ok(())
+}
+
+pub async fn it_reports_a_missing_row<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_missing_row").await?;
+
+    let sql = "SELECT id FROM conformance_missing_row";
+
+    let error = sqlx::query(sql)
+        .fetch_one(&mut conn)
+        .await
+        .err()
+        .expect("fetched a row of an empty table");
+    assert!(matches!(error, sqlx::Error::RowNotFound), "{:?}", error);
+
+    assert!(sqlx::query(sql).fetch_optional(&mut conn).await?.is_none());
+    assert!(sqlx::query(sql).fetch_all(&mut conn).await?.is_empty());
+
+    Ok(())
+}
+
+pub async fn it_reports_missing_columns<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    for<'a> &'a str: ColumnIndex<D::Row>,
+    i32: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    let sql = format!("SELECT {} AS id", D::param(1));
+
+    let row = sqlx::query(&sql).bind(7_i32).fetch_one(&mut conn).await?;
+
+    assert_eq!(row.len(), 1);
+    assert_eq!(row.try_get::<i32, _>(0)?, 7);
+    assert_eq!(row.try_get::<i32, _>("id")?, 7);
+
+    let error = row.try_get::<i32, _>(1).unwrap_err();
+    assert!(
+        matches!(
+            error,
+            sqlx::Error::ColumnIndexOutOfBounds { index: 1, len: 1 }
+        ),
+        "{:?}",
+        error
+    );
+
+    let error = row.try_get::<i32, _>("name").unwrap_err();
+    assert!(
+        matches!(&error, sqlx::Error::ColumnNotFound(name) if name == "name"),
+        "{:?}",
+        error
+    );
+
+    Ok(())
+}
+
+pub async fn it_fetches_rows_in_order<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i32: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+    String: for<'r> Decode<'r, D> + Type<D>,
+    for<'a> &'a str: Encode<'a, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_rows_in_order").await?;
+
+    // inserted out of order, so that the order of the result is that of the query
+    for id in [3, 1, 5, 2, 4].iter() {
+        insert(&mut conn, "conformance_rows_in_order", *id, &id.to_string()).await?;
+    }
+
+    let rows: Vec<(i32, String)> =
+        sqlx::query("SELECT id, name FROM conformance_rows_in_order ORDER BY id DESC")
+            .fetch(&mut conn)
+            .map_ok(|row| (row.get(0), row.get(1)))
+            .try_collect()
+            .await?;
+
+    let expected: Vec<(i32, String)> = (1..=5).rev().map(|id| (id, id.to_string())).collect();
+    assert_eq!(rows, expected);
+
+    Ok(())
+}
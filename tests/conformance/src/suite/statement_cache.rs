@@ -0,0 +1,96 @@
+//! Prepared statements are cached per connection, unless they are not persistent, and the
+//! cache can be cleared.
+
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Connection, Decode, Encode, Executor, IntoArguments, Row, Type};
+
+use crate::Dialect;
+
+// a query of its own for each test, so that the statement is not cached already
+async fn select<D>(conn: &mut D::Connection, tag: &str, persistent: bool) -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i64: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    let sql = format!("SELECT {} AS {}", D::param(1), tag);
+
+    let row = sqlx::query(&sql)
+        .bind(1_i64)
+        .persistent(persistent)
+        .fetch_one(conn)
+        .await?;
+
+    assert_eq!(row.try_get::<i64, _>(0)?, 1);
+
+    Ok(())
+}
+
+pub async fn it_caches_prepared_statements<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i64: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    let cached = conn.cached_statements_size();
+
+    select(&mut conn, "cached", true).await?;
+    assert_eq!(conn.cached_statements_size(), cached + 1);
+
+    // the statement is prepared once
+    select(&mut conn, "cached", true).await?;
+    assert_eq!(conn.cached_statements_size(), cached + 1);
+
+    select(&mut conn, "cached_too", true).await?;
+    assert_eq!(conn.cached_statements_size(), cached + 2);
+
+    Ok(())
+}
+
+pub async fn it_does_not_cache_statements_that_are_not_persistent<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i64: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    let cached = conn.cached_statements_size();
+
+    for _ in 0..3 {
+        select(&mut conn, "not_persistent", false).await?;
+        assert_eq!(conn.cached_statements_size(), cached);
+    }
+
+    Ok(())
+}
+
+pub async fn it_clears_cached_statements<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i64: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+
+    select(&mut conn, "cleared", true).await?;
+    select(&mut conn, "cleared_too", true).await?;
+    assert!(conn.cached_statements_size() >= 2);
+
+    conn.clear_cached_statements().await?;
+    assert_eq!(conn.cached_statements_size(), 0);
+
+    // the statements are prepared again
+    select(&mut conn, "cleared", true).await?;
+    assert_eq!(conn.cached_statements_size(), 1);
+
+    Ok(())
+}
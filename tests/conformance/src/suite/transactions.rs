@@ -0,0 +1,156 @@
+//! Transactions and savepoints commit and roll back the statements run in them, and only
+//! those.
+
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Connection, Decode, Encode, Executor, IntoArguments, Type};
+
+use super::{count, create_table, insert};
+use crate::Dialect;
+
+pub async fn it_commits<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i32: for<'q> Encode<'q, D> + Type<D>,
+    i64: for<'r> Decode<'r, D> + Type<D>,
+    for<'a> &'a str: Encode<'a, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_commits").await?;
+
+    let mut tx = conn.begin().await?;
+    insert(&mut *tx, "conformance_commits", 1, "one").await?;
+    insert(&mut *tx, "conformance_commits", 2, "two").await?;
+    tx.commit().await?;
+
+    assert_eq!(count(&mut conn, "conformance_commits").await?, 2);
+
+    // and the rows are there for everyone else
+    let mut other = crate::connect::<D>().await?;
+    assert_eq!(count(&mut other, "conformance_commits").await?, 2);
+
+    Ok(())
+}
+
+pub async fn it_rolls_back<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i32: for<'q> Encode<'q, D> + Type<D>,
+    i64: for<'r> Decode<'r, D> + Type<D>,
+    for<'a> &'a str: Encode<'a, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_rolls_back").await?;
+
+    let mut tx = conn.begin().await?;
+    insert(&mut *tx, "conformance_rolls_back", 1, "one").await?;
+    assert_eq!(count(&mut *tx, "conformance_rolls_back").await?, 1);
+    tx.rollback().await?;
+
+    assert_eq!(count(&mut conn, "conformance_rolls_back").await?, 0);
+
+    Ok(())
+}
+
+pub async fn it_rolls_back_when_dropped<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i32: for<'q> Encode<'q, D> + Type<D>,
+    i64: for<'r> Decode<'r, D> + Type<D>,
+    for<'a> &'a str: Encode<'a, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_rolls_back_when_dropped").await?;
+
+    {
+        let mut tx = conn.begin().await?;
+        insert(&mut *tx, "conformance_rolls_back_when_dropped", 1, "one").await?;
+    }
+
+    // the rollback is sent with the next statement on the connection
+    assert_eq!(
+        count(&mut conn, "conformance_rolls_back_when_dropped").await?,
+        0
+    );
+
+    // and the connection is no longer in a transaction
+    insert(&mut conn, "conformance_rolls_back_when_dropped", 2, "two").await?;
+
+    let mut other = crate::connect::<D>().await?;
+    assert_eq!(
+        count(&mut other, "conformance_rolls_back_when_dropped").await?,
+        1
+    );
+
+    Ok(())
+}
+
+pub async fn it_rolls_back_to_a_savepoint<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i32: for<'q> Encode<'q, D> + Type<D>,
+    i64: for<'r> Decode<'r, D> + Type<D>,
+    for<'a> &'a str: Encode<'a, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_savepoint").await?;
+
+    let mut tx = conn.begin().await?;
+    insert(&mut *tx, "conformance_savepoint", 1, "one").await?;
+
+    let mut savepoint = tx.begin().await?;
+    insert(&mut *savepoint, "conformance_savepoint", 2, "two").await?;
+    assert_eq!(count(&mut *savepoint, "conformance_savepoint").await?, 2);
+    savepoint.rollback().await?;
+
+    // the statements before the savepoint are kept
+    assert_eq!(count(&mut *tx, "conformance_savepoint").await?, 1);
+
+    let mut savepoint = tx.begin().await?;
+    insert(&mut *savepoint, "conformance_savepoint", 3, "three").await?;
+    savepoint.commit().await?;
+
+    tx.commit().await?;
+
+    assert_eq!(count(&mut conn, "conformance_savepoint").await?, 2);
+
+    Ok(())
+}
+
+pub async fn it_hides_uncommitted_writes_from_other_connections<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i32: for<'q> Encode<'q, D> + Type<D>,
+    i64: for<'r> Decode<'r, D> + Type<D>,
+    for<'a> &'a str: Encode<'a, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    let mut other = crate::connect::<D>().await?;
+    create_table(&mut conn, "conformance_uncommitted").await?;
+
+    let mut tx = conn.begin().await?;
+    insert(&mut *tx, "conformance_uncommitted", 1, "one").await?;
+
+    assert_eq!(count(&mut *tx, "conformance_uncommitted").await?, 1);
+    assert_eq!(count(&mut other, "conformance_uncommitted").await?, 0);
+
+    tx.commit().await?;
+
+    assert_eq!(count(&mut other, "conformance_uncommitted").await?, 1);
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+//! Values are bound and decoded without change, and values that cannot be decoded are
+//! reported rather than converted.
+
+use std::fmt::Debug;
+
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Decode, Encode, Executor, IntoArguments, Row, Type};
+
+use crate::Dialect;
+
+// selects each value as a bind parameter, and checks that it is decoded unchanged
+async fn round_trip<D, T>(values: &[T]) -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    T: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+    T: Clone + Debug + PartialEq + Send + 'static,
+{
+    let mut conn = crate::connect::<D>().await?;
+    let sql = format!("SELECT {}", D::param(1));
+
+    for value in values {
+        let row = sqlx::query(&sql)
+            .bind(value.clone())
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(row.try_get::<T, _>(0)?, *value);
+    }
+
+    Ok(())
+}
+
+pub async fn it_round_trips_bool<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    bool: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    round_trip::<D, bool>(&[true, false]).await
+}
+
+pub async fn it_round_trips_i16<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i16: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    round_trip::<D, i16>(&[0, 1, -1, i16::MIN, i16::MAX]).await
+}
+
+pub async fn it_round_trips_i32<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i32: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    round_trip::<D, i32>(&[0, 1, -1, i32::MIN, i32::MAX]).await
+}
+
+pub async fn it_round_trips_i64<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i64: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    round_trip::<D, i64>(&[0, 1, -1, i64::MIN, i64::MAX]).await
+}
+
+pub async fn it_round_trips_f64<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    f64: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    round_trip::<D, f64>(&[0.0, -1.5, 3.141592653589793, f64::MAX, f64::MIN_POSITIVE]).await
+}
+
+pub async fn it_round_trips_strings<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    String: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    round_trip::<D, String>(&[
+        String::new(),
+        "hello, world".to_owned(),
+        "it's \"quoted\" \\ escaped".to_owned(),
+        "Привет, 世界 🦀".to_owned(),
+    ])
+    .await
+}
+
+pub async fn it_round_trips_bytes<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    Vec<u8>: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    round_trip::<D, Vec<u8>>(&[
+        Vec::new(),
+        vec![0],
+        vec![0xDE, 0xAD, 0xBE, 0xEF],
+        (0..=255).collect(),
+    ])
+    .await
+}
+
+pub async fn it_round_trips_nulls<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    Option<i32>: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+    Option<String>: for<'q> Encode<'q, D> + for<'r> Decode<'r, D> + Type<D>,
+{
+    round_trip::<D, Option<i32>>(&[None, Some(1)]).await?;
+    round_trip::<D, Option<String>>(&[None, Some(String::new())]).await?;
+
+    // an untyped `NULL` decodes into any `Option`
+    let mut conn = crate::connect::<D>().await?;
+    let row = sqlx::query("SELECT NULL").fetch_one(&mut conn).await?;
+
+    assert_eq!(row.try_get::<Option<i32>, _>(0)?, None);
+    assert_eq!(row.try_get::<Option<String>, _>(0)?, None);
+
+    Ok(())
+}
+
+pub async fn it_refuses_to_decode_null_into_a_non_option<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i32: for<'r> Decode<'r, D> + Type<D>,
+    Option<i32>: for<'q> Encode<'q, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    let sql = format!("SELECT {}", D::param(1));
+
+    let row = sqlx::query(&sql)
+        .bind(None::<i32>)
+        .fetch_one(&mut conn)
+        .await?;
+
+    let error = row.try_get::<i32, _>(0).unwrap_err();
+    assert!(
+        matches!(error, sqlx::Error::ColumnDecode { .. }),
+        "{:?}",
+        error
+    );
+
+    Ok(())
+}
+
+pub async fn it_refuses_to_decode_into_another_type<D>() -> anyhow::Result<()>
+where
+    D: Dialect,
+    for<'c> &'c mut D::Connection: Executor<'c, Database = D>,
+    for<'q> <D as HasArguments<'q>>::Arguments: IntoArguments<'q, D>,
+    usize: ColumnIndex<D::Row>,
+    i64: for<'r> Decode<'r, D> + Type<D>,
+    bool: for<'r> Decode<'r, D> + Type<D>,
+    String: for<'q> Encode<'q, D> + Type<D>,
+{
+    let mut conn = crate::connect::<D>().await?;
+    let sql = format!("SELECT {}", D::param(1));
+
+    let row = sqlx::query(&sql)
+        .bind("42".to_owned())
+        .fetch_one(&mut conn)
+        .await?;
+
+    for error in [
+        row.try_get::<i64, _>(0).unwrap_err(),
+        row.try_get::<bool, _>(0).unwrap_err(),
+    ]
+    .iter()
+    {
+        assert!(
+            matches!(error, sqlx::Error::ColumnDecode { .. }),
+            "{:?}",
+            error
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1 @@
+sqlx_conformance::conformance_suite!(sqlx::MySql);
@@ -0,0 +1,436 @@
+use futures::TryStreamExt;
+use sqlx::any::{AnyConnectOptions, AnyDriverVtable, AnyKind};
+use sqlx::encode::{DynEncode, DynValue, Encode, EncodeError, IsNull};
+use sqlx::memory::{Memory, MemoryArgumentBuffer, MemoryConnectOptions};
+use sqlx::middleware::{QueryContext, QueryMiddleware};
+use sqlx::{ConnectOptions, Connection, Error, Row};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+async fn new() -> anyhow::Result<sqlx::MemoryConnection> {
+    let mut conn = sqlx::MemoryConnection::connect("memory:").await?;
+
+    conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, email TEXT)")
+        .await?;
+
+    Ok(conn)
+}
+
+#[sqlx_macros::test]
+async fn it_inserts_and_selects() -> anyhow::Result<()> {
+    let mut conn = new().await?;
+
+    let done = sqlx::query("INSERT INTO users (name, email) VALUES (?, ?), (?, NULL)")
+        .bind("alice")
+        .bind("alice@example.com")
+        .bind("bob")
+        .execute(&mut conn)
+        .await?;
+
+    assert_eq!(done.rows_affected(), 2);
+    assert_eq!(done.last_insert_id(), Some(2));
+
+    let rows = sqlx::query("SELECT id, name, email FROM users")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<i64, _>("id"), 1);
+    assert_eq!(rows[0].get::<&str, _>("name"), "alice");
+    assert_eq!(
+        rows[0].get::<Option<String>, _>("email").as_deref(),
+        Some("alice@example.com")
+    );
+    assert_eq!(rows[1].get::<Option<String>, _>("email"), None);
+
+    let (id,): (i32,) = sqlx::query_as("SELECT id FROM users WHERE name = $1")
+        .bind("bob")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(id, 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_shares_the_store_between_connections() -> anyhow::Result<()> {
+    let options = MemoryConnectOptions::new();
+
+    let mut a = options.connect().await?;
+    let mut b = options.connect().await?;
+
+    a.execute("CREATE TABLE t (x INTEGER)").await?;
+    a.execute("INSERT INTO t VALUES (1)").await?;
+
+    let x: i64 = sqlx::query_scalar("SELECT x FROM t")
+        .fetch_one(&mut b)
+        .await?;
+
+    assert_eq!(x, 1);
+
+    // a separate connect from a url starts out empty
+    let mut c = sqlx::MemoryConnection::connect("memory:").await?;
+
+    assert!(c.execute("SELECT x FROM t").await.is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rolls_back_a_transaction() -> anyhow::Result<()> {
+    let mut conn = new().await?;
+
+    conn.execute("INSERT INTO users (name) VALUES ('alice')")
+        .await?;
+
+    let mut tx = conn.begin().await?;
+
+    tx.execute("INSERT INTO users (name) VALUES ('bob')")
+        .await?;
+    tx.execute("DELETE FROM users WHERE name = 'alice'").await?;
+
+    tx.rollback().await?;
+
+    let names: Vec<String> = sqlx::query_scalar("SELECT name FROM users")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(names, ["alice"]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_unsupported_sql() -> anyhow::Result<()> {
+    let mut conn = new().await?;
+
+    let err = conn
+        .execute("SELECT id FROM users ORDER BY id")
+        .await
+        .unwrap_err();
+
+    let err = err.into_database_error().unwrap();
+
+    assert_eq!(err.code().as_deref(), Some("unsupported"));
+    assert_eq!(
+        err.message(),
+        "memory driver does not support `ORDER BY id`"
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_enforces_constraints() -> anyhow::Result<()> {
+    let mut conn = new().await?;
+
+    conn.execute("INSERT INTO users (id, name) VALUES (1, 'alice')")
+        .await?;
+
+    let err = conn
+        .execute("INSERT INTO users (id, name) VALUES (1, 'bob')")
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.as_database_error().and_then(|e| e.code()).as_deref(),
+        Some("unique_violation")
+    );
+
+    let err = conn
+        .execute("INSERT INTO users (id) VALUES (2)")
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.as_database_error().and_then(|e| e.code()).as_deref(),
+        Some("not_null_violation")
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_connects_through_any() -> anyhow::Result<()> {
+    let mut conn = sqlx::AnyConnection::connect("memory:").await?;
+
+    conn.execute("CREATE TABLE t (x INTEGER, y TEXT)").await?;
+
+    sqlx::query("INSERT INTO t (x, y) VALUES (?, ?)")
+        .bind(7_i64)
+        .bind("seven")
+        .execute(&mut conn)
+        .await?;
+
+    let row = sqlx::query("SELECT x, y FROM t")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(row.try_get::<i64, _>(0)?, 7);
+    assert_eq!(row.try_get::<String, _>("y")?, "seven");
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_refuses_migrations_through_any() -> anyhow::Result<()> {
+    use sqlx::migrate::{Migrate, MigrateDatabase, MigrateError};
+
+    let err = sqlx::Any::database_exists("memory:").await.unwrap_err();
+    assert!(matches!(err, Error::Configuration(_)), "{:?}", err);
+
+    let mut conn = sqlx::AnyConnection::connect("memory:").await?;
+
+    let err = conn.ensure_migrations_table().await.unwrap_err();
+    assert!(
+        matches!(err, MigrateError::Execute(Error::Configuration(_))),
+        "{:?}",
+        err
+    );
+    assert_eq!(
+        err.to_string(),
+        "while executing migrations: error with configuration: migrations are not supported by the memory driver"
+    );
+
+    Ok(())
+}
+
+// a driver from outside of SQLx, as far as `Any` can tell, that runs on the in-memory driver
+static MYDB: AnyDriverVtable = AnyDriverVtable {
+    kind: AnyKind::Registered("MyDB"),
+    parse: |_| {
+        Ok(AnyConnectOptions::from_driver(
+            &MYDB,
+            MemoryConnectOptions::new(),
+        ))
+    },
+    connect: |options| {
+        Box::pin(async move {
+            let options: &MemoryConnectOptions = options.driver_options().unwrap();
+
+            Ok(sqlx::AnyConnection::from_driver(options.connect().await?))
+        })
+    },
+};
+
+#[sqlx_macros::test]
+async fn it_connects_through_a_registered_driver() -> anyhow::Result<()> {
+    sqlx::any::register_driver("mydb", &MYDB)?;
+    assert!(sqlx::any::register_driver("mydb", &MYDB).is_err());
+
+    let options: AnyConnectOptions = "mydb://localhost/test".parse()?;
+    assert_eq!(options.kind(), AnyKind::Registered("MyDB"));
+
+    let mut conn = sqlx::AnyConnection::connect_with(&options).await?;
+
+    conn.execute("CREATE TABLE t (x INTEGER, y TEXT)").await?;
+
+    let done = sqlx::query("INSERT INTO t (x, y) VALUES (?, ?), (?, ?)")
+        .bind(7_i64)
+        .bind("seven")
+        .bind(8_i32)
+        .bind(None::<String>)
+        .execute(&mut conn)
+        .await?;
+
+    assert_eq!(done.rows_affected(), 2);
+
+    let rows = sqlx::query("SELECT x, y FROM t")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].try_get::<i64, _>("x")?, 7);
+    assert_eq!(rows[0].try_get::<String, _>("y")?, "seven");
+    assert_eq!(rows[1].try_get::<i32, _>(0)?, 8);
+    assert_eq!(rows[1].try_get::<Option<String>, _>("y")?, None);
+    assert!(rows[0].try_get::<bool, _>("y").is_err());
+
+    // transactions are begun and ended by the driver
+    let mut tx = conn.begin().await?;
+    sqlx::query("DELETE FROM t").execute(&mut tx).await?;
+    tx.rollback().await?;
+
+    let rows = sqlx::query("SELECT x FROM t").fetch_all(&mut conn).await?;
+    assert_eq!(rows.len(), 2);
+
+    conn.close().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_executes_the_sql_returned_by_query_middleware() -> anyhow::Result<()> {
+    // the `people` of an older schema are now `users`
+    struct Rename;
+
+    impl QueryMiddleware for Rename {
+        fn before_execute<'q>(
+            &self,
+            sql: &'q str,
+            _: &QueryContext,
+        ) -> Result<Cow<'q, str>, sqlx::error::BoxDynError> {
+            if sql.contains("people") {
+                Ok(Cow::Owned(sql.replace("people", "users")))
+            } else {
+                Ok(Cow::Borrowed(sql))
+            }
+        }
+    }
+
+    let mut options = MemoryConnectOptions::new();
+    options.query_middleware(Arc::new(Rename));
+
+    let mut conn = options.connect().await?;
+
+    conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+
+    sqlx::query("INSERT INTO people (name) VALUES (?)")
+        .bind("alice")
+        .execute(&mut conn)
+        .await?;
+
+    let name: String = sqlx::query_scalar("SELECT name FROM people")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(name, "alice");
+
+    Ok(())
+}
+
+#[test]
+fn it_has_a_database_type() {
+    fn assert_database<DB: sqlx::Database>() {}
+
+    assert_database::<Memory>();
+}
+
+#[sqlx_macros::test]
+async fn it_binds_values_of_types_known_at_runtime() -> anyhow::Result<()> {
+    let mut conn = new().await?;
+
+    sqlx::query("INSERT INTO users (name, email) VALUES (?, ?), (?, ?)")
+        .bind_dyn(DynValue::from("alice"))
+        .bind_dyn(Box::new(None::<String>) as Box<dyn DynEncode<'_, Memory>>)
+        .bind_dyn(DynValue::from("bob"))
+        .bind_dyn(DynValue::from("bob@example.com"))
+        .execute(&mut conn)
+        .await?;
+
+    // the filters of a search, as chosen by a user
+    let filters: Vec<(&str, DynValue)> = vec![("id", 2.into()), ("name", "bob".into())];
+
+    let sql = format!(
+        "SELECT email FROM users WHERE {}",
+        filters
+            .iter()
+            .map(|(column, _)| format!("{} = ?", column))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    );
+
+    let mut query = sqlx::query_as(&sql);
+
+    for (_, value) in filters {
+        query = query.bind_dyn(value);
+    }
+
+    let (email,): (Option<String>,) = query.fetch_one(&mut conn).await?;
+    assert_eq!(email.as_deref(), Some("bob@example.com"));
+
+    let email: Option<String> = sqlx::query_scalar("SELECT email FROM users WHERE name = ?")
+        .bind_dyn(DynValue::from("alice"))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(email, None);
+
+    Ok(())
+}
+
+struct Unencodable;
+
+impl sqlx::Type<Memory> for Unencodable {
+    fn type_info() -> sqlx::memory::MemoryTypeInfo {
+        <String as sqlx::Type<Memory>>::type_info()
+    }
+}
+
+impl Encode<'_, Memory> for Unencodable {
+    fn encode_by_ref(&self, _: &mut MemoryArgumentBuffer) -> Result<IsNull, EncodeError> {
+        Err("cannot be encoded".into())
+    }
+}
+
+#[sqlx_macros::test]
+async fn it_reports_the_index_of_a_dyn_value_that_cannot_be_encoded() -> anyhow::Result<()> {
+    let mut conn = new().await?;
+
+    let res = sqlx::query("INSERT INTO users (name, email) VALUES (?, ?)")
+        .bind_dyn(DynValue::from("alice"))
+        .bind_dyn(Box::new(Unencodable) as Box<dyn DynEncode<'_, Memory>>)
+        .execute(&mut conn)
+        .await;
+
+    assert!(
+        matches!(res, Err(Error::ArgumentEncode { index: 1, .. })),
+        "{:?}",
+        res
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fetches_columns() -> anyhow::Result<()> {
+    let mut conn = new().await?;
+
+    conn.execute("INSERT INTO users (name, email) VALUES ('alice', 'alice@example.com'), ('bob', NULL), ('carol', NULL)")
+        .await?;
+
+    let (ids, names, emails) = sqlx::query("SELECT id, name, email FROM users")
+        .fetch_columnar::<(i64, String, Option<String>), _>(&mut conn)
+        .await?;
+
+    assert_eq!(ids, [1, 2, 3]);
+    assert_eq!(names, ["alice", "bob", "carol"]);
+    assert_eq!(emails, [Some("alice@example.com".to_owned()), None, None]);
+
+    let chunks: Vec<(Vec<i64>,)> = sqlx::query("SELECT id FROM users")
+        .fetch_columnar_chunks::<(i64,), _>(&mut conn, 2)
+        .try_collect()
+        .await?;
+
+    assert_eq!(chunks, [(vec![1, 2],), (vec![3],)]);
+
+    // nothing is decoded from a result that does not match
+    let error = sqlx::query("SELECT id, name FROM users")
+        .fetch_columnar::<(i64, i64), _>(&mut conn)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::TypeValidation(_)), "{:?}", error);
+
+    let error = sqlx::query("SELECT id, name FROM users")
+        .fetch_columnar::<(i64,), _>(&mut conn)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::Decode(_)), "{:?}", error);
+
+    let error = sqlx::query("SELECT id FROM users")
+        .fetch_columnar::<(i64, String), _>(&mut conn)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::TypeValidation(_)), "{:?}", error);
+
+    // an empty result is empty columns
+    let (ids,) = sqlx::query("SELECT id FROM users WHERE name = 'dave'")
+        .fetch_columnar::<(i64,), _>(&mut conn)
+        .await?;
+    assert!(ids.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,1334 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use futures::future::{self, BoxFuture, FutureExt};
+
+use sqlx::events::{CloseReason, ConnectionInfo};
+use sqlx::memory::{Memory, MemoryConnectOptions, MemoryPool, MemoryPoolOptions};
+use sqlx::pool::{
+    AdmissionPolicy, Credential, PartitionedPool, PartitionedPoolOptions, PasswordProvider,
+    PoolConnection, PoolLoad, PoolResize, Priority, RejectWhenEstimatedWaitExceeds,
+    RejectWhenWaitersExceed,
+};
+use sqlx::testing::VirtualTime;
+use sqlx::{Acquire, Connection, ConnectionEventListener, Database, Error, Executor};
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+#[test]
+fn it_closes_connections_idle_for_longer_than_idle_timeout() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .idle_timeout(MINUTE)
+            .max_lifetime(None)
+            .connect("memory:")
+            .await?;
+
+        assert_eq!(pool.size(), 1);
+
+        VirtualTime::advance(MINUTE / 2).await;
+        assert_eq!(pool.size(), 1);
+
+        // reaped the next time the reaper runs, a few times a minute
+        VirtualTime::advance(2 * MINUTE).await;
+        assert_eq!(pool.size(), 0);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_keeps_min_connections_past_idle_timeout() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .min_connections(2)
+            .idle_timeout(MINUTE)
+            .connect("memory:")
+            .await?;
+
+        assert_eq!(pool.size(), 2);
+
+        VirtualTime::advance(60 * MINUTE).await;
+        assert_eq!(pool.size(), 2);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_replaces_connections_older_than_max_lifetime() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .max_lifetime(10 * MINUTE)
+            .idle_timeout(None)
+            .connect("memory:")
+            .await?;
+
+        drop(pool.acquire().await?);
+        assert_eq!(pool.stats().connections_opened, 1);
+
+        VirtualTime::advance(5 * MINUTE).await;
+
+        drop(pool.acquire().await?);
+        assert_eq!(pool.stats().connections_opened, 1);
+
+        VirtualTime::advance(30 * MINUTE).await;
+        assert_eq!(pool.size(), 0);
+
+        drop(pool.acquire().await?);
+        assert_eq!(pool.stats().connections_opened, 2);
+
+        Ok(())
+    })
+}
+
+// the size of a pool of 20 connections opened at once, after each of 70 minutes
+fn size_over_time(lifetime_jitter: f64) -> anyhow::Result<Vec<u32>> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .max_connections(20)
+            .max_lifetime(30 * MINUTE)
+            .idle_timeout(None)
+            .lifetime_jitter(lifetime_jitter)
+            .connect("memory:")
+            .await?;
+
+        let conns = future::try_join_all((0..20).map(|_| pool.acquire())).await?;
+
+        for conn in &conns {
+            let max_lifetime = conn.max_lifetime().unwrap();
+            let band = (30.0 * (1.0 - lifetime_jitter))..=(30.0 * (1.0 + lifetime_jitter));
+
+            assert!(band.contains(&(max_lifetime.as_secs_f64() / 60.0)));
+        }
+
+        drop(conns);
+
+        let mut sizes = Vec::new();
+
+        for _ in 0..70 {
+            VirtualTime::advance(MINUTE).await;
+            sizes.push(pool.size());
+        }
+
+        Ok(sizes)
+    })
+}
+
+#[test]
+fn it_spreads_the_max_lifetime_of_connections_opened_together() -> anyhow::Result<()> {
+    // every connection is closed by the same run of the reaper
+    let sizes = size_over_time(0.0)?;
+
+    let closes = sizes.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    assert_eq!(closes, 1, "{:?}", sizes);
+    assert_eq!(sizes.last(), Some(&0));
+
+    // the connections are closed a few at a time between 27 and 33 minutes
+    let sizes = size_over_time(0.1)?;
+
+    assert!(sizes[..26].iter().all(|&size| size == 20), "{:?}", sizes);
+    assert!(sizes[34..].iter().all(|&size| size == 0), "{:?}", sizes);
+
+    let closes = sizes.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    assert!(closes >= 3, "{:?}", sizes);
+
+    Ok(())
+}
+
+#[test]
+fn it_times_out_acquire_when_the_pool_is_exhausted() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .max_connections(1)
+            .connect_timeout(30 * MINUTE)
+            .connect("memory:")
+            .await?;
+
+        let conn = pool.acquire().await?;
+
+        let start = sqlx_rt::Instant::now();
+        let res = pool.acquire().await;
+
+        assert!(matches!(res, Err(Error::PoolTimedOut)), "{:?}", res.err());
+
+        // timers have a resolution of a millisecond
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= 30 * MINUTE && elapsed < 31 * MINUTE,
+            "{:?}",
+            elapsed
+        );
+
+        // a connection released while waiting is handed over
+        let waiting = {
+            let pool = pool.clone();
+            sqlx_rt::spawn(async move { pool.acquire().await.map(drop) })
+        };
+
+        VirtualTime::advance(MINUTE).await;
+        drop(conn);
+
+        waiting.await??;
+
+        Ok(())
+    })
+}
+
+// `Executor` is not imported here: pools, pool connections, transactions and connections all
+// have the common methods of their own
+#[test]
+fn it_runs_queries_without_the_executor_trait() -> anyhow::Result<()> {
+    use sqlx::Row;
+
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new().connect("memory:").await?;
+
+        pool.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+            .await?;
+
+        let mut conn = pool.acquire().await?;
+        let done = conn
+            .execute("INSERT INTO users (name) VALUES ('alice')")
+            .await?;
+        assert_eq!(done.rows_affected(), 1);
+
+        let mut tx = conn.begin().await?;
+        tx.execute("INSERT INTO users (name) VALUES ('bob')")
+            .await?;
+        assert_eq!(tx.fetch_all("SELECT name FROM users").await?.len(), 2);
+        tx.commit().await?;
+
+        let row = pool
+            .fetch_one("SELECT name FROM users WHERE id = 1")
+            .await?;
+        assert_eq!(row.try_get::<String, _>(0)?, "alice");
+
+        assert!(pool
+            .fetch_optional("SELECT name FROM users WHERE id = 3")
+            .await?
+            .is_none());
+
+        let mut conn = conn.detach();
+        assert_eq!(conn.fetch_all("SELECT id FROM users").await?.len(), 2);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_tracks_checked_out_connections() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .max_connections(2)
+            .checkout_warning_after(Duration::from_millis(50))
+            .connect("memory:")
+            .await?;
+
+        let first = pool.acquire().await?;
+        let mut second = pool.acquire().await?;
+        second.leaked_detach();
+
+        assert_eq!(pool.stats().checked_out, 2);
+
+        VirtualTime::advance(Duration::from_millis(150)).await;
+
+        drop(first);
+        drop(second);
+
+        let stats = pool.stats();
+        assert_eq!(stats.checked_out, 0);
+        assert!(stats.longest_checkout >= Duration::from_millis(150));
+        assert!(stats.longest_checkout < Duration::from_millis(200));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_frees_the_slot_of_a_detached_connection() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .max_connections(2)
+            .connect("memory:")
+            .await?;
+
+        let first = pool.acquire().await?;
+        let _second = pool.acquire().await?;
+        assert!(pool.try_acquire().is_none());
+
+        let mut detached = first.detach();
+        assert_eq!(pool.size(), 1);
+
+        let _third = pool.acquire().await?;
+        assert_eq!(pool.size(), 2);
+
+        detached.ping().await?;
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_replaces_detached_connections_up_to_min_connections() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .min_connections(2)
+            .idle_timeout(MINUTE)
+            .connect("memory:")
+            .await?;
+
+        assert_eq!(pool.size(), 2);
+
+        let _detached = pool.acquire().await?.detach();
+        assert_eq!(pool.size(), 1);
+
+        VirtualTime::advance(2 * MINUTE).await;
+        assert_eq!(pool.size(), 2);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_never_opens_a_connection_in_try_acquire() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new().connect_lazy("memory:")?;
+
+        assert!(pool.try_acquire().is_none());
+        assert_eq!(pool.size(), 0);
+
+        drop(pool.acquire().await?);
+        assert!(pool.try_acquire().is_some());
+
+        let partitioned = partitioned_pool(10, MemoryPoolOptions::new());
+
+        assert!(partitioned.try_acquire(&"a").is_none());
+        assert_eq!(partitioned.num_partitions(), 0);
+
+        drop(partitioned.acquire(&"a").await?);
+        assert!(partitioned.try_acquire(&"a").is_some());
+        assert!(partitioned.try_acquire(&"b").is_none());
+
+        Ok(())
+    })
+}
+
+// on a runtime of several threads, so that acquires are cancelled while other threads hand
+// connections to them
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn it_hands_connections_past_cancelled_acquires() -> anyhow::Result<()> {
+    let pool = MemoryPoolOptions::new()
+        .max_connections(2)
+        .connect_timeout(Duration::from_secs(10))
+        .connect("memory:")
+        .await?;
+
+    let start = std::time::Instant::now();
+
+    let tasks: Vec<_> = (0..16)
+        .map(|task| {
+            let pool = pool.clone();
+
+            tokio::spawn(async move {
+                for i in 0..250_u64 {
+                    if (task + i) % 3 == 0 {
+                        // given up after being polled once or twice, often just as another
+                        // thread hands a connection to it
+                        let acquire = pool.acquire();
+                        futures::pin_mut!(acquire);
+                        let _ = future::select(acquire, sqlx_rt::yield_now().boxed()).await;
+                    } else {
+                        let conn = pool.acquire().await?;
+
+                        for _ in 0..i % 4 {
+                            sqlx_rt::yield_now().await;
+                        }
+
+                        drop(conn);
+                    }
+                }
+
+                Ok::<_, Error>(())
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        task.await??;
+    }
+
+    // a single connection left idle while tasks waited would have held one up for the whole
+    // of `connect_timeout`
+    let elapsed = start.elapsed();
+    assert!(elapsed < Duration::from_secs(5), "{:?}", elapsed);
+
+    let wait = Duration::from_millis(100);
+    let first = tokio::time::timeout(wait, pool.acquire()).await??;
+    let second = tokio::time::timeout(wait, pool.acquire()).await??;
+
+    assert_eq!(pool.size(), 2);
+    drop((first, second));
+
+    Ok(())
+}
+
+#[test]
+fn it_hands_the_slots_of_a_grown_pool_to_waiting_tasks() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .max_connections(1)
+            .connect_timeout(30 * MINUTE)
+            .connect("memory:")
+            .await?;
+
+        let _conn = pool.acquire().await?;
+
+        let waiting: Vec<_> = (0..2)
+            .map(|_| {
+                let pool = pool.clone();
+                sqlx_rt::spawn(async move { pool.acquire().await })
+            })
+            .collect();
+
+        VirtualTime::advance(MINUTE).await;
+
+        // nothing is returned to the pool; the waiting tasks open connections of their own
+        pool.resize(PoolResize::new().max_connections(3))
+            .expect("valid resize");
+
+        for task in waiting {
+            drop(task.await?);
+        }
+
+        let stats = pool.stats();
+        assert_eq!(stats.max_connections, 3);
+        assert_eq!(stats.connections_opened, 3);
+        assert_eq!(stats.pending_shrink, 0);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_shrinks_a_pool_as_its_connections_are_returned() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .max_connections(3)
+            .connect_timeout(MINUTE)
+            .connect("memory:")
+            .await?;
+
+        let first = pool.acquire().await?;
+        let second = pool.acquire().await?;
+        drop(pool.acquire().await?);
+        assert_eq!(pool.size(), 3);
+
+        // the idle connection is closed right away, the others are left to their users
+        pool.resize(PoolResize::new().max_connections(1))
+            .expect("valid resize");
+
+        VirtualTime::advance(Duration::from_millis(1)).await;
+        assert_eq!(pool.size(), 2);
+        assert_eq!(pool.stats().max_connections, 1);
+        assert_eq!(pool.stats().pending_shrink, 1);
+
+        drop(first);
+
+        VirtualTime::advance(Duration::from_millis(1)).await;
+        assert_eq!(pool.size(), 1);
+        assert_eq!(pool.stats().pending_shrink, 0);
+
+        // the last connection is kept, and no other is opened next to it
+        drop(second);
+        let conn = pool.acquire().await?;
+
+        let res = pool.acquire().await;
+        assert!(matches!(res, Err(Error::PoolTimedOut)), "{:?}", res.err());
+
+        drop(conn);
+        assert_eq!(pool.size(), 1);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_rejects_a_resize_to_invalid_limits() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .max_connections(4)
+            .min_connections(2)
+            .connect("memory:")
+            .await?;
+
+        let errors = pool
+            .resize(PoolResize::new().max_connections(1).idle_timeout(MINUTE))
+            .unwrap_err();
+
+        let options: Vec<_> = errors.iter().map(|error| error.option()).collect();
+        assert_eq!(options, ["min_connections"]);
+
+        // nothing was changed
+        let stats = pool.stats();
+        assert_eq!((stats.max_connections, stats.min_connections), (4, 2));
+        assert_eq!(pool.size(), 2);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_opens_min_connections_raised_by_a_resize() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("memory:")
+            .await?;
+
+        assert_eq!(pool.size(), 1);
+
+        pool.resize(PoolResize::new().min_connections(3))
+            .expect("valid resize");
+
+        VirtualTime::advance(Duration::from_millis(1)).await;
+        assert_eq!(pool.size(), 3);
+
+        // the pool had no reaper, as it had no timeouts; the resize starts one
+        pool.resize(PoolResize::new().min_connections(0).idle_timeout(MINUTE))
+            .expect("valid resize");
+
+        VirtualTime::advance(3 * MINUTE).await;
+        assert_eq!(pool.size(), 0);
+
+        Ok(())
+    })
+}
+
+// acquires from many threads while the pool is grown and shrunk under them
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn it_resizes_while_connections_are_acquired() -> anyhow::Result<()> {
+    let pool = MemoryPoolOptions::new()
+        .max_connections(2)
+        .connect_timeout(Duration::from_secs(10))
+        .connect("memory:")
+        .await?;
+
+    let start = std::time::Instant::now();
+    let resizing = Arc::new(AtomicBool::new(true));
+    let peak = Arc::new(AtomicUsize::new(0));
+
+    let resizer = {
+        let pool = pool.clone();
+        let resizing = Arc::clone(&resizing);
+
+        tokio::spawn(async move {
+            for max in [8, 1, 4, 2, 6, 1, 3].iter().cycle().take(70) {
+                pool.resize(PoolResize::new().max_connections(*max))
+                    .expect("valid resize");
+
+                sqlx_rt::yield_now().await;
+            }
+
+            resizing.store(false, Ordering::SeqCst);
+        })
+    };
+
+    let tasks: Vec<_> = (0..16)
+        .map(|_| {
+            let pool = pool.clone();
+            let resizing = Arc::clone(&resizing);
+            let peak = Arc::clone(&peak);
+
+            tokio::spawn(async move {
+                while resizing.load(Ordering::SeqCst) {
+                    let conn = pool.acquire().await?;
+                    peak.fetch_max(pool.size() as usize, Ordering::SeqCst);
+
+                    sqlx_rt::yield_now().await;
+                    drop(conn);
+                }
+
+                Ok::<_, Error>(())
+            })
+        })
+        .collect();
+
+    resizer.await?;
+
+    for task in tasks {
+        task.await??;
+    }
+
+    // no task waited out `connect_timeout` for a slot the pool had
+    let elapsed = start.elapsed();
+    assert!(elapsed < Duration::from_secs(5), "{:?}", elapsed);
+    assert!(peak.load(Ordering::SeqCst) <= 8);
+
+    // converges on the last size once every connection is back
+    pool.resize(PoolResize::new().max_connections(2))
+        .expect("valid resize");
+
+    for _ in 0..100 {
+        if pool.stats().pending_shrink == 0 {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert!(pool.size() <= 2, "{}", pool.size());
+    assert_eq!(pool.stats().pending_shrink, 0);
+
+    let wait = Duration::from_millis(100);
+    let first = tokio::time::timeout(wait, pool.acquire()).await??;
+    let second = tokio::time::timeout(wait, pool.acquire()).await??;
+    drop((first, second));
+
+    Ok(())
+}
+
+#[test]
+fn it_breaks_down_the_time_of_each_acquire() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .max_connections(2)
+            .test_before_acquire(true)
+            .connect_timeout(30 * MINUTE)
+            .connect_lazy("memory:")?;
+
+        // the pool has room for both, so they are opened without waiting
+        let first = pool.acquire().await?;
+        let second = pool.acquire().await?;
+
+        for conn in &[&first, &second] {
+            let stats = conn.acquire_stats();
+            assert!(stats.connect.is_some(), "{:?}", stats);
+            assert_eq!(stats.queue_wait, Duration::default());
+            assert_eq!(stats.ping, None);
+        }
+
+        // three tasks wait for the two connections, each holding the one it gets for a second
+        let waiting: Vec<_> = (0..3)
+            .map(|_| {
+                let pool = pool.clone();
+
+                sqlx_rt::spawn(async move {
+                    let conn = pool.acquire().await?;
+                    sqlx_rt::sleep(Duration::from_secs(1)).await;
+
+                    Ok::<_, Error>(conn.acquire_stats())
+                })
+            })
+            .collect();
+
+        VirtualTime::advance(Duration::from_secs(5)).await;
+        drop((first, second));
+
+        let mut waits = Vec::new();
+
+        for task in waiting {
+            let stats = task.await??;
+
+            // idle connections are tested, and none is opened
+            assert!(stats.ping.is_some(), "{:?}", stats);
+            assert_eq!(stats.connect, None);
+
+            let parts = stats.queue_wait + stats.ping.unwrap_or_default();
+            assert!(parts <= stats.total, "{:?}", stats);
+            assert!(
+                stats.total - parts < Duration::from_millis(10),
+                "{:?}",
+                stats
+            );
+
+            waits.push(stats.queue_wait);
+        }
+
+        // two get the connections returned after five seconds, and the last one waits for
+        // either of them to be done with theirs
+        waits.sort();
+        assert!(waits[0] >= Duration::from_secs(5), "{:?}", waits);
+        assert!(waits[2] >= Duration::from_secs(6), "{:?}", waits);
+
+        let stats = pool.stats();
+        assert_eq!(stats.acquire_wait.count(), 5);
+        assert_eq!(stats.connect_time.count(), 2);
+
+        // the acquires that did not wait, and those that waited for five to ten seconds
+        assert_eq!(
+            stats.acquire_wait.quantile(0.4),
+            Some(Duration::from_millis(1))
+        );
+        assert_eq!(
+            stats.acquire_wait.quantile(1.0),
+            Some(Duration::from_secs(10))
+        );
+
+        // a window of the histogram holds only what happened in it
+        let before = pool.stats();
+        drop(pool.acquire().await?);
+
+        let window = pool.stats().acquire_wait.since(&before.acquire_wait);
+        assert_eq!(window.count(), 1);
+
+        Ok(())
+    })
+}
+
+// offers a pool of four connections 100 jobs a second for two seconds, each holding its
+// connection for 100ms, so it can only do 40 a second; returns how long each job that was let in
+// waited for its connection, from the shortest wait to the longest
+async fn overload(pool: &MemoryPool) -> anyhow::Result<Vec<Duration>> {
+    let jobs: Vec<_> = (0..200)
+        .map(|job| {
+            let pool = pool.clone();
+
+            sqlx_rt::spawn(async move {
+                sqlx_rt::sleep(Duration::from_millis(10 * job)).await;
+
+                let start = sqlx_rt::Instant::now();
+
+                match pool.acquire().await {
+                    Ok(_conn) => {
+                        let waited = start.elapsed();
+                        sqlx_rt::sleep(Duration::from_millis(100)).await;
+
+                        Ok(Some(waited))
+                    }
+
+                    Err(Error::PoolOverloaded) => Ok(None),
+                    Err(error) => Err(error),
+                }
+            })
+        })
+        .collect();
+
+    VirtualTime::advance(MINUTE).await;
+
+    let mut waits = Vec::new();
+
+    for job in jobs {
+        waits.extend(job.await??);
+    }
+
+    waits.sort();
+
+    Ok(waits)
+}
+
+fn p99(waits: &[Duration]) -> Duration {
+    waits[(waits.len() * 99 + 99) / 100 - 1]
+}
+
+#[test]
+fn it_sheds_load_to_bound_the_wait_for_connections() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let options = || {
+            MemoryPoolOptions::new()
+                .max_connections(4)
+                .connect_timeout(30 * MINUTE)
+        };
+
+        // every job gets in, and the queue grows until the jobs stop coming
+        let pool = options().connect_lazy("memory:")?;
+        let waits = overload(&pool).await?;
+
+        assert_eq!(waits.len(), 200);
+        assert!(p99(&waits) > Duration::from_secs(2), "{:?}", p99(&waits));
+
+        // with at most eight jobs waiting, none waits for more than three rounds of the four
+        // connections
+        let pool = options()
+            .admission_policy(Arc::new(RejectWhenWaitersExceed(8)))
+            .connect_lazy("memory:")?;
+        let waits = overload(&pool).await?;
+
+        assert!(
+            p99(&waits) <= Duration::from_millis(300),
+            "{:?}",
+            p99(&waits)
+        );
+        assert_eq!(pool.stats().acquires_rejected as usize, 200 - waits.len());
+        assert!(waits.len() < 120, "{}", waits.len());
+
+        // the estimate learns from the waits that are over, so it lets more in before shedding
+        let pool = options()
+            .admission_policy(Arc::new(RejectWhenEstimatedWaitExceeds(
+                Duration::from_millis(100),
+            )))
+            .connect_lazy("memory:")?;
+        let waits = overload(&pool).await?;
+
+        assert!(p99(&waits) < Duration::from_secs(1), "{:?}", p99(&waits));
+        assert_eq!(pool.stats().acquires_rejected as usize, 200 - waits.len());
+        assert!(waits.len() < 120, "{}", waits.len());
+
+        Ok(())
+    })
+}
+
+// sheds acquires of low priority as soon as any task waits
+struct ShedLowPriority;
+
+impl AdmissionPolicy for ShedLowPriority {
+    fn admit(&self, load: &PoolLoad<'_>, priority: Priority) -> bool {
+        priority > Priority::Low || load.waiters() == 0
+    }
+}
+
+#[test]
+fn it_sheds_acquires_by_priority() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new()
+            .max_connections(1)
+            .connect_timeout(30 * MINUTE)
+            .admission_policy(Arc::new(ShedLowPriority))
+            .connect("memory:")
+            .await?;
+
+        // the policy is not asked while there is a connection to be had
+        let conn = pool.acquire_with_priority(Priority::Low).await?;
+
+        let waiting = {
+            let pool = pool.clone();
+            sqlx_rt::spawn(async move { pool.acquire().await.map(drop) })
+        };
+
+        VirtualTime::advance(MINUTE).await;
+
+        let res = pool.acquire_with_priority(Priority::Low).await;
+        assert!(matches!(res, Err(Error::PoolOverloaded)), "{:?}", res.err());
+        assert_eq!(pool.stats().acquires_rejected, 1);
+
+        // others still wait their turn
+        let waiting_too = {
+            let pool = pool.clone();
+            sqlx_rt::spawn(
+                async move { pool.acquire_with_priority(Priority::High).await.map(drop) },
+            )
+        };
+
+        VirtualTime::advance(MINUTE).await;
+        drop(conn);
+
+        waiting.await??;
+        waiting_too.await??;
+
+        assert_eq!(pool.stats().acquires_rejected, 1);
+
+        Ok(())
+    })
+}
+
+fn partitioned_pool(
+    max_connections: u32,
+    partition: MemoryPoolOptions,
+) -> PartitionedPool<Memory, &'static str> {
+    PartitionedPoolOptions::new()
+        .max_connections(max_connections)
+        .partition_options(partition)
+        .partition_idle_timeout(10 * MINUTE)
+        .connect_lazy_with(|_| Ok(MemoryConnectOptions::new()))
+}
+
+#[test]
+fn it_limits_connections_across_partitions() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = partitioned_pool(
+            2,
+            MemoryPoolOptions::new()
+                .max_connections(2)
+                .connect_timeout(MINUTE),
+        );
+
+        let first = pool.acquire(&"a").await?;
+        let second = pool.acquire(&"b").await?;
+
+        assert_eq!(pool.size(), 2);
+        assert_eq!(pool.num_partitions(), 2);
+
+        let res = pool.acquire(&"a").await;
+        assert!(matches!(res, Err(Error::PoolTimedOut)), "{:?}", res.err());
+
+        drop(first);
+        drop(second);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_takes_idle_connections_from_other_partitions() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = partitioned_pool(
+            2,
+            MemoryPoolOptions::new()
+                .max_connections(2)
+                .connect_timeout(MINUTE),
+        );
+
+        let first = pool.acquire(&"a").await?;
+        let second = pool.acquire(&"a").await?;
+        drop(first);
+        drop(second);
+
+        assert_eq!(pool.size(), 2);
+        assert_eq!(pool.num_idle(), 2);
+
+        // the limit is reached, so an idle connection of `a` is closed for `b`
+        let conn = pool.acquire(&"b").await?;
+
+        assert_eq!(pool.size(), 2);
+        assert_eq!(pool.partition_stats(&"a").map(|s| s.checked_out), Some(0));
+        assert_eq!(pool.partition_stats(&"b").map(|s| s.checked_out), Some(1));
+
+        drop(conn);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_evicts_partitions_unused_for_longer_than_partition_idle_timeout() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = partitioned_pool(10, MemoryPoolOptions::new().idle_timeout(None));
+
+        drop(pool.acquire(&"a").await?);
+        let held = pool.acquire(&"b").await?;
+
+        VirtualTime::advance(5 * MINUTE).await;
+        assert_eq!(pool.num_partitions(), 2);
+
+        // `b` has a connection checked out, so it is kept
+        VirtualTime::advance(10 * MINUTE).await;
+        assert_eq!(pool.keys(), vec!["b"]);
+        assert_eq!(pool.size(), 1);
+
+        // the evicted partition still counts towards the totals
+        let stats = pool.stats();
+        assert_eq!(stats.connections_opened, 2);
+        assert_eq!(stats.checked_out, 1);
+
+        let by_partition = pool.stats_by_partition();
+        assert_eq!(by_partition.len(), 1);
+        assert_eq!(by_partition[0].1.connections_opened, 1);
+
+        drop(held);
+
+        // acquiring from an evicted partition creates it anew
+        drop(pool.acquire(&"a").await?);
+        assert_eq!(pool.num_partitions(), 2);
+
+        pool.close().await;
+        assert!(pool.is_closed());
+        assert_eq!(pool.size(), 0);
+        assert!(matches!(pool.acquire(&"a").await, Err(Error::PoolClosed)));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_signals_tasks_when_the_pool_starts_closing() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new().connect("memory:").await?;
+
+        let mut close_event = pool.close_event();
+
+        // runs to completion while the pool is open
+        assert_eq!(close_event.do_until(future::ready(1)).await?, 1);
+
+        // a worker holding a connection until it is told the pool is closing
+        let worker = {
+            let pool = pool.clone();
+
+            sqlx_rt::spawn(async move {
+                let mut close_event = pool.close_event();
+                let conn = pool.acquire().await?;
+
+                let result = close_event.do_until(future::pending::<()>()).await;
+                drop(conn);
+
+                Ok::<_, Error>(result)
+            })
+        };
+
+        while pool.num_idle() > 0 {
+            sqlx_rt::yield_now().await;
+        }
+
+        assert_eq!(pool.size(), 1);
+
+        // does not resolve until the worker has returned its connection
+        pool.close().await;
+        assert_eq!(pool.size(), 0);
+
+        assert!(matches!(worker.await??, Err(Error::PoolClosed)));
+
+        // resolves right away once closed, without running the future
+        let mut ran = false;
+        let result = close_event.do_until(async { ran = true }).await;
+
+        assert!(matches!(result, Err(Error::PoolClosed)));
+        assert!(!ran);
+
+        pool.close_event().await;
+
+        Ok(())
+    })
+}
+
+#[test]
+fn it_reports_every_connection_that_could_not_be_opened() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let refuse = |options: MemoryPoolOptions| {
+            options
+                .after_connect(|_| Box::pin(async { Err(Error::Configuration("refused".into())) }))
+        };
+
+        let error = refuse(MemoryPoolOptions::new().min_connections(3))
+            .connect("memory:")
+            .await
+            .unwrap_err();
+
+        match &error {
+            Error::Aggregate(errors) => {
+                assert_eq!(errors.attempted(), 3);
+
+                let labels: Vec<&str> = errors.errors().iter().map(|(l, _)| &**l).collect();
+                assert_eq!(labels, ["connection 1", "connection 2", "connection 3"]);
+
+                assert!(errors
+                    .errors()
+                    .iter()
+                    .all(|(_, error)| matches!(error, Error::Configuration(_))));
+            }
+
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        assert!(error
+            .to_string()
+            .starts_with("3 of 3 connections failed:\n  connection 1: "));
+
+        // the first failure, for code that walks the chain of sources
+        let source = std::error::Error::source(&error).unwrap();
+        assert!(matches!(
+            source.downcast_ref::<Error>(),
+            Some(Error::Configuration(_))
+        ));
+
+        // opening a single connection fails with its own error
+        let error = refuse(MemoryPoolOptions::new())
+            .connect("memory:")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, Error::Configuration(_)));
+
+        Ok(())
+    })
+}
+
+// helpers written against the traits, as applications write them
+#[derive(Default)]
+struct EventRecorder(Mutex<Vec<String>>);
+
+impl ConnectionEventListener for EventRecorder {
+    fn on_connect(&self, info: &ConnectionInfo) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("connect {}", info.database()));
+    }
+
+    fn on_close(&self, _: &ConnectionInfo, reason: CloseReason) {
+        self.0.lock().unwrap().push(format!("close {:?}", reason));
+    }
+}
+
+#[test]
+fn it_reports_why_connections_are_closed() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let events = Arc::new(EventRecorder::default());
+        let reject = Arc::new(AtomicBool::new(true));
+
+        let pool = MemoryPoolOptions::new()
+            .idle_timeout(MINUTE)
+            .max_lifetime(None)
+            .after_release({
+                let reject = Arc::clone(&reject);
+                move |_| !reject.swap(false, Ordering::SeqCst)
+            })
+            .connection_events(events.clone())
+            .connect("memory:")
+            .await?;
+
+        // rejected by `after_release`
+        drop(pool.acquire().await?);
+        assert_eq!(pool.size(), 0);
+
+        // reaped once idle for too long
+        drop(pool.acquire().await?);
+        VirtualTime::advance(2 * MINUTE).await;
+        assert_eq!(pool.size(), 0);
+
+        // closed on its own, and with the pool
+        pool.acquire().await?.detach().close().await?;
+        drop(pool.acquire().await?);
+        pool.close().await;
+
+        assert_eq!(
+            *events.0.lock().unwrap(),
+            [
+                "connect Memory",
+                "close ResetFailed",
+                "connect Memory",
+                "close IdleReaped",
+                "connect Memory",
+                "close Explicit",
+                "connect Memory",
+                "close Explicit",
+            ]
+        );
+
+        Ok(())
+    })
+}
+
+// the first password it provides has already expired, the others last for an hour
+#[derive(Default)]
+struct RotatingPassword(AtomicUsize);
+
+impl PasswordProvider for RotatingPassword {
+    fn password(&self) -> BoxFuture<'_, Result<Credential, Error>> {
+        let n = self.0.fetch_add(1, Ordering::SeqCst);
+
+        let expires_at = if n == 0 {
+            SystemTime::now() - MINUTE
+        } else {
+            SystemTime::now() + 60 * MINUTE
+        };
+
+        let credential = Credential::new(format!("token-{}", n)).expires_at(expires_at);
+
+        Box::pin(future::ready(Ok(credential)))
+    }
+}
+
+#[test]
+fn it_replaces_connections_whose_credential_expired_when_returned() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let provider = Arc::new(RotatingPassword::default());
+        let events = Arc::new(EventRecorder::default());
+
+        let pool = MemoryPoolOptions::new()
+            .password_provider(provider.clone())
+            .connection_events(events.clone())
+            .connect("memory:")
+            .await?;
+
+        // the connection keeps working while it is checked out
+        let mut conn = pool.acquire().await?;
+        conn.ping().await?;
+        assert_eq!(pool.stats().expired_credentials, 1);
+
+        drop(conn);
+        assert_eq!(pool.size(), 0);
+        assert_eq!(pool.stats().expired_credentials, 0);
+
+        // its replacement is opened with a fresh credential, and kept
+        drop(pool.acquire().await?);
+        assert_eq!(pool.size(), 1);
+        assert_eq!(pool.stats().expired_credentials, 0);
+        assert_eq!(provider.0.load(Ordering::SeqCst), 2);
+
+        assert_eq!(
+            *events.0.lock().unwrap(),
+            [
+                "connect Memory",
+                "close CredentialExpired",
+                "connect Memory"
+            ]
+        );
+
+        Ok(())
+    })
+}
+
+async fn insert<'c, E>(executor: E, n: i64) -> Result<(), Error>
+where
+    E: Executor<'c, Database = Memory>,
+{
+    sqlx::query("INSERT INTO numbers (n) VALUES (?)")
+        .bind(n)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
+async fn insert_twice<'c, A>(conn: A, n: i64) -> Result<(), Error>
+where
+    A: Acquire<'c, Database = Memory>,
+{
+    let mut conn = conn.acquire().await?;
+
+    insert(&mut *conn, n).await?;
+    insert(&mut *conn, n).await
+}
+
+async fn insert_in_transaction<'c, A>(conn: A, n: i64) -> Result<(), Error>
+where
+    A: Acquire<'c, Database = Memory>,
+{
+    let mut tx = conn.begin().await?;
+
+    insert(&mut tx, n).await?;
+    tx.commit().await
+}
+
+async fn begin_and_roll_back<DB: Database>(conn: &mut PoolConnection<DB>) -> Result<(), Error> {
+    Acquire::begin(conn).await?.rollback().await
+}
+
+#[test]
+fn it_lends_a_pooled_connection_to_helpers_as_often_as_needed() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new().connect("memory:").await?;
+        let mut conn = pool.acquire().await?;
+
+        conn.execute("CREATE TABLE numbers (n INTEGER)").await?;
+
+        // a loop of queries on one connection
+        for n in 0..3 {
+            insert(&mut conn, n).await?;
+            insert_twice(&mut conn, n).await?;
+        }
+
+        // begin, query, then begin again
+        insert_in_transaction(&mut conn, 3).await?;
+        insert(&mut conn, 4).await?;
+        insert_in_transaction(&mut conn, 5).await?;
+
+        // from code that is generic over the database
+        begin_and_roll_back(&mut conn).await?;
+        begin_and_roll_back(&mut conn).await?;
+
+        // through a reference that is passed on
+        let conn = &mut conn;
+
+        insert_twice(&mut *conn, 6).await?;
+        insert_in_transaction(&mut *conn, 7).await?;
+        insert(&mut **conn, 8).await?;
+
+        let numbers: Vec<i64> = sqlx::query_scalar("SELECT n FROM numbers")
+            .fetch_all(&mut *conn)
+            .await?;
+
+        assert_eq!(numbers.len(), 16);
+
+        Ok(())
+    })
+}
+
+#[tokio::test]
+async fn it_moves_a_transaction_of_the_pool_into_another_task() -> anyhow::Result<()> {
+    let pool = MemoryPoolOptions::new()
+        .max_connections(1)
+        .connect("memory:")
+        .await?;
+
+    pool.execute("CREATE TABLE numbers (n INTEGER)").await?;
+
+    // committed on another task, which returns the connection to the pool
+    let mut tx = pool.begin().await?;
+    insert(&mut tx, 1).await?;
+
+    tokio::spawn(async move { tx.commit().await }).await??;
+
+    // dropped on another task, which rolls it back before the connection is idle again
+    let mut tx = pool.begin().await?;
+    insert(&mut tx, 2).await?;
+
+    tokio::spawn(async move { drop(tx) }).await?;
+
+    let numbers: Vec<i64> = sqlx::query_scalar("SELECT n FROM numbers")
+        .fetch_all(&pool)
+        .await?;
+
+    assert_eq!(numbers, [1]);
+    assert_eq!(pool.size(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn it_serves_cached_queries_without_a_round_trip() -> anyhow::Result<()> {
+    VirtualTime::new().block_on(async {
+        let pool = MemoryPoolOptions::new().connect("memory:").await?;
+
+        pool.execute("CREATE TABLE numbers (n INTEGER)").await?;
+        pool.execute("INSERT INTO numbers (n) VALUES (1)").await?;
+
+        let select = |n: i64| {
+            sqlx::query_scalar("SELECT n FROM numbers WHERE n = ?")
+                .bind(n)
+                .cached(MINUTE)
+        };
+
+        let numbers: Vec<i64> = select(1).fetch_all(&pool).await?;
+        assert_eq!(numbers, [1]);
+
+        let stats = pool.stats();
+        assert_eq!((stats.query_cache_hits, stats.query_cache_misses), (0, 1));
+
+        // a warm cache never goes to the database, even after it has changed
+        pool.execute("INSERT INTO numbers (n) VALUES (1)").await?;
+        let queries = pool.stats().queries;
+
+        let numbers: Vec<i64> = select(1).fetch_all(&pool).await?;
+        assert_eq!(numbers, [1]);
+
+        let number: i64 = select(1).fetch_one(&pool).await?;
+        assert_eq!(number, 1);
+
+        let stats = pool.stats();
+        assert_eq!(stats.queries, queries);
+        assert_eq!((stats.query_cache_hits, stats.query_cache_misses), (2, 1));
+
+        // other bind values are another query
+        assert_eq!(select(2).fetch_optional(&pool).await?, None::<i64>);
+        assert_eq!(pool.stats().queries, queries + 1);
+
+        // as is one that is not cached
+        let numbers: Vec<i64> = sqlx::query_scalar("SELECT n FROM numbers WHERE n = ?")
+            .bind(1_i64)
+            .fetch_all(&pool)
+            .await?;
+        assert_eq!(numbers, [1, 1]);
+        assert_eq!(pool.stats().queries, queries + 2);
+
+        // until it expires
+        VirtualTime::advance(2 * MINUTE).await;
+
+        let numbers: Vec<i64> = select(1).fetch_all(&pool).await?;
+        assert_eq!(numbers, [1, 1]);
+
+        // or is invalidated
+        pool.execute("INSERT INTO numbers (n) VALUES (1)").await?;
+        pool.invalidate_query_cache();
+
+        let numbers: Vec<i64> = select(1).fetch_all(&pool).await?;
+        assert_eq!(numbers, [1, 1, 1]);
+
+        let stats = pool.stats();
+        assert_eq!(stats.queries, queries + 5);
+        assert_eq!((stats.query_cache_hits, stats.query_cache_misses), (2, 4));
+
+        Ok(())
+    })
+}
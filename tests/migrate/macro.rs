@@ -12,6 +12,7 @@ async fn same_output() -> anyhow::Result<()> {
         assert_eq!(e.description, r.description);
         assert_eq!(e.sql, r.sql);
         assert_eq!(e.checksum, r.checksum);
+        assert_eq!(e.no_tx, r.no_tx);
     }
 
     Ok(())
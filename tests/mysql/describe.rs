@@ -69,3 +69,46 @@ async fn uses_alias_name() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+// the nullability inferred for each column of a query, by the shape of the query
+const NULLABILITY: &[(&str, &[Option<bool>])] = &[
+    ("SELECT id, owner_id FROM tweet", &[Some(false), Some(true)]),
+    (
+        "SELECT t1.id, t2.text FROM tweet t1 INNER JOIN tweet t2 ON t1.owner_id = t2.id",
+        &[Some(false), Some(false)],
+    ),
+    (
+        "SELECT t1.id, t2.text FROM tweet t1 LEFT JOIN tweet t2 ON t1.owner_id = t2.id",
+        &[Some(false), Some(true)],
+    ),
+    (
+        "SELECT t1.id, t2.text FROM tweet t1 RIGHT JOIN tweet t2 ON t1.owner_id = t2.id",
+        &[Some(true), Some(false)],
+    ),
+    (
+        "SELECT t1.id, t2.id, t3.text FROM tweet t1 \
+         INNER JOIN tweet t2 ON t1.owner_id = t2.id \
+         LEFT JOIN tweet t3 ON t2.owner_id = t3.id",
+        &[Some(false), Some(false), Some(true)],
+    ),
+    (
+        "SELECT t1.id, t2.text FROM tweet t1 LEFT JOIN tweet t2 ON t1.owner_id = t2.id \
+         WHERE t1.id = ?",
+        &[Some(false), Some(true)],
+    ),
+    ("SELECT (SELECT id FROM tweet LIMIT 1) AS id", &[Some(true)]),
+];
+
+#[sqlx_macros::test]
+async fn it_describes_nullability_by_query_shape() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    for (query, expected) in NULLABILITY {
+        let d = conn.describe(query).await?;
+        let nullable: Vec<_> = (0..d.columns().len()).map(|i| d.nullable(i)).collect();
+
+        assert_eq!(nullable, *expected, "{}", query);
+    }
+
+    Ok(())
+}
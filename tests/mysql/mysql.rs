@@ -1,6 +1,9 @@
-use futures::TryStreamExt;
-use sqlx::mysql::{MySql, MySqlConnection, MySqlPool, MySqlPoolOptions, MySqlRow};
-use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
+use futures::{StreamExt, TryStreamExt};
+use sqlx::mysql::{
+    MySql, MySqlArguments, MySqlConnectOptions, MySqlConnection, MySqlFlavor, MySqlPool,
+    MySqlPoolOptions, MySqlRow, MySqlSslMode, MySqlValueFormat,
+};
+use sqlx::{Arguments, Column, Connection, Executor, Row, Statement, TypeInfo};
 use sqlx_test::{new, setup_if_needed};
 use std::env;
 
@@ -166,6 +169,45 @@ async fn it_can_fetch_one_and_ping() -> anyhow::Result<()> {
 }
 
 /// Test that we can interleave reads and writes to the database in one simple query.
+#[sqlx_macros::test]
+async fn it_recovers_from_a_query_dropped_part_way_through() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let mut query = conn.fetch_all("SELECT SLEEP(0.1)");
+    assert!(futures::poll!(&mut query).is_pending());
+    drop(query);
+
+    let val: i32 = conn.fetch_one("SELECT 1").await?.get(0);
+    assert_eq!(val, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rejects_queries_while_a_leaked_query_is_in_flight() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // a query that was sent, and will never be finished or dropped
+    let mut query = conn.fetch_all("SELECT SLEEP(0.1)");
+    assert!(futures::poll!(&mut query).is_pending());
+    std::mem::forget(query);
+
+    let res = conn.execute("SELECT 1").await;
+
+    assert!(
+        matches!(
+            res,
+            Err(sqlx::Error::ConnectionBusy {
+                operation: "fetch_many"
+            })
+        ),
+        "{:?}",
+        res
+    );
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_interleaves_reads_and_writes() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
@@ -237,6 +279,79 @@ async fn it_caches_statements() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_rejects_writes_on_read_only_connections() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut conn = MySqlConnection::connect_with(&options.clone().read_only(true)).await?;
+
+    let value: i32 = sqlx::query_scalar("# a comment\nSELECT 1 AS update_time")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, 1);
+
+    let err = conn.execute("DELETE FROM tweet").await.unwrap_err();
+    assert!(
+        matches!(&err, sqlx::Error::ReadOnlyViolation(sql) if sql == "DELETE FROM tweet"),
+        "{:?}",
+        err
+    );
+
+    // without the check the server still refuses to write
+    let mut conn =
+        MySqlConnection::connect_with(&options.read_only(true).read_only_statement_check(false))
+            .await?;
+
+    let read_only: i64 = sqlx::query_scalar("SELECT @@session.transaction_read_only")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(read_only, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_closes_statements_it_does_not_cache() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // without arguments this is a plain text query, so it prepares nothing itself
+    async fn session_status(conn: &mut MySqlConnection, name: &str) -> anyhow::Result<u64> {
+        let row = conn
+            .fetch_one(&*format!("SHOW SESSION STATUS LIKE '{}'", name))
+            .await?;
+
+        Ok(row.get::<String, _>(1).parse()?)
+    }
+
+    let prepared = session_status(&mut conn, "Com_stmt_prepare").await?;
+    let closed = session_status(&mut conn, "Com_stmt_close").await?;
+
+    for i in 0..3 {
+        let row = sqlx::query("SELECT ?")
+            .bind(i)
+            .persistent(false)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(i, row.get::<u32, _>(0));
+    }
+
+    assert_eq!(0, conn.cached_statements_size());
+
+    // the close of the last statement goes out with the next command
+    assert_eq!(
+        session_status(&mut conn, "Com_stmt_prepare").await?,
+        prepared + 3
+    );
+    assert_eq!(
+        session_status(&mut conn, "Com_stmt_close").await?,
+        closed + 3
+    );
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_bind_null_and_non_null_issue_540() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
@@ -387,3 +502,1020 @@ async fn test_issue_622() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_executes_returning_id() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE insert_ids (id BIGINT AUTO_INCREMENT PRIMARY KEY, name TEXT)",
+    )
+    .await?;
+
+    let first = sqlx::query("INSERT INTO insert_ids (name) VALUES (?)")
+        .bind("first")
+        .execute_returning_id(&mut conn)
+        .await?;
+    let second = sqlx::query("INSERT INTO insert_ids (name) VALUES (?)")
+        .bind("second")
+        .execute_returning_id(&mut conn)
+        .await?;
+    assert_eq!(second, first + 1);
+
+    let error = sqlx::query("UPDATE insert_ids SET name = 'renamed'")
+        .execute_returning_id(&mut conn)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, sqlx::Error::InsertIdNotFound));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_streams_every_result_of_raw_sql() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let results: Vec<_> = sqlx::raw_sql(
+        "CREATE TEMPORARY TABLE raw_sql_test (id INT); \
+         INSERT INTO raw_sql_test VALUES (1), (2); \
+         SELECT id FROM raw_sql_test ORDER BY id; \
+         SELECT 'a' AS first, 'b' AS second",
+    )
+    .fetch_many(&mut conn)
+    .try_collect()
+    .await?;
+
+    let rows: Vec<_> = results
+        .iter()
+        .filter_map(|result| result.as_ref().right())
+        .map(|row| row.len())
+        .collect();
+    assert_eq!(rows, [1, 1, 2]);
+
+    assert_eq!(conn.cached_statements_size(), 0);
+
+    // MySQL stops at the statement that failed
+    let rows: Vec<_> = sqlx::raw_sql("SELECT 1; SELECT * FROM raw_sql_missing; SELECT 2")
+        .fetch(&mut conn)
+        .collect()
+        .await;
+    assert_eq!(rows.len(), 2);
+    assert!(rows[0].is_ok());
+    assert!(rows[1].is_err());
+
+    conn.ping().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_resolves_duplicate_column_names_to_the_first_column() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    for _ in 0..2 {
+        // the second run uses the cached statement
+        let row = sqlx::query("SELECT 1 AS id, 2 AS id, 3 AS other")
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(row.try_get::<i64, _>("id")?, 1);
+        assert_eq!(row.try_get::<i64, _>("other")?, 3);
+        assert!(row.try_get::<i64, _>("missing").is_err());
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_runs_queries_through_an_erased_executor() -> anyhow::Result<()> {
+    use sqlx::erased::{ErasedArguments, ErasedExecutor, ErasedValue};
+
+    let mut conn = new::<MySql>().await?;
+    let executor: &mut dyn ErasedExecutor = &mut conn;
+
+    executor
+        .execute_erased(
+            r#"
+CREATE TEMPORARY TABLE erased (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    name TEXT,
+    data VARBINARY(10),
+    score DOUBLE
+)
+            "#,
+            ErasedArguments::new(),
+        )
+        .await?;
+
+    let mut arguments = ErasedArguments::new();
+    arguments.add("ferris");
+    arguments.add(vec![0_u8, 1]);
+    arguments.add(None::<f64>);
+
+    let result = executor
+        .execute_erased(
+            "INSERT INTO erased (name, data, score) VALUES (?, ?, ?)",
+            arguments,
+        )
+        .await?;
+    assert_eq!(result.rows_affected(), 1);
+    assert_eq!(result.last_insert_id(), Some(1));
+
+    let row = executor
+        .fetch_optional_erased(
+            "SELECT * FROM erased WHERE id = ?",
+            vec![1].into_iter().collect(),
+        )
+        .await?
+        .unwrap();
+
+    assert_eq!(
+        row.values(),
+        [
+            ErasedValue::Int(1),
+            ErasedValue::Text("ferris".into()),
+            ErasedValue::Bytes(vec![0, 1]),
+            ErasedValue::Null,
+        ]
+    );
+
+    // unsigned values above `i64::MAX` do not fit
+    let error = executor
+        .fetch_all_erased(
+            "SELECT CAST(18446744073709551615 AS UNSIGNED)",
+            ErasedArguments::new(),
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(error, sqlx::Error::ColumnDecode { .. }),
+        "{}",
+        error
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_distinguishes_binary_and_text_columns() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE mixed_charsets (
+    utf8 VARCHAR(10) CHARACTER SET utf8mb4,
+    latin1 VARCHAR(10) CHARACTER SET latin1,
+    varbinary VARBINARY(10),
+    fixed BINARY(4),
+    bytes BLOB
+)
+        "#,
+    )
+    .await?;
+
+    sqlx::query("INSERT INTO mixed_charsets VALUES (?, ?, ?, ?, ?)")
+        .bind("héllo")
+        .bind("héllo")
+        .bind(&b"valid"[..])
+        .bind(&b"ab"[..])
+        .bind(&b"\xff\xfe"[..])
+        .execute(&mut conn)
+        .await?;
+
+    // once from the text protocol, once from the binary protocol
+    let rows = vec![
+        conn.fetch_one("SELECT * FROM mixed_charsets").await?,
+        sqlx::query("SELECT * FROM mixed_charsets")
+            .fetch_one(&mut conn)
+            .await?,
+    ];
+
+    for row in rows {
+        let names: Vec<_> = row.columns().iter().map(|c| c.type_info().name()).collect();
+        assert_eq!(names, ["VARCHAR", "VARCHAR", "VARBINARY", "BINARY", "BLOB"]);
+
+        // text is sent in the connection character set, whatever the column's is
+        assert_eq!(row.try_get::<String, _>("utf8")?, "héllo");
+        assert_eq!(row.try_get::<String, _>("latin1")?, "héllo");
+        assert_eq!(row.try_get::<Vec<u8>, _>("latin1")?, "héllo".as_bytes());
+
+        assert_eq!(row.try_get::<String, _>("varbinary")?, "valid");
+        assert_eq!(row.try_get::<Vec<u8>, _>("varbinary")?, b"valid");
+
+        // the padding of BINARY(n) is returned as stored
+        assert_eq!(row.try_get::<Vec<u8>, _>("fixed")?, b"ab\0\0");
+        assert_eq!(row.try_get::<String, _>("fixed")?, "ab\0\0");
+
+        assert_eq!(row.try_get::<Vec<u8>, _>("bytes")?, b"\xff\xfe");
+        let error = row.try_get::<String, _>("bytes").unwrap_err();
+        assert!(error.to_string().contains("BLOB"), "{}", error);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sets_session_variables_for_the_transaction() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let (timeout, time_zone): (u64, String) =
+        sqlx::query_as("SELECT @@SESSION.max_execution_time, @@SESSION.time_zone")
+            .fetch_one(&mut conn)
+            .await?;
+
+    let mut tx = conn.begin().await?;
+    tx.set_local("max_execution_time", 5000_u64).await?;
+    tx.set_locals(vec![("time_zone", "+01:00"), ("time_zone", "+02:00")])
+        .await?;
+
+    // a savepoint that is released keeps its variables until the transaction ends
+    let mut savepoint = tx.begin().await?;
+    savepoint.set_local("max_execution_time", 6000_u64).await?;
+    savepoint.commit().await?;
+
+    let row: (u64, String) =
+        sqlx::query_as("SELECT @@SESSION.max_execution_time, @@SESSION.time_zone")
+            .fetch_one(&mut tx)
+            .await?;
+    assert_eq!(row, (6000, "+02:00".to_owned()));
+
+    // one that is rolled back restores them
+    let mut savepoint = tx.begin().await?;
+    savepoint.set_local("max_execution_time", 7000_u64).await?;
+    savepoint.rollback().await?;
+
+    let value: u64 = sqlx::query_scalar("SELECT @@SESSION.max_execution_time")
+        .fetch_one(&mut tx)
+        .await?;
+    assert_eq!(value, 6000);
+
+    assert!(tx.set_local("time_zone = 0, @x", "").await.is_err());
+
+    tx.commit().await?;
+
+    let row: (u64, String) =
+        sqlx::query_as("SELECT @@SESSION.max_execution_time, @@SESSION.time_zone")
+            .fetch_one(&mut conn)
+            .await?;
+    assert_eq!(row, (timeout, time_zone.clone()));
+
+    // dropping the transaction restores them with the queued rollback
+    let mut tx = conn.begin().await?;
+    tx.set_local("time_zone", "+03:00").await?;
+    drop(tx);
+
+    let value: String = sqlx::query_scalar("SELECT @@SESSION.time_zone")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, time_zone);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_commits_prepared_xa_transactions_from_another_connection() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TABLE IF NOT EXISTS two_phase (id INT)")
+        .await?;
+    conn.execute("DELETE FROM two_phase").await?;
+
+    // an ordinary transaction cannot be prepared
+    let tx = conn.begin().await?;
+    let error = tx.prepare_two_phase("sqlx test: plain").await.unwrap_err();
+    assert!(matches!(error, sqlx::Error::Configuration(_)), "{}", error);
+
+    let mut tx = conn.begin_two_phase("sqlx's test: commit").await?;
+    tx.execute("INSERT INTO two_phase VALUES (1)").await?;
+    let committed = tx.prepare_two_phase("sqlx's test: commit").await?;
+
+    let mut tx = conn.begin_two_phase("sqlx's test: rollback").await?;
+    tx.execute("INSERT INTO two_phase VALUES (2)").await?;
+    let rolled_back = tx.prepare_two_phase("sqlx's test: rollback").await?;
+
+    // an XA transaction that is not prepared commits in one phase
+    let mut tx = conn.begin_two_phase("sqlx test: one phase").await?;
+    tx.execute("INSERT INTO two_phase VALUES (3)").await?;
+    tx.commit().await?;
+
+    conn.close().await?;
+
+    let mut conn = new::<MySql>().await?;
+    let prepared = conn.list_prepared().await?;
+    assert!(prepared.contains(&committed), "{:?}", prepared);
+    assert!(prepared.contains(&rolled_back), "{:?}", prepared);
+
+    conn.commit_prepared(committed.gid()).await?;
+    conn.rollback_prepared(rolled_back.gid()).await?;
+
+    let ids: Vec<i32> = sqlx::query_scalar("SELECT id FROM two_phase ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+    assert_eq!(ids, [1, 3]);
+
+    // a dropped XA transaction is rolled back
+    let mut tx = conn.begin_two_phase("sqlx test: dropped").await?;
+    tx.execute("INSERT INTO two_phase VALUES (4)").await?;
+    drop(tx);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM two_phase")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_replaces_invalid_utf8_on_lossy_connections() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+
+    let setup = r#"
+CREATE TEMPORARY TABLE legacy (name VARCHAR(10) CHARACTER SET latin1, data VARBINARY(10));
+INSERT INTO legacy VALUES ('café', X'FFFE'), ('plain', X'FFFE');
+SET character_set_results = NULL;
+    "#;
+
+    // with no result character set, latin1 text is sent as stored
+    let mut conn = MySqlConnection::connect_with(&options).await?;
+    conn.execute(setup).await?;
+
+    let row = conn.fetch_one("SELECT name FROM legacy").await?;
+    assert!(row.try_get::<String, _>("name").is_err());
+
+    let mut conn = MySqlConnection::connect_with(&options.lossy_utf8(true)).await?;
+    conn.execute(setup).await?;
+
+    // once from the text protocol, once from the binary protocol
+    for results in vec![
+        conn.fetch_many("SELECT * FROM legacy")
+            .try_collect::<Vec<_>>()
+            .await?,
+        sqlx::query("SELECT * FROM legacy")
+            .fetch_many(&mut conn)
+            .try_collect()
+            .await?,
+    ] {
+        let (done, rows): (Vec<_>, Vec<_>) = results.into_iter().partition(|v| v.is_left());
+        let rows: Vec<MySqlRow> = rows.into_iter().filter_map(|v| v.right()).collect();
+
+        assert_eq!(rows[0].try_get::<String, _>("name")?, "caf\u{fffd}");
+        assert_eq!(rows[1].try_get::<&str, _>("name")?, "plain");
+
+        // binary values are never changed
+        assert_eq!(rows[0].try_get::<Vec<u8>, _>("data")?, b"\xff\xfe");
+        assert!(rows[0].try_get::<String, _>("data").is_err());
+
+        let done = done.into_iter().filter_map(|v| v.left()).last().unwrap();
+        assert_eq!(done.lossy_utf8_conversions(), 1);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_tracks_session_state() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let database: String = sqlx::query_scalar("SELECT DATABASE()")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(conn.schema(), Some(&*database));
+
+    conn.execute("SET time_zone = '+03:00'").await?;
+    assert_eq!(conn.parameter("time_zone"), Some("+03:00"));
+    assert_eq!(conn.parameter("TIME_ZONE"), Some("+03:00"));
+
+    conn.execute("USE information_schema").await?;
+    assert_eq!(conn.schema(), Some("information_schema"));
+
+    conn.execute(&*format!("USE `{}`", database)).await?;
+    assert_eq!(conn.schema(), Some(&*database));
+
+    // DDL commits the transaction, after which the dropped transaction has nothing to roll back
+    // and rolling back to its savepoint would fail
+    let mut tx = conn.begin().await?;
+    let mut savepoint = tx.begin().await?;
+    savepoint
+        .execute("DROP TABLE IF EXISTS _sqlx_session_tracking")
+        .await?;
+    drop(savepoint);
+    drop(tx);
+
+    conn.ping().await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "chrono")]
+#[sqlx_macros::test]
+async fn it_reports_values_that_cannot_be_encoded() -> anyhow::Result<()> {
+    use sqlx::types::chrono::NaiveDate;
+
+    let mut conn = new::<MySql>().await?;
+
+    // a year before 0 does not fit the wire format of DATE
+    let res = sqlx::query("SELECT ?, ?")
+        .bind(1_i32)
+        .bind(NaiveDate::from_ymd(-1, 1, 1))
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(
+        matches!(res, Err(sqlx::Error::ArgumentEncode { index: 1, .. })),
+        "{:?}",
+        res
+    );
+
+    conn.ping().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_warns_about_transactions_left_on_pooled_connections() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let pool = MySqlPoolOptions::new()
+        .max_connections(1)
+        .track_session_state(true)
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    // a transaction that is dropped is rolled back before the check
+    drop(pool.acquire().await?.begin().await?);
+
+    let mut conn = pool.acquire().await?;
+    assert_eq!(pool.stats().session_state_leaks, 0);
+
+    let state = conn.session_state().await?;
+    assert_eq!(state.in_transaction, Some(false));
+    assert_eq!(state.search_path, None);
+
+    conn.execute("BEGIN").await?;
+    drop(conn);
+
+    let mut conn = pool.acquire().await?;
+    assert_eq!(pool.stats().session_state_leaks, 1);
+    assert_eq!(conn.session_state().await?.in_transaction, Some(true));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_quotes_literals_the_server_reads_back() -> anyhow::Result<()> {
+    for sql_mode in &["", ",NO_BACKSLASH_ESCAPES"] {
+        let mut conn = new::<MySql>().await?;
+
+        conn.execute(&*format!(
+            "SET SESSION sql_mode = CONCAT(@@sql_mode, '{}')",
+            sql_mode
+        ))
+        .await?;
+
+        for value in sqlx_test::strings_to_quote(200) {
+            let sql = format!("SELECT {}", conn.quote_literal(&value)?);
+            let row = conn.fetch_one(&*sql).await?;
+
+            assert_eq!(row.try_get::<String, _>(0)?, value, "{}", sql);
+        }
+    }
+
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let conn = MySqlConnection::connect_with(&options.charset("gbk")).await?;
+
+    assert!(conn.quote_literal("¿'").is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sends_unprepared_queries_without_binds_as_they_are() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    async fn prepared(conn: &mut MySqlConnection) -> anyhow::Result<u64> {
+        let row = conn
+            .fetch_one("SHOW SESSION STATUS LIKE 'Com_stmt_prepare'")
+            .await?;
+
+        Ok(row.get::<String, _>(1).parse()?)
+    }
+
+    let sql = "SELECT CAST(1 AS SIGNED), CAST('1.50' AS DECIMAL(4, 2))";
+    let before = prepared(&mut conn).await?;
+
+    // with `COM_QUERY`, which returns values in the text format
+    let row = sqlx::query(sql)
+        .persistent(false)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(prepared(&mut conn).await?, before);
+    assert_eq!(row.try_get_raw(0)?.format(), MySqlValueFormat::Text);
+    assert_eq!(row.try_get::<i64, _>(0)?, 1);
+    assert_eq!(row.try_get::<String, _>(1)?, "1.50");
+
+    let row = sqlx::query(sql)
+        .persistent(false)
+        .always_prepare(true)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(prepared(&mut conn).await?, before + 1);
+    assert_eq!(row.try_get_raw(0)?.format(), MySqlValueFormat::Binary);
+    assert_eq!(row.try_get::<i64, _>(0)?, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_creates_and_drops_databases() -> anyhow::Result<()> {
+    use sqlx::migrate::MigrateDatabase;
+
+    setup_if_needed();
+
+    // a name that must be quoted
+    let mut url = url::Url::parse(&env::var("DATABASE_URL")?)?;
+    url.set_path(&format!("sqlx-Admin-{}", std::process::id()));
+    let url = url.to_string();
+
+    MySql::force_drop_database(&url).await?;
+    assert!(!MySql::database_exists(&url).await?);
+
+    MySql::create_database(&url).await?;
+    assert!(MySql::database_exists(&url).await?);
+
+    let error = MySql::create_database(&url).await.unwrap_err();
+    assert!(
+        matches!(&error, sqlx::Error::DatabaseExists(name) if name.starts_with("sqlx-Admin-")),
+        "{:?}",
+        error
+    );
+
+    // a transaction that has read a table of the database would hold `drop_database` up until
+    // it ends; `force_drop_database` ends its session
+    let mut conn = MySqlConnection::connect(&url).await?;
+    conn.execute("CREATE TABLE t (x INT)").await?;
+    conn.execute("BEGIN; SELECT * FROM t").await?;
+
+    MySql::force_drop_database(&url).await?;
+    assert!(!MySql::database_exists(&url).await?);
+    assert!(conn.execute("SELECT 1").await.is_err());
+
+    // dropping a database that does not exist is not an error
+    MySql::drop_database(&url).await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_writes_bind_values_into_the_sql_with_the_simple_protocol() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut conn = MySqlConnection::connect_with(&options.prefer_simple_protocol(true)).await?;
+
+    // the placeholders in the strings and the comment are not ones
+    let row: (i64, String, Option<i32>, Vec<u8>, f64, String) =
+        sqlx::query_as("SELECT ? + 1, CONCAT(?, '?'), ?, ?, ? /* ? */, 'a\\'?'")
+            .bind(1_i64)
+            .bind("it's\\'); DROP TABLE tweet; --")
+            .bind(None::<i32>)
+            .bind(&[0_u8, 0xff][..])
+            .bind(-0.25_f64)
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(
+        row,
+        (
+            2,
+            "it's\\'); DROP TABLE tweet; --?".to_owned(),
+            None,
+            vec![0, 0xff],
+            -0.25,
+            "a'?".to_owned()
+        )
+    );
+
+    // statements are prepared for their metadata only
+    let statement = conn.prepare("SELECT ? * 2").await?;
+    let value: i64 = statement
+        .query_scalar()
+        .bind(21_i64)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 42);
+    assert_eq!(conn.cached_statements_size(), 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_executes_in_bulk() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE bulk (id INT PRIMARY KEY, name TEXT)")
+        .await?;
+
+    let rows = (1..=3).map(|id| {
+        let mut arguments = MySqlArguments::default();
+        arguments.add(id);
+        arguments.add(Some(format!("name {}", id)).filter(|_| id != 2));
+        arguments
+    });
+
+    let done = conn
+        .execute_bulk("INSERT INTO bulk (id, name) VALUES (?, ?)", rows)
+        .await?;
+
+    assert_eq!(done.rows_affected(), 3);
+
+    // the types differ between the rows, so each is executed in turn
+    let mut first = MySqlArguments::default();
+    first.add(4_i32);
+    first.add("four");
+
+    let mut second = MySqlArguments::default();
+    second.add(5_i64);
+    second.add("five");
+
+    let done = conn
+        .execute_bulk(
+            "INSERT INTO bulk (id, name) VALUES (?, ?)",
+            vec![first, second],
+        )
+        .await?;
+
+    assert_eq!(done.rows_affected(), 2);
+
+    let names: Vec<Option<String>> = sqlx::query_scalar("SELECT name FROM bulk ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(
+        names,
+        [
+            Some("name 1".to_owned()),
+            None,
+            Some("name 3".to_owned()),
+            Some("four".to_owned()),
+            Some("five".to_owned()),
+        ]
+    );
+
+    let done = conn
+        .execute_bulk("INSERT INTO bulk (id) VALUES (?)", Vec::new())
+        .await?;
+
+    assert_eq!(done.rows_affected(), 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_detects_the_server_flavor() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let version: String = sqlx::query_scalar("SELECT VERSION()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let flavor = conn.server_flavor();
+
+    assert_eq!(
+        flavor == MySqlFlavor::MariaDb,
+        version.contains("MariaDB"),
+        "{}",
+        version
+    );
+
+    let (major, minor, patch) = conn.server_version();
+    assert!(version.contains(&format!("{}.{}.{}", major, minor, patch)));
+
+    // the extended type info of MariaDB tells a JSON column from a `LONGTEXT`
+    if flavor == MySqlFlavor::MariaDb && conn.server_version() >= (10, 5, 2) {
+        conn.execute("CREATE TEMPORARY TABLE documents (doc JSON)")
+            .await?;
+
+        let statement = conn.prepare("SELECT doc FROM documents").await?;
+
+        assert_eq!(statement.columns()[0].type_info().name(), "JSON");
+    }
+
+    Ok(())
+}
+
+// the cipher of the connection, empty if it is not encrypted
+async fn ssl_cipher(conn: &mut MySqlConnection) -> anyhow::Result<String> {
+    let (_, cipher): (String, String) = sqlx::query_as("SHOW STATUS LIKE 'Ssl_cipher'")
+        .fetch_one(conn)
+        .await?;
+
+    Ok(cipher)
+}
+
+// whether the server uses the certificates of `tests/certs`, as the `mysql_8` service of the
+// docker-compose file does; any other server has certificates of its own, if any
+async fn uses_test_certificates(conn: &mut MySqlConnection) -> anyhow::Result<bool> {
+    let ca: Option<String> = sqlx::query_scalar("SELECT @@ssl_ca")
+        .fetch_one(conn)
+        .await?;
+
+    Ok(matches!(ca, Some(ca) if ca.ends_with("ca.crt")))
+}
+
+#[sqlx_macros::test]
+async fn it_negotiates_each_ssl_mode() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+
+    let mut conn = MySqlConnection::connect_with(&options).await?;
+
+    if conn.server_flavor() != MySqlFlavor::MySql
+        || conn.server_version() < (8, 0, 0)
+        || !uses_test_certificates(&mut conn).await?
+    {
+        return Ok(());
+    }
+
+    let options = options.ssl_mode(MySqlSslMode::Disabled);
+    let mut conn = MySqlConnection::connect_with(&options).await?;
+    assert_eq!(ssl_cipher(&mut conn).await?, "");
+
+    for mode in vec![MySqlSslMode::Preferred, MySqlSslMode::Required] {
+        let mut conn = MySqlConnection::connect_with(&options.clone().ssl_mode(mode)).await?;
+        assert_ne!(ssl_cipher(&mut conn).await?, "", "{:?}", mode);
+    }
+
+    let options = options.ssl_ca("tests/certs/ca.crt");
+
+    let mut conn =
+        MySqlConnection::connect_with(&options.clone().ssl_mode(MySqlSslMode::VerifyCa)).await?;
+    assert_ne!(ssl_cipher(&mut conn).await?, "");
+
+    // the certificate of the server is issued to `sqlx.rs` only, not to the host connected to
+    let options = options.ssl_mode(MySqlSslMode::VerifyIdentity);
+
+    assert!(MySqlConnection::connect_with(&options).await.is_err());
+
+    let mut conn = MySqlConnection::connect_with(&options.ssl_server_name("sqlx.rs")).await?;
+    assert_ne!(ssl_cipher(&mut conn).await?, "");
+
+    Ok(())
+}
+
+// choosing cipher suites and loading a PEM client certificate are only done with rustls
+#[cfg(any(
+    feature = "runtime-tokio-rustls",
+    feature = "runtime-async-std-rustls",
+    feature = "runtime-actix-rustls"
+))]
+#[sqlx_macros::test]
+async fn it_meets_require_cipher_and_require_x509() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+
+    let mut conn = MySqlConnection::connect_with(&options).await?;
+
+    if conn.server_flavor() != MySqlFlavor::MySql
+        || conn.server_version() < (8, 0, 0)
+        || !uses_test_certificates(&mut conn).await?
+    {
+        return Ok(());
+    }
+
+    conn.execute(
+        r#"
+DROP USER IF EXISTS sqlx_cipher, sqlx_x509;
+CREATE USER sqlx_cipher IDENTIFIED BY 'password' REQUIRE CIPHER 'ECDHE-RSA-AES256-GCM-SHA384';
+CREATE USER sqlx_x509 IDENTIFIED BY 'password' REQUIRE X509;
+    "#,
+    )
+    .await?;
+
+    let options = options
+        .password("password")
+        .ssl_mode(MySqlSslMode::Required);
+
+    let cipher = options.clone().username("sqlx_cipher");
+
+    // TLS 1.3 would be negotiated otherwise, with a suite of its own
+    assert!(MySqlConnection::connect_with(&cipher).await.is_err());
+
+    let mut conn =
+        MySqlConnection::connect_with(&cipher.ssl_cipher("ECDHE-RSA-AES256-GCM-SHA384")).await?;
+    assert_eq!(ssl_cipher(&mut conn).await?, "ECDHE-RSA-AES256-GCM-SHA384");
+
+    let x509 = options.username("sqlx_x509");
+
+    assert!(MySqlConnection::connect_with(&x509).await.is_err());
+
+    let mut conn = MySqlConnection::connect_with(
+        &x509
+            .ssl_cert("tests/certs/client.crt")
+            .ssl_key("tests/keys/client.key"),
+    )
+    .await?;
+    assert_ne!(ssl_cipher(&mut conn).await?, "");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_refuses_values_larger_than_the_limit() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // a larger result is `NULL`, with a warning
+    let max_allowed_packet: u64 = sqlx::query_scalar("SELECT @@max_allowed_packet")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let size = if max_allowed_packet > 10_000_000 {
+        10_000_000
+    } else {
+        1_000_000
+    };
+
+    for prepared in vec![false, true] {
+        let sql = format!("SELECT 1, REPEAT('x', {}), 'tail'", size);
+
+        let err = sqlx::query(&sql)
+            .always_prepare(prepared)
+            .max_value_size(1 << 16)
+            .map(|_| ())
+            .fetch_one(&mut conn)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                sqlx::Error::ValueTooLarge {
+                    column: Some(1),
+                    size: s,
+                    limit: 65_536,
+                } if s == size
+            ),
+            "{:?}",
+            err
+        );
+
+        let err = sqlx::query("SELECT REPEAT('x', 1000) UNION ALL SELECT REPEAT('x', 1001)")
+            .always_prepare(prepared)
+            .max_row_size(1000)
+            .map(|_| ())
+            .fetch_all(&mut conn)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                sqlx::Error::ValueTooLarge {
+                    column: None,
+                    size: 1001,
+                    limit: 1000,
+                }
+            ),
+            "{:?}",
+            err
+        );
+
+        let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+        assert_eq!(value, 1);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_counts_found_rows_when_asked_to() -> anyhow::Result<()> {
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+
+    for &found_rows in [false, true].iter() {
+        let mut conn =
+            MySqlConnection::connect_with(&options.clone().found_rows(found_rows)).await?;
+
+        conn.execute("CREATE TEMPORARY TABLE found_rows (id INT PRIMARY KEY, name TEXT)")
+            .await?;
+        conn.execute("INSERT INTO found_rows (id, name) VALUES (1, 'one'), (2, 'two')")
+            .await?;
+
+        // sets one row to the value it already has, and changes the other
+        let done = sqlx::query("UPDATE found_rows SET name = 'one'")
+            .execute(&mut conn)
+            .await?;
+
+        assert_eq!(done.rows_affected(), if found_rows { 2 } else { 1 });
+        assert_eq!(done.matched_rows(), Some(2));
+        assert_eq!(done.changed_rows(), Some(1));
+        assert_eq!(done.warnings(), 0);
+
+        conn.close().await?;
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_binds_lists_to_in() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE in_list (id INT PRIMARY KEY)")
+        .await?;
+
+    let values: Vec<String> = (1..=1000).map(|id| format!("({})", id)).collect();
+    conn.execute(&*format!(
+        "INSERT INTO in_list (id) VALUES {}",
+        values.join(", ")
+    ))
+    .await?;
+
+    let ids: Vec<i32> = sqlx::query_scalar("SELECT id FROM in_list WHERE id IN {in}")
+        .bind_expanded(vec![5])
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(ids, [5]);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM in_list WHERE id > ? AND id IN {in}")
+        .bind(10)
+        .bind_expanded(1..=1000)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 990);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM in_list WHERE id NOT IN {in}")
+        .bind_expanded(vec![1, 2, 3])
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 997);
+
+    // an empty list is refused unless it is allowed, when it matches nothing
+    let err = sqlx::query("SELECT id FROM in_list WHERE id IN {in}")
+        .bind_expanded(Vec::<i32>::new())
+        .fetch_all(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, sqlx::Error::ArgumentEncode { index: 0, .. }),
+        "{:?}",
+        err
+    );
+
+    for &(sql, expected) in [
+        ("SELECT COUNT(*) FROM in_list WHERE id IN {in}", 0),
+        ("SELECT COUNT(*) FROM in_list WHERE id NOT IN {in}", 1000),
+    ]
+    .iter()
+    {
+        let count: i64 = sqlx::query_scalar(sql)
+            .bind_expanded_allow_empty(Vec::<i32>::new())
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(count, expected, "{}", sql);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_caches_a_statement_per_padded_length_of_a_list() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+    conn.clear_cached_statements().await?;
+
+    let sql = "SELECT COUNT(*) FROM (SELECT 1 AS id UNION SELECT 2 UNION SELECT 3) AS t \
+               WHERE id IN {in}";
+
+    // 3 and 4 values are both bound as 4, the last value repeated
+    for (values, expected) in [(vec![1, 2, 3], 3), (vec![1, 2, 5, 6], 2)].iter() {
+        let count: i64 = sqlx::query_scalar(sql)
+            .bind_expanded(values.clone())
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(count, *expected);
+    }
+
+    assert_eq!(conn.cached_statements_size(), 1);
+
+    let count: i64 = sqlx::query_scalar(sql)
+        .bind_expanded(vec![3, 4, 5, 6, 7])
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1);
+    assert_eq!(conn.cached_statements_size(), 2);
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! Kept apart from the other tests, as these play exchanges captured from MySQL to the driver
+//! instead of talking to it; see `sqlx::wire::replay`.
+
+use futures::TryStreamExt;
+use sqlx::mysql::{MySqlConnectOptions, MySqlConnection, MySqlFlavor, MySqlSslMode};
+use sqlx::wire::replay::{Replay, ReplayServer};
+use sqlx::{Connection, Executor, Row};
+
+// a server playing `replay`, and the options of the client it was recorded with
+fn serve(replay: &str) -> anyhow::Result<(ReplayServer, MySqlConnectOptions)> {
+    let server = Replay::parse(replay)?.serve()?;
+
+    let options = MySqlConnectOptions::new()
+        .host("127.0.0.1")
+        .port(server.port())
+        .username("root")
+        .application_name("replay")
+        .ssl_mode(MySqlSslMode::Disabled);
+
+    Ok((server, options))
+}
+
+#[sqlx_macros::test]
+async fn it_replays_multiple_result_sets() -> anyhow::Result<()> {
+    let (server, options) = serve(include_str!("fixtures/replay/multiple_result_sets.replay"))?;
+
+    // setting up the session is a script of two statements already
+    let mut conn = MySqlConnection::connect_with(&options).await?;
+    assert_eq!(conn.server_flavor(), MySqlFlavor::MySql);
+
+    let results: Vec<_> = conn
+        .fetch_many("SELECT 1 AS n; SELECT 'two' AS s, NULL AS t; DO 0")
+        .try_collect()
+        .await?;
+
+    // each result set ends with a result, as does the statement
+    let is_row: Vec<_> = results.iter().map(|result| result.is_right()).collect();
+    assert_eq!(is_row, [true, false, true, false, false]);
+
+    let rows: Vec<_> = results
+        .iter()
+        .filter_map(|result| result.as_ref().right())
+        .collect();
+    assert_eq!(rows.len(), 2);
+
+    assert_eq!(rows[0].try_get::<i64, _>("n")?, 1);
+    assert_eq!(rows[1].try_get::<String, _>("s")?, "two");
+    assert_eq!(rows[1].try_get::<Option<String>, _>("t")?, None);
+
+    conn.close().await?;
+
+    server.finish()?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sets_up_the_session_in_one_round_trip() -> anyhow::Result<()> {
+    let replay = include_str!("fixtures/replay/batched_setup.replay");
+
+    // the handshake, and then the setup of the session
+    assert_eq!(Replay::parse(replay)?.round_trips(), 2);
+
+    let (server, options) = serve(replay)?;
+
+    let options = options
+        .read_only(true)
+        .init_command("SET SESSION sql_safe_updates = 1;");
+
+    let conn = MySqlConnection::connect_with(&options).await?;
+    assert_eq!(conn.server_flavor(), MySqlFlavor::MySql);
+
+    conn.close().await?;
+
+    server.finish()?;
+
+    Ok(())
+}
@@ -4,7 +4,7 @@ extern crate time_ as time;
 use std::str::FromStr;
 
 use sqlx::mysql::MySql;
-use sqlx::{Executor, Row};
+use sqlx::{Column, Row};
 use sqlx_test::{new, test_type};
 
 test_type!(bool(MySql, "false" == false, "true" == true));
@@ -42,6 +42,15 @@ test_type!(bytes<Vec<u8>>(MySql,
         == vec![0_u8, 0, 0, 0, 0x52]
 ));
 
+test_type!(byte_array<[u8; 4]>(MySql,
+    "X'DEADBEEF'" == [0xDE_u8, 0xAD, 0xBE, 0xEF],
+    "X'00000052'" == [0_u8, 0, 0, 0x52]
+));
+
+test_type!(byte_array_empty<[u8; 0]>(MySql,
+    "X''" == [0_u8; 0]
+));
+
 #[cfg(feature = "uuid")]
 test_type!(uuid<sqlx::types::Uuid>(MySql,
     "x'b731678f636f4135bc6f19440c13bd19'"
@@ -127,6 +136,76 @@ mod chrono {
     }
 }
 
+mod system_time {
+    use super::*;
+    use sqlx::types::{TimestampOutOfRange, UnixMillis, UnixTimestamp};
+    use sqlx_test::{test_decode_type, test_prepared_type};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    test_type!(system_time<SystemTime>(MySql,
+        "TIMESTAMP '2019-01-02 05:10:20.115100'"
+            == UNIX_EPOCH + Duration::from_micros(1_546_405_820_115_100),
+        "TIMESTAMP '1950-06-01 00:00:00'" == UNIX_EPOCH - Duration::from_secs(618_105_600),
+    ));
+
+    test_type!(unix_timestamp<UnixTimestamp>(MySql,
+        "CAST(1546405820 AS SIGNED)" == UnixTimestamp(1_546_405_820),
+        "CAST(-1 AS SIGNED)" == UnixTimestamp(-1),
+    ));
+
+    // a DATETIME column is written with `FROM_UNIXTIME`
+    test_prepared_type!(unix_timestamp_datetime<UnixTimestamp>(MySql,
+        "SELECT {0} <=> FROM_UNIXTIME(?), {0}, FROM_UNIXTIME(?)",
+        "TIMESTAMP '2019-01-02 05:10:20'" == UnixTimestamp(1_546_405_820),
+    ));
+
+    // rounded down towards the past
+    test_decode_type!(unix_timestamp_rounded<UnixTimestamp>(MySql,
+        "TIMESTAMP '2019-01-02 05:10:20.9'" == UnixTimestamp(1_546_405_820),
+        "TIMESTAMP '1969-12-31 23:59:59.5'" == UnixTimestamp(-1),
+    ));
+
+    test_decode_type!(unix_millis<UnixMillis>(MySql,
+        "TIMESTAMP '2019-01-02 05:10:20.1159'" == UnixMillis(1_546_405_820_115),
+        "CAST(1546405820115 AS SIGNED)" == UnixMillis(1_546_405_820_115),
+    ));
+
+    #[sqlx_macros::test]
+    async fn it_refuses_datetimes_out_of_range() -> anyhow::Result<()> {
+        let mut conn = sqlx_test::new::<MySql>().await?;
+
+        // past the year 9999
+        let error = sqlx::query("SELECT ?")
+            .bind(UNIX_EPOCH + Duration::from_secs(300_000_000_000))
+            .fetch_one(&mut conn)
+            .await
+            .unwrap_err();
+
+        match error {
+            sqlx::Error::ArgumentEncode { index: 0, source } => {
+                let source = source.downcast_ref::<TimestampOutOfRange>().unwrap();
+                assert_eq!(source.target(), "DATETIME");
+            }
+
+            error => panic!("unexpected error: {}", error),
+        }
+
+        // a zero date is no instant
+        conn.execute("SET @@sql_mode := REPLACE(@@sql_mode, 'NO_ZERO_DATE', '');")
+            .await?;
+
+        let row = sqlx::query("SELECT TIMESTAMP '0000-00-00 00:00:00'")
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(row.get::<Option<SystemTime>, _>(0), None);
+        assert!(row.try_get::<SystemTime, _>(0).is_err());
+        assert!(row.try_get::<UnixTimestamp, _>(0).is_err());
+
+        Ok(())
+    }
+}
+
 #[cfg(feature = "time")]
 mod time_tests {
     use super::*;
@@ -309,3 +388,75 @@ CREATE TEMPORARY TABLE with_bits (
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn test_decimal_year_enum_and_set() -> anyhow::Result<()> {
+    use sqlx::TypeInfo;
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE with_text_types (
+    id INT PRIMARY KEY,
+    amount DECIMAL(10, 2) NOT NULL,
+    year YEAR NOT NULL,
+    size ENUM('small', 'medium', 'large') NOT NULL,
+    tags SET('red', 'green', 'blue') NOT NULL
+);
+    "#,
+    )
+    .await?;
+
+    conn.execute(
+        r#"
+INSERT INTO with_text_types (id, amount, year, size, tags) VALUES
+    (1, -12.5, 2021, 'medium', 'blue,red'),
+    (2, 0, 0, 'small', '');
+    "#,
+    )
+    .await?;
+
+    let sql = "SELECT amount, year, size, tags FROM with_text_types ORDER BY id";
+
+    // BINARY, then TEXT
+    for rows in vec![
+        sqlx::query(sql).fetch_all(&mut conn).await?,
+        conn.fetch_all(sql).await?,
+    ] {
+        let names: Vec<_> = rows[0]
+            .columns()
+            .iter()
+            .map(|column| column.type_info().name().to_owned())
+            .collect();
+
+        assert_eq!(names, ["DECIMAL", "YEAR", "ENUM", "SET"]);
+
+        assert_eq!(rows[0].try_get::<String, _>("amount")?, "-12.50");
+        assert_eq!(rows[0].try_get::<u16, _>("year")?, 2021);
+        assert_eq!(rows[0].try_get::<i32, _>("year")?, 2021);
+        assert_eq!(rows[0].try_get::<String, _>("size")?, "medium");
+
+        // members are in the order of the definition of the `SET`
+        assert_eq!(rows[0].try_get::<String, _>("tags")?, "red,blue");
+        assert_eq!(rows[0].try_get::<Vec<String>, _>("tags")?, ["red", "blue"]);
+
+        assert_eq!(rows[1].try_get::<String, _>("amount")?, "0.00");
+        assert_eq!(rows[1].try_get::<u16, _>("year")?, 0);
+        assert_eq!(rows[1].try_get::<String, _>("tags")?, "");
+        assert!(rows[1].try_get::<Vec<String>, _>("tags")?.is_empty());
+    }
+
+    sqlx::query("UPDATE with_text_types SET tags = ? WHERE id = 2")
+        .bind(vec!["green".to_owned(), "red".to_owned()])
+        .execute(&mut conn)
+        .await?;
+
+    let tags: Vec<String> = sqlx::query_scalar("SELECT tags FROM with_text_types WHERE id = 2")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(tags, ["red", "green"]);
+
+    Ok(())
+}
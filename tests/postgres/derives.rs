@@ -1,9 +1,12 @@
 use futures::TryStreamExt;
-use sqlx::{Connection, Executor, FromRow, Postgres};
+use sqlx::postgres::{PgConnectOptions, PgConnection};
+use sqlx::{ColumnNameMatching, Connection, Executor, FromRow, Postgres, Row};
 use sqlx_core::postgres::types::PgRange;
 use sqlx_test::{new, test_type};
+use std::env;
 use std::fmt::Debug;
 use std::ops::Bound;
+use std::str::FromStr;
 
 // Transparent types are rust-side wrappers over DB types
 #[derive(PartialEq, Debug, sqlx::Type)]
@@ -100,6 +103,23 @@ enum Mood {
     Sad,
 }
 
+// Created in `setup.sql`
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(type_name = "status", rename_all = "lowercase")]
+enum Status {
+    New,
+    Open,
+    Closed,
+}
+
+// Knows the type, but not all of its labels
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(type_name = "status", rename_all = "lowercase")]
+enum OpenStatus {
+    New,
+    Open,
+}
+
 // Records must map to a custom type
 // Note that all types are types in Postgres
 #[derive(PartialEq, Debug, sqlx::Type)]
@@ -335,6 +355,86 @@ SELECT id, mood FROM people WHERE id = $1
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_enum_array_type() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let rec: (bool, Vec<Status>) = sqlx::query_as(
+        "
+SELECT $1 = '{new,closed}'::status[], $1
+        ",
+    )
+    .bind(vec![Status::New, Status::Closed])
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert!(rec.0);
+    assert_eq!(rec.1, vec![Status::New, Status::Closed]);
+
+    let rec: (bool, Vec<Option<Status>>) = sqlx::query_as(
+        "
+SELECT $1 = '{open,NULL,closed}'::status[], $1
+        ",
+    )
+    .bind(vec![Some(Status::Open), None, Some(Status::Closed)])
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert!(rec.0);
+    assert_eq!(rec.1, vec![Some(Status::Open), None, Some(Status::Closed)]);
+
+    // the text format, as returned to a simple query
+    let row = conn
+        .fetch("SELECT '{NULL,new}'::status[]")
+        .try_next()
+        .await?
+        .unwrap();
+
+    let statuses: Vec<Option<Status>> = row.try_get(0)?;
+    assert_eq!(statuses, vec![None, Some(Status::New)]);
+
+    let res: Result<(Vec<OpenStatus>,), _> = sqlx::query_as("SELECT '{new,open,closed}'::status[]")
+        .fetch_one(&mut conn)
+        .await;
+
+    let err = res.unwrap_err().to_string();
+    assert!(err.contains("at index 2"), "{}", err);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_record_array_type() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let items = vec![
+        InventoryItem {
+            name: "fuzzy dice".to_owned(),
+            supplier_id: Some(42),
+            price: Some(199),
+        },
+        InventoryItem {
+            name: "spare tyre".to_owned(),
+            supplier_id: None,
+            price: None,
+        },
+    ];
+
+    let rec: (bool, Vec<InventoryItem>) = sqlx::query_as(
+        "
+SELECT $1 = ARRAY[ROW('fuzzy dice', 42, 199), ROW('spare tyre', NULL, NULL)]::inventory_item[], $1
+        ",
+    )
+    .bind(&items)
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert!(rec.0);
+    assert_eq!(rec.1, items);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn test_record_type() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -559,3 +659,277 @@ async fn test_default() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "macros")]
+#[sqlx_macros::test]
+async fn test_from_row_with_try_from() -> anyhow::Result<()> {
+    #[derive(Debug, sqlx::FromRow)]
+    struct Account {
+        id: i32,
+        #[sqlx(try_from = "i16")]
+        login_count: u16,
+        #[sqlx(try_from = "i64", default)]
+        quota: i128,
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Pair(#[sqlx(try_from = "i32")] u8, i32);
+
+    let mut conn = new::<Postgres>().await?;
+
+    let account: Account = sqlx::query_as("SELECT 1 AS id, 7::int2 AS login_count")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(account.id, 1);
+    assert_eq!(account.login_count, 7);
+    assert_eq!(account.quota, 0);
+
+    // the column is checked against the type it is converted from
+    let accounts: Vec<Account> =
+        sqlx::query_as("SELECT 1 AS id, 7::int2 AS login_count, 5::int8 AS quota")
+            .fetch_checked(&mut conn)
+            .try_collect()
+            .await?;
+
+    assert_eq!(accounts[0].quota, 5);
+
+    let err = sqlx::query_as::<_, Account>("SELECT 1 AS id, (-1)::int2 AS login_count")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, sqlx::Error::ColumnDecode { index, .. } if index == "\"login_count\""),
+        "unexpected error: {:?}",
+        err
+    );
+
+    let pair: Pair = sqlx::query_as("SELECT 200, 300")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!((pair.0, pair.1), (200, 300));
+
+    let err = sqlx::query_as::<_, Pair>("SELECT 300, 200")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, sqlx::Error::ColumnDecode { index, .. } if index == "0"),
+        "unexpected error: {:?}",
+        err
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "macros")]
+#[sqlx_macros::test]
+async fn test_from_row_with_decode_with() -> anyhow::Result<()> {
+    use sqlx::error::BoxDynError;
+    use sqlx::postgres::PgValueRef;
+    use sqlx::{Decode, ValueRef};
+
+    fn decode_tags(value: PgValueRef<'_>) -> Result<Vec<String>, BoxDynError> {
+        let tags = <&str as Decode<Postgres>>::decode(value)?;
+
+        if tags.is_empty() {
+            return Err("no tags".into());
+        }
+
+        Ok(tags.split(',').map(str::to_owned).collect())
+    }
+
+    // generic over the database, so for rows of any database
+    fn decode_is_null<'r, V: ValueRef<'r>>(value: V) -> Result<bool, BoxDynError> {
+        Ok(value.is_null())
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    #[sqlx(database = "Postgres")]
+    struct Post {
+        id: i32,
+        #[sqlx(decode_with = "decode_tags")]
+        tags: Vec<String>,
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Missing {
+        #[sqlx(decode_with = "decode_is_null", rename = "note")]
+        missing_note: bool,
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    let post: Post = sqlx::query_as("SELECT 1 AS id, 'a,b' AS tags")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(post.id, 1);
+    assert_eq!(post.tags, ["a", "b"]);
+
+    let err = sqlx::query_as::<_, Post>("SELECT 1 AS id, '' AS tags")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, sqlx::Error::ColumnDecode { index, source } if index == "\"tags\"" && source.to_string() == "no tags"),
+        "unexpected error: {:?}",
+        err
+    );
+
+    let missing: Missing = sqlx::query_as("SELECT NULL::text AS note")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert!(missing.missing_note);
+
+    Ok(())
+}
+
+#[cfg(feature = "macros")]
+#[sqlx_macros::test]
+async fn test_from_row_with_skip() -> anyhow::Result<()> {
+    #[derive(Debug, sqlx::FromRow)]
+    struct Account {
+        id: i32,
+        #[sqlx(skip)]
+        sessions: Vec<String>,
+        name: String,
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Pair(i32, #[sqlx(skip)] Option<i32>, String);
+
+    let mut conn = new::<Postgres>().await?;
+
+    // a skipped field is not read even if there is a column by its name
+    let accounts: Vec<Account> = sqlx::query_as("SELECT 1 AS id, 'Herp' AS name, 'x' AS sessions")
+        .fetch_checked(&mut conn)
+        .try_collect()
+        .await?;
+
+    assert_eq!(accounts[0].id, 1);
+    assert!(accounts[0].sessions.is_empty());
+    assert_eq!(accounts[0].name, "Herp");
+
+    let pair: Pair = sqlx::query_as("SELECT 1, 'Derp'")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!((pair.0, pair.1, &*pair.2), (1, None, "Derp"));
+
+    Ok(())
+}
+
+#[cfg(feature = "macros")]
+#[sqlx_macros::test]
+async fn test_fetch_checked() -> anyhow::Result<()> {
+    #[derive(Debug, sqlx::FromRow)]
+    struct Account {
+        id: i64,
+        name: String,
+        #[sqlx(rename = "is_active")]
+        active: bool,
+        #[sqlx(default)]
+        note: Option<String>,
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    let accounts: Vec<Account> =
+        sqlx::query_as("SELECT 1::int8 AS id, 'Herp' AS name, true AS is_active")
+            .fetch_checked(&mut conn)
+            .try_collect()
+            .await?;
+
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].name, "Herp");
+    assert_eq!(accounts[0].note, None);
+
+    let err = sqlx::query_as::<_, Account>("SELECT '1' AS id, 'Herp' AS name")
+        .fetch_checked(&mut conn)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap_err();
+
+    let mismatches = match err {
+        sqlx::Error::TypeValidation(mismatches) => mismatches,
+        err => panic!("expected a type validation error, got {:?}", err),
+    };
+
+    assert_eq!(mismatches.len(), 2);
+
+    assert_eq!(mismatches[0].column(), "id");
+    assert_eq!(mismatches[0].rust_type(), "i64");
+    assert_eq!(mismatches[0].expected(), "INT8");
+    assert_eq!(mismatches[0].found(), Some("TEXT"));
+
+    assert_eq!(mismatches[1].column(), "is_active");
+    assert_eq!(mismatches[1].found(), None);
+
+    // a result with no rows is not checked
+    let accounts: Vec<Account> = sqlx::query_as("SELECT 1::int4 AS id WHERE false")
+        .fetch_checked(&mut conn)
+        .try_collect()
+        .await?;
+
+    assert!(accounts.is_empty());
+
+    // tuples are checked by position
+    let err = sqlx::query_as::<_, (i32, String)>("SELECT 1, 2")
+        .fetch_checked(&mut conn)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.to_string().contains("column `1`"),
+        "unexpected error: {}",
+        err
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_from_row_matches_folded_column_names() -> anyhow::Result<()> {
+    #[derive(Debug, sqlx::FromRow)]
+    #[sqlx(rename_all = "camelCase")]
+    struct Account {
+        user_id: i32,
+        display_name: String,
+    }
+
+    let options = PgConnectOptions::from_str(&env::var("DATABASE_URL")?)?
+        .column_name_matching(ColumnNameMatching::SnakeCaseInsensitive);
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    // Postgres folds the unquoted aliases to `userid` and `displayname`
+    let account: Account = sqlx::query_as("SELECT 1::int4 AS userId, 'ann'::text AS displayName")
+        .fetch_checked(&mut conn)
+        .try_next()
+        .await?
+        .unwrap();
+
+    assert_eq!(account.user_id, 1);
+    assert_eq!(account.display_name, "ann");
+
+    // a quoted alias keeps its case, so both columns match and neither exactly
+    let err = sqlx::query_as::<_, Account>(
+        r#"SELECT 1::int4 AS userid, 2::int4 AS "USERID", 'ann'::text AS displayName"#,
+    )
+    .fetch_one(&mut conn)
+    .await
+    .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "column name userId is ambiguous: it matches each of userid, USERID"
+    );
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use sqlx::{postgres::Postgres, Column, Executor, TypeInfo};
+use sqlx::{postgres::Postgres, Column, Connection, Executor, TypeInfo};
 use sqlx_test::new;
 
 #[sqlx_macros::test]
@@ -88,8 +88,132 @@ async fn it_describes_composite() -> anyhow::Result<()> {
 
     assert_eq!(
         format!("{:?}", ty.kind()),
-        r#"Composite([("name", PgTypeInfo(Text)), ("supplier_id", PgTypeInfo(Int4)), ("price", PgTypeInfo(Int8))])"#
+        r#"Composite([(name, PgTypeInfo(Text)), (supplier_id, PgTypeInfo(Int4)), (price, PgTypeInfo(Int8))])"#
     );
 
     Ok(())
 }
+
+// the nullability inferred for each column of a query, by the shape of the query
+const NULLABILITY: &[(&str, &[Option<bool>])] = &[
+    ("SELECT id, owner_id FROM tweet", &[Some(false), Some(true)]),
+    // joins
+    (
+        "SELECT t1.id, t2.text FROM tweet t1 INNER JOIN tweet t2 ON t1.owner_id = t2.id",
+        &[Some(false), Some(false)],
+    ),
+    (
+        "SELECT t1.id, t2.text FROM tweet t1 LEFT JOIN tweet t2 ON t1.owner_id = t2.id",
+        &[Some(false), Some(true)],
+    ),
+    (
+        "SELECT t1.id, t2.text FROM tweet t1 RIGHT JOIN tweet t2 ON t1.owner_id = t2.id",
+        &[Some(true), Some(false)],
+    ),
+    (
+        "SELECT t1.id, t2.text FROM tweet t1 FULL JOIN tweet t2 ON t1.owner_id = t2.id",
+        &[Some(true), Some(true)],
+    ),
+    (
+        "SELECT t1.id, t2.id, t3.text FROM tweet t1 \
+         INNER JOIN tweet t2 ON t1.owner_id = t2.id \
+         LEFT JOIN tweet t3 ON t2.owner_id = t3.id",
+        &[Some(false), Some(false), Some(true)],
+    ),
+    (
+        "SELECT t1.id, t2.text FROM tweet t1 LEFT JOIN tweet t2 ON t1.owner_id = t2.id \
+         ORDER BY t2.text LIMIT $1",
+        &[Some(false), Some(true)],
+    ),
+    (
+        "SELECT t1.id, t2.text FROM tweet t1 LEFT JOIN tweet t2 ON t1.owner_id = t2.id \
+         WHERE t1.id = $1",
+        &[Some(false), Some(true)],
+    ),
+    // sub-selects
+    (
+        "SELECT s.id, s.text FROM ( \
+            SELECT t1.id, t2.text FROM tweet t1 LEFT JOIN tweet t2 ON t1.owner_id = t2.id \
+            OFFSET $1 \
+         ) s WHERE s.id > $2",
+        &[Some(false), Some(true)],
+    ),
+    (
+        "WITH c AS MATERIALIZED ( \
+            SELECT t1.id, t2.text FROM tweet t1 LEFT JOIN tweet t2 ON t1.owner_id = t2.id \
+         ) SELECT c.id, c.text FROM c",
+        &[Some(false), Some(true)],
+    ),
+    ("SELECT (SELECT id FROM tweet LIMIT 1) AS id", &[Some(true)]),
+    (
+        "SELECT t1.id, (SELECT t2.text FROM tweet t2 WHERE t2.id = t1.owner_id) AS text \
+         FROM tweet t1",
+        &[Some(false), Some(true)],
+    ),
+    // expressions
+    ("SELECT 1::int8 + 10", &[None]),
+    ("SELECT id + 1 FROM tweet", &[None]),
+    (
+        "SELECT upper(t2.text) FROM tweet t1 LEFT JOIN tweet t2 ON t1.owner_id = t2.id",
+        &[Some(true)],
+    ),
+];
+
+#[sqlx_macros::test]
+async fn it_describes_nullability_by_query_shape() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    for (query, expected) in NULLABILITY {
+        let d = conn.describe(query).await?;
+        let nullable: Vec<_> = (0..d.columns().len()).map(|i| d.nullable(i)).collect();
+
+        assert_eq!(nullable, *expected, "{}", query);
+    }
+
+    // the plans are explained as generic plans, without changing how queries are planned
+    let mode: String = sqlx::query_scalar("SHOW plan_cache_mode")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(mode, "auto");
+
+    let d = conn.describe("SHOW search_path").await?;
+    assert_eq!(d.nullable(0), None);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_nullability_without_explain() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+DO $$ BEGIN
+    CREATE ROLE sqlx_describe_no_access;
+EXCEPTION WHEN duplicate_object THEN
+END $$;
+        "#,
+    )
+    .await?;
+
+    // without permission to read from `tweet`, EXPLAIN fails
+    let mut tx = conn.begin().await?;
+    tx.execute("SET LOCAL ROLE sqlx_describe_no_access").await?;
+
+    let d = tx.describe("SELECT id, owner_id FROM tweet").await?;
+
+    assert_eq!(d.nullable(0), Some(false));
+    assert_eq!(d.nullable(1), Some(true));
+
+    // and takes no more than itself down with it
+    let role: String = sqlx::query_scalar("SELECT current_user::text")
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(role, "sqlx_describe_no_access");
+
+    tx.rollback().await?;
+
+    Ok(())
+}
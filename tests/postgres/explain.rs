@@ -0,0 +1,104 @@
+//! Kept apart from the other tests, as it installs the logger of the process.
+
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgExplainOptions};
+use sqlx::{ConnectOptions, Connection};
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// keeps the lines logged for statements
+struct QueryLog(Arc<Mutex<Vec<String>>>);
+
+impl log::Log for QueryLog {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        metadata.target() == "sqlx::query"
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if self.enabled(record.metadata()) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[sqlx_macros::test]
+async fn it_explains_slow_statements() -> anyhow::Result<()> {
+    let _ = dotenv::dotenv();
+
+    let lines = Arc::new(Mutex::new(Vec::new()));
+
+    log::set_boxed_logger(Box::new(QueryLog(lines.clone())))?;
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut options = options.explain_slow_statements(
+        Duration::from_secs(0),
+        PgExplainOptions::new().analyze(true),
+    );
+
+    options.log_slow_statements(log::LevelFilter::Warn, Duration::from_secs(0));
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    conn.execute("CREATE TEMPORARY TABLE explained (id INT4)")
+        .await?;
+
+    // explained once, the second time being within the interval
+    for _ in 0..2 {
+        let rows: Vec<(i32,)> = sqlx::query_as("SELECT $1::int4 + 1 AS explained")
+            .bind(1_i32)
+            .fetch_all(&mut conn)
+            .await?;
+
+        assert_eq!(rows, [(2,)]);
+    }
+
+    let mut tx = conn.begin().await?;
+
+    // explained without `ANALYZE`, which would insert another row
+    sqlx::query("INSERT INTO explained (id) VALUES ($1)")
+        .bind(1_i32)
+        .execute(&mut tx)
+        .await?;
+
+    // cannot be explained, which leaves the transaction usable
+    tx.execute("SELECT 1 AS explained; SELECT 2").await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT count(*) FROM explained")
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    tx.rollback().await?;
+
+    let lines = lines.lock().unwrap();
+    let planned = |summary: &str| -> Vec<&String> {
+        lines
+            .iter()
+            .filter(|line| line.starts_with(summary) && line.contains("plan:"))
+            .collect()
+    };
+
+    let select = planned("SELECT $1::int4 + 1");
+    assert_eq!(select.len(), 1);
+    assert!(select[0].contains("\"Node Type\""));
+    assert!(select[0].contains("\"Actual Rows\""));
+
+    let insert = planned("INSERT INTO explained (id)");
+    assert_eq!(insert.len(), 1);
+    assert!(!insert[0].contains("\"Actual Rows\""));
+
+    // logged, but without a plan
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("SELECT 1 AS explained;")));
+    assert!(planned("SELECT 1 AS explained;").is_empty());
+
+    // the `EXPLAIN` itself is not logged
+    assert!(!lines.iter().any(|line| line.starts_with("EXPLAIN")));
+
+    Ok(())
+}
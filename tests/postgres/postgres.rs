@@ -1,11 +1,22 @@
 use futures::TryStreamExt;
+use sqlx::database::Capabilities;
+use sqlx::middleware::{QueryContext, QueryMiddleware, SqlCommenter};
+use sqlx::postgres::types::{PgInterval, PgRange};
+use sqlx::postgres::{quote_identifier, quote_qualified};
 use sqlx::postgres::{
-    PgConnectOptions, PgConnection, PgDatabaseError, PgErrorPosition, PgSeverity,
+    PgAdvisoryLock, PgAdvisoryLockKey, PgAnalyzeOptions, PgConnectOptions, PgConnection,
+    PgCopyCsvOptions, PgDatabaseError, PgErrorPosition, PgFlavor, PgReplication,
+    PgReplicationMessage, PgSeverity, PgSslMode, PgSslNegotiation, PgStatementCacheMode,
+    PgTypeInfo,
+};
+use sqlx::postgres::{PgPoolOptions, PgRow, PgValueFormat, Postgres};
+use sqlx::{
+    BindLogging, Column, ConnectOptions, Connection, Executor, Row, Statement, TypeInfo, ValueRef,
 };
-use sqlx::postgres::{PgPoolOptions, PgRow, Postgres};
-use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
 use sqlx_test::{new, setup_if_needed};
+use std::borrow::Cow;
 use std::env;
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
@@ -282,6 +293,150 @@ async fn it_can_fail_and_recover() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_recovers_from_a_query_dropped_part_way_through() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let mut query = conn.fetch_all("SELECT pg_sleep(0.1)");
+    assert!(futures::poll!(&mut query).is_pending());
+    drop(query);
+
+    let val: i32 = conn.fetch_one("SELECT 1::int4").await?.get(0);
+    assert_eq!(val, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rejects_queries_while_a_leaked_query_is_in_flight() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    // a query that was sent, and will never be finished or dropped
+    let mut query = conn.fetch_all("SELECT pg_sleep(0.1)");
+    assert!(futures::poll!(&mut query).is_pending());
+    std::mem::forget(query);
+
+    let res = conn.execute("SELECT 1").await;
+
+    assert!(
+        matches!(
+            res,
+            Err(sqlx::Error::ConnectionBusy {
+                operation: "fetch_many"
+            })
+        ),
+        "{:?}",
+        res
+    );
+
+    let err = conn.ping().await.unwrap_err();
+    assert!(err.is_connection_error());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_closes_pooled_connections_with_a_leaked_query() -> anyhow::Result<()> {
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    let mut conn = pool.acquire().await?;
+    let pid: i32 = conn.fetch_one("SELECT pg_backend_pid()").await?.get(0);
+
+    let mut query = conn.fetch_all("SELECT pg_sleep(0.1)");
+    assert!(futures::poll!(&mut query).is_pending());
+    std::mem::forget(query);
+
+    drop(conn);
+
+    // the connection is closed when it fails to flush, instead of going back to the pool
+    let other: i32 = pool.fetch_one("SELECT pg_backend_pid()").await?.get(0);
+    assert_ne!(pid, other);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_tests_idle_connections_when_the_server_shuts_down() -> anyhow::Result<()> {
+    let (errors, window) = errors_after_terminating_backends(true).await?;
+    eprintln!(
+        "with test_idle_on_shutdown: {} errors in {:?}",
+        errors, window
+    );
+    assert_eq!(errors, 1);
+
+    let (errors, window) = errors_after_terminating_backends(false).await?;
+    eprintln!(
+        "without test_idle_on_shutdown: {} errors in {:?}",
+        errors, window
+    );
+    assert_eq!(errors, 5);
+
+    Ok(())
+}
+
+// terminates every backend of a pool of 5 connections, like a failover would, and counts the
+// queries that fail before the pool recovers, with the time from the first error to the last
+async fn errors_after_terminating_backends(test_idle: bool) -> anyhow::Result<(usize, Duration)> {
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .test_before_acquire(false)
+        .test_idle_on_shutdown(test_idle)
+        .connect(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    let mut conns = Vec::new();
+    let mut pids = Vec::new();
+
+    for _ in 0..5 {
+        let mut conn = pool.acquire().await?;
+        pids.push(
+            conn.fetch_one("SELECT pg_backend_pid()")
+                .await?
+                .get::<i32, _>(0),
+        );
+        conns.push(conn);
+    }
+
+    drop(conns);
+
+    let mut admin = new::<Postgres>().await?;
+    sqlx::query("SELECT pg_terminate_backend(pid) FROM UNNEST($1::int4[]) pid")
+        .bind(&pids)
+        .execute(&mut admin)
+        .await?;
+
+    sqlx_rt::sleep(Duration::from_millis(100)).await;
+
+    let mut errors = 0;
+    let mut first_error = None;
+    let mut window = Duration::default();
+
+    for _ in 0..5 {
+        match pool.fetch_one("SELECT 1").await {
+            Ok(_) => {}
+
+            Err(error) => {
+                assert!(error.is_connection_error(), "{:?}", error);
+
+                errors += 1;
+                let first = *first_error.get_or_insert_with(std::time::Instant::now);
+                window = first.elapsed();
+            }
+        }
+
+        // give the pool a moment to test its idle connections
+        sqlx_rt::sleep(Duration::from_millis(50)).await;
+    }
+
+    assert!(pool.stats().server_shutdowns >= 1);
+    pool.close().await;
+
+    Ok((errors, window))
+}
+
 #[sqlx_macros::test]
 async fn it_can_fail_and_recover_with_pool() -> anyhow::Result<()> {
     let pool = sqlx_test::pool::<Postgres>().await?;
@@ -353,6 +508,68 @@ async fn it_can_query_all_scalar() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_can_query_scalar_with_trailing_columns() -> anyhow::Result<()> {
+    let pool = sqlx_test::pool::<Postgres>().await?;
+
+    // `point` has no Rust type; it is never decoded
+    let scalar: i32 = sqlx::query_scalar("SELECT 42, '(1,2)'::point")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(scalar, 42);
+
+    let mut tx = pool.begin().await?;
+
+    let scalar: Vec<String> =
+        sqlx::query_scalar("SELECT 'a', '(1,2)'::point UNION ALL SELECT 'b', NULL")
+            .fetch_all(&mut tx)
+            .await?;
+    assert_eq!(scalar, vec!["a", "b"]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rejects_query_scalar_without_columns() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let res: Result<i32, _> = sqlx::query_scalar("SELECT").fetch_one(&mut conn).await;
+    let err = res.unwrap_err();
+
+    assert!(matches!(err, sqlx::Error::Decode(_)));
+    assert!(err.to_string().contains("at least one column"), "{}", err);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_binds_tuples_of_arguments() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let (sum, text): (i32, String) =
+        sqlx::query_as_with("SELECT $1::int4 + $2, $3::text", (1_i32, 2_i32, "three"))
+            .fetch_one(&mut conn)
+            .await?;
+    assert_eq!(sum, 3);
+    assert_eq!(text, "three");
+
+    let value: Option<i64> = sqlx::query_scalar_with("SELECT $1", (None::<i64>,))
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, None);
+
+    let done = sqlx::query_with("SELECT 1", ()).execute(&mut conn).await?;
+    assert_eq!(done.rows_affected(), 1);
+
+    // each element is bound exactly like `.bind()`, so a mismatch fails the same way
+    let res = sqlx::query_with("SELECT $1::int4, $2::int4", (1_i32, "two"))
+        .execute(&mut conn)
+        .await;
+    assert!(res.unwrap_err().to_string().contains("\"two\""));
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_work_with_transactions() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -705,6 +922,115 @@ async fn it_closes_statement_from_cache_issue_470() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_never_evicts_pinned_statements() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let mut conn = PgConnection::connect_with(&options.statement_cache_capacity(2)).await?;
+
+    conn.prepare_pinned("SELECT $1::int4 AS pinned").await?;
+
+    // one-off statements only ever evict each other
+    for i in 0..5 {
+        let val: i32 = sqlx::query_scalar(&*format!("SELECT $1::int4 + {}", i))
+            .bind(1_i32)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(val, i + 1);
+    }
+
+    let val: i32 = sqlx::query_scalar("SELECT $1::int4 AS pinned")
+        .bind(7_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(val, 7);
+
+    let stats = conn.stats().statement_cache;
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.evictions, 4);
+
+    // once pinned statements fill the cache, others are run without being cached
+    conn.prepare_pinned("SELECT $1::int4 AS also_pinned")
+        .await?;
+    assert!(conn
+        .prepare_pinned("SELECT $1::int4 AS one_too_many")
+        .await
+        .is_err());
+
+    let val: i32 = sqlx::query_scalar("SELECT $1::int4 AS not_cached")
+        .bind(3_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(val, 3);
+    assert_eq!(conn.cached_statements_size(), 2);
+    assert_eq!(conn.stats().statement_cache.refusals, 2);
+
+    // the statements that were not cached are closed on the server: only the pinned ones are
+    // left, next to this one
+    let prepared: i64 = sqlx::query_scalar("SELECT count(*) FROM pg_prepared_statements")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(prepared, 3);
+
+    conn.clear_cached_statements().await?;
+    assert_eq!(conn.cached_statements_size(), 0);
+
+    Ok(())
+}
+
+// the server requires ALPN for direct TLS, which only rustls does
+#[cfg(any(
+    feature = "runtime-tokio-rustls",
+    feature = "runtime-async-std-rustls",
+    feature = "runtime-actix-rustls"
+))]
+#[sqlx_macros::test]
+async fn it_negotiates_tls_directly() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let options = options.ssl_negotiation(PgSslNegotiation::Direct);
+
+    // `prefer` falls back to an `SSLRequest`, and then to plaintext, as the server allows
+    let mut conn = PgConnection::connect_with(&options.clone().ssl_mode(PgSslMode::Prefer)).await?;
+
+    let (version, ssl): (i32, bool) = sqlx::query_as(
+        "SELECT current_setting('server_version_num')::int, current_setting('ssl')::bool",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    let encrypted: bool =
+        sqlx::query_scalar("SELECT ssl FROM pg_stat_ssl WHERE pid = pg_backend_pid()")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(encrypted, ssl);
+
+    // `require` takes direct TLS or nothing
+    let direct = PgConnection::connect_with(&options.ssl_mode(PgSslMode::Require)).await;
+
+    if ssl && version >= 170000 {
+        let mut conn = direct?;
+
+        let encrypted: bool =
+            sqlx::query_scalar("SELECT ssl FROM pg_stat_ssl WHERE pid = pg_backend_pid()")
+                .fetch_one(&mut conn)
+                .await?;
+
+        assert!(encrypted);
+    } else {
+        assert!(direct.is_err());
+    }
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_sets_application_name() -> anyhow::Result<()> {
     sqlx_test::setup_if_needed();
@@ -726,112 +1052,434 @@ async fn it_sets_application_name() -> anyhow::Result<()> {
 }
 
 #[sqlx_macros::test]
-async fn it_can_handle_parameter_status_message_issue_484() -> anyhow::Result<()> {
-    new::<Postgres>().await?.execute("SET NAMES 'UTF8'").await?;
+async fn it_defaults_application_name_to_the_executable() -> anyhow::Result<()> {
+    if env::var_os("PGAPPNAME").is_some() {
+        return Ok(());
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    let name: String = sqlx::query_scalar("select current_setting('application_name')")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let exe = env::current_exe()?;
+    let expected = exe.file_stem().unwrap().to_string_lossy();
+
+    assert_eq!(name, expected);
+
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
-    let mut conn = new::<Postgres>().await?;
-    let mut tx = conn.begin().await?;
+async fn it_rejects_writes_on_read_only_connections() -> anyhow::Result<()> {
+    setup_if_needed();
 
-    let tweet_id: i64 =
-        sqlx::query_scalar("INSERT INTO tweet ( text ) VALUES ( 'Hello, World' ) RETURNING id")
-            .fetch_one(&mut tx)
-            .await?;
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut conn = PgConnection::connect_with(&options.clone().read_only(true)).await?;
 
-    let statement = tx.prepare("SELECT * FROM tweet WHERE id = $1").await?;
+    let value: i32 = sqlx::query_scalar(
+        "-- a comment\nSELECT update_time FROM (SELECT 1 AS update_time) AS t WHERE $1",
+    )
+    .bind(true)
+    .fetch_one(&mut conn)
+    .await?;
+    assert_eq!(value, 1);
 
-    assert_eq!(statement.column(0).name(), "id");
-    assert_eq!(statement.column(1).name(), "created_at");
-    assert_eq!(statement.column(2).name(), "text");
-    assert_eq!(statement.column(3).name(), "owner_id");
+    let err = conn
+        .execute("CREATE TEMPORARY TABLE read_only (id INT)")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, sqlx::Error::ReadOnlyViolation(sql) if sql.starts_with("CREATE")),
+        "{:?}",
+        err
+    );
 
-    assert_eq!(statement.column(0).type_info().name(), "INT8");
-    assert_eq!(statement.column(1).type_info().name(), "TIMESTAMPTZ");
-    assert_eq!(statement.column(2).type_info().name(), "TEXT");
-    assert_eq!(statement.column(3).type_info().name(), "INT8");
+    let err = conn
+        .execute("SELECT 1; DROP TABLE tweet")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, sqlx::Error::ReadOnlyViolation(sql) if sql == "DROP TABLE tweet"),
+        "{:?}",
+        err
+    );
 
-    let row = statement.query().bind(tweet_id).fetch_one(&mut tx).await?;
-    let tweet_text: &str = row.try_get("text")?;
+    // nothing was sent, so the connection is still usable
+    let mut tx = conn.begin().await?;
+    sqlx::query("SELECT 1").execute(&mut tx).await?;
+    tx.rollback().await?;
 
-    assert_eq!(tweet_text, "Hello, World");
+    // without the check the server still refuses to write
+    let mut conn =
+        PgConnection::connect_with(&options.read_only(true).read_only_statement_check(false))
+            .await?;
+
+    let err = conn
+        .execute("CREATE TEMPORARY TABLE read_only (id INT)")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.as_database_error().and_then(|e| e.code()).as_deref(),
+        Some("25006"),
+        "{:?}",
+        err
+    );
 
     Ok(())
 }
 
-// repro is more reliable with the basic scheduler used by `#[tokio::test]`
-#[cfg(feature = "_rt-tokio")]
-#[tokio::test]
-async fn test_issue_622() -> anyhow::Result<()> {
-    use std::time::Instant;
-
+#[sqlx_macros::test]
+async fn it_appends_a_suffix_to_pool_connection_names() -> anyhow::Result<()> {
     setup_if_needed();
 
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+
     let pool = PgPoolOptions::new()
-        .max_connections(1) // also fails with higher counts, e.g. 5
-        .connect(&std::env::var("DATABASE_URL").unwrap())
+        .connection_name_suffix(|n| format!("-pool-{}", n))
+        .connect_with(options.application_name("myservice"))
         .await?;
 
-    println!("pool state: {:?}", pool);
+    let mut conns = vec![pool.acquire().await?, pool.acquire().await?];
+    let mut names = Vec::new();
 
-    let mut handles = vec![];
+    for conn in &mut conns {
+        let name: String = sqlx::query_scalar("select current_setting('application_name')")
+            .fetch_one(&mut **conn)
+            .await?;
 
-    // given repro spawned 100 tasks but I found it reliably reproduced with 3
-    for i in 0..3 {
-        let pool = pool.clone();
+        names.push(name);
+    }
 
-        handles.push(sqlx_rt::spawn(async move {
-            {
-                let mut conn = pool.acquire().await.unwrap();
+    names.sort();
 
-                let _ = sqlx::query("SELECT 1").fetch_one(&mut conn).await.unwrap();
+    assert_eq!(names, ["myservice-pool-0", "myservice-pool-1"]);
 
-                // conn gets dropped here and should be returned to the pool
-            }
+    Ok(())
+}
 
-            // (do some other work here without holding on to a connection)
-            // this actually fixes the issue, depending on the timeout used
-            // sqlx_rt::sleep(Duration::from_millis(500)).await;
+#[sqlx_macros::test]
+async fn it_runs_queries_with_bind_value_logging() -> anyhow::Result<()> {
+    setup_if_needed();
 
-            {
-                let start = Instant::now();
-                match pool.acquire().await {
-                    Ok(conn) => {
-                        println!("{} acquire took {:?}", i, start.elapsed());
-                        drop(conn);
-                    }
-                    Err(e) => panic!("{} acquire returned error: {} pool state: {:?}", i, e, pool),
-                }
-            }
+    let mut options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    options.log_bind_values(BindLogging::Redacted(vec![1]));
 
-            Result::<(), anyhow::Error>::Ok(())
-        }));
-    }
+    let mut conn = PgConnection::connect_with(&options).await?;
 
-    futures::future::try_join_all(handles).await?;
+    let (a, b, c): (i32, String, Option<String>) = sqlx::query_as("select $1, $2, $3")
+        .bind(1_i32)
+        .bind("password")
+        .bind(None::<String>)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!((a, &*b, c), (1, "password", None));
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn test_describe_outer_join_nullable() -> anyhow::Result<()> {
+async fn it_shrinks_buffers_after_a_large_result() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
 
-    // test nullability inference for various joins
-
-    // inner join, nullability should not be overridden
-    // language=PostgreSQL
-    let describe = conn
-        .describe(
-            "select tweet.id
-from (values (null)) vals(val)
-         inner join tweet on false",
-        )
+    let value: String = sqlx::query_scalar("select repeat('x', 1000000)")
+        .fetch_one(&mut conn)
         .await?;
 
-    assert_eq!(describe.nullable(0), Some(false));
+    assert_eq!(value.len(), 1_000_000);
+
+    // the read buffer takes back the memory of the large row once the row has been dropped
+    let _: i32 = sqlx::query_scalar("select 1").fetch_one(&mut conn).await?;
+    assert!(conn.buffer_sizes().read > 512 * 1024);
+
+    conn.shrink_buffers();
+
+    assert!(conn.buffer_sizes().total() < 64 * 1024);
+
+    // the connection still works after shrinking
+    let value: i32 = sqlx::query_scalar("select 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_caps_buffer_growth() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut conn = PgConnection::connect_with(&options.buffer_max(64 * 1024)).await?;
+
+    let large = "y".repeat(1_000_000);
+
+    let value: String = sqlx::query_scalar("select $1 || repeat('x', 1000000)")
+        .bind(&large)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value.len(), 2_000_000);
+
+    let sizes = conn.buffer_sizes();
+    assert!(sizes.read <= 64 * 1024, "{:?}", sizes);
+    assert!(sizes.write <= 64 * 1024, "{:?}", sizes);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_shrinks_buffers_on_release_to_the_pool() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .shrink_buffers_above(64 * 1024)
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    let mut conn = pool.acquire().await?;
+
+    let _: String = sqlx::query_scalar("select repeat('x', 1000000)")
+        .fetch_one(&mut conn)
+        .await?;
+    let _: i32 = sqlx::query_scalar("select 1").fetch_one(&mut conn).await?;
+
+    assert!(conn.buffer_sizes().total() > 64 * 1024);
+
+    drop(conn);
+
+    let conn = pool.acquire().await?;
+    assert!(conn.buffer_sizes().total() <= 64 * 1024);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_counts_queries_and_bytes() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    let before = conn.stats();
+
+    conn.execute("SELECT 1").await?;
+    let after = conn.stats();
+
+    assert_eq!(after.queries - before.queries, 1);
+    assert!(after.average_latency.is_some());
+    assert!(after.total_latency > before.total_latency);
+    assert!(after.connected_at == before.connected_at);
+
+    // Query: tag, length and the string
+    assert_eq!(after.bytes_sent - before.bytes_sent, 1 + 4 + 9);
+
+    // RowDescription for `?column?`, one DataRow, CommandComplete and ReadyForQuery
+    assert_eq!(
+        after.bytes_received - before.bytes_received,
+        (1 + 4 + 2 + 9 + 18) + (1 + 4 + 2 + 4 + 1) + (1 + 4 + 9) + (1 + 4 + 1)
+    );
+
+    assert_eq!(before.last_ping, None);
+    conn.ping().await?;
+
+    let stats = conn.stats();
+    assert!(stats.last_ping.is_some());
+    assert_eq!(stats.queries, after.queries + 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_aggregates_connection_stats_in_the_pool() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .test_before_acquire(false)
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    for _ in 0..3 {
+        pool.execute("SELECT 1").await?;
+    }
+
+    let conn = pool.acquire().await?;
+    let stats = conn.stats();
+    assert_eq!(stats.queries, 3);
+    drop(conn);
+
+    let pool_stats = pool.stats();
+    assert_eq!(pool_stats.connections_opened, 1);
+    assert_eq!(pool_stats.queries, 3);
+    assert_eq!(pool_stats.total_latency, stats.total_latency);
+    assert_eq!(pool_stats.bytes_sent, stats.bytes_sent);
+    assert_eq!(pool_stats.bytes_received, stats.bytes_received);
+    assert!(pool_stats.average_latency().is_some());
+
+    Ok(())
+}
+
+#[cfg(all(feature = "chrono", feature = "time"))]
+#[sqlx_macros::test]
+async fn it_decodes_text_timestamps_in_any_time_zone() -> anyhow::Result<()> {
+    use sqlx::types::chrono::{DateTime, NaiveDate, Utc};
+    use sqlx::types::time::{Date, OffsetDateTime};
+
+    let mut conn = new::<Postgres>().await?;
+
+    let expected = DateTime::<Utc>::from_utc(
+        NaiveDate::from_ymd(2026, 10, 14).and_hms_micro(12, 30, 0, 250_000),
+        Utc,
+    );
+
+    // a query without arguments uses the simple protocol, and so the text format
+    let sql = "SELECT '2026-10-14 12:30:00.25+00'::timestamptz, '2026-10-14'::date";
+
+    for time_zone in &["UTC", "America/New_York", "Asia/Kolkata"] {
+        conn.execute(&*format!("SET TIME ZONE '{}'", time_zone))
+            .await?;
+
+        let row = conn.fetch_one(sql).await?;
+
+        assert_eq!(
+            row.try_get::<DateTime<Utc>, _>(0)?,
+            expected,
+            "{}",
+            time_zone
+        );
+        assert_eq!(
+            row.try_get::<OffsetDateTime, _>(0)?.unix_timestamp(),
+            expected.timestamp(),
+            "{}",
+            time_zone
+        );
+        assert_eq!(row.try_get::<Date, _>(1)?.year(), 2026);
+    }
+
+    // other date styles cannot be decoded from text, but binary results are unaffected
+    conn.execute("SET datestyle TO German").await?;
+
+    let err = conn
+        .fetch_one(sql)
+        .await?
+        .try_get::<DateTime<Utc>, _>(0)
+        .unwrap_err();
+    assert!(err.to_string().contains("DateStyle"), "{}", err);
+
+    let (value,): (DateTime<Utc>,) = sqlx::query_as("SELECT $1::timestamptz")
+        .bind(expected)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, expected);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_handle_parameter_status_message_issue_484() -> anyhow::Result<()> {
+    new::<Postgres>().await?.execute("SET NAMES 'UTF8'").await?;
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    let mut tx = conn.begin().await?;
+
+    let tweet_id: i64 =
+        sqlx::query_scalar("INSERT INTO tweet ( text ) VALUES ( 'Hello, World' ) RETURNING id")
+            .fetch_one(&mut tx)
+            .await?;
+
+    let statement = tx.prepare("SELECT * FROM tweet WHERE id = $1").await?;
+
+    assert_eq!(statement.column(0).name(), "id");
+    assert_eq!(statement.column(1).name(), "created_at");
+    assert_eq!(statement.column(2).name(), "text");
+    assert_eq!(statement.column(3).name(), "owner_id");
+
+    assert_eq!(statement.column(0).type_info().name(), "INT8");
+    assert_eq!(statement.column(1).type_info().name(), "TIMESTAMPTZ");
+    assert_eq!(statement.column(2).type_info().name(), "TEXT");
+    assert_eq!(statement.column(3).type_info().name(), "INT8");
+
+    let row = statement.query().bind(tweet_id).fetch_one(&mut tx).await?;
+    let tweet_text: &str = row.try_get("text")?;
+
+    assert_eq!(tweet_text, "Hello, World");
+
+    Ok(())
+}
+
+// repro is more reliable with the basic scheduler used by `#[tokio::test]`
+#[cfg(feature = "_rt-tokio")]
+#[tokio::test]
+async fn test_issue_622() -> anyhow::Result<()> {
+    use std::time::Instant;
+
+    setup_if_needed();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1) // also fails with higher counts, e.g. 5
+        .connect(&std::env::var("DATABASE_URL").unwrap())
+        .await?;
+
+    println!("pool state: {:?}", pool);
+
+    let mut handles = vec![];
+
+    // given repro spawned 100 tasks but I found it reliably reproduced with 3
+    for i in 0..3 {
+        let pool = pool.clone();
+
+        handles.push(sqlx_rt::spawn(async move {
+            {
+                let mut conn = pool.acquire().await.unwrap();
+
+                let _ = sqlx::query("SELECT 1").fetch_one(&mut conn).await.unwrap();
+
+                // conn gets dropped here and should be returned to the pool
+            }
+
+            // (do some other work here without holding on to a connection)
+            // this actually fixes the issue, depending on the timeout used
+            // sqlx_rt::sleep(Duration::from_millis(500)).await;
+
+            {
+                let start = Instant::now();
+                match pool.acquire().await {
+                    Ok(conn) => {
+                        println!("{} acquire took {:?}", i, start.elapsed());
+                        drop(conn);
+                    }
+                    Err(e) => panic!("{} acquire returned error: {} pool state: {:?}", i, e, pool),
+                }
+            }
+
+            Result::<(), anyhow::Error>::Ok(())
+        }));
+    }
+
+    futures::future::try_join_all(handles).await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_describe_outer_join_nullable() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    // test nullability inference for various joins
+
+    // inner join, nullability should not be overridden
+    // language=PostgreSQL
+    let describe = conn
+        .describe(
+            "select tweet.id
+from (values (null)) vals(val)
+         inner join tweet on false",
+        )
+        .await?;
+
+    assert_eq!(describe.nullable(0), Some(false));
 
     // language=PostgreSQL
     let describe = conn
@@ -887,3 +1535,3025 @@ from (values (null)) vals(val)
 
     Ok(())
 }
+
+#[cfg(feature = "json")]
+#[sqlx_macros::test]
+async fn it_can_debug_and_convert_rows_to_json() -> anyhow::Result<()> {
+    use sqlx::RowExt;
+
+    let mut conn = new::<Postgres>().await?;
+
+    // language=PostgreSQL
+    let sql = r#"select 1::int4 as "id", 'hello'::text as "name", null::text as "missing", true as "ok", '{"a":1}'::jsonb as "data", '\xdead'::bytea as "raw""#;
+
+    let row = sqlx::query(sql).fetch_one(&mut conn).await?;
+
+    assert_eq!(
+        format!("{:?}", row),
+        r#"PgRow { id: 1, name: "hello", missing: NULL, ok: true, data: {"a": 1}, raw: <binary, 2 bytes> }"#
+    );
+
+    let json = serde_json::Value::Object(row.to_json());
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "id": 1,
+            "name": "hello",
+            "missing": null,
+            "ok": true,
+            "data": { "a": 1 },
+            "raw": { "type": "BYTEA", "base64": "3q0=" },
+        })
+    );
+
+    // the simple query protocol returns everything as text
+    let row = conn.fetch_one(sql).await?;
+
+    assert_eq!(
+        format!("{:?}", row),
+        r#"PgRow { id: 1, name: "hello", missing: NULL, ok: true, data: {"a": 1}, raw: "\\xdead" }"#
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rejects_too_many_arguments() -> anyhow::Result<()> {
+    use sqlx::postgres::PgArguments;
+
+    let mut conn = new::<Postgres>().await?;
+
+    let count = PgArguments::MAX_ARGUMENTS + 1;
+    let placeholders: Vec<String> = (1..=count).map(|i| format!("${}", i)).collect();
+    let sql = format!("SELECT {}", placeholders.join(", "));
+
+    let mut query = sqlx::query(&sql);
+
+    for i in 0..count {
+        query = query.bind(i as i32);
+    }
+
+    let res = query.execute(&mut conn).await;
+
+    assert!(matches!(
+        res,
+        Err(sqlx::Error::TooManyArguments { given, limit })
+            if given == count && limit == PgArguments::MAX_ARGUMENTS
+    ));
+
+    // the connection should be left untouched
+    conn.ping().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_values_that_cannot_be_encoded() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    // too many microseconds for an INTERVAL
+    let res = sqlx::query("SELECT $1::int4, $2::interval")
+        .bind(1_i32)
+        .bind(Duration::from_secs(u64::MAX))
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(
+        matches!(res, Err(sqlx::Error::ArgumentEncode { index: 1, .. })),
+        "{:?}",
+        res
+    );
+
+    // PostgreSQL does not allow NUL in text
+    let res = sqlx::query("SELECT $1::text")
+        .bind("nul\0byte")
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(
+        matches!(res, Err(sqlx::Error::ArgumentEncode { index: 0, .. })),
+        "{:?}",
+        res
+    );
+
+    // Postgres has no unsigned integers and a `u64` is sent as INT8
+    let res = sqlx::query("SELECT $1::int8")
+        .bind(i64::MAX as u64 + 1)
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(
+        matches!(res, Err(sqlx::Error::ArgumentEncode { index: 0, .. })),
+        "{:?}",
+        res
+    );
+
+    // only the first error is reported
+    let res = sqlx::query("SELECT $1::text, $2::interval")
+        .bind("\0")
+        .bind(Duration::from_secs(u64::MAX))
+        .execute(&mut conn)
+        .await;
+
+    assert!(
+        matches!(res, Err(sqlx::Error::ArgumentEncode { index: 0, .. })),
+        "{:?}",
+        res
+    );
+
+    // nothing was sent, so the connection is still usable
+    let value: i32 = sqlx::query_scalar("SELECT $1::int4")
+        .bind(5_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 5);
+
+    Ok(())
+}
+
+#[cfg(feature = "bigdecimal")]
+#[sqlx_macros::test]
+async fn it_reports_decimals_too_large_to_encode() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let value: sqlx::types::BigDecimal = "1e200000".parse()?;
+
+    let res = sqlx::query("SELECT $1::numeric")
+        .bind(value)
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(
+        matches!(res, Err(sqlx::Error::ArgumentEncode { index: 0, .. })),
+        "{:?}",
+        res
+    );
+
+    conn.ping().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_delivers_notices_to_the_handler() -> anyhow::Result<()> {
+    use std::sync::{Arc, Mutex};
+
+    sqlx_test::setup_if_needed();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ = received.clone();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let options = options.notice_handler(Arc::new(move |notice| {
+        if notice.message() == "panic" {
+            panic!("handler panicked");
+        }
+
+        received_.lock().unwrap().push((
+            notice.severity(),
+            notice.message().to_owned(),
+            notice.hint().map(ToOwned::to_owned),
+        ));
+    }));
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    // a notice raised between rows must not disturb decoding
+    // language=PostgreSQL
+    conn.execute(
+        r#"
+CREATE OR REPLACE FUNCTION pg_temp.noisy(i int4) RETURNS int4 AS $$
+BEGIN
+    RAISE NOTICE 'row %', i USING HINT = 'a hint';
+    RETURN i;
+END;
+$$ LANGUAGE plpgsql
+    "#,
+    )
+    .await?;
+
+    let values: Vec<i32> =
+        sqlx::query_scalar("SELECT pg_temp.noisy(i) FROM generate_series(1, 3) i")
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(values, vec![1, 2, 3]);
+
+    conn.execute("DO $$ BEGIN RAISE WARNING 'panic'; END $$")
+        .await?;
+
+    conn.execute("DO $$ BEGIN RAISE WARNING 'still here'; END $$")
+        .await?;
+
+    let received = received.lock().unwrap();
+
+    assert_eq!(received.len(), 4);
+    assert_eq!(
+        received[0],
+        (
+            PgSeverity::Notice,
+            "row 1".to_owned(),
+            Some("a hint".to_owned())
+        )
+    );
+    assert_eq!(
+        received[3],
+        (PgSeverity::Warning, "still here".to_owned(), None)
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sends_options_as_startup_parameters() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+
+    // `TimeZone` is also one of the defaults sent by SQLx
+    let options = options.options([("search_path", "myschema"), ("TimeZone", "Europe/Berlin")]);
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    let (search_path, time_zone): (String, String) =
+        sqlx::query_as("select current_setting('search_path'), current_setting('TimeZone')")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(search_path, "myschema");
+    assert_eq!(time_zone, "Europe/Berlin");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_traces_wire_messages() -> anyhow::Result<()> {
+    use sqlx::wire::{WireDirection, WireMessage, WireTracer};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Collect(Mutex<Vec<(WireDirection, Option<u8>, Option<Vec<u8>>)>>);
+
+    impl WireTracer for Collect {
+        fn trace(&self, message: &WireMessage<'_>) {
+            self.0.lock().unwrap().push((
+                message.direction(),
+                message.tag(),
+                message.payload().map(<[u8]>::to_vec),
+            ));
+        }
+
+        fn include_payload(&self) -> bool {
+            true
+        }
+    }
+
+    sqlx_test::setup_if_needed();
+
+    let tracer = Arc::new(Collect::default());
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut conn = PgConnection::connect_with(&options.wire_tracing(tracer.clone())).await?;
+
+    tracer.0.lock().unwrap().clear();
+
+    conn.execute("SELECT 1").await?;
+
+    let messages = tracer.0.lock().unwrap().clone();
+
+    assert_eq!(
+        messages[0],
+        (
+            WireDirection::Sent,
+            Some(b'Q'),
+            Some(b"SELECT 1\0".to_vec())
+        )
+    );
+
+    let received: Vec<_> = messages[1..]
+        .iter()
+        .map(|(direction, tag, _)| (*direction, tag.map(char::from)))
+        .collect();
+
+    assert_eq!(
+        received,
+        [
+            (WireDirection::Received, Some('T')),
+            (WireDirection::Received, Some('D')),
+            (WireDirection::Received, Some('C')),
+            (WireDirection::Received, Some('Z')),
+        ]
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sets_the_search_path() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let schemas = [r#"tenant "quoted""#, "tenant with spaces", "tenänt_ünïcødé"];
+
+    let mut conn = new::<Postgres>().await?;
+
+    for schema in &schemas {
+        let schema = quote_identifier(schema)?;
+
+        conn.execute(&*format!("DROP SCHEMA IF EXISTS {} CASCADE", schema))
+            .await?;
+        conn.execute(&*format!("CREATE SCHEMA {}", schema)).await?;
+    }
+
+    conn.execute(&*format!(
+        "CREATE TABLE {} (id int)",
+        quote_qualified(schemas[1], r#"my "table""#)?
+    ))
+    .await?;
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut conn = PgConnection::connect_with(&options.search_path(&schemas)).await?;
+
+    let current: Vec<String> = sqlx::query_scalar("select unnest(current_schemas(false))::text")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(current, schemas);
+
+    // unqualified names resolve through the search path
+    let count: i64 = sqlx::query_scalar(r#"select count(*) from "my ""table""""#)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_quotes_literals_the_server_reads_back() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    for standard_conforming_strings in &["on", "off"] {
+        conn.execute(&*format!(
+            "SET standard_conforming_strings = {}",
+            standard_conforming_strings
+        ))
+        .await?;
+
+        // the server reports the change
+        assert_eq!(
+            conn.standard_conforming_strings(),
+            *standard_conforming_strings == "on"
+        );
+
+        for value in sqlx_test::strings_to_quote(200) {
+            let sql = format!("SELECT {}::text", conn.quote_literal(&value)?);
+            let row = conn.fetch_one(&*sql).await?;
+
+            assert_eq!(row.try_get::<String, _>(0)?, value, "{}", sql);
+        }
+    }
+
+    assert!(conn.quote_literal("a\0b").is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sends_unprepared_queries_without_binds_as_they_are() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let sql = "SELECT 1::int4, '1 year 2 mons -3 days 04:05:06.789'::interval";
+    let interval = PgInterval {
+        months: 14,
+        days: -3,
+        microseconds: 14_706_789_000,
+    };
+
+    // with the simple query protocol, which returns values in the text format
+    let row = sqlx::query(sql)
+        .persistent(false)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(row.try_get_raw(0)?.format(), PgValueFormat::Text);
+    assert_eq!(row.try_get::<i32, _>(0)?, 1);
+    assert_eq!(row.try_get::<PgInterval, _>(1)?, interval);
+
+    let row = sqlx::query(sql)
+        .persistent(false)
+        .always_prepare(true)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(row.try_get_raw(0)?.format(), PgValueFormat::Binary);
+    assert_eq!(row.try_get::<PgInterval, _>(1)?, interval);
+    assert_eq!(conn.cached_statements_size(), 0);
+
+    // a persistent query is prepared and cached even without binds
+    let row = sqlx::query(sql).fetch_one(&mut conn).await?;
+
+    assert_eq!(row.try_get_raw(0)?.format(), PgValueFormat::Binary);
+    assert_eq!(conn.cached_statements_size(), 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_widens_and_narrows_integers() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let row = conn
+        .fetch_one("SELECT 12::int2, 5::int8, 3000000000::int8")
+        .await?;
+
+    assert_eq!(row.try_get::<i64, _>(0)?, 12);
+    assert_eq!(row.try_get::<i32, _>(1)?, 5);
+    assert_eq!(row.try_get::<i64, _>(2)?, 3_000_000_000);
+
+    match row.try_get::<i32, _>(2) {
+        Err(sqlx::Error::ColumnDecode { index, source }) => {
+            assert_eq!(index, "2");
+            assert_eq!(
+                source.to_string(),
+                "value 3000000000 is out of range for i32"
+            );
+        }
+
+        other => panic!("expected a decode error, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_binds_with_a_declared_type() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let ty: String = sqlx::query_scalar("SELECT pg_typeof($1)::text")
+        .bind_with_type("hello", PgTypeInfo::with_name("varchar"))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(ty, "character varying");
+
+    // the same SQL with another declared type is prepared again
+    let ty: String = sqlx::query_scalar("SELECT pg_typeof($1)::text")
+        .bind_with_type("hello", PgTypeInfo::try_from_oid(25).unwrap())
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(ty, "text");
+
+    // a `NULL` is sent with the type of the `Option`, not as `unknown`
+    let ty: String = sqlx::query_scalar("SELECT pg_typeof($1)::text AS ty")
+        .bind(None::<i64>)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(ty, "bigint");
+
+    // an `i32` is not a valid binary `int8`
+    let res = sqlx::query("SELECT $1")
+        .bind_with_type(5_i32, PgTypeInfo::with_name("int8"))
+        .execute(&mut conn)
+        .await;
+
+    assert!(matches!(res, Err(sqlx::Error::Database(_))), "{:?}", res);
+
+    // the connection is still usable afterwards
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_streams_changes_from_a_replication_slot() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let mut replication = PgReplication::connect(&env::var("DATABASE_URL")?).await?;
+
+    // temporary, so it is dropped with the connection
+    let slot = replication
+        .create_slot("sqlx_test_slot", "test_decoding", true)
+        .await?;
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TABLE IF NOT EXISTS _sqlx_replication_test (id INT)")
+        .await?;
+    conn.execute("INSERT INTO _sqlx_replication_test (id) VALUES (42)")
+        .await?;
+
+    let mut stream = replication
+        .start_replication(&slot.name, slot.consistent_point, &[("include-xids", "0")])
+        .await?;
+
+    loop {
+        match stream.recv().await? {
+            Some(PgReplicationMessage::XLogData { wal_end, data, .. }) => {
+                stream.standby_status_update(wal_end).await?;
+
+                if String::from_utf8_lossy(&data)
+                    == "table public._sqlx_replication_test: INSERT: id[integer]:42"
+                {
+                    break;
+                }
+            }
+
+            Some(PgReplicationMessage::Keepalive { wal_end, .. }) => {
+                stream.standby_status_update(wal_end).await?;
+            }
+
+            None => panic!("the server stopped streaming"),
+        }
+    }
+
+    // replication commands work again after streaming is stopped
+    let mut replication = stream.finish().await?;
+    replication
+        .create_slot("sqlx_test_slot_2", "test_decoding", true)
+        .await?;
+
+    replication.close().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_recovers_from_errors_in_the_extended_protocol() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    // fails at `Parse`
+    let res = sqlx::query("SELEC $1").bind(1_i32).execute(&mut conn).await;
+    let err = res.unwrap_err().into_database_error().unwrap();
+    let err = err.downcast_ref::<PgDatabaseError>();
+
+    assert_eq!(err.severity(), PgSeverity::Error);
+    assert_eq!(err.code(), "42601");
+    assert_eq!(err.position(), Some(PgErrorPosition::Original(1)));
+
+    let value: i32 = sqlx::query_scalar("SELECT $1")
+        .bind(1_i32)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, 1);
+
+    // fails at `Bind`, after the statement was prepared and cached
+    let res = sqlx::query("SELECT $1::int4")
+        .bind("not an int")
+        .fetch_one(&mut conn)
+        .await;
+    assert!(matches!(res, Err(sqlx::Error::Database(_))), "{:?}", res);
+
+    let value: i32 = sqlx::query_scalar("SELECT $1::int4")
+        .bind(2_i32)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, 2);
+
+    // fails at `Execute`
+    let res = sqlx::query("SELECT 1 / $1")
+        .bind(0_i32)
+        .fetch_one(&mut conn)
+        .await;
+    let err = res.unwrap_err().into_database_error().unwrap();
+    assert_eq!(err.code().as_deref(), Some("22012"));
+
+    let value: i32 = sqlx::query_scalar("SELECT 1 / $1")
+        .bind(1_i32)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_does_not_report_errors_from_abandoned_queries() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    // only the first row is read; the error from the third row arrives after the caller
+    // has stopped listening and has to be discarded, not handed to the next query
+    let row = conn
+        .fetch_one("SELECT 1 / (3 - x) FROM generate_series(1, 3) x")
+        .await?;
+    assert_eq!(row.get::<i32, _>(0), 0);
+
+    let value: i32 = sqlx::query_scalar("SELECT 5").fetch_one(&mut conn).await?;
+    assert_eq!(value, 5);
+
+    // and the same with the extended protocol
+    let mut rows = sqlx::query("SELECT 1 / ($1 - x) FROM generate_series(1, 3) x")
+        .bind(3_i32)
+        .fetch(&mut conn);
+    let _ = rows.try_next().await?;
+    drop(rows);
+
+    let value: i32 = sqlx::query_scalar("SELECT $1")
+        .bind(6_i32)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, 6);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_exposes_raw_values() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    // prepared statements use the binary format
+    let row = sqlx::query("SELECT 'hello'::text, 7::int4, NULL::int4")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let value = row.try_get_raw(0)?;
+    assert_eq!(value.format(), PgValueFormat::Binary);
+    assert_eq!(value.type_info().name(), "TEXT");
+    assert_eq!(value.as_str().unwrap(), "hello");
+
+    let value = row.try_get_raw(1)?;
+    assert_eq!(value.as_bytes().unwrap(), &7_i32.to_be_bytes());
+
+    let value = row.try_get_raw(2)?;
+    assert!(value.is_null());
+    assert!(value.as_bytes().is_err());
+
+    // simple queries use the text format
+    let row = conn.fetch_one("SELECT 7::int4").await?;
+    let value = row.try_get_raw(0)?;
+
+    assert_eq!(value.format(), PgValueFormat::Text);
+    assert_eq!(value.as_str().unwrap(), "7");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_acquires_and_releases_advisory_locks() -> anyhow::Result<()> {
+    let mut conn1 = new::<Postgres>().await?;
+    let mut conn2 = new::<Postgres>().await?;
+
+    let lock = PgAdvisoryLock::new("sqlx-test-advisory-lock");
+
+    let mut guard = lock.acquire(&mut conn1).await?;
+
+    // the guard can be used as the connection
+    let one: i32 = sqlx::query_scalar("select 1")
+        .fetch_one(&mut *guard)
+        .await?;
+    assert_eq!(one, 1);
+
+    assert!(lock.try_acquire(&mut conn2).await?.is_none());
+
+    // dropping the guard queues the unlock for the next command on the connection
+    drop(guard);
+    conn1.ping().await?;
+
+    let guard = lock
+        .try_acquire(&mut conn2)
+        .await?
+        .expect("lock was not released");
+    guard.release_now().await?;
+
+    let explicit = PgAdvisoryLock::with_key(PgAdvisoryLockKey::BigInt(8_675_309));
+    let guard = explicit.acquire(&mut conn1).await?;
+
+    let held: i64 = sqlx::query_scalar(
+        "select count(*) from pg_locks where locktype = 'advisory' and pid = pg_backend_pid()",
+    )
+    .fetch_one(guard.release_now().await?)
+    .await?;
+
+    assert_eq!(held, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_warns_when_an_advisory_lock_was_released_behind_the_guard() -> anyhow::Result<()> {
+    use std::sync::{Arc, Mutex};
+
+    setup_if_needed();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ = received.clone();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let options = options.notice_handler(Arc::new(move |notice| {
+        received_.lock().unwrap().push(notice.message().to_owned());
+    }));
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    let lock = PgAdvisoryLock::with_key(PgAdvisoryLockKey::IntPair(1, 2));
+    let mut guard = lock.acquire(&mut conn).await?;
+
+    guard.execute("select pg_advisory_unlock_all()").await?;
+    drop(guard);
+
+    conn.ping().await?;
+
+    // Postgres warns about the unlock itself as well
+    let received = received.lock().unwrap();
+    assert!(
+        received.iter().any(|m| m.contains("(1, 2) was not held")),
+        "{:?}",
+        received
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_executes_raw_scripts() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let outcomes = conn
+        .execute_raw_script(
+            r#"
+CREATE TEMPORARY TABLE script_test (id INT);
+
+-- semicolons in a function body; are left to the server
+CREATE FUNCTION pg_temp.script_test_insert(n INT) RETURNS VOID AS $$
+BEGIN
+    INSERT INTO script_test SELECT generate_series(1, n);
+    RAISE NOTICE 'inserted %; done', n;
+END;
+$$ LANGUAGE plpgsql;
+
+SELECT pg_temp.script_test_insert(3);
+SELECT id, 'a;b' AS text FROM script_test ORDER BY id;
+DELETE FROM script_test WHERE id > 1;
+            "#,
+        )
+        .await?;
+
+    let tags: Vec<_> = outcomes.iter().map(|outcome| outcome.tag()).collect();
+    assert_eq!(
+        tags,
+        [
+            "CREATE TABLE",
+            "CREATE FUNCTION",
+            "SELECT 1",
+            "SELECT 3",
+            "DELETE 2"
+        ]
+    );
+
+    let rows = outcomes[3].rows();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[2].try_get::<i32, _>("id")?, 3);
+    assert_eq!(rows[2].try_get::<&str, _>("text")?, "a;b");
+    assert_eq!(outcomes[4].rows_affected(), 2);
+
+    assert!(conn.execute_raw_script("-- nothing").await?.is_empty());
+
+    // the server runs the script in one implicit transaction
+    assert!(conn
+        .execute_raw_script("INSERT INTO script_test VALUES (10); SELECT 1 / 0;")
+        .await
+        .is_err());
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM script_test")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
+// counts the lines and bytes written to it instead of keeping them, failing once it has been
+// written `fail_after` bytes, if set
+#[derive(Default)]
+struct CsvCounter {
+    lines: usize,
+    bytes: usize,
+    fail_after: Option<usize>,
+}
+
+impl CsvCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if matches!(self.fail_after, Some(n) if self.bytes >= n) {
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, "disk full"));
+        }
+
+        self.lines += buf.iter().filter(|&&b| b == b'\n').count();
+        self.bytes += buf.len();
+
+        Ok(buf.len())
+    }
+}
+
+#[cfg(any(feature = "_rt-tokio", feature = "_rt-actix"))]
+impl sqlx_rt::AsyncWrite for CsvCounter {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        std::task::Poll::Ready(self.get_mut().write(buf))
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "_rt-async-std")]
+impl sqlx_rt::AsyncWrite for CsvCounter {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        std::task::Poll::Ready(self.get_mut().write(buf))
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+#[sqlx_macros::test]
+async fn it_copies_out_csv() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let mut csv = Vec::new();
+    let rows = conn
+        .copy_out_csv(
+            "(SELECT * FROM (VALUES (1, 'a;b', NULL), (2, 'it''s', '')) AS t (id, text, note))",
+            &mut csv,
+            &PgCopyCsvOptions::new()
+                .header(true)
+                .delimiter(';')
+                .null("NULL"),
+        )
+        .await?;
+
+    assert_eq!(rows, 2);
+    assert_eq!(
+        String::from_utf8(csv)?,
+        "id;text;note\n1;\"a;b\";NULL\n2;it's;\n"
+    );
+
+    let mut csv = Vec::new();
+    let rows = conn
+        .copy_out_csv("tweet (id)", &mut csv, &PgCopyCsvOptions::new())
+        .await?;
+
+    assert_eq!(rows, 0);
+    assert!(csv.is_empty());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_copies_out_a_million_rows_as_csv() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let mut counter = CsvCounter::default();
+    let rows = conn
+        .copy_out_csv(
+            "(SELECT n, n % 2 = 0 FROM generate_series(1, 1000000) n)",
+            &mut counter,
+            &PgCopyCsvOptions::new(),
+        )
+        .await?;
+
+    // e.g. `10,t\n`
+    let expected_bytes: usize = (1..=1_000_000)
+        .map(|n: usize| n.to_string().len() + 3)
+        .sum();
+
+    assert_eq!(rows, 1_000_000);
+    assert_eq!(counter.lines, 1_000_000);
+    assert_eq!(counter.bytes, expected_bytes);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_recovers_from_a_failed_copy_out() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    // the writer fails
+    let mut counter = CsvCounter {
+        fail_after: Some(1000),
+        ..CsvCounter::default()
+    };
+
+    let res = conn
+        .copy_out_csv(
+            "(SELECT generate_series(1, 1000000))",
+            &mut counter,
+            &PgCopyCsvOptions::new(),
+        )
+        .await;
+
+    assert!(matches!(res, Err(sqlx::Error::Io(_))), "{:?}", res);
+    assert!(counter.bytes >= 1000);
+
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    // the server fails
+    let mut counter = CsvCounter::default();
+
+    let res = conn
+        .copy_out_csv(
+            "(SELECT 1 / (n - 500000) FROM generate_series(1, 1000000) n)",
+            &mut counter,
+            &PgCopyCsvOptions::new(),
+        )
+        .await;
+
+    let err = res.unwrap_err().into_database_error().unwrap();
+    assert_eq!(err.code().as_deref(), Some("22012"));
+
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    // or never starts
+    let res = conn
+        .copy_out_csv("no_such_table", &mut counter, &PgCopyCsvOptions::new())
+        .await;
+
+    assert!(res.is_err());
+
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_applies_no_transaction_migrations() -> anyhow::Result<()> {
+    use sqlx::migrate::{Migrate, Migration, MigrationType};
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        "DROP TABLE IF EXISTS no_tx_migration_test; CREATE TABLE no_tx_migration_test (id INT)",
+    )
+    .await?;
+    conn.ensure_migrations_table().await?;
+
+    let migration = Migration::new(
+        -20261014,
+        "no transaction".into(),
+        MigrationType::Simple,
+        "-- no-transaction\nCREATE INDEX CONCURRENTLY no_tx_migration_test_id ON no_tx_migration_test (id);"
+            .into(),
+    );
+    assert!(migration.no_tx);
+
+    conn.apply(&migration).await?;
+
+    sqlx::query("DELETE FROM _sqlx_migrations WHERE version = $1")
+        .bind(migration.version)
+        .execute(&mut conn)
+        .await?;
+    conn.execute("DROP TABLE no_tx_migration_test").await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_runs_queued_rollbacks_before_other_queued_work() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    let lock = PgAdvisoryLock::new("queued-work-test");
+
+    let mut tx = conn.begin().await?;
+    let mut guard = lock.acquire(&mut *tx).await?;
+    assert!(guard.execute("SELECT 1 / 0").await.is_err());
+
+    // the unlock is queued first, but would be rejected by the failed transaction
+    drop(guard);
+    drop(tx);
+
+    let locks: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM pg_locks WHERE locktype = 'advisory' AND pid = pg_backend_pid()",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+    assert_eq!(locks, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_closes_evicted_statements_lazily() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut conn = options.statement_cache_capacity(1).connect().await?;
+
+    for n in 0..3_i32 {
+        let sql = format!("SELECT $1 + {}", n);
+        let _: i32 = sqlx::query_scalar(&sql)
+            .bind(1)
+            .fetch_one(&mut conn)
+            .await?;
+    }
+
+    // the statement evicted last is closed before this query runs
+    let prepared: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pg_prepared_statements")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(prepared, 2, "the cached statement and this one");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_streams_every_result_of_raw_sql() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let results: Vec<_> = sqlx::raw_sql(
+        "CREATE TEMPORARY TABLE raw_sql_test (id INT); \
+         INSERT INTO raw_sql_test VALUES (1), (2); \
+         SELECT id FROM raw_sql_test ORDER BY id; \
+         SELECT 'a' AS first, 'b' AS second",
+    )
+    .fetch_many(&mut conn)
+    .try_collect()
+    .await?;
+
+    let shape: Vec<_> = results
+        .iter()
+        .map(|result| {
+            result.as_ref().either(
+                |done| format!("done {}", done.rows_affected()),
+                |row| format!("row of {}", row.len()),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        shape,
+        ["done 0", "done 2", "row of 1", "row of 1", "done 2", "row of 2", "done 1"]
+    );
+
+    assert_eq!(conn.cached_statements_size(), 0);
+
+    // an error ends the stream after the results before it
+    let mut results = sqlx::raw_sql("SELECT 1; SELECT 1 / 0; SELECT 2").fetch_many(&mut conn);
+
+    assert!(results.try_next().await?.unwrap().is_right());
+    assert!(results.try_next().await?.unwrap().is_left());
+    assert!(results.try_next().await.is_err());
+    assert!(results.try_next().await?.is_none());
+
+    drop(results);
+    conn.ping().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_resolves_duplicate_column_names_to_the_first_column() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    for _ in 0..2 {
+        // the second run uses the cached statement
+        let row = sqlx::query("SELECT 1 AS id, 2 AS id, 3 AS other")
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(row.try_get::<i32, _>("id")?, 1);
+        assert_eq!(row.try_get::<i32, _>("other")?, 3);
+        assert!(row.try_get::<i32, _>("missing").is_err());
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_runs_queries_through_an_erased_executor() -> anyhow::Result<()> {
+    use sqlx::erased::{ErasedArguments, ErasedExecutor, ErasedValue};
+
+    // stands in for code that only knows about the trait object
+    async fn count_visible(
+        executor: &mut dyn ErasedExecutor,
+        name: &str,
+    ) -> anyhow::Result<Option<i64>> {
+        let row = executor
+            .fetch_optional_erased(
+                "SELECT count(*) FROM erased WHERE name = $1 AND visible",
+                vec![name].into_iter().collect(),
+            )
+            .await?;
+
+        Ok(row.and_then(|row| row.values()[0].as_i64()))
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute_erased(
+        "CREATE TEMPORARY TABLE erased (id INT4, name TEXT, visible BOOLEAN, score FLOAT8, data BYTEA)",
+        ErasedArguments::new(),
+    )
+    .await?;
+
+    let mut arguments = ErasedArguments::new();
+    arguments.add(1);
+    arguments.add("ferris");
+    arguments.add(true);
+    arguments.add(None::<f64>);
+    arguments.add(&b"\x00\x01"[..]);
+
+    let result = conn
+        .execute_erased(
+            "INSERT INTO erased VALUES ($1, $2, $3, $4, $5), ($1 + 1, $2, false, 0.5, NULL)",
+            arguments,
+        )
+        .await?;
+    assert_eq!(result.rows_affected(), 2);
+    assert_eq!(result.last_insert_id(), None);
+
+    let rows = conn
+        .fetch_all_erased("SELECT * FROM erased ORDER BY id", ErasedArguments::new())
+        .await?;
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+        rows[0].columns(),
+        ["id", "name", "visible", "score", "data"]
+    );
+    assert_eq!(
+        rows[0].values(),
+        [
+            ErasedValue::Int(1),
+            ErasedValue::Text("ferris".into()),
+            ErasedValue::Bool(true),
+            ErasedValue::Null,
+            ErasedValue::Bytes(vec![0, 1]),
+        ]
+    );
+    assert_eq!(rows[1].try_get("score")?.as_f64(), Some(0.5));
+    assert!(rows[1].try_get("data")?.is_null());
+
+    assert_eq!(count_visible(&mut conn, "ferris").await?, Some(1));
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    let mut executors: Vec<Box<dyn ErasedExecutor>> = vec![Box::new(conn), Box::new(pool)];
+
+    for executor in &mut executors {
+        let row = executor
+            .fetch_optional_erased("SELECT $1::int8 + 1", vec![Some(41)].into_iter().collect())
+            .await?
+            .unwrap();
+        assert_eq!(row.try_get(0)?, &ErasedValue::Int(42));
+
+        // other types are an error at runtime, and can be cast to text instead
+        let error = executor
+            .fetch_all_erased("SELECT 1.5::numeric AS n", ErasedArguments::new())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("NUMERIC"), "{}", error);
+
+        let row = executor
+            .fetch_optional_erased("SELECT 1.5::numeric::text AS n", ErasedArguments::new())
+            .await?
+            .unwrap();
+        assert_eq!(row.try_get("n")?.as_str(), Some("1.5"));
+
+        // an untyped NULL is inferred by Postgres
+        let row = executor
+            .fetch_optional_erased(
+                "SELECT coalesce($1, 3) AS n",
+                vec![ErasedValue::Null].into_iter().collect(),
+            )
+            .await?
+            .unwrap();
+        assert_eq!(row.try_get("n")?, &ErasedValue::Int(3));
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sets_configuration_for_the_transaction() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    async fn setting(conn: &mut PgConnection, name: &str) -> anyhow::Result<String> {
+        Ok(sqlx::query_scalar("SELECT current_setting($1, true)")
+            .bind(name)
+            .fetch_one(conn)
+            .await?)
+    }
+
+    let mut tx = conn.begin().await?;
+    tx.set_local("statement_timeout", "5s").await?;
+    tx.set_locals(vec![
+        ("app.current_tenant", "it's 42"),
+        ("search_path", "public, pg_catalog"),
+    ])
+    .await?;
+
+    assert_eq!(setting(&mut tx, "statement_timeout").await?, "5s");
+    assert_eq!(setting(&mut tx, "app.current_tenant").await?, "it's 42");
+    assert_eq!(setting(&mut tx, "search_path").await?, "public, pg_catalog");
+
+    // rolling back a savepoint undoes the settings made within it
+    let mut savepoint = tx.begin().await?;
+    savepoint.set_local("app.current_tenant", "7").await?;
+    assert_eq!(setting(&mut savepoint, "app.current_tenant").await?, "7");
+    savepoint.rollback().await?;
+    assert_eq!(setting(&mut tx, "app.current_tenant").await?, "it's 42");
+
+    let error = tx
+        .set_local("statement_timeout; DROP TABLE x", "0")
+        .await
+        .unwrap_err();
+    assert!(matches!(error, sqlx::Error::Encode(_)), "{}", error);
+
+    tx.commit().await?;
+
+    assert_eq!(setting(&mut conn, "statement_timeout").await?, "0");
+    assert_eq!(setting(&mut conn, "app.current_tenant").await?, "");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_commits_prepared_transactions_from_another_connection() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TABLE IF NOT EXISTS two_phase (id INT)")
+        .await?;
+    conn.execute("DELETE FROM two_phase").await?;
+
+    let mut tx = conn.begin().await?;
+    tx.execute("INSERT INTO two_phase VALUES (1)").await?;
+    let committed = tx.prepare_two_phase("sqlx's test: commit").await?;
+
+    let mut tx = conn.begin().await?;
+    tx.execute("INSERT INTO two_phase VALUES (2)").await?;
+    let rolled_back = tx.prepare_two_phase("sqlx's test: rollback").await?;
+
+    // the prepared transactions are no longer part of the session, so closing it keeps them
+    conn.close().await?;
+
+    let mut conn = new::<Postgres>().await?;
+    let prepared = conn.list_prepared().await?;
+    assert!(prepared.contains(&committed), "{:?}", prepared);
+    assert!(prepared.contains(&rolled_back), "{:?}", prepared);
+
+    conn.commit_prepared(committed.gid()).await?;
+    conn.rollback_prepared(rolled_back.gid()).await?;
+
+    let ids: Vec<i32> = sqlx::query_scalar("SELECT id FROM two_phase")
+        .fetch_all(&mut conn)
+        .await?;
+    assert_eq!(ids, [1]);
+
+    let prepared = conn.list_prepared().await?;
+    assert!(!prepared.contains(&committed), "{:?}", prepared);
+    assert!(!prepared.contains(&rolled_back), "{:?}", prepared);
+
+    let mut tx = conn.begin().await?;
+    let savepoint = tx.begin().await?;
+    assert!(savepoint.prepare_two_phase("savepoint").await.is_err());
+    drop(tx);
+
+    let error = conn.commit_prepared("a\\b").await.unwrap_err();
+    assert!(matches!(error, sqlx::Error::Encode(_)), "{}", error);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_decodes_domains_as_their_base_type() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    let mut tx = conn.begin().await?;
+
+    tx.execute(
+        r#"
+CREATE DOMAIN positive_int AS int4 CHECK (VALUE > 0);
+CREATE DOMAIN small_positive_int AS positive_int CHECK (VALUE < 100);
+CREATE DOMAIN booking AS int4range CHECK (NOT isempty(VALUE));
+        "#,
+    )
+    .await?;
+
+    let row = sqlx::query("SELECT 5::positive_int, 7::small_positive_int, '[1,5)'::booking")
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(row.column(0).type_info().name(), "INT4");
+    assert_eq!(row.try_get::<i32, _>(0)?, 5);
+    assert_eq!(row.try_get::<i64, _>(1)?, 7);
+    assert_eq!(row.try_get::<PgRange<i32>, _>(2)?, PgRange::from(1..5));
+
+    let value: i32 = sqlx::query_scalar("SELECT $1::positive_int")
+        .bind(42_i32)
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(value, 42);
+
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_hints_at_the_statement_cache_mode_when_statements_disappear() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let value: i32 = sqlx::query_scalar("SELECT $1::int4 + 1")
+        .bind(1_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 2);
+
+    // what a pooler does by moving the connection to another server session
+    conn.execute("DEALLOCATE ALL").await?;
+
+    let error = sqlx::query_scalar::<_, i32>("SELECT $1::int4 + 1")
+        .bind(1_i32)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    let error = error.into_database_error().unwrap();
+    let error = error.downcast_ref::<PgDatabaseError>();
+
+    assert_eq!(error.code(), "26000");
+    assert!(error.hint().unwrap().contains("statement_cache_mode"));
+    assert!(error
+        .to_string()
+        .contains("PgStatementCacheMode::Transaction"));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_runs_without_named_statements() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    for mode in &[
+        PgStatementCacheMode::Transaction,
+        PgStatementCacheMode::DescribeOnly,
+    ] {
+        let mut conn = env::var("DATABASE_URL")?
+            .parse::<PgConnectOptions>()?
+            .statement_cache_mode(*mode)
+            .connect()
+            .await?;
+
+        for _ in 0..2 {
+            let (value, text): (i32, String) = sqlx::query_as("SELECT $1::int4 + 1, $2::text")
+                .bind(1_i32)
+                .bind("hello")
+                .fetch_one(&mut conn)
+                .await?;
+
+            assert_eq!((value, &*text), (2, "hello"));
+
+            // nothing the connection relies on is lost
+            conn.execute("DEALLOCATE ALL").await?;
+        }
+
+        let statements: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pg_prepared_statements")
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(statements, 0);
+
+        let cached = if *mode == PgStatementCacheMode::DescribeOnly {
+            2
+        } else {
+            0
+        };
+
+        assert_eq!(conn.cached_statements_size(), cached, "{:?}", mode);
+
+        // statements prepared up front work as well
+        let statement = conn.prepare("SELECT $1::int8 * 2").await?;
+        let value: i64 = statement
+            .query_scalar()
+            .bind(21_i64)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(value, 42);
+
+        conn.close().await?;
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_connects_to_an_address_without_resolving_the_host() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let url = env::var("DATABASE_URL")?;
+    let port = url::Url::parse(&url)?.port().unwrap_or(5432);
+
+    // the name cannot be resolved, so the connection has to use the address
+    let options = url.parse::<PgConnectOptions>()?.host("db.invalid");
+
+    // nothing listens on port 1; the next address is tried
+    let mut conn = options
+        .clone()
+        .addresses(vec![
+            ([127, 0, 0, 1], 1).into(),
+            ([127, 0, 0, 1], port).into(),
+        ])
+        .connect()
+        .await?;
+
+    conn.ping().await?;
+    conn.close().await?;
+
+    let error = options
+        .address(([127, 0, 0, 1], 1).into())
+        .connect()
+        .await
+        .unwrap_err();
+
+    let message = error.to_string();
+    assert!(message.contains("db.invalid"), "{}", message);
+    assert!(message.contains("127.0.0.1:1"), "{}", message);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rejects_messages_over_the_maximum_size() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let options = env::var("DATABASE_URL")?
+        .parse::<PgConnectOptions>()?
+        .max_message_size(4096);
+
+    let mut conn = options.connect().await?;
+
+    let value: String = sqlx::query_scalar("SELECT repeat('x', 2048)")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value.len(), 2048);
+
+    let error = sqlx::query_scalar::<_, String>("SELECT repeat('x', 8192)")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, sqlx::Error::Protocol(_)), "{:?}", error);
+    assert!(error.to_string().contains("max_message_size"), "{}", error);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_opens_min_connections_eagerly_or_on_warm_up() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let pool_options = || PgPoolOptions::new().min_connections(3);
+
+    let pool = pool_options().connect_with(options.clone()).await?;
+    assert_eq!(pool.size(), 3);
+
+    let pool = pool_options().connect_lazy_with(options.clone());
+    assert_eq!(pool.size(), 0);
+
+    let warm_up = pool.warm_up().await;
+    assert_eq!(warm_up.created, 3);
+    assert!(warm_up.errors.is_empty(), "{:?}", warm_up.errors);
+    assert_eq!(pool.size(), 3);
+    assert_eq!(pool.num_idle(), 3);
+
+    assert_eq!(pool.warm_up().await.created, 0);
+
+    // nothing listens on port 1
+    let unreachable = options.host("127.0.0.1").port(1);
+
+    let error = pool_options()
+        .connect_with(unreachable.clone())
+        .await
+        .unwrap_err();
+
+    // each of the connections fails
+    match &error {
+        sqlx::Error::Aggregate(errors) => {
+            assert_eq!(errors.attempted(), 3);
+            assert_eq!(errors.errors().len(), 3);
+
+            for (index, (label, error)) in errors.errors().iter().enumerate() {
+                assert_eq!(label, &format!("connection {}", index + 1));
+                assert!(matches!(error, sqlx::Error::Io(_)), "{:?}", error);
+            }
+        }
+
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    let pool = pool_options().connect_lazy_with(unreachable);
+    let warm_up = pool.warm_up().await;
+
+    assert_eq!(warm_up.created, 0);
+    assert_eq!(warm_up.errors.len(), 3);
+    assert_eq!(pool.size(), 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_comments_statements_with_query_middleware() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let commenter = SqlCommenter::new()
+        .tag("application", "sqlx test")
+        .tags_with(|context| vec![("prepared".to_owned(), context.prepared().to_string())]);
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .query_middleware(Arc::new(commenter))
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    // the server sees the statement as it was rewritten
+    let current: String =
+        sqlx::query_scalar("SELECT query FROM pg_stat_activity WHERE pid = pg_backend_pid()")
+            .fetch_one(&pool)
+            .await?;
+
+    assert_eq!(
+        current,
+        "SELECT query FROM pg_stat_activity WHERE pid = pg_backend_pid() \
+         /*application='sqlx%20test',prepared='true'*/"
+    );
+
+    // bind parameters are left alone, and the rewritten statement is cached
+    let mut conn = pool.acquire().await?;
+    let cached = conn.cached_statements_size();
+
+    for _ in 0..2 {
+        let sum: i32 = sqlx::query_scalar("SELECT $1::int4 + 1")
+            .bind(41_i32)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(sum, 42);
+    }
+
+    assert_eq!(conn.cached_statements_size(), cached + 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rejects_statements_with_query_middleware() -> anyhow::Result<()> {
+    struct NoDrop;
+
+    impl QueryMiddleware for NoDrop {
+        fn before_execute<'q>(
+            &self,
+            sql: &'q str,
+            _: &QueryContext,
+        ) -> Result<Cow<'q, str>, sqlx::error::BoxDynError> {
+            if sql.trim_start().to_ascii_uppercase().starts_with("DROP") {
+                return Err("DROP is not allowed".into());
+            }
+
+            Ok(Cow::Borrowed(sql))
+        }
+    }
+
+    let mut options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    options.query_middleware(Arc::new(NoDrop));
+
+    let mut conn = options.connect().await?;
+
+    let error = conn
+        .execute("DROP TABLE IF EXISTS it_rejects_statements")
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(error, sqlx::Error::QueryRejected(_)),
+        "{:?}",
+        error
+    );
+    assert!(
+        error.to_string().contains("DROP is not allowed"),
+        "{}",
+        error
+    );
+
+    // nothing was sent, so the connection is still usable
+    let one: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(one, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_caps_the_notifications_buffered_by_a_listener() -> anyhow::Result<()> {
+    use sqlx::postgres::PgListener;
+
+    setup_if_needed();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    let mut listener = PgListener::connect_with(&pool).await?;
+    listener.listen("buffer_chan").await?;
+    listener.set_max_buffered_notifications(Some(10));
+
+    // the notifications arrive while the listener runs the query, so they are buffered
+    (&mut listener)
+        .execute("SELECT pg_notify('buffer_chan', i::text) FROM generate_series(1, 100) i")
+        .await?;
+
+    assert_eq!(listener.buffered_notifications(), 10);
+    assert_eq!(listener.dropped_notifications(), 90);
+
+    let mut payloads = Vec::new();
+
+    while let Some(notification) = listener.try_recv().await? {
+        payloads.push(notification.payload().parse::<i32>()?);
+
+        if listener.buffered_notifications() == 0 {
+            break;
+        }
+    }
+
+    assert_eq!(payloads, (91..=100).collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fans_out_notifications_to_typed_subscriptions() -> anyhow::Result<()> {
+    use futures::future::{select, Either};
+    use futures::StreamExt;
+    use sqlx::postgres::{PgListener, PgSubscription, PgSubscriptionEvent};
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Order {
+        id: i32,
+    }
+
+    // drives the listener until the subscription has an event
+    async fn next(
+        listener: &mut PgListener,
+        subscription: &mut PgSubscription<Order>,
+    ) -> Result<PgSubscriptionEvent<Order>, sqlx::Error> {
+        let recv = listener.recv();
+        let next = subscription.next();
+
+        futures::pin_mut!(recv, next);
+
+        match select(recv, next).await {
+            Either::Left((notification, _)) => panic!("unexpected {:?}", notification),
+            Either::Right((event, _)) => event.expect("subscription ended"),
+        }
+    }
+
+    async fn listening(listener: &mut PgListener) -> anyhow::Result<Vec<String>> {
+        Ok(
+            sqlx::query_scalar("SELECT pg_listening_channels() ORDER BY 1")
+                .fetch_all(listener)
+                .await?,
+        )
+    }
+
+    let mut conn = new::<Postgres>().await?;
+    let mut listener = PgListener::connect(&env::var("DATABASE_URL")?).await?;
+
+    let mut first = listener.subscribe::<Order>("orders_chan").await?;
+    let mut second = listener.subscribe::<Order>("orders_chan").await?;
+
+    conn.execute(
+        r#"NOTIFY orders_chan, '{"id": 1}'; NOTIFY orders_chan, 'not json'; NOTIFY orders_chan, '{"id": 2}'"#,
+    )
+    .await?;
+
+    // each subscription gets every notification, and a bad payload is only an error in-stream
+    for subscription in [&mut first, &mut second].iter_mut() {
+        assert_eq!(
+            next(&mut listener, subscription).await?,
+            PgSubscriptionEvent::Notification(Order { id: 1 })
+        );
+
+        assert!(matches!(
+            next(&mut listener, subscription).await,
+            Err(sqlx::Error::Decode(_))
+        ));
+
+        assert_eq!(
+            next(&mut listener, subscription).await?,
+            PgSubscriptionEvent::Notification(Order { id: 2 })
+        );
+    }
+
+    // a lost connection is reconnected, listening again, and the subscriptions are told
+    let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&mut listener)
+        .await?;
+
+    conn.execute(&*format!("SELECT pg_terminate_backend({})", pid))
+        .await?;
+
+    assert_eq!(
+        next(&mut listener, &mut first).await?,
+        PgSubscriptionEvent::Reconnected
+    );
+
+    conn.execute(r#"NOTIFY orders_chan, '{"id": 3}'"#).await?;
+
+    assert_eq!(
+        next(&mut listener, &mut first).await?,
+        PgSubscriptionEvent::Notification(Order { id: 3 })
+    );
+
+    assert_eq!(second.buffered_notifications(), 2);
+
+    // the listener stops listening once the last subscription to a channel is dropped, unless
+    // the channel was listened to
+    listener.listen("orders_listened").await?;
+    let third = listener.subscribe::<Order>("orders_listened").await?;
+
+    drop(first);
+    let _ = sqlx_rt::timeout(Duration::from_millis(100), listener.try_recv()).await;
+    assert_eq!(
+        listening(&mut listener).await?,
+        ["orders_chan", "orders_listened"]
+    );
+
+    drop(second);
+    drop(third);
+    let _ = sqlx_rt::timeout(Duration::from_millis(100), listener.try_recv()).await;
+    assert_eq!(listening(&mut listener).await?, ["orders_listened"]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_shares_one_listener_between_the_subscribers_of_a_pool() -> anyhow::Result<()> {
+    use futures::StreamExt;
+    use sqlx::events::{CloseReason, ConnectionInfo};
+    use sqlx::postgres::{PgSubscription, PgSubscriptionEvent};
+    use sqlx::ConnectionEventListener;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Events {
+        connected: Mutex<Vec<u64>>,
+        broken: Mutex<Vec<u64>>,
+    }
+
+    impl ConnectionEventListener for Events {
+        fn on_connect(&self, info: &ConnectionInfo) {
+            let pid = info.connection_id().unwrap();
+            self.connected.lock().unwrap().push(pid);
+        }
+
+        fn on_close(&self, info: &ConnectionInfo, reason: CloseReason) {
+            if reason == CloseReason::Broken {
+                let pid = info.connection_id().unwrap();
+                self.broken.lock().unwrap().push(pid);
+            }
+        }
+    }
+
+    async fn next(
+        subscription: &mut PgSubscription<i32>,
+    ) -> anyhow::Result<PgSubscriptionEvent<i32>> {
+        let event = sqlx_rt::timeout(Duration::from_secs(10), subscription.next()).await?;
+
+        Ok(event.expect("subscription ended")?)
+    }
+
+    setup_if_needed();
+
+    let events = Arc::new(Events::default());
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connection_events(events.clone())
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    // the only connection of the pool is held throughout, so the listener has one of its own
+    let mut notifier = pool.acquire().await?;
+    let notifier_pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&mut notifier)
+        .await?;
+
+    let listener = pool.listener();
+    let mut steady = listener.subscribe::<i32>("shared_steady").await?;
+
+    assert_eq!(pool.size(), 1);
+
+    // many subscribers churn their subscriptions to a few channels while notifications flow
+    let churned = AtomicBool::new(false);
+
+    let notify = async {
+        let mut sent = 0;
+
+        while !churned.load(Ordering::SeqCst) {
+            notifier
+                .execute(&*format!(
+                    "NOTIFY shared_steady, '{}'; NOTIFY shared_churn_0, '0'; \
+                     NOTIFY shared_churn_1, '1'; NOTIFY shared_churn_2, '2'",
+                    sent
+                ))
+                .await?;
+
+            sent += 1;
+            sqlx_rt::sleep(Duration::from_millis(1)).await;
+        }
+
+        Ok::<_, anyhow::Error>(sent)
+    };
+
+    let churn = async {
+        let subscribers = (0..200).map(|i| {
+            let listener = &listener;
+
+            async move {
+                for round in 0..5 {
+                    let channel = format!("shared_churn_{}", (i + round) % 3);
+                    let mut subscription = listener.subscribe::<i32>(&channel).await?;
+
+                    // a notification sent after subscribing is received
+                    assert!(matches!(
+                        next(&mut subscription).await?,
+                        PgSubscriptionEvent::Notification(_)
+                    ));
+                }
+
+                Ok::<_, anyhow::Error>(())
+            }
+        });
+
+        let result = futures::future::try_join_all(subscribers).await;
+        churned.store(true, Ordering::SeqCst);
+
+        result
+    };
+
+    let (sent, _) = futures::try_join!(notify, churn)?;
+
+    // the subscription that stayed missed none of them
+    for expected in 0..sent {
+        assert_eq!(
+            next(&mut steady).await?,
+            PgSubscriptionEvent::Notification(expected)
+        );
+    }
+
+    assert_eq!(listener.channels(), ["shared_steady"]);
+    assert_eq!(pool.size(), 1);
+
+    // the listening connection is reopened once lost, and its loss is reported
+    let listener_pid = *events.connected.lock().unwrap().last().unwrap();
+    assert_ne!(listener_pid, notifier_pid as u64);
+
+    notifier
+        .execute(&*format!("SELECT pg_terminate_backend({})", listener_pid))
+        .await?;
+
+    assert_eq!(next(&mut steady).await?, PgSubscriptionEvent::Reconnected);
+    assert_eq!(*events.broken.lock().unwrap(), [listener_pid]);
+    assert_eq!(events.connected.lock().unwrap().len(), 3);
+
+    notifier.execute("NOTIFY shared_steady, '-1'").await?;
+    assert_eq!(
+        next(&mut steady).await?,
+        PgSubscriptionEvent::Notification(-1)
+    );
+
+    // closing the pool ends the subscriptions
+    drop(notifier);
+    pool.close().await;
+
+    assert!(sqlx_rt::timeout(Duration::from_secs(10), steady.next())
+        .await?
+        .is_none());
+    assert!(matches!(
+        listener.subscribe::<i32>("shared_steady").await,
+        Err(sqlx::Error::PoolClosed)
+    ));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_caps_the_notices_handled_per_query() -> anyhow::Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    setup_if_needed();
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let handled_ = handled.clone();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let options = options
+        .notice_handler(Arc::new(move |_| {
+            handled_.fetch_add(1, Ordering::SeqCst);
+        }))
+        .max_notices_per_query(5);
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    conn.execute("DO $$ BEGIN FOR i IN 1..100 LOOP RAISE NOTICE 'n %', i; END LOOP; END $$")
+        .await?;
+
+    assert_eq!(handled.load(Ordering::SeqCst), 5);
+    assert_eq!(conn.stats().notices_suppressed, 95);
+
+    // the count starts over with the next query
+    conn.execute("DO $$ BEGIN RAISE NOTICE 'again'; END $$")
+        .await?;
+
+    assert_eq!(handled.load(Ordering::SeqCst), 6);
+    assert_eq!(conn.stats().notices_suppressed, 95);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_binds_values_of_types_known_at_runtime() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let values: Vec<sqlx::encode::DynValue> = vec![5_i32.into(), "five".into(), true.into()];
+
+    let mut query = sqlx::query("SELECT $1 + 1 = 6 AND $2 = 'five' AND $3");
+
+    for value in values {
+        query = query.bind_dyn(value);
+    }
+
+    let row = query.fetch_one(&mut conn).await?;
+    assert!(row.try_get::<bool, _>(0)?);
+
+    // a value is declared with the type it encodes to, even a `NULL`
+    let ty: String = sqlx::query_scalar("SELECT pg_typeof($1)::text")
+        .bind_dyn(Box::new(None::<f64>) as Box<dyn sqlx::encode::DynEncode<'_, Postgres>>)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(ty, "double precision");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_warns_about_session_state_left_on_pooled_connections() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .track_session_state(true)
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    // a transaction that is dropped is rolled back before the check, and the statements SQLx
+    // prepares itself are not counted
+    {
+        let mut conn = pool.acquire().await?;
+        let mut tx = conn.begin().await?;
+
+        sqlx::query("SELECT $1::int4")
+            .bind(1_i32)
+            .execute(&mut tx)
+            .await?;
+    }
+
+    let mut conn = pool.acquire().await?;
+    assert_eq!(pool.stats().session_state_leaks, 0);
+
+    let state = conn.session_state().await?;
+    assert_eq!(state.in_transaction, Some(false));
+    assert_eq!(state.prepared_statements, Some(0));
+    assert_eq!(state.temp_tables, Some(0));
+
+    let leaks: &[(&str, fn(&sqlx::SessionState) -> bool)] = &[
+        ("BEGIN", |state| state.in_transaction == Some(true)),
+        ("ROLLBACK; PREPARE leaked AS SELECT 1", |state| {
+            state.prepared_statements == Some(1)
+        }),
+        ("SET search_path TO pg_catalog", |state| {
+            state.search_path.as_deref() == Some("pg_catalog")
+        }),
+        ("CREATE TEMPORARY TABLE leaked (x INT)", |state| {
+            state.temp_tables == Some(1)
+        }),
+    ];
+
+    for (i, (sql, leaked)) in leaks.iter().enumerate() {
+        conn.execute(*sql).await?;
+        drop(conn);
+
+        // waits for the connection to be checked
+        conn = pool.acquire().await?;
+
+        assert_eq!(pool.stats().session_state_leaks, i as u64 + 1, "{}", sql);
+        assert!(leaked(&conn.session_state().await?), "{}", sql);
+    }
+
+    Ok(())
+}
+
+// an `INT4` that panics when decoded if it is greater than 3
+struct PanicsPast3(i32);
+
+impl sqlx::Type<Postgres> for PanicsPast3 {
+    fn type_info() -> PgTypeInfo {
+        <i32 as sqlx::Type<Postgres>>::type_info()
+    }
+}
+
+impl<'r> sqlx::Decode<'r, Postgres> for PanicsPast3 {
+    fn decode(value: sqlx::postgres::PgValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let value = <i32 as sqlx::Decode<Postgres>>::decode(value)?;
+
+        if value > 3 {
+            panic!("cannot decode {}", value);
+        }
+
+        Ok(PanicsPast3(value))
+    }
+}
+
+#[sqlx_macros::test]
+async fn it_recovers_from_a_panic_in_decode() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    let pid: i32 = pool.fetch_one("SELECT pg_backend_pid()").await?.get(0);
+
+    // the panic is in the middle of the rows of the response
+    let mut rows =
+        sqlx::query_as::<_, (PanicsPast3,)>("SELECT generate_series(1, 10)").fetch(&pool);
+    let mut decoded = Vec::new();
+
+    let error = loop {
+        match rows.try_next().await {
+            Ok(Some((value,))) => decoded.push(value.0),
+            Ok(None) => panic!("every row was decoded"),
+            Err(error) => break error,
+        }
+    };
+
+    drop(rows);
+
+    assert_eq!(decoded, [1, 2, 3]);
+    assert!(
+        matches!(&error, sqlx::Error::Panic(message) if message == "cannot decode 4"),
+        "{:?}",
+        error
+    );
+
+    // the same connection goes on with the next query
+    let row = pool.fetch_one("SELECT pg_backend_pid(), 1 + 1").await?;
+    assert_eq!(row.get::<i32, _>(0), pid);
+    assert_eq!(row.get::<i32, _>(1), 2);
+
+    let error = sqlx::query("SELECT 1")
+        .try_map(|_: PgRow| -> Result<i32, sqlx::Error> { panic!("in try_map") })
+        .fetch_one(&pool)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&error, sqlx::Error::Panic(message) if message == "in try_map"),
+        "{:?}",
+        error
+    );
+
+    let value: i32 = pool.fetch_one("SELECT 3").await?.get(0);
+    assert_eq!(value, 3);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_discards_connections_whose_pool_hook_panicked() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .after_release(|_| panic!("in after_release"))
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    let first: i32 = pool.fetch_one("SELECT pg_backend_pid()").await?.get(0);
+    let second: i32 = pool.fetch_one("SELECT pg_backend_pid()").await?.get(0);
+
+    // each connection the hook panicked on is closed, and replaced by the next acquire
+    assert_ne!(first, second);
+    assert_eq!(pool.size(), 0);
+
+    Ok(())
+}
+
+// `DATABASE_URL`, for the database and user given
+#[cfg(feature = "migrate")]
+fn url_for(database: &str, user: Option<(&str, &str)>) -> anyhow::Result<String> {
+    let mut url = url::Url::parse(&env::var("DATABASE_URL")?)?;
+    url.set_path(database);
+
+    if let Some((username, password)) = user {
+        let _ = url.set_username(username);
+        let _ = url.set_password(Some(password));
+    }
+
+    Ok(url.to_string())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_creates_and_drops_databases() -> anyhow::Result<()> {
+    use sqlx::migrate::MigrateDatabase;
+
+    sqlx_test::setup_if_needed();
+
+    // a name that must be quoted
+    let url = url_for(&format!("sqlx-Admin-{}", std::process::id()), None)?;
+
+    Postgres::force_drop_database(&url).await?;
+    assert!(!Postgres::database_exists(&url).await?);
+
+    Postgres::create_database(&url).await?;
+    assert!(Postgres::database_exists(&url).await?);
+
+    let error = Postgres::create_database(&url).await.unwrap_err();
+    assert!(
+        matches!(&error, sqlx::Error::DatabaseExists(name) if name.starts_with("sqlx-Admin-")),
+        "{:?}",
+        error
+    );
+
+    // a session connected to the database stops `drop_database`, but not `force_drop_database`
+    let mut conn = PgConnection::connect(&url).await?;
+    conn.execute("CREATE TABLE t (x INT)").await?;
+
+    assert!(Postgres::drop_database(&url).await.is_err());
+    assert!(Postgres::database_exists(&url).await?);
+
+    Postgres::force_drop_database(&url).await?;
+    assert!(!Postgres::database_exists(&url).await?);
+    assert!(conn.execute("SELECT 1").await.is_err());
+
+    // dropping a database that does not exist is not an error
+    Postgres::drop_database(&url).await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_tells_a_denied_create_database_apart() -> anyhow::Result<()> {
+    use sqlx::migrate::MigrateDatabase;
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        "DROP ROLE IF EXISTS sqlx_no_createdb; \
+         CREATE ROLE sqlx_no_createdb LOGIN NOCREATEDB PASSWORD 'password'",
+    )
+    .await?;
+
+    let url = url_for(
+        &format!("sqlx_denied_{}", std::process::id()),
+        Some(("sqlx_no_createdb", "password")),
+    )?;
+
+    let error = Postgres::create_database(&url).await.unwrap_err();
+    assert!(
+        matches!(error, sqlx::Error::DatabasePermissionDenied(_)),
+        "{:?}",
+        error
+    );
+
+    conn.execute("DROP ROLE sqlx_no_createdb").await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "serde-row")]
+#[sqlx_macros::test]
+async fn it_reads_rows_into_serde_types() -> anyhow::Result<()> {
+    use serde::Deserialize;
+    use sqlx::serde_row::from_row;
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Account {
+        id: i64,
+        // decoded as the wider `INT4`, and checked to be in range
+        port: u16,
+        balance: f64,
+        active: bool,
+        #[serde(with = "serde_bytes_compat")]
+        key: Vec<u8>,
+        note: Option<String>,
+    }
+
+    // `Vec<u8>` is a sequence to serde; bytes need asking for
+    mod serde_bytes_compat {
+        pub fn deserialize<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+            struct Bytes;
+
+            impl serde::de::Visitor<'_> for Bytes {
+                type Value = Vec<u8>;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str("bytes")
+                }
+
+                fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+                    Ok(v)
+                }
+            }
+
+            d.deserialize_byte_buf(Bytes)
+        }
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    let row = sqlx::query(
+        "SELECT 1::int8 AS id, 5432 AS port, 2.5::float8 AS balance, true AS active, \
+         '\\x0102'::bytea AS key, NULL::text AS note",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(
+        from_row::<Account, _>(&row)?,
+        Account {
+            id: 1,
+            port: 5432,
+            balance: 2.5,
+            active: true,
+            key: vec![1, 2],
+            note: None,
+        }
+    );
+
+    // whatever the column holds
+    let row = sqlx::query("SELECT 5432 AS port, true AS active, NULL::text AS note")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let values: HashMap<String, serde_json::Value> = from_row(&row)?;
+    assert_eq!(values["port"], 5432);
+    assert_eq!(values["active"], true);
+    assert_eq!(values["note"], serde_json::Value::Null);
+
+    // the driver's own rules: text is no integer
+    let row = sqlx::query("SELECT 1::int8 AS id, '5432' AS port")
+        .fetch_one(&mut conn)
+        .await?;
+
+    match from_row::<(i64, u16), _>(&row) {
+        Err(sqlx::Error::ColumnDecode { index, .. }) => assert_eq!(index, "\"port\""),
+        other => panic!("expected a mismatched port, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_writes_bind_values_into_the_sql_with_the_simple_protocol() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let mut conn = env::var("DATABASE_URL")?
+        .parse::<PgConnectOptions>()?
+        .prefer_simple_protocol(true)
+        .connect()
+        .await?;
+
+    // the placeholder in the string is not one
+    let row: (i32, String, Option<i64>, Vec<u8>, Vec<f64>, bool, String) =
+        sqlx::query_as("SELECT $1 + 1, $2 || '$1', $3, $4, $5, $6, '$2'")
+            .bind(1_i32)
+            .bind("it's'); DROP TABLE tweet; --")
+            .bind(None::<i64>)
+            .bind(&[0_u8, 0xff][..])
+            .bind(vec![1.5_f64, -0.25])
+            .bind(true)
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(
+        row,
+        (
+            2,
+            "it's'); DROP TABLE tweet; --$1".to_owned(),
+            None,
+            vec![0, 0xff],
+            vec![1.5, -0.25],
+            true,
+            "$2".to_owned()
+        )
+    );
+
+    // without standard conforming strings, backslashes must still arrive as written
+    conn.execute("SET standard_conforming_strings = off")
+        .await?;
+
+    let text: String = sqlx::query_scalar("SELECT $1")
+        .bind("C:\\' OR 1=1 --")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(text, "C:\\' OR 1=1 --");
+
+    conn.execute("RESET standard_conforming_strings").await?;
+
+    // statements are parsed, but never kept
+    let statement = conn.prepare("SELECT $1::int8 * 2").await?;
+    let value: i64 = statement
+        .query_scalar()
+        .bind(21_i64)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 42);
+
+    let described = conn.describe("SELECT 1::int4 AS one").await?;
+    assert_eq!(described.columns()[0].name(), "one");
+
+    let statements: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pg_prepared_statements")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(statements, 0);
+    assert_eq!(conn.cached_statements_size(), 0);
+
+    // a type that cannot be written fails before anything is sent
+    let result = sqlx::query("SELECT $1")
+        .bind_with_type("happy", PgTypeInfo::with_name("mood"))
+        .execute(&mut conn)
+        .await;
+
+    assert!(matches!(result, Err(sqlx::Error::ArgumentEncode { .. })));
+    conn.ping().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_decodes_the_same_values_in_either_result_format() -> anyhow::Result<()> {
+    use sqlx::postgres::types::{PgBits, PgLtree, PgMoney, PgTsVector, PgXml};
+    use sqlx::postgres::PgResultFormat;
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE EXTENSION IF NOT EXISTS ltree").await?;
+
+    const SQL: &str = "SELECT true, 2::int2, -3::int4, 4000000000::int8, 1.5::float4, \
+        -2.25::float8, 'text'::text, '\\x00ff'::bytea, 24::oid, \
+        '1 day 02:00:03.5'::interval, '[1,5)'::int4range, '{1,NULL,3}'::int4[], \
+        '{\"a b\",c}'::text[], 12.34::money, 'top.science'::ltree, \
+        'a:1 b:2'::tsvector, '<a>b</a>'::xml, B'101'::bit(3), \
+        '2023-03-01'::date, '13:14:15.25'::time, '2023-03-01 13:14:15.25'::timestamp, \
+        '2023-03-01 13:14:15.25+02'::timestamptz, '13:14:15.25+02'::timetz, \
+        123.456::numeric, 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::uuid, \
+        '{\"a\": [1, 2]}'::jsonb, '{\"b\": null}'::json, '192.168.0.1/24'::inet, \
+        B'1001'::varbit, '13:14:15'::time, '13:14:15-05:30'::timetz, ROW(1, 'a \"b\"')";
+
+    let fetch = |format: PgResultFormat| sqlx::query(SQL).result_format(format);
+
+    let text = fetch(PgResultFormat::Text).fetch_one(&mut conn).await?;
+    let binary = fetch(PgResultFormat::Binary).fetch_one(&mut conn).await?;
+
+    // every other column in the text format
+    let formats = (0..text.len())
+        .map(|i| match i % 2 {
+            0 => PgValueFormat::Text,
+            _ => PgValueFormat::Binary,
+        })
+        .collect();
+
+    let mixed = fetch(PgResultFormat::PerColumn(formats))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(text.try_get_raw(0)?.format(), PgValueFormat::Text);
+    assert_eq!(binary.try_get_raw(0)?.format(), PgValueFormat::Binary);
+    assert_eq!(mixed.try_get_raw(0)?.format(), PgValueFormat::Text);
+    assert_eq!(mixed.try_get_raw(1)?.format(), PgValueFormat::Binary);
+
+    macro_rules! same {
+        ($($index:literal: $ty:ty),* $(,)?) => {
+            $(
+                let expected: $ty = binary.try_get($index)?;
+
+                assert_eq!(text.try_get::<$ty, _>($index)?, expected, "column {}", $index);
+                assert_eq!(mixed.try_get::<$ty, _>($index)?, expected, "column {}", $index);
+            )*
+        };
+    }
+
+    same!(
+        0: bool,
+        1: i16,
+        2: i32,
+        3: i64,
+        4: f32,
+        5: f64,
+        6: String,
+        7: Vec<u8>,
+        8: u32,
+        9: PgInterval,
+        10: PgRange<i32>,
+        11: Vec<Option<i32>>,
+        12: Vec<String>,
+        13: PgMoney,
+        14: PgLtree,
+        15: PgTsVector,
+        16: PgXml,
+        17: PgBits,
+        31: (i32, String),
+    );
+
+    #[cfg(feature = "chrono")]
+    {
+        use sqlx::postgres::types::PgTimeTz;
+        use sqlx::types::chrono::{
+            DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc,
+        };
+
+        same!(
+            18: NaiveDate,
+            19: NaiveTime,
+            20: NaiveDateTime,
+            21: DateTime<Utc>,
+            22: PgTimeTz<NaiveTime, FixedOffset>,
+            29: NaiveTime,
+            30: PgTimeTz<NaiveTime, FixedOffset>,
+        );
+    }
+
+    #[cfg(feature = "time")]
+    {
+        use sqlx::postgres::types::PgTimeTz;
+        use sqlx::types::time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+        same!(
+            18: Date,
+            19: Time,
+            20: PrimitiveDateTime,
+            21: OffsetDateTime,
+            22: PgTimeTz<Time, UtcOffset>,
+            29: Time,
+            30: PgTimeTz<Time, UtcOffset>,
+        );
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    same!(23: sqlx::types::BigDecimal);
+
+    #[cfg(feature = "decimal")]
+    same!(23: sqlx::types::Decimal);
+
+    #[cfg(feature = "uuid")]
+    same!(24: sqlx::types::Uuid);
+
+    #[cfg(feature = "json")]
+    same!(25: serde_json::Value, 26: serde_json::Value);
+
+    #[cfg(feature = "ipnetwork")]
+    same!(27: sqlx::types::ipnetwork::IpNetwork);
+
+    #[cfg(feature = "bit-vec")]
+    same!(28: sqlx::types::BitVec);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_refuses_values_larger_than_the_limit() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let err = sqlx::query("SELECT 1::int4, repeat('x', 10000000), 'tail'")
+        .max_value_size(1 << 20)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            sqlx::Error::ValueTooLarge {
+                column: Some(1),
+                size: 10_000_000,
+                limit: 1_048_576,
+            }
+        ),
+        "{:?}",
+        err
+    );
+
+    // the row was skipped over rather than read
+    assert!(
+        conn.buffer_sizes().read < 1 << 20,
+        "{:?}",
+        conn.buffer_sizes()
+    );
+
+    // and so are the rows after it, before the next query
+    let err =
+        sqlx::query_scalar::<_, String>("SELECT repeat('x', 10000000) FROM generate_series(1, 3)")
+            .max_row_size(1000)
+            .fetch_all(&mut conn)
+            .await
+            .unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            sqlx::Error::ValueTooLarge {
+                column: None,
+                size: 10_000_000,
+                limit: 1000,
+            }
+        ),
+        "{:?}",
+        err
+    );
+
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+    assert!(
+        conn.buffer_sizes().read < 1 << 20,
+        "{:?}",
+        conn.buffer_sizes()
+    );
+
+    // rows within the limits are read as usual, whatever the protocol
+    let values: Vec<(i32, Option<String>)> =
+        sqlx::query_as("SELECT $1, NULL::text UNION ALL SELECT 2, repeat('x', 1000)")
+            .bind(1_i32)
+            .max_value_size(1000)
+            .max_row_size(1004)
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[1].1.as_deref().map(str::len), Some(1000));
+
+    let value: String = sqlx::query_scalar("SELECT repeat('x', 1000)")
+        .max_value_size(1000)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value.len(), 1000);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_binds_lists_to_in() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let ids: Vec<i32> =
+        sqlx::query_scalar("SELECT id FROM generate_series(1, 1000) id WHERE id IN {in}")
+            .bind_expanded(vec![5])
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(ids, [5]);
+
+    // the list takes the number of the next parameter
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM generate_series(1, 1000) id WHERE id > $1 AND id IN {in} AND id < $3",
+    )
+    .bind(10)
+    .bind_expanded((1..=1000).collect::<Vec<i32>>())
+    .bind(1000)
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(count, 989);
+
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM generate_series(1, 1000) id WHERE id NOT IN {in}")
+            .bind_expanded(vec![1, 2, 3])
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(count, 997);
+
+    // an empty list is refused unless it is allowed, when it matches nothing
+    let err = sqlx::query("SELECT id FROM generate_series(1, 1000) id WHERE id IN {in}")
+        .bind_expanded(Vec::<i32>::new())
+        .fetch_all(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, sqlx::Error::ArgumentEncode { index: 0, .. }),
+        "{:?}",
+        err
+    );
+
+    for &(sql, expected) in [
+        (
+            "SELECT COUNT(*) FROM generate_series(1, 1000) id WHERE id IN {in}",
+            0,
+        ),
+        (
+            "SELECT COUNT(*) FROM generate_series(1, 1000) id WHERE id NOT IN {in}",
+            1000,
+        ),
+    ]
+    .iter()
+    {
+        let count: i64 = sqlx::query_scalar(sql)
+            .bind_expanded_allow_empty(Vec::<i32>::new())
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(count, expected, "{}", sql);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_caches_one_statement_for_lists_of_any_length() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    conn.clear_cached_statements().await?;
+
+    for len in [1, 3, 4, 5, 1000].iter() {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM generate_series(1, 1000) id WHERE id IN {in}")
+                .bind_expanded((1..=*len).collect::<Vec<i32>>())
+                .fetch_one(&mut conn)
+                .await?;
+
+        assert_eq!(count, i64::from(*len));
+    }
+
+    assert_eq!(conn.cached_statements_size(), 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_detects_the_server_flavor() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let version: String = sqlx::query_scalar("SELECT version()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let flavor = conn.server_flavor();
+
+    assert_eq!(
+        flavor == PgFlavor::CockroachDb,
+        version.contains("CockroachDB"),
+        "{}",
+        version
+    );
+
+    // CockroachDB has no `LISTEN`
+    assert_eq!(
+        conn.capabilities().contains(Capabilities::LISTEN_NOTIFY),
+        flavor != PgFlavor::CockroachDb
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_analyzes_a_query_with_its_binds() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let plan = sqlx::query("SELECT * FROM generate_series(1, $1) n WHERE n % 2 = 0")
+        .bind(10_i32)
+        .analyze(&mut conn, PgAnalyzeOptions::new())
+        .await?;
+
+    assert_eq!(plan.root.node_type, "Function Scan");
+    assert_eq!(plan.root.actual_rows, 5.0);
+    assert_eq!(plan.root.actual_loops, 1);
+    assert!(plan.root.actual_total_time.is_some());
+    assert!(plan.execution_time.is_some());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rolls_back_an_analyzed_write_unless_allowed() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE analyzed (id INT)")
+        .await?;
+
+    let plan = sqlx::query("INSERT INTO analyzed (id) VALUES ($1)")
+        .bind(1_i32)
+        .analyze(&mut conn, PgAnalyzeOptions::new())
+        .await?;
+
+    assert_eq!(plan.root.node_type, "ModifyTable");
+    assert_eq!(plan.root.relation_name.as_deref(), Some("analyzed"));
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM analyzed")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 0);
+
+    sqlx::query("INSERT INTO analyzed (id) VALUES ($1)")
+        .bind(1_i32)
+        .analyze(&mut conn, PgAnalyzeOptions::new().allow_mutation(true))
+        .await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM analyzed")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_resolves_types_of_the_same_name_in_different_schemas() -> anyhow::Result<()> {
+    use sqlx::postgres::PgTypeKind;
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+DROP SCHEMA IF EXISTS resolve_a CASCADE;
+DROP SCHEMA IF EXISTS resolve_b CASCADE;
+CREATE SCHEMA resolve_a;
+CREATE SCHEMA resolve_b;
+CREATE TYPE resolve_a.shade AS ENUM ('light');
+CREATE TYPE resolve_b.shade AS ENUM ('dark');
+"#,
+    )
+    .await?;
+
+    let variants = |info: PgTypeInfo| match info.kind() {
+        PgTypeKind::Enum(variants) => variants.to_vec(),
+        kind => panic!("not an enum: {:?}", kind),
+    };
+
+    assert_eq!(
+        variants(conn.resolve_type("resolve_a.shade").await?),
+        ["light"]
+    );
+    assert_eq!(
+        variants(conn.resolve_type("resolve_b.shade").await?),
+        ["dark"]
+    );
+
+    // an unqualified name is looked up along the search path
+    conn.execute("SET search_path TO resolve_b, public").await?;
+    assert_eq!(variants(conn.resolve_type("shade").await?), ["dark"]);
+
+    assert!(matches!(
+        conn.resolve_type("resolve_a.no_such_type").await,
+        Err(sqlx::Error::TypeNotFound { .. })
+    ));
+
+    let shades = conn
+        .cached_types()
+        .filter(|info| info.name() == "shade")
+        .count();
+
+    assert_eq!(shades, 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_warms_the_types_of_new_pool_connections() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+DROP SCHEMA IF EXISTS warmed CASCADE;
+CREATE SCHEMA warmed;
+CREATE TYPE warmed.flavor AS ENUM ('sweet', 'sour');
+"#,
+    )
+    .await?;
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .warm_types(&["warmed.flavor", "int4"])
+        .connect(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    let pooled = pool.acquire().await?;
+    assert!(pooled.cached_types().any(|info| info.name() == "flavor"));
+
+    let pool = PgPoolOptions::new()
+        .connect_timeout(Duration::from_secs(5))
+        .warm_types(&["warmed.no_such_type"])
+        .connect_lazy(&dotenv::var("DATABASE_URL")?)?;
+
+    assert!(pool.acquire().await.is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_merges_two_cursors_fetched_in_turn() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let err = conn.cursor("SELECT 1").await.unwrap_err();
+    assert!(matches!(err, sqlx::Error::Configuration(_)), "{:?}", err);
+
+    let mut tx = conn.begin().await?;
+
+    let mut odd = tx
+        .cursor(sqlx::query("SELECT n FROM generate_series(1, $1, 2) n").bind(99_i32))
+        .await?;
+    let mut even = tx
+        .cursor("SELECT n FROM generate_series(2, 100, 2) n")
+        .await?;
+
+    // the lowest of the next value of each cursor goes first, as in a merge join
+    let mut merged = Vec::new();
+    let mut odd_rows: Vec<i32> = Vec::new();
+    let mut even_rows: Vec<i32> = Vec::new();
+
+    loop {
+        if odd_rows.is_empty() {
+            for row in odd.fetch_next(&mut tx, 7).await? {
+                odd_rows.push(row.try_get(0)?);
+            }
+            odd_rows.reverse();
+        }
+
+        if even_rows.is_empty() {
+            for row in even.fetch_next(&mut tx, 3).await? {
+                even_rows.push(row.try_get(0)?);
+            }
+            even_rows.reverse();
+        }
+
+        let next = match (odd_rows.last(), even_rows.last()) {
+            (Some(odd), Some(even)) if odd < even => odd_rows.pop(),
+            (_, Some(_)) => even_rows.pop(),
+            (Some(_), None) => odd_rows.pop(),
+            (None, None) => break,
+        };
+
+        merged.extend(next);
+
+        // the connection runs other queries between batches
+        if merged.len() == 50 {
+            let n: i32 = tx.fetch_one("SELECT 50").await?.try_get(0)?;
+            assert_eq!(n, 50);
+        }
+    }
+
+    assert_eq!(merged, (1..=100).collect::<Vec<i32>>());
+    assert!(odd.fetch_next(&mut tx, 7).await?.is_empty());
+
+    // the portals of dropped cursors are closed before the next command
+    drop(odd);
+    drop(even);
+
+    let portals: i64 = tx
+        .fetch_one("SELECT count(*) FROM pg_cursors WHERE name LIKE 'sqlx_p_%'")
+        .await?
+        .try_get(0)?;
+    assert_eq!(portals, 0);
+
+    // and a cursor fails once its transaction has ended
+    let mut cursor = tx.cursor("SELECT 1").await?;
+    tx.commit().await?;
+
+    let err = cursor.fetch_next(&mut conn, 1).await.unwrap_err();
+    assert!(matches!(err, sqlx::Error::Configuration(_)), "{:?}", err);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_executes_a_batch_in_a_transaction() -> anyhow::Result<()> {
+    use sqlx::postgres::PgArguments;
+    use sqlx::Arguments;
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE batch_items (id INT4 PRIMARY KEY, count INT4 NOT NULL)")
+        .await?;
+
+    let mut tx = conn.begin().await?;
+
+    // more statements than are pipelined to a round trip, of three kinds in turn
+    let mut batch = tx.batch();
+
+    for id in 0..300_i32 {
+        batch = batch
+            .add_query(sqlx::query("INSERT INTO batch_items (id, count) VALUES ($1, 1)").bind(id))
+            .add_query(
+                sqlx::query("UPDATE batch_items SET count = count + $2 WHERE id = $1")
+                    .bind(id)
+                    .bind(id),
+            );
+
+        if id % 2 == 0 {
+            let mut arguments = PgArguments::default();
+            arguments.add(id);
+
+            batch = batch.add("DELETE FROM batch_items WHERE id = $1", arguments);
+        }
+    }
+
+    assert_eq!(batch.len(), 750);
+
+    let results = batch.execute().await?;
+    assert_eq!(results.len(), 750);
+    assert!(results.iter().all(|result| result.rows_affected() == 1));
+
+    let (rows, total): (i64, i64) = sqlx::query_as("SELECT count(*), sum(count) FROM batch_items")
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(rows, 150);
+    assert_eq!(total, (1..300).step_by(2).map(|id| id + 1).sum::<i64>());
+
+    // a failed batch in a savepoint is rolled back to it, and the transaction goes on
+    let mut savepoint = tx.begin().await?;
+
+    let err = savepoint
+        .batch()
+        .add_query(sqlx::query("DELETE FROM batch_items"))
+        .add_query(sqlx::query(
+            "INSERT INTO batch_items (id, count) VALUES (1, 1)",
+        ))
+        .add_query(sqlx::query(
+            "INSERT INTO batch_items (id, count) VALUES (1, 1)",
+        ))
+        .add_query(sqlx::query("DELETE FROM batch_items"))
+        .execute()
+        .await
+        .unwrap_err();
+
+    match &err {
+        sqlx::Error::Batch { index, source } => {
+            assert_eq!(*index, 2);
+
+            let source = source.as_database_error().unwrap();
+            assert_eq!(source.code().as_deref(), Some("23505"));
+        }
+
+        _ => panic!("expected a batch error, got {:?}", err),
+    }
+
+    savepoint.rollback().await?;
+
+    let rows: i64 = sqlx::query_scalar("SELECT count(*) FROM batch_items")
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(rows, 150);
+
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,259 @@
+//! Kept apart from the other tests, as these talk to a scripted server on a local socket
+//! rather than to PostgreSQL.
+
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgSslMode};
+use sqlx::{Connection, Error, Executor};
+use std::io::{self, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+fn message(tag: u8, body: &[u8]) -> Vec<u8> {
+    let mut message = vec![tag];
+    message.extend(&(body.len() as u32 + 4).to_be_bytes());
+    message.extend(body);
+    message
+}
+
+fn authentication_ok() -> Vec<u8> {
+    message(b'R', &0_u32.to_be_bytes())
+}
+
+fn parameter(name: &str, value: &str) -> Vec<u8> {
+    message(b'S', format!("{}\0{}\0", name, value).as_bytes())
+}
+
+// the parameters the driver expects every server to report
+fn parameters() -> Vec<u8> {
+    [
+        parameter("standard_conforming_strings", "on"),
+        parameter("integer_datetimes", "on"),
+        parameter("client_encoding", "UTF8"),
+        parameter("DateStyle", "ISO, MDY"),
+        parameter("IntervalStyle", "postgres"),
+    ]
+    .concat()
+}
+
+fn backend_key_data() -> Vec<u8> {
+    message(
+        b'K',
+        &[10182_u32.to_be_bytes(), 2303903019_u32.to_be_bytes()].concat(),
+    )
+}
+
+fn ready_for_query() -> Vec<u8> {
+    message(b'Z', b"I")
+}
+
+// a server that reads the startup message of one connection, answers with `script`, and
+// then reads whatever else it is sent until the client hangs up
+fn serve(script: Vec<u8>) -> PgConnectOptions {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    thread::spawn(move || {
+        let (mut socket, _) = listener.accept().unwrap();
+
+        let mut len = [0; 4];
+        socket.read_exact(&mut len).unwrap();
+
+        let mut startup = vec![0; u32::from_be_bytes(len) as usize - 4];
+        socket.read_exact(&mut startup).unwrap();
+
+        // the client may have given up on the connection already
+        let _ = socket.write_all(&script);
+        let _ = io::copy(&mut socket, &mut io::sink());
+    });
+
+    PgConnectOptions::new()
+        .host("127.0.0.1")
+        .port(port)
+        .username("postgres")
+        .ssl_mode(PgSslMode::Disable)
+}
+
+#[sqlx_macros::test]
+async fn it_connects_without_backend_key_data() -> anyhow::Result<()> {
+    let script = || [authentication_ok(), parameters(), ready_for_query()].concat();
+
+    let conn = PgConnection::connect_with(&serve(script())).await?;
+
+    assert!(matches!(conn.cancel_token(), Err(Error::Protocol(_))));
+
+    conn.close().await?;
+
+    let strict = serve(script()).strict_protocol(true);
+
+    assert!(matches!(
+        PgConnection::connect_with(&strict).await,
+        Err(Error::Protocol(_))
+    ));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_assumes_parameters_the_server_does_not_report() -> anyhow::Result<()> {
+    let script = || {
+        [
+            authentication_ok(),
+            parameter("server_version", "13.0"),
+            backend_key_data(),
+            ready_for_query(),
+        ]
+        .concat()
+    };
+
+    let conn = PgConnection::connect_with(&serve(script())).await?;
+
+    assert!(conn.standard_conforming_strings());
+    assert!(conn.cancel_token().is_ok());
+
+    conn.close().await?;
+
+    let strict = serve(script()).strict_protocol(true);
+
+    assert!(matches!(
+        PgConnection::connect_with(&strict).await,
+        Err(Error::Protocol(_))
+    ));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_skips_messages_of_unknown_types() -> anyhow::Result<()> {
+    // `NegotiateProtocolVersion`, which the driver does not read, while connecting; and a
+    // made-up message with the response to a query
+    let negotiate = message(b'v', &[0_u32.to_be_bytes(), 0_u32.to_be_bytes()].concat());
+
+    let script = || {
+        [
+            authentication_ok(),
+            negotiate.clone(),
+            parameters(),
+            backend_key_data(),
+            ready_for_query(),
+            message(b'!', b"unheard of"),
+            message(b'C', b"SET\0"),
+            ready_for_query(),
+        ]
+        .concat()
+    };
+
+    let mut conn = PgConnection::connect_with(&serve(script())).await?;
+
+    let result = conn.execute("SET search_path TO public").await?;
+    assert_eq!(result.rows_affected(), 0);
+
+    conn.close().await?;
+
+    let strict = serve(script()).strict_protocol(true);
+
+    assert!(matches!(
+        PgConnection::connect_with(&strict).await,
+        Err(Error::Protocol(_))
+    ));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sends_a_cancel_request() -> anyhow::Result<()> {
+    let conn = PgConnection::connect_with(&serve(
+        [
+            authentication_ok(),
+            parameters(),
+            backend_key_data(),
+            ready_for_query(),
+        ]
+        .concat(),
+    ))
+    .await?;
+
+    // a server that only reads the request, and then hangs up
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let port = listener.local_addr()?.port();
+    let (sender, received) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (mut socket, _) = listener.accept().unwrap();
+
+        let mut request = [0; 16];
+        socket.read_exact(&mut request).unwrap();
+        sender.send(request).unwrap();
+    });
+
+    let options = PgConnectOptions::new()
+        .host("127.0.0.1")
+        .port(port)
+        .ssl_mode(PgSslMode::Disable);
+
+    conn.cancel_token()?.cancel(&options).await?;
+
+    let request = received.recv()?;
+    assert_eq!(&request[..8], b"\x00\x00\x00\x10\x04\xd2\x16\x2e");
+    assert_eq!(&request[8..12], &10182_u32.to_be_bytes());
+    assert_eq!(&request[12..], &2303903019_u32.to_be_bytes());
+
+    conn.close().await?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[sqlx_macros::test]
+async fn it_checks_the_owner_of_the_socket() -> anyhow::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+    use std::os::unix::net::UnixListener;
+
+    let dir = std::env::temp_dir().join(format!("sqlx-socket-owner-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(".s.PGSQL.5432");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path)?;
+    let uid = std::fs::metadata(&path)?.uid();
+
+    let options = PgConnectOptions::new()
+        .socket(&dir)
+        .port(5432)
+        .username("postgres")
+        .ssl_mode(PgSslMode::Disable);
+
+    // a socket of someone else is not connected to
+    let error = PgConnection::connect_with(&options.clone().expected_socket_owner(uid + 1))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::PolicyViolation(_)), "{:?}", error);
+
+    thread::spawn(move || {
+        let (mut socket, _) = listener.accept().unwrap();
+
+        let mut len = [0; 4];
+        socket.read_exact(&mut len).unwrap();
+
+        let mut startup = vec![0; u32::from_be_bytes(len) as usize - 4];
+        socket.read_exact(&mut startup).unwrap();
+
+        let script = [authentication_ok(), parameters(), ready_for_query()].concat();
+        socket.write_all(&script).unwrap();
+
+        let _ = io::copy(&mut socket, &mut io::sink());
+    });
+
+    // the server runs as the owner of its socket, which is the user running the test; only
+    // Tokio and actix can read the credentials of the peer
+    let options = options
+        .expected_socket_owner(uid)
+        .verify_socket_peer(cfg!(any(feature = "_rt-tokio", feature = "_rt-actix")));
+    let conn = PgConnection::connect_with(&options).await?;
+
+    conn.close().await?;
+    std::fs::remove_dir_all(&dir)?;
+
+    Ok(())
+}
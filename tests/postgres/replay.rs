@@ -0,0 +1,145 @@
+//! Kept apart from the other tests, as these play exchanges captured from PostgreSQL to the
+//! driver instead of talking to it; see `sqlx::wire::replay`.
+
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgCopyCsvOptions, PgSslMode};
+use sqlx::wire::replay::{Replay, ReplayServer};
+use sqlx::{Connection, Executor, Row};
+use std::time::Duration;
+
+// a server playing `replay`, and the options of the client it was recorded with
+fn serve(replay: &str) -> anyhow::Result<(ReplayServer, PgConnectOptions)> {
+    let server = Replay::parse(replay)?.serve()?;
+
+    let options = PgConnectOptions::new()
+        .host("127.0.0.1")
+        .port(server.port())
+        .username("postgres")
+        .database("postgres")
+        .application_name("replay")
+        .ssl_mode(PgSslMode::Disable);
+
+    Ok((server, options))
+}
+
+#[sqlx_macros::test]
+async fn it_replays_the_handshake() -> anyhow::Result<()> {
+    let (server, options) = serve(include_str!("fixtures/replay/handshake.replay"))?;
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    assert!(conn.standard_conforming_strings());
+    assert!(conn.cancel_token().is_ok());
+
+    conn.ping().await?;
+    conn.close().await?;
+
+    server.finish()?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sets_up_the_session_in_the_startup_message() -> anyhow::Result<()> {
+    let replay = include_str!("fixtures/replay/startup_parameters.replay");
+    assert_eq!(Replay::parse(replay)?.round_trips(), 1);
+
+    let (server, options) = serve(replay)?;
+
+    let options = options
+        .timezone("Europe/Berlin")
+        .search_path(&["app"])
+        .statement_timeout(Duration::from_secs(30));
+
+    // the server holds the client to the single round trip of the replay
+    PgConnection::connect_with(&options).await?.close().await?;
+
+    server.finish()?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_replays_an_error_in_an_extended_query() -> anyhow::Result<()> {
+    let (server, options) = serve(include_str!(
+        "fixtures/replay/error_in_extended_query.replay"
+    ))?;
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    // fails at `Execute`, after the statement was prepared
+    let err = sqlx::query("SELECT 10 / $1")
+        .bind(0_i32)
+        .execute(&mut conn)
+        .await
+        .unwrap_err()
+        .into_database_error()
+        .unwrap();
+
+    assert_eq!(err.code().as_deref(), Some("22012"));
+
+    // the statement stays cached, and the `ReadyForQuery` after the error is read first
+    let values: Vec<i32> = sqlx::query_scalar("SELECT 10 / $1")
+        .bind(2_i32)
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(values, [5]);
+
+    conn.close().await?;
+
+    server.finish()?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_replays_a_cancelled_copy() -> anyhow::Result<()> {
+    let (server, options) = serve(include_str!("fixtures/replay/cancelled_copy.replay"))?;
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+    let mut csv = Vec::new();
+
+    let err = conn
+        .copy_out_csv(
+            "(SELECT n FROM generate_series(1, 1000000) AS n)",
+            &mut csv,
+            &PgCopyCsvOptions::new(),
+        )
+        .await
+        .unwrap_err()
+        .into_database_error()
+        .unwrap();
+
+    assert_eq!(err.code().as_deref(), Some("57014"));
+
+    // the rows before the cancellation are left to the writer
+    assert_eq!(csv, b"1\n2\n3\n");
+
+    let value: i32 = conn.fetch_one("SELECT 1").await?.try_get(0)?;
+    assert_eq!(value, 1);
+
+    conn.close().await?;
+
+    server.finish()?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fails_a_replay_the_client_strays_from() -> anyhow::Result<()> {
+    let (server, options) = serve(include_str!("fixtures/replay/handshake.replay"))?;
+
+    // a different application name changes the startup message
+    let conn = PgConnection::connect_with(&options.application_name("strayed")).await;
+    assert!(conn.is_err());
+
+    let err = server.finish().unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("line 6: expected the client to send `>> - 00030000"),
+        "{}",
+        err
+    );
+
+    Ok(())
+}
@@ -4,8 +4,12 @@ use std::ops::Bound;
 #[cfg(feature = "decimal")]
 use std::str::FromStr;
 
-use sqlx::postgres::types::{PgInterval, PgMoney, PgRange};
+use sqlx::postgres::types::{
+    PgBits, PgInterval, PgLtree, PgMacAddress, PgMacAddress8, PgMoney, PgNumericU64, PgRange,
+    PgTsVector, PgXml,
+};
 use sqlx::postgres::Postgres;
+use sqlx::types::Lossy;
 use sqlx_test::{test_decode_type, test_prepared_type, test_type};
 
 test_type!(null<Option<i16>>(Postgres,
@@ -42,6 +46,75 @@ test_prepared_type!(byte_slice<&[u8]>(Postgres,
         == &[0_u8, 0, 0, 0, 0x52][..]
 ));
 
+test_type!(byte_array<[u8; 4]>(Postgres,
+    "E'\\\\xDEADBEEF'::bytea" == [0xDE_u8, 0xAD, 0xBE, 0xEF],
+    "E'\\\\x00000052'::bytea" == [0_u8, 0, 0, 0x52]
+));
+
+test_type!(byte_array_6<[u8; 6]>(Postgres,
+    "E'\\\\x08002B010203'::bytea" == [0x08_u8, 0x00, 0x2b, 0x01, 0x02, 0x03]
+));
+
+test_type!(byte_array_8<[u8; 8]>(Postgres,
+    "E'\\\\x08002B0102030405'::bytea" == [0x08_u8, 0x00, 0x2b, 0x01, 0x02, 0x03, 0x04, 0x05]
+));
+
+test_type!(byte_array_empty<[u8; 0]>(Postgres,
+    "E'\\\\x'::bytea" == [0_u8; 0]
+));
+
+test_type!(byte_array_null<Option<[u8; 4]>>(Postgres,
+    "NULL::bytea" == None::<[u8; 4]>,
+    "E'\\\\xDEADBEEF'::bytea" == Some([0xDE_u8, 0xAD, 0xBE, 0xEF])
+));
+
+test_type!(int_array<[i32; 3]>(Postgres,
+    "'{1,2,3}'::int4[]" == [1_i32, 2, 3],
+));
+
+test_type!(int_array_empty<[i32; 0]>(Postgres,
+    "'{}'::int4[]" == [0_i32; 0],
+));
+
+test_type!(int_array_null<[Option<i32>; 3]>(Postgres,
+    "array[1,NULL,3]::int4[]" == [Some(1_i32), None, Some(3)],
+));
+
+#[sqlx_macros::test]
+async fn it_refuses_fixed_size_arrays_of_another_length() -> anyhow::Result<()> {
+    use sqlx::{Connection, Row};
+
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+
+    let sql = "SELECT E'\\\\xDEADBEEF'::bytea, '{1,2,3}'::int4[], NULL::bytea";
+    let row = conn.fetch_one(sqlx::query(sql)).await?;
+    let row_text = conn.fetch_one(sql).await?;
+
+    for row in &[row, row_text] {
+        let error = row.try_get::<[u8; 3], _>(0).unwrap_err().to_string();
+        assert!(error.contains("expected 3 bytes, got 4"), "{}", error);
+
+        let error = row
+            .try_get::<Option<[u8; 5]>, _>(0)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("expected 5 bytes, got 4"), "{}", error);
+
+        let error = row.try_get::<[i32; 4], _>(1).unwrap_err().to_string();
+        assert!(
+            error.contains("expected an array of 4 elements, got 3 elements"),
+            "{}",
+            error
+        );
+
+        assert_eq!(row.try_get::<Option<[u8; 3]>, _>(2)?, None);
+    }
+
+    conn.close().await?;
+
+    Ok(())
+}
+
 test_type!(str<&str>(Postgres,
     "'this is foo'" == "this is foo",
     "''" == "",
@@ -94,6 +167,76 @@ test_type!(i32_vec<Vec<i32>>(Postgres,
 
 test_type!(i64(Postgres, "9358295312::bigint" == 9358295312_i64));
 
+test_type!(u16(
+    Postgres,
+    "0::int" == 0_u16,
+    "32768::int" == 32768_u16,
+    "65535::int" == u16::MAX,
+));
+
+test_type!(u16_vec<Vec<u16>>(Postgres,
+    "'{0,1,65535}'::int[]" == vec![0_u16, 1, u16::MAX],
+));
+
+test_type!(u64(
+    Postgres,
+    "0::bigint" == 0_u64,
+    "9223372036854775807::bigint" == i64::MAX as u64,
+));
+
+test_type!(numeric_u64<PgNumericU64>(Postgres,
+    "0::numeric" == PgNumericU64(0),
+    "9223372036854775808::numeric" == PgNumericU64(i64::MAX as u64 + 1),
+    "18446744073709551615::numeric" == PgNumericU64(u64::MAX),
+));
+
+test_decode_type!(u64_from_ints<u64>(Postgres,
+    "7::int2" == 7_u64,
+    "65536::int4" == 65536_u64,
+));
+
+#[sqlx_macros::test]
+async fn it_refuses_to_decode_negatives_into_unsigned_types() -> anyhow::Result<()> {
+    use sqlx::{Connection, Row};
+
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+
+    for sql in &[
+        "SELECT -1::int2",
+        "SELECT -1::int4",
+        "SELECT (-9223372036854775808)::int8",
+    ] {
+        let row = conn.fetch_one(sqlx::query(sql)).await?;
+        let row_text = conn.fetch_one(*sql).await?;
+
+        assert!(row.try_get::<u16, _>(0).is_err(), "{}", sql);
+        assert!(row.try_get::<u32, _>(0).is_err(), "{}", sql);
+        assert!(row.try_get::<u64, _>(0).is_err(), "{}", sql);
+        assert!(row_text.try_get::<u64, _>(0).is_err(), "{}", sql);
+    }
+
+    for sql in &["SELECT 65536::int4", "SELECT -1::int4"] {
+        let row = conn.fetch_one(sqlx::query(sql)).await?;
+        assert!(row.try_get::<u16, _>(0).is_err(), "{}", sql);
+    }
+
+    for sql in &[
+        "SELECT 18446744073709551616::numeric",
+        "SELECT (-1)::numeric",
+        "SELECT 1.0::numeric",
+    ] {
+        let row = conn.fetch_one(sqlx::query(sql)).await?;
+        let row_text = conn.fetch_one(*sql).await?;
+
+        assert!(row.try_get::<PgNumericU64, _>(0).is_err(), "{}", sql);
+        assert!(row_text.try_get::<PgNumericU64, _>(0).is_err(), "{}", sql);
+    }
+
+    conn.close().await?;
+
+    Ok(())
+}
+
 test_type!(f32(Postgres, "9419.122::real" == 9419.122_f32));
 
 test_type!(f64(
@@ -192,6 +335,101 @@ test_type!(bitvec<sqlx::types::BitVec>(
     },
 ));
 
+test_type!(bits<PgBits>(
+    Postgres,
+    "B'01101001'" == PgBits::from_bytes(8, vec![0b0110_1001]).unwrap(),
+    "B'110'" == PgBits::from_bytes(3, vec![0b1100_0000]).unwrap(),
+    "B'01101'::bit(5)" == PgBits::from_bytes(5, vec![0b0110_1000]).unwrap(),
+    "B''::varbit" == PgBits::new(),
+));
+
+test_type!(bits_vec<Vec<PgBits>>(
+    Postgres,
+    "array[B'1', B'0101']::varbit[]"
+        == vec![
+            PgBits::from_bytes(1, vec![0b1000_0000]).unwrap(),
+            PgBits::from_bytes(4, vec![0b0101_0000]).unwrap(),
+        ],
+));
+
+// XML has no equality operator, so the values are compared as text
+test_type!(xml<PgXml>(
+    Postgres,
+    "SELECT ({0}::text is not distinct from $1::text)::int4, {0}, $2",
+    "'<a href=\"x\">b</a>'::xml" == PgXml("<a href=\"x\">b</a>".to_owned()),
+    "'text <b/>'::xml" == PgXml("text <b/>".to_owned()),
+));
+
+test_type!(xml_vec<Vec<PgXml>>(
+    Postgres,
+    "SELECT ({0}::text[] is not distinct from $1::text[])::int4, {0}, $2",
+    "array['<a/>', '<b/>']::xml[]" == vec![PgXml("<a/>".to_owned()), PgXml("<b/>".to_owned())],
+));
+
+test_type!(tsvector<PgTsVector>(
+    Postgres,
+    "'a fat cat'::tsvector" == PgTsVector("'a' 'cat' 'fat'".to_owned()),
+    r"$$a:1,3A fat:2B it's:4C b\\c$$::tsvector"
+        == PgTsVector(r"'a':1,3A 'b\\c' 'fat':2B 'it''s':4C".to_owned()),
+    "''::tsvector" == PgTsVector(String::new()),
+));
+
+test_type!(tsvector_vec<Vec<PgTsVector>>(
+    Postgres,
+    "array['a:1', 'b c']::tsvector[]"
+        == vec![
+            PgTsVector("'a':1".to_owned()),
+            PgTsVector("'b' 'c'".to_owned()),
+        ],
+));
+
+#[sqlx_macros::test]
+async fn it_encodes_tsvector_text_in_any_form() -> anyhow::Result<()> {
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+
+    let value: PgTsVector = "fat:2b cat:3,1 fat:1".parse()?;
+
+    let (text, vector): (String, PgTsVector) = sqlx::query_as("SELECT $1::text, $1")
+        .bind(&value)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(text, "'cat':1,3 'fat':1,2B");
+    assert_eq!(vector.as_str(), text);
+
+    Ok(())
+}
+
+// needs the `ltree` extension, which is created by `setup.sql`
+test_type!(ltree<PgLtree>(
+    Postgres,
+    "'Top.Science.Astronomy'::ltree" == PgLtree("Top.Science.Astronomy".to_owned()),
+    "''::ltree" == PgLtree(String::new()),
+));
+
+test_type!(ltree_vec<Vec<PgLtree>>(
+    Postgres,
+    "array['a.b', 'c']::ltree[]" == vec![PgLtree("a.b".to_owned()), PgLtree("c".to_owned())],
+));
+
+test_type!(macaddr<PgMacAddress>(
+    Postgres,
+    "'08:00:2b:01:02:03'::macaddr" == PgMacAddress([0x08, 0x00, 0x2b, 0x01, 0x02, 0x03]),
+    "'ff-ff-ff-ff-ff-ff'::macaddr" == PgMacAddress([0xff; 6]),
+));
+
+test_type!(macaddr8<PgMacAddress8>(
+    Postgres,
+    "'08:00:2b:01:02:03:04:05'::macaddr8"
+        == PgMacAddress8([0x08, 0x00, 0x2b, 0x01, 0x02, 0x03, 0x04, 0x05]),
+));
+
+test_type!(macaddr_vec<Vec<PgMacAddress>>(
+    Postgres,
+    "'{08:00:2b:01:02:03,00:00:00:00:00:00}'::macaddr[]"
+        == vec![PgMacAddress([0x08, 0x00, 0x2b, 0x01, 0x02, 0x03]), PgMacAddress([0; 6])],
+));
+
 #[cfg(feature = "ipnetwork")]
 test_type!(ipnetwork_vec<Vec<sqlx::types::ipnetwork::IpNetwork>>(Postgres,
     "'{127.0.0.1,8.8.8.8/24}'::inet[]"
@@ -251,6 +489,16 @@ mod chrono {
             ]
     ));
 
+    test_type!(chrono_tstzrange<PgRange<DateTime::<Utc>>>(Postgres,
+        "'[2019-01-02 05:10:20+00,2019-01-03 05:10:20+00)'::tstzrange"
+            == PgRange::from(
+                Utc.ymd(2019, 1, 2).and_hms(5, 10, 20)..Utc.ymd(2019, 1, 3).and_hms(5, 10, 20)
+            ),
+        "'[2019-01-02 05:10:20+00,)'::tstzrange"
+            == PgRange::from(Utc.ymd(2019, 1, 2).and_hms(5, 10, 20)..),
+        "'empty'::tstzrange" == PgRange::<DateTime<Utc>>::empty()
+    ));
+
     test_type!(chrono_time_tz<PgTimeTz>(Postgres,
         "TIMETZ '05:10:20.115100+00'" == PgTimeTz { time: NaiveTime::from_hms_micro(5, 10, 20, 115100), offset: FixedOffset::east(0) },
         "TIMETZ '05:10:20.115100+06:30'" == PgTimeTz { time: NaiveTime::from_hms_micro(5, 10, 20, 115100), offset: FixedOffset::east(60 * 60 * 6 + 1800) },
@@ -259,6 +507,122 @@ mod chrono {
     ));
 }
 
+mod system_time {
+    use super::*;
+    use sqlx::types::{TimestampOutOfRange, UnixMillis, UnixTimestamp};
+    use sqlx::{Connection, Row};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    test_type!(system_time<SystemTime>(Postgres,
+        "'2019-01-02 05:10:20.1151+00'::timestamptz"
+            == UNIX_EPOCH + Duration::from_micros(1_546_405_820_115_100),
+        "'1969-12-31 23:59:59.5+00'::timestamptz" == UNIX_EPOCH - Duration::from_millis(500),
+        "'0044-03-15 12:00:00+00 BC'::timestamptz"
+            == UNIX_EPOCH - Duration::from_secs(63_517_780_800),
+    ));
+
+    test_decode_type!(system_time_without_zone<SystemTime>(Postgres,
+        "'2019-01-02 05:10:20'::timestamp" == UNIX_EPOCH + Duration::from_secs(1_546_405_820),
+    ));
+
+    test_prepared_type!(unix_timestamp<UnixTimestamp>(Postgres,
+        "SELECT ({0} is not distinct from $1)::int4, {0}, $2::timestamptz",
+        "'2019-01-02 05:10:20+00'::timestamptz" == UnixTimestamp(1_546_405_820),
+        "'1969-12-31 23:59:59+00'::timestamptz" == UnixTimestamp(-1),
+    ));
+
+    test_prepared_type!(unix_timestamp_int8<UnixTimestamp>(Postgres,
+        "SELECT ({0} is not distinct from $1)::int4, {0}, $2::int8",
+        "1546405820::int8" == UnixTimestamp(1_546_405_820),
+        "(-1)::int8" == UnixTimestamp(-1),
+    ));
+
+    test_prepared_type!(unix_millis<UnixMillis>(Postgres,
+        "SELECT ({0} is not distinct from $1)::int4, {0}, $2::timestamptz",
+        "'2019-01-02 05:10:20.115+00'::timestamptz" == UnixMillis(1_546_405_820_115),
+    ));
+
+    // rounded down towards the past
+    test_decode_type!(unix_timestamp_rounded<UnixTimestamp>(Postgres,
+        "'2019-01-02 05:10:20.9+00'::timestamptz" == UnixTimestamp(1_546_405_820),
+        "'1969-12-31 23:59:59.5+00'::timestamptz" == UnixTimestamp(-1),
+    ));
+
+    test_decode_type!(unix_millis_rounded<UnixMillis>(Postgres,
+        "'1969-12-31 23:59:59.9995+00'::timestamptz" == UnixMillis(-1),
+        "1546405820115::int8" == UnixMillis(1_546_405_820_115),
+    ));
+
+    #[sqlx_macros::test]
+    async fn it_refuses_timestamps_out_of_range() -> anyhow::Result<()> {
+        let mut conn = sqlx_test::new::<Postgres>().await?;
+
+        // past the year 294276
+        let error = sqlx::query("SELECT $1")
+            .bind(UNIX_EPOCH + Duration::from_secs(10_000_000_000_000))
+            .fetch_one(&mut conn)
+            .await
+            .unwrap_err();
+
+        match error {
+            sqlx::Error::ArgumentEncode { index: 0, source } => {
+                let source = source.downcast_ref::<TimestampOutOfRange>().unwrap();
+                assert_eq!(source.target(), "TIMESTAMPTZ");
+            }
+
+            error => panic!("unexpected error: {}", error),
+        }
+
+        // an infinite timestamp is no instant
+        let row = conn
+            .fetch_one(sqlx::query("SELECT 'infinity'::timestamptz"))
+            .await?;
+        assert!(row.try_get::<SystemTime, _>(0).is_err());
+        assert!(row.try_get::<UnixTimestamp, _>(0).is_err());
+
+        // a unix timestamp beyond the range of TIMESTAMPTZ still fits in a BIGINT
+        let far: i64 = sqlx::query_scalar("SELECT $1::int8")
+            .bind(UnixTimestamp(i64::MAX))
+            .fetch_one(&mut conn)
+            .await?;
+        assert_eq!(far, i64::MAX);
+
+        conn.close().await?;
+
+        Ok(())
+    }
+
+    #[sqlx_macros::test]
+    async fn it_reads_timestamps_without_zone_as_utc() -> anyhow::Result<()> {
+        let mut conn = sqlx_test::new::<Postgres>().await?;
+
+        // a TIMESTAMPTZ is the same instant in any session time zone, ...
+        conn.execute("SET TIME ZONE 'America/New_York'").await?;
+
+        let row = conn
+            .fetch_one(sqlx::query(
+                "SELECT '2019-01-02 05:10:20+00'::timestamptz, '2019-01-02 05:10:20+00'::timestamptz::timestamp",
+            ))
+            .await?;
+
+        assert_eq!(
+            row.try_get::<SystemTime, _>(0)?,
+            UNIX_EPOCH + Duration::from_secs(1_546_405_820)
+        );
+
+        // ... but one cast to a TIMESTAMP has the wall clock time of the session, which is then
+        // taken to be in UTC
+        assert_eq!(
+            row.try_get::<SystemTime, _>(1)?,
+            UNIX_EPOCH + Duration::from_secs(1_546_405_820 - 5 * 60 * 60)
+        );
+
+        conn.close().await?;
+
+        Ok(())
+    }
+}
+
 #[cfg(feature = "time")]
 mod time_tests {
     use super::*;
@@ -308,7 +672,7 @@ mod json {
     use serde_json::{json, Value as JsonValue};
     use sqlx::postgres::PgRow;
     use sqlx::types::Json;
-    use sqlx::{Executor, Row};
+    use sqlx::Row;
     use sqlx_test::new;
 
     // When testing JSON, coerce to JSONB for `=` comparison as `JSON = JSON` is not
@@ -419,6 +783,78 @@ test_type!(decimal<sqlx::types::Decimal>(Postgres,
     "12345.6789::numeric" == sqlx::types::Decimal::from_str("12345.6789").unwrap(),
 ));
 
+#[cfg(feature = "decimal")]
+test_decode_type!(decimal_widened<sqlx::types::Decimal>(Postgres,
+    "42::int2" == sqlx::types::Decimal::from(42),
+    "(-9223372036854775808)::int8" == sqlx::types::Decimal::from(i64::MIN),
+    "0.1::float8" == sqlx::types::Decimal::from_str("0.1").unwrap(),
+    "0.1::float4" == sqlx::types::Decimal::from_str("0.1").unwrap(),
+    "-12345.6789::float8" == sqlx::types::Decimal::from_str("-12345.6789").unwrap(),
+));
+
+test_decode_type!(lossy_f64<Lossy<f64>>(Postgres,
+    "0.1::numeric" == Lossy(0.1),
+    "-123.456::numeric" == Lossy(-123.456),
+    "9007199254740993::numeric" == Lossy(9007199254740992.0),
+    "9007199254740995::numeric" == Lossy(9007199254740996.0),
+    "0.30000000000000000001::numeric" == Lossy(0.3),
+    "'Infinity'::numeric" == Lossy(f64::INFINITY),
+    "'-Infinity'::numeric" == Lossy(f64::NEG_INFINITY),
+    "1e-400::numeric" == Lossy(0.0),
+    "2.5::float4" == Lossy(2.5),
+    "9007199254740993::int8" == Lossy(9007199254740992.0),
+));
+
+test_decode_type!(lossy_i64<Lossy<i64>>(Postgres,
+    "0::numeric" == Lossy(0_i64),
+    "100000000::numeric" == Lossy(100000000_i64),
+    "9223372036854775807::numeric" == Lossy(i64::MAX),
+    "-9223372036854775808::numeric" == Lossy(i64::MIN),
+    "7::int2" == Lossy(7_i64),
+));
+
+#[sqlx_macros::test]
+async fn it_refuses_lossy_numerics_it_cannot_convert() -> anyhow::Result<()> {
+    use sqlx::{Connection, Row};
+
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+
+    // the default is still strict
+    let row = conn.fetch_one(sqlx::query("SELECT 0.1::numeric")).await?;
+    assert!(row.try_get::<f64, _>(0).is_err());
+    assert!(row.try_get::<i64, _>(0).is_err());
+
+    let row = conn.fetch_one(sqlx::query("SELECT 'NaN'::numeric")).await?;
+    let row_text = conn.fetch_one("SELECT 'NaN'::numeric").await?;
+
+    assert!(row.try_get::<Lossy<f64>, _>(0)?.is_nan());
+    assert!(row_text.try_get::<Lossy<f64>, _>(0)?.is_nan());
+
+    for sql in &[
+        "SELECT 'NaN'::numeric",
+        "SELECT 1.50::numeric",
+        "SELECT 1.00::numeric",
+        "SELECT 9223372036854775808::numeric",
+        "SELECT 'Infinity'::numeric",
+    ] {
+        let row = conn.fetch_one(sqlx::query(sql)).await?;
+        let row_text = conn.fetch_one(*sql).await?;
+
+        assert!(row.try_get::<Lossy<i64>, _>(0).is_err(), "{}", sql);
+        assert!(row_text.try_get::<Lossy<i64>, _>(0).is_err(), "{}", sql);
+    }
+
+    let row = conn.fetch_one(sqlx::query("SELECT 1e400::numeric")).await?;
+    let row_text = conn.fetch_one("SELECT 1e400::numeric").await?;
+
+    assert!(row.try_get::<Lossy<f64>, _>(0).is_err());
+    assert!(row_text.try_get::<Lossy<f64>, _>(0).is_err());
+
+    conn.close().await?;
+
+    Ok(())
+}
+
 const EXC2: Bound<i32> = Bound::Excluded(2);
 const EXC3: Bound<i32> = Bound::Excluded(3);
 const INC1: Bound<i32> = Bound::Included(1);
@@ -441,9 +877,26 @@ test_type!(int4range<PgRange<i32>>(Postgres,
     "'[1,]'::int4range" == PgRange::from((INC1, UNB)),
     "'[1,2)'::int4range" == PgRange::from((INC1, EXC2)),
     "'[1,2]'::int4range" == PgRange::from((INC1, EXC3)),
+    "'empty'::int4range" == PgRange::<i32>::empty(),
+    "'[1,1)'::int4range" == PgRange::<i32>::empty(),
+));
+
+test_type!(int8range<PgRange<i64>>(Postgres,
+    "'[1,5000000000)'::int8range" == PgRange::from(1_i64..5_000_000_000),
+    "'(,5000000000]'::int8range" == PgRange::from(..5_000_000_001_i64),
+    "'empty'::int8range" == PgRange::<i64>::empty(),
 ));
 
-test_prepared_type!(interval<PgInterval>(
+#[cfg(feature = "decimal")]
+test_type!(decimal_range<PgRange<sqlx::types::Decimal>>(Postgres,
+    "'[0.5,12.25]'::numrange"
+        == PgRange::from(
+            sqlx::types::Decimal::from_str("0.5").unwrap()..=sqlx::types::Decimal::from_str("12.25").unwrap()
+        ),
+    "'empty'::numrange" == PgRange::<sqlx::types::Decimal>::empty(),
+));
+
+test_type!(interval<PgInterval>(
     Postgres,
     "INTERVAL '1h'"
         == PgInterval {
@@ -471,7 +924,11 @@ test_prepared_type!(interval<PgInterval>(
         },
 ));
 
-test_prepared_type!(money<PgMoney>(Postgres, "123.45::money" == PgMoney(12345)));
+test_type!(money<PgMoney>(Postgres,
+    "123.45::money" == PgMoney(12345),
+    "(-1234.56)::money" == PgMoney(-123456),
+    "0::money" == PgMoney(0),
+));
 
 test_prepared_type!(money_vec<Vec<PgMoney>>(Postgres,
     "array[123.45,420.00,666.66]::money[]" == vec![PgMoney(12345), PgMoney(42000), PgMoney(66666)],
@@ -0,0 +1,82 @@
+use std::borrow::Cow;
+
+use sqlx::migrate::{MigrateError, Migration, MigrationType, Migrator};
+use sqlx::{Connection, Error, SqliteConnection};
+
+fn migrator(scripts: &[&'static str]) -> Migrator {
+    let migrations = scripts
+        .iter()
+        .enumerate()
+        .map(|(index, sql)| {
+            Migration::new(
+                index as i64 + 1,
+                Cow::Owned(format!("migration {}", index + 1)),
+                MigrationType::Simple,
+                Cow::Borrowed(*sql),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    Migrator {
+        migrations: Cow::Owned(migrations),
+    }
+}
+
+#[sqlx_macros::test]
+async fn it_reports_every_modified_migration() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    migrator(&[
+        "CREATE TABLE a (id INTEGER);",
+        "CREATE TABLE b (id INTEGER);",
+        "CREATE TABLE c (id INTEGER);",
+    ])
+    .run(&mut conn)
+    .await?;
+
+    let error = migrator(&[
+        "CREATE TABLE a (id BIGINT);",
+        "CREATE TABLE b (id INTEGER);",
+        "CREATE TABLE c (id BIGINT);",
+        "CREATE TABLE d (id INTEGER);",
+    ])
+    .run(&mut conn)
+    .await
+    .unwrap_err();
+
+    match &error {
+        MigrateError::Execute(Error::Aggregate(errors)) => {
+            assert_eq!(errors.attempted(), 3);
+
+            let labels: Vec<&str> = errors.errors().iter().map(|(l, _)| &**l).collect();
+            assert_eq!(labels, ["migration 1", "migration 3"]);
+
+            assert!(matches!(
+                &errors.errors()[0].1,
+                Error::Migrate(error) if matches!(**error, MigrateError::VersionMismatch(1))
+            ));
+        }
+
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    // nothing is applied while applied migrations do not validate
+    let tables: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE name = 'd'")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(tables, 0);
+
+    // a single modified migration fails as it is
+    let error = migrator(&[
+        "CREATE TABLE a (id INTEGER);",
+        "CREATE TABLE b (id BIGINT);",
+    ])
+    .run(&mut conn)
+    .await
+    .unwrap_err();
+
+    assert!(matches!(error, MigrateError::VersionMismatch(2)));
+
+    Ok(())
+}
@@ -1,10 +1,11 @@
 use futures::TryStreamExt;
-use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::{
     query, sqlite::Sqlite, sqlite::SqliteRow, Column, Connection, Executor, Row, SqliteConnection,
     SqlitePool, Statement, TypeInfo,
 };
 use sqlx_test::new;
+use std::str::FromStr;
 
 #[sqlx_macros::test]
 async fn it_connects() -> anyhow::Result<()> {
@@ -202,6 +203,25 @@ async fn it_executes_with_pool() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_counts_queries() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+    let before = conn.stats().queries;
+
+    conn.execute("SELECT 1").await?;
+    let _: i32 = sqlx::query_scalar("SELECT ?")
+        .bind(2_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    let stats = conn.stats();
+    assert_eq!(stats.queries - before, 2);
+    assert!(stats.average_latency.is_some());
+    assert_eq!((stats.bytes_sent, stats.bytes_received), (0, 0));
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_opens_in_memory() -> anyhow::Result<()> {
     // If the filename is ":memory:", then a private, temporary in-memory database
@@ -355,16 +375,16 @@ async fn it_interleaves_reads_and_writes() -> anyhow::Result<()> {
 
     let mut cursor = conn.fetch(
         "
-CREATE TABLE IF NOT EXISTS _sqlx_test (
+CREATE TEMPORARY TABLE _sqlx_test_interleave (
     id INT PRIMARY KEY,
     text TEXT NOT NULL
 );
 
 SELECT 'Hello World' as _1;
 
-INSERT INTO _sqlx_test (text) VALUES ('this is a test');
+INSERT INTO _sqlx_test_interleave (text) VALUES ('this is a test');
 
-SELECT id, text FROM _sqlx_test;
+SELECT id, text FROM _sqlx_test_interleave;
     ",
     );
 
@@ -455,6 +475,39 @@ async fn it_caches_statements() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_never_evicts_pinned_statements() -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")?.statement_cache_capacity(1);
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    conn.prepare_pinned("SELECT ? AS pinned").await?;
+    assert!(conn
+        .prepare_pinned("SELECT ? AS one_too_many")
+        .await
+        .is_err());
+
+    // run without being cached, as the pinned statement leaves no room
+    let val: i32 = sqlx::query_scalar("SELECT ? + 1")
+        .bind(1_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(val, 2);
+
+    let val: i32 = sqlx::query_scalar("SELECT ? AS pinned")
+        .bind(7_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(val, 7);
+    assert_eq!(conn.cached_statements_size(), 1);
+
+    let stats = conn.stats().statement_cache;
+    assert_eq!((stats.hits, stats.refusals), (1, 2));
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
     let mut conn = new::<Sqlite>().await?;
@@ -504,3 +557,443 @@ async fn it_resets_prepared_statement_after_fetch_one() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_resolves_duplicate_column_names_to_the_first_column() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    for _ in 0..2 {
+        // the second run uses the cached statement
+        let row = sqlx::query("SELECT 1 AS id, 2 AS id, 3 AS other")
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(row.try_get::<i32, _>("id")?, 1);
+        assert_eq!(row.try_get::<i32, _>("other")?, 3);
+        assert!(row.try_get::<i32, _>("missing").is_err());
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_runs_queries_through_an_erased_executor() -> anyhow::Result<()> {
+    use sqlx::erased::{ErasedArguments, ErasedExecutor, ErasedValue};
+
+    let mut conn = new::<Sqlite>().await?;
+    let executor: &mut dyn ErasedExecutor = &mut conn;
+
+    executor
+        .execute_erased(
+            "CREATE TEMPORARY TABLE erased (id INTEGER PRIMARY KEY, name TEXT, visible BOOLEAN, data BLOB)",
+            ErasedArguments::new(),
+        )
+        .await?;
+
+    let mut arguments = ErasedArguments::new();
+    arguments.add("ferris");
+    arguments.add(true);
+    arguments.add(vec![0_u8, 1]);
+
+    let result = executor
+        .execute_erased(
+            "INSERT INTO erased (name, visible, data) VALUES (?, ?, ?)",
+            arguments,
+        )
+        .await?;
+    assert_eq!(result.rows_affected(), 1);
+    assert_eq!(result.last_insert_id(), Some(1));
+
+    let row = executor
+        .fetch_optional_erased(
+            "SELECT *, 2.5 AS score, NULL AS missing FROM erased WHERE id = ?",
+            vec![1].into_iter().collect(),
+        )
+        .await?
+        .unwrap();
+
+    assert_eq!(
+        row.values(),
+        [
+            ErasedValue::Int(1),
+            ErasedValue::Text("ferris".into()),
+            ErasedValue::Bool(true),
+            ErasedValue::Bytes(vec![0, 1]),
+            ErasedValue::Float(2.5),
+            ErasedValue::Null,
+        ]
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[sqlx_macros::test]
+async fn it_reports_values_that_cannot_be_encoded() -> anyhow::Result<()> {
+    use sqlx::types::Json;
+    use std::collections::HashMap;
+
+    let mut conn = new::<Sqlite>().await?;
+
+    // JSON object keys must be strings
+    let mut map = HashMap::new();
+    map.insert(vec![1_i32], 1_i32);
+
+    let res = sqlx::query("SELECT ?1, ?2")
+        .bind(1_i32)
+        .bind(Json(map))
+        .execute(&mut conn)
+        .await;
+
+    assert!(
+        matches!(res, Err(sqlx::Error::ArgumentEncode { index: 1, .. })),
+        "{:?}",
+        res
+    );
+
+    let value: i32 = sqlx::query_scalar("SELECT ?1")
+        .bind(5_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 5);
+
+    Ok(())
+}
+
+#[cfg(feature = "serde-row")]
+#[sqlx_macros::test]
+async fn it_reads_rows_into_serde_types() -> anyhow::Result<()> {
+    use serde::Deserialize;
+    use sqlx::serde_row::{from_row, from_row_with, SerdeRowOptions};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Role {
+        Admin,
+        Member,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct User {
+        id: i64,
+        name: String,
+        email: Option<String>,
+        age: u8,
+        role: Role,
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let row = sqlx::query(
+        "SELECT 1 AS id, 'ann' AS name, NULL AS email, 42 AS age, 'admin' AS role, 'x' AS extra",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(
+        from_row::<User, _>(&row)?,
+        User {
+            id: 1,
+            name: "ann".into(),
+            email: None,
+            age: 42,
+            role: Role::Admin,
+        }
+    );
+
+    let options = SerdeRowOptions::new().deny_unknown_columns(true);
+    let err = from_row_with::<User, _>(&row, options).unwrap_err();
+    assert!(err.to_string().contains("\"extra\""), "{}", err);
+
+    // every column, in order
+    let (id, name): (i64, String) =
+        from_row(&sqlx::query("SELECT 2, 'bob'").fetch_one(&mut conn).await?)?;
+
+    assert_eq!((id, name), (2, "bob".to_owned()));
+
+    // errors name the column
+    let row = sqlx::query("SELECT 1 AS id, 'ann' AS name, 300 AS age, 'admin' AS role")
+        .fetch_one(&mut conn)
+        .await?;
+
+    match from_row::<User, _>(&row) {
+        Err(sqlx::Error::ColumnDecode { index, .. }) => assert_eq!(index, "\"age\""),
+        other => panic!("expected an out-of-range age, got {:?}", other),
+    }
+
+    let row = sqlx::query("SELECT 1 AS id, 42 AS age, 'admin' AS role")
+        .fetch_one(&mut conn)
+        .await?;
+
+    match from_row::<User, _>(&row) {
+        Err(sqlx::Error::ColumnNotFound(name)) => assert_eq!(name, "name"),
+        other => panic!("expected a missing name, got {:?}", other),
+    }
+
+    let row = sqlx::query("SELECT 1 AS id, 'ann' AS name, 42 AS age, 'owner' AS role")
+        .fetch_one(&mut conn)
+        .await?;
+
+    match from_row::<User, _>(&row) {
+        Err(sqlx::Error::ColumnDecode { index, .. }) => assert_eq!(index, "\"role\""),
+        other => panic!("expected an unknown role, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_refuses_values_larger_than_the_limit() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let err = sqlx::query_as::<_, (i32, Vec<u8>, String)>("SELECT 1, zeroblob(10000000), 'tail'")
+        .max_value_size(1 << 20)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            sqlx::Error::ValueTooLarge {
+                column: Some(1),
+                size: 10_000_000,
+                limit: 1_048_576,
+            }
+        ),
+        "{:?}",
+        err
+    );
+
+    let err = sqlx::query_as::<_, (Vec<u8>, String)>(
+        "SELECT zeroblob(1000), 'abc' UNION ALL SELECT zeroblob(1001), 'abc'",
+    )
+    .max_row_size(1003)
+    .fetch_all(&mut conn)
+    .await
+    .unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            sqlx::Error::ValueTooLarge {
+                column: None,
+                size: 1004,
+                limit: 1003,
+            }
+        ),
+        "{:?}",
+        err
+    );
+
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_binds_lists_to_in() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let numbers = "WITH RECURSIVE numbers (id) AS \
+                   (SELECT 1 UNION ALL SELECT id + 1 FROM numbers WHERE id < 1000)";
+
+    let ids: Vec<i32> = sqlx::query_scalar(&format!(
+        "{} SELECT id FROM numbers WHERE id IN {{in}}",
+        numbers
+    ))
+    .bind_expanded(vec![5])
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(ids, [5]);
+
+    let count: i64 = sqlx::query_scalar(&format!(
+        "{} SELECT COUNT(*) FROM numbers WHERE id > ? AND id IN {{in}}",
+        numbers
+    ))
+    .bind(10)
+    .bind_expanded(1..=1000)
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(count, 990);
+
+    // an empty list is refused unless it is allowed, when it matches nothing
+    let sql = format!(
+        "{} SELECT COUNT(*) FROM numbers WHERE id IN {{in}}",
+        numbers
+    );
+
+    let err = sqlx::query(&sql)
+        .bind_expanded(Vec::<i32>::new())
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, sqlx::Error::ArgumentEncode { index: 0, .. }),
+        "{:?}",
+        err
+    );
+
+    let count: i64 = sqlx::query_scalar(&sql)
+        .bind_expanded_allow_empty(Vec::<i32>::new())
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 0);
+
+    let count: i64 = sqlx::query_scalar(&sql.replace("IN {in}", "NOT IN {in}"))
+        .bind_expanded_allow_empty(Vec::<i32>::new())
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1000);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_caches_a_statement_per_padded_length_of_a_list() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+    conn.clear_cached_statements().await?;
+
+    let sql = "SELECT COUNT(*) FROM (SELECT 1 AS id UNION SELECT 2 UNION SELECT 3) \
+               WHERE id IN {in}";
+
+    // 3 and 4 values are both bound as 4, the last value repeated
+    for (values, expected) in [(vec![1, 2, 3], 3), (vec![1, 2, 5, 6], 2)].iter() {
+        let count: i64 = sqlx::query_scalar(sql)
+            .bind_expanded(values.clone())
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(count, *expected);
+    }
+
+    assert_eq!(conn.cached_statements_size(), 1);
+
+    let count: i64 = sqlx::query_scalar(sql)
+        .bind_expanded(vec![3, 4, 5, 6, 7])
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1);
+    assert_eq!(conn.cached_statements_size(), 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_decodes_a_cached_column_once_per_type() -> anyhow::Result<()> {
+    use sqlx::decode::Decode;
+    use sqlx::error::BoxDynError;
+    use sqlx::sqlite::{SqliteTypeInfo, SqliteValueRef};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static DECODES: AtomicUsize = AtomicUsize::new(0);
+
+    // a string that counts how often it is decoded
+    #[derive(Debug, Clone, PartialEq)]
+    struct Counted(String);
+
+    impl sqlx::Type<Sqlite> for Counted {
+        fn type_info() -> SqliteTypeInfo {
+            <String as sqlx::Type<Sqlite>>::type_info()
+        }
+    }
+
+    impl<'r> Decode<'r, Sqlite> for Counted {
+        fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+            DECODES.fetch_add(1, Ordering::SeqCst);
+
+            Ok(Counted(<String as Decode<Sqlite>>::decode(value)?))
+        }
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let row = conn
+        .fetch_one("SELECT 'one' AS a, 'two' AS b, 3 AS c")
+        .await?
+        .cached();
+
+    assert_eq!(row.try_get::<Counted, _>("a")?, Counted("one".into()));
+    assert_eq!(row.try_get::<Counted, _>(0)?, Counted("one".into()));
+    assert_eq!(DECODES.load(Ordering::SeqCst), 1);
+
+    assert_eq!(row.try_get::<Counted, _>("b")?, Counted("two".into()));
+    assert_eq!(DECODES.load(Ordering::SeqCst), 2);
+
+    // another type is decoded on its own, and one that is cheap to decode every time
+    assert_eq!(
+        row.try_get::<Option<String>, _>("a")?.as_deref(),
+        Some("one")
+    );
+    assert_eq!(row.try_get::<i32, _>("c")?, 3);
+    assert!(row.try_get::<Counted, _>("d").is_err());
+
+    // the row itself does not cache
+    assert_eq!(Row::get::<Counted, _>(&*row, "a"), Counted("one".into()));
+    assert_eq!(DECODES.load(Ordering::SeqCst), 3);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_matches_column_names_ignoring_case_and_underscores() -> anyhow::Result<()> {
+    use sqlx::{ColumnNameMatching, FromRow};
+
+    #[derive(FromRow)]
+    struct Account {
+        user_id: i64,
+        display_name: String,
+    }
+
+    // names only match exactly, unless the connection asks for more
+    let mut conn = new::<Sqlite>().await?;
+
+    let row = conn.fetch_one("SELECT 1 AS userId").await?;
+
+    assert_eq!(row.try_get::<i64, _>("userId")?, 1);
+    assert!(matches!(
+        row.try_get::<i64, _>("userid"),
+        Err(sqlx::Error::ColumnNotFound(_))
+    ));
+
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")?
+        .column_name_matching(ColumnNameMatching::SnakeCaseInsensitive);
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    let row = conn
+        .fetch_one("SELECT 1 AS userId, 'ann' AS DISPLAY_NAME, 2 AS user_id_2")
+        .await?;
+
+    assert_eq!(row.try_get::<i64, _>("userid")?, 1);
+    assert_eq!(row.try_get::<String, _>("displayName")?, "ann");
+
+    let account = Account::from_row(&row)?;
+    assert_eq!((account.user_id, &*account.display_name), (1, "ann"));
+
+    // two columns that both match, and neither exactly, are an error
+    let row = conn.fetch_one("SELECT 1 AS userid, 2 AS UserId").await?;
+
+    match row.try_get::<i64, _>("userId") {
+        Err(sqlx::Error::ColumnAmbiguous { name, candidates }) => {
+            assert_eq!(name, "userId");
+            assert_eq!(candidates, ["userid", "UserId"]);
+        }
+
+        other => panic!("expected an ambiguous column, got {:?}", other),
+    }
+
+    assert!(matches!(
+        Account::from_row(&row),
+        Err(sqlx::Error::ColumnAmbiguous { .. })
+    ));
+
+    Ok(())
+}
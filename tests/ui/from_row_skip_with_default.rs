@@ -0,0 +1,8 @@
+#[derive(sqlx::FromRow)]
+struct Account {
+    id: i32,
+    #[sqlx(skip, default)]
+    sessions: Vec<String>,
+}
+
+fn main() {}
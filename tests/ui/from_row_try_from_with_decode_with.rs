@@ -0,0 +1,8 @@
+#[derive(sqlx::FromRow)]
+struct Account {
+    id: i32,
+    #[sqlx(try_from = "i16", decode_with = "decode_login_count")]
+    login_count: u16,
+}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[derive(sqlx::FromRow)]
+struct Account(i32, #[sqlx(rename = "name")] String);
+
+fn main() {}
@@ -199,5 +199,17 @@ for runtime in ["async-std", "tokio", "actix"]:
             tag=f"mssql_{version}" if runtime == "async-std" else f"mssql_{version}_{runtime}",
         )
 
+    #
+    # conformance
+    #
+
+    for database, service in [("postgres", "postgres_13"), ("postgres", "cockroachdb_22_1"), ("mysql", "mysql_8")]:
+        run(
+            f"cargo test --manifest-path tests/conformance/Cargo.toml --no-default-features --features {database},runtime-{runtime}",
+            comment=f"conformance suite on {service}",
+            service=service,
+            tag=f"conformance_{service}" if runtime == "async-std" else f"conformance_{service}_{runtime}",
+        )
+
 # TODO: Use [grcov] if available
 # ~/.cargo/bin/grcov tests/.cache/target/debug -s sqlx-core/ -t html --llvm --branch -o ./target/debug/coverage